@@ -11,12 +11,16 @@ use crate::{
         AccessScope, AccessTo, AccessTokenInner, AccountTenantIds, Permissions, RECOVERY_ADMIN_ID,
         permissions::{BuildPermissions, PermissionsListBuilder},
     },
-    network::limiter::{ConcurrencyLimiter, LimiterResult},
+    network::{
+        limiter::{ConcurrencyLimiter, LimiterResult},
+        sessions::SessionLimits,
+    },
 };
 use ahash::AHasher;
+use jmap_proto::request::capability::{Capability, CapabilityIds};
 use registry::{
     schema::{
-        enums::Permission,
+        enums::{Permission, ServiceProtocol},
         structs::{self, Account, Roles, UserRoles},
     },
     types::EnumImpl,
@@ -124,8 +128,12 @@ impl Server {
                     }
                 }
 
+                // Bumping the credential generation revokes all issued tokens,
+                // including those of accounts without a password. The version is
+                // never zero, which is reserved for tokens that carry none
                 let now = now();
-                let mut credential_version = 0;
+                let credential_generation = account.credential_generation;
+                let mut credential_version = credential_generation.wrapping_add(1).max(1);
                 let mut credential_scopes = Vec::with_capacity(account.credentials.len());
 
                 credential_scopes.push(AccessScope::new(permissions.finalize(), u32::MAX));
@@ -133,7 +141,11 @@ impl Server {
                 for credential in account.credentials {
                     match credential {
                         structs::Credential::Password(credential) => {
-                            credential_version = xxh3::xxh3_64(credential.secret.as_bytes()).max(1);
+                            credential_version = xxh3::xxh3_64_with_seed(
+                                credential.secret.as_bytes(),
+                                credential_generation,
+                            )
+                            .max(1);
 
                             if credential.expires_at.is_some() || !credential.allowed_ips.is_empty()
                             {
@@ -174,6 +186,7 @@ impl Server {
                                 };
                                 credential_scopes.push(AccessScope {
                                     credential_id,
+                                    capabilities: jmap_capabilities(&permissions),
                                     permissions,
                                     expires_at,
                                     allowed_ips: credential
@@ -202,6 +215,18 @@ impl Server {
                         .jmap
                         .upload_max_concurrent
                         .map(ConcurrencyLimiter::new),
+                    session_limits: SessionLimits {
+                        max_sessions: account.max_sessions,
+                        max_protocol_sessions: account.max_sessions_per_protocol,
+                    },
+                    disabled_protocols: if !account.enabled_protocols.is_empty() {
+                        !account
+                            .enabled_protocols
+                            .iter()
+                            .fold(0u16, |acc, protocol| acc | (1 << protocol.to_id()))
+                    } else {
+                        0
+                    },
                     obj_size: 0,
                     revision,
                     revision_account,
@@ -245,6 +270,8 @@ impl Server {
                         .jmap
                         .upload_max_concurrent
                         .map(ConcurrencyLimiter::new),
+                    session_limits: SessionLimits::default(),
+                    disabled_protocols: 0,
                     obj_size: 0,
                     revision,
                     revision_account,
@@ -377,6 +404,8 @@ impl AccessToken {
     pub fn new(inner: Arc<AccessTokenInner>, remote_ip: IpAddr) -> trc::Result<Self> {
         AccessToken {
             scope_idx: 0,
+            impersonator_id: None,
+            step_up_at: 0,
             inner,
         }
         .assert_is_valid(remote_ip)
@@ -385,6 +414,8 @@ impl AccessToken {
     pub fn new_maybe_invalid(inner: Arc<AccessTokenInner>) -> Self {
         AccessToken {
             scope_idx: 0,
+            impersonator_id: None,
+            step_up_at: 0,
             inner,
         }
     }
@@ -405,7 +436,12 @@ impl AccessToken {
                     .ctx(trc::Key::Id, credential_id)
                     .reason("Credential expired or removed.")
             })
-            .map(|scope_idx| AccessToken { scope_idx, inner })
+            .map(|scope_idx| AccessToken {
+                scope_idx,
+                impersonator_id: None,
+                step_up_at: 0,
+                inner,
+            })
             .and_then(|token| token.assert_is_valid(remote_ip))
     }
 
@@ -419,6 +455,8 @@ impl AccessToken {
         } else {
             AccessToken {
                 scope_idx: 0,
+                impersonator_id: None,
+                step_up_at: 0,
                 inner,
             }
             .assert_is_valid(remote_ip)
@@ -430,9 +468,19 @@ impl AccessToken {
         let mut s = AHasher::default();
         self.inner.member_of.hash(&mut s);
         self.inner.access_to.hash(&mut s);
+        self.jmap_capabilities().hash(&mut s);
         s.finish() as u32
     }
 
+    /// Returns the JMAP capabilities available to the active credential scope.
+    pub fn jmap_capabilities(&self) -> CapabilityIds {
+        self.inner
+            .scopes
+            .get(self.scope_idx)
+            .map(|scope| scope.capabilities)
+            .unwrap_or_default()
+    }
+
     #[inline(always)]
     pub fn account_id(&self) -> u32 {
         self.inner.account_id
@@ -443,6 +491,35 @@ impl AccessToken {
         self.inner.tenant_id
     }
 
+    /// Returns the account that performs actions on behalf of this token, which
+    /// is the master user when the account is being impersonated.
+    #[inline(always)]
+    pub fn actor_id(&self) -> u32 {
+        self.impersonator_id.unwrap_or(self.inner.account_id)
+    }
+
+    #[inline(always)]
+    pub fn impersonator_id(&self) -> Option<u32> {
+        self.impersonator_id
+    }
+
+    pub fn with_impersonator(mut self, impersonator_id: Option<u32>) -> Self {
+        self.impersonator_id = impersonator_id;
+        self
+    }
+
+    /// Returns the UNIX timestamp of the last second factor verification
+    /// for this session, or zero if none took place.
+    #[inline(always)]
+    pub fn step_up_at(&self) -> u64 {
+        self.step_up_at
+    }
+
+    pub fn with_step_up(mut self, step_up_at: u64) -> Self {
+        self.step_up_at = step_up_at;
+        self
+    }
+
     pub fn secondary_ids(&self) -> impl Iterator<Item = &u32> {
         self.inner
             .member_of
@@ -537,6 +614,7 @@ impl AccessToken {
                         }
 
                         scopes.push(AccessScope {
+                            capabilities: jmap_capabilities(&permissions),
                             permissions,
                             credential_id: scope.credential_id,
                             expires_at: u64::MAX,
@@ -556,6 +634,8 @@ impl AccessToken {
                     concurrent_http_requests: old_inner.concurrent_http_requests.clone(),
                     concurrent_imap_requests: old_inner.concurrent_imap_requests.clone(),
                     concurrent_uploads: old_inner.concurrent_uploads.clone(),
+                    session_limits: old_inner.session_limits.clone(),
+                    disabled_protocols: old_inner.disabled_protocols,
                     revision_account: old_inner.revision_account,
                     revision: old_inner.revision,
                     credential_version: old_inner.credential_version,
@@ -564,6 +644,8 @@ impl AccessToken {
 
                 access_token = AccessToken {
                     scope_idx: access_token.scope_idx,
+                    impersonator_id: access_token.impersonator_id,
+                    step_up_at: access_token.step_up_at,
                     inner: Arc::new(inner),
                 };
             }
@@ -706,6 +788,28 @@ impl AccessToken {
             .map_or(LimiterResult::Disabled, |limiter| limiter.is_allowed())
     }
 
+    pub fn session_limits(&self) -> &SessionLimits {
+        &self.inner.session_limits
+    }
+
+    pub fn is_protocol_enabled(&self, protocol: ServiceProtocol) -> bool {
+        self.inner.disabled_protocols & (1 << protocol.to_id()) == 0
+    }
+
+    pub fn assert_protocol_enabled(self, protocol: ServiceProtocol) -> trc::Result<Self> {
+        if self.is_protocol_enabled(protocol) {
+            Ok(self)
+        } else {
+            Err(trc::AuthEvent::ProtocolDisabled
+                .into_err()
+                .details(format!(
+                    "{} access is disabled for this account.",
+                    protocol.as_str().to_uppercase()
+                ))
+                .account_id(self.account_id()))
+        }
+    }
+
     pub fn concurrent_http_requests(&self) -> u64 {
         self.inner
             .concurrent_http_requests
@@ -746,6 +850,8 @@ impl AccessToken {
     pub fn new_admin() -> AccessToken {
         AccessToken {
             scope_idx: 0,
+            impersonator_id: None,
+            step_up_at: 0,
             inner: Arc::new(AccessTokenInner::new_admin()),
         }
     }
@@ -760,6 +866,8 @@ impl AccessToken {
         }
         AccessToken {
             scope_idx: 0,
+            impersonator_id: None,
+            step_up_at: 0,
             inner: Arc::new(AccessTokenInner {
                 account_id,
                 tenant_id: Default::default(),
@@ -769,6 +877,8 @@ impl AccessToken {
                 concurrent_http_requests: Default::default(),
                 concurrent_imap_requests: Default::default(),
                 concurrent_uploads: Default::default(),
+                session_limits: Default::default(),
+                disabled_protocols: Default::default(),
                 revision: Default::default(),
                 revision_account: Default::default(),
                 credential_version: Default::default(),
@@ -814,6 +924,8 @@ impl AccessTokenInner {
             concurrent_http_requests: Default::default(),
             concurrent_imap_requests: Default::default(),
             concurrent_uploads: Default::default(),
+            session_limits: Default::default(),
+            disabled_protocols: Default::default(),
             revision: Default::default(),
             revision_account: Default::default(),
             credential_version: Default::default(),
@@ -837,6 +949,7 @@ impl AccessTokenInner {
 impl AccessScope {
     pub fn new(permissions: Permissions, credential_id: u32) -> Self {
         Self {
+            capabilities: jmap_capabilities(&permissions),
             permissions,
             credential_id,
             expires_at: u64::MAX,
@@ -845,6 +958,39 @@ impl AccessScope {
     }
 }
 
+fn jmap_capabilities(permissions: &Permissions) -> CapabilityIds {
+    CapabilityIds(
+        Capability::all_capabilities()
+            .iter()
+            .filter(|capability| {
+                let permission = match capability {
+                    Capability::Mail | Capability::MailShare | Capability::FollowUp => {
+                        Permission::JmapEmailGet
+                    }
+                    Capability::Submission => Permission::JmapEmailSubmissionCreate,
+                    Capability::VacationResponse => Permission::JmapVacationResponseGet,
+                    Capability::Contacts => Permission::JmapContactCardGet,
+                    Capability::ContactsParse => Permission::JmapContactCardParse,
+                    Capability::Calendars => Permission::JmapCalendarEventGet,
+                    Capability::CalendarsParse => Permission::JmapCalendarEventParse,
+                    Capability::Sieve => Permission::JmapSieveScriptGet,
+                    Capability::Blob => Permission::JmapBlobGet,
+                    Capability::Quota => Permission::JmapQuotaGet,
+                    Capability::FileNode => Permission::JmapFileNodeGet,
+                    Capability::WebPushVapid => Permission::JmapPushSubscriptionCreate,
+                    Capability::Core
+                    | Capability::WebSocket
+                    | Capability::Principals
+                    | Capability::PrincipalsOwner
+                    | Capability::PrincipalsAvailability
+                    | Capability::Stalwart => return true,
+                };
+                permissions.get(permission as usize)
+            })
+            .fold(0, |ids, capability| ids | *capability as u32),
+    )
+}
+
 fn hash_account(account: &Account) -> u64 {
     let mut s = AHasher::default();
 
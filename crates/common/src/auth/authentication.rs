@@ -5,26 +5,33 @@
  */
 
 use crate::{
-    Server,
+    KV_TOTP_STEP, Server,
     auth::{
         AccessToken, AuthRequest, DomainCache,
+        chain::{ChainRecipient, source_name},
         credential::{ApiKey, AppPassword},
         oauth::GrantType,
     },
+    telemetry::audit::AuditSource,
 };
 use base64::{Engine, engine::general_purpose};
 use directory::{
     Credentials, Directory, Recipient,
     core::secret::{SecretVerificationResult, verify_mfa_secret_hash, verify_secret_hash},
 };
-use registry::schema::{
-    enums::Permission,
-    structs::{self, Credential},
+use registry::{
+    schema::{
+        enums::{AuditEventType, Permission, ServiceProtocol},
+        structs::{self, AuditEvent, Credential},
+    },
+    types::datetime::UTCDateTime,
 };
 use std::{net::IpAddr, sync::Arc};
-use store::write::now;
+use store::{dispatch::lookup::KeyValue, write::now};
 use trc::AddContext;
 
+const TOTP_STEP_EXPIRY: u64 = 86400;
+
 pub struct UsernameParts {
     pub account: Username,
     pub master_user: Option<Username>,
@@ -42,8 +49,38 @@ impl Server {
             .await
             .and_then(|token| token.assert_has_permission(Permission::Authenticate))
         {
-            Ok(token) => Ok(token),
+            Ok(token) => {
+                self.audit(
+                    AuditSource {
+                        access_token: &token,
+                        protocol: req.protocol,
+                        remote_ip: Some(req.remote_ip),
+                    },
+                    token.account_id(),
+                    AuditEventType::Login,
+                    [],
+                )
+                .await;
+
+                Ok(token)
+            }
             Err(err) => {
+                if matches!(err.as_ref(), trc::EventType::Auth(trc::AuthEvent::Failed))
+                    && let Some(account_id) = err.value_as_uint(trc::Key::AccountId)
+                {
+                    self.audit_event(AuditEvent {
+                        account_id: account_id.into(),
+                        actor_id: account_id.into(),
+                        timestamp: UTCDateTime::now(),
+                        event: AuditEventType::LoginFailed,
+                        protocol: req.protocol,
+                        remote_ip: Some(registry::types::ipaddr::IpAddr(req.remote_ip)),
+                        object_ids: Default::default(),
+                        details: None,
+                    })
+                    .await;
+                }
+
                 // Random delay to mitigate user enumeration attacks
                 #[cfg(not(feature = "test_mode"))]
                 {
@@ -168,78 +205,52 @@ impl Server {
                     };
                 }
 
-                // Obtain external directory, if any
+                // Obtain directory chain or external directory, if any
                 let mut is_alias_login = false;
-                let token = if let Some(directory) = self.get_directory_for_cached_domain(&domain) {
-                    let directory_account = if username.is_master() {
-                        directory
-                            .authenticate(&Credentials::Basic {
-                                username: auth_as_address.to_string(),
-                                secret: secret.clone(),
-                                mfa_token: mfa_token.clone(),
-                            })
-                            .await?
-                    } else {
-                        directory.authenticate(&req.credentials).await?
+                let mut source = None;
+                let master_credentials;
+                let credentials = if username.is_master() {
+                    master_credentials = Credentials::Basic {
+                        username: auth_as_address.to_string(),
+                        secret: secret.clone(),
+                        mfa_token: mfa_token.clone(),
                     };
-
+                    &master_credentials
+                } else {
+                    &req.credentials
+                };
+                let token = if let Some(chain) = self.get_directory_chain_for_cached_domain(&domain)
+                {
+                    let (token, is_alias, chain_source) = self
+                        .authenticate_chain(
+                            chain,
+                            auth_as,
+                            domain.id,
+                            credentials,
+                            req.remote_ip,
+                            req.session_id,
+                        )
+                        .await?;
+                    is_alias_login = is_alias;
+                    source = Some(source_name(chain_source));
+                    Ok(token)
+                } else if let Some(directory) = self.get_directory_for_cached_domain(&domain) {
+                    let directory_account = directory.authenticate(credentials).await?;
                     is_alias_login = directory_account.email != auth_as_address;
                     self.build_directory_token(directory_account, req.remote_ip)
                         .await
-                } else if let Some(account_id) =
-                    self.account_id_from_parts(auth_as_local, domain.id).await?
+                } else if let Some((token, is_alias)) = self
+                    .authenticate_internal(
+                        auth_as,
+                        domain.id,
+                        credentials,
+                        req.remote_ip,
+                        req.session_id,
+                    )
+                    .await?
                 {
-                    if let Some(account) = self
-                        .registry()
-                        .object::<structs::Account>(account_id.into())
-                        .await?
-                        .and_then(|account| account.into_user())
-                    {
-                        let Some(credential) = account.password_credential() else {
-                            return Err(trc::AuthEvent::Failed
-                                .into_err()
-                                .ctx(trc::Key::AccountName, auth_as_address.to_string())
-                                .ctx(trc::Key::AccountId, account_id)
-                                .ctx(trc::Key::SpanId, req.session_id)
-                                .reason("Password credential not found for account"));
-                        };
-
-                        match verify_mfa_secret_hash(
-                            credential.otp_auth.as_deref(),
-                            mfa_token.as_deref(),
-                            credential.secret.as_str(),
-                            secret,
-                        )
-                        .await?
-                        {
-                            SecretVerificationResult::Valid => {
-                                is_alias_login = account.name != auth_as_local;
-                                self.access_token(account_id)
-                                    .await
-                                    .and_then(|token| AccessToken::new(token, req.remote_ip))
-                            }
-                            SecretVerificationResult::Invalid => Err(trc::AuthEvent::Failed
-                                .into_err()
-                                .ctx(trc::Key::AccountName, auth_as_address.to_string())
-                                .ctx(trc::Key::AccountId, account_id)
-                                .ctx(trc::Key::SpanId, req.session_id)
-                                .reason("Authentication failed")),
-                            SecretVerificationResult::MissingMfaToken => {
-                                Err(trc::AuthEvent::MfaRequired
-                                    .into_err()
-                                    .ctx(trc::Key::AccountName, auth_as_address.to_string())
-                                    .ctx(trc::Key::AccountId, account_id)
-                                    .ctx(trc::Key::SpanId, req.session_id)
-                                    .reason("MFA token required"))
-                            }
-                        }
-                    } else {
-                        Err(trc::AuthEvent::Error
-                            .into_err()
-                            .ctx(trc::Key::AccountName, auth_as_address.to_string())
-                            .ctx(trc::Key::AccountId, account_id)
-                            .reason("Account not found in registry"))
-                    }
+                    is_alias_login = is_alias;
+                    Ok(token)
                 } else {
                     Err(trc::AuthEvent::Failed
                         .into_err()
@@ -270,11 +281,38 @@ impl Server {
                         && let Some(impersonated_domain) = username.account().domain()
                         && let Some(impersonated_domain_cache) =
                             self.domain(impersonated_domain).await?
-                        && let Some(directory) =
-                            self.get_directory_for_cached_domain(&impersonated_domain_cache)
-                        && let Recipient::Account(account) = directory.recipient(address).await?
                     {
-                        account_id = Some(Box::pin(self.synchronize_account(account)).await?.id);
+                        let recipient = if let Some(chain) =
+                            self.get_directory_chain_for_cached_domain(&impersonated_domain_cache)
+                        {
+                            match self
+                                .chain_recipient(
+                                    chain,
+                                    username.account().local(),
+                                    impersonated_domain_cache.id,
+                                    address,
+                                )
+                                .await?
+                            {
+                                ChainRecipient::Internal(id) => {
+                                    account_id = Some(id);
+                                    None
+                                }
+                                ChainRecipient::External(recipient) => Some(recipient),
+                                ChainRecipient::NotFound => None,
+                            }
+                        } else if let Some(directory) =
+                            self.get_directory_for_cached_domain(&impersonated_domain_cache)
+                        {
+                            Some(directory.recipient(address).await?)
+                        } else {
+                            None
+                        };
+
+                        if let Some(Recipient::Account(account)) = recipient {
+                            account_id =
+                                Some(Box::pin(self.synchronize_account(account)).await?.id);
+                        }
                     }
                     if let Some(account_id) = account_id {
                         trc::event!(
@@ -283,11 +321,13 @@ impl Server {
                             AccountId = account_id,
                             SpanId = req.session_id,
                             Details = master_address.to_string(),
+                            Source = source,
                         );
 
-                        self.access_token(account_id)
-                            .await
-                            .map(AccessToken::new_maybe_invalid)
+                        self.access_token(account_id).await.map(|impersonated| {
+                            AccessToken::new_maybe_invalid(impersonated)
+                                .with_impersonator(Some(token.account_id()))
+                        })
                     } else {
                         Err(trc::AuthEvent::Failed
                             .into_err()
@@ -301,6 +341,7 @@ impl Server {
                         AccountName = auth_as_address.to_string(),
                         AccountId = token.account_id(),
                         SpanId = req.session_id,
+                        Source = source,
                     );
 
                     Ok(token)
@@ -366,7 +407,8 @@ impl Server {
                     Ok(token_info) => self
                         .access_token(token_info.account_id)
                         .await
-                        .and_then(|token| AccessToken::new(token, req.remote_ip)),
+                        .and_then(|token| AccessToken::new(token, req.remote_ip))
+                        .map(|token| token.with_step_up(token_info.step_up_at)),
                     Err(err) => {
                         if let Some(external_error) = external_error {
                             Err(external_error)
@@ -469,6 +511,125 @@ impl Server {
         }
     }
 
+    pub(crate) async fn authenticate_internal(
+        &self,
+        auth_as: &Username,
+        domain_id: u32,
+        credentials: &Credentials,
+        remote_ip: IpAddr,
+        span_id: u64,
+    ) -> trc::Result<Option<(AccessToken, bool)>> {
+        let Credentials::Basic {
+            secret, mfa_token, ..
+        } = credentials
+        else {
+            return Ok(None);
+        };
+        let Some(account_id) = self
+            .account_id_from_parts(auth_as.local(), domain_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(account) = self
+            .registry()
+            .object::<structs::Account>(account_id.into())
+            .await?
+            .and_then(|account| account.into_user())
+        else {
+            return Err(trc::AuthEvent::Error
+                .into_err()
+                .ctx(trc::Key::AccountName, auth_as.address().to_string())
+                .ctx(trc::Key::AccountId, account_id)
+                .reason("Account not found in registry"));
+        };
+        let Some(credential) = account.password_credential() else {
+            return Err(trc::AuthEvent::Failed
+                .into_err()
+                .ctx(trc::Key::AccountName, auth_as.address().to_string())
+                .ctx(trc::Key::AccountId, account_id)
+                .ctx(trc::Key::SpanId, span_id)
+                .reason("Password credential not found for account"));
+        };
+
+        match verify_mfa_secret_hash(
+            credential.otp_auth.as_deref(),
+            mfa_token.as_deref(),
+            credential.secret.as_str(),
+            secret,
+        )
+        .await?
+        {
+            SecretVerificationResult::ValidTotp(step)
+                if !self.accept_totp_step(account_id, step).await? =>
+            {
+                Err(trc::AuthEvent::Failed
+                    .into_err()
+                    .ctx(trc::Key::AccountName, auth_as.address().to_string())
+                    .ctx(trc::Key::AccountId, account_id)
+                    .ctx(trc::Key::SpanId, span_id)
+                    .reason("TOTP code already used"))
+            }
+            SecretVerificationResult::Valid | SecretVerificationResult::ValidTotp(_) => self
+                .access_token(account_id)
+                .await
+                .and_then(|token| AccessToken::new(token, remote_ip))
+                .map(|token| Some((token, account.name != auth_as.local()))),
+            SecretVerificationResult::Invalid => Err(trc::AuthEvent::Failed
+                .into_err()
+                .ctx(trc::Key::AccountName, auth_as.address().to_string())
+                .ctx(trc::Key::AccountId, account_id)
+                .ctx(trc::Key::SpanId, span_id)
+                .reason("Authentication failed")),
+            SecretVerificationResult::MissingMfaToken => Err(trc::AuthEvent::MfaRequired
+                .into_err()
+                .ctx(trc::Key::AccountName, auth_as.address().to_string())
+                .ctx(trc::Key::AccountId, account_id)
+                .ctx(trc::Key::SpanId, span_id)
+                .reason("MFA token required")),
+        }
+    }
+
+    /// Records the time step of an accepted TOTP code, returning `false` if
+    /// the code or an earlier one was already used by the account.
+    pub async fn accept_totp_step(&self, account_id: u32, step: u64) -> trc::Result<bool> {
+        let store = self.in_memory_store();
+        let last_step_key = KeyValue::<()>::build_key(KV_TOTP_STEP, account_id.to_be_bytes());
+        if store
+            .key_get::<i64>(last_step_key.clone())
+            .await
+            .caused_by(trc::location!())?
+            .is_some_and(|last_step| step as i64 <= last_step)
+        {
+            return Ok(false);
+        }
+
+        // Claim the step so concurrent logins with the same code are refused
+        let mut step_key = Vec::with_capacity(12);
+        step_key.extend_from_slice(&account_id.to_be_bytes());
+        step_key.extend_from_slice(&step.to_be_bytes());
+        if !store
+            .try_lock(KV_TOTP_STEP, &step_key, TOTP_STEP_EXPIRY)
+            .await
+            .caused_by(trc::location!())?
+        {
+            return Ok(false);
+        }
+
+        store
+            .key_set(
+                KeyValue::with_prefix(
+                    KV_TOTP_STEP,
+                    account_id.to_be_bytes(),
+                    (step as i64).to_be_bytes().to_vec(),
+                )
+                .expires(TOTP_STEP_EXPIRY),
+            )
+            .await
+            .caused_by(trc::location!())
+            .map(|_| true)
+    }
+
     async fn resolve_domain(&self, domain_name: &str) -> trc::Result<Arc<DomainCache>> {
         if let Some(domain) = self.domain(domain_name).await? {
             Ok(domain)
@@ -492,7 +653,7 @@ impl Server {
         }
     }
 
-    async fn build_directory_token(
+    pub(crate) async fn build_directory_token(
         &self,
         account: directory::Account,
         remote_ip: IpAddr,
@@ -626,11 +787,17 @@ impl Username {
 }
 
 impl AuthRequest {
-    pub fn from_credentials(credentials: Credentials, session_id: u64, remote_ip: IpAddr) -> Self {
+    pub fn from_credentials(
+        credentials: Credentials,
+        session_id: u64,
+        remote_ip: IpAddr,
+        protocol: ServiceProtocol,
+    ) -> Self {
         Self {
             credentials,
             session_id,
             remote_ip,
+            protocol,
         }
     }
 
@@ -639,6 +806,7 @@ impl AuthRequest {
         pass: impl Into<String>,
         session_id: u64,
         remote_ip: IpAddr,
+        protocol: ServiceProtocol,
     ) -> Self {
         Self::from_credentials(
             Credentials::Basic {
@@ -648,6 +816,7 @@ impl AuthRequest {
             },
             session_id,
             remote_ip,
+            protocol,
         )
     }
 
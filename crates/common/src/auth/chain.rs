@@ -0,0 +1,313 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    Server,
+    auth::{AccessToken, DomainCache, EmailCache, authentication::Username},
+};
+use directory::{Credentials, DirectorySource, Recipient};
+use std::net::IpAddr;
+use types::id::Id;
+use utils::cache::CacheItemWeight;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrincipalSource {
+    Found(DirectorySource),
+    NotFound,
+}
+
+#[derive(Debug)]
+pub enum ChainRecipient {
+    Internal(u32),
+    External(Recipient),
+    NotFound,
+}
+
+impl CacheItemWeight for PrincipalSource {
+    fn weight(&self) -> u64 {
+        std::mem::size_of::<PrincipalSource>() as u64
+    }
+}
+
+impl Server {
+    pub fn get_directory_chain_for_cached_domain(
+        &self,
+        domain: &DomainCache,
+    ) -> Option<&[DirectorySource]> {
+        // SPDX-SnippetBegin
+        // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+        // SPDX-License-Identifier: LicenseRef-SEL
+        #[cfg(feature = "enterprise")]
+        if self.core.is_enterprise_edition() {
+            if !domain.directory_chain.is_empty() {
+                return Some(&domain.directory_chain);
+            } else if domain.id_directory.is_some() {
+                return None;
+            }
+        }
+        // SPDX-SnippetEnd
+
+        let chain = &self.get_directory_chain().sources;
+        (!chain.is_empty()).then_some(chain.as_ref())
+    }
+
+    pub(crate) async fn authenticate_chain(
+        &self,
+        chain: &[DirectorySource],
+        auth_as: &Username,
+        domain_id: u32,
+        credentials: &Credentials,
+        remote_ip: IpAddr,
+        session_id: u64,
+    ) -> trc::Result<(AccessToken, bool, DirectorySource)> {
+        let address = auth_as.address();
+
+        // Try the directory that satisfied the last request first
+        match self.inner.cache.directory_principals.get(address) {
+            Some(PrincipalSource::Found(source)) if chain.contains(&source) => {
+                if let Some((token, is_alias_login)) = self
+                    .authenticate_source(
+                        source,
+                        auth_as,
+                        domain_id,
+                        credentials,
+                        remote_ip,
+                        session_id,
+                    )
+                    .await
+                    .map_err(|err| err.ctx(trc::Key::Source, source_name(source)))?
+                {
+                    return Ok((token, is_alias_login, source));
+                }
+                self.inner.cache.directory_principals.remove(address);
+            }
+            // Negative entries are only trusted by recipient lookups, an account
+            // that was just provisioned must be able to log in right away
+            _ => {}
+        }
+
+        for &source in chain {
+            match self
+                .authenticate_source(
+                    source,
+                    auth_as,
+                    domain_id,
+                    credentials,
+                    remote_ip,
+                    session_id,
+                )
+                .await
+            {
+                Ok(Some((token, is_alias_login))) => {
+                    self.cache_principal_source(address, PrincipalSource::Found(source));
+                    return Ok((token, is_alias_login, source));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    // The account exists in this directory, do not fall through
+                    if matches!(
+                        err.as_ref(),
+                        trc::EventType::Auth(trc::AuthEvent::Failed | trc::AuthEvent::MfaRequired)
+                    ) {
+                        self.cache_principal_source(address, PrincipalSource::Found(source));
+                    }
+                    return Err(err.ctx(trc::Key::Source, source_name(source)));
+                }
+            }
+        }
+
+        Err(trc::AuthEvent::Failed
+            .into_err()
+            .ctx(trc::Key::AccountName, address.to_string())
+            .ctx(trc::Key::SpanId, session_id)
+            .reason("Account not found in any chained directory"))
+    }
+
+    async fn authenticate_source(
+        &self,
+        source: DirectorySource,
+        auth_as: &Username,
+        domain_id: u32,
+        credentials: &Credentials,
+        remote_ip: IpAddr,
+        session_id: u64,
+    ) -> trc::Result<Option<(AccessToken, bool)>> {
+        match source {
+            DirectorySource::Internal => {
+                self.authenticate_internal(auth_as, domain_id, credentials, remote_ip, session_id)
+                    .await
+            }
+            DirectorySource::External(id) => {
+                let Some(directory) = self.get_directory(&id) else {
+                    return Ok(None);
+                };
+                if let Some(account) = directory.try_authenticate(credentials).await? {
+                    let is_alias_login = account.email != auth_as.address();
+                    self.build_directory_token(account, remote_ip)
+                        .await
+                        .map(|token| Some((token, is_alias_login)))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    pub async fn chain_recipient(
+        &self,
+        chain: &[DirectorySource],
+        local_part: &str,
+        domain_id: u32,
+        address: &str,
+    ) -> trc::Result<ChainRecipient> {
+        let merge_lookups = self.get_directory_chain().merge_lookups;
+
+        match self.inner.cache.directory_principals.get(address) {
+            Some(PrincipalSource::NotFound) => return Ok(ChainRecipient::NotFound),
+            Some(PrincipalSource::Found(source)) if !merge_lookups && chain.contains(&source) => {
+                match self
+                    .lookup_source(source, local_part, domain_id, address)
+                    .await?
+                {
+                    ChainRecipient::NotFound => {
+                        self.inner.cache.directory_principals.remove(address);
+                    }
+                    result => return Ok(result),
+                }
+            }
+            _ => {}
+        }
+
+        let mut result = ChainRecipient::NotFound;
+        let mut found_in = None;
+        for &source in chain {
+            match (
+                self.lookup_source(source, local_part, domain_id, address)
+                    .await?,
+                &mut result,
+            ) {
+                (ChainRecipient::NotFound, _) => {}
+                (lookup, ChainRecipient::NotFound) => {
+                    result = lookup;
+                    found_in = Some(source);
+                    if !merge_lookups {
+                        break;
+                    }
+                }
+                (ChainRecipient::External(other), ChainRecipient::External(current)) => {
+                    merge_recipient(current, other);
+                }
+                _ => {}
+            }
+        }
+
+        self.cache_principal_source(
+            address,
+            found_in.map_or(PrincipalSource::NotFound, PrincipalSource::Found),
+        );
+
+        Ok(result)
+    }
+
+    async fn lookup_source(
+        &self,
+        source: DirectorySource,
+        local_part: &str,
+        domain_id: u32,
+        address: &str,
+    ) -> trc::Result<ChainRecipient> {
+        match source {
+            DirectorySource::Internal => {
+                if let Some(EmailCache::Account(account_id)) =
+                    self.rcpt_id_from_parts(local_part, domain_id).await?
+                    && self.try_account(account_id).await?.is_some()
+                {
+                    Ok(ChainRecipient::Internal(account_id))
+                } else {
+                    Ok(ChainRecipient::NotFound)
+                }
+            }
+            DirectorySource::External(id) => {
+                if let Some(directory) = self
+                    .get_directory(&id)
+                    .filter(|directory| directory.can_lookup_recipients())
+                {
+                    match directory.recipient(address).await? {
+                        Recipient::Invalid => Ok(ChainRecipient::NotFound),
+                        recipient => Ok(ChainRecipient::External(recipient)),
+                    }
+                } else {
+                    Ok(ChainRecipient::NotFound)
+                }
+            }
+        }
+    }
+
+    fn cache_principal_source(&self, address: &str, source: PrincipalSource) {
+        let ttl = self.get_directory_chain().cache_ttl;
+        if !ttl.is_zero() {
+            self.inner
+                .cache
+                .directory_principals
+                .insert(address.into(), source, ttl);
+        }
+    }
+}
+
+pub fn source_name(source: DirectorySource) -> String {
+    match source {
+        DirectorySource::Internal => "internal".to_string(),
+        DirectorySource::External(id) => Id::from(id).to_string(),
+    }
+}
+
+fn merge_recipient(current: &mut Recipient, other: Recipient) {
+    match (current, other) {
+        (Recipient::Account(current), Recipient::Account(other)) => {
+            merge_aliases(
+                &current.email,
+                &mut current.email_aliases,
+                other.email_aliases,
+            );
+            if let Some(groups) = other.groups {
+                let current_groups = current.groups.get_or_insert_default();
+                for group in groups {
+                    if !current_groups.contains(&group) {
+                        current_groups.push(group);
+                    }
+                }
+            }
+            if current.description.is_none() {
+                current.description = other.description;
+            }
+            if current.enabled_protocols.is_none() {
+                current.enabled_protocols = other.enabled_protocols;
+            }
+            if current.categorize_mail.is_none() {
+                current.categorize_mail = other.categorize_mail;
+            }
+        }
+        (Recipient::Group(current), Recipient::Group(other)) => {
+            merge_aliases(
+                &current.email,
+                &mut current.email_aliases,
+                other.email_aliases,
+            );
+            if current.description.is_none() {
+                current.description = other.description;
+            }
+        }
+        _ => {}
+    }
+}
+
+fn merge_aliases(email: &str, aliases: &mut Vec<String>, other: Vec<String>) {
+    for alias in other {
+        if alias != email && !aliases.contains(&alias) {
+            aliases.push(alias);
+        }
+    }
+}
@@ -5,14 +5,17 @@
  */
 
 use crate::{
-    expr::if_block::IfBlock,
-    network::limiter::ConcurrencyLimiter,
+    config::{mailstore::email::AccountTemplate, smtp::resolver::PolicyOverride},
+    expr::{Variable, if_block::IfBlock},
+    network::{batv::BatvKeys, limiter::ConcurrencyLimiter, sessions::SessionLimits},
     storage::{ObjectQuota, TenantQuota},
 };
-use directory::Credentials;
+use compact_str::CompactString;
+use directory::{Credentials, DirectorySource};
+use jmap_proto::request::capability::CapabilityIds;
 use quick_cache::Equivalent;
 use registry::{
-    schema::enums::{Locale, Permission},
+    schema::enums::{Locale, Permission, ServiceProtocol},
     types::{EnumImpl, ipmask::IpAddrOrMask},
 };
 use std::{
@@ -27,10 +30,12 @@ use utils::{cache::CacheItemWeight, map::bitmap::Bitmap};
 
 pub mod access_token;
 pub mod authentication;
+pub mod chain;
 pub mod credential;
 pub mod oauth;
 pub mod permissions;
 pub mod rate_limit;
+pub mod revoke;
 
 pub const RECOVERY_ADMIN_ID: u32 = u32::MAX;
 const PERMISSIONS_BITSET_SIZE: usize = Permission::COUNT.div_ceil(std::mem::size_of::<usize>());
@@ -61,14 +66,31 @@ pub struct DomainCache {
     pub names: Box<[Box<str>]>,
     pub id: u32,
     pub id_directory: Option<u32>,
+    pub directory_chain: Box<[DirectorySource]>,
     pub id_tenant: Option<u32>,
     pub catch_all: Option<Box<str>>,
     pub sub_addressing_custom: Option<Box<IfBlock>>,
+    pub account_template: Option<Arc<AccountTemplate>>,
+    pub quota_warning_thresholds: Box<[u64]>,
+    pub settings: DomainSettings,
+    pub batv: Option<Box<BatvKeys>>,
     pub flags: u8,
 }
 
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DomainSettings {
+    pub dkim_sign_domain: Option<Box<str>>,
+    pub report_from_address: Option<Box<str>>,
+    pub max_message_size: Option<u64>,
+    pub spam_threshold: Option<f64>,
+    pub mta_sts: Option<PolicyOverride>,
+}
+
 pub const DOMAIN_FLAG_RELAY: u8 = 1;
 pub const DOMAIN_FLAG_SUB_ADDRESSING: u8 = 1 << 1;
+pub const DOMAIN_FLAG_AUDIT_LOG: u8 = 1 << 2;
+pub const DOMAIN_FLAG_AUDIT_READS: u8 = 1 << 3;
+pub const DOMAIN_FLAG_DELIVERED_TO: u8 = 1 << 4;
 
 #[derive(Debug, Clone, Default)]
 pub struct AccountCache {
@@ -79,6 +101,7 @@ pub struct AccountCache {
     pub id_member_of: TinyVec<[u32; 3]>,
     pub quota_disk: u64,
     pub quota_objects: Option<Box<ObjectQuota>>,
+    pub max_message_size: Option<u64>,
     pub description: Option<Box<str>>,
     pub encryption_key: Option<EncryptionKeys>,
     pub locale: Locale,
@@ -96,6 +119,10 @@ pub const ACCOUNT_FLAG_ENCRYPT_ALGO_AES128: u64 = 1 << 5;
 pub const ACCOUNT_FLAG_ENCRYPT_APPEND: u64 = 1 << 6;
 pub const ACCOUNT_FLAG_ENCRYPT_ALGO_AES256_GCM: u64 = 1 << 7;
 pub const ACCOUNT_FLAG_ENCRYPT_ALGO_CHACHA20_POLY1305: u64 = 1 << 8;
+pub const ACCOUNT_FLAG_ITIP_AUTO_ADD: u64 = 1 << 9;
+pub const ACCOUNT_FLAG_ITIP_DISABLED: u64 = 1 << 10;
+pub const ACCOUNT_FLAG_SIEVE_LOG: u64 = 1 << 11;
+pub const ACCOUNT_FLAG_CATEGORIZE: u64 = 1 << 12;
 
 #[derive(Debug, Clone)]
 pub struct RoleCache {
@@ -111,10 +138,12 @@ pub struct MailingListCache {
 
 #[derive(Debug, Clone)]
 pub struct TenantCache {
+    pub name: Box<str>,
     pub id_roles: TinyVec<[u32; 3]>,
     pub quota_disk: u64,
     pub quota_objects: Option<Box<TenantQuota>>,
     pub permissions: Option<Box<PermissionsGroup>>,
+    pub settings: DomainSettings,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -127,6 +156,8 @@ pub struct PermissionsGroup {
 #[derive(Debug, Default, Clone)]
 pub struct AccessToken {
     scope_idx: usize,
+    impersonator_id: Option<u32>,
+    step_up_at: u64,
     inner: Arc<AccessTokenInner>,
 }
 
@@ -140,6 +171,8 @@ pub struct AccessTokenInner {
     pub(crate) concurrent_http_requests: Option<ConcurrencyLimiter>,
     pub(crate) concurrent_imap_requests: Option<ConcurrencyLimiter>,
     pub(crate) concurrent_uploads: Option<ConcurrencyLimiter>,
+    pub(crate) session_limits: SessionLimits,
+    pub(crate) disabled_protocols: u16,
     pub(crate) revision_account: u64,
     pub(crate) revision: u64,
     pub(crate) credential_version: u64,
@@ -149,6 +182,7 @@ pub struct AccessTokenInner {
 #[derive(Debug, Default, Hash, Clone)]
 pub struct AccessScope {
     pub permissions: Permissions,
+    pub capabilities: CapabilityIds,
     pub credential_id: u32,
     pub expires_at: u64,
     pub allowed_ips: Box<[IpAddrOrMask]>,
@@ -177,6 +211,7 @@ pub struct AuthRequest {
     pub credentials: Credentials,
     pub session_id: u64,
     pub remote_ip: IpAddr,
+    pub protocol: ServiceProtocol,
 }
 
 impl CacheItemWeight for AccessTokenInner {
@@ -206,6 +241,17 @@ impl CacheItemWeight for DomainCache {
                 .sub_addressing_custom
                 .as_ref()
                 .map_or(0, |s| s.weight())
+            + self
+                .account_template
+                .as_ref()
+                .map_or(0, |t| t.size() as u64)
+            + (self.quota_warning_thresholds.len() * std::mem::size_of::<u64>()) as u64
+            + (self.directory_chain.len() * std::mem::size_of::<DirectorySource>()) as u64
+            + self.settings.weight()
+            + self
+                .batv
+                .as_ref()
+                .map_or(0, |_| std::mem::size_of::<BatvKeys>() as u64)
     }
 }
 
@@ -262,7 +308,19 @@ impl CacheItemWeight for MailingListCache {
 impl CacheItemWeight for TenantCache {
     fn weight(&self) -> u64 {
         std::mem::size_of::<TenantCache>() as u64
+            + self.name.len() as u64
             + self.permissions.as_ref().map_or(0, |p| p.weight())
+            + self.settings.weight()
+    }
+}
+
+impl CacheItemWeight for DomainSettings {
+    fn weight(&self) -> u64 {
+        self.dkim_sign_domain.as_ref().map_or(0, |s| s.len() as u64)
+            + self
+                .report_from_address
+                .as_ref()
+                .map_or(0, |s| s.len() as u64)
     }
 }
 
@@ -280,6 +338,8 @@ impl BuildAccessToken for Arc<AccessTokenInner> {
     fn build(self) -> AccessToken {
         AccessToken {
             scope_idx: 0,
+            impersonator_id: None,
+            step_up_at: 0,
             inner: self,
         }
     }
@@ -308,3 +368,39 @@ impl DomainCache {
         self.names.first().map(|s| s.as_ref()).unwrap_or_default()
     }
 }
+
+impl DomainSettings {
+    pub fn with_fallback(self, fallback: &DomainSettings) -> Self {
+        DomainSettings {
+            dkim_sign_domain: self
+                .dkim_sign_domain
+                .or_else(|| fallback.dkim_sign_domain.clone()),
+            report_from_address: self
+                .report_from_address
+                .or_else(|| fallback.report_from_address.clone()),
+            max_message_size: self.max_message_size.or(fallback.max_message_size),
+            spam_threshold: self.spam_threshold.or(fallback.spam_threshold),
+            mta_sts: self.mta_sts.or_else(|| fallback.mta_sts.clone()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Variable<'static>> {
+        match name {
+            "dkim-sign-domain" => self
+                .dkim_sign_domain
+                .as_deref()
+                .map(|value| Variable::from(CompactString::from(value))),
+            "report-address" => self
+                .report_from_address
+                .as_deref()
+                .map(|value| Variable::from(CompactString::from(value))),
+            "max-message-size" => self.max_message_size.map(Variable::from),
+            "spam-threshold" => self.spam_threshold.map(Variable::from),
+            _ => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &DomainSettings::default()
+    }
+}
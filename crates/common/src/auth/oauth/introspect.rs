@@ -4,9 +4,11 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::{Server, auth::AccessToken};
+use super::GrantType;
+use crate::Server;
 use serde::{Deserialize, Serialize};
 use trc::{AddContext, AuthEvent, EventType};
+use types::id::Id;
 
 #[derive(Debug, Default, Clone, Eq, PartialEq, Deserialize, Serialize)]
 pub struct OAuthIntrospect {
@@ -47,26 +49,34 @@ pub struct OAuthIntrospect {
 }
 
 impl Server {
-    pub async fn introspect_access_token(
-        &self,
-        token: &str,
-        access_token: &AccessToken,
-    ) -> trc::Result<OAuthIntrospect> {
+    pub async fn introspect_access_token(&self, token: &str) -> trc::Result<OAuthIntrospect> {
+        // Tokens are self-contained and revoked through the credential version kept in
+        // the registry, so any node can introspect tokens issued by any other node
         match self.validate_access_token(None, token).await {
-            Ok(token_info) => Ok(OAuthIntrospect {
-                active: true,
-                username: self
-                    .account(access_token.account_id())
-                    .await
-                    .caused_by(trc::location!())?
-                    .name()
-                    .to_string()
-                    .into(),
-                token_type: Some("bearer".into()),
-                exp: Some(token_info.expiry as i64),
-                iat: Some(token_info.issued_at as i64),
-                ..Default::default()
-            }),
+            Ok(token_info)
+                if matches!(
+                    token_info.grant_type,
+                    GrantType::AccessToken | GrantType::RefreshToken
+                ) =>
+            {
+                Ok(OAuthIntrospect {
+                    active: true,
+                    scope: token_info.claims,
+                    username: self
+                        .account(token_info.account_id)
+                        .await
+                        .caused_by(trc::location!())?
+                        .name()
+                        .to_string()
+                        .into(),
+                    token_type: Some("bearer".into()),
+                    exp: Some(token_info.expiry as i64),
+                    iat: Some(token_info.issued_at as i64),
+                    sub: Some(Id::from(token_info.account_id).to_string()),
+                    ..Default::default()
+                })
+            }
+            Ok(_) => Ok(OAuthIntrospect::default()),
             Err(err)
                 if matches!(
                     err.event_type(),
@@ -20,6 +20,7 @@ pub const FAILED_TO_DECODE_TOKEN: &str = concat!(
 const TOKEN_HEADER: &str = "sw1.";
 const TOKEN_KEY_CONTEXT: &str = "stalwart-oauth-token-sw1";
 const OAUTH_EPOCH: u64 = 946684800; // Jan 1, 2000
+const STEP_UP_FLAG: u8 = 0x80;
 
 pub struct TokenInfo {
     pub grant_type: GrantType,
@@ -28,6 +29,7 @@ pub struct TokenInfo {
     pub expiry: u64,
     pub issued_at: u64,
     pub expires_in: u64,
+    pub step_up_at: u64,
 }
 
 struct RawToken {
@@ -37,6 +39,7 @@ struct RawToken {
     issued_at: u64,
     expiry: u64,
     credential_version: u64,
+    step_up_at: u64,
 }
 
 impl Server {
@@ -50,17 +53,45 @@ impl Server {
         credential_version: Option<u64>,
     ) -> trc::Result<String> {
         let issued_at = seconds_since_oauth_epoch();
-        let raw = RawToken {
-            grant_type,
-            account_id,
-            claims: claims.map(|claims| claims.to_string()),
-            issued_at,
-            expiry: issued_at + expiry_in,
-            credential_version: credential_version
-                .filter(|_| !matches!(grant_type, GrantType::Rsvp))
-                .unwrap_or_default(),
-        };
+        self.seal_access_token(
+            RawToken {
+                grant_type,
+                account_id,
+                claims: claims.map(|claims| claims.to_string()),
+                issued_at,
+                expiry: issued_at + expiry_in,
+                credential_version: credential_version
+                    .filter(|_| !matches!(grant_type, GrantType::Rsvp))
+                    .unwrap_or_default(),
+                step_up_at: 0,
+            },
+            account_name,
+        )
+    }
+
+    pub async fn encode_step_up_access_token(
+        &self,
+        account_id: u32,
+        account_name: &str,
+        expiry_in: u64,
+        credential_version: u64,
+    ) -> trc::Result<String> {
+        let issued_at = seconds_since_oauth_epoch();
+        self.seal_access_token(
+            RawToken {
+                grant_type: GrantType::AccessToken,
+                account_id,
+                claims: None,
+                issued_at,
+                expiry: issued_at + expiry_in,
+                credential_version,
+                step_up_at: issued_at,
+            },
+            account_name,
+        )
+    }
 
+    fn seal_access_token(&self, raw: RawToken, account_name: &str) -> trc::Result<String> {
         seal_token(
             self.core.oauth.oauth_key.as_bytes(),
             &raw,
@@ -101,14 +132,15 @@ impl Server {
                 .details("Invalid grant type"));
         }
 
-        // Enforce credential revocation for long lived tokens
-        if token.credential_version != 0 {
+        // Enforce credential revocation, RSVP tokens are bound to an event
+        // and carry no credential version
+        if token.grant_type != GrantType::Rsvp {
             let current = self
                 .access_token(token.account_id)
                 .await
                 .map_err(|err| trc::AuthEvent::Error.into_err().ctx(trc::Key::Details, err))?
                 .credential_version();
-            if current != token.credential_version {
+            if token.credential_version == 0 || current != token.credential_version {
                 return Err(trc::AuthEvent::TokenExpired
                     .into_err()
                     .details("Token revoked"));
@@ -122,6 +154,11 @@ impl Server {
             expiry: token.expiry + OAUTH_EPOCH,
             issued_at: token.issued_at + OAUTH_EPOCH,
             expires_in: token.expiry - now,
+            step_up_at: if token.step_up_at != 0 {
+                token.step_up_at + OAUTH_EPOCH
+            } else {
+                0
+            },
         })
     }
 }
@@ -129,10 +166,17 @@ impl Server {
 fn seal_token(key: &[u8], token: &RawToken, footer: &[u8]) -> Result<String, String> {
     let mut payload = Vec::with_capacity(32);
     payload.push_leb128(token.account_id);
-    payload.push(token.grant_type.id());
+    if token.step_up_at != 0 {
+        payload.push(token.grant_type.id() | STEP_UP_FLAG);
+    } else {
+        payload.push(token.grant_type.id());
+    }
     payload.push_leb128(token.issued_at);
     payload.push_leb128(token.expiry);
     payload.push_leb128(token.credential_version);
+    if token.step_up_at != 0 {
+        payload.push_leb128(token.step_up_at);
+    }
     if let Some(claims) = token.claims.as_deref().filter(|claims| !claims.is_empty()) {
         payload.extend_from_slice(claims.as_bytes());
     }
@@ -181,10 +225,16 @@ fn open_token(key: &[u8], token: &str) -> Result<RawToken, ()> {
 
     let mut bytes = payload.iter();
     let account_id: u32 = bytes.next_leb128().ok_or(())?;
-    let grant_type = GrantType::from_id(bytes.next().copied().ok_or(())?).ok_or(())?;
+    let grant_type_id = bytes.next().copied().ok_or(())?;
+    let grant_type = GrantType::from_id(grant_type_id & !STEP_UP_FLAG).ok_or(())?;
     let issued_at: u64 = bytes.next_leb128().ok_or(())?;
     let expiry: u64 = bytes.next_leb128().ok_or(())?;
     let credential_version: u64 = bytes.next_leb128().ok_or(())?;
+    let step_up_at: u64 = if grant_type_id & STEP_UP_FLAG != 0 {
+        bytes.next_leb128().ok_or(())?
+    } else {
+        0
+    };
     let bytes = bytes.as_slice();
     let claims = if bytes.is_empty() {
         None
@@ -199,6 +249,7 @@ fn open_token(key: &[u8], token: &str) -> Result<RawToken, ()> {
         issued_at,
         expiry,
         credential_version,
+        step_up_at,
     })
 }
 
@@ -225,6 +276,7 @@ mod tests {
             issued_at: 1_000,
             expiry: 2_000,
             credential_version: cv,
+            step_up_at: 0,
         }
     }
 
@@ -235,6 +287,7 @@ mod tests {
         assert_eq!(a.issued_at, b.issued_at);
         assert_eq!(a.expiry, b.expiry);
         assert_eq!(a.credential_version, b.credential_version);
+        assert_eq!(a.step_up_at, b.step_up_at);
     }
 
     #[test]
@@ -408,6 +461,32 @@ mod tests {
         assert_eq_fields(&open_token(KEY, &a).unwrap(), &open_token(KEY, &b).unwrap());
     }
 
+    #[test]
+    fn step_up_timestamp_round_trips() {
+        for raw in [
+            RawToken {
+                step_up_at: 1_500,
+                ..sample(GrantType::AccessToken, None, 3)
+            },
+            RawToken {
+                step_up_at: u64::MAX,
+                ..sample(GrantType::AccessToken, Some("claims"), 0)
+            },
+        ] {
+            let token = seal_token(KEY, &raw, NAME).unwrap();
+            assert_eq_fields(&raw, &open_token(KEY, &token).unwrap());
+        }
+
+        // Tokens issued without a step-up do not carry the timestamp
+        let opened = open_token(
+            KEY,
+            &seal_token(KEY, &sample(GrantType::RefreshToken, None, 0), NAME).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(opened.grant_type, GrantType::RefreshToken);
+        assert_eq!(opened.step_up_at, 0);
+    }
+
     #[test]
     fn claims_with_separators_round_trip_exactly() {
         let raw = sample(GrantType::Rsvp, Some("a;b;c;d@e.org;999"), 0);
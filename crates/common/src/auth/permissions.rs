@@ -16,6 +16,7 @@ use registry::{
     },
     types::EnumImpl,
 };
+use store::write::now;
 use trc::AddContext;
 use types::id::Id;
 use utils::map::vec_map::VecMap;
@@ -143,6 +144,24 @@ impl Server {
             .await
             .map(|permissions| access_token.can_grant_permissions(permissions.finalize()))
     }
+
+    pub fn assert_step_up(
+        &self,
+        access_token: &AccessToken,
+        permission: Permission,
+    ) -> trc::Result<()> {
+        let security = &self.core.network.security;
+        if !security.step_up_permissions.get(permission as usize)
+            || access_token.step_up_at() + security.step_up_max_age > now()
+        {
+            Ok(())
+        } else {
+            Err(trc::AuthEvent::StepUpRequired
+                .into_err()
+                .details(permission.as_str())
+                .account_id(access_token.account_id()))
+        }
+    }
 }
 
 impl AccessToken {
@@ -244,7 +263,8 @@ impl Default for DefaultPermissions {
             match permission {
                 Permission::Authenticate
                 | Permission::AuthenticateWithAlias
-                | Permission::InteractAi => {
+                | Permission::InteractAi
+                | Permission::ActionSignOutEverywhere => {
                     default.user.push(permission);
                     default.superuser.push(permission);
                     default.tenant.push(permission);
@@ -253,10 +273,20 @@ impl Default for DefaultPermissions {
                 | Permission::UnlimitedRequests
                 | Permission::UnlimitedUploads
                 | Permission::LiveMetrics
-                | Permission::LiveTracing => {
+                | Permission::LiveTracing
+                | Permission::OAuthDeviceAuthorize
+                | Permission::StoreBackup
+                | Permission::SenderReputationGet
+                | Permission::SenderReputationReset => {
                     default.superuser.push(permission);
                 }
-                Permission::FetchAnyBlob | Permission::LiveDeliveryTest => {
+                Permission::FetchAnyBlob
+                | Permission::LiveDeliveryTest
+                | Permission::QueuedMessagePreview
+                | Permission::SessionList
+                | Permission::SessionTerminate
+                | Permission::SieveRedirectGet
+                | Permission::SieveRedirectReset => {
                     default.superuser.push(permission);
                     default.tenant.push(permission);
                 }
@@ -294,9 +324,18 @@ impl Default for DefaultPermissions {
                         || name.starts_with("sysRole")
                         || name.starts_with("sysOAuthClient")
                         || name.starts_with("sysMailingList")
+                        || name.starts_with("sysMessageTemplate")
                         || name.starts_with("sysExternalReport")
                         || name.starts_with("sysDnsServer")
                         || name.starts_with("sysQueuedMessage")
+                        || matches!(
+                            permission,
+                            Permission::SysAuditEventGet
+                                | Permission::SysAuditEventQuery
+                                | Permission::SysUnsubscribeEventGet
+                                | Permission::SysUnsubscribeEventQuery
+                                | Permission::SysUnsubscribeEventDestroy
+                        )
                     {
                         default.tenant.push(permission);
                         default.superuser.push(permission);
@@ -0,0 +1,64 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{Server, cache::invalidate::CacheInvalidationBuilder};
+use registry::{
+    schema::{
+        prelude::{Object, ObjectType},
+        structs::Account,
+    },
+    types::id::ObjectId,
+};
+use store::registry::write::{RegistryWrite, RegistryWriteResult};
+use trc::AddContext;
+use types::id::Id;
+
+impl Server {
+    // Bumps the credential generation of an account, which invalidates all
+    // issued OAuth tokens and terminates its sessions on every node.
+    pub async fn revoke_credentials(&self, account_id: u32) -> trc::Result<bool> {
+        let Some(current_account) = self
+            .registry()
+            .get(ObjectId::new(ObjectType::Account, account_id.into()))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(false);
+        };
+        let Some(mut updated_account) = Account::from(current_account.clone()).into_user() else {
+            return Ok(false);
+        };
+        updated_account.credential_generation += 1;
+
+        let updated_account = Object::from(Account::User(updated_account));
+        match self
+            .registry()
+            .write(RegistryWrite::update(
+                Id::from(account_id),
+                &updated_account,
+                &current_account,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        {
+            RegistryWriteResult::Success(id) => {
+                let mut invalidator = CacheInvalidationBuilder::default();
+                invalidator.process_update(id, &current_account, &updated_account);
+                self.invalidate_caches(invalidator)
+                    .await
+                    .caused_by(trc::location!())?;
+
+                Ok(true)
+            }
+            failure => Err(trc::AuthEvent::Error
+                .into_err()
+                .caused_by(trc::location!())
+                .ctx(trc::Key::AccountId, account_id)
+                .details("Failed to revoke account credentials")
+                .reason(failure)),
+        }
+    }
+}
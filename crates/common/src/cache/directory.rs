@@ -74,6 +74,21 @@ impl Server {
                     updated_account.description = account.description;
                     has_changes = true;
                 }
+                if let Some(enabled_protocols) = account.enabled_protocols
+                    && (updated_account.enabled_protocols.len() != enabled_protocols.len()
+                        || !enabled_protocols
+                            .iter()
+                            .all(|protocol| updated_account.enabled_protocols.contains(protocol)))
+                {
+                    updated_account.enabled_protocols = enabled_protocols.into();
+                    has_changes = true;
+                }
+                if let Some(categorize_mail) = account.categorize_mail
+                    && categorize_mail != updated_account.categorize_mail
+                {
+                    updated_account.categorize_mail = categorize_mail;
+                    has_changes = true;
+                }
                 for alias in account.email_aliases {
                     if let Some((local, alias_domain)) = self.validate_alias(&alias).await?
                         && alias_domain.id_tenant == domain.id_tenant
@@ -188,6 +203,8 @@ impl Server {
                     aliases: aliases.into(),
                     created_at: UTCDateTime::now(),
                     description: account.description,
+                    enabled_protocols: account.enabled_protocols.unwrap_or_default().into(),
+                    categorize_mail: account.categorize_mail.unwrap_or_default(),
                     member_group_ids: member_group_ids.into(),
                     member_tenant_id: domain.id_tenant.map(Id::from),
                     roles: UserRoles::User,
@@ -12,7 +12,7 @@ use crate::{
 use ahash::AHashSet;
 use registry::{
     schema::{
-        prelude::{Object, ObjectInner, ObjectType},
+        prelude::{Object, ObjectInner, ObjectType, Property},
         structs::{Account, EmailAlias},
     },
     types::id::ObjectId,
@@ -39,11 +39,14 @@ impl CacheInvalidationBuilder {
                 let permissions_changed = current.permissions != new.permissions;
                 let roles_changed = current.roles != new.roles;
                 let tenant_changed = current.member_tenant_id != new.member_tenant_id;
-                let details_changed =
-                    current.locale != new.locale || current.description != new.description;
+                let details_changed = current.locale != new.locale
+                    || current.description != new.description
+                    || current.max_message_size != new.max_message_size
+                    || current.categorize_mail != new.categorize_mail;
                 let groups_changed = current.member_group_ids != new.member_group_ids;
                 let aliases_changed = current.aliases != new.aliases;
-                let credentials_changed = current.credentials != new.credentials;
+                let credentials_changed = current.credentials != new.credentials
+                    || current.credential_generation != new.credential_generation;
                 let encryption_changed = current.encryption_at_rest != new.encryption_at_rest;
 
                 if was_renamed
@@ -70,6 +73,10 @@ impl CacheInvalidationBuilder {
                     self.invalidate(CacheInvalidation::AccessToken(id));
                 }
 
+                if current.credential_generation != new.credential_generation {
+                    self.invalidate(CacheInvalidation::Sessions(id));
+                }
+
                 if was_renamed {
                     self.invalidate(CacheInvalidation::DavResources(id));
                 }
@@ -119,6 +126,12 @@ impl CacheInvalidationBuilder {
                     || (current.sub_addressing != new.sub_addressing)
                     || (current.allow_relaying != new.allow_relaying)
                     || (current.is_enabled != new.is_enabled)
+                    || (current.mta_sts_mode != new.mta_sts_mode)
+                    || (current.mta_sts_mx_hosts != new.mta_sts_mx_hosts)
+                    || (current.mta_sts_max_age != new.mta_sts_max_age)
+                    || (current.batv_key != new.batv_key)
+                    || (current.batv_previous_key != new.batv_previous_key)
+                    || (current.batv_validity != new.batv_validity)
                 {
                     self.invalidate(CacheInvalidation::Domain(id));
                 }
@@ -209,7 +222,14 @@ impl CacheInvalidationBuilder {
     }
 
     pub fn process_create(&mut self, object: &Object) {
-        self.invalidate_negative_email(&object.inner);
+        match &object.inner {
+            ObjectInner::DkimSignature(object) => {
+                self.invalidate(CacheInvalidation::DkimSignature(
+                    object.domain_id().document_id(),
+                ));
+            }
+            inner => self.invalidate_negative_email(inner),
+        }
     }
 
     fn invalidate_negative_email(&mut self, object: &ObjectInner) {
@@ -310,6 +330,31 @@ impl Server {
             }
         }
 
+        // Invalidate the access tokens of group members, as they include the
+        // resources shared with the group
+        let account_ids = changes
+            .iter()
+            .filter_map(|change| {
+                if let CacheInvalidation::AccessToken(account_id) = change {
+                    Some(*account_id)
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        for account_id in account_ids {
+            for member_id in self
+                .registry()
+                .query::<RoaringBitmap>(
+                    RegistryQuery::new(ObjectType::Account)
+                        .equal(Property::MemberGroupIds, account_id),
+                )
+                .await?
+            {
+                changes.insert(CacheInvalidation::AccessToken(member_id));
+            }
+        }
+
         let changes = changes.into_iter().collect::<Vec<_>>();
         self.invalidate_local_caches(&changes).await;
         self.cluster_broadcast(BroadcastEvent::CacheInvalidate(changes))
@@ -338,6 +383,7 @@ impl Server {
     pub fn invalidate_all_local_negative_caches(&self) {
         self.inner.cache.domain_names_negative.clear();
         self.inner.cache.emails_negative.clear();
+        self.inner.cache.directory_principals.clear();
     }
 
     pub fn invalidate_local_negative_account_cache(
@@ -424,6 +470,9 @@ impl Server {
                 } => {
                     negative_emails.insert((*domain_id, *local_part_hash));
                 }
+                CacheInvalidation::Sessions(id) => {
+                    self.terminate_sessions(*id, None);
+                }
             }
         }
 
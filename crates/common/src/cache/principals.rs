@@ -7,25 +7,34 @@
 use crate::{
     Server,
     auth::{
-        ACCOUNT_FLAG_ENCRYPT_ALGO_AES128, ACCOUNT_FLAG_ENCRYPT_ALGO_AES256,
-        ACCOUNT_FLAG_ENCRYPT_ALGO_AES256_GCM, ACCOUNT_FLAG_ENCRYPT_ALGO_CHACHA20_POLY1305,
-        ACCOUNT_FLAG_ENCRYPT_APPEND, ACCOUNT_FLAG_ENCRYPT_METHOD_PGP,
-        ACCOUNT_FLAG_ENCRYPT_METHOD_SMIME, ACCOUNT_FLAG_ENCRYPT_TRAIN_SPAM_FILTER, ACCOUNT_IS_USER,
-        AccountCache, AccountInfo, AccountTenantIds, DOMAIN_FLAG_RELAY, DOMAIN_FLAG_SUB_ADDRESSING,
-        DomainCache, EmailAddress, EmailAddressRef, EmailCache, MailingListCache, PermissionsGroup,
-        RECOVERY_ADMIN_ID, RoleCache, TenantCache, permissions::BuildPermissions,
+        ACCOUNT_FLAG_CATEGORIZE, ACCOUNT_FLAG_ENCRYPT_ALGO_AES128,
+        ACCOUNT_FLAG_ENCRYPT_ALGO_AES256, ACCOUNT_FLAG_ENCRYPT_ALGO_AES256_GCM,
+        ACCOUNT_FLAG_ENCRYPT_ALGO_CHACHA20_POLY1305, ACCOUNT_FLAG_ENCRYPT_APPEND,
+        ACCOUNT_FLAG_ENCRYPT_METHOD_PGP, ACCOUNT_FLAG_ENCRYPT_METHOD_SMIME,
+        ACCOUNT_FLAG_ENCRYPT_TRAIN_SPAM_FILTER, ACCOUNT_FLAG_ITIP_AUTO_ADD,
+        ACCOUNT_FLAG_ITIP_DISABLED, ACCOUNT_FLAG_SIEVE_LOG, ACCOUNT_IS_USER, AccountCache,
+        AccountInfo, AccountTenantIds, DOMAIN_FLAG_AUDIT_LOG, DOMAIN_FLAG_AUDIT_READS,
+        DOMAIN_FLAG_DELIVERED_TO, DOMAIN_FLAG_RELAY, DOMAIN_FLAG_SUB_ADDRESSING, DomainCache,
+        DomainSettings, EmailAddress, EmailAddressRef, EmailCache, MailingListCache,
+        PermissionsGroup, RECOVERY_ADMIN_ID, RoleCache, TenantCache, permissions::BuildPermissions,
+    },
+    config::{
+        mailstore::email::{AccountTemplate, quota_warning_thresholds},
+        smtp::{auth::DkimSigners, resolver::PolicyOverride},
     },
-    config::smtp::auth::DkimSigners,
     expr::if_block::BootstrapExprExt,
-    network::mta::AddressResolver,
+    network::{batv::BatvKeys, mta::AddressResolver},
     storage::{
         ObjectQuota, TenantQuota,
         encryption::{EncryptionMethod, parse_public_key},
     },
 };
+use directory::DirectorySource;
 use registry::{
     schema::{
-        enums::{DkimRotationStage, Locale, StorageQuota, TenantStorageQuota},
+        enums::{
+            CalendarInvitationPolicy, DkimRotationStage, Locale, StorageQuota, TenantStorageQuota,
+        },
         prelude::{ObjectType, Property},
         structs::{
             Account, DkimSignature, Domain, EncryptionAtRest, MailingList, MaskedEmail,
@@ -40,7 +49,7 @@ use store::{
     registry::{RegistryQuery, bootstrap::Bootstrap},
     write::{key::KeySerializer, now},
 };
-use trc::{AddContext, StoreEvent};
+use trc::{AddContext, DkimEvent, StoreEvent};
 use types::id::Id;
 use utils::DomainPart;
 
@@ -146,6 +155,23 @@ impl Server {
                 if domain.allow_relaying {
                     flags |= DOMAIN_FLAG_RELAY;
                 }
+                if domain.audit_log {
+                    flags |= DOMAIN_FLAG_AUDIT_LOG;
+                    if domain.audit_message_reads {
+                        flags |= DOMAIN_FLAG_AUDIT_READS;
+                    }
+                }
+                if domain.add_delivered_to_header {
+                    flags |= DOMAIN_FLAG_DELIVERED_TO;
+                }
+
+                // BATV signing keys
+                let batv = {
+                    let mut bp = Bootstrap::new_uninitialized(self.registry().clone());
+                    let batv = BatvKeys::parse(&domain, domain_id, &mut bp).await;
+                    bp.log_errors();
+                    batv.map(Box::new)
+                };
                 let sub_addressing_custom = match domain.sub_addressing {
                     SubAddressing::Enabled => {
                         flags |= DOMAIN_FLAG_SUB_ADDRESSING;
@@ -180,9 +206,41 @@ impl Server {
                         .collect(),
                     id: domain_id,
                     id_directory: domain.directory_id.map(|id| id.document_id()),
+                    directory_chain: domain
+                        .directory_chain
+                        .values()
+                        .map(|entry| match entry.directory_id {
+                            Some(id) => DirectorySource::External(id.document_id()),
+                            None => DirectorySource::Internal,
+                        })
+                        .collect(),
                     id_tenant: domain.member_tenant_id.map(|id| id.document_id()),
                     catch_all: domain.catch_all_address.map(|s| s.into_boxed_str()),
                     sub_addressing_custom,
+                    account_template: AccountTemplate::new(
+                        domain.provision_folders.into_inner(),
+                        domain.provision_subscribe,
+                        domain.provision_sieve_script,
+                        domain.provision_signature,
+                    )
+                    .map(Arc::new),
+                    quota_warning_thresholds: quota_warning_thresholds(
+                        domain.quota_warning_thresholds.into_inner(),
+                    ),
+                    settings: DomainSettings {
+                        dkim_sign_domain: domain.dkim_sign_domain.map(|s| s.into_boxed_str()),
+                        report_from_address: domain.report_from_address.map(|s| s.into_boxed_str()),
+                        max_message_size: domain.max_message_size,
+                        spam_threshold: domain.spam_threshold.map(|v| v.into_inner()),
+                        mta_sts: PolicyOverride::new(
+                            domain.mta_sts_mode,
+                            domain.mta_sts_mx_hosts.into_inner(),
+                            domain
+                                .mta_sts_max_age
+                                .map(|max_age| max_age.into_inner().as_secs()),
+                        ),
+                    },
+                    batv,
                     flags,
                 });
 
@@ -328,6 +386,7 @@ impl Server {
                 id_member_of: Default::default(),
                 quota_disk: Default::default(),
                 quota_objects: Default::default(),
+                max_message_size: Default::default(),
                 description: Some("Recovery admin account".into()),
                 encryption_key: Default::default(),
                 locale: Default::default(),
@@ -403,6 +462,21 @@ impl Server {
                         }
 
                         let mut flags = ACCOUNT_IS_USER;
+                        match account.calendar_invitations {
+                            CalendarInvitationPolicy::Default => {}
+                            CalendarInvitationPolicy::AutoAdd => {
+                                flags |= ACCOUNT_FLAG_ITIP_AUTO_ADD;
+                            }
+                            CalendarInvitationPolicy::Disabled => {
+                                flags |= ACCOUNT_FLAG_ITIP_DISABLED;
+                            }
+                        }
+                        if account.sieve_logging {
+                            flags |= ACCOUNT_FLAG_SIEVE_LOG;
+                        }
+                        if account.categorize_mail {
+                            flags |= ACCOUNT_FLAG_CATEGORIZE;
+                        }
                         let encryption_settings = match account.encryption_at_rest {
                             EncryptionAtRest::Disabled => None,
                             EncryptionAtRest::Aes256(settings) => {
@@ -482,6 +556,7 @@ impl Server {
                                 .collect(),
                             quota_disk,
                             quota_objects: quota_objects.map(Box::new),
+                            max_message_size: account.max_message_size,
                             description: account.description.map(Into::into),
                             locale: account.locale,
                             encryption_key,
@@ -541,6 +616,7 @@ impl Server {
                             id_member_of: Default::default(),
                             quota_disk,
                             quota_objects: quota_objects.map(Box::new),
+                            max_message_size: None,
                             description: account.description.map(Into::into),
                             encryption_key: None,
                             locale: account.locale,
@@ -801,6 +877,7 @@ impl Server {
                 };
 
                 let cache = Arc::new(TenantCache {
+                    name: tenant.name.into_boxed_str(),
                     id_roles: tenant
                         .roles
                         .role_ids()
@@ -817,6 +894,13 @@ impl Server {
                     quota_disk,
                     quota_objects: quota_objects.map(Box::new),
                     permissions,
+                    settings: DomainSettings {
+                        dkim_sign_domain: tenant.dkim_sign_domain.map(|s| s.into_boxed_str()),
+                        report_from_address: tenant.report_from_address.map(|s| s.into_boxed_str()),
+                        max_message_size: tenant.max_message_size,
+                        spam_threshold: tenant.spam_threshold.map(|v| v.into_inner()),
+                        mta_sts: None,
+                    },
                 });
 
                 let _ = guard.insert(cache.clone());
@@ -861,10 +945,94 @@ impl Server {
         }
     }
 
-    pub async fn dkim_signers(&self, domain: &str) -> trc::Result<Option<Arc<DkimSigners>>> {
+    /// Resolves the settings of a local domain, falling back to the settings
+    /// of its tenant for any setting the domain does not override.
+    pub async fn domain_settings(&self, domain: &str) -> trc::Result<Option<DomainSettings>> {
         let Some(domain) = self.domain(domain).await? else {
             return Ok(None);
         };
+
+        let settings = if let Some(tenant_id) = domain.id_tenant {
+            domain
+                .settings
+                .clone()
+                .with_fallback(&self.tenant(tenant_id).await?.settings)
+        } else {
+            domain.settings.clone()
+        };
+
+        Ok((!settings.is_empty()).then_some(settings))
+    }
+
+    /// Returns the maximum size of messages accepted for a local recipient,
+    /// taken from its account and falling back to the settings of its domain.
+    pub async fn rcpt_max_message_size(&self, address: &str) -> trc::Result<Option<u64>> {
+        let Some((_, domain)) = address.rsplit_once('@') else {
+            return Ok(None);
+        };
+
+        if let Some(EmailCache::Account(account_id)) = self.rcpt_id_from_email(address).await?
+            && let Some(max_message_size) = self.account(account_id).await?.max_message_size
+        {
+            return Ok(Some(max_message_size));
+        }
+
+        Ok(self
+            .domain_settings(domain)
+            .await?
+            .and_then(|settings| settings.max_message_size))
+    }
+
+    /// Returns the canonical address to stamp in a Delivered-To header when
+    /// delivering to an account, or `None` if stamping is disabled globally
+    /// or for the account's primary domain.
+    pub async fn delivered_to_address<'x>(
+        &self,
+        account: &'x AccountCache,
+    ) -> trc::Result<Option<&'x str>> {
+        if self.core.smtp.session.data.add_delivered_to
+            && let Some(address) = account.addresses.first()
+            && self
+                .domain_by_id(address.domain_id)
+                .await?
+                .is_none_or(|domain| domain.flags & DOMAIN_FLAG_DELIVERED_TO != 0)
+        {
+            Ok(Some(account.name()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub async fn dkim_signers(&self, domain: &str) -> trc::Result<Option<Arc<DkimSigners>>> {
+        let Some(mut domain) = self.domain(domain).await? else {
+            return Ok(None);
+        };
+
+        // Sign using the keys of another domain, if configured. The keys of a
+        // domain owned by a different tenant are never used.
+        if let Some(sign_domain) = self
+            .domain_settings(domain.name())
+            .await?
+            .and_then(|settings| settings.dkim_sign_domain)
+            && !domain
+                .names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&sign_domain))
+        {
+            let Some(sign_domain) = self.domain(&sign_domain).await? else {
+                return Ok(None);
+            };
+            if sign_domain.id_tenant != domain.id_tenant {
+                trc::event!(
+                    Dkim(DkimEvent::SignerNotFound),
+                    Domain = sign_domain.name().to_string(),
+                    Details = "Signing domain belongs to a different tenant",
+                );
+                return Ok(None);
+            }
+            domain = sign_domain;
+        }
+
         let cache = &self.inner.cache.dkim_signers;
         match cache.get_value_or_guard_async(&domain.id).await {
             Ok(signers) => {
@@ -874,7 +1042,7 @@ impl Server {
                     Collection = "dkimSigners",
                 );
 
-                Ok(Some(signers))
+                Ok((!signers.is_empty()).then_some(signers))
             }
             Err(guard) => {
                 trc::event!(
@@ -904,13 +1072,11 @@ impl Server {
                     }
                 }
 
-                if !signers.dkim1.is_empty() || signers.dkim2.is_some() {
-                    let signers = Arc::new(signers);
-                    let _ = guard.insert(signers.clone());
-                    Ok(Some(signers))
-                } else {
-                    Ok(None)
-                }
+                // Domains without usable keys are cached as well to avoid
+                // querying the registry for every message
+                let signers = Arc::new(signers);
+                let _ = guard.insert(signers.clone());
+                Ok((!signers.is_empty()).then_some(signers))
             }
         }
     }
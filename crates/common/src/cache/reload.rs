@@ -110,13 +110,18 @@ impl Server {
                     coordinator: storage.coordinator.clone(),
                     directory: directory.default_directory,
                     directories: directory.directories,
+                    directory_chain: directory.chain,
                 };
 
                 // Parse tracers
                 let tracers = Telemetry::parse(&mut bootstrap, &storage).await;
 
                 if bootstrap.errors.is_empty() {
-                    let core = Box::pin(Core::parse(&mut bootstrap, storage)).await;
+                    let mut core = Box::pin(Core::parse(&mut bootstrap, storage)).await;
+                    core.validate_references(&mut bootstrap).await;
+                    core.smtp
+                        .queue
+                        .inherit_relay_health(&self.inner.shared_core.load().smtp.queue);
 
                     if bootstrap.errors.is_empty() {
                         let mut servers = Listeners::parse(&mut bootstrap).await;
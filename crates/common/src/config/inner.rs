@@ -8,7 +8,10 @@ use super::server::tls::build_self_signed_cert;
 use crate::{
     Caches, Data, DavResource, DavResources, MailboxCache, MessageStoreCache, MessageUidCache,
     TlsConnectors,
-    auth::{AccessTokenInner, AccountCache, DomainCache, MailingListCache, RoleCache, TenantCache},
+    auth::{
+        AccessTokenInner, AccountCache, DomainCache, MailingListCache, RoleCache, TenantCache,
+        chain::PrincipalSource,
+    },
     config::{
         mailstore::spamfilter::SpamClassifier,
         server::tls::parse_certificates,
@@ -87,6 +90,12 @@ impl Data {
             registry_id_gen: id_generator.clone(),
             span_id_gen: id_generator,
             queue_status: true.into(),
+            queue_metrics: Default::default(),
+            drain: Default::default(),
+            sessions: Default::default(),
+            change_floors: Default::default(),
+            readiness: Default::default(),
+            slow_requests: Default::default(),
             applications,
             logos: Default::default(),
             smtp_connectors: TlsConnectors::try_new().failed("Failed to build TLS connectors"),
@@ -145,6 +154,10 @@ impl Caches {
                 cache.domain_names_negative,
                 (std::mem::size_of::<DomainCache>() + 255) as u64,
             ),
+            directory_principals: CacheWithTtl::new(
+                cache.directory_principals,
+                (std::mem::size_of::<PrincipalSource>() + 255) as u64,
+            ),
             domains: Cache::new(
                 cache.domains,
                 (std::mem::size_of::<DomainCache>() + 255) as u64,
@@ -227,6 +240,12 @@ impl Default for Data {
             span_id_gen: Default::default(),
             registry_id_gen: Default::default(),
             queue_status: true.into(),
+            queue_metrics: Default::default(),
+            drain: Default::default(),
+            sessions: Default::default(),
+            change_floors: Default::default(),
+            readiness: Default::default(),
+            slow_requests: Default::default(),
             applications: WebApplications::new(),
             logos: Default::default(),
             smtp_connectors: TlsConnectors::try_new().unwrap(),
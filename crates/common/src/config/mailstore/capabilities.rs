@@ -210,7 +210,7 @@ impl JmapConfig {
         self.capabilities.account.insert(
             Capability::Submission,
             Capabilities::Submission(SubmissionCapabilities {
-                max_delayed_send: 86400 * 30,
+                max_delayed_send: email.max_delayed_send.into_inner().as_secs(),
                 submission_extensions: VecMap::from_iter([
                     ("FUTURERELEASE".to_string(), Vec::new()),
                     ("SIZE".to_string(), Vec::new()),
@@ -279,6 +279,7 @@ impl JmapConfig {
                 max_data_sources: self.request_max_calls as u64,
                 supported_type_names: vec![
                     DataType::Email,
+                    DataType::Mailbox,
                     DataType::Thread,
                     DataType::SieveScript,
                 ],
@@ -296,6 +297,16 @@ impl JmapConfig {
             Capabilities::Empty(EmptyCapabilities::default()),
         );
 
+        // Add follow-up reminder capabilities
+        self.capabilities.session.append(
+            Capability::FollowUp,
+            Capabilities::Empty(EmptyCapabilities::default()),
+        );
+        self.capabilities.account.insert(
+            Capability::FollowUp,
+            Capabilities::Empty(EmptyCapabilities::default()),
+        );
+
         // Add Web Push VAPID capabilities
         if let Some(application_server_key) = self
             .vapid
@@ -9,25 +9,25 @@ use nlp::language::Language;
 use registry::{
     schema::{
         enums::{
-            CompressionAlgo, SearchCalendarField, SearchContactField, SearchEmailField,
-            StorageQuota,
+            ChangeLogCollection, CompressionAlgo, DuplicateDelivery, SearchCalendarField,
+            SearchContactField, SearchEmailField, SharedBlobQuota, StorageQuota,
         },
         prelude::ObjectType,
         structs::{
             AddressBook, Authentication, Calendar, DataRetention, Domain, Email, FileStorage, Jmap,
-            Search, SieveUserInterpreter, SystemSettings,
+            MailCategory, Rate, Search, SieveUserInterpreter, SystemSettings,
         },
     },
     types::EnumImpl,
 };
-use std::time::Duration;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use store::{
     registry::bootstrap::Bootstrap,
     search::{CalendarSearchField, ContactSearchField, EmailSearchField, SearchField},
     write::SearchIndex,
 };
-use types::special_use::SpecialUse;
-use utils::cron::SimpleCron;
+use types::{collection::SyncCollection, special_use::SpecialUse};
+use utils::{cron::SimpleCron, map::vec_map::VecMap};
 
 use crate::storage::ObjectQuota;
 
@@ -44,27 +44,50 @@ pub struct EmailConfig {
     pub mail_max_size: usize,
     pub mail_autoexpunge_after: Option<u64>,
     pub email_submission_autoexpunge_after: Option<u64>,
+    pub email_submission_max_delay: Duration,
 
     pub changes_max_history: Option<usize>,
+    pub changes_max_age: VecMap<SyncCollection, Duration>,
     pub share_notification_max_history: Option<Duration>,
+    pub audit_log_max_history: Option<Duration>,
 
     pub sieve_max_script_name: usize,
 
     pub default_folders: Vec<DefaultFolder>,
     pub shared_folder: String,
+    pub account_template: Option<Arc<AccountTemplate>>,
+
+    pub quota_warning_thresholds: Box<[u64]>,
+    pub quota_warning_notify: bool,
+    pub quota_warning_interval: Duration,
+
+    pub duplicate_delivery: DuplicateDelivery,
+    pub duplicate_delivery_window: Duration,
+    pub shared_blob_quota: SharedBlobQuota,
 
     pub encrypt: bool,
     pub encrypt_append: bool,
+    pub re_encrypt_concurrency: usize,
+    pub import_root: Option<PathBuf>,
 
     pub index_batch_size: usize,
     pub index_fields: AHashMap<SearchIndex, AHashSet<SearchField>>,
+    pub reindex_concurrency: usize,
+    pub reindex_rate: Option<Rate>,
 
     pub max_objects: ObjectQuota,
+    pub max_delivery_callbacks: Option<u32>,
+    pub max_follow_ups: Option<u32>,
+    pub recall_window: Duration,
+    pub recall_notice: bool,
+    pub categories: Vec<Arc<MailCategoryRule>>,
     pub compression: CompressionAlgo,
 
     pub account_purge_frequency: SimpleCron,
     pub data_purge_frequency: SimpleCron,
     pub blob_purge_frequency: SimpleCron,
+    pub quota_reconcile_interval: Duration,
+    pub quota_reconcile_sample_size: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -76,6 +99,24 @@ pub struct DefaultFolder {
     pub create: bool,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct AccountTemplate {
+    pub folders: Vec<String>,
+    pub subscribe: bool,
+    pub sieve_script: Option<String>,
+    pub signature: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MailCategoryRule {
+    pub name: String,
+    pub mailbox_name: Option<String>,
+    pub tags: AHashSet<String>,
+    pub keywords: Vec<String>,
+    pub min_sender_messages: Option<u64>,
+    pub min_matches: usize,
+}
+
 impl EmailConfig {
     pub async fn parse(bp: &mut Bootstrap) -> Self {
         let email = bp.setting_infallible::<Email>().await;
@@ -108,6 +149,31 @@ impl EmailConfig {
             "localhost.local".to_string()
         };
 
+        // Parse mail categories
+        let categories = bp
+            .list_infallible::<MailCategory>()
+            .await
+            .into_iter()
+            .filter(|category| category.object.enable)
+            .map(|category| {
+                let category = category.object;
+                Arc::new(MailCategoryRule {
+                    name: category.name,
+                    mailbox_name: category.mailbox_name.filter(|name| !name.trim().is_empty()),
+                    tags: category.match_tags.into_inner().into_iter().collect(),
+                    keywords: category
+                        .match_keywords
+                        .into_inner()
+                        .into_iter()
+                        .map(|keyword| keyword.to_lowercase())
+                        .filter(|keyword| !keyword.is_empty())
+                        .collect(),
+                    min_sender_messages: category.min_sender_messages,
+                    min_matches: category.min_matches.max(1) as usize,
+                })
+            })
+            .collect();
+
         // Parse default object quotas
         let mut max_objects = ObjectQuota::default();
         for (item, max) in [
@@ -144,6 +210,41 @@ impl EmailConfig {
             }
         }
 
+        // Parse change log retention
+        let mut changes_max_age = VecMap::new();
+        for (collection, sync_collection) in [
+            (ChangeLogCollection::Email, SyncCollection::Email),
+            (ChangeLogCollection::Thread, SyncCollection::Thread),
+            (ChangeLogCollection::Calendar, SyncCollection::Calendar),
+            (
+                ChangeLogCollection::AddressBook,
+                SyncCollection::AddressBook,
+            ),
+            (ChangeLogCollection::FileNode, SyncCollection::FileNode),
+            (ChangeLogCollection::Identity, SyncCollection::Identity),
+            (
+                ChangeLogCollection::EmailSubmission,
+                SyncCollection::EmailSubmission,
+            ),
+            (
+                ChangeLogCollection::SieveScript,
+                SyncCollection::SieveScript,
+            ),
+            (
+                ChangeLogCollection::CalendarEventNotification,
+                SyncCollection::CalendarEventNotification,
+            ),
+        ] {
+            if let Some(max_age) = dr
+                .max_changes_age_per_collection
+                .get(&collection)
+                .copied()
+                .or(dr.max_changes_age)
+            {
+                changes_max_age.append(sync_collection, max_age.into_inner());
+            }
+        }
+
         // Parse default folders
         let mut default_folders = Vec::new();
         let mut shared_folder = "Shared Folders".to_string();
@@ -277,22 +378,101 @@ impl EmailConfig {
             email_submission_autoexpunge_after: dr
                 .expunge_submissions_after
                 .map(|d| d.into_inner().as_secs()),
+            email_submission_max_delay: email.max_delayed_send.into_inner(),
             changes_max_history: dr.max_changes_history.map(|v| v as usize),
+            changes_max_age,
             share_notification_max_history: dr.expunge_share_notify_after.map(|v| v.into_inner()),
+            audit_log_max_history: dr.hold_audit_events_for.map(|v| v.into_inner()),
             sieve_max_script_name: sieve.max_script_name_length as usize,
             encrypt: email.encrypt_at_rest,
             encrypt_append: email.encrypt_on_append,
+            re_encrypt_concurrency: email.re_encrypt_concurrency as usize,
+            import_root: email.import_root.map(PathBuf::from),
             index_batch_size: search.index_batch_size as usize,
             index_fields,
+            reindex_concurrency: search.reindex_concurrency as usize,
+            reindex_rate: search.reindex_rate_limit,
             max_objects,
+            max_delivery_callbacks: email.max_delivery_callbacks.map(|max| max as u32),
+            max_follow_ups: email.max_follow_ups.map(|max| max as u32),
+            recall_window: email.recall_window.into_inner(),
+            recall_notice: email.recall_notice,
+            categories,
             default_folders,
             shared_folder,
+            account_template: AccountTemplate::new(
+                email.provision_folders.into_inner(),
+                email.provision_subscribe,
+                email.provision_sieve_script,
+                email.provision_signature,
+            )
+            .map(Arc::new),
+            quota_warning_thresholds: quota_warning_thresholds(
+                email.quota_warning_thresholds.into_inner(),
+            ),
+            quota_warning_notify: email.quota_warning_notify,
+            quota_warning_interval: email.quota_warning_interval.into_inner(),
+            duplicate_delivery: email.duplicate_delivery,
+            duplicate_delivery_window: email.duplicate_delivery_window.into_inner(),
+            shared_blob_quota: email.shared_blob_quota,
             account_purge_frequency: dr.expunge_schedule.into(),
             data_purge_frequency: dr.data_cleanup_schedule.into(),
             blob_purge_frequency: dr.blob_cleanup_schedule.into(),
+            quota_reconcile_interval: dr.quota_reconcile_interval.into_inner(),
+            quota_reconcile_sample_size: dr.quota_reconcile_sample_size as usize,
             compression: email.compression_algorithm,
             default_domain_id: system.default_domain_id.id() as u32,
             default_domain_name,
         }
     }
 }
+
+/// Returns the configured quota warning percentages in ascending order.
+pub fn quota_warning_thresholds(thresholds: Vec<u64>) -> Box<[u64]> {
+    let mut thresholds = thresholds
+        .into_iter()
+        .filter(|threshold| (1..=100).contains(threshold))
+        .collect::<Vec<_>>();
+    thresholds.sort_unstable();
+    thresholds.dedup();
+    thresholds.into_boxed_slice()
+}
+
+impl AccountTemplate {
+    pub fn new(
+        folders: Vec<String>,
+        subscribe: bool,
+        sieve_script: Option<String>,
+        signature: Option<String>,
+    ) -> Option<Self> {
+        let folders = folders
+            .into_iter()
+            .filter(|folder| !folder.is_empty())
+            .collect::<Vec<_>>();
+        let sieve_script = sieve_script.filter(|script| !script.trim().is_empty());
+        let signature = signature.filter(|signature| !signature.is_empty());
+
+        if !folders.is_empty() || sieve_script.is_some() || signature.is_some() {
+            Some(AccountTemplate {
+                folders,
+                subscribe,
+                sieve_script,
+                signature,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.folders
+            .iter()
+            .map(|folder| folder.len())
+            .sum::<usize>()
+            + self.sieve_script.as_ref().map_or(0, |script| script.len())
+            + self
+                .signature
+                .as_ref()
+                .map_or(0, |signature| signature.len())
+    }
+}
@@ -17,9 +17,13 @@ pub struct ImapConfig {
     pub timeout_auth: Duration,
     pub timeout_unauth: Duration,
     pub timeout_idle: Duration,
+    pub idle_notify_delay: Duration,
 
     pub rate_requests: Option<Rate>,
     pub rate_concurrent: Option<u64>,
+
+    pub search_cache_size: usize,
+    pub search_cache_ttl: Duration,
 }
 
 impl ImapConfig {
@@ -32,9 +36,12 @@ impl ImapConfig {
             timeout_auth: imap.timeout_authenticated.into_inner(),
             timeout_unauth: imap.timeout_anonymous.into_inner(),
             timeout_idle: imap.timeout_idle.into_inner(),
+            idle_notify_delay: imap.idle_notify_delay.into_inner(),
             rate_requests: imap.max_request_rate,
             rate_concurrent: imap.max_concurrent,
             allow_plain_auth: imap.allow_plain_text_auth,
+            search_cache_size: imap.search_cache_size as usize,
+            search_cache_ttl: imap.search_cache_ttl.into_inner(),
         }
     }
 }
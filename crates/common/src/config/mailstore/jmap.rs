@@ -5,6 +5,7 @@
  */
 
 use crate::network::webpush::{Vapid, VapidKey};
+use ahash::AHashMap;
 use jmap_proto::request::capability::BaseCapabilities;
 use registry::schema::{prelude::ObjectType, structs::Jmap};
 use std::{sync::Arc, time::Duration};
@@ -46,6 +47,11 @@ pub struct JmapConfig {
     pub web_socket_throttle: Duration,
     pub web_socket_timeout: Duration,
     pub web_socket_heartbeat: Duration,
+    pub web_socket_max_concurrent: usize,
+
+    pub slow_method_threshold: Option<Duration>,
+    pub slow_method_thresholds: AHashMap<String, Duration>,
+    pub method_profiling: bool,
 
     pub vapid: Option<Arc<Vapid>>,
 
@@ -90,6 +96,14 @@ impl JmapConfig {
             web_socket_throttle: jmap.websocket_throttle.into_inner(),
             web_socket_timeout: jmap.websocket_timeout.into_inner(),
             web_socket_heartbeat: jmap.websocket_heartbeat.into_inner(),
+            web_socket_max_concurrent: jmap.websocket_max_concurrent as usize,
+            slow_method_threshold: jmap.slow_method_threshold.map(|d| d.into_inner()),
+            slow_method_thresholds: jmap
+                .slow_method_threshold_per_method
+                .into_iter()
+                .map(|(method, threshold)| (method, threshold.into_inner()))
+                .collect(),
+            method_profiling: jmap.method_profiling,
             push_attempt_interval: jmap.push_attempt_wait.into_inner(),
             push_attempts_max: jmap.push_max_attempts as u32,
             push_retry_interval: jmap.push_retry_wait.into_inner(),
@@ -128,4 +142,11 @@ impl JmapConfig {
         jmap.add_capabilities(bp).await;
         jmap
     }
+
+    pub fn slow_method_threshold(&self, method: &str) -> Option<Duration> {
+        self.slow_method_thresholds
+            .get(method)
+            .copied()
+            .or(self.slow_method_threshold)
+    }
 }
@@ -15,17 +15,18 @@ use crate::{
 use ahash::AHashMap;
 use registry::{
     schema::{
+        enums::SieveRedirectSender,
         prelude::ObjectType,
         structs::{
             SieveSystemInterpreter, SieveSystemScript, SieveUserInterpreter, SieveUserScript,
             SystemSettings,
         },
     },
-    types::EnumImpl,
+    types::{EnumImpl, ipmask::IpAddrOrMask},
 };
 use sieve::{Compiler, Runtime, Sieve, compiler::grammar::Capability};
 use std::sync::Arc;
-use store::registry::bootstrap::Bootstrap;
+use store::registry::bootstrap::{Bootstrap, ReferenceKind};
 
 pub struct Scripting {
     pub untrusted_compiler: Compiler,
@@ -33,6 +34,14 @@ pub struct Scripting {
     pub trusted_runtime: Runtime,
     pub trusted_compiler: Compiler,
     pub max_received_headers: usize,
+    pub max_redirect_passes: usize,
+    pub redirect_sender: SieveRedirectSender,
+    pub trusted_redirect_hosts: Vec<IpAddrOrMask>,
+    pub untrusted_redirect_limit: RedirectLimit,
+    pub trusted_redirect_limit: RedirectLimit,
+    pub vacation_expiry: u64,
+    pub duplicate_max_expiry: u64,
+    pub log: SieveLogSettings,
     pub from_addr: IfBlock,
     pub from_name: IfBlock,
     pub return_path: IfBlock,
@@ -41,6 +50,20 @@ pub struct Scripting {
     pub untrusted_scripts: AHashMap<String, Arc<Sieve>>,
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RedirectLimit {
+    pub max_redirects: u64,
+    pub max_destinations: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SieveLogSettings {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub retention: u64,
+    pub add_header: bool,
+}
+
 impl Scripting {
     pub async fn parse(bp: &mut Bootstrap) -> Self {
         // Parse untrusted compiler
@@ -182,6 +205,25 @@ impl Scripting {
             untrusted_scripts,
             trusted_scripts,
             max_received_headers: untrusted.max_received_headers as usize,
+            max_redirect_passes: untrusted.max_redirect_passes as usize,
+            redirect_sender: untrusted.redirect_envelope_sender,
+            trusted_redirect_hosts: untrusted.trusted_redirect_hosts.into_inner(),
+            untrusted_redirect_limit: RedirectLimit {
+                max_redirects: untrusted.max_redirects_per_day,
+                max_destinations: untrusted.max_redirect_destinations,
+            },
+            trusted_redirect_limit: RedirectLimit {
+                max_redirects: trusted.max_redirects_per_day,
+                max_destinations: trusted.max_redirect_destinations,
+            },
+            vacation_expiry: untrusted.default_expiry_vacation.into_inner().as_secs(),
+            duplicate_max_expiry: untrusted.max_expiry_duplicate.into_inner().as_secs(),
+            log: SieveLogSettings {
+                enabled: untrusted.log_enabled,
+                max_entries: untrusted.log_max_entries as usize,
+                retention: untrusted.log_retention.into_inner().as_secs(),
+                add_header: untrusted.log_header,
+            },
             from_addr: bp.compile_expr(
                 ObjectType::SieveSystemScript.singleton(),
                 &trusted.ctx_default_from_address(),
@@ -194,9 +236,10 @@ impl Scripting {
                 ObjectType::SieveSystemScript.singleton(),
                 &trusted.ctx_default_return_path(),
             ),
-            sign: bp.compile_expr(
+            sign: bp.compile_named_expr(
                 ObjectType::SieveSystemScript.singleton(),
                 &trusted.ctx_dkim_sign_domain(),
+                ReferenceKind::DkimSigner,
             ),
         }
     }
@@ -212,6 +255,14 @@ impl Clone for Scripting {
             from_name: self.from_name.clone(),
             return_path: self.return_path.clone(),
             max_received_headers: self.max_received_headers,
+            max_redirect_passes: self.max_redirect_passes,
+            redirect_sender: self.redirect_sender,
+            trusted_redirect_hosts: self.trusted_redirect_hosts.clone(),
+            untrusted_redirect_limit: self.untrusted_redirect_limit,
+            trusted_redirect_limit: self.trusted_redirect_limit,
+            vacation_expiry: self.vacation_expiry,
+            duplicate_max_expiry: self.duplicate_max_expiry,
+            log: self.log,
             sign: self.sign.clone(),
             trusted_scripts: self.trusted_scripts.clone(),
             untrusted_scripts: self.untrusted_scripts.clone(),
@@ -4,21 +4,29 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::expr::{
-    Variable,
-    functions::ResolveVariable,
-    if_block::{BootstrapExprExt, IfBlock},
+use crate::{
+    expr::{
+        Variable,
+        functions::ResolveVariable,
+        if_block::{BootstrapExprExt, IfBlock},
+    },
+    network::{
+        clamd::{ClamdAddress, ClamdConnectionManager},
+        limiter::ConcurrencyLimiter,
+    },
 };
 use ahash::AHashSet;
+use deadpool::{Runtime, managed::Pool};
 use mail_auth::common::resolver::ToReverseName;
 use nlp::classifier::model::{CcfhClassifier, FhClassifier};
 use registry::schema::{
-    enums::{ExpressionVariable, ModelSize},
+    enums::{ExpressionVariable, ModelSize, SpamVirusAction},
     prelude::ObjectType,
     structs::{
-        self, SpamDnsblServer, SpamDnsblSettings, SpamFileExtension, SpamPyzor, SpamRule,
-        SpamSettings, SpamTag,
+        self, SpamClamAv, SpamDnsblServer, SpamDnsblSettings, SpamFileExtension, SpamPyzor,
+        SpamRule, SpamSettings, SpamTag,
     },
+    types::ipmask::IpAddrOrMask,
 };
 use std::{
     net::{IpAddr, SocketAddr},
@@ -48,14 +56,26 @@ pub struct SpamFilterConfig {
     pub card_is_ham: bool,
     pub trusted_reply: bool,
     pub grey_list_expiry: Option<u64>,
+    pub trusted_forwarders: Vec<IpAddrOrMask>,
+    pub trusted_forwarder_depth: usize,
 
+    pub archive: ArchiveConfig,
     pub dnsbl: DnsBlConfig,
     pub rules: SpamFilterRules,
     pub lists: SpamFilterLists,
     pub pyzor: Option<PyzorConfig>,
+    pub clamav: Option<ClamAvConfig>,
     pub classifier: Option<ClassifierConfig>,
+    pub reputation: Option<SenderReputationConfig>,
     pub scores: SpamFilterScoreConfig,
     pub spam_rules_url: Option<String>,
+    pub rspamd_api: Option<RspamdApiConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RspamdApiConfig {
+    pub secret: Option<String>,
+    pub limiter: ConcurrencyLimiter,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -65,6 +85,15 @@ pub struct SpamFilterScoreConfig {
     pub spam_threshold: f32,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveConfig {
+    pub max_depth: usize,
+    pub max_entries: usize,
+    pub max_ratio: u64,
+    pub max_size: usize,
+    pub timeout: Duration,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct DnsBlConfig {
     pub max_ip_checks: usize,
@@ -123,6 +152,24 @@ pub struct PyzorConfig {
     pub ratio: f64,
 }
 
+#[derive(Debug, Clone)]
+pub struct ClamAvConfig {
+    pub pool: Pool<ClamdConnectionManager>,
+    pub timeout: Duration,
+    pub max_size: usize,
+    pub action: SpamVirusAction,
+    pub fail_open: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SenderReputationConfig {
+    pub window: u64,
+    pub min_messages: u64,
+    pub good_score: f64,
+    pub bad_score: f64,
+    pub skip_greylist: bool,
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct SpamFilterRules {
     pub url: Vec<IfBlock>,
@@ -195,7 +242,15 @@ impl SpamFilterConfig {
             rules: SpamFilterRules::parse(bp).await,
             lists: SpamFilterLists::parse(bp).await,
             pyzor: PyzorConfig::parse(bp).await,
+            clamav: ClamAvConfig::parse(bp).await,
             classifier: ClassifierConfig::parse(bp).await,
+            reputation: spam.reputation_enable.then(|| SenderReputationConfig {
+                window: spam.reputation_window.into_inner().as_secs().max(1),
+                min_messages: spam.reputation_min_messages.max(1),
+                good_score: spam.reputation_good_score.into_inner(),
+                bad_score: spam.reputation_bad_score.into_inner(),
+                skip_greylist: spam.reputation_skip_greylist,
+            }),
             scores: SpamFilterScoreConfig {
                 reject_threshold: spam.score_reject.into_inner() as f32,
                 discard_threshold: spam.score_discard.into_inner() as f32,
@@ -203,6 +258,34 @@ impl SpamFilterConfig {
             },
             grey_list_expiry: spam.greylist_for.map(|d| d.into_inner().as_secs()),
             spam_rules_url: spam.spam_filter_rules_url,
+            trusted_forwarders: spam.trusted_forwarders.into_inner(),
+            trusted_forwarder_depth: spam.trusted_forwarder_depth as usize,
+            archive: ArchiveConfig {
+                max_depth: spam.archive_max_depth as usize,
+                max_entries: spam.archive_max_entries as usize,
+                max_ratio: spam.archive_max_ratio,
+                max_size: spam.archive_max_size as usize,
+                timeout: spam.archive_scan_timeout.into_inner(),
+            },
+            rspamd_api: if spam.enable_rspamd_api {
+                Some(RspamdApiConfig {
+                    secret: spam
+                        .rspamd_api_secret
+                        .secret()
+                        .await
+                        .map_err(|err| {
+                            bp.build_error(
+                                ObjectType::SpamSettings.singleton(),
+                                format!("Unable to retrieve rspamd endpoint secret: {err}"),
+                            );
+                        })
+                        .unwrap_or_default()
+                        .map(|secret| secret.into_owned()),
+                    limiter: ConcurrencyLimiter::new(spam.rspamd_api_max_concurrent),
+                })
+            } else {
+                None
+            },
         }
     }
 }
@@ -449,6 +532,46 @@ impl PyzorConfig {
     }
 }
 
+impl ClamAvConfig {
+    pub async fn parse(bp: &mut Bootstrap) -> Option<Self> {
+        let clamav = bp.setting_infallible::<SpamClamAv>().await;
+
+        if !clamav.enable {
+            return None;
+        }
+
+        let timeout = clamav.timeout.into_inner();
+        let manager = ClamdConnectionManager {
+            address: ClamdAddress::parse(&clamav.address),
+            timeout,
+        };
+        match Pool::builder(manager)
+            .runtime(Runtime::Tokio1)
+            .max_size(clamav.pool_max_connections as usize)
+            .create_timeout(timeout.into())
+            .wait_timeout(timeout.into())
+            .recycle_timeout(timeout.into())
+            .build()
+        {
+            Ok(pool) => ClamAvConfig {
+                pool,
+                timeout,
+                max_size: clamav.max_size as usize,
+                action: clamav.action,
+                fail_open: clamav.fail_open,
+            }
+            .into(),
+            Err(err) => {
+                bp.build_error(
+                    ObjectType::SpamClamAv.singleton(),
+                    format!("Failed to build ClamAV connection pool: {err}"),
+                );
+                None
+            }
+        }
+    }
+}
+
 impl ClassifierConfig {
     pub async fn parse(bp: &mut Bootstrap) -> Option<Self> {
         let classifier = bp.setting_infallible::<structs::SpamClassifier>().await;
@@ -6,7 +6,11 @@
 
 use std::io::Cursor;
 
-use self::{mailstore::jmap::JmapConfig, smtp::SmtpConfig, storage::Storage};
+use self::{
+    mailstore::jmap::JmapConfig,
+    smtp::SmtpConfig,
+    storage::{BackupConfig, Storage},
+};
 use crate::{
     Core, Network,
     auth::oauth::config::OAuthConfig,
@@ -21,15 +25,18 @@ use pkcs8::EncodePrivateKey;
 use ring::signature::{EcdsaKeyPair, RsaKeyPair};
 use store::registry::bootstrap::Bootstrap;
 use telemetry::Metrics;
+use templates::MessageTemplates;
 
 pub mod groupware;
 pub mod inner;
 pub mod mailstore;
 pub mod network;
+pub mod references;
 pub mod server;
 pub mod smtp;
 pub mod storage;
 pub mod telemetry;
+pub mod templates;
 
 impl Core {
     pub async fn parse(bp: &mut Bootstrap, mut storage: Storage) -> Self {
@@ -89,6 +96,8 @@ impl Core {
             spam: SpamFilterConfig::parse(bp).await,
             email: EmailConfig::parse(bp).await,
             groupware: GroupwareConfig::parse(bp).await,
+            templates: MessageTemplates::parse(bp).await,
+            backup: BackupConfig::parse(bp).await,
             storage,
         }
     }
@@ -16,7 +16,9 @@ use crate::{
     },
 };
 use registry::schema::{
-    enums::{AcmeChallengeType, ClusterTaskType, ProviderInfo, ServiceProtocol},
+    enums::{
+        AcmeChallengeType, ClusterTaskType, HealthCheckDependency, ProviderInfo, ServiceProtocol,
+    },
     prelude::{ObjectType, Property},
     structs::{
         self, AcmeProvider, Asn, ClusterTaskGroup, HttpForm, MailExchanger, Rate, Service,
@@ -36,6 +38,7 @@ pub struct Network {
     pub contact_form: Option<ContactForm>,
     pub asn_geo_lookup: AsnGeoLookupConfig,
     pub task_manager: TaskManager,
+    pub shutdown_grace_period: Duration,
     pub has_acme_tls_challenge: bool,
     pub has_acme_http_challenge: bool,
     pub info: NetworkInfo,
@@ -63,6 +66,11 @@ pub struct Http {
     pub response_headers: Vec<(hyper::header::HeaderName, hyper::header::HeaderValue)>,
     pub use_forwarded: bool,
     pub redirect_root: Option<String>,
+    pub queue_preview_size: usize,
+    pub readiness_check_timeout: Duration,
+    pub readiness_timeout: Duration,
+    pub readiness_cache_ttl: Duration,
+    pub readiness_optional: Vec<HealthCheckDependency>,
 }
 
 #[derive(Clone)]
@@ -328,6 +336,7 @@ impl Network {
             roles: ClusterRoles::default(),
             http: Http::parse(bp, &http_host).await,
             task_manager: bp.setting_infallible::<TaskManager>().await,
+            shutdown_grace_period: system.shutdown_grace_period.into_inner(),
             has_acme_tls_challenge,
             has_acme_http_challenge,
             info: NetworkInfo {
@@ -461,6 +470,11 @@ impl Http {
             response_headers: http_headers,
             use_forwarded: http.use_x_forwarded,
             redirect_root: http.redirect_root,
+            queue_preview_size: http.queue_preview_size as usize,
+            readiness_check_timeout: http.readiness_check_timeout.into_inner(),
+            readiness_timeout: http.readiness_timeout.into_inner(),
+            readiness_cache_ttl: http.readiness_cache_ttl.into_inner(),
+            readiness_optional: http.readiness_optional.into_inner(),
         }
     }
 }
@@ -0,0 +1,125 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::Core;
+use ahash::{AHashMap, AHashSet};
+use registry::{
+    schema::structs::{
+        Domain, HttpLookup, MemoryLookupKey, MemoryLookupKeyValue, StoreLookup, SystemSettings,
+    },
+    types::error::Warning,
+};
+use std::collections::hash_map::Entry;
+use store::registry::bootstrap::{Bootstrap, ReferenceKind};
+
+impl Core {
+    // Verifies that every name referenced by an expression resolves to a
+    // configured object. Returns false if unknown references were reported
+    // as errors.
+    pub async fn validate_references(&self, bp: &mut Bootstrap) -> bool {
+        let references = std::mem::take(&mut bp.references);
+        if references.is_empty() {
+            return true;
+        }
+
+        let is_strict = bp
+            .setting_infallible::<SystemSettings>()
+            .await
+            .strict_references;
+        let mut known_names: AHashMap<ReferenceKind, Option<AHashSet<String>>> = AHashMap::new();
+        let mut is_valid = true;
+
+        for reference in references {
+            let names = match known_names.entry(reference.kind) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(self.known_names(bp, reference.kind).await),
+            };
+            let Some(names) = names else {
+                continue;
+            };
+            let name = if reference.kind == ReferenceKind::DkimSigner {
+                reference.name.to_lowercase()
+            } else {
+                reference.name
+            };
+            if names.contains(&name) {
+                continue;
+            }
+
+            let message = format!("Unknown {} '{name}'", reference.kind.as_str());
+            if is_strict {
+                bp.invalid_property(reference.object_id, reference.property, message);
+                is_valid = false;
+            } else {
+                bp.warnings.push(Warning::for_property(
+                    reference.object_id,
+                    reference.property,
+                    message,
+                ));
+            }
+        }
+
+        is_valid
+    }
+
+    async fn known_names(&self, bp: &Bootstrap, kind: ReferenceKind) -> Option<AHashSet<String>> {
+        let queue = &self.smtp.queue;
+
+        match kind {
+            ReferenceKind::DeliverySchedule => {
+                Some(names_with(queue.queue_strategy.keys(), &["default"]))
+            }
+            ReferenceKind::Route => {
+                Some(names_with(queue.routing_strategy.keys(), &["local", "mx"]))
+            }
+            ReferenceKind::ConnectionStrategy => {
+                Some(names_with(queue.connection_strategy.keys(), &["default"]))
+            }
+            ReferenceKind::TlsStrategy => Some(names_with(queue.tls_strategy.keys(), &["default"])),
+            ReferenceKind::SieveScript => Some(names_with(self.sieve.trusted_scripts.keys(), &[])),
+            ReferenceKind::LookupStore => {
+                // An empty name or "*" refers to the default in-memory store
+                let mut names = AHashSet::from_iter(["".to_string(), "*".to_string()]);
+                for store in bp.registry.list::<StoreLookup>().await.ok()? {
+                    names.insert(store.object.namespace);
+                }
+                for store in bp.registry.list::<HttpLookup>().await.ok()? {
+                    names.insert(store.object.namespace);
+                }
+                for store in bp.registry.list::<MemoryLookupKey>().await.ok()? {
+                    names.insert(store.object.namespace);
+                }
+                for store in bp.registry.list::<MemoryLookupKeyValue>().await.ok()? {
+                    names.insert(store.object.namespace);
+                }
+                Some(names)
+            }
+            ReferenceKind::DkimSigner => {
+                // Any configured domain can be used for signing, keys are
+                // resolved per message and may be added later
+                let mut names = AHashSet::new();
+                for domain in bp.registry.list::<Domain>().await.ok()? {
+                    names.extend(domain_names(&domain.object));
+                }
+                Some(names)
+            }
+        }
+    }
+}
+
+fn names_with<'x>(names: impl Iterator<Item = &'x String>, built_in: &[&str]) -> AHashSet<String> {
+    names
+        .cloned()
+        .chain(built_in.iter().map(|name| name.to_string()))
+        .collect()
+}
+
+fn domain_names(domain: &Domain) -> impl Iterator<Item = String> + '_ {
+    [&domain.name]
+        .into_iter()
+        .chain(domain.aliases.iter())
+        .map(|name| name.to_lowercase())
+}
@@ -10,7 +10,10 @@ use super::{
 };
 use crate::{
     Inner,
-    network::{TcpAcceptor, tls::CertificateResolver},
+    network::{
+        TcpAcceptor,
+        tls::{CertificateResolver, OptionalClientCertVerifier},
+    },
 };
 use registry::{
     schema::{
@@ -21,10 +24,13 @@ use registry::{
     types::{id::ObjectId, map::Map},
 };
 use rustls::{
-    ALL_VERSIONS, ServerConfig, SupportedCipherSuite,
+    ALL_VERSIONS, RootCertStore, ServerConfig, SupportedCipherSuite,
     crypto::aws_lc_rs::{ALL_CIPHER_SUITES, cipher_suite::*, default_provider},
+    server::{WebPkiClientVerifier, danger::ClientCertVerifier},
 };
+use rustls_pemfile::certs;
 use std::{
+    io::Cursor,
     net::{IpAddr, Ipv4Addr, SocketAddr as StdSocketAddr},
     str::FromStr,
     sync::Arc,
@@ -277,8 +283,58 @@ impl Listeners {
                         .collect();
                 }
 
+                // Build client certificate verifier
+                let provider = Arc::new(provider);
+                let mut client_cert_verifier = None;
+                let client_verifier: Option<Arc<dyn ClientCertVerifier>> = if let Some(ca_pem) =
+                    listener
+                        .tls_client_ca_certificates
+                        .as_deref()
+                        .filter(|ca_pem| !ca_pem.trim().is_empty())
+                {
+                    // Certificates must chain up to one of the configured authorities
+                    let mut roots = RootCertStore::empty();
+                    for cert in certs(&mut Cursor::new(ca_pem.as_bytes())) {
+                        if let Err(err) = cert
+                            .map_err(|err| err.to_string())
+                            .and_then(|cert| roots.add(cert).map_err(|err| err.to_string()))
+                        {
+                            bp.build_error(id, format!("Invalid client CA certificate: {err}"));
+                            return;
+                        }
+                    }
+                    let builder =
+                        WebPkiClientVerifier::builder_with_provider(roots.into(), provider.clone());
+                    match if listener.tls_require_client_cert {
+                        builder.build()
+                    } else {
+                        builder.allow_unauthenticated().build()
+                    } {
+                        Ok(verifier) => {
+                            // Only chains validated against the configured authorities
+                            // can be mapped to an account
+                            client_cert_verifier = Some(verifier.clone());
+                            Some(verifier)
+                        }
+                        Err(err) => {
+                            bp.build_error(
+                                id,
+                                format!("Failed to build client certificate verifier: {err}"),
+                            );
+                            return;
+                        }
+                    }
+                } else if listener.tls_request_client_cert || listener.tls_require_client_cert {
+                    Some(Arc::new(OptionalClientCertVerifier::new(
+                        provider.signature_verification_algorithms,
+                        listener.tls_require_client_cert,
+                    )))
+                } else {
+                    None
+                };
+
                 // Build server config
-                let mut server_config = match ServerConfig::builder_with_provider(provider.into())
+                let mut server_config = match ServerConfig::builder_with_provider(provider)
                     .with_protocol_versions(if tls_v3 == tls_v2 {
                         ALL_VERSIONS
                     } else if tls_v3 {
@@ -286,9 +342,11 @@ impl Listeners {
                     } else {
                         TLS12_VERSION
                     }) {
-                    Ok(server_config) => server_config
-                        .with_no_client_auth()
-                        .with_cert_resolver(resolver.clone()),
+                    Ok(server_config) => match client_verifier {
+                        Some(verifier) => server_config.with_client_cert_verifier(verifier),
+                        None => server_config.with_no_client_auth(),
+                    }
+                    .with_cert_resolver(resolver.clone()),
                     Err(err) => {
                         bp.build_error(id, format!("Failed to build TLS server config: {err}"));
                         return;
@@ -303,6 +361,7 @@ impl Listeners {
                     acceptor: TlsAcceptor::from(default_config.clone()),
                     config: default_config,
                     implicit: listener.tls_implicit,
+                    client_cert_verifier,
                 }
             } else {
                 TcpAcceptor::Plain
@@ -103,6 +103,12 @@ pub(crate) async fn parse_certificates(
             continue;
         } else if not_valid_before > now {
             continue; // Skip certificates that are not yet valid
+        } else if cert
+            .staged_until
+            .as_ref()
+            .is_some_and(|until| until.timestamp() > now)
+        {
+            continue; // Skip certificates awaiting matching TLSA records
         }
 
         let secret = match cert.private_key.secret().await {
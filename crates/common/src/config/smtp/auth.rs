@@ -4,10 +4,14 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::expr::{
-    self,
-    if_block::{BootstrapExprExt, IfBlock},
+use crate::{
+    expr::{
+        self,
+        if_block::{BootstrapExprExt, IfBlock},
+    },
+    network::limiter::ConcurrencyLimiter,
 };
+use ahash::AHashSet;
 use mail_auth::{
     common::crypto::{Ed25519Key, HashAlgorithm, RsaKey, Sha256, SigningKey},
     dkim::{Canonicalization, Done},
@@ -18,12 +22,13 @@ use registry::{
     schema::{
         enums::{self, Dkim2Flag, ExpressionConstant},
         prelude::ObjectType,
-        structs::{Dkim1Signature, DkimSignature, SenderAuth},
+        structs::{Dkim1Signature, DkimSignature, Rate, SenderAuth},
     },
     types::{ObjectImpl, map::Map},
 };
 use rustls_pki_types::{PrivateKeyDer, PrivatePkcs1KeyDer, PrivatePkcs8KeyDer, pem::PemObject};
-use store::registry::bootstrap::Bootstrap;
+use std::time::Duration;
+use store::registry::bootstrap::{Bootstrap, ReferenceKind};
 use utils::cache::CacheItemWeight;
 
 #[derive(Clone)]
@@ -33,6 +38,7 @@ pub struct MailAuthConfig {
     pub spf: SpfAuthConfig,
     pub dmarc: DmarcAuthConfig,
     pub iprev: IpRevAuthConfig,
+    pub sender_verify: SenderVerifyConfig,
 }
 
 #[derive(Clone)]
@@ -62,6 +68,27 @@ pub struct DmarcAuthConfig {
 #[derive(Clone)]
 pub struct IpRevAuthConfig {
     pub verify: IfBlock,
+    pub require: IfBlock,
+    pub require_temp_fail: bool,
+    pub timeout: Duration,
+}
+
+#[derive(Clone)]
+pub struct SenderVerifyConfig {
+    pub verify: IfBlock,
+    pub skip_domains: AHashSet<String>,
+    pub pass_ttl: Duration,
+    pub fail_ttl: Duration,
+    pub rate: Option<Rate>,
+    pub timeout: Duration,
+    pub limiter: ConcurrencyLimiter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SenderVerifyResult {
+    Pass,
+    Fail,
+    TempError,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -69,6 +96,7 @@ pub enum VerifyStrategy {
     #[default]
     Relaxed,
     Strict,
+    StrictDryRun,
     Disable,
 }
 
@@ -91,9 +119,10 @@ impl MailAuthConfig {
             dkim: DkimAuthConfig {
                 verify: bp
                     .compile_expr(ObjectType::SenderAuth.singleton(), &auth.ctx_dkim_verify()),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::SenderAuth.singleton(),
                     &auth.ctx_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 strict: auth.dkim_strict,
             },
@@ -120,12 +149,34 @@ impl MailAuthConfig {
                     ObjectType::SenderAuth.singleton(),
                     &auth.ctx_reverse_ip_verify(),
                 ),
+                require: bp.compile_expr(
+                    ObjectType::SenderAuth.singleton(),
+                    &auth.ctx_reverse_ip_require(),
+                ),
+                require_temp_fail: auth.reverse_ip_require_temp_fail,
+                timeout: auth.reverse_ip_timeout.into_inner(),
+            },
+            sender_verify: SenderVerifyConfig {
+                verify: bp.compile_expr(
+                    ObjectType::SenderAuth.singleton(),
+                    &auth.ctx_sender_verify(),
+                ),
+                skip_domains: auth.sender_verify_skip_domains.into_iter().collect(),
+                pass_ttl: auth.sender_verify_pass_ttl.into_inner(),
+                fail_ttl: auth.sender_verify_fail_ttl.into_inner(),
+                rate: auth.sender_verify_rate,
+                timeout: auth.sender_verify_timeout.into_inner(),
+                limiter: ConcurrencyLimiter::new(auth.sender_verify_max_concurrent),
             },
         }
     }
 }
 
 impl DkimSigners {
+    pub fn is_empty(&self) -> bool {
+        self.dkim1.is_empty() && self.dkim2.is_none()
+    }
+
     pub async fn insert(&mut self, domain: String, signature: DkimSignature) -> trc::Result<()> {
         let mut errors = vec![];
         if !signature.validate(&mut errors) {
@@ -345,6 +396,7 @@ impl<'x> TryFrom<expr::Variable<'x>> for VerifyStrategy {
             expr::Variable::Constant(c) => match c {
                 ExpressionConstant::Relaxed => Ok(VerifyStrategy::Relaxed),
                 ExpressionConstant::Strict => Ok(VerifyStrategy::Strict),
+                ExpressionConstant::StrictDryRun => Ok(VerifyStrategy::StrictDryRun),
                 ExpressionConstant::Disable => Ok(VerifyStrategy::Disable),
                 _ => Err(()),
             },
@@ -356,13 +408,31 @@ impl<'x> TryFrom<expr::Variable<'x>> for VerifyStrategy {
 impl VerifyStrategy {
     #[inline(always)]
     pub fn verify(&self) -> bool {
-        matches!(self, VerifyStrategy::Strict | VerifyStrategy::Relaxed)
+        matches!(
+            self,
+            VerifyStrategy::Strict | VerifyStrategy::StrictDryRun | VerifyStrategy::Relaxed
+        )
     }
 
     #[inline(always)]
     pub fn is_strict(&self) -> bool {
         matches!(self, VerifyStrategy::Strict)
     }
+
+    #[inline(always)]
+    pub fn is_dry_run(&self) -> bool {
+        matches!(self, VerifyStrategy::StrictDryRun)
+    }
+}
+
+impl SenderVerifyResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SenderVerifyResult::Pass => "pass",
+            SenderVerifyResult::Fail => "fail",
+            SenderVerifyResult::TempError => "temperror",
+        }
+    }
 }
 
 impl CacheItemWeight for Dkim1Signer {
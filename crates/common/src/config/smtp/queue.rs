@@ -11,25 +11,37 @@ use crate::{
         if_block::{BootstrapExprExt, IfBlock},
         *,
     },
+    network::{limiter::ConcurrencyLimiter, unsubscribe::Unsubscribe},
 };
 use ahash::AHashMap;
+use calcard::common::timezone::Tz;
+use chrono::{Datelike, TimeZone, Timelike};
 use directory::Credentials;
 use mail_auth::IpLookupStrategy;
-use registry::schema::{
-    enums::{self, ExpressionConstant, ExpressionVariable, MtaRequiredOrOptional},
-    prelude::ObjectType,
-    structs::{
-        DsnReportSettings, MtaConnectionStrategy, MtaDeliveryExpiration, MtaDeliverySchedule,
-        MtaDeliveryScheduleIntervalsOrDefault, MtaInboundThrottle, MtaOutboundStrategy,
-        MtaOutboundThrottle, MtaQueueQuota, MtaRoute, MtaTlsStrategy, MtaVirtualQueue,
+use registry::{
+    schema::{
+        enums::{self, ExpressionConstant, ExpressionVariable, MtaRequiredOrOptional},
+        prelude::ObjectType,
+        structs::{
+            DsnReportSettings, MtaConnectionStrategy, MtaDeliveryExpiration, MtaDeliverySchedule,
+            MtaDeliveryScheduleIntervalsOrDefault, MtaInboundThrottle, MtaOutboundStrategy,
+            MtaOutboundThrottle, MtaQueueQuota, MtaRoute, MtaTlsStrategy, MtaVirtualQueue, Rate,
+        },
     },
+    types::EnumImpl,
 };
 use std::{
     fmt::Display,
     hash::{Hash, Hasher},
     net::IpAddr,
-    time::Duration,
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use store::registry::bootstrap::ReferenceKind;
 
 #[derive(
     Debug,
@@ -49,6 +61,23 @@ pub struct QueueName([u8; 8]);
 
 pub const DEFAULT_QUEUE_NAME: QueueName = QueueName([b'd', b'e', b'f', b'a', b'u', b'l', b't', 0]);
 
+// Limits for the custom metadata attached to queued messages by trusted
+// scripts and MTA hooks. Keys starting with the reserved prefix are kept
+// for internal use.
+pub const QUEUE_META_MAX_ENTRIES: usize = 16;
+pub const QUEUE_META_MAX_KEY_LEN: usize = 64;
+pub const QUEUE_META_MAX_VALUE_LEN: usize = 512;
+pub const QUEUE_META_RESERVED_PREFIX: &str = "stalwart.";
+
+pub fn is_valid_queue_meta(key: &str, value: &str) -> bool {
+    (1..=QUEUE_META_MAX_KEY_LEN).contains(&key.len())
+        && value.len() <= QUEUE_META_MAX_VALUE_LEN
+        && !key.starts_with(QUEUE_META_RESERVED_PREFIX)
+        && key
+            .bytes()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || b"-_.".contains(&ch))
+}
+
 #[derive(Clone)]
 pub struct QueueConfig {
     // Strategy resolver
@@ -71,6 +100,9 @@ pub struct QueueConfig {
     pub routing_strategy: AHashMap<String, RoutingStrategy>,
     pub tls_strategy: AHashMap<String, TlsStrategy>,
     pub virtual_queues: AHashMap<QueueName, VirtualQueue>,
+
+    // One-click unsubscribe
+    pub unsubscribe: Option<Unsubscribe>,
 }
 
 #[derive(Clone, Hash, PartialEq, Eq, Debug)]
@@ -92,11 +124,16 @@ pub struct Dsn {
     pub name: IfBlock,
     pub address: IfBlock,
     pub sign: IfBlock,
+    pub spam_threshold: Option<f32>,
+    pub suppress_no_mx: bool,
+    pub suppression_list: Option<String>,
+    pub rate: Option<Rate>,
 }
 
 #[derive(Clone, Debug)]
 pub struct VirtualQueue {
     pub threads: usize,
+    pub rate_limit: Option<IfBlock>,
 }
 
 #[derive(Clone, Debug)]
@@ -105,6 +142,15 @@ pub struct QueueStrategy {
     pub notify: Vec<u64>,
     pub expiry: QueueExpiry,
     pub virtual_queue: QueueName,
+    pub window: Option<DeliveryWindow>,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeliveryWindow {
+    pub days: u8,
+    pub hour_start: u32,
+    pub hour_end: u32,
+    pub tz: Tz,
 }
 
 #[derive(
@@ -121,6 +167,8 @@ pub struct QueueStrategy {
 pub enum QueueExpiry {
     Ttl(u64),
     Attempts(u32),
+    // 32-bit TTL keeps the archived size of queued recipients unchanged
+    TtlOrAttempts { ttl: u32, attempts: u32 },
 }
 
 #[derive(Clone, Debug)]
@@ -138,6 +186,7 @@ pub struct TlsStrategy {
 pub struct ConnectionStrategy {
     pub source_ipv4: Vec<IpAndHost>,
     pub source_ipv6: Vec<IpAndHost>,
+    pub source_ip_selection: IpSelection,
     pub ehlo_hostname: Option<String>,
 
     pub timeout_connect: Duration,
@@ -152,6 +201,23 @@ pub struct ConnectionStrategy {
 pub struct IpAndHost {
     pub ip: IpAddr,
     pub host: Option<String>,
+    pub weight: u64,
+    pub warmup: Option<IpWarmup>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum IpSelection {
+    #[default]
+    Weighted,
+    RoundRobin(Arc<AtomicUsize>),
+}
+
+#[derive(Clone, Debug)]
+pub struct IpWarmup {
+    pub start: u64,
+    pub days: u64,
+    pub initial_limit: u64,
+    pub target_limit: u64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -177,14 +243,33 @@ pub struct QueueQuota {
     pub messages: Option<u64>,
 }
 
-#[derive(Clone, Hash, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct RelayConfig {
-    pub address: HostOrIp<Box<str>, IpStr>,
-    pub port: u16,
+    pub hosts: Vec<RelayHost>,
+    pub host_selection: IpSelection,
     pub protocol: ServerProtocol,
     pub auth: Option<Credentials>,
     pub tls_implicit: bool,
     pub tls_allow_invalid_certs: bool,
+    pub failure_threshold: u64,
+    pub failure_cool_off: Duration,
+    pub health_check_interval: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RelayHost {
+    pub address: HostOrIp<Box<str>, IpStr>,
+    pub port: u16,
+    pub weight: u64,
+    pub limiter: Option<ConcurrencyLimiter>,
+    pub health: Arc<RelayHostHealth>,
+}
+
+#[derive(Debug, Default)]
+pub struct RelayHostHealth {
+    failures: AtomicU64,
+    down_until: AtomicU64,
+    next_probe: AtomicU64,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -213,16 +298,26 @@ impl QueueConfig {
         let dsn = bp.setting_infallible::<DsnReportSettings>().await;
 
         let mut queue = QueueConfig {
-            route: bp.compile_expr(ObjectType::MtaOutboundStrategy.singleton(), &st.ctx_route()),
-            queue: bp.compile_expr(
+            route: bp.compile_named_expr(
+                ObjectType::MtaOutboundStrategy.singleton(),
+                &st.ctx_route(),
+                ReferenceKind::Route,
+            ),
+            queue: bp.compile_named_expr(
                 ObjectType::MtaOutboundStrategy.singleton(),
                 &st.ctx_schedule(),
+                ReferenceKind::DeliverySchedule,
             ),
-            connection: bp.compile_expr(
+            connection: bp.compile_named_expr(
                 ObjectType::MtaOutboundStrategy.singleton(),
                 &st.ctx_connection(),
+                ReferenceKind::ConnectionStrategy,
+            ),
+            tls: bp.compile_named_expr(
+                ObjectType::MtaOutboundStrategy.singleton(),
+                &st.ctx_tls(),
+                ReferenceKind::TlsStrategy,
             ),
-            tls: bp.compile_expr(ObjectType::MtaOutboundStrategy.singleton(), &st.ctx_tls()),
             dsn: Dsn {
                 name: bp.compile_expr(
                     ObjectType::DsnReportSettings.singleton(),
@@ -232,10 +327,15 @@ impl QueueConfig {
                     ObjectType::DsnReportSettings.singleton(),
                     &dsn.ctx_from_address(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::DsnReportSettings.singleton(),
                     &dsn.ctx_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
+                spam_threshold: dsn.spam_threshold.map(|score| score.into_inner() as f32),
+                suppress_no_mx: dsn.suppress_no_mx,
+                suppression_list: dsn.suppression_list.filter(|list| !list.is_empty()),
+                rate: dsn.rate_limit,
             },
             inbound_limiters: QueueRateLimiters::parse_inbound(bp).await,
             outbound_limiters: QueueRateLimiters::parse_outbound(bp).await,
@@ -245,6 +345,7 @@ impl QueueConfig {
             routing_strategy: Default::default(),
             tls_strategy: Default::default(),
             virtual_queues: Default::default(),
+            unsubscribe: Unsubscribe::parse(bp).await,
         };
 
         // Parse virtual queues
@@ -252,10 +353,12 @@ impl QueueConfig {
         for obj in bp.list_infallible::<MtaVirtualQueue>().await {
             if let Some(queue_name) = QueueName::new(&obj.object.name) {
                 queue_id_to_name.insert(obj.id.id(), queue_name);
+                let rate_limit = bp.compile_expr(obj.id, &obj.object.ctx_rate_limit());
                 queue.virtual_queues.insert(
                     queue_name,
                     VirtualQueue {
                         threads: obj.object.threads_per_node as usize,
+                        rate_limit: (!rate_limit.is_empty()).then_some(rate_limit),
                     },
                 );
             }
@@ -273,6 +376,50 @@ impl QueueConfig {
                 continue;
             };
 
+            // Parse delivery window
+            let window = if !obj.object.delivery_days.is_empty()
+                || obj.object.delivery_hour_start.is_some()
+                || obj.object.delivery_hour_end.is_some()
+                || obj.object.delivery_time_zone.is_some()
+            {
+                let hour_start = obj.object.delivery_hour_start.unwrap_or(0) as u32;
+                let hour_end = obj.object.delivery_hour_end.unwrap_or(24) as u32;
+                if hour_start >= hour_end {
+                    bp.build_error(
+                        obj.id,
+                        "Delivery window start hour must be earlier than its end hour.",
+                    );
+                    continue;
+                }
+
+                let tz = match obj.object.delivery_time_zone {
+                    Some(tz) => match Tz::from_str(tz.as_str()) {
+                        Ok(tz) => tz,
+                        Err(_) => {
+                            bp.build_error(
+                                obj.id,
+                                format!("Unsupported time zone '{}'.", tz.as_str()),
+                            );
+                            continue;
+                        }
+                    },
+                    None => Tz::UTC,
+                };
+
+                Some(DeliveryWindow {
+                    days: obj
+                        .object
+                        .delivery_days
+                        .iter()
+                        .fold(0, |days, day| days | (1 << day.to_id())),
+                    hour_start,
+                    hour_end,
+                    tz,
+                })
+            } else {
+                None
+            };
+
             queue.queue_strategy.insert(
                 obj.object.name,
                 QueueStrategy {
@@ -306,13 +453,21 @@ impl QueueConfig {
                     },
                     expiry: match obj.object.expiry {
                         MtaDeliveryExpiration::Ttl(exp) => {
-                            QueueExpiry::Ttl(exp.expire.into_inner().as_secs())
+                            let ttl = exp.expire.into_inner().as_secs();
+                            match exp.max_attempts {
+                                Some(attempts) => QueueExpiry::TtlOrAttempts {
+                                    ttl: ttl.min(u32::MAX as u64) as u32,
+                                    attempts: attempts as u32,
+                                },
+                                None => QueueExpiry::Ttl(ttl),
+                            }
                         }
                         MtaDeliveryExpiration::Attempts(exp) => {
                             QueueExpiry::Attempts(exp.max_attempts as u32)
                         }
                     },
                     virtual_queue,
+                    window,
                 },
             );
         }
@@ -326,6 +481,13 @@ impl QueueConfig {
                 let ip_host = IpAndHost {
                     ip: ip_host.source_ip.into_inner(),
                     host: ip_host.ehlo_hostname,
+                    weight: ip_host.weight,
+                    warmup: ip_host.warmup_start.map(|start| IpWarmup {
+                        start: start.timestamp() as u64,
+                        days: ip_host.warmup_days,
+                        initial_limit: ip_host.warmup_initial_limit,
+                        target_limit: ip_host.warmup_target_limit,
+                    }),
                 };
                 if ip_host.ip.is_ipv4() {
                     source_ipv4.push(ip_host);
@@ -339,6 +501,12 @@ impl QueueConfig {
                 ConnectionStrategy {
                     source_ipv4,
                     source_ipv6,
+                    source_ip_selection: match obj.object.source_ip_selection {
+                        enums::MtaIpSelection::Weighted => IpSelection::Weighted,
+                        enums::MtaIpSelection::RoundRobin => {
+                            IpSelection::RoundRobin(Arc::new(AtomicUsize::new(0)))
+                        }
+                    },
                     ehlo_hostname: obj.object.ehlo_hostname,
                     timeout_connect: obj.object.connect_timeout.into_inner(),
                     timeout_greeting: obj.object.greeting_timeout.into_inner(),
@@ -350,7 +518,8 @@ impl QueueConfig {
             );
         }
 
-        // Parse routing strategies
+        // Parse routing strategies, routes relaying to the same host share its health
+        let mut relay_health = AHashMap::new();
         for obj in bp.list_infallible::<MtaRoute>().await {
             match obj.object {
                 MtaRoute::Mx(route) => {
@@ -377,18 +546,34 @@ impl QueueConfig {
                             bp.build_error(obj.id, err);
                         })
                         .unwrap_or_default();
+                    let mut hosts = Vec::with_capacity(route.additional_hosts.len() + 1);
+                    hosts.push(RelayHost::new(
+                        route.address,
+                        route.port,
+                        route.weight,
+                        route.max_connections,
+                        &mut relay_health,
+                    ));
+                    for host in route.additional_hosts {
+                        hosts.push(RelayHost::new(
+                            host.address,
+                            host.port,
+                            host.weight,
+                            host.max_connections,
+                            &mut relay_health,
+                        ));
+                    }
+
                     queue.routing_strategy.insert(
                         route.name,
                         RoutingStrategy::Relay(RelayConfig {
-                            address: if let Ok(ip) = route.address.parse() {
-                                HostOrIp::Ip(IpStr {
-                                    ip,
-                                    ip_str: route.address.into(),
-                                })
-                            } else {
-                                HostOrIp::Host(route.address.into())
+                            hosts,
+                            host_selection: match route.host_selection {
+                                enums::MtaIpSelection::Weighted => IpSelection::Weighted,
+                                enums::MtaIpSelection::RoundRobin => {
+                                    IpSelection::RoundRobin(Arc::new(AtomicUsize::new(0)))
+                                }
                             },
-                            port: route.port as u16,
                             protocol: match route.protocol {
                                 enums::MtaProtocol::Smtp => ServerProtocol::Smtp,
                                 enums::MtaProtocol::Lmtp => ServerProtocol::Lmtp,
@@ -402,6 +587,11 @@ impl QueueConfig {
                             }),
                             tls_implicit: route.implicit_tls,
                             tls_allow_invalid_certs: route.allow_invalid_certs,
+                            failure_threshold: route.failure_threshold,
+                            failure_cool_off: route.failure_cool_off.into_inner(),
+                            health_check_interval: route
+                                .health_check_interval
+                                .map(|interval| interval.into_inner()),
                         }),
                     );
                 }
@@ -669,8 +859,8 @@ impl<'x> TryFrom<Variable<'x>> for IpLookupStrategy {
 impl std::fmt::Debug for RelayConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RelayConfig")
-            .field("address", &self.address)
-            .field("port", &self.port)
+            .field("hosts", &self.hosts)
+            .field("host_selection", &self.host_selection)
             .field("protocol", &self.protocol)
             .field("tls_implicit", &self.tls_implicit)
             .field("tls_allow_invalid_certs", &self.tls_allow_invalid_certs)
@@ -678,6 +868,176 @@ impl std::fmt::Debug for RelayConfig {
     }
 }
 
+impl Hash for RelayConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hosts.hash(state);
+        self.protocol.hash(state);
+        self.auth.hash(state);
+        self.tls_implicit.hash(state);
+        self.tls_allow_invalid_certs.hash(state);
+    }
+}
+
+impl PartialEq for RelayConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.hosts == other.hosts
+            && self.protocol == other.protocol
+            && self.auth == other.auth
+            && self.tls_implicit == other.tls_implicit
+            && self.tls_allow_invalid_certs == other.tls_allow_invalid_certs
+    }
+}
+
+impl Eq for RelayConfig {}
+
+impl QueueConfig {
+    // Relay hosts keep their health across configuration reloads
+    pub fn inherit_relay_health(&mut self, prev: &QueueConfig) {
+        let prev_health = prev
+            .routing_strategy
+            .values()
+            .filter_map(|route| match route {
+                RoutingStrategy::Relay(config) => Some(&config.hosts),
+                _ => None,
+            })
+            .flatten()
+            .map(|host| ((host.hostname().to_lowercase(), host.port), &host.health))
+            .collect::<AHashMap<_, _>>();
+
+        for route in self.routing_strategy.values_mut() {
+            if let RoutingStrategy::Relay(config) = route {
+                for host in &mut config.hosts {
+                    if let Some(health) =
+                        prev_health.get(&(host.hostname().to_lowercase(), host.port))
+                    {
+                        host.health = (*health).clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl RelayHost {
+    fn new(
+        address: String,
+        port: u64,
+        weight: u64,
+        max_connections: Option<u64>,
+        relay_health: &mut AHashMap<(String, u16), Arc<RelayHostHealth>>,
+    ) -> Self {
+        let port = port as u16;
+        let health = relay_health
+            .entry((address.to_lowercase(), port))
+            .or_default()
+            .clone();
+        RelayHost {
+            address: if let Ok(ip) = address.parse() {
+                HostOrIp::Ip(IpStr {
+                    ip,
+                    ip_str: address.into(),
+                })
+            } else {
+                HostOrIp::Host(address.into())
+            },
+            port,
+            weight,
+            limiter: max_connections.map(ConcurrencyLimiter::new),
+            health,
+        }
+    }
+
+    pub fn hostname(&self) -> &str {
+        match &self.address {
+            HostOrIp::Host(host) => host.as_ref(),
+            HostOrIp::Ip(ip) => ip.ip_str.as_ref(),
+        }
+    }
+}
+
+impl Hash for RelayHost {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+        self.port.hash(state);
+    }
+}
+
+impl PartialEq for RelayHost {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address && self.port == other.port
+    }
+}
+
+impl Eq for RelayHost {}
+
+impl RelayHostHealth {
+    // Hosts marked as down become available again once their cool-off
+    // period expires, a single failure then marks them down again.
+    pub fn is_available(&self) -> bool {
+        self.down_until.load(Ordering::Relaxed) <= now_millis()
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.down_until.load(Ordering::Relaxed) != 0
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    // Timestamp in milliseconds until which the host is excluded from delivery
+    pub fn down_until(&self) -> Option<u64> {
+        Some(self.down_until.load(Ordering::Relaxed)).filter(|until| *until != 0)
+    }
+
+    // Returns true if the host has just been marked as down
+    pub fn record_failure(&self, threshold: u64, cool_off: Duration) -> bool {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        failures >= threshold
+            && self.down_until.swap(
+                now_millis() + cool_off.as_millis() as u64,
+                Ordering::Relaxed,
+            ) == 0
+    }
+
+    // Returns true if the host was down and has recovered
+    pub fn record_success(&self) -> bool {
+        self.failures.store(0, Ordering::Relaxed);
+        self.down_until.swap(0, Ordering::Relaxed) != 0
+    }
+
+    // Time left until the next probe is due
+    pub fn next_probe_in(&self) -> Duration {
+        Duration::from_millis(
+            self.next_probe
+                .load(Ordering::Relaxed)
+                .saturating_sub(now_millis()),
+        )
+    }
+
+    // Returns true if a probe is due, scheduling the next one
+    pub fn try_schedule_probe(&self, interval: Duration) -> bool {
+        let now = now_millis();
+        let next_probe = self.next_probe.load(Ordering::Relaxed);
+        next_probe <= now
+            && self
+                .next_probe
+                .compare_exchange(
+                    next_probe,
+                    now + interval.as_millis() as u64,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
 impl TlsStrategy {
     #[inline(always)]
     pub fn try_dane(&self) -> bool {
@@ -721,6 +1081,110 @@ impl TlsStrategy {
     }
 }
 
+impl DeliveryWindow {
+    /// Returns `None` when deliveries are allowed at `timestamp`, otherwise
+    /// the timestamp at which the window next opens.
+    pub fn next_start(&self, timestamp: u64) -> Option<u64> {
+        let local = self.tz.timestamp_opt(timestamp as i64, 0).single()?;
+        if self.is_day_allowed(local.weekday())
+            && (self.hour_start..self.hour_end).contains(&local.hour())
+        {
+            return None;
+        }
+
+        let mut date = local.date_naive();
+        for _ in 0..=7 {
+            if self.is_day_allowed(date.weekday())
+                && let Some(start) = date
+                    .and_hms_opt(self.hour_start, 0, 0)
+                    .and_then(|start| {
+                        // Window starts falling into a DST gap open one hour later
+                        self.tz.from_local_datetime(&start).earliest().or_else(|| {
+                            self.tz
+                                .from_local_datetime(&(start + chrono::Duration::hours(1)))
+                                .earliest()
+                        })
+                    })
+                    .map(|start| start.timestamp() as u64)
+                && start > timestamp
+            {
+                return Some(start);
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn is_day_allowed(&self, day: chrono::Weekday) -> bool {
+        self.days == 0 || self.days & (1 << day.num_days_from_monday()) != 0
+    }
+}
+
+impl IpWarmup {
+    // Returns the maximum number of messages allowed today, ramping up
+    // geometrically from the initial to the target limit.
+    pub fn daily_limit(&self, now: u64) -> Option<u64> {
+        let day = now.saturating_sub(self.start) / 86400;
+        if day < self.days {
+            let initial = self.initial_limit.max(1) as f64;
+            let target = self.target_limit.max(self.initial_limit).max(1) as f64;
+            Some((initial * (target / initial).powf(day as f64 / self.days as f64)) as u64)
+        } else {
+            None
+        }
+    }
+}
+
+impl QueueExpiry {
+    /// Seconds after message creation at which delivery is abandoned.
+    pub fn ttl(&self) -> Option<u64> {
+        match self {
+            QueueExpiry::Ttl(ttl) => Some(*ttl),
+            QueueExpiry::TtlOrAttempts { ttl, .. } => Some(*ttl as u64),
+            QueueExpiry::Attempts(_) => None,
+        }
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        match self {
+            QueueExpiry::Attempts(attempts) | QueueExpiry::TtlOrAttempts { attempts, .. } => {
+                Some(*attempts)
+            }
+            QueueExpiry::Ttl(_) => None,
+        }
+    }
+
+    /// Replaces the time limit, keeping any attempt limit in place.
+    pub fn with_ttl(self, ttl: u64) -> Self {
+        match self.max_attempts() {
+            Some(attempts) => QueueExpiry::TtlOrAttempts {
+                ttl: ttl.min(u32::MAX as u64) as u32,
+                attempts,
+            },
+            None => QueueExpiry::Ttl(ttl),
+        }
+    }
+}
+
+impl ArchivedQueueExpiry {
+    pub fn ttl(&self) -> Option<u64> {
+        match self {
+            ArchivedQueueExpiry::Ttl(ttl) => Some(ttl.to_native()),
+            ArchivedQueueExpiry::TtlOrAttempts { ttl, .. } => Some(ttl.to_native() as u64),
+            ArchivedQueueExpiry::Attempts(_) => None,
+        }
+    }
+
+    pub fn max_attempts(&self) -> Option<u32> {
+        match self {
+            ArchivedQueueExpiry::Attempts(attempts)
+            | ArchivedQueueExpiry::TtlOrAttempts { attempts, .. } => Some(attempts.to_native()),
+            ArchivedQueueExpiry::Ttl(_) => None,
+        }
+    }
+}
+
 impl Hash for MxConfig {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.max_mx.hash(state);
@@ -779,6 +1243,12 @@ impl ArchivedQueueName {
     }
 }
 
+impl From<[u8; 8]> for QueueName {
+    fn from(name: [u8; 8]) -> Self {
+        QueueName(name)
+    }
+}
+
 impl Default for QueueName {
     fn default() -> Self {
         DEFAULT_QUEUE_NAME
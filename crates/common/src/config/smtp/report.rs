@@ -11,13 +11,14 @@ use crate::expr::{
 };
 use registry::schema::{
     enums::ExpressionConstant,
-    prelude::ObjectType,
+    prelude::{ObjectType, Property},
     structs::{
         DataRetention, DkimReportSettings, DmarcReportSettings, ReportSettings, SpfReportSettings,
         TlsReportSettings,
     },
 };
 use std::{str::FromStr, time::Duration};
+use store::registry::bootstrap::ReferenceKind;
 
 #[derive(Clone)]
 pub struct ReportConfig {
@@ -54,6 +55,7 @@ pub struct AggregateReport {
     pub send: IfBlock,
     pub sign: IfBlock,
     pub max_size: IfBlock,
+    pub max_compressed_size: IfBlock,
 }
 
 #[derive(Clone)]
@@ -110,9 +112,10 @@ impl ReportConfig {
                     ObjectType::DkimReportSettings.singleton(),
                     &dkim.ctx_subject(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::DkimReportSettings.singleton(),
                     &dkim.ctx_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 send: bp.compile_expr(
                     ObjectType::DkimReportSettings.singleton(),
@@ -132,9 +135,10 @@ impl ReportConfig {
                     ObjectType::SpfReportSettings.singleton(),
                     &spf.ctx_subject(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::SpfReportSettings.singleton(),
                     &spf.ctx_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 send: bp.compile_expr(
                     ObjectType::SpfReportSettings.singleton(),
@@ -154,9 +158,10 @@ impl ReportConfig {
                     ObjectType::DmarcReportSettings.singleton(),
                     &dmarc.ctx_failure_subject(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::DmarcReportSettings.singleton(),
                     &dmarc.ctx_failure_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 send: bp.compile_expr(
                     ObjectType::DmarcReportSettings.singleton(),
@@ -184,14 +189,19 @@ impl ReportConfig {
                     ObjectType::DmarcReportSettings.singleton(),
                     &dmarc.ctx_aggregate_send_frequency(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::DmarcReportSettings.singleton(),
                     &dmarc.ctx_aggregate_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 max_size: bp.compile_expr(
                     ObjectType::DmarcReportSettings.singleton(),
                     &dmarc.ctx_aggregate_max_report_size(),
                 ),
+                max_compressed_size: bp.compile_expr(
+                    ObjectType::DmarcReportSettings.singleton(),
+                    &dmarc.ctx_aggregate_max_compressed_size(),
+                ),
             },
             tls: AggregateReport {
                 name: bp.compile_expr(
@@ -214,14 +224,19 @@ impl ReportConfig {
                     ObjectType::TlsReportSettings.singleton(),
                     &tls.ctx_send_frequency(),
                 ),
-                sign: bp.compile_expr(
+                sign: bp.compile_named_expr(
                     ObjectType::TlsReportSettings.singleton(),
                     &tls.ctx_dkim_sign_domain(),
+                    ReferenceKind::DkimSigner,
                 ),
                 max_size: bp.compile_expr(
                     ObjectType::TlsReportSettings.singleton(),
                     &tls.ctx_max_report_size(),
                 ),
+                max_compressed_size: IfBlock::empty(
+                    ObjectType::TlsReportSettings.singleton(),
+                    Property::MaxReportSize,
+                ),
             },
         }
     }
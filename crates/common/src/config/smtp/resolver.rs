@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::{Server, auth::DomainCache};
 use ahash::AHashMap;
 use mail_auth::{
     MessageAuthenticator,
@@ -90,6 +91,14 @@ pub struct Policy {
     pub max_age: u64,
 }
 
+// Per-domain overrides of the server-wide MTA-STS policy
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PolicyOverride {
+    pub mode: Option<Mode>,
+    pub mx: Box<[MxPattern]>,
+    pub max_age: Option<u64>,
+}
+
 impl CacheItemWeight for Tlsa {
     fn weight(&self) -> u64 {
         self.entries
@@ -278,21 +287,8 @@ impl Policy {
 
             let mut policy = Policy {
                 id: Default::default(),
-                mode: match mta.mode {
-                    PolicyEnforcement::Enforce => Mode::Enforce,
-                    PolicyEnforcement::Testing => Mode::Testing,
-                    PolicyEnforcement::Disable => Mode::None,
-                },
-                mx: mx_hosts
-                    .into_iter()
-                    .map(|mx| {
-                        if let Some(mx) = mx.strip_prefix("*.") {
-                            MxPattern::StartsWith(mx.to_string())
-                        } else {
-                            MxPattern::Equals(mx)
-                        }
-                    })
-                    .collect(),
+                mode: mta.mode.into(),
+                mx: mx_hosts.into_iter().map(MxPattern::from).collect(),
                 max_age: mta.max_age.into_inner().as_secs(),
             };
 
@@ -304,6 +300,40 @@ impl Policy {
         }
     }
 
+    // Builds the policy published for a domain, falling back to the server-wide
+    // policy for any setting the domain does not override.
+    pub fn with_override(global: Option<&Policy>, domain: Option<&PolicyOverride>) -> Option<Self> {
+        let Some(domain) = domain else {
+            return global.cloned();
+        };
+        let mx = if !domain.mx.is_empty() {
+            domain.mx.clone()
+        } else {
+            global?.mx.clone()
+        };
+        let defaults = MtaSts::default();
+
+        let mut policy = Policy {
+            id: Default::default(),
+            mode: domain
+                .mode
+                .or_else(|| global.map(|policy| policy.mode))
+                .unwrap_or_else(|| defaults.mode.into()),
+            mx,
+            max_age: domain
+                .max_age
+                .or_else(|| global.map(|policy| policy.max_age))
+                .unwrap_or_else(|| defaults.max_age.into_inner().as_secs()),
+        };
+        policy.id = policy.hash().to_string();
+
+        Some(policy)
+    }
+
+    pub fn txt_record(&self) -> String {
+        format!("v=STSv1; id={}", self.id)
+    }
+
     fn hash(&self) -> u64 {
         let mut s = DefaultHasher::new();
         self.mode.hash(&mut s);
@@ -313,6 +343,95 @@ impl Policy {
     }
 }
 
+impl Server {
+    pub fn mta_sts_policy(&self, domain: Option<&DomainCache>) -> Option<Policy> {
+        Policy::with_override(
+            self.core.smtp.session.mta_sts_policy.as_ref(),
+            domain.and_then(|domain| domain.settings.mta_sts.as_ref()),
+        )
+    }
+
+    // Returns the policy MX patterns that do not match any of the hostnames
+    // this server is configured to answer as.
+    pub fn mta_sts_unmatched_mx<'x>(&self, policy: &'x Policy) -> Vec<&'x MxPattern> {
+        let network = &self.core.network;
+        let default_host = network.server_name.as_str();
+        let hostnames = network
+            .info
+            .mxs
+            .iter()
+            .map(|mx| mx.hostname.as_deref().unwrap_or(default_host))
+            .chain(
+                network
+                    .info
+                    .services
+                    .iter()
+                    .map(|(_, service)| service.hostname.as_deref().unwrap_or(default_host)),
+            )
+            .chain([default_host])
+            .collect::<Vec<_>>();
+
+        policy
+            .mx
+            .iter()
+            .filter(|mx| !hostnames.iter().any(|hostname| mx.matches(hostname)))
+            .collect()
+    }
+}
+
+impl PolicyOverride {
+    pub fn new(
+        mode: Option<PolicyEnforcement>,
+        mx_hosts: Vec<String>,
+        max_age: Option<u64>,
+    ) -> Option<Self> {
+        if mode.is_some() || !mx_hosts.is_empty() || max_age.is_some() {
+            let mut mx_hosts = mx_hosts;
+            mx_hosts.sort_unstable();
+            mx_hosts.dedup();
+
+            Some(PolicyOverride {
+                mode: mode.map(Mode::from),
+                mx: mx_hosts.into_iter().map(MxPattern::from).collect(),
+                max_age,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl MxPattern {
+    pub fn matches(&self, hostname: &str) -> bool {
+        match self {
+            MxPattern::Equals(mx) => mx.eq_ignore_ascii_case(hostname),
+            MxPattern::StartsWith(mx) => hostname
+                .split_once('.')
+                .is_some_and(|(_, domain)| mx.eq_ignore_ascii_case(domain)),
+        }
+    }
+}
+
+impl From<String> for MxPattern {
+    fn from(mx: String) -> Self {
+        if let Some(mx) = mx.strip_prefix("*.") {
+            MxPattern::StartsWith(mx.to_string())
+        } else {
+            MxPattern::Equals(mx)
+        }
+    }
+}
+
+impl From<PolicyEnforcement> for Mode {
+    fn from(mode: PolicyEnforcement) -> Self {
+        match mode {
+            PolicyEnforcement::Enforce => Mode::Enforce,
+            PolicyEnforcement::Testing => Mode::Testing,
+            PolicyEnforcement::Disable => Mode::None,
+        }
+    }
+}
+
 impl FromStr for Mode {
     type Err = String;
     fn from_str(value: &str) -> Result<Self, Self::Err> {
@@ -362,7 +481,7 @@ impl Display for Policy {
         match self.mode {
             Mode::Enforce => f.write_str("enforce")?,
             Mode::Testing => f.write_str("testing")?,
-            Mode::None => unreachable!(),
+            Mode::None => f.write_str("none")?,
         }
         f.write_str("\r\nmax_age: ")?;
         self.max_age.fmt(f)?;
@@ -10,14 +10,14 @@ use crate::expr::{
     Variable,
     if_block::{BootstrapExprExt, IfBlock},
 };
-use ahash::AHashSet;
+use ahash::{AHashMap, AHashSet};
 use hyper::HeaderMap;
 use registry::schema::{
-    enums::{self, ExpressionConstant, MtaStage},
+    enums::{self, ExpressionConstant, MtaHookProtocol, MtaResponseId, MtaStage},
     prelude::ObjectType,
     structs::{
-        MtaExtensions, MtaHook, MtaInboundSession, MtaMilter, MtaStageAuth, MtaStageConnect,
-        MtaStageData, MtaStageEhlo, MtaStageMail, MtaStageRcpt,
+        MtaExtensions, MtaHook, MtaInboundSession, MtaMilter, MtaResponse, MtaStageAuth,
+        MtaStageConnect, MtaStageData, MtaStageEhlo, MtaStageMail, MtaStageRcpt,
     },
 };
 use smtp_proto::*;
@@ -26,6 +26,7 @@ use std::{
     str::FromStr,
     time::Duration,
 };
+use store::registry::bootstrap::ReferenceKind;
 
 #[derive(Clone)]
 pub struct SessionConfig {
@@ -44,6 +45,7 @@ pub struct SessionConfig {
 
     pub milters: Vec<Milter>,
     pub hooks: Vec<MTAHook>,
+    pub responses: AHashMap<MtaResponseId, IfBlock>,
 }
 
 #[derive(Clone)]
@@ -72,6 +74,9 @@ pub struct Extensions {
     pub future_release: IfBlock,
     pub deliver_by: IfBlock,
     pub mt_priority: IfBlock,
+    pub hidden: IfBlock,
+    pub reject_hidden: IfBlock,
+    pub delivery_callback: IfBlock,
 }
 
 #[derive(Clone)]
@@ -81,6 +86,8 @@ pub struct Auth {
     pub must_match_sender: IfBlock,
     pub errors_max: IfBlock,
     pub errors_wait: IfBlock,
+    pub client_cert: IfBlock,
+    pub client_cert_revocation_list: Option<String>,
 }
 
 #[derive(Clone)]
@@ -98,6 +105,9 @@ pub struct Rcpt {
     pub errors_max: IfBlock,
     pub errors_wait: IfBlock,
     pub max_recipients: IfBlock,
+    pub max_expansion_depth: usize,
+    pub max_expansion_recipients: usize,
+    pub expansion_trace_header: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -122,6 +132,16 @@ pub struct Data {
     pub add_message_id: IfBlock,
     pub add_date: IfBlock,
     pub add_delivered_to: bool,
+    pub from_alignment: IfBlock,
+    pub lmtp_local_delivery: IfBlock,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FromAlignment {
+    Reject,
+    Rewrite,
+    #[default]
+    Disable,
 }
 
 #[derive(Clone)]
@@ -161,6 +181,8 @@ pub struct MTAHook {
     pub tempfail_on_error: bool,
     pub run_on_stage: AHashSet<Stage>,
     pub max_response_size: usize,
+    pub protocol: MtaHookProtocol,
+    pub stream_chunk_size: usize,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -190,9 +212,18 @@ impl SessionConfig {
             let id = hook.id;
             let hook = hook.object;
             let enable = bp.compile_expr(id, &hook.ctx_enable());
+            let content_type = match hook.protocol {
+                MtaHookProtocol::Json => "application/json",
+                MtaHookProtocol::Stream => "application/x-ndjson",
+                MtaHookProtocol::Grpc => "application/grpc",
+            };
+            if hook.protocol == MtaHookProtocol::Grpc && hook.allow_invalid_certs {
+                bp.build_error(id, "Invalid certificates cannot be allowed for gRPC hooks");
+                continue;
+            }
             let headers = match hook
                 .http_auth
-                .build_headers(hook.http_headers, "application/json".into())
+                .build_headers(hook.http_headers, content_type.into())
                 .await
             {
                 Ok(headers) => headers,
@@ -212,9 +243,37 @@ impl SessionConfig {
                 tempfail_on_error: hook.temp_fail_on_error,
                 run_on_stage: hook.stages.into_iter().map(Stage::from).collect(),
                 max_response_size: hook.max_response_size as usize,
+                protocol: hook.protocol,
+                stream_chunk_size: hook.stream_chunk_size as usize,
             });
         }
 
+        let mut responses = AHashMap::new();
+        for response in bp.list_infallible::<MtaResponse>().await {
+            responses.insert(
+                response.object.response_id,
+                bp.compile_expr(response.id, &response.object.ctx_message()),
+            );
+        }
+
+        let client_cert = bp.compile_expr(
+            ObjectType::MtaStageAuth.singleton(),
+            &auth.ctx_client_cert_account(),
+        );
+        let client_cert_revocation_list = auth
+            .client_cert_revocation_list
+            .clone()
+            .filter(|list| !list.is_empty());
+        if !client_cert.is_false() && client_cert_revocation_list.is_none() {
+            bp.build_warning(
+                ObjectType::MtaStageAuth.singleton(),
+                concat!(
+                    "Client certificate revocation checking is disabled. ",
+                    "Configure a revocation list to reject revoked certificates."
+                ),
+            );
+        }
+
         SessionConfig {
             timeout: bp.compile_expr(
                 ObjectType::MtaInboundSession.singleton(),
@@ -233,9 +292,10 @@ impl SessionConfig {
                     ObjectType::MtaStageConnect.singleton(),
                     &connect.ctx_hostname(),
                 ),
-                script: bp.compile_expr(
+                script: bp.compile_named_expr(
                     ObjectType::MtaStageConnect.singleton(),
                     &connect.ctx_script(),
+                    ReferenceKind::SieveScript,
                 ),
                 greeting: bp.compile_expr(
                     ObjectType::MtaStageConnect.singleton(),
@@ -243,7 +303,11 @@ impl SessionConfig {
                 ),
             },
             ehlo: Ehlo {
-                script: bp.compile_expr(ObjectType::MtaStageEhlo.singleton(), &ehlo.ctx_script()),
+                script: bp.compile_named_expr(
+                    ObjectType::MtaStageEhlo.singleton(),
+                    &ehlo.ctx_script(),
+                    ReferenceKind::SieveScript,
+                ),
                 require: bp.compile_expr(ObjectType::MtaStageEhlo.singleton(), &ehlo.ctx_require()),
                 reject_non_fqdn: bp.compile_expr(
                     ObjectType::MtaStageEhlo.singleton(),
@@ -268,9 +332,15 @@ impl SessionConfig {
                     ObjectType::MtaStageAuth.singleton(),
                     &auth.ctx_wait_on_fail(),
                 ),
+                client_cert,
+                client_cert_revocation_list,
             },
             mail: Mail {
-                script: bp.compile_expr(ObjectType::MtaStageMail.singleton(), &mail.ctx_script()),
+                script: bp.compile_named_expr(
+                    ObjectType::MtaStageMail.singleton(),
+                    &mail.ctx_script(),
+                    ReferenceKind::SieveScript,
+                ),
                 rewrite: bp.compile_expr(ObjectType::MtaStageMail.singleton(), &mail.ctx_rewrite()),
                 is_allowed: bp.compile_expr(
                     ObjectType::MtaStageMail.singleton(),
@@ -278,7 +348,11 @@ impl SessionConfig {
                 ),
             },
             rcpt: Rcpt {
-                script: bp.compile_expr(ObjectType::MtaStageRcpt.singleton(), &rcpt.ctx_script()),
+                script: bp.compile_named_expr(
+                    ObjectType::MtaStageRcpt.singleton(),
+                    &rcpt.ctx_script(),
+                    ReferenceKind::SieveScript,
+                ),
                 relay: bp.compile_expr(
                     ObjectType::MtaStageRcpt.singleton(),
                     &rcpt.ctx_allow_relaying(),
@@ -296,9 +370,16 @@ impl SessionConfig {
                     ObjectType::MtaStageRcpt.singleton(),
                     &rcpt.ctx_max_recipients(),
                 ),
+                max_expansion_depth: rcpt.max_expansion_depth as usize,
+                max_expansion_recipients: rcpt.max_expansion_recipients as usize,
+                expansion_trace_header: rcpt.expansion_trace_header,
             },
             data: Data {
-                script: bp.compile_expr(ObjectType::MtaStageData.singleton(), &data.ctx_script()),
+                script: bp.compile_named_expr(
+                    ObjectType::MtaStageData.singleton(),
+                    &data.ctx_script(),
+                    ReferenceKind::SieveScript,
+                ),
                 spam_filter: bp.compile_expr(
                     ObjectType::MtaStageData.singleton(),
                     &data.ctx_enable_spam_filter(),
@@ -340,6 +421,14 @@ impl SessionConfig {
                     &data.ctx_add_date_header(),
                 ),
                 add_delivered_to: data.add_delivered_to_header,
+                from_alignment: bp.compile_expr(
+                    ObjectType::MtaStageData.singleton(),
+                    &data.ctx_from_alignment(),
+                ),
+                lmtp_local_delivery: bp.compile_expr(
+                    ObjectType::MtaStageData.singleton(),
+                    &data.ctx_lmtp_local_delivery(),
+                ),
             },
             extensions: Extensions {
                 pipelining: bp
@@ -367,6 +456,18 @@ impl SessionConfig {
                     ObjectType::MtaExtensions.singleton(),
                     &ext.ctx_mt_priority(),
                 ),
+                hidden: bp.compile_expr(
+                    ObjectType::MtaExtensions.singleton(),
+                    &ext.ctx_hidden_extensions(),
+                ),
+                reject_hidden: bp.compile_expr(
+                    ObjectType::MtaExtensions.singleton(),
+                    &ext.ctx_reject_hidden_extensions(),
+                ),
+                delivery_callback: bp.compile_expr(
+                    ObjectType::MtaExtensions.singleton(),
+                    &ext.ctx_delivery_callback(),
+                ),
             },
             mta_sts_policy: Policy::try_parse(bp).await,
             milters: bp
@@ -413,6 +514,7 @@ impl SessionConfig {
                 })
                 .collect(),
             hooks,
+            responses,
         }
     }
 }
@@ -521,6 +623,86 @@ impl From<u64> for Mechanism {
     }
 }
 
+static EHLO_KEYWORDS: &[(&str, u32)] = &[
+    ("PIPELINING", EXT_PIPELINING),
+    ("CHUNKING", EXT_CHUNKING),
+    ("SIZE", EXT_SIZE),
+    ("DSN", EXT_DSN),
+    ("REQUIRETLS", EXT_REQUIRE_TLS),
+    ("8BITMIME", EXT_8BIT_MIME),
+    ("BINARYMIME", EXT_BINARY_MIME),
+    ("SMTPUTF8", EXT_SMTP_UTF8),
+    ("ENHANCEDSTATUSCODES", EXT_ENHANCED_STATUS_CODES),
+    ("DELIVERBY", EXT_DELIVER_BY),
+    ("FUTURERELEASE", EXT_FUTURE_RELEASE),
+    ("MT-PRIORITY", EXT_MT_PRIORITY),
+    ("NO-SOLICITING", EXT_NO_SOLICITING),
+    ("VRFY", EXT_VRFY),
+    ("EXPN", EXT_EXPN),
+];
+
+/// Set of EHLO capabilities, as a bitmask of `EXT_*` flags.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EhloExtensions(u32);
+
+impl EhloExtensions {
+    pub fn contains(&self, extension: u32) -> bool {
+        self.0 & extension != 0
+    }
+
+    pub fn keyword(extension: u32) -> &'static str {
+        EHLO_KEYWORDS
+            .iter()
+            .find(|(_, ext)| *ext == extension)
+            .map_or("UNKNOWN", |(keyword, _)| keyword)
+    }
+}
+
+impl FromStr for EhloExtensions {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        EHLO_KEYWORDS
+            .iter()
+            .find(|(keyword, _)| keyword.eq_ignore_ascii_case(value.trim()))
+            .map(|(_, ext)| EhloExtensions(*ext))
+            .ok_or_else(|| format!("Unsupported EHLO extension {:?}.", value))
+    }
+}
+
+impl<'x> TryFrom<Variable<'x>> for EhloExtensions {
+    type Error = ();
+
+    fn try_from(value: Variable<'x>) -> Result<Self, Self::Error> {
+        match value {
+            Variable::Integer(0) => Ok(EhloExtensions::default()),
+            Variable::String(value) => value.as_str().parse().map_err(|_| ()),
+            Variable::Array(items) => {
+                let mut extensions = 0;
+
+                for item in items {
+                    match item {
+                        Variable::String(value) => {
+                            extensions |=
+                                value.as_str().parse::<EhloExtensions>().map_err(|_| ())?.0
+                        }
+                        _ => return Err(()),
+                    }
+                }
+
+                Ok(EhloExtensions(extensions))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<EhloExtensions> for u32 {
+    fn from(value: EhloExtensions) -> Self {
+        value.0
+    }
+}
+
 impl<'x> TryFrom<Variable<'x>> for MtPriority {
     type Error = ();
 
@@ -549,6 +731,22 @@ impl<'x> TryFrom<Variable<'x>> for MtPriority {
     }
 }
 
+impl<'x> TryFrom<Variable<'x>> for FromAlignment {
+    type Error = ();
+
+    fn try_from(value: Variable<'x>) -> Result<Self, Self::Error> {
+        match value {
+            Variable::Constant(value) => match value {
+                ExpressionConstant::Reject => Ok(FromAlignment::Reject),
+                ExpressionConstant::Rewrite => Ok(FromAlignment::Rewrite),
+                ExpressionConstant::Disable => Ok(FromAlignment::Disable),
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
 impl From<MtaStage> for Stage {
     fn from(value: MtaStage) -> Self {
         match value {
@@ -5,9 +5,9 @@
  */
 
 use coordinator::Coordinator;
-use directory::{Directories, Directory};
-use registry::schema::prelude::ObjectType;
-use std::{collections::HashMap, sync::Arc};
+use directory::{Directories, Directory, DirectoryChain};
+use registry::schema::{prelude::ObjectType, structs::DataRetention};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use store::{
     BlobStore, InMemoryStore, RegistryStore, SearchStore, Store, registry::bootstrap::Bootstrap,
 };
@@ -26,6 +26,14 @@ pub struct Storage {
     pub coordinator: Coordinator,
     pub directory: Option<Arc<Directory>>,
     pub directories: IdMap<Directory>,
+    pub directory_chain: DirectoryChain,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BackupConfig {
+    pub root: Option<PathBuf>,
+    pub max_increments: u64,
+    pub include_blobs: bool,
 }
 
 impl Storage {
@@ -52,6 +60,19 @@ impl Storage {
             metrics: Store::build_metrics(bp).await.unwrap_or_default(),
             directory: directory.default_directory,
             directories: directory.directories,
+            directory_chain: directory.chain,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub async fn parse(bp: &mut Bootstrap) -> Self {
+        let dr = bp.setting_infallible::<DataRetention>().await;
+
+        BackupConfig {
+            root: dr.backup_directory.map(PathBuf::from),
+            max_increments: dr.backup_max_increments,
+            include_blobs: dr.backup_include_blobs,
         }
     }
 }
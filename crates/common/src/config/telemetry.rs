@@ -21,7 +21,7 @@ use registry::schema::{
 };
 use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
 use store::registry::bootstrap::Bootstrap;
-use trc::{EventType, Level, MetricType, TelemetryEvent, ipc::subscriber::Interests};
+use trc::{Collector, EventType, Level, MetricType, TelemetryEvent, ipc::subscriber::Interests};
 
 #[derive(Debug)]
 pub struct TelemetrySubscriber {
@@ -128,6 +128,7 @@ pub struct Metrics {
     pub prometheus: Option<PrometheusMetrics>,
     pub otel: Option<Arc<OtelMetrics>>,
     pub log_path: Option<String>,
+    pub queue_top_domains: usize,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -148,6 +149,10 @@ impl Telemetry {
             let event_id = metric_type.event_id();
             if event_id != usize::MAX {
                 telemetry.metrics.set(event_id);
+            } else {
+                for event_id in Collector::metric_source_events(metric_type) {
+                    telemetry.metrics.set(event_id);
+                }
             }
         });
 
@@ -686,6 +691,7 @@ impl Metrics {
                 }
                 structs::MetricsOtel::Disabled => None,
             },
+            queue_top_domains: metrics.queue_top_domains as usize,
             log_path: bp
                 .list_infallible::<Tracer>()
                 .await
@@ -0,0 +1,345 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{Server, auth::EmailCache, network::batv::parse_prvs};
+use ahash::AHashMap;
+use registry::{
+    schema::{
+        enums::{Locale, MessageTemplateId},
+        prelude::Property,
+        structs,
+    },
+    types::{EnumImpl, id::ObjectId},
+};
+use std::str::FromStr;
+use store::registry::bootstrap::Bootstrap;
+use trc::AddContext;
+use utils::template::{Template, TemplateItem, Variables};
+
+#[derive(Debug, Clone, Default)]
+pub struct MessageTemplates {
+    templates: AHashMap<(MessageTemplateId, TemplateOwner), Vec<MessageTemplateEntry>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum TemplateOwner {
+    Domain(u32),
+    Tenant(u32),
+    Global,
+}
+
+// Domain, tenant and locale a generated message is rendered for
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TemplateScope {
+    pub domain_id: Option<u32>,
+    pub tenant_id: Option<u32>,
+    pub locale: Locale,
+}
+
+#[derive(Debug, Clone)]
+struct MessageTemplateEntry {
+    id: ObjectId,
+    locale: Option<Locale>,
+    template: Result<MessageTemplate, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageTemplate {
+    pub subject: Template<MessageTemplateVariable>,
+    pub text_body: Template<MessageTemplateVariable>,
+    pub html_body: Option<Template<MessageTemplateVariable>>,
+}
+
+pub struct RenderedMessage {
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+pub enum MessageTemplateVariable {
+    #[default]
+    Recipient,
+    Reason,
+    QueueId,
+    Size,
+    Hostname,
+    Percent,
+    Used,
+    Limit,
+    Subject,
+    Body,
+}
+
+impl MessageTemplates {
+    pub async fn parse(bp: &mut Bootstrap) -> Self {
+        let mut templates: AHashMap<_, Vec<MessageTemplateEntry>> = AHashMap::new();
+
+        for template in bp.list_infallible::<structs::MessageTemplate>().await {
+            let id = template.id;
+            let object = template.object;
+
+            // Invalid templates are kept so that rendering falls back to the
+            // built-in message instead of using a less specific template
+            let parsed = MessageTemplate::parse(&object);
+            if let Err((property, err)) = &parsed {
+                bp.build_warning(
+                    id,
+                    format!("Invalid template in property {}: {err}", property.as_str()),
+                );
+            }
+
+            let owner = if let Some(domain_id) = object.domain_id {
+                TemplateOwner::Domain(domain_id.document_id())
+            } else if let Some(tenant_id) = object.member_tenant_id {
+                TemplateOwner::Tenant(tenant_id.document_id())
+            } else {
+                TemplateOwner::Global
+            };
+
+            templates
+                .entry((object.template_id, owner))
+                .or_default()
+                .push(MessageTemplateEntry {
+                    id,
+                    locale: object.locale,
+                    template: parsed.map_err(|(_, err)| err),
+                });
+        }
+
+        MessageTemplates { templates }
+    }
+
+    // Templates are looked up for the domain, the tenant and finally the
+    // global ones, each one in the recipient's locale before its default
+    pub fn get(
+        &self,
+        template_id: MessageTemplateId,
+        scope: &TemplateScope,
+    ) -> Option<&MessageTemplate> {
+        for owner in [
+            scope.domain_id.map(TemplateOwner::Domain),
+            scope.tenant_id.map(TemplateOwner::Tenant),
+            Some(TemplateOwner::Global),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(entry) = self
+                .templates
+                .get(&(template_id, owner))
+                .and_then(|entries| MessageTemplateEntry::find(entries, scope.locale))
+            {
+                return match &entry.template {
+                    Ok(template) => Some(template),
+                    Err(err) => {
+                        trc::event!(
+                            Resource(trc::ResourceEvent::TemplateError),
+                            Id = entry.id.id().id(),
+                            Details = template_id.as_str(),
+                            Reason = err.clone(),
+                        );
+                        None
+                    }
+                };
+            }
+        }
+
+        None
+    }
+}
+
+impl Server {
+    // Obtains the template scope of an address, addresses without an
+    // account use the tenant of their domain and the default locale
+    pub async fn recipient_template_scope(&self, address: &str) -> TemplateScope {
+        let address = parse_prvs(address)
+            .map_or(address, |(_, address)| address)
+            .to_lowercase();
+        let domain = match address.rsplit_once('@') {
+            Some((_, domain)) => self.domain(domain).await.unwrap_or_else(|err| {
+                trc::error!(
+                    err.caused_by(trc::location!())
+                        .details("Failed to obtain recipient domain")
+                );
+                None
+            }),
+            None => None,
+        };
+
+        match self.rcpt_id_from_email(&address).await {
+            Ok(Some(EmailCache::Account(account_id))) => {
+                match self.account(account_id).await.caused_by(trc::location!()) {
+                    Ok(account) => {
+                        return TemplateScope {
+                            domain_id: domain.map(|domain| domain.id).or_else(|| {
+                                account.addresses.first().map(|address| address.domain_id)
+                            }),
+                            tenant_id: account.id_tenant,
+                            locale: account.locale,
+                        };
+                    }
+                    Err(err) => {
+                        trc::error!(err.details("Failed to obtain recipient locale"));
+                    }
+                }
+            }
+            Ok(_) => (),
+            Err(err) => {
+                trc::error!(
+                    err.caused_by(trc::location!())
+                        .details("Failed to obtain recipient locale")
+                );
+            }
+        }
+
+        TemplateScope {
+            domain_id: domain.as_ref().map(|domain| domain.id),
+            tenant_id: domain.and_then(|domain| domain.id_tenant),
+            locale: Locale::default(),
+        }
+    }
+
+    // Accounts use the domain of their primary address
+    pub async fn account_template_scope(&self, account_id: u32) -> TemplateScope {
+        match self.account(account_id).await.caused_by(trc::location!()) {
+            Ok(account) => TemplateScope {
+                domain_id: account.addresses.first().map(|address| address.domain_id),
+                tenant_id: account.id_tenant,
+                locale: account.locale,
+            },
+            Err(err) => {
+                trc::error!(
+                    err.account_id(account_id)
+                        .details("Failed to obtain account locale")
+                );
+                TemplateScope::default()
+            }
+        }
+    }
+}
+
+impl MessageTemplateEntry {
+    fn find(entries: &[Self], locale: Locale) -> Option<&Self> {
+        let language = locale
+            .as_str()
+            .split_once('_')
+            .map_or(locale.as_str(), |(language, _)| language);
+
+        entries
+            .iter()
+            .find(|entry| entry.locale == Some(locale))
+            .or_else(|| {
+                entries.iter().find(|entry| {
+                    entry.locale.is_some_and(|entry_locale| {
+                        entry_locale
+                            .as_str()
+                            .split_once('_')
+                            .is_some_and(|(entry_language, _)| entry_language == language)
+                    })
+                })
+            })
+            .or_else(|| entries.iter().find(|entry| entry.locale.is_none()))
+    }
+}
+
+impl MessageTemplate {
+    fn parse(template: &structs::MessageTemplate) -> Result<Self, (Property, String)> {
+        Ok(MessageTemplate {
+            subject: parse_text_template(&template.subject)
+                .map_err(|err| (Property::Subject, err))?,
+            text_body: parse_text_template(&template.text_body)
+                .map_err(|err| (Property::TextBody, err))?,
+            html_body: template
+                .html_body
+                .as_deref()
+                .map(parse_html_template)
+                .transpose()
+                .map_err(|err| (Property::HtmlBody, err))?,
+        })
+    }
+
+    pub fn render(
+        &self,
+        variables: &Variables<MessageTemplateVariable, String>,
+    ) -> RenderedMessage {
+        RenderedMessage {
+            // Line breaks in the subject would inject headers
+            subject: self
+                .subject
+                .eval(variables)
+                .replace(['\r', '\n'], " ")
+                .trim()
+                .to_string(),
+            text_body: self.text_body.eval(variables),
+            html_body: self
+                .html_body
+                .as_ref()
+                .map(|template| template.eval(variables)),
+        }
+    }
+}
+
+// Variables are only HTML-escaped in HTML bodies, except for the body of
+// auto-replies which is already HTML
+fn parse_html_template(value: &str) -> Result<Template<MessageTemplateVariable>, String> {
+    let mut template = Template::parse(value)?;
+    for item in &mut template.items {
+        if let TemplateItem::Variable {
+            name: MessageTemplateVariable::Body,
+            escape,
+        } = item
+        {
+            *escape = false;
+        }
+    }
+    Ok(template)
+}
+
+fn parse_text_template(value: &str) -> Result<Template<MessageTemplateVariable>, String> {
+    let mut template = Template::parse(value)?;
+    for item in &mut template.items {
+        if let TemplateItem::Variable { escape, .. } = item {
+            *escape = false;
+        }
+    }
+    Ok(template)
+}
+
+impl FromStr for MessageTemplateVariable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recipient" => Ok(MessageTemplateVariable::Recipient),
+            "reason" => Ok(MessageTemplateVariable::Reason),
+            "queue_id" => Ok(MessageTemplateVariable::QueueId),
+            "size" => Ok(MessageTemplateVariable::Size),
+            "hostname" => Ok(MessageTemplateVariable::Hostname),
+            "percent" => Ok(MessageTemplateVariable::Percent),
+            "used" => Ok(MessageTemplateVariable::Used),
+            "limit" => Ok(MessageTemplateVariable::Limit),
+            "subject" => Ok(MessageTemplateVariable::Subject),
+            "body" => Ok(MessageTemplateVariable::Body),
+            _ => Err(format!("Unknown message template variable: {}", s)),
+        }
+    }
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{size} {}", UNITS[0])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
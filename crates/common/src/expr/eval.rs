@@ -7,7 +7,7 @@
 use super::{
     BinaryOperator, Constant, Expression, ExpressionItem, StringCow, SystemVariable, UnaryOperator,
     Variable,
-    functions::{FUNCTIONS, ResolveVariable},
+    functions::{F_HEADER, F_HEADERS, F_META, FUNCTIONS, ResolveVariable},
     if_block::IfBlock,
 };
 use crate::Server;
@@ -278,12 +278,22 @@ impl<'x, V: ResolveVariable> EvalContext<'x, V, Expression, &mut Vec<CompactStri
                     let result = if let Some((_, fnc, _)) = FUNCTIONS.get(*id as usize) {
                         (fnc)(arguments)
                     } else {
-                        Box::pin(self.core.eval_fnc(
-                            *id - FUNCTIONS.len() as u32,
-                            arguments,
-                            self.session_id,
-                        ))
-                        .await?
+                        match *id - FUNCTIONS.len() as u32 {
+                            fnc_id @ (F_HEADER | F_HEADERS) => {
+                                let name = arguments.pop().unwrap_or_default().into_string();
+                                self.resolver
+                                    .resolve_header(self.core, name.as_str(), fnc_id == F_HEADERS)
+                                    .await
+                            }
+                            F_META => {
+                                let name = arguments.pop().unwrap_or_default().into_string();
+                                self.resolver.resolve_meta(name.as_str())
+                            }
+                            fnc_id => {
+                                Box::pin(self.core.eval_fnc(fnc_id, arguments, self.session_id))
+                                    .await?
+                            }
+                        }
                     };
 
                     stack.push(result);
@@ -9,7 +9,7 @@ use crate::{Server, expr::StringCow};
 use compact_str::{CompactString, ToCompactString};
 use mail_auth::IpLookupStrategy;
 use std::{cmp::Ordering, net::IpAddr, vec::IntoIter};
-use store::{Deserialize, Rows, Value, dispatch::lookup::KeyValue};
+use store::{Deserialize, Rows, Value, dispatch::lookup::KeyValue, write::now};
 use trc::AddContext;
 
 impl Server {
@@ -106,6 +106,32 @@ impl Server {
             }
             F_DNS_QUERY => self.dns_query(params).await,
             F_SQL_QUERY => self.sql_query(params, session_id).await,
+            F_IN_DELIVERY_WINDOW => {
+                let queue = params.next_as_string();
+
+                Ok(self
+                    .core
+                    .smtp
+                    .queue
+                    .queue_strategy
+                    .get(queue.as_str())
+                    .and_then(|queue| queue.window.as_ref())
+                    .is_none_or(|window| window.next_start(now()).is_none())
+                    .into())
+            }
+            F_DOMAIN_SETTING => {
+                let domain = params.next_as_string();
+                let name = params.next_as_string();
+
+                self.domain_settings(domain.as_str())
+                    .await
+                    .caused_by(trc::location!())
+                    .map(|settings| {
+                        settings
+                            .and_then(|settings| settings.get(name.as_str()))
+                            .unwrap_or_default()
+                    })
+            }
             _ => Ok(Variable::default()),
         }
     }
@@ -353,6 +379,11 @@ impl From<store::Value<'static>> for VariableWrapper {
                 std::borrow::Cow::Borrowed(v) => CompactString::from_utf8_lossy(v),
                 std::borrow::Cow::Owned(v) => CompactString::from_utf8_lossy(&v),
             })),
+            Value::Array(v) => Variable::Array(
+                v.into_iter()
+                    .map(|v| VariableWrapper::from(v).into_inner())
+                    .collect(),
+            ),
             Value::Null => Variable::String(StringCow::Borrowed("")),
         })
     }
@@ -380,6 +411,7 @@ fn into_variable(value: Value) -> Variable {
         Value::Float(v) => Variable::Float(v),
         Value::Text(v) => Variable::String(v.into()),
         Value::Blob(v) => Variable::String(StringCow::Owned(CompactString::from_utf8_lossy(&v))),
+        Value::Array(v) => Variable::Array(v.into_iter().map(into_variable).collect()),
         Value::Null => Variable::default(),
     }
 }
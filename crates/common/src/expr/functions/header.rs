@@ -0,0 +1,105 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::expr::Variable;
+use compact_str::CompactString;
+use mail_parser::{Header, MessageParser};
+
+/// Maximum size of the header block made available to expressions.
+pub const MAX_EXPR_HEADERS_SIZE: usize = 64 * 1024;
+const MAX_EXPR_HEADERS: usize = 512;
+
+/// Header values available to the `header()` and `headers()` expression functions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MessageHeaders {
+    headers: Vec<(CompactString, CompactString)>,
+}
+
+impl MessageHeaders {
+    pub fn new(headers: &[Header<'_>], raw_message: &[u8]) -> Self {
+        let mut total_size = 0;
+
+        MessageHeaders {
+            headers: headers
+                .iter()
+                .take(MAX_EXPR_HEADERS)
+                .map_while(|header| {
+                    let value = raw_message
+                        .get(header.offset_start as usize..header.offset_end as usize)
+                        .unwrap_or_default();
+                    total_size += value.len();
+                    (total_size <= MAX_EXPR_HEADERS_SIZE).then(|| {
+                        (
+                            CompactString::from(header.name.as_str()),
+                            unfold_value(value),
+                        )
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Parses the header block of a raw message, ignoring its body.
+    pub fn parse(raw_message: &[u8]) -> Self {
+        let raw_message = raw_message
+            .get(..MAX_EXPR_HEADERS_SIZE)
+            .unwrap_or(raw_message);
+        MessageParser::new()
+            .parse_headers(raw_message)
+            .map(|message| MessageHeaders::new(message.headers(), raw_message))
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, name: &str, all: bool) -> Variable<'_> {
+        if all {
+            self.all(name)
+        } else {
+            self.first(name)
+        }
+    }
+
+    pub fn first(&self, name: &str) -> Variable<'_> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| Variable::from(value.as_str()))
+            .unwrap_or_default()
+    }
+
+    pub fn all(&self, name: &str) -> Variable<'_> {
+        Variable::Array(
+            self.headers
+                .iter()
+                .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| Variable::from(value.as_str()))
+                .collect(),
+        )
+    }
+}
+
+/// Value returned when the message headers are not available.
+pub fn missing_header(all: bool) -> Variable<'static> {
+    if all {
+        Variable::Array(vec![])
+    } else {
+        Variable::default()
+    }
+}
+
+fn unfold_value(value: &[u8]) -> CompactString {
+    let value = String::from_utf8_lossy(value);
+    let mut result = CompactString::with_capacity(value.len());
+    for line in value.split('\n') {
+        let line = line.trim();
+        if !line.is_empty() {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            result.push_str(line);
+        }
+    }
+    result
+}
@@ -19,6 +19,16 @@ pub(crate) fn fn_is_empty(v: Vec<Variable>) -> Variable {
     .into()
 }
 
+pub(crate) fn fn_coalesce(v: Vec<Variable>) -> Variable {
+    v.into_iter()
+        .find(|v| match v {
+            Variable::String(s) => !s.is_empty(),
+            Variable::Integer(_) | Variable::Float(_) | Variable::Constant(_) => true,
+            Variable::Array(a) => !a.is_empty(),
+        })
+        .unwrap_or_default()
+}
+
 pub(crate) fn fn_is_number(v: Vec<Variable>) -> Variable {
     matches!(&v[0], Variable::Integer(_) | Variable::Float(_)).into()
 }
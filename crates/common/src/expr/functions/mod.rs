@@ -5,17 +5,36 @@
  */
 
 use super::{StringCow, Variable};
+use crate::Server;
 use registry::schema::enums::ExpressionVariable;
 
 pub mod array;
 pub mod asynch;
 pub mod email;
+pub mod header;
 pub mod misc;
 pub mod text;
 
 pub trait ResolveVariable: Sync + Send {
     fn resolve_variable(&self, variable: ExpressionVariable) -> Variable<'_>;
     fn resolve_global(&self, variable: &str) -> Variable<'_>;
+
+    /// Resolves the first value (or all values) of a message header, in
+    /// contexts where a message is available.
+    fn resolve_header(
+        &self,
+        _server: &Server,
+        _name: &str,
+        all: bool,
+    ) -> impl Future<Output = Variable<'_>> + Send {
+        async move { header::missing_header(all) }
+    }
+
+    /// Resolves a custom metadata value, in contexts where a queued message
+    /// is available.
+    fn resolve_meta(&self, _name: &str) -> Variable<'_> {
+        Variable::default()
+    }
 }
 
 impl<'x> Variable<'x> {
@@ -81,8 +100,12 @@ pub(crate) const FUNCTIONS: &[(&str, fn(Vec<Variable>) -> Variable, u32)] = &[
     ("split_words", text::fn_split_words, 1),
     ("hash", text::fn_hash, 2),
     ("if_then", misc::fn_if_then, 3),
+    ("coalesce", misc::fn_coalesce, VARIADIC_ARGS),
 ];
 
+/// Functions declared with this argument count accept one or more arguments.
+pub(crate) const VARIADIC_ARGS: u32 = u32::MAX;
+
 pub const F_IS_LOCAL_DOMAIN: u32 = 0;
 pub const F_IS_LOCAL_ADDRESS: u32 = 1;
 pub const F_KEY_GET: u32 = 2;
@@ -92,6 +115,11 @@ pub const F_COUNTER_INCR: u32 = 5;
 pub const F_COUNTER_GET: u32 = 6;
 pub const F_SQL_QUERY: u32 = 7;
 pub const F_DNS_QUERY: u32 = 8;
+pub const F_IN_DELIVERY_WINDOW: u32 = 9;
+pub const F_DOMAIN_SETTING: u32 = 10;
+pub const F_HEADER: u32 = 11;
+pub const F_HEADERS: u32 = 12;
+pub const F_META: u32 = 13;
 
 pub const ASYNC_FUNCTIONS: &[(&str, u32, u32)] = &[
     ("is_local_domain", F_IS_LOCAL_DOMAIN, 1),
@@ -103,6 +131,11 @@ pub const ASYNC_FUNCTIONS: &[(&str, u32, u32)] = &[
     ("counter_get", F_COUNTER_GET, 2),
     ("dns_query", F_DNS_QUERY, 2),
     ("sql_query", F_SQL_QUERY, 3),
+    ("in_delivery_window", F_IN_DELIVERY_WINDOW, 1),
+    ("domain_setting", F_DOMAIN_SETTING, 2),
+    ("header", F_HEADER, 1),
+    ("headers", F_HEADERS, 1),
+    ("meta", F_META, 1),
 ];
 
 pub struct EmptyResolver;
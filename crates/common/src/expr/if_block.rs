@@ -6,6 +6,10 @@
 
 use super::{
     ExpressionItem,
+    functions::{
+        F_COUNTER_GET, F_COUNTER_INCR, F_IN_DELIVERY_WINDOW, F_KEY_EXISTS, F_KEY_GET, F_KEY_SET,
+        F_SQL_QUERY, FUNCTIONS,
+    },
     parser::ExpressionParser,
     tokenizer::{TokenMap, Tokenizer},
 };
@@ -16,9 +20,9 @@ use registry::{
         prelude::{ExpressionContext, Property},
         structs,
     },
-    types::id::ObjectId,
+    types::{EnumImpl, id::ObjectId},
 };
-use store::registry::bootstrap::Bootstrap;
+use store::registry::bootstrap::{Bootstrap, ReferenceKind};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IfThen {
@@ -71,6 +75,15 @@ impl IfBlock {
     pub fn is_empty(&self) -> bool {
         self.default.is_empty() && self.if_then.is_empty()
     }
+
+    // Optional features are disabled with an expression that is always false
+    pub fn is_false(&self) -> bool {
+        self.if_then.is_empty()
+            && matches!(
+                self.default.items.as_ref(),
+                [] | [ExpressionItem::Constant(Constant::Integer(0))]
+            )
+    }
 }
 
 impl Expression {
@@ -79,11 +92,81 @@ impl Expression {
             .parse()
             .unwrap()
     }
+
+    pub fn as_constant_string(&self) -> Option<&str> {
+        match self.items.as_ref() {
+            [ExpressionItem::Constant(Constant::String(value))] => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    // Returns the string literals passed as the first argument of
+    // functions that look up a store or a delivery schedule by name.
+    fn named_arguments(&self) -> Vec<(ReferenceKind, &str)> {
+        let mut stack: Vec<Option<&str>> = Vec::new();
+        let mut names = Vec::new();
+
+        for item in self.items.iter() {
+            match item {
+                ExpressionItem::Constant(Constant::String(value)) => {
+                    stack.push(Some(value.as_str()));
+                }
+                ExpressionItem::Constant(_)
+                | ExpressionItem::Variable(_)
+                | ExpressionItem::Global(_)
+                | ExpressionItem::System(_)
+                | ExpressionItem::Capture(_) => {
+                    stack.push(None);
+                }
+                ExpressionItem::UnaryOperator(_) | ExpressionItem::Regex(_) => {
+                    stack.pop();
+                    stack.push(None);
+                }
+                ExpressionItem::BinaryOperator(_) | ExpressionItem::ArrayAccess => {
+                    stack.pop();
+                    stack.pop();
+                    stack.push(None);
+                }
+                ExpressionItem::ArrayBuild(num_items) => {
+                    stack.truncate(stack.len().saturating_sub(*num_items as usize));
+                    stack.push(None);
+                }
+                ExpressionItem::Function { id, num_args } => {
+                    let first_arg = stack.len().saturating_sub(*num_args as usize);
+                    let kind = match id.checked_sub(FUNCTIONS.len() as u32) {
+                        Some(
+                            F_KEY_GET | F_KEY_EXISTS | F_KEY_SET | F_COUNTER_INCR | F_COUNTER_GET
+                            | F_SQL_QUERY,
+                        ) => Some(ReferenceKind::LookupStore),
+                        Some(F_IN_DELIVERY_WINDOW) => Some(ReferenceKind::DeliverySchedule),
+                        _ => None,
+                    };
+                    if *num_args > 0
+                        && let Some(kind) = kind
+                        && let Some(Some(name)) = stack.get(first_arg)
+                    {
+                        names.push((kind, *name));
+                    }
+                    stack.truncate(first_arg);
+                    stack.push(None);
+                }
+                ExpressionItem::JmpIf { .. } => {}
+            }
+        }
+
+        names
+    }
 }
 
 pub trait BootstrapExprExt {
     fn compile_expr(&mut self, id: ObjectId, expr_ctx: &ExpressionContext<'_>) -> IfBlock;
     fn compile_default_expr(&mut self, id: ObjectId, expr_ctx: &ExpressionContext<'_>) -> IfBlock;
+    fn compile_named_expr(
+        &mut self,
+        id: ObjectId,
+        expr_ctx: &ExpressionContext<'_>,
+        kind: ReferenceKind,
+    ) -> IfBlock;
     fn try_compile_expr(
         &mut self,
         id: ObjectId,
@@ -114,6 +197,29 @@ impl BootstrapExprExt for Bootstrap {
         }
     }
 
+    fn compile_named_expr(
+        &mut self,
+        id: ObjectId,
+        expr_ctx: &ExpressionContext<'_>,
+        kind: ReferenceKind,
+    ) -> IfBlock {
+        let if_block = self.compile_expr(id, expr_ctx);
+
+        // Results that are string literals are names that must resolve
+        for expr in if_block
+            .if_then
+            .iter()
+            .map(|if_then| &if_then.then)
+            .chain([&if_block.default])
+        {
+            if let Some(name) = expr.as_constant_string() {
+                self.add_reference(if_block.id, if_block.property, kind, name);
+            }
+        }
+
+        if_block
+    }
+
     fn try_compile_expr(
         &mut self,
         id: ObjectId,
@@ -149,7 +255,13 @@ impl BootstrapExprExt for Bootstrap {
 
         let token_map = TokenMap::default()
             .with_variables(expr_ctx.allowed_variables)
-            .with_constants(expr_ctx.allowed_constants);
+            .with_constants(expr_ctx.allowed_constants)
+            .with_strict(expr.strict)
+            .with_context(format!(
+                "{}.{}",
+                id.object().as_str(),
+                expr_ctx.property.as_str()
+            ));
 
         let default = match ExpressionParser::new(Tokenizer::new(&expr.else_, &token_map)).parse() {
             Ok(expr) => expr,
@@ -202,12 +314,28 @@ impl BootstrapExprExt for Bootstrap {
             }
         }
 
-        Some(IfBlock {
+        let if_block = IfBlock {
             id,
             property: expr_ctx.property,
             if_then: if_then.into_boxed_slice(),
             default,
-        })
+        };
+
+        // Record stores and schedules passed by name to functions, built-in
+        // defaults may refer to optional lookup namespaces
+        if expr_ctx.default.as_ref() != Some(expr) {
+            for (kind, name) in if_block
+                .if_then
+                .iter()
+                .flat_map(|if_then| [&if_then.expr, &if_then.then])
+                .chain([&if_block.default])
+                .flat_map(|expr| expr.named_arguments())
+            {
+                self.add_reference(id, expr_ctx.property, kind, name);
+            }
+        }
+
+        Some(if_block)
     }
 }
 
@@ -4,7 +4,10 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{BinaryOperator, Expression, ExpressionItem, Token, tokenizer::Tokenizer};
+use super::{
+    BinaryOperator, Expression, ExpressionItem, Token, functions::VARIADIC_ARGS,
+    tokenizer::Tokenizer,
+};
 
 pub struct ExpressionParser<'x> {
     pub(crate) tokenizer: Tokenizer<'x>,
@@ -78,7 +81,15 @@ impl<'x> ExpressionParser<'x> {
                     match self.operator_stack.last() {
                         Some((Token::Function { id, num_args, name }, _)) => {
                             let got_args = self.arg_count.pop().unwrap();
-                            if got_args != *num_args as i32 {
+                            let num_args = if *num_args == VARIADIC_ARGS {
+                                if got_args < 1 {
+                                    return Err(format!(
+                                        "Expression function {:?} expected at least 1 argument",
+                                        name
+                                    ));
+                                }
+                                got_args as u32
+                            } else if got_args != *num_args as i32 {
                                 return Err(if *id != u32::MAX {
                                     format!(
                                         "Expression function {:?} expected {} arguments, got {}",
@@ -87,15 +98,14 @@ impl<'x> ExpressionParser<'x> {
                                 } else {
                                     "Missing array index".to_string()
                                 });
-                            }
+                            } else {
+                                *num_args
+                            };
 
                             let expr = match *id {
                                 ID_ARRAY_ACCESS => ExpressionItem::ArrayAccess,
-                                ID_ARRAY_BUILD => ExpressionItem::ArrayBuild(*num_args),
-                                id => ExpressionItem::Function {
-                                    id,
-                                    num_args: *num_args,
-                                },
+                                ID_ARRAY_BUILD => ExpressionItem::ArrayBuild(num_args),
+                                id => ExpressionItem::Function { id, num_args },
                             };
 
                             self.operator_stack.pop();
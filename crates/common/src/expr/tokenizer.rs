@@ -27,10 +27,12 @@ pub struct Tokenizer<'x> {
     is_eof: bool,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct TokenMap {
     pub variables: AHashSet<ExpressionVariable>,
     pub constants: AHashSet<ExpressionConstant>,
+    pub context: Option<String>,
+    pub strict: bool,
 }
 
 impl<'x> Tokenizer<'x> {
@@ -362,12 +364,17 @@ impl<'x> Tokenizer<'x> {
                     num_args: *num_args,
                 })
             } else if let Some(variable) = ExpressionVariable::parse(buf.as_str()) {
-                if self.token_map.variables.is_empty()
+                if !self.token_map.strict
+                    || self.token_map.variables.is_empty()
                     || self.token_map.variables.contains(&variable)
                 {
                     Ok(Token::Variable(variable))
                 } else {
-                    Err(format!("Variable {:?} not allowed in this context", buf))
+                    Err(format!(
+                        "Variable {:?} is not available in {}",
+                        buf,
+                        self.token_map.context_name()
+                    ))
                 }
             } else if let Some(constant) = ExpressionConstant::parse(buf.as_str()) {
                 if self.token_map.constants.is_empty()
@@ -375,7 +382,11 @@ impl<'x> Tokenizer<'x> {
                 {
                     Ok(Token::Constant(Constant::Static(constant)))
                 } else {
-                    Err(format!("Constant {:?} not allowed in this context", buf))
+                    Err(format!(
+                        "Constant {:?} is not available in {}",
+                        buf,
+                        self.token_map.context_name()
+                    ))
                 }
             } else if let Ok(duration) = registry::types::duration::Duration::from_str(&buf) {
                 Ok(Token::Constant(Constant::Integer(
@@ -388,7 +399,40 @@ impl<'x> Tokenizer<'x> {
     }
 }
 
+impl Default for TokenMap {
+    fn default() -> Self {
+        Self {
+            variables: AHashSet::new(),
+            constants: AHashSet::new(),
+            context: None,
+            strict: true,
+        }
+    }
+}
+
 impl TokenMap {
+    /// Names the context in error messages, e.g. the object and property
+    /// the expression belongs to.
+    pub fn with_context(mut self, context: impl Into<String>) -> Self {
+        self.context = Some(context.into());
+        self
+    }
+
+    /// In strict mode (the default) referencing a variable that is not
+    /// available in the current context is a compile-time error, otherwise
+    /// the variable is accepted and resolves to an empty value at runtime.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    fn context_name(&self) -> String {
+        self.context
+            .as_ref()
+            .map(|context| format!("context {context:?}"))
+            .unwrap_or_else(|| "this context".to_string())
+    }
+
     pub fn with_variables(mut self, variables: &[ExpressionVariable]) -> Self {
         self.variables.extend(variables.iter().copied());
         self
@@ -15,7 +15,10 @@ use mail_auth::{
     mta_sts::TlsRpt,
     report::{Record, tlsrpt::FailureDetails},
 };
-use registry::{schema::prelude::ObjectType, types::id::ObjectId};
+use registry::{
+    schema::{enums::ServiceProtocol, prelude::ObjectType},
+    types::id::ObjectId,
+};
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
@@ -77,8 +80,18 @@ pub enum BroadcastEvent {
     CacheInvalidate(Vec<CacheInvalidation>),
     CacheInvalidateAll,
     CacheInvalidateNegative,
-    MtaQueueStatus { is_running: bool },
+    MtaQueueStatus {
+        is_running: bool,
+    },
     QueueRefresh,
+    SessionTerminate {
+        account_id: u32,
+        protocol: Option<ServiceProtocol>,
+    },
+    SessionList {
+        request_id: u64,
+    },
+    TraceOverridesUpdate,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -104,6 +117,7 @@ pub enum CacheInvalidation {
         domain_id: u32,
         local_part_hash: u32,
     },
+    Sessions(u32),
 }
 
 #[derive(Debug)]
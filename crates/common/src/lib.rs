@@ -10,7 +10,10 @@ use crate::auth::{AccessTokenInner, EmailAddress};
 use crate::manager::application::WebApplications;
 use crate::network::asn::AsnGeoLookupData;
 use crate::{
-    auth::{AccountCache, DomainCache, EmailCache, MailingListCache, RoleCache, TenantCache},
+    auth::{
+        AccountCache, DomainCache, EmailCache, MailingListCache, RoleCache, TenantCache,
+        chain::PrincipalSource,
+    },
     config::{
         mailstore::{
             email::EmailConfig,
@@ -21,7 +24,11 @@ use crate::{
         smtp::auth::DkimSigners,
     },
     ipc::TrainTaskController,
-    network::security::BlockedIps,
+    network::{
+        drain::DrainState, health::ReadinessCache, security::BlockedIps, sessions::SessionRegistry,
+    },
+    storage::floor::ChangeFloors,
+    telemetry::{metrics::queue::QueueMetrics, slow::SlowRequests},
 };
 use ahash::{AHashMap, AHashSet};
 use arc_swap::ArcSwap;
@@ -35,13 +42,15 @@ use config::{
         SmtpConfig,
         resolver::{Policy, Tlsa},
     },
-    storage::Storage,
+    storage::{BackupConfig, Storage},
     telemetry::Metrics,
+    templates::MessageTemplates,
 };
 use ipc::{BroadcastEvent, PushEvent, QueueEvent, ReportingEvent};
 use mail_auth::{MX, RecordSet, Txt};
 use manager::application::Resource;
 use parking_lot::{Mutex, RwLock};
+use registry::schema::structs::AuditEvent;
 use rustls::sign::CertifiedKey;
 use std::sync::atomic::AtomicU64;
 use std::{
@@ -127,6 +136,29 @@ pub const KV_LOCK_QUEUE_MESSAGE: u8 = 21;
 pub const KV_LOCK_TASK: u8 = 23;
 pub const KV_LOCK_DAV: u8 = 25;
 pub const KV_SIEVE_ID: u8 = 26;
+pub const KV_LOCK_ACME: u8 = 27;
+pub const KV_RATE_LIMIT_WARMUP: u8 = 28;
+pub const KV_SENDER_VERIFY: u8 = 29;
+pub const KV_RATE_LIMIT_SENDER_VERIFY: u8 = 30;
+pub const KV_QUOTA_WARNING: u8 = 31;
+pub const KV_SIEVE_VACATION: u8 = 32;
+pub const KV_DELIVERY_DEDUP: u8 = 33;
+pub const KV_SESSION_COUNT: u8 = 34;
+pub const KV_SIEVE_REDIRECT: u8 = 35;
+pub const KV_SIEVE_REDIRECT_RCPT: u8 = 36;
+pub const KV_URLAUTH_KEY: u8 = 37;
+pub const KV_SIEVE_LOG: u8 = 38;
+pub const KV_SENDER_REPUTATION: u8 = 39;
+pub const KV_RATE_LIMIT_QUEUE: u8 = 40;
+pub const KV_UNSUBSCRIBE: u8 = 41;
+pub const KV_RATE_LIMIT_UNSUBSCRIBE: u8 = 42;
+pub const KV_RATE_LIMIT_DSN: u8 = 43;
+pub const KV_CATEGORY_SENDER: u8 = 44;
+pub const KV_TOTP_STEP: u8 = 45;
+pub const KV_SESSION_LIST: u8 = 46;
+pub const KV_CHANGE_FLOOR: u8 = 47;
+pub const KV_TRACE_OVERRIDES: u8 = 48;
+pub const KV_LOCK_UPDATE: u8 = 49;
 
 #[derive(Clone)]
 pub struct Server {
@@ -158,6 +190,12 @@ pub struct Data {
     pub span_id_gen: SnowflakeIdGenerator,
     pub registry_id_gen: SnowflakeIdGenerator,
     pub queue_status: AtomicBool,
+    pub queue_metrics: QueueMetrics,
+    pub drain: DrainState,
+    pub sessions: SessionRegistry,
+    pub change_floors: ChangeFloors,
+    pub readiness: ReadinessCache,
+    pub slow_requests: SlowRequests,
 
     pub applications: WebApplications,
     pub logos: Mutex<AHashMap<Box<str>, LogoCache>>,
@@ -186,6 +224,7 @@ pub struct Caches {
     pub emails_negative: CacheWithTtl<EmailAddress, ()>,
     pub domain_names: Cache<Box<str>, u32>,
     pub domain_names_negative: CacheWithTtl<Box<str>, ()>,
+    pub directory_principals: CacheWithTtl<Box<str>, PrincipalSource>,
 
     pub domains: Cache<u32, Arc<DomainCache>>,
     pub accounts: Cache<u32, Arc<AccountCache>>,
@@ -267,6 +306,8 @@ pub struct HttpAuthCache {
     pub account_id: u32,
     pub revision: u64,
     pub credential_id: Option<u32>,
+    pub impersonator_id: Option<u32>,
+    pub step_up_at: u64,
     pub expires: Instant,
 }
 
@@ -275,6 +316,7 @@ pub struct Ipc {
     pub task_tx: Arc<Notify>,
     pub queue_tx: mpsc::Sender<QueueEvent>,
     pub report_tx: mpsc::Sender<ReportingEvent>,
+    pub audit_tx: mpsc::Sender<AuditEvent>,
     pub broadcast_tx: Option<mpsc::Sender<BroadcastEvent>>,
     pub train_task_controller: Arc<TrainTaskController>,
 }
@@ -382,6 +424,8 @@ pub struct Core {
     pub smtp: SmtpConfig,
     pub spam: SpamFilterConfig,
     pub groupware: GroupwareConfig,
+    pub templates: MessageTemplates,
+    pub backup: BackupConfig,
     pub metrics: Metrics,
 
     // SPDX-SnippetBegin
@@ -14,6 +14,7 @@ use crate::{
     manager::defaults::BootstrapDefaults,
 };
 use arc_swap::ArcSwap;
+use registry::schema::structs::AuditEvent;
 use std::{
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
@@ -34,6 +35,7 @@ pub struct IpcReceivers {
     pub push_rx: Option<mpsc::Receiver<PushEvent>>,
     pub queue_rx: Option<mpsc::Receiver<QueueEvent>>,
     pub report_rx: Option<mpsc::Receiver<ReportingEvent>>,
+    pub audit_rx: Option<mpsc::Receiver<AuditEvent>>,
     pub broadcast_rx: Option<mpsc::Receiver<BroadcastEvent>>,
 }
 
@@ -47,7 +49,8 @@ Usage: stalwart [OPTIONS]
 Options:
   -c, --config <PATH>              Start server with the specified configuration file
   -e, --export <PATH>              Export all store data to a specific path
-  -i, --import <PATH>              Import store data from a specific path
+  -i, --import <PATH>              Import store data from a specific path, or restore
+                                   an incremental backup chain
   -o, --console                    Open the store console
   -h, --help                       Print help
   -V, --version                    Print version
@@ -162,6 +165,16 @@ impl BootManager {
                 #[cfg(not(feature = "enterprise"))]
                 telemetry.enable(false);
 
+                // Validate references to named objects
+                if !core.validate_references(&mut bootstrap).await
+                    && !bootstrap.registry.is_recovery_mode()
+                {
+                    bootstrap.log_errors();
+                    failed(
+                        "⚠️ Startup failed: configuration references unknown objects, see the logs for details",
+                    );
+                }
+
                 if bootstrap.registry.is_bootstrap_mode() {
                     trc::event!(
                         Server(trc::ServerEvent::BootstrapMode),
@@ -283,12 +296,14 @@ pub fn build_ipc(has_pubsub: bool) -> (Ipc, IpcReceivers) {
     let (push_tx, push_rx) = mpsc::channel(IPC_CHANNEL_BUFFER);
     let (queue_tx, queue_rx) = mpsc::channel(IPC_CHANNEL_BUFFER);
     let (report_tx, report_rx) = mpsc::channel(IPC_CHANNEL_BUFFER);
+    let (audit_tx, audit_rx) = mpsc::channel(IPC_CHANNEL_BUFFER);
     let (broadcast_tx, broadcast_rx) = mpsc::channel(IPC_CHANNEL_BUFFER);
     (
         Ipc {
             push_tx,
             queue_tx,
             report_tx,
+            audit_tx,
             broadcast_tx: has_pubsub.then_some(broadcast_tx),
             task_tx: Arc::new(Notify::new()),
             train_task_controller: Arc::new(TrainTaskController::default()),
@@ -297,6 +312,7 @@ pub fn build_ipc(has_pubsub: bool) -> (Ipc, IpcReceivers) {
             push_rx: Some(push_rx),
             queue_rx: Some(queue_rx),
             report_rx: Some(report_rx),
+            audit_rx: Some(audit_rx),
             broadcast_rx: has_pubsub.then_some(broadcast_rx),
         },
     )
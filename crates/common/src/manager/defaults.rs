@@ -163,11 +163,13 @@ async fn insert_safe_defaults(bp: &mut Bootstrap) -> trc::Result<()> {
                 description: "Local delivery queue".to_string().into(),
                 name: "local".into(),
                 threads_per_node: 25,
+                ..Default::default()
             },
             MtaVirtualQueue {
                 description: "Remote delivery queue".to_string().into(),
                 name: "remote".into(),
                 threads_per_node: 50,
+                ..Default::default()
             },
             MtaVirtualQueue {
                 description: "Delivery Status Notification delivery queue"
@@ -175,11 +177,13 @@ async fn insert_safe_defaults(bp: &mut Bootstrap) -> trc::Result<()> {
                     .into(),
                 name: "dsn".into(),
                 threads_per_node: 5,
+                ..Default::default()
             },
             MtaVirtualQueue {
                 description: "DMARC and TLS report delivery queue".to_string().into(),
                 name: "report".into(),
                 threads_per_node: 5,
+                ..Default::default()
             },
         ]
         .into_iter()
@@ -199,20 +203,24 @@ async fn insert_safe_defaults(bp: &mut Bootstrap) -> trc::Result<()> {
                 description: "Local delivery schedule".to_string().into(),
                 expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                     expire: Duration::from_millis(3 * 24 * 60 * 60 * 1000),
+                    max_attempts: None,
                 }),
                 notify: MtaDeliveryScheduleIntervalsOrDefault::Default,
                 retry: MtaDeliveryScheduleIntervalsOrDefault::Default,
                 queue_id: 0u64.into(),
+                ..Default::default()
             },
             MtaDeliverySchedule {
                 name: "remote".into(),
                 description: "Remote delivery schedule".to_string().into(),
                 expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                     expire: Duration::from_millis(3 * 24 * 60 * 60 * 1000),
+                    max_attempts: None,
                 }),
                 notify: MtaDeliveryScheduleIntervalsOrDefault::Default,
                 retry: MtaDeliveryScheduleIntervalsOrDefault::Default,
                 queue_id: 1u64.into(),
+                ..Default::default()
             },
             MtaDeliverySchedule {
                 name: "dsn".into(),
@@ -242,6 +250,7 @@ async fn insert_safe_defaults(bp: &mut Bootstrap) -> trc::Result<()> {
                     },
                 ),
                 queue_id: 2u64.into(),
+                ..Default::default()
             },
             MtaDeliverySchedule {
                 name: "report".into(),
@@ -266,6 +275,7 @@ async fn insert_safe_defaults(bp: &mut Bootstrap) -> trc::Result<()> {
                     },
                 ),
                 queue_id: 3u64.into(),
+                ..Default::default()
             },
         ]
         .into_iter()
@@ -0,0 +1,1004 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::backup::{Family, MAGIC_MARKER};
+use crate::{Core, DATABASE_SCHEMA_VERSION};
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
+use registry::schema::enums::CompressionAlgo;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+use store::{
+    write::{AnyClass, AnyKey, BatchBuilder, ValueClass, key::DeserializeBigEndian, now},
+    *,
+};
+use trc::AddContext;
+use types::{
+    blob_hash::{BLOB_HASH_LEN, BlobHash},
+    collection::Collection,
+    field::Field,
+};
+use utils::codec::leb128::Leb128_;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SET_PREFIX: &str = "backup-";
+const SYSTEM_ACCOUNT_ID: u32 = u32::MAX;
+const DIGEST_LEN: usize = 16;
+
+// Subspaces keyed by account id, only accounts with new changes are compared
+const ACCOUNT_SUBSPACES: &[u8] = &[SUBSPACE_PROPERTY, SUBSPACE_INDEXES, SUBSPACE_LOGS];
+
+static BACKUP_RUNNING: AtomicBool = AtomicBool::new(false);
+
+struct RunningGuard;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncrementalBackupParams {
+    pub path: PathBuf,
+    pub max_increments: u64,
+    pub full: bool,
+    pub include_blobs: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub sequence: u64,
+    pub kind: BackupKind,
+    pub baseline: u64,
+    pub parent: Option<BackupParent>,
+    pub schema_version: u32,
+    pub started_at: u64,
+    pub completed_at: u64,
+    pub include_blobs: bool,
+    pub change_ids: BTreeMap<u32, u64>,
+    pub files: BTreeMap<String, BackupFile>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupKind {
+    Full,
+    Incremental,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupParent {
+    pub directory: String,
+    pub sequence: u64,
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    pub size: u64,
+    pub records: u64,
+    pub checksum: String,
+}
+
+impl Core {
+    pub async fn incremental_backup(
+        &self,
+        params: IncrementalBackupParams,
+    ) -> trc::Result<BackupManifest> {
+        if BACKUP_RUNNING.swap(true, Ordering::AcqRel) {
+            return Err(trc::LimitEvent::ConcurrentRequest
+                .into_err()
+                .details("A backup is already in progress"));
+        }
+        let _guard = RunningGuard;
+        self.incremental_backup_(params).await
+    }
+
+    async fn incremental_backup_(
+        &self,
+        params: IncrementalBackupParams,
+    ) -> trc::Result<BackupManifest> {
+        let started_at = now();
+        std::fs::create_dir_all(&params.path).map_err(|err| fs_error(err, &params.path))?;
+
+        let schema_version = self
+            .storage
+            .data
+            .get_value::<u32>(AnyKey {
+                subspace: SUBSPACE_PROPERTY,
+                key: vec![0u8],
+            })
+            .await
+            .caused_by(trc::location!())?
+            .unwrap_or(DATABASE_SCHEMA_VERSION);
+
+        // Chain to the latest complete set unless a new baseline is due
+        let latest = latest_backup_set(&params.path)?;
+        let sequence = latest
+            .as_ref()
+            .map_or(1, |(_, manifest)| manifest.sequence + 1);
+        let parent = latest.filter(|(_, manifest)| {
+            !params.full
+                && manifest.schema_version == schema_version
+                && manifest.include_blobs == params.include_blobs
+                && manifest.sequence - manifest.baseline < params.max_increments
+                && ACCOUNT_SUBSPACES
+                    .iter()
+                    .copied()
+                    .chain(global_subspaces())
+                    .all(|subspace| manifest.files.contains_key(&file_name("digest", subspace)))
+        });
+
+        // Sets without a manifest were interrupted and are discarded
+        let set_path = params.path.join(set_name(sequence));
+        if set_path.exists() {
+            std::fs::remove_dir_all(&set_path).map_err(|err| fs_error(err, &set_path))?;
+        }
+        std::fs::create_dir(&set_path).map_err(|err| fs_error(err, &set_path))?;
+
+        // Each set records the digest of every key, increments only export the
+        // keys that were added, modified or deleted since the parent set
+        let change_ids = self.account_change_ids().await?;
+        let mut files = BTreeMap::new();
+        for subspace in ACCOUNT_SUBSPACES.iter().copied().chain(global_subspaces()) {
+            let mut writer = DeltaWriter::create(
+                &set_path,
+                parent.as_ref().map(|(path, _)| path.as_path()),
+                subspace,
+                schema_version,
+            )?;
+            match &parent {
+                Some((_, manifest)) if ACCOUNT_SUBSPACES.contains(&subspace) => {
+                    // Only accounts with new entries in the change log are compared,
+                    // accounts modified while exporting are compared again by the
+                    // next increment
+                    for account_id in dirty_accounts(&change_ids, &manifest.change_ids) {
+                        let from_key = account_id.to_be_bytes().to_vec();
+                        let mut to_key = from_key.clone();
+                        to_key.extend_from_slice(&[u8::MAX; 32]);
+                        writer.copy_until(Some(from_key.as_slice()))?;
+                        self.export_range(&mut writer, from_key, to_key).await?;
+                        writer.delete_until(
+                            account_id
+                                .checked_add(1)
+                                .map(|account_id| account_id.to_be_bytes())
+                                .as_ref()
+                                .map(|key| key.as_slice()),
+                        )?;
+                    }
+                    writer.copy_until(None)?;
+                }
+                _ => {
+                    self.export_range(&mut writer, vec![0u8], vec![u8::MAX; 32])
+                        .await?;
+                    writer.delete_until(None)?;
+                }
+            }
+            files.extend(writer.finish()?);
+        }
+
+        // Blobs are immutable, only hashes not present in the parent set are exported
+        files.extend(
+            self.export_blobs(
+                &set_path,
+                parent.as_ref().map(|(path, _)| path.as_path()),
+                schema_version,
+                params.include_blobs,
+            )
+            .await?,
+        );
+
+        let manifest = BackupManifest {
+            sequence,
+            kind: if parent.is_some() {
+                BackupKind::Incremental
+            } else {
+                BackupKind::Full
+            },
+            baseline: parent
+                .as_ref()
+                .map_or(sequence, |(_, manifest)| manifest.baseline),
+            parent: parent
+                .map(|(path, manifest)| {
+                    manifest_checksum(&path).map(|checksum| BackupParent {
+                        directory: set_name(manifest.sequence),
+                        sequence: manifest.sequence,
+                        checksum,
+                    })
+                })
+                .transpose()?,
+            schema_version,
+            started_at,
+            completed_at: now(),
+            include_blobs: params.include_blobs,
+            change_ids,
+            files,
+        };
+
+        // The manifest is written last, it marks the set as complete
+        let tmp_path = set_path.join(format!("{MANIFEST_FILE}.tmp"));
+        let manifest_path = set_path.join(MANIFEST_FILE);
+        std::fs::write(
+            &tmp_path,
+            serde_json::to_vec_pretty(&manifest).unwrap_or_default(),
+        )
+        .map_err(|err| fs_error(err, &tmp_path))?;
+        std::fs::rename(&tmp_path, &manifest_path).map_err(|err| fs_error(err, &manifest_path))?;
+
+        Ok(manifest)
+    }
+
+    // Last change id of each account, as assigned by the change log
+    async fn account_change_ids(&self) -> trc::Result<BTreeMap<u32, u64>> {
+        let store = &self.storage.data;
+        let is_sql = store.is_sql();
+        let mut change_ids = BTreeMap::new();
+        store
+            .iterate(
+                IterateParams::new(
+                    AnyKey {
+                        subspace: SUBSPACE_COUNTER,
+                        key: vec![0u8],
+                    },
+                    AnyKey {
+                        subspace: SUBSPACE_COUNTER,
+                        key: vec![u8::MAX; 32],
+                    },
+                )
+                .set_values(!is_sql),
+                |key, value| {
+                    if key.len() == U32_LEN {
+                        let change_id = if !is_sql {
+                            i64::from_le_bytes(value.try_into().map_err(|_| {
+                                trc::Error::corrupted_key(key, value.into(), trc::location!())
+                            })?) as u64
+                        } else {
+                            0
+                        };
+                        change_ids.insert(key.deserialize_be_u32(0)?, change_id);
+                    }
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())?;
+
+        if is_sql {
+            for (account_id, change_id) in change_ids.iter_mut() {
+                *change_id = store
+                    .get_counter(ValueKey {
+                        account_id: *account_id,
+                        collection: 0,
+                        document_id: 0,
+                        class: ValueClass::ChangeId,
+                    })
+                    .await
+                    .caused_by(trc::location!())? as u64;
+            }
+        }
+
+        Ok(change_ids)
+    }
+
+    async fn export_range(
+        &self,
+        writer: &mut DeltaWriter,
+        from_key: Vec<u8>,
+        to_key: Vec<u8>,
+    ) -> trc::Result<()> {
+        let store = &self.storage.data;
+        let subspace = writer.subspace;
+        let from_key = AnyKey {
+            subspace,
+            key: from_key,
+        };
+        let to_key = AnyKey {
+            subspace,
+            key: to_key,
+        };
+
+        if !store.is_sql() || (subspace != SUBSPACE_COUNTER && subspace != SUBSPACE_QUOTA) {
+            store
+                .iterate(
+                    IterateParams::new(from_key, to_key)
+                        .set_values(![SUBSPACE_INDEXES, SUBSPACE_REGISTRY_IDX].contains(&subspace)),
+                    |key, value| {
+                        writer.write(key, value)?;
+                        Ok(true)
+                    },
+                )
+                .await
+                .caused_by(trc::location!())
+        } else {
+            let mut keys = Vec::with_capacity(128);
+            store
+                .iterate(
+                    IterateParams::new(from_key, to_key).no_values(),
+                    |key, _| {
+                        keys.push(key.to_vec());
+                        Ok(true)
+                    },
+                )
+                .await
+                .caused_by(trc::location!())?;
+
+            for key in keys {
+                let value = (store
+                    .get_counter(ValueClass::Any(AnyClass {
+                        subspace,
+                        key: key.clone(),
+                    }))
+                    .await
+                    .caused_by(trc::location!())? as u64)
+                    .to_le_bytes();
+                writer.write(&key, &value)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    async fn export_blobs(
+        &self,
+        set_path: &Path,
+        parent_path: Option<&Path>,
+        schema_version: u32,
+        include_blobs: bool,
+    ) -> trc::Result<Vec<(String, BackupFile)>> {
+        let mut hashes = Vec::new();
+        let mut last_hash = BlobHash::default();
+        self.storage
+            .data
+            .iterate(
+                IterateParams::new(
+                    AnyKey {
+                        subspace: SUBSPACE_BLOB_LINK,
+                        key: vec![0u8],
+                    },
+                    AnyKey {
+                        subspace: SUBSPACE_BLOB_LINK,
+                        key: vec![u8::MAX; 32],
+                    },
+                )
+                .no_values(),
+                |key, _| {
+                    let hash =
+                        BlobHash::try_from_hash_slice(key.get(0..BLOB_HASH_LEN).ok_or_else(
+                            || trc::Error::corrupted_key(key, None, trc::location!()),
+                        )?)
+                        .map_err(|_| trc::Error::corrupted_key(key, None, trc::location!()))?;
+
+                    if last_hash != hash {
+                        hashes.push(hash.clone());
+                        last_hash = hash;
+                    }
+
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())?;
+
+        // Both lists are sorted, merge them to find the new hashes
+        let mut previous = parent_path
+            .map(|path| RecordReader::open(&path.join(file_name("hashes", SUBSPACE_BLOBS))))
+            .transpose()?;
+        let mut previous_hash = match &mut previous {
+            Some(reader) => reader.next()?.map(|(hash, _)| hash),
+            None => None,
+        };
+        let mut hash_list = RecordWriter::create(
+            set_path.join(file_name("hashes", SUBSPACE_BLOBS)),
+            SUBSPACE_BLOBS,
+            schema_version,
+        )?;
+        let mut blobs = RecordWriter::create(
+            set_path.join(file_name("subspace", SUBSPACE_BLOBS)),
+            SUBSPACE_BLOBS,
+            schema_version,
+        )?;
+        for hash in hashes {
+            hash_list.write(hash.as_slice(), &[])?;
+
+            let mut is_new = true;
+            while let Some(previous_hash_) = &previous_hash {
+                match previous_hash_.as_slice().cmp(hash.as_slice()) {
+                    std::cmp::Ordering::Less => {}
+                    std::cmp::Ordering::Equal => is_new = false,
+                    std::cmp::Ordering::Greater => break,
+                }
+                previous_hash = match &mut previous {
+                    Some(reader) => reader.next()?.map(|(hash, _)| hash),
+                    None => None,
+                };
+                if !is_new {
+                    break;
+                }
+            }
+
+            if is_new
+                && include_blobs
+                && let Some(blob) = self
+                    .storage
+                    .blob
+                    .get_blob(hash.as_slice(), 0..usize::MAX)
+                    .await
+                    .caused_by(trc::location!())?
+            {
+                blobs.write(hash.as_slice(), &blob)?;
+            }
+        }
+
+        Ok(vec![hash_list.finish()?, blobs.finish()?])
+    }
+}
+
+pub fn latest_backup_set(path: &Path) -> trc::Result<Option<(PathBuf, BackupManifest)>> {
+    let mut latest: Option<(PathBuf, BackupManifest)> = None;
+    for entry in std::fs::read_dir(path).map_err(|err| fs_error(err, path))? {
+        let entry = entry.map_err(|err| fs_error(err, path))?;
+        let set_path = entry.path();
+        if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(SET_PREFIX))
+            && set_path.join(MANIFEST_FILE).is_file()
+        {
+            let manifest = read_manifest(&set_path)?;
+            if latest
+                .as_ref()
+                .is_none_or(|(_, latest)| manifest.sequence > latest.sequence)
+            {
+                latest = Some((set_path, manifest));
+            }
+        }
+    }
+
+    Ok(latest)
+}
+
+pub fn verify_backup_chain(path: &Path) -> trc::Result<Vec<(PathBuf, BackupManifest)>> {
+    let (mut set_path, mut manifest) = if path.join(MANIFEST_FILE).is_file() {
+        (path.to_path_buf(), read_manifest(path)?)
+    } else {
+        latest_backup_set(path)?.ok_or_else(|| {
+            trc::StoreEvent::NotFound
+                .into_err()
+                .details("No complete backup set found")
+                .ctx(trc::Key::Path, path.display().to_string())
+        })?
+    };
+
+    if manifest.schema_version != DATABASE_SCHEMA_VERSION {
+        return Err(
+            chain_error(&set_path, "Unsupported database schema version")
+                .ctx(trc::Key::Version, manifest.schema_version),
+        );
+    }
+
+    let mut chain = Vec::new();
+    loop {
+        for (name, file) in &manifest.files {
+            let file_path = set_path.join(name);
+            if !file_path.is_file() {
+                return Err(chain_error(&file_path, "Backup file is missing"));
+            }
+            let (size, checksum) = file_checksum(&file_path)?;
+            if size != file.size || checksum != file.checksum {
+                return Err(chain_error(&file_path, "Backup file checksum mismatch"));
+            }
+        }
+
+        let parent = match (manifest.kind, &manifest.parent) {
+            (BackupKind::Full, _) => {
+                chain.push((set_path, manifest));
+                break;
+            }
+            (BackupKind::Incremental, Some(parent)) => parent.clone(),
+            (BackupKind::Incremental, None) => {
+                return Err(chain_error(&set_path, "Incremental set has no parent"));
+            }
+        };
+
+        let parent_path = set_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(&parent.directory);
+        if !parent_path.join(MANIFEST_FILE).is_file() {
+            return Err(chain_error(&parent_path, "Parent backup set is missing"));
+        }
+        if manifest_checksum(&parent_path)? != parent.checksum {
+            return Err(chain_error(
+                &parent_path,
+                "Parent manifest checksum mismatch",
+            ));
+        }
+        let parent_manifest = read_manifest(&parent_path)?;
+        if parent_manifest.sequence != parent.sequence
+            || parent_manifest.sequence >= manifest.sequence
+            || parent_manifest.baseline != manifest.baseline
+            || parent_manifest.schema_version != manifest.schema_version
+        {
+            return Err(chain_error(
+                &parent_path,
+                "Parent backup set does not match",
+            ));
+        }
+
+        chain.push((set_path, manifest));
+        set_path = parent_path;
+        manifest = parent_manifest;
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+pub async fn restore_backup_chain(
+    store: Store,
+    blob_store: BlobStore,
+    path: &Path,
+) -> trc::Result<BackupManifest> {
+    let mut chain = verify_backup_chain(path)?;
+
+    // Sets are replayed in chain order, deletions are applied before the
+    // records of the same subspace
+    for (set_path, manifest) in &chain {
+        for name in manifest.files.keys() {
+            let is_deletion = name.starts_with("deleted_");
+            if is_deletion || name.starts_with("subspace_") {
+                restore_records(&store, &blob_store, &set_path.join(name), is_deletion).await?;
+            }
+        }
+    }
+
+    // Make sure that every linked blob is available
+    let (set_path, manifest) = chain.pop().unwrap();
+    let hashes_path = set_path.join(file_name("hashes", SUBSPACE_BLOBS));
+    if hashes_path.is_file() {
+        let mut reader = RecordReader::open(&hashes_path)?;
+        while let Some((hash, _)) = reader.next()? {
+            if blob_store
+                .get_blob(&hash, 0..1)
+                .await
+                .caused_by(trc::location!())?
+                .is_none()
+            {
+                return Err(trc::StoreEvent::DataCorruption
+                    .into_err()
+                    .details("Blob referenced by the backup is missing from the blob store")
+                    .ctx(
+                        trc::Key::BlobId,
+                        BlobHash::try_from_hash_slice(&hash)
+                            .map(|hash| hash.to_hex())
+                            .unwrap_or_default(),
+                    ));
+            }
+        }
+    }
+
+    Ok(manifest)
+}
+
+async fn restore_records(
+    store: &Store,
+    blob_store: &BlobStore,
+    path: &Path,
+    is_deletion: bool,
+) -> trc::Result<()> {
+    let mut reader = RecordReader::open(path)?;
+    let mut batch = BatchBuilder::new();
+
+    while let Some((key, value)) = reader.next()? {
+        match reader.subspace {
+            SUBSPACE_INDEXES if is_deletion => {
+                if key.len() < (U32_LEN * 2) + 2 {
+                    return Err(trc::Error::corrupted_key(&key, None, trc::location!()));
+                }
+                let account_id = key.as_slice().deserialize_be_u32(0)?;
+                let document_id = key.as_slice().deserialize_be_u32(key.len() - U32_LEN)?;
+                batch
+                    .with_account_id(account_id)
+                    .with_collection(Collection::from(key[U32_LEN]))
+                    .with_document(document_id)
+                    .unindex(
+                        Field::new(key[U32_LEN + 1]),
+                        key[U32_LEN + 2..key.len() - U32_LEN].to_vec(),
+                    );
+            }
+            subspace if is_deletion => {
+                batch.clear(ValueClass::Any(AnyClass { subspace, key }));
+            }
+            SUBSPACE_BLOBS => {
+                blob_store
+                    .put_blob(&key, &value, CompressionAlgo::Lz4)
+                    .await
+                    .caused_by(trc::location!())?;
+                continue;
+            }
+            SUBSPACE_COUNTER | SUBSPACE_QUOTA => {
+                let value = u64::from_le_bytes(value.try_into().map_err(|_| {
+                    trc::StoreEvent::DataCorruption
+                        .into_err()
+                        .details("Invalid counter value")
+                        .ctx(trc::Key::Path, path.display().to_string())
+                })?) as i64;
+                let class = ValueClass::Any(AnyClass {
+                    subspace: reader.subspace,
+                    key,
+                });
+                batch.clear(class.clone()).add(class, value);
+            }
+            SUBSPACE_INDEXES => {
+                if key.len() < (U32_LEN * 2) + 2 {
+                    return Err(trc::Error::corrupted_key(&key, None, trc::location!()));
+                }
+                let account_id = key.as_slice().deserialize_be_u32(0)?;
+                let document_id = key.as_slice().deserialize_be_u32(key.len() - U32_LEN)?;
+                batch
+                    .with_account_id(account_id)
+                    .with_collection(Collection::from(key[U32_LEN]))
+                    .with_document(document_id)
+                    .index(
+                        Field::new(key[U32_LEN + 1]),
+                        key[U32_LEN + 2..key.len() - U32_LEN].to_vec(),
+                    );
+            }
+            subspace => {
+                batch.set(ValueClass::Any(AnyClass { subspace, key }), value);
+            }
+        }
+
+        if batch.is_large_batch() {
+            store
+                .write(batch.build_all())
+                .await
+                .caused_by(trc::location!())?;
+            batch = BatchBuilder::new();
+        }
+    }
+
+    if !batch.is_empty() {
+        store
+            .write(batch.build_all())
+            .await
+            .caused_by(trc::location!())?;
+    }
+
+    Ok(())
+}
+
+struct DeltaWriter {
+    subspace: u8,
+    records: RecordWriter,
+    deleted: RecordWriter,
+    digest: RecordWriter,
+    parent: Option<RecordReader>,
+    parent_next: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl DeltaWriter {
+    fn create(
+        set_path: &Path,
+        parent_path: Option<&Path>,
+        subspace: u8,
+        version: u32,
+    ) -> trc::Result<Self> {
+        let mut parent = parent_path
+            .map(|path| RecordReader::open(&path.join(file_name("digest", subspace))))
+            .transpose()?;
+        let parent_next = match &mut parent {
+            Some(reader) => reader.next()?,
+            None => None,
+        };
+
+        Ok(DeltaWriter {
+            subspace,
+            records: RecordWriter::create(
+                set_path.join(file_name("subspace", subspace)),
+                subspace,
+                version,
+            )?,
+            deleted: RecordWriter::create(
+                set_path.join(file_name("deleted", subspace)),
+                subspace,
+                version,
+            )?,
+            digest: RecordWriter::create(
+                set_path.join(file_name("digest", subspace)),
+                subspace,
+                version,
+            )?,
+            parent,
+            parent_next,
+        })
+    }
+
+    // Keys must be written in ascending order, parent keys that sort before
+    // the written key no longer exist
+    fn write(&mut self, key: &[u8], value: &[u8]) -> trc::Result<()> {
+        let hash = blake3::hash(value);
+        let digest = &hash.as_bytes()[..DIGEST_LEN];
+        self.delete_until(Some(key))?;
+        let is_unchanged = if let Some((parent_key, parent_digest)) = &self.parent_next
+            && parent_key.as_slice() == key
+        {
+            let is_unchanged = parent_digest.as_slice() == digest;
+            self.advance()?;
+            is_unchanged
+        } else {
+            false
+        };
+
+        if !is_unchanged {
+            self.records.write(key, value)?;
+        }
+        self.digest.write(key, digest)
+    }
+
+    // Parent keys below the bound are kept as they are
+    fn copy_until(&mut self, bound: Option<&[u8]>) -> trc::Result<()> {
+        while let Some((key, digest)) = &self.parent_next
+            && bound.is_none_or(|bound| key.as_slice() < bound)
+        {
+            self.digest.write(key, digest)?;
+            self.advance()?;
+        }
+        Ok(())
+    }
+
+    // Parent keys below the bound are deleted
+    fn delete_until(&mut self, bound: Option<&[u8]>) -> trc::Result<()> {
+        while let Some((key, _)) = &self.parent_next
+            && bound.is_none_or(|bound| key.as_slice() < bound)
+        {
+            self.deleted.write(key, &[])?;
+            self.advance()?;
+        }
+        Ok(())
+    }
+
+    fn advance(&mut self) -> trc::Result<()> {
+        self.parent_next = match &mut self.parent {
+            Some(reader) => reader.next()?,
+            None => None,
+        };
+        Ok(())
+    }
+
+    fn finish(self) -> trc::Result<[(String, BackupFile); 3]> {
+        Ok([
+            self.records.finish()?,
+            self.deleted.finish()?,
+            self.digest.finish()?,
+        ])
+    }
+}
+
+struct RecordWriter {
+    path: PathBuf,
+    subspace: u8,
+    file: FrameEncoder<BufWriter<File>>,
+    records: u64,
+}
+
+impl RecordWriter {
+    fn create(path: PathBuf, subspace: u8, version: u32) -> trc::Result<Self> {
+        let mut file = FrameEncoder::new(BufWriter::new(
+            File::create(&path).map_err(|err| fs_error(err, &path))?,
+        ));
+        file.write_all(&[MAGIC_MARKER, subspace])
+            .and_then(|_| file.write_all(&version.to_le_bytes()))
+            .map_err(|err| fs_error(err, &path))?;
+
+        Ok(RecordWriter {
+            path,
+            subspace,
+            file,
+            records: 0,
+        })
+    }
+
+    fn write(&mut self, key: &[u8], value: &[u8]) -> trc::Result<()> {
+        key.len()
+            .to_leb128_writer(&mut self.file)
+            .and_then(|_| self.file.write_all(key))
+            .and_then(|_| value.len().to_leb128_writer(&mut self.file))
+            .and_then(|_| self.file.write_all(value))
+            .map_err(|err| fs_error(err, &self.path))?;
+        self.records += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> trc::Result<(String, BackupFile)> {
+        self.file
+            .finish()
+            .map_err(std::io::Error::from)
+            .and_then(|mut file| file.flush())
+            .map_err(|err| fs_error(err, &self.path))?;
+        let (size, checksum) = file_checksum(&self.path)?;
+
+        Ok((
+            self.path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            BackupFile {
+                size,
+                records: self.records,
+                checksum,
+            },
+        ))
+    }
+}
+
+struct RecordReader {
+    path: PathBuf,
+    subspace: u8,
+    file: FrameDecoder<BufReader<File>>,
+}
+
+impl RecordReader {
+    fn open(path: &Path) -> trc::Result<Self> {
+        let mut file = FrameDecoder::new(BufReader::new(
+            File::open(path).map_err(|err| fs_error(err, path))?,
+        ));
+        let mut header = [0u8; 6];
+        file.read_exact(&mut header)
+            .map_err(|err| fs_error(err, path))?;
+        if header[0] != MAGIC_MARKER {
+            return Err(chain_error(path, "Invalid magic marker"));
+        }
+        let version = u32::from_le_bytes(header[2..].try_into().unwrap());
+        if version != DATABASE_SCHEMA_VERSION {
+            return Err(chain_error(path, "Invalid database schema version")
+                .ctx(trc::Key::Version, version));
+        }
+
+        Ok(RecordReader {
+            path: path.to_path_buf(),
+            subspace: header[1],
+            file,
+        })
+    }
+
+    fn next(&mut self) -> trc::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        let Some(key_len) = self.read_size()? else {
+            return Ok(None);
+        };
+        let mut key = vec![0; key_len];
+        self.file
+            .read_exact(&mut key)
+            .map_err(|err| fs_error(err, &self.path))?;
+        let value_len = self
+            .read_size()?
+            .ok_or_else(|| chain_error(&self.path, "Unexpected end of file"))?;
+        let mut value = vec![0; value_len];
+        self.file
+            .read_exact(&mut value)
+            .map_err(|err| fs_error(err, &self.path))?;
+
+        Ok(Some((key, value)))
+    }
+
+    fn read_size(&mut self) -> trc::Result<Option<usize>> {
+        let mut result = 0;
+        let mut buf = [0u8; 1];
+
+        for shift in [0, 7, 14, 21, 28] {
+            if let Err(err) = self.file.read_exact(&mut buf) {
+                return if err.kind() == ErrorKind::UnexpectedEof && shift == 0 {
+                    Ok(None)
+                } else {
+                    Err(fs_error(err, &self.path))
+                };
+            }
+
+            let byte = buf[0];
+            result |= ((byte & 0x7F) as usize) << shift;
+            if (byte & 0x80) == 0 {
+                return Ok(Some(result));
+            }
+        }
+
+        Err(chain_error(&self.path, "Invalid leb128 sequence"))
+    }
+}
+
+fn read_manifest(set_path: &Path) -> trc::Result<BackupManifest> {
+    let path = set_path.join(MANIFEST_FILE);
+    serde_json::from_slice(&std::fs::read(&path).map_err(|err| fs_error(err, &path))?)
+        .map_err(|err| chain_error(&path, "Failed to parse manifest").reason(err))
+}
+
+fn manifest_checksum(set_path: &Path) -> trc::Result<String> {
+    file_checksum(&set_path.join(MANIFEST_FILE)).map(|(_, checksum)| checksum)
+}
+
+fn file_checksum(path: &Path) -> trc::Result<(u64, String)> {
+    let mut hasher = blake3::Hasher::new();
+    hasher
+        .update_reader(File::open(path).map_err(|err| fs_error(err, path))?)
+        .map_err(|err| fs_error(err, path))?;
+    let size = std::fs::metadata(path)
+        .map_err(|err| fs_error(err, path))?
+        .len();
+    Ok((size, hasher.finalize().to_hex().to_string()))
+}
+
+fn global_subspaces() -> impl Iterator<Item = u8> {
+    [
+        Family::Data,
+        Family::Registry,
+        Family::Blob,
+        Family::Changelog,
+        Family::Queue,
+        Family::Report,
+        Family::Telemetry,
+        Family::Tasks,
+    ]
+    .into_iter()
+    .flat_map(|family| family.subspaces().iter().copied())
+    .filter(|subspace| !ACCOUNT_SUBSPACES.contains(subspace) && *subspace != SUBSPACE_BLOBS)
+}
+
+fn dirty_accounts(current: &BTreeMap<u32, u64>, previous: &BTreeMap<u32, u64>) -> BTreeSet<u32> {
+    // Data outside of user accounts is not tracked by the change log
+    current
+        .iter()
+        .filter(|(account_id, change_id)| previous.get(account_id) != Some(change_id))
+        .map(|(account_id, _)| *account_id)
+        .chain(
+            previous
+                .keys()
+                .filter(|account_id| !current.contains_key(account_id))
+                .copied(),
+        )
+        .chain([SYSTEM_ACCOUNT_ID])
+        .collect()
+}
+
+fn file_name(prefix: &str, subspace: u8) -> String {
+    format!("{prefix}_{}", char::from(subspace))
+}
+
+fn set_name(sequence: u64) -> String {
+    format!("{SET_PREFIX}{sequence:08}")
+}
+
+fn fs_error(err: std::io::Error, path: &Path) -> trc::Error {
+    trc::StoreEvent::FilesystemError
+        .reason(err)
+        .ctx(trc::Key::Path, path.display().to_string())
+}
+
+fn chain_error(path: &Path, details: &'static str) -> trc::Error {
+    trc::StoreEvent::DataCorruption
+        .into_err()
+        .details(details)
+        .ctx(trc::Key::Path, path.display().to_string())
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        BACKUP_RUNNING.store(false, Ordering::Release);
+    }
+}
+
+impl Default for IncrementalBackupParams {
+    fn default() -> Self {
+        IncrementalBackupParams {
+            path: PathBuf::new(),
+            max_increments: 6,
+            full: false,
+            include_blobs: true,
+        }
+    }
+}
@@ -4,7 +4,10 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::backup::MAGIC_MARKER;
+use super::{
+    backup::MAGIC_MARKER,
+    incremental::{latest_backup_set, restore_backup_chain},
+};
 use crate::{Core, DATABASE_SCHEMA_VERSION};
 use lz4_flex::frame::FrameDecoder;
 use registry::schema::enums::CompressionAlgo;
@@ -22,8 +25,20 @@ use utils::{UnwrapFailure, failed};
 
 impl Core {
     pub async fn restore(&self, src: PathBuf) {
-        // Backup the core
-        if src.is_dir() {
+        // Incremental backup sets are replayed in chain order
+        if src.join("manifest.json").is_file()
+            || latest_backup_set(&src).is_ok_and(|latest| latest.is_some())
+        {
+            println!("Restoring backup chain from {}.", src.display());
+            let manifest =
+                restore_backup_chain(self.storage.data.clone(), self.storage.blob.clone(), &src)
+                    .await
+                    .failed("Failed to restore backup chain");
+            println!(
+                "Restored backup set {} (baseline {}).",
+                manifest.sequence, manifest.baseline
+            );
+        } else if src.is_dir() {
             // Iterate directory and spawn a task for each file
             let mut tasks = Vec::new();
             for entry in std::fs::read_dir(&src).failed("Failed to read directory") {
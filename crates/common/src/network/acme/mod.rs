@@ -52,6 +52,9 @@ pub enum AcmeError {
         max_retries: u32,
         wait: Option<Duration>,
     },
+    Locked {
+        wait: Duration,
+    },
 }
 
 #[derive(
@@ -282,6 +285,11 @@ impl Display for AcmeError {
                     write!(f, "Rate limited. Retry after some time")
                 }
             }
+            AcmeError::Locked { wait } => write!(
+                f,
+                "Renewal in progress on another node. Retry after {} seconds",
+                wait.as_secs()
+            ),
         }
     }
 }
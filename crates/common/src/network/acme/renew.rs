@@ -5,7 +5,7 @@
  */
 
 use crate::{
-    Server,
+    KV_LOCK_ACME, Server,
     ipc::{BroadcastEvent, RegistryChange},
     network::acme::{
         AcmeDnsParameters, AcmeError, AcmeResult, ParsedCert, directory::AcmeRequestBuilder,
@@ -23,6 +23,7 @@ use registry::{
     },
     types::{datetime::UTCDateTime, id::ObjectId, map::Map},
 };
+use std::time::Duration;
 use store::{
     registry::{
         RegistryQuery,
@@ -30,8 +31,18 @@ use store::{
     },
     write::now,
 };
+use trc::{AcmeEvent, DaneEvent};
 use types::id::Id;
 
+// Upper bound for a full order including challenge validation and DNS propagation
+const ACME_LOCK_EXPIRY: u64 = 30 * 60;
+
+#[cfg(not(feature = "test_mode"))]
+const ACME_LOCK_RETRY: Duration = Duration::from_secs(5 * 60);
+
+#[cfg(feature = "test_mode")]
+const ACME_LOCK_RETRY: Duration = Duration::from_secs(1);
+
 impl Server {
     pub async fn acme_renew(&self, domain_id: Id) -> AcmeResult<Vec<Task>> {
         let Some(domain) = self.registry().object::<Domain>(domain_id).await? else {
@@ -40,7 +51,7 @@ impl Server {
                 domain_id
             )));
         };
-        let cert = match domain.certificate_management {
+        let cert = match &domain.certificate_management {
             CertificateManagement::Manual => {
                 return Err(AcmeError::Invalid(
                     "ACME not configured for domain".to_string(),
@@ -65,9 +76,66 @@ impl Server {
         let domains = request.build_domains(
             self,
             &domain.name,
-            &cert.subject_alternative_names.into_inner(),
+            cert.subject_alternative_names.as_slice(),
         );
 
+        // Only one cluster node may place an order for this set of names,
+        // the others pick up the resulting certificate from the registry
+        let lock_key = acme_lock_key(&domains);
+        if !self
+            .in_memory_store()
+            .try_lock(KV_LOCK_ACME, lock_key.as_bytes(), ACME_LOCK_EXPIRY)
+            .await?
+        {
+            trc::event!(
+                Acme(AcmeEvent::RenewLocked),
+                Id = domain_id.id(),
+                Hostname = domains.as_slice(),
+            );
+            return Err(AcmeError::Locked {
+                wait: ACME_LOCK_RETRY,
+            });
+        }
+
+        let result = self
+            .acme_renew_locked(
+                domain_id,
+                domain,
+                request,
+                domains,
+                challenge_type,
+                renew_before,
+                reuse_key,
+            )
+            .await;
+
+        // Release the lock so that another node can retry after a failure
+        if let Err(err) = self
+            .in_memory_store()
+            .remove_lock(KV_LOCK_ACME, lock_key.as_bytes())
+            .await
+        {
+            trc::error!(
+                err.details("Failed to release ACME renewal lock.")
+                    .caused_by(trc::location!())
+            );
+        }
+
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn acme_renew_locked(
+        &self,
+        domain_id: Id,
+        domain: Domain,
+        request: AcmeRequestBuilder,
+        domains: Vec<String>,
+        challenge_type: AcmeChallengeType,
+        renew_before: AcmeRenewBefore,
+        reuse_key: bool,
+    ) -> AcmeResult<Vec<Task>> {
+        // Check the renewal is still due, another node might have just renewed it
         if let Some(renew_at) = self
             .acme_certificate_renewal_due(&domains, renew_before, now())
             .await?
@@ -121,9 +189,14 @@ impl Server {
         let pem_cert = request
             .renew(self, domains, reuse_key_pem, dns_parameters)
             .await?;
+        let staged_until = match &domain.certificate_management {
+            CertificateManagement::Automatic(props) if props.dane_rollover => Some(
+                UTCDateTime::from_timestamp((now() + props.dane_max_delay.as_secs()) as i64),
+            ),
+            _ => None,
+        };
         let parsed_cert = ParsedCert::parse(&pem_cert.certificate)?;
-        let mut new_sans = parsed_cert.sans.clone();
-        new_sans.sort();
+        let new_sans = parsed_cert.sans.clone();
         let certificate = Certificate {
             private_key: SecretText::Text(SecretTextValue {
                 secret: pem_cert.private_key,
@@ -135,6 +208,7 @@ impl Server {
             not_valid_after: UTCDateTime::from_timestamp(parsed_cert.valid_not_after.timestamp()),
             not_valid_before: UTCDateTime::from_timestamp(parsed_cert.valid_not_before.timestamp()),
             subject_alternative_names: Map::new(parsed_cert.sans),
+            staged_until,
         };
         let now = now();
         let expires_in = (parsed_cert.valid_not_after.timestamp() as u64).saturating_sub(now);
@@ -151,41 +225,18 @@ impl Server {
             .await?
         {
             RegistryWriteResult::Success(id) => {
-                // Repoint the default certificate to the renewed object when it
-                // tracks the same SAN set, so its id does not go stale
-                if let Some(old) = self
-                    .registry()
-                    .get(ObjectType::SystemSettings.singleton())
-                    .await?
-                {
-                    let mut settings = SystemSettings::from(old.clone());
-                    if let Some(default_id) = settings.default_certificate_id
-                        && let Some(default_cert) =
-                            self.registry().object::<Certificate>(default_id).await?
-                    {
-                        let mut default_sans =
-                            default_cert.subject_alternative_names.clone().into_inner();
-                        default_sans.sort();
-                        if default_sans == new_sans {
-                            settings.default_certificate_id = Some(id);
-                            if let Err(err) = self
-                                .registry()
-                                .write(RegistryWrite::update(
-                                    Id::singleton(),
-                                    &settings.into(),
-                                    &old,
-                                ))
-                                .await
-                            {
-                                trc::error!(
-                                    err.details(
-                                        "Failed to update default certificate after ACME renewal."
-                                    )
-                                    .caused_by(trc::location!())
-                                );
-                            }
-                        }
-                    }
+                // Staged certificates keep the current default until they are activated
+                if staged_until.is_none() {
+                    self.repoint_default_certificate(id, new_sans).await?;
+                } else {
+                    trc::event!(
+                        Dane(DaneEvent::RolloverStaged),
+                        Id = id.id(),
+                        Domain = domain.name.clone(),
+                        Hostname = new_sans,
+                        Expires = staged_until
+                            .map(|until| trc::Value::Timestamp(until.timestamp() as u64)),
+                    );
                 }
 
                 // Reload registry
@@ -217,12 +268,64 @@ impl Server {
                     }));
                 }
 
+                // Activate the certificate once matching TLSA records are published
+                if staged_until.is_some() {
+                    tasks.push(Task::DaneRollover(TaskDomainManagement {
+                        domain_id,
+                        status: TaskStatus::now(),
+                    }));
+                }
+
                 Ok(tasks)
             }
             err => Err(AcmeError::Registry(err)),
         }
     }
 
+    // Repoint the default certificate to a new object when it tracks the
+    // same SAN set, so its id does not go stale
+    pub(crate) async fn repoint_default_certificate(
+        &self,
+        id: Id,
+        mut sans: Vec<String>,
+    ) -> trc::Result<()> {
+        let Some(old) = self
+            .registry()
+            .get(ObjectType::SystemSettings.singleton())
+            .await?
+        else {
+            return Ok(());
+        };
+        let mut settings = SystemSettings::from(old.clone());
+        if let Some(default_id) = settings.default_certificate_id
+            && default_id != id
+            && let Some(default_cert) = self.registry().object::<Certificate>(default_id).await?
+        {
+            let mut default_sans = default_cert.subject_alternative_names.into_inner();
+            default_sans.sort();
+            sans.sort();
+            if default_sans == sans {
+                settings.default_certificate_id = Some(id);
+                if let Err(err) = self
+                    .registry()
+                    .write(RegistryWrite::update(
+                        Id::singleton(),
+                        &settings.into(),
+                        &old,
+                    ))
+                    .await
+                {
+                    trc::error!(
+                        err.details("Failed to update default certificate.")
+                            .caused_by(trc::location!())
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn acme_certificate_by_domains(
         &self,
         domains: &[String],
@@ -297,3 +400,9 @@ impl Server {
         not_valid_before + total * numerator / denominator
     }
 }
+
+fn acme_lock_key(domains: &[String]) -> String {
+    let mut domains = domains.iter().map(String::as_str).collect::<Vec<_>>();
+    domains.sort_unstable();
+    domains.join(",")
+}
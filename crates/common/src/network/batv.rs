@@ -0,0 +1,152 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::Server;
+use registry::{
+    schema::{prelude::ObjectType, structs::Domain},
+    types::id::ObjectId,
+};
+use ring::hmac;
+use std::fmt::Write;
+use store::{registry::bootstrap::Bootstrap, write::now};
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+#[derive(Debug, Clone)]
+pub struct BatvKeys {
+    // The first key signs, all of them are accepted when validating
+    pub keys: Box<[hmac::Key]>,
+    pub validity: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatvResult {
+    Valid,
+    Invalid,
+    Expired,
+}
+
+impl Server {
+    pub async fn batv_sign(&self, return_path: &str) -> trc::Result<Option<String>> {
+        if parse_prvs(return_path).is_some() {
+            return Ok(None);
+        }
+        let Some((local_part, domain)) = return_path.rsplit_once('@') else {
+            return Ok(None);
+        };
+
+        Ok(self.domain(domain).await?.and_then(|cache| {
+            cache
+                .batv
+                .as_ref()
+                .map(|batv| batv.sign(local_part, domain, now()))
+        }))
+    }
+
+    // Returns the validation result and the untagged address for
+    // BATV addresses of local domains
+    pub async fn batv_verify<'x>(
+        &self,
+        address: &'x str,
+    ) -> trc::Result<Option<(BatvResult, &'x str)>> {
+        let Some((tag, untagged)) = parse_prvs(address) else {
+            return Ok(None);
+        };
+        let Some((_, domain)) = untagged.rsplit_once('@') else {
+            return Ok(None);
+        };
+
+        Ok(self.domain(domain).await?.and_then(|domain| {
+            domain
+                .batv
+                .as_ref()
+                .map(|batv| (batv.verify(tag, untagged, now()), untagged))
+        }))
+    }
+}
+
+impl BatvKeys {
+    pub async fn parse(domain: &Domain, domain_id: u32, bp: &mut Bootstrap) -> Option<Self> {
+        let id = ObjectId::new(ObjectType::Domain, domain_id.into());
+        let key = match domain.batv_key.secret().await {
+            Ok(Some(key)) => key,
+            Ok(None) => return None,
+            Err(err) => {
+                bp.build_error(id, format!("Unable to retrieve BATV key: {err}"));
+                return None;
+            }
+        };
+        let mut keys = vec![hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes())];
+        match domain.batv_previous_key.secret().await {
+            Ok(Some(key)) => {
+                keys.push(hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes()));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                bp.build_error(id, format!("Unable to retrieve previous BATV key: {err}"));
+            }
+        }
+
+        Some(BatvKeys {
+            keys: keys.into_boxed_slice(),
+            validity: (domain.batv_validity.into_inner().as_secs() / SECONDS_PER_DAY).clamp(1, 365),
+        })
+    }
+
+    // Tags have the form "prvs=KDDDSSSSSS=local@domain", where K is the key
+    // number (always 0 as all keys are tried on validation), DDD the expiration
+    // day modulo 1000 and SSSSSS the truncated HMAC of the address
+    pub fn sign(&self, local_part: &str, domain: &str, now: u64) -> String {
+        let address = format!("{local_part}@{domain}");
+        let day = (now / SECONDS_PER_DAY + self.validity) % 1000;
+        format!(
+            "prvs={}={address}",
+            signature(&self.keys[0], day, &address.to_lowercase())
+        )
+    }
+
+    pub fn verify(&self, tag: &str, address: &str, now: u64) -> BatvResult {
+        let Some(day) = tag
+            .get(1..4)
+            .filter(|day| day.bytes().all(|ch| ch.is_ascii_digit()))
+            .and_then(|day| day.parse::<u64>().ok())
+        else {
+            return BatvResult::Invalid;
+        };
+
+        let address = address.to_lowercase();
+        if !self
+            .keys
+            .iter()
+            .any(|key| signature(key, day, &address).eq_ignore_ascii_case(tag))
+        {
+            BatvResult::Invalid
+        } else if (day + 1000 - (now / SECONDS_PER_DAY) % 1000) % 1000 > self.validity {
+            BatvResult::Expired
+        } else {
+            BatvResult::Valid
+        }
+    }
+}
+
+// Splits a BATV address into its tag and the original address
+pub fn parse_prvs(address: &str) -> Option<(&str, &str)> {
+    let (tag, address) = address
+        .get(..5)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("prvs="))
+        .and_then(|_| address[5..].split_once('='))?;
+
+    (tag.len() == 10 && address.contains('@')).then_some((tag, address))
+}
+
+fn signature(key: &hmac::Key, day: u64, address: &str) -> String {
+    let mut tag = format!("0{day:03}");
+    let hash = hmac::sign(key, format!("{tag}{address}").as_bytes());
+    for byte in &hash.as_ref()[..3] {
+        let _ = write!(tag, "{byte:02x}");
+    }
+    tag
+}
@@ -0,0 +1,233 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::config::mailstore::spamfilter::ClamAvConfig;
+use deadpool::managed::{self, Object, PoolError};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+const MAX_RESPONSE_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClamdAddress {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+#[derive(Debug)]
+pub struct ClamdConnectionManager {
+    pub address: ClamdAddress,
+    pub timeout: Duration,
+}
+
+#[derive(Debug)]
+pub struct ClamdConnection {
+    stream: ClamdStream,
+    request_id: u64,
+}
+
+#[derive(Debug)]
+enum ClamdStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClamdVerdict {
+    Clean,
+    Infected(String),
+}
+
+impl ClamAvConfig {
+    pub async fn scan(&self, message: &[u8]) -> trc::Result<ClamdVerdict> {
+        let mut conn = self.pool.get().await.map_err(|err| match err {
+            PoolError::Backend(err) => err,
+            PoolError::Timeout(_) => trc::SpamEvent::ClamAvError
+                .into_err()
+                .details("Connection timed out"),
+            err => trc::SpamEvent::ClamAvError.into_err().reason(err),
+        })?;
+
+        let result = match tokio::time::timeout(self.timeout, conn.scan(message)).await {
+            Ok(result) => result,
+            Err(_) => Err(trc::SpamEvent::ClamAvError
+                .into_err()
+                .details("Scan timed out")),
+        };
+
+        // Connections are not reused after a failed scan, clamd may have ended the session
+        if result.is_err() {
+            let _ = Object::take(conn);
+        }
+
+        result
+    }
+}
+
+impl ClamdConnection {
+    async fn scan(&mut self, message: &[u8]) -> trc::Result<ClamdVerdict> {
+        self.stream.write_all(b"zINSTREAM\0").await?;
+        for chunk in message.chunks(CHUNK_SIZE) {
+            self.stream
+                .write_all(&(chunk.len() as u32).to_be_bytes())
+                .await?;
+            self.stream.write_all(chunk).await?;
+        }
+        self.stream.write_all(&[0, 0, 0, 0]).await?;
+
+        let response = self.read_response().await?;
+        if response == "stream: OK" {
+            Ok(ClamdVerdict::Clean)
+        } else if let Some(signature) = response
+            .strip_prefix("stream: ")
+            .and_then(|response| response.strip_suffix(" FOUND"))
+        {
+            Ok(ClamdVerdict::Infected(signature.to_string()))
+        } else {
+            Err(trc::SpamEvent::ClamAvError
+                .into_err()
+                .details("Unexpected clamd response")
+                .ctx(trc::Key::Reason, response))
+        }
+    }
+
+    async fn ping(&mut self) -> trc::Result<()> {
+        self.stream.write_all(b"zPING\0").await?;
+        let response = self.read_response().await?;
+        if response == "PONG" {
+            Ok(())
+        } else {
+            Err(trc::SpamEvent::ClamAvError
+                .into_err()
+                .details("Unexpected clamd response")
+                .ctx(trc::Key::Reason, response))
+        }
+    }
+
+    async fn read_response(&mut self) -> trc::Result<String> {
+        // Responses within a session are prefixed with the request number
+        self.request_id += 1;
+        let mut response = Vec::with_capacity(64);
+        let mut buf = [0u8; 128];
+        loop {
+            let bytes_read = self.stream.read(&mut buf).await?;
+            if bytes_read == 0 {
+                return Err(trc::SpamEvent::ClamAvError
+                    .into_err()
+                    .details("Connection closed by clamd"));
+            }
+            response.extend_from_slice(&buf[..bytes_read]);
+            if let Some(pos) = response.iter().position(|&ch| ch == 0) {
+                response.truncate(pos);
+                break;
+            } else if response.len() > MAX_RESPONSE_SIZE {
+                return Err(trc::SpamEvent::ClamAvError
+                    .into_err()
+                    .details("clamd response too large"));
+            }
+        }
+
+        let response = String::from_utf8_lossy(&response);
+        response
+            .split_once(": ")
+            .filter(|(id, _)| id.parse::<u64>().ok() == Some(self.request_id))
+            .map(|(_, response)| response.trim().to_string())
+            .ok_or_else(|| {
+                trc::SpamEvent::ClamAvError
+                    .into_err()
+                    .details("Invalid clamd response")
+                    .ctx(trc::Key::Reason, response.to_string())
+            })
+    }
+}
+
+impl ClamdStream {
+    async fn write_all(&mut self, bytes: &[u8]) -> trc::Result<()> {
+        match self {
+            ClamdStream::Tcp(stream) => stream.write_all(bytes).await,
+            #[cfg(unix)]
+            ClamdStream::Unix(stream) => stream.write_all(bytes).await,
+        }
+        .map_err(|err| trc::SpamEvent::ClamAvError.into_err().reason(err))
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> trc::Result<usize> {
+        match self {
+            ClamdStream::Tcp(stream) => stream.read(buf).await,
+            #[cfg(unix)]
+            ClamdStream::Unix(stream) => stream.read(buf).await,
+        }
+        .map_err(|err| trc::SpamEvent::ClamAvError.into_err().reason(err))
+    }
+}
+
+impl managed::Manager for ClamdConnectionManager {
+    type Type = ClamdConnection;
+    type Error = trc::Error;
+
+    async fn create(&self) -> Result<ClamdConnection, trc::Error> {
+        let stream = tokio::time::timeout(self.timeout, async {
+            match &self.address {
+                ClamdAddress::Tcp(address) => {
+                    TcpStream::connect(address).await.map(ClamdStream::Tcp)
+                }
+                #[cfg(unix)]
+                ClamdAddress::Unix(path) => tokio::net::UnixStream::connect(path)
+                    .await
+                    .map(ClamdStream::Unix),
+            }
+        })
+        .await
+        .map_err(|_| {
+            trc::SpamEvent::ClamAvError
+                .into_err()
+                .details("Connection timed out")
+        })?
+        .map_err(|err| trc::SpamEvent::ClamAvError.into_err().reason(err))?;
+
+        // Sessions allow the same connection to be reused for multiple scans
+        let mut conn = ClamdConnection {
+            stream,
+            request_id: 0,
+        };
+        conn.stream.write_all(b"zIDSESSION\0").await?;
+
+        Ok(conn)
+    }
+
+    async fn recycle(
+        &self,
+        conn: &mut ClamdConnection,
+        _: &managed::Metrics,
+    ) -> managed::RecycleResult<trc::Error> {
+        tokio::time::timeout(self.timeout, conn.ping())
+            .await
+            .map_err(|_| {
+                trc::SpamEvent::ClamAvError
+                    .into_err()
+                    .details("Health check timed out")
+            })
+            .and_then(|result| result)
+            .map_err(managed::RecycleError::Backend)
+    }
+}
+
+impl ClamdAddress {
+    pub fn parse(address: &str) -> Self {
+        #[cfg(unix)]
+        if address.starts_with('/') {
+            return ClamdAddress::Unix(address.into());
+        }
+
+        ClamdAddress::Tcp(address.to_string())
+    }
+}
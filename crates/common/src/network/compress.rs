@@ -0,0 +1,219 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{
+    borrow::Cow,
+    io,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
+use rustls_pki_types::CertificateDer;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use super::SessionStream;
+
+const BUF_SIZE: usize = 8192;
+
+// Decompressed data may exceed this many times the compressed input by at
+// most the maximum request size. A single highly compressible request is
+// accepted, while the session as a whole cannot inflate without bound.
+const MAX_EXPANSION_RATIO: u64 = 128;
+
+// Raw DEFLATE stream (RFC 1951) as used by IMAP COMPRESS=DEFLATE (RFC 4978).
+// Both directions use fixed size buffers and compressed output is only
+// sent to the peer when the buffer fills up or the stream is flushed.
+pub struct DeflateStream<T: SessionStream> {
+    inner: T,
+    compress: Compress,
+    decompress: Decompress,
+    rx_buf: Box<[u8]>,
+    rx_pos: usize,
+    rx_len: usize,
+    rx_allowance: u64,
+    tx_buf: Vec<u8>,
+    tx_pos: usize,
+    tx_pending: bool,
+}
+
+impl<T: SessionStream> DeflateStream<T> {
+    pub fn new(inner: T, max_request_size: usize) -> Self {
+        DeflateStream {
+            inner,
+            compress: Compress::new(Compression::default(), false),
+            decompress: Decompress::new(false),
+            rx_buf: vec![0; BUF_SIZE].into_boxed_slice(),
+            rx_pos: 0,
+            rx_len: 0,
+            rx_allowance: max_request_size as u64,
+            tx_buf: Vec::with_capacity(BUF_SIZE),
+            tx_pos: 0,
+            tx_pending: false,
+        }
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.tx_pos < self.tx_buf.len() {
+            let written =
+                ready!(Pin::new(&mut self.inner).poll_write(cx, &self.tx_buf[self.tx_pos..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.tx_pos += written;
+        }
+        self.tx_buf.clear();
+        self.tx_pos = 0;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T: SessionStream> AsyncRead for DeflateStream<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            // Inflate any buffered input, the decompressor might also have
+            // output pending from a previous call
+            let total_in = this.decompress.total_in();
+            let total_out = this.decompress.total_out();
+            let status = this
+                .decompress
+                .decompress(
+                    &this.rx_buf[this.rx_pos..this.rx_len],
+                    buf.initialize_unfilled(),
+                    FlushDecompress::None,
+                )
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            let consumed = (this.decompress.total_in() - total_in) as usize;
+            let produced = (this.decompress.total_out() - total_out) as usize;
+            this.rx_pos += consumed;
+
+            if produced > 0 {
+                if this.decompress.total_out()
+                    > this
+                        .decompress
+                        .total_in()
+                        .saturating_mul(MAX_EXPANSION_RATIO)
+                        .saturating_add(this.rx_allowance)
+                {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Decompressed data exceeds the maximum size",
+                    )));
+                }
+                buf.advance(produced);
+                return Poll::Ready(Ok(()));
+            } else if status == Status::StreamEnd {
+                return Poll::Ready(Ok(()));
+            } else if consumed > 0 && this.rx_pos < this.rx_len {
+                continue;
+            }
+
+            // Read more compressed data
+            if this.rx_pos > 0 {
+                this.rx_buf.copy_within(this.rx_pos..this.rx_len, 0);
+                this.rx_len -= this.rx_pos;
+                this.rx_pos = 0;
+            }
+            if this.rx_len == this.rx_buf.len() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Invalid compressed data",
+                )));
+            }
+            let mut rx_buf = ReadBuf::new(&mut this.rx_buf[this.rx_len..]);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut rx_buf))?;
+            let bytes_read = rx_buf.filled().len();
+            if bytes_read == 0 {
+                return Poll::Ready(Ok(()));
+            }
+            this.rx_len += bytes_read;
+        }
+    }
+}
+
+impl<T: SessionStream> AsyncWrite for DeflateStream<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        loop {
+            let total_in = this.compress.total_in();
+            this.compress
+                .compress_vec(buf, &mut this.tx_buf, FlushCompress::None)
+                .map_err(io::Error::other)?;
+            let consumed = (this.compress.total_in() - total_in) as usize;
+            if consumed > 0 {
+                this.tx_pending = true;
+                return Poll::Ready(Ok(consumed));
+            }
+
+            // The output buffer is full
+            ready!(this.poll_drain(cx))?;
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        // Emit a sync flush so the peer can decode the complete response
+        while this.tx_pending {
+            this.compress
+                .compress_vec(&[], &mut this.tx_buf, FlushCompress::Sync)
+                .map_err(io::Error::other)?;
+            if this.tx_buf.len() < this.tx_buf.capacity() {
+                this.tx_pending = false;
+            } else {
+                ready!(this.poll_drain(cx))?;
+            }
+        }
+
+        ready!(this.poll_drain(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+impl<T: SessionStream> SessionStream for DeflateStream<T> {
+    fn is_tls(&self) -> bool {
+        self.inner.is_tls()
+    }
+
+    fn tls_version_and_cipher(&self) -> (Cow<'static, str>, Cow<'static, str>) {
+        self.inner.tls_version_and_cipher()
+    }
+
+    fn peer_certificates(&self) -> Option<&[CertificateDer<'static>]> {
+        self.inner.peer_certificates()
+    }
+}
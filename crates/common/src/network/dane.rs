@@ -0,0 +1,222 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    Server,
+    config::smtp::resolver::{Tlsa, TlsaMatching},
+    ipc::{BroadcastEvent, RegistryChange},
+};
+use ahash::AHashSet;
+use registry::{
+    schema::{prelude::ObjectType, structs::Certificate},
+    types::id::ObjectId,
+};
+use rustls_pemfile::certs;
+use rustls_pki_types::CertificateDer;
+use sha2::{Digest, Sha256, Sha512};
+use std::{fmt::Display, io::Cursor};
+use store::{
+    registry::write::{RegistryWrite, RegistryWriteResult},
+    write::now,
+};
+use trc::AddContext;
+use types::id::Id;
+use utils::HexEncode;
+use x509_parser::parse_x509_certificate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaneRecord {
+    pub is_end_entity: bool,
+    pub data: Vec<u8>,
+}
+
+// A certificate stored in the registry that is not yet valid or that is
+// staged for a DANE rollover and has not been activated
+pub struct UpcomingCertificate {
+    pub id: Id,
+    pub certificate: Certificate,
+    pub chain: Vec<CertificateDer<'static>>,
+}
+
+impl DaneRecord {
+    // Builds a "3 1 1" record for the leaf certificate and a "2 1 1" record
+    // for each issuer in the chain
+    pub fn from_chain(chain: &[CertificateDer<'_>]) -> Vec<DaneRecord> {
+        chain
+            .iter()
+            .enumerate()
+            .filter_map(|(cert_num, der)| {
+                parse_x509_certificate(der.as_ref())
+                    .ok()
+                    .map(|(_, cert)| DaneRecord {
+                        is_end_entity: cert_num == 0,
+                        data: Sha256::digest(cert.subject_pki.raw).to_vec(),
+                    })
+            })
+            .collect()
+    }
+
+    pub fn usage(&self) -> u8 {
+        if self.is_end_entity { 3 } else { 2 }
+    }
+}
+
+impl Display for DaneRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} 1 1 {}", self.usage(), self.data.hex_encode())
+    }
+}
+
+// Returns whether any of the published TLSA records matches the chain. As the
+// chain is our own, only the digests are compared and no path validation is done.
+pub fn tlsa_matches_chain(tlsa: &Tlsa, chain: &[CertificateDer<'_>]) -> bool {
+    chain.iter().enumerate().any(|(cert_num, der)| {
+        let Ok((_, cert)) = parse_x509_certificate(der.as_ref()) else {
+            return false;
+        };
+        tlsa.entries
+            .iter()
+            .filter(|entry| entry.is_end_entity == (cert_num == 0))
+            .any(|entry| {
+                let data = if entry.is_spki {
+                    cert.subject_pki.raw
+                } else {
+                    der.as_ref()
+                };
+                match entry.matching {
+                    TlsaMatching::Full => data == entry.data,
+                    TlsaMatching::Sha256 => Sha256::digest(data).as_slice() == entry.data,
+                    TlsaMatching::Sha512 => Sha512::digest(data).as_slice() == entry.data,
+                }
+            })
+    })
+}
+
+pub fn certificate_covers<'x>(mut names: impl Iterator<Item = &'x str>, hostname: &str) -> bool {
+    names.any(|name| {
+        name == hostname
+            || name.strip_prefix("*.").is_some_and(|domain| {
+                hostname
+                    .split_once('.')
+                    .is_some_and(|(_, parent)| parent == domain)
+            })
+    })
+}
+
+pub fn parse_certificate_chain(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, String> {
+    let chain = certs(&mut Cursor::new(pem))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Failed to read certificates: {err}"))?;
+    if !chain.is_empty() {
+        Ok(chain)
+    } else {
+        Err("No certificates found.".to_string())
+    }
+}
+
+impl Server {
+    // TLSA records for SMTP are published under "_25._tcp" of each MX hostname
+    pub fn dane_mx_hostnames(&self) -> Vec<String> {
+        let network = &self.core.network;
+        let mut seen = AHashSet::new();
+        network
+            .info
+            .mxs
+            .iter()
+            .map(|mx| {
+                mx.hostname
+                    .as_deref()
+                    .unwrap_or(network.server_name.as_str())
+                    .trim_end_matches('.')
+                    .to_lowercase()
+            })
+            .filter(|hostname| seen.insert(hostname.clone()))
+            .collect()
+    }
+
+    pub async fn upcoming_certificates(&self) -> trc::Result<Vec<UpcomingCertificate>> {
+        let now = now() as i64;
+        let mut upcoming = Vec::new();
+        for cert_obj in self
+            .registry()
+            .list::<Certificate>()
+            .await
+            .caused_by(trc::location!())?
+        {
+            let certificate = cert_obj.object;
+            if certificate.not_valid_after.timestamp() <= now
+                || (certificate.not_valid_before.timestamp() <= now
+                    && certificate.staged_until.is_none())
+            {
+                continue;
+            }
+
+            let chain = match certificate.certificate.value().await {
+                Ok(pem) => parse_certificate_chain(pem.as_bytes()),
+                Err(err) => Err(format!("Failed to obtain certificate value: {err}")),
+            };
+            match chain {
+                Ok(chain) => upcoming.push(UpcomingCertificate {
+                    id: cert_obj.id.id(),
+                    certificate,
+                    chain,
+                }),
+                Err(err) => {
+                    trc::error!(
+                        trc::StoreEvent::UnexpectedError
+                            .into_err()
+                            .id(cert_obj.id.id().id())
+                            .reason(err)
+                            .caused_by(trc::location!())
+                    );
+                }
+            }
+        }
+
+        Ok(upcoming)
+    }
+
+    // Clears the staged flag of a certificate and starts serving it
+    pub async fn activate_staged_certificate(&self, id: Id) -> trc::Result<()> {
+        let Some(current) = self
+            .registry()
+            .get(ObjectId::new(ObjectType::Certificate, id))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(());
+        };
+        let mut certificate = Certificate::from(current.clone());
+        if certificate.staged_until.take().is_none() {
+            return Ok(());
+        }
+        let sans = certificate.subject_alternative_names.clone().into_inner();
+
+        match self
+            .registry()
+            .write(RegistryWrite::update(id, &certificate.into(), &current))
+            .await
+            .caused_by(trc::location!())?
+        {
+            RegistryWriteResult::Success(_) => {}
+            err => {
+                return Err(trc::StoreEvent::UnexpectedError
+                    .into_err()
+                    .details("Failed to activate staged certificate")
+                    .reason(err)
+                    .caused_by(trc::location!()));
+            }
+        }
+        self.repoint_default_certificate(id, sans).await?;
+
+        let change = RegistryChange::Reload(ObjectType::Certificate);
+        Box::pin(self.reload_registry(change)).await?;
+        self.cluster_broadcast(BroadcastEvent::RegistryChange(change))
+            .await;
+
+        Ok(())
+    }
+}
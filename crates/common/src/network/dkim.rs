@@ -19,10 +19,17 @@ use store::rand::{self, Rng};
 
 pub async fn generate_dkim_private_key(
     key_type: DkimSignatureType,
+) -> trc::Result<Result<String, String>> {
+    generate_dkim_private_key_with_size(key_type, 2048).await
+}
+
+pub async fn generate_dkim_private_key_with_size(
+    key_type: DkimSignatureType,
+    rsa_bits: usize,
 ) -> trc::Result<Result<String, String>> {
     let private_key = tokio::task::spawn_blocking(move || match key_type {
         DkimSignatureType::Dkim1RsaSha256 | DkimSignatureType::Dkim2RsaSha256 => {
-            DkimKeyPair::generate_rsa(2048).map(|key| (key, "RSA PRIVATE KEY"))
+            DkimKeyPair::generate_rsa(rsa_bits).map(|key| (key, "RSA PRIVATE KEY"))
         }
         DkimSignatureType::Dkim1Ed25519Sha256 | DkimSignatureType::Dkim2Ed25519Sha256 => {
             DkimKeyPair::generate_ed25519().map(|key| (key, "PRIVATE KEY"))
@@ -106,30 +113,22 @@ pub async fn generate_dkim_dns_record(
     key: &DkimSignature,
     domain: &str,
 ) -> trc::Result<NamedDnsRecord> {
-    let public_key = generate_dkim_public_key(key).await?;
+    Ok(NamedDnsRecord {
+        name: generate_dkim_dns_record_name(key, domain),
+        record: DnsRecord::TXT(generate_dkim_txt_value(key).await?),
+    })
+}
 
-    let (selector, record) = match key {
-        DkimSignature::Dkim1Ed25519Sha256(sign) => (
-            &sign.selector,
-            format!("v=DKIM1; k=ed25519; h=sha256; p={public_key}"),
-        ),
-        DkimSignature::Dkim2Ed25519Sha256(sign) => (
-            &sign.selector,
-            format!("v=DKIM1; k=ed25519; h=sha256; p={public_key}"),
-        ),
-        DkimSignature::Dkim1RsaSha256(sign) => (
-            &sign.selector,
-            format!("v=DKIM1; k=rsa; h=sha256; p={public_key}"),
-        ),
-        DkimSignature::Dkim2RsaSha256(sign) => (
-            &sign.selector,
-            format!("v=DKIM1; k=rsa; h=sha256; p={public_key}"),
-        ),
-    };
+pub async fn generate_dkim_txt_value(key: &DkimSignature) -> trc::Result<String> {
+    let public_key = generate_dkim_public_key(key).await?;
 
-    Ok(NamedDnsRecord {
-        name: format!("{selector}._domainkey.{domain}."),
-        record: DnsRecord::TXT(record),
+    Ok(match key {
+        DkimSignature::Dkim1Ed25519Sha256(_) | DkimSignature::Dkim2Ed25519Sha256(_) => {
+            format!("v=DKIM1; k=ed25519; h=sha256; p={public_key}")
+        }
+        DkimSignature::Dkim1RsaSha256(_) | DkimSignature::Dkim2RsaSha256(_) => {
+            format!("v=DKIM1; k=rsa; h=sha256; p={public_key}")
+        }
     })
 }
 
@@ -137,6 +136,36 @@ pub fn generate_dkim_dns_record_name(key: &DkimSignature, domain: &str) -> Strin
     format!("{}._domainkey.{domain}.", key.selector())
 }
 
+/// Split a TXT record value into character-strings of at most 255 bytes.
+pub fn split_txt_value(value: &str) -> Vec<&str> {
+    let mut chunks = Vec::with_capacity(value.len() / 255 + 1);
+    let mut value = value;
+    while value.len() > 255 {
+        let mut pos = 255;
+        while !value.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        let (chunk, rest) = value.split_at(pos);
+        chunks.push(chunk);
+        value = rest;
+    }
+    chunks.push(value);
+    chunks
+}
+
+/// Extract the public key (`p=` tag) from a DKIM TXT record, ignoring whitespace.
+pub fn dkim_public_key_tag(record: &str) -> Option<String> {
+    record.split(';').find_map(|tag| {
+        let (name, value) = tag.split_once('=')?;
+        (name.trim() == "p").then(|| {
+            value
+                .chars()
+                .filter(|ch| !ch.is_ascii_whitespace())
+                .collect()
+        })
+    })
+}
+
 /// Generate a DKIM selector from a template string.
 ///
 /// Supported variables:
@@ -303,4 +332,28 @@ mod tests {
         let expected = Utc::now().format("%Y%m").to_string();
         assert_eq!(sel, expected);
     }
+
+    #[test]
+    fn txt_value_splitting() {
+        assert_eq!(split_txt_value("v=DKIM1; p=abc"), vec!["v=DKIM1; p=abc"]);
+
+        let value = format!("v=DKIM1; k=rsa; p={}", "A".repeat(600));
+        let chunks = split_txt_value(&value);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 255));
+        assert_eq!(chunks.concat(), value);
+    }
+
+    #[test]
+    fn public_key_tag() {
+        assert_eq!(
+            dkim_public_key_tag("v=DKIM1; k=rsa; p=MIIB IjAN\tBgkq ; t=s").as_deref(),
+            Some("MIIBIjANBgkq")
+        );
+        assert_eq!(
+            dkim_public_key_tag("v=DKIM1; k=ed25519; p="),
+            Some(String::new())
+        );
+        assert_eq!(dkim_public_key_tag("v=DKIM1; k=rsa"), None);
+    }
 }
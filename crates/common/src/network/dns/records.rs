@@ -4,7 +4,17 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::{Server, config::network::Pacc, network::dkim::generate_dkim_dns_record};
+use crate::{
+    Server,
+    config::{
+        network::Pacc,
+        smtp::resolver::{Policy, PolicyOverride},
+    },
+    network::{
+        dane::{DaneRecord, certificate_covers},
+        dkim::generate_dkim_dns_record,
+    },
+};
 use ahash::{AHashMap, AHashSet};
 use base64::{Engine, engine::general_purpose};
 use dns_update::{
@@ -21,7 +31,6 @@ use sha2::{Digest, Sha256};
 use store::registry::RegistryQuery;
 use trc::AddContext;
 use types::id::Id;
-use x509_parser::parse_x509_certificate;
 
 impl Server {
     pub async fn build_dns_records(
@@ -120,7 +129,17 @@ impl Server {
                     }
                 }
                 DnsRecordType::MtaSts => {
-                    if let Some(policy) = &self.core.smtp.session.mta_sts_policy {
+                    let domain_policy = PolicyOverride::new(
+                        domain.mta_sts_mode,
+                        domain.mta_sts_mx_hosts.as_slice().to_vec(),
+                        domain
+                            .mta_sts_max_age
+                            .map(|max_age| max_age.into_inner().as_secs()),
+                    );
+                    if let Some(policy) = Policy::with_override(
+                        self.core.smtp.session.mta_sts_policy.as_ref(),
+                        domain_policy.as_ref(),
+                    ) {
                         records.push(NamedDnsRecord {
                             name: format!("mta-sts.{domain_name}."),
                             record: DnsRecord::CNAME(format!("{default_host}.")),
@@ -128,7 +147,7 @@ impl Server {
 
                         records.push(NamedDnsRecord {
                             name: format!("_mta-sts.{domain_name}."),
-                            record: DnsRecord::TXT(format!("v=STSv1; id={}", policy.id)),
+                            record: DnsRecord::TXT(policy.txt_record()),
                         });
                     }
                 }
@@ -308,40 +327,54 @@ impl Server {
                         }
                     }
 
+                    // Publish the records of upcoming certificates along with the
+                    // current ones, so that they are in place before a rollover
+                    let upcoming = self.upcoming_certificates().await?;
                     for (hostname, ports) in hostnames {
-                        if let Some(key) = self.resolve_certificate(&hostname) {
-                            for (cert_num, cert) in key.cert.iter().enumerate() {
-                                let parsed_cert = match parse_x509_certificate(cert) {
-                                    Ok((_, parsed_cert)) => parsed_cert,
-                                    Err(err) => {
-                                        trc::error!(
-                                            trc::StoreEvent::UnexpectedError
-                                                .into_err()
-                                                .reason(err)
-                                                .caused_by(trc::location!())
-                                        );
-                                        continue;
-                                    }
-                                };
+                        let mut dane_records = Vec::new();
+                        for chain in self
+                            .resolve_certificate(&hostname)
+                            .map(|key| key.cert.clone())
+                            .into_iter()
+                            .chain(
+                                upcoming
+                                    .iter()
+                                    .filter(|cert| {
+                                        certificate_covers(
+                                            cert.certificate
+                                                .subject_alternative_names
+                                                .iter()
+                                                .map(String::as_str),
+                                            &hostname,
+                                        )
+                                    })
+                                    .map(|cert| cert.chain.clone()),
+                            )
+                        {
+                            for record in DaneRecord::from_chain(&chain) {
+                                if !dane_records.contains(&record) {
+                                    dane_records.push(record);
+                                }
+                            }
+                        }
 
-                                let cert_usage = if cert_num == 0 {
-                                    TlsaCertUsage::DaneEe
-                                } else {
-                                    TlsaCertUsage::DaneTa
-                                };
-                                let cert_data = sha2::Sha256::digest(parsed_cert.subject_pki.raw);
+                        for record in dane_records {
+                            let cert_usage = if record.is_end_entity {
+                                TlsaCertUsage::DaneEe
+                            } else {
+                                TlsaCertUsage::DaneTa
+                            };
 
-                                for port in &ports {
-                                    records.push(NamedDnsRecord {
-                                        name: format!("_{port}._tcp.{hostname}."),
-                                        record: DnsRecord::TLSA(TLSARecord {
-                                            cert_usage,
-                                            selector: TlsaSelector::Spki,
-                                            matching: TlsaMatching::Sha256,
-                                            cert_data: cert_data.to_vec(),
-                                        }),
-                                    });
-                                }
+                            for port in &ports {
+                                records.push(NamedDnsRecord {
+                                    name: format!("_{port}._tcp.{hostname}."),
+                                    record: DnsRecord::TLSA(TLSARecord {
+                                        cert_usage,
+                                        selector: TlsaSelector::Spki,
+                                        matching: TlsaMatching::Sha256,
+                                        cert_data: record.data.clone(),
+                                    }),
+                                });
                             }
                         }
                     }
@@ -0,0 +1,100 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::limiter::{ConcurrencyLimiter, InFlight};
+use crate::Server;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Tracks whether the server is draining and the SMTP sessions and queue
+// deliveries that have to complete before it can shut down.
+pub struct DrainState {
+    status: watch::Sender<bool>,
+    sessions: ConcurrencyLimiter,
+    deliveries: ConcurrencyLimiter,
+}
+
+impl DrainState {
+    pub fn start(&self) -> bool {
+        self.status
+            .send_if_modified(|is_draining| !std::mem::replace(is_draining, true))
+    }
+
+    pub fn is_draining(&self) -> bool {
+        *self.status.borrow()
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.status.subscribe()
+    }
+
+    pub fn track_session(&self) -> InFlight {
+        self.sessions.track()
+    }
+
+    pub fn track_delivery(&self) -> InFlight {
+        self.deliveries.track()
+    }
+
+    pub fn is_drained(&self) -> bool {
+        !self.sessions.is_active() && !self.deliveries.is_active()
+    }
+
+    pub async fn wait(&self, grace_period: Duration) -> bool {
+        let deadline = Instant::now() + grace_period;
+
+        while !self.is_drained() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        true
+    }
+}
+
+impl Default for DrainState {
+    fn default() -> Self {
+        Self {
+            status: watch::channel(false).0,
+            sessions: ConcurrencyLimiter::new(u64::MAX),
+            deliveries: ConcurrencyLimiter::new(u64::MAX),
+        }
+    }
+}
+
+impl Server {
+    pub fn is_draining(&self) -> bool {
+        self.inner.data.drain.is_draining()
+    }
+
+    pub fn start_drain(&self) {
+        if self.inner.data.drain.start() {
+            trc::event!(
+                Server(trc::ServerEvent::Draining),
+                Limit = self.core.network.shutdown_grace_period,
+            );
+        }
+    }
+
+    pub async fn wait_for_drain(&self) {
+        if !self
+            .inner
+            .data
+            .drain
+            .wait(self.core.network.shutdown_grace_period)
+            .await
+        {
+            trc::event!(
+                Server(trc::ServerEvent::DrainTimeout),
+                Limit = self.core.network.shutdown_grace_period,
+            );
+        }
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::Server;
+use futures::future::join_all;
+use registry::{schema::enums::HealthCheckDependency, types::EnumImpl};
+use serde::Serialize;
+use std::time::Instant;
+use tokio::sync::Mutex;
+
+// Artificial delay added to the health check of each dependency, used to
+// test how the readiness probe handles stores that stop responding
+#[cfg(feature = "test_mode")]
+pub static HEALTH_CHECK_DELAY: [std::sync::atomic::AtomicU64; 4] =
+    [const { std::sync::atomic::AtomicU64::new(0) }; 4];
+
+// Last readiness result, probes arriving while the stores are being checked
+// wait for that check instead of starting their own
+#[derive(Default)]
+pub struct ReadinessCache {
+    last: Mutex<Option<(Instant, Readiness)>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    pub status: ReadinessStatus,
+    pub checks: Vec<HealthCheck>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadinessStatus {
+    Ready,
+    Unavailable,
+    Draining,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub dependency: HealthCheckDependency,
+    pub status: HealthCheckStatus,
+    pub critical: bool,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthCheckStatus {
+    Ok,
+    Failed,
+    Timeout,
+}
+
+impl Server {
+    pub async fn readiness(&self) -> Readiness {
+        if self.is_draining() {
+            return Readiness {
+                status: ReadinessStatus::Draining,
+                checks: vec![],
+            };
+        }
+
+        let ttl = self.core.network.http.readiness_cache_ttl;
+        if ttl.is_zero() {
+            return self.check_readiness().await;
+        }
+
+        let mut last = self.inner.data.readiness.last.lock().await;
+        if let Some((checked_at, readiness)) = last.as_ref()
+            && checked_at.elapsed() < ttl
+        {
+            return readiness.clone();
+        }
+        let readiness = self.check_readiness().await;
+        *last = Some((Instant::now(), readiness.clone()));
+        readiness
+    }
+
+    // Checks all stores concurrently using their pooled connections, each
+    // check is bounded by its own timeout and by the overall budget
+    async fn check_readiness(&self) -> Readiness {
+        let http = &self.core.network.http;
+        let timeout = http.readiness_check_timeout.min(http.readiness_timeout);
+        let checks = join_all(
+            [
+                HealthCheckDependency::DataStore,
+                HealthCheckDependency::BlobStore,
+                HealthCheckDependency::InMemoryStore,
+                HealthCheckDependency::SearchStore,
+            ]
+            .into_iter()
+            .map(|dependency| async move {
+                let started = Instant::now();
+                let status =
+                    match tokio::time::timeout(timeout, self.health_check(dependency)).await {
+                        Ok(Ok(())) => HealthCheckStatus::Ok,
+                        Ok(Err(err)) => {
+                            trc::event!(
+                                Server(trc::ServerEvent::HealthCheckFailed),
+                                Details = dependency.as_str(),
+                                CausedBy = err,
+                                Elapsed = started.elapsed(),
+                            );
+                            HealthCheckStatus::Failed
+                        }
+                        Err(_) => {
+                            trc::event!(
+                                Server(trc::ServerEvent::HealthCheckFailed),
+                                Details = dependency.as_str(),
+                                Reason = "Timed out",
+                                Elapsed = started.elapsed(),
+                            );
+                            HealthCheckStatus::Timeout
+                        }
+                    };
+
+                HealthCheck {
+                    dependency,
+                    status,
+                    critical: !http.readiness_optional.contains(&dependency),
+                    latency_ms: started.elapsed().as_millis() as u64,
+                }
+            }),
+        )
+        .await;
+
+        Readiness {
+            status: if checks
+                .iter()
+                .all(|check| !check.critical || check.status == HealthCheckStatus::Ok)
+            {
+                ReadinessStatus::Ready
+            } else {
+                ReadinessStatus::Unavailable
+            },
+            checks,
+        }
+    }
+
+    async fn health_check(&self, dependency: HealthCheckDependency) -> trc::Result<()> {
+        #[cfg(feature = "test_mode")]
+        {
+            let delay =
+                HEALTH_CHECK_DELAY[dependency as usize].load(std::sync::atomic::Ordering::Relaxed);
+            if delay > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            }
+        }
+
+        match dependency {
+            HealthCheckDependency::DataStore => self.store().health_check().await,
+            HealthCheckDependency::BlobStore => self.blob_store().health_check().await,
+            HealthCheckDependency::InMemoryStore => self.in_memory_store().health_check().await,
+            HealthCheckDependency::SearchStore => self.search_store().health_check().await,
+        }
+    }
+}
@@ -49,6 +49,11 @@ impl ConcurrencyLimiter {
         }
     }
 
+    pub fn track(&self) -> InFlight {
+        self.0.concurrent.fetch_add(1, Ordering::Relaxed);
+        InFlight(self.0.clone())
+    }
+
     pub fn check_is_allowed(&self) -> bool {
         self.0.concurrent.load(Ordering::Relaxed) < self.0.max_concurrent
     }
@@ -60,6 +65,10 @@ impl ConcurrencyLimiter {
     pub fn max_concurrent(&self) -> u64 {
         self.0.max_concurrent
     }
+
+    pub fn num_concurrent(&self) -> u64 {
+        self.0.concurrent.load(Ordering::Relaxed)
+    }
 }
 
 impl InFlight {
@@ -7,6 +7,7 @@
 use super::{
     ServerInstance, SessionData, SessionManager, SessionStream, TcpAcceptor,
     limiter::{ConcurrencyLimiter, LimiterResult},
+    tls::PolicyRejection,
 };
 use crate::{
     BuildServer, Inner, Server,
@@ -46,6 +47,7 @@ impl Listener {
         let is_tls = matches!(instance.acceptor, TcpAcceptor::Tls { implicit, .. } if implicit);
         let is_https = is_tls && self.protocol == ServerProtocol::Http;
         let has_proxies = !instance.proxy_networks.is_empty();
+        let is_smtp = matches!(self.protocol, ServerProtocol::Smtp | ServerProtocol::Lmtp);
 
         // Spawn listeners
         for listener in self.listeners {
@@ -86,6 +88,7 @@ impl Listener {
 
             // Spawn listener
             let mut shutdown_rx = instance.shutdown_rx.clone();
+            let mut drain_rx = inner.data.drain.subscribe();
             let manager = manager.clone();
             let instance = instance.clone();
             let inner = inner.clone();
@@ -197,6 +200,23 @@ impl Listener {
                                 }
                             }
                         },
+                        _ = drain_rx.wait_for(|is_draining| *is_draining), if is_smtp => {
+                            // Stop accepting new SMTP sessions, existing sessions are
+                            // given a grace period to complete their transactions
+                            trc::event!(
+                                Network(trc::NetworkEvent::ListenStop),
+                                ListenerId = instance.id.clone(),
+                                LocalIp = local_addr.ip(),
+                                Tls = is_tls,
+                                LocalPort = local_addr.port(),
+                                Reason = "Server draining",
+                            );
+
+                            drop(listener);
+                            let _ = shutdown_rx.changed().await;
+                            manager.shutdown().await;
+                            break;
+                        },
                         _ = shutdown_rx.changed() => {
 
                             trc::event!(
@@ -401,12 +421,22 @@ impl ServerInstance {
                     Ok(stream)
                 }
                 Err(err) => {
-                    trc::event!(
-                        Tls(trc::TlsEvent::HandshakeError),
-                        ListenerId = self.id.clone(),
-                        SpanId = session_id,
-                        Reason = err.to_string(),
-                    );
+                    if let Some(rejection) = PolicyRejection::from_error(&err) {
+                        trc::event!(
+                            Tls(trc::TlsEvent::PolicyRejected),
+                            ListenerId = self.id.clone(),
+                            SpanId = session_id,
+                            Version = rejection.offered_version(),
+                            Reason = rejection.reason(),
+                        );
+                    } else {
+                        trc::event!(
+                            Tls(trc::TlsEvent::HandshakeError),
+                            ListenerId = self.id.clone(),
+                            SpanId = session_id,
+                            Reason = err.to_string(),
+                        );
+                    }
                     Err(())
                 }
             },
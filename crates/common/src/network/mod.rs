@@ -4,7 +4,10 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use self::limiter::{ConcurrencyLimiter, InFlight};
+use self::{
+    limiter::{ConcurrencyLimiter, InFlight},
+    tls::PolicyRejection,
+};
 use crate::{
     Server,
     config::server::ServerProtocol,
@@ -12,7 +15,8 @@ use crate::{
 };
 use compact_str::ToCompactString;
 use registry::{schema::enums::ExpressionVariable, types::ipmask::IpAddrOrMask};
-use rustls::ServerConfig;
+use rustls::{ServerConfig, server::danger::ClientCertVerifier};
+use rustls_pki_types::CertificateDer;
 use std::fmt::Debug;
 use std::{borrow::Cow, net::IpAddr, sync::Arc, time::Instant};
 use tokio::{
@@ -26,14 +30,22 @@ use utils::snowflake::SnowflakeIdGenerator;
 pub mod acme;
 pub mod asn;
 pub mod autoconfig;
+pub mod batv;
+pub mod clamd;
+pub mod compress;
+pub mod dane;
 pub mod dkim;
 pub mod dns;
+pub mod drain;
+pub mod health;
 pub mod limiter;
 pub mod listen;
 pub mod mta;
 pub mod security;
+pub mod sessions;
 pub mod stream;
 pub mod tls;
+pub mod unsubscribe;
 pub mod webpush;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
@@ -46,6 +58,18 @@ pub enum RcptResolution {
     UnknownDomain,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RcptExpansion {
+    pub recipients: Vec<ExpandedRcpt>,
+    pub is_truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedRcpt {
+    pub address: String,
+    pub expanded_from: Box<str>,
+}
+
 pub struct ServerInstance {
     pub id: String,
     pub protocol: ServerProtocol,
@@ -62,6 +86,7 @@ pub enum TcpAcceptor {
         config: Arc<ServerConfig>,
         acceptor: TlsAcceptor,
         implicit: bool,
+        client_cert_verifier: Option<Arc<dyn ClientCertVerifier>>,
     },
     #[default]
     Plain,
@@ -92,6 +117,7 @@ pub struct SessionData<T: SessionStream> {
 pub trait SessionStream: AsyncRead + AsyncWrite + Unpin + 'static + Sync + Send {
     fn is_tls(&self) -> bool;
     fn tls_version_and_cipher(&self) -> (Cow<'static, str>, Cow<'static, str>);
+    fn peer_certificates(&self) -> Option<&[CertificateDer<'static>]>;
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -99,6 +125,7 @@ pub enum SessionResult {
     Continue,
     Close,
     UpgradeTls,
+    UpgradeCompress,
 }
 
 pub trait SessionManager: Sync + Send + 'static + Clone {
@@ -158,14 +185,26 @@ pub trait SessionManager: Sync + Send + 'static + Clone {
                                 .await;
                         }
                         Err(err) => {
-                            trc::event!(
-                                Tls(trc::TlsEvent::HandshakeError),
-                                ListenerId = session.instance.id.clone(),
-                                LocalPort = local_port,
-                                RemoteIp = session.remote_ip,
-                                RemotePort = session.remote_port,
-                                Reason = err.to_string(),
-                            );
+                            if let Some(rejection) = PolicyRejection::from_error(&err) {
+                                trc::event!(
+                                    Tls(trc::TlsEvent::PolicyRejected),
+                                    ListenerId = session.instance.id.clone(),
+                                    LocalPort = local_port,
+                                    RemoteIp = session.remote_ip,
+                                    RemotePort = session.remote_port,
+                                    Version = rejection.offered_version(),
+                                    Reason = rejection.reason(),
+                                );
+                            } else {
+                                trc::event!(
+                                    Tls(trc::TlsEvent::HandshakeError),
+                                    ListenerId = session.instance.id.clone(),
+                                    LocalPort = local_port,
+                                    RemoteIp = session.remote_ip,
+                                    RemotePort = session.remote_port,
+                                    Reason = err.to_string(),
+                                );
+                            }
 
                             return;
                         }
@@ -6,22 +6,27 @@
 
 use crate::{
     Server,
-    auth::{DOMAIN_FLAG_RELAY, DOMAIN_FLAG_SUB_ADDRESSING, EmailAddressRef, EmailCache},
+    auth::{
+        DOMAIN_FLAG_RELAY, DOMAIN_FLAG_SUB_ADDRESSING, EmailAddressRef, EmailCache,
+        chain::ChainRecipient,
+    },
     config::{
         mailstore::spamfilter::SpamClassifier,
         smtp::{
             auth::DkimSigners,
             queue::{
-                ConnectionStrategy, DEFAULT_QUEUE_NAME, MxConfig, QueueExpiry, QueueName,
-                QueueStrategy, RequireOptional, RoutingStrategy, TlsStrategy, VirtualQueue,
+                ConnectionStrategy, DEFAULT_QUEUE_NAME, IpSelection, MxConfig, QueueExpiry,
+                QueueName, QueueStrategy, RequireOptional, RoutingStrategy, TlsStrategy,
+                VirtualQueue,
             },
         },
     },
     expr::{Variable, functions::ResolveVariable},
     manager::SPAM_CLASSIFIER_KEY,
-    network::RcptResolution,
+    network::{ExpandedRcpt, RcptExpansion, RcptResolution},
 };
-use directory::Recipient;
+use ahash::AHashSet;
+use directory::{DirectorySource, Recipient};
 use mail_auth::IpLookupStrategy;
 use registry::schema::{enums::ExpressionVariable, structs::MaskedEmail};
 use sieve::Sieve;
@@ -31,10 +36,10 @@ use std::{
     time::Duration,
 };
 use store::{
-    Deserialize, IterateParams, ValueKey,
-    write::{AlignedBytes, Archive, QueueClass, ValueClass, now},
+    Deserialize,
+    write::{AlignedBytes, Archive, now},
 };
-use trc::{AddContext, SpamEvent};
+use trc::{AddContext, SmtpEvent, SpamEvent};
 use types::id::Id;
 
 impl Server {
@@ -103,18 +108,39 @@ impl Server {
         }
         // SPDX-SnippetEnd
 
-        // Obtain external directory, if configured
+        // Obtain directory chain or external directory, if configured
+        let chain = self.get_directory_chain_for_cached_domain(&domain);
         let directory = self
             .get_directory_for_cached_domain(&domain)
-            .filter(|directory| directory.can_lookup_recipients());
-        if let Some(directory) = directory {
+            .filter(|directory| chain.is_none() && directory.can_lookup_recipients());
+        if chain.is_some() || directory.is_some() {
             let is_subaddressed = local_part.as_ref() != local_part_orig;
             let address = if is_subaddressed {
                 Cow::Owned(format!("{local_part}@{domain_part}"))
             } else {
                 Cow::Borrowed(rcpt)
             };
-            match directory.recipient(address.as_ref()).await? {
+            let recipient = match (chain, directory) {
+                (Some(chain), _) => {
+                    match self
+                        .chain_recipient(chain, local_part.as_ref(), domain.id, address.as_ref())
+                        .await?
+                    {
+                        ChainRecipient::External(recipient) => recipient,
+                        ChainRecipient::Internal(_) => {
+                            return Ok(if is_subaddressed {
+                                RcptResolution::Rewrite(address.into_owned())
+                            } else {
+                                RcptResolution::Accept
+                            });
+                        }
+                        ChainRecipient::NotFound => Recipient::Invalid,
+                    }
+                }
+                (None, Some(directory)) => directory.recipient(address.as_ref()).await?,
+                (None, None) => Recipient::Invalid,
+            };
+            match recipient {
                 Recipient::Account(account) => {
                     Box::pin(self.synchronize_account(account)).await?;
                     return Ok(if is_subaddressed {
@@ -136,12 +162,15 @@ impl Server {
         }
 
         // Try resolving address from registry
+        let has_internal_accounts = chain.map_or(directory.is_none(), |chain| {
+            chain.contains(&DirectorySource::Internal)
+        });
         if let Some(address_type) = self
             .rcpt_id_from_parts(local_part.as_ref(), domain.id)
             .await?
         {
             match address_type {
-                EmailCache::Account(id) if directory.is_none() => {
+                EmailCache::Account(id) if has_internal_accounts => {
                     if self.try_account(id).await?.is_some() {
                         return if local_part.as_ref() == local_part_orig {
                             Ok(RcptResolution::Accept)
@@ -184,6 +213,96 @@ impl Server {
         }
     }
 
+    pub async fn rcpt_expand(
+        &self,
+        rcpt: &str,
+        members: &[Box<str>],
+        session_id: u64,
+    ) -> trc::Result<RcptExpansion> {
+        let mut expansion = RcptExpansion::default();
+        let mut chain = vec![rcpt.to_lowercase()];
+        let mut seen = AHashSet::new();
+
+        for member in members {
+            self.rcpt_expand_member(member, &mut chain, &mut seen, &mut expansion, session_id)
+                .await?;
+            if expansion.is_truncated {
+                break;
+            }
+        }
+
+        Ok(expansion)
+    }
+
+    async fn rcpt_expand_member(
+        &self,
+        address: &str,
+        chain: &mut Vec<String>,
+        seen: &mut AHashSet<String>,
+        expansion: &mut RcptExpansion,
+        session_id: u64,
+    ) -> trc::Result<()> {
+        let config = &self.core.smtp.session.rcpt;
+        let address = address.to_lowercase();
+
+        // Break alias and list cycles
+        if chain.contains(&address) {
+            trc::event!(
+                Smtp(SmtpEvent::ExpansionLoop),
+                SpanId = session_id,
+                To = address,
+                Details = chain.join(", "),
+            );
+            return Ok(());
+        }
+
+        let members = match self.rcpt_resolve(&address, session_id).await? {
+            RcptResolution::Expand(members) => members,
+            RcptResolution::Rewrite(rewritten) => Arc::from([rewritten.into_boxed_str()]),
+            _ => {
+                if seen.insert(address.clone()) {
+                    if expansion.recipients.len() < config.max_expansion_recipients {
+                        expansion.recipients.push(ExpandedRcpt {
+                            address,
+                            expanded_from: chain.join(", ").into_boxed_str(),
+                        });
+                    } else {
+                        expansion.is_truncated = true;
+                        trc::event!(
+                            Smtp(SmtpEvent::ExpansionLimitExceeded),
+                            SpanId = session_id,
+                            To = chain[0].clone(),
+                            Limit = config.max_expansion_recipients,
+                        );
+                    }
+                }
+                return Ok(());
+            }
+        };
+
+        if chain.len() >= config.max_expansion_depth {
+            trc::event!(
+                Smtp(SmtpEvent::ExpansionDepthExceeded),
+                SpanId = session_id,
+                To = address,
+                Details = chain.join(", "),
+                Limit = config.max_expansion_depth,
+            );
+            return Ok(());
+        }
+
+        chain.push(address);
+        for member in members.iter() {
+            Box::pin(self.rcpt_expand_member(member, chain, seen, expansion, session_id)).await?;
+            if expansion.is_truncated {
+                break;
+            }
+        }
+        chain.pop();
+
+        Ok(())
+    }
+
     pub async fn get_dkim_signers(
         &self,
         domain: &str,
@@ -254,7 +373,10 @@ impl Server {
     }
 
     pub fn get_virtual_queue_or_default(&self, name: &QueueName) -> &VirtualQueue {
-        static DEFAULT_QUEUE: VirtualQueue = VirtualQueue { threads: 25 };
+        static DEFAULT_QUEUE: VirtualQueue = VirtualQueue {
+            threads: 25,
+            rate_limit: None,
+        };
         self.core
             .smtp
             .queue
@@ -290,6 +412,7 @@ impl Server {
             ],
             expiry: QueueExpiry::Ttl(432000), // 5 days
             virtual_queue: QueueName::default(),
+            window: None,
         });
         self.core
             .smtp
@@ -342,6 +465,7 @@ impl Server {
         static DEFAULT_CONNECTION: ConnectionStrategy = ConnectionStrategy {
             source_ipv4: Vec::new(),
             source_ipv6: Vec::new(),
+            source_ip_selection: IpSelection::Weighted,
             ehlo_hostname: None,
             timeout_connect: Duration::from_secs(5 * 60),
             timeout_greeting: Duration::from_secs(5 * 60),
@@ -409,22 +533,12 @@ impl Server {
 
     pub async fn total_queued_messages(&self) -> trc::Result<u64> {
         let mut total = 0;
-        self.store()
-            .iterate(
-                IterateParams::new(
-                    ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
-                    ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
-                )
-                .no_values(),
-                |_, _| {
-                    total += 1;
-
-                    Ok(true)
-                },
-            )
-            .await
-            .caused_by(trc::location!())
-            .map(|_| total)
+        let mut stream = self.store().queue_ids();
+        while stream.next().await.caused_by(trc::location!())?.is_some() {
+            total += 1;
+        }
+
+        Ok(total)
     }
 }
 
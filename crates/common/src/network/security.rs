@@ -6,8 +6,9 @@
 
 use crate::{
     KV_RATE_LIMIT_AUTH, KV_RATE_LIMIT_LOITER, KV_RATE_LIMIT_RCPT, KV_RATE_LIMIT_SCAN, Server,
+    auth::{Permissions, permissions::BuildPermissions},
     ipc::{BroadcastEvent, RegistryChange},
-    network::ip_to_bytes,
+    network::{ip_to_bytes, sessions::SessionLimits},
 };
 use ahash::AHashSet;
 use registry::{
@@ -58,6 +59,11 @@ pub struct Security {
     pub password_min_length: u32,
     pub password_min_strength: Score,
     pub password_default_expiration: Option<u64>,
+
+    pub step_up_permissions: Permissions,
+    pub step_up_max_age: u64,
+
+    pub session_limits: SessionLimits,
 }
 
 #[derive(Default)]
@@ -195,6 +201,12 @@ impl Security {
                 PasswordStrength::Four => Score::Four,
             },
             password_default_expiration: auth.password_default_expiry.map(|v| v.as_secs()),
+            step_up_permissions: Permissions::from_permission(auth.step_up_permissions.as_slice()),
+            step_up_max_age: auth.step_up_max_age.as_secs(),
+            session_limits: SessionLimits {
+                max_sessions: auth.max_sessions,
+                max_protocol_sessions: auth.max_sessions_per_protocol,
+            },
         }
     }
 
@@ -0,0 +1,419 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{KV_SESSION_COUNT, KV_SESSION_LIST, Server, auth::AccessToken, ipc::BroadcastEvent};
+use ahash::AHashMap;
+use parking_lot::Mutex;
+use registry::{
+    schema::enums::{ClusterNodeStatus, Permission, ServiceProtocol},
+    types::EnumImpl,
+};
+use std::{fmt::Write, net::IpAddr, time::Duration};
+use store::{dispatch::lookup::KeyValue, write::now};
+use tokio::sync::watch;
+use utils::map::vec_map::VecMap;
+
+// The TTL is set when a shared counter is created and is not refreshed
+// afterwards, so counters left behind by nodes that went away with open
+// sessions are eventually cleaned up even for accounts that keep logging in.
+const SHARED_COUNTER_TTL: u64 = 86400;
+
+// Session lists published by other nodes are only read by the request
+// that asked for them.
+const SESSION_LIST_TTL: u64 = 60;
+const SESSION_LIST_TIMEOUT: Duration = Duration::from_secs(2);
+const SESSION_LIST_POLL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionLimits {
+    pub max_sessions: Option<u64>,
+    pub max_protocol_sessions: VecMap<ServiceProtocol, u64>,
+}
+
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: Mutex<AHashMap<u32, Vec<PrincipalSession>>>,
+}
+
+struct PrincipalSession {
+    info: SessionInfo,
+    terminate: watch::Sender<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub node_id: u64,
+    pub session_id: u64,
+    pub protocol: ServiceProtocol,
+    pub remote_ip: IpAddr,
+    pub created_at: u64,
+}
+
+// Keeps an authenticated session registered until it is dropped.
+pub struct SessionLease {
+    server: Server,
+    account_id: u32,
+    session_id: u64,
+    protocol: ServiceProtocol,
+    shared_total: bool,
+    shared_protocol: bool,
+    terminate: watch::Receiver<bool>,
+}
+
+#[derive(Clone, Default)]
+pub struct SessionSignal(Option<watch::Receiver<bool>>);
+
+impl SessionLimits {
+    pub fn max_sessions(&self, protocol: ServiceProtocol) -> (Option<u64>, Option<u64>) {
+        (
+            self.max_sessions,
+            self.max_protocol_sessions.get(&protocol).copied(),
+        )
+    }
+}
+
+impl SessionLease {
+    pub fn signal(&self) -> SessionSignal {
+        SessionSignal(Some(self.terminate.clone()))
+    }
+}
+
+impl SessionSignal {
+    pub async fn terminated(&mut self) {
+        // Sessions without a lease or whose lease was dropped never terminate
+        let is_terminated = match &mut self.0 {
+            Some(terminate) => terminate.wait_for(|v| *v).await.is_ok(),
+            None => false,
+        };
+
+        if !is_terminated {
+            std::future::pending::<()>().await
+        }
+    }
+}
+
+impl Drop for SessionLease {
+    fn drop(&mut self) {
+        {
+            let mut sessions = self.server.inner.data.sessions.sessions.lock();
+            if let Some(account_sessions) = sessions.get_mut(&self.account_id) {
+                account_sessions.retain(|session| {
+                    session.info.session_id != self.session_id
+                        || session.info.protocol != self.protocol
+                });
+                if account_sessions.is_empty() {
+                    sessions.remove(&self.account_id);
+                }
+            }
+        }
+
+        if self.shared_total || self.shared_protocol {
+            let server = self.server.clone();
+            let keys = shared_keys(
+                self.account_id,
+                self.protocol,
+                self.shared_total,
+                self.shared_protocol,
+            );
+            let Ok(handle) = tokio::runtime::Handle::try_current() else {
+                // Leases dropped outside a runtime (e.g. during shutdown) leave
+                // their counters to expire
+                return;
+            };
+            handle.spawn(async move {
+                for key in keys {
+                    if let Err(err) = server
+                        .in_memory_store()
+                        .counter_incr(KeyValue::with_prefix(KV_SESSION_COUNT, key, -1), false)
+                        .await
+                    {
+                        trc::error!(
+                            err.details("Failed to release shared session counter.")
+                                .caused_by(trc::location!())
+                        );
+                    }
+                }
+            });
+        }
+    }
+}
+
+impl Server {
+    pub async fn acquire_session(
+        &self,
+        access_token: &AccessToken,
+        protocol: ServiceProtocol,
+        session_id: u64,
+        remote_ip: IpAddr,
+    ) -> trc::Result<SessionLease> {
+        let account_id = access_token.account_id();
+        let (max_total, max_protocol) =
+            if !access_token.has_permission(Permission::UnlimitedRequests) {
+                let global = &self.core.network.security.session_limits;
+                let account = access_token.session_limits();
+                let (global_total, global_protocol) = global.max_sessions(protocol);
+                let (account_total, account_protocol) = account.max_sessions(protocol);
+                (
+                    account_total.or(global_total),
+                    account_protocol.or(global_protocol),
+                )
+            } else {
+                (None, None)
+            };
+
+        // Local fast path
+        let (terminate_tx, terminate) = watch::channel(false);
+        {
+            let mut sessions = self.inner.data.sessions.sessions.lock();
+            let account_sessions = sessions.entry(account_id).or_default();
+            if max_total.is_some_and(|max| account_sessions.len() as u64 >= max)
+                || max_protocol.is_some_and(|max| {
+                    account_sessions
+                        .iter()
+                        .filter(|session| session.info.protocol == protocol)
+                        .count() as u64
+                        >= max
+                })
+            {
+                if account_sessions.is_empty() {
+                    sessions.remove(&account_id);
+                }
+                return Err(too_many_sessions(account_id, max_total, max_protocol));
+            }
+            account_sessions.push(PrincipalSession {
+                info: SessionInfo {
+                    node_id: self.core.network.node_id,
+                    session_id,
+                    protocol,
+                    remote_ip,
+                    created_at: now(),
+                },
+                terminate: terminate_tx,
+            });
+        }
+
+        let mut lease = SessionLease {
+            server: self.clone(),
+            account_id,
+            session_id,
+            protocol,
+            shared_total: false,
+            shared_protocol: false,
+            terminate,
+        };
+
+        // Enforce the limits across the cluster, dropping the lease on
+        // rejection releases any counters incremented so far
+        if self.inner.ipc.broadcast_tx.is_some() {
+            for (key, max, is_total) in [
+                (shared_key(account_id, None), max_total, true),
+                (shared_key(account_id, Some(protocol)), max_protocol, false),
+            ] {
+                let Some(max) = max else {
+                    continue;
+                };
+                let count = self
+                    .in_memory_store()
+                    .counter_incr(KeyValue::with_prefix(KV_SESSION_COUNT, &key, 1), true)
+                    .await?;
+                if is_total {
+                    lease.shared_total = true;
+                } else {
+                    lease.shared_protocol = true;
+                }
+                if count == 1 {
+                    self.in_memory_store()
+                        .counter_incr(
+                            KeyValue::with_prefix(KV_SESSION_COUNT, key, 0)
+                                .expires(SHARED_COUNTER_TTL),
+                            false,
+                        )
+                        .await?;
+                }
+                if count > max as i64 {
+                    return Err(too_many_sessions(account_id, max_total, max_protocol));
+                }
+            }
+        }
+
+        Ok(lease)
+    }
+
+    pub fn terminate_sessions(&self, account_id: u32, protocol: Option<ServiceProtocol>) -> usize {
+        let sessions = self.inner.data.sessions.sessions.lock();
+        sessions
+            .get(&account_id)
+            .map(|account_sessions| {
+                account_sessions
+                    .iter()
+                    .filter(|session| protocol.is_none_or(|p| p == session.info.protocol))
+                    .filter(|session| session.terminate.send(true).is_ok())
+                    .count()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn session_list(&self) -> Vec<(u32, Vec<SessionInfo>)> {
+        let sessions = self.inner.data.sessions.sessions.lock();
+        let mut list = sessions
+            .iter()
+            .map(|(account_id, account_sessions)| {
+                (
+                    *account_id,
+                    account_sessions
+                        .iter()
+                        .map(|session| session.info.clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        list.sort_unstable_by_key(|(account_id, _)| *account_id);
+        list
+    }
+
+    // Lists the sessions open on all active nodes, nodes that do not
+    // publish their sessions in time are left out
+    pub async fn cluster_session_list(&self) -> trc::Result<Vec<(u32, Vec<SessionInfo>)>> {
+        let mut list = self.session_list();
+        if self.inner.ipc.broadcast_tx.is_none() {
+            return Ok(list);
+        }
+
+        let this_node_id = self.core.network.node_id;
+        let mut pending = self
+            .registry()
+            .cluster_node_list()
+            .await?
+            .into_iter()
+            .filter(|node| node.status == ClusterNodeStatus::Active && node.node_id != this_node_id)
+            .map(|node| node.node_id)
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return Ok(list);
+        }
+
+        let request_id = self.inner.data.jmap_id_gen.generate();
+        self.cluster_broadcast(BroadcastEvent::SessionList { request_id })
+            .await;
+
+        let started = tokio::time::Instant::now();
+        loop {
+            let mut waiting = Vec::with_capacity(pending.len());
+            for node_id in pending {
+                if let Some(sessions) = self
+                    .in_memory_store()
+                    .key_get::<String>(KeyValue::<()>::build_key(
+                        KV_SESSION_LIST,
+                        session_list_key(request_id, node_id),
+                    ))
+                    .await?
+                {
+                    for (account_id, session) in sessions.lines().filter_map(parse_session) {
+                        match list.binary_search_by_key(&account_id, |(id, _)| *id) {
+                            Ok(idx) => list[idx].1.push(session),
+                            Err(idx) => list.insert(idx, (account_id, vec![session])),
+                        }
+                    }
+                } else {
+                    waiting.push(node_id);
+                }
+            }
+
+            if waiting.is_empty() || started.elapsed() >= SESSION_LIST_TIMEOUT {
+                return Ok(list);
+            }
+            pending = waiting;
+            tokio::time::sleep(SESSION_LIST_POLL).await;
+        }
+    }
+
+    pub async fn publish_session_list(&self, request_id: u64) -> trc::Result<()> {
+        let mut sessions = String::new();
+        for (account_id, account_sessions) in self.session_list() {
+            for session in account_sessions {
+                let _ = writeln!(
+                    &mut sessions,
+                    "{account_id} {} {} {} {} {}",
+                    session.node_id,
+                    session.session_id,
+                    session.protocol.to_id(),
+                    session.created_at,
+                    session.remote_ip
+                );
+            }
+        }
+
+        self.in_memory_store()
+            .key_set(
+                KeyValue::with_prefix(
+                    KV_SESSION_LIST,
+                    session_list_key(request_id, self.core.network.node_id),
+                    sessions.into_bytes(),
+                )
+                .expires(SESSION_LIST_TTL),
+            )
+            .await
+    }
+}
+
+fn session_list_key(request_id: u64, node_id: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(std::mem::size_of::<u64>() * 2);
+    key.extend_from_slice(&request_id.to_be_bytes());
+    key.extend_from_slice(&node_id.to_be_bytes());
+    key
+}
+
+fn parse_session(line: &str) -> Option<(u32, SessionInfo)> {
+    let mut parts = line.split(' ');
+    let account_id = parts.next()?.parse().ok()?;
+    Some((
+        account_id,
+        SessionInfo {
+            node_id: parts.next()?.parse().ok()?,
+            session_id: parts.next()?.parse().ok()?,
+            protocol: ServiceProtocol::from_id(parts.next()?.parse().ok()?)?,
+            created_at: parts.next()?.parse().ok()?,
+            remote_ip: parts.next()?.parse().ok()?,
+        },
+    ))
+}
+
+fn too_many_sessions(
+    account_id: u32,
+    max_total: Option<u64>,
+    max_protocol: Option<u64>,
+) -> trc::Error {
+    trc::LimitEvent::ConcurrentConnection
+        .into_err()
+        .details("Too many connections")
+        .account_id(account_id)
+        .ctx_opt(trc::Key::Limit, max_protocol.or(max_total))
+}
+
+fn shared_key(account_id: u32, protocol: Option<ServiceProtocol>) -> Vec<u8> {
+    let mut key = Vec::with_capacity(std::mem::size_of::<u32>() + 1);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    if let Some(protocol) = protocol {
+        key.push(protocol.to_id() as u8);
+    }
+    key
+}
+
+fn shared_keys(
+    account_id: u32,
+    protocol: ServiceProtocol,
+    total: bool,
+    per_protocol: bool,
+) -> Vec<Vec<u8>> {
+    let mut keys = Vec::with_capacity(2);
+    if total {
+        keys.push(shared_key(account_id, None));
+    }
+    if per_protocol {
+        keys.push(shared_key(account_id, Some(protocol)));
+    }
+    keys
+}
@@ -10,11 +10,19 @@ use super::{
 };
 use crate::{Inner, Server};
 use rustls::{
+    DigitallySignedStruct, DistinguishedName, PeerIncompatible, SignatureScheme,
     SupportedProtocolVersion,
-    server::{ClientHello, ResolvesServerCert},
+    client::danger::HandshakeSignatureValid,
+    crypto::{WebPkiSupportedAlgorithms, verify_tls12_signature, verify_tls13_signature},
+    server::{
+        ClientHello, ResolvesServerCert,
+        danger::{ClientCertVerified, ClientCertVerifier},
+    },
     sign::CertifiedKey,
     version::{TLS12, TLS13},
 };
+use rustls_pki_types::{CertificateDer, UnixTime};
+use sha2::{Digest, Sha256};
 use std::{
     cmp::Ordering,
     fmt::{self, Formatter},
@@ -22,6 +30,11 @@ use std::{
 };
 use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
 use tokio_rustls::{Accept, LazyConfigAcceptor};
+use utils::HexEncode;
+use x509_parser::{
+    parse_x509_certificate,
+    prelude::{GeneralName, ParsedExtension},
+};
 
 pub static TLS13_VERSION: &[&SupportedProtocolVersion] = &[&TLS13];
 pub static TLS12_VERSION: &[&SupportedProtocolVersion] = &[&TLS12];
@@ -102,6 +115,144 @@ impl CertificateResolver {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientCertificate {
+    pub subject: String,
+    pub emails: Vec<String>,
+    pub dns_names: Vec<String>,
+    pub fingerprint: String,
+}
+
+impl ClientCertificate {
+    pub fn parse(der: &[u8]) -> Option<Self> {
+        let (_, cert) = parse_x509_certificate(der).ok()?;
+        let mut emails = Vec::new();
+        let mut dns_names = Vec::new();
+        for ext in cert.extensions() {
+            if let ParsedExtension::SubjectAlternativeName(san) = ext.parsed_extension() {
+                for name in &san.general_names {
+                    match name {
+                        GeneralName::RFC822Name(email) => emails.push(email.to_lowercase()),
+                        GeneralName::DNSName(name) => dns_names.push(name.to_lowercase()),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        Some(ClientCertificate {
+            subject: cert.subject().to_string(),
+            emails,
+            dns_names,
+            fingerprint: Sha256::digest(der).hex_encode(),
+        })
+    }
+}
+
+// Requests a certificate from the client without validating its chain, the
+// handshake signature is still verified.
+#[derive(Debug)]
+pub struct OptionalClientCertVerifier {
+    algorithms: WebPkiSupportedAlgorithms,
+    mandatory: bool,
+}
+
+impl OptionalClientCertVerifier {
+    pub fn new(algorithms: WebPkiSupportedAlgorithms, mandatory: bool) -> Self {
+        Self {
+            algorithms,
+            mandatory,
+        }
+    }
+}
+
+impl ClientCertVerifier for OptionalClientCertVerifier {
+    fn offer_client_auth(&self) -> bool {
+        true
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        self.mandatory
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        Ok(ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+pub enum PolicyRejection {
+    ProtocolVersion(&'static str),
+    CipherSuites,
+}
+
+impl PolicyRejection {
+    // Classifies handshake errors caused by the listener's protocol version
+    // or cipher suite policy, along with the highest version the client offered
+    pub fn from_error(err: &std::io::Error) -> Option<Self> {
+        match err.get_ref()?.downcast_ref::<rustls::Error>()? {
+            rustls::Error::PeerIncompatible(incompatible) => match incompatible {
+                PeerIncompatible::Tls12NotOffered => {
+                    Some(PolicyRejection::ProtocolVersion("TLSv1.1 or earlier"))
+                }
+                PeerIncompatible::SupportedVersionsExtensionRequired => {
+                    Some(PolicyRejection::ProtocolVersion("TLSv1.2"))
+                }
+                PeerIncompatible::Tls12NotOfferedOrEnabled => {
+                    Some(PolicyRejection::ProtocolVersion("TLSv1.2 or earlier"))
+                }
+                PeerIncompatible::NoCipherSuitesInCommon => Some(PolicyRejection::CipherSuites),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn offered_version(&self) -> &'static str {
+        match self {
+            PolicyRejection::ProtocolVersion(version) => version,
+            PolicyRejection::CipherSuites => "unknown",
+        }
+    }
+
+    pub fn reason(&self) -> &'static str {
+        match self {
+            PolicyRejection::ProtocolVersion(_) => "Protocol version not allowed",
+            PolicyRejection::CipherSuites => "No cipher suites in common",
+        }
+    }
+}
+
 impl TcpAcceptor {
     pub async fn accept<IO>(
         &self,
@@ -117,6 +268,7 @@ impl TcpAcceptor {
                 config,
                 acceptor,
                 implicit,
+                ..
             } if *implicit => match enable_acme {
                 None => TcpAcceptorResult::Tls(acceptor.accept(stream)),
                 Some(core) => {
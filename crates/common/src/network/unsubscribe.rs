@@ -0,0 +1,150 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::expr::if_block::{BootstrapExprExt, IfBlock};
+use base64::{Engine, engine::general_purpose};
+use registry::schema::{
+    prelude::ObjectType,
+    structs::{MtaUnsubscribe, Rate},
+};
+use ring::hmac;
+use store::registry::bootstrap::Bootstrap;
+
+pub const UNSUBSCRIBE_PATH: &str = "unsubscribe";
+pub const UNSUBSCRIBE_POST_BODY: &str = "List-Unsubscribe=One-Click";
+const MAX_CAMPAIGN_ID_LEN: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct Unsubscribe {
+    pub enable: IfBlock,
+    pub campaign_id: IfBlock,
+    pub key: hmac::Key,
+    pub validity: u64,
+    pub rate: Option<Rate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsubscribeToken {
+    pub sender: String,
+    pub recipient: String,
+    pub campaign_id: Option<String>,
+    pub expires: u64,
+}
+
+impl Unsubscribe {
+    pub async fn parse(bp: &mut Bootstrap) -> Option<Self> {
+        let st = bp.setting_infallible::<MtaUnsubscribe>().await;
+        let id = ObjectType::MtaUnsubscribe.singleton();
+        let is_enabled = !st.enable.match_.is_empty() || st.enable.else_.trim() != "false";
+        let key = match st.signature_key.secret().await {
+            Ok(Some(key)) => key,
+            Ok(None) => {
+                if is_enabled {
+                    bp.build_error(
+                        id,
+                        "A signature key is required to generate unsubscribe links",
+                    );
+                }
+                return None;
+            }
+            Err(err) => {
+                bp.build_error(id, format!("Unable to retrieve unsubscribe key: {err}"));
+                return None;
+            }
+        };
+
+        Some(Unsubscribe {
+            enable: bp.compile_expr(id, &st.ctx_enable()),
+            campaign_id: bp.compile_expr(id, &st.ctx_campaign_id()),
+            key: hmac::Key::new(hmac::HMAC_SHA256, key.as_bytes()),
+            validity: st.token_validity.into_inner().as_secs(),
+            rate: st.rate_limit,
+        })
+    }
+
+    // Tokens have the form "payload.signature", where the payload contains the
+    // expiration time, sender, recipient and campaign id separated by newlines
+    pub fn sign(&self, token: &UnsubscribeToken) -> String {
+        let payload = token.payload();
+        let signature = hmac::sign(&self.key, payload.as_bytes());
+        let mut out = String::with_capacity((payload.len() + signature.as_ref().len()) * 2);
+        general_purpose::URL_SAFE_NO_PAD.encode_string(payload.as_bytes(), &mut out);
+        out.push('.');
+        general_purpose::URL_SAFE_NO_PAD.encode_string(signature.as_ref(), &mut out);
+        out
+    }
+
+    pub fn verify(&self, token: &str, now: u64) -> Option<UnsubscribeToken> {
+        let (payload, signature) = token.split_once('.')?;
+        let payload = general_purpose::URL_SAFE_NO_PAD
+            .decode(payload.as_bytes())
+            .ok()?;
+        let signature = general_purpose::URL_SAFE_NO_PAD
+            .decode(signature.as_bytes())
+            .ok()?;
+        hmac::verify(&self.key, &payload, &signature).ok()?;
+
+        let payload = String::from_utf8(payload).ok()?;
+        let mut fields = payload.split('\n');
+        let token = UnsubscribeToken {
+            expires: fields.next()?.parse().ok()?,
+            sender: fields.next()?.to_string(),
+            recipient: fields.next()?.to_string(),
+            campaign_id: fields
+                .next()
+                .filter(|id| !id.is_empty())
+                .map(|id| id.to_string()),
+        };
+
+        (fields.next().is_none() && token.expires > now && !token.recipient.is_empty())
+            .then_some(token)
+    }
+
+    pub fn write_headers(&self, base_url: &str, token: &UnsubscribeToken, out: &mut Vec<u8>) {
+        out.extend_from_slice(b"List-Unsubscribe: <");
+        out.extend_from_slice(base_url.trim_end_matches('/').as_bytes());
+        out.push(b'/');
+        out.extend_from_slice(UNSUBSCRIBE_PATH.as_bytes());
+        out.push(b'/');
+        out.extend_from_slice(self.sign(token).as_bytes());
+        out.extend_from_slice(b">\r\nList-Unsubscribe-Post: ");
+        out.extend_from_slice(UNSUBSCRIBE_POST_BODY.as_bytes());
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+impl UnsubscribeToken {
+    pub fn new(
+        sender: impl Into<String>,
+        recipient: impl Into<String>,
+        campaign_id: Option<&str>,
+        expires: u64,
+    ) -> Self {
+        UnsubscribeToken {
+            sender: sender.into(),
+            recipient: recipient.into(),
+            campaign_id: campaign_id
+                .map(|id| {
+                    id.chars()
+                        .filter(|ch| !ch.is_control())
+                        .take(MAX_CAMPAIGN_ID_LEN)
+                        .collect::<String>()
+                })
+                .filter(|id| !id.trim().is_empty()),
+            expires,
+        }
+    }
+
+    fn payload(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}",
+            self.expires,
+            self.sender,
+            self.recipient,
+            self.campaign_id.as_deref().unwrap_or_default()
+        )
+    }
+}
@@ -27,6 +27,14 @@ pub enum ScriptModification {
         name: Arc<String>,
         value: Arc<String>,
     },
+    AddSpamTag {
+        name: String,
+        weight: f32,
+    },
+    SetQueueMeta {
+        key: String,
+        value: String,
+    },
 }
 
 pub fn into_sieve_value(value: Value) -> Variable {
@@ -36,6 +44,12 @@ pub fn into_sieve_value(value: Value) -> Variable {
         Value::Float(v) => Variable::Float(v),
         Value::Text(v) => Variable::String(v.into_owned().into()),
         Value::Blob(v) => Variable::String(v.into_owned().into_string().into()),
+        Value::Array(v) => Variable::Array(
+            v.into_iter()
+                .map(into_sieve_value)
+                .collect::<Vec<_>>()
+                .into(),
+        ),
         Value::Null => Variable::default(),
     }
 }
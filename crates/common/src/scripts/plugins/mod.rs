@@ -11,6 +11,8 @@ pub mod http;
 pub mod llm_prompt;
 pub mod lookup;
 pub mod query;
+pub mod queue;
+pub mod spam;
 pub mod text;
 
 use mail_parser::Message;
@@ -28,10 +30,11 @@ pub struct PluginContext<'x> {
     pub server: &'x Server,
     pub message: &'x Message<'x>,
     pub modifications: &'x mut Vec<ScriptModification>,
+    pub spam_score: Option<f32>,
     pub arguments: Vec<Variable>,
 }
 
-const PLUGINS_REGISTER: [RegisterPluginFnc; 13] = [
+const PLUGINS_REGISTER: [RegisterPluginFnc; 16] = [
     query::register,
     exec::register,
     lookup::register,
@@ -45,6 +48,9 @@ const PLUGINS_REGISTER: [RegisterPluginFnc; 13] = [
     text::register_tokenize,
     text::register_domain_part,
     llm_prompt::register,
+    spam::register_add_tag,
+    spam::register_get_score,
+    queue::register_meta_set,
 ];
 
 pub trait RegisterSievePlugins {
@@ -93,6 +99,9 @@ impl Core {
             10 => text::exec_tokenize(ctx),
             11 => text::exec_domain_part(ctx),
             12 => llm_prompt::exec(ctx).await,
+            13 => spam::exec_add_tag(ctx),
+            14 => spam::exec_get_score(ctx),
+            15 => queue::exec_meta_set(ctx),
             _ => unreachable!(),
         };
 
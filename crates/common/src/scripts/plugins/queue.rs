@@ -0,0 +1,31 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use sieve::{FunctionMap, runtime::Variable};
+
+use crate::{config::smtp::queue::is_valid_queue_meta, scripts::ScriptModification};
+
+use super::PluginContext;
+
+pub fn register_meta_set(plugin_id: u32, fnc_map: &mut FunctionMap) {
+    fnc_map.set_external_function("queue_meta_set", plugin_id, 2);
+}
+
+pub fn exec_meta_set(ctx: PluginContext<'_>) -> trc::Result<Variable> {
+    let key = ctx.arguments[0].to_string().trim().to_ascii_lowercase();
+    let value = ctx.arguments[1].to_string();
+
+    Ok(if is_valid_queue_meta(&key, &value) {
+        ctx.modifications.push(ScriptModification::SetQueueMeta {
+            key,
+            value: value.into_owned(),
+        });
+        true
+    } else {
+        false
+    }
+    .into())
+}
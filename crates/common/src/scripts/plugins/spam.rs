@@ -0,0 +1,55 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use sieve::{FunctionMap, runtime::Variable};
+
+use crate::scripts::ScriptModification;
+
+use super::PluginContext;
+
+pub fn register_add_tag(plugin_id: u32, fnc_map: &mut FunctionMap) {
+    fnc_map.set_external_function("add_spam_tag", plugin_id, 2);
+}
+
+pub fn register_get_score(plugin_id: u32, fnc_map: &mut FunctionMap) {
+    fnc_map.set_external_function("get_spam_score", plugin_id, 0);
+}
+
+pub fn exec_add_tag(ctx: PluginContext<'_>) -> trc::Result<Variable> {
+    let name = ctx.arguments[0].to_string();
+    let name = name.trim();
+    let weight = match &ctx.arguments[1] {
+        Variable::Integer(weight) => *weight as f32,
+        Variable::Float(weight) => *weight as f32,
+        weight => weight.to_string().trim().parse().unwrap_or(f32::NAN),
+    };
+
+    Ok(if !name.is_empty() && weight.is_finite() {
+        ctx.modifications.push(ScriptModification::AddSpamTag {
+            name: name.to_string(),
+            weight,
+        });
+        true
+    } else {
+        false
+    }
+    .into())
+}
+
+pub fn exec_get_score(ctx: PluginContext<'_>) -> trc::Result<Variable> {
+    // Include tags added earlier by the same script
+    let score = ctx.spam_score.unwrap_or_default()
+        + ctx
+            .modifications
+            .iter()
+            .filter_map(|modification| match modification {
+                ScriptModification::AddSpamTag { weight, .. } => Some(*weight),
+                _ => None,
+            })
+            .sum::<f32>();
+
+    Ok(Variable::Float(score as f64))
+}
@@ -10,7 +10,7 @@ use mail_parser::{
     decoders::{base64::base64_decode, quoted_printable::quoted_printable_decode},
 };
 use store::{
-    U32_LEN, U64_LEN,
+    U32_LEN, U64_LEN, ValueKey,
     dispatch::lookup::KeyValue,
     write::{BatchBuilder, BlobLink, BlobOp, now},
 };
@@ -18,6 +18,7 @@ use trc::AddContext;
 use types::{
     blob::{BlobClass, BlobId, BlobSection},
     blob_hash::BlobHash,
+    collection::Collection,
 };
 
 const COUNT_BYTES: u32 = 20;
@@ -198,6 +199,35 @@ impl Server {
         ))
     }
 
+    // Returns the documents in an account collection that reference a blob. Links
+    // are removed after their document is unindexed, so the documents of any
+    // remaining links are checked for existence.
+    pub async fn blob_references(
+        &self,
+        account_id: u32,
+        collection: Collection,
+        hash: &BlobHash,
+    ) -> trc::Result<Vec<u32>> {
+        let mut document_ids = Vec::new();
+        for document_id in self
+            .store()
+            .blob_document_links(hash, account_id, collection.into())
+            .await
+            .caused_by(trc::location!())?
+        {
+            if self
+                .store()
+                .key_exists(ValueKey::archive(account_id, collection, document_id))
+                .await
+                .caused_by(trc::location!())?
+            {
+                document_ids.push(document_id);
+            }
+        }
+
+        Ok(document_ids)
+    }
+
     pub async fn get_blob_section(
         &self,
         hash: &BlobHash,
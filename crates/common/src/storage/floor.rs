@@ -0,0 +1,297 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{KV_CHANGE_FLOOR, Server};
+use ahash::AHashMap;
+use parking_lot::Mutex;
+use std::{fmt::Write, time::Duration};
+use store::{dispatch::lookup::KeyValue, write::now};
+use trc::AddContext;
+use utils::snowflake::SnowflakeIdGenerator;
+
+// States referenced by client requests protect the change log for this long
+// after they were first seen, requesting the same state again does not
+// extend it.
+const SEEN_STATE_TTL: u64 = 7 * 86400;
+
+// Older client states are capped to this age so a client cannot hold back
+// pruning indefinitely.
+const SEEN_STATE_MAX_AGE: Duration = Duration::from_secs(30 * 86400);
+
+// Floors of connected push clients are refreshed on every notification and
+// expire on their own when the node holding the connection goes away.
+const LEASE_TTL: u64 = 86400;
+
+// Oldest change ids still referenced by the clients of each account. Floors
+// are kept in the in-memory store so they are shared by all nodes and survive
+// restarts, seen states are cached locally to avoid a lookup per request.
+// Change log entries past the floor are never pruned.
+#[derive(Default)]
+pub struct ChangeFloors {
+    seen: Mutex<AHashMap<u32, StoredFloor>>,
+}
+
+// Client id 0 holds the states seen in client requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StoredFloor {
+    client_id: u64,
+    change_id: u64,
+    expires: u64,
+}
+
+// Keeps the floor of a connected push client registered until it is dropped.
+pub struct ChangeFloorLease {
+    server: Server,
+    client_id: u64,
+    accounts: Vec<LeaseAccount>,
+}
+
+struct LeaseAccount {
+    account_id: u32,
+    last_change_id: u64,
+}
+
+impl ChangeFloorLease {
+    pub async fn notified(&mut self, account_id: u32, change_id: u64) {
+        // A state is acknowledged once the client is notified of a newer one,
+        // until then it may still be syncing from the previous state
+        let Some(account) = self
+            .accounts
+            .iter_mut()
+            .find(|account| account.account_id == account_id)
+            .filter(|account| change_id > account.last_change_id)
+        else {
+            return;
+        };
+        let floor = StoredFloor {
+            client_id: self.client_id,
+            change_id: account.last_change_id,
+            expires: now() + LEASE_TTL,
+        };
+        account.last_change_id = change_id;
+
+        if let Err(err) = self
+            .server
+            .update_change_floors(account_id, |floors| {
+                floors.retain(|stored| stored.client_id != floor.client_id);
+                floors.push(floor);
+            })
+            .await
+        {
+            trc::error!(
+                err.account_id(account_id)
+                    .details("Failed to update change floor")
+            );
+        }
+    }
+}
+
+impl Drop for ChangeFloorLease {
+    fn drop(&mut self) {
+        let server = self.server.clone();
+        let client_id = self.client_id;
+        let account_ids = self
+            .accounts
+            .iter()
+            .map(|account| account.account_id)
+            .collect::<Vec<_>>();
+
+        tokio::spawn(async move {
+            for account_id in account_ids {
+                if let Err(err) = server
+                    .update_change_floors(account_id, |floors| {
+                        floors.retain(|floor| floor.client_id != client_id);
+                    })
+                    .await
+                {
+                    trc::error!(
+                        err.account_id(account_id)
+                            .details("Failed to release change floor")
+                    );
+                }
+            }
+        });
+    }
+}
+
+impl Server {
+    pub async fn acquire_change_floor(&self, account_ids: Vec<u32>) -> ChangeFloorLease {
+        let client_id = self.inner.data.jmap_id_gen.generate();
+        let change_id = SnowflakeIdGenerator::from_duration(Duration::ZERO).unwrap_or_default();
+        let floor = StoredFloor {
+            client_id,
+            change_id,
+            expires: now() + LEASE_TTL,
+        };
+
+        for account_id in &account_ids {
+            if let Err(err) = self
+                .update_change_floors(*account_id, |floors| floors.push(floor))
+                .await
+            {
+                trc::error!(
+                    err.account_id(*account_id)
+                        .details("Failed to acquire change floor")
+                );
+            }
+        }
+
+        ChangeFloorLease {
+            server: self.clone(),
+            client_id,
+            accounts: account_ids
+                .into_iter()
+                .map(|account_id| LeaseAccount {
+                    account_id,
+                    last_change_id: change_id,
+                })
+                .collect(),
+        }
+    }
+
+    pub async fn change_floor_seen(&self, account_id: u32, change_id: u64) {
+        let change_id = SnowflakeIdGenerator::from_duration(SEEN_STATE_MAX_AGE)
+            .map_or(change_id, |min_change_id| change_id.max(min_change_id));
+        let current_time = now();
+        if self
+            .inner
+            .data
+            .change_floors
+            .seen
+            .lock()
+            .get(&account_id)
+            .is_some_and(|floor| floor.expires > current_time && floor.change_id <= change_id)
+        {
+            return;
+        }
+
+        // Newer states only replace the floor once the older one expires
+        let mut seen = None;
+        match self
+            .update_change_floors(account_id, |floors| {
+                let floor = match floors.iter_mut().find(|floor| floor.client_id == 0) {
+                    Some(floor) => {
+                        floor.change_id = floor.change_id.min(change_id);
+                        *floor
+                    }
+                    None => {
+                        let floor = StoredFloor {
+                            client_id: 0,
+                            change_id,
+                            expires: current_time + SEEN_STATE_TTL,
+                        };
+                        floors.push(floor);
+                        floor
+                    }
+                };
+                seen = Some(floor);
+            })
+            .await
+        {
+            Ok(_) => {
+                if let Some(floor) = seen {
+                    self.inner
+                        .data
+                        .change_floors
+                        .seen
+                        .lock()
+                        .insert(account_id, floor);
+                }
+            }
+            Err(err) => {
+                trc::error!(
+                    err.account_id(account_id)
+                        .details("Failed to update change floor")
+                );
+            }
+        }
+    }
+
+    pub async fn change_floor(&self, account_id: u32) -> trc::Result<Option<u64>> {
+        self.change_floors(account_id)
+            .await
+            .map(|floors| floors.into_iter().map(|floor| floor.change_id).min())
+    }
+
+    async fn change_floors(&self, account_id: u32) -> trc::Result<Vec<StoredFloor>> {
+        let current_time = now();
+        self.in_memory_store()
+            .key_get::<String>(KeyValue::<()>::build_key(
+                KV_CHANGE_FLOOR,
+                account_id.to_be_bytes(),
+            ))
+            .await
+            .caused_by(trc::location!())
+            .map(|floors| {
+                floors
+                    .unwrap_or_default()
+                    .lines()
+                    .filter_map(parse_floor)
+                    .filter(|floor| floor.expires > current_time)
+                    .collect()
+            })
+    }
+
+    async fn update_change_floors(
+        &self,
+        account_id: u32,
+        update: impl FnOnce(&mut Vec<StoredFloor>),
+    ) -> trc::Result<()> {
+        // Floors of all clients share one key, so updates are serialized to
+        // avoid dropping the floor of a concurrent client
+        self.in_memory_update(KV_CHANGE_FLOOR, &account_id.to_be_bytes(), async {
+            let mut floors = self.change_floors(account_id).await?;
+            update(&mut floors);
+            self.write_change_floors(account_id, &floors).await
+        })
+        .await
+    }
+
+    async fn write_change_floors(
+        &self,
+        account_id: u32,
+        floors: &[StoredFloor],
+    ) -> trc::Result<()> {
+        if let Some(expires) = floors.iter().map(|floor| floor.expires).max() {
+            let mut value = String::new();
+            for floor in floors {
+                let _ = writeln!(
+                    &mut value,
+                    "{} {} {}",
+                    floor.client_id, floor.change_id, floor.expires
+                );
+            }
+            self.in_memory_store()
+                .key_set(
+                    KeyValue::with_prefix(
+                        KV_CHANGE_FLOOR,
+                        account_id.to_be_bytes(),
+                        value.into_bytes(),
+                    )
+                    .expires(expires.saturating_sub(now()).max(1)),
+                )
+                .await
+                .caused_by(trc::location!())
+        } else {
+            self.in_memory_store()
+                .key_delete(KeyValue::<()>::build_key(
+                    KV_CHANGE_FLOOR,
+                    account_id.to_be_bytes(),
+                ))
+                .await
+                .caused_by(trc::location!())
+        }
+    }
+}
+
+fn parse_floor(line: &str) -> Option<StoredFloor> {
+    let mut parts = line.split(' ');
+    Some(StoredFloor {
+        client_id: parts.next()?.parse().ok()?,
+        change_id: parts.next()?.parse().ok()?,
+        expires: parts.next()?.parse().ok()?,
+    })
+}
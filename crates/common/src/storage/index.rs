@@ -50,6 +50,10 @@ pub enum IndexValue<'x> {
     Quota {
         used: u32,
     },
+    ContainerSize {
+        ids: Vec<u32>,
+        used: u32,
+    },
     LogContainer {
         sync_collection: SyncCollection,
     },
@@ -514,6 +518,13 @@ fn build_index(
                 batch.add(ValueClass::TenantQuota(tenant_id), value);
             }
         }
+        IndexValue::ContainerSize { ids, used } => {
+            let value = if set { used as i64 } else { -(used as i64) };
+
+            for container_id in ids {
+                batch.add(ValueClass::MailboxSize(container_id), value);
+            }
+        }
         IndexValue::LogItem {
             sync_collection,
             prefix,
@@ -735,6 +746,32 @@ fn merge_index(
                 batch.add(ValueClass::TenantQuota(tenant_id), value);
             }
         }
+        (
+            IndexValue::ContainerSize {
+                ids: old_ids,
+                used: old_used,
+            },
+            IndexValue::ContainerSize {
+                ids: new_ids,
+                used: new_used,
+            },
+        ) => {
+            for container_id in &old_ids {
+                if !new_ids.contains(container_id) {
+                    batch.add(ValueClass::MailboxSize(*container_id), -(old_used as i64));
+                } else if old_used != new_used {
+                    batch.add(
+                        ValueClass::MailboxSize(*container_id),
+                        new_used as i64 - old_used as i64,
+                    );
+                }
+            }
+            for container_id in new_ids {
+                if !old_ids.contains(&container_id) {
+                    batch.add(ValueClass::MailboxSize(container_id), new_used as i64);
+                }
+            }
+        }
         (
             IndexValue::LogItem {
                 sync_collection,
@@ -4,8 +4,8 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::Server;
-use directory::Directory;
+use crate::{KV_LOCK_UPDATE, Server};
+use directory::{Directory, DirectoryChain};
 use registry::{
     schema::{
         enums::{StorageQuota, TenantStorageQuota},
@@ -13,19 +13,26 @@ use registry::{
     },
     types::EnumImpl,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use store::{BlobStore, InMemoryStore, RegistryStore, SearchStore, Store};
+use trc::AddContext;
 
 pub mod archive;
 pub mod blob;
 pub mod dav;
 pub mod document;
 pub mod encryption;
+pub mod floor;
 pub mod index;
 pub mod quota;
 pub mod state;
 pub mod transaction;
 
+// Read-modify-write locks expire on their own if the node holding them goes
+// away, waiting nodes back off exponentially before giving up.
+const UPDATE_LOCK_EXPIRY: u64 = 5;
+const UPDATE_LOCK_ATTEMPTS: u32 = 8;
+
 #[derive(Debug, Clone)]
 pub struct ObjectQuota([u32; StorageQuota::COUNT - 1]);
 
@@ -78,6 +85,11 @@ impl Server {
         self.core.storage.directory.as_ref()
     }
 
+    #[inline(always)]
+    pub fn get_directory_chain(&self) -> &DirectoryChain {
+        &self.core.storage.directory_chain
+    }
+
     #[inline(always)]
     pub fn get_lookup_store(&self, name: &str) -> Option<InMemoryStore> {
         if !name.is_empty() && name != "*" {
@@ -87,6 +99,48 @@ impl Server {
         }
     }
 
+    /// Runs a read-modify-write sequence on an in-memory store value while
+    /// holding a cluster-wide lock on its key, the in-memory store cannot
+    /// assert values so concurrent updates would otherwise be lost.
+    pub async fn in_memory_update<T>(
+        &self,
+        prefix: u8,
+        key: &[u8],
+        update: impl Future<Output = trc::Result<T>>,
+    ) -> trc::Result<T> {
+        let mut lock_key = Vec::with_capacity(key.len() + 1);
+        lock_key.push(prefix);
+        lock_key.extend_from_slice(key);
+
+        let mut attempt = 0;
+        while !self
+            .in_memory_store()
+            .try_lock(KV_LOCK_UPDATE, &lock_key, UPDATE_LOCK_EXPIRY)
+            .await
+            .caused_by(trc::location!())?
+        {
+            attempt += 1;
+            if attempt >= UPDATE_LOCK_ATTEMPTS {
+                return Err(trc::StoreEvent::AssertValueFailed
+                    .into_err()
+                    .details("Timed out waiting for a concurrent update")
+                    .caused_by(trc::location!()));
+            }
+            tokio::time::sleep(Duration::from_millis(10 << attempt)).await;
+        }
+
+        let result = update.await;
+        if let Err(err) = self
+            .in_memory_store()
+            .remove_lock(KV_LOCK_UPDATE, &lock_key)
+            .await
+        {
+            trc::error!(err.details("Failed to release update lock"));
+        }
+
+        result
+    }
+
     pub async fn total_accounts(&self) -> trc::Result<usize> {
         self.registry().count_object(ObjectType::Account).await
     }
@@ -5,16 +5,26 @@
  */
 
 use crate::{
-    Server,
+    KV_QUOTA_WARNING, Server,
     auth::AccountCache,
+    cache::invalidate::CacheInvalidationBuilder,
     storage::{ObjectQuota, TenantQuota},
 };
 use registry::{
-    schema::enums::{StorageQuota, TenantStorageQuota},
-    types::EnumImpl,
+    schema::{
+        enums::{StorageQuota, TenantStorageQuota},
+        prelude::{Object, ObjectType},
+        structs::{Account, Task, TaskQuotaWarning, TaskStatus},
+    },
+    types::{EnumImpl, id::ObjectId},
+};
+use store::{
+    ValueKey,
+    registry::write::{RegistryWrite, RegistryWriteResult},
+    write::{BatchBuilder, ValueClass},
 };
-use store::{ValueKey, write::ValueClass};
 use trc::AddContext;
+use types::id::Id;
 
 impl Server {
     pub async fn get_used_quota_account(&self, account_id: u32) -> trc::Result<i64> {
@@ -30,6 +40,24 @@ impl Server {
             .await
             .add_context(|err| err.caused_by(trc::location!()).account_id(account_id))
     }
+    pub async fn get_used_quota_mailbox(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+    ) -> trc::Result<i64> {
+        self.core
+            .storage
+            .data
+            .get_counter(ValueKey {
+                account_id,
+                collection: 0,
+                document_id: 0,
+                class: ValueClass::MailboxSize(mailbox_id),
+            })
+            .await
+            .add_context(|err| err.caused_by(trc::location!()).account_id(account_id))
+    }
+
     // SPDX-SnippetBegin
     // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
     // SPDX-License-Identifier: LicenseRef-SEL
@@ -92,6 +120,143 @@ impl Server {
         Ok(())
     }
 
+    /// Returns the highest quota warning threshold crossed by an account
+    /// whose disk usage grew by `delta` bytes.
+    pub async fn quota_warning_crossed(
+        &self,
+        account_id: u32,
+        delta: i64,
+    ) -> trc::Result<Option<u64>> {
+        if delta <= 0 {
+            return Ok(None);
+        }
+        let account = self.account(account_id).await.caused_by(trc::location!())?;
+        if account.quota_disk == 0 {
+            return Ok(None);
+        }
+
+        // Domain thresholds take precedence over the global thresholds
+        let domain = if let Some(address) = account.addresses.first() {
+            self.domain_by_id(address.domain_id)
+                .await
+                .caused_by(trc::location!())?
+        } else {
+            None
+        };
+        let thresholds = domain
+            .as_ref()
+            .map(|domain| &domain.quota_warning_thresholds)
+            .filter(|thresholds| !thresholds.is_empty())
+            .unwrap_or(&self.core.email.quota_warning_thresholds);
+        if thresholds.is_empty() {
+            return Ok(None);
+        }
+
+        let quota = account.quota_disk as u128;
+        let used = self.get_used_quota_account(account_id).await?.max(0) as u128;
+        let previous = used.saturating_sub(delta as u128);
+        Ok(thresholds
+            .iter()
+            .rev()
+            .find(|&&threshold| {
+                let limit = quota * threshold as u128;
+                previous * 100 < limit && limit <= used * 100
+            })
+            .copied())
+    }
+
+    /// Schedules a quota warning message for an account, at most once per
+    /// threshold within the configured interval.
+    pub async fn quota_warning_notify(&self, account_id: u32, threshold: u64) -> trc::Result<()> {
+        if !self.core.email.quota_warning_notify {
+            return Ok(());
+        }
+
+        let mut key = Vec::with_capacity(std::mem::size_of::<u32>() + 1);
+        key.extend_from_slice(&account_id.to_be_bytes());
+        key.push(threshold as u8);
+        if !self
+            .in_memory_store()
+            .try_lock(
+                KV_QUOTA_WARNING,
+                &key,
+                self.core.email.quota_warning_interval.as_secs(),
+            )
+            .await
+            .caused_by(trc::location!())?
+        {
+            return Ok(());
+        }
+
+        let mut batch = BatchBuilder::new();
+        batch.schedule_task(Task::QuotaWarning(TaskQuotaWarning {
+            account_id: account_id.into(),
+            threshold,
+            status: TaskStatus::now(),
+        }));
+        self.store()
+            .write(batch.build_all())
+            .await
+            .caused_by(trc::location!())?;
+        self.notify_task_queue();
+
+        Ok(())
+    }
+
+    /// Updates the quota limits of an account, a limit of zero removes it.
+    pub async fn set_account_quotas(
+        &self,
+        account_id: u32,
+        limits: &[(StorageQuota, u64)],
+    ) -> trc::Result<()> {
+        let current_account = self
+            .registry()
+            .get(ObjectId::new(ObjectType::Account, account_id.into()))
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(|| {
+                trc::StoreEvent::NotFound
+                    .into_err()
+                    .caused_by(trc::location!())
+                    .account_id(account_id)
+            })?;
+        let mut account = Account::from(current_account.clone());
+        let quotas = account.quotas_mut();
+        for (resource, limit) in limits {
+            if *limit > 0 {
+                quotas.set(*resource, *limit);
+            } else {
+                quotas.remove(resource);
+            }
+        }
+
+        let updated_account = Object::from(account);
+        match self
+            .registry()
+            .write(RegistryWrite::update(
+                Id::from(account_id),
+                &updated_account,
+                &current_account,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        {
+            RegistryWriteResult::Success(id) => {
+                let mut invalidator = CacheInvalidationBuilder::default();
+                invalidator.process_update(id, &current_account, &updated_account);
+                self.invalidate_caches(invalidator)
+                    .await
+                    .caused_by(trc::location!())
+            }
+            failure => Err(trc::StoreEvent::UnexpectedError
+                .into_err()
+                .caused_by(trc::location!())
+                .details("Failed to update account quotas")
+                .account_id(account_id)
+                .reason(failure)),
+        }
+    }
+
     #[inline(always)]
     pub fn object_quota(&self, user_quotas: Option<&ObjectQuota>, object: StorageQuota) -> u32 {
         user_quotas.unwrap_or(&self.core.email.max_objects).0[object as usize]
@@ -15,11 +15,15 @@ use types::{
     collection::SyncCollection,
     type_state::{DataType, StateChange},
 };
-use utils::{map::bitmap::Bitmap, snowflake::SnowflakeIdGenerator};
+use utils::{
+    map::{bitmap::Bitmap, vec_map::VecMap},
+    snowflake::SnowflakeIdGenerator,
+};
 
 impl Server {
     pub async fn commit_batch(&self, mut builder: BatchBuilder) -> trc::Result<AssignedIds> {
         let mut assigned_ids = AssignedIds::default();
+        let quota_changes = builder.quota_changes();
         let mut commit_points = builder.commit_points();
 
         for commit_point in commit_points.iter() {
@@ -29,9 +33,26 @@ impl Server {
                 .extend(self.store().write(batch).await?.ids);
         }
 
+        // Check whether any quota warning thresholds were crossed
+        let mut quota_warnings = VecMap::new();
+        for (account_id, delta) in quota_changes.iter() {
+            match self.quota_warning_crossed(*account_id, *delta).await {
+                Ok(Some(threshold)) => {
+                    quota_warnings.append(*account_id, threshold);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    trc::error!(err.account_id(*account_id).caused_by(trc::location!()));
+                }
+            }
+        }
+
         if let Some(changes) = builder.changes() {
             for (account_id, changed_collections) in changes {
                 let mut state_change = StateChange::new(account_id);
+                if quota_warnings.contains_key(&account_id) {
+                    state_change.set_change(DataType::Quota);
+                }
                 for changed_collection in changed_collections.changed_containers {
                     if let Some(data_type) = DataType::try_from_sync(changed_collection, true) {
                         state_change.set_change(data_type);
@@ -59,6 +80,18 @@ impl Server {
             }
         }
 
+        for (account_id, threshold) in quota_warnings {
+            trc::event!(
+                Limit(trc::LimitEvent::QuotaWarning),
+                AccountId = account_id,
+                Limit = threshold,
+            );
+
+            if let Err(err) = self.quota_warning_notify(account_id, threshold).await {
+                trc::error!(err.account_id(account_id).caused_by(trc::location!()));
+            }
+        }
+
         Ok(assigned_ids)
     }
 
@@ -66,108 +99,154 @@ impl Server {
         &self,
         account_id: u32,
         max_entries: Option<usize>,
+        max_age: &VecMap<SyncCollection, Duration>,
+        floor: Option<u64>,
         max_duration: Option<Duration>,
     ) -> trc::Result<()> {
-        if let Some(max_entries) = max_entries {
-            for sync_collection in [
-                SyncCollection::Email,
-                SyncCollection::Thread,
-                SyncCollection::Identity,
-                SyncCollection::EmailSubmission,
-                SyncCollection::SieveScript,
-                SyncCollection::FileNode,
-                SyncCollection::AddressBook,
-                SyncCollection::Calendar,
-                SyncCollection::CalendarEventNotification,
-            ] {
-                let collection = sync_collection.into();
-                let from_key = LogKey {
-                    account_id,
-                    collection,
-                    change_id: 0,
-                };
-                let to_key = LogKey {
-                    account_id,
-                    collection,
-                    change_id: u64::MAX,
-                };
-
-                let mut first_change_id = 0;
-                let mut num_changes = 0;
+        let mut total_pruned = 0u64;
+
+        for sync_collection in [
+            SyncCollection::Email,
+            SyncCollection::Thread,
+            SyncCollection::Identity,
+            SyncCollection::EmailSubmission,
+            SyncCollection::SieveScript,
+            SyncCollection::FileNode,
+            SyncCollection::AddressBook,
+            SyncCollection::Calendar,
+            SyncCollection::CalendarEventNotification,
+        ] {
+            // Entries older than the maximum age are kept while a client may still
+            // sync from them, the maximum number of entries is always enforced
+            let max_age_change_id = max_age
+                .get(&sync_collection)
+                .and_then(|max_age| SnowflakeIdGenerator::from_duration(*max_age))
+                .map(|change_id| floor.map_or(change_id, |floor| change_id.min(floor)));
+            if max_entries.is_none() && max_age_change_id.is_none() {
+                continue;
+            }
 
+            let collection = sync_collection.into();
+            let from_key = LogKey {
+                account_id,
+                collection,
+                change_id: 0,
+            };
+            let to_key = LogKey {
+                account_id,
+                collection,
+                change_id: u64::MAX,
+            };
+
+            let mut first_change_id = None;
+            let mut num_changes = 0;
+            let mut num_pruned = 0u64;
+
+            self.store()
+                .iterate(
+                    IterateParams::new(from_key, to_key)
+                        .descending()
+                        .no_values(),
+                    |key, _| {
+                        let change_id = key.deserialize_be_u64(key.len() - U64_LEN)?;
+                        if first_change_id.is_some() {
+                            num_pruned += 1;
+                        } else if max_entries.is_some_and(|max_entries| num_changes >= max_entries)
+                            || max_age_change_id
+                                .is_some_and(|max_change_id| change_id < max_change_id)
+                        {
+                            first_change_id = Some(change_id);
+                        }
+                        num_changes += 1;
+
+                        Ok(true)
+                    },
+                )
+                .await
+                .caused_by(trc::location!())?;
+
+            let Some(first_change_id) = first_change_id else {
+                continue;
+            };
+
+            // The truncation entry replaces the newest pruned change
+            let mut is_truncated = false;
+            let marker_key = LogKey {
+                account_id,
+                collection,
+                change_id: first_change_id,
+            };
+            self.store()
+                .iterate(
+                    IterateParams::new(marker_key, marker_key).ascending(),
+                    |_, value| {
+                        is_truncated = value.is_empty();
+
+                        Ok(false)
+                    },
+                )
+                .await
+                .caused_by(trc::location!())?;
+            if !is_truncated {
+                num_pruned += 1;
+            } else if num_pruned == 0 {
+                continue;
+            }
+
+            self.store()
+                .delete_range(
+                    LogKey {
+                        account_id,
+                        collection,
+                        change_id: 0,
+                    },
+                    marker_key,
+                )
+                .await
+                .caused_by(trc::location!())?;
+
+            // Delete vanished items
+            if let Some(vanished_collection) = sync_collection.vanished_collection().map(u8::from) {
                 self.store()
-                    .iterate(
-                        IterateParams::new(from_key, to_key)
-                            .descending()
-                            .no_values(),
-                        |key, _| {
-                            first_change_id = key.deserialize_be_u64(key.len() - U64_LEN)?;
-                            num_changes += 1;
-
-                            Ok(num_changes <= max_entries)
+                    .delete_range(
+                        LogKey {
+                            account_id,
+                            collection: vanished_collection,
+                            change_id: 0,
+                        },
+                        LogKey {
+                            account_id,
+                            collection: vanished_collection,
+                            change_id: first_change_id,
                         },
                     )
                     .await
                     .caused_by(trc::location!())?;
+            }
 
-                if num_changes > max_entries {
-                    self.store()
-                        .delete_range(
-                            LogKey {
-                                account_id,
-                                collection,
-                                change_id: 0,
-                            },
-                            LogKey {
-                                account_id,
-                                collection,
-                                change_id: first_change_id,
-                            },
-                        )
-                        .await
-                        .caused_by(trc::location!())?;
-
-                    // Delete vanished items
-                    if let Some(vanished_collection) =
-                        sync_collection.vanished_collection().map(u8::from)
-                    {
-                        self.store()
-                            .delete_range(
-                                LogKey {
-                                    account_id,
-                                    collection: vanished_collection,
-                                    change_id: 0,
-                                },
-                                LogKey {
-                                    account_id,
-                                    collection: vanished_collection,
-                                    change_id: first_change_id,
-                                },
-                            )
-                            .await
-                            .caused_by(trc::location!())?;
-                    }
+            // Write truncation entry for cache
+            let mut batch = BatchBuilder::new();
+            batch.with_account_id(account_id).set(
+                ValueClass::Any(AnyClass {
+                    subspace: SUBSPACE_LOGS,
+                    key: marker_key.serialize(0),
+                }),
+                Vec::new(),
+            );
+            self.store()
+                .write(batch.build_all())
+                .await
+                .caused_by(trc::location!())?;
 
-                    // Write truncation entry for cache
-                    let mut batch = BatchBuilder::new();
-                    batch.with_account_id(account_id).set(
-                        ValueClass::Any(AnyClass {
-                            subspace: SUBSPACE_LOGS,
-                            key: LogKey {
-                                account_id,
-                                collection,
-                                change_id: first_change_id,
-                            }
-                            .serialize(0),
-                        }),
-                        Vec::new(),
-                    );
-                    self.store()
-                        .write(batch.build_all())
-                        .await
-                        .caused_by(trc::location!())?;
-                }
-            }
+            total_pruned += num_pruned;
+        }
+
+        if total_pruned > 0 {
+            trc::event!(
+                Store(trc::StoreEvent::ChangesPruned),
+                AccountId = account_id,
+                Total = total_pruned,
+            );
         }
 
         if let Some(max_duration) = max_duration {
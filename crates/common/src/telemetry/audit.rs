@@ -0,0 +1,196 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    BuildServer, Inner, Server,
+    auth::{AccessToken, DOMAIN_FLAG_AUDIT_LOG, DOMAIN_FLAG_AUDIT_READS},
+};
+use registry::{
+    schema::{
+        enums::{AuditEventType, ServiceProtocol},
+        structs::AuditEvent,
+    },
+    types::{EnumImpl, ObjectImpl, datetime::UTCDateTime, ipaddr::IpAddr, map::Map},
+};
+use std::{future::Future, sync::Arc, time::Duration};
+use store::{
+    Deserialize, IterateParams, Store, ValueKey,
+    ahash::AHashSet,
+    write::{AuditClass, BatchBuilder, ValueClass},
+};
+use tokio::sync::mpsc;
+use trc::{AddContext, TelemetryEvent};
+use types::id::Id;
+use utils::snowflake::SnowflakeIdGenerator;
+
+const MAX_BATCH_SIZE: usize = 256;
+
+#[derive(Clone, Copy)]
+pub struct AuditSource<'x> {
+    pub access_token: &'x AccessToken,
+    pub protocol: ServiceProtocol,
+    pub remote_ip: Option<std::net::IpAddr>,
+}
+
+impl Server {
+    pub async fn audit(
+        &self,
+        source: AuditSource<'_>,
+        account_id: u32,
+        event: AuditEventType,
+        object_ids: impl IntoIterator<Item = Id>,
+    ) {
+        if self.is_audit_enabled(account_id, event).await {
+            self.send_audit_event(AuditEvent {
+                account_id: account_id.into(),
+                actor_id: source.access_token.actor_id().into(),
+                timestamp: UTCDateTime::now(),
+                event,
+                protocol: source.protocol,
+                remote_ip: source.remote_ip.map(IpAddr),
+                object_ids: Map::new(object_ids.into_iter().map(|id| id.to_string()).collect()),
+                details: None,
+            });
+        }
+    }
+
+    pub async fn audit_event(&self, event: AuditEvent) {
+        if self
+            .is_audit_enabled(event.account_id.document_id(), event.event)
+            .await
+        {
+            self.send_audit_event(event);
+        }
+    }
+
+    /// Audit logging is enabled per domain, the account's primary address
+    /// determines which domain settings apply.
+    pub async fn is_audit_enabled(&self, account_id: u32, event: AuditEventType) -> bool {
+        let flag = if event == AuditEventType::MessageRead {
+            DOMAIN_FLAG_AUDIT_READS
+        } else {
+            DOMAIN_FLAG_AUDIT_LOG
+        };
+
+        let domain_id = match self.try_account(account_id).await {
+            Ok(Some(account)) => match account.addresses.first() {
+                Some(address) => address.domain_id,
+                None => return false,
+            },
+            Ok(None) => return false,
+            Err(err) => {
+                trc::error!(err.caused_by(trc::location!()));
+                return false;
+            }
+        };
+
+        match self.domain_by_id(domain_id).await {
+            Ok(domain) => domain.is_some_and(|domain| domain.flags & flag != 0),
+            Err(err) => {
+                trc::error!(err.caused_by(trc::location!()));
+                false
+            }
+        }
+    }
+
+    fn send_audit_event(&self, event: AuditEvent) {
+        let account_id = event.account_id.document_id();
+        if let Err(err) = self.inner.ipc.audit_tx.try_send(event) {
+            trc::event!(
+                Telemetry(TelemetryEvent::AuditError),
+                AccountId = account_id,
+                Reason = err.to_string(),
+            );
+        }
+    }
+}
+
+pub fn spawn_audit_writer(inner: Arc<Inner>, mut rx: mpsc::Receiver<AuditEvent>) {
+    tokio::spawn(async move {
+        let mut events = Vec::with_capacity(MAX_BATCH_SIZE);
+
+        while rx.recv_many(&mut events, MAX_BATCH_SIZE).await > 0 {
+            let server = inner.build_server();
+            let mut batch = BatchBuilder::new();
+
+            for event in events.drain(..) {
+                let event_id = server.inner.data.span_id_gen.generate();
+                batch
+                    .set(
+                        ValueClass::Audit(AuditClass::Index {
+                            account_id: event.account_id.document_id(),
+                            event_id,
+                        }),
+                        vec![event.event.to_id() as u8],
+                    )
+                    .set(
+                        ValueClass::Audit(AuditClass::Event(event_id)),
+                        event.to_pickled_vec(),
+                    );
+            }
+
+            if let Err(err) = server.store().write(batch.build_all()).await {
+                trc::event!(
+                    Telemetry(TelemetryEvent::AuditError),
+                    Reason = "Failed to write audit events",
+                    CausedBy = err,
+                );
+            }
+        }
+    });
+}
+
+pub trait AuditStore: Sync + Send {
+    fn purge_audit_events(&self, period: Duration) -> impl Future<Output = trc::Result<()>> + Send;
+}
+
+impl AuditStore for Store {
+    async fn purge_audit_events(&self, period: Duration) -> trc::Result<()> {
+        let until_event_id = SnowflakeIdGenerator::from_duration(period).ok_or_else(|| {
+            trc::StoreEvent::UnexpectedError
+                .caused_by(trc::location!())
+                .ctx(trc::Key::Reason, "Failed to generate reference event id.")
+        })?;
+
+        // Collect the accounts with expired events
+        let mut account_ids = AHashSet::new();
+        self.iterate(
+            IterateParams::new(
+                ValueKey::from(ValueClass::Audit(AuditClass::Event(0))),
+                ValueKey::from(ValueClass::Audit(AuditClass::Event(until_event_id))),
+            )
+            .ascending(),
+            |_, value| {
+                account_ids.insert(AuditEvent::deserialize(value)?.account_id.document_id());
+                Ok(true)
+            },
+        )
+        .await
+        .caused_by(trc::location!())?;
+
+        for account_id in account_ids {
+            self.delete_range(
+                ValueKey::from(ValueClass::Audit(AuditClass::Index {
+                    account_id,
+                    event_id: 0,
+                })),
+                ValueKey::from(ValueClass::Audit(AuditClass::Index {
+                    account_id,
+                    event_id: until_event_id,
+                })),
+            )
+            .await
+            .caused_by(trc::location!())?;
+        }
+
+        self.delete_range(
+            ValueKey::from(ValueClass::Audit(AuditClass::Event(0))),
+            ValueKey::from(ValueClass::Audit(AuditClass::Event(until_event_id))),
+        )
+        .await
+        .caused_by(trc::location!())
+    }
+}
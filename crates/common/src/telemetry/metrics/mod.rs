@@ -6,6 +6,7 @@
 
 pub mod otel;
 pub mod prometheus;
+pub mod queue;
 
 // SPDX-SnippetBegin
 // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
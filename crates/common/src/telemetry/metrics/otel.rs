@@ -5,6 +5,7 @@
  */
 
 use crate::config::telemetry::OtelMetrics;
+use opentelemetry::KeyValue;
 use opentelemetry_sdk::metrics::{
     Temporality,
     data::{
@@ -75,6 +76,65 @@ impl OtelMetrics {
             ));
         }
 
+        // Add labeled counters
+        let mut counters: Vec<(trc::MetricType, Vec<SumDataPoint<u64>>)> = Vec::new();
+        for counter in Collector::collect_labeled_counters() {
+            let point = SumDataPoint::new(attributes(counter.labels()), counter.value(), vec![]);
+            if let Some((_, points)) = counters.iter_mut().find(|(id, _)| *id == counter.id()) {
+                points.push(point);
+            } else {
+                counters.push((counter.id(), vec![point]));
+            }
+        }
+        for (id, points) in counters {
+            metrics.push(Metric::new(
+                id.as_str(),
+                id.description(),
+                id.unit(),
+                AggregatedMetrics::U64(MetricData::Sum(Sum::new(
+                    points,
+                    start_time,
+                    time,
+                    Temporality::Cumulative,
+                    true,
+                ))),
+            ));
+        }
+
+        // Add labeled histograms
+        let mut histograms: Vec<(trc::MetricType, Vec<HistogramDataPoint<u64>>)> = Vec::new();
+        for labeled in Collector::collect_labeled_histograms() {
+            let histogram = labeled.histogram();
+            let point = HistogramDataPoint::new(
+                attributes(labeled.labels()),
+                histogram.count(),
+                histogram.upper_bounds_vec(),
+                histogram.buckets_vec(),
+                histogram.min(),
+                histogram.max(),
+                histogram.sum(),
+                vec![],
+            );
+            if let Some((_, points)) = histograms.iter_mut().find(|(id, _)| *id == histogram.id()) {
+                points.push(point);
+            } else {
+                histograms.push((histogram.id(), vec![point]));
+            }
+        }
+        for (id, points) in histograms {
+            metrics.push(Metric::new(
+                id.as_str(),
+                id.description(),
+                id.unit(),
+                AggregatedMetrics::U64(MetricData::Histogram(Histogram::new(
+                    points,
+                    start_time,
+                    time,
+                    Temporality::Cumulative,
+                ))),
+            ));
+        }
+
         // Export metrics
         let rm = ResourceMetrics::new(
             self.resource.clone(),
@@ -98,3 +158,10 @@ impl OtelMetrics {
         });*/
     }
 }
+
+fn attributes(labels: &'static [(&'static str, &'static str)]) -> Vec<KeyValue> {
+    labels
+        .iter()
+        .map(|(name, value)| KeyValue::new(*name, *value))
+        .collect()
+}
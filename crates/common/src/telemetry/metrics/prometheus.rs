@@ -6,8 +6,9 @@
 
 use prometheus::{
     TextEncoder,
-    proto::{Bucket, Counter, Gauge, Histogram, Metric, MetricFamily, MetricType},
+    proto::{Bucket, Counter, Gauge, Histogram, LabelPair, Metric, MetricFamily, MetricType},
 };
+use store::write::now;
 use trc::{Collector, atomics::histogram::AtomicHistogram};
 
 use crate::Server;
@@ -56,6 +57,84 @@ impl Server {
             metrics.push(metric);
         }
 
+        // Add labeled counters and histograms, grouping samples of the same metric
+        let mut labeled: Vec<(trc::MetricType, MetricType, Vec<Metric>)> = Vec::new();
+        for counter in Collector::collect_labeled_counters() {
+            let mut sample = new_counter(counter.value());
+            sample.set_label(new_labels(counter.labels()));
+            add_labeled_metric(&mut labeled, counter.id(), MetricType::COUNTER, sample);
+        }
+        for histogram in Collector::collect_labeled_histograms() {
+            let mut sample = new_histogram(histogram.histogram());
+            sample.set_label(new_labels(histogram.labels()));
+            add_labeled_metric(
+                &mut labeled,
+                histogram.histogram().id(),
+                MetricType::HISTOGRAM,
+                sample,
+            );
+        }
+        for (id, metric_type, samples) in labeled {
+            metrics.push(new_metric_family(id, metric_type, samples));
+        }
+
+        // Add queue metrics
+        let queue_metrics = &self.inner.data.queue_metrics;
+        let queues = queue_metrics.queue_gauges();
+        if !queues.is_empty() {
+            let now = now();
+            for id in [
+                trc::MetricType::QueueMessages,
+                trc::MetricType::QueueBytes,
+                trc::MetricType::QueueOldestAge,
+            ] {
+                let samples = queues
+                    .iter()
+                    .map(|(queue_name, gauges)| {
+                        let mut sample = new_gauge(match id {
+                            trc::MetricType::QueueMessages => gauges.messages,
+                            trc::MetricType::QueueBytes => gauges.bytes,
+                            _ => gauges.oldest_age(now) * 1000,
+                        });
+                        sample.set_label(new_labels(&[("queue", queue_name.as_str())]));
+                        sample
+                    })
+                    .collect();
+                metrics.push(new_metric_family(id, MetricType::GAUGE, samples));
+            }
+        }
+        for histogram in [queue_metrics.message_age(), queue_metrics.throttle_delay()] {
+            if histogram.is_active() {
+                metrics.push(new_metric_family(
+                    histogram.id(),
+                    MetricType::HISTOGRAM,
+                    vec![new_histogram(histogram)],
+                ));
+            }
+        }
+        let domains = queue_metrics.domain_counters();
+        if !domains.is_empty() {
+            for id in [
+                trc::MetricType::QueueDomainAttempts,
+                trc::MetricType::QueueDomainDeferrals,
+                trc::MetricType::QueueDomainBounces,
+            ] {
+                let samples = domains
+                    .iter()
+                    .map(|(domain, counters)| {
+                        let mut sample = new_counter(match id {
+                            trc::MetricType::QueueDomainAttempts => counters.attempts,
+                            trc::MetricType::QueueDomainDeferrals => counters.deferrals,
+                            _ => counters.bounces,
+                        });
+                        sample.set_label(new_labels(&[("domain", domain.as_ref())]));
+                        sample
+                    })
+                    .collect();
+                metrics.push(new_metric_family(id, MetricType::COUNTER, samples));
+            }
+        }
+
         TextEncoder::new().encode_to_string(&metrics).map_err(|e| {
             trc::EventType::Telemetry(trc::TelemetryEvent::OtelExporterError).reason(e)
         })
@@ -75,6 +154,47 @@ fn metric_name(id: impl AsRef<str>) -> String {
     name
 }
 
+fn add_labeled_metric(
+    metrics: &mut Vec<(trc::MetricType, MetricType, Vec<Metric>)>,
+    id: trc::MetricType,
+    metric_type: MetricType,
+    sample: Metric,
+) {
+    if let Some((_, _, samples)) = metrics
+        .iter_mut()
+        .find(|(metric_id, _, _)| *metric_id == id)
+    {
+        samples.push(sample);
+    } else {
+        metrics.push((id, metric_type, vec![sample]));
+    }
+}
+
+fn new_metric_family(
+    id: trc::MetricType,
+    metric_type: MetricType,
+    samples: Vec<Metric>,
+) -> MetricFamily {
+    let mut metric = MetricFamily::default();
+    metric.set_name(metric_name(id.as_str()));
+    metric.set_help(id.description().into());
+    metric.set_field_type(metric_type);
+    metric.set_metric(samples);
+    metric
+}
+
+fn new_labels(labels: &[(&str, &str)]) -> Vec<LabelPair> {
+    labels
+        .iter()
+        .map(|(name, value)| {
+            let mut label = LabelPair::default();
+            label.set_name(name.to_string());
+            label.set_value(value.to_string());
+            label
+        })
+        .collect()
+}
+
 fn new_counter(value: u64) -> Metric {
     let mut m = Metric::default();
     let mut counter = Counter::default();
@@ -0,0 +1,195 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::config::smtp::queue::QueueName;
+use ahash::AHashMap;
+use parking_lot::Mutex;
+use trc::{MetricType, atomics::histogram::AtomicHistogram};
+
+pub const OTHER_DOMAINS: &str = "other";
+
+// Queue depth and per-domain delivery metrics, updated incrementally by the
+// queue and corrected periodically by a full scan of the queue.
+pub struct QueueMetrics {
+    queues: Mutex<AHashMap<QueueName, QueueGauges>>,
+    domains: Mutex<DomainTable>,
+    message_age: AtomicHistogram<12>,
+    throttle_delay: AtomicHistogram<12>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueueGauges {
+    pub messages: u64,
+    pub bytes: u64,
+    pub oldest: Option<u64>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DomainCounters {
+    pub attempts: u64,
+    pub deferrals: u64,
+    pub bounces: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryOutcome {
+    Attempt,
+    Deferral,
+    Bounce,
+}
+
+#[derive(Debug, Default)]
+struct DomainTable {
+    tracked: AHashMap<Box<str>, DomainCounters>,
+    other: DomainCounters,
+}
+
+impl QueueMetrics {
+    pub fn message_queued(&self, queue: QueueName, size: u64, created: u64) {
+        let mut queues = self.queues.lock();
+        let gauges = queues.entry(queue).or_default();
+        gauges.messages += 1;
+        gauges.bytes += size;
+        gauges.oldest = Some(gauges.oldest.map_or(created, |oldest| oldest.min(created)));
+    }
+
+    pub fn message_dequeued(&self, queue: QueueName, size: u64) {
+        let mut queues = self.queues.lock();
+        if let Some(gauges) = queues.get_mut(&queue) {
+            gauges.messages = gauges.messages.saturating_sub(1);
+            gauges.bytes = gauges.bytes.saturating_sub(size);
+
+            // The next oldest message is not known until the next reconciliation
+            if gauges.messages == 0 {
+                gauges.bytes = 0;
+                gauges.oldest = None;
+            }
+        }
+    }
+
+    pub fn message_attempted(&self, age: u64) {
+        self.message_age.observe(age * 1000);
+    }
+
+    pub fn record_delivery(&self, domain: &str, outcome: DeliveryOutcome, max_domains: usize) {
+        let mut domains = self.domains.lock();
+        let counters = if let Some(counters) = domains.tracked.get_mut(domain) {
+            counters
+        } else if domains.tracked.len() < max_domains {
+            domains.tracked.entry(domain.into()).or_default()
+        } else {
+            &mut domains.other
+        };
+
+        match outcome {
+            DeliveryOutcome::Attempt => counters.attempts += 1,
+            DeliveryOutcome::Deferral => counters.deferrals += 1,
+            DeliveryOutcome::Bounce => counters.bounces += 1,
+        }
+    }
+
+    /// Replaces the queue gauges with the results of a full queue scan and
+    /// keeps separate counters only for the domains with the most queued
+    /// recipients, folding the counters of any other domain into "other".
+    pub fn reconcile(
+        &self,
+        queues: AHashMap<QueueName, QueueGauges>,
+        domain_volume: AHashMap<Box<str>, u64>,
+        max_domains: usize,
+    ) {
+        *self.queues.lock() = queues;
+
+        let mut domains = self.domains.lock();
+        let mut ranked = domains
+            .tracked
+            .iter()
+            .map(|(domain, counters)| {
+                (
+                    domain.clone(),
+                    domain_volume.get(domain).copied().unwrap_or_default(),
+                    counters.attempts,
+                )
+            })
+            .chain(
+                domain_volume
+                    .iter()
+                    .filter(|(domain, _)| !domains.tracked.contains_key(*domain))
+                    .map(|(domain, volume)| (domain.clone(), *volume, 0)),
+            )
+            .collect::<Vec<_>>();
+        ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(a.0.cmp(&b.0)));
+        ranked.truncate(max_domains);
+
+        let mut tracked = AHashMap::with_capacity(ranked.len());
+        for (domain, _, _) in ranked {
+            let counters = domains.tracked.remove(&domain).unwrap_or_default();
+            tracked.insert(domain, counters);
+        }
+        for counters in std::mem::replace(&mut domains.tracked, tracked).into_values() {
+            domains.other.attempts += counters.attempts;
+            domains.other.deferrals += counters.deferrals;
+            domains.other.bounces += counters.bounces;
+        }
+    }
+
+    pub fn queue_gauges(&self) -> Vec<(QueueName, QueueGauges)> {
+        let mut queues = self
+            .queues
+            .lock()
+            .iter()
+            .map(|(name, gauges)| (*name, *gauges))
+            .collect::<Vec<_>>();
+        queues.sort_unstable_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        queues
+    }
+
+    pub fn domain_counters(&self) -> Vec<(Box<str>, DomainCounters)> {
+        let domains = self.domains.lock();
+        let mut counters = domains
+            .tracked
+            .iter()
+            .map(|(domain, counters)| (domain.clone(), *counters))
+            .collect::<Vec<_>>();
+        counters.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        if domains.other != DomainCounters::default() {
+            counters.push((OTHER_DOMAINS.into(), domains.other));
+        }
+        counters
+    }
+
+    pub fn message_throttled(&self, delay_ms: u64) {
+        self.throttle_delay.observe(delay_ms);
+    }
+
+    pub fn message_age(&self) -> &AtomicHistogram<12> {
+        &self.message_age
+    }
+
+    pub fn throttle_delay(&self) -> &AtomicHistogram<12> {
+        &self.throttle_delay
+    }
+}
+
+impl QueueGauges {
+    pub fn oldest_age(&self, now: u64) -> u64 {
+        self.oldest
+            .map(|oldest| now.saturating_sub(oldest))
+            .unwrap_or_default()
+    }
+}
+
+impl Default for QueueMetrics {
+    fn default() -> Self {
+        Self {
+            queues: Default::default(),
+            domains: Default::default(),
+            message_age: AtomicHistogram::<12>::new_long_durations(MetricType::QueueMessageAge),
+            throttle_delay: AtomicHistogram::<12>::new_medium_durations(
+                MetricType::QueueThrottleDelay,
+            ),
+        }
+    }
+}
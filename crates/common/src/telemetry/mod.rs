@@ -4,7 +4,10 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod audit;
 pub mod metrics;
+pub mod overrides;
+pub mod slow;
 pub mod tracers;
 pub mod webhooks;
 
@@ -20,10 +23,12 @@ impl Telemetry {
     pub fn enable(self, is_enterprise: bool) {
         // Spawn tracers
         for tracer in self.tracers.subscribers {
+            let overrides = tracer.typ.accepts_overrides();
             tracer.typ.spawn(
                 SubscriberBuilder::new(tracer.id)
                     .with_interests(tracer.interests)
-                    .with_lossy(tracer.lossy),
+                    .with_lossy(tracer.lossy)
+                    .with_overrides(overrides),
                 is_enterprise,
             );
         }
@@ -54,10 +59,12 @@ impl Telemetry {
             if active_subscribers.contains(&tracer.id) {
                 Collector::update_subscriber(tracer.id, tracer.interests, tracer.lossy);
             } else {
+                let overrides = tracer.typ.accepts_overrides();
                 tracer.typ.spawn(
                     SubscriberBuilder::new(tracer.id)
                         .with_interests(tracer.interests)
-                        .with_lossy(tracer.lossy),
+                        .with_lossy(tracer.lossy)
+                        .with_overrides(overrides),
                     is_enterprise,
                 );
             }
@@ -82,7 +89,8 @@ impl Telemetry {
         spawn_console_tracer(
             SubscriberBuilder::new("stderr".to_string())
                 .with_interests(interests.clone())
-                .with_lossy(false),
+                .with_lossy(false)
+                .with_overrides(true),
             crate::config::telemetry::ConsoleTracer {
                 ansi: true,
                 multiline: false,
@@ -96,6 +104,25 @@ impl Telemetry {
 }
 
 impl TelemetrySubscriberType {
+    // Tracing overrides only apply to log outputs, webhooks and the
+    // tracing history keep receiving the events they were configured with
+    pub fn accepts_overrides(&self) -> bool {
+        match self {
+            TelemetrySubscriberType::ConsoleTracer(_)
+            | TelemetrySubscriberType::LogTracer(_)
+            | TelemetrySubscriberType::OtelTracer(_) => true,
+            #[cfg(unix)]
+            TelemetrySubscriberType::JournalTracer(_) => true,
+            TelemetrySubscriberType::Webhook(_) => false,
+            // SPDX-SnippetBegin
+            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+            // SPDX-License-Identifier: LicenseRef-SEL
+            #[cfg(feature = "enterprise")]
+            TelemetrySubscriberType::StoreTracer(_) => false,
+            // SPDX-SnippetEnd
+        }
+    }
+
     pub fn spawn(self, builder: SubscriberBuilder, is_enterprise: bool) {
         match self {
             TelemetrySubscriberType::ConsoleTracer(settings) => {
@@ -0,0 +1,153 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{KV_TRACE_OVERRIDES, Server, ipc::BroadcastEvent};
+use std::{fmt::Write, str::FromStr};
+use store::{dispatch::lookup::KeyValue, write::now};
+use trc::{
+    AddContext, Collector, Key, Level,
+    ipc::overrides::{MAX_OVERRIDES, TraceOverride},
+};
+
+// Tracing overrides are kept in the in-memory store so that every node
+// applies the same rules, including nodes that start while they are active.
+// Changes are broadcast to the cluster and each node reverts the rules on
+// its own once they expire.
+impl Server {
+    pub async fn trace_overrides(&self) -> trc::Result<Vec<TraceOverride>> {
+        let current_time = now();
+        self.in_memory_store()
+            .key_get::<String>(KeyValue::<()>::build_key(KV_TRACE_OVERRIDES, b""))
+            .await
+            .caused_by(trc::location!())
+            .map(|overrides| {
+                overrides
+                    .unwrap_or_default()
+                    .lines()
+                    .filter_map(parse_override)
+                    .filter(|rule| rule.expires > current_time)
+                    .collect()
+            })
+    }
+
+    pub async fn add_trace_override(&self, mut rule: TraceOverride) -> trc::Result<TraceOverride> {
+        // All rules share one key, so updates are serialized to avoid dropping
+        // a rule changed concurrently by another node
+        self.in_memory_update(KV_TRACE_OVERRIDES, b"", async {
+            let mut overrides = self.trace_overrides().await?;
+            if overrides.len() >= MAX_OVERRIDES {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details(format!(
+                        "Too many active overrides, at most {MAX_OVERRIDES} are allowed"
+                    )));
+            }
+
+            rule.id = self.inner.data.jmap_id_gen.generate();
+            overrides.push(rule.clone());
+            self.update_trace_overrides(overrides).await?;
+
+            Ok(rule)
+        })
+        .await
+    }
+
+    pub async fn remove_trace_override(&self, id: u64) -> trc::Result<bool> {
+        self.in_memory_update(KV_TRACE_OVERRIDES, b"", async {
+            let mut overrides = self.trace_overrides().await?;
+            let num_overrides = overrides.len();
+            overrides.retain(|rule| rule.id != id);
+            if overrides.len() != num_overrides {
+                self.update_trace_overrides(overrides).await?;
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        })
+        .await
+    }
+
+    // Applies the overrides stored in the cluster to this node
+    pub async fn apply_trace_overrides(&self) {
+        match self.trace_overrides().await {
+            Ok(overrides) => Collector::set_overrides(overrides),
+            Err(err) => {
+                trc::error!(err.details("Failed to load tracing overrides"));
+            }
+        }
+    }
+
+    async fn update_trace_overrides(&self, overrides: Vec<TraceOverride>) -> trc::Result<()> {
+        let store_key = KeyValue::<()>::build_key(KV_TRACE_OVERRIDES, b"");
+        if let Some(expires) = overrides.iter().map(|rule| rule.expires).max() {
+            let mut value = String::new();
+            for rule in &overrides {
+                let _ = write!(
+                    &mut value,
+                    "{} {} {} {} {} {}",
+                    rule.id,
+                    rule.expires,
+                    rule.level.as_str(),
+                    rule.account_id
+                        .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                    rule.queue_id
+                        .map_or_else(|| "-".to_string(), |id| id.to_string()),
+                    rule.filter,
+                );
+                if let Some((key, expected)) = &rule.key {
+                    let _ = write!(&mut value, " {} {expected}", key.as_str());
+                }
+                value.push('\n');
+            }
+            self.in_memory_store()
+                .key_set(
+                    KeyValue::new(store_key, value.into_bytes())
+                        .expires(expires.saturating_sub(now()).max(1)),
+                )
+                .await
+                .caused_by(trc::location!())?;
+        } else {
+            self.in_memory_store()
+                .key_delete(store_key)
+                .await
+                .caused_by(trc::location!())?;
+        }
+
+        Collector::set_overrides(overrides);
+        self.cluster_broadcast(BroadcastEvent::TraceOverridesUpdate)
+            .await;
+
+        Ok(())
+    }
+}
+
+fn parse_override(line: &str) -> Option<TraceOverride> {
+    let mut parts = line.splitn(8, ' ');
+    let id = parts.next()?.parse().ok()?;
+    let expires = parts.next()?.parse().ok()?;
+    let level = Level::from_str(parts.next()?).ok()?;
+    let account_id = match parts.next()? {
+        "-" => None,
+        id => Some(id.parse().ok()?),
+    };
+    let queue_id = match parts.next()? {
+        "-" => None,
+        id => Some(id.parse().ok()?),
+    };
+    let filter = parts.next()?;
+    let key = match parts.next() {
+        Some(key) => Some((Key::from_str(key).ok()?, parts.next()?.into())),
+        None => None,
+    };
+
+    TraceOverride::new(id, filter, level, expires)
+        .ok()
+        .map(|rule| {
+            rule.with_account_id(account_id)
+                .with_queue_id(queue_id)
+                .with_key(key)
+        })
+}
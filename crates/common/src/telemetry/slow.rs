@@ -0,0 +1,50 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+const MAX_SLOW_REQUESTS: usize = 100;
+
+/// Most recent slow method calls handled by this node, oldest first.
+#[derive(Default)]
+pub struct SlowRequests {
+    entries: Mutex<VecDeque<SlowRequest>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowRequest {
+    pub timestamp: u64,
+    pub session_id: u64,
+    pub account_id: u32,
+    pub method: String,
+    pub arguments: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<usize>,
+    pub size: usize,
+    pub store_reads: u64,
+    pub parse_time_ms: u64,
+    pub store_time_ms: u64,
+    pub serialize_time_ms: u64,
+    pub elapsed_ms: u64,
+    pub threshold_ms: u64,
+}
+
+impl SlowRequests {
+    pub fn push(&self, request: SlowRequest) {
+        let mut entries = self.entries.lock();
+        if entries.len() == MAX_SLOW_REQUESTS {
+            entries.pop_front();
+        }
+        entries.push_back(request);
+    }
+
+    pub fn list(&self) -> Vec<SlowRequest> {
+        self.entries.lock().iter().cloned().collect()
+    }
+}
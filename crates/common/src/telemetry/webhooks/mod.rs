@@ -134,6 +134,10 @@ async fn post_webhook_events(
     let body = serde_json::to_string(events)
         .map_err(|err| format!("Failed to serialize events: {}", err))?;
 
+    post_webhook(settings, body).await
+}
+
+pub async fn post_webhook(settings: &WebhookTracer, body: String) -> Result<(), String> {
     // Add HMAC-SHA256 signature
     let mut headers = settings.headers.clone();
     if !settings.key.is_empty() {
@@ -6,7 +6,7 @@
 
 use std::borrow::Cow;
 
-use request::TextMatch;
+use request::{Filter, FilterOp, TextMatch};
 pub mod property;
 pub mod request;
 pub mod response;
@@ -1306,9 +1306,10 @@ impl<T: AttributeValue> Attribute<T> {
                 }
             },
             "collation" => {
-                if let Some(c) = Collation::try_parse(value.as_ref()) {
-                    return Some(Attribute::Collation(c));
-                }
+                return Some(Attribute::Collation(
+                    Collation::try_parse(value.as_ref())
+                        .unwrap_or_else(|| Collation::Unsupported(value.into_owned())),
+                ));
             },
             "start" => {
                 if let Some(value) = T::from_str(value.as_ref()) {
@@ -1353,6 +1354,7 @@ pub enum Collation {
     AsciiCasemap,
     Octet,
     UnicodeCasemap,
+    Unsupported(String),
 }
 
 impl Collation {
@@ -1365,12 +1367,13 @@ impl Collation {
         )
     }
 
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Collation::AsciiNumeric => "i;ascii-numeric",
             Collation::AsciiCasemap => "i;ascii-casemap",
             Collation::Octet => "i;octet",
             Collation::UnicodeCasemap => "i;unicode-casemap",
+            Collation::Unsupported(name) => name,
         }
     }
 }
@@ -1458,27 +1461,68 @@ impl YesNo {
 
 impl TextMatch {
     pub fn matches(&self, text: &str) -> bool {
-        match self.collation {
-            Collation::Octet => {
-                (match self.match_type {
-                    MatchType::Equals => text == self.value,
-                    MatchType::Contains => text.contains(&self.value),
-                    MatchType::StartsWith => text.starts_with(&self.value),
-                    MatchType::EndsWith => text.ends_with(&self.value),
-                }) ^ self.negate
+        (match &self.collation {
+            Collation::Octet => self.match_type.matches(text, &self.value),
+            Collation::AsciiCasemap => self
+                .match_type
+                .matches(&text.to_ascii_lowercase(), &self.value.to_ascii_lowercase()),
+            Collation::UnicodeCasemap => self
+                .match_type
+                .matches(&text.to_lowercase(), &self.value.to_lowercase()),
+            Collation::AsciiNumeric => {
+                // RFC 4790 only defines equality for this collation
+                matches!(self.match_type, MatchType::Equals)
+                    && ascii_numeric(text) == ascii_numeric(&self.value)
             }
-            _ => {
-                (match self.match_type {
-                    MatchType::Equals => text.to_lowercase() == self.value.to_lowercase(),
-                    MatchType::Contains => text.to_lowercase().contains(&self.value.to_lowercase()),
-                    MatchType::StartsWith => {
-                        text.to_lowercase().starts_with(&self.value.to_lowercase())
-                    }
-                    MatchType::EndsWith => {
-                        text.to_lowercase().ends_with(&self.value.to_lowercase())
-                    }
-                }) ^ self.negate
+            Collation::Unsupported(_) => false,
+        }) ^ self.negate
+    }
+}
+
+impl MatchType {
+    fn matches(&self, text: &str, value: &str) -> bool {
+        match self {
+            MatchType::Equals => text == value,
+            MatchType::Contains => text.contains(value),
+            MatchType::StartsWith => text.starts_with(value),
+            MatchType::EndsWith => text.ends_with(value),
+        }
+    }
+}
+
+impl<A, B, C> Filter<A, B, C> {
+    pub fn unsupported_collation(&self) -> Option<&str> {
+        match self {
+            Filter::Component {
+                op: FilterOp::TextMatch(text_match),
+                ..
+            }
+            | Filter::Property {
+                op: FilterOp::TextMatch(text_match),
+                ..
             }
+            | Filter::Parameter {
+                op: FilterOp::TextMatch(text_match),
+                ..
+            } => match &text_match.collation {
+                Collation::Unsupported(name) => Some(name),
+                _ => None,
+            },
+            _ => None,
         }
     }
 }
+
+/// Returns the numeric value of the leading digits, strings not starting
+/// with a digit are all equal to positive infinity.
+fn ascii_numeric(text: &str) -> Option<&str> {
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(text, |pos| &text[..pos]);
+    if !digits.is_empty() {
+        let value = digits.trim_start_matches('0');
+        Some(if value.is_empty() { "0" } else { value })
+    } else {
+        None
+    }
+}
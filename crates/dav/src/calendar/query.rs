@@ -6,7 +6,7 @@
 
 use super::freebusy::freebusy_in_range;
 use crate::{
-    DavError,
+    DavError, DavErrorCondition,
     common::{
         CalendarFilter, DavQuery,
         propfind::{PropFindItem, PropFindRequestHandler},
@@ -28,7 +28,7 @@ use dav_proto::{
     schema::{
         property::{CalDavProperty, CalendarData, DavProperty},
         request::{CalendarQuery, Filter, FilterOp, PropFind, Timezone},
-        response::MultiStatus,
+        response::{CalCondition, MultiStatus},
     },
 };
 use groupware::{
@@ -61,6 +61,18 @@ impl CalendarQueryRequestHandler for Server {
         headers: &RequestHeaders<'_>,
         request: CalendarQuery,
     ) -> crate::Result<HttpResponse> {
+        // Validate collations
+        if let Some(collation) = request
+            .filters
+            .iter()
+            .find_map(|filter| filter.unsupported_collation())
+        {
+            return Err(DavError::Condition(DavErrorCondition::new(
+                StatusCode::FORBIDDEN,
+                CalCondition::SupportedCollation(collation.to_string()),
+            )));
+        }
+
         // Validate URI
         let resource_ = self
             .validate_uri(access_token, headers.uri)
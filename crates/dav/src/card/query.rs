@@ -5,7 +5,7 @@
  */
 
 use crate::{
-    DavError,
+    DavError, DavErrorCondition,
     common::{
         AddressbookFilter, DavQuery,
         propfind::{PropFindItem, PropFindRequestHandler},
@@ -22,7 +22,7 @@ use dav_proto::{
     schema::{
         property::CardDavPropertyName,
         request::{AddressbookQuery, Filter, FilterOp, VCardPropertyWithGroup},
-        response::MultiStatus,
+        response::{CardCondition, MultiStatus},
     },
 };
 use groupware::cache::GroupwareCache;
@@ -48,6 +48,18 @@ impl CardQueryRequestHandler for Server {
         headers: &RequestHeaders<'_>,
         request: AddressbookQuery,
     ) -> crate::Result<HttpResponse> {
+        // Validate collations
+        if let Some(collation) = request
+            .filters
+            .iter()
+            .find_map(|filter| filter.unsupported_collation())
+        {
+            return Err(DavError::Condition(DavErrorCondition::new(
+                StatusCode::FORBIDDEN,
+                CardCondition::SupportedCollation(collation.to_string()),
+            )));
+        }
+
         // Validate URI
         let resource_ = self
             .validate_uri(access_token, headers.uri)
@@ -89,6 +89,18 @@ impl LdapDirectory {
                 .into_iter()
                 .map(|a| a.to_lowercase())
                 .collect(),
+            attr_enabled_protocols: config
+                .attr_enabled_protocols
+                .into_inner()
+                .into_iter()
+                .map(|a| a.to_lowercase())
+                .collect(),
+            attr_categorize_mail: config
+                .attr_categorize_mail
+                .into_inner()
+                .into_iter()
+                .map(|a| a.to_lowercase())
+                .collect(),
             group_class: config.group_class,
             attrs_principal: vec![],
         };
@@ -101,6 +113,8 @@ impl LdapDirectory {
             &mappings.attr_email_alias,
             &mappings.attr_email,
             &mappings.attr_class,
+            &mappings.attr_enabled_protocols,
+            &mappings.attr_categorize_mail,
         ] {
             mappings
                 .attrs_principal
@@ -12,6 +12,18 @@ use utils::sanitize_email;
 
 impl LdapDirectory {
     pub async fn authenticate(&self, credentials: &Credentials) -> trc::Result<Account> {
+        self.try_authenticate(credentials).await?.ok_or_else(|| {
+            trc::AuthEvent::Failed
+                .into_err()
+                .details("Authentication filter yielded no results")
+        })
+    }
+
+    // Returns None when no entry matches the login filter
+    pub async fn try_authenticate(
+        &self,
+        credentials: &Credentials,
+    ) -> trc::Result<Option<Account>> {
         let (username, secret) = match credentials {
             Credentials::Basic {
                 username, secret, ..
@@ -52,10 +64,7 @@ impl LdapDirectory {
                         .details(vec![result.dn, filter]));
                 }
             } else {
-                return Err(trc::AuthEvent::Failed
-                    .into_err()
-                    .details("Auth bind lookup filter yielded no results")
-                    .details(vec![filter]));
+                return Ok(None);
             }
         } else {
             let filter = self.mappings.filter_login.build(username);
@@ -79,16 +88,13 @@ impl LdapDirectory {
                 }
                 result
             } else {
-                return Err(trc::AuthEvent::Failed
-                    .into_err()
-                    .details("Authentication filter yielded no results")
-                    .details(vec![filter]));
+                return Ok(None);
             }
         };
 
         self.add_group_membership(&mut conn, &mut result).await?;
 
-        Ok(result.account)
+        Ok(Some(result.account))
     }
 
     pub async fn recipient(&self, address: &str) -> trc::Result<Recipient> {
@@ -230,7 +236,11 @@ struct LdapResult {
 
 impl LdapMappings {
     fn map_entry(&self, entry: SearchEntry) -> LdapResult {
-        let mut account = Account::default();
+        let mut account = Account {
+            // A missing attribute enables all protocols
+            enabled_protocols: (!self.attr_enabled_protocols.is_empty()).then(Vec::new),
+            ..Default::default()
+        };
         let mut is_group = false;
 
         for (attr, value) in entry.attrs {
@@ -263,6 +273,14 @@ impl LdapMappings {
                 }
             } else if self.attr_groups.contains(&attr) {
                 account.groups.get_or_insert_default().extend(value);
+            } else if self.attr_enabled_protocols.contains(&attr) {
+                for value in value {
+                    account.add_enabled_protocols(&value);
+                }
+            } else if self.attr_categorize_mail.contains(&attr) {
+                if let Some(value) = value.into_iter().next() {
+                    account.set_categorize_mail(&value);
+                }
             } else if self.attr_class.contains(&attr) {
                 for value in value {
                     is_group |= value.eq_ignore_ascii_case(&self.group_class);
@@ -30,6 +30,8 @@ pub struct LdapMappings {
     attr_secret_changed: Vec<String>,
     attr_email: Vec<String>,
     attr_email_alias: Vec<String>,
+    attr_enabled_protocols: Vec<String>,
+    attr_categorize_mail: Vec<String>,
     attrs_principal: Vec<String>,
     group_class: String,
 }
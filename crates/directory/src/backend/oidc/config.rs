@@ -24,6 +24,8 @@ impl OpenIdDirectory {
             claim_email: config.claim_username,
             claim_name: config.claim_name,
             claim_groups: config.claim_groups,
+            claim_enabled_protocols: config.claim_enabled_protocols,
+            claim_categorize_mail: config.claim_categorize_mail,
             default_domain: config.username_domain,
         })
         .await
@@ -98,6 +100,12 @@ impl OpenIdDirectory {
             if let Some(g) = &config.claim_groups {
                 check(g, "claim_groups");
             }
+            if let Some(p) = &config.claim_enabled_protocols {
+                check(p, "claim_enabled_protocols");
+            }
+            if let Some(c) = &config.claim_categorize_mail {
+                check(c, "claim_categorize_mail");
+            }
         }
 
         /*{
@@ -89,6 +89,16 @@ impl OpenIdDirectory {
                             .config
                             .claim_groups
                             .as_ref()
+                            .is_some_and(|claim| claims.get(claim).is_none())
+                        || self
+                            .config
+                            .claim_enabled_protocols
+                            .as_ref()
+                            .is_some_and(|claim| claims.get(claim).is_none())
+                        || self
+                            .config
+                            .claim_categorize_mail
+                            .as_ref()
                             .is_some_and(|claim| claims.get(claim).is_none());
 
                     if jwt_email.is_none() || missing_profile {
@@ -230,7 +240,7 @@ impl OpenIdDirectory {
         email: String,
         claims: &serde_json::Value,
     ) -> Result<Account, OidcError> {
-        Ok(Account {
+        let mut account = Account {
             email,
             email_aliases: Vec::new(),
             secret: None,
@@ -253,7 +263,37 @@ impl OpenIdDirectory {
                 .and_then(|name_claim| claims.get(name_claim))
                 .and_then(|v| v.as_str())
                 .map(|s| s.to_string()),
-        })
+            enabled_protocols: self
+                .config
+                .claim_enabled_protocols
+                .as_ref()
+                .map(|_| Vec::new()),
+            categorize_mail: None,
+        };
+
+        if let Some(protocols) = self
+            .config
+            .claim_enabled_protocols
+            .as_ref()
+            .and_then(|protocols_claim| claims.get(protocols_claim))
+        {
+            for protocol in extract_string_list(protocols) {
+                account.add_enabled_protocols(&protocol);
+            }
+        }
+
+        match self
+            .config
+            .claim_categorize_mail
+            .as_ref()
+            .and_then(|categorize_claim| claims.get(categorize_claim))
+        {
+            Some(serde_json::Value::Bool(value)) => account.categorize_mail = Some(*value),
+            Some(serde_json::Value::String(value)) => account.set_categorize_mail(value),
+            _ => {}
+        }
+
+        Ok(account)
     }
 
     fn resolve_email(&self, claims: &serde_json::Value) -> Result<String, OidcError> {
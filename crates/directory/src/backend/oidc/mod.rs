@@ -21,6 +21,8 @@ pub struct OidcConfig {
     pub claim_email: String,
     pub claim_name: Option<String>,
     pub claim_groups: Option<String>,
+    pub claim_enabled_protocols: Option<String>,
+    pub claim_categorize_mail: Option<String>,
     pub default_domain: Option<String>,
 }
 
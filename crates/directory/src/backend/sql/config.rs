@@ -52,6 +52,8 @@ impl SqlDirectory {
             column_secret: config.column_secret,
             column_type: config.column_class,
             column_description: config.column_description,
+            column_enabled_protocols: config.column_enabled_protocols,
+            column_categorize_mail: config.column_categorize_mail,
         };
 
         Ok(Directory::Sql(SqlDirectory {
@@ -12,6 +12,24 @@ use utils::sanitize_email;
 
 impl SqlDirectory {
     pub async fn authenticate(&self, credentials: &Credentials) -> trc::Result<Account> {
+        self.try_authenticate(credentials).await?.ok_or_else(|| {
+            let err = trc::AuthEvent::Failed
+                .into_err()
+                .details("SQL login query did not return an account");
+            match credentials {
+                Credentials::Basic { username, .. } => {
+                    err.ctx(trc::Key::AccountName, username.to_string())
+                }
+                Credentials::Bearer { .. } => err,
+            }
+        })
+    }
+
+    // Returns None when the login query does not return an account
+    pub async fn try_authenticate(
+        &self,
+        credentials: &Credentials,
+    ) -> trc::Result<Option<Account>> {
         let (username, secret) = match credentials {
             Credentials::Basic {
                 username, secret, ..
@@ -29,10 +47,7 @@ impl SqlDirectory {
                 .await
                 .caused_by(trc::location!())?,
         ) else {
-            return Err(trc::AuthEvent::Failed
-                .into_err()
-                .details("SQL login query did not return an account")
-                .ctx(trc::Key::AccountName, username.to_string()));
+            return Ok(None);
         };
 
         // Validate secret
@@ -89,7 +104,7 @@ impl SqlDirectory {
             account.email = sanitize_email(username).unwrap_or_else(|| username.to_lowercase());
         }
 
-        Ok(account)
+        Ok(Some(account))
     }
 
     pub async fn recipient(&self, address: &str) -> trc::Result<Recipient> {
@@ -151,7 +166,11 @@ impl SqlMappings {
             return Recipient::Invalid;
         }
 
-        let mut account = Account::default();
+        let mut account = Account {
+            // A missing or empty column enables all protocols
+            enabled_protocols: self.column_enabled_protocols.as_ref().map(|_| Vec::new()),
+            ..Default::default()
+        };
         let mut is_group = false;
 
         if let Some(row) = rows.rows.into_iter().next() {
@@ -175,6 +194,15 @@ impl SqlMappings {
                     && let Value::Text(text) = value
                 {
                     account.description = Some(text.into_owned());
+                } else if let Some(column_enabled_protocols) = &self.column_enabled_protocols
+                    && name.eq_ignore_ascii_case(column_enabled_protocols)
+                    && let Value::Text(text) = value
+                {
+                    account.add_enabled_protocols(&text);
+                } else if let Some(column_categorize_mail) = &self.column_categorize_mail
+                    && name.eq_ignore_ascii_case(column_categorize_mail)
+                {
+                    account.set_categorize_mail(&value.to_str());
                 }
             }
         }
@@ -24,4 +24,6 @@ pub(crate) struct SqlMappings {
     column_secret: String,
     column_type: Option<String>,
     column_description: Option<String>,
+    column_enabled_protocols: Option<String>,
+    column_categorize_mail: Option<String>,
 }
@@ -5,15 +5,20 @@
  */
 
 use crate::{
-    Directories,
+    Directories, Directory, DirectoryChain, DirectorySource,
     backend::{ldap::LdapDirectory, oidc::OpenIdDirectory, sql::SqlDirectory},
 };
-use registry::schema::{
-    prelude::ObjectType,
-    structs::{self, Authentication},
+use registry::{
+    schema::{
+        enums::DirectoryLookupMode,
+        prelude::ObjectType,
+        structs::{self, Authentication},
+    },
+    types::id::ObjectId,
 };
 use std::{collections::HashMap, sync::Arc};
 use store::registry::bootstrap::Bootstrap;
+use types::id::Id;
 
 impl Directories {
     pub async fn build(bp: &mut Bootstrap) -> Self {
@@ -55,9 +60,52 @@ impl Directories {
             None
         };
 
+        let sources = auth
+            .directory_chain
+            .values()
+            .filter_map(|entry| {
+                DirectorySource::parse(
+                    bp,
+                    &directories,
+                    ObjectType::Authentication.singleton(),
+                    entry.directory_id,
+                )
+            })
+            .collect();
+
         Directories {
             default_directory,
             directories,
+            chain: DirectoryChain {
+                sources,
+                merge_lookups: auth.directory_lookup_mode == DirectoryLookupMode::Merge,
+                cache_ttl: auth.directory_cache_ttl.into_inner(),
+            },
+        }
+    }
+}
+
+impl DirectorySource {
+    pub fn parse(
+        bp: &mut Bootstrap,
+        directories: &HashMap<u32, Arc<Directory>, nohash_hasher::BuildNoHashHasher<u32>>,
+        object_id: ObjectId,
+        directory_id: Option<Id>,
+    ) -> Option<Self> {
+        match directory_id {
+            Some(directory_id) => {
+                let id = directory_id.id() as u32;
+                if directories.contains_key(&id) {
+                    Some(DirectorySource::External(id))
+                } else {
+                    bp.build_error(
+                        object_id,
+                        format!("Chained directory with ID {directory_id} not found"),
+                    );
+                    None
+                }
+            }
+            None => Some(DirectorySource::Internal),
         }
     }
 }
@@ -17,6 +17,21 @@ impl Directory {
         .caused_by(trc::location!())
     }
 
+    // Returns None when the directory does not hold the account, as opposed
+    // to an error when the account exists but the credentials are rejected
+    pub async fn try_authenticate(
+        &self,
+        credentials: &Credentials,
+    ) -> trc::Result<Option<Account>> {
+        match &self {
+            Directory::Ldap(store) => store.try_authenticate(credentials).await,
+            Directory::Sql(store) => store.try_authenticate(credentials).await,
+            Directory::OpenId(_) if matches!(credentials, Credentials::Basic { .. }) => Ok(None),
+            Directory::OpenId(store) => store.authenticate(credentials).await.map(Some),
+        }
+        .caused_by(trc::location!())
+    }
+
     pub async fn recipient(&self, address: &str) -> trc::Result<Recipient> {
         match &self {
             Directory::Ldap(store) => store.recipient(address).await,
@@ -20,12 +20,15 @@ use sha1::Digest;
 use sha1::Sha1;
 use sha2::Sha256;
 use sha2::Sha512;
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::oneshot;
 use totp_rs::TOTP;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SecretVerificationResult {
     Valid,
+    /// Valid secret and TOTP code, with the time step the code belongs to.
+    ValidTotp(u64),
     Invalid,
     MissingMfaToken,
 }
@@ -38,20 +41,13 @@ pub async fn verify_mfa_secret_hash(
 ) -> trc::Result<SecretVerificationResult> {
     if let Some(totp_uri) = totp_uri {
         if let Some(totp_token) = totp_token {
-            let result = verify_secret_hash(hashed_secret, secret.as_bytes()).await?
-                && TOTP::from_url(totp_uri)
-                    .map_err(|err| {
-                        trc::AuthEvent::Error
-                            .reason(err)
-                            .details(totp_uri.to_string())
-                    })?
-                    .check_current(totp_token)
-                    .unwrap_or(false);
-            Ok(if result {
-                SecretVerificationResult::Valid
+            if verify_secret_hash(hashed_secret, secret.as_bytes()).await?
+                && let Some(step) = verify_totp(totp_uri, totp_token)?
+            {
+                Ok(SecretVerificationResult::ValidTotp(step))
             } else {
-                SecretVerificationResult::Invalid
-            })
+                Ok(SecretVerificationResult::Invalid)
+            }
         } else if !hashed_secret.is_empty()
             && !secret.is_empty()
             && verify_secret_hash(hashed_secret, secret.as_bytes()).await?
@@ -74,6 +70,33 @@ pub async fn verify_mfa_secret_hash(
     }
 }
 
+/// Verifies a TOTP code and returns the time step it was generated for.
+pub fn verify_totp(totp_uri: &str, totp_token: &str) -> trc::Result<Option<u64>> {
+    let totp = TOTP::from_url(totp_uri).map_err(|err| {
+        trc::AuthEvent::Error
+            .reason(err)
+            .details(totp_uri.to_string())
+    })?;
+    let period = totp.step.max(1);
+    let current = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+        / period;
+    let skew = totp.skew as u64;
+
+    Ok(
+        (current.saturating_sub(skew)..=current + skew).find(|&step| {
+            let code = totp.generate(step * period);
+            code.len() == totp_token.len()
+                && code
+                    .bytes()
+                    .zip(totp_token.bytes())
+                    .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+                    == 0
+        }),
+    )
+}
+
 async fn verify_hash_prefix(hashed_secret: &str, secret: &[u8]) -> trc::Result<bool> {
     let is_argon = hashed_secret.starts_with("$argon2");
     let is_pbkdf2 = !is_argon && hashed_secret.starts_with("$pbkdf2");
@@ -10,7 +10,8 @@ use crate::backend::oidc::OpenIdDirectory;
 use backend::{ldap::LdapDirectory, sql::SqlDirectory};
 use deadpool::managed::PoolError;
 use ldap3::LdapError;
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use registry::{schema::enums::ServiceProtocol, types::EnumImpl};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Duration};
 
 pub mod backend;
 pub mod core;
@@ -49,6 +50,8 @@ pub struct Account {
     pub secret: Option<String>,
     pub groups: Option<Vec<String>>,
     pub description: Option<String>,
+    pub enabled_protocols: Option<Vec<ServiceProtocol>>,
+    pub categorize_mail: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -58,10 +61,48 @@ pub struct Group {
     pub description: Option<String>,
 }
 
+impl Account {
+    // Protocols can be provided as multiple values or as a single
+    // comma-separated value, unknown protocols are ignored
+    pub(crate) fn add_enabled_protocols(&mut self, value: &str) {
+        let enabled_protocols = self.enabled_protocols.get_or_insert_default();
+        for protocol in value.split([',', ' ']) {
+            if let Some(protocol) = ServiceProtocol::parse(&protocol.trim().to_ascii_lowercase())
+                && !enabled_protocols.contains(&protocol)
+            {
+                enabled_protocols.push(protocol);
+            }
+        }
+    }
+
+    // Empty values leave the account setting unchanged
+    pub(crate) fn set_categorize_mail(&mut self, value: &str) {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" => {}
+            "true" | "yes" | "on" | "1" => self.categorize_mail = Some(true),
+            _ => self.categorize_mail = Some(false),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Directories {
     pub default_directory: Option<Arc<Directory>>,
     pub directories: HashMap<u32, Arc<Directory>, nohash_hasher::BuildNoHashHasher<u32>>,
+    pub chain: DirectoryChain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DirectorySource {
+    Internal,
+    External(u32),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DirectoryChain {
+    pub sources: Box<[DirectorySource]>,
+    pub merge_lookups: bool,
+    pub cache_ttl: Duration,
 }
 
 impl Debug for Directory {
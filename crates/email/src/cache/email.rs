@@ -130,7 +130,11 @@ fn insert_item(
                 item.keywords |= 1 << id;
             }
             Err(custom) => {
-                if let Some(idx) = cache.keywords.iter().position(|k| **k == *custom) {
+                if let Some(idx) = cache
+                    .keywords
+                    .iter()
+                    .position(|k| k.eq_ignore_ascii_case(custom))
+                {
                     item.keywords |= 1 << (OTHER + idx);
                 } else if cache.keywords.len() < (128 - OTHER) {
                     cache.keywords.push(custom.into());
@@ -194,6 +198,8 @@ pub trait MessageCacheAccess {
     fn expand_keywords(&self, message: &MessageCache) -> impl Iterator<Item = Keyword>;
 
     fn has_keyword(&self, message: &MessageCache, keyword: &Keyword) -> bool;
+
+    fn has_keyword_capacity(&self, keywords: &[Keyword]) -> bool;
 }
 
 impl MessageCacheAccess for MessageStoreCache {
@@ -307,6 +313,15 @@ impl MessageCacheAccess for MessageStoreCache {
     fn has_keyword(&self, message: &MessageCache, keyword: &Keyword) -> bool {
         keyword_to_id(self, keyword).is_some_and(|id| message.keywords & (1 << id) != 0)
     }
+
+    fn has_keyword_capacity(&self, keywords: &[Keyword]) -> bool {
+        // Custom keywords share a fixed number of slots per account
+        keywords
+            .iter()
+            .filter(|keyword| keyword_to_id(self, keyword).is_none())
+            .count()
+            <= (128 - OTHER).saturating_sub(self.emails.keywords.len())
+    }
 }
 
 fn email_insert(cache: &mut MessagesCacheBuilder, item: MessageCache) {
@@ -333,7 +348,7 @@ fn keyword_to_id(cache: &MessageStoreCache, keyword: &Keyword) -> Option<u32> {
             .emails
             .keywords
             .iter()
-            .position(|k| **k == *name)
+            .position(|k| k.eq_ignore_ascii_case(name))
             .map(|idx| (OTHER + idx) as u32),
     }
 }
@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::provision::AccountProvisioning;
 use common::{MessageStoreCache, Server, UpdateLock, cache::LockResult};
 use email::{full_email_cache_build, update_email_cache};
 use mailbox::{full_mailbox_cache_build, update_mailbox_cache};
@@ -47,7 +48,26 @@ impl MessageCacheFetch for Server {
                     Elapsed = start_time.elapsed(),
                 );
 
-                return Ok(cache);
+                // Apply the account template on first access, boxed as provisioning
+                // reads the message cache to create folders
+                return match Box::pin(self.provision_account(account_id)).await {
+                    Ok(true) => {
+                        let cache =
+                            full_cache_build(self, account_id, cache.update_lock.clone()).await?;
+                        cache_store.update(account_id, cache.clone());
+                        Ok(cache)
+                    }
+                    Ok(false) => Ok(cache),
+                    Err(err) => {
+                        // Retry on the next access
+                        cache_store.remove(&account_id);
+                        trc::error!(
+                            err.account_id(account_id)
+                                .details("Failed to provision account")
+                        );
+                        Ok(cache)
+                    }
+                };
             }
         };
 
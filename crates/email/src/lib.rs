@@ -10,6 +10,7 @@ pub mod cache;
 pub mod identity;
 pub mod mailbox;
 pub mod message;
+pub mod provision;
 pub mod push;
 pub mod sieve;
 pub mod submission;
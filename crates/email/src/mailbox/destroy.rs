@@ -6,7 +6,7 @@
 
 use super::*;
 use crate::{
-    cache::{MessageCacheFetch, email::MessageCacheAccess},
+    cache::{MessageCacheFetch, email::MessageCacheAccess, mailbox::MailboxCacheAccess},
     message::{delete::EmailDeletion, metadata::MessageData},
 };
 use common::{
@@ -25,7 +25,7 @@ use trc::AddContext;
 use types::{
     acl::Acl,
     collection::{Collection, VanishedCollection},
-    field::MailboxField,
+    field::{MailboxField, PrincipalField},
 };
 
 pub trait MailboxDestroy: Sync + Send {
@@ -210,6 +210,17 @@ impl MailboxDestroy for Server {
                 .clear(MailboxField::UidCounter)
                 .custom(ObjectIndexBuilder::<_, ()>::new().with_current(mailbox))
                 .caused_by(trc::location!())?;
+
+            // The Snoozed mailbox is created again on the next snooze
+            if cache
+                .mailbox_by_role(&SpecialUse::Snoozed)
+                .is_some_and(|mailbox| mailbox.document_id == document_id)
+            {
+                batch
+                    .with_collection(Collection::Principal)
+                    .with_document(0)
+                    .clear(PrincipalField::SnoozedMailboxId);
+            }
         } else {
             return Ok(Err(MailboxDestroyError::NotFound));
         };
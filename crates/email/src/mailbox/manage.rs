@@ -9,9 +9,9 @@ use crate::cache::MessageCacheFetch;
 use common::{Server, storage::index::ObjectIndexBuilder};
 use registry::schema::enums::StorageQuota;
 use std::future::Future;
-use store::write::BatchBuilder;
+use store::{SerializeInfallible, write::BatchBuilder};
 use trc::AddContext;
-use types::collection::Collection;
+use types::{collection::Collection, field::PrincipalField};
 
 pub trait MailboxFnc: Sync + Send {
     fn create_system_folders(
@@ -24,6 +24,13 @@ pub trait MailboxFnc: Sync + Send {
         account_id: u32,
         path: &str,
     ) -> impl Future<Output = trc::Result<Option<u32>>> + Send;
+
+    fn mailbox_create_path_subscribed(
+        &self,
+        account_id: u32,
+        path: &str,
+        subscribe: bool,
+    ) -> impl Future<Output = trc::Result<Option<u32>>> + Send;
 }
 
 impl MailboxFnc for Server {
@@ -73,6 +80,12 @@ impl MailboxFnc for Server {
             .await
             .caused_by(trc::location!())?;
 
+        // Flag the account as pending provisioning
+        batch
+            .with_collection(Collection::Principal)
+            .with_document(0)
+            .set(PrincipalField::Provisioned, 0u64.serialize());
+
         self.core
             .storage
             .data
@@ -84,6 +97,16 @@ impl MailboxFnc for Server {
     }
 
     async fn mailbox_create_path(&self, account_id: u32, path: &str) -> trc::Result<Option<u32>> {
+        self.mailbox_create_path_subscribed(account_id, path, false)
+            .await
+    }
+
+    async fn mailbox_create_path_subscribed(
+        &self,
+        account_id: u32,
+        path: &str,
+        subscribe: bool,
+    ) -> trc::Result<Option<u32>> {
         let cache = self
             .get_cached_messages(account_id)
             .await
@@ -146,14 +169,15 @@ impl MailboxFnc for Server {
             for name in create_paths {
                 let document_id = next_document_id;
                 next_document_id -= 1;
+                let mut mailbox = Mailbox::new(name).with_parent_id(next_parent_id);
+                if subscribe {
+                    mailbox.add_subscriber(account_id);
+                }
                 batch
                     .with_account_id(account_id)
                     .with_collection(Collection::Mailbox)
                     .with_document(document_id)
-                    .custom(
-                        ObjectIndexBuilder::<(), _>::new()
-                            .with_changes(Mailbox::new(name).with_parent_id(next_parent_id)),
-                    )
+                    .custom(ObjectIndexBuilder::<(), _>::new().with_changes(mailbox))
                     .caused_by(trc::location!())?;
                 next_parent_id = document_id + 1;
             }
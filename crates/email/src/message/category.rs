@@ -0,0 +1,385 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::metadata::{ArchivedMessageData, MessageDataBuilder, MessageMetadata};
+use crate::{
+    cache::{MessageCacheFetch, mailbox::MailboxCacheAccess},
+    mailbox::{JUNK_ID, TRASH_ID},
+};
+use common::{KV_CATEGORY_SENDER, Server, config::mailstore::email::MailCategoryRule};
+use mail_parser::Message;
+use std::{future::Future, sync::Arc};
+use store::{
+    ValueKey,
+    dispatch::lookup::KeyValue,
+    write::{AlignedBytes, Archive, Archiver, BatchBuilder},
+};
+use trc::AddContext;
+use types::{
+    collection::Collection,
+    field::{EmailField, PrincipalField},
+    keyword::{ArchivedKeyword, Keyword},
+};
+use utils::sanitize_email;
+
+pub const CATEGORY_KEYWORD_PREFIX: &str = "$category:";
+
+// Senders are counted until no messages are received from them for this long
+const SENDER_MESSAGES_TTL: u64 = 30 * 86400;
+
+// The least recently assigned senders are forgotten past this limit
+const MAX_CATEGORY_OVERRIDES: usize = 1000;
+
+// Categories chosen by the user for each sender, an empty category keeps
+// the messages of the sender in the Inbox
+#[derive(
+    rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Default, Debug, Clone, PartialEq, Eq,
+)]
+pub struct CategoryOverrides {
+    pub senders: Vec<CategoryOverride>,
+}
+
+#[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CategoryOverride {
+    pub sender: String,
+    pub category: String,
+}
+
+pub trait EmailCategoryFnc: Sync + Send {
+    fn email_category(
+        &self,
+        account_id: u32,
+        message: &Message<'_>,
+        spam_tags: &[String],
+    ) -> impl Future<Output = trc::Result<Option<Arc<MailCategoryRule>>>> + Send;
+
+    fn email_category_override(
+        &self,
+        account_id: u32,
+        sender: &str,
+        category: Option<&str>,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn email_category_reset(&self, account_id: u32)
+    -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn email_category_feedback(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        prev_data: &ArchivedMessageData,
+        new_data: &MessageDataBuilder,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+}
+
+impl EmailCategoryFnc for Server {
+    async fn email_category(
+        &self,
+        account_id: u32,
+        message: &Message<'_>,
+        spam_tags: &[String],
+    ) -> trc::Result<Option<Arc<MailCategoryRule>>> {
+        let categories = &self.core.email.categories;
+        let Some(sender) = message
+            .from()
+            .and_then(|from| from.first())
+            .and_then(|from| from.address())
+            .and_then(sanitize_email)
+        else {
+            return Ok(None);
+        };
+
+        // Number of earlier messages received by this account from the sender
+        let sender_messages = if categories
+            .iter()
+            .any(|rule| rule.min_sender_messages.is_some())
+        {
+            self.in_memory_store()
+                .counter_incr(
+                    KeyValue::new(sender_key(account_id, &sender), 1).expires(SENDER_MESSAGES_TTL),
+                    true,
+                )
+                .await
+                .caused_by(trc::location!())?
+                .saturating_sub(1)
+                .max(0) as u64
+        } else {
+            0
+        };
+
+        // Categories chosen by the user for this sender take precedence
+        if let Some(overrides_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Principal,
+                0,
+                PrincipalField::CategoryOverrides,
+            ))
+            .await
+            .caused_by(trc::location!())?
+            && let Some(category) = overrides_
+                .unarchive::<CategoryOverrides>()
+                .caused_by(trc::location!())?
+                .senders
+                .iter()
+                .find(|item| item.sender.as_str() == sender)
+        {
+            return Ok(categories
+                .iter()
+                .find(|rule| rule.name == category.category.as_str())
+                .cloned());
+        }
+
+        // Only the tags added by the local spam filter are trusted, along with
+        // those derived from the headers
+        let mut tags = spam_tags.iter().map(String::as_str).collect::<Vec<_>>();
+        if message.header_raw("List-Id").is_some() {
+            tags.push("HAS_LIST_ID");
+        }
+        if message.header_raw("List-Unsubscribe").is_some() {
+            tags.push("HAS_LIST_UNSUB");
+        }
+        if message
+            .header_raw("Precedence")
+            .is_some_and(|value| matches!(value.trim().to_lowercase().as_str(), "bulk" | "list"))
+        {
+            tags.push("PRECEDENCE_BULK");
+        }
+        tags.sort_unstable();
+        tags.dedup();
+
+        let mut text = message.subject().unwrap_or_default().to_lowercase();
+        if categories.iter().any(|rule| !rule.keywords.is_empty())
+            && let Some(body) = message.body_text(0)
+        {
+            text.push(' ');
+            text.push_str(&body.to_lowercase());
+        }
+
+        let mut best: Option<(usize, &Arc<MailCategoryRule>)> = None;
+        for rule in categories {
+            let mut signals = tags.iter().filter(|tag| rule.tags.contains(**tag)).count();
+            if rule.keywords.iter().any(|keyword| text.contains(keyword)) {
+                signals += 1;
+            }
+            if rule
+                .min_sender_messages
+                .is_some_and(|min_messages| sender_messages >= min_messages)
+            {
+                signals += 1;
+            }
+
+            if signals >= rule.min_matches
+                && best.is_none_or(|(best_signals, _)| signals > best_signals)
+            {
+                best = Some((signals, rule));
+            }
+        }
+
+        Ok(best.map(|(_, rule)| rule.clone()))
+    }
+
+    async fn email_category_override(
+        &self,
+        account_id: u32,
+        sender: &str,
+        category: Option<&str>,
+    ) -> trc::Result<()> {
+        let category = category.unwrap_or_default();
+        let mut retry_count = 0;
+
+        loop {
+            let overrides_ = self
+                .store()
+                .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                    account_id,
+                    Collection::Principal,
+                    0,
+                    PrincipalField::CategoryOverrides,
+                ))
+                .await
+                .caused_by(trc::location!())?;
+            let mut overrides = if let Some(overrides_) = &overrides_ {
+                overrides_
+                    .deserialize::<CategoryOverrides>()
+                    .caused_by(trc::location!())?
+            } else {
+                CategoryOverrides::default()
+            };
+            if overrides
+                .senders
+                .last()
+                .is_some_and(|item| item.sender == sender && item.category == category)
+            {
+                return Ok(());
+            }
+
+            overrides.senders.retain(|item| item.sender != sender);
+            overrides.senders.push(CategoryOverride {
+                sender: sender.to_string(),
+                category: category.to_string(),
+            });
+            if overrides.senders.len() > MAX_CATEGORY_OVERRIDES {
+                let excess = overrides.senders.len() - MAX_CATEGORY_OVERRIDES;
+                overrides.senders.drain(..excess);
+            }
+
+            let mut batch = BatchBuilder::new();
+            batch
+                .with_account_id(account_id)
+                .with_collection(Collection::Principal)
+                .with_document(0);
+            if let Some(overrides_) = overrides_ {
+                batch.assert_value(PrincipalField::CategoryOverrides, overrides_);
+            }
+            batch.set(
+                PrincipalField::CategoryOverrides,
+                Archiver::new(overrides)
+                    .serialize()
+                    .caused_by(trc::location!())?,
+            );
+
+            match self.commit_batch(batch).await {
+                Ok(_) => return Ok(()),
+                Err(err) if err.is_assertion_failure() && retry_count < 3 => {
+                    retry_count += 1;
+                }
+                Err(err) => return Err(err.caused_by(trc::location!())),
+            }
+        }
+    }
+
+    async fn email_category_reset(&self, account_id: u32) -> trc::Result<()> {
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Principal)
+            .with_document(0)
+            .clear(PrincipalField::CategoryOverrides);
+        self.commit_batch(batch)
+            .await
+            .caused_by(trc::location!())
+            .map(|_| ())
+    }
+
+    async fn email_category_feedback(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        prev_data: &ArchivedMessageData,
+        new_data: &MessageDataBuilder,
+    ) -> trc::Result<()> {
+        let categories = &self.core.email.categories;
+        let Some(prev_category) = prev_data.keywords.iter().find_map(|keyword| match keyword {
+            ArchivedKeyword::Other(keyword) => category_name(keyword).and_then(|name| {
+                categories
+                    .iter()
+                    .find(|rule| rule.name.eq_ignore_ascii_case(name))
+            }),
+            _ => None,
+        }) else {
+            return Ok(());
+        };
+
+        // Deleting or reporting a message as spam does not change its category
+        if new_data
+            .mailboxes
+            .iter()
+            .any(|mailbox| mailbox.mailbox_id == TRASH_ID || mailbox.mailbox_id == JUNK_ID)
+        {
+            return Ok(());
+        }
+
+        let cache = self
+            .get_cached_messages(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        let category_mailbox_id = |rule: &MailCategoryRule| {
+            rule.mailbox_name
+                .as_deref()
+                .and_then(|name| cache.mailbox_by_path(name))
+                .map(|mailbox| mailbox.document_id)
+        };
+        let has_category = |rule: &MailCategoryRule| {
+            new_data.keywords.iter().any(|keyword| match keyword {
+                Keyword::Other(keyword) => {
+                    category_name(keyword).is_some_and(|name| name.eq_ignore_ascii_case(&rule.name))
+                }
+                _ => false,
+            })
+        };
+        let is_removed = !has_category(prev_category)
+            || category_mailbox_id(prev_category).is_some_and(|mailbox_id| {
+                prev_data.has_mailbox_id(mailbox_id)
+                    && !new_data
+                        .mailboxes
+                        .iter()
+                        .any(|mailbox| mailbox.mailbox_id == mailbox_id)
+            });
+        if !is_removed {
+            return Ok(());
+        }
+
+        // Moving a message into the folder of another category reassigns the sender
+        let new_category = categories
+            .iter()
+            .filter(|rule| rule.name != prev_category.name)
+            .find(|rule| {
+                category_mailbox_id(rule).is_some_and(|mailbox_id| {
+                    new_data
+                        .mailboxes
+                        .iter()
+                        .any(|mailbox| mailbox.mailbox_id == mailbox_id)
+                }) || has_category(rule)
+            });
+
+        let Some(metadata_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(());
+        };
+        let Some(sender) = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?
+            .root_part()
+            .from()
+            .and_then(sanitize_email)
+        else {
+            return Ok(());
+        };
+
+        self.email_category_override(
+            account_id,
+            &sender,
+            new_category.map(|rule| rule.name.as_str()),
+        )
+        .await
+    }
+}
+
+fn category_name(keyword: &str) -> Option<&str> {
+    keyword
+        .get(..CATEGORY_KEYWORD_PREFIX.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(CATEGORY_KEYWORD_PREFIX))
+        .map(|_| &keyword[CATEGORY_KEYWORD_PREFIX.len()..])
+}
+
+fn sender_key(account_id: u32, sender: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(5 + sender.len());
+    key.push(KV_CATEGORY_SENDER);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key.extend_from_slice(sender.as_bytes());
+    key
+}
@@ -7,6 +7,7 @@
 use super::{
     ingest::{EmailIngest, IngestedEmail},
     metadata::{MessageData, MessageMetadata},
+    shared::{BlobReferences, EmailSharedBlob, MessageReferences},
 };
 use crate::{
     mailbox::UidMailbox,
@@ -29,7 +30,7 @@ use registry::{
 };
 use store::write::{BatchBuilder, IndexPropertyClass, ValueClass};
 use store::{
-    ValueKey,
+    SerializeInfallible, ValueKey,
     write::{AlignedBytes, Archive},
 };
 use trc::AddContext;
@@ -90,10 +91,28 @@ impl EmailCopy for Server {
             return Ok(Err(CopyMessageError::NotFound));
         };
 
-        // Check quota
+        // Obtain the contents referenced by the message
         let size = metadata.root_part().offset_end;
+        let references = self
+            .store()
+            .get_value::<MessageReferences>(ValueKey::property(
+                from_account_id,
+                Collection::Email,
+                from_message_id,
+                EmailField::References,
+            ))
+            .await
+            .caused_by(trc::location!())?
+            .unwrap_or_else(|| MessageReferences::message(metadata.blob_hash.clone(), size));
+
+        // Check quota, contents already stored for another message in the
+        // account are not charged again
         let to_account = self.account(to_account_id).await?;
-        match self.has_available_quota(&to_account, size as u64).await {
+        let charge = self
+            .blob_reference_charge(to_account_id, &references)
+            .await
+            .caused_by(trc::location!())?;
+        match self.has_available_quota(&to_account, charge).await {
             Ok(_) => (),
             Err(err) => {
                 if err.matches(trc::EventType::Limit(trc::LimitEvent::Quota))
@@ -229,6 +248,12 @@ impl EmailCopy for Server {
         metadata
             .index(&mut batch, true)
             .caused_by(trc::location!())?;
+        let mut blob_references = BlobReferences::new(to_account_id, tenant_id);
+        blob_references.add(&references);
+        batch.set(EmailField::References, references.serialize());
+        self.write_blob_references(&mut batch, blob_references)
+            .await
+            .caused_by(trc::location!())?;
 
         // Insert and obtain ids
         let change_id = self
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use aes::cipher::{BlockModeEncrypt, KeyIvInit, block_padding::Pkcs7};
+use aes::cipher::{BlockModeDecrypt, BlockModeEncrypt, KeyIvInit, block_padding::Pkcs7};
 use aes_gcm::{
     Aes256Gcm,
     aead::{AeadInPlace, KeyInit, generic_array::GenericArray},
@@ -16,9 +16,15 @@ use common::auth::{
     ACCOUNT_FLAG_ENCRYPT_TRAIN_SPAM_FILTER, EncryptionKeys,
 };
 use mail_builder::{encoders::base64::base64_encode_mime, mime::make_boundary};
-use mail_parser::{Message, MimeHeaders, PartType};
+use mail_parser::{Message, MimeHeaders, PartType, decoders::base64::base64_decode};
 use openpgp::{
-    parse::Parse,
+    Cert, KeyHandle,
+    crypto::SessionKey,
+    packet::{PKESK, SKESK},
+    parse::{
+        Parse,
+        stream::{DecryptionHelper, DecryptorBuilder, MessageStructure, VerificationHelper},
+    },
     serialize::stream,
     types::{KeyFlags, SymmetricAlgorithm},
 };
@@ -32,9 +38,14 @@ use rasn_cms::{
     algorithms::{AES128_CBC, AES256_CBC, RSA},
     pkcs7_compat::EncapsulatedContentInfo,
 };
-use rsa::{Oaep, Pkcs1v15Encrypt, RsaPublicKey, pkcs1::DecodeRsaPublicKey, sha2::Sha256};
+use rsa::{
+    Oaep, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
+    pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey},
+    pkcs8::DecodePrivateKey,
+    sha2::Sha256,
+};
 use sequoia_openpgp as openpgp;
-use std::io::Cursor;
+use std::{borrow::Cow, io::Cursor};
 
 const AES256_GCM: &Oid =
     Oid::JOINT_ISO_ITU_T_COUNTRY_US_ORGANIZATION_GOV_CSOR_NIST_ALGORITHMS_AES256_GCM;
@@ -60,6 +71,7 @@ pub trait EncryptMessage {
         flags: u64,
     ) -> Result<Vec<u8>, EncryptMessageError>;
     fn is_encrypted(&self) -> bool;
+    fn remove_contents(&mut self);
 }
 
 impl EncryptMessage for Message<'_> {
@@ -392,6 +404,265 @@ impl EncryptMessage for Message<'_> {
 
         false
     }
+
+    fn remove_contents(&mut self) {
+        for part in &mut self.parts {
+            match &mut part.body {
+                PartType::Text(txt) | PartType::Html(txt) => {
+                    *txt = Cow::from("");
+                }
+                PartType::Binary(bin) | PartType::InlineBinary(bin) => {
+                    *bin = Cow::from(&[][..]);
+                }
+                PartType::Message(_) => {
+                    part.body = PartType::Binary(Cow::from(&[][..]));
+                }
+                PartType::Multipart(_) => (),
+            }
+        }
+    }
+}
+
+#[allow(async_fn_in_trait)]
+pub trait DecryptMessage {
+    async fn decrypt(&self, keys: &[String]) -> Result<Vec<u8>, EncryptMessageError>;
+}
+
+impl DecryptMessage for Message<'_> {
+    async fn decrypt(&self, keys: &[String]) -> Result<Vec<u8>, EncryptMessageError> {
+        let root = self.root_part();
+        let raw_message = self.raw_message();
+        let mut message = Vec::with_capacity(raw_message.len());
+
+        // Keep the headers that were not moved to the encrypted part
+        for header in root.headers() {
+            if !header.name.is_mime_header() {
+                message.extend_from_slice(
+                    &raw_message[header.offset_field() as usize..header.offset_end() as usize],
+                );
+            }
+        }
+
+        // Decrypt inner message
+        let keys = keys.to_vec();
+        let is_pgp = self.content_type().is_some_and(|ct| {
+            ct.c_type.eq_ignore_ascii_case("multipart")
+                && ct
+                    .c_subtype
+                    .as_ref()
+                    .is_some_and(|st| st.eq_ignore_ascii_case("encrypted"))
+        });
+        let inner_message = if is_pgp {
+            let contents = self
+                .parts
+                .iter()
+                .skip(1)
+                .find(|part| {
+                    part.content_type().is_some_and(|ct| {
+                        ct.c_subtype
+                            .as_ref()
+                            .is_some_and(|st| st.eq_ignore_ascii_case("octet-stream"))
+                    })
+                })
+                .map(|part| part.contents().to_vec())
+                .ok_or_else(|| {
+                    EncryptMessageError::Error("Missing OpenPGP encrypted part".into())
+                })?;
+            tokio::task::spawn_blocking(move || decrypt_pgp(&keys, &contents))
+        } else {
+            let contents = root.contents().to_vec();
+            tokio::task::spawn_blocking(move || decrypt_smime(&keys, &contents))
+        }
+        .await
+        .map_err(|err| {
+            EncryptMessageError::Error(format!("Failed to decrypt message: {}", err))
+        })??;
+        message.extend_from_slice(&inner_message);
+
+        Ok(message)
+    }
+}
+
+fn decrypt_pgp(keys: &[String], contents: &[u8]) -> Result<Vec<u8>, EncryptMessageError> {
+    let policy = openpgp::policy::StandardPolicy::new();
+
+    for key in keys {
+        let Some(cert) = Cert::from_bytes(key.as_bytes())
+            .ok()
+            .filter(|cert| cert.is_tsk())
+        else {
+            continue;
+        };
+        let Ok(mut decryptor) = DecryptorBuilder::from_bytes(contents)
+            .and_then(|builder| builder.with_policy(&policy, None, SecretKeyHelper { cert }))
+        else {
+            continue;
+        };
+        let mut message = Vec::with_capacity(contents.len());
+        if std::io::copy(&mut decryptor, &mut message).is_ok() {
+            return Ok(message);
+        }
+    }
+
+    Err(EncryptMessageError::Error(
+        "No private key available to decrypt the OpenPGP message".into(),
+    ))
+}
+
+fn decrypt_smime(keys: &[String], contents: &[u8]) -> Result<Vec<u8>, EncryptMessageError> {
+    let private_keys = keys
+        .iter()
+        .flat_map(|key| parse_private_keys(key))
+        .collect::<Vec<_>>();
+    if private_keys.is_empty() {
+        return Err(EncryptMessageError::Error(
+            "No private key available to decrypt the S/MIME message".into(),
+        ));
+    }
+
+    let content_info = rasn::der::decode::<EncapsulatedContentInfo>(contents).map_err(|err| {
+        EncryptMessageError::Error(format!("Failed to decode ContentInfo: {}", err))
+    })?;
+    let content = content_info
+        .content
+        .as_ref()
+        .map(|content| content.as_bytes())
+        .ok_or_else(|| EncryptMessageError::Error("Missing enveloped content".into()))?;
+    let (recipient_infos, encrypted_content_info, mac) =
+        if *content_info.content_type == *CONTENT_AUTH_ENVELOPED_DATA {
+            let data = rasn::der::decode::<AuthEnvelopedData>(content).map_err(|err| {
+                EncryptMessageError::Error(format!("Failed to decode AuthEnvelopedData: {}", err))
+            })?;
+            (
+                data.recipient_infos,
+                data.auth_encrypted_content_info,
+                Some(data.mac),
+            )
+        } else if *content_info.content_type == *CONTENT_ENVELOPED_DATA {
+            let data = rasn::der::decode::<EnvelopedData>(content).map_err(|err| {
+                EncryptMessageError::Error(format!("Failed to decode EnvelopedData: {}", err))
+            })?;
+            (data.recipient_infos, data.encrypted_content_info, None)
+        } else {
+            return Err(EncryptMessageError::Error(
+                "Unsupported S/MIME content type".into(),
+            ));
+        };
+
+    // Decrypt the content encryption key
+    let key = recipient_infos
+        .to_vec()
+        .into_iter()
+        .filter_map(|info| match info {
+            RecipientInfo::KeyTransRecipientInfo(info) => Some(info),
+            _ => None,
+        })
+        .find_map(|info| {
+            private_keys.iter().find_map(|private_key| {
+                if *info.key_encryption_algorithm.algorithm == *RSAES_OAEP {
+                    private_key.decrypt(Oaep::new::<Sha256>(), &info.encrypted_key)
+                } else {
+                    private_key.decrypt(Pkcs1v15Encrypt, &info.encrypted_key)
+                }
+                .ok()
+            })
+        })
+        .ok_or_else(|| {
+            EncryptMessageError::Error("No private key matches the message recipients".into())
+        })?;
+
+    // Decrypt contents
+    let algorithm = &encrypted_content_info.content_encryption_algorithm;
+    let cipher = SymmetricCipher::from_oid(&algorithm.algorithm).ok_or_else(|| {
+        EncryptMessageError::Error("Unsupported content encryption algorithm".into())
+    })?;
+    cipher.decrypt(
+        &key,
+        algorithm
+            .parameters
+            .as_ref()
+            .map(|parameters| parameters.as_bytes())
+            .unwrap_or_default(),
+        encrypted_content_info
+            .encrypted_content
+            .as_deref()
+            .ok_or_else(|| EncryptMessageError::Error("Missing encrypted content".into()))?,
+        mac.as_deref(),
+    )
+}
+
+fn parse_private_keys(text: &str) -> Vec<RsaPrivateKey> {
+    let mut keys = Vec::new();
+    let mut block: Option<(&str, Vec<u8>)> = None;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(tag) = line
+            .strip_prefix("-----BEGIN ")
+            .and_then(|line| line.strip_suffix("-----"))
+        {
+            block = Some((tag, Vec::new()));
+        } else if line.starts_with("-----END ") {
+            if let Some((tag, data)) = block.take()
+                && let Some(der) = base64_decode(&data)
+            {
+                keys.extend(match tag {
+                    "PRIVATE KEY" => RsaPrivateKey::from_pkcs8_der(&der).ok(),
+                    "RSA PRIVATE KEY" => RsaPrivateKey::from_pkcs1_der(&der).ok(),
+                    _ => None,
+                });
+            }
+        } else if let Some((_, data)) = &mut block {
+            data.extend_from_slice(line.as_bytes());
+        }
+    }
+
+    keys
+}
+
+struct SecretKeyHelper {
+    cert: Cert,
+}
+
+impl VerificationHelper for SecretKeyHelper {
+    fn get_certs(&mut self, _: &[KeyHandle]) -> openpgp::Result<Vec<Cert>> {
+        Ok(Vec::new())
+    }
+
+    fn check(&mut self, _: MessageStructure) -> openpgp::Result<()> {
+        Ok(())
+    }
+}
+
+impl DecryptionHelper for SecretKeyHelper {
+    fn decrypt(
+        &mut self,
+        pkesks: &[PKESK],
+        _: &[SKESK],
+        sym_algo: Option<SymmetricAlgorithm>,
+        decrypt: &mut dyn FnMut(Option<SymmetricAlgorithm>, &SessionKey) -> bool,
+    ) -> openpgp::Result<Option<Cert>> {
+        let policy = openpgp::policy::StandardPolicy::new();
+
+        for key in self
+            .cert
+            .keys()
+            .with_policy(&policy, None)
+            .supported()
+            .unencrypted_secret()
+            .key_flags(KeyFlags::empty().set_transport_encryption())
+        {
+            let mut keypair = key.key().clone().into_keypair()?;
+            for pkesk in pkesks {
+                if let Some((algo, session_key)) = pkesk.decrypt(&mut keypair, sym_algo)
+                    && decrypt(algo, &session_key)
+                {
+                    return Ok(Some(self.cert.clone()));
+                }
+            }
+        }
+
+        Err(openpgp::Error::InvalidOperation("No matching private key".into()).into())
+    }
 }
 
 pub trait EncryptionFlags {
@@ -435,6 +706,20 @@ pub enum SymmetricCipher {
 }
 
 impl SymmetricCipher {
+    fn from_oid(oid: &Oid) -> Option<Self> {
+        if oid == AES128_CBC {
+            Some(SymmetricCipher::Aes128Cbc)
+        } else if oid == AES256_CBC {
+            Some(SymmetricCipher::Aes256Cbc)
+        } else if oid == AES256_GCM {
+            Some(SymmetricCipher::Aes256Gcm)
+        } else if oid == CHACHA20_POLY1305 {
+            Some(SymmetricCipher::ChaCha20Poly1305)
+        } else {
+            None
+        }
+    }
+
     fn key_size(self) -> usize {
         match self {
             SymmetricCipher::Aes128Cbc => 16,
@@ -491,6 +776,70 @@ impl SymmetricCipher {
         }
     }
 
+    fn decrypt(
+        self,
+        key: &[u8],
+        parameters: &[u8],
+        contents: &[u8],
+        mac: Option<&[u8]>,
+    ) -> Result<Vec<u8>, EncryptMessageError> {
+        let nonce = if self == SymmetricCipher::Aes256Gcm {
+            rasn::der::decode::<GcmParameters>(parameters).map(|parameters| parameters.nonce)
+        } else {
+            rasn::der::decode::<OctetString>(parameters)
+        }
+        .map_err(|err| {
+            EncryptMessageError::Error(format!("Failed to decode cipher parameters: {}", err))
+        })?;
+        if key.len() != self.key_size() || nonce.len() != self.nonce_size() {
+            return Err(EncryptMessageError::Error(
+                "Invalid content encryption key or nonce length".into(),
+            ));
+        }
+
+        match self {
+            SymmetricCipher::Aes128Cbc => {
+                cbc::Decryptor::<aes::Aes128>::new_from_slices(key, &nonce)
+                    .expect("invalid key or iv length")
+                    .decrypt_padded_vec::<Pkcs7>(contents)
+                    .ok()
+            }
+            SymmetricCipher::Aes256Cbc => {
+                cbc::Decryptor::<aes::Aes256>::new_from_slices(key, &nonce)
+                    .expect("invalid key or iv length")
+                    .decrypt_padded_vec::<Pkcs7>(contents)
+                    .ok()
+            }
+            SymmetricCipher::Aes256Gcm | SymmetricCipher::ChaCha20Poly1305 => {
+                let mac = mac.filter(|mac| mac.len() == 16).ok_or_else(|| {
+                    EncryptMessageError::Error("Missing or invalid authentication tag".into())
+                })?;
+                let mut buffer = contents.to_vec();
+                let result = if self == SymmetricCipher::Aes256Gcm {
+                    Aes256Gcm::new_from_slice(key)
+                        .expect("invalid key length")
+                        .decrypt_in_place_detached(
+                            GenericArray::from_slice(&nonce),
+                            b"",
+                            &mut buffer,
+                            GenericArray::from_slice(mac),
+                        )
+                } else {
+                    ChaCha20Poly1305::new_from_slice(key)
+                        .expect("invalid key length")
+                        .decrypt_in_place_detached(
+                            GenericArray::from_slice(&nonce),
+                            b"",
+                            &mut buffer,
+                            GenericArray::from_slice(mac),
+                        )
+                };
+                result.is_ok().then_some(buffer)
+            }
+        }
+        .ok_or_else(|| EncryptMessageError::Error("Failed to decrypt message contents".into()))
+    }
+
     fn content_encryption_algorithm(
         self,
         nonce: &[u8],
@@ -556,7 +905,7 @@ impl SymmetricCipher {
     }
 }
 
-#[derive(rasn::AsnType, rasn::Encode)]
+#[derive(rasn::AsnType, rasn::Encode, rasn::Decode)]
 struct GcmParameters {
     nonce: OctetString,
     icv_len: u8,
@@ -5,14 +5,17 @@
  */
 
 use super::metadata::MessageData;
-use crate::cache::{MessageCacheFetch, email::MessageCacheAccess};
+use crate::{
+    cache::{MessageCacheFetch, email::MessageCacheAccess},
+    push::PushSubscriptions,
+};
 use common::{Server, storage::index::ObjectIndexBuilder};
 use groupware::calendar::storage::ItipAutoExpunge;
 use registry::schema::enums::IndexDocumentType;
 use registry::schema::structs::{Task, TaskIndexDocument, TaskStatus};
 use std::future::Future;
 use store::write::key::DeserializeBigEndian;
-use store::write::{IndexPropertyClass, now};
+use store::write::{AlignedBytes, Archive, IndexPropertyClass, now};
 use store::{IterateParams, U32_LEN, U64_LEN, ValueKey};
 use store::{
     roaring::RoaringBitmap,
@@ -20,7 +23,8 @@ use store::{
 };
 use trc::AddContext;
 use types::collection::{Collection, SyncCollection, VanishedCollection};
-use types::field::{EmailField, EmailSubmissionField};
+use types::field::{EmailField, EmailSubmissionField, PrincipalField};
+use utils::snowflake::SnowflakeIdGenerator;
 
 pub trait EmailDeletion: Sync + Send {
     fn emails_delete(
@@ -91,6 +95,8 @@ impl EmailDeletion for Server {
                             .with_current(metadata),
                     )
                     .caused_by(trc::location!())?
+                    .clear(ValueClass::Property(EmailField::Snooze.into()))
+                    .clear(ValueClass::Property(EmailField::FollowUp.into()))
                     .schedule_task(Task::UnindexDocument(TaskIndexDocument {
                         account_id: account_id.into(),
                         document_id: document_id.into(),
@@ -170,10 +176,41 @@ impl EmailDeletion for Server {
                 .caused_by(trc::location!())?;
         }
 
-        // Purge changelogs
+        // Purge changelogs, keeping the states that clients may still sync from
+        let mut floor = None;
+        if !self.core.email.changes_max_age.is_empty() {
+            floor = self
+                .change_floor(account_id)
+                .await
+                .caused_by(trc::location!())?;
+            if let Some(push_floor) = self
+                .store()
+                .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                    account_id,
+                    Collection::Principal,
+                    0,
+                    PrincipalField::PushSubscriptions,
+                ))
+                .await
+                .caused_by(trc::location!())?
+                .map(|archive| {
+                    archive
+                        .unarchive::<PushSubscriptions>()
+                        .map(|subscriptions| subscriptions.oldest_renewal())
+                })
+                .transpose()
+                .caused_by(trc::location!())?
+                .flatten()
+                .and_then(SnowflakeIdGenerator::from_timestamp)
+            {
+                floor = Some(floor.map_or(push_floor, |floor| floor.min(push_floor)));
+            }
+        }
         self.delete_changes(
             account_id,
             self.core.email.changes_max_history,
+            &self.core.email.changes_max_age,
+            floor,
             self.core.email.share_notification_max_history,
         )
         .await
@@ -7,23 +7,25 @@
 use super::ingest::{EmailIngest, IngestEmail, IngestSource};
 use crate::{mailbox::INBOX_ID, sieve::ingest::SieveScriptIngest};
 use common::{
-    Server,
+    KV_DELIVERY_DEDUP, Server,
     auth::BuildAccessToken,
     ipc::{EmailPush, PushNotification},
 };
 use mail_parser::MessageParser;
-use registry::schema::enums::Permission;
-use std::{borrow::Cow, future::Future};
-use store::ahash::AHashMap;
+use registry::schema::enums::{DuplicateDelivery, Permission};
+use std::{borrow::Cow, future::Future, net::IpAddr};
+use store::{ahash::AHashMap, blake3};
 use types::blob_hash::BlobHash;
 
 #[derive(Debug)]
 pub struct IngestMessage {
     pub sender_address: String,
     pub sender_authenticated: bool,
+    pub remote_ip: Option<IpAddr>,
     pub recipients: Vec<IngestRecipient>,
     pub message_blob: BlobHash,
     pub message_size: u64,
+    pub spam_tags: Vec<String>,
     pub session_id: u64,
 }
 
@@ -38,6 +40,7 @@ pub struct IngestRecipient {
 pub enum LocalDeliveryStatus {
     Success,
     TemporaryFailure {
+        code: [u8; 3],
         reason: Cow<'static, str>,
     },
     PermanentFailure {
@@ -55,6 +58,8 @@ pub struct AutogeneratedMessage {
     pub sender_address: String,
     pub recipients: Vec<String>,
     pub message: Vec<u8>,
+    pub flags: u64,
+    pub rcpt_flags: u64,
 }
 
 pub trait MailDelivery: Sync + Send {
@@ -86,6 +91,7 @@ impl MailDelivery for Server {
                 return LocalDeliveryResult {
                     status: (0..message.recipients.len())
                         .map(|_| LocalDeliveryStatus::TemporaryFailure {
+                            code: [4, 3, 0],
                             reason: "Blob not found.".into(),
                         })
                         .collect::<Vec<_>>(),
@@ -102,6 +108,7 @@ impl MailDelivery for Server {
                 return LocalDeliveryResult {
                     status: (0..message.recipients.len())
                         .map(|_| LocalDeliveryStatus::TemporaryFailure {
+                            code: [4, 3, 0],
                             reason: "Temporary I/O error.".into(),
                         })
                         .collect::<Vec<_>>(),
@@ -110,6 +117,14 @@ impl MailDelivery for Server {
             }
         };
 
+        // Messages without a Message-ID are never deduplicated
+        let dedup_hash = (self.core.email.duplicate_delivery != DuplicateDelivery::Disabled)
+            .then(|| duplicate_hash(&raw_message))
+            .flatten();
+
+        // Addresses this message was already delivered to or looped through
+        let loop_addresses = loop_addresses(&raw_message);
+
         // Obtain the account IDs for each recipient
         let mut account_ids: AHashMap<u32, usize> =
             AHashMap::with_capacity(message.recipients.len());
@@ -137,6 +152,7 @@ impl MailDelivery for Server {
                             .caused_by(trc::location!())
                     );
                     result.status.push(LocalDeliveryStatus::TemporaryFailure {
+                        code: [4, 3, 0],
                         reason: "Address lookup failed.".into(),
                     });
                     continue;
@@ -150,6 +166,93 @@ impl MailDelivery for Server {
                 continue;
             }
 
+            // Reject messages that already reached this account, the canonical
+            // address is used so loops through aliases are detected as well
+            if !loop_addresses.is_empty() {
+                match self.account(account_id).await {
+                    Ok(account) => {
+                        if loop_addresses
+                            .iter()
+                            .any(|address| address.eq_ignore_ascii_case(account.name()))
+                        {
+                            trc::event!(
+                                MessageIngest(trc::MessageIngestEvent::LoopDetected),
+                                SpanId = message.session_id,
+                                AccountId = account_id,
+                                To = rcpt.address.to_string(),
+                            );
+
+                            account_ids.insert(account_id, result.status.len());
+                            result.status.push(LocalDeliveryStatus::PermanentFailure {
+                                code: [5, 4, 6],
+                                reason: "Mail loop detected.".into(),
+                            });
+                            continue;
+                        }
+                    }
+                    Err(err) => {
+                        trc::error!(
+                            err.details("Failed to obtain account.")
+                                .ctx(trc::Key::To, rcpt.address.to_string())
+                                .span_id(message.session_id)
+                                .caused_by(trc::location!())
+                        );
+                        result.status.push(LocalDeliveryStatus::TemporaryFailure {
+                            code: [4, 3, 0],
+                            reason: "Address lookup failed.".into(),
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            // Check whether the same message was recently delivered to this account,
+            // the key is only kept if the delivery succeeds so our own retries
+            // are not considered duplicates
+            let mut dedup_key = None;
+            let mut is_duplicate = false;
+            if let Some(dedup_hash) = &dedup_hash {
+                let key = duplicate_key(account_id, dedup_hash);
+                match self
+                    .in_memory_store()
+                    .try_lock(
+                        KV_DELIVERY_DEDUP,
+                        &key,
+                        self.core.email.duplicate_delivery_window.as_secs().max(1),
+                    )
+                    .await
+                {
+                    Ok(true) => {
+                        dedup_key = Some(key);
+                    }
+                    Ok(false) => {
+                        is_duplicate = true;
+                    }
+                    Err(err) => {
+                        trc::error!(
+                            err.details("Failed to check for duplicate delivery.")
+                                .span_id(message.session_id)
+                                .caused_by(trc::location!())
+                        );
+                    }
+                }
+            }
+
+            if is_duplicate {
+                trc::event!(
+                    MessageIngest(trc::MessageIngestEvent::Duplicate),
+                    SpanId = message.session_id,
+                    AccountId = account_id,
+                    To = rcpt.address.to_string(),
+                );
+
+                if self.core.email.duplicate_delivery == DuplicateDelivery::Discard {
+                    account_ids.insert(account_id, result.status.len());
+                    result.status.push(LocalDeliveryStatus::Success);
+                    continue;
+                }
+            }
+
             // Obtain access token
             let status = match self.access_token(account_id).await.and_then(|token| {
                 token
@@ -173,6 +276,8 @@ impl MailDelivery for Server {
                                     deliver_to: &rcpt.address,
                                     is_sender_authenticated: message.sender_authenticated,
                                     is_spam: rcpt.is_spam,
+                                    is_duplicate,
+                                    spam_tags: &message.spam_tags,
                                 },
                                 session_id: message.session_id,
                             })
@@ -185,7 +290,10 @@ impl MailDelivery for Server {
                                 &raw_message,
                                 &message.sender_address,
                                 message.sender_authenticated,
+                                message.remote_ip,
                                 &rcpt,
+                                &message.spam_tags,
+                                is_duplicate,
                                 message.session_id,
                                 active_script,
                                 &mut result.autogenerated,
@@ -214,14 +322,30 @@ impl MailDelivery for Server {
                     LocalDeliveryStatus::Success
                 }
                 Err(err) => {
+                    // Allow the message to be retried
+                    if let Some(dedup_key) = &dedup_key
+                        && let Err(err) = self
+                            .in_memory_store()
+                            .remove_lock(KV_DELIVERY_DEDUP, dedup_key)
+                            .await
+                    {
+                        trc::error!(
+                            err.details("Failed to remove duplicate delivery key.")
+                                .span_id(message.session_id)
+                                .caused_by(trc::location!())
+                        );
+                    }
+
                     let status = match err.as_ref() {
                         trc::EventType::Limit(trc::LimitEvent::Quota) => {
                             LocalDeliveryStatus::TemporaryFailure {
+                                code: [4, 2, 2],
                                 reason: "Mailbox over quota.".into(),
                             }
                         }
                         trc::EventType::Limit(trc::LimitEvent::TenantQuota) => {
                             LocalDeliveryStatus::TemporaryFailure {
+                                code: [4, 2, 2],
                                 reason: "Organization over quota.".into(),
                             }
                         }
@@ -248,6 +372,7 @@ impl MailDelivery for Server {
                             }
                         }
                         _ => LocalDeliveryStatus::TemporaryFailure {
+                            code: [4, 3, 0],
                             reason: "Transient server failure.".into(),
                         },
                     };
@@ -270,3 +395,51 @@ impl MailDelivery for Server {
         result
     }
 }
+
+fn loop_addresses(raw_message: &[u8]) -> Vec<String> {
+    MessageParser::new()
+        .parse_headers(raw_message)
+        .map(|message| {
+            message
+                .headers()
+                .iter()
+                .filter(|header| {
+                    let name = header.name.as_str();
+                    name.eq_ignore_ascii_case("Delivered-To") || name.eq_ignore_ascii_case("X-Loop")
+                })
+                .filter_map(|header| header.value.as_text())
+                .map(|value| {
+                    value
+                        .trim()
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn duplicate_hash(raw_message: &[u8]) -> Option<blake3::Hash> {
+    let message = MessageParser::new().parse(raw_message)?;
+    let message_id = message.message_id().filter(|id| !id.is_empty())?;
+
+    // Trace headers are added on every delivery attempt, so only the
+    // message body is hashed
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(message_id.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(
+        raw_message
+            .get(message.root_part().raw_body_offset() as usize..)
+            .unwrap_or_default(),
+    );
+    Some(hasher.finalize())
+}
+
+fn duplicate_key(account_id: u32, hash: &blake3::Hash) -> Vec<u8> {
+    let mut key = Vec::with_capacity(36);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key.extend_from_slice(hash.as_bytes());
+    key
+}
@@ -0,0 +1,333 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{
+    ingest::EmailIngest,
+    metadata::{MessageData, MessageMetadata},
+};
+use crate::{
+    cache::{MessageCacheFetch, mailbox::MailboxCacheAccess},
+    mailbox::UidMailbox,
+};
+use common::{Server, storage::index::ObjectIndexBuilder};
+use std::future::Future;
+use store::{
+    IndexKeyPrefix, IterateParams, U32_LEN, U64_LEN, ValueKey,
+    roaring::RoaringBitmap,
+    write::{
+        AlignedBytes, Archive, BatchBuilder, IndexPropertyClass, ValueClass,
+        key::DeserializeBigEndian, now,
+    },
+};
+use trc::AddContext;
+use types::{
+    collection::{Collection, SyncCollection},
+    field::EmailField,
+    keyword::Keyword,
+};
+use utils::cheeky_hash::CheekyHash;
+
+pub const FOLLOW_UP_KEYWORD: &str = "$followup";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmailFollowUp {
+    pub until: u64,
+    pub mailbox_id: Option<u32>,
+    pub message_id: CheekyHash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowUpResult {
+    Flagged,
+    NotWatched,
+    NotDue,
+}
+
+pub trait EmailFollowUpFnc: Sync + Send {
+    fn email_follow_up(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<EmailFollowUp>>> + Send;
+
+    fn email_follow_up_message_id(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<CheekyHash>>> + Send;
+
+    fn email_follow_up_ids(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<RoaringBitmap>> + Send;
+
+    fn email_follow_ups_replied(
+        &self,
+        account_id: u32,
+        reference_ids: &[CheekyHash],
+    ) -> impl Future<Output = trc::Result<Vec<(u32, EmailFollowUp)>>> + Send;
+
+    fn email_follow_up_expire(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<FollowUpResult>> + Send;
+}
+
+impl EmailFollowUpFnc for Server {
+    async fn email_follow_up(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<Option<EmailFollowUp>> {
+        self.store()
+            .get_value::<EmailFollowUp>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::FollowUp,
+            ))
+            .await
+            .caused_by(trc::location!())
+    }
+
+    async fn email_follow_up_message_id(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<Option<CheekyHash>> {
+        let Some(metadata_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(None);
+        };
+
+        Ok(metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?
+            .root_part()
+            .message_id()
+            .filter(|message_id| !message_id.is_empty())
+            .map(|message_id| CheekyHash::new(message_id.as_bytes())))
+    }
+
+    async fn email_follow_up_ids(&self, account_id: u32) -> trc::Result<RoaringBitmap> {
+        let mut document_ids = RoaringBitmap::new();
+        self.store()
+            .iterate(
+                IterateParams::new(
+                    ValueKey::property(account_id, Collection::Email, 0, EmailField::FollowUp),
+                    ValueKey::property(
+                        account_id,
+                        Collection::Email,
+                        u32::MAX,
+                        EmailField::FollowUp,
+                    ),
+                )
+                .ascending()
+                .no_values(),
+                |key, _| {
+                    document_ids.insert(key.deserialize_be_u32(key.len() - U32_LEN)?);
+
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())
+            .map(|_| document_ids)
+    }
+
+    async fn email_follow_ups_replied(
+        &self,
+        account_id: u32,
+        reference_ids: &[CheekyHash],
+    ) -> trc::Result<Vec<(u32, EmailFollowUp)>> {
+        let mut document_ids = Vec::new();
+        for reference_id in reference_ids {
+            let key_len = IndexKeyPrefix::len() + reference_id.len() + U32_LEN;
+            self.store()
+                .iterate(
+                    IterateParams::new(
+                        ValueKey {
+                            account_id,
+                            collection: Collection::Email.into(),
+                            document_id: 0,
+                            class: ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                                property: EmailField::FollowUpMessageId.into(),
+                                hash: *reference_id,
+                            }),
+                        },
+                        ValueKey {
+                            account_id,
+                            collection: Collection::Email.into(),
+                            document_id: u32::MAX,
+                            class: ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                                property: EmailField::FollowUpMessageId.into(),
+                                hash: *reference_id,
+                            }),
+                        },
+                    )
+                    .ascending()
+                    .no_values(),
+                    |key, _| {
+                        if key.len() == key_len {
+                            document_ids
+                                .push((key.deserialize_be_u32(key_len - U32_LEN)?, *reference_id));
+                        }
+
+                        Ok(true)
+                    },
+                )
+                .await
+                .caused_by(trc::location!())?;
+        }
+
+        // Index entries of deleted messages are removed asynchronously, make
+        // sure the watch is still active before returning it
+        let mut follow_ups = Vec::with_capacity(document_ids.len());
+        for (document_id, reference_id) in document_ids {
+            if let Some(follow_up) = self
+                .email_follow_up(account_id, document_id)
+                .await
+                .caused_by(trc::location!())?
+                && follow_up.message_id == reference_id
+                && !follow_ups.iter().any(|(id, _)| *id == document_id)
+            {
+                follow_ups.push((document_id, follow_up));
+            }
+        }
+
+        Ok(follow_ups)
+    }
+
+    async fn email_follow_up_expire(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<FollowUpResult> {
+        let Some(follow_up) = self
+            .email_follow_up(account_id, document_id)
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(FollowUpResult::NotWatched);
+        };
+        if follow_up.until > now() {
+            // The deadline was extended after this reminder was scheduled
+            return Ok(FollowUpResult::NotDue);
+        }
+        let Some(data_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                account_id,
+                Collection::Email,
+                document_id,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(FollowUpResult::NotWatched);
+        };
+        let data = data_
+            .to_unarchived::<MessageData>()
+            .caused_by(trc::location!())?;
+
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Email)
+            .with_document(document_id);
+        follow_up.clear(&mut batch);
+
+        // Flag the message and resurface it in the requested mailbox
+        let mut new_data = data.inner.to_builder();
+        new_data.add_keyword(Keyword::parse(FOLLOW_UP_KEYWORD));
+        if let Some(mailbox_id) = follow_up.mailbox_id
+            && data.inner.message_uid(mailbox_id).is_none()
+            && self
+                .get_cached_messages(account_id)
+                .await
+                .caused_by(trc::location!())?
+                .has_mailbox_id(&mailbox_id)
+        {
+            let uid = *self
+                .assign_mailbox_uids(account_id, mailbox_id, 1)
+                .await
+                .caused_by(trc::location!())?
+                .start();
+            new_data.add_mailbox(UidMailbox::new(mailbox_id, uid));
+            batch.log_container_property_change(SyncCollection::Email, mailbox_id);
+        }
+        batch
+            .custom(
+                ObjectIndexBuilder::new()
+                    .with_current(data)
+                    .with_changes(new_data.seal()),
+            )
+            .caused_by(trc::location!())?;
+
+        self.commit_batch(batch)
+            .await
+            .caused_by(trc::location!())
+            .map(|_| FollowUpResult::Flagged)
+    }
+}
+
+impl EmailFollowUp {
+    pub fn set(&self, batch: &mut BatchBuilder) {
+        batch
+            .set(
+                ValueClass::Property(EmailField::FollowUp.into()),
+                self.serialize(),
+            )
+            .set(
+                ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                    property: EmailField::FollowUpMessageId.into(),
+                    hash: self.message_id,
+                }),
+                vec![],
+            );
+    }
+
+    pub fn clear(&self, batch: &mut BatchBuilder) {
+        batch
+            .clear(ValueClass::Property(EmailField::FollowUp.into()))
+            .clear(ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                property: EmailField::FollowUpMessageId.into(),
+                hash: self.message_id,
+            }));
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(U64_LEN + U32_LEN + CheekyHash::HASH_SIZE);
+        bytes.extend_from_slice(&self.until.to_be_bytes());
+        bytes.extend_from_slice(&self.mailbox_id.unwrap_or(u32::MAX).to_be_bytes());
+        bytes.extend_from_slice(self.message_id.as_raw_bytes());
+        bytes
+    }
+}
+
+impl store::Deserialize for EmailFollowUp {
+    fn deserialize(bytes: &[u8]) -> trc::Result<Self> {
+        Ok(EmailFollowUp {
+            until: bytes.deserialize_be_u64(0)?,
+            mailbox_id: Some(bytes.deserialize_be_u32(U64_LEN)?)
+                .filter(|mailbox_id| *mailbox_id != u32::MAX),
+            message_id: bytes
+                .get(U64_LEN + U32_LEN..)
+                .and_then(CheekyHash::deserialize)
+                .ok_or_else(|| trc::StoreEvent::DataCorruption.caused_by(trc::location!()))?,
+        })
+    }
+}
@@ -54,60 +54,14 @@ impl MessageMetadata {
 
         Ok(())
     }
-}
-
-impl ArchivedMessageMetadata {
-    #[inline(always)]
-    pub fn root_part(&self) -> &ArchivedMessageMetadataPart {
-        &self.contents[0].parts[0]
-    }
 
-    pub fn unindex(&self, batch: &mut BatchBuilder) {
-        // Delete metadata
-        let thread_name = self
-            .contents
-            .first()
-            .and_then(|c| c.parts.first())
-            .and_then(|p| {
-                p.headers.iter().rev().find_map(|h| {
-                    if let ArchivedMetadataHeaderName::Subject = &h.name {
-                        h.value.as_text()
-                    } else {
-                        None
-                    }
-                })
-            })
-            .map(thread_name)
-            .unwrap_or_default();
-
-        batch
-            .clear(EmailField::Metadata)
-            .clear(ValueClass::IndexProperty(IndexPropertyClass::Hash {
-                property: EmailField::Threading.into(),
-                hash: CheekyHash::new(if !thread_name.is_empty() {
-                    thread_name
-                } else {
-                    "!"
-                }),
-            }))
-            .clear(BlobOp::Link {
-                hash: BlobHash::from(&self.blob_hash),
-                to: BlobLink::Document,
-            });
-    }
-}
-
-impl IndexMessage for BatchBuilder {
-    fn index_message<'x>(
-        &mut self,
-        tenant_id: Option<u32>,
+    pub(crate) fn build<'x>(
         mut message: mail_parser::Message<'x>,
         extra_headers: Vec<u8>,
         mut extra_headers_parsed: Vec<mail_parser::Header<'x>>,
         blob_hash: BlobHash,
-        data: MessageData,
         received_at: u64,
-    ) -> trc::Result<&mut Self> {
+    ) -> Self {
         let mut has_attachments = false;
         let mut preview = None;
         let preview_part_id = message
@@ -213,7 +167,7 @@ impl IndexMessage for BatchBuilder {
         };
 
         // Build metadata
-        let metadata = MessageMetadata {
+        MessageMetadata {
             preview: preview.unwrap_or_default().into_owned().into_boxed_str(),
             raw_headers: raw_headers.into_boxed_slice(),
             contents: build_metadata_contents(message),
@@ -224,7 +178,75 @@ impl IndexMessage for BatchBuilder {
             } else {
                 0
             }) | (received_at & MESSAGE_RECEIVED_MASK),
-        };
+        }
+    }
+}
+
+impl ArchivedMessageMetadata {
+    #[inline(always)]
+    pub fn root_part(&self) -> &ArchivedMessageMetadataPart {
+        &self.contents[0].parts[0]
+    }
+
+    pub fn unindex(&self, batch: &mut BatchBuilder) {
+        // Delete metadata
+        let thread_name = self
+            .contents
+            .first()
+            .and_then(|c| c.parts.first())
+            .and_then(|p| {
+                p.headers.iter().rev().find_map(|h| {
+                    if let ArchivedMetadataHeaderName::Subject = &h.name {
+                        h.value.as_text()
+                    } else {
+                        None
+                    }
+                })
+            })
+            .map(thread_name)
+            .unwrap_or_default();
+
+        batch
+            .clear(EmailField::Metadata)
+            .clear(ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                property: EmailField::Threading.into(),
+                hash: CheekyHash::new(if !thread_name.is_empty() {
+                    thread_name
+                } else {
+                    "!"
+                }),
+            }))
+            .clear(BlobOp::Link {
+                hash: BlobHash::from(&self.blob_hash),
+                to: BlobLink::Document,
+            });
+        if let Some(message_id) = self.root_part().message_id() {
+            batch.clear(ValueClass::IndexProperty(IndexPropertyClass::Hash {
+                property: EmailField::FollowUpMessageId.into(),
+                hash: CheekyHash::new(message_id.as_bytes()),
+            }));
+        }
+    }
+}
+
+impl IndexMessage for BatchBuilder {
+    fn index_message<'x>(
+        &mut self,
+        tenant_id: Option<u32>,
+        message: mail_parser::Message<'x>,
+        extra_headers: Vec<u8>,
+        extra_headers_parsed: Vec<mail_parser::Header<'x>>,
+        blob_hash: BlobHash,
+        data: MessageData,
+        received_at: u64,
+    ) -> trc::Result<&mut Self> {
+        let metadata = MessageMetadata::build(
+            message,
+            extra_headers,
+            extra_headers_parsed,
+            blob_hash,
+            received_at,
+        );
 
         self.set(
             BlobOp::Link {
@@ -39,6 +39,10 @@ impl IndexableObject for MessageData {
                 },
             },
             IndexValue::Quota { used: self.size },
+            IndexValue::ContainerSize {
+                ids: mailboxes.clone(),
+                used: self.size,
+            },
             IndexValue::LogItem {
                 sync_collection: SyncCollection::Email,
                 prefix: self.thread_id.into(),
@@ -79,6 +83,10 @@ impl IndexableObject for &ArchivedMessageData {
             IndexValue::Quota {
                 used: self.size.to_native(),
             },
+            IndexValue::ContainerSize {
+                ids: mailboxes.clone(),
+                used: self.size.to_native(),
+            },
             IndexValue::LogItem {
                 sync_collection: SyncCollection::Email,
                 prefix: self.thread_id.to_native().into(),
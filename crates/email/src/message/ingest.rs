@@ -7,20 +7,26 @@
 use super::crypto::{EncryptMessage, EncryptMessageError};
 use crate::{
     cache::{MessageCacheFetch, email::MessageCacheAccess, mailbox::MailboxCacheAccess},
-    mailbox::{INBOX_ID, JUNK_ID, SENT_ID, TRASH_ID, UidMailbox},
+    mailbox::{INBOX_ID, JUNK_ID, SENT_ID, TRASH_ID, UidMailbox, manage::MailboxFnc},
     message::{
+        category::{CATEGORY_KEYWORD_PREFIX, EmailCategoryFnc},
         crypto::EncryptionFlags,
+        followup::EmailFollowUpFnc,
         index::{IndexMessage, extractors::VisitText},
         metadata::{MessageData, MessageMetadata},
+        shared::{BlobReferences, EmailSharedBlob, MessageReferences},
     },
 };
-use common::{Server, auth::AccessToken};
+use common::{
+    Server,
+    auth::{ACCOUNT_FLAG_CATEGORIZE, ACCOUNT_FLAG_ITIP_DISABLED, AccessToken},
+};
 use groupware::{
     calendar::itip::{ItipIngest, ItipIngestError},
     scheduling::{ItipError, ItipMessages},
 };
 use mail_parser::{
-    DateTime, Header, HeaderName, HeaderValue, Message, MessageParser, MimeHeaders, PartType,
+    DateTime, Header, HeaderName, HeaderValue, Message, MessageParser, MimeHeaders,
     parsers::fields::thread::thread_name,
 };
 use registry::{
@@ -32,7 +38,7 @@ use registry::{
     types::{EnumImpl, ObjectImpl, datetime::UTCDateTime, id::ObjectId, map::Map},
 };
 use std::future::Future;
-use std::{borrow::Cow, cmp::Ordering, fmt::Write, time::Instant};
+use std::{borrow::Cow, cmp::Ordering, fmt::Write, ops::RangeInclusive, time::Instant};
 use store::write::{AlignedBytes, Archive, RegistryClass};
 use store::{
     IndexKeyPrefix, IterateParams, SerializeInfallible, U32_LEN, ValueKey,
@@ -82,6 +88,8 @@ pub enum IngestSource<'x> {
         deliver_to: &'x str,
         is_sender_authenticated: bool,
         is_spam: bool,
+        is_duplicate: bool,
+        spam_tags: &'x [String],
     },
     Jmap {
         train_classifier: bool,
@@ -89,6 +97,9 @@ pub enum IngestSource<'x> {
     Imap {
         train_classifier: bool,
     },
+    Import {
+        deduplicate: bool,
+    },
     Restore,
 }
 
@@ -109,6 +120,12 @@ pub trait EmailIngest: Sync + Send {
         mailbox_ids: impl IntoIterator<Item = u32> + Sync + Send,
         generate_email_id: bool,
     ) -> impl Future<Output = trc::Result<impl Iterator<Item = u32> + 'static>> + Send;
+    fn assign_mailbox_uids(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+        count: u32,
+    ) -> impl Future<Output = trc::Result<RangeInclusive<u32>>> + Send;
     fn add_account_spam_sample(
         &self,
         batch: &mut BatchBuilder,
@@ -163,6 +180,7 @@ impl EmailIngest for Server {
         // Obtain message references and thread name
         let mut message_id = None;
         let mut message_ids = Vec::new();
+        let mut reference_ids = Vec::new();
         let thread_result = {
             let mut subject = "";
             for header in message.root_part().headers().iter().rev() {
@@ -178,9 +196,14 @@ impl EmailIngest for Server {
                     HeaderName::InReplyTo
                     | HeaderName::References
                     | HeaderName::ResentMessageId => {
+                        let is_reference = !matches!(header.name, HeaderName::ResentMessageId);
                         header.value.visit_text(|id| {
                             if !id.is_empty() {
-                                message_ids.push(CheekyHash::new(id.as_bytes()));
+                                let id = CheekyHash::new(id.as_bytes());
+                                if is_reference {
+                                    reference_ids.push(id);
+                                }
+                                message_ids.push(id);
                             }
                         });
                     }
@@ -204,20 +227,57 @@ impl EmailIngest for Server {
                 .await?
         };
 
-        // Skip duplicate messages for SMTP ingestion
-        if !thread_result.duplicate_ids.is_empty() && params.source.is_smtp() {
-            // Fetch cached messages
-            let cache = self
-                .get_cached_messages(account_id)
-                .await
-                .caused_by(trc::location!())?;
+        // Skip duplicate messages for SMTP ingestion and deduplicated imports
+        if !thread_result.duplicate_ids.is_empty() {
+            let is_duplicate = match params.source {
+                IngestSource::Smtp {
+                    is_duplicate: false,
+                    ..
+                } => {
+                    // Fetch cached messages
+                    let cache = self
+                        .get_cached_messages(account_id)
+                        .await
+                        .caused_by(trc::location!())?;
+
+                    let target_mailbox_id = params.mailbox_ids.first().copied().unwrap_or(INBOX_ID);
+                    cache
+                        .in_mailboxes(&[target_mailbox_id, JUNK_ID])
+                        .any(|m| thread_result.duplicate_ids.contains(&m.document_id))
+                }
+                IngestSource::Import { deduplicate: true } => {
+                    // Imported messages must also have identical contents
+                    let blob_hash = BlobHash::generate(params.raw_message);
+                    let mut is_duplicate = false;
+                    for document_id in &thread_result.duplicate_ids {
+                        if let Some(metadata) = self
+                            .store()
+                            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                                account_id,
+                                Collection::Email,
+                                *document_id,
+                                EmailField::Metadata,
+                            ))
+                            .await
+                            .caused_by(trc::location!())?
+                            && metadata
+                                .unarchive::<MessageMetadata>()
+                                .caused_by(trc::location!())?
+                                .blob_hash
+                                .0
+                                .as_slice()
+                                == blob_hash.as_slice()
+                        {
+                            is_duplicate = true;
+                            break;
+                        }
+                    }
+                    is_duplicate
+                }
+                _ => false,
+            };
 
-            // Skip duplicate messages
-            let target_mailbox_id = params.mailbox_ids.first().copied().unwrap_or(INBOX_ID);
-            if cache
-                .in_mailboxes(&[target_mailbox_id, JUNK_ID])
-                .any(|m| thread_result.duplicate_ids.contains(&m.document_id))
-            {
+            if is_duplicate {
                 trc::event!(
                     MessageIngest(MessageIngestEvent::Duplicate),
                     SpanId = params.session_id,
@@ -236,6 +296,26 @@ impl EmailIngest for Server {
             }
         }
 
+        // Replies cancel pending follow-up reminders on the referenced messages
+        let mut replied_follow_ups = Vec::new();
+        if matches!(params.source, IngestSource::Smtp { .. }) && !reference_ids.is_empty() {
+            let follow_ups = self
+                .email_follow_ups_replied(account_id, &reference_ids)
+                .await
+                .caused_by(trc::location!())?;
+            if !follow_ups.is_empty() {
+                let cache = self
+                    .get_cached_messages(account_id)
+                    .await
+                    .caused_by(trc::location!())?;
+                for (document_id, follow_up) in follow_ups {
+                    if let Some(message) = cache.email_by_id(&document_id) {
+                        replied_follow_ups.push((document_id, message.thread_id, follow_up));
+                    }
+                }
+            }
+        }
+
         // Spam classification and training
         let mut train_spam = None;
         let mut extra_headers = String::new();
@@ -246,13 +326,25 @@ impl EmailIngest for Server {
                 deliver_to,
                 is_sender_authenticated,
                 mut is_spam,
+                is_duplicate,
+                spam_tags,
             } => {
-                // Add delivered to header
-                if self.core.smtp.session.data.add_delivered_to {
-                    extra_headers = format!("Delivered-To: {deliver_to}\r\n");
+                // Flag repeated deliveries detected by the deduplication window
+                if is_duplicate {
+                    params.keywords.push(Keyword::Other("$duplicate".into()));
+                }
+
+                // Add delivered to header, using the canonical address so
+                // loops through aliases can be detected on redelivery
+                if let Some(address) = self
+                    .delivered_to_address(&account)
+                    .await
+                    .caused_by(trc::location!())?
+                {
+                    extra_headers = format!("Delivered-To: {address}\r\n");
                     extra_headers_parsed.push(Header {
                         name: HeaderName::Other("Delivered-To".into()),
-                        value: HeaderValue::Text(deliver_to.into()),
+                        value: HeaderValue::Text(address.to_string().into()),
                         offset_field: 0,
                         offset_start: 13,
                         offset_end: extra_headers.len() as u32,
@@ -360,8 +452,33 @@ impl EmailIngest for Server {
                     }
                 }
 
+                // Sort messages delivered to the Inbox into categories
+                if !is_spam
+                    && params.mailbox_ids == [INBOX_ID]
+                    && account.flags & ACCOUNT_FLAG_CATEGORIZE != 0
+                    && !self.core.email.categories.is_empty()
+                    && let Some(category) = self
+                        .email_category(account_id, &message, spam_tags)
+                        .await
+                        .caused_by(trc::location!())?
+                {
+                    params.keywords.push(Keyword::from_other(format!(
+                        "{CATEGORY_KEYWORD_PREFIX}{}",
+                        category.name
+                    )));
+                    if let Some(mailbox_name) = &category.mailbox_name
+                        && let Some(mailbox_id) = self
+                            .mailbox_create_path(account_id, mailbox_name)
+                            .await
+                            .caused_by(trc::location!())?
+                    {
+                        params.mailbox_ids[0] = mailbox_id;
+                    }
+                }
+
                 // iMIP processing
                 if self.core.groupware.itip_enabled
+                    && account.flags & ACCOUNT_FLAG_ITIP_DISABLED == 0
                     && !is_spam
                     && is_sender_authenticated
                     && params
@@ -453,9 +570,17 @@ impl EmailIngest for Server {
 
                 is_spam
             }
-            IngestSource::Jmap { train_classifier } | IngestSource::Imap { train_classifier } => {
+            IngestSource::Jmap { .. } | IngestSource::Imap { .. } | IngestSource::Import { .. } => {
                 // Determine spam training
-                if train_classifier && self.core.spam.enabled {
+                if matches!(
+                    params.source,
+                    IngestSource::Jmap {
+                        train_classifier: true
+                    } | IngestSource::Imap {
+                        train_classifier: true
+                    }
+                ) && self.core.spam.enabled
+                {
                     if params.keywords.contains(&Keyword::Junk) {
                         train_spam = Some(true);
                     } else if params.keywords.contains(&Keyword::NotJunk) {
@@ -500,7 +625,7 @@ impl EmailIngest for Server {
             IngestSource::Jmap { .. } | IngestSource::Imap { .. } => {
                 self.core.email.encrypt && self.core.email.encrypt_append
             }
-            IngestSource::Smtp { .. } => self.core.email.encrypt,
+            IngestSource::Smtp { .. } | IngestSource::Import { .. } => self.core.email.encrypt,
             IngestSource::Restore => false,
         };
         let is_encrypted = if do_encrypt
@@ -528,20 +653,7 @@ impl EmailIngest for Server {
                     }
 
                     // Remove contents from parsed message
-                    for part in &mut message.parts {
-                        match &mut part.body {
-                            PartType::Text(txt) | PartType::Html(txt) => {
-                                *txt = Cow::from("");
-                            }
-                            PartType::Binary(bin) | PartType::InlineBinary(bin) => {
-                                *bin = Cow::from(&[][..]);
-                            }
-                            PartType::Message(_) => {
-                                part.body = PartType::Binary(Cow::from(&[][..]));
-                            }
-                            PartType::Multipart(_) => (),
-                        }
-                    }
+                    message.remove_contents();
 
                     true
                 }
@@ -590,6 +702,17 @@ impl EmailIngest for Server {
             .collect::<Vec<_>>();
         batch.with_account_id(account_id);
 
+        // Clear follow-up reminders that received a reply
+        if !replied_follow_ups.is_empty() {
+            batch.with_collection(Collection::Email);
+            for (document_id, thread_id, follow_up) in &replied_follow_ups {
+                batch
+                    .with_document(*document_id)
+                    .log_item_update(SyncCollection::Email, Some(*thread_id));
+                follow_up.clear(&mut batch);
+            }
+        }
+
         // Determine thread id
         let thread_id = if let Some(thread_id) = thread_result.thread_id {
             thread_id
@@ -607,6 +730,11 @@ impl EmailIngest for Server {
             thread_id,
             size: (message.raw_message.len() + extra_headers.len()) as u32,
         };
+        let references = if !is_encrypted {
+            MessageReferences::new(&message, blob_hash.clone(), data.size)
+        } else {
+            MessageReferences::message(blob_hash.clone(), data.size)
+        };
 
         // Request spam training
         if let Some(learn_spam) = train_spam {
@@ -658,11 +786,19 @@ impl EmailIngest for Server {
             batch.clear(blob_hold);
         }
 
+        // Track the contents referenced by the message
+        let mut blob_references = BlobReferences::new(account_id, tenant_id);
+        blob_references.add(&references);
+        batch.set(EmailField::References, references.serialize());
+        self.write_blob_references(&mut batch, blob_references)
+            .await
+            .caused_by(trc::location!())?;
+
         // Merge threads if necessary
         if !thread_result.merge_ids.is_empty()
             || matches!(
                 params.source,
-                IngestSource::Jmap { .. } | IngestSource::Imap { .. }
+                IngestSource::Jmap { .. } | IngestSource::Imap { .. } | IngestSource::Import { .. }
             )
         {
             batch.schedule_task(Task::MergeThreads(TaskMergeThreads {
@@ -699,7 +835,8 @@ impl EmailIngest for Server {
                     } else {
                         MessageIngestEvent::Spam
                     },
-                IngestSource::Jmap { .. } | IngestSource::Restore => MessageIngestEvent::JmapAppend,
+                IngestSource::Jmap { .. } | IngestSource::Import { .. } | IngestSource::Restore =>
+                    MessageIngestEvent::JmapAppend,
                 IngestSource::Imap { .. } => MessageIngestEvent::ImapAppend,
             }),
             SpanId = params.session_id,
@@ -868,6 +1005,43 @@ impl EmailIngest for Server {
         }
     }
 
+    async fn assign_mailbox_uids(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+        count: u32,
+    ) -> trc::Result<RangeInclusive<u32>> {
+        if count == 0 {
+            return Ok(1..=0);
+        }
+
+        // Reserve a contiguous range of UIDs with a single increment
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Mailbox)
+            .with_document(mailbox_id)
+            .add_and_get(MailboxField::UidCounter, count as i64);
+
+        match self
+            .core
+            .storage
+            .data
+            .write(batch.build_all())
+            .await?
+            .ids
+            .first()
+        {
+            Some(AssignedId::Counter(last_uid)) => {
+                let last_uid = *last_uid as u32;
+                Ok((last_uid + 1 - count)..=last_uid)
+            }
+            _ => Err(trc::StoreEvent::UnexpectedError
+                .caused_by(trc::location!())
+                .ctx(trc::Key::Reason, "No UIDs were generated")),
+        }
+    }
+
     async fn add_account_spam_sample(
         &self,
         batch: &mut BatchBuilder,
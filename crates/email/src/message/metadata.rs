@@ -914,11 +914,25 @@ pub struct MessageDataBuilder {
 
 impl MessageDataBuilder {
     pub fn set_keywords(&mut self, keywords: Vec<Keyword>) {
-        self.keywords = keywords;
+        // Keep the existing spelling of custom keywords that only differ in case,
+        // so a JMAP read-modify-write does not alter flags set over IMAP
+        let mut new_keywords: Vec<Keyword> = Vec::with_capacity(keywords.len());
+        for keyword in keywords {
+            let keyword = self
+                .keywords
+                .iter()
+                .find(|k| k.is_equivalent(&keyword))
+                .cloned()
+                .unwrap_or(keyword);
+            if !new_keywords.iter().any(|k| k.is_equivalent(&keyword)) {
+                new_keywords.push(keyword);
+            }
+        }
+        self.keywords = new_keywords;
     }
 
     pub fn add_keyword(&mut self, keyword: Keyword) -> bool {
-        if !self.keywords.contains(&keyword) {
+        if !self.keywords.iter().any(|k| k.is_equivalent(&keyword)) {
             self.keywords.push(keyword);
             true
         } else {
@@ -928,7 +942,7 @@ impl MessageDataBuilder {
 
     pub fn remove_keyword(&mut self, keyword: &Keyword) -> bool {
         let prev_len = self.keywords.len();
-        self.keywords.retain(|k| k != keyword);
+        self.keywords.retain(|k| !k.is_equivalent(keyword));
         self.keywords.len() != prev_len
     }
 
@@ -947,7 +961,7 @@ impl MessageDataBuilder {
     }
 
     pub fn has_keyword(&self, keyword: &Keyword) -> bool {
-        self.keywords.iter().any(|k| k == keyword)
+        self.keywords.iter().any(|k| k.is_equivalent(keyword))
     }
 
     pub fn has_keyword_changes(&self, prev_data: &ArchivedMessageData) -> bool {
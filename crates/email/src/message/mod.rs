@@ -4,10 +4,17 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod category;
 pub mod copy;
 pub mod crypto;
 pub mod delete;
 pub mod delivery;
+pub mod followup;
 pub mod index;
 pub mod ingest;
 pub mod metadata;
+pub mod re_encrypt;
+pub mod recall;
+pub mod shared;
+pub mod snooze;
+pub mod urlauth;
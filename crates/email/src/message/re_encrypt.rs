@@ -0,0 +1,238 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::crypto::{DecryptMessage, EncryptMessage, EncryptMessageError};
+use crate::message::{
+    metadata::{MESSAGE_RECEIVED_MASK, MessageData, MessageMetadata},
+    shared::{BlobReferences, EmailSharedBlob, MessageReferences},
+};
+use common::{Server, auth::EncryptionKeys, storage::index::ObjectIndexBuilder};
+use mail_parser::MessageParser;
+use registry::schema::{
+    enums::IndexDocumentType,
+    structs::{Task, TaskIndexDocument, TaskStatus},
+};
+use std::future::Future;
+use store::{
+    SerializeInfallible, ValueKey,
+    write::{AlignedBytes, Archive, BatchBuilder, BlobLink, BlobOp},
+};
+use trc::AddContext;
+use types::{blob_hash::BlobHash, collection::Collection, field::EmailField};
+use utils::chained_bytes::ChainedBytes;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReEncryptResult {
+    ReEncrypted,
+    Skipped(&'static str),
+    NotFound,
+}
+
+pub trait EmailReEncrypt: Sync + Send {
+    fn re_encrypt_message(
+        &self,
+        account_id: u32,
+        tenant_id: Option<u32>,
+        document_id: u32,
+        keys: &EncryptionKeys,
+        flags: u64,
+        decryption_keys: &[String],
+    ) -> impl Future<Output = trc::Result<ReEncryptResult>> + Send;
+}
+
+impl EmailReEncrypt for Server {
+    async fn re_encrypt_message(
+        &self,
+        account_id: u32,
+        tenant_id: Option<u32>,
+        document_id: u32,
+        keys: &EncryptionKeys,
+        flags: u64,
+        decryption_keys: &[String],
+    ) -> trc::Result<ReEncryptResult> {
+        // Obtain message data and metadata
+        let Some(data_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                account_id,
+                Collection::Email,
+                document_id,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(ReEncryptResult::NotFound);
+        };
+        let Some(metadata_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(ReEncryptResult::NotFound);
+        };
+        let metadata = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?;
+        let Some(raw_body) = self
+            .blob_store()
+            .get_blob(metadata.blob_hash.0.as_slice(), 0..usize::MAX)
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(ReEncryptResult::NotFound);
+        };
+
+        // Rebuild the full message, including any headers added during delivery
+        let raw_message = ChainedBytes::new(metadata.raw_headers.as_ref())
+            .with_last(
+                raw_body
+                    .get(metadata.blob_body_offset.to_native() as usize..)
+                    .unwrap_or_default(),
+            )
+            .to_bytes();
+        let Some(message) = MessageParser::default().parse(&raw_message) else {
+            return Ok(ReEncryptResult::Skipped("Failed to parse message"));
+        };
+
+        // Messages encrypted with a previous key are decrypted using the private
+        // keys held by the server, anything else is left untouched
+        let decrypted_message;
+        let message = if message.is_encrypted() {
+            if decryption_keys.is_empty() {
+                return Ok(ReEncryptResult::Skipped(
+                    "Message is encrypted with a key not held by the server",
+                ));
+            }
+            match message.decrypt(decryption_keys).await {
+                Ok(raw_message) => {
+                    decrypted_message = raw_message;
+                }
+                Err(_) => {
+                    return Ok(ReEncryptResult::Skipped(
+                        "Message is encrypted with a key not held by the server",
+                    ));
+                }
+            }
+            let Some(message) = MessageParser::default().parse(&decrypted_message) else {
+                return Ok(ReEncryptResult::Skipped(
+                    "Failed to parse decrypted message",
+                ));
+            };
+            message
+        } else {
+            message
+        };
+        let raw_message = match message.encrypt(keys, flags).await {
+            Ok(raw_message) => raw_message,
+            Err(EncryptMessageError::AlreadyEncrypted) => {
+                return Ok(ReEncryptResult::Skipped("Message is already encrypted"));
+            }
+            Err(EncryptMessageError::Error(err)) => {
+                trc::bail!(
+                    trc::StoreEvent::CryptoError
+                        .into_err()
+                        .caused_by(trc::location!())
+                        .reason(err)
+                );
+            }
+        };
+        let mut message = MessageParser::default()
+            .parse(raw_message.as_slice())
+            .ok_or_else(|| {
+                trc::StoreEvent::CryptoError
+                    .into_err()
+                    .caused_by(trc::location!())
+                    .details("Failed to parse encrypted e-mail message.")
+            })?;
+        message.remove_contents();
+
+        // Store encrypted blob
+        let (blob_hash, blob_hold) = self
+            .put_temporary_blob(account_id, raw_message.as_slice(), 60)
+            .await
+            .caused_by(trc::location!())?;
+
+        // Update message size
+        let prev_data = data_
+            .to_unarchived::<MessageData>()
+            .caused_by(trc::location!())?;
+        let mut new_data = prev_data
+            .deserialize::<MessageData>()
+            .caused_by(trc::location!())?;
+        new_data.size = raw_message.len() as u32;
+
+        // The encrypted message no longer shares contents with other messages
+        let mut blob_references = BlobReferences::new(account_id, tenant_id);
+        let references = self
+            .store()
+            .get_value::<MessageReferences>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::References,
+            ))
+            .await
+            .caused_by(trc::location!())?
+            .map(|references| {
+                let new_references = MessageReferences::message(blob_hash.clone(), new_data.size);
+                blob_references.remove(&references);
+                blob_references.add(&new_references);
+                new_references
+            });
+
+        // Replace metadata and blob link in a single transaction
+        let old_blob_hash = BlobHash::from(&metadata.blob_hash);
+        let new_metadata = MessageMetadata::build(
+            message,
+            Vec::new(),
+            Vec::new(),
+            blob_hash,
+            metadata.rcvd_attach.to_native() & MESSAGE_RECEIVED_MASK,
+        );
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Email)
+            .with_document(document_id)
+            .custom(
+                ObjectIndexBuilder::new()
+                    .with_tenant_id(tenant_id)
+                    .with_current(prev_data)
+                    .with_changes(new_data),
+            )
+            .caused_by(trc::location!())?
+            .clear(BlobOp::Link {
+                hash: old_blob_hash,
+                to: BlobLink::Document,
+            });
+        new_metadata
+            .index(&mut batch, true)
+            .caused_by(trc::location!())?;
+        if let Some(references) = references {
+            batch.set(EmailField::References, references.serialize());
+            self.write_blob_references(&mut batch, blob_references)
+                .await
+                .caused_by(trc::location!())?;
+        }
+        batch
+            .clear(blob_hold)
+            .schedule_task(Task::IndexDocument(TaskIndexDocument {
+                account_id: account_id.into(),
+                document_id: document_id.into(),
+                document_type: IndexDocumentType::Email,
+                status: TaskStatus::now(),
+            }));
+        self.commit_batch(batch).await.caused_by(trc::location!())?;
+
+        Ok(ReEncryptResult::ReEncrypted)
+    }
+}
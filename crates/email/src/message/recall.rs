@@ -0,0 +1,560 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{
+    delete::EmailDeletion,
+    index::extractors::{AddressElement, VisitTextArchived},
+    ingest::{EmailIngest, IngestEmail, IngestSource},
+    metadata::{
+        ArchivedMetadataHeaderName, MESSAGE_RECEIVED_MASK, MessageData, MessageMetadata,
+        MetadataHeaderName,
+    },
+};
+use crate::{
+    cache::{MessageCacheFetch, email::MessageCacheAccess, mailbox::MailboxCacheAccess},
+    mailbox::{INBOX_ID, JUNK_ID, SENT_ID},
+    submission::EmailSubmission,
+};
+use common::{Server, auth::BuildAccessToken, telemetry::audit::AuditSource};
+use mail_builder::{MessageBuilder, headers::HeaderType};
+use mail_parser::{MessageParser, parsers::fields::thread::thread_name};
+use registry::schema::enums::AuditEventType;
+use std::future::Future;
+use store::{
+    IterateParams, U32_LEN, ValueKey,
+    ahash::AHashMap,
+    roaring::RoaringBitmap,
+    write::{
+        AlignedBytes, Archive, BatchBuilder, IndexPropertyClass, ValueClass,
+        key::DeserializeBigEndian, now,
+    },
+};
+use trc::AddContext;
+use types::{
+    collection::Collection,
+    field::{EmailField, EmailSubmissionField},
+    id::Id,
+    keyword::ArchivedKeyword,
+    special_use::SpecialUse,
+};
+use utils::cheeky_hash::CheekyHash;
+
+// Number of times a copy is re-evaluated when it changes while being recalled
+const MAX_RECALL_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecallStatus {
+    Recalled,
+    Replaced,
+    Read,
+    Moved,
+    NotFound,
+    NotLocal,
+    Expired,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecallOutcome {
+    pub recipient: String,
+    pub status: RecallStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecallError {
+    NotFound,
+    NotSender,
+    NoMessageId,
+}
+
+// Sent message being recalled, shared by the checks on each delivered copy
+struct RecallMessage<'x> {
+    from: &'x str,
+    subject: &'x str,
+    addresses: &'x [String],
+    message_ids: Vec<CheekyHash>,
+    notice: bool,
+    source: AuditSource<'x>,
+}
+
+pub trait EmailRecall: Sync + Send {
+    fn email_recall(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        source: AuditSource<'_>,
+    ) -> impl Future<Output = trc::Result<Result<Vec<RecallOutcome>, RecallError>>> + Send;
+}
+
+impl EmailRecall for Server {
+    async fn email_recall(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        source: AuditSource<'_>,
+    ) -> trc::Result<Result<Vec<RecallOutcome>, RecallError>> {
+        let Some(metadata_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(Err(RecallError::NotFound));
+        };
+        let metadata = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?;
+        let root_part = metadata.root_part();
+
+        // Only messages sent from this account may be recalled, either filed in
+        // its Sent mailbox or submitted through an EmailSubmission
+        let submission_recipients = self
+            .email_submission_recipients(account_id, document_id)
+            .await
+            .caused_by(trc::location!())?;
+        if submission_recipients.is_none() {
+            let cache = self
+                .get_cached_messages(account_id)
+                .await
+                .caused_by(trc::location!())?;
+            let sent_id = cache
+                .mailbox_by_role(&SpecialUse::Sent)
+                .map(|m| m.document_id)
+                .unwrap_or(SENT_ID);
+            if !cache.email_by_id(&document_id).is_some_and(|email| {
+                email
+                    .mailboxes
+                    .iter()
+                    .any(|mailbox| mailbox.mailbox_id == sent_id)
+            }) {
+                return Ok(Err(RecallError::NotSender));
+            }
+        }
+        let account = self
+            .account_info(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        let Some(from) = root_part.from().filter(|from| {
+            account
+                .addresses()
+                .iter()
+                .any(|address| address.eq_ignore_ascii_case(from))
+        }) else {
+            return Ok(Err(RecallError::NotSender));
+        };
+
+        if root_part.message_id().is_none_or(|id| id.is_empty()) {
+            return Ok(Err(RecallError::NoMessageId));
+        }
+
+        // Delivered copies share the thread name and message references of the sent message
+        let mut message_ids = Vec::new();
+        for header in root_part.headers.iter() {
+            if matches!(
+                header.name,
+                ArchivedMetadataHeaderName::MessageId
+                    | ArchivedMetadataHeaderName::InReplyTo
+                    | ArchivedMetadataHeaderName::References
+                    | ArchivedMetadataHeaderName::ResentMessageId
+            ) {
+                header.value.visit_text(|id| {
+                    if !id.is_empty() {
+                        message_ids.push(CheekyHash::new(id.as_bytes()));
+                    }
+                });
+            }
+        }
+        message_ids.sort_unstable();
+        message_ids.dedup();
+        let subject = root_part.subject().unwrap_or_default();
+        let message = RecallMessage {
+            from,
+            subject,
+            addresses: account.addresses(),
+            message_ids,
+            notice: self.core.email.recall_notice,
+            source,
+        };
+
+        // Recipients are taken from the submission envelopes, which include
+        // Bcc recipients, or from the headers of messages only filed in Sent
+        let recipients = if let Some(recipients) = submission_recipients {
+            recipients
+        } else {
+            let mut recipients = Vec::new();
+            for name in [
+                MetadataHeaderName::To,
+                MetadataHeaderName::Cc,
+                MetadataHeaderName::Bcc,
+            ] {
+                for value in root_part.header_values(&name) {
+                    value.visit_addresses(|element, address| {
+                        if element == AddressElement::Address {
+                            let address = address.trim().to_lowercase();
+                            if !address.is_empty() && !recipients.contains(&address) {
+                                recipients.push(address);
+                            }
+                        }
+                    });
+                }
+            }
+            recipients
+        };
+
+        let mut outcomes = Vec::with_capacity(recipients.len());
+        let mut account_status: AHashMap<u32, RecallStatus> = AHashMap::new();
+        for recipient in recipients {
+            let status = match self.account_id_from_email(&recipient, true).await {
+                Ok(Some(rcpt_account_id)) if rcpt_account_id == account_id => continue,
+                Ok(Some(rcpt_account_id)) => {
+                    if let Some(status) = account_status.get(&rcpt_account_id) {
+                        *status
+                    } else {
+                        let status = match self
+                            .email_recall_copies(rcpt_account_id, &recipient, &message)
+                            .await
+                        {
+                            Ok(status) => status,
+                            Err(err) => {
+                                trc::error!(
+                                    err.account_id(rcpt_account_id)
+                                        .ctx(trc::Key::To, recipient.clone())
+                                        .details("Failed to recall message")
+                                        .caused_by(trc::location!())
+                                );
+                                RecallStatus::Failed
+                            }
+                        };
+                        account_status.insert(rcpt_account_id, status);
+                        status
+                    }
+                }
+                Ok(None) => RecallStatus::NotLocal,
+                Err(err) => {
+                    trc::error!(
+                        err.ctx(trc::Key::To, recipient.clone())
+                            .details("Failed to lookup recipient")
+                            .caused_by(trc::location!())
+                    );
+                    RecallStatus::Failed
+                }
+            };
+
+            outcomes.push(RecallOutcome { recipient, status });
+        }
+
+        Ok(Ok(outcomes))
+    }
+}
+
+trait EmailRecallCopy: Sync + Send {
+    fn email_submission_recipients(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<Vec<String>>>> + Send;
+
+    fn email_recall_copies(
+        &self,
+        account_id: u32,
+        recipient: &str,
+        message: &RecallMessage<'_>,
+    ) -> impl Future<Output = trc::Result<RecallStatus>> + Send;
+
+    fn email_recall_copy(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        message: &RecallMessage<'_>,
+    ) -> impl Future<Output = trc::Result<RecallStatus>> + Send;
+}
+
+impl EmailRecallCopy for Server {
+    // Envelope recipients of the submissions that sent a message, or None if
+    // the message was not sent through an EmailSubmission
+    async fn email_submission_recipients(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<Option<Vec<String>>> {
+        let mut submission_ids = Vec::new();
+        self.store()
+            .iterate(
+                IterateParams::new(
+                    ValueKey {
+                        account_id,
+                        collection: Collection::EmailSubmission.into(),
+                        document_id: 0,
+                        class: ValueClass::IndexProperty(IndexPropertyClass::Integer {
+                            property: EmailSubmissionField::Metadata.into(),
+                            value: 0,
+                        }),
+                    },
+                    ValueKey {
+                        account_id,
+                        collection: Collection::EmailSubmission.into(),
+                        document_id: u32::MAX,
+                        class: ValueClass::IndexProperty(IndexPropertyClass::Integer {
+                            property: EmailSubmissionField::Metadata.into(),
+                            value: u64::MAX,
+                        }),
+                    },
+                )
+                .ascending(),
+                |key, value| {
+                    if value.deserialize_be_u32(0)? == document_id {
+                        submission_ids.push(key.deserialize_be_u32(key.len() - U32_LEN)?);
+                    }
+
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())?;
+
+        if submission_ids.is_empty() {
+            return Ok(None);
+        }
+
+        let mut recipients = Vec::new();
+        for submission_id in submission_ids {
+            let Some(submission_) = self
+                .store()
+                .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                    account_id,
+                    Collection::EmailSubmission,
+                    submission_id,
+                ))
+                .await
+                .caused_by(trc::location!())?
+            else {
+                continue;
+            };
+            let submission = submission_
+                .unarchive::<EmailSubmission>()
+                .caused_by(trc::location!())?;
+            for rcpt in submission.envelope.rcpt_to.iter() {
+                let address = rcpt.email.trim().to_lowercase();
+                if !address.is_empty() && !recipients.contains(&address) {
+                    recipients.push(address);
+                }
+            }
+        }
+
+        Ok(Some(recipients))
+    }
+
+    async fn email_recall_copies(
+        &self,
+        account_id: u32,
+        recipient: &str,
+        message: &RecallMessage<'_>,
+    ) -> trc::Result<RecallStatus> {
+        let mut status = RecallStatus::NotFound;
+        for document_id in self
+            .find_thread_id(
+                account_id,
+                thread_name(message.subject),
+                &message.message_ids,
+            )
+            .await
+            .caused_by(trc::location!())?
+            .duplicate_ids
+        {
+            let copy_status = self
+                .email_recall_copy(account_id, document_id, message)
+                .await
+                .caused_by(trc::location!())?;
+            if copy_status == RecallStatus::Recalled || status == RecallStatus::NotFound {
+                status = copy_status;
+            }
+        }
+
+        if status == RecallStatus::Recalled && message.notice {
+            let domain = recipient
+                .rsplit_once('@')
+                .map(|(_, domain)| domain)
+                .unwrap_or(self.core.email.default_domain_name.as_str());
+            let notice = MessageBuilder::new()
+                .from(message.from)
+                .to(recipient)
+                .subject(format!("Recalled: {}", message.subject))
+                .header("Auto-Submitted", HeaderType::Text("auto-generated".into()))
+                .message_id(format!("recall.{account_id}.{}@{domain}", now()))
+                .text_body(format!(
+                    "{} recalled the message \"{}\". It has been removed from your mailbox.",
+                    message.from, message.subject
+                ))
+                .write_to_vec()
+                .unwrap_or_default();
+            let access_token = self
+                .access_token(account_id)
+                .await
+                .caused_by(trc::location!())?
+                .build();
+            match self
+                .email_ingest(IngestEmail {
+                    raw_message: &notice,
+                    blob_hash: None,
+                    message: MessageParser::new().parse(&notice),
+                    access_token: &access_token,
+                    mailbox_ids: vec![INBOX_ID],
+                    keywords: vec![],
+                    received_at: None,
+                    source: IngestSource::Import { deduplicate: false },
+                    session_id: 0,
+                })
+                .await
+            {
+                Ok(_) => {
+                    status = RecallStatus::Replaced;
+                }
+                Err(err) => {
+                    // The message was already recalled, report it as such
+                    trc::error!(
+                        err.account_id(account_id)
+                            .details("Failed to deliver recall notice")
+                            .caused_by(trc::location!())
+                    );
+                }
+            }
+        }
+
+        Ok(status)
+    }
+
+    async fn email_recall_copy(
+        &self,
+        account_id: u32,
+        document_id: u32,
+        message: &RecallMessage<'_>,
+    ) -> trc::Result<RecallStatus> {
+        // Copies sharing the thread but not sent from this account are not
+        // touched, and each copy can only be recalled for a limited time after
+        // it was delivered
+        let Some(metadata_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(RecallStatus::NotFound);
+        };
+        let metadata = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?;
+        let root_part = metadata.root_part();
+        let is_sender = |address: &str| {
+            message
+                .addresses
+                .iter()
+                .any(|item| item.eq_ignore_ascii_case(address))
+        };
+        if !root_part.from().is_some_and(is_sender)
+            || root_part
+                .header_value(&MetadataHeaderName::Sender)
+                .and_then(|header| header.as_single_address())
+                .and_then(|addr| addr.address.as_deref())
+                .is_some_and(|sender| !is_sender(sender))
+        {
+            return Ok(RecallStatus::NotFound);
+        }
+        let received_at = metadata.rcvd_attach.to_native() & MESSAGE_RECEIVED_MASK;
+        if received_at.saturating_add(self.core.email.recall_window.as_secs()) < now() {
+            return Ok(RecallStatus::Expired);
+        }
+
+        for _ in 0..MAX_RECALL_ATTEMPTS {
+            let Some(data_) = self
+                .store()
+                .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                    account_id,
+                    Collection::Email,
+                    document_id,
+                ))
+                .await
+                .caused_by(trc::location!())?
+            else {
+                return Ok(RecallStatus::NotFound);
+            };
+            let data = data_
+                .to_unarchived::<MessageData>()
+                .caused_by(trc::location!())?;
+
+            // Copies the recipient has already seen or filed away are left alone
+            if data
+                .inner
+                .keywords
+                .iter()
+                .any(|keyword| matches!(keyword, ArchivedKeyword::Seen))
+            {
+                return Ok(RecallStatus::Read);
+            } else if data.inner.mailboxes.is_empty() {
+                return Ok(RecallStatus::NotFound);
+            } else if data
+                .inner
+                .mailboxes
+                .iter()
+                .any(|mailbox| !matches!(mailbox.mailbox_id.to_native(), INBOX_ID | JUNK_ID))
+            {
+                return Ok(RecallStatus::Moved);
+            }
+
+            // Make sure the copy was not modified since it was checked
+            let tenant_id = self
+                .account_info(account_id)
+                .await
+                .caused_by(trc::location!())?
+                .tenant_id();
+            let mut batch = BatchBuilder::new();
+            batch
+                .with_account_id(account_id)
+                .with_collection(Collection::Email)
+                .with_document(document_id)
+                .assert_value(ValueClass::Property(EmailField::Archive.into()), &data);
+            let not_destroyed = self
+                .emails_delete(
+                    account_id,
+                    tenant_id,
+                    &mut batch,
+                    RoaringBitmap::from_iter([document_id]),
+                )
+                .await
+                .caused_by(trc::location!())?;
+            if !not_destroyed.is_empty() {
+                return Ok(RecallStatus::NotFound);
+            }
+
+            match self.commit_batch(batch).await {
+                Ok(_) => {
+                    self.notify_task_queue();
+                    self.audit(
+                        message.source,
+                        account_id,
+                        AuditEventType::MessageDelete,
+                        [Id::from(document_id)],
+                    )
+                    .await;
+                    return Ok(RecallStatus::Recalled);
+                }
+                Err(err) if err.is_assertion_failure() => {}
+                Err(err) => return Err(err.caused_by(trc::location!())),
+            }
+        }
+
+        Ok(RecallStatus::Failed)
+    }
+}
@@ -0,0 +1,405 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::Server;
+use mail_parser::Message;
+use registry::schema::enums::SharedBlobQuota;
+use std::future::Future;
+use store::{
+    Deserialize, IterateParams, SerializeInfallible, U32_LEN, ValueKey,
+    ahash::AHashMap,
+    write::{
+        AssignedIds, BatchBuilder, MergeResult, Params, ValueClass,
+        assert::{AssertValue, ToAssertValue},
+        key::DeserializeBigEndian,
+    },
+};
+use trc::AddContext;
+use types::{
+    blob_hash::{BLOB_HASH_LEN, BlobHash},
+    collection::Collection,
+    field::EmailField,
+};
+
+// Attachments smaller than this are only charged as part of their message
+const MIN_SHARED_ATTACHMENT_SIZE: usize = 1024;
+
+// Contents referenced by a message: the message blob followed by its larger
+// attachments. Each entry holds the number of bytes it accounts for, which
+// add up to the size of the message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageReferences {
+    pub size: u32,
+    pub blobs: Vec<(BlobHash, u32)>,
+}
+
+// Number of messages in the account referencing a blob, and the size charged
+// against the account's quota by the first of them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlobReference {
+    pub count: u32,
+    pub charged: u32,
+}
+
+// Reference changes of one account, collected before they are written so that
+// every blob is updated once per batch
+pub struct BlobReferences {
+    account_id: u32,
+    tenant_id: Option<u32>,
+    size: i64,
+    blobs: AHashMap<BlobHash, (i64, u32)>,
+}
+
+pub trait EmailSharedBlob: Sync + Send {
+    fn blob_reference(
+        &self,
+        account_id: u32,
+        hash: &BlobHash,
+    ) -> impl Future<Output = trc::Result<Option<BlobReference>>> + Send;
+
+    fn blob_reference_charge(
+        &self,
+        account_id: u32,
+        references: &MessageReferences,
+    ) -> impl Future<Output = trc::Result<u64>> + Send;
+
+    fn write_blob_references(
+        &self,
+        batch: &mut BatchBuilder,
+        references: BlobReferences,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn account_blob_references(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<AHashMap<BlobHash, BlobReference>>> + Send;
+
+    fn account_message_references(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<AHashMap<u32, MessageReferences>>> + Send;
+}
+
+impl EmailSharedBlob for Server {
+    async fn blob_reference(
+        &self,
+        account_id: u32,
+        hash: &BlobHash,
+    ) -> trc::Result<Option<BlobReference>> {
+        self.store()
+            .get_value::<BlobReference>(ValueKey {
+                account_id,
+                collection: 0,
+                document_id: 0,
+                class: ValueClass::BlobReference(hash.clone()),
+            })
+            .await
+            .caused_by(trc::location!())
+    }
+
+    // Quota needed to store a message in an account
+    async fn blob_reference_charge(
+        &self,
+        account_id: u32,
+        references: &MessageReferences,
+    ) -> trc::Result<u64> {
+        if self.core.email.shared_blob_quota == SharedBlobQuota::ChargeEachReference {
+            return Ok(references.size as u64);
+        }
+
+        let mut charge = 0;
+        for (hash, size) in &references.blobs {
+            if self.blob_reference(account_id, hash).await?.is_none() {
+                charge += *size as u64;
+            }
+        }
+        Ok(charge)
+    }
+
+    // Reference counts are updated in the same batch as the messages, and the
+    // quota is corrected for contents that were already charged. Under the
+    // charge once policy the batch fails with an assertion error if a blob
+    // gained its first or lost its last reference after it was read, so the
+    // correction is never applied twice.
+    async fn write_blob_references(
+        &self,
+        batch: &mut BatchBuilder,
+        references: BlobReferences,
+    ) -> trc::Result<()> {
+        let is_charge_once = self.core.email.shared_blob_quota == SharedBlobQuota::ChargeOnce;
+        let account_id = references.account_id;
+        let mut quota = if is_charge_once { -references.size } else { 0 };
+
+        batch.with_account_id(account_id);
+        for (hash, (delta, charged)) in references.blobs {
+            if delta == 0 {
+                continue;
+            }
+            let current = self
+                .blob_reference(account_id, &hash)
+                .await?
+                .unwrap_or_default();
+            let updated = current.count as i64 + delta;
+            if current.count == 0 && updated > 0 {
+                quota += charged as i64;
+            } else if current.count > 0 && updated <= 0 {
+                quota -= current.charged as i64;
+            }
+
+            batch.merge_fnc(
+                ValueClass::BlobReference(hash),
+                Params::with_capacity(5)
+                    .with_i64(delta)
+                    .with_u64(current.count as u64)
+                    .with_u64(current.charged as u64)
+                    .with_u64(charged as u64)
+                    .with_bool(is_charge_once),
+                merge_blob_reference,
+            );
+        }
+
+        if is_charge_once && quota != 0 {
+            batch.add(ValueClass::Quota, quota);
+            if let Some(tenant_id) = references.tenant_id {
+                batch.add(ValueClass::TenantQuota(tenant_id), quota);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn account_blob_references(
+        &self,
+        account_id: u32,
+    ) -> trc::Result<AHashMap<BlobHash, BlobReference>> {
+        let mut references = AHashMap::new();
+        self.store()
+            .iterate(
+                IterateParams::new(
+                    ValueKey {
+                        account_id,
+                        collection: 0,
+                        document_id: 0,
+                        class: ValueClass::BlobReference(BlobHash::default()),
+                    },
+                    ValueKey {
+                        account_id,
+                        collection: 0,
+                        document_id: 0,
+                        class: ValueClass::BlobReference(BlobHash::new_max()),
+                    },
+                )
+                .ascending(),
+                |key, value| {
+                    let hash = key
+                        .get(U32_LEN + 1..)
+                        .and_then(|hash| BlobHash::try_from_hash_slice(hash).ok())
+                        .ok_or_else(|| trc::Error::corrupted_key(key, None, trc::location!()))?;
+                    references.insert(hash, BlobReference::deserialize(value)?);
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())
+            .map(|_| references)
+    }
+
+    async fn account_message_references(
+        &self,
+        account_id: u32,
+    ) -> trc::Result<AHashMap<u32, MessageReferences>> {
+        let mut references = AHashMap::new();
+        self.store()
+            .iterate(
+                IterateParams::new(
+                    ValueKey::property(account_id, Collection::Email, 0, EmailField::References),
+                    ValueKey::property(
+                        account_id,
+                        Collection::Email,
+                        u32::MAX,
+                        EmailField::References,
+                    ),
+                )
+                .ascending(),
+                |key, value| {
+                    references.insert(
+                        key.deserialize_be_u32(key.len() - U32_LEN)?,
+                        MessageReferences::deserialize(value)?,
+                    );
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!())
+            .map(|_| references)
+    }
+}
+
+fn merge_blob_reference(
+    params: &Params,
+    _: &AssignedIds,
+    bytes: Option<&[u8]>,
+) -> trc::Result<MergeResult> {
+    let current = bytes
+        .map(BlobReference::deserialize)
+        .transpose()?
+        .unwrap_or_default();
+    let delta = params.i64(0);
+    let updated = current.count as i64 + delta;
+
+    if params.bool(4) {
+        let read_count = params.u64(1) as i64;
+        if (current.count == 0) != (read_count == 0)
+            || (updated <= 0) != (read_count + delta <= 0)
+            || (current.count > 0 && current.charged as u64 != params.u64(2))
+        {
+            return Err(trc::StoreEvent::AssertValueFailed
+                .into_err()
+                .details("Blob references changed, likely due to concurrent modification.")
+                .caused_by(trc::location!()));
+        }
+    }
+
+    if updated > 0 {
+        Ok(MergeResult::Update(
+            BlobReference {
+                count: updated as u32,
+                charged: if current.count > 0 {
+                    current.charged
+                } else {
+                    params.u64(3) as u32
+                },
+            }
+            .serialize(),
+        ))
+    } else if bytes.is_some() {
+        Ok(MergeResult::Delete)
+    } else {
+        Ok(MergeResult::Skip)
+    }
+}
+
+impl MessageReferences {
+    pub fn new(message: &Message<'_>, blob_hash: BlobHash, size: u32) -> Self {
+        let mut references = MessageReferences {
+            size,
+            blobs: vec![(blob_hash, size)],
+        };
+
+        for part in message.attachments() {
+            let contents = part.contents();
+            let part_size = part.offset_end.saturating_sub(part.offset_body);
+            if contents.len() < MIN_SHARED_ATTACHMENT_SIZE || part_size > references.blobs[0].1 {
+                continue;
+            }
+            let hash = BlobHash::generate(contents);
+            if !references.blobs.iter().any(|(item, _)| item == &hash) {
+                references.blobs[0].1 -= part_size;
+                references.blobs.push((hash, part_size));
+            }
+        }
+
+        references
+    }
+
+    // Messages stored before references were tracked, or whose contents are
+    // encrypted, only reference their own blob
+    pub fn message(blob_hash: BlobHash, size: u32) -> Self {
+        MessageReferences {
+            size,
+            blobs: vec![(blob_hash, size)],
+        }
+    }
+}
+
+impl BlobReferences {
+    pub fn new(account_id: u32, tenant_id: Option<u32>) -> Self {
+        BlobReferences {
+            account_id,
+            tenant_id,
+            size: 0,
+            blobs: AHashMap::new(),
+        }
+    }
+
+    pub fn add(&mut self, references: &MessageReferences) {
+        self.size += references.size as i64;
+        for (hash, charged) in &references.blobs {
+            self.blobs.entry(hash.clone()).or_insert((0, *charged)).0 += 1;
+        }
+    }
+
+    pub fn remove(&mut self, references: &MessageReferences) {
+        self.size -= references.size as i64;
+        for (hash, charged) in &references.blobs {
+            self.blobs.entry(hash.clone()).or_insert((0, *charged)).0 -= 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blobs.is_empty()
+    }
+}
+
+impl SerializeInfallible for MessageReferences {
+    fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(U32_LEN + self.blobs.len() * (BLOB_HASH_LEN + U32_LEN));
+        bytes.extend_from_slice(&self.size.to_be_bytes());
+        for (hash, charged) in &self.blobs {
+            bytes.extend_from_slice(hash.as_slice());
+            bytes.extend_from_slice(&charged.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+impl Deserialize for MessageReferences {
+    fn deserialize(bytes: &[u8]) -> trc::Result<Self> {
+        let entries = bytes.get(U32_LEN..).unwrap_or_default();
+        if entries.len() % (BLOB_HASH_LEN + U32_LEN) != 0 {
+            return Err(trc::StoreEvent::DataCorruption
+                .caused_by(trc::location!())
+                .ctx(trc::Key::Value, bytes));
+        }
+
+        Ok(MessageReferences {
+            size: bytes.deserialize_be_u32(0)?,
+            blobs: entries
+                .chunks_exact(BLOB_HASH_LEN + U32_LEN)
+                .map(|entry| {
+                    Ok((
+                        BlobHash::try_from_hash_slice(&entry[..BLOB_HASH_LEN]).unwrap(),
+                        entry.deserialize_be_u32(BLOB_HASH_LEN)?,
+                    ))
+                })
+                .collect::<trc::Result<_>>()?,
+        })
+    }
+}
+
+impl SerializeInfallible for BlobReference {
+    fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(U32_LEN * 2);
+        bytes.extend_from_slice(&self.count.to_be_bytes());
+        bytes.extend_from_slice(&self.charged.to_be_bytes());
+        bytes
+    }
+}
+
+impl ToAssertValue for BlobReference {
+    fn to_assert_value(&self) -> AssertValue {
+        AssertValue::U64(((self.count as u64) << 32) | self.charged as u64)
+    }
+}
+
+impl Deserialize for BlobReference {
+    fn deserialize(bytes: &[u8]) -> trc::Result<Self> {
+        Ok(BlobReference {
+            count: bytes.deserialize_be_u32(0)?,
+            charged: bytes.deserialize_be_u32(U32_LEN)?,
+        })
+    }
+}
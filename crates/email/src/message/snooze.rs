@@ -0,0 +1,304 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{ingest::EmailIngest, metadata::MessageData};
+use crate::{
+    cache::{MessageCacheFetch, mailbox::MailboxCacheAccess},
+    mailbox::{INBOX_ID, Mailbox, UidMailbox},
+};
+use common::{Server, storage::index::ObjectIndexBuilder};
+use std::future::Future;
+use store::{
+    SerializeInfallible, U32_LEN, U64_LEN, ValueKey,
+    write::{AlignedBytes, Archive, BatchBuilder, ValueClass, key::DeserializeBigEndian, now},
+};
+use trc::AddContext;
+use types::{
+    collection::{Collection, SyncCollection, VanishedCollection},
+    field::{EmailField, PrincipalField},
+    keyword::Keyword,
+    special_use::SpecialUse,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailSnooze {
+    pub until: u64,
+    pub mailbox_id: u32,
+    // Other mailboxes the message belonged to when it was snoozed
+    pub other_mailbox_ids: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozedMailbox {
+    Existing(u32),
+    New {
+        document_id: u32,
+        // Last Snoozed mailbox created for the account, it may have been deleted since
+        previous_id: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsnoozeResult {
+    Restored,
+    NotSnoozed,
+    NotDue,
+}
+
+pub trait EmailSnoozeFnc: Sync + Send {
+    fn snoozed_mailbox_id(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<SnoozedMailbox>> + Send;
+
+    fn create_snoozed_mailbox(
+        &self,
+        batch: &mut BatchBuilder,
+        account_id: u32,
+        document_id: u32,
+        previous_id: Option<u32>,
+    ) -> trc::Result<()>;
+
+    fn email_snooze(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<EmailSnooze>>> + Send;
+
+    fn email_unsnooze(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<UnsnoozeResult>> + Send;
+}
+
+impl EmailSnoozeFnc for Server {
+    // The Snoozed mailbox is created on first use, new mailboxes are written
+    // with create_snoozed_mailbox in the same batch as the snoozed message
+    async fn snoozed_mailbox_id(&self, account_id: u32) -> trc::Result<SnoozedMailbox> {
+        let cache = self
+            .get_cached_messages(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        if let Some(mailbox) = cache.mailbox_by_role(&SpecialUse::Snoozed) {
+            return Ok(SnoozedMailbox::Existing(mailbox.document_id));
+        }
+
+        let previous_id = self
+            .store()
+            .get_value::<u32>(ValueKey::property(
+                account_id,
+                Collection::Principal,
+                0,
+                PrincipalField::SnoozedMailboxId,
+            ))
+            .await
+            .caused_by(trc::location!())?;
+        self.store()
+            .assign_document_ids(account_id, Collection::Mailbox, 1)
+            .await
+            .caused_by(trc::location!())
+            .map(|document_id| SnoozedMailbox::New {
+                document_id,
+                previous_id,
+            })
+    }
+
+    fn create_snoozed_mailbox(
+        &self,
+        batch: &mut BatchBuilder,
+        account_id: u32,
+        document_id: u32,
+        previous_id: Option<u32>,
+    ) -> trc::Result<()> {
+        let name = self
+            .core
+            .email
+            .default_folders
+            .iter()
+            .find(|folder| folder.special_use == SpecialUse::Snoozed)
+            .map_or("Snoozed", |folder| folder.name.as_str());
+        let mut mailbox = Mailbox::new(name).with_role(SpecialUse::Snoozed);
+        mailbox.add_subscriber(account_id);
+
+        // Concurrent first snoozes race to replace the same id, the losing
+        // batch fails its assertion instead of creating a second mailbox
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Principal)
+            .with_document(0);
+        match previous_id {
+            Some(previous_id) => batch.assert_value(PrincipalField::SnoozedMailboxId, previous_id),
+            None => batch.assert_value(PrincipalField::SnoozedMailboxId, ()),
+        };
+        batch.set(PrincipalField::SnoozedMailboxId, document_id.serialize());
+
+        batch
+            .with_collection(Collection::Mailbox)
+            .with_document(document_id)
+            .custom(ObjectIndexBuilder::<(), _>::new().with_changes(mailbox))
+            .caused_by(trc::location!())
+            .map(|_| ())
+    }
+
+    async fn email_snooze(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<Option<EmailSnooze>> {
+        self.store()
+            .get_value::<EmailSnooze>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Snooze,
+            ))
+            .await
+            .caused_by(trc::location!())
+    }
+
+    async fn email_unsnooze(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<UnsnoozeResult> {
+        let Some(snooze) = self
+            .email_snooze(account_id, document_id)
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(UnsnoozeResult::NotSnoozed);
+        };
+        if snooze.until > now() {
+            // The message was snoozed again after this restore was scheduled
+            return Ok(UnsnoozeResult::NotDue);
+        }
+        let Some(data_) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                account_id,
+                Collection::Email,
+                document_id,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(UnsnoozeResult::NotSnoozed);
+        };
+        let data = data_
+            .to_unarchived::<MessageData>()
+            .caused_by(trc::location!())?;
+        let cache = self
+            .get_cached_messages(account_id)
+            .await
+            .caused_by(trc::location!())?;
+
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Email)
+            .with_document(document_id)
+            .clear(ValueClass::Property(EmailField::Snooze.into()));
+
+        // Messages moved out of the Snoozed mailbox in the meantime stay where they are
+        let mut result = UnsnoozeResult::NotSnoozed;
+        if let Some((snoozed_id, uid)) =
+            cache
+                .mailbox_by_role(&SpecialUse::Snoozed)
+                .and_then(|mailbox| {
+                    data.inner
+                        .message_uid(mailbox.document_id)
+                        .map(|uid| (mailbox.document_id, uid))
+                })
+        {
+            // Move the message back to all its mailboxes and mark it as unseen
+            let mut new_data = data.inner.to_builder();
+            new_data.remove_mailbox(snoozed_id);
+            new_data.remove_keyword(&Keyword::Seen);
+            for restore_id in snooze
+                .restore_mailbox_ids(snoozed_id, |mailbox_id| cache.has_mailbox_id(&mailbox_id))
+            {
+                if !new_data
+                    .mailboxes
+                    .iter()
+                    .any(|mailbox| mailbox.mailbox_id == restore_id)
+                {
+                    let restore_uid = *self
+                        .assign_mailbox_uids(account_id, restore_id, 1)
+                        .await
+                        .caused_by(trc::location!())?
+                        .start();
+                    new_data.add_mailbox(UidMailbox::new(restore_id, restore_uid));
+                    batch.log_container_property_change(SyncCollection::Email, restore_id);
+                }
+            }
+            batch
+                .custom(
+                    ObjectIndexBuilder::new()
+                        .with_current(data)
+                        .with_changes(new_data.seal()),
+                )
+                .caused_by(trc::location!())?
+                .log_container_property_change(SyncCollection::Email, snoozed_id)
+                .log_vanished_item(VanishedCollection::Email, (snoozed_id, uid));
+            result = UnsnoozeResult::Restored;
+        }
+
+        self.commit_batch(batch)
+            .await
+            .caused_by(trc::location!())
+            .map(|_| result)
+    }
+}
+
+impl EmailSnooze {
+    // Mailboxes a message is moved back to, falling back to the Inbox when
+    // the restore mailbox no longer exists
+    pub fn restore_mailbox_ids(&self, snoozed_id: u32, exists: impl Fn(u32) -> bool) -> Vec<u32> {
+        let mut mailbox_ids = Vec::with_capacity(self.other_mailbox_ids.len() + 1);
+        mailbox_ids.push(
+            if self.mailbox_id != snoozed_id && exists(self.mailbox_id) {
+                self.mailbox_id
+            } else {
+                INBOX_ID
+            },
+        );
+        for &mailbox_id in &self.other_mailbox_ids {
+            if mailbox_id != snoozed_id && !mailbox_ids.contains(&mailbox_id) && exists(mailbox_id)
+            {
+                mailbox_ids.push(mailbox_id);
+            }
+        }
+        mailbox_ids
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(U64_LEN + U32_LEN * (self.other_mailbox_ids.len() + 1));
+        bytes.extend_from_slice(&self.until.to_be_bytes());
+        bytes.extend_from_slice(&self.mailbox_id.to_be_bytes());
+        for mailbox_id in &self.other_mailbox_ids {
+            bytes.extend_from_slice(&mailbox_id.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+impl store::Deserialize for EmailSnooze {
+    fn deserialize(bytes: &[u8]) -> trc::Result<Self> {
+        let mut other_mailbox_ids = Vec::new();
+        let mut offset = U64_LEN + U32_LEN;
+        while offset < bytes.len() {
+            other_mailbox_ids.push(bytes.deserialize_be_u32(offset)?);
+            offset += U32_LEN;
+        }
+
+        Ok(EmailSnooze {
+            until: bytes.deserialize_be_u64(0)?,
+            mailbox_id: bytes.deserialize_be_u32(U64_LEN)?,
+            other_mailbox_ids,
+        })
+    }
+}
@@ -0,0 +1,445 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    cache::{MessageCacheFetch, email::MessageCacheAccess},
+    mailbox::INBOX_ID,
+    message::metadata::MessageMetadata,
+};
+use common::{KV_URLAUTH_KEY, Server};
+use mail_parser::{DateTime, MessageParser, MessagePart, PartType};
+use percent_encoding::percent_decode_str;
+use ring::hmac;
+use std::future::Future;
+use store::{
+    ValueKey,
+    dispatch::lookup::KeyValue,
+    write::{AlignedBytes, Archive, now},
+};
+use trc::AddContext;
+use types::{collection::Collection, field::EmailField};
+use utils::HexEncode;
+
+pub const URLAUTH_MECHANISM: &str = "INTERNAL";
+
+// Mailbox keys are rotated once they expire, revoking the URLs they authorized
+const URLAUTH_KEY_EXPIRY: u64 = 30 * 24 * 60 * 60;
+
+// Authorized IMAP URL as defined in RFC 4467, restricted to
+// messages or sections of messages owned by the URL user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImapUrl {
+    pub user: String,
+    pub mailbox: String,
+    pub uid_validity: Option<u32>,
+    pub uid: u32,
+    pub section: Option<String>,
+    pub expire: Option<i64>,
+    pub access: UrlAccess,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlAccess {
+    Submit(String),
+    User(String),
+    AuthUser,
+    Anonymous,
+}
+
+pub trait UrlAuthorization: Sync + Send {
+    fn urlauth_generate(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+        rump: &str,
+    ) -> impl Future<Output = trc::Result<String>> + Send;
+
+    fn urlauth_reset(
+        &self,
+        account_id: u32,
+        mailbox_id: Option<u32>,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn urlauth_fetch(
+        &self,
+        account_id: u32,
+        account_name: &str,
+        url: &str,
+    ) -> impl Future<Output = trc::Result<Vec<u8>>> + Send;
+}
+
+impl UrlAuthorization for Server {
+    async fn urlauth_generate(
+        &self,
+        account_id: u32,
+        mailbox_id: u32,
+        rump: &str,
+    ) -> trc::Result<String> {
+        let key = match mailbox_key(self, account_id, mailbox_id).await? {
+            Some(key) => key,
+            None => {
+                // Key creation is serialized so that concurrent sessions do not
+                // overwrite each other's key and invalidate the URLs it signed
+                let key_name = key_name(account_id, mailbox_id);
+                self.in_memory_update(KV_URLAUTH_KEY, &key_name[1..], async {
+                    if let Some(key) = mailbox_key(self, account_id, mailbox_id).await? {
+                        return Ok(key);
+                    }
+
+                    // Generate a new key for the mailbox
+                    let key = rand::random::<[u8; 32]>();
+                    self.in_memory_store()
+                        .key_set(
+                            KeyValue::new(key_name.clone(), key.hex_encode().into_bytes())
+                                .expires(URLAUTH_KEY_EXPIRY),
+                        )
+                        .await
+                        .caused_by(trc::location!())?;
+                    Ok(hmac::Key::new(hmac::HMAC_SHA256, &key))
+                })
+                .await?
+            }
+        };
+
+        Ok(format!(
+            "{rump}:{}:{}",
+            URLAUTH_MECHANISM.to_ascii_lowercase(),
+            hmac::sign(&key, rump.as_bytes()).hex_encode()
+        ))
+    }
+
+    async fn urlauth_reset(&self, account_id: u32, mailbox_id: Option<u32>) -> trc::Result<()> {
+        let store = self.in_memory_store();
+        if let Some(mailbox_id) = mailbox_id {
+            store.key_delete(key_name(account_id, mailbox_id)).await
+        } else {
+            store
+                .key_delete_prefix(&KeyValue::<()>::build_key(
+                    KV_URLAUTH_KEY,
+                    account_id.to_be_bytes(),
+                ))
+                .await
+        }
+        .caused_by(trc::location!())
+    }
+
+    async fn urlauth_fetch(
+        &self,
+        account_id: u32,
+        account_name: &str,
+        url: &str,
+    ) -> trc::Result<Vec<u8>> {
+        // Parse URL
+        let (rump, mechanism, token) = split_url(url).ok_or_else(|| {
+            trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid authorized IMAP URL.")
+        })?;
+        let url = ImapUrl::parse(rump).ok_or_else(|| {
+            trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid IMAP URL.")
+        })?;
+        if !mechanism.eq_ignore_ascii_case(URLAUTH_MECHANISM) {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Unsupported authorization mechanism."));
+        }
+
+        // Validate access
+        if !url.user.eq_ignore_ascii_case(account_name) || !url.access.allows(account_name) {
+            return Err(trc::SecurityEvent::Unauthorized
+                .into_err()
+                .details("URL access not permitted."));
+        }
+        if url.expire.is_some_and(|expire| expire <= now() as i64) {
+            return Err(trc::SecurityEvent::Unauthorized
+                .into_err()
+                .details("URL has expired."));
+        }
+
+        // Obtain mailbox
+        let cache = self
+            .get_cached_messages(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        let mailbox = cache
+            .mailboxes
+            .items
+            .iter()
+            .find(|mailbox| {
+                if url.mailbox.eq_ignore_ascii_case("inbox") {
+                    mailbox.document_id == INBOX_ID
+                } else {
+                    mailbox.path == url.mailbox
+                }
+            })
+            .filter(|mailbox| {
+                url.uid_validity
+                    .is_none_or(|uid_validity| uid_validity == mailbox.uid_validity)
+            })
+            .ok_or_else(|| {
+                trc::ResourceEvent::NotFound
+                    .into_err()
+                    .details("Mailbox not found.")
+            })?;
+
+        // Validate token
+        if !mailbox_key(self, account_id, mailbox.document_id)
+            .await?
+            .zip(hex_decode(token))
+            .is_some_and(|(key, token)| hmac::verify(&key, rump.as_bytes(), &token).is_ok())
+        {
+            return Err(trc::SecurityEvent::Unauthorized
+                .into_err()
+                .details("Invalid URLAUTH token."));
+        }
+
+        // Obtain message
+        let document_id = cache
+            .in_mailbox(mailbox.document_id)
+            .find(|message| {
+                message
+                    .mailboxes
+                    .iter()
+                    .any(|m| m.mailbox_id == mailbox.document_id && m.uid == url.uid)
+            })
+            .map(|message| message.document_id)
+            .ok_or_else(|| {
+                trc::ResourceEvent::NotFound
+                    .into_err()
+                    .details("Message not found.")
+            })?;
+        let metadata_ = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                document_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(|| {
+                trc::ResourceEvent::NotFound
+                    .into_err()
+                    .details("Message not found.")
+            })?;
+        let metadata = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?;
+        let raw_body = self
+            .blob_store()
+            .get_blob(metadata.blob_hash.0.as_slice(), 0..usize::MAX)
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(|| {
+                trc::StoreEvent::NotFound
+                    .into_err()
+                    .details("Blob not found.")
+                    .caused_by(trc::location!())
+            })?;
+        let mut raw_message = metadata.raw_headers.to_vec();
+        raw_message.extend_from_slice(
+            raw_body
+                .get(metadata.blob_body_offset.to_native() as usize..)
+                .unwrap_or_default(),
+        );
+
+        match &url.section {
+            Some(section) => message_section(&raw_message, section).ok_or_else(|| {
+                trc::ResourceEvent::NotFound
+                    .into_err()
+                    .details("Message section not found.")
+            }),
+            None => Ok(raw_message),
+        }
+    }
+}
+
+impl ImapUrl {
+    pub fn parse(rump: &str) -> Option<Self> {
+        let rest = rump
+            .get(..7)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("imap://"))
+            .and_then(|_| rump.get(7..))?;
+        let (authority, path) = rest.split_once('/')?;
+        let (user, _) = authority.rsplit_once('@')?;
+        let user = decode(user.split(';').next()?)?;
+        let mut segments = path.split("/;");
+        let mut params = segments.next()?.split(';');
+        let mailbox = decode(params.next()?)?;
+        if user.is_empty() || mailbox.is_empty() {
+            return None;
+        }
+
+        let mut url = ImapUrl {
+            user,
+            mailbox,
+            uid_validity: None,
+            uid: 0,
+            section: None,
+            expire: None,
+            access: UrlAccess::Anonymous,
+        };
+        let mut has_access = false;
+        for param in params.chain(segments.flat_map(|segment| segment.split(';'))) {
+            let (key, value) = param.split_once('=')?;
+            hashify::fnc_map_ignore_case!(key.as_bytes(),
+                "UIDVALIDITY" => {
+                    url.uid_validity = value.parse::<u32>().ok().filter(|v| *v > 0)?.into();
+                },
+                "UID" => {
+                    url.uid = value.parse::<u32>().ok().filter(|v| *v > 0)?;
+                },
+                "SECTION" => {
+                    url.section = decode(value)?.into();
+                },
+                "EXPIRE" => {
+                    url.expire = DateTime::parse_rfc3339(&decode(value)?)?.to_timestamp().into();
+                },
+                "URLAUTH" => {
+                    url.access = UrlAccess::parse(&decode(value)?)?;
+                    has_access = true;
+                },
+                _ => {
+                    return None;
+                }
+            );
+        }
+
+        if url.uid > 0 && has_access {
+            Some(url)
+        } else {
+            None
+        }
+    }
+}
+
+impl UrlAccess {
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some((prefix, user)) = value.split_once('+') {
+            if user.is_empty() {
+                None
+            } else if prefix.eq_ignore_ascii_case("submit") {
+                Some(UrlAccess::Submit(user.to_string()))
+            } else if prefix.eq_ignore_ascii_case("user") {
+                Some(UrlAccess::User(user.to_string()))
+            } else {
+                None
+            }
+        } else if value.eq_ignore_ascii_case("authuser") {
+            Some(UrlAccess::AuthUser)
+        } else if value.eq_ignore_ascii_case("anonymous") {
+            Some(UrlAccess::Anonymous)
+        } else {
+            None
+        }
+    }
+
+    pub fn allows(&self, account_name: &str) -> bool {
+        match self {
+            UrlAccess::Submit(user) | UrlAccess::User(user) => {
+                user.eq_ignore_ascii_case(account_name)
+            }
+            UrlAccess::AuthUser | UrlAccess::Anonymous => true,
+        }
+    }
+}
+
+// Splits an authorized URL into its rump, mechanism and token
+pub fn split_url(url: &str) -> Option<(&str, &str, &str)> {
+    let (rest, token) = url.rsplit_once(':')?;
+    let (rump, mechanism) = rest.rsplit_once(':')?;
+    if token.len() >= 32
+        && token.bytes().all(|ch| ch.is_ascii_hexdigit())
+        && !mechanism.is_empty()
+        && mechanism
+            .bytes()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, b'-' | b'.'))
+    {
+        Some((rump, mechanism, token))
+    } else {
+        None
+    }
+}
+
+fn message_section(raw_message: &[u8], section: &str) -> Option<Vec<u8>> {
+    let message = MessageParser::new().parse(raw_message)?;
+    let mut part: &MessagePart = message.root_part();
+    let mut is_root = true;
+    let mut items = section.split('.').peekable();
+
+    while let Some(item) = items.next() {
+        if let Ok(num) = item.parse::<usize>() {
+            part = match &part.body {
+                PartType::Multipart(children) => message
+                    .parts
+                    .get(*children.get(num.checked_sub(1)?)? as usize)?,
+                _ if num == 1 && is_root => part,
+                _ => return None,
+            };
+            is_root = false;
+        } else if items.peek().is_none() {
+            let range = hashify::tiny_map_ignore_case!(item.as_bytes(),
+                "HEADER" => (part.offset_header, part.offset_body),
+                "TEXT" => (part.offset_body, part.offset_end),
+                "MIME" => (part.offset_header, part.offset_body),
+            )?;
+            return raw_message
+                .get(range.0 as usize..range.1 as usize)
+                .map(|bytes| bytes.to_vec());
+        } else {
+            return None;
+        }
+    }
+
+    raw_message
+        .get(part.offset_body as usize..part.offset_end as usize)
+        .map(|bytes| bytes.to_vec())
+}
+
+async fn mailbox_key(
+    server: &Server,
+    account_id: u32,
+    mailbox_id: u32,
+) -> trc::Result<Option<hmac::Key>> {
+    server
+        .in_memory_store()
+        .key_get::<String>(key_name(account_id, mailbox_id))
+        .await
+        .caused_by(trc::location!())
+        .map(|key| {
+            key.and_then(|key| hex_decode(&key))
+                .map(|key| hmac::Key::new(hmac::HMAC_SHA256, &key))
+        })
+}
+
+fn key_name(account_id: u32, mailbox_id: u32) -> Vec<u8> {
+    let mut key = Vec::with_capacity(9);
+    key.push(KV_URLAUTH_KEY);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key.extend_from_slice(&mailbox_id.to_be_bytes());
+    key
+}
+
+fn decode(value: &str) -> Option<String> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .ok()
+        .map(|value| value.into_owned())
+}
+
+fn hex_decode(value: &str) -> Option<Vec<u8>> {
+    (0..value.len())
+        .step_by(2)
+        .map(|pos| {
+            value
+                .get(pos..pos + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
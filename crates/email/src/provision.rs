@@ -0,0 +1,195 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{mailbox::manage::MailboxFnc, sieve::SieveScript};
+use common::{
+    Server, config::mailstore::email::AccountTemplate, storage::index::ObjectIndexBuilder,
+};
+use std::{future::Future, sync::Arc};
+use store::{
+    SerializeInfallible, ValueKey,
+    write::{Archiver, BatchBuilder, ValueClass, now},
+};
+use trc::AddContext;
+use types::{
+    collection::Collection,
+    field::{PrincipalField, SieveField},
+};
+
+const PROVISIONED_SCRIPT_NAME: &str = "default";
+
+/// Account template resolved for a specific account, with the values used
+/// to expand template variables.
+#[derive(Debug, Clone)]
+pub struct ResolvedAccountTemplate {
+    pub template: Arc<AccountTemplate>,
+    pub name: String,
+    pub domain: String,
+}
+
+pub trait AccountProvisioning: Sync + Send {
+    fn account_template(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<ResolvedAccountTemplate>>> + Send;
+
+    fn provision_account(&self, account_id: u32) -> impl Future<Output = trc::Result<bool>> + Send;
+}
+
+impl AccountProvisioning for Server {
+    async fn account_template(
+        &self,
+        account_id: u32,
+    ) -> trc::Result<Option<ResolvedAccountTemplate>> {
+        let account = self.account(account_id).await.caused_by(trc::location!())?;
+        let Some(address) = account.addresses.first() else {
+            return Ok(self.core.email.account_template.clone().map(|template| {
+                ResolvedAccountTemplate {
+                    template,
+                    name: account.name.to_string(),
+                    domain: self.core.email.default_domain_name.clone(),
+                }
+            }));
+        };
+
+        // Domain templates take precedence over the global template
+        let domain = self
+            .domain_by_id(address.domain_id)
+            .await
+            .caused_by(trc::location!())?;
+        Ok(domain
+            .as_ref()
+            .and_then(|domain| domain.account_template.clone())
+            .or_else(|| self.core.email.account_template.clone())
+            .map(|template| ResolvedAccountTemplate {
+                template,
+                name: address.local_part.to_string(),
+                domain: domain
+                    .as_ref()
+                    .map(|domain| domain.name().to_string())
+                    .unwrap_or_else(|| self.core.email.default_domain_name.clone()),
+            }))
+    }
+
+    async fn provision_account(&self, account_id: u32) -> trc::Result<bool> {
+        // Only accounts flagged on creation are provisioned, and only once
+        let marker = ValueKey {
+            account_id,
+            collection: Collection::Principal.into(),
+            document_id: 0,
+            class: ValueClass::Property(PrincipalField::Provisioned.into()),
+        };
+        if self
+            .store()
+            .get_value::<u64>(marker)
+            .await
+            .caused_by(trc::location!())?
+            != Some(0)
+        {
+            return Ok(false);
+        }
+
+        let mut batch = BatchBuilder::new();
+        batch.with_account_id(account_id);
+
+        if let Some(template) = self
+            .account_template(account_id)
+            .await
+            .caused_by(trc::location!())?
+        {
+            // Create folders, existing folders are left untouched
+            for folder in &template.template.folders {
+                let path = template.expand(folder);
+                if self
+                    .mailbox_create_path_subscribed(account_id, &path, template.template.subscribe)
+                    .await
+                    .caused_by(trc::location!())?
+                    .is_none()
+                {
+                    trc::error!(
+                        trc::ResourceEvent::BadParameters
+                            .into_err()
+                            .account_id(account_id)
+                            .details("Provisioning folder exceeds mailbox limits")
+                            .ctx(trc::Key::Path, path)
+                    );
+                }
+            }
+
+            // Create and activate the default Sieve script
+            if let Some(script) = &template.template.sieve_script
+                && self
+                    .document_ids(account_id, Collection::SieveScript, SieveField::Name)
+                    .await
+                    .caused_by(trc::location!())?
+                    .is_empty()
+            {
+                let script = template.expand(script).into_bytes();
+                match self.core.sieve.untrusted_compiler.compile(&script) {
+                    Ok(compiled) => {
+                        let size = script.len() as u32;
+                        let mut blob = script;
+                        blob.extend(
+                            Archiver::new(compiled)
+                                .untrusted()
+                                .serialize()
+                                .caused_by(trc::location!())?,
+                        );
+                        let (blob_hash, blob_hold) = self
+                            .put_temporary_blob(account_id, &blob, 60)
+                            .await
+                            .caused_by(trc::location!())?;
+                        let document_id = self
+                            .store()
+                            .assign_document_ids(account_id, Collection::SieveScript, 1)
+                            .await
+                            .caused_by(trc::location!())?;
+                        batch
+                            .with_collection(Collection::SieveScript)
+                            .with_document(document_id)
+                            .custom(
+                                ObjectIndexBuilder::<(), _>::new().with_changes(
+                                    SieveScript::new(PROVISIONED_SCRIPT_NAME, blob_hash)
+                                        .with_size(size),
+                                ),
+                            )
+                            .caused_by(trc::location!())?
+                            .clear(blob_hold)
+                            .with_collection(Collection::Principal)
+                            .with_document(0)
+                            .set(PrincipalField::ActiveScriptId, document_id.serialize());
+                    }
+                    Err(err) => {
+                        // Invalid scripts are a configuration error, retrying won't help
+                        trc::error!(
+                            trc::SieveEvent::UnexpectedError
+                                .into_err()
+                                .account_id(account_id)
+                                .reason(err)
+                                .details("Failed to compile provisioning Sieve script")
+                        );
+                    }
+                }
+            }
+        }
+
+        batch
+            .with_collection(Collection::Principal)
+            .with_document(0)
+            .set(PrincipalField::Provisioned, now().serialize());
+        self.commit_batch(batch).await.caused_by(trc::location!())?;
+
+        Ok(true)
+    }
+}
+
+impl ResolvedAccountTemplate {
+    pub fn expand(&self, value: &str) -> String {
+        value
+            .replace("$name", &self.name)
+            .replace("$domain", &self.domain)
+    }
+}
@@ -4,9 +4,13 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use store::write::now;
 use types::type_state::DataType;
 use utils::map::bitmap::Bitmap;
 
+// Subscriptions expire at most this long after they were created or renewed.
+pub const PUSH_EXPIRES_MAX: u64 = 7 * 24 * 3600;
+
 #[derive(
     rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Default, Debug, Clone, PartialEq, Eq,
 )]
@@ -35,6 +39,25 @@ pub struct PushSubscriptions {
     pub subscriptions: Vec<PushSubscription>,
 }
 
+impl ArchivedPushSubscriptions {
+    /// Oldest time at which an active subscription could have been created or renewed.
+    pub fn oldest_renewal(&self) -> Option<u64> {
+        let current_time = now();
+        self.subscriptions
+            .iter()
+            .filter(|subscription| {
+                subscription.verified && subscription.expires.to_native() > current_time
+            })
+            .map(|subscription| {
+                subscription
+                    .expires
+                    .to_native()
+                    .saturating_sub(PUSH_EXPIRES_MAX)
+            })
+            .min()
+    }
+}
+
 #[derive(
     rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Default, Debug, Clone, PartialEq, Eq,
 )]
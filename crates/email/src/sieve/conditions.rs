@@ -0,0 +1,668 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::log::SieveLogEntry;
+use mail_parser::Message;
+use std::borrow::Cow;
+
+pub(super) fn evaluate(
+    script: &[u8],
+    message: &Message<'_>,
+    envelope_from: &str,
+    envelope_to: &str,
+    entry: &mut SieveLogEntry,
+) {
+    let commands = ScriptParser {
+        tokens: tokenize(script),
+        pos: 0,
+    }
+    .commands();
+    TestEvaluator {
+        message,
+        envelope_from,
+        envelope_to,
+        entry,
+    }
+    .run(&commands);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Identifier(String),
+    Tag(String),
+    String(String),
+    Number(u64),
+    Char(u8),
+}
+
+enum Command {
+    Conditional {
+        branches: Vec<(Test, Vec<Command>)>,
+        otherwise: Option<Vec<Command>>,
+    },
+    Stop,
+    Other,
+}
+
+impl Command {
+    fn may_stop(&self) -> bool {
+        match self {
+            Command::Conditional {
+                branches,
+                otherwise,
+            } => branches
+                .iter()
+                .map(|(_, block)| block)
+                .chain(otherwise)
+                .flatten()
+                .any(Command::may_stop),
+            Command::Stop => true,
+            Command::Other => false,
+        }
+    }
+}
+
+enum Test {
+    Match(MatchTest),
+    Size { over: bool, limit: u64 },
+    AllOf(Vec<Test>),
+    AnyOf(Vec<Test>),
+    Not(Box<Test>),
+    Constant(bool),
+    Unsupported,
+}
+
+struct MatchTest {
+    name: &'static str,
+    match_type: MatchType,
+    address_part: AddressPart,
+    case_sensitive: bool,
+    fields: Vec<String>,
+    keys: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+enum MatchType {
+    Is,
+    Contains,
+    Matches,
+}
+
+#[derive(Clone, Copy)]
+enum AddressPart {
+    All,
+    LocalPart,
+    Domain,
+}
+
+enum Argument {
+    Tag(String),
+    Strings(Vec<String>),
+    Number(u64),
+}
+
+fn tokenize(script: &[u8]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(&ch) = script.get(pos) {
+        match ch {
+            b'#' => {
+                pos = script[pos..]
+                    .iter()
+                    .position(|&ch| ch == b'\n')
+                    .map_or(script.len(), |end| pos + end + 1);
+            }
+            b'/' if script.get(pos + 1) == Some(&b'*') => {
+                pos = script[pos + 2..]
+                    .windows(2)
+                    .position(|chars| chars == b"*/")
+                    .map_or(script.len(), |end| pos + end + 4);
+            }
+            b'"' => {
+                let mut value = Vec::new();
+                pos += 1;
+                while let Some(&ch) = script.get(pos) {
+                    pos += 1;
+                    match ch {
+                        b'"' => break,
+                        b'\\' => {
+                            if let Some(&ch) = script.get(pos) {
+                                value.push(ch);
+                                pos += 1;
+                            }
+                        }
+                        _ => value.push(ch),
+                    }
+                }
+                tokens.push(Token::String(String::from_utf8_lossy(&value).into_owned()));
+            }
+            b'[' | b']' | b'(' | b')' | b'{' | b'}' | b',' | b';' => {
+                tokens.push(Token::Char(ch));
+                pos += 1;
+            }
+            b':' => {
+                pos += 1;
+                tokens.push(Token::Tag(read_word(script, &mut pos)));
+            }
+            b'0'..=b'9' => {
+                let mut number = 0u64;
+                while let Some(ch) = script.get(pos).filter(|ch| ch.is_ascii_digit()) {
+                    number = number.saturating_mul(10).saturating_add((ch - b'0') as u64);
+                    pos += 1;
+                }
+                let multiplier = match script.get(pos).map(|ch| ch.to_ascii_uppercase()) {
+                    Some(b'K') => 1024,
+                    Some(b'M') => 1024 * 1024,
+                    Some(b'G') => 1024 * 1024 * 1024,
+                    _ => 1,
+                };
+                if multiplier != 1 {
+                    pos += 1;
+                }
+                tokens.push(Token::Number(number.saturating_mul(multiplier)));
+            }
+            _ if ch.is_ascii_alphabetic() || ch == b'_' => {
+                let word = read_word(script, &mut pos);
+                if word.eq_ignore_ascii_case("text") && script.get(pos) == Some(&b':') {
+                    tokens.push(Token::String(read_multiline(script, &mut pos)));
+                } else {
+                    tokens.push(Token::Identifier(word));
+                }
+            }
+            _ => {
+                pos += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+fn read_word(script: &[u8], pos: &mut usize) -> String {
+    let start = *pos;
+    while script
+        .get(*pos)
+        .is_some_and(|ch| ch.is_ascii_alphanumeric() || *ch == b'_')
+    {
+        *pos += 1;
+    }
+    String::from_utf8_lossy(&script[start..*pos]).into_owned()
+}
+
+fn read_multiline(script: &[u8], pos: &mut usize) -> String {
+    let mut value = String::new();
+    let mut lines = script[*pos..].split(|&ch| ch == b'\n').skip(1);
+    *pos = script[*pos..]
+        .iter()
+        .position(|&ch| ch == b'\n')
+        .map_or(script.len(), |end| *pos + end + 1);
+
+    for line in lines.by_ref() {
+        *pos = (*pos + line.len() + 1).min(script.len());
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line == b"." {
+            break;
+        }
+        value.push_str(&String::from_utf8_lossy(
+            line.strip_prefix(b".")
+                .filter(|_| line.starts_with(b".."))
+                .unwrap_or(line),
+        ));
+        value.push_str("\r\n");
+    }
+
+    value
+}
+
+struct ScriptParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ScriptParser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn commands(&mut self) -> Vec<Command> {
+        let mut commands = Vec::new();
+
+        while let Some(token) = self.next() {
+            let name = match token {
+                Token::Identifier(name) => name,
+                Token::Char(b'}') => break,
+                _ => continue,
+            };
+
+            match name.to_ascii_lowercase().as_str() {
+                "if" => {
+                    let test = self.test();
+                    let block = self.block();
+                    commands.push(Command::Conditional {
+                        branches: vec![(test, block)],
+                        otherwise: None,
+                    });
+                }
+                "elsif" => {
+                    let test = self.test();
+                    let block = self.block();
+                    if let Some(Command::Conditional { branches, .. }) = commands.last_mut() {
+                        branches.push((test, block));
+                    }
+                }
+                "else" => {
+                    let block = self.block();
+                    if let Some(Command::Conditional { otherwise, .. }) = commands.last_mut() {
+                        *otherwise = Some(block);
+                    }
+                }
+                "stop" | "return" => {
+                    self.skip_command();
+                    commands.push(Command::Stop);
+                }
+                _ => {
+                    self.skip_command();
+                    commands.push(Command::Other);
+                }
+            }
+        }
+
+        commands
+    }
+
+    fn block(&mut self) -> Vec<Command> {
+        if self.peek() == Some(&Token::Char(b'{')) {
+            self.pos += 1;
+            self.commands()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn skip_command(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Char(b';') if depth == 0 => break,
+                Token::Char(b'{') => depth += 1,
+                Token::Char(b'}') if depth == 0 => {
+                    self.pos -= 1;
+                    break;
+                }
+                Token::Char(b'}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn test(&mut self) -> Test {
+        let Some(Token::Identifier(name)) = self.next() else {
+            return Test::Unsupported;
+        };
+
+        match name.to_ascii_lowercase().as_str() {
+            "allof" => Test::AllOf(self.test_list()),
+            "anyof" => Test::AnyOf(self.test_list()),
+            "not" => Test::Not(Box::new(self.test())),
+            "true" => Test::Constant(true),
+            "false" => Test::Constant(false),
+            "header" => MatchTest::parse("header", self.arguments()),
+            "address" => MatchTest::parse("address", self.arguments()),
+            "envelope" => MatchTest::parse("envelope", self.arguments()),
+            "size" => {
+                let mut over = None;
+                let mut limit = None;
+                for argument in self.arguments() {
+                    match argument {
+                        Argument::Tag(tag) if tag.eq_ignore_ascii_case("over") => over = Some(true),
+                        Argument::Tag(tag) if tag.eq_ignore_ascii_case("under") => {
+                            over = Some(false)
+                        }
+                        Argument::Number(number) => limit = Some(number),
+                        _ => return Test::Unsupported,
+                    }
+                }
+                match (over, limit) {
+                    (Some(over), Some(limit)) => Test::Size { over, limit },
+                    _ => Test::Unsupported,
+                }
+            }
+            _ => {
+                self.arguments();
+                Test::Unsupported
+            }
+        }
+    }
+
+    fn test_list(&mut self) -> Vec<Test> {
+        let mut tests = Vec::new();
+        if self.next() == Some(Token::Char(b'(')) {
+            loop {
+                tests.push(self.test());
+                if self.next() != Some(Token::Char(b',')) {
+                    break;
+                }
+            }
+        } else {
+            tests.push(Test::Unsupported);
+        }
+        tests
+    }
+
+    fn arguments(&mut self) -> Vec<Argument> {
+        let mut arguments = Vec::new();
+
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Tag(tag) => arguments.push(Argument::Tag(tag.clone())),
+                Token::String(value) => arguments.push(Argument::Strings(vec![value.clone()])),
+                Token::Number(number) => arguments.push(Argument::Number(*number)),
+                Token::Char(b'[') => {
+                    let mut values = Vec::new();
+                    while let Some(token) = self.tokens.get(self.pos + 1) {
+                        self.pos += 1;
+                        match token {
+                            Token::String(value) => values.push(value.clone()),
+                            Token::Char(b',') => {}
+                            _ => break,
+                        }
+                    }
+                    arguments.push(Argument::Strings(values));
+                }
+                _ => break,
+            }
+            self.pos += 1;
+        }
+
+        arguments
+    }
+}
+
+impl MatchTest {
+    fn parse(name: &'static str, arguments: Vec<Argument>) -> Test {
+        let mut test = MatchTest {
+            name,
+            match_type: MatchType::Is,
+            address_part: AddressPart::All,
+            case_sensitive: false,
+            fields: Vec::new(),
+            keys: Vec::new(),
+        };
+        let mut arguments = arguments.into_iter();
+        let mut strings = Vec::new();
+
+        while let Some(argument) = arguments.next() {
+            match argument {
+                Argument::Tag(tag) => match tag.to_ascii_lowercase().as_str() {
+                    "is" => test.match_type = MatchType::Is,
+                    "contains" => test.match_type = MatchType::Contains,
+                    "matches" => test.match_type = MatchType::Matches,
+                    "all" => test.address_part = AddressPart::All,
+                    "localpart" => test.address_part = AddressPart::LocalPart,
+                    "domain" => test.address_part = AddressPart::Domain,
+                    "comparator" => match arguments.next() {
+                        Some(Argument::Strings(comparator))
+                            if comparator.first().is_some_and(|c| c == "i;octet") =>
+                        {
+                            test.case_sensitive = true;
+                        }
+                        Some(Argument::Strings(comparator))
+                            if comparator.first().is_some_and(|c| c == "i;ascii-casemap") => {}
+                        _ => return Test::Unsupported,
+                    },
+                    _ => return Test::Unsupported,
+                },
+                Argument::Strings(values) => strings.push(values),
+                Argument::Number(_) => return Test::Unsupported,
+            }
+        }
+
+        // Variables are expanded at runtime
+        if strings.len() != 2 || strings.iter().flatten().any(|value| value.contains("${")) {
+            return Test::Unsupported;
+        }
+        test.keys = strings.pop().unwrap_or_default();
+        test.fields = strings.pop().unwrap_or_default();
+
+        Test::Match(test)
+    }
+
+    fn find_match(&self, value: &str) -> Option<&str> {
+        let value = if self.case_sensitive {
+            Cow::Borrowed(value)
+        } else {
+            Cow::Owned(value.to_lowercase())
+        };
+
+        self.keys
+            .iter()
+            .find(|key| {
+                let key = if self.case_sensitive {
+                    Cow::Borrowed(key.as_str())
+                } else {
+                    Cow::Owned(key.to_lowercase())
+                };
+                match self.match_type {
+                    MatchType::Is => value == key,
+                    MatchType::Contains => value.contains(&*key),
+                    MatchType::Matches => wildcard_match(&value, &key),
+                }
+            })
+            .map(|key| key.as_str())
+    }
+}
+
+fn wildcard_match(value: &str, pattern: &str) -> bool {
+    enum Pattern {
+        Any,
+        One,
+        Char(char),
+    }
+
+    let mut chars = pattern.chars();
+    let mut pattern = Vec::new();
+    while let Some(ch) = chars.next() {
+        pattern.push(match ch {
+            '*' => Pattern::Any,
+            '?' => Pattern::One,
+            '\\' => Pattern::Char(chars.next().unwrap_or('\\')),
+            _ => Pattern::Char(ch),
+        });
+    }
+    let value = value.chars().collect::<Vec<_>>();
+
+    let (mut v, mut p) = (0, 0);
+    let mut backtrack = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some(Pattern::Any) => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some(Pattern::One) => {
+                p += 1;
+                v += 1;
+            }
+            Some(Pattern::Char(ch)) if *ch == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => {
+                let Some((any_p, any_v)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((any_p, any_v + 1));
+                p = any_p + 1;
+                v = any_v + 1;
+            }
+        }
+    }
+
+    pattern[p..]
+        .iter()
+        .all(|pattern| matches!(pattern, Pattern::Any))
+}
+
+struct TestEvaluator<'x, 'y> {
+    message: &'x Message<'x>,
+    envelope_from: &'x str,
+    envelope_to: &'x str,
+    entry: &'y mut SieveLogEntry,
+}
+
+impl TestEvaluator<'_, '_> {
+    // Returns false once the script stops or may have stopped
+    fn run(&mut self, commands: &[Command]) -> bool {
+        for command in commands {
+            match command {
+                Command::Conditional {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut block = otherwise.as_deref();
+                    for (test, branch) in branches {
+                        match self.eval(test) {
+                            Some(true) => {
+                                block = Some(branch.as_slice());
+                                break;
+                            }
+                            Some(false) => {}
+                            None if command.may_stop() => return false,
+                            None => {
+                                // The branch taken is unknown, resume after the conditional
+                                block = None;
+                                break;
+                            }
+                        }
+                    }
+                    if let Some(block) = block
+                        && !self.run(block)
+                    {
+                        return false;
+                    }
+                }
+                Command::Stop => return false,
+                Command::Other => {}
+            }
+        }
+
+        true
+    }
+
+    fn eval(&mut self, test: &Test) -> Option<bool> {
+        match test {
+            Test::Match(test) => self.eval_match(test),
+            Test::Size { over, limit } => {
+                let size = self.message.raw_message().len() as u64;
+                let result = if *over { size > *limit } else { size < *limit };
+                if result {
+                    self.entry.add_test(
+                        "size",
+                        format!("{} {limit}", if *over { "over" } else { "under" }),
+                    );
+                }
+                Some(result)
+            }
+            Test::AllOf(tests) => {
+                let mut result = Some(true);
+                for test in tests {
+                    match self.eval(test) {
+                        Some(false) => return Some(false),
+                        Some(true) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Test::AnyOf(tests) => {
+                let mut result = Some(false);
+                for test in tests {
+                    match self.eval(test) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => result = None,
+                    }
+                }
+                result
+            }
+            Test::Not(test) => self.eval(test).map(|result| !result),
+            Test::Constant(result) => Some(*result),
+            Test::Unsupported => None,
+        }
+    }
+
+    fn eval_match(&mut self, test: &MatchTest) -> Option<bool> {
+        let mut matched = None;
+
+        for field in &test.fields {
+            if test.name == "envelope" {
+                let address = if field.eq_ignore_ascii_case("from") {
+                    self.envelope_from
+                } else if field.eq_ignore_ascii_case("to") {
+                    self.envelope_to
+                } else {
+                    return None;
+                };
+                matched = test.find_match(address_part(address, test.address_part));
+            } else {
+                for header in self.message.headers() {
+                    if !header.name.as_str().eq_ignore_ascii_case(field) {
+                        continue;
+                    }
+                    if test.name == "address" {
+                        matched = header.value.as_address().and_then(|addresses| {
+                            addresses.iter().find_map(|address| {
+                                test.find_match(address_part(
+                                    address.address().unwrap_or_default(),
+                                    test.address_part,
+                                ))
+                            })
+                        });
+                    } else if let Some(value) = header.value.as_text() {
+                        matched = test.find_match(value);
+                    } else if let Some(value) = self
+                        .message
+                        .raw_message()
+                        .get(header.offset_start as usize..header.offset_end as usize)
+                        .and_then(|value| std::str::from_utf8(value).ok())
+                    {
+                        matched = test.find_match(value.trim().replace("\r\n", "").as_str());
+                    }
+                    if matched.is_some() {
+                        break;
+                    }
+                }
+            }
+
+            if let Some(key) = matched {
+                self.entry.add_test(test.name, format!("{field} {key}"));
+                return Some(true);
+            }
+        }
+
+        Some(false)
+    }
+}
+
+fn address_part(address: &str, part: AddressPart) -> &str {
+    match part {
+        AddressPart::All => address,
+        AddressPart::LocalPart => address.rsplit_once('@').map_or(address, |(local, _)| local),
+        AddressPart::Domain => address.rsplit_once('@').map_or("", |(_, domain)| domain),
+    }
+}
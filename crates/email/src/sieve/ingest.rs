@@ -4,7 +4,15 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{ActiveScript, SeenIdHash, SieveScript};
+use super::{
+    ActiveScript, SeenIdHash, SieveScript,
+    log::{SieveLog, SieveLogActionType, SieveLogEntry},
+    redirect::{RedirectScope, SieveRedirectLimit},
+    vacation::{
+        VacationReply, addressed_identity, apply_auto_reply_template, is_list_or_bulk,
+        suppression_key,
+    },
+};
 use crate::{
     cache::{MessageCacheFetch, mailbox::MailboxCacheAccess},
     mailbox::{INBOX_ID, TRASH_ID, manage::MailboxFnc},
@@ -13,18 +21,31 @@ use crate::{
         ingest::{EmailIngest, IngestEmail, IngestSource, IngestedEmail},
     },
 };
-use common::{Server, auth::AccessToken, scripts::plugins::PluginContext};
+use common::{
+    Server,
+    auth::{ACCOUNT_FLAG_SIEVE_LOG, AccessToken},
+    scripts::plugins::PluginContext,
+};
 use mail_builder::headers::date::Date;
 use mail_parser::{HeaderName, MessageParser};
-use sieve::{Envelope, Event, Input, Mailbox, Recipient, Sieve, SpamStatus};
-use std::{borrow::Cow, sync::Arc};
+use registry::schema::enums::SieveRedirectSender;
+use sieve::{
+    Envelope, Event, Input, Mailbox, Recipient, Sieve, SpamStatus,
+    compiler::grammar::actions::action_redirect::{Notify, NotifyItem, Ret},
+};
+use smtp_proto::{
+    MAIL_RET_FULL, MAIL_RET_HDRS, RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_NEVER,
+    RCPT_NOTIFY_SUCCESS,
+};
+use std::{borrow::Cow, net::IpAddr, sync::Arc};
 use std::{future::Future, str::FromStr};
 use store::{
     Deserialize, Serialize, ValueKey,
     ahash::AHashMap,
     dispatch::lookup::KeyValue,
     write::{
-        AlignedBytes, Archive, ArchiveVersion, Archiver, BatchBuilder, BlobLink, BlobOp, ValueClass,
+        AlignedBytes, Archive, ArchiveVersion, Archiver, BatchBuilder, BlobLink, BlobOp,
+        ValueClass, now,
     },
 };
 use trc::{AddContext, SieveEvent, SmtpEvent};
@@ -37,6 +58,9 @@ use types::{
     special_use::SpecialUse,
 };
 
+const REDIRECTED_FROM_HEADER: &str = "X-Sieve-Redirected-From";
+const REDIRECT_LIMITED_KEYWORD: &str = "$redirect-limited";
+
 struct SieveMessage<'x> {
     pub raw_message: Cow<'x, [u8]>,
     pub file_into: Vec<u32>,
@@ -53,7 +77,10 @@ pub trait SieveScriptIngest: Sync + Send {
         raw_message: &[u8],
         envelope_from: &str,
         envelope_from_authenticated: bool,
+        remote_ip: Option<IpAddr>,
         envelope_to: &IngestRecipient,
+        spam_tags: &[String],
+        is_duplicate: bool,
         session_id: u64,
         active_script: ActiveScript,
         autogenerated: &mut Vec<AutogeneratedMessage>,
@@ -80,6 +107,12 @@ pub trait SieveScriptIngest: Sync + Send {
         account_id: u32,
         document_id: u32,
     ) -> impl Future<Output = trc::Result<Option<CompiledScript>>> + Send;
+
+    fn sieve_script_source(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> impl Future<Output = trc::Result<Option<Vec<u8>>>> + Send;
 }
 
 impl SieveScriptIngest for Server {
@@ -91,7 +124,10 @@ impl SieveScriptIngest for Server {
         raw_message: &[u8],
         envelope_from: &str,
         envelope_from_authenticated: bool,
+        remote_ip: Option<IpAddr>,
         envelope_to: &IngestRecipient,
+        spam_tags: &[String],
+        is_duplicate: bool,
         session_id: u64,
         active_script: ActiveScript,
         autogenerated: &mut Vec<AutogeneratedMessage>,
@@ -120,18 +156,89 @@ impl SieveScriptIngest for Server {
             .await
             .caused_by(trc::location!())?;
 
+        // Obtain the account's own addresses, including aliases, and use the one
+        // the message was addressed to as the user address
+        let account_info = self
+            .account_info(account_id)
+            .await
+            .caused_by(trc::location!())?;
+        let mut own_addresses = account_info.addresses().to_vec();
+        if !own_addresses
+            .iter()
+            .any(|address| address.eq_ignore_ascii_case(&envelope_to.address))
+        {
+            own_addresses.push(envelope_to.address.clone());
+        }
+        let user_address = addressed_identity(&message, &own_addresses)
+            .unwrap_or(envelope_to.address.as_str())
+            .to_string();
+
+        // Count the times this message was already redirected by this account
+        let redirect_passes = message
+            .headers()
+            .iter()
+            .filter(|header| {
+                header
+                    .name
+                    .as_str()
+                    .eq_ignore_ascii_case(REDIRECTED_FROM_HEADER)
+                    && header
+                        .value
+                        .as_text()
+                        .is_some_and(|value| value.trim().eq_ignore_ascii_case(account_info.name()))
+            })
+            .count();
+
+        // Messages relayed back by trusted hosts are allowed one additional pass
+        let max_redirect_passes = if remote_ip.is_some_and(|ip| {
+            self.core
+                .sieve
+                .trusted_redirect_hosts
+                .iter()
+                .any(|network| network.matches(&ip))
+        }) {
+            self.core.sieve.max_redirect_passes + 1
+        } else {
+            self.core.sieve.max_redirect_passes
+        };
+
+        // Execution log, only allocated when enabled for the account
+        let mut log = (self.core.sieve.log.enabled
+            || account_info.account().flags & ACCOUNT_FLAG_SIEVE_LOG != 0)
+            .then(|| SieveLogEntry {
+                received_at: now(),
+                script_name: active_script.script_name.clone(),
+                message_id: message.message_id().map(Into::into),
+                envelope_from: envelope_from.to_string(),
+                ..Default::default()
+            });
+
+        // The interpreter does not report the tests it evaluates, so the script's
+        // conditions are matched against the message when logging
+        if let Some(log) = &mut log
+            && let Ok(Some(script)) = self
+                .sieve_script_source(account_id, active_script.document_id)
+                .await
+        {
+            log.add_matched_tests(&script, &message, envelope_from, &envelope_to.address);
+        }
+
         // Create Sieve instance
         let mut instance = self.core.sieve.untrusted_runtime.filter_parsed(message);
 
         // Set account name and email
-        let account_info = self.account(account_id).await.caused_by(trc::location!())?;
         let mail_from = account_info.name().to_string();
+        let add_delivered_to = self
+            .delivered_to_address(account_info.account())
+            .await
+            .caused_by(trc::location!())?
+            .is_some();
         instance.set_user_full_name(
             account_info
                 .description()
                 .unwrap_or_else(|| account_info.name()),
         );
-        instance.set_user_address(&mail_from);
+        instance.set_user_address(&user_address);
 
         // Set envelope
         instance.set_envelope(Envelope::From, envelope_from);
@@ -145,6 +252,26 @@ impl SieveScriptIngest for Server {
             SpamStatus::Ham
         });
 
+        // Set environment items (RFC 5183) that depend on the delivery
+        instance.set_env_variable("host", self.core.network.server_name.clone());
+        if let Some((_, domain)) = envelope_to.address.rsplit_once('@') {
+            instance.set_env_variable("domain", domain.to_lowercase());
+        }
+        if let Some(remote_ip) = remote_ip {
+            instance.set_env_variable("remote-ip", remote_ip.to_string());
+        }
+        instance.set_env_variable("vnd.stalwart.account", account_info.name().to_string());
+        instance.set_env_variable("vnd.stalwart.account-id", Id::from(account_id).to_string());
+        instance.set_env_variable("vnd.stalwart.session-id", session_id.to_string());
+        instance.set_env_variable(
+            "vnd.stalwart.sender-authenticated",
+            if envelope_from_authenticated {
+                "true"
+            } else {
+                "false"
+            },
+        );
+
         let mut input = Input::script(
             active_script.script_name.to_string(),
             active_script.script.clone(),
@@ -170,6 +297,10 @@ impl SieveScriptIngest for Server {
             imap_uids: Vec::new(),
         };
         let mut checked_ids: AHashMap<SeenIdHash, bool> = AHashMap::new();
+        let mut seen_ids: Vec<(SeenIdHash, u64)> = Vec::new();
+        let mut last_duplicate_id: Option<(String, u64)> = None;
+        let mut has_runtime_error = false;
+        let mut redirect_limited: Vec<usize> = Vec::new();
 
         while let Some(event) = instance.run(input) {
             match event {
@@ -213,11 +344,11 @@ impl SieveScriptIngest for Server {
                             }
 
                             let mut result = true;
-                            for mailbox in mailboxes {
+                            for mailbox in &mailboxes {
                                 match mailbox {
                                     Mailbox::Name(name) => {
                                         if !matches!(
-                                            cache.mailbox_by_path(&name),
+                                            cache.mailbox_by_path(name),
                                             Some(item) if special_use_ids.is_empty() ||
                                             special_use_ids.contains(&item.document_id)
                                         ) {
@@ -226,7 +357,7 @@ impl SieveScriptIngest for Server {
                                         }
                                     }
                                     Mailbox::Id(id) => {
-                                        if !matches!(Id::from_str(&id), Ok(id) if
+                                        if !matches!(Id::from_str(id), Ok(id) if
                                                         cache.has_mailbox_id(&id.document_id()) &&
                                                         (special_use_ids.is_empty() ||
                                                         special_use_ids.contains(&id.document_id())))
@@ -237,6 +368,19 @@ impl SieveScriptIngest for Server {
                                     }
                                 }
                             }
+                            if result && let Some(log) = &mut log {
+                                log.add_test(
+                                    "mailboxexists",
+                                    mailboxes
+                                        .iter()
+                                        .map(|mailbox| match mailbox {
+                                            Mailbox::Name(name) => name.as_str(),
+                                            Mailbox::Id(id) => id.as_str(),
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join(" "),
+                                );
+                            }
                             input = result.into();
                         } else if !special_use.is_empty() {
                             let mut result = true;
@@ -251,19 +395,23 @@ impl SieveScriptIngest for Server {
                                     }
                                 }
                             }
+                            if result && let Some(log) = &mut log {
+                                log.add_test("specialuse_exists", special_use.join(" "));
+                            }
                             input = result.into();
                         } else {
                             input = false.into();
                         }
                     }
                     Event::DuplicateId { id, expiry, last } => {
+                        let expiry = expiry.min(self.core.sieve.duplicate_max_expiry);
                         let id_hash = SeenIdHash::new(
                             account_id,
                             active_script.version.hash().unwrap_or_default(),
                             &id,
                         );
-                        if let Some(result) = checked_ids.get(&id_hash) {
-                            input = (*result).into();
+                        let exists = if let Some(result) = checked_ids.get(&id_hash) {
+                            *result
                         } else {
                             let exists = self
                                 .in_memory_store()
@@ -271,22 +419,31 @@ impl SieveScriptIngest for Server {
                                 .await
                                 .caused_by(trc::location!())?;
 
+                            // Ids are recorded once the script completes (RFC 7352, section 3)
                             if !exists || last {
-                                self.in_memory_store()
-                                    .key_set(KeyValue::new(id_hash.key(), vec![]).expires(expiry))
-                                    .await
-                                    .caused_by(trc::location!())?;
+                                seen_ids.push((id_hash.clone(), expiry));
                             }
 
                             checked_ids.insert(id_hash, exists);
-                            input = exists.into();
+                            exists
+                        };
+                        if exists && let Some(log) = &mut log {
+                            log.add_test("duplicate", id.as_str());
                         }
+                        input = exists.into();
+                        last_duplicate_id = Some((id, expiry));
                     }
                     Event::Discard => {
+                        if let Some(log) = &mut log {
+                            log.add_action(SieveLogActionType::Discard, None, None);
+                        }
                         do_discard = true;
                         input = true.into();
                     }
                     Event::Reject { reason, .. } => {
+                        if let Some(log) = &mut log {
+                            log.add_action(SieveLogActionType::Reject, Some(reason.clone()), None);
+                        }
                         reject_reason = reason.into();
                         do_discard = true;
                         input = true.into();
@@ -297,8 +454,18 @@ impl SieveScriptIngest for Server {
                             if !message.file_into.contains(&INBOX_ID) {
                                 message.file_into.push(INBOX_ID);
                             }
+                            if let Some(log) = &mut log {
+                                log.add_action(SieveLogActionType::Keep, None, None);
+                            }
                             do_deliver = true;
                         } else {
+                            if let Some(log) = &mut log {
+                                log.add_action(
+                                    SieveLogActionType::Keep,
+                                    None,
+                                    Some("Unknown message id"),
+                                );
+                            }
                             trc::event!(
                                 Sieve(SieveEvent::UnexpectedError),
                                 Details = "Unknown message id.",
@@ -366,8 +533,10 @@ impl SieveScriptIngest for Server {
                         }
 
                         // Default to Inbox
+                        let mut log_error = None;
                         if target_id == u32::MAX {
                             target_id = INBOX_ID;
+                            log_error = Some("Mailbox does not exist, filed into Inbox");
                         }
 
                         if let Some(message) = messages.get_mut(message_id) {
@@ -377,7 +546,21 @@ impl SieveScriptIngest for Server {
                             }
                             message.did_file_into = true;
                             do_deliver = true;
+                            if let Some(log) = &mut log {
+                                log.add_action(
+                                    SieveLogActionType::FileInto,
+                                    Some(folder),
+                                    log_error,
+                                );
+                            }
                         } else {
+                            if let Some(log) = &mut log {
+                                log.add_action(
+                                    SieveLogActionType::FileInto,
+                                    Some(folder),
+                                    Some("Unknown message id"),
+                                );
+                            }
                             trc::event!(
                                 Sieve(SieveEvent::UnexpectedError),
                                 Details = "Unknown message id.",
@@ -389,12 +572,21 @@ impl SieveScriptIngest for Server {
                     }
                     Event::SendMessage {
                         recipient,
+                        notify,
+                        return_of_content,
                         message_id,
                         ..
                     } => {
                         input = true.into();
                         if let Some(message) = messages.get(message_id) {
                             if received_headers >= self.core.sieve.max_received_headers {
+                                if let Some(log) = &mut log {
+                                    log.add_action(
+                                        SieveLogActionType::Redirect,
+                                        None,
+                                        Some("Too many Received headers"),
+                                    );
+                                }
                                 trc::event!(
                                     Smtp(SmtpEvent::LoopDetected),
                                     From = mail_from.clone(),
@@ -415,7 +607,162 @@ impl SieveScriptIngest for Server {
                                 }
                             };
 
-                            if message.raw_message.len() <= self.core.email.mail_max_size {
+                            // The incoming message can only be redirected, responses created by
+                            // the script are parsed once for the checks and the reply template
+                            let created_message = (message_id != 0)
+                                .then(|| MessageParser::new().parse(message.raw_message.as_ref()))
+                                .flatten();
+
+                            // Enforce RFC 5230 rules on vacation responses
+                            let is_redirect = if let Some(reply) =
+                                created_message.as_ref().and_then(|created_message| {
+                                    VacationReply::new(
+                                        created_message,
+                                        last_duplicate_id.as_ref().map(|(id, _)| id.as_str()),
+                                    )
+                                }) {
+                                let expiry = last_duplicate_id
+                                    .take()
+                                    .map(|(_, expiry)| expiry)
+                                    .unwrap_or(self.core.sieve.vacation_expiry);
+                                let keys = recipients
+                                    .iter()
+                                    .map(|rcpt| suppression_key(account_id, rcpt, &reply.handle))
+                                    .collect::<Vec<_>>();
+                                let mut reason = if is_list_or_bulk(instance.message()) {
+                                    Some("Message is from a list or bulk sender")
+                                } else if reply.from.as_ref().is_some_and(|from| {
+                                    !own_addresses
+                                        .iter()
+                                        .any(|address| address.eq_ignore_ascii_case(from))
+                                }) {
+                                    Some("From address is not an identity of the account")
+                                } else {
+                                    None
+                                };
+                                if reason.is_none() {
+                                    for key in &keys {
+                                        if self
+                                            .in_memory_store()
+                                            .key_exists(key.clone())
+                                            .await
+                                            .caused_by(trc::location!())?
+                                        {
+                                            reason = Some("Response already sent to this sender");
+                                            break;
+                                        }
+                                    }
+                                }
+
+                                if let Some(reason) = reason {
+                                    if let Some(log) = &mut log {
+                                        log.add_action(
+                                            SieveLogActionType::Vacation,
+                                            Some(recipients.join(", ")),
+                                            Some(reason),
+                                        );
+                                    }
+                                    trc::event!(
+                                        Sieve(SieveEvent::VacationSuppressed),
+                                        From = mail_from.clone(),
+                                        To = recipients
+                                            .iter()
+                                            .map(|r| trc::Value::String(r.as_str().into()))
+                                            .collect::<Vec<_>>(),
+                                        Reason = reason,
+                                        SpanId = session_id
+                                    );
+
+                                    continue;
+                                }
+
+                                for key in keys {
+                                    self.in_memory_store()
+                                        .key_set(KeyValue::new(key, vec![]).expires(expiry))
+                                        .await
+                                        .caused_by(trc::location!())?;
+                                }
+
+                                false
+                            } else {
+                                true
+                            };
+                            let templated_message = if let Some(created_message) =
+                                created_message.as_ref().filter(|_| !is_redirect)
+                            {
+                                apply_auto_reply_template(
+                                    self,
+                                    account_id,
+                                    message.raw_message.as_ref(),
+                                    created_message,
+                                )
+                                .await
+                            } else {
+                                None
+                            };
+                            let response = templated_message
+                                .as_deref()
+                                .unwrap_or(message.raw_message.as_ref());
+
+                            // Refuse to redirect messages that already looped through this account
+                            if is_redirect && redirect_passes >= max_redirect_passes {
+                                if let Some(log) = &mut log {
+                                    log.add_action(
+                                        SieveLogActionType::Redirect,
+                                        Some(recipients.join(", ")),
+                                        Some("Mail loop detected"),
+                                    );
+                                }
+                                trc::event!(
+                                    Smtp(SmtpEvent::LoopDetected),
+                                    From = mail_from.clone(),
+                                    To = recipients
+                                        .iter()
+                                        .map(|r| trc::Value::String(r.as_str().into()))
+                                        .collect::<Vec<_>>(),
+                                    Total = redirect_passes,
+                                    Limit = max_redirect_passes,
+                                    SpanId = session_id,
+                                );
+
+                                continue;
+                            }
+
+                            // Deliver locally once the account exceeds its daily redirect limits
+                            if is_redirect
+                                && !self
+                                    .sieve_redirect_allowed(
+                                        account_id,
+                                        &recipients,
+                                        RedirectScope::Untrusted,
+                                        session_id,
+                                    )
+                                    .await
+                                    .caused_by(trc::location!())?
+                            {
+                                if !redirect_limited.contains(&message_id) {
+                                    redirect_limited.push(message_id);
+                                }
+                                if let Some(log) = &mut log {
+                                    log.add_action(
+                                        SieveLogActionType::Redirect,
+                                        Some(recipients.join(", ")),
+                                        Some("Daily redirect limit exceeded, filed into Inbox"),
+                                    );
+                                }
+
+                                continue;
+                            }
+
+                            let log_action = if is_redirect {
+                                SieveLogActionType::Redirect
+                            } else {
+                                SieveLogActionType::Vacation
+                            };
+                            if response.len() <= self.core.email.mail_max_size {
+                                if let Some(log) = &mut log {
+                                    log.add_action(log_action, Some(recipients.join(", ")), None);
+                                }
                                 trc::event!(
                                     Sieve(SieveEvent::SendMessage),
                                     From = mail_from.clone(),
@@ -423,26 +770,65 @@ impl SieveScriptIngest for Server {
                                         .iter()
                                         .map(|r| trc::Value::String(r.as_str().into()))
                                         .collect::<Vec<_>>(),
-                                    Size = message.raw_message.len(),
+                                    Size = response.len(),
                                     SpanId = session_id
                                 );
 
-                                let mut raw_message =
-                                    Vec::with_capacity(160 + message.raw_message.len());
+                                let mut raw_message = Vec::with_capacity(160 + response.len());
                                 write_received_header(
                                     &mut raw_message,
                                     &self.core.network.server_name,
                                     session_id,
                                 );
-                                raw_message.extend_from_slice(message.raw_message.as_ref());
+                                write_loop_header(&mut raw_message, &mail_from);
+                                let sender_address = if is_redirect {
+                                    write_redirected_header(&mut raw_message, &mail_from);
+                                    if add_delivered_to {
+                                        write_delivered_to_header(&mut raw_message, &mail_from);
+                                    }
+
+                                    match self.core.sieve.redirect_sender {
+                                        SieveRedirectSender::Original => envelope_from.to_string(),
+                                        SieveRedirectSender::Account => mail_from.clone(),
+                                    }
+                                } else {
+                                    mail_from.clone()
+                                };
+                                raw_message.extend_from_slice(response);
 
                                 autogenerated.push(AutogeneratedMessage {
-                                    sender_address: mail_from.clone(),
+                                    sender_address,
                                     recipients,
                                     message: raw_message,
+                                    flags: match return_of_content {
+                                        Ret::Full => MAIL_RET_FULL,
+                                        Ret::Hdrs => MAIL_RET_HDRS,
+                                        Ret::Default => 0,
+                                    },
+                                    rcpt_flags: match notify {
+                                        Notify::Never => RCPT_NOTIFY_NEVER,
+                                        Notify::Items(items) => {
+                                            items.into_iter().fold(0, |flags, item| {
+                                                flags
+                                                    | match item {
+                                                        NotifyItem::Success => RCPT_NOTIFY_SUCCESS,
+                                                        NotifyItem::Failure => RCPT_NOTIFY_FAILURE,
+                                                        NotifyItem::Delay => RCPT_NOTIFY_DELAY,
+                                                    }
+                                            })
+                                        }
+                                        Notify::Default => 0,
+                                    },
                                 });
                                 do_redirect = true;
                             } else {
+                                if let Some(log) = &mut log {
+                                    log.add_action(
+                                        log_action,
+                                        Some(recipients.join(", ")),
+                                        Some("Message is too large"),
+                                    );
+                                }
                                 trc::event!(
                                     Sieve(SieveEvent::MessageTooLarge),
                                     From = mail_from.clone(),
@@ -450,12 +836,19 @@ impl SieveScriptIngest for Server {
                                         .iter()
                                         .map(|r| trc::Value::String(r.as_str().into()))
                                         .collect::<Vec<_>>(),
-                                    Size = message.raw_message.len(),
+                                    Size = response.len(),
                                     Limit = self.core.email.mail_max_size,
                                     SpanId = session_id,
                                 );
                             }
                         } else {
+                            if let Some(log) = &mut log {
+                                log.add_action(
+                                    SieveLogActionType::Redirect,
+                                    None,
+                                    Some("Unknown message id"),
+                                );
+                            }
                             trc::event!(
                                 Sieve(SieveEvent::UnexpectedError),
                                 Details = "Unknown message id.",
@@ -466,9 +859,29 @@ impl SieveScriptIngest for Server {
                             continue;
                         }
                     }
-                    Event::ListContains { .. }
-                    | Event::Notify { .. }
-                    | Event::SetEnvelope { .. } => {
+                    Event::Notify { method, .. } => {
+                        // Not allowed
+                        if let Some(log) = &mut log {
+                            log.add_action(
+                                SieveLogActionType::Notify,
+                                Some(method),
+                                Some("Notifications are not allowed"),
+                            );
+                        }
+                        input = false.into();
+                    }
+                    Event::SetEnvelope { value, .. } => {
+                        // Not allowed
+                        if let Some(log) = &mut log {
+                            log.add_action(
+                                SieveLogActionType::SetEnvelope,
+                                Some(value),
+                                Some("Envelope changes are not allowed"),
+                            );
+                        }
+                        input = false.into();
+                    }
+                    Event::ListContains { .. } => {
                         // Not allowed
                         input = false.into();
                     }
@@ -482,6 +895,7 @@ impl SieveScriptIngest for Server {
                                     server: self,
                                     message: instance.message(),
                                     modifications: &mut Vec::new(),
+                                    spam_score: None,
                                     access_token: access_token.into(),
                                     arguments,
                                 },
@@ -505,12 +919,17 @@ impl SieveScriptIngest for Server {
                 }
 
                 Err(err) => {
+                    let reason = err.to_string();
+                    if let Some(log) = &mut log {
+                        log.errors.push(reason.clone());
+                    }
                     trc::event!(
                         Sieve(SieveEvent::RuntimeError),
-                        Reason = err.to_string(),
+                        Reason = reason,
                         SpanId = session_id
                     );
 
+                    has_runtime_error = true;
                     input = true.into();
                 }
             }
@@ -519,29 +938,61 @@ impl SieveScriptIngest for Server {
         // Fail-safe, no discard and no keep seen, assume that something went wrong and file anyway.
         if !do_deliver && !do_discard && !do_redirect {
             messages[0].file_into.push(INBOX_ID);
+            if let Some(log) = &mut log {
+                log.add_action(SieveLogActionType::ImplicitKeep, None, None);
+            }
         }
 
+        // Add the execution summary to delivered messages when requested
+        let log_header = log
+            .as_ref()
+            .filter(|_| self.core.sieve.log.add_header)
+            .map(|log| {
+                format!(
+                    "X-Sieve-Log: {}\r\n",
+                    log.summary().replace(['\r', '\n'], " ")
+                )
+            });
+
         // Deliver messages
         let mut last_temp_error = None;
         let mut has_delivered = false;
-        for (message_id, sieve_message) in messages.into_iter().enumerate() {
+        for (message_id, mut sieve_message) in messages.into_iter().enumerate() {
+            if redirect_limited.contains(&message_id) {
+                if sieve_message.file_into.is_empty() {
+                    sieve_message.file_into.push(INBOX_ID);
+                }
+                sieve_message
+                    .flags
+                    .push(Keyword::from(REDIRECT_LIMITED_KEYWORD.to_string()));
+            }
+
             if !sieve_message.file_into.is_empty() {
+                if let Some(log_header) = &log_header {
+                    let mut raw_message =
+                        Vec::with_capacity(log_header.len() + sieve_message.raw_message.len());
+                    raw_message.extend_from_slice(log_header.as_bytes());
+                    raw_message.extend_from_slice(sieve_message.raw_message.as_ref());
+                    sieve_message.raw_message = raw_message.into();
+                }
+
                 // Parse message if needed
-                let (blob_hash, message) = if message_id == 0 && !instance.has_message_changed() {
-                    (blob_hash.into(), instance.take_message())
-                } else if let Some(message) =
-                    MessageParser::new().parse(sieve_message.raw_message.as_ref())
-                {
-                    (None, message)
-                } else {
-                    trc::event!(
-                        Sieve(SieveEvent::UnexpectedError),
-                        Details = "Failed to parse Sieve generated message.",
-                        SpanId = session_id
-                    );
+                let (blob_hash, message) =
+                    if message_id == 0 && !instance.has_message_changed() && log_header.is_none() {
+                        (blob_hash.into(), instance.take_message())
+                    } else if let Some(message) =
+                        MessageParser::new().parse(sieve_message.raw_message.as_ref())
+                    {
+                        (None, message)
+                    } else {
+                        trc::event!(
+                            Sieve(SieveEvent::UnexpectedError),
+                            Details = "Failed to parse Sieve generated message.",
+                            SpanId = session_id
+                        );
 
-                    continue;
-                };
+                        continue;
+                    };
 
                 // Deliver message
                 match self
@@ -557,6 +1008,8 @@ impl SieveScriptIngest for Server {
                             deliver_to: envelope_to.address.as_str(),
                             is_sender_authenticated: envelope_from_authenticated,
                             is_spam: envelope_to.is_spam,
+                            is_duplicate,
+                            spam_tags,
                         },
                         session_id,
                     })
@@ -573,6 +1026,28 @@ impl SieveScriptIngest for Server {
             }
         }
 
+        // Store the execution log entry
+        if let Some(log) = log
+            && let Err(err) = self.sieve_log_append(account_id, log).await
+        {
+            trc::error!(
+                err.span_id(session_id)
+                    .details("Failed to store Sieve log entry")
+            );
+        }
+
+        // Record duplicate ids, unless the script failed or the message will be retried
+        if !has_runtime_error
+            && (reject_reason.is_some() || has_delivered || last_temp_error.is_none())
+        {
+            for (id_hash, expiry) in seen_ids {
+                self.in_memory_store()
+                    .key_set(KeyValue::new(id_hash.key(), vec![]).expires(expiry))
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+        }
+
         if let Some(reject_reason) = reject_reason {
             Err(
                 trc::EventType::MessageIngest(trc::MessageIngestEvent::Error)
@@ -776,6 +1251,57 @@ impl SieveScriptIngest for Server {
             }
         }
     }
+
+    async fn sieve_script_source(
+        &self,
+        account_id: u32,
+        document_id: u32,
+    ) -> trc::Result<Option<Vec<u8>>> {
+        let Some(script_object) = self
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+                account_id,
+                Collection::SieveScript,
+                document_id,
+            ))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let script = script_object
+            .unarchive::<SieveScript>()
+            .caused_by(trc::location!())?;
+
+        // The script source precedes the precompiled script in the blob
+        self.core
+            .storage
+            .blob
+            .get_blob(
+                script.blob_hash.0.as_ref(),
+                0..u32::from(script.size) as usize,
+            )
+            .await
+            .caused_by(trc::location!())
+    }
+}
+
+fn write_redirected_header(buf: &mut Vec<u8>, account_name: &str) {
+    buf.extend_from_slice(REDIRECTED_FROM_HEADER.as_bytes());
+    buf.extend_from_slice(b": ");
+    buf.extend_from_slice(account_name.as_bytes());
+    buf.extend_from_slice(b"\r\n");
+}
+
+fn write_loop_header(buf: &mut Vec<u8>, account_name: &str) {
+    buf.extend_from_slice(b"X-Loop: ");
+    buf.extend_from_slice(account_name.as_bytes());
+    buf.extend_from_slice(b"\r\n");
+}
+
+fn write_delivered_to_header(buf: &mut Vec<u8>, account_name: &str) {
+    buf.extend_from_slice(b"Delivered-To: ");
+    buf.extend_from_slice(account_name.as_bytes());
+    buf.extend_from_slice(b"\r\n");
 }
 
 fn write_received_header(buf: &mut Vec<u8>, hostname: &str, id: u64) {
@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::conditions;
+use common::{KV_SIEVE_LOG, Server, config::mailstore::scripts::SieveLogSettings};
+use mail_parser::Message;
+use std::future::Future;
+use store::{
+    Serialize,
+    dispatch::lookup::KeyValue,
+    write::{AlignedBytes, Archive, Archiver, now},
+};
+use trc::AddContext;
+
+#[derive(
+    rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Debug, Default, Clone, PartialEq, Eq,
+)]
+pub struct SieveLogEntry {
+    pub received_at: u64,
+    pub script_name: String,
+    pub message_id: Option<String>,
+    pub envelope_from: String,
+    pub tests: Vec<SieveLogTest>,
+    pub actions: Vec<SieveLogAction>,
+    pub errors: Vec<String>,
+}
+
+#[derive(
+    rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Debug, Default, Clone, PartialEq, Eq,
+)]
+pub struct SieveLogTest {
+    pub name: String,
+    pub argument: String,
+}
+
+#[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct SieveLogAction {
+    pub action: SieveLogActionType,
+    pub target: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(rkyv::Archive, rkyv::Deserialize, rkyv::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SieveLogActionType {
+    Keep,
+    FileInto,
+    Discard,
+    Reject,
+    Redirect,
+    Vacation,
+    Notify,
+    SetEnvelope,
+    ImplicitKeep,
+}
+
+pub trait SieveLog: Sync + Send {
+    fn sieve_log_append(
+        &self,
+        account_id: u32,
+        entry: SieveLogEntry,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn sieve_log_get(
+        &self,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<Vec<SieveLogEntry>>> + Send;
+}
+
+impl SieveLog for Server {
+    async fn sieve_log_append(&self, account_id: u32, entry: SieveLogEntry) -> trc::Result<()> {
+        let SieveLogSettings {
+            max_entries,
+            retention,
+            ..
+        } = self.core.sieve.log;
+
+        // Reserve a slot in the ring buffer, concurrent deliveries never
+        // overwrite each other's entries
+        let seq = self
+            .in_memory_store()
+            .counter_incr(
+                KeyValue::new(log_key(account_id), 1).expires(retention),
+                true,
+            )
+            .await
+            .caused_by(trc::location!())?;
+
+        self.in_memory_store()
+            .key_set(
+                KeyValue::new(
+                    log_slot_key(account_id, seq.saturating_sub(1) as u64, max_entries),
+                    Archiver::new(entry)
+                        .untrusted()
+                        .serialize()
+                        .caused_by(trc::location!())?,
+                )
+                .expires(retention),
+            )
+            .await
+            .caused_by(trc::location!())
+    }
+
+    async fn sieve_log_get(&self, account_id: u32) -> trc::Result<Vec<SieveLogEntry>> {
+        let max_entries = self.core.sieve.log.max_entries;
+        let seq = self
+            .in_memory_store()
+            .counter_get(log_key(account_id))
+            .await
+            .caused_by(trc::location!())?
+            .max(0) as u64;
+        let min_received_at = now().saturating_sub(self.core.sieve.log.retention);
+        let mut entries = Vec::new();
+
+        for seq in seq.saturating_sub(max_entries as u64)..seq {
+            if let Some(archive) = self
+                .in_memory_store()
+                .key_get::<Archive<AlignedBytes>>(log_slot_key(account_id, seq, max_entries))
+                .await
+                .caused_by(trc::location!())?
+            {
+                let entry = archive
+                    .deserialize::<SieveLogEntry>()
+                    .caused_by(trc::location!())?;
+                if entry.received_at >= min_received_at {
+                    entries.push(entry);
+                }
+            }
+        }
+
+        // Slots are written concurrently, return the entries oldest first
+        entries.sort_by_key(|entry| entry.received_at);
+
+        Ok(entries)
+    }
+}
+
+impl SieveLogEntry {
+    pub fn add_test(&mut self, name: &str, argument: impl Into<String>) {
+        self.tests.push(SieveLogTest {
+            name: name.to_string(),
+            argument: argument.into(),
+        });
+    }
+
+    pub fn add_action(
+        &mut self,
+        action: SieveLogActionType,
+        target: Option<String>,
+        error: Option<&str>,
+    ) {
+        self.actions.push(SieveLogAction {
+            action,
+            target,
+            error: error.map(Into::into),
+        });
+    }
+
+    /// Records the header, address, envelope and size tests that evaluate to
+    /// true, following the branches of the script the interpreter would take.
+    pub fn add_matched_tests(
+        &mut self,
+        script: &[u8],
+        message: &Message<'_>,
+        envelope_from: &str,
+        envelope_to: &str,
+    ) {
+        conditions::evaluate(script, message, envelope_from, envelope_to, self);
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("script={}", self.script_name);
+        for action in &self.actions {
+            summary.push_str("; ");
+            summary.push_str(action.action.as_str());
+            if let Some(target) = &action.target {
+                summary.push(' ');
+                summary.push_str(target);
+            }
+            if action.error.is_some() {
+                summary.push_str(" (failed)");
+            }
+        }
+        if !self.errors.is_empty() {
+            summary.push_str("; errors=");
+            summary.push_str(&self.errors.len().to_string());
+        }
+        summary
+    }
+}
+
+impl SieveLogActionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SieveLogActionType::Keep => "keep",
+            SieveLogActionType::FileInto => "fileinto",
+            SieveLogActionType::Discard => "discard",
+            SieveLogActionType::Reject => "reject",
+            SieveLogActionType::Redirect => "redirect",
+            SieveLogActionType::Vacation => "vacation",
+            SieveLogActionType::Notify => "notify",
+            SieveLogActionType::SetEnvelope => "setenvelope",
+            SieveLogActionType::ImplicitKeep => "implicit_keep",
+        }
+    }
+}
+
+fn log_key(account_id: u32) -> Vec<u8> {
+    let mut key = Vec::with_capacity(9);
+    key.push(KV_SIEVE_LOG);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key
+}
+
+fn log_slot_key(account_id: u32, seq: u64, max_entries: usize) -> Vec<u8> {
+    let mut key = log_key(account_id);
+    key.extend_from_slice(&((seq % max_entries.max(1) as u64) as u32).to_be_bytes());
+    key
+}
@@ -10,9 +10,13 @@ use std::sync::Arc;
 use store::{blake3, write::ArchiveVersion};
 use types::blob_hash::BlobHash;
 
+mod conditions;
 pub mod delete;
 pub mod index;
 pub mod ingest;
+pub mod log;
+pub mod redirect;
+pub mod vacation;
 
 #[derive(Debug, Clone)]
 pub struct ActiveScript {
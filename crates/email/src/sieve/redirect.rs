@@ -0,0 +1,231 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    KV_SIEVE_REDIRECT, KV_SIEVE_REDIRECT_RCPT, Server, config::mailstore::scripts::RedirectLimit,
+};
+use std::future::Future;
+use store::{dispatch::lookup::KeyValue, write::now};
+use trc::{AddContext, SieveEvent};
+
+const DAY: u64 = 86400;
+const COUNTER_REDIRECTS: u8 = 0;
+const COUNTER_DESTINATIONS: u8 = 1;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RedirectCounters {
+    pub redirects: u64,
+    pub destinations: u64,
+}
+
+// Redirects issued by trusted (system) and untrusted (user) scripts are
+// counted separately, each against its own limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectScope {
+    Untrusted,
+    Trusted,
+}
+
+pub trait SieveRedirectLimit: Sync + Send {
+    fn sieve_redirect_allowed(
+        &self,
+        account_id: u32,
+        recipients: &[String],
+        scope: RedirectScope,
+        session_id: u64,
+    ) -> impl Future<Output = trc::Result<bool>> + Send;
+
+    fn sieve_redirect_counters(
+        &self,
+        account_id: u32,
+        scope: RedirectScope,
+    ) -> impl Future<Output = trc::Result<RedirectCounters>> + Send;
+
+    fn sieve_redirect_reset(&self, account_id: u32)
+    -> impl Future<Output = trc::Result<()>> + Send;
+}
+
+impl SieveRedirectLimit for Server {
+    async fn sieve_redirect_allowed(
+        &self,
+        account_id: u32,
+        recipients: &[String],
+        scope: RedirectScope,
+        session_id: u64,
+    ) -> trc::Result<bool> {
+        let limit = scope.limit(self);
+        if limit.max_redirects == 0 && limit.max_destinations == 0 {
+            return Ok(true);
+        }
+
+        let now = now();
+        let day = now / DAY;
+        let expires = (day + 1) * DAY - now;
+        let store = self.in_memory_store();
+
+        // Count the redirect, concurrent redirects see each other's increments
+        let redirects_key = counter_key(account_id, scope, day, COUNTER_REDIRECTS);
+        let redirects = store
+            .counter_incr(
+                KeyValue::new(redirects_key.clone(), 1).expires(expires),
+                true,
+            )
+            .await
+            .caused_by(trc::location!())?;
+        let mut reason = (limit.max_redirects > 0 && redirects > limit.max_redirects as i64)
+            .then_some(("Too many redirects", redirects - 1, limit.max_redirects));
+
+        // Claim the destinations that were not redirected to today
+        let mut new_destinations: Vec<Vec<u8>> = Vec::new();
+        if reason.is_none() {
+            for rcpt in recipients {
+                let key = destination_key(account_id, scope, day, &rcpt.trim().to_lowercase());
+                if !new_destinations.contains(&key)
+                    && store
+                        .try_lock(KV_SIEVE_REDIRECT_RCPT, &key, expires)
+                        .await
+                        .caused_by(trc::location!())?
+                {
+                    new_destinations.push(key);
+                }
+            }
+
+            if !new_destinations.is_empty() {
+                let destinations = store
+                    .counter_incr(
+                        KeyValue::new(
+                            counter_key(account_id, scope, day, COUNTER_DESTINATIONS),
+                            new_destinations.len() as i64,
+                        )
+                        .expires(expires),
+                        true,
+                    )
+                    .await
+                    .caused_by(trc::location!())?;
+                if limit.max_destinations > 0 && destinations > limit.max_destinations as i64 {
+                    reason = Some((
+                        "Too many redirect destinations",
+                        destinations - new_destinations.len() as i64,
+                        limit.max_destinations,
+                    ));
+                    store
+                        .counter_incr(
+                            KeyValue::new(
+                                counter_key(account_id, scope, day, COUNTER_DESTINATIONS),
+                                -(new_destinations.len() as i64),
+                            ),
+                            false,
+                        )
+                        .await
+                        .caused_by(trc::location!())?;
+                    for key in &new_destinations {
+                        store
+                            .remove_lock(KV_SIEVE_REDIRECT_RCPT, key)
+                            .await
+                            .caused_by(trc::location!())?;
+                    }
+                }
+            }
+        }
+
+        let Some((reason, total, limit)) = reason else {
+            return Ok(true);
+        };
+
+        // Release the redirect that was not sent
+        store
+            .counter_incr(KeyValue::new(redirects_key, -1), false)
+            .await
+            .caused_by(trc::location!())?;
+
+        trc::event!(
+            Sieve(SieveEvent::RedirectLimitExceeded),
+            AccountId = account_id,
+            To = recipients
+                .iter()
+                .map(|r| trc::Value::String(r.as_str().into()))
+                .collect::<Vec<_>>(),
+            Reason = reason,
+            Total = total.max(0) as u64,
+            Limit = limit,
+            SpanId = session_id,
+        );
+
+        Ok(false)
+    }
+
+    async fn sieve_redirect_counters(
+        &self,
+        account_id: u32,
+        scope: RedirectScope,
+    ) -> trc::Result<RedirectCounters> {
+        let day = now() / DAY;
+        let store = self.in_memory_store();
+        let redirects = store
+            .counter_get(counter_key(account_id, scope, day, COUNTER_REDIRECTS))
+            .await
+            .caused_by(trc::location!())?;
+        let destinations = store
+            .counter_get(counter_key(account_id, scope, day, COUNTER_DESTINATIONS))
+            .await
+            .caused_by(trc::location!())?;
+
+        Ok(RedirectCounters {
+            redirects: redirects.max(0) as u64,
+            destinations: destinations.max(0) as u64,
+        })
+    }
+
+    async fn sieve_redirect_reset(&self, account_id: u32) -> trc::Result<()> {
+        for prefix in [KV_SIEVE_REDIRECT, KV_SIEVE_REDIRECT_RCPT] {
+            let mut key = Vec::with_capacity(5);
+            key.push(prefix);
+            key.extend_from_slice(&account_id.to_be_bytes());
+            self.in_memory_store()
+                .key_delete_prefix(&key)
+                .await
+                .caused_by(trc::location!())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RedirectScope {
+    pub fn limit(&self, server: &Server) -> RedirectLimit {
+        match self {
+            RedirectScope::Untrusted => server.core.sieve.untrusted_redirect_limit,
+            RedirectScope::Trusted => server.core.sieve.trusted_redirect_limit,
+        }
+    }
+
+    fn id(&self) -> u8 {
+        match self {
+            RedirectScope::Untrusted => 0,
+            RedirectScope::Trusted => 1,
+        }
+    }
+}
+
+fn counter_key(account_id: u32, scope: RedirectScope, day: u64, counter: u8) -> Vec<u8> {
+    let mut key = Vec::with_capacity(15);
+    key.push(KV_SIEVE_REDIRECT);
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key.push(scope.id());
+    key.extend_from_slice(&day.to_be_bytes());
+    key.push(counter);
+    key
+}
+
+// Destination keys are claimed with try_lock, which adds the prefix
+fn destination_key(account_id: u32, scope: RedirectScope, day: u64, rcpt: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(13 + rcpt.len());
+    key.extend_from_slice(&account_id.to_be_bytes());
+    key.push(scope.id());
+    key.extend_from_slice(&day.to_be_bytes());
+    key.extend_from_slice(rcpt.as_bytes());
+    key
+}
@@ -0,0 +1,205 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{KV_SIEVE_VACATION, Server, config::templates::MessageTemplateVariable};
+use mail_builder::{
+    MessageBuilder,
+    headers::{HeaderType, raw::Raw},
+};
+use mail_parser::{HeaderName, HeaderValue, Message, PartType};
+use registry::schema::enums::MessageTemplateId;
+use store::blake3;
+use utils::template::Variables;
+
+pub(crate) struct VacationReply {
+    pub from: Option<String>,
+    pub handle: String,
+}
+
+impl VacationReply {
+    // RFC 5230 requires vacation responses to be marked as auto-replied,
+    // which is used here to tell them apart from redirects and notifications.
+    pub fn new(message: &Message<'_>, duplicate_id: Option<&str>) -> Option<Self> {
+        if !message
+            .header(HeaderName::AutoSubmitted)
+            .and_then(|value| value.as_text())
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("auto-replied"))
+        {
+            return None;
+        }
+
+        let from = message
+            .from()
+            .and_then(|from| from.first())
+            .and_then(|addr| addr.address())
+            .map(|addr| addr.trim().to_lowercase());
+
+        // Use the tracking id derived from ":handle" when available, otherwise
+        // fall back to a hash of the response subject and body
+        let handle = if let Some(duplicate_id) = duplicate_id {
+            duplicate_id.to_string()
+        } else {
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(message.subject().unwrap_or_default().as_bytes());
+            hasher.update(message.body_text(0).unwrap_or_default().as_bytes());
+            hasher.update(message.body_html(0).unwrap_or_default().as_bytes());
+            hasher.finalize().to_hex().to_string()
+        };
+
+        Some(VacationReply { from, handle })
+    }
+}
+
+// Wraps a response in the auto-reply template of the account's domain, tenant
+// and locale. Templates are resolved when the response is sent so that
+// changes to them apply to existing vacation scripts.
+pub(crate) async fn apply_auto_reply_template(
+    server: &Server,
+    account_id: u32,
+    raw_message: &[u8],
+    message: &Message<'_>,
+) -> Option<Vec<u8>> {
+    let scope = server.account_template_scope(account_id).await;
+    let template = server
+        .core
+        .templates
+        .get(MessageTemplateId::AutoReply, &scope)?;
+    let subject = message.subject().unwrap_or_default();
+
+    let mut variables = Variables::new();
+    variables.insert_single(
+        MessageTemplateVariable::Recipient,
+        message
+            .from()
+            .and_then(|from| from.first())
+            .and_then(|addr| addr.address())
+            .unwrap_or_default()
+            .to_string(),
+    );
+    variables.insert_single(MessageTemplateVariable::Subject, subject.to_string());
+    variables.insert_single(
+        MessageTemplateVariable::Body,
+        message.body_text(0).unwrap_or_default().into_owned(),
+    );
+    let rendered = template.render(&variables);
+
+    // HTML templates are only used when the response has an HTML body
+    let html_body = message
+        .html_body
+        .first()
+        .and_then(|part_id| message.part(*part_id))
+        .and_then(|part| match &part.body {
+            PartType::Html(html) => Some(html.as_ref()),
+            _ => None,
+        })
+        .zip(template.html_body.as_ref())
+        .map(|(body, template)| {
+            variables.insert_single(MessageTemplateVariable::Body, body.to_string());
+            template.eval(&variables)
+        });
+
+    // Keep the headers added by the vacation action, the body is rebuilt
+    let mut builder = MessageBuilder::new();
+    for header in message.headers() {
+        if !matches!(
+            header.name,
+            HeaderName::Subject
+                | HeaderName::MimeVersion
+                | HeaderName::ContentType
+                | HeaderName::ContentTransferEncoding
+        ) && let Some(value) = raw_message
+            .get(header.offset_start as usize..header.offset_end as usize)
+            .and_then(|value| std::str::from_utf8(value).ok())
+        {
+            builder = builder.header(
+                header.name.as_str().to_string(),
+                HeaderType::Raw(Raw::new(value.trim().to_string())),
+            );
+        }
+    }
+    builder = builder
+        .subject(if !rendered.subject.is_empty() {
+            rendered.subject
+        } else {
+            subject.to_string()
+        })
+        .text_body(rendered.text_body);
+    if let Some(html_body) = html_body {
+        builder = builder.html_body(html_body);
+    }
+
+    builder.write_to_vec().ok()
+}
+
+pub(crate) fn is_list_or_bulk(message: &Message<'_>) -> bool {
+    message.headers().iter().any(|header| match &header.name {
+        HeaderName::ListId
+        | HeaderName::ListArchive
+        | HeaderName::ListHelp
+        | HeaderName::ListOwner
+        | HeaderName::ListPost
+        | HeaderName::ListSubscribe
+        | HeaderName::ListUnsubscribe
+        | HeaderName::ListUnsubscribePost => true,
+        HeaderName::AutoSubmitted => {
+            header_text(&header.value).is_some_and(|value| !value.eq_ignore_ascii_case("no"))
+        }
+        HeaderName::Other(name) if name.eq_ignore_ascii_case("Precedence") => {
+            header_text(&header.value).is_some_and(|value| {
+                ["bulk", "list", "junk"]
+                    .iter()
+                    .any(|precedence| value.eq_ignore_ascii_case(precedence))
+            })
+        }
+        _ => false,
+    })
+}
+
+pub(crate) fn addressed_identity<'x>(
+    message: &Message<'_>,
+    addresses: &'x [String],
+) -> Option<&'x str> {
+    message
+        .headers()
+        .iter()
+        .filter(|header| {
+            matches!(
+                header.name,
+                HeaderName::To
+                    | HeaderName::Cc
+                    | HeaderName::Bcc
+                    | HeaderName::ResentTo
+                    | HeaderName::ResentCc
+                    | HeaderName::ResentBcc
+            )
+        })
+        .filter_map(|header| header.value.as_address())
+        .flat_map(|rcpts| rcpts.iter())
+        .filter_map(|rcpt| rcpt.address())
+        .find_map(|rcpt| {
+            addresses
+                .iter()
+                .find(|address| address.eq_ignore_ascii_case(rcpt.trim()))
+        })
+        .map(|address| address.as_str())
+}
+
+pub(crate) fn suppression_key(account_id: u32, sender: &str, handle: &str) -> Vec<u8> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&account_id.to_be_bytes());
+    hasher.update(sender.trim().to_lowercase().as_bytes());
+    hasher.update(&[0]);
+    hasher.update(handle.as_bytes());
+
+    let mut key = Vec::with_capacity(33);
+    key.push(KV_SIEVE_VACATION);
+    key.extend_from_slice(hasher.finalize().as_bytes());
+    key
+}
+
+fn header_text<'x>(value: &'x HeaderValue<'_>) -> Option<&'x str> {
+    value.as_text().map(|value| value.trim())
+}
@@ -68,6 +68,38 @@ pub enum UndoStatus {
     Canceled,
 }
 
+impl EmailSubmission {
+    /// Submissions held until their sendAt time have not been handed to
+    /// the queue yet and have no delivery status.
+    pub fn is_scheduled(&self) -> bool {
+        self.queue_id.is_none()
+            && matches!(self.undo_status, UndoStatus::Pending)
+            && self.delivery_status.is_empty()
+    }
+
+    /// Records the reply received for each recipient, recipients without an
+    /// error reply were queued.
+    pub fn set_delivery_status(&mut self, rcpt_responses: Vec<(String, Option<String>)>) {
+        self.delivery_status = rcpt_responses
+            .into_iter()
+            .map(|(address, response)| {
+                (
+                    address,
+                    DeliveryStatus {
+                        delivered: if response.is_none() {
+                            Delivered::Unknown
+                        } else {
+                            Delivered::No
+                        },
+                        smtp_reply: response.unwrap_or_else(|| "250 2.1.5 Queued".to_string()),
+                        displayed: false,
+                    },
+                )
+            })
+            .collect();
+    }
+}
+
 impl UndoStatus {
     pub fn parse(s: &str) -> Option<Self> {
         hashify::tiny_map!(s.as_bytes(),
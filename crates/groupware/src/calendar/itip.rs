@@ -12,7 +12,7 @@ use crate::{
         EVENT_NOTIFICATION_IS_CHANGE,
     },
     scheduling::{
-        ItipError, ItipMessage,
+        Email, ItipError, ItipMessage,
         inbound::{
             MergeResult, itip_import_message, itip_merge_changes, itip_method, itip_process_message,
         },
@@ -29,7 +29,7 @@ use calcard::{
 };
 use common::{
     DavName, Server,
-    auth::{AccountInfo, oauth::GrantType},
+    auth::{ACCOUNT_FLAG_ITIP_AUTO_ADD, AccountInfo, oauth::GrantType},
     config::groupware::CalendarTemplateVariable,
     i18n,
 };
@@ -192,6 +192,9 @@ impl ItipIngest for Server {
                     MergeResult::Actions(changes) => {
                         // Merge changes
                         itip_merge_changes(&mut event.data.event, changes);
+                        if is_organizer_update {
+                            itip_default_partstat(&mut event.data.event, account_info.addresses());
+                        }
 
                         // Calculate the new ical size
                         event.size = event.data.event.to_string().len() as u32;
@@ -291,6 +294,7 @@ impl ItipIngest for Server {
         } else {
             // Verify that auto-adding invitations is allowed
             if !self.core.groupware.itip_auto_add
+                && account_info.account.flags & ACCOUNT_FLAG_ITIP_AUTO_ADD == 0
                 && !matches!(changed_by, ChangedBy::PrincipalId(_))
                 && !self
                     .document_exists(
@@ -310,6 +314,7 @@ impl ItipIngest for Server {
             // Import the iTIP message
             let mut ical = itip.clone();
             itip_import_message(&mut ical)?;
+            itip_default_partstat(&mut ical, account_info.addresses());
 
             // Validate quota
             if self
@@ -710,3 +715,32 @@ impl From<trc::Error> for ItipIngestError {
         ItipIngestError::Internal(err)
     }
 }
+
+// Local attendees without a participation status have not replied yet
+fn itip_default_partstat(ical: &mut ICalendar, account_addresses: &[String]) {
+    for entry in ical
+        .components
+        .iter_mut()
+        .filter(|comp| comp.component_type.is_scheduling_object())
+        .flat_map(|comp| comp.entries.iter_mut())
+        .filter(|entry| {
+            entry.name == ICalendarProperty::Attendee
+                && !entry
+                    .params
+                    .iter()
+                    .any(|param| param.name == ICalendarParameterName::Partstat)
+        })
+    {
+        if entry
+            .values
+            .first()
+            .and_then(|value| value.as_text())
+            .and_then(|value| Email::new(value, account_addresses))
+            .is_some_and(|email| email.is_local)
+        {
+            entry.params.push(ICalendarParameter::partstat(
+                ICalendarParticipationStatus::NeedsAction,
+            ));
+        }
+    }
+}
@@ -9,6 +9,7 @@
 use calcard::common::timezone::Tz;
 use common::DavResources;
 use percent_encoding::{AsciiSet, CONTROLS};
+use registry::schema::enums::ServiceProtocol;
 use types::collection::{Collection, SyncCollection};
 
 pub mod cache;
@@ -80,6 +81,20 @@ impl DavResourceName {
         }
     }
 
+    // Protocols serving this resource, access requires any of them enabled
+    pub fn protocols(&self) -> &'static [ServiceProtocol] {
+        match self {
+            DavResourceName::Card => &[ServiceProtocol::Carddav],
+            DavResourceName::Cal | DavResourceName::Scheduling => &[ServiceProtocol::Caldav],
+            DavResourceName::File => &[ServiceProtocol::Webdav],
+            DavResourceName::Principal => &[
+                ServiceProtocol::Caldav,
+                ServiceProtocol::Carddav,
+                ServiceProtocol::Webdav,
+            ],
+        }
+    }
+
     pub fn collection_path(&self) -> &'static str {
         match self {
             DavResourceName::Card => "/dav/card/",
@@ -0,0 +1,182 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    Server,
+    auth::AccessToken,
+    network::dane::{DaneRecord, certificate_covers},
+};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::{schema::enums::Permission, types::datetime::UTCDateTime};
+use serde::Serialize;
+use smtp::outbound::dane::rollover::{DanePublishedTlsa, PublishedTlsa};
+use trc::AddContext;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaneReport {
+    pub hostnames: Vec<DaneHostname>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaneHostname {
+    pub hostname: String,
+    pub name: String,
+    pub status: DanePublishedStatus,
+    pub published: Vec<String>,
+    pub certificates: Vec<DaneCertificate>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DanePublishedStatus {
+    Published,
+    NotFound,
+    Bogus,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DaneCertificateStage {
+    Active,
+    Staged,
+    Next,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaneCertificate {
+    pub stage: DaneCertificateStage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_valid_before: Option<UTCDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not_valid_after: Option<UTCDateTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staged_until: Option<UTCDateTime>,
+    pub records: Vec<String>,
+    pub matches: Option<bool>,
+}
+
+pub trait DaneApi: Sync + Send {
+    fn handle_dane_get_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl DaneApi for Server {
+    async fn handle_dane_get_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysCertificateGet)?;
+
+        let upcoming = self
+            .upcoming_certificates()
+            .await
+            .caused_by(trc::location!())?;
+        let mut hostnames = Vec::new();
+        let mut warnings = Vec::new();
+
+        for hostname in self.dane_mx_hostnames() {
+            let name = format!("_25._tcp.{hostname}.");
+            let published = self.published_tlsa(&hostname).await;
+            let status = match &published {
+                PublishedTlsa::Records(_) => DanePublishedStatus::Published,
+                PublishedTlsa::NotFound => DanePublishedStatus::NotFound,
+                PublishedTlsa::Bogus => {
+                    warnings.push(format!(
+                        "TLSA records for {name} failed DNSSEC validation, DANE clients will not deliver to {hostname}"
+                    ));
+                    DanePublishedStatus::Bogus
+                }
+                PublishedTlsa::Error(err) => {
+                    warnings.push(format!("Failed to fetch TLSA records for {name}: {err}"));
+                    DanePublishedStatus::Error
+                }
+            };
+            let is_published = matches!(status, DanePublishedStatus::Published);
+
+            let mut certificates = Vec::new();
+            if let Some(key) = self.resolve_certificate(&hostname) {
+                let matches = published.matches(&key.cert);
+                if is_published && matches == Some(false) {
+                    warnings.push(format!(
+                        "The certificate served for {hostname} does not match any TLSA record at {name}"
+                    ));
+                }
+                certificates.push(DaneCertificate {
+                    stage: DaneCertificateStage::Active,
+                    id: None,
+                    not_valid_before: None,
+                    not_valid_after: None,
+                    staged_until: None,
+                    records: dane_records(DaneRecord::from_chain(&key.cert)),
+                    matches,
+                });
+            }
+
+            for cert in upcoming.iter().filter(|cert| {
+                certificate_covers(
+                    cert.certificate
+                        .subject_alternative_names
+                        .iter()
+                        .map(String::as_str),
+                    &hostname,
+                )
+            }) {
+                let matches = published.matches(&cert.chain);
+                let staged_until = cert.certificate.staged_until;
+                if is_published && matches == Some(false) {
+                    warnings.push(format!(
+                        "Upcoming certificate {} for {hostname} does not match any TLSA record at {name}, publish its records before it is activated",
+                        cert.id
+                    ));
+                }
+                certificates.push(DaneCertificate {
+                    stage: if staged_until.is_some() {
+                        DaneCertificateStage::Staged
+                    } else {
+                        DaneCertificateStage::Next
+                    },
+                    id: Some(cert.id.to_string()),
+                    not_valid_before: Some(cert.certificate.not_valid_before),
+                    not_valid_after: Some(cert.certificate.not_valid_after),
+                    staged_until,
+                    records: dane_records(DaneRecord::from_chain(&cert.chain)),
+                    matches,
+                });
+            }
+
+            hostnames.push(DaneHostname {
+                hostname,
+                name,
+                status,
+                published: published.records(),
+                certificates,
+            });
+        }
+
+        Ok(JsonResponse::new(DaneReport {
+            hostnames,
+            warnings,
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
+
+fn dane_records(records: Vec<DaneRecord>) -> Vec<String> {
+    records
+        .into_iter()
+        .map(|record| record.to_string())
+        .collect()
+}
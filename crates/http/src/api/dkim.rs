@@ -0,0 +1,402 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    Server,
+    auth::{AccessToken, DomainCache},
+    cache::invalidate::CacheInvalidationBuilder,
+    network::dkim::{
+        dkim_public_key_tag, generate_dkim_dns_record_name, generate_dkim_private_key_with_size,
+        generate_dkim_selector, generate_dkim_txt_value, split_txt_value,
+    },
+};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use mail_auth::common::{parse::TxtRecordParser, verify::DomainKey};
+use registry::{
+    schema::{
+        enums::{DkimRotationStage, DkimSignatureType, Permission},
+        prelude::{Object, ObjectType, Property},
+        structs::{
+            Dkim1Signature, Dkim2Signature, DkimManagement, DkimManagementProperties,
+            DkimSignature, Domain, SecretText, SecretTextValue,
+        },
+    },
+    types::id::ObjectId,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use store::registry::{
+    RegistryQuery,
+    write::{RegistryWrite, RegistryWriteResult},
+};
+use trc::{AddContext, DkimEvent};
+use types::id::Id;
+
+// Keys are always created pending, they are activated once their DNS record is verified
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct DkimKeyRequest {
+    pub algorithm: DkimKeyAlgorithm,
+    pub version: Option<u8>,
+    pub selector: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DkimKeyAlgorithm {
+    #[default]
+    #[serde(rename = "rsa-2048")]
+    Rsa2048,
+    #[serde(rename = "rsa-4096")]
+    Rsa4096,
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DkimKeyResponse {
+    pub id: String,
+    pub domain: String,
+    pub selector: String,
+    pub algorithm: DkimKeyAlgorithm,
+    pub stage: DkimRotationStage,
+    pub dns_record: DkimDnsRecord,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DkimDnsRecord {
+    pub name: String,
+    pub value: String,
+    pub zone_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DkimVerifyResponse {
+    pub selector: String,
+    pub dns_record: DkimDnsRecord,
+    pub published: Option<String>,
+    pub matches: bool,
+    pub stage: DkimRotationStage,
+    pub error: Option<String>,
+}
+
+pub trait DkimApi: Sync + Send {
+    fn handle_dkim_generate_request(
+        &self,
+        domain: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_dkim_verify_request(
+        &self,
+        domain: &str,
+        selector: &str,
+        activate: bool,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl DkimApi for Server {
+    async fn handle_dkim_generate_request(
+        &self,
+        domain: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysDkimSignatureCreate)?;
+
+        // An empty body requests a key with the default parameters
+        let request = match body.as_deref() {
+            Some(body) if !body.is_empty() => serde_json::from_slice::<DkimKeyRequest>(body)
+                .map_err(|err| {
+                    trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+                })?,
+            _ => DkimKeyRequest::default(),
+        };
+        let key_type = match (request.version.unwrap_or(1), request.algorithm) {
+            (1, DkimKeyAlgorithm::Ed25519) => DkimSignatureType::Dkim1Ed25519Sha256,
+            (1, _) => DkimSignatureType::Dkim1RsaSha256,
+            (2, DkimKeyAlgorithm::Ed25519) => DkimSignatureType::Dkim2Ed25519Sha256,
+            (2, _) => DkimSignatureType::Dkim2RsaSha256,
+            (version, _) => {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("Invalid DKIM version")
+                    .ctx(trc::Key::Value, u64::from(version)));
+            }
+        };
+
+        // Derive the selector from the domain's template unless one was provided
+        let domain = dkim_domain(self, domain, access_token).await?;
+        let template = match request.selector {
+            Some(selector) => selector,
+            None => match self
+                .registry()
+                .object::<Domain>(Id::from(domain.id))
+                .await
+                .caused_by(trc::location!())?
+                .map(|domain| domain.dkim_management)
+            {
+                Some(DkimManagement::Automatic(props)) => props.selector_template,
+                _ => DkimManagementProperties::default().selector_template,
+            },
+        };
+        let selector = generate_dkim_selector(&template, key_type).map_err(|err| {
+            trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid DKIM selector")
+                .reason(err)
+        })?;
+        if find_signature(self, &domain, &selector).await?.is_some() {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("A DKIM signature with this selector already exists")
+                .ctx(trc::Key::Id, selector));
+        }
+
+        // Generate key
+        let rsa_bits = if request.algorithm == DkimKeyAlgorithm::Rsa4096 {
+            4096
+        } else {
+            2048
+        };
+        let secret = generate_dkim_private_key_with_size(key_type, rsa_bits)
+            .await?
+            .map_err(|err| trc::DkimEvent::BuildError.into_err().reason(err))?;
+        let stage = DkimRotationStage::Pending;
+        let private_key = SecretText::Text(SecretTextValue { secret });
+        let domain_id = Id::from(domain.id);
+        let member_tenant_id = domain.id_tenant.map(Id::from);
+        let signature = match key_type {
+            DkimSignatureType::Dkim1Ed25519Sha256 | DkimSignatureType::Dkim1RsaSha256 => {
+                let signature = Dkim1Signature {
+                    stage,
+                    domain_id,
+                    member_tenant_id,
+                    selector: selector.clone(),
+                    private_key,
+                    ..Default::default()
+                };
+                if key_type == DkimSignatureType::Dkim1Ed25519Sha256 {
+                    DkimSignature::Dkim1Ed25519Sha256(signature)
+                } else {
+                    DkimSignature::Dkim1RsaSha256(signature)
+                }
+            }
+            DkimSignatureType::Dkim2Ed25519Sha256 | DkimSignatureType::Dkim2RsaSha256 => {
+                let signature = Dkim2Signature {
+                    stage,
+                    domain_id,
+                    member_tenant_id,
+                    selector: selector.clone(),
+                    private_key,
+                    ..Default::default()
+                };
+                if key_type == DkimSignatureType::Dkim2Ed25519Sha256 {
+                    DkimSignature::Dkim2Ed25519Sha256(signature)
+                } else {
+                    DkimSignature::Dkim2RsaSha256(signature)
+                }
+            }
+        };
+        let dns_record = dkim_dns_record(&signature, &domain).await?;
+
+        // Write key and reload the domain's signers
+        let object = Object::from(signature);
+        let id = match self
+            .registry()
+            .write(RegistryWrite::insert(&object))
+            .await
+            .caused_by(trc::location!())?
+        {
+            RegistryWriteResult::Success(id) => id,
+            failure => {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("Failed to create DKIM signature")
+                    .reason(failure));
+            }
+        };
+        let mut invalidator = CacheInvalidationBuilder::default();
+        invalidator.process_create(&object);
+        self.invalidate_caches(invalidator)
+            .await
+            .caused_by(trc::location!())?;
+
+        trc::event!(
+            Dkim(DkimEvent::SignatureCreated),
+            Id = selector.clone(),
+            Details = domain.names[0].to_string()
+        );
+
+        Ok(JsonResponse::new(DkimKeyResponse {
+            id: id.to_string(),
+            domain: domain.names[0].to_string(),
+            selector,
+            algorithm: request.algorithm,
+            stage,
+            dns_record,
+        })
+        .no_cache()
+        .into_http_response())
+    }
+
+    async fn handle_dkim_verify_request(
+        &self,
+        domain: &str,
+        selector: &str,
+        activate: bool,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysDkimSignatureGet)?;
+        if activate {
+            access_token.enforce_permission(Permission::SysDkimSignatureUpdate)?;
+        }
+
+        let domain = dkim_domain(self, domain, access_token).await?;
+        let (id, current) = find_signature(self, &domain, selector)
+            .await?
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        let mut signature = DkimSignature::from(current.clone());
+        let dns_record = dkim_dns_record(&signature, &domain).await?;
+
+        // Compare the published public key against the stored one
+        let (published, error) = match self
+            .core
+            .smtp
+            .resolvers
+            .dns
+            .txt_raw_lookup(dns_record.name.as_str())
+            .await
+        {
+            Ok(result) => (Some(String::from_utf8_lossy(&result).into_owned()), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        let (matches, error) = match &published {
+            Some(published) => {
+                if let Err(err) = DomainKey::parse(published.as_bytes()) {
+                    (false, Some(format!("Invalid DKIM record: {err}")))
+                } else if dkim_public_key_tag(published) != dkim_public_key_tag(&dns_record.value) {
+                    (
+                        false,
+                        Some("Published public key does not match".to_string()),
+                    )
+                } else {
+                    (true, None)
+                }
+            }
+            None => (false, error),
+        };
+
+        // Activate the signature once the record is published
+        if matches && activate && !signature.is_active() {
+            signature.set_stage(DkimRotationStage::Active);
+            let updated = Object::from(signature.clone());
+            match self
+                .registry()
+                .write(RegistryWrite::update(id, &updated, &current))
+                .await
+                .caused_by(trc::location!())?
+            {
+                RegistryWriteResult::Success(id) => {
+                    let mut invalidator = CacheInvalidationBuilder::default();
+                    invalidator.process_update(id, &current, &updated);
+                    self.invalidate_caches(invalidator)
+                        .await
+                        .caused_by(trc::location!())?;
+                }
+                failure => {
+                    return Err(trc::ResourceEvent::BadParameters
+                        .into_err()
+                        .details("Failed to activate DKIM signature")
+                        .reason(failure));
+                }
+            }
+
+            trc::event!(
+                Dkim(DkimEvent::SignaturePublished),
+                Id = selector.to_string(),
+                Details = domain.names[0].to_string()
+            );
+        }
+
+        Ok(JsonResponse::new(DkimVerifyResponse {
+            selector: selector.to_string(),
+            stage: signature.stage(),
+            dns_record,
+            published,
+            matches,
+            error,
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
+
+async fn dkim_domain(
+    server: &Server,
+    domain: &str,
+    access_token: &AccessToken,
+) -> trc::Result<Arc<DomainCache>> {
+    // Tenants can only manage the keys of their own domains
+    server
+        .domain(&domain.to_lowercase())
+        .await?
+        .filter(|domain| {
+            access_token
+                .tenant_id()
+                .is_none_or(|tenant_id| domain.id_tenant == Some(tenant_id))
+        })
+        .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())
+}
+
+async fn find_signature(
+    server: &Server,
+    domain: &DomainCache,
+    selector: &str,
+) -> trc::Result<Option<(Id, Object)>> {
+    let ids = server
+        .registry()
+        .query::<Vec<Id>>(
+            RegistryQuery::new(ObjectType::DkimSignature).equal(Property::DomainId, domain.id),
+        )
+        .await
+        .caused_by(trc::location!())?;
+
+    for id in ids {
+        if let Some(object) = server
+            .registry()
+            .get(ObjectId::new(ObjectType::DkimSignature, id))
+            .await
+            .caused_by(trc::location!())?
+            && DkimSignature::from(object.clone()).selector() == selector
+        {
+            return Ok(Some((id, object)));
+        }
+    }
+
+    Ok(None)
+}
+
+async fn dkim_dns_record(
+    signature: &DkimSignature,
+    domain: &DomainCache,
+) -> trc::Result<DkimDnsRecord> {
+    let value = generate_dkim_txt_value(signature).await?;
+
+    Ok(DkimDnsRecord {
+        zone_value: split_txt_value(&value)
+            .into_iter()
+            .map(|chunk| format!("\"{chunk}\""))
+            .collect::<Vec<_>>()
+            .join(" "),
+        name: generate_dkim_dns_record_name(signature, &domain.names[0]),
+        value,
+    })
+}
@@ -9,13 +9,42 @@
 // SPDX-License-Identifier: LicenseRef-SEL
 #[cfg(feature = "enterprise")]
 pub mod telemetry;
+#[cfg(feature = "enterprise")]
+pub mod trace;
 // SPDX-SnippetEnd
+pub mod dane;
 pub mod diagnose;
+pub mod dkim;
+pub mod mta_sts;
+pub mod oauth;
+pub mod queue;
+pub mod quota;
+pub mod reputation;
+pub mod sessions;
+pub mod sieve;
+pub mod slow;
+pub mod store;
+pub mod tracing;
 
 use crate::{
-    api::diagnose::{DeliveryStage, spawn_delivery_diagnose},
+    api::{
+        dane::DaneApi,
+        diagnose::{DeliveryStage, spawn_delivery_diagnose},
+        dkim::DkimApi,
+        mta_sts::MtaStsApi,
+        oauth::OAuthDeviceApi,
+        queue::QueueApi,
+        quota::QuotaApi,
+        reputation::SenderReputationApi,
+        sessions::SessionApi,
+        sieve::SieveRedirectApi,
+        slow::SlowRequestApi,
+        store::StoreBackupApi,
+        tracing::TracingOverrideApi,
+    },
     auth::{
         authenticate::Authenticator, oauth::auth::OAuthApiHandler, permissions::AccountApiHandler,
+        step_up::StepUpHandler,
     },
 };
 use common::{
@@ -87,6 +116,16 @@ impl ManagementApi for Server {
                     Err(trc::ResourceEvent::NotFound.into_err())
                 }
             }
+            "step-up" if is_post => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                self.handle_step_up_request(
+                    &access_token,
+                    session,
+                    body.ok_or_else(|| trc::LimitEvent::SizeRequest.into_err())?,
+                )
+                .await
+            }
             "account" => {
                 // Authenticate request
                 let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
@@ -109,6 +148,227 @@ impl ManagementApi for Server {
                     Ok(HttpResponse::redirect(format!("/api/schema/{SCHEMA_HASH}")))
                 }
             }
+            "queue" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (
+                    path.get(1).copied().unwrap_or_default(),
+                    path.get(2).copied(),
+                    path.get(3).copied(),
+                    req.method(),
+                ) {
+                    ("messages", Some(id), Some("preview"), &Method::GET) => {
+                        self.handle_queue_preview_request(id, &access_token).await
+                    }
+                    ("routes", None | Some(""), _, &Method::GET) => {
+                        self.handle_queue_routes_request(&access_token).await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "store" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), path.get(2).copied(), req.method()) {
+                    (Some("backup"), None | Some(""), &Method::POST) => {
+                        self.handle_store_backup_request(body, &access_token).await
+                    }
+                    (Some("quota"), Some(account_id), &Method::POST) if !account_id.is_empty() => {
+                        self.handle_quota_reconcile_request(account_id, &access_token)
+                            .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "sessions" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), req.method()) {
+                    (None | Some(""), &Method::GET) => {
+                        self.handle_session_list_request(&access_token).await
+                    }
+                    (Some(account_id), &Method::DELETE) => {
+                        let params = UrlParams::new(req.uri().query());
+                        self.handle_session_terminate_request(
+                            account_id,
+                            params.get("protocol"),
+                            params.parse("revoke").unwrap_or_default(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "sieve" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), path.get(2).copied(), req.method()) {
+                    (Some("redirects"), Some(account_id), &Method::GET)
+                        if !account_id.is_empty() =>
+                    {
+                        self.handle_sieve_redirect_get_request(account_id, &access_token)
+                            .await
+                    }
+                    (Some("redirects"), Some(account_id), &Method::DELETE)
+                        if !account_id.is_empty() =>
+                    {
+                        self.handle_sieve_redirect_reset_request(account_id, &access_token)
+                            .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "reputation" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), path.get(2).copied(), req.method()) {
+                    (Some(kind), Some(value), &Method::GET) if !value.is_empty() => {
+                        self.handle_sender_reputation_get_request(
+                            kind,
+                            decode_path_element(value).as_ref(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    (Some(kind), Some(value), &Method::DELETE) if !value.is_empty() => {
+                        self.handle_sender_reputation_reset_request(
+                            kind,
+                            decode_path_element(value).as_ref(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "dkim" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (
+                    path.get(1).copied(),
+                    path.get(2).copied(),
+                    path.get(3).copied(),
+                    req.method(),
+                ) {
+                    (Some(domain), Some("generate"), None, &Method::POST) if !domain.is_empty() => {
+                        self.handle_dkim_generate_request(
+                            decode_path_element(domain).as_ref(),
+                            body,
+                            &access_token,
+                        )
+                        .await
+                    }
+                    (Some(domain), Some("verify"), Some(selector), &Method::POST)
+                        if !domain.is_empty() && !selector.is_empty() =>
+                    {
+                        let params = UrlParams::new(req.uri().query());
+                        self.handle_dkim_verify_request(
+                            decode_path_element(domain).as_ref(),
+                            decode_path_element(selector).as_ref(),
+                            params.parse("activate").unwrap_or_default(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "oauth" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), path.get(2).copied(), req.method()) {
+                    (Some("device"), Some(user_code), &Method::GET) if !user_code.is_empty() => {
+                        self.handle_device_authorization_list(
+                            decode_path_element(user_code).as_ref(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    (Some("device"), Some(user_code), &Method::POST) if !user_code.is_empty() => {
+                        self.handle_device_authorization_decision(
+                            decode_path_element(user_code).as_ref(),
+                            body,
+                            &access_token,
+                        )
+                        .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "dane" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), req.method()) {
+                    (None | Some(""), &Method::GET) => {
+                        self.handle_dane_get_request(&access_token).await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "mta-sts" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), req.method()) {
+                    (Some(domain), &Method::GET) if !domain.is_empty() => {
+                        self.handle_mta_sts_get_request(
+                            decode_path_element(domain).as_ref(),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    (Some(domain), &Method::POST) if !domain.is_empty() => {
+                        self.handle_mta_sts_update_request(
+                            decode_path_element(domain).as_ref(),
+                            body,
+                            &access_token,
+                        )
+                        .await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
+            "telemetry" => {
+                // Authenticate request
+                let (_in_flight, access_token) = self.authenticate_headers(req, session).await?;
+                match (path.get(1).copied(), path.get(2).copied(), req.method()) {
+                    // SPDX-SnippetBegin
+                    // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+                    // SPDX-License-Identifier: LicenseRef-SEL
+                    #[cfg(feature = "enterprise")]
+                    (Some("trace"), Some(id), &Method::GET)
+                        if !id.is_empty() && self.core.is_enterprise_edition() =>
+                    {
+                        use crate::api::trace::TraceApi;
+
+                        self.handle_message_trace_request(
+                            id,
+                            &UrlParams::new(req.uri().query()),
+                            &access_token,
+                        )
+                        .await
+                    }
+                    // SPDX-SnippetEnd
+                    (Some("trace"), _, &Method::GET) => {
+                        Err(trc::ResourceEvent::NotFound
+                            .ctx(trc::Key::Details, "Enterprise feature"))
+                    }
+                    (Some("overrides"), None | Some(""), &Method::GET) => {
+                        self.handle_tracing_override_list(&access_token).await
+                    }
+                    (Some("overrides"), None | Some(""), &Method::POST) => {
+                        self.handle_tracing_override_create(body, &access_token)
+                            .await
+                    }
+                    (Some("overrides"), Some(id), &Method::DELETE) if !id.is_empty() => {
+                        self.handle_tracing_override_delete(id, &access_token).await
+                    }
+                    (Some("slow-requests"), None | Some(""), &Method::GET) => {
+                        self.handle_slow_request_list(&access_token).await
+                    }
+                    _ => Err(trc::ResourceEvent::NotFound.into_err()),
+                }
+            }
             "token" => {
                 let access_token = self.management_access_token(req, session).await?;
                 let account_id = access_token.account_id();
@@ -120,6 +380,7 @@ impl ManagementApi for Server {
                     Some("tracing") if self.core.is_enterprise_edition() => {
                         // Validate the access token
                         access_token.enforce_permission(Permission::LiveTracing)?;
+                        self.assert_step_up(&access_token, Permission::LiveTracing)?;
 
                         // Issue a live telemetry token valid for 60 seconds
                         Ok(HttpResponse::new(StatusCode::OK)
@@ -131,7 +392,7 @@ impl ManagementApi for Server {
                                     self.account(account_id).await?.name(),
                                     60,
                                     None,
-                                    None,
+                                    access_token.credential_version().into(),
                                 )
                                 .await?,
                             ))
@@ -140,6 +401,7 @@ impl ManagementApi for Server {
                     Some("metrics") if self.core.is_enterprise_edition() => {
                         // Validate the access token
                         access_token.enforce_permission(Permission::LiveMetrics)?;
+                        self.assert_step_up(&access_token, Permission::LiveMetrics)?;
 
                         // Issue a live telemetry token valid for 60 seconds
                         Ok(HttpResponse::new(StatusCode::OK)
@@ -151,7 +413,7 @@ impl ManagementApi for Server {
                                     self.account(account_id).await?.name(),
                                     60,
                                     None,
-                                    None,
+                                    access_token.credential_version().into(),
                                 )
                                 .await?,
                             ))
@@ -160,6 +422,7 @@ impl ManagementApi for Server {
                     Some("delivery") => {
                         // Validate the access token
                         access_token.enforce_permission(Permission::LiveDeliveryTest)?;
+                        self.assert_step_up(&access_token, Permission::LiveDeliveryTest)?;
 
                         // Issue a live telemetry token valid for 60 seconds
                         Ok(HttpResponse::new(StatusCode::OK)
@@ -171,7 +434,7 @@ impl ManagementApi for Server {
                                     self.account(account_id).await?.name(),
                                     60,
                                     None,
-                                    None,
+                                    access_token.credential_version().into(),
                                 )
                                 .await?,
                             ))
@@ -196,6 +459,7 @@ impl ManagementApi for Server {
                     ("delivery", Some(target), &Method::GET) => {
                         // Validate the access token
                         access_token.enforce_permission(Permission::LiveDeliveryTest)?;
+                        self.assert_step_up(&access_token, Permission::LiveDeliveryTest)?;
 
                         let timeout = Duration::from_secs(
                             params
@@ -290,7 +554,9 @@ impl ManagementApi for Server {
                 self.validate_access_token(grant_type.into(), token)
                     .await
                     .map(|token_info| {
+                        // Live tokens are only issued after passing the step-up check
                         AccessToken::from_permissions(token_info.account_id, [permission])
+                            .with_step_up(token_info.issued_at)
                     })
             } else {
                 self.authenticate_headers(req, session)
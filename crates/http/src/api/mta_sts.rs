@@ -0,0 +1,192 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    Server,
+    auth::{AccessToken, DomainCache},
+    cache::invalidate::CacheInvalidationBuilder,
+    config::smtp::resolver::Mode,
+};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::{
+    schema::{
+        enums::{Permission, PolicyEnforcement},
+        prelude::{Object, ObjectType},
+        structs::Domain,
+    },
+    types::{duration::Duration, id::ObjectId, map::Map},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use store::registry::write::{RegistryWrite, RegistryWriteResult};
+use trc::AddContext;
+use types::id::Id;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MtaStsPolicy {
+    pub domain: String,
+    pub id: String,
+    pub txt_record: String,
+    pub mode: Mode,
+    pub mx_hosts: Vec<String>,
+    pub max_age: u64,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MtaStsUpdate {
+    pub mode: Option<PolicyEnforcement>,
+    pub mx_hosts: Vec<String>,
+    pub max_age: Option<u64>,
+}
+
+pub trait MtaStsApi: Sync + Send {
+    fn handle_mta_sts_get_request(
+        &self,
+        domain: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_mta_sts_update_request(
+        &self,
+        domain: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl MtaStsApi for Server {
+    async fn handle_mta_sts_get_request(
+        &self,
+        domain: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysDomainGet)?;
+
+        let domain = mta_sts_domain(self, domain, access_token).await?;
+        mta_sts_response(self, &domain)
+    }
+
+    async fn handle_mta_sts_update_request(
+        &self,
+        domain: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysDomainUpdate)?;
+
+        let request = serde_json::from_slice::<MtaStsUpdate>(body.as_deref().unwrap_or_default())
+            .map_err(|err| {
+            trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+        })?;
+        if request.max_age == Some(0) {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid max age"));
+        }
+        if let Some(mx) = request.mx_hosts.iter().find(|mx| {
+            let mx = mx.strip_prefix("*.").unwrap_or(mx);
+            mx.is_empty() || mx.contains(['*', ' ', '/', ':'])
+        }) {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid MX pattern")
+                .ctx(trc::Key::Value, mx.to_string()));
+        }
+
+        // Update the domain object
+        let domain = mta_sts_domain(self, domain, access_token).await?;
+        let object_id = ObjectId::new(ObjectType::Domain, domain.id.into());
+        let current = self
+            .registry()
+            .get(object_id)
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        let mut updated = Domain::from(current.clone());
+        updated.mta_sts_mode = request.mode;
+        updated.mta_sts_mx_hosts = Map::new(request.mx_hosts);
+        updated.mta_sts_max_age = request
+            .max_age
+            .map(|max_age| Duration(std::time::Duration::from_secs(max_age)));
+        let updated = Object::from(updated);
+
+        match self
+            .registry()
+            .write(RegistryWrite::update(
+                Id::from(domain.id),
+                &updated,
+                &current,
+            ))
+            .await
+            .caused_by(trc::location!())?
+        {
+            RegistryWriteResult::Success(id) => {
+                let mut invalidator = CacheInvalidationBuilder::default();
+                invalidator.process_update(id, &current, &updated);
+                self.invalidate_caches(invalidator)
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+            failure => {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("Failed to update MTA-STS policy")
+                    .reason(failure));
+            }
+        }
+
+        let domain = self
+            .domain_by_id(domain.id)
+            .await?
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        mta_sts_response(self, &domain)
+    }
+}
+
+async fn mta_sts_domain(
+    server: &Server,
+    domain: &str,
+    access_token: &AccessToken,
+) -> trc::Result<Arc<DomainCache>> {
+    // Tenants can only manage the policies of their own domains
+    server
+        .domain(&domain.to_lowercase())
+        .await?
+        .filter(|domain| {
+            access_token
+                .tenant_id()
+                .is_none_or(|tenant_id| domain.id_tenant == Some(tenant_id))
+        })
+        .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())
+}
+
+fn mta_sts_response(server: &Server, domain: &DomainCache) -> trc::Result<HttpResponse> {
+    let policy = server.mta_sts_policy(Some(domain)).ok_or_else(|| {
+        trc::ResourceEvent::NotFound
+            .into_err()
+            .details("No MX hosts configured for MTA-STS")
+    })?;
+    let warnings = server
+        .mta_sts_unmatched_mx(&policy)
+        .into_iter()
+        .map(|mx| format!("MX pattern \"{mx}\" does not match any configured hostname"))
+        .collect();
+
+    Ok(JsonResponse::new(MtaStsPolicy {
+        domain: domain.names[0].to_string(),
+        txt_record: policy.txt_record(),
+        mx_hosts: policy.mx.iter().map(|mx| mx.to_string()).collect(),
+        id: policy.id,
+        mode: policy.mode,
+        max_age: policy.max_age,
+        warnings,
+    })
+    .no_cache()
+    .into_http_response())
+}
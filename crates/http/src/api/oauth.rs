@@ -0,0 +1,208 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::auth::oauth::{OAuthCode, OAuthStatus, auth::OAuthApiHandler};
+use common::{
+    KV_OAUTH, Server,
+    auth::{
+        AccessToken, EmailCache,
+        oauth::{USER_CODE_LEN, client_id::decode_client_id},
+    },
+};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::enums::Permission;
+use serde::{Deserialize, Serialize};
+use store::{
+    dispatch::lookup::KeyValue,
+    write::{AlignedBytes, Archive},
+};
+use trc::AddContext;
+use types::id::Id;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorization {
+    pub user_code: String,
+    pub client_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorizationDecision {
+    pub action: DeviceAuthorizationAction,
+    #[serde(default)]
+    pub account_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceAuthorizationAction {
+    Approve,
+    Deny,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorizationResult {
+    pub user_code: String,
+    pub action: DeviceAuthorizationAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<Id>,
+}
+
+pub trait OAuthDeviceApi: Sync + Send {
+    fn handle_device_authorization_list(
+        &self,
+        user_code: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_device_authorization_decision(
+        &self,
+        user_code: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl OAuthDeviceApi for Server {
+    async fn handle_device_authorization_list(
+        &self,
+        user_code: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::OAuthDeviceAuthorize)?;
+
+        let user_code = normalize_user_code(user_code);
+        let mut authorizations = Vec::new();
+        if let Some(archive) = pending_device_auth(self, &user_code).await? {
+            let oauth = archive
+                .unarchive::<OAuthCode>()
+                .caused_by(trc::location!())?;
+            if oauth.status == OAuthStatus::Pending {
+                authorizations.push(DeviceAuthorization {
+                    client_name: decode_client_id(
+                        self.core.oauth.oauth_key.as_bytes(),
+                        oauth.client_id.as_str(),
+                    )
+                    .and_then(|meta| meta.client_name),
+                    client_id: oauth.client_id.to_string(),
+                    scope: oauth.scope.as_ref().map(|scope| scope.to_string()),
+                    user_code,
+                });
+            }
+        }
+
+        Ok(JsonResponse::new(authorizations)
+            .no_cache()
+            .into_http_response())
+    }
+
+    async fn handle_device_authorization_decision(
+        &self,
+        user_code: &str,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::OAuthDeviceAuthorize)?;
+
+        let request = serde_json::from_slice::<DeviceAuthorizationDecision>(
+            body.as_deref().unwrap_or_default(),
+        )
+        .map_err(|err| {
+            trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+        })?;
+
+        // The portal authenticates the user itself, approvals name the account to authorize
+        let account_id = match (request.action, request.account_name.as_deref()) {
+            (DeviceAuthorizationAction::Approve, Some(account_name)) => {
+                let account_id = match self
+                    .rcpt_id_from_email(&account_name.trim().to_lowercase())
+                    .await?
+                {
+                    Some(EmailCache::Account(account_id)) => account_id,
+                    _ => return Err(trc::ResourceEvent::NotFound.into_err()),
+                };
+
+                // Tenants can only authorize their own accounts
+                if let Some(tenant_id) = access_token.tenant_id()
+                    && self.account(account_id).await?.id_tenant != Some(tenant_id)
+                {
+                    return Err(trc::ResourceEvent::NotFound.into_err());
+                }
+
+                Some(account_id)
+            }
+            (DeviceAuthorizationAction::Approve, None) => {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("An account name is required to approve a device"));
+            }
+            (DeviceAuthorizationAction::Deny, _) => None,
+        };
+
+        let user_code = normalize_user_code(user_code);
+        let archive = pending_device_auth(self, &user_code)
+            .await?
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        let oauth = archive
+            .unarchive::<OAuthCode>()
+            .caused_by(trc::location!())?;
+        if oauth.status != OAuthStatus::Pending {
+            return Err(trc::ResourceEvent::NotFound.into_err());
+        }
+        self.complete_device_auth(
+            &user_code,
+            oauth,
+            if account_id.is_some() {
+                OAuthStatus::Authorized
+            } else {
+                OAuthStatus::Denied
+            },
+            account_id.unwrap_or(u32::MAX),
+        )
+        .await?;
+
+        Ok(JsonResponse::new(DeviceAuthorizationResult {
+            user_code,
+            action: request.action,
+            account_id: account_id.map(Id::from),
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
+
+async fn pending_device_auth(
+    server: &Server,
+    user_code: &str,
+) -> trc::Result<Option<Archive<AlignedBytes>>> {
+    // Device codes are longer than user codes, which keeps them out of reach
+    if user_code.len() != USER_CODE_LEN + 1 {
+        return Ok(None);
+    }
+
+    server
+        .in_memory_store()
+        .key_get::<Archive<AlignedBytes>>(KeyValue::<()>::build_key(KV_OAUTH, user_code.as_bytes()))
+        .await
+}
+
+fn normalize_user_code(user_code: &str) -> String {
+    let mut code = user_code
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect::<String>();
+    if code.len() == USER_CODE_LEN {
+        code.insert(USER_CODE_LEN / 2, '-');
+    }
+    code
+}
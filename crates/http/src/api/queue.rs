@@ -0,0 +1,238 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    Server,
+    auth::AccessToken,
+    config::smtp::queue::{IpSelection, RoutingStrategy},
+};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use jmap::registry::mapping::queued_message::tenant_domains;
+use mail_parser::{Address, MessageParser};
+use registry::schema::enums::Permission;
+use serde::Serialize;
+use smtp::queue::{Message, spool::SmtpSpool};
+use std::str::FromStr;
+use trc::AddContext;
+use types::{blob_hash::BlobHash, id::Id};
+use utils::DomainPart;
+
+// Headers are expected to fit in this many bytes, anything beyond is
+// only read for the body excerpt
+const PREVIEW_HEADERS_SIZE: usize = 16 * 1024;
+
+// HTML and transfer encodings take more space than the plain text they
+// decode to, so more than the excerpt size is read from the body
+const PREVIEW_BODY_RATIO: usize = 4;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedMessagePreview {
+    pub id: Id,
+    pub size: u64,
+    pub subject: Option<String>,
+    pub from: Vec<PreviewAddress>,
+    pub to: Vec<PreviewAddress>,
+    pub date: Option<String>,
+    pub message_id: Option<String>,
+    pub spam_score: Option<f64>,
+    pub excerpt: String,
+    pub is_truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewAddress {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayRouteStatus {
+    pub name: String,
+    pub host_selection: &'static str,
+    pub hosts: Vec<RelayHostStatus>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelayHostStatus {
+    pub address: String,
+    pub port: u16,
+    pub weight: u64,
+    pub max_connections: Option<u64>,
+    pub active_connections: u64,
+    pub status: &'static str,
+    pub consecutive_failures: u64,
+    pub down_until: Option<u64>,
+}
+
+pub trait QueueApi: Sync + Send {
+    fn handle_queue_preview_request(
+        &self,
+        id: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_queue_routes_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl QueueApi for Server {
+    async fn handle_queue_preview_request(
+        &self,
+        id: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        // Previews do not require access to the full message
+        access_token.enforce_permission(Permission::QueuedMessagePreview)?;
+
+        let id = Id::from_str(id).map_err(|_| trc::ResourceEvent::NotFound.into_err())?;
+        let message_archive = self
+            .read_message_archive(id.id())
+            .await?
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        let message = message_archive
+            .unarchive::<Message>()
+            .caused_by(trc::location!())?;
+
+        // Tenants can only preview messages sent from their domains
+        if let Some(tenant_id) = access_token.tenant_id() {
+            let domains = tenant_domains(self, tenant_id).await?;
+            if !message
+                .return_path
+                .try_domain_part()
+                .is_some_and(|domain| domains.contains(domain))
+            {
+                return Err(trc::ResourceEvent::NotFound.into_err());
+            }
+        }
+
+        // Fetch the headers and the first body chunk
+        let excerpt_size = self.core.network.http.queue_preview_size;
+        let size = message.size.to_native();
+        let read_size = PREVIEW_HEADERS_SIZE + excerpt_size * PREVIEW_BODY_RATIO;
+        let raw_message = self
+            .blob_store()
+            .get_blob_prefix(BlobHash::from(&message.blob_hash).as_slice(), read_size)
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(|| {
+                trc::StoreEvent::NotFound
+                    .into_err()
+                    .details("Blob not found")
+                    .caused_by(trc::location!())
+            })?;
+        let is_partial = raw_message.len() >= read_size;
+        let parsed = MessageParser::new().parse(&raw_message).ok_or_else(|| {
+            trc::ResourceEvent::BadParameters.ctx(trc::Key::Reason, "Failed to parse message")
+        })?;
+
+        let mut excerpt = parsed.body_text(0).unwrap_or_default().into_owned();
+        let mut is_truncated = is_partial;
+        if excerpt.len() > excerpt_size {
+            let mut pos = excerpt_size;
+            while !excerpt.is_char_boundary(pos) {
+                pos -= 1;
+            }
+            excerpt.truncate(pos);
+            is_truncated = true;
+        }
+
+        Ok(JsonResponse::new(QueuedMessagePreview {
+            id,
+            size,
+            subject: parsed.subject().map(Into::into),
+            from: parsed.from().map(preview_addresses).unwrap_or_default(),
+            to: parsed.to().map(preview_addresses).unwrap_or_default(),
+            date: parsed.date().map(|date| date.to_rfc3339()),
+            message_id: parsed.message_id().map(Into::into),
+            spam_score: parsed.header_raw("X-Spam-Score").and_then(parse_spam_score),
+            excerpt,
+            is_truncated,
+        })
+        .no_cache()
+        .into_http_response())
+    }
+
+    async fn handle_queue_routes_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysMtaRouteGet)?;
+
+        let mut routes = self
+            .core
+            .smtp
+            .queue
+            .routing_strategy
+            .iter()
+            .filter_map(|(name, route)| {
+                if let RoutingStrategy::Relay(config) = route {
+                    Some(RelayRouteStatus {
+                        name: name.clone(),
+                        host_selection: match config.host_selection {
+                            IpSelection::Weighted => "weighted",
+                            IpSelection::RoundRobin(_) => "roundRobin",
+                        },
+                        hosts: config
+                            .hosts
+                            .iter()
+                            .map(|host| RelayHostStatus {
+                                address: host.hostname().into(),
+                                port: host.port,
+                                weight: host.weight,
+                                max_connections: host
+                                    .limiter
+                                    .as_ref()
+                                    .map(|limiter| limiter.max_concurrent()),
+                                active_connections: host
+                                    .limiter
+                                    .as_ref()
+                                    .map_or(0, |limiter| limiter.num_concurrent()),
+                                status: if host.health.is_available() {
+                                    "up"
+                                } else {
+                                    "down"
+                                },
+                                consecutive_failures: host.health.failures(),
+                                down_until: host
+                                    .health
+                                    .down_until()
+                                    .filter(|_| !host.health.is_available())
+                                    .map(|until| until.div_ceil(1000)),
+                            })
+                            .collect(),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        routes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(JsonResponse::new(routes).no_cache().into_http_response())
+    }
+}
+
+fn preview_addresses(address: &Address<'_>) -> Vec<PreviewAddress> {
+    address
+        .iter()
+        .map(|addr| PreviewAddress {
+            name: addr.name().map(Into::into),
+            email: addr.address().map(Into::into),
+        })
+        .collect()
+}
+
+fn parse_spam_score(value: &str) -> Option<f64> {
+    value
+        .split(',')
+        .find_map(|part| part.trim().strip_prefix("score="))
+        .and_then(|score| score.trim().parse().ok())
+}
@@ -0,0 +1,45 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::enums::Permission;
+use services::task_manager::maintenance::recalculate_quota;
+use std::str::FromStr;
+use types::id::Id;
+
+pub trait QuotaApi: Sync + Send {
+    fn handle_quota_reconcile_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl QuotaApi for Server {
+    async fn handle_quota_reconcile_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::TaskAccountMaintenance)?;
+
+        let account_id = Id::from_str(account_id)
+            .map_err(|_| trc::ResourceEvent::NotFound.into_err())?
+            .document_id();
+
+        // Tenants can only reconcile the counters of their own accounts
+        if let Some(tenant_id) = access_token.tenant_id()
+            && self.account(account_id).await?.id_tenant != Some(tenant_id)
+        {
+            return Err(trc::ResourceEvent::NotFound.into_err());
+        }
+
+        let result = recalculate_quota(self, account_id).await?;
+
+        Ok(JsonResponse::new(result).no_cache().into_http_response())
+    }
+}
@@ -0,0 +1,101 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::enums::Permission;
+use serde::Serialize;
+use spam_filter::modules::reputation::{ReputationSubject, SenderReputationStore};
+use std::net::IpAddr;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenderReputationResponse {
+    pub messages: f64,
+    pub spam: f64,
+    pub bounces: f64,
+    pub score: f64,
+}
+
+pub trait SenderReputationApi: Sync + Send {
+    fn handle_sender_reputation_get_request(
+        &self,
+        kind: &str,
+        value: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_sender_reputation_reset_request(
+        &self,
+        kind: &str,
+        value: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl SenderReputationApi for Server {
+    async fn handle_sender_reputation_get_request(
+        &self,
+        kind: &str,
+        value: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SenderReputationGet)?;
+
+        let value = value.trim().to_lowercase();
+        sender_reputation_response(self, reputation_subject(kind, &value)?).await
+    }
+
+    async fn handle_sender_reputation_reset_request(
+        &self,
+        kind: &str,
+        value: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SenderReputationReset)?;
+
+        let value = value.trim().to_lowercase();
+        let subject = reputation_subject(kind, &value)?;
+        self.sender_reputation_reset(subject).await?;
+        sender_reputation_response(self, subject).await
+    }
+}
+
+fn reputation_subject<'x>(kind: &str, value: &'x str) -> trc::Result<ReputationSubject<'x>> {
+    match kind {
+        "domain" if !value.is_empty() => Ok(ReputationSubject::Domain(value)),
+        "ip" => value
+            .parse::<IpAddr>()
+            .map(ReputationSubject::Ip)
+            .map_err(|_| {
+                trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("Invalid IP address")
+            }),
+        _ => Err(trc::ResourceEvent::NotFound.into_err()),
+    }
+}
+
+async fn sender_reputation_response(
+    server: &Server,
+    subject: ReputationSubject<'_>,
+) -> trc::Result<HttpResponse> {
+    let Some(config) = &server.core.spam.reputation else {
+        return Err(trc::ResourceEvent::NotFound
+            .into_err()
+            .details("Sender reputation is disabled"));
+    };
+    let stats = server.sender_reputation_stats(subject).await?;
+
+    Ok(JsonResponse::new(SenderReputationResponse {
+        messages: stats.messages,
+        spam: stats.spam,
+        bounces: stats.bounces,
+        score: stats.score(config.min_messages),
+    })
+    .no_cache()
+    .into_http_response())
+}
@@ -0,0 +1,152 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken, ipc::BroadcastEvent};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::{
+    schema::enums::{Permission, ServiceProtocol},
+    types::EnumImpl,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, net::IpAddr, str::FromStr};
+use types::id::Id;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSessions {
+    pub account_id: Id,
+    pub name: String,
+    pub total: usize,
+    pub protocols: BTreeMap<&'static str, usize>,
+    pub sessions: Vec<SessionDetails>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDetails {
+    pub node_id: u64,
+    pub session_id: u64,
+    pub protocol: &'static str,
+    pub remote_ip: IpAddr,
+    pub created_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TerminatedSessions {
+    pub terminated: usize,
+}
+
+pub trait SessionApi: Sync + Send {
+    fn handle_session_list_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_session_terminate_request(
+        &self,
+        account_id: &str,
+        protocol: Option<&str>,
+        revoke: bool,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl SessionApi for Server {
+    async fn handle_session_list_request(
+        &self,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SessionList)?;
+
+        let mut accounts = Vec::new();
+        for (account_id, sessions) in self.cluster_session_list().await? {
+            let account = self.account(account_id).await?;
+            if access_token
+                .tenant_id()
+                .is_some_and(|tenant_id| account.id_tenant != Some(tenant_id))
+            {
+                continue;
+            }
+
+            let mut protocols = BTreeMap::new();
+            for session in &sessions {
+                *protocols.entry(session.protocol.as_str()).or_default() += 1;
+            }
+
+            accounts.push(AccountSessions {
+                account_id: Id::from(account_id),
+                name: account.name.to_string(),
+                total: sessions.len(),
+                protocols,
+                sessions: sessions
+                    .into_iter()
+                    .map(|session| SessionDetails {
+                        node_id: session.node_id,
+                        session_id: session.session_id,
+                        protocol: session.protocol.as_str(),
+                        remote_ip: session.remote_ip,
+                        created_at: session.created_at,
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(JsonResponse::new(accounts).no_cache().into_http_response())
+    }
+
+    async fn handle_session_terminate_request(
+        &self,
+        account_id: &str,
+        protocol: Option<&str>,
+        revoke: bool,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SessionTerminate)?;
+
+        let account_id = Id::from_str(account_id)
+            .map_err(|_| trc::ResourceEvent::NotFound.into_err())?
+            .document_id();
+        let protocol = protocol
+            .map(|protocol| {
+                ServiceProtocol::parse(protocol).ok_or_else(|| {
+                    trc::ResourceEvent::BadParameters
+                        .into_err()
+                        .details("Invalid protocol")
+                })
+            })
+            .transpose()?;
+        if revoke && protocol.is_some() {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Revoking credentials terminates sessions of all protocols"));
+        }
+
+        // Tenants can only terminate sessions of their own accounts
+        if let Some(tenant_id) = access_token.tenant_id()
+            && self.account(account_id).await?.id_tenant != Some(tenant_id)
+        {
+            return Err(trc::ResourceEvent::NotFound.into_err());
+        }
+
+        let terminated = self.terminate_sessions(account_id, protocol);
+        if revoke {
+            // Also invalidates issued tokens and terminates sessions on other nodes
+            if !self.revoke_credentials(account_id).await? {
+                return Err(trc::ResourceEvent::NotFound.into_err());
+            }
+        } else {
+            self.cluster_broadcast(BroadcastEvent::SessionTerminate {
+                account_id,
+                protocol,
+            })
+            .await;
+        }
+
+        Ok(JsonResponse::new(TerminatedSessions { terminated })
+            .no_cache()
+            .into_http_response())
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use email::sieve::redirect::{RedirectScope, SieveRedirectLimit};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::enums::Permission;
+use serde::Serialize;
+use std::str::FromStr;
+use types::id::Id;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectCountersResponse {
+    pub account_id: Id,
+    pub untrusted: ScopeRedirectCounters,
+    pub trusted: ScopeRedirectCounters,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeRedirectCounters {
+    pub redirects: u64,
+    pub destinations: u64,
+    pub max_redirects: u64,
+    pub max_destinations: u64,
+}
+
+pub trait SieveRedirectApi: Sync + Send {
+    fn handle_sieve_redirect_get_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_sieve_redirect_reset_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl SieveRedirectApi for Server {
+    async fn handle_sieve_redirect_get_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SieveRedirectGet)?;
+
+        let account_id = redirect_account_id(self, account_id, access_token).await?;
+        redirect_counters_response(self, account_id).await
+    }
+
+    async fn handle_sieve_redirect_reset_request(
+        &self,
+        account_id: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SieveRedirectReset)?;
+
+        let account_id = redirect_account_id(self, account_id, access_token).await?;
+        self.sieve_redirect_reset(account_id).await?;
+        redirect_counters_response(self, account_id).await
+    }
+}
+
+async fn redirect_account_id(
+    server: &Server,
+    account_id: &str,
+    access_token: &AccessToken,
+) -> trc::Result<u32> {
+    let account_id = Id::from_str(account_id)
+        .map_err(|_| trc::ResourceEvent::NotFound.into_err())?
+        .document_id();
+
+    // Tenants can only manage the counters of their own accounts
+    if let Some(tenant_id) = access_token.tenant_id()
+        && server.account(account_id).await?.id_tenant != Some(tenant_id)
+    {
+        return Err(trc::ResourceEvent::NotFound.into_err());
+    }
+
+    Ok(account_id)
+}
+
+async fn redirect_counters_response(server: &Server, account_id: u32) -> trc::Result<HttpResponse> {
+    Ok(JsonResponse::new(RedirectCountersResponse {
+        account_id: Id::from(account_id),
+        untrusted: scope_counters(server, account_id, RedirectScope::Untrusted).await?,
+        trusted: scope_counters(server, account_id, RedirectScope::Trusted).await?,
+    })
+    .no_cache()
+    .into_http_response())
+}
+
+async fn scope_counters(
+    server: &Server,
+    account_id: u32,
+    scope: RedirectScope,
+) -> trc::Result<ScopeRedirectCounters> {
+    let counters = server.sieve_redirect_counters(account_id, scope).await?;
+    let limit = scope.limit(server);
+
+    Ok(ScopeRedirectCounters {
+        redirects: counters.redirects,
+        destinations: counters.destinations,
+        max_redirects: limit.max_redirects,
+        max_destinations: limit.max_destinations,
+    })
+}
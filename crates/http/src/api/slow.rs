@@ -0,0 +1,41 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::enums::Permission;
+
+pub trait SlowRequestApi: Sync + Send {
+    fn handle_slow_request_list(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+// Slow requests are kept in memory by the node that handled them
+impl SlowRequestApi for Server {
+    async fn handle_slow_request_list(
+        &self,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::LiveTracing)?;
+
+        let mut requests = self.inner.data.slow_requests.list();
+
+        // Tenants can only see the requests of their own accounts
+        if let Some(tenant_id) = access_token.tenant_id() {
+            let mut tenant_requests = Vec::with_capacity(requests.len());
+            for request in requests {
+                if self.account(request.account_id).await?.id_tenant == Some(tenant_id) {
+                    tenant_requests.push(request);
+                }
+            }
+            requests = tenant_requests;
+        }
+
+        Ok(JsonResponse::new(requests).no_cache().into_http_response())
+    }
+}
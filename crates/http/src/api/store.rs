@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::{
+    enums::{Permission, TaskStoreMaintenanceType},
+    structs::{Task, TaskStatus, TaskStoreMaintenance},
+};
+use serde::{Deserialize, Serialize};
+use store::write::{BatchBuilder, now};
+use trc::AddContext;
+use types::id::Id;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BackupRequest {
+    full: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupResponse {
+    task_id: Id,
+}
+
+pub trait StoreBackupApi: Sync + Send {
+    fn handle_store_backup_request(
+        &self,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl StoreBackupApi for Server {
+    async fn handle_store_backup_request(
+        &self,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::StoreBackup)?;
+
+        let request = match body.as_deref() {
+            Some(body) if !body.is_empty() => serde_json::from_slice::<BackupRequest>(body)
+                .map_err(|err| {
+                    trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+                })?,
+            _ => BackupRequest::default(),
+        };
+        if self.core.backup.root.is_none() {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("No backup directory is configured"));
+        }
+
+        // Backups are written by the task manager to the configured directory
+        let task_id = self.registry().assign_id();
+        let mut batch = BatchBuilder::new();
+        batch.schedule_task_with_id(
+            task_id,
+            Task::StoreMaintenance(TaskStoreMaintenance {
+                maintenance_type: if request.full {
+                    TaskStoreMaintenanceType::FullBackup
+                } else {
+                    TaskStoreMaintenanceType::IncrementalBackup
+                },
+                shard_index: None,
+                status: TaskStatus::at(now() as i64),
+            }),
+        );
+        self.store()
+            .write(batch.build_all())
+            .await
+            .caused_by(trc::location!())?;
+        self.notify_task_queue();
+
+        Ok(JsonResponse::new(BackupResponse {
+            task_id: task_id.into(),
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
@@ -49,6 +49,7 @@ impl TelemetryApi for Server {
         if is_tracing {
             // Validate the access token
             access_token.enforce_permission(Permission::LiveTracing)?;
+            self.assert_step_up(access_token, Permission::LiveTracing)?;
 
             let mut key_filters = AHashMap::new();
             let mut filter = None;
@@ -176,6 +177,7 @@ impl TelemetryApi for Server {
         } else {
             // Validate the access token
             access_token.enforce_permission(Permission::LiveMetrics)?;
+            self.assert_step_up(access_token, Permission::LiveMetrics)?;
 
             let interval = Duration::from_secs(
                 params
@@ -269,6 +271,40 @@ impl TelemetryApi for Server {
                                     );
                                 }
                             }
+                            for counter in Collector::collect_labeled_counters() {
+                                if metric_types.is_empty() || metric_types.contains(&counter.id()) {
+                                    if !is_first {
+                                        metrics.push(',');
+                                    } else {
+                                        is_first = false;
+                                    }
+                                    let _ = write!(
+                                        &mut metrics,
+                                        "{{\"metric\":\"{}\",\"@type\":\"Counter\",\"labels\":{},\"count\":{}}}",
+                                        counter.id().as_str(),
+                                        labels_json(counter.labels()),
+                                        counter.value()
+                                    );
+                                }
+                            }
+                            for labeled in Collector::collect_labeled_histograms() {
+                                let histogram = labeled.histogram();
+                                if metric_types.is_empty() || metric_types.contains(&histogram.id()) {
+                                    if !is_first {
+                                        metrics.push(',');
+                                    } else {
+                                        is_first = false;
+                                    }
+                                    let _ = write!(
+                                        &mut metrics,
+                                        "{{\"metric\":\"{}\",\"@type\":\"Histogram\",\"labels\":{},\"count\":{},\"sum\":{}}}",
+                                        histogram.id().as_str(),
+                                        labels_json(labeled.labels()),
+                                        histogram.count(),
+                                        histogram.sum()
+                                    );
+                                }
+                            }
                             metrics.push_str("]\n\n");
 
                             yield Ok(Frame::data(Bytes::from(metrics)));
@@ -279,3 +315,15 @@ impl TelemetryApi for Server {
         }
     }
 }
+
+fn labels_json(labels: &[(&str, &str)]) -> String {
+    let mut json = String::from("{");
+    for (idx, (name, value)) in labels.iter().enumerate() {
+        if idx > 0 {
+            json.push(',');
+        }
+        let _ = write!(&mut json, "\"{name}\":\"{value}\"");
+    }
+    json.push('}');
+    json
+}
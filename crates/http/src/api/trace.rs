@@ -0,0 +1,168 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: LicenseRef-SEL
+ *
+ * This file is subject to the Stalwart Enterprise License Agreement (SEL) and
+ * is NOT open source software.
+ *
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::schema::{
+    enums::Permission,
+    structs::{Trace, TraceEvent, TraceKeyValue, TraceValue, TraceValueUnsignedInt},
+};
+use serde::Serialize;
+use std::{future::Future, str::FromStr};
+use store::{
+    ValueKey,
+    search::{SearchFilter, SearchQuery, TracingSearchField},
+    write::{SearchIndex, TelemetryClass, ValueClass},
+};
+use trc::{AddContext, Key};
+use types::id::Id;
+use utils::url_params::UrlParams;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageTrace {
+    pub queue_ids: Vec<Id>,
+    pub span_ids: Vec<Id>,
+    pub total: usize,
+    pub position: usize,
+    pub events: Vec<MessageTraceEvent>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageTraceEvent {
+    pub span_id: Id,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+pub trait TraceApi: Sync + Send {
+    fn handle_message_trace_request(
+        &self,
+        id: &str,
+        params: &UrlParams<'_>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl TraceApi for Server {
+    async fn handle_message_trace_request(
+        &self,
+        id: &str,
+        params: &UrlParams<'_>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysTraceGet)?;
+
+        let id = Id::from_str(id)
+            .map_err(|_| trc::ResourceEvent::NotFound.into_err())?
+            .id();
+        let position = params.parse::<usize>("position").unwrap_or(0);
+        let limit = params
+            .parse::<usize>("limit")
+            .filter(|limit| *limit > 0)
+            .unwrap_or(DEFAULT_LIMIT)
+            .min(MAX_LIMIT);
+
+        // The id is either a span id or a queue id
+        let mut queue_ids = Vec::new();
+        let mut span_ids = Vec::new();
+        if let Some(trace) = span_trace(self, id).await? {
+            span_ids.push(id);
+            for event in trace.events.iter() {
+                for TraceKeyValue { key, value } in event.key_values.iter() {
+                    if let (Key::QueueId, TraceValue::UnsignedInt(TraceValueUnsignedInt { value })) =
+                        (key, value)
+                        && !queue_ids.contains(value)
+                    {
+                        queue_ids.push(*value);
+                    }
+                }
+            }
+        } else {
+            queue_ids.push(id);
+        }
+
+        // Find all spans related to the message
+        for queue_id in &queue_ids {
+            span_ids.extend(
+                self.search_store()
+                    .query_global(
+                        SearchQuery::new(SearchIndex::Tracing)
+                            .with_filter(SearchFilter::eq(TracingSearchField::QueueId, *queue_id)),
+                    )
+                    .await
+                    .caused_by(trc::location!())?,
+            );
+        }
+        span_ids.sort_unstable();
+        span_ids.dedup();
+
+        // Merge the events of all spans, sessions may include events
+        // belonging to other messages received over the same connection
+        let mut events = Vec::new();
+        let mut found_span_ids = Vec::with_capacity(span_ids.len());
+        for span_id in span_ids {
+            let Some(trace) = span_trace(self, span_id).await? else {
+                continue;
+            };
+            found_span_ids.push(Id::from(span_id));
+            events.extend(
+                trace
+                    .events
+                    .into_iter()
+                    .filter(|event| {
+                        event
+                            .key_values
+                            .iter()
+                            .all(|kv| match (&kv.key, &kv.value) {
+                                (
+                                    Key::QueueId,
+                                    TraceValue::UnsignedInt(TraceValueUnsignedInt { value }),
+                                ) => queue_ids.contains(value),
+                                _ => true,
+                            })
+                    })
+                    .map(|event| MessageTraceEvent {
+                        span_id: Id::from(span_id),
+                        event,
+                    }),
+            );
+        }
+        if found_span_ids.is_empty() {
+            return Err(trc::ResourceEvent::NotFound.into_err());
+        }
+        events.sort_by_key(|event| event.event.timestamp.timestamp());
+
+        let total = events.len();
+        Ok(JsonResponse::new(MessageTrace {
+            queue_ids: queue_ids.into_iter().map(Id::from).collect(),
+            span_ids: found_span_ids,
+            total,
+            position,
+            events: events.into_iter().skip(position).take(limit).collect(),
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
+
+async fn span_trace(server: &Server, span_id: u64) -> trc::Result<Option<Trace>> {
+    server
+        .tracing_store()
+        .get_value::<Trace>(ValueKey::from(ValueClass::Telemetry(TelemetryClass::Span(
+            span_id,
+        ))))
+        .await
+        .caused_by(trc::location!())
+}
@@ -0,0 +1,183 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken};
+use http_proto::{HttpResponse, JsonResponse, ToHttpResponse};
+use registry::{schema::enums::Permission, types::datetime::UTCDateTime};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use store::write::now;
+use trc::{
+    Key, Level,
+    ipc::overrides::{MAX_OVERRIDE_TTL, TraceOverride},
+};
+use types::id::Id;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracingOverrideRequest {
+    pub filter: String,
+    pub level: String,
+    #[serde(default)]
+    pub account_id: Option<Id>,
+    #[serde(default)]
+    pub queue_id: Option<Id>,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+    pub ttl: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracingOverride {
+    pub id: u64,
+    pub filter: String,
+    pub level: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<Id>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_id: Option<Id>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    pub expires: UTCDateTime,
+    pub events: usize,
+}
+
+pub trait TracingOverrideApi: Sync + Send {
+    fn handle_tracing_override_list(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_tracing_override_create(
+        &self,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+
+    fn handle_tracing_override_delete(
+        &self,
+        id: &str,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl TracingOverrideApi for Server {
+    async fn handle_tracing_override_list(
+        &self,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysEventTracingLevelGet)?;
+
+        Ok(JsonResponse::new(
+            self.trace_overrides()
+                .await?
+                .iter()
+                .map(TracingOverride::from)
+                .collect::<Vec<_>>(),
+        )
+        .no_cache()
+        .into_http_response())
+    }
+
+    async fn handle_tracing_override_create(
+        &self,
+        body: Option<Vec<u8>>,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysEventTracingLevelCreate)?;
+
+        let request: TracingOverrideRequest =
+            serde_json::from_slice(body.as_deref().unwrap_or_default()).map_err(|err| {
+                trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+            })?;
+        let level = Level::from_str(&request.level).map_err(|_| {
+            trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Invalid level")
+                .ctx(trc::Key::Value, request.level.clone())
+        })?;
+
+        if request.ttl == 0 || request.ttl > MAX_OVERRIDE_TTL {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details(format!(
+                    "The TTL must be between 1 and {MAX_OVERRIDE_TTL} seconds"
+                )));
+        }
+
+        // Events can also be matched by the value of one of their keys
+        let key = match (request.key, request.value) {
+            (Some(key), Some(value)) if !value.is_empty() && !value.contains(['\r', '\n']) => {
+                let key = Key::from_str(&key).map_err(|_| {
+                    trc::ResourceEvent::BadParameters
+                        .into_err()
+                        .details("Invalid key")
+                        .ctx(trc::Key::Key, key.clone())
+                })?;
+                Some((key, value.into()))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(trc::ResourceEvent::BadParameters
+                    .into_err()
+                    .details("A key match requires both a key and a single line value"));
+            }
+        };
+
+        let rule = self
+            .add_trace_override(
+                TraceOverride::new(0, request.filter, level, now().saturating_add(request.ttl))?
+                    .with_account_id(request.account_id.map(|id| id.document_id()))
+                    .with_queue_id(request.queue_id.map(|id| id.id()))
+                    .with_key(key),
+            )
+            .await?;
+
+        Ok(JsonResponse::new(TracingOverride::from(&rule))
+            .no_cache()
+            .into_http_response())
+    }
+
+    async fn handle_tracing_override_delete(
+        &self,
+        id: &str,
+        access_token: &AccessToken,
+    ) -> trc::Result<HttpResponse> {
+        access_token.enforce_permission(Permission::SysEventTracingLevelDestroy)?;
+
+        let id = id
+            .parse::<u64>()
+            .map_err(|_| trc::ResourceEvent::NotFound.into_err())?;
+        if self.remove_trace_override(id).await? {
+            Ok(JsonResponse::new(serde_json::json!({ "id": id }))
+                .no_cache()
+                .into_http_response())
+        } else {
+            Err(trc::ResourceEvent::NotFound.into_err())
+        }
+    }
+}
+
+impl From<&TraceOverride> for TracingOverride {
+    fn from(rule: &TraceOverride) -> Self {
+        TracingOverride {
+            id: rule.id,
+            filter: rule.filter.clone(),
+            level: rule.level.as_str(),
+            account_id: rule.account_id.map(Id::from),
+            queue_id: rule.queue_id.map(Id::from),
+            key: rule.key.as_ref().map(|(key, _)| key.as_str()),
+            value: rule.key.as_ref().map(|(_, value)| value.to_string()),
+            expires: UTCDateTime::from_timestamp(rule.expires as i64),
+            events: rule.num_events(),
+        }
+    }
+}
@@ -10,6 +10,7 @@ use directory::Credentials;
 use http_proto::{HttpRequest, HttpSessionData};
 use hyper::header;
 use mail_parser::decoders::base64::base64_decode;
+use registry::schema::enums::ServiceProtocol;
 use std::future::Future;
 use std::time::{Duration, Instant};
 
@@ -19,6 +20,13 @@ pub trait Authenticator: Sync + Send {
         req: &HttpRequest,
         session: &HttpSessionData,
     ) -> impl Future<Output = trc::Result<(Option<InFlight>, AccessToken)>> + Send;
+
+    fn authenticate_protocol(
+        &self,
+        req: &HttpRequest,
+        session: &HttpSessionData,
+        protocols: &[ServiceProtocol],
+    ) -> impl Future<Output = trc::Result<(Option<InFlight>, AccessToken)>> + Send;
 }
 
 impl Authenticator for Server {
@@ -36,7 +44,9 @@ impl Authenticator for Server {
                         self.access_token(http_cache.account_id).await?,
                         http_cache.credential_id,
                         session.remote_ip,
-                    )?;
+                    )?
+                    .with_impersonator(http_cache.impersonator_id)
+                    .with_step_up(http_cache.step_up_at);
 
                     if access_token.revision() == http_cache.revision {
                         // Enforce authenticated rate limit
@@ -87,6 +97,7 @@ impl Authenticator for Server {
                     credentials,
                     session.session_id,
                     session.remote_ip,
+                    ServiceProtocol::Jmap,
                 ))
                 .await?;
 
@@ -97,6 +108,8 @@ impl Authenticator for Server {
                     account_id: access_token.account_id(),
                     revision: access_token.revision(),
                     credential_id: access_token.credential_id(),
+                    impersonator_id: access_token.impersonator_id(),
+                    step_up_at: access_token.step_up_at(),
                     expires: Instant::now()
                         + Duration::from_secs(self.core.oauth.oauth_expiry_token),
                 },
@@ -117,6 +130,28 @@ impl Authenticator for Server {
                 .caused_by(trc::location!()))
         }
     }
+
+    // Authenticates a request to a protocol endpoint, which is allowed if the
+    // account has any of the protocols serving it enabled
+    async fn authenticate_protocol(
+        &self,
+        req: &HttpRequest,
+        session: &HttpSessionData,
+        protocols: &[ServiceProtocol],
+    ) -> trc::Result<(Option<InFlight>, AccessToken)> {
+        let (in_flight, access_token) = self.authenticate_headers(req, session).await?;
+
+        match protocols
+            .iter()
+            .find(|protocol| access_token.is_protocol_enabled(**protocol))
+            .or(protocols.first())
+        {
+            Some(protocol) => access_token
+                .assert_protocol_enabled(*protocol)
+                .map(|access_token| (in_flight, access_token)),
+            None => Ok((in_flight, access_token)),
+        }
+    }
 }
 
 pub trait HttpHeaders {
@@ -7,3 +7,4 @@
 pub mod authenticate;
 pub mod oauth;
 pub mod permissions;
+pub mod step_up;
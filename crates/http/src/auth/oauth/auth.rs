@@ -4,7 +4,9 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{DeviceAuthResponse, FormData, MAX_POST_LEN, OAuthCode, PkceCodeChallenge};
+use super::{
+    ArchivedOAuthCode, DeviceAuthResponse, FormData, MAX_POST_LEN, OAuthCode, PkceCodeChallenge,
+};
 use crate::auth::oauth::{
     OAuthStatus, openid::OpenIdHandler, registration::ClientRegistrationHandler,
 };
@@ -22,6 +24,7 @@ use common::{
 };
 use directory::Credentials;
 use http_proto::*;
+use registry::schema::enums::ServiceProtocol;
 use std::future::Future;
 use store::{
     Serialize,
@@ -55,6 +58,7 @@ pub struct OAuthMetadata {
     pub device_authorization_endpoint: String,
     pub registration_endpoint: String,
     pub introspection_endpoint: String,
+    pub introspection_endpoint_auth_methods_supported: &'static [&'static str],
     pub grant_types_supported: &'static [&'static str],
     pub response_types_supported: &'static [&'static str],
     pub scopes_supported: &'static [&'static str],
@@ -82,6 +86,14 @@ pub trait OAuthApiHandler: Sync + Send {
         session: &HttpSessionData,
     ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
 
+    fn complete_device_auth(
+        &self,
+        user_code: &str,
+        oauth: &ArchivedOAuthCode,
+        status: OAuthStatus,
+        account_id: u32,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
     fn handle_oauth_metadata(&self) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
 
     fn handle_oauth_protected_resource(
@@ -285,6 +297,7 @@ impl OAuthApiHandler for Server {
                         },
                         session_id: session.session_id,
                         remote_ip: session.remote_ip,
+                        protocol: ServiceProtocol::Jmap,
                     })
                     .await
                 {
@@ -389,47 +402,18 @@ impl OAuthApiHandler for Server {
                                 },
                                 session_id: session.session_id,
                                 remote_ip: session.remote_ip,
+                                protocol: ServiceProtocol::Jmap,
                             })
                             .await
                         {
                             Ok(access_token) => {
-                                let new_oauth_code = OAuthCode {
-                                    status: OAuthStatus::Authorized,
-                                    account_id: access_token.account_id(),
-                                    client_id: oauth.client_id.to_string(),
-                                    nonce: oauth.nonce.as_ref().map(|s| s.to_string()),
-                                    params: Default::default(),
-                                    code_challenge: PkceCodeChallenge::None,
-                                    scope: oauth.scope.as_ref().map(|s| s.to_string()),
-                                    resources: oauth
-                                        .resources
-                                        .iter()
-                                        .map(|s| s.to_string())
-                                        .collect(),
-                                };
-
-                                // Delete issued user code
-                                self.in_memory_store()
-                                    .key_delete(KeyValue::<()>::build_key(
-                                        KV_OAUTH,
-                                        code.as_bytes(),
-                                    ))
-                                    .await?;
-
-                                // Update device code status
-                                self.in_memory_store()
-                                    .key_set(
-                                        KeyValue::with_prefix(
-                                            KV_OAUTH,
-                                            oauth.params.as_bytes(),
-                                            Archiver::new(new_oauth_code)
-                                                .untrusted()
-                                                .serialize()
-                                                .caused_by(trc::location!())?,
-                                        )
-                                        .expires(self.core.oauth.oauth_expiry_auth_code),
-                                    )
-                                    .await?;
+                                self.complete_device_auth(
+                                    &code,
+                                    oauth,
+                                    OAuthStatus::Authorized,
+                                    access_token.account_id(),
+                                )
+                                .await?;
 
                                 result = LoginResponse::Verified;
                             }
@@ -547,6 +531,45 @@ impl OAuthApiHandler for Server {
         .into_http_response())
     }
 
+    async fn complete_device_auth(
+        &self,
+        user_code: &str,
+        oauth: &ArchivedOAuthCode,
+        status: OAuthStatus,
+        account_id: u32,
+    ) -> trc::Result<()> {
+        let new_oauth_code = OAuthCode {
+            status,
+            account_id,
+            client_id: oauth.client_id.to_string(),
+            nonce: oauth.nonce.as_ref().map(|s| s.to_string()),
+            params: Default::default(),
+            code_challenge: PkceCodeChallenge::None,
+            scope: oauth.scope.as_ref().map(|s| s.to_string()),
+            resources: oauth.resources.iter().map(|s| s.to_string()).collect(),
+        };
+
+        // Delete issued user code
+        self.in_memory_store()
+            .key_delete(KeyValue::<()>::build_key(KV_OAUTH, user_code.as_bytes()))
+            .await?;
+
+        // Update device code status
+        self.in_memory_store()
+            .key_set(
+                KeyValue::with_prefix(
+                    KV_OAUTH,
+                    oauth.params.as_bytes(),
+                    Archiver::new(new_oauth_code)
+                        .untrusted()
+                        .serialize()
+                        .caused_by(trc::location!())?,
+                )
+                .expires(self.core.oauth.oauth_expiry_auth_code),
+            )
+            .await
+    }
+
     async fn handle_oauth_metadata(&self) -> trc::Result<HttpResponse> {
         let base_url = &self.core.network.http.url_https;
 
@@ -555,6 +578,10 @@ impl OAuthApiHandler for Server {
             token_endpoint: format!("{base_url}/auth/token"),
             device_authorization_endpoint: format!("{base_url}/auth/device"),
             introspection_endpoint: format!("{base_url}/auth/introspect"),
+            introspection_endpoint_auth_methods_supported: &[
+                "client_secret_post",
+                "client_secret_basic",
+            ],
             registration_endpoint: format!("{base_url}/auth/register"),
             grant_types_supported: &[
                 "authorization_code",
@@ -31,6 +31,7 @@ pub enum OAuthStatus {
     Authorized,
     TokenIssued,
     Pending,
+    Denied,
 }
 
 const MAX_POST_LEN: usize = 2048;
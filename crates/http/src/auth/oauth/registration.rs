@@ -56,6 +56,12 @@ pub trait ClientRegistrationHandler: Sync + Send {
         client_id: &str,
         client_secret: Option<&str>,
     ) -> impl Future<Output = trc::Result<Option<ErrorType>>> + Send;
+
+    fn verify_confidential_client(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> impl Future<Output = trc::Result<bool>> + Send;
 }
 impl ClientRegistrationHandler for Server {
     async fn handle_oauth_registration_request(
@@ -293,23 +299,7 @@ impl ClientRegistrationHandler for Server {
         if decode_client_id(self.core.oauth.oauth_key.as_bytes(), client_id).is_some() {
             return Ok(None);
         }
-        let Some(client_id) = self
-            .registry()
-            .primary_key(
-                ObjectType::OAuthClient.into(),
-                Property::ClientId,
-                client_id.as_bytes().to_vec(),
-            )
-            .await?
-        else {
-            return Ok(None);
-        };
-        let Some(client) = self
-            .registry()
-            .object::<OAuthClient>(client_id.id())
-            .await
-            .caused_by(trc::location!())?
-        else {
+        let Some(client) = registered_client(self, client_id).await? else {
             return Ok(None);
         };
 
@@ -327,6 +317,43 @@ impl ClientRegistrationHandler for Server {
             _ => Ok(None),
         }
     }
+
+    async fn verify_confidential_client(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> trc::Result<bool> {
+        // Only registered clients holding a secret can authenticate
+        match registered_client(self, client_id)
+            .await?
+            .and_then(|client| client.secret)
+        {
+            Some(hash) if !hash.is_empty() => verify_secret_hash(&hash, client_secret.as_bytes())
+                .await
+                .caused_by(trc::location!()),
+            _ => Ok(false),
+        }
+    }
+}
+
+async fn registered_client(server: &Server, client_id: &str) -> trc::Result<Option<OAuthClient>> {
+    let Some(client_id) = server
+        .registry()
+        .primary_key(
+            ObjectType::OAuthClient.into(),
+            Property::ClientId,
+            client_id.as_bytes().to_vec(),
+        )
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    server
+        .registry()
+        .object::<OAuthClient>(client_id.id())
+        .await
+        .caused_by(trc::location!())
 }
 
 fn registration_error(error: ClientRegistrationError) -> HttpResponse {
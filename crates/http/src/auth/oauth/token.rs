@@ -8,17 +8,14 @@ use super::{
     ArchivedOAuthStatus, ArchivedPkceCodeChallenge, ErrorType, FormData, MAX_POST_LEN, OAuthCode,
     OAuthResponse, OAuthStatus, TokenResponse, registration::ClientRegistrationHandler,
 };
-use crate::auth::authenticate::HttpHeaders;
+use crate::auth::authenticate::{Authenticator, HttpHeaders};
 use base64::{
     Engine,
     engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
 };
 use common::{
     KV_OAUTH, Server,
-    auth::{
-        AccessToken,
-        oauth::{GrantType, oidc::StandardClaims},
-    },
+    auth::oauth::{GrantType, oidc::StandardClaims},
 };
 use http_proto::*;
 use hyper::StatusCode;
@@ -29,6 +26,7 @@ use store::{
     write::{AlignedBytes, Archive},
 };
 use trc::AddContext;
+use utils::constant_time_eq;
 
 pub trait TokenHandler: Sync + Send {
     fn handle_token_request(
@@ -40,8 +38,7 @@ pub trait TokenHandler: Sync + Send {
     fn handle_token_introspect(
         &self,
         req: &mut HttpRequest,
-        access_token: &AccessToken,
-        session_id: u64,
+        session: &HttpSessionData,
     ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
 
     #[allow(clippy::too_many_arguments)]
@@ -216,6 +213,17 @@ impl TokenHandler for Server {
                             ArchivedOAuthStatus::TokenIssued => {
                                 TokenResponse::error(ErrorType::ExpiredToken)
                             }
+                            ArchivedOAuthStatus::Denied => {
+                                // Report the denial once
+                                self.in_memory_store()
+                                    .key_delete(KeyValue::<()>::build_key(
+                                        KV_OAUTH,
+                                        device_code.as_bytes(),
+                                    ))
+                                    .await?;
+
+                                TokenResponse::error(ErrorType::AccessDenied)
+                            }
                         }
                     };
                 }
@@ -243,7 +251,7 @@ impl TokenHandler for Server {
                             "",
                             issuer,
                             None,
-                            None,
+                            token_info.claims,
                             token_info.expires_in
                                 <= self.core.oauth.oauth_expiry_refresh_token_renew,
                             false,
@@ -284,20 +292,43 @@ impl TokenHandler for Server {
     async fn handle_token_introspect(
         &self,
         req: &mut HttpRequest,
-        access_token: &AccessToken,
-        session_id: u64,
+        session: &HttpSessionData,
     ) -> trc::Result<HttpResponse> {
-        // Parse token
-        let token = FormData::from_request(req, 1024, session_id)
-            .await?
-            .remove("token")
-            .ok_or_else(|| {
-                trc::ResourceEvent::BadParameters
-                    .into_err()
-                    .details("Client ID is missing.")
-            })?;
+        // Resource servers authenticate with their client credentials (RFC 7662),
+        // other callers with a bearer token
+        let params = FormData::from_request(req, MAX_POST_LEN, session.session_id).await?;
+        let _in_flight =
+            if let (Some(client_id), Some(client_secret)) = client_credentials(req, &params) {
+                // Limit unauthenticated requests
+                self.is_http_anonymous_request_allowed(session.remote_ip)
+                    .await?;
+
+                if self
+                    .verify_confidential_client(&client_id, &client_secret)
+                    .await?
+                {
+                    None
+                } else if params.has_field("client_secret") {
+                    return Ok(JsonResponse::with_status(
+                        StatusCode::UNAUTHORIZED,
+                        TokenResponse::error(ErrorType::InvalidClient),
+                    )
+                    .into_http_response());
+                } else {
+                    // Basic credentials that belong to an account rather than a client
+                    self.authenticate_headers(req, session).await?.0
+                }
+            } else {
+                self.authenticate_headers(req, session).await?.0
+            };
+
+        let token = params.get("token").ok_or_else(|| {
+            trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Token is missing.")
+        })?;
 
-        self.introspect_access_token(&token, access_token)
+        self.introspect_access_token(token)
             .await
             .map(|response| JsonResponse::new(response).no_cache().into_http_response())
     }
@@ -327,7 +358,7 @@ impl TokenHandler for Server {
                     account_id,
                     account_name,
                     self.core.oauth.oauth_expiry_token,
-                    None,
+                    scope.as_deref(),
                     credential_version.into(),
                 )
                 .await?,
@@ -339,7 +370,7 @@ impl TokenHandler for Server {
                     account_id,
                     account_name,
                     self.core.oauth.oauth_expiry_refresh_token,
-                    None,
+                    scope.as_deref(),
                     credential_version.into(),
                 )
                 .await?
@@ -409,16 +440,6 @@ fn verify_pkce(stored: &ArchivedPkceCodeChallenge, verifier: Option<&str>) -> bo
                 .bytes()
                 .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~'))
     };
-    let constant_time_eq = |a: &[u8], b: &[u8]| {
-        if a.len() != b.len() {
-            return false;
-        }
-        let mut diff: u8 = 0;
-        for (x, y) in a.iter().zip(b.iter()) {
-            diff |= x ^ y;
-        }
-        diff == 0
-    };
 
     match (stored, verifier) {
         (ArchivedPkceCodeChallenge::None, None) => true,
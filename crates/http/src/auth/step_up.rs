@@ -0,0 +1,156 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::auth::oauth::OAuthResponse;
+use common::{
+    Server,
+    auth::{AccessToken, AuthRequest},
+};
+use directory::{Credentials, core::secret::verify_totp};
+use http_proto::{HttpResponse, HttpSessionData, JsonResponse, ToHttpResponse};
+use registry::schema::{enums::ServiceProtocol, structs};
+use serde::Deserialize;
+use std::future::Future;
+use trc::AddContext;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepUpRequest {
+    pub mfa_token: String,
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+pub trait StepUpHandler: Sync + Send {
+    fn handle_step_up_request(
+        &self,
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+        body: Vec<u8>,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl StepUpHandler for Server {
+    async fn handle_step_up_request(
+        &self,
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+        body: Vec<u8>,
+    ) -> trc::Result<HttpResponse> {
+        let request = serde_json::from_slice::<StepUpRequest>(&body).map_err(|err| {
+            trc::EventType::Resource(trc::ResourceEvent::BadParameters).from_json_error(err)
+        })?;
+        let account_id = access_token.account_id();
+
+        // The issued token carries the full account permissions, so sessions
+        // authenticated with a scoped credential or impersonation are not allowed.
+        if access_token.credential_id().is_some() || access_token.impersonator_id().is_some() {
+            return Err(trc::SecurityEvent::Unauthorized
+                .into_err()
+                .account_id(account_id)
+                .details("Step-up is only available to sessions authenticated with a password"));
+        }
+
+        // Verify the second factor configured for the account: the TOTP enrolled in
+        // its password credential, or the factors enforced by its external directory
+        let account = self
+            .registry()
+            .object::<structs::Account>(account_id.into())
+            .await
+            .caused_by(trc::location!())?
+            .and_then(|account| account.into_user())
+            .ok_or_else(|| trc::ResourceEvent::NotFound.into_err())?;
+        let account_name = self
+            .account(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .name()
+            .to_string();
+        let mfa_token = request.mfa_token.trim();
+        let is_valid = match account.password_credential() {
+            Some(credential) => {
+                let Some(totp_uri) = credential.otp_auth.as_deref() else {
+                    return Err(trc::SecurityEvent::Unauthorized
+                        .into_err()
+                        .account_id(account_id)
+                        .details("No second factor is enrolled for this account"));
+                };
+                match verify_totp(totp_uri, mfa_token)? {
+                    Some(step) => self
+                        .accept_totp_step(account_id, step)
+                        .await
+                        .caused_by(trc::location!())?,
+                    None => false,
+                }
+            }
+            None => {
+                let Some(secret) = request.secret else {
+                    return Err(trc::SecurityEvent::Unauthorized
+                        .into_err()
+                        .account_id(account_id)
+                        .details("The account secret is required to step up"));
+                };
+                // Failed attempts are counted towards fail2ban by the authentication itself
+                self.authenticate(&AuthRequest {
+                    credentials: Credentials::Basic {
+                        username: account_name.clone(),
+                        secret,
+                        mfa_token: mfa_token.to_string().into(),
+                    },
+                    session_id: session.session_id,
+                    remote_ip: session.remote_ip,
+                    protocol: ServiceProtocol::Jmap,
+                })
+                .await?
+                .account_id()
+                    == account_id
+            }
+        };
+        if !is_valid {
+            return if self.has_auth_fail2ban()
+                && self
+                    .is_auth_fail2banned(session.remote_ip, Some(&account_name))
+                    .await?
+            {
+                Err(trc::SecurityEvent::AuthenticationBan
+                    .into_err()
+                    .ctx(trc::Key::RemoteIp, session.remote_ip)
+                    .ctx(trc::Key::AccountName, account_name))
+            } else {
+                Err(trc::SecurityEvent::Unauthorized
+                    .into_err()
+                    .account_id(account_id)
+                    .details("Invalid second factor"))
+            };
+        }
+
+        // Issue an access token carrying the verification time
+        let credential_version = self
+            .access_token(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .credential_version();
+        let expires_in = self.core.oauth.oauth_expiry_token;
+
+        Ok(JsonResponse::new(OAuthResponse {
+            access_token: self
+                .encode_step_up_access_token(
+                    account_id,
+                    &account_name,
+                    expires_in,
+                    credential_version,
+                )
+                .await?,
+            token_type: "bearer".to_string(),
+            expires_in,
+            refresh_token: None,
+            scope: None,
+            id_token: None,
+        })
+        .no_cache()
+        .into_http_response())
+    }
+}
@@ -178,6 +178,7 @@ impl FormHandler for Server {
                 .deliver_message(IngestMessage {
                     sender_address: from_email,
                     sender_authenticated: false,
+                    remote_ip: Some(session.remote_ip),
                     recipients: form
                         .rcpt_to
                         .iter()
@@ -189,6 +190,7 @@ impl FormHandler for Server {
                         .collect(),
                     message_blob,
                     message_size: message.len() as u64,
+                    spam_tags: vec![],
                     session_id: session.session_id,
                 })
                 .await
@@ -198,7 +200,7 @@ impl FormHandler for Server {
                     LocalDeliveryStatus::Success => {
                         has_success = true;
                     }
-                    LocalDeliveryStatus::TemporaryFailure { reason }
+                    LocalDeliveryStatus::TemporaryFailure { reason, .. }
                     | LocalDeliveryStatus::PermanentFailure { reason, .. } => {
                         failure = Some(reason)
                     }
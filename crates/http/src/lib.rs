@@ -10,6 +10,8 @@ pub mod api;
 pub mod auth;
 pub mod form;
 pub mod request;
+pub mod rspamd;
+pub mod unsubscribe;
 
 use common::Inner;
 use std::sync::Arc;
@@ -15,18 +15,22 @@ use crate::{
         },
     },
     form::FormHandler,
+    rspamd::RspamdHandler,
+    unsubscribe::UnsubscribeHandler,
 };
 use common::{
     BuildServer, Inner, KV_ACME, Server,
     ipc::PushEvent,
     manager::application::Resource,
-    network::{SessionData, SessionManager, SessionStream},
+    network::{SessionData, SessionManager, SessionStream, health::ReadinessStatus},
+    telemetry::audit::AuditSource,
 };
 use dav::{DavMethod, request::DavRequestHandler};
 use groupware::{DavResourceName, calendar::itip::ItipIngest};
 use http_proto::{
     DownloadResponse, HtmlResponse, HttpContext, HttpRequest, HttpResponse, HttpResponseBody,
-    HttpSessionData, JsonProblemResponse, ToHttpResponse, form_urlencoded, request::fetch_body,
+    HttpSessionData, JsonProblemResponse, JsonResponse, ToHttpResponse, form_urlencoded,
+    request::fetch_body,
 };
 use hyper::{
     Method, StatusCode, body,
@@ -37,19 +41,23 @@ use hyper::{
 use hyper_util::rt::TokioIo;
 use jmap::{
     api::{
-        ToJmapHttpResponse, event_source::EventSourceHandler, request::RequestHandler,
-        session::SessionHandler,
+        ToJmapHttpResponse, auth::JmapStepUp, event_source::EventSourceHandler,
+        request::RequestHandler, session::SessionHandler,
     },
     blob::{download::BlobDownload, upload::BlobUpload},
     websocket::upgrade::WebSocketUpgrade,
 };
 use jmap_proto::request::{Request, capability::Session};
 use percent_encoding::percent_decode_str;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission, ServiceProtocol};
 use std::{net::IpAddr, str::FromStr, sync::Arc};
 use store::dispatch::lookup::KeyValue;
 use trc::SecurityEvent;
-use types::{blob::BlobId, id::Id};
+use types::{
+    blob::{BlobClass, BlobId},
+    collection::Collection,
+    id::Id,
+};
 
 pub trait ParseHttp: Sync + Send {
     fn parse_http_request(
@@ -85,8 +93,9 @@ impl ParseHttp for Server {
                 match (path.next().unwrap_or_default(), req.method()) {
                     ("", &Method::POST) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                            .await?;
 
                         if let Some(content_type) = req.headers().get(CONTENT_TYPE) {
                             let is_json = content_type
@@ -118,23 +127,25 @@ impl ParseHttp for Server {
                         .await
                         .ok_or_else(|| trc::LimitEvent::SizeRequest.into_err())?;
 
+                        let request = Request::parse(
+                            &bytes,
+                            self.core.jmap.request_max_calls,
+                            self.core.jmap.request_max_size,
+                        )?;
+
+                        // Reject the whole request if it contains protected operations
+                        self.assert_jmap_request_step_up(&request, &access_token)?;
+
                         return Ok(self
-                            .handle_jmap_request(
-                                Request::parse(
-                                    &bytes,
-                                    self.core.jmap.request_max_calls,
-                                    self.core.jmap.request_max_size,
-                                )?,
-                                &access_token,
-                                &session,
-                            )
+                            .handle_jmap_request(request, &access_token, &session)
                             .await
                             .into_http_response());
                     }
                     ("download", &Method::GET) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                            .await?;
 
                         if let (Some(_), Some(blob_id), Some(name)) = (
                             path.next().and_then(|p| Id::from_str(p).ok()),
@@ -142,28 +153,51 @@ impl ParseHttp for Server {
                             path.next(),
                         ) {
                             return match self.blob_download(&blob_id, &access_token).await? {
-                                Some(blob) => Ok(DownloadResponse {
-                                    filename: name.to_string(),
-                                    content_type: req
-                                        .uri()
-                                        .query()
-                                        .and_then(|q| {
-                                            form_urlencoded::parse(q.as_bytes())
-                                                .find(|(k, _)| k == "accept")
-                                                .map(|(_, v)| v.into_owned())
-                                        })
-                                        .unwrap_or("application/octet-stream".to_string()),
-                                    blob,
+                                Some(blob) => {
+                                    if let BlobClass::Linked {
+                                        account_id,
+                                        collection,
+                                        document_id,
+                                    } = &blob_id.class
+                                        && *collection == Collection::Email as u8
+                                    {
+                                        self.audit(
+                                            AuditSource {
+                                                access_token: &access_token,
+                                                protocol: ServiceProtocol::Jmap,
+                                                remote_ip: Some(session.remote_ip),
+                                            },
+                                            *account_id,
+                                            AuditEventType::Export,
+                                            [Id::from(*document_id)],
+                                        )
+                                        .await;
+                                    }
+
+                                    Ok(DownloadResponse {
+                                        filename: name.to_string(),
+                                        content_type: req
+                                            .uri()
+                                            .query()
+                                            .and_then(|q| {
+                                                form_urlencoded::parse(q.as_bytes())
+                                                    .find(|(k, _)| k == "accept")
+                                                    .map(|(_, v)| v.into_owned())
+                                            })
+                                            .unwrap_or("application/octet-stream".to_string()),
+                                        blob,
+                                    }
+                                    .into_http_response())
                                 }
-                                .into_http_response()),
                                 None => Err(trc::ResourceEvent::NotFound.into_err()),
                             };
                         }
                     }
                     ("upload", &Method::POST) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                            .await?;
 
                         if let Some(account_id) = path.next().and_then(|p| Id::from_str(p).ok()) {
                             return match fetch_body(
@@ -195,15 +229,17 @@ impl ParseHttp for Server {
                     }
                     ("eventsource", &Method::GET) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                            .await?;
 
                         return self.handle_event_source(req, access_token).await;
                     }
                     ("ws", &Method::GET) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                            .await?;
 
                         return self
                             .upgrade_websocket_connection(req, access_token, session)
@@ -212,8 +248,9 @@ impl ParseHttp for Server {
                     ("session", &Method::GET) => {
                         return if req.headers().contains_key(header::AUTHORIZATION) {
                             // Authenticate request
-                            let (_in_flight, access_token) =
-                                self.authenticate_headers(&req, &session).await?;
+                            let (_in_flight, access_token) = self
+                                .authenticate_protocol(&req, &session, &[ServiceProtocol::Jmap])
+                                .await?;
 
                             self.handle_session_resource(
                                 self.core.network.http.url_https.to_string(),
@@ -257,8 +294,9 @@ impl ParseHttp for Server {
                         ),
                     (Some(resource), Some(method)) => {
                         // Authenticate request
-                        let (_in_flight, access_token) =
-                            self.authenticate_headers(&req, &session).await?;
+                        let (_in_flight, access_token) = self
+                            .authenticate_protocol(&req, &session, resource.protocols())
+                            .await?;
 
                         self.handle_dav_request(req, access_token, &session, resource, method)
                             .await
@@ -324,7 +362,20 @@ impl ParseHttp for Server {
                     self.is_http_anonymous_request_allowed(session.remote_ip)
                         .await?;
 
-                    return if let Some(policy) = &self.core.smtp.session.mta_sts_policy {
+                    // Serve the policy of the domain the request was addressed to
+                    let domain = req
+                        .headers()
+                        .get(header::HOST)
+                        .and_then(|h| h.to_str().ok())
+                        .map(|h| h.rsplit_once(':').map_or(h, |(h, _)| h).to_lowercase())
+                        .and_then(|h| h.strip_prefix("mta-sts.").map(|h| h.to_string()));
+                    let domain = if let Some(domain) = domain {
+                        self.domain(&domain).await?
+                    } else {
+                        None
+                    };
+
+                    return if let Some(policy) = self.mta_sts_policy(domain.as_deref()) {
                         Ok(Resource::new("text/plain", policy.to_string().into_bytes())
                             .into_http_response())
                     } else {
@@ -392,13 +443,7 @@ impl ParseHttp for Server {
                     return self.handle_token_request(&mut req, session).await;
                 }
                 ("introspect", &Method::POST) => {
-                    // Authenticate request
-                    let (_in_flight, access_token) =
-                        self.authenticate_headers(&req, &session).await?;
-
-                    return self
-                        .handle_token_introspect(&mut req, &access_token, session.session_id)
-                        .await;
+                    return self.handle_token_introspect(&mut req, &session).await;
                 }
                 ("userinfo", &Method::GET) => {
                     // Authenticate request
@@ -532,13 +577,16 @@ impl ParseHttp for Server {
                         return Ok(JsonProblemResponse(StatusCode::OK).into_http_response());
                     }
                     "ready" => {
-                        return Ok(JsonProblemResponse({
-                            if !self.core.storage.data.is_none() {
+                        let readiness = self.readiness().await;
+                        return Ok(JsonResponse::with_status(
+                            if readiness.status == ReadinessStatus::Ready {
                                 StatusCode::OK
                             } else {
                                 StatusCode::SERVICE_UNAVAILABLE
-                            }
-                        })
+                            },
+                            readiness,
+                        )
+                        .no_cache()
                         .into_http_response());
                     }
                     _ => (),
@@ -617,6 +665,34 @@ impl ParseHttp for Server {
                     }
                 }
             }
+            "unsubscribe" => {
+                if let Some(config) = &self.core.smtp.queue.unsubscribe
+                    && let Some(token) = path.next().filter(|token| !token.is_empty())
+                {
+                    match *req.method() {
+                        Method::GET | Method::POST => {
+                            self.is_http_anonymous_request_allowed(session.remote_ip)
+                                .await?;
+
+                            let token = token.to_string();
+                            return self
+                                .handle_unsubscribe(&mut req, &session, config, &token)
+                                .await;
+                        }
+                        Method::OPTIONS => {
+                            return Ok(HttpResponse::new(StatusCode::NO_CONTENT));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            "checkv2" => {
+                if let Some(rspamd) = &self.core.spam.rspamd_api
+                    && req.method() == Method::POST
+                {
+                    return self.handle_rspamd_check(&mut req, &session, rspamd).await;
+                }
+            }
             "login" | "device" => {
                 let page = include_str!(concat!(
                     env!("CARGO_MANIFEST_DIR"),
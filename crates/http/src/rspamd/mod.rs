@@ -0,0 +1,213 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::auth::authenticate::HttpHeaders;
+use common::{
+    Server,
+    config::mailstore::spamfilter::{RspamdApiConfig, SpamFilterAction},
+    network::limiter::LimiterResult,
+};
+use http_proto::{request::fetch_body, *};
+use hyper::header::HeaderMap;
+use jmap::registry::mapping::action::{SpamCheck, spam_check};
+use serde_json::{Map, Value, json};
+use smtp_proto::{MAIL_BODY_8BITMIME, MAIL_SMTPUTF8};
+use std::{future::Future, net::IpAddr};
+use utils::constant_time_eq;
+
+pub trait RspamdHandler: Sync + Send {
+    fn handle_rspamd_check(
+        &self,
+        req: &mut HttpRequest,
+        session: &HttpSessionData,
+        config: &RspamdApiConfig,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl RspamdHandler for Server {
+    async fn handle_rspamd_check(
+        &self,
+        req: &mut HttpRequest,
+        session: &HttpSessionData,
+        config: &RspamdApiConfig,
+    ) -> trc::Result<HttpResponse> {
+        // Rspamd clients send the shared secret in the Password header
+        let is_authorized = match &config.secret {
+            Some(secret) => req
+                .headers()
+                .get("Password")
+                .and_then(|value| value.to_str().ok())
+                .or_else(|| {
+                    req.authorization().and_then(|(scheme, token)| {
+                        scheme.eq_ignore_ascii_case("bearer").then_some(token)
+                    })
+                })
+                .is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes())),
+            None => session.remote_ip.is_loopback(),
+        };
+        if !is_authorized {
+            return Err(trc::AuthEvent::Failed
+                .into_err()
+                .details("Invalid or missing credentials.")
+                .caused_by(trc::location!()));
+        }
+
+        let _in_flight = match config.limiter.is_allowed() {
+            LimiterResult::Allowed(in_flight) => Some(in_flight),
+            LimiterResult::Forbidden => {
+                return Err(trc::LimitEvent::ConcurrentRequest.into_err());
+            }
+            LimiterResult::Disabled => None,
+        };
+
+        // Obtain message and envelope
+        let raw_message = fetch_body(req, self.core.email.mail_max_size, session.session_id)
+            .await
+            .ok_or_else(|| trc::LimitEvent::SizeRequest.into_err())?;
+        let headers = req.headers();
+        let remote_ip = header_value(headers, "IP")
+            .and_then(|ip| ip.parse::<IpAddr>().ok())
+            .unwrap_or(session.remote_ip);
+        let ehlo_domain = header_value(headers, "Helo").unwrap_or_default();
+        let env_from = header_value(headers, "From")
+            .map(|from| from.trim_start_matches('<').trim_end_matches('>'))
+            .unwrap_or_default();
+        let env_rcpt_to = headers
+            .get_all("Rcpt")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|rcpt| {
+                rcpt.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_lowercase()
+            })
+            .filter(|rcpt| !rcpt.is_empty())
+            .collect::<Vec<_>>();
+        let env_from_flags = if raw_message.is_ascii() {
+            0
+        } else {
+            MAIL_BODY_8BITMIME | MAIL_SMTPUTF8
+        };
+
+        let Some(result) = spam_check(
+            self,
+            SpamCheck {
+                raw_message: &raw_message,
+                remote_ip,
+                ehlo_domain: if !ehlo_domain.is_empty() {
+                    ehlo_domain
+                } else {
+                    "unknown"
+                },
+                env_from,
+                env_from_flags,
+                env_rcpt_to: &env_rcpt_to,
+                authenticated_as: header_value(headers, "User").filter(|user| !user.is_empty()),
+                is_tls: header_value(headers, "TLS-Version").is_some(),
+            },
+        )
+        .await
+        else {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Failed to parse message"));
+        };
+
+        // Map tags to symbols
+        let mut symbols = Map::with_capacity(result.tags.len());
+        for tag in result.tags {
+            let score = match self.core.spam.lists.scores.get(&tag) {
+                Some(SpamFilterAction::Allow(score)) => *score,
+                _ => 0.0,
+            };
+            symbols.insert(
+                tag.clone(),
+                json!({
+                    "name": tag,
+                    "score": score,
+                    "metric_score": score,
+                }),
+            );
+        }
+
+        let scores = &self.core.spam.scores;
+        let mut thresholds = Map::new();
+        thresholds.insert("add header".into(), json!(scores.spam_threshold));
+        if scores.discard_threshold > 0.0 {
+            thresholds.insert("discard".into(), json!(scores.discard_threshold));
+        }
+        if scores.reject_threshold > 0.0 {
+            thresholds.insert("reject".into(), json!(scores.reject_threshold));
+        }
+
+        let (action, is_skipped, add_headers) = match result.action {
+            SpamFilterAction::Allow(score) => (
+                if score.is_spam {
+                    "add header"
+                } else {
+                    "no action"
+                },
+                false,
+                milter_headers(&score.headers),
+            ),
+            SpamFilterAction::Discard => ("discard", false, Map::new()),
+            SpamFilterAction::Reject => ("reject", false, Map::new()),
+            SpamFilterAction::Disabled => ("no action", true, Map::new()),
+        };
+
+        Ok(JsonResponse::new(json!({
+            "is_skipped": is_skipped,
+            "score": result.score,
+            "required_score": scores.spam_threshold,
+            "action": action,
+            "thresholds": thresholds,
+            "symbols": symbols,
+            "milter": {
+                "add_headers": add_headers,
+                "remove_headers": {},
+            },
+        }))
+        .into_http_response())
+    }
+}
+
+fn header_value<'x>(headers: &'x HeaderMap, name: &str) -> Option<&'x str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim())
+}
+
+fn milter_headers(headers: &str) -> Map<String, Value> {
+    let mut result = Map::new();
+    let mut current: Option<(&str, String)> = None;
+
+    for line in headers.split("\r\n") {
+        if line.starts_with([' ', '\t']) {
+            // Continuation of a folded header
+            if let Some((_, value)) = &mut current {
+                value.push_str("\r\n");
+                value.push_str(line);
+            }
+            continue;
+        }
+
+        if let Some((name, value)) = current.take() {
+            result.insert(name.to_string(), json!({ "value": value, "order": 0 }));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            current = Some((name.trim(), value.trim_start().to_string()));
+        }
+    }
+
+    if let Some((name, value)) = current {
+        result.insert(name.to_string(), json!({ "value": value, "order": 0 }));
+    }
+
+    result
+}
@@ -0,0 +1,180 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{
+    KV_RATE_LIMIT_UNSUBSCRIBE, KV_UNSUBSCRIBE, Server,
+    network::{ip_to_bytes, unsubscribe::Unsubscribe},
+};
+use http_proto::*;
+use hyper::{Method, StatusCode};
+use registry::{
+    schema::structs::UnsubscribeEvent,
+    types::{datetime::UTCDateTime, ipaddr::IpAddr},
+};
+use std::future::Future;
+use store::{
+    registry::write::{RegistryWrite, RegistryWriteResult},
+    write::now,
+};
+use trc::AddContext;
+use utils::DomainPart;
+
+const MAX_POST_SIZE: usize = 1024;
+
+pub trait UnsubscribeHandler: Sync + Send {
+    fn handle_unsubscribe(
+        &self,
+        req: &mut HttpRequest,
+        session: &HttpSessionData,
+        config: &Unsubscribe,
+        token: &str,
+    ) -> impl Future<Output = trc::Result<HttpResponse>> + Send;
+}
+
+impl UnsubscribeHandler for Server {
+    async fn handle_unsubscribe(
+        &self,
+        req: &mut HttpRequest,
+        session: &HttpSessionData,
+        config: &Unsubscribe,
+        token: &str,
+    ) -> trc::Result<HttpResponse> {
+        // Validate rate
+        if let Some(rate) = &config.rate
+            && !session.remote_ip.is_loopback()
+            && self
+                .in_memory_store()
+                .is_rate_allowed(
+                    KV_RATE_LIMIT_UNSUBSCRIBE,
+                    &ip_to_bytes(&session.remote_ip),
+                    rate,
+                    false,
+                )
+                .await
+                .caused_by(trc::location!())?
+                .is_some()
+        {
+            return Err(trc::LimitEvent::TooManyRequests.into_err());
+        }
+
+        let Some(token) = config.verify(token, now()) else {
+            return Ok(HtmlResponse::with_status(
+                StatusCode::NOT_FOUND,
+                page("This unsubscribe link is invalid or has expired."),
+            )
+            .into_http_response());
+        };
+
+        // RFC 8058 requires GET requests to have no side effects, as links are
+        // often fetched by anti-spam filters. Only the one-click POST unsubscribes.
+        if req.method() != Method::POST {
+            return Ok(HtmlResponse::new(page(&format!(
+                concat!(
+                    "<p>Unsubscribe {} from messages sent by {}?</p>",
+                    "<form method=\"post\">",
+                    "<input type=\"hidden\" name=\"List-Unsubscribe\" value=\"One-Click\">",
+                    "<button type=\"submit\">Unsubscribe</button>",
+                    "</form>"
+                ),
+                html_escape(&token.recipient),
+                html_escape(&token.sender)
+            )))
+            .into_http_response());
+        }
+
+        let body = fetch_body(req, MAX_POST_SIZE, session.session_id)
+            .await
+            .unwrap_or_default();
+        if !form_urlencoded::parse(&body)
+            .any(|(name, value)| name == "List-Unsubscribe" && value == "One-Click")
+        {
+            return Err(trc::ResourceEvent::BadParameters
+                .into_err()
+                .details("Missing one-click unsubscribe body"));
+        }
+
+        // Record the event once per sender, recipient and campaign
+        let key = format!(
+            "{}\n{}\n{}",
+            token.sender,
+            token.recipient,
+            token.campaign_id.as_deref().unwrap_or_default()
+        );
+        if self
+            .in_memory_store()
+            .try_lock(KV_UNSUBSCRIBE, key.as_bytes(), config.validity.max(1))
+            .await
+            .caused_by(trc::location!())?
+        {
+            let member_tenant_id = self
+                .domain(token.sender.domain_part())
+                .await
+                .caused_by(trc::location!())?
+                .and_then(|domain| domain.id_tenant.map(Into::into));
+            match self
+                .registry()
+                .write(RegistryWrite::insert(
+                    &UnsubscribeEvent {
+                        sender: token.sender.clone(),
+                        recipient: token.recipient.clone(),
+                        campaign_id: token.campaign_id.clone(),
+                        timestamp: UTCDateTime::now(),
+                        remote_ip: Some(IpAddr(session.remote_ip)),
+                        member_tenant_id,
+                    }
+                    .into(),
+                ))
+                .await
+                .caused_by(trc::location!())?
+            {
+                RegistryWriteResult::Success(_) => {}
+                err => {
+                    return Err(trc::StoreEvent::UnexpectedError
+                        .into_err()
+                        .details("Failed to record unsubscribe event")
+                        .reason(err)
+                        .caused_by(trc::location!()));
+                }
+            }
+
+            trc::event!(
+                Queue(trc::QueueEvent::Unsubscribe),
+                SpanId = session.session_id,
+                From = token.sender,
+                To = token.recipient,
+                Id = token.campaign_id,
+                RemoteIp = session.remote_ip,
+            );
+        }
+
+        Ok(HtmlResponse::new(page("You have been unsubscribed.")).into_http_response())
+    }
+}
+
+fn page(body: &str) -> String {
+    format!(
+        concat!(
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\">",
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">",
+            "<title>Unsubscribe</title></head><body>{}</body></html>"
+        ),
+        body
+    )
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
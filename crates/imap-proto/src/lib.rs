@@ -75,9 +75,17 @@ pub enum Command {
     // RFC 9208
     GetQuota,
     GetQuotaRoot,
+    SetQuota,
 
     // RFC 9698
     GetJmapAccess,
+
+    // RFC 4467
+    GenUrlAuth,
+    ResetKey,
+
+    // RFC 4978
+    Compress,
 }
 
 impl Command {
@@ -154,6 +162,9 @@ pub enum ResponseCode {
 
     // USEATTR
     UseAttr,
+
+    // COMPRESS
+    CompressionActive,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
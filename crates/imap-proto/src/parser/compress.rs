@@ -0,0 +1,81 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use compact_str::ToCompactString;
+
+use crate::{
+    Command,
+    protocol::compress::{self, Algorithm},
+    receiver::{Request, bad},
+};
+
+impl Request<Command> {
+    pub fn parse_compress(self) -> trc::Result<compress::Arguments> {
+        match self.tokens.len() {
+            1 => {
+                let algorithm = self.tokens.into_iter().next().unwrap().unwrap_bytes();
+                if algorithm.eq_ignore_ascii_case(b"DEFLATE") {
+                    Ok(compress::Arguments {
+                        tag: self.tag,
+                        algorithm: Algorithm::Deflate,
+                    })
+                } else {
+                    Err(bad(
+                        self.tag.to_compact_string(),
+                        format!(
+                            "Unsupported compression algorithm '{}'.",
+                            String::from_utf8_lossy(&algorithm)
+                        ),
+                    ))
+                }
+            }
+            0 => Err(self.into_error("Missing compression algorithm.")),
+            _ => Err(self.into_error("Too many arguments.")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        protocol::compress::{self, Algorithm},
+        receiver::Receiver,
+    };
+
+    #[test]
+    fn parse_compress() {
+        let mut receiver = Receiver::new();
+
+        for command in ["a COMPRESS DEFLATE\r\n", "a compress deflate\r\n"] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_compress()
+                    .unwrap(),
+                compress::Arguments {
+                    tag: "a".into(),
+                    algorithm: Algorithm::Deflate,
+                }
+            );
+        }
+
+        for command in [
+            "b COMPRESS\r\n",
+            "b COMPRESS LZ4\r\n",
+            "b COMPRESS DEFLATE X\r\n",
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_compress()
+                    .is_err(),
+                "{command}"
+            );
+        }
+    }
+}
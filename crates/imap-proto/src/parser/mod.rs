@@ -7,6 +7,7 @@
 pub mod acl;
 pub mod append;
 pub mod authenticate;
+pub mod compress;
 pub mod copy_move;
 pub mod create;
 pub mod delete;
@@ -24,6 +25,7 @@ pub mod status;
 pub mod store;
 pub mod subscribe;
 pub mod thread;
+pub mod urlauth;
 
 use std::{borrow::Cow, str::FromStr};
 
@@ -34,6 +36,7 @@ use crate::{
     protocol::{Flag, Sequence},
     receiver::CommandParser,
 };
+use types::keyword::unescape_keyword;
 
 pub type Result<T> = std::result::Result<T, Cow<'static, str>>;
 
@@ -80,7 +83,11 @@ impl CommandParser for Command {
             "ID" => Command::Id,
             "GETQUOTA" => Command::GetQuota,
             "GETQUOTAROOT" => Command::GetQuotaRoot,
+            "SETQUOTA" => Command::SetQuota,
             "GETJMAPACCESS" => Command::GetJmapAccess,
+            "GENURLAUTH" => Command::GenUrlAuth,
+            "RESETKEY" => Command::ResetKey,
+            "COMPRESS" => Command::Compress,
         )
     }
 
@@ -131,7 +138,7 @@ impl Flag {
             } else {
                 String::from_utf8(value)
                     .map_err(|_| Cow::from("Invalid UTF-8."))
-                    .map(|v| Flag::Keyword(v.into_boxed_str()))
+                    .map(|v| Flag::Keyword(unescape_keyword(&v).into()))
             }
         } else {
             Err(Cow::from("Null flags are not allowed."))
@@ -422,6 +429,12 @@ mod tests {
             Command::parse(b"GETJMAPACCESS", false),
             Some(Command::GetJmapAccess)
         );
+        assert_eq!(
+            Command::parse(b"GENURLAUTH", false),
+            Some(Command::GenUrlAuth)
+        );
+        assert_eq!(Command::parse(b"RESETKEY", false), Some(Command::ResetKey));
+        assert_eq!(Command::parse(b"COMPRESS", false), Some(Command::Compress));
         assert_eq!(Command::parse(b"NOTACOMMAND", false), None);
     }
 
@@ -8,11 +8,13 @@ use compact_str::ToCompactString;
 
 use crate::{
     Command,
-    protocol::quota,
-    receiver::{Request, bad},
+    protocol::{capability::QuotaResourceName, quota},
+    receiver::{Request, Token, bad},
     utf7::utf7_maybe_decode,
 };
 
+use super::parse_number;
+
 impl Request<Command> {
     pub fn parse_get_quota_root(self, is_utf8: bool) -> trc::Result<quota::Arguments> {
         match self.tokens.len() {
@@ -49,11 +51,85 @@ impl Request<Command> {
             _ => Err(self.into_error("Too many arguments.")),
         }
     }
+
+    pub fn parse_set_quota(self) -> trc::Result<quota::SetArguments> {
+        let mut tokens = self.tokens.into_iter();
+        let name = tokens
+            .next()
+            .ok_or_else(|| bad(self.tag.to_compact_string(), "Missing quota root."))?
+            .unwrap_string()
+            .map_err(|v| bad(self.tag.to_compact_string(), v))?;
+        if !tokens
+            .next()
+            .is_some_and(|token| token.is_parenthesis_open())
+        {
+            return Err(bad(
+                self.tag.to_compact_string(),
+                "Expected resource limits list.",
+            ));
+        }
+
+        let mut limits = Vec::new();
+        loop {
+            match tokens.next() {
+                Some(Token::ParenthesisClose) => break,
+                Some(token) => {
+                    let resource = QuotaResourceName::parse(&token.unwrap_bytes())
+                        .map_err(|v| bad(self.tag.to_compact_string(), v))?;
+                    let limit = tokens
+                        .next()
+                        .ok_or_else(|| bad(self.tag.to_compact_string(), "Missing resource limit."))
+                        .and_then(|token| {
+                            parse_number::<u64>(&token.unwrap_bytes())
+                                .map_err(|v| bad(self.tag.to_compact_string(), v))
+                        })?;
+                    limits.push((resource, limit));
+                }
+                None => {
+                    return Err(bad(
+                        self.tag.to_compact_string(),
+                        "Missing closing parenthesis.",
+                    ));
+                }
+            }
+        }
+
+        if tokens.next().is_none() {
+            Ok(quota::SetArguments {
+                tag: self.tag,
+                name,
+                limits,
+            })
+        } else {
+            Err(bad(self.tag.to_compact_string(), "Too many arguments."))
+        }
+    }
+}
+
+impl QuotaResourceName {
+    pub fn parse(value: &[u8]) -> super::Result<Self> {
+        hashify::tiny_map_ignore_case!(value,
+            "STORAGE" => Self::Storage,
+            "MESSAGE" => Self::Message,
+            "MAILBOX" => Self::Mailbox,
+            "ANNOTATION-STORAGE" => Self::AnnotationStorage,
+        )
+        .ok_or_else(|| {
+            format!(
+                "Unsupported quota resource '{}'.",
+                String::from_utf8_lossy(value)
+            )
+            .into()
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{protocol::quota, receiver::Receiver};
+    use crate::{
+        protocol::{capability::QuotaResourceName, quota},
+        receiver::Receiver,
+    };
 
     #[test]
     fn parse_quota() {
@@ -90,5 +166,58 @@ mod tests {
                 .unwrap(),
             arguments
         );
+
+        for (command, arguments) in [
+            (
+                "A001 SETQUOTA \"#12\" (STORAGE 512)\r\n",
+                quota::SetArguments {
+                    name: "#12".into(),
+                    tag: "A001".into(),
+                    limits: vec![(QuotaResourceName::Storage, 512)],
+                },
+            ),
+            (
+                "A002 SETQUOTA #12 (storage 1024 MESSAGE 100)\r\n",
+                quota::SetArguments {
+                    name: "#12".into(),
+                    tag: "A002".into(),
+                    limits: vec![
+                        (QuotaResourceName::Storage, 1024),
+                        (QuotaResourceName::Message, 100),
+                    ],
+                },
+            ),
+            (
+                "A003 SETQUOTA \"\" ()\r\n",
+                quota::SetArguments {
+                    name: "".into(),
+                    tag: "A003".into(),
+                    limits: vec![],
+                },
+            ),
+        ] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_set_quota()
+                    .unwrap(),
+                arguments
+            );
+        }
+
+        for command in [
+            "A004 SETQUOTA #12 (STORAGE)\r\n",
+            "A005 SETQUOTA #12 (FOOBAR 10)\r\n",
+            "A006 SETQUOTA #12 STORAGE 10\r\n",
+        ] {
+            assert!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_set_quota()
+                    .is_err()
+            );
+        }
     }
 }
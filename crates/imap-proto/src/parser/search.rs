@@ -42,7 +42,7 @@ impl Request<Command> {
                 }
                 Some(Token::Argument(value)) if value.eq_ignore_ascii_case(b"charset") => {
                     tokens.next();
-                    decoder = charset_decoder(
+                    decoder = parse_charset(
                         &tokens
                             .next()
                             .ok_or_else(|| bad(self.tag.to_compact_string(), "Missing charset."))?
@@ -500,6 +500,19 @@ pub fn parse_filters(
     Ok(filters)
 }
 
+// UTF-8 and its ASCII subset are decoded natively, so that every alias
+// of these charsets yields the same criteria
+pub fn parse_charset(charset: &[u8]) -> Option<DecoderFnc> {
+    if ["utf-8", "utf8", "us-ascii", "ascii"]
+        .iter()
+        .any(|name| charset.eq_ignore_ascii_case(name.as_bytes()))
+    {
+        None
+    } else {
+        charset_decoder(charset)
+    }
+}
+
 pub fn decode_argument(
     tokens: &mut Peekable<IntoIter<Token>>,
     decoder: Option<DecoderFnc>,
@@ -5,7 +5,6 @@
  */
 
 use compact_str::ToCompactString;
-use mail_parser::decoders::charsets::map::charset_decoder;
 
 use crate::{
     Command,
@@ -13,7 +12,7 @@ use crate::{
     receiver::{Request, Token, bad},
 };
 
-use super::search::{parse_filters, parse_result_options};
+use super::search::{parse_charset, parse_filters, parse_result_options};
 
 impl Request<Command> {
     #[allow(clippy::while_let_on_iterator)]
@@ -76,7 +75,7 @@ impl Request<Command> {
             return Err(bad(self.tag.to_compact_string(), "Missing sort criteria."));
         }
 
-        let decoder = charset_decoder(
+        let decoder = parse_charset(
             &tokens
                 .next()
                 .ok_or_else(|| bad(self.tag.to_compact_string(), "Missing charset."))?
@@ -5,7 +5,6 @@
  */
 
 use compact_str::ToCompactString;
-use mail_parser::decoders::charsets::map::charset_decoder;
 
 use crate::{
     Command,
@@ -13,7 +12,7 @@ use crate::{
     receiver::{Request, bad},
 };
 
-use super::search::parse_filters;
+use super::search::{parse_charset, parse_filters};
 
 impl Request<Command> {
     #[allow(clippy::while_let_on_iterator)]
@@ -31,7 +30,7 @@ impl Request<Command> {
         )
         .map_err(|v| bad(self.tag.to_compact_string(), v))?;
 
-        let decoder = charset_decoder(
+        let decoder = parse_charset(
             &tokens
                 .next()
                 .ok_or_else(|| bad(self.tag.to_compact_string(), "Missing charset."))?
@@ -0,0 +1,182 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use compact_str::ToCompactString;
+
+use crate::{
+    Command,
+    protocol::urlauth::{self, UrlRump},
+    receiver::{Request, bad},
+    utf7::utf7_maybe_decode,
+};
+
+impl Request<Command> {
+    pub fn parse_genurlauth(self) -> trc::Result<urlauth::GenArguments> {
+        if self.tokens.is_empty() {
+            return Err(self.into_error("Missing arguments."));
+        } else if self.tokens.len() % 2 != 0 {
+            return Err(self.into_error("Missing authorization mechanism."));
+        }
+
+        let mut urls = Vec::with_capacity(self.tokens.len() / 2);
+        let mut tokens = self.tokens.into_iter();
+        while let (Some(url), Some(mechanism)) = (tokens.next(), tokens.next()) {
+            urls.push(UrlRump {
+                url: url
+                    .unwrap_string()
+                    .map_err(|v| bad(self.tag.to_compact_string(), v))?,
+                mechanism: mechanism
+                    .unwrap_string()
+                    .map_err(|v| bad(self.tag.to_compact_string(), v))?,
+            });
+        }
+
+        Ok(urlauth::GenArguments {
+            tag: self.tag,
+            urls,
+        })
+    }
+
+    pub fn parse_resetkey(self, is_utf8: bool) -> trc::Result<urlauth::ResetArguments> {
+        let mut tokens = self.tokens.into_iter();
+        let mailbox_name = tokens
+            .next()
+            .map(|token| {
+                token
+                    .unwrap_string()
+                    .map(|name| utf7_maybe_decode(name, is_utf8))
+                    .map_err(|v| bad(self.tag.to_compact_string(), v))
+            })
+            .transpose()?;
+        let mechanisms = tokens
+            .map(|token| {
+                token
+                    .unwrap_string()
+                    .map_err(|v| bad(self.tag.to_compact_string(), v))
+            })
+            .collect::<trc::Result<Vec<_>>>()?;
+
+        Ok(urlauth::ResetArguments {
+            tag: self.tag,
+            mailbox_name,
+            mechanisms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        protocol::urlauth::{self, UrlRump},
+        receiver::Receiver,
+    };
+
+    #[test]
+    fn parse_genurlauth() {
+        let mut receiver = Receiver::new();
+
+        for (command, arguments) in [
+            (
+                concat!(
+                    "a GENURLAUTH \"imap://joe@example.com/INBOX/;uid=20/;section=1.2;",
+                    "urlauth=submit+fred\" INTERNAL\r\n"
+                ),
+                urlauth::GenArguments {
+                    tag: "a".into(),
+                    urls: vec![UrlRump {
+                        url:
+                            "imap://joe@example.com/INBOX/;uid=20/;section=1.2;urlauth=submit+fred"
+                                .into(),
+                        mechanism: "INTERNAL".into(),
+                    }],
+                },
+            ),
+            (
+                concat!(
+                    "b GENURLAUTH \"imap://joe@example.com/INBOX/;uid=20;urlauth=authuser\" ",
+                    "INTERNAL \"imap://joe@example.com/Sent/;uid=3;urlauth=anonymous\" internal\r\n"
+                ),
+                urlauth::GenArguments {
+                    tag: "b".into(),
+                    urls: vec![
+                        UrlRump {
+                            url: "imap://joe@example.com/INBOX/;uid=20;urlauth=authuser".into(),
+                            mechanism: "INTERNAL".into(),
+                        },
+                        UrlRump {
+                            url: "imap://joe@example.com/Sent/;uid=3;urlauth=anonymous".into(),
+                            mechanism: "internal".into(),
+                        },
+                    ],
+                },
+            ),
+        ] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_genurlauth()
+                    .unwrap(),
+                arguments,
+                "Failed to parse {command}"
+            );
+        }
+
+        assert!(
+            receiver
+                .parse(
+                    &mut "c GENURLAUTH \"imap://joe@host/INBOX/;uid=1;urlauth=authuser\"\r\n"
+                        .as_bytes()
+                        .iter()
+                )
+                .unwrap()
+                .parse_genurlauth()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_resetkey() {
+        let mut receiver = Receiver::new();
+
+        for (command, arguments) in [
+            (
+                "a RESETKEY\r\n",
+                urlauth::ResetArguments {
+                    tag: "a".into(),
+                    mailbox_name: None,
+                    mechanisms: vec![],
+                },
+            ),
+            (
+                "b RESETKEY INBOX\r\n",
+                urlauth::ResetArguments {
+                    tag: "b".into(),
+                    mailbox_name: Some("INBOX".into()),
+                    mechanisms: vec![],
+                },
+            ),
+            (
+                "c RESETKEY \"Sent Items\" INTERNAL\r\n",
+                urlauth::ResetArguments {
+                    tag: "c".into(),
+                    mailbox_name: Some("Sent Items".into()),
+                    mechanisms: vec!["INTERNAL".into()],
+                },
+            ),
+        ] {
+            assert_eq!(
+                receiver
+                    .parse(&mut command.as_bytes().iter())
+                    .unwrap()
+                    .parse_resetkey(true)
+                    .unwrap(),
+                arguments,
+                "Failed to parse {command}"
+            );
+        }
+    }
+}
@@ -54,6 +54,8 @@ pub enum Capability {
     QuotaResource(QuotaResourceName),
     QuotaSet,
     JmapAccess,
+    UrlAuth,
+    CompressDeflate, //COMPRESS=DEFLATE
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -121,10 +123,16 @@ impl Capability {
             }
             Capability::QuotaSet => b"QUOTA=SET",
             Capability::JmapAccess => b"JMAPACCESS",
+            Capability::UrlAuth => b"URLAUTH",
+            Capability::CompressDeflate => b"COMPRESS=DEFLATE",
         });
     }
 
-    pub fn all_capabilities(is_authenticated: bool, offer_tls: bool) -> Vec<Capability> {
+    pub fn all_capabilities(
+        is_authenticated: bool,
+        offer_tls: bool,
+        offer_compress: bool,
+    ) -> Vec<Capability> {
         let mut capabilities = vec![
             Capability::IMAP4rev2,
             Capability::IMAP4rev1,
@@ -167,7 +175,13 @@ impl Capability {
                 Capability::Rights,
                 Capability::Quota,
                 Capability::QuotaResource(QuotaResourceName::Storage),
+                Capability::QuotaResource(QuotaResourceName::Message),
+                Capability::QuotaSet,
+                Capability::UrlAuth,
             ]);
+            if offer_compress {
+                capabilities.push(Capability::CompressDeflate);
+            }
         } else {
             capabilities.extend([
                 Capability::Auth(Mechanism::Plain),
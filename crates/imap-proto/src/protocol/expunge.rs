@@ -60,6 +60,35 @@ impl Vanished {
     }
 }
 
+// Vanished UIDs as inclusive ranges, avoids expanding large gaps into
+// individual UIDs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VanishedRanges {
+    pub earlier: bool,
+    pub ranges: Vec<(u32, u32)>,
+}
+
+impl VanishedRanges {
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        if self.earlier {
+            buf.extend_from_slice(b"* VANISHED (EARLIER) ");
+        } else {
+            buf.extend_from_slice(b"* VANISHED ");
+        }
+        for (pos, (start, end)) in self.ranges.iter().enumerate() {
+            if pos > 0 {
+                buf.push(b',');
+            }
+            buf.extend_from_slice(start.to_string().as_bytes());
+            if end != start {
+                buf.push(b':');
+                buf.extend_from_slice(end.to_string().as_bytes());
+            }
+        }
+        buf.extend_from_slice(b"\r\n");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::protocol::ImapResponse;
@@ -107,5 +136,16 @@ mod tests {
             .unwrap(),
             "* VANISHED 3:5\r\n"
         );
+
+        let mut buf = Vec::new();
+        super::VanishedRanges {
+            earlier: true,
+            ranges: vec![(1, 1), (3, 4_000_000_000), (4_000_000_002, 4_000_000_002)],
+        }
+        .serialize(&mut buf);
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "* VANISHED (EARLIER) 1,3:4000000000,4000000002\r\n"
+        );
     }
 }
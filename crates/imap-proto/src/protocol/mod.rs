@@ -10,13 +10,14 @@ use chrono::{DateTime, Utc};
 use compact_str::CompactString;
 use std::{cmp::Ordering, fmt::Display};
 use types::id::Id;
-use types::keyword::{ArchivedKeyword, Keyword};
+use types::keyword::{ArchivedKeyword, Keyword, escape_keyword};
 use utils::chained_bytes::SliceRange;
 
 pub mod acl;
 pub mod append;
 pub mod authenticate;
 pub mod capability;
+pub mod compress;
 pub mod copy_move;
 pub mod create;
 pub mod delete;
@@ -34,6 +35,7 @@ pub mod status;
 pub mod store;
 pub mod subscribe;
 pub mod thread;
+pub mod urlauth;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProtocolVersion {
@@ -140,6 +142,41 @@ impl Sequence {
         }
     }
 
+    // Sorted and merged inclusive ranges covered by the sequence set
+    pub fn ranges(&self, max_value: u32) -> Vec<(u32, u32)> {
+        let mut ranges = Vec::new();
+        self.push_ranges(max_value, &mut ranges);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(u32, u32)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => {
+                    last.1 = last.1.max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+
+    fn push_ranges(&self, max_value: u32, ranges: &mut Vec<(u32, u32)>) {
+        match self {
+            Sequence::Number { value } => ranges.push((*value, *value)),
+            Sequence::Range { start, end } => {
+                let start = start.unwrap_or(max_value);
+                let end = end.unwrap_or(max_value);
+                ranges.push((start.min(end), start.max(end)));
+            }
+            Sequence::List { items } => {
+                for item in items {
+                    item.push_ranges(max_value, ranges);
+                }
+            }
+            Sequence::SavedSearch => {}
+        }
+    }
+
     pub fn expand(&self, max_value: u32) -> AHashSet<u32> {
         match self {
             Sequence::Number { value } => AHashSet::from_iter([*value]),
@@ -338,7 +375,10 @@ impl Flag {
             Flag::New => b"$new",
             Flag::Notify => b"$notify",
             Flag::Unsubscribed => b"$unsubscribed",
-            Flag::Keyword(keyword) => keyword.as_bytes(),
+            Flag::Keyword(keyword) => {
+                buf.extend_from_slice(escape_keyword(keyword, false).as_bytes());
+                return;
+            }
         });
     }
 }
@@ -530,6 +570,7 @@ impl ResponseCode {
                 return;
             }
             ResponseCode::UseAttr => b"USEATTR",
+            ResponseCode::CompressionActive => b"COMPRESSIONACTIVE",
         });
     }
 
@@ -573,6 +614,7 @@ impl ResponseCode {
             ResponseCode::ObjectId { .. } => "OBJECTID",
             ResponseCode::HighestModseq { .. } => "HIGHESTMODSEQ",
             ResponseCode::UseAttr => "USEATTR",
+            ResponseCode::CompressionActive => "COMPRESSIONACTIVE",
         }
     }
 }
@@ -656,6 +698,9 @@ impl SerializeResponse for trc::Error {
                     Some(ResponseCode::OverQuota.as_str())
                 }
                 trc::EventType::Limit(_) => Some(ResponseCode::Limit.as_str()),
+                trc::EventType::Auth(trc::AuthEvent::ProtocolDisabled) => {
+                    Some(ResponseCode::AuthorizationFailed.as_str())
+                }
                 trc::EventType::Auth(_) => Some(ResponseCode::AuthenticationFailed.as_str()),
                 trc::EventType::Security(_) => Some(ResponseCode::AuthorizationFailed.as_str()),
                 _ => None,
@@ -764,7 +809,11 @@ impl Display for Command {
             Command::Id => write!(f, "ID"),
             Command::GetQuota => write!(f, "GETQUOTA"),
             Command::GetQuotaRoot => write!(f, "GETQUOTAROOT"),
+            Command::SetQuota => write!(f, "SETQUOTA"),
             Command::GetJmapAccess => write!(f, "GETJMAPACCESS"),
+            Command::GenUrlAuth => write!(f, "GENURLAUTH"),
+            Command::ResetKey => write!(f, "RESETKEY"),
+            Command::Compress => write!(f, "COMPRESS"),
         }
     }
 }
@@ -835,4 +884,21 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn sequence_set_ranges() {
+        for (sequence, expected_result, max_value) in [
+            ("1,5:10", vec![(1, 1), (5, 10)], 10),
+            ("12:*,2,4:7,5:9", vec![(2, 2), (4, 9), (12, 15)], 15),
+            ("*:4,3,1:2", vec![(1, 7)], 7),
+            ("4:2,*", vec![(2, 4), (9, 9)], 9),
+        ] {
+            assert_eq!(
+                parse_sequence_set(sequence.as_bytes())
+                    .unwrap()
+                    .ranges(max_value),
+                expected_result
+            );
+        }
+    }
 }
@@ -12,6 +12,13 @@ pub struct Arguments {
     pub name: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetArguments {
+    pub tag: String,
+    pub name: String,
+    pub limits: Vec<(QuotaResourceName, u64)>,
+}
+
 pub struct QuotaItem {
     pub name: String,
     pub resources: Vec<QuotaResource>,
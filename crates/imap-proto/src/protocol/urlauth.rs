@@ -0,0 +1,68 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{ImapResponse, quoted_string};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GenArguments {
+    pub tag: String,
+    pub urls: Vec<UrlRump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlRump {
+    pub url: String,
+    pub mechanism: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetArguments {
+    pub tag: String,
+    pub mailbox_name: Option<String>,
+    pub mechanisms: Vec<String>,
+}
+
+pub struct GenResponse {
+    pub urls: Vec<String>,
+}
+
+impl ImapResponse for GenResponse {
+    fn serialize(self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(64);
+        buf.extend_from_slice(b"* GENURLAUTH");
+        for url in &self.urls {
+            buf.push(b' ');
+            quoted_string(&mut buf, url);
+        }
+        buf.extend_from_slice(b"\r\n");
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::ImapResponse;
+
+    #[test]
+    fn serialize_genurlauth() {
+        assert_eq!(
+            String::from_utf8(
+                super::GenResponse {
+                    urls: vec![
+                        "imap://joe@example.com/INBOX/;uid=20;urlauth=submit+joe:internal:91354a47"
+                            .into()
+                    ],
+                }
+                .serialize()
+            )
+            .unwrap(),
+            concat!(
+                "* GENURLAUTH \"imap://joe@example.com/INBOX/;uid=20;",
+                "urlauth=submit+joe:internal:91354a47\"\r\n"
+            )
+        );
+    }
+}
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use std::{iter::Peekable, sync::Arc, vec::IntoIter};
+use std::{iter::Peekable, sync::Arc, time::Instant, vec::IntoIter};
 
 use common::{
     KV_RATE_LIMIT_IMAP,
@@ -93,6 +93,8 @@ impl<T: SessionStream> Session<T> {
 
         let mut requests = requests.into_iter().peekable();
         while let Some(request) = requests.next() {
+            let command = request.command;
+            let op_start = Instant::now();
             let result = match request.command {
                 Command::List | Command::Lsub => self
                     .handle_list(request)
@@ -240,6 +242,10 @@ impl<T: SessionStream> Session<T> {
                     .handle_get_quota_root(request)
                     .await
                     .map(|_| SessionResult::Continue),
+                Command::SetQuota => self
+                    .handle_set_quota(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
                 Command::Unauthenticate => self
                     .handle_unauthenticate(request)
                     .await
@@ -252,12 +258,29 @@ impl<T: SessionStream> Session<T> {
                     .handle_jmap_access(request)
                     .await
                     .map(|_| SessionResult::Continue),
+                Command::GenUrlAuth => self
+                    .handle_genurlauth(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
+                Command::ResetKey => self
+                    .handle_resetkey(request)
+                    .await
+                    .map(|_| SessionResult::Continue),
+                Command::Compress => {
+                    let is_pipelined = requests.peek().is_some()
+                        || needs_literal.is_some()
+                        || self.receiver.state != self.receiver.start_state;
+                    self.handle_compress(request, is_pipelined).await
+                }
             };
 
             match result {
                 Ok(SessionResult::Continue) => (),
                 Ok(result) => return result,
                 Err(err) => {
+                    if let Some(event) = crate::op::command_event(&command) {
+                        crate::op::record_command_error(event, &err, op_start);
+                    }
                     if !self.write_error(err).await {
                         return SessionResult::Close;
                     }
@@ -318,7 +341,12 @@ impl<T: SessionStream> Session<T> {
         match &request.command {
             Command::Capability | Command::Noop | Command::Logout | Command::Id => Ok(request),
             Command::StartTls => {
-                if !self.is_tls {
+                if self.is_compressed {
+                    Err(trc::ImapEvent::Error
+                        .into_err()
+                        .details("STARTTLS is not allowed after COMPRESS.")
+                        .id(request.tag))
+                } else if !self.is_tls {
                     if self.instance.acceptor.is_tls() {
                         Ok(request)
                     } else {
@@ -380,16 +408,30 @@ impl<T: SessionStream> Session<T> {
             | Command::GetAcl
             | Command::ListRights
             | Command::MyRights
-            | Command::Unauthenticate
             | Command::GetQuota
             | Command::GetQuotaRoot
-            | Command::GetJmapAccess => {
+            | Command::SetQuota
+            | Command::GetJmapAccess
+            | Command::GenUrlAuth
+            | Command::ResetKey
+            | Command::Compress => {
+                if let State::Authenticated { .. } | State::Selected { .. } = state {
+                    Ok(request)
+                } else {
+                    Err(trc::ImapEvent::Error
+                        .into_err()
+                        .details("Not authenticated.")
+                        .id(request.tag))
+                }
+            }
+            Command::Unauthenticate => {
                 if let State::Authenticated { .. } | State::Selected { .. } = state {
                     Ok(request)
                 } else {
                     Err(trc::ImapEvent::Error
                         .into_err()
                         .details("Not authenticated.")
+                        .ctx(trc::Key::Type, ResponseType::Bad)
                         .id(request.tag))
                 }
             }
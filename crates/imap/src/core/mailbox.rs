@@ -9,7 +9,7 @@ use crate::core::Mailbox;
 use ahash::AHashMap;
 use common::{
     auth::AccessToken,
-    network::{SessionStream, limiter::InFlight},
+    network::{SessionStream, limiter::InFlight, sessions::SessionLease},
     sharing::EffectiveAcl,
 };
 use email::{
@@ -31,6 +31,7 @@ impl<T: SessionStream> SessionData<T> {
         session: &Session<T>,
         access_token: AccessToken,
         in_flight: Option<InFlight>,
+        session_lease: SessionLease,
     ) -> trc::Result<Self> {
         let mut session = SessionData {
             stream_tx: session.stream_tx.clone(),
@@ -42,6 +43,8 @@ impl<T: SessionStream> SessionData<T> {
             remote_addr: session.remote_addr,
             access_token,
             in_flight,
+            session_lease,
+            is_detached: false.into(),
         };
 
         // Fetch mailboxes for the main account
@@ -8,17 +8,27 @@ use ahash::AHashMap;
 use common::{
     Inner, Server,
     auth::AccessToken,
-    network::{ServerInstance, SessionStream, limiter::InFlight},
+    network::{
+        ServerInstance, SessionStream,
+        limiter::InFlight,
+        sessions::{SessionLease, SessionSignal},
+    },
+    telemetry::audit::AuditSource,
 };
 use imap_proto::{
     Command,
     protocol::{ProtocolVersion, list::Attribute},
     receiver::Receiver,
 };
+use registry::schema::enums::ServiceProtocol;
 use std::{
     collections::BTreeMap,
     net::IpAddr,
-    sync::{Arc, atomic::AtomicU32},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU32},
+    },
+    time::Instant,
 };
 use tokio::{
     io::{ReadHalf, WriteHalf},
@@ -49,6 +59,7 @@ pub struct Session<T: SessionStream> {
     pub version: ProtocolVersion,
     pub state: State<T>,
     pub is_tls: bool,
+    pub is_compressed: bool,
     pub is_condstore: bool,
     pub is_qresync: bool,
     pub is_utf8: bool,
@@ -70,12 +81,15 @@ pub struct SessionData<T: SessionStream> {
     pub state: AtomicU32,
     pub remote_addr: IpAddr,
     pub in_flight: Option<InFlight>,
+    pub session_lease: SessionLease,
+    pub is_detached: AtomicBool,
 }
 
 pub struct SelectedMailbox {
     pub id: MailboxId,
     pub state: parking_lot::Mutex<MailboxState>,
     pub saved_search: parking_lot::Mutex<SavedSearch>,
+    pub search_cache: parking_lot::Mutex<SearchCache>,
     pub is_select: bool,
     pub is_condstore: bool,
 }
@@ -148,6 +162,19 @@ pub enum SavedSearch {
     None,
 }
 
+#[derive(Default)]
+pub struct SearchCache {
+    pub entries: Vec<SearchCacheEntry>,
+}
+
+pub struct SearchCacheEntry {
+    pub key: String,
+    pub change_id: u64,
+    pub expires: Instant,
+    pub ids: Arc<Vec<u32>>,
+    pub include_highest_modseq: bool,
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ImapUidToId {
     pub uid: u32,
@@ -168,6 +195,15 @@ pub enum State<T: SessionStream> {
 }
 
 impl<T: SessionStream> State<T> {
+    pub fn session_signal(&self) -> SessionSignal {
+        match self {
+            State::Authenticated { data } | State::Selected { data, .. } => {
+                data.session_lease.signal()
+            }
+            State::NotAuthenticated { .. } => SessionSignal::default(),
+        }
+    }
+
     pub fn try_replace_stream_tx<U: SessionStream>(
         self,
         new_stream: Arc<tokio::sync::Mutex<WriteHalf<U>>>,
@@ -198,10 +234,19 @@ impl<T: SessionStream> SessionData<T> {
             .await
             .and_then(|inner| {
                 AccessToken::renew(inner, self.access_token.credential_id(), self.remote_addr)
+                    .map(|token| token.with_impersonator(self.access_token.impersonator_id()))
             })
             .caused_by(trc::location!())
     }
 
+    pub fn audit_source(&self) -> AuditSource<'_> {
+        AuditSource {
+            access_token: &self.access_token,
+            protocol: ServiceProtocol::Imap,
+            remote_ip: Some(self.remote_addr),
+        }
+    }
+
     pub fn replace_stream_tx<U: SessionStream>(
         self,
         new_stream: Arc<tokio::sync::Mutex<WriteHalf<U>>>,
@@ -214,8 +259,10 @@ impl<T: SessionStream> SessionData<T> {
             stream_tx: new_stream,
             state: self.state,
             in_flight: self.in_flight,
+            session_lease: self.session_lease,
             access_token: self.access_token,
             remote_addr: self.remote_addr,
+            is_detached: self.is_detached,
         }
     }
 }
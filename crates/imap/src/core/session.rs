@@ -8,13 +8,16 @@ use super::{ImapSessionManager, Session, State};
 use crate::{GREETING_WITH_TLS, GREETING_WITHOUT_TLS};
 use common::{
     BuildServer,
-    network::{SessionData, SessionManager, SessionResult, SessionStream, stream::NullIo},
+    network::{
+        SessionData, SessionManager, SessionResult, SessionStream, compress::DeflateStream,
+        stream::NullIo,
+    },
 };
 use imap_proto::{
     protocol::{ProtocolVersion, SerializeResponse},
     receiver::Receiver,
 };
-use std::sync::Arc;
+use std::sync::{Arc, atomic::Ordering};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_rustls::server::TlsStream;
 
@@ -25,12 +28,23 @@ impl SessionManager for ImapSessionManager {
         session: SessionData<T>,
     ) -> impl std::future::Future<Output = ()> + Send {
         async move {
-            if let Ok(mut session) = Session::new(session, self).await
-                && session.handle_conn().await
-                && session.instance.acceptor.is_tls()
-                && let Ok(mut session) = session.into_tls().await
-            {
-                session.handle_conn().await;
+            if let Ok(mut session) = Session::new(session, self).await {
+                match session.handle_conn().await {
+                    SessionResult::UpgradeTls if session.instance.acceptor.is_tls() => {
+                        if let Ok(mut session) = session.into_tls().await
+                            && session.handle_conn().await == SessionResult::UpgradeCompress
+                            && let Ok(mut session) = session.into_compressed()
+                        {
+                            session.handle_conn().await;
+                        }
+                    }
+                    SessionResult::UpgradeCompress => {
+                        if let Ok(mut session) = session.into_compressed() {
+                            session.handle_conn().await;
+                        }
+                    }
+                    _ => {}
+                }
             }
         }
     }
@@ -42,11 +56,13 @@ impl SessionManager for ImapSessionManager {
 }
 
 impl<T: SessionStream> Session<T> {
-    pub async fn handle_conn(&mut self) -> bool {
+    pub async fn handle_conn(&mut self) -> SessionResult {
         let mut buf = vec![0; 8192];
         let mut shutdown_rx = self.instance.shutdown_rx.clone();
 
         loop {
+            let mut session_signal = self.state.session_signal();
+
             tokio::select! {
                 result = tokio::time::timeout(
                     if !matches!(self.state, State::NotAuthenticated {..}) {
@@ -60,12 +76,12 @@ impl<T: SessionStream> Session<T> {
                             if bytes_read > 0 {
                                 match self.ingest(&buf[..bytes_read]).await {
                                     SessionResult::Continue => (),
-                                    SessionResult::UpgradeTls => {
-                                        return true;
-                                    }
                                     SessionResult::Close => {
                                         break;
                                     }
+                                    result => {
+                                        return result;
+                                    }
                                 }
                             } else {
                                 trc::event!(
@@ -106,10 +122,20 @@ impl<T: SessionStream> Session<T> {
                     self.write_bytes(&b"* BYE Server shutting down.\r\n"[..]).await.ok();
                     break;
                 }
+                _ = session_signal.terminated() => {
+                    trc::event!(
+                        Network(trc::NetworkEvent::Closed),
+                        SpanId = self.session_id,
+                        Reason = "Session terminated by administrator",
+                        CausedBy = trc::location!()
+                    );
+                    self.write_bytes(&b"* BYE Session terminated by administrator.\r\n"[..]).await.ok();
+                    break;
+                }
             };
         }
 
-        false
+        SessionResult::Close
     }
 
     pub async fn new(
@@ -144,6 +170,7 @@ impl<T: SessionStream> Session<T> {
             version: ProtocolVersion::Rev1,
             state: State::NotAuthenticated { auth_failures: 0 },
             is_tls,
+            is_compressed: false,
             is_condstore: false,
             is_qresync: false,
             is_utf8: false,
@@ -202,6 +229,66 @@ impl<T: SessionStream> Session<T> {
             version: self.version,
             state: state.try_replace_stream_tx(stream_tx.clone()).unwrap(),
             is_tls: true,
+            is_compressed: self.is_compressed,
+            is_condstore: self.is_condstore,
+            is_qresync: self.is_qresync,
+            is_utf8: self.is_utf8,
+            is_objectid: self.is_objectid,
+            session_id: self.session_id,
+            in_flight: self.in_flight,
+            remote_addr: self.remote_addr,
+            stream_rx,
+            stream_tx,
+        })
+    }
+
+    pub fn into_compressed(self) -> Result<Session<DeflateStream<T>>, ()> {
+        // Drop references to write half from state
+        let state = if let Some(state) =
+            self.state
+                .try_replace_stream_tx(Arc::new(tokio::sync::Mutex::new(
+                    tokio::io::split(NullIo::default()).1,
+                ))) {
+            state
+        } else {
+            trc::event!(
+                Network(trc::NetworkEvent::SplitError),
+                SpanId = self.session_id,
+                Details = "Failed to obtain write half state"
+            );
+            return Err(());
+        };
+
+        // Take ownership of WriteHalf and unsplit it from ReadHalf
+        let stream = if let Ok(stream_tx) =
+            Arc::try_unwrap(self.stream_tx).map(|mutex| mutex.into_inner())
+        {
+            self.stream_rx.unsplit(stream_tx)
+        } else {
+            trc::event!(
+                Network(trc::NetworkEvent::SplitError),
+                SpanId = self.session_id,
+                Details = "Failed to take ownership of write half"
+            );
+
+            return Err(());
+        };
+
+        // Wrap the stream, everything from now on is compressed
+        let (stream_rx, stream_tx) = tokio::io::split(DeflateStream::new(
+            stream,
+            self.server.core.imap.max_request_size,
+        ));
+        let stream_tx = Arc::new(tokio::sync::Mutex::new(stream_tx));
+
+        Ok(Session {
+            server: self.server,
+            instance: self.instance,
+            receiver: self.receiver,
+            version: self.version,
+            state: state.try_replace_stream_tx(stream_tx.clone()).unwrap(),
+            is_tls: self.is_tls,
+            is_compressed: true,
             is_condstore: self.is_condstore,
             is_qresync: self.is_qresync,
             is_utf8: self.is_utf8,
@@ -269,6 +356,12 @@ impl<T: SessionStream> Session<T> {
 
 impl<T: SessionStream> super::SessionData<T> {
     pub async fn write_bytes(&self, bytes: impl AsRef<[u8]>) -> trc::Result<()> {
+        // Operations still running after UNAUTHENTICATE must not leak
+        // responses to the next user of the connection
+        if self.is_detached.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
         let bytes = bytes.as_ref();
 
         trc::event!(
@@ -21,7 +21,7 @@ use imap_proto::{
     },
     receiver::Request,
 };
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission};
 use std::time::Instant;
 use store::{
     ValueKey,
@@ -31,6 +31,7 @@ use trc::AddContext;
 use types::{
     acl::{Acl, AclGrant},
     collection::Collection,
+    id::Id,
 };
 use utils::map::bitmap::Bitmap;
 
@@ -44,7 +45,7 @@ impl<T: SessionStream> Session<T> {
         let is_utf8 = self.version.is_rev2() || self.is_utf8;
         let data = self.state.session_data();
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::GetAcl, {
             let (mailbox_id, mailbox_, _) = data
                 .get_acl_mailbox(&arguments, true)
                 .await
@@ -173,7 +174,7 @@ impl<T: SessionStream> Session<T> {
         let data = self.state.session_data();
         let is_utf8 = self.version.is_rev2() || self.is_utf8;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::MyRights, {
             let (mailbox_id, mailbox_, access_token) = data
                 .get_acl_mailbox(&arguments, false)
                 .await
@@ -181,11 +182,13 @@ impl<T: SessionStream> Session<T> {
             let mailbox = mailbox_
                 .to_unarchived::<email::mailbox::Mailbox>()
                 .imap_ctx(&arguments.tag, trc::location!())?;
-            let rights = if access_token.is_shared(mailbox_id.account_id) {
+            let rights = if !access_token.is_member(mailbox_id.account_id) {
                 let acl = mailbox.inner.acls.effective_acl(&access_token);
                 let mut rights = Vec::with_capacity(5);
                 if acl.contains(Acl::ReadItems) {
                     rights.push(Rights::Read);
+                }
+                if acl.contains(Acl::Read) {
                     rights.push(Rights::Lookup);
                 }
                 if acl.contains(Acl::AddItems) {
@@ -208,6 +211,9 @@ impl<T: SessionStream> Session<T> {
                 if acl.contains(Acl::Submit) {
                     rights.push(Rights::Post);
                 }
+                if acl.contains(Acl::Share) {
+                    rights.push(Rights::Administer);
+                }
                 rights
             } else {
                 vec![
@@ -262,7 +268,7 @@ impl<T: SessionStream> Session<T> {
         let arguments = request.parse_acl(self.is_utf8)?;
         let data = self.state.session_data();
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::SetAcl, {
             // Validate mailbox
             let (mailbox_id, current_mailbox, _) = data
                 .get_acl_mailbox(&arguments, true)
@@ -388,6 +394,15 @@ impl<T: SessionStream> Session<T> {
                 Elapsed = op_start.elapsed()
             );
 
+            data.server
+                .audit(
+                    data.audit_source(),
+                    mailbox_id.account_id,
+                    AuditEventType::AclChange,
+                    [Id::from(mailbox_id.mailbox_id)],
+                )
+                .await;
+
             data.write_bytes(
                 StatusResponse::completed(command)
                     .with_tag(arguments.tag)
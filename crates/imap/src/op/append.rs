@@ -51,7 +51,7 @@ impl<T: SessionStream> Session<T> {
         };
         let is_qresync = self.is_qresync;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::Append, {
             let response = data
                 .append_messages(arguments, selected_mailbox, mailbox, is_qresync, op_start)
                 .await?
@@ -105,6 +105,11 @@ impl<T: SessionStream> SessionData<T> {
         let mut response = StatusResponse::completed(Command::Append);
         let mut created_ids = Vec::with_capacity(arguments.messages.len());
         let mut last_change_id = None;
+        let total_bytes = arguments
+            .messages
+            .iter()
+            .map(|message| message.message.len())
+            .sum::<usize>();
         for message in arguments.messages {
             match self
                 .server
@@ -170,6 +175,7 @@ impl<T: SessionStream> SessionData<T> {
                 .iter()
                 .map(|r| trc::Value::from(r.id))
                 .collect::<Vec<_>>(),
+            Size = total_bytes,
             Elapsed = op_start.elapsed()
         );
 
@@ -12,12 +12,12 @@ use common::{
 use directory::Credentials;
 use imap_proto::{
     Command, ResponseCode, StatusResponse,
-    protocol::{authenticate::Mechanism, capability::Capability},
+    protocol::{ProtocolVersion, authenticate::Mechanism, capability::Capability},
     receiver::{self, Request},
 };
 use mail_parser::decoders::base64::base64_decode;
-use registry::schema::enums::Permission;
-use std::sync::Arc;
+use registry::schema::enums::{Permission, ServiceProtocol};
+use std::sync::{Arc, atomic::Ordering};
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_authenticate(&mut self, request: Request<Command>) -> trc::Result<()> {
@@ -74,6 +74,7 @@ impl<T: SessionStream> Session<T> {
                 credentials,
                 self.session_id,
                 self.remote_addr,
+                ServiceProtocol::Imap,
             ))
             .await
             .map_err(|err| {
@@ -90,7 +91,12 @@ impl<T: SessionStream> Session<T> {
 
                 err.id(tag.clone())
             })
-            .and_then(|token| token.assert_has_permission(Permission::ImapAuthenticate))?;
+            .and_then(|token| token.assert_has_permission(Permission::ImapAuthenticate))
+            .and_then(|token| {
+                token
+                    .assert_protocol_enabled(ServiceProtocol::Imap)
+                    .map_err(|err| err.id(tag.clone()))
+            })?;
 
         // Enforce concurrency limits
         let in_flight = match access_token.is_imap_request_allowed() {
@@ -103,10 +109,22 @@ impl<T: SessionStream> Session<T> {
             LimiterResult::Disabled => None,
         };
 
+        // Enforce session limits
+        let session_lease = self
+            .server
+            .acquire_session(
+                &access_token,
+                ServiceProtocol::Imap,
+                self.session_id,
+                self.remote_addr,
+            )
+            .await
+            .map_err(|err| err.id(tag.clone()))?;
+
         // Create session
         self.state = State::Authenticated {
             data: Arc::new(
-                SessionData::new(self, access_token, in_flight)
+                SessionData::new(self, access_token, in_flight, session_lease)
                     .await
                     .map_err(|err| err.id(tag.clone()))?,
             ),
@@ -116,7 +134,8 @@ impl<T: SessionStream> Session<T> {
                 .with_code(ResponseCode::Capability {
                     capabilities: Capability::all_capabilities(
                         true,
-                        !self.is_tls && self.instance.acceptor.is_tls(),
+                        !self.is_tls && !self.is_compressed && self.instance.acceptor.is_tls(),
+                        !self.is_compressed,
                     ),
                 })
                 .with_tag(tag)
@@ -126,10 +145,29 @@ impl<T: SessionStream> Session<T> {
     }
 
     pub async fn handle_unauthenticate(&mut self, request: Request<Command>) -> trc::Result<()> {
+        // Detach the account from any operations still running in the background
+        if let State::Authenticated { data } | State::Selected { data, .. } = &self.state {
+            data.is_detached.store(true, Ordering::Relaxed);
+        }
+
+        // Drop the selected mailbox, cached access token and any state
+        // negotiated with ENABLE (RFC 8437, section 3)
         self.state = State::NotAuthenticated { auth_failures: 0 };
+        self.version = ProtocolVersion::Rev1;
+        self.is_condstore = false;
+        self.is_qresync = false;
+        self.is_utf8 = false;
+        self.is_objectid = false;
 
         self.write_bytes(
             StatusResponse::completed(Command::Unauthenticate)
+                .with_code(ResponseCode::Capability {
+                    capabilities: Capability::all_capabilities(
+                        false,
+                        !self.is_tls && !self.is_compressed && self.instance.acceptor.is_tls(),
+                        false,
+                    ),
+                })
                 .with_tag(request.tag)
                 .into_bytes(),
         )
@@ -0,0 +1,58 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::core::Session;
+use common::network::{SessionResult, SessionStream};
+use imap_proto::{
+    Command, ResponseCode, ResponseType, StatusResponse, protocol::compress::Algorithm,
+    receiver::Request,
+};
+use std::time::Instant;
+
+impl<T: SessionStream> Session<T> {
+    pub async fn handle_compress(
+        &mut self,
+        request: Request<Command>,
+        is_pipelined: bool,
+    ) -> trc::Result<SessionResult> {
+        let op_start = Instant::now();
+        let arguments = request.parse_compress()?;
+
+        if self.is_compressed {
+            return Err(trc::ImapEvent::Error
+                .into_err()
+                .details("Compression is already active.")
+                .code(ResponseCode::CompressionActive)
+                .id(arguments.tag));
+        } else if is_pipelined {
+            // Anything received after the command would have to be
+            // decompressed, so the client must wait for the response
+            return Err(trc::ImapEvent::Error
+                .into_err()
+                .details("COMPRESS cannot be pipelined.")
+                .ctx(trc::Key::Type, ResponseType::Bad)
+                .id(arguments.tag));
+        }
+
+        match arguments.algorithm {
+            Algorithm::Deflate => {
+                trc::event!(
+                    Imap(trc::ImapEvent::Compress),
+                    SpanId = self.session_id,
+                    Elapsed = op_start.elapsed()
+                );
+
+                self.write_bytes(
+                    StatusResponse::ok("DEFLATE active")
+                        .with_tag(arguments.tag)
+                        .into_bytes(),
+                )
+                .await
+                .map(|_| SessionResult::UpgradeCompress)
+            }
+        }
+    }
+}
@@ -14,6 +14,7 @@ use email::{
     cache::{MessageCacheFetch, email::MessageCacheAccess},
     mailbox::{JUNK_ID, TRASH_ID, UidMailbox},
     message::{
+        category::EmailCategoryFnc,
         copy::{CopyMessageError, EmailCopy},
         ingest::EmailIngest,
         metadata::MessageData,
@@ -30,7 +31,6 @@ use store::{
     roaring::RoaringBitmap,
     write::{AlignedBytes, Archive, BatchBuilder},
 };
-use trc::AddContext;
 use types::{
     acl::Acl,
     collection::{Collection, VanishedCollection},
@@ -56,7 +56,13 @@ impl<T: SessionStream> Session<T> {
         let (data, src_mailbox) = self.state.mailbox_state();
         let is_qresync = self.is_qresync;
 
-        spawn_op!(data, {
+        let event = if is_move {
+            trc::ImapEvent::Move
+        } else {
+            trc::ImapEvent::Copy
+        };
+
+        spawn_op!(data, event, {
             // Refresh mailboxes
             data.synchronize_mailboxes(false)
                 .await
@@ -197,10 +203,11 @@ impl<T: SessionStream> SessionData<T> {
         let mut copied_ids = Vec::with_capacity(ids.len());
 
         if src_mailbox.id.account_id == dest_mailbox.account_id {
-            // Mailboxes are in the same account
+            // Mailboxes are in the same account, only the mailbox membership
+            // is updated while the blob, search index and thread ids are preserved
             let account_id = src_mailbox.id.account_id;
-            let dest_mailbox_id = UidMailbox::new_unassigned(dest_mailbox_id);
             let mut batch = BatchBuilder::new();
+            let mut pending = Vec::with_capacity(ids.len());
 
             for (id, imap_id) in ids {
                 // Obtain mailbox tags
@@ -234,7 +241,7 @@ impl<T: SessionStream> SessionData<T> {
                     .inner
                     .mailboxes
                     .iter()
-                    .find(|mailbox| mailbox.mailbox_id == dest_mailbox_id.mailbox_id)
+                    .find(|mailbox| mailbox.mailbox_id == dest_mailbox_id)
                 {
                     copied_ids.push((imap_id.uid, mailbox.uid.to_native()));
 
@@ -262,38 +269,37 @@ impl<T: SessionStream> SessionData<T> {
                     continue;
                 }
 
+                pending.push((id, imap_id, data_));
+            }
+
+            // Assign IMAP UIDs in the destination mailbox
+            let uids = self
+                .server
+                .assign_mailbox_uids(account_id, dest_mailbox_id, pending.len() as u32)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?;
+
+            for ((id, imap_id, data_), uid) in pending.into_iter().zip(uids) {
+                let data = data_
+                    .to_unarchived::<MessageData>()
+                    .imap_ctx(&arguments.tag, trc::location!())?;
+
                 // Prepare changes
                 let mut new_data = data.inner.to_builder();
 
                 // Add destination folder
-                new_data.add_mailbox(dest_mailbox_id);
+                new_data.add_mailbox(UidMailbox::new(dest_mailbox_id, uid));
                 if is_move {
                     new_data.remove_mailbox(src_mailbox.id.mailbox_id);
                 }
+                copied_ids.push((imap_id.uid, uid));
 
-                // Assign IMAP UIDs
-                let ids = self
-                    .server
-                    .assign_email_ids(
-                        account_id,
-                        new_data
-                            .mailboxes
-                            .iter()
-                            .filter(|m| m.uid == 0)
-                            .map(|m| m.mailbox_id),
-                        false,
-                    )
-                    .await
-                    .caused_by(trc::location!())?;
-
-                for (uid_mailbox, uid) in new_data
-                    .mailboxes
-                    .iter_mut()
-                    .filter(|m| m.uid == 0)
-                    .zip(ids)
-                {
-                    copied_ids.push((imap_id.uid, uid));
-                    uid_mailbox.uid = uid;
+                // Learn the preferred category of senders from messages moved out of a category
+                if is_move {
+                    self.server
+                        .email_category_feedback(account_id, id, data.inner, &new_data)
+                        .await
+                        .imap_ctx(&arguments.tag, trc::location!())?;
                 }
 
                 // Prepare write batch
@@ -315,14 +321,12 @@ impl<T: SessionStream> SessionData<T> {
                 }
 
                 // Add message to training queue
-                if dest_mailbox_id.mailbox_id == JUNK_ID {
+                if dest_mailbox_id == JUNK_ID {
                     self.server
                         .add_account_spam_sample(&mut batch, account_id, id, true, self.session_id)
                         .await
                         .imap_ctx(&arguments.tag, trc::location!())?;
-                } else if src_mailbox.id.mailbox_id == JUNK_ID
-                    && dest_mailbox_id.mailbox_id != TRASH_ID
-                {
+                } else if src_mailbox.id.mailbox_id == JUNK_ID && dest_mailbox_id != TRASH_ID {
                     self.server
                         .add_account_spam_sample(&mut batch, account_id, id, false, self.session_id)
                         .await
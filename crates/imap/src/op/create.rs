@@ -6,7 +6,7 @@
 
 use crate::{
     core::{Session, SessionData},
-    op::ImapContext,
+    op::{ImapContext, record_command_error},
     spawn_op,
 };
 use common::{network::SessionStream, storage::index::ObjectIndexBuilder};
@@ -31,18 +31,23 @@ impl<T: SessionStream> Session<T> {
         let is_utf8 = self.is_utf8;
         let is_objectid = self.is_objectid;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::CreateMailbox, {
             for request in requests {
+                let op_start = Instant::now();
                 match request.parse_create(is_utf8) {
                     Ok(argument) => match data.create_folder(argument, is_objectid).await {
                         Ok(response) => {
                             data.write_bytes(response.into_bytes()).await?;
                         }
                         Err(error) => {
+                            record_command_error(trc::ImapEvent::CreateMailbox, &error, op_start);
                             data.write_error(error).await?;
                         }
                     },
-                    Err(err) => data.write_error(err).await?,
+                    Err(err) => {
+                        record_command_error(trc::ImapEvent::CreateMailbox, &err, op_start);
+                        data.write_error(err).await?;
+                    }
                 }
             }
 
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::ImapContext;
+use super::{ImapContext, record_command_error};
 use crate::{
     core::{Session, SessionData},
     spawn_op,
@@ -14,8 +14,9 @@ use email::mailbox::destroy::{MailboxDestroy, MailboxDestroyError};
 use imap_proto::{
     Command, ResponseCode, StatusResponse, protocol::delete::Arguments, receiver::Request,
 };
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission};
 use std::time::Instant;
+use types::id::Id;
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_delete(&mut self, requests: Vec<Request<Command>>) -> trc::Result<()> {
@@ -25,18 +26,23 @@ impl<T: SessionStream> Session<T> {
         let data = self.state.session_data();
         let is_utf8 = self.is_utf8;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::DeleteMailbox, {
             for request in requests {
+                let op_start = Instant::now();
                 match request.parse_delete(is_utf8) {
                     Ok(argument) => match data.delete_folder(argument).await {
                         Ok(response) => {
                             data.write_bytes(response.into_bytes()).await?;
                         }
                         Err(error) => {
+                            record_command_error(trc::ImapEvent::DeleteMailbox, &error, op_start);
                             data.write_error(error).await?;
                         }
                     },
-                    Err(response) => data.write_error(response).await?,
+                    Err(response) => {
+                        record_command_error(trc::ImapEvent::DeleteMailbox, &response, op_start);
+                        data.write_error(response).await?;
+                    }
                 }
             }
 
@@ -122,6 +128,15 @@ impl<T: SessionStream> SessionData<T> {
             Elapsed = op_start.elapsed()
         );
 
+        self.server
+            .audit(
+                self.audit_source(),
+                account_id,
+                AuditEventType::MailboxDelete,
+                [Id::from(mailbox_id)],
+            )
+            .await;
+
         Ok(StatusResponse::ok("Mailbox deleted.").with_tag(arguments.tag))
     }
 }
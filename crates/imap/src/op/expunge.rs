@@ -18,7 +18,7 @@ use imap_proto::{
     receiver::{Request, Token},
 };
 use registry::schema::{
-    enums::{IndexDocumentType, Permission},
+    enums::{AuditEventType, IndexDocumentType, Permission},
     structs::{Task, TaskIndexDocument, TaskStatus},
 };
 use std::{sync::Arc, time::Instant};
@@ -27,6 +27,7 @@ use trc::AddContext;
 use types::{
     acl::Acl,
     collection::{Collection, VanishedCollection},
+    id::Id,
     keyword::Keyword,
 };
 
@@ -161,6 +162,17 @@ impl<T: SessionStream> SessionData<T> {
             self.server.notify_task_queue();
         }
 
+        if !deleted_ids.is_empty() {
+            self.server
+                .audit(
+                    self.audit_source(),
+                    account_id,
+                    AuditEventType::MessageDelete,
+                    deleted_ids.iter().map(Id::from),
+                )
+                .await;
+        }
+
         Ok(())
     }
 
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{FromModSeq, ImapContext};
+use super::{FromModSeq, ImapContext, record_command_error};
 use crate::{
     core::{SelectedMailbox, Session, SessionData},
     spawn_op,
@@ -23,8 +23,8 @@ use imap_proto::{
     Command, ResponseCode, ResponseType, StatusResponse,
     parser::PushUnique,
     protocol::{
-        Flag, ObjectId,
-        expunge::Vanished,
+        Flag, ObjectId, Sequence,
+        expunge::{Vanished, VanishedRanges},
         fetch::{
             self, Arguments, Attribute, BodyContents, BodyPart, BodyPartExtension, BodyPartFields,
             DataItem, Envelope, FetchItem, Section,
@@ -32,7 +32,7 @@ use imap_proto::{
     },
     receiver::Request,
 };
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission};
 use std::{borrow::Cow, sync::Arc, time::Instant};
 use store::{
     ValueKey,
@@ -64,6 +64,7 @@ impl<T: SessionStream> Session<T> {
         let mut activate_objectid = false;
 
         for request in requests {
+            let op_start = Instant::now();
             let is_uid = matches!(request.command, Command::Fetch(true));
             match request.parse_fetch() {
                 Ok(arguments) => {
@@ -81,10 +82,10 @@ impl<T: SessionStream> Session<T> {
                         activate_objectid = true;
                     }
 
-                    ops.push(Ok((is_uid, enabled_condstore, arguments)));
+                    ops.push(Ok((is_uid, enabled_condstore, arguments, op_start)));
                 }
                 Err(err) => {
-                    ops.push(Err(err));
+                    ops.push(Err((err, op_start)));
                 }
             }
         }
@@ -93,10 +94,10 @@ impl<T: SessionStream> Session<T> {
             self.write_bytes(enabled).await?;
         }
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::Fetch, {
             for op in ops {
                 match op {
-                    Ok((is_uid, enabled_condstore, arguments)) => {
+                    Ok((is_uid, enabled_condstore, arguments, op_start)) => {
                         let response = data
                             .fetch(
                                 arguments,
@@ -104,13 +105,16 @@ impl<T: SessionStream> Session<T> {
                                 is_uid,
                                 is_qresync,
                                 enabled_condstore,
-                                Instant::now(),
+                                op_start,
                             )
                             .await?;
 
                         data.write_bytes(response.into_bytes()).await?;
                     }
-                    Err(err) => data.write_error(err).await?,
+                    Err((err, op_start)) => {
+                        record_command_error(trc::ImapEvent::Fetch, &err, op_start);
+                        data.write_error(err).await?;
+                    }
                 }
             }
 
@@ -163,6 +167,9 @@ impl<T: SessionStream> SessionData<T> {
         // Convert state to modseq
         if let Some(changed_since) = arguments.changed_since {
             // Obtain changes since the modseq.
+            self.server
+                .change_floor_seen(account_id, changed_since.saturating_sub(1))
+                .await;
             let changelog = self
                 .server
                 .store()
@@ -174,9 +181,15 @@ impl<T: SessionStream> SessionData<T> {
                 .await
                 .imap_ctx(&arguments.tag, trc::location!())?;
 
-            // Process changes
-            let mut changed_ids = AHashMap::new();
-            let mut has_vanished = false;
+            // Process changes, the client has to resync every message when the
+            // change log was pruned past its modseq
+            let is_truncated = changelog.is_truncated;
+            let mut changed_ids = if is_truncated {
+                ids.clone()
+            } else {
+                AHashMap::new()
+            };
+            let mut has_vanished = is_truncated;
 
             for change in changelog.changes {
                 match change {
@@ -198,34 +211,53 @@ impl<T: SessionStream> SessionData<T> {
 
             // Send vanished UIDs
             if arguments.include_vanished && has_vanished {
-                // Add to vanished all known destroyed Ids
-                let vanished = self
-                    .server
-                    .store()
-                    .vanished::<(u32, u32)>(
-                        account_id,
-                        VanishedCollection::Email.into(),
-                        Query::from_modseq(changed_since),
-                    )
-                    .await
-                    .imap_ctx(&arguments.tag, trc::location!())?
-                    .into_iter()
-                    .filter_map(|(mailbox_id, uid)| {
-                        if mailbox.id.mailbox_id == mailbox_id {
-                            Some(uid)
-                        } else {
-                            None
+                let mut buf = Vec::new();
+                if is_truncated {
+                    // The vanished log was pruned along with the change log, report
+                    // every UID in the set that is no longer in the mailbox
+                    let state = mailbox.state.lock();
+                    let mut uids = state.uid_to_id.keys().copied().collect::<Vec<_>>();
+                    uids.sort_unstable();
+                    let ranges = missing_uid_ranges(&uids, state.uid_max, &arguments.sequence_set);
+                    if !ranges.is_empty() {
+                        VanishedRanges {
+                            earlier: true,
+                            ranges,
                         }
-                    })
-                    .collect::<Vec<_>>();
-
-                if !vanished.is_empty() {
-                    let mut buf = Vec::with_capacity(vanished.len() * 3);
-                    Vanished {
-                        earlier: true,
-                        ids: vanished,
+                        .serialize(&mut buf);
+                    }
+                } else {
+                    // Add to vanished all known destroyed Ids
+                    let vanished = self
+                        .server
+                        .store()
+                        .vanished::<(u32, u32)>(
+                            account_id,
+                            VanishedCollection::Email.into(),
+                            Query::from_modseq(changed_since),
+                        )
+                        .await
+                        .imap_ctx(&arguments.tag, trc::location!())?
+                        .into_iter()
+                        .filter_map(|(mailbox_id, uid)| {
+                            if mailbox.id.mailbox_id == mailbox_id {
+                                Some(uid)
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    if !vanished.is_empty() {
+                        buf.reserve(vanished.len() * 3);
+                        Vanished {
+                            earlier: true,
+                            ids: vanished,
+                        }
+                        .serialize(&mut buf);
                     }
-                    .serialize(&mut buf);
+                }
+
+                if !buf.is_empty() {
                     self.write_bytes(buf).await?;
                 }
             }
@@ -331,6 +363,8 @@ impl<T: SessionStream> SessionData<T> {
             .get_cached_messages(account_id)
             .await
             .imap_ctx(&arguments.tag, trc::location!())?;
+        let mut total_bytes = 0;
+        let mut read_ids = Vec::new();
 
         for (seqnum, uid, id) in ids {
             // Obtain attributes and keywords
@@ -394,6 +428,8 @@ impl<T: SessionStream> SessionData<T> {
 
                     continue;
                 }
+
+                read_ids.push(Id::from(id));
             }
 
             let message = &metadata.contents[0];
@@ -550,6 +586,7 @@ impl<T: SessionStream> SessionData<T> {
             // Serialize fetch item
             let mut buf = Vec::with_capacity(128);
             FetchItem { id: seqnum, items }.serialize(&mut buf);
+            total_bytes += buf.len();
             self.write_bytes(buf).await?;
 
             // Add to set flags
@@ -616,9 +653,21 @@ impl<T: SessionStream> SessionData<T> {
                 .iter()
                 .map(|c| trc::Value::from(format!("{c:?}")))
                 .collect::<Vec<_>>(),
+            Size = total_bytes,
             Elapsed = op_start.elapsed()
         );
 
+        if !read_ids.is_empty() {
+            self.server
+                .audit(
+                    self.audit_source(),
+                    account_id,
+                    AuditEventType::MessageRead,
+                    read_ids,
+                )
+                .await;
+        }
+
         // Condstore was enabled with this command
         if enabled_condstore {
             self.write_bytes(
@@ -1242,3 +1291,30 @@ impl AsImapAddress for ArchivedMetadataHeaderValue {
         addresses
     }
 }
+
+// UIDs in the sequence set that are not in the mailbox, as inclusive ranges.
+// Only the UIDs present in the mailbox are visited, gaps are never expanded.
+fn missing_uid_ranges(uids: &[u32], uid_max: u32, sequence_set: &Sequence) -> Vec<(u32, u32)> {
+    let mut missing = Vec::new();
+    for (start, end) in sequence_set.ranges(uid_max) {
+        let (start, end) = (start.max(1) as u64, end.min(uid_max) as u64);
+        if start > end {
+            continue;
+        }
+        let mut next = start;
+        for &uid in &uids[uids.partition_point(|&uid| (uid as u64) < start)..] {
+            let uid = uid as u64;
+            if uid > end {
+                break;
+            }
+            if uid > next {
+                missing.push((next as u32, (uid - 1) as u32));
+            }
+            next = uid + 1;
+        }
+        if next <= end {
+            missing.push((next as u32, end as u32));
+        }
+    }
+    missing
+}
@@ -27,6 +27,8 @@ use trc::AddContext;
 use types::{collection::SyncCollection, type_state::DataType};
 use utils::map::bitmap::Bitmap;
 
+const FETCH_CHUNK_SIZE: usize = 100;
+
 impl<T: SessionStream> Session<T> {
     pub async fn handle_idle(&mut self, request: Request<Command>) -> trc::Result<()> {
         // Validate access
@@ -66,7 +68,12 @@ impl<T: SessionStream> Session<T> {
         );
 
         let op_start = Instant::now();
+        let notify_delay = self.server.core.imap.idle_notify_delay;
+        let mut notify_at = None;
+        let mut has_mailbox_changes = false;
+        let mut has_email_changes = false;
         let mut buf = vec![0; 4];
+        let mut session_signal = data.session_lease.signal();
         loop {
             tokio::select! {
                 result = tokio::time::timeout(self.server.core.imap.timeout_idle, self.stream_rx.read_exact(&mut buf)) => {
@@ -74,6 +81,9 @@ impl<T: SessionStream> Session<T> {
                         Ok(Ok(bytes_read)) => {
                             if bytes_read > 0 {
                                 if (buf[..bytes_read]).windows(4).any(|w| w == b"DONE") {
+                                    if notify_at.is_some() {
+                                        data.write_changes(&mailbox, has_mailbox_changes, has_email_changes, is_qresync, is_rev2, is_utf8).await?;
+                                    }
                                     trc::event!(Imap(trc::ImapEvent::IdleStop), SpanId = self.session_id, Elapsed = op_start.elapsed());
                                     return self.write_bytes(StatusResponse::completed(Command::Idle)
                                                                     .with_tag(request.tag)
@@ -94,9 +104,6 @@ impl<T: SessionStream> Session<T> {
                 }
                 push_notification = push_rx.recv() => {
                     if let Some(push_notification) = push_notification {
-                        let mut has_mailbox_changes = false;
-                        let mut has_email_changes = false;
-
                         match push_notification {
                             PushNotification::StateChange(state_change) => {
                                 for type_state in state_change.types {
@@ -118,14 +125,25 @@ impl<T: SessionStream> Session<T> {
                             PushNotification::CalendarAlert(_) => (),
                         }
 
-                        if has_mailbox_changes || has_email_changes {
-                            data.write_changes(&mailbox, has_mailbox_changes, has_email_changes, is_qresync, is_rev2, is_utf8).await?;
+                        // Hold back changes for a short while so bursts are sent together
+                        if (has_mailbox_changes || has_email_changes) && notify_at.is_none() {
+                            notify_at = Some(tokio::time::Instant::now() + notify_delay);
                         }
                     } else {
                         self.write_bytes(&b"* BYE Server shutting down.\r\n"[..]).await.ok();
                         return Err(trc::NetworkEvent::Closed.into_err().details("IDLE channel closed.").id(request.tag));
                     }
                 }
+                _ = tokio::time::sleep_until(notify_at.unwrap_or_else(tokio::time::Instant::now)), if notify_at.is_some() => {
+                    data.write_changes(&mailbox, has_mailbox_changes, has_email_changes, is_qresync, is_rev2, is_utf8).await?;
+                    notify_at = None;
+                    has_mailbox_changes = false;
+                    has_email_changes = false;
+                }
+                _ = session_signal.terminated() => {
+                    // The session loop closes the connection
+                    return Ok(());
+                }
             }
         }
     }
@@ -218,7 +236,8 @@ impl<T: SessionStream> SessionData<T> {
                     )
                     .await
                     .caused_by(trc::location!())?;
-                let changed_ids = {
+                // Multiple changes to the same message produce a single FETCH
+                let mut changed_ids = {
                     let state = mailbox.state.lock();
                     changelog
                         .changes
@@ -232,33 +251,38 @@ impl<T: SessionStream> SessionData<T> {
                             })
                         })
                         .collect::<AHashSet<_>>()
+                        .into_iter()
+                        .collect::<Vec<_>>()
                 };
+                changed_ids.sort_unstable();
 
-                if !changed_ids.is_empty() {
+                // Large batches are written in chunks, yielding between writes
+                for (chunk_num, chunk) in changed_ids.chunks(FETCH_CHUNK_SIZE).enumerate() {
+                    if chunk_num > 0 {
+                        tokio::task::yield_now().await;
+                    }
                     let op_start = Instant::now();
-                    return self
-                        .fetch(
-                            fetch::Arguments {
-                                tag: "".into(),
-                                sequence_set: Sequence::List {
-                                    items: changed_ids
-                                        .into_iter()
-                                        .map(|uid| Sequence::Number { value: uid })
-                                        .collect(),
-                                },
-                                attributes: vec![fetch::Attribute::Flags, fetch::Attribute::Uid],
-                                changed_since: None,
-                                include_vanished: false,
+                    self.fetch(
+                        fetch::Arguments {
+                            tag: "".into(),
+                            sequence_set: Sequence::List {
+                                items: chunk
+                                    .iter()
+                                    .map(|&uid| Sequence::Number { value: uid })
+                                    .collect(),
                             },
-                            mailbox.clone(),
-                            true,
-                            is_qresync,
-                            false,
-                            op_start,
-                        )
-                        .await
-                        .caused_by(trc::location!())
-                        .map(|_| ());
+                            attributes: vec![fetch::Attribute::Flags, fetch::Attribute::Uid],
+                            changed_since: None,
+                            include_vanished: false,
+                        },
+                        mailbox.clone(),
+                        true,
+                        is_qresync,
+                        false,
+                        op_start,
+                    )
+                    .await
+                    .caused_by(trc::location!())?;
                 }
             }
         }
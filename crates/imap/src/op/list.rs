@@ -51,6 +51,11 @@ impl<T: SessionStream> Session<T> {
 
             spawn_op!(
                 data,
+                if is_lsub {
+                    trc::ImapEvent::Lsub
+                } else {
+                    trc::ImapEvent::List
+                },
                 data.list(arguments, is_lsub, version, is_utf8, op_start)
                     .await
             )
@@ -5,13 +5,16 @@
  */
 
 use ::store::query::log::Query;
-use imap_proto::ResponseCode;
+use imap_proto::{Command, ResponseCode, ResponseType};
+use std::time::Instant;
+use trc::ipc::metrics::CommandOutcome;
 
 pub mod acl;
 pub mod append;
 pub mod authenticate;
 pub mod capability;
 pub mod close;
+pub mod compress;
 pub mod copy_move;
 pub mod create;
 pub mod delete;
@@ -32,6 +35,7 @@ pub mod status;
 pub mod store;
 pub mod subscribe;
 pub mod thread;
+pub mod urlauth;
 
 trait FromModSeq {
     fn from_modseq(modseq: u64) -> Self;
@@ -59,8 +63,10 @@ impl ToModSeq for u64 {
 
 #[macro_export]
 macro_rules! spawn_op {
-    ($data:expr, $($code:tt)*) => {
+    ($data:expr, $command:expr, $($code:tt)*) => {
         {
+        let command = $command;
+        let op_start = std::time::Instant::now();
 
         tokio::spawn(async move {
             let data = &($data);
@@ -70,6 +76,7 @@ macro_rules! spawn_op {
             })
             .await
             {
+                $crate::op::record_command_error(command, &err, op_start);
                 let _ = data.write_error(err).await;
             }
         });
@@ -77,6 +84,63 @@ macro_rules! spawn_op {
         Ok(())}
     };
 }
+
+pub fn record_command_error(command: trc::ImapEvent, err: &trc::Error, op_start: Instant) {
+    trc::Collector::record_imap_command(
+        command,
+        if err.value_as_str(trc::Key::Type) == Some(ResponseType::Bad.as_str()) {
+            CommandOutcome::Bad
+        } else {
+            CommandOutcome::No
+        },
+        op_start.elapsed().as_millis() as u64,
+    );
+}
+
+pub fn command_event(command: &Command) -> Option<trc::ImapEvent> {
+    match command {
+        Command::Capability => trc::ImapEvent::Capabilities.into(),
+        Command::Noop | Command::Check => trc::ImapEvent::Noop.into(),
+        Command::Logout => trc::ImapEvent::Logout.into(),
+        Command::Enable => trc::ImapEvent::Enable.into(),
+        Command::Select | Command::Examine => trc::ImapEvent::Select.into(),
+        Command::Create => trc::ImapEvent::CreateMailbox.into(),
+        Command::Delete => trc::ImapEvent::DeleteMailbox.into(),
+        Command::Rename => trc::ImapEvent::RenameMailbox.into(),
+        Command::Subscribe => trc::ImapEvent::Subscribe.into(),
+        Command::Unsubscribe => trc::ImapEvent::Unsubscribe.into(),
+        Command::List => trc::ImapEvent::List.into(),
+        Command::Lsub => trc::ImapEvent::Lsub.into(),
+        Command::Namespace => trc::ImapEvent::Namespace.into(),
+        Command::Status => trc::ImapEvent::Status.into(),
+        Command::Append => trc::ImapEvent::Append.into(),
+        Command::Idle => trc::ImapEvent::IdleStop.into(),
+        Command::Close | Command::Unselect => trc::ImapEvent::Close.into(),
+        Command::Expunge(_) => trc::ImapEvent::Expunge.into(),
+        Command::Search(_) => trc::ImapEvent::Search.into(),
+        Command::Fetch(_) => trc::ImapEvent::Fetch.into(),
+        Command::Store(_) => trc::ImapEvent::Store.into(),
+        Command::Copy(_) => trc::ImapEvent::Copy.into(),
+        Command::Move(_) => trc::ImapEvent::Move.into(),
+        Command::Sort(_) => trc::ImapEvent::Sort.into(),
+        Command::Thread(_) => trc::ImapEvent::Thread.into(),
+        Command::SetAcl | Command::DeleteAcl => trc::ImapEvent::SetAcl.into(),
+        Command::GetAcl => trc::ImapEvent::GetAcl.into(),
+        Command::ListRights => trc::ImapEvent::ListRights.into(),
+        Command::MyRights => trc::ImapEvent::MyRights.into(),
+        Command::GetQuota | Command::GetQuotaRoot => trc::ImapEvent::GetQuota.into(),
+        Command::SetQuota => trc::ImapEvent::SetQuota.into(),
+        Command::Id => trc::ImapEvent::Id.into(),
+        Command::GenUrlAuth => trc::ImapEvent::GenUrlAuth.into(),
+        Command::ResetKey => trc::ImapEvent::ResetKey.into(),
+        Command::Compress => trc::ImapEvent::Compress.into(),
+        Command::StartTls
+        | Command::Authenticate
+        | Command::Login
+        | Command::Unauthenticate
+        | Command::GetJmapAccess => None,
+    }
+}
 pub trait ImapContext<T> {
     fn imap_ctx(self, tag: &str, location: &'static str) -> trc::Result<T>;
 }
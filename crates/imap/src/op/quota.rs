@@ -12,21 +12,22 @@
 
 use crate::{
     core::{Session, SessionData},
-    op::ImapContext,
+    op::{ImapContext, record_command_error},
     spawn_op,
 };
-use common::network::SessionStream;
+use common::{auth::AccountCache, network::SessionStream};
+use email::cache::MessageCacheFetch;
 use imap_proto::{
     Command, ResponseCode, StatusResponse,
     protocol::{
         ImapResponse,
         capability::QuotaResourceName,
-        quota::{Arguments, QuotaItem, QuotaResource, Response},
+        quota::{Arguments, QuotaItem, QuotaResource, Response, SetArguments},
     },
     receiver::Request,
 };
-use registry::schema::enums::Permission;
-use std::time::Instant;
+use registry::schema::enums::{Permission, StorageQuota};
+use std::{sync::Arc, time::Instant};
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_get_quota(&mut self, request: Request<Command>) -> trc::Result<()> {
@@ -35,17 +36,22 @@ impl<T: SessionStream> Session<T> {
 
         let data = self.state.session_data();
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::GetQuota, {
+            let op_start = Instant::now();
             match request.parse_get_quota() {
                 Ok(argument) => match data.get_quota(argument).await {
                     Ok(response) => {
                         data.write_bytes(response).await?;
                     }
                     Err(error) => {
+                        record_command_error(trc::ImapEvent::GetQuota, &error, op_start);
                         data.write_error(error).await?;
                     }
                 },
-                Err(err) => data.write_error(err).await?,
+                Err(err) => {
+                    record_command_error(trc::ImapEvent::GetQuota, &err, op_start);
+                    data.write_error(err).await?;
+                }
             }
 
             Ok(())
@@ -59,17 +65,50 @@ impl<T: SessionStream> Session<T> {
         let data = self.state.session_data();
         let is_utf8 = self.is_utf8;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::GetQuota, {
+            let op_start = Instant::now();
             match request.parse_get_quota_root(is_utf8) {
                 Ok(argument) => match data.get_quota_root(argument).await {
                     Ok(response) => {
                         data.write_bytes(response).await?;
                     }
                     Err(error) => {
+                        record_command_error(trc::ImapEvent::GetQuota, &error, op_start);
                         data.write_error(error).await?;
                     }
                 },
-                Err(err) => data.write_error(err).await?,
+                Err(err) => {
+                    record_command_error(trc::ImapEvent::GetQuota, &err, op_start);
+                    data.write_error(err).await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    pub async fn handle_set_quota(&mut self, request: Request<Command>) -> trc::Result<()> {
+        // Validate access
+        self.assert_has_permission(Permission::ImapStatus)?;
+
+        let data = self.state.session_data();
+
+        spawn_op!(data, trc::ImapEvent::SetQuota, {
+            let op_start = Instant::now();
+            match request.parse_set_quota() {
+                Ok(argument) => match data.set_quota(argument).await {
+                    Ok(response) => {
+                        data.write_bytes(response).await?;
+                    }
+                    Err(error) => {
+                        record_command_error(trc::ImapEvent::SetQuota, &error, op_start);
+                        data.write_error(error).await?;
+                    }
+                },
+                Err(err) => {
+                    record_command_error(trc::ImapEvent::SetQuota, &err, op_start);
+                    data.write_error(err).await?;
+                }
             }
 
             Ok(())
@@ -87,56 +126,36 @@ impl<T: SessionStream> SessionData<T> {
             .imap_ctx(&arguments.tag, trc::location!())?;
 
         // Validate quota root
-        let account_id: u32 = arguments
-            .name
-            .strip_prefix("#")
-            .and_then(|id| id.parse().ok())
-            .filter(|id| self.access_token.is_member(*id))
+        let account = self
+            .quota_root_account(&arguments.name)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?
+            .filter(|account| {
+                self.access_token.is_member(account.id) || self.can_set_quota(account)
+            })
             .ok_or_else(|| {
                 trc::ImapEvent::Error
                     .into_err()
                     .details("Invalid quota root parameter.")
                     .id(arguments.tag.to_string())
             })?;
-
-        // Obtain access token for mailbox
-        let account = self
-            .server
-            .account(account_id)
-            .await
-            .imap_ctx(&arguments.tag, trc::location!())?;
-        let used_quota = self
-            .server
-            .get_used_quota_account(account_id)
+        let quota_item = self
+            .quota_item(arguments.name.clone(), &account)
             .await
             .imap_ctx(&arguments.tag, trc::location!())?;
 
         trc::event!(
             Imap(trc::ImapEvent::GetQuota),
             SpanId = self.session_id,
-            Id = arguments.name.clone(),
-            Details = vec![
-                trc::Value::from(used_quota),
-                trc::Value::from(account.disk_quota())
-            ],
+            Id = arguments.name,
+            Details = quota_details(&quota_item),
             Elapsed = op_start.elapsed()
         );
 
         // Build response
         let response = Response {
             quota_root_items: vec![],
-            quota_items: vec![QuotaItem {
-                name: arguments.name,
-                resources: if account.disk_quota() > 0 {
-                    vec![QuotaResource {
-                        resource: QuotaResourceName::Storage,
-                        total: account.disk_quota(),
-                        used: used_quota as u64,
-                    }]
-                } else {
-                    vec![]
-                },
-            }],
+            quota_items: vec![quota_item],
         };
 
         Ok(StatusResponse::ok("GETQUOTA successful.")
@@ -163,15 +182,14 @@ impl<T: SessionStream> SessionData<T> {
                 .id(arguments.tag));
         };
 
-        // Obtain access token for mailbox
+        // Obtain quota usage for the account
         let account = self
             .server
             .account(account_id)
             .await
             .imap_ctx(&arguments.tag, trc::location!())?;
-        let used_quota = self
-            .server
-            .get_used_quota_account(account_id)
+        let quota_item = self
+            .quota_item(format!("#{account_id}"), &account)
             .await
             .imap_ctx(&arguments.tag, trc::location!())?;
 
@@ -179,32 +197,153 @@ impl<T: SessionStream> SessionData<T> {
             Imap(trc::ImapEvent::GetQuota),
             SpanId = self.session_id,
             MailboxName = arguments.name.clone(),
-            Details = vec![
-                trc::Value::from(used_quota),
-                trc::Value::from(account.disk_quota())
-            ],
+            Details = quota_details(&quota_item),
             Elapsed = op_start.elapsed()
         );
 
         // Build response
         let response = Response {
-            quota_root_items: vec![arguments.name, format!("#{account_id}")],
-            quota_items: vec![QuotaItem {
-                name: format!("#{account_id}"),
-                resources: if account.disk_quota() > 0 {
-                    vec![QuotaResource {
-                        resource: QuotaResourceName::Storage,
-                        total: account.disk_quota(),
-                        used: used_quota as u64,
-                    }]
-                } else {
-                    vec![]
-                },
-            }],
+            quota_root_items: vec![arguments.name, quota_item.name.clone()],
+            quota_items: vec![quota_item],
         };
 
         Ok(StatusResponse::ok("GETQUOTAROOT successful.")
             .with_tag(arguments.tag)
             .serialize(response.serialize()))
     }
+
+    pub async fn set_quota(&self, arguments: SetArguments) -> trc::Result<Vec<u8>> {
+        let op_start = Instant::now();
+
+        // Validate quota root
+        let account = self
+            .quota_root_account(&arguments.name)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?
+            .ok_or_else(|| {
+                trc::ImapEvent::Error
+                    .into_err()
+                    .details("Invalid quota root parameter.")
+                    .id(arguments.tag.to_string())
+            })?;
+        if !self.can_set_quota(&account) {
+            return Err(trc::ImapEvent::Error
+                .into_err()
+                .details("You do not have enough permissions to set quotas on this account.")
+                .code(ResponseCode::NoPerm)
+                .id(arguments.tag));
+        }
+
+        // Resources not included in the request are reset to unlimited
+        let mut limits = [
+            (StorageQuota::MaxDiskQuota, 0),
+            (StorageQuota::MaxEmails, 0),
+        ];
+        for (resource, limit) in arguments.limits {
+            match resource {
+                QuotaResourceName::Storage => limits[0].1 = limit.saturating_mul(1024),
+                QuotaResourceName::Message => limits[1].1 = limit,
+                QuotaResourceName::Mailbox | QuotaResourceName::AnnotationStorage => {
+                    return Err(trc::ImapEvent::Error
+                        .into_err()
+                        .details("Unsupported quota resource.")
+                        .code(ResponseCode::Cannot)
+                        .id(arguments.tag));
+                }
+            }
+        }
+        self.server
+            .set_account_quotas(account.id, &limits)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+
+        // Return the updated quota
+        let account = self
+            .server
+            .account(account.id)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+        let quota_item = self
+            .quota_item(arguments.name.clone(), &account)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+
+        trc::event!(
+            Imap(trc::ImapEvent::SetQuota),
+            SpanId = self.session_id,
+            Id = arguments.name,
+            AccountId = account.id,
+            Details = quota_details(&quota_item),
+            Elapsed = op_start.elapsed()
+        );
+
+        let response = Response {
+            quota_root_items: vec![],
+            quota_items: vec![quota_item],
+        };
+
+        Ok(StatusResponse::ok("SETQUOTA successful.")
+            .with_tag(arguments.tag)
+            .serialize(response.serialize()))
+    }
+
+    async fn quota_root_account(&self, name: &str) -> trc::Result<Option<Arc<AccountCache>>> {
+        if let Some(account_id) = name.strip_prefix("#").and_then(|id| id.parse().ok()) {
+            self.server.try_account(account_id).await
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn quota_item(&self, name: String, account: &AccountCache) -> trc::Result<QuotaItem> {
+        let mut resources = Vec::with_capacity(2);
+
+        if account.disk_quota() > 0 {
+            resources.push(QuotaResource {
+                resource: QuotaResourceName::Storage,
+                total: account.disk_quota(),
+                used: self.server.get_used_quota_account(account.id).await?.max(0) as u64,
+            });
+        }
+
+        let max_messages = self
+            .server
+            .object_quota(account.object_quotas(), StorageQuota::MaxEmails);
+        if max_messages != 0 && max_messages != u32::MAX {
+            resources.push(QuotaResource {
+                resource: QuotaResourceName::Message,
+                total: max_messages as u64,
+                used: self
+                    .server
+                    .get_cached_messages(account.id)
+                    .await?
+                    .emails
+                    .items
+                    .len() as u64,
+            });
+        }
+
+        Ok(QuotaItem { name, resources })
+    }
+
+    fn can_set_quota(&self, account: &AccountCache) -> bool {
+        self.access_token
+            .has_permission(Permission::SysAccountUpdate)
+            && self
+                .access_token
+                .tenant_id()
+                .is_none_or(|tenant_id| account.tenant_id() == Some(tenant_id))
+    }
+}
+
+fn quota_details(item: &QuotaItem) -> Vec<trc::Value> {
+    item.resources
+        .iter()
+        .flat_map(|resource| {
+            [
+                trc::Value::from(resource.used),
+                trc::Value::from(resource.total),
+            ]
+        })
+        .collect()
 }
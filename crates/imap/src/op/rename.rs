@@ -36,7 +36,7 @@ impl<T: SessionStream> Session<T> {
         let data = self.state.session_data();
         let is_objectid = self.is_objectid;
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::RenameMailbox, {
             let response = data.rename_folder(arguments, is_objectid, op_start).await?;
             data.write_bytes(response.into_bytes()).await
         })
@@ -6,10 +6,12 @@
 
 use super::{FromModSeq, ToModSeq};
 use crate::{
-    core::{ImapId, SavedSearch, SelectedMailbox, Session, SessionData},
+    core::{
+        ImapId, SavedSearch, SearchCache, SearchCacheEntry, SelectedMailbox, Session, SessionData,
+    },
     spawn_op,
 };
-use common::network::SessionStream;
+use common::{config::mailstore::imap::ImapConfig, network::SessionStream};
 use email::cache::{MessageCacheFetch, email::MessageCacheAccess};
 use imap_proto::{
     Command, StatusResponse,
@@ -22,7 +24,7 @@ use imap_proto::{
 use mail_parser::HeaderName;
 use nlp::language::Language;
 use registry::schema::enums::Permission;
-use std::{str::FromStr, sync::Arc, time::Instant};
+use std::{fmt::Write, str::FromStr, sync::Arc, time::Instant};
 use store::{
     query::log::Query,
     roaring::RoaringBitmap,
@@ -69,7 +71,13 @@ impl<T: SessionStream> Session<T> {
                 (None, None)
             };
 
-        spawn_op!(data, {
+        let event = if is_sort {
+            trc::ImapEvent::Sort
+        } else {
+            trc::ImapEvent::Search
+        };
+
+        spawn_op!(data, event, {
             let tag = std::mem::take(&mut arguments.tag);
             let bytes = match data
                 .search(
@@ -115,16 +123,46 @@ impl<T: SessionStream> SessionData<T> {
         is_uid: bool,
         op_start: Instant,
     ) -> trc::Result<search::Response> {
-        // Run query
+        // Run query, reusing cached results if the mailbox has not changed
         let is_sort = arguments.sort.is_some();
-        let (result_set, include_highest_modseq) = self
-            .query(
-                arguments.filter,
-                arguments.sort.unwrap_or_default(),
-                &mailbox,
-                &prev_saved_search,
-            )
-            .await?;
+        let comparators = arguments.sort.unwrap_or_default();
+        let cache_key = if self.server.core.imap.search_cache_size > 0 {
+            search_cache_key(&arguments.filter, &comparators)
+        } else {
+            None
+        };
+        let cache_key = if let Some(cache_key) = cache_key {
+            let change_id = self
+                .server
+                .get_cached_messages(mailbox.id.account_id)
+                .await
+                .caused_by(trc::location!())?
+                .last_change_id;
+            Some((cache_key, change_id))
+        } else {
+            None
+        };
+        let cached = cache_key.as_ref().and_then(|(cache_key, change_id)| {
+            mailbox.search_cache.lock().get(cache_key, *change_id)
+        });
+        let (result_set, include_highest_modseq) = if let Some(cached) = cached {
+            cached
+        } else {
+            let (result_set, include_highest_modseq) = self
+                .query(arguments.filter, comparators, &mailbox, &prev_saved_search)
+                .await?;
+            let result_set = Arc::new(result_set);
+            if let Some((cache_key, change_id)) = cache_key {
+                mailbox.search_cache.lock().insert(
+                    cache_key,
+                    change_id,
+                    result_set.clone(),
+                    include_highest_modseq,
+                    &self.server.core.imap,
+                );
+            }
+            (result_set, include_highest_modseq)
+        };
 
         // Obtain modseq
         let highest_modseq = if include_highest_modseq {
@@ -148,7 +186,7 @@ impl<T: SessionStream> SessionData<T> {
         };
         let mut imap_ids = Vec::with_capacity(results_len);
         mailbox.map_search_results(
-            result_set.into_iter(),
+            result_set.iter().copied(),
             is_uid,
             arguments.result_options.contains(&ResultOption::Min),
             arguments.result_options.contains(&ResultOption::Max),
@@ -721,3 +759,108 @@ impl SavedSearch {
         }
     }
 }
+
+impl SearchCache {
+    pub fn get(&mut self, key: &str, change_id: u64) -> Option<(Arc<Vec<u32>>, bool)> {
+        let now = Instant::now();
+        self.entries
+            .retain(|entry| entry.change_id == change_id && entry.expires > now);
+        let pos = self.entries.iter().position(|entry| entry.key == key)?;
+        let entry = self.entries.remove(pos);
+        let result = (entry.ids.clone(), entry.include_highest_modseq);
+        self.entries.push(entry);
+        Some(result)
+    }
+
+    pub fn insert(
+        &mut self,
+        key: String,
+        change_id: u64,
+        ids: Arc<Vec<u32>>,
+        include_highest_modseq: bool,
+        config: &ImapConfig,
+    ) {
+        self.entries.retain(|entry| entry.key != key);
+        if self.entries.len() >= config.search_cache_size {
+            let evict = self.entries.len() + 1 - config.search_cache_size;
+            self.entries.drain(..evict);
+        }
+        self.entries.push(SearchCacheEntry {
+            key,
+            change_id,
+            expires: Instant::now() + config.search_cache_ttl,
+            ids,
+            include_highest_modseq,
+        });
+    }
+}
+
+// Builds a canonical representation of the search criteria, top-level terms
+// are ANDed together so their order does not affect the result. Text arguments
+// have already been decoded from the requested charset, so the key does not
+// depend on how the client spelled it.
+fn search_cache_key(filters: &[Filter], comparators: &[Comparator]) -> Option<String> {
+    let mut terms = Vec::with_capacity(filters.len());
+    let mut term = String::new();
+    let mut depth = 0u32;
+
+    for filter in filters {
+        match filter {
+            Filter::Sequence(sequence, is_uid) if !is_uid || sequence.is_saved_search() => {
+                // Sequence numbers and saved searches depend on the session state
+                return None;
+            }
+            Filter::Older(_) | Filter::Younger(_) => {
+                // Relative to the current time
+                return None;
+            }
+            Filter::And | Filter::Or | Filter::Not => {
+                depth += 1;
+            }
+            Filter::End => {
+                depth = depth.checked_sub(1)?;
+            }
+            _ => {}
+        }
+
+        let text = match filter {
+            Filter::Bcc(text) => Some(("BCC", text)),
+            Filter::Body(text) => Some(("BODY", text)),
+            Filter::Cc(text) => Some(("CC", text)),
+            Filter::From(text) => Some(("FROM", text)),
+            Filter::Subject(text) => Some(("SUBJECT", text)),
+            Filter::Text(text) => Some(("TEXT", text)),
+            Filter::To(text) => Some(("TO", text)),
+            _ => None,
+        };
+        let _ = if let Some((name, text)) = text {
+            write!(term, "{name}({:?}) ", text.to_lowercase())
+        } else if let Filter::Header(header, text) = filter {
+            write!(
+                term,
+                "HEADER({:?} {:?}) ",
+                header.to_lowercase(),
+                text.to_lowercase()
+            )
+        } else {
+            write!(term, "{filter:?} ")
+        };
+
+        if depth == 0 {
+            terms.push(std::mem::take(&mut term));
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+
+    terms.sort_unstable();
+    terms.dedup();
+    let mut key = terms.concat();
+    for comparator in comparators {
+        let _ = write!(key, "{comparator:?} ");
+    }
+
+    Some(key)
+}
@@ -5,7 +5,7 @@
  */
 
 use super::{ImapContext, ToModSeq};
-use crate::core::{SavedSearch, SelectedMailbox, Session, State};
+use crate::core::{SavedSearch, SearchCache, SelectedMailbox, Session, State};
 use common::network::SessionStream;
 use imap_proto::{
     Command, ResponseCode, StatusResponse,
@@ -90,6 +90,7 @@ impl<T: SessionStream> Session<T> {
                 id: mailbox,
                 state: parking_lot::Mutex::new(state),
                 saved_search: parking_lot::Mutex::new(SavedSearch::None),
+                search_cache: parking_lot::Mutex::new(SearchCache::default()),
                 is_select,
                 is_condstore,
             });
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::ToModSeq;
+use super::{ToModSeq, record_command_error};
 use crate::{
     core::{Mailbox, Session, SessionData},
     op::ImapContext,
@@ -37,14 +37,15 @@ impl<T: SessionStream> Session<T> {
         let mut parsed = Vec::with_capacity(requests.len());
         let mut activate = false;
         for request in requests {
+            let op_start = Instant::now();
             match request.parse_status(is_utf8) {
                 Ok(arguments) => {
                     if arguments.items.contains(&Status::ObjectId) {
                         activate = true;
                     }
-                    parsed.push(Ok(arguments));
+                    parsed.push(Ok((arguments, op_start)));
                 }
-                Err(err) => parsed.push(Err(err)),
+                Err(err) => parsed.push(Err((err, op_start))),
             }
         }
         if activate && let Some(enabled) = self.activate_objectid() {
@@ -53,13 +54,12 @@ impl<T: SessionStream> Session<T> {
 
         let data = self.state.session_data();
 
-        spawn_op!(data, {
+        spawn_op!(data, trc::ImapEvent::Status, {
             let mut did_sync = false;
 
             for request in parsed {
                 match request {
-                    Ok(arguments) => {
-                        let op_start = Instant::now();
+                    Ok((arguments, op_start)) => {
                         if !did_sync {
                             // Refresh mailboxes
                             data.synchronize_mailboxes(false)
@@ -95,7 +95,10 @@ impl<T: SessionStream> Session<T> {
                         )
                         .await?;
                     }
-                    Err(err) => data.write_error(err).await?,
+                    Err((err, op_start)) => {
+                        record_command_error(trc::ImapEvent::Status, &err, op_start);
+                        data.write_error(err).await?;
+                    }
                 }
             }
 
@@ -243,10 +246,25 @@ impl<T: SessionStream> SessionData<T> {
                         .in_mailbox_with_keyword(mailbox.mailbox_id, &Keyword::Deleted)
                         .map(|x| x.size)
                         .sum::<u32>() as u64,
-                    Status::Size => cache
-                        .in_mailbox(mailbox.mailbox_id)
-                        .map(|x| x.size)
-                        .sum::<u32>() as u64,
+                    Status::Size => {
+                        let size = self
+                            .server
+                            .get_used_quota_mailbox(mailbox.account_id, mailbox.mailbox_id)
+                            .await
+                            .caused_by(trc::location!())?
+                            .max(0) as u64;
+
+                        // Mailboxes created before size counters were maintained
+                        // have no counter until their quota is recalculated
+                        if size == 0 {
+                            cache
+                                .in_mailbox(mailbox.mailbox_id)
+                                .map(|x| x.size as u64)
+                                .sum::<u64>()
+                        } else {
+                            size
+                        }
+                    }
                     _ => {
                         unreachable!()
                     }
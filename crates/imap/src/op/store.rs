@@ -13,7 +13,7 @@ use ahash::AHashSet;
 use common::{network::SessionStream, storage::index::ObjectIndexBuilder};
 use email::{
     mailbox::TRASH_ID,
-    message::{ingest::EmailIngest, metadata::MessageData},
+    message::{category::EmailCategoryFnc, ingest::EmailIngest, metadata::MessageData},
 };
 use imap_proto::{
     Command, ResponseCode, ResponseType, StatusResponse,
@@ -54,7 +54,7 @@ impl<T: SessionStream> Session<T> {
         let is_condstore = self.is_condstore || mailbox.is_condstore;
 
         if spawn {
-            spawn_op!(data, {
+            spawn_op!(data, trc::ImapEvent::Store, {
                 let response = data
                     .store(arguments, mailbox, is_uid, is_condstore, op_start)
                     .await?;
@@ -298,6 +298,12 @@ impl<T: SessionStream> SessionData<T> {
                 }
             }
 
+            // Learn the preferred category of senders from messages moved out of a category
+            self.server
+                .email_category_feedback(account_id, *id, data.inner, &new_data)
+                .await
+                .imap_ctx(response.tag.as_ref().unwrap(), trc::location!())?;
+
             // Write changes
             batch
                 .with_account_id(account_id)
@@ -32,7 +32,13 @@ impl<T: SessionStream> Session<T> {
         let arguments = request.parse_subscribe(self.is_utf8)?;
         let data = self.state.session_data();
 
-        spawn_op!(data, {
+        let event = if is_subscribe {
+            trc::ImapEvent::Subscribe
+        } else {
+            trc::ImapEvent::Unsubscribe
+        };
+
+        spawn_op!(data, event, {
             let response = data
                 .subscribe_folder(
                     arguments.tag,
@@ -0,0 +1,191 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::ImapContext;
+use crate::{
+    core::{Session, SessionData},
+    spawn_op,
+};
+use common::network::SessionStream;
+use email::message::urlauth::{ImapUrl, URLAUTH_MECHANISM, UrlAuthorization, split_url};
+use imap_proto::{
+    Command, ResponseCode, StatusResponse,
+    protocol::{
+        ImapResponse,
+        urlauth::{GenArguments, GenResponse, ResetArguments},
+    },
+    receiver::Request,
+};
+use registry::schema::enums::Permission;
+use std::time::Instant;
+
+impl<T: SessionStream> Session<T> {
+    pub async fn handle_genurlauth(&mut self, request: Request<Command>) -> trc::Result<()> {
+        // Validate access
+        self.assert_has_permission(Permission::ImapUrlAuth)?;
+
+        let op_start = Instant::now();
+        let arguments = request.parse_genurlauth()?;
+        let data = self.state.session_data();
+
+        spawn_op!(data, trc::ImapEvent::GenUrlAuth, {
+            let response = data.genurlauth(arguments, op_start).await?;
+
+            data.write_bytes(response).await
+        })
+    }
+
+    pub async fn handle_resetkey(&mut self, request: Request<Command>) -> trc::Result<()> {
+        // Validate access
+        self.assert_has_permission(Permission::ImapUrlAuth)?;
+
+        let op_start = Instant::now();
+        let arguments = request.parse_resetkey(self.is_utf8)?;
+        let data = self.state.session_data();
+
+        spawn_op!(data, trc::ImapEvent::ResetKey, {
+            let response = data.resetkey(arguments, op_start).await?;
+
+            data.write_bytes(response.into_bytes()).await
+        })
+    }
+}
+
+impl<T: SessionStream> SessionData<T> {
+    pub async fn genurlauth(
+        &self,
+        arguments: GenArguments,
+        op_start: Instant,
+    ) -> trc::Result<Vec<u8>> {
+        // Refresh mailboxes
+        self.synchronize_mailboxes(false)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+        let account = self
+            .server
+            .account(self.account_id)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+
+        let mut urls = Vec::with_capacity(arguments.urls.len());
+        for rump in &arguments.urls {
+            if !rump.mechanism.eq_ignore_ascii_case(URLAUTH_MECHANISM) {
+                return Err(trc::ImapEvent::Error
+                    .into_err()
+                    .details(format!(
+                        "Unsupported authorization mechanism '{}'.",
+                        rump.mechanism
+                    ))
+                    .id(arguments.tag));
+            }
+
+            // URLs can only reference messages owned by the current user
+            let url = ImapUrl::parse(&rump.url)
+                .filter(|_| split_url(&rump.url).is_none())
+                .ok_or_else(|| {
+                    trc::ImapEvent::Error
+                        .into_err()
+                        .details("Invalid URL rump.")
+                        .code(ResponseCode::Parse)
+                        .id(arguments.tag.clone())
+                })?;
+            if !url.user.eq_ignore_ascii_case(account.name()) || !url.access.allows(account.name())
+            {
+                return Err(trc::ImapEvent::Error
+                    .into_err()
+                    .details("URL access identifier not permitted.")
+                    .code(ResponseCode::NoPerm)
+                    .id(arguments.tag));
+            }
+            let mailbox_id = self
+                .get_mailbox_by_name(&url.mailbox)
+                .filter(|mailbox| mailbox.account_id == self.account_id)
+                .ok_or_else(|| {
+                    trc::ImapEvent::Error
+                        .into_err()
+                        .details("Mailbox does not exist.")
+                        .code(ResponseCode::NonExistent)
+                        .id(arguments.tag.clone())
+                })?
+                .mailbox_id;
+
+            urls.push(
+                self.server
+                    .urlauth_generate(self.account_id, mailbox_id, &rump.url)
+                    .await
+                    .imap_ctx(&arguments.tag, trc::location!())?,
+            );
+
+            trc::event!(
+                Imap(trc::ImapEvent::GenUrlAuth),
+                SpanId = self.session_id,
+                AccountId = self.account_id,
+                MailboxId = mailbox_id,
+                Uid = url.uid,
+                Elapsed = op_start.elapsed()
+            );
+        }
+
+        Ok(StatusResponse::completed(Command::GenUrlAuth)
+            .with_tag(arguments.tag)
+            .serialize(GenResponse { urls }.serialize()))
+    }
+
+    pub async fn resetkey(
+        &self,
+        arguments: ResetArguments,
+        op_start: Instant,
+    ) -> trc::Result<StatusResponse> {
+        if let Some(mechanism) = arguments
+            .mechanisms
+            .iter()
+            .find(|mechanism| !mechanism.eq_ignore_ascii_case(URLAUTH_MECHANISM))
+        {
+            return Err(trc::ImapEvent::Error
+                .into_err()
+                .details(format!(
+                    "Unsupported authorization mechanism '{mechanism}'."
+                ))
+                .id(arguments.tag));
+        }
+
+        // Validate mailbox
+        let mailbox_id = if let Some(mailbox_name) = &arguments.mailbox_name {
+            self.synchronize_mailboxes(false)
+                .await
+                .imap_ctx(&arguments.tag, trc::location!())?;
+            Some(
+                self.get_mailbox_by_name(mailbox_name)
+                    .filter(|mailbox| mailbox.account_id == self.account_id)
+                    .ok_or_else(|| {
+                        trc::ImapEvent::Error
+                            .into_err()
+                            .details("Mailbox does not exist.")
+                            .code(ResponseCode::NonExistent)
+                            .id(arguments.tag.clone())
+                    })?
+                    .mailbox_id,
+            )
+        } else {
+            None
+        };
+
+        self.server
+            .urlauth_reset(self.account_id, mailbox_id)
+            .await
+            .imap_ctx(&arguments.tag, trc::location!())?;
+
+        trc::event!(
+            Imap(trc::ImapEvent::ResetKey),
+            SpanId = self.session_id,
+            AccountId = self.account_id,
+            MailboxId = mailbox_id,
+            Elapsed = op_start.elapsed()
+        );
+
+        Ok(StatusResponse::completed(Command::ResetKey).with_tag(arguments.tag))
+    }
+}
@@ -150,6 +150,13 @@ impl Serialize for MethodErrorWrapper {
                         "between the old and new states."
                     ),
                 ),
+                trc::JmapEvent::TooManyChanges => (
+                    "tooManyChanges",
+                    concat!(
+                        "There are more changes than the client's ",
+                        "maxChanges argument."
+                    ),
+                ),
                 trc::JmapEvent::UnknownCapability
                 | trc::JmapEvent::NotJson
                 | trc::JmapEvent::NotRequest => (
@@ -164,6 +171,10 @@ impl Serialize for MethodErrorWrapper {
                     "This server is temporarily unavailable.",
                 ),
             },
+            trc::EventType::Auth(trc::AuthEvent::StepUpRequired) => (
+                "stepUpRequired",
+                "This operation requires a recent second factor verification.",
+            ),
             _ => (
                 "serverUnavailable",
                 concat!(
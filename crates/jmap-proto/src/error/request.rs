@@ -30,6 +30,8 @@ pub enum RequestErrorType {
     NotRequest,
     #[serde(rename = "urn:ietf:params:jmap:error:limit")]
     Limit,
+    #[serde(rename = "stepUpRequired")]
+    StepUpRequired,
     #[serde(rename = "about:blank")]
     Other,
 }
@@ -149,6 +151,22 @@ impl<'x> RequestError<'x> {
         )
     }
 
+    pub fn step_up_required() -> Self {
+        RequestError {
+            p_type: RequestErrorType::StepUpRequired,
+            status: 403,
+            title: Some("Step-up Required".into()),
+            detail: concat!(
+                "This operation requires a recent second factor verification. ",
+                "Verify your second factor and retry the request."
+            )
+            .into(),
+            limit: None,
+            rate_limit: Vec::new(),
+            retry_after: None,
+        }
+    }
+
     pub fn over_blob_quota(max_files: usize, max_bytes: usize) -> Self {
         RequestError::blank(
             429,
@@ -21,8 +21,10 @@ pub mod lookup;
 pub mod parse;
 pub mod query;
 pub mod query_changes;
+pub mod recall;
 pub mod search_snippet;
 pub mod set;
+pub mod sieve_log;
 pub mod upload;
 pub mod validate;
 
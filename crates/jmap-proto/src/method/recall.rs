@@ -0,0 +1,83 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    error::set::SetError,
+    object::email::EmailProperty,
+    request::deserialize::{DeserializeArguments, deserialize_request},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use types::id::Id;
+use utils::map::vec_map::VecMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct EmailRecallRequest {
+    pub account_id: Id,
+    pub ids: Vec<Id>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailRecallResponse {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+
+    #[serde(rename = "recalled")]
+    #[serde(skip_serializing_if = "VecMap::is_empty")]
+    pub recalled: VecMap<Id, Vec<EmailRecallOutcome>>,
+
+    #[serde(rename = "notRecalled")]
+    #[serde(skip_serializing_if = "VecMap::is_empty")]
+    pub not_recalled: VecMap<Id, SetError<EmailProperty>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailRecallOutcome {
+    pub email: String,
+    pub status: EmailRecallStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EmailRecallStatus {
+    Recalled,
+    Replaced,
+    Read,
+    Moved,
+    NotFound,
+    NotLocal,
+    Expired,
+    Failed,
+}
+
+impl<'de> DeserializeArguments<'de> for EmailRecallRequest {
+    fn deserialize_argument<A>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        hashify::fnc_map!(key.as_bytes(),
+            b"accountId" => {
+                self.account_id = crate::request::deserialize_account_id(map)?;
+            },
+            b"ids" => {
+                self.ids = map.next_value()?;
+            },
+            _ => {
+                let _ = map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        );
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for EmailRecallRequest {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_request(deserializer)
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    request::deserialize::{DeserializeArguments, deserialize_request},
+    types::date::UTCDate,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use types::id::Id;
+
+#[derive(Debug, Clone, Default)]
+pub struct SieveLogQueryRequest {
+    pub account_id: Id,
+    pub position: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SieveLogQueryResponse {
+    pub account_id: Id,
+    pub position: usize,
+    pub total: usize,
+    pub list: Vec<SieveLogEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SieveLogEntry {
+    pub received_at: UTCDate,
+    pub script_name: String,
+    pub message_id: Option<String>,
+    pub envelope_from: String,
+    pub tests: Vec<SieveLogTest>,
+    pub actions: Vec<SieveLogAction>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SieveLogTest {
+    pub name: String,
+    pub argument: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SieveLogAction {
+    pub action: &'static str,
+    pub target: Option<String>,
+    pub error: Option<String>,
+}
+
+impl<'de> DeserializeArguments<'de> for SieveLogQueryRequest {
+    fn deserialize_argument<A>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        hashify::fnc_map!(key.as_bytes(),
+            b"accountId" => {
+                self.account_id = crate::request::deserialize_account_id(map)?;
+            },
+            b"position" => {
+                self.position = map.next_value()?;
+            },
+            b"limit" => {
+                self.limit = map.next_value()?;
+            },
+            _ => {
+                let _ = map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        );
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for SieveLogQueryRequest {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_request(deserializer)
+    }
+}
@@ -29,6 +29,10 @@ pub enum EmailProperty {
     Keywords,
     Size,
     ReceivedAt,
+    SnoozedUntil,
+    RestoreMailboxId,
+    FollowUpAt,
+    FollowUpMailboxId,
 
     // Address
     Name,
@@ -112,7 +116,9 @@ impl Property for EmailProperty {
         let allow_patch = key.is_none();
         if let Some(Key::Property(key)) = key {
             match key.patch_or_prop() {
-                EmailProperty::Keywords => EmailProperty::Keyword(Keyword::parse(value)).into(),
+                EmailProperty::Keywords => {
+                    EmailProperty::Keyword(Keyword::parse_escaped(value)).into()
+                }
                 EmailProperty::MailboxIds => match parse_ref(value) {
                     MaybeReference::Value(v) => Some(EmailProperty::IdValue(v)),
                     MaybeReference::Reference(v) => Some(EmailProperty::IdReference(v)),
@@ -152,6 +158,10 @@ impl Property for EmailProperty {
             EmailProperty::PartId => "partId",
             EmailProperty::Preview => "preview",
             EmailProperty::ReceivedAt => "receivedAt",
+            EmailProperty::SnoozedUntil => "snoozedUntil",
+            EmailProperty::RestoreMailboxId => "restoreMailboxId",
+            EmailProperty::FollowUpAt => "followUpAt",
+            EmailProperty::FollowUpMailboxId => "followUpMailboxId",
             EmailProperty::References => "references",
             EmailProperty::ReplyTo => "replyTo",
             EmailProperty::Sender => "sender",
@@ -168,7 +178,7 @@ impl Property for EmailProperty {
             EmailProperty::IsEncodingProblem => "isEncodingProblem",
             EmailProperty::IsTruncated => "isTruncated",
             EmailProperty::Header(header) => return header.to_string().into(),
-            EmailProperty::Keyword(keyword) => return keyword.to_string().into(),
+            EmailProperty::Keyword(keyword) => return keyword.to_jmap().into_owned().into(),
             EmailProperty::IdValue(id) => return id.to_string().into(),
             EmailProperty::Pointer(json_pointer) => return json_pointer.to_string().into(),
             EmailProperty::IdReference(r) => return format!("#{r}").into(),
@@ -183,13 +193,15 @@ impl Element for EmailValue {
     fn try_parse<P>(key: &Key<'_, Self::Property>, value: &str) -> Option<Self> {
         if let Key::Property(prop) = key {
             match prop.patch_or_prop() {
-                EmailProperty::Id | EmailProperty::ThreadId | EmailProperty::MailboxIds => {
-                    match parse_ref(value) {
-                        MaybeReference::Value(v) => Some(EmailValue::Id(v)),
-                        MaybeReference::Reference(v) => Some(EmailValue::IdReference(v)),
-                        MaybeReference::ParseError => None,
-                    }
-                }
+                EmailProperty::Id
+                | EmailProperty::ThreadId
+                | EmailProperty::MailboxIds
+                | EmailProperty::RestoreMailboxId
+                | EmailProperty::FollowUpMailboxId => match parse_ref(value) {
+                    MaybeReference::Value(v) => Some(EmailValue::Id(v)),
+                    MaybeReference::Reference(v) => Some(EmailValue::IdReference(v)),
+                    MaybeReference::ParseError => None,
+                },
                 EmailProperty::BlobId => match parse_ref(value) {
                     MaybeReference::Value(v) => Some(EmailValue::BlobId(v)),
                     MaybeReference::Reference(v) => Some(EmailValue::IdReference(v)),
@@ -200,6 +212,8 @@ impl Element for EmailValue {
                     ..
                 })
                 | EmailProperty::ReceivedAt
+                | EmailProperty::SnoozedUntil
+                | EmailProperty::FollowUpAt
                 | EmailProperty::SentAt => UTCDate::from_str(value).ok().map(EmailValue::Date),
                 _ => None,
             }
@@ -228,6 +242,10 @@ impl EmailProperty {
                 "keywords" => EmailProperty::Keywords,
                 "size" => EmailProperty::Size,
                 "receivedAt" => EmailProperty::ReceivedAt,
+                "snoozedUntil" => EmailProperty::SnoozedUntil,
+                "restoreMailboxId" => EmailProperty::RestoreMailboxId,
+                "followUpAt" => EmailProperty::FollowUpAt,
+                "followUpMailboxId" => EmailProperty::FollowUpMailboxId,
                 "name" => EmailProperty::Name,
                 "email" => EmailProperty::Email,
                 "addresses" => EmailProperty::Addresses,
@@ -524,6 +542,7 @@ pub enum EmailFilter {
     HasKeyword(Keyword),
     NotKeyword(Keyword),
     HasAttachment(bool),
+    HasFollowUp(bool),
     From(String),
     To(String),
     Cc(String),
@@ -597,6 +616,9 @@ impl<'de> DeserializeArguments<'de> for EmailFilter {
             b"hasAttachment" => {
                 *self = EmailFilter::HasAttachment(map.next_value()?);
             },
+            b"hasFollowUp" => {
+                *self = EmailFilter::HasFollowUp(map.next_value()?);
+            },
             b"from" => {
                 *self = EmailFilter::From(map.next_value()?);
             },
@@ -754,6 +776,7 @@ impl Display for EmailFilter {
             EmailFilter::HasKeyword(_) => "hasKeyword",
             EmailFilter::NotKeyword(_) => "notKeyword",
             EmailFilter::HasAttachment(_) => "hasAttachment",
+            EmailFilter::HasFollowUp(_) => "hasFollowUp",
             EmailFilter::From(_) => "from",
             EmailFilter::To(_) => "to",
             EmailFilter::Cc(_) => "cc",
@@ -93,9 +93,11 @@ pub enum Capability {
     Stalwart = 1 << 17,
     #[serde(rename(serialize = "urn:ietf:params:jmap:webpush-vapid"))]
     WebPushVapid = 1 << 18,
+    #[serde(rename(serialize = "urn:stalwart:jmap:followup"))]
+    FollowUp = 1 << 19,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct CapabilityIds(pub u32);
 
@@ -331,6 +333,7 @@ impl Capability {
             Capability::MailShare => "urn:ietf:params:jmap:mail:share",
             Capability::Stalwart => "urn:stalwart:jmap",
             Capability::WebPushVapid => "urn:ietf:params:jmap:webpush-vapid",
+            Capability::FollowUp => "urn:stalwart:jmap:followup",
         }
     }
 
@@ -354,6 +357,7 @@ impl Capability {
             Capability::MailShare,
             Capability::Stalwart,
             Capability::WebPushVapid,
+            Capability::FollowUp,
         ]
     }
 }
@@ -475,6 +479,7 @@ impl Capability {
             "urn:ietf:params:jmap:mail:share" => Capability::MailShare,
             "urn:stalwart:jmap" => Capability::Stalwart,
             "urn:ietf:params:jmap:webpush-vapid" => Capability::WebPushVapid,
+            "urn:stalwart:jmap:followup" => Capability::FollowUp,
         )
     }
 }
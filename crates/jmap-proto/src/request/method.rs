@@ -30,6 +30,7 @@ pub enum MethodObject {
     EmailSubmission,
     VacationResponse,
     SieveScript,
+    SieveLog,
     Principal,
     Quota,
     Calendar,
@@ -54,7 +55,7 @@ impl MethodObject {
             MethodObject::Blob => Capability::Blob,
             MethodObject::Identity | MethodObject::EmailSubmission => Capability::Submission,
             MethodObject::VacationResponse => Capability::VacationResponse,
-            MethodObject::SieveScript => Capability::Sieve,
+            MethodObject::SieveScript | MethodObject::SieveLog => Capability::Sieve,
             MethodObject::Principal | MethodObject::ShareNotification => Capability::Principals,
             MethodObject::Quota => Capability::Quota,
             MethodObject::Calendar
@@ -83,6 +84,7 @@ pub enum MethodFunction {
     Upload,
     Echo,
     GetAvailability,
+    Recall,
 }
 
 impl Display for MethodName {
@@ -125,6 +127,7 @@ impl MethodName {
             (MethodFunction::Copy, MethodObject::Email) => "Email/copy",
             (MethodFunction::Import, MethodObject::Email) => "Email/import",
             (MethodFunction::Parse, MethodObject::Email) => "Email/parse",
+            (MethodFunction::Recall, MethodObject::Email) => "Email/recall",
 
             (MethodFunction::Get, MethodObject::SearchSnippet) => "SearchSnippet/get",
 
@@ -148,6 +151,8 @@ impl MethodName {
             (MethodFunction::Query, MethodObject::SieveScript) => "SieveScript/query",
             (MethodFunction::Validate, MethodObject::SieveScript) => "SieveScript/validate",
 
+            (MethodFunction::Query, MethodObject::SieveLog) => "SieveLog/query",
+
             (MethodFunction::Get, MethodObject::Principal) => "Principal/get",
             (MethodFunction::Set, MethodObject::Principal) => "Principal/set",
             (MethodFunction::Query, MethodObject::Principal) => "Principal/query",
@@ -265,6 +270,7 @@ impl MethodName {
             "Email/copy" => (MethodObject::Email, MethodFunction::Copy),
             "Email/import" => (MethodObject::Email, MethodFunction::Import),
             "Email/parse" => (MethodObject::Email, MethodFunction::Parse),
+            "Email/recall" => (MethodObject::Email, MethodFunction::Recall),
 
             "SearchSnippet/get" => (MethodObject::SearchSnippet, MethodFunction::Get),
 
@@ -286,6 +292,8 @@ impl MethodName {
             "SieveScript/query" => (MethodObject::SieveScript, MethodFunction::Query),
             "SieveScript/validate" => (MethodObject::SieveScript, MethodFunction::Validate),
 
+            "SieveLog/query" => (MethodObject::SieveLog, MethodFunction::Query),
+
             "Principal/get" => (MethodObject::Principal, MethodFunction::Get),
             "Principal/set" => (MethodObject::Principal, MethodFunction::Set),
             "Principal/query" => (MethodObject::Principal, MethodFunction::Query),
@@ -382,6 +390,7 @@ impl Display for MethodObject {
             MethodObject::VacationResponse => "VacationResponse",
             MethodObject::PushSubscription => "PushSubscription",
             MethodObject::SieveScript => "SieveScript",
+            MethodObject::SieveLog => "SieveLog",
             MethodObject::Principal => "Principal",
             MethodObject::Core => "Core",
             MethodObject::Mailbox => "Mailbox",
@@ -420,6 +429,7 @@ impl MethodFunction {
             MethodFunction::Upload => "upload",
             MethodFunction::Echo => "echo",
             MethodFunction::GetAvailability => "getAvailability",
+            MethodFunction::Recall => "recall",
         }
     }
 }
@@ -23,8 +23,10 @@ use crate::{
         parse::ParseRequest,
         query::QueryRequest,
         query_changes::QueryChangesRequest,
+        recall::EmailRecallRequest,
         search_snippet::GetSearchSnippetRequest,
         set::SetRequest,
+        sieve_log::SieveLogQueryRequest,
         upload::BlobUploadRequest,
         validate::ValidateSieveScriptRequest,
     },
@@ -40,7 +42,7 @@ use crate::{
     request::{capability::CapabilityIds, reference::MaybeIdReference},
 };
 use jmap_tools::{Null, Value};
-use std::{collections::HashMap, fmt::Debug, str::FromStr};
+use std::{collections::HashMap, fmt::Debug, str::FromStr, time::Duration};
 use types::id::Id;
 use utils::map::vec_map::VecMap;
 
@@ -61,6 +63,7 @@ pub struct Request<'x> {
     pub using: CapabilityIds,
     pub method_calls: Vec<Call<RequestMethod<'x>>>,
     pub created_ids: Option<HashMap<String, AnyId>>,
+    pub parse_time: Duration,
 }
 
 #[derive(Debug)]
@@ -77,11 +80,13 @@ pub enum RequestMethod<'x> {
     Changes(Box<ChangesRequest>),
     Copy(CopyRequestMethod<'x>),
     ImportEmail(Box<ImportEmailRequest>),
+    RecallEmail(Box<EmailRecallRequest>),
     Parse(ParseRequestMethod),
     Query(QueryRequestMethod),
     QueryChanges(QueryChangesRequestMethod),
     SearchSnippet(Box<GetSearchSnippetRequest>),
     ValidateScript(Box<ValidateSieveScriptRequest>),
+    SieveLog(Box<SieveLogQueryRequest>),
     LookupBlob(Box<BlobLookupRequest>),
     UploadBlob(Box<BlobUploadRequest>),
     Echo(Value<'x, Null, Null>),
@@ -233,6 +238,7 @@ impl Default for Request<'_> {
             using: CapabilityIds::default(),
             method_calls: Vec::new(),
             created_ids: None,
+            parse_time: Duration::ZERO,
         }
     }
 }
@@ -16,14 +16,19 @@ use serde::{
     Deserialize, Deserializer,
     de::{self, SeqAccess, Visitor},
 };
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    time::Instant,
+};
 
 impl<'x> Request<'x> {
     pub fn parse(json: &'x [u8], max_calls: usize, max_size: usize) -> trc::Result<Self> {
         if json.len() <= max_size {
+            let parse_start = Instant::now();
             match serde_json::from_slice::<Request>(json) {
-                Ok(request) => {
+                Ok(mut request) => {
                     if request.method_calls.len() <= max_calls {
+                        request.parse_time = parse_start.elapsed();
                         Ok(request)
                     } else {
                         Err(trc::LimitEvent::CallsIn.into_err())
@@ -635,6 +640,13 @@ impl<'de> Visitor<'de> for CallVisitor {
                     return Err(de::Error::invalid_length(1, &self));
                 }
             },
+            (MethodFunction::Recall, MethodObject::Email) => match seq.next_element() {
+                Ok(Some(value)) => RequestMethod::RecallEmail(value),
+                Err(err) => RequestMethod::invalid(err),
+                Ok(None) => {
+                    return Err(de::Error::invalid_length(1, &self));
+                }
+            },
             (MethodFunction::Parse, MethodObject::Email) => match seq.next_element() {
                 Ok(Some(value)) => RequestMethod::Parse(ParseRequestMethod::Email(value)),
                 Err(err) => RequestMethod::invalid(err),
@@ -674,6 +686,13 @@ impl<'de> Visitor<'de> for CallVisitor {
                     return Err(de::Error::invalid_length(1, &self));
                 }
             },
+            (MethodFunction::Query, MethodObject::SieveLog) => match seq.next_element() {
+                Ok(Some(value)) => RequestMethod::SieveLog(value),
+                Err(err) => RequestMethod::invalid(err),
+                Ok(None) => {
+                    return Err(de::Error::invalid_length(1, &self));
+                }
+            },
             (MethodFunction::Echo, MethodObject::Core) => match seq.next_element() {
                 Ok(Some(value)) => RequestMethod::Echo(value),
                 Err(err) => RequestMethod::invalid(err),
@@ -15,7 +15,7 @@ use serde::{
     Deserialize, Deserializer,
     de::{self, MapAccess, Visitor},
 };
-use std::{borrow::Cow, collections::HashMap, fmt};
+use std::{borrow::Cow, collections::HashMap, fmt, time::Instant};
 use types::type_state::DataType;
 
 #[derive(Debug)]
@@ -106,12 +106,17 @@ enum MessageType {
 impl<'x> WebSocketMessage<'x> {
     pub fn parse(json: &'x [u8], max_calls: usize, max_size: usize) -> trc::Result<Self> {
         if json.len() <= max_size {
+            let parse_start = Instant::now();
             match serde_json::from_slice::<Self>(json) {
                 Ok(WebSocketMessage::Request(req))
                     if req.request.method_calls.len() > max_calls =>
                 {
                     Err(trc::LimitEvent::CallsIn.into_err())
                 }
+                Ok(WebSocketMessage::Request(mut req)) => {
+                    req.request.parse_time = parse_start.elapsed();
+                    Ok(WebSocketMessage::Request(req))
+                }
                 Ok(msg) => Ok(msg),
                 Err(err) => Err(trc::JmapEvent::NotRequest
                     .into_err()
@@ -20,8 +20,10 @@ use crate::{
         parse::ParseResponse,
         query::QueryResponse,
         query_changes::QueryChangesResponse,
+        recall::EmailRecallResponse,
         search_snippet::GetSearchSnippetResponse,
         set::SetResponse,
+        sieve_log::SieveLogQueryResponse,
         upload::BlobUploadResponse,
         validate::ValidateSieveScriptResponse,
     },
@@ -53,6 +55,7 @@ use crate::{
     request::{Call, method::MethodName},
 };
 use jmap_tools::{Null, Value};
+use serde_json::value::RawValue;
 use std::collections::HashMap;
 
 #[derive(Debug, serde::Serialize)]
@@ -63,15 +66,18 @@ pub enum ResponseMethod<'x> {
     Changes(ChangesResponseMethod),
     Copy(CopyResponseMethod),
     ImportEmail(ImportEmailResponse),
+    RecallEmail(EmailRecallResponse),
     Parse(ParseResponseMethod),
     QueryChanges(QueryChangesResponse),
     Query(QueryResponse),
     SearchSnippet(GetSearchSnippetResponse),
     ValidateScript(ValidateSieveScriptResponse),
+    SieveLog(SieveLogQueryResponse),
     LookupBlob(BlobLookupResponse),
     UploadBlob(BlobUploadResponse),
     Echo(Value<'x, Null, Null>),
     Error(MethodErrorWrapper),
+    Serialized(Box<RawValue>),
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -431,6 +437,12 @@ impl<'x> From<ImportEmailResponse> for ResponseMethod<'x> {
     }
 }
 
+impl<'x> From<EmailRecallResponse> for ResponseMethod<'x> {
+    fn from(value: EmailRecallResponse) -> Self {
+        ResponseMethod::RecallEmail(value)
+    }
+}
+
 impl<'x> From<ParseResponse<Email>> for ResponseMethod<'x> {
     fn from(value: ParseResponse<Email>) -> Self {
         ResponseMethod::Parse(ParseResponseMethod::Email(value))
@@ -467,6 +479,12 @@ impl<'x> From<ValidateSieveScriptResponse> for ResponseMethod<'x> {
     }
 }
 
+impl<'x> From<SieveLogQueryResponse> for ResponseMethod<'x> {
+    fn from(value: SieveLogQueryResponse) -> Self {
+        ResponseMethod::SieveLog(value)
+    }
+}
+
 impl<'x> From<BlobLookupResponse> for ResponseMethod<'x> {
     fn from(value: BlobLookupResponse) -> Self {
         ResponseMethod::LookupBlob(value)
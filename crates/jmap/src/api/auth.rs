@@ -4,17 +4,17 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use common::auth::AccessToken;
+use common::{Server, auth::AccessToken};
 use jmap_proto::{
     method::set::SetRequest,
     object::JmapObject,
     request::{
         CopyRequestMethod, GetRequestMethod, ParseRequestMethod, QueryChangesRequestMethod,
-        QueryRequestMethod, RequestMethod, SetRequestMethod, method::MethodObject,
+        QueryRequestMethod, Request, RequestMethod, SetRequestMethod, method::MethodObject,
         reference::MaybeResultReference,
     },
 };
-use registry::schema::enums::Permission;
+use registry::schema::{enums::Permission, prelude::ObjectType};
 use types::{collection::Collection, id::Id};
 
 pub trait JmapAuthorization {
@@ -28,6 +28,14 @@ pub trait JmapAuthorization {
     -> trc::Result<&Self>;
 }
 
+pub trait JmapStepUp: Sync + Send {
+    fn assert_jmap_request_step_up(
+        &self,
+        request: &Request,
+        access_token: &AccessToken,
+    ) -> trc::Result<()>;
+}
+
 impl JmapAuthorization for AccessToken {
     fn assert_is_member(&self, account_id: Id) -> trc::Result<&Self> {
         if self.is_member(account_id.document_id()) {
@@ -203,7 +211,13 @@ impl JmapAuthorization for AccessToken {
                         let MethodObject::Registry(object_type) = object else {
                             unreachable!()
                         };
-                        let set_permissions = object_type.set_permission();
+                        let mut set_permissions = object_type.set_permission();
+                        if object_type == ObjectType::Action
+                            && !self.has_permission(set_permissions[0])
+                        {
+                            // Signing out everywhere is a self-service action
+                            set_permissions[0] = Permission::ActionSignOutEverywhere;
+                        }
                         validate_set(
                             s,
                             self,
@@ -238,6 +252,7 @@ impl JmapAuthorization for AccessToken {
                 | MethodObject::SearchSnippet
                 | MethodObject::VacationResponse
                 | MethodObject::SieveScript
+                | MethodObject::SieveLog
                 | MethodObject::Registry(_) => Permission::JmapEmailChanges,
             },
             RequestMethod::Copy(m) => match &m {
@@ -248,6 +263,7 @@ impl JmapAuthorization for AccessToken {
                 CopyRequestMethod::FileNode(_) => Permission::JmapFileNodeCopy,
             },
             RequestMethod::ImportEmail(_) => Permission::JmapEmailImport,
+            RequestMethod::RecallEmail(_) => Permission::JmapEmailRecall,
             RequestMethod::Parse(m) => match &m {
                 ParseRequestMethod::Email(_) => Permission::JmapEmailParse,
                 ParseRequestMethod::ContactCard(_) => Permission::JmapContactCardParse,
@@ -300,6 +316,7 @@ impl JmapAuthorization for AccessToken {
             },
             RequestMethod::SearchSnippet(_) => Permission::JmapSearchSnippetGet,
             RequestMethod::ValidateScript(_) => Permission::JmapSieveScriptValidate,
+            RequestMethod::SieveLog(_) => Permission::JmapSieveLogQuery,
             RequestMethod::LookupBlob(_) => Permission::JmapBlobLookup,
             RequestMethod::UploadBlob(_) => Permission::JmapBlobUpload,
             RequestMethod::Echo(_) => Permission::JmapCoreEcho,
@@ -316,6 +333,50 @@ impl JmapAuthorization for AccessToken {
     }
 }
 
+impl JmapStepUp for Server {
+    fn assert_jmap_request_step_up(
+        &self,
+        request: &Request,
+        access_token: &AccessToken,
+    ) -> trc::Result<()> {
+        if self.core.network.security.step_up_permissions.is_empty() {
+            return Ok(());
+        }
+
+        for call in &request.method_calls {
+            let MethodObject::Registry(object_type) = call.name.obj else {
+                continue;
+            };
+
+            match &call.method {
+                RequestMethod::Get(GetRequestMethod::Registry(_)) => {
+                    self.assert_step_up(access_token, object_type.get_permission())?;
+                }
+                RequestMethod::Query(QueryRequestMethod::Registry(_)) => {
+                    self.assert_step_up(access_token, object_type.query_permission())?;
+                }
+                RequestMethod::Set(SetRequestMethod::Registry(set)) => {
+                    let [create, update, destroy] = object_type.set_permission();
+                    if set.create.as_ref().is_some_and(|objs| !objs.is_empty()) {
+                        self.assert_step_up(access_token, create)?;
+                    }
+                    if set.update.as_ref().is_some_and(|objs| !objs.is_empty()) {
+                        self.assert_step_up(access_token, update)?;
+                    }
+                    if set.destroy.as_ref().is_some_and(|objs| match objs {
+                        MaybeResultReference::Value(v) => !v.is_empty(),
+                        MaybeResultReference::Reference(_) => true,
+                    }) {
+                        self.assert_step_up(access_token, destroy)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 fn validate_set<T: JmapObject>(
     set: &SetRequest<'_, T>,
     access_token: &AccessToken,
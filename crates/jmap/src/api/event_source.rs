@@ -97,6 +97,9 @@ impl EventSourceHandler for Server {
 
         // Register with push manager
         let mut push_rx = self.subscribe_push_manager(&access_token, types).await?;
+        let mut change_floor = self
+            .acquire_change_floor(access_token.member_ids().collect())
+            .await;
         let mut changed: VecMap<Id, VecMap<DataType, State>> = VecMap::new();
         let throttle = self.core.jmap.event_source_throttle;
 
@@ -113,6 +116,7 @@ impl EventSourceHandler for Server {
                         Ok(Some(notification)) => {
                             match notification {
                                 PushNotification::StateChange(state_change) => {
+                                    change_floor.notified(state_change.account_id, state_change.change_id).await;
                                     for type_state in state_change.types {
                                         changed
                                             .get_mut_or_insert(state_change.account_id.into())
@@ -127,6 +131,7 @@ impl EventSourceHandler for Server {
                                 }
                                 PushNotification::EmailPush(email_push) => {
                                     let state_change = email_push.to_state_change();
+                                    change_floor.notified(state_change.account_id, state_change.change_id).await;
                                     for type_state in state_change.types {
                                         changed
                                             .get_mut_or_insert(state_change.account_id.into())
@@ -162,6 +162,7 @@ impl ToRequestError for trc::Error {
                 trc::AuthEvent::MfaRequired => {
                     RequestError::blank(402, "MFA code required", self.as_ref().message())
                 }
+                trc::AuthEvent::StepUpRequired => RequestError::step_up_required(),
                 trc::AuthEvent::TooManyAttempts => {
                     let mut err = RequestError::too_many_auth_attempts();
                     if let Some(reset) = self.value(trc::Key::Expires).and_then(|v| v.to_uint()) {
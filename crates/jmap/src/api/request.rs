@@ -6,7 +6,7 @@
 
 use crate::{
     addressbook::{get::AddressBookGet, set::AddressBookSet},
-    api::auth::JmapAuthorization,
+    api::{auth::JmapAuthorization, session::SessionHandler},
     blob::{copy::BlobCopy, get::BlobOperations, upload::BlobUpload},
     calendar::{get::CalendarGet, set::CalendarSet},
     calendar_event::{
@@ -24,7 +24,7 @@ use crate::{
     },
     email::{
         copy::JmapEmailCopy, get::EmailGet, import::EmailImport, parse::EmailParse,
-        query::EmailQuery, set::EmailSet, snippet::EmailSearchSnippet,
+        query::EmailQuery, recall::EmailRecallHandler, set::EmailSet, snippet::EmailSearchSnippet,
     },
     file::{copy::FileNodeCopy, get::FileNodeGet, query::FileNodeQuery, set::FileNodeSet},
     identity::{get::IdentityGet, set::IdentitySet},
@@ -38,29 +38,47 @@ use crate::{
         get::ShareNotificationGet, query::ShareNotificationQuery, set::ShareNotificationSet,
     },
     sieve::{
-        get::SieveScriptGet, query::SieveScriptQuery, set::SieveScriptSet,
+        get::SieveScriptGet, log::SieveLogQuery, query::SieveScriptQuery, set::SieveScriptSet,
         validate::SieveScriptValidate,
     },
     submission::{get::EmailSubmissionGet, query::EmailSubmissionQuery, set::EmailSubmissionSet},
     thread::get::ThreadGet,
     vacation::{get::VacationResponseGet, set::VacationResponseSet},
 };
-use common::{Server, auth::AccessToken};
+use common::{Server, auth::AccessToken, telemetry::slow::SlowRequest};
 use http_proto::HttpSessionData;
 use jmap_proto::{
+    method::query::{Filter, QueryRequest},
+    object::{
+        JmapObject,
+        email::{EmailFilter, EmailProperty, EmailValue},
+    },
     request::{
-        Call, CopyRequestMethod, GetRequestMethod, INVALID_ACCOUNT_ID, ParseRequestMethod,
-        QueryRequestMethod, Request, RequestMethod, SetRequestMethod,
+        Call, CopyRequestMethod, GetRequestMethod, INVALID_ACCOUNT_ID, MaybeInvalid,
+        ParseRequestMethod, QueryChangesRequestMethod, QueryRequestMethod, Request, RequestMethod,
+        SetRequestMethod,
         capability::Capability,
         method::{MethodName, MethodObject},
+        reference::MaybeResultReference,
     },
     response::{Response, ResponseMethod, SetResponseMethod},
 };
+use jmap_tools::{Key, Value};
 use std::future::Future;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use store::{dispatch::timing::StoreTiming, write::now};
 use trc::JmapEvent;
 use types::{collection::Collection, id::Id};
 
+pub struct SlowMethodCall {
+    method_name: MethodName,
+    arguments: String,
+    store_reads: u64,
+    store_time: Duration,
+    elapsed: Duration,
+    threshold: Duration,
+}
+
 pub trait RequestHandler: Sync + Send {
     fn handle_jmap_request<'x>(
         &self,
@@ -70,6 +88,16 @@ pub trait RequestHandler: Sync + Send {
     ) -> impl Future<Output = Response<'x>> + Send;
 
     fn handle_method_call<'x>(
+        &self,
+        method: RequestMethod<'x>,
+        method_name: MethodName,
+        access_token: &AccessToken,
+        next_call: &mut Option<Call<RequestMethod<'x>>>,
+        slow_call: &mut Option<SlowMethodCall>,
+        session: &HttpSessionData,
+    ) -> impl Future<Output = trc::Result<ResponseMethod<'x>>> + Send;
+
+    fn dispatch_method_call<'x>(
         &self,
         method: RequestMethod<'x>,
         method_name: MethodName,
@@ -88,8 +116,17 @@ impl RequestHandler for Server {
     ) -> Response<'x> {
         let add_created_ids = request.created_ids.is_some();
         let using = request.using;
+        let parse_time = request.parse_time;
+        let mut slow_calls = Vec::new();
+        let session_state = match self.session_state(access_token).await {
+            Ok(session_state) => session_state,
+            Err(err) => {
+                trc::error!(err.span_id(session.session_id));
+                access_token.state()
+            }
+        };
         let mut response = Response::new(
-            access_token.state(),
+            session_state,
             request.created_ids.unwrap_or_default(),
             request.method_calls.len(),
         );
@@ -119,10 +156,24 @@ impl RequestHandler for Server {
                     );
                     continue;
                 }
+                if !using.contains(Capability::FollowUp)
+                    && let Some(property) = follow_up_property(&call.method)
+                {
+                    response.push_response(
+                        call.id,
+                        MethodName::error(),
+                        trc::JmapEvent::InvalidArguments.into_err().details(format!(
+                            "Property {property} requires capability {} which is not present in the \"using\" property.",
+                            Capability::FollowUp.as_str()
+                        )),
+                    );
+                    continue;
+                }
             }
 
             loop {
                 let mut next_call = None;
+                let mut slow_call = None;
 
                 // Add response
                 let method_name = call.name.as_str();
@@ -132,6 +183,7 @@ impl RequestHandler for Server {
                         call.name,
                         access_token,
                         &mut next_call,
+                        &mut slow_call,
                         session,
                     )
                     .await
@@ -201,6 +253,9 @@ impl RequestHandler for Server {
                         }
 
                         response.push_response(call.id, call.name, method_response);
+                        if let Some(slow_call) = slow_call {
+                            slow_calls.push((response.method_responses.len() - 1, slow_call));
+                        }
                     }
                     Err(error) => {
                         let method_error = error.clone();
@@ -231,6 +286,52 @@ impl RequestHandler for Server {
             response.created_ids.clear();
         }
 
+        // Slow responses are serialized here rather than when the whole response is
+        // written, so that their serialization time can be measured without serializing twice
+        for (index, slow_call) in slow_calls {
+            let method_response = &mut response.method_responses[index].method;
+            let results = result_count(method_response);
+            let serialize_start = Instant::now();
+            let Ok(serialized) = serde_json::value::to_raw_value(method_response) else {
+                continue;
+            };
+            let serialize_time = serialize_start.elapsed();
+            let size = serialized.get().len();
+            *method_response = ResponseMethod::Serialized(serialized);
+
+            trc::event!(
+                Jmap(JmapEvent::SlowMethodCall),
+                Id = slow_call.method_name.as_str(),
+                SpanId = session.session_id,
+                AccountId = access_token.account_id(),
+                Details = slow_call.arguments.clone(),
+                Total = results,
+                ParseElapsed = parse_time,
+                StoreReads = slow_call.store_reads,
+                StoreElapsed = slow_call.store_time,
+                SerializeElapsed = serialize_time,
+                Size = size,
+                Limit = slow_call.threshold,
+                Elapsed = slow_call.elapsed
+            );
+
+            self.inner.data.slow_requests.push(SlowRequest {
+                timestamp: now(),
+                session_id: session.session_id,
+                account_id: access_token.account_id(),
+                method: slow_call.method_name.as_str().into_owned(),
+                arguments: slow_call.arguments,
+                results,
+                size,
+                store_reads: slow_call.store_reads,
+                parse_time_ms: parse_time.as_millis() as u64,
+                store_time_ms: slow_call.store_time.as_millis() as u64,
+                serialize_time_ms: serialize_time.as_millis() as u64,
+                elapsed_ms: slow_call.elapsed.as_millis() as u64,
+                threshold_ms: slow_call.threshold.as_millis() as u64,
+            });
+        }
+
         response
     }
 
@@ -240,6 +341,7 @@ impl RequestHandler for Server {
         method_name: MethodName,
         access_token: &AccessToken,
         next_call: &mut Option<Call<RequestMethod<'x>>>,
+        slow_call: &mut Option<SlowMethodCall>,
         session: &HttpSessionData,
     ) -> trc::Result<ResponseMethod<'x>> {
         let op_start = Instant::now();
@@ -247,7 +349,65 @@ impl RequestHandler for Server {
         // Check permissions
         access_token.assert_has_jmap_permission(&method, method_name.obj)?;
 
+        // Summarize the arguments before they are consumed, never including their content
+        let slow_method_threshold = self.core.jmap.slow_method_threshold(&method_name.as_str());
+        let arguments = slow_method_threshold.map(|_| method_summary(&method));
+
         // Handle method
+        let store_timing = StoreTiming::default();
+        let response = store_timing
+            .measure(self.dispatch_method_call(
+                method,
+                method_name,
+                access_token,
+                next_call,
+                session,
+            ))
+            .await?;
+        let elapsed = op_start.elapsed();
+
+        if self.core.jmap.method_profiling {
+            trc::event!(
+                Jmap(JmapEvent::MethodCall),
+                Id = method_name.as_str(),
+                SpanId = session.session_id,
+                AccountId = access_token.account_id(),
+                StoreReads = store_timing.reads(),
+                StoreElapsed = store_timing.read_time(),
+                Elapsed = elapsed,
+            );
+        } else {
+            trc::event!(
+                Jmap(JmapEvent::MethodCall),
+                Id = method_name.as_str(),
+                SpanId = session.session_id,
+                AccountId = access_token.account_id(),
+                Elapsed = elapsed,
+            );
+        }
+
+        if let Some(threshold) = slow_method_threshold.filter(|threshold| elapsed >= *threshold) {
+            *slow_call = Some(SlowMethodCall {
+                method_name,
+                arguments: arguments.unwrap_or_default(),
+                store_reads: store_timing.reads(),
+                store_time: store_timing.read_time(),
+                elapsed,
+                threshold,
+            });
+        }
+
+        Ok(response)
+    }
+
+    async fn dispatch_method_call<'x>(
+        &self,
+        method: RequestMethod<'x>,
+        method_name: MethodName,
+        access_token: &AccessToken,
+        next_call: &mut Option<Call<RequestMethod<'x>>>,
+        session: &HttpSessionData,
+    ) -> trc::Result<ResponseMethod<'x>> {
         let response = match method {
             RequestMethod::Get(req) => match req {
                 GetRequestMethod::Email(mut req) => {
@@ -480,7 +640,7 @@ impl RequestHandler for Server {
                     resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
                     access_token.assert_has_access(req.account_id, Collection::Mailbox)?;
 
-                    self.mailbox_set(*req, access_token).await?.into()
+                    self.mailbox_set(*req, access_token, session).await?.into()
                 }
                 SetRequestMethod::Identity(mut req) => {
                     resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
@@ -655,6 +815,16 @@ impl RequestHandler for Server {
 
                 self.email_import(*req, access_token, session).await?.into()
             }
+            RequestMethod::RecallEmail(mut req) => {
+                resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
+                if req.account_id.document_id() != access_token.account_id() {
+                    return Err(trc::JmapEvent::Forbidden
+                        .into_err()
+                        .details("Messages can only be recalled by their sender."));
+                }
+
+                self.email_recall(*req, access_token, session).await?.into()
+            }
             RequestMethod::Parse(req) => match req {
                 ParseRequestMethod::Email(mut req) => {
                     resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
@@ -688,11 +858,17 @@ impl RequestHandler for Server {
 
                 self.sieve_script_validate(*req, access_token).await?.into()
             }
+            RequestMethod::SieveLog(mut req) => {
+                resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
+                access_token.assert_is_member(req.account_id)?;
+
+                self.sieve_log_query(*req).await?.into()
+            }
             RequestMethod::LookupBlob(mut req) => {
                 resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
                 access_token.assert_is_member(req.account_id)?;
 
-                self.blob_lookup(*req).await?.into()
+                self.blob_lookup(*req, access_token).await?.into()
             }
             RequestMethod::UploadBlob(mut req) => {
                 resolve_account_id(&mut req.account_id, method_name.obj, access_token)?;
@@ -704,18 +880,136 @@ impl RequestHandler for Server {
             RequestMethod::Error(error) => return Err(error),
         };
 
-        trc::event!(
-            Jmap(JmapEvent::MethodCall),
-            Id = method_name.as_str(),
-            SpanId = session.session_id,
-            AccountId = access_token.account_id(),
-            Elapsed = op_start.elapsed(),
-        );
-
         Ok(response)
     }
 }
 
+fn method_summary(method: &RequestMethod<'_>) -> String {
+    match method {
+        RequestMethod::Query(req) => match req {
+            QueryRequestMethod::Email(req) => query_summary(req),
+            QueryRequestMethod::Mailbox(req) => query_summary(req),
+            QueryRequestMethod::EmailSubmission(req) => query_summary(req),
+            QueryRequestMethod::Sieve(req) => query_summary(req),
+            QueryRequestMethod::Principal(req) => query_summary(req),
+            QueryRequestMethod::Quota(req) => query_summary(req),
+            QueryRequestMethod::AddressBook(req) => query_summary(req),
+            QueryRequestMethod::ContactCard(req) => query_summary(req),
+            QueryRequestMethod::FileNode(req) => query_summary(req),
+            QueryRequestMethod::Calendar(req) => query_summary(req),
+            QueryRequestMethod::CalendarEvent(req) => query_summary(req),
+            QueryRequestMethod::CalendarEventNotification(req) => query_summary(req),
+            QueryRequestMethod::ShareNotification(req) => query_summary(req),
+            QueryRequestMethod::Registry(req) => query_summary(req),
+        },
+        RequestMethod::Changes(req) => {
+            format!("maxChanges={}", req.max_changes.unwrap_or_default())
+        }
+        _ => String::new(),
+    }
+}
+
+// Follow-up reminders extend Email and are only available to clients that
+// requested the follow-up capability
+fn follow_up_property(method: &RequestMethod<'_>) -> Option<&'static str> {
+    let property_name = |property: &EmailProperty| match property {
+        EmailProperty::FollowUpAt => Some("followUpAt"),
+        EmailProperty::FollowUpMailboxId => Some("followUpMailboxId"),
+        _ => None,
+    };
+    let object_property = |value: &Value<'_, EmailProperty, EmailValue>| {
+        value.as_object().and_then(|object| {
+            object.keys().find_map(|key| match key {
+                Key::Property(property) => property_name(property),
+                _ => None,
+            })
+        })
+    };
+    let filter_property = |filter: &[Filter<EmailFilter>]| {
+        filter.iter().find_map(|filter| match filter {
+            Filter::Property(EmailFilter::HasFollowUp(_)) => Some("hasFollowUp"),
+            _ => None,
+        })
+    };
+
+    match method {
+        RequestMethod::Get(GetRequestMethod::Email(req)) => match &req.properties {
+            Some(MaybeResultReference::Value(properties)) => {
+                properties.iter().find_map(|property| match property {
+                    MaybeInvalid::Value(property) => property_name(property),
+                    MaybeInvalid::Invalid(_) => None,
+                })
+            }
+            _ => None,
+        },
+        RequestMethod::Set(SetRequestMethod::Email(req)) => req
+            .create
+            .iter()
+            .flat_map(|create| create.values())
+            .chain(req.update.iter().flat_map(|update| update.values()))
+            .find_map(object_property),
+        RequestMethod::Query(QueryRequestMethod::Email(req)) => filter_property(&req.filter),
+        RequestMethod::QueryChanges(QueryChangesRequestMethod::Email(req)) => {
+            filter_property(&req.filter)
+        }
+        _ => None,
+    }
+}
+
+// Describes the shape of a query filter (operators and conditions) without its values
+fn query_summary<T: JmapObject>(req: &QueryRequest<T>) -> String {
+    let mut shape = String::with_capacity(req.filter.len() * 2);
+    let mut needs_comma = false;
+    for filter in &req.filter {
+        if needs_comma && !matches!(filter, Filter::Close) {
+            shape.push(',');
+        }
+        match filter {
+            Filter::Property(_) => {
+                shape.push('*');
+                needs_comma = true;
+            }
+            Filter::And => {
+                shape.push_str("AND(");
+                needs_comma = false;
+            }
+            Filter::Or => {
+                shape.push_str("OR(");
+                needs_comma = false;
+            }
+            Filter::Not => {
+                shape.push_str("NOT(");
+                needs_comma = false;
+            }
+            Filter::Close => {
+                shape.push(')');
+                needs_comma = true;
+            }
+        }
+    }
+
+    format!(
+        "filter={} sort={} position={} anchor={} limit={} calculateTotal={}",
+        if shape.is_empty() { "none" } else { &shape },
+        req.sort.as_ref().map_or(0, |sort| sort.len()),
+        req.position.unwrap_or_default(),
+        req.anchor.is_some(),
+        req.limit
+            .map_or_else(|| "none".to_string(), |limit| limit.to_string()),
+        req.calculate_total.unwrap_or_default()
+    )
+}
+
+fn result_count(response: &ResponseMethod<'_>) -> Option<usize> {
+    match response {
+        ResponseMethod::Query(response) => Some(response.ids.len()),
+        ResponseMethod::QueryChanges(response) => {
+            Some(response.added.len() + response.removed.len())
+        }
+        _ => None,
+    }
+}
+
 pub(crate) fn resolve_account_id(
     account_id: &mut Id,
     obj: MethodObject,
@@ -4,12 +4,19 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use common::{Server, auth::AccessToken};
+use common::{
+    Server,
+    auth::{AccessToken, AccountCache},
+};
 use jmap_proto::request::capability::{
-    Account, Capabilities, Capability, EmptyCapabilities, Session,
+    Account, Capabilities, Capability, CapabilityIds, EmptyCapabilities, Session,
+};
+use registry::schema::enums::StorageQuota;
+use std::{
+    future::Future,
+    hash::{Hash, Hasher},
 };
-use registry::schema::enums::Permission;
-use std::future::Future;
+use store::ahash::AHasher;
 use trc::AddContext;
 use types::id::Id;
 use utils::map::vec_map::VecMap;
@@ -20,6 +27,11 @@ pub trait SessionHandler: Sync + Send {
         base_url: String,
         access_token: &AccessToken,
     ) -> impl Future<Output = trc::Result<Session>> + Send;
+
+    fn session_state(
+        &self,
+        access_token: &AccessToken,
+    ) -> impl Future<Output = trc::Result<u32>> + Send;
 }
 
 impl SessionHandler for Server {
@@ -29,8 +41,13 @@ impl SessionHandler for Server {
         access_token: &AccessToken,
     ) -> trc::Result<Session> {
         let mut session = Session::new(base_url, &self.core.jmap.capabilities);
-        session.set_state(access_token.state());
-        let account_capabilities = &self.core.jmap.capabilities.account;
+        let mut state = AHasher::default();
+        access_token.state().hash(&mut state);
+        let capabilities = access_token.jmap_capabilities();
+        session
+            .capabilities
+            .inner
+            .retain(|capability| capabilities.contains(capability.key));
 
         // Set primary account
         let account = self
@@ -38,24 +55,22 @@ impl SessionHandler for Server {
             .await
             .caused_by(trc::location!())?;
         session.username = account.name().to_string();
+        hash_account_limits(&account, &mut state);
         let account_id = Id::from(access_token.account_id());
-        let mut account = Account {
+        let mut primary_account = Account {
             name: account.name().to_string(),
             is_personal: true,
             is_read_only: false,
-            account_capabilities: VecMap::with_capacity(account_capabilities.len()),
+            account_capabilities: VecMap::with_capacity(capabilities.0.count_ones() as usize),
         };
-        for capability in access_token.account_capabilities() {
+        for capability in account_capabilities(capabilities) {
             session.primary_accounts.append(capability, account_id);
-            account.account_capabilities.append(
+            primary_account.account_capabilities.append(
                 capability,
-                account_capabilities
-                    .get(&capability)
-                    .map(|v| v.to_account_capabilities(account_id.into(), true))
-                    .unwrap_or_else(|| Capabilities::Empty(EmptyCapabilities::default())),
+                self.account_capability(capability, &account, account_id, true),
             );
         }
-        session.accounts.append(account_id, account);
+        session.accounts.append(account_id, primary_account);
 
         // Add secondary accounts
         for &account_id in access_token.secondary_ids() {
@@ -72,61 +87,112 @@ impl SessionHandler for Server {
                 );
                 continue;
             };
+            hash_account_limits(&account, &mut state);
 
             let account_id = Id::from(account_id);
-            let mut account = Account {
+            let mut secondary_account = Account {
                 name: account.name().to_string(),
                 is_personal: false,
                 is_read_only: false,
-                account_capabilities: VecMap::with_capacity(account_capabilities.len()),
+                account_capabilities: VecMap::with_capacity(capabilities.0.count_ones() as usize),
             };
-            for capability in access_token.account_capabilities() {
-                account.account_capabilities.append(
+            for capability in account_capabilities(capabilities) {
+                secondary_account.account_capabilities.append(
                     capability,
-                    account_capabilities
-                        .get(&capability)
-                        .map(|v| v.to_account_capabilities(account_id.into(), is_owner))
-                        .unwrap_or_else(|| Capabilities::Empty(EmptyCapabilities::default())),
+                    self.account_capability(capability, &account, account_id, is_owner),
                 );
             }
-            session.accounts.append(account_id, account);
+            session.accounts.append(account_id, secondary_account);
         }
+        session.set_state(state.finish() as u32);
 
         Ok(session)
     }
+
+    // The session state changes whenever the limits advertised in the
+    // session object do, so clients know when to refetch it
+    async fn session_state(&self, access_token: &AccessToken) -> trc::Result<u32> {
+        let mut state = AHasher::default();
+        access_token.state().hash(&mut state);
+        hash_account_limits(
+            &self
+                .account(access_token.account_id())
+                .await
+                .caused_by(trc::location!())?,
+            &mut state,
+        );
+        for &account_id in access_token.secondary_ids() {
+            if let Some(account) = self
+                .try_account(account_id)
+                .await
+                .caused_by(trc::location!())?
+            {
+                hash_account_limits(&account, &mut state);
+            }
+        }
+        Ok(state.finish() as u32)
+    }
 }
 
-trait AccountCapabilities {
-    fn account_capabilities(&self) -> impl Iterator<Item = Capability>;
+trait AccountCapability {
+    fn account_capability(
+        &self,
+        capability: Capability,
+        account: &AccountCache,
+        account_id: Id,
+        may_create: bool,
+    ) -> Capabilities;
 }
 
-impl AccountCapabilities for AccessToken {
-    fn account_capabilities(&self) -> impl Iterator<Item = Capability> {
-        Capability::all_capabilities()
-            .iter()
-            .filter(move |capability| {
-                let permission = match capability {
-                    Capability::Mail | Capability::MailShare => Permission::JmapEmailGet,
-                    Capability::Submission => Permission::JmapEmailSubmissionCreate,
-                    Capability::VacationResponse => Permission::JmapVacationResponseGet,
-                    Capability::Contacts => Permission::JmapContactCardGet,
-                    Capability::ContactsParse => Permission::JmapContactCardParse,
-                    Capability::Calendars => Permission::JmapCalendarEventGet,
-                    Capability::CalendarsParse => Permission::JmapCalendarEventParse,
-                    Capability::Sieve => Permission::JmapSieveScriptGet,
-                    Capability::Blob => Permission::JmapBlobGet,
-                    Capability::Quota => Permission::JmapQuotaGet,
-                    Capability::FileNode => Permission::JmapFileNodeGet,
-                    Capability::WebSocket
-                    | Capability::Principals
-                    | Capability::PrincipalsAvailability
-                    | Capability::Stalwart => return true,
-                    Capability::Core | Capability::PrincipalsOwner | Capability::WebPushVapid => {
-                        return false;
-                    }
-                };
-                self.has_permission(permission)
-            })
-            .copied()
+impl AccountCapability for Server {
+    fn account_capability(
+        &self,
+        capability: Capability,
+        account: &AccountCache,
+        account_id: Id,
+        may_create: bool,
+    ) -> Capabilities {
+        let Some(capabilities) = self.core.jmap.capabilities.account.get(&capability) else {
+            return Capabilities::Empty(EmptyCapabilities::default());
+        };
+
+        // Apply the limits configured for this account
+        match capabilities.to_account_capabilities(account_id.into(), may_create) {
+            Capabilities::Mail(mut mail) => {
+                if let Some(max_message_size) = account.max_message_size {
+                    mail.max_size_attachments_per_email =
+                        mail.max_size_attachments_per_email.min(max_message_size);
+                }
+                Capabilities::Mail(mail)
+            }
+            Capabilities::SieveAccount(mut sieve) => {
+                if let Some(quota) = account.object_quotas() {
+                    sieve.max_scripts = quota.get(StorageQuota::MaxSieveScripts) as u64;
+                }
+                Capabilities::SieveAccount(sieve)
+            }
+            capabilities => capabilities,
+        }
     }
 }
+
+fn account_capabilities(capabilities: CapabilityIds) -> impl Iterator<Item = Capability> {
+    Capability::all_capabilities()
+        .iter()
+        .filter(move |capability| {
+            !matches!(
+                capability,
+                Capability::Core | Capability::PrincipalsOwner | Capability::WebPushVapid
+            ) && capabilities.contains(**capability)
+        })
+        .copied()
+}
+
+fn hash_account_limits(account: &AccountCache, state: &mut AHasher) {
+    account.id.hash(state);
+    account.max_message_size.hash(state);
+    account
+        .object_quotas()
+        .map(|quota| quota.get(StorageQuota::MaxSieveScripts))
+        .hash(state);
+}
@@ -5,8 +5,11 @@
  */
 
 use super::download::BlobDownload;
-use common::{Server, auth::AccessToken};
-use email::message::metadata::MessageData;
+use common::{MessageStoreCache, Server, auth::AccessToken};
+use email::{
+    cache::{MessageCacheFetch, email::MessageCacheAccess},
+    message::metadata::{ArchivedMetadataPartType, MessageMetadata, PART_SIZE_MASK},
+};
 use jmap_proto::{
     method::{
         get::{GetRequest, GetResponse},
@@ -25,8 +28,11 @@ use store::{
     write::{AlignedBytes, Archive},
 };
 use trc::AddContext;
-use types::{blob::BlobClass, collection::Collection, id::Id, type_state::DataType};
-use utils::map::vec_map::VecMap;
+use types::{
+    blob::BlobClass, blob_hash::BlobHash, collection::Collection, field::EmailField, id::Id,
+    type_state::DataType,
+};
+use utils::{chained_bytes::ChainedBytes, map::vec_map::VecMap};
 
 pub trait BlobOperations: Sync + Send {
     fn blob_get(
@@ -38,7 +44,15 @@ pub trait BlobOperations: Sync + Send {
     fn blob_lookup(
         &self,
         request: BlobLookupRequest,
+        access_token: &AccessToken,
     ) -> impl Future<Output = trc::Result<BlobLookupResponse>> + Send;
+
+    fn emails_referencing_blob(
+        &self,
+        account_id: u32,
+        cache: &MessageStoreCache,
+        contents: &[u8],
+    ) -> impl Future<Output = trc::Result<Vec<u32>>> + Send;
 }
 
 impl BlobOperations for Server {
@@ -159,10 +173,15 @@ impl BlobOperations for Server {
         Ok(response)
     }
 
-    async fn blob_lookup(&self, request: BlobLookupRequest) -> trc::Result<BlobLookupResponse> {
+    async fn blob_lookup(
+        &self,
+        request: BlobLookupRequest,
+        access_token: &AccessToken,
+    ) -> trc::Result<BlobLookupResponse> {
         let mut include_email = false;
         let mut include_mailbox = false;
         let mut include_thread = false;
+        let mut include_sieve = false;
 
         let type_names = request
             .type_names
@@ -179,7 +198,10 @@ impl BlobOperations for Server {
                         DataType::Thread => {
                             include_thread = true;
                         }
-                        _ => (),
+                        DataType::SieveScript => {
+                            include_sieve = true;
+                        }
+                        _ => return Err(trc::JmapEvent::UnknownDataType.into_err()),
                     }
 
                     Ok(value)
@@ -193,69 +215,79 @@ impl BlobOperations for Server {
             list: Vec::with_capacity(request.ids.len()),
             not_found: vec![],
         };
+        let cache = if include_email || include_mailbox || include_thread {
+            Some(
+                self.get_cached_messages(req_account_id)
+                    .await
+                    .caused_by(trc::location!())?,
+            )
+        } else {
+            None
+        };
 
         for id in request.ids.into_valid() {
-            let mut matched_ids = VecMap::new();
+            if !self.has_access_blob(&id, access_token).await? {
+                response.not_found.push(id);
+                continue;
+            }
 
-            match &id.class {
-                BlobClass::Linked {
-                    account_id,
-                    collection,
-                    document_id,
-                } if *account_id == req_account_id => {
-                    let collection = Collection::from(*collection);
-                    if collection == Collection::Email {
-                        if let Some(data_) = self
-                            .store()
-                            .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
-                                req_account_id,
-                                Collection::Email,
-                                *document_id,
-                            ))
-                            .await?
-                        {
-                            let data = data_
-                                .unarchive::<MessageData>()
-                                .caused_by(trc::location!())?;
-                            if include_email {
-                                matched_ids.append(
-                                    DataType::Email,
-                                    vec![Id::from_parts(u32::from(data.thread_id), *document_id)],
-                                );
-                            }
-                            if include_thread {
-                                matched_ids.append(
-                                    DataType::Thread,
-                                    vec![Id::from(u32::from(data.thread_id))],
-                                );
-                            }
-                            if include_mailbox {
-                                matched_ids.append(
-                                    DataType::Mailbox,
-                                    data.mailboxes
-                                        .iter()
-                                        .map(|m| {
-                                            debug_assert!(m.uid != 0);
-                                            Id::from(u32::from(m.mailbox_id))
-                                        })
-                                        .collect::<Vec<_>>(),
-                                );
-                            }
+            // Blobs with no references return empty lists
+            let mut matched_ids = VecMap::with_capacity(type_names.len());
+            for type_name in &type_names {
+                matched_ids.append(*type_name, Vec::new());
+            }
+
+            if let Some(cache) = &cache {
+                // Attachment blob ids share the hash of the message containing them
+                let mut document_ids = self
+                    .blob_references(req_account_id, Collection::Email, &id.hash)
+                    .await
+                    .caused_by(trc::location!())?;
+
+                // Uploaded blobs are not linked to the messages that include them
+                // as attachments, match them against the decoded message parts
+                if document_ids.is_empty()
+                    && id.section.is_none()
+                    && matches!(id.class, BlobClass::Reserved { .. })
+                    && let Some(contents) = self.blob_download(&id, access_token).await?
+                {
+                    document_ids = self
+                        .emails_referencing_blob(req_account_id, cache, &contents)
+                        .await?;
+                }
+
+                for document_id in document_ids {
+                    let Some(email) = cache.email_by_id(&document_id) else {
+                        continue;
+                    };
+                    if include_email && let Some(ids) = matched_ids.get_mut(&DataType::Email) {
+                        ids.push(Id::from_parts(email.thread_id, document_id));
+                    }
+                    if include_thread && let Some(ids) = matched_ids.get_mut(&DataType::Thread) {
+                        let thread_id = Id::from(email.thread_id);
+                        if !ids.contains(&thread_id) {
+                            ids.push(thread_id);
                         }
-                    } else {
-                        match DataType::try_from(collection) {
-                            Ok(data_type) if type_names.contains(&data_type) => {
-                                matched_ids.append(data_type, vec![Id::from(*document_id)]);
+                    }
+                    if include_mailbox && let Some(ids) = matched_ids.get_mut(&DataType::Mailbox) {
+                        for mailbox in email.mailboxes.iter() {
+                            let mailbox_id = Id::from(mailbox.mailbox_id);
+                            if !ids.contains(&mailbox_id) {
+                                ids.push(mailbox_id);
                             }
-                            _ => (),
                         }
                     }
                 }
-                BlobClass::Reserved { account_id, .. } if *account_id == req_account_id => {}
-                _ => {
-                    response.not_found.push(id);
-                    continue;
-                }
+            }
+
+            if include_sieve && let Some(ids) = matched_ids.get_mut(&DataType::SieveScript) {
+                ids.extend(
+                    self.blob_references(req_account_id, Collection::SieveScript, &id.hash)
+                        .await
+                        .caused_by(trc::location!())?
+                        .into_iter()
+                        .map(Id::from),
+                );
             }
 
             response.list.push(BlobInfo { id, matched_ids });
@@ -263,4 +295,74 @@ impl BlobOperations for Server {
 
         Ok(response)
     }
+
+    async fn emails_referencing_blob(
+        &self,
+        account_id: u32,
+        cache: &MessageStoreCache,
+        contents: &[u8],
+    ) -> trc::Result<Vec<u32>> {
+        let mut document_ids = Vec::new();
+
+        for email in cache.emails.items.iter() {
+            let Some(metadata_) = self
+                .store()
+                .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                    account_id,
+                    Collection::Email,
+                    email.document_id,
+                    EmailField::Metadata,
+                ))
+                .await
+                .caused_by(trc::location!())?
+            else {
+                continue;
+            };
+            let metadata = metadata_
+                .unarchive::<MessageMetadata>()
+                .caused_by(trc::location!())?;
+
+            // The blob is the decoded contents of one of the message parts
+            let candidates = metadata
+                .contents
+                .first()
+                .into_iter()
+                .flat_map(|message| message.parts.iter())
+                .filter(|part| {
+                    matches!(
+                        part.body,
+                        ArchivedMetadataPartType::Text
+                            | ArchivedMetadataPartType::Html
+                            | ArchivedMetadataPartType::Binary
+                            | ArchivedMetadataPartType::InlineBinary
+                    ) && (u32::from(part.flags) & PART_SIZE_MASK) as usize == contents.len()
+                })
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                continue;
+            }
+            let blob_hash = BlobHash::from(&metadata.blob_hash);
+            let Some(raw_body) = self
+                .blob_store()
+                .get_blob(blob_hash.as_slice(), 0..usize::MAX)
+                .await
+                .caused_by(trc::location!())?
+            else {
+                continue;
+            };
+            let raw_message = ChainedBytes::new(metadata.raw_headers.as_ref()).with_last(
+                raw_body
+                    .get(metadata.blob_body_offset.to_native() as usize..)
+                    .unwrap_or_default(),
+            );
+            if candidates
+                .iter()
+                .any(|part| part.contents(&raw_message).as_ref() == contents)
+            {
+                document_ids.push(email.document_id);
+            }
+        }
+
+        Ok(document_ids)
+    }
 }
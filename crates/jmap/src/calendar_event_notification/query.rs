@@ -82,7 +82,8 @@ impl CalendarEventNotificationQuery for Server {
                         }),
                     },
                 )
-                .ascending(),
+                .ascending()
+                .read_only(),
                 |key, value| {
                     let document_id = key.deserialize_be_u32(key.len() - U32_LEN)?;
                     notifications.push(Notification {
@@ -216,6 +216,8 @@ impl ChangesLookup for Server {
                 (0, changelog)
             }
             State::Exact(change_id) => {
+                self.change_floor_seen(account_id, *change_id).await;
+
                 let last_state = match collection {
                     SyncCollection::Calendar | SyncCollection::AddressBook => self
                         .fetch_dav_resources(access_token.account_id(), account_id, collection)
@@ -251,6 +253,9 @@ impl ChangesLookup for Server {
                 )
             }
             State::Intermediate(intermediate_state) => {
+                self.change_floor_seen(account_id, intermediate_state.from_id)
+                    .await;
+
                 let changelog = self
                     .store()
                     .changes(
@@ -411,6 +416,7 @@ impl IntermediateChangesResponse {
             | MethodObject::SearchSnippet
             | MethodObject::VacationResponse
             | MethodObject::SieveScript
+            | MethodObject::SieveLog
             | MethodObject::Principal
             | MethodObject::Quota
             | MethodObject::Registry(_) => unreachable!(),
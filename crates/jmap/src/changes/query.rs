@@ -13,6 +13,7 @@ use crate::{
     submission::query::EmailSubmissionQuery,
 };
 use common::{Server, auth::AccessToken};
+use email::cache::{MessageCacheFetch, email::MessageCacheAccess};
 use jmap_proto::{
     method::{
         changes::{ChangesRequest, ChangesResponse},
@@ -22,6 +23,9 @@ use jmap_proto::{
     request::{QueryChangesRequestMethod, method::MethodObject},
 };
 use std::future::Future;
+use store::ahash::AHashSet;
+use trc::AddContext;
+use types::{acl::Acl, id::Id};
 
 pub trait QueryChanges: Sync + Send {
     fn query_changes(
@@ -43,6 +47,8 @@ impl QueryChanges for Server {
         let changes;
         let has_changes;
         let up_to_id;
+        let max_changes;
+        let mut collapse_threads = None;
 
         match request {
             QueryChangesRequestMethod::Email(mut request) => {
@@ -58,14 +64,19 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
-                is_mutable = request.filter.iter().any(|f| !f.is_immutable())
+                max_changes = request.max_changes;
+                if request.arguments.collapse_threads.unwrap_or(false) {
+                    collapse_threads = Some(request.account_id.document_id());
+                }
+                is_mutable = collapse_threads.is_some()
+                    || request.filter.iter().any(|f| !f.is_immutable())
                     || request
                         .sort
                         .as_ref()
@@ -86,13 +97,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self.mailbox_query((*request).into(), access_token).await?;
             }
             QueryChangesRequestMethod::EmailSubmission(mut request) => {
@@ -112,13 +124,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self.email_submission_query((*request).into()).await?;
             }
             QueryChangesRequestMethod::ContactCard(mut request) => {
@@ -138,13 +151,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self
                     .contact_card_query((*request).into(), access_token)
                     .await?;
@@ -166,13 +180,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self
                     .file_node_query((*request).into(), access_token)
                     .await?;
@@ -194,13 +209,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self
                     .calendar_event_query((*request).into(), access_token)
                     .await?;
@@ -222,13 +238,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self
                     .calendar_event_notification_query((*request).into(), access_token)
                     .await?;
@@ -250,13 +267,14 @@ impl QueryChanges for Server {
                     .response;
                 let calculate_total = request.calculate_total.unwrap_or(false);
                 has_changes = changes.has_changes();
-                response = build_query_changes_response(&request, &changes);
+                response = build_query_changes_response(&request, &changes)?;
 
                 if !has_changes && !calculate_total {
                     return Ok(response);
                 }
 
                 up_to_id = request.up_to_id;
+                max_changes = request.max_changes;
                 results = self.share_notification_query((*request).into()).await?;
             }
             QueryChangesRequestMethod::Principal(_) => {
@@ -269,13 +287,51 @@ impl QueryChanges for Server {
 
         if has_changes {
             if is_mutable {
+                // Any updated item may have left or joined the results, so it is
+                // removed and then re-added at its current position if it still matches
+                let mut changed_threads = AHashSet::new();
+                let mut removed_ids = changes.updated.iter().copied().collect::<AHashSet<_>>();
+                response.removed = changes.updated;
+
+                if let Some(account_id) = collapse_threads {
+                    // A change to any email can alter which one represents its thread
+                    changed_threads.extend(
+                        changes
+                            .created
+                            .iter()
+                            .chain(response.removed.iter())
+                            .chain(changes.destroyed.iter())
+                            .map(|id| id.prefix_id()),
+                    );
+                    let cache = self
+                        .get_cached_messages(account_id)
+                        .await
+                        .caused_by(trc::location!())?;
+                    let shared_ids = access_token
+                        .is_shared(account_id)
+                        .then(|| cache.shared_messages(access_token, Acl::ReadItems));
+                    for item in &cache.emails.items {
+                        if changed_threads.contains(&item.thread_id)
+                            && shared_ids
+                                .as_ref()
+                                .is_none_or(|ids| ids.contains(item.document_id))
+                        {
+                            let id = Id::from_parts(item.thread_id, item.document_id);
+                            if removed_ids.insert(id) {
+                                response.removed.push(id);
+                            }
+                        }
+                    }
+                }
+
                 for (index, id) in results.ids.into_iter().enumerate() {
-                    if changes.created.contains(&id) || changes.updated.contains(&id) {
+                    if changes.created.contains(&id)
+                        || removed_ids.contains(&id)
+                        || changed_threads.contains(&id.prefix_id())
+                    {
                         response.added.push(AddedItem::new(id, index));
                     }
                 }
-
-                response.removed = changes.updated;
             } else {
                 for (index, id) in results.ids.into_iter().enumerate() {
                     if changes.created.contains(&id) {
@@ -290,6 +346,12 @@ impl QueryChanges for Server {
             if !changes.destroyed.is_empty() {
                 response.removed.extend(changes.destroyed);
             }
+
+            if max_changes.is_some_and(|max_changes| {
+                max_changes > 0 && response.removed.len() + response.added.len() > max_changes
+            }) {
+                return Err(trc::JmapEvent::TooManyChanges.into_err());
+            }
         }
         response.total = results.total;
 
@@ -308,13 +370,22 @@ fn build_changes_request<T: JmapObject>(req: &QueryChangesRequest<T>) -> Changes
 fn build_query_changes_response<T: JmapObject>(
     req: &QueryChangesRequest<T>,
     changes: &ChangesResponse<NullObject>,
-) -> QueryChangesResponse {
-    QueryChangesResponse {
+) -> trc::Result<QueryChangesResponse> {
+    // Partial changes would leave the client with an inconsistent view of the results
+    if changes.has_more_changes {
+        return Err(if req.max_changes.is_some_and(|n| n != 0) {
+            trc::JmapEvent::TooManyChanges.into_err()
+        } else {
+            trc::JmapEvent::CannotCalculateChanges.into_err()
+        });
+    }
+
+    Ok(QueryChangesResponse {
         account_id: req.account_id,
         old_query_state: changes.old_state.clone(),
         new_query_state: changes.new_state.clone(),
         total: None,
         removed: vec![],
         added: vec![],
-    }
+    })
 }
@@ -108,7 +108,8 @@ impl ContactCardQuery for Server {
                             }),
                         },
                     )
-                    .ascending(),
+                    .ascending()
+                    .read_only(),
                     |key, value| {
                         created_to_updated.push(CreatedUpdated {
                             document_id: key.deserialize_be_u32(key.len() - U32_LEN)?,
@@ -15,7 +15,7 @@ use email::{
 };
 use http_proto::HttpSessionData;
 use jmap_proto::{
-    error::set::SetError,
+    error::set::{SetError, SetErrorType},
     method::{
         copy::{CopyRequest, CopyResponse},
         set::SetRequest,
@@ -30,7 +30,7 @@ use jmap_proto::{
 use jmap_tools::{Key, Value};
 use std::future::Future;
 use trc::AddContext;
-use types::acl::Acl;
+use types::{acl::Acl, keyword::Keyword};
 use utils::map::vec_map::VecMap;
 
 pub trait JmapEmailCopy: Sync + Send {
@@ -86,18 +86,26 @@ impl JmapEmailCopy for Server {
             None
         };
         let on_success_delete = request.on_success_destroy_original.unwrap_or(false);
+        let can_destroy_ids = if on_success_delete && !access_token.is_member(from_account_id) {
+            from_cache
+                .shared_messages(access_token, Acl::RemoveItems)
+                .into()
+        } else {
+            None
+        };
         let mut destroy_ids = Vec::new();
 
         'create: for (id, create) in request.create.into_valid() {
-            let mut from_message_id = None;
+            let mut from_id = None;
             let mut mailboxes = Vec::new();
-            let mut keywords = Vec::new();
+            let mut keywords: Option<Vec<Keyword>> = None;
+            let mut keyword_patches = Vec::new();
             let mut received_at = None;
 
             for (property, value) in create.into_expanded_object() {
                 match (property, value) {
                     (Key::Property(EmailProperty::Id), Value::Element(EmailValue::Id(src))) => {
-                        from_message_id = Some(src.document_id());
+                        from_id = Some(src);
                     }
                     (Key::Property(EmailProperty::MailboxIds), Value::Object(ids)) => {
                         mailboxes = ids
@@ -108,20 +116,21 @@ impl JmapEmailCopy for Server {
                             .collect();
                     }
                     (Key::Property(EmailProperty::Keywords), Value::Object(keywords_)) => {
-                        keywords = keywords_
-                            .into_expanded_boolean_set()
-                            .filter_map(|id| id.try_into_property()?.try_into_keyword())
-                            .collect();
+                        keywords = Some(
+                            keywords_
+                                .into_expanded_boolean_set()
+                                .filter_map(|id| id.try_into_property()?.try_into_keyword())
+                                .collect(),
+                        );
+                        keyword_patches.clear();
                     }
                     (Key::Property(EmailProperty::Pointer(pointer)), value) => {
                         match handle_email_patch(&pointer, value) {
                             PatchResult::SetKeyword(keyword) => {
-                                if !keywords.contains(keyword) {
-                                    keywords.push(keyword.clone());
-                                }
+                                keyword_patches.push((keyword.clone(), true));
                             }
                             PatchResult::RemoveKeyword(keyword) => {
-                                keywords.retain(|k| k != keyword);
+                                keyword_patches.push((keyword.clone(), false));
                             }
                             PatchResult::AddMailbox(id) => {
                                 if !mailboxes.contains(&id) {
@@ -155,7 +164,7 @@ impl JmapEmailCopy for Server {
                 }
             }
 
-            let Some(from_message_id) = from_message_id else {
+            let Some(from_id) = from_id else {
                 response.not_created.append(
                     id,
                     SetError::invalid_properties()
@@ -164,6 +173,7 @@ impl JmapEmailCopy for Server {
                 );
                 continue 'create;
             };
+            let from_message_id = from_id.document_id();
             if !from_message_ids.contains(from_message_id) {
                 response.not_created.append(
                     id,
@@ -175,6 +185,17 @@ impl JmapEmailCopy for Server {
                 continue 'create;
             }
 
+            // Destroying the original requires permission to remove it
+            if matches!(&can_destroy_ids, Some(ids) if !ids.contains(from_message_id)) {
+                response.not_created.append(
+                    id,
+                    SetError::forbidden().with_description(format!(
+                        "You are not allowed to delete message {from_id}."
+                    )),
+                );
+                continue 'create;
+            }
+
             // Make sure message belongs to at least one mailbox
             if mailboxes.is_empty() {
                 response.not_created.append(
@@ -207,6 +228,32 @@ impl JmapEmailCopy for Server {
                 }
             }
 
+            // Keywords not provided are copied from the original message
+            let mut keywords = keywords.unwrap_or_else(|| {
+                from_cache
+                    .email_by_id(&from_message_id)
+                    .map(|email| from_cache.expand_keywords(email).collect())
+                    .unwrap_or_default()
+            });
+            for (keyword, is_set) in keyword_patches {
+                if is_set {
+                    if !keywords.iter().any(|k| k.is_equivalent(&keyword)) {
+                        keywords.push(keyword);
+                    }
+                } else {
+                    keywords.retain(|k| !k.is_equivalent(&keyword));
+                }
+            }
+            if !cache.has_keyword_capacity(&keywords) {
+                response.not_created.append(
+                    id,
+                    SetError::new(SetErrorType::TooManyKeywords)
+                        .with_property(EmailProperty::Keywords)
+                        .with_description("Too many distinct keywords in account."),
+                );
+                continue 'create;
+            }
+
             // Add response
             match self
                 .copy_message(
@@ -224,6 +271,11 @@ impl JmapEmailCopy for Server {
                     response
                         .created
                         .append(id, ingested_into_object(email).into());
+
+                    // Add to destroy list
+                    if on_success_delete {
+                        destroy_ids.push(MaybeInvalid::Value(from_id));
+                    }
                 }
                 Err(err) => {
                     response.not_created.append(
@@ -236,11 +288,6 @@ impl JmapEmailCopy for Server {
                     );
                 }
             }
-
-            // Add to destroy list
-            if on_success_delete {
-                destroy_ids.push(MaybeInvalid::Value(id));
-            }
         }
 
         // Update state
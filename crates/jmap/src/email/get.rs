@@ -12,9 +12,13 @@ use crate::{changes::state::JmapCacheState, email::headers::HeaderToValue};
 use common::{Server, auth::AccessToken};
 use email::{
     cache::{MessageCacheFetch, email::MessageCacheAccess},
-    message::metadata::{
-        ArchivedMetadataPartType, MESSAGE_HAS_ATTACHMENT, MESSAGE_RECEIVED_MASK, MessageMetadata,
-        MetadataHeaderName, PART_ENCODING_PROBLEM,
+    message::{
+        followup::EmailFollowUpFnc,
+        metadata::{
+            ArchivedMetadataPartType, MESSAGE_HAS_ATTACHMENT, MESSAGE_RECEIVED_MASK,
+            MessageMetadata, MetadataHeaderName, PART_ENCODING_PROBLEM,
+        },
+        snooze::EmailSnoozeFnc,
     },
 };
 use jmap_proto::{
@@ -149,6 +153,18 @@ impl EmailGet for Server {
                 break;
             }
         }
+        let needs_snooze = properties.iter().any(|property| {
+            matches!(
+                property,
+                EmailProperty::SnoozedUntil | EmailProperty::RestoreMailboxId
+            )
+        });
+        let needs_follow_up = properties.iter().any(|property| {
+            matches!(
+                property,
+                EmailProperty::FollowUpAt | EmailProperty::FollowUpMailboxId
+            )
+        });
 
         for id in ids {
             // Obtain the email object
@@ -185,6 +201,24 @@ impl EmailGet for Server {
                 }
             };
 
+            // Retrieve the snooze state if needed
+            let snooze = if needs_snooze {
+                self.email_snooze(account_id, id.document_id())
+                    .await
+                    .caused_by(trc::location!())?
+            } else {
+                None
+            };
+
+            // Retrieve the follow-up reminder if needed
+            let follow_up = if needs_follow_up {
+                self.email_follow_up(account_id, id.document_id())
+                    .await
+                    .caused_by(trc::location!())?
+            } else {
+                None
+            };
+
             // Retrieve raw message if needed
             let blob_hash = BlobHash::from(&metadata.blob_hash);
             let raw_body;
@@ -274,6 +308,44 @@ impl EmailGet for Server {
                             )),
                         );
                     }
+                    EmailProperty::SnoozedUntil => {
+                        email.insert_unchecked(
+                            EmailProperty::SnoozedUntil,
+                            snooze.as_ref().map_or(Value::Null, |snooze| {
+                                Value::Element(EmailValue::Date(UTCDate::from_timestamp(
+                                    snooze.until as i64,
+                                )))
+                            }),
+                        );
+                    }
+                    EmailProperty::RestoreMailboxId => {
+                        email.insert_unchecked(
+                            EmailProperty::RestoreMailboxId,
+                            snooze.as_ref().map_or(Value::Null, |snooze| {
+                                Value::Element(EmailValue::Id(Id::from(snooze.mailbox_id)))
+                            }),
+                        );
+                    }
+                    EmailProperty::FollowUpAt => {
+                        email.insert_unchecked(
+                            EmailProperty::FollowUpAt,
+                            follow_up.map_or(Value::Null, |follow_up| {
+                                Value::Element(EmailValue::Date(UTCDate::from_timestamp(
+                                    follow_up.until as i64,
+                                )))
+                            }),
+                        );
+                    }
+                    EmailProperty::FollowUpMailboxId => {
+                        email.insert_unchecked(
+                            EmailProperty::FollowUpMailboxId,
+                            follow_up
+                                .and_then(|follow_up| follow_up.mailbox_id)
+                                .map_or(Value::Null, |mailbox_id| {
+                                    Value::Element(EmailValue::Id(Id::from(mailbox_id)))
+                                }),
+                        );
+                    }
                     EmailProperty::Preview => {
                         if !metadata.preview.is_empty() {
                             email.insert_unchecked(
@@ -201,6 +201,13 @@ impl EmailImport for Server {
                                 .with_description("You have exceeded your disk quota."),
                         );
                     }
+                    trc::EventType::Limit(trc::LimitEvent::TenantQuota) => {
+                        response.not_created.append(
+                            id,
+                            SetError::new(SetErrorType::OverQuota)
+                                .with_description("Your organization has exceeded its disk quota."),
+                        );
+                    }
                     trc::EventType::MessageIngest(trc::MessageIngestEvent::Error) => {
                         response.not_created.append(
                             id,
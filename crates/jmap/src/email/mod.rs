@@ -19,6 +19,7 @@ pub mod headers;
 pub mod import;
 pub mod parse;
 pub mod query;
+pub mod recall;
 pub mod set;
 pub mod snippet;
 
@@ -6,7 +6,10 @@
 
 use crate::{api::query::QueryResponseBuilder, changes::state::JmapCacheState};
 use common::{MessageStoreCache, Server, auth::AccessToken};
-use email::cache::{MessageCacheFetch, email::MessageCacheAccess};
+use email::{
+    cache::{MessageCacheFetch, email::MessageCacheAccess},
+    message::followup::EmailFollowUpFnc,
+};
 use jmap_proto::{
     method::query::{Filter, QueryRequest, QueryResponse},
     object::email::{Email, EmailComparator, EmailFilter},
@@ -252,6 +255,19 @@ impl EmailQuery for Server {
                                 .map(|item| item.document_id),
                         )))
                     }
+                    EmailFilter::HasFollowUp(has_follow_up) => {
+                        let follow_up_ids = self
+                            .email_follow_up_ids(account_id)
+                            .await
+                            .caused_by(trc::location!())?;
+                        if has_follow_up {
+                            filters.push(SearchFilter::is_in_set(follow_up_ids));
+                        } else {
+                            filters.push(SearchFilter::Not);
+                            filters.push(SearchFilter::is_in_set(follow_up_ids));
+                            filters.push(SearchFilter::End);
+                        }
+                    }
                     other => {
                         return Err(trc::JmapEvent::UnsupportedFilter
                             .into_err()
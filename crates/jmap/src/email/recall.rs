@@ -0,0 +1,93 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{Server, auth::AccessToken, telemetry::audit::AuditSource};
+use email::message::recall::{EmailRecall, RecallError, RecallStatus};
+use http_proto::HttpSessionData;
+use jmap_proto::{
+    error::set::SetError,
+    method::recall::{
+        EmailRecallOutcome, EmailRecallRequest, EmailRecallResponse, EmailRecallStatus,
+    },
+};
+use registry::schema::enums::ServiceProtocol;
+use std::future::Future;
+use trc::AddContext;
+use utils::map::vec_map::VecMap;
+
+pub trait EmailRecallHandler: Sync + Send {
+    fn email_recall(
+        &self,
+        request: EmailRecallRequest,
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+    ) -> impl Future<Output = trc::Result<EmailRecallResponse>> + Send;
+}
+
+impl EmailRecallHandler for Server {
+    async fn email_recall(
+        &self,
+        request: EmailRecallRequest,
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+    ) -> trc::Result<EmailRecallResponse> {
+        let account_id = request.account_id.document_id();
+        let source = AuditSource {
+            access_token,
+            protocol: ServiceProtocol::Jmap,
+            remote_ip: Some(session.remote_ip),
+        };
+        let mut response = EmailRecallResponse {
+            account_id: request.account_id,
+            recalled: VecMap::with_capacity(request.ids.len()),
+            not_recalled: VecMap::new(),
+        };
+
+        for id in request.ids {
+            match EmailRecall::email_recall(self, account_id, id.document_id(), source)
+                .await
+                .caused_by(trc::location!())?
+            {
+                Ok(outcomes) => {
+                    response.recalled.append(
+                        id,
+                        outcomes
+                            .into_iter()
+                            .map(|outcome| EmailRecallOutcome {
+                                email: outcome.recipient,
+                                status: match outcome.status {
+                                    RecallStatus::Recalled => EmailRecallStatus::Recalled,
+                                    RecallStatus::Replaced => EmailRecallStatus::Replaced,
+                                    RecallStatus::Read => EmailRecallStatus::Read,
+                                    RecallStatus::Moved => EmailRecallStatus::Moved,
+                                    RecallStatus::NotFound => EmailRecallStatus::NotFound,
+                                    RecallStatus::NotLocal => EmailRecallStatus::NotLocal,
+                                    RecallStatus::Expired => EmailRecallStatus::Expired,
+                                    RecallStatus::Failed => EmailRecallStatus::Failed,
+                                },
+                            })
+                            .collect(),
+                    );
+                }
+                Err(err) => {
+                    response.not_recalled.append(
+                        id,
+                        match err {
+                            RecallError::NotFound => SetError::not_found(),
+                            RecallError::NotSender => SetError::forbidden().with_description(
+                                "Only messages sent from this account can be recalled.",
+                            ),
+                            RecallError::NoMessageId => SetError::invalid_properties()
+                                .with_description("Message does not have a Message-ID."),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(response)
+    }
+}
@@ -12,14 +12,18 @@ use crate::{
 };
 use common::{
     Server, auth::AccessToken, ipc::PushNotification, storage::index::ObjectIndexBuilder,
+    telemetry::audit::AuditSource,
 };
 use email::{
     cache::{MessageCacheFetch, email::MessageCacheAccess, mailbox::MailboxCacheAccess},
-    mailbox::{JUNK_ID, TRASH_ID, UidMailbox},
+    mailbox::{INBOX_ID, JUNK_ID, TRASH_ID, UidMailbox},
     message::{
+        category::EmailCategoryFnc,
         delete::EmailDeletion,
+        followup::{EmailFollowUp, EmailFollowUpFnc},
         ingest::{EmailIngest, IngestEmail, IngestSource},
         metadata::MessageData,
+        snooze::{EmailSnooze, EmailSnoozeFnc, SnoozedMailbox},
     },
 };
 use http_proto::HttpSessionData;
@@ -41,20 +45,26 @@ use mail_builder::{
     mime::{BodyPart, MimePart},
 };
 use mail_parser::MessageParser;
+use registry::schema::{
+    enums::{AuditEventType, ServiceProtocol},
+    structs::{Task, TaskFollowUpReminder, TaskRestoreSnoozedEmail, TaskStatus},
+};
 use std::future::Future;
 use std::{borrow::Cow, collections::HashMap};
 use store::{
     ValueKey,
     ahash::AHashMap,
     roaring::RoaringBitmap,
-    write::{AlignedBytes, Archive, BatchBuilder},
+    write::{AlignedBytes, Archive, BatchBuilder, ValueClass, now},
 };
 use trc::AddContext;
 use types::{
     acl::Acl,
     collection::{Collection, SyncCollection, VanishedCollection},
+    field::EmailField,
     id::Id,
     keyword::{ArchivedKeyword, Keyword},
+    special_use::SpecialUse,
     type_state::{DataType, StateChange},
 };
 
@@ -183,12 +193,12 @@ impl EmailSet for Server {
                     (EmailProperty::Pointer(pointer), value) => {
                         match handle_email_patch(&pointer, value) {
                             PatchResult::SetKeyword(keyword) => {
-                                if !keywords.contains(keyword) {
+                                if !keywords.iter().any(|k| k.is_equivalent(keyword)) {
                                     keywords.push(keyword.clone());
                                 }
                             }
                             PatchResult::RemoveKeyword(keyword) => {
-                                keywords.retain(|k| k != keyword);
+                                keywords.retain(|k| !k.is_equivalent(keyword));
                             }
                             PatchResult::AddMailbox(id) => {
                                 if !mailboxes.contains(&id) {
@@ -784,6 +794,13 @@ impl EmailSet for Server {
                             .with_description("You have exceeded your disk quota."),
                     );
                 }
+                Err(err) if err.matches(trc::EventType::Limit(trc::LimitEvent::TenantQuota)) => {
+                    response.not_created.append(
+                        id,
+                        SetError::new(SetErrorType::OverQuota)
+                            .with_description("Your organization has exceeded its disk quota."),
+                    );
+                }
                 Err(err) => return Err(err),
             }
         }
@@ -791,6 +808,10 @@ impl EmailSet for Server {
         // Process updates
         let mut batch = BatchBuilder::new();
         let mut changed_mailboxes: AHashMap<u32, Vec<u32>> = AHashMap::new();
+        let mut snoozed_mailbox_id = None;
+        let mut create_snoozed_mailbox = None;
+        let mut follow_up_count = None;
+        let mut has_scheduled = false;
         let mut will_update = Vec::with_capacity(request.update.as_ref().map_or(0, |u| u.len()));
         'update: for (id, object) in request.unwrap_update() {
             let id = match id {
@@ -827,6 +848,10 @@ impl EmailSet for Server {
                 .to_unarchived::<MessageData>()
                 .caused_by(trc::location!())?;
             let mut new_data = data.inner.to_builder();
+            let mut snoozed_until = None;
+            let mut restore_mailbox_id = None;
+            let mut follow_up_at = None;
+            let mut follow_up_mailbox_id = None;
 
             for (property, mut value) in object.into_expanded_object() {
                 if let Err(err) = response.resolve_self_references(&mut value, 0, false) {
@@ -877,6 +902,40 @@ impl EmailSet for Server {
                             }
                         }
                     }
+                    (
+                        Key::Property(EmailProperty::SnoozedUntil),
+                        Value::Element(EmailValue::Date(date)),
+                    ) => {
+                        snoozed_until = Some(Some(date.timestamp() as u64));
+                    }
+                    (Key::Property(EmailProperty::SnoozedUntil), Value::Null) => {
+                        snoozed_until = Some(None);
+                    }
+                    (
+                        Key::Property(EmailProperty::RestoreMailboxId),
+                        Value::Element(EmailValue::Id(mailbox_id)),
+                    ) => {
+                        restore_mailbox_id = Some(mailbox_id.document_id());
+                    }
+                    (Key::Property(EmailProperty::RestoreMailboxId), Value::Null) => {}
+                    (
+                        Key::Property(EmailProperty::FollowUpAt),
+                        Value::Element(EmailValue::Date(date)),
+                    ) => {
+                        follow_up_at = Some(Some(date.timestamp() as u64));
+                    }
+                    (Key::Property(EmailProperty::FollowUpAt), Value::Null) => {
+                        follow_up_at = Some(None);
+                    }
+                    (
+                        Key::Property(EmailProperty::FollowUpMailboxId),
+                        Value::Element(EmailValue::Id(mailbox_id)),
+                    ) => {
+                        follow_up_mailbox_id = Some(Some(mailbox_id.document_id()));
+                    }
+                    (Key::Property(EmailProperty::FollowUpMailboxId), Value::Null) => {
+                        follow_up_mailbox_id = Some(None);
+                    }
                     (Key::Property(EmailProperty::Id), value) => {
                         if !crate::matches_id(&value, id) {
                             response.not_updated.append(
@@ -895,9 +954,304 @@ impl EmailSet for Server {
                 }
             }
 
+            // Process snooze changes
+            let mut snooze_change = None;
+            if snoozed_until.is_some() || restore_mailbox_id.is_some() {
+                if access_token.is_shared(account_id) {
+                    response.not_updated.append(
+                        id,
+                        SetError::forbidden()
+                            .with_description("Messages in shared accounts cannot be snoozed."),
+                    );
+                    continue 'update;
+                }
+
+                let current = self
+                    .email_snooze(account_id, document_id)
+                    .await
+                    .caused_by(trc::location!())?;
+                match snoozed_until {
+                    Some(Some(until)) => {
+                        if until <= now() {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailProperty::SnoozedUntil)
+                                    .with_description("snoozedUntil must be in the future."),
+                            );
+                            continue 'update;
+                        }
+
+                        let snoozed_id = match snoozed_mailbox_id {
+                            Some(snoozed_id) => snoozed_id,
+                            None => {
+                                let snoozed_id = match self
+                                    .snoozed_mailbox_id(account_id)
+                                    .await
+                                    .caused_by(trc::location!())?
+                                {
+                                    SnoozedMailbox::Existing(snoozed_id) => snoozed_id,
+                                    SnoozedMailbox::New {
+                                        document_id,
+                                        previous_id,
+                                    } => {
+                                        create_snoozed_mailbox = Some((document_id, previous_id));
+                                        document_id
+                                    }
+                                };
+                                snoozed_mailbox_id = Some(snoozed_id);
+                                snoozed_id
+                            }
+                        };
+
+                        // Restore to the requested mailbox, or where the message was snoozed
+                        // from, along with the other mailboxes the message belongs to
+                        let mut mailbox_ids = current
+                            .as_ref()
+                            .map(|snooze| {
+                                let mut mailbox_ids = vec![snooze.mailbox_id];
+                                mailbox_ids.extend_from_slice(&snooze.other_mailbox_ids);
+                                mailbox_ids
+                            })
+                            .unwrap_or_default();
+                        for mailbox in data.inner.mailboxes.iter() {
+                            let mailbox_id = mailbox.mailbox_id.to_native();
+                            if mailbox_id != snoozed_id && !mailbox_ids.contains(&mailbox_id) {
+                                mailbox_ids.push(mailbox_id);
+                            }
+                        }
+                        let mailbox_id = restore_mailbox_id
+                            .or(mailbox_ids.first().copied())
+                            .unwrap_or(INBOX_ID);
+                        mailbox_ids.retain(|id| *id != mailbox_id);
+                        if mailbox_id == snoozed_id || !cache.has_mailbox_id(&mailbox_id) {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailProperty::RestoreMailboxId)
+                                    .with_description(format!(
+                                        "mailboxId {} cannot be used to restore messages.",
+                                        Id::from(mailbox_id)
+                                    )),
+                            );
+                            continue 'update;
+                        }
+
+                        // Snoozed messages are only kept in the Snoozed mailbox
+                        new_data.set_mailboxes(vec![
+                            data.inner
+                                .message_uid(snoozed_id)
+                                .map(|uid| UidMailbox::new(snoozed_id, uid))
+                                .unwrap_or_else(|| UidMailbox::new_unassigned(snoozed_id)),
+                        ]);
+                        snooze_change = Some(Some(EmailSnooze {
+                            until,
+                            mailbox_id,
+                            other_mailbox_ids: mailbox_ids,
+                        }));
+                    }
+                    Some(None) => {
+                        // Unsnoozing moves the message back without changing its keywords
+                        if let Some(current) = current {
+                            if let Some(snoozed_id) = cache
+                                .mailbox_by_role(&SpecialUse::Snoozed)
+                                .map(|mailbox| mailbox.document_id)
+                                .filter(|snoozed_id| {
+                                    new_data
+                                        .mailboxes
+                                        .iter()
+                                        .any(|mailbox| mailbox.mailbox_id == *snoozed_id)
+                                })
+                            {
+                                new_data.remove_mailbox(snoozed_id);
+                                for mailbox_id in current
+                                    .restore_mailbox_ids(snoozed_id, |mailbox_id| {
+                                        cache.has_mailbox_id(&mailbox_id)
+                                    })
+                                {
+                                    if !new_data
+                                        .mailboxes
+                                        .iter()
+                                        .any(|mailbox| mailbox.mailbox_id == mailbox_id)
+                                    {
+                                        new_data
+                                            .add_mailbox(UidMailbox::new_unassigned(mailbox_id));
+                                    }
+                                }
+                            }
+                            snooze_change = Some(None);
+                        }
+                    }
+                    None => {
+                        let Some(current) = current else {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailProperty::RestoreMailboxId)
+                                    .with_description("Message is not snoozed."),
+                            );
+                            continue 'update;
+                        };
+                        let mailbox_id = restore_mailbox_id.unwrap_or(current.mailbox_id);
+                        if cache
+                            .mailbox_by_role(&SpecialUse::Snoozed)
+                            .is_some_and(|mailbox| mailbox.document_id == mailbox_id)
+                            || !cache.has_mailbox_id(&mailbox_id)
+                        {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailProperty::RestoreMailboxId)
+                                    .with_description(format!(
+                                        "mailboxId {} cannot be used to restore messages.",
+                                        Id::from(mailbox_id)
+                                    )),
+                            );
+                            continue 'update;
+                        }
+                        snooze_change = Some(Some(EmailSnooze {
+                            until: current.until,
+                            mailbox_id,
+                            other_mailbox_ids: current
+                                .other_mailbox_ids
+                                .into_iter()
+                                .chain([current.mailbox_id])
+                                .filter(|id| *id != mailbox_id)
+                                .collect(),
+                        }));
+                    }
+                }
+            }
+
+            // Process follow-up reminders
+            let mut follow_up_set = None;
+            let mut follow_up_clear = None;
+            if follow_up_at.is_some() || follow_up_mailbox_id.is_some() {
+                if access_token.is_shared(account_id) {
+                    response.not_updated.append(
+                        id,
+                        SetError::forbidden().with_description(
+                            "Follow-up reminders cannot be set on messages in shared accounts.",
+                        ),
+                    );
+                    continue 'update;
+                }
+
+                let current = self
+                    .email_follow_up(account_id, document_id)
+                    .await
+                    .caused_by(trc::location!())?;
+                let mailbox_id = match follow_up_mailbox_id {
+                    Some(Some(mailbox_id)) if !cache.has_mailbox_id(&mailbox_id) => {
+                        response.not_updated.append(
+                            id,
+                            SetError::invalid_properties()
+                                .with_property(EmailProperty::FollowUpMailboxId)
+                                .with_description(format!(
+                                    "mailboxId {} does not exist.",
+                                    Id::from(mailbox_id)
+                                )),
+                        );
+                        continue 'update;
+                    }
+                    Some(mailbox_id) => mailbox_id,
+                    None => current.and_then(|follow_up| follow_up.mailbox_id),
+                };
+
+                match (follow_up_at, current) {
+                    (Some(Some(until)), _) if until <= now() => {
+                        response.not_updated.append(
+                            id,
+                            SetError::invalid_properties()
+                                .with_property(EmailProperty::FollowUpAt)
+                                .with_description("followUpAt must be in the future."),
+                        );
+                        continue 'update;
+                    }
+                    (Some(Some(until)), Some(current)) => {
+                        follow_up_set = Some(EmailFollowUp {
+                            until,
+                            mailbox_id,
+                            message_id: current.message_id,
+                        });
+                    }
+                    (Some(Some(until)), None) => {
+                        // Enforce the maximum number of pending reminders
+                        if let Some(max_follow_ups) = self.core.email.max_follow_ups {
+                            let count = match follow_up_count {
+                                Some(count) => count,
+                                None => self
+                                    .email_follow_up_ids(account_id)
+                                    .await
+                                    .caused_by(trc::location!())?
+                                    .len(),
+                            };
+                            if count >= max_follow_ups as u64 {
+                                response.not_updated.append(
+                                    id,
+                                    SetError::new(SetErrorType::OverQuota).with_description(
+                                        format!(
+                                            "You have exceeded your quota of {} follow-up reminders.",
+                                            max_follow_ups
+                                        ),
+                                    ),
+                                );
+                                continue 'update;
+                            }
+                            follow_up_count = Some(count + 1);
+                        }
+
+                        // Replies are matched using the Message-ID of the watched message
+                        let Some(message_id) = self
+                            .email_follow_up_message_id(account_id, document_id)
+                            .await
+                            .caused_by(trc::location!())?
+                        else {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailProperty::FollowUpAt)
+                                    .with_description(
+                                        "Messages without a Message-ID cannot be watched for replies.",
+                                    ),
+                            );
+                            continue 'update;
+                        };
+                        follow_up_set = Some(EmailFollowUp {
+                            until,
+                            mailbox_id,
+                            message_id,
+                        });
+                    }
+                    (Some(None), current) => {
+                        follow_up_clear = current;
+                    }
+                    (None, Some(current)) => {
+                        follow_up_set = Some(EmailFollowUp {
+                            mailbox_id,
+                            ..current
+                        });
+                    }
+                    (None, None) => {
+                        response.not_updated.append(
+                            id,
+                            SetError::invalid_properties()
+                                .with_property(EmailProperty::FollowUpMailboxId)
+                                .with_description("Message has no follow-up reminder."),
+                        );
+                        continue 'update;
+                    }
+                }
+            }
+
             let has_keyword_changes = new_data.has_keyword_changes(data.inner);
             let has_mailbox_changes = new_data.has_mailbox_changes(data.inner);
-            if !has_keyword_changes && !has_mailbox_changes {
+            if !has_keyword_changes
+                && !has_mailbox_changes
+                && snooze_change.is_none()
+                && follow_up_set.is_none()
+                && follow_up_clear.is_none()
+            {
                 response.updated.append(id, None);
                 continue 'update;
             }
@@ -971,7 +1325,9 @@ impl EmailSet for Server {
 
                 // Make sure all new mailboxIds are valid
                 for mailbox_id in new_data.added_mailboxes(data.inner) {
-                    if cache.has_mailbox_id(&mailbox_id.mailbox_id) {
+                    if cache.has_mailbox_id(&mailbox_id.mailbox_id)
+                        || snoozed_mailbox_id == Some(mailbox_id.mailbox_id)
+                    {
                         // Verify permissions on shared accounts
                         if can_add_mailbox_ids
                             .as_ref()
@@ -1061,6 +1417,21 @@ impl EmailSet for Server {
                 }
             }
 
+            // Learn the preferred category of senders from messages moved out of a category
+            if has_keyword_changes || has_mailbox_changes {
+                self.email_category_feedback(account_id, document_id, data.inner, &new_data)
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+
+            // The Snoozed mailbox is created along with the first message snoozed into it
+            if matches!(snooze_change, Some(Some(_)))
+                && let Some((snoozed_id, previous_id)) = create_snoozed_mailbox.take()
+            {
+                self.create_snoozed_mailbox(&mut batch, account_id, snoozed_id, previous_id)
+                    .caused_by(trc::location!())?;
+            }
+
             // Write changes
             batch
                 .with_account_id(account_id)
@@ -1073,6 +1444,43 @@ impl EmailSet for Server {
                 )
                 .caused_by(trc::location!())?;
 
+            // Schedule the restore of snoozed messages
+            match snooze_change {
+                Some(Some(snooze)) => {
+                    batch
+                        .set(
+                            ValueClass::Property(EmailField::Snooze.into()),
+                            snooze.serialize(),
+                        )
+                        .schedule_task(Task::RestoreSnoozedEmail(TaskRestoreSnoozedEmail {
+                            account_id: account_id.into(),
+                            document_id: document_id.into(),
+                            status: TaskStatus::at(snooze.until as i64),
+                        }));
+                    has_scheduled = true;
+                }
+                Some(None) => {
+                    batch.clear(ValueClass::Property(EmailField::Snooze.into()));
+                }
+                None => {}
+            }
+
+            // Schedule follow-up reminders
+            if let Some(follow_up) = follow_up_clear {
+                follow_up.clear(&mut batch);
+            }
+            if let Some(follow_up) = follow_up_set {
+                follow_up.set(&mut batch);
+                if follow_up_at.is_some() {
+                    batch.schedule_task(Task::FollowUpReminder(TaskFollowUpReminder {
+                        account_id: account_id.into(),
+                        document_id: document_id.into(),
+                        status: TaskStatus::at(follow_up.until as i64),
+                    }));
+                    has_scheduled = true;
+                }
+            }
+
             if let Some(train_spam) = train_spam {
                 self.add_account_spam_sample(
                     &mut batch,
@@ -1110,6 +1518,10 @@ impl EmailSet for Server {
                     for id in will_update {
                         response.updated.append(id, None);
                     }
+
+                    if has_scheduled {
+                        self.notify_task_queue();
+                    }
                 }
                 Err(err) if err.is_assertion_failure() => {
                     for id in will_update {
@@ -1195,6 +1607,21 @@ impl EmailSet for Server {
 
                     response.destroyed = destroyed;
                 }
+
+                self.audit(
+                    AuditSource {
+                        access_token,
+                        protocol: ServiceProtocol::Jmap,
+                        remote_ip: Some(session.remote_ip),
+                    },
+                    account_id,
+                    AuditEventType::MessageDelete,
+                    response
+                        .destroyed
+                        .iter()
+                        .map(|id| Id::from(id.document_id())),
+                )
+                .await;
             }
         }
 
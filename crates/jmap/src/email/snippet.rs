@@ -19,8 +19,12 @@ use jmap_proto::{
     object::email::EmailFilter,
     request::MaybeInvalid,
 };
-use mail_parser::decoders::html::html_to_text;
-use nlp::language::{Language, search_snippet::generate_snippet, stemmer::Stemmer};
+use mail_parser::{HeaderName, decoders::html::html_to_text};
+use nlp::language::{
+    Language,
+    search_snippet::{generate_snippet, generate_substring_snippet},
+    stemmer::Stemmer,
+};
 use std::future::Future;
 use store::{
     ValueKey,
@@ -48,16 +52,17 @@ impl EmailSearchSnippet for Server {
         let mut filter_stack = vec![];
         let mut include_term = true;
         let mut terms = vec![];
+        let mut subject_substrings = vec![];
         let mut is_exact = false;
         let mut language = self.core.email.default_language;
 
         for cond in request.filter {
             match cond {
                 Filter::Property(cond) => {
-                    if let EmailFilter::Text(text)
-                    | EmailFilter::Subject(text)
-                    | EmailFilter::Body(text) = cond
-                        && include_term
+                    if include_term
+                        && let EmailFilter::Text(text)
+                        | EmailFilter::Subject(text)
+                        | EmailFilter::Body(text) = cond
                     {
                         let (text, language_) =
                             Language::detect(text, self.core.email.default_language);
@@ -77,6 +82,13 @@ impl EmailSearchSnippet for Server {
                                 }
                             }
                         }
+                    } else if include_term
+                        && let EmailFilter::Header(header) = cond
+                        && let [name, value] = header.as_slice()
+                        && matches!(HeaderName::parse(name.as_str()), Some(HeaderName::Subject))
+                    {
+                        // Header filters are matched as substrings rather than through the index
+                        subject_substrings.push(value.clone());
                     }
                 }
                 Filter::And | Filter::Or => {
@@ -133,7 +145,7 @@ impl EmailSearchSnippet for Server {
             if !document_ids.contains(document_id) {
                 not_found.push(MaybeInvalid::Value(email_id));
                 continue;
-            } else if terms.is_empty() {
+            } else if terms.is_empty() && subject_substrings.is_empty() {
                 response.list.push(snippet);
                 continue;
             }
@@ -163,10 +175,21 @@ impl EmailSearchSnippet for Server {
                 .root_part()
                 .header_value(&MetadataHeaderName::Subject)
                 .and_then(|v| v.as_text())
-                .and_then(|v| generate_snippet(v, &terms, language, is_exact))
+                .and_then(|v| {
+                    let matched = if !terms.is_empty() {
+                        generate_snippet(v, &terms, language, is_exact)
+                    } else {
+                        None
+                    };
+                    matched.or_else(|| generate_substring_snippet(v, &subject_substrings))
+                })
             {
                 snippet.subject = subject.into();
             }
+            if terms.is_empty() {
+                response.list.push(snippet);
+                continue;
+            }
 
             // Download message
             let raw_body = if let Some(raw_body) = self
@@ -249,7 +272,6 @@ impl EmailSearchSnippet for Server {
                     _ => (),
                 }
             }
-            //}
 
             response.list.push(snippet);
         }
@@ -6,7 +6,10 @@
 
 use crate::changes::state::StateManager;
 use common::{Server, storage::index::ObjectIndexBuilder};
-use email::identity::{ArchivedEmailAddress, Identity};
+use email::{
+    identity::{ArchivedEmailAddress, Identity},
+    provision::AccountProvisioning,
+};
 use jmap_proto::{
     method::get::{GetRequest, GetResponse},
     object::identity::{self, IdentityProperty, IdentityValue},
@@ -167,6 +170,20 @@ impl IdentityGet for Server {
             .with_account_id(account_id)
             .with_collection(Collection::Identity);
 
+        // Add the signature from the account template
+        let text_signature = self
+            .account_template(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .and_then(|template| {
+                template
+                    .template
+                    .signature
+                    .as_ref()
+                    .map(|signature| template.expand(signature))
+            })
+            .unwrap_or_default();
+
         // Create identities
         let name = account_info.description().unwrap_or(account_info.name());
         let mut next_document_id = self
@@ -196,6 +213,7 @@ impl IdentityGet for Server {
                 .custom(ObjectIndexBuilder::<(), _>::new().with_changes(Identity {
                     name,
                     email,
+                    text_signature: text_signature.clone(),
                     ..Default::default()
                 }))
                 .caused_by(trc::location!())?;
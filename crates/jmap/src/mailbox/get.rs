@@ -47,7 +47,7 @@ impl MailboxGet for Server {
         ]);
         let account_id = request.account_id.document_id();
         let cache = self.get_cached_messages(account_id).await?;
-        let shared_ids = if access_token.is_shared(account_id) {
+        let shared_ids = if !access_token.is_member(account_id) {
             cache.shared_mailboxes(access_token, Acl::Read).into()
         } else {
             None
@@ -133,12 +133,12 @@ impl MailboxGet for Server {
                             .into(),
                     ),
                     MailboxProperty::MyRights => {
-                        if access_token.is_shared(account_id) {
+                        if access_token.is_member(account_id) {
+                            JmapRights::all_rights::<Mailbox>()
+                        } else {
                             JmapRights::rights::<Mailbox>(
                                 cached_mailbox.acls.as_slice().effective_acl(access_token),
                             )
-                        } else {
-                            JmapRights::all_rights::<Mailbox>()
                         }
                     }
                     MailboxProperty::IsSubscribed => Value::Bool(
@@ -10,6 +10,7 @@ use crate::{
 };
 use common::{
     Server, auth::AccessToken, sharing::EffectiveAcl, storage::index::ObjectIndexBuilder,
+    telemetry::audit::AuditSource,
 };
 #[allow(unused_imports)]
 use email::mailbox::{INBOX_ID, JUNK_ID, TRASH_ID, UidMailbox};
@@ -20,6 +21,7 @@ use email::{
         destroy::{MailboxDestroy, MailboxDestroyError},
     },
 };
+use http_proto::HttpSessionData;
 use jmap_proto::{
     error::set::{SetError, SetErrorType},
     method::set::{SetRequest, SetResponse},
@@ -29,7 +31,7 @@ use jmap_proto::{
     types::state::State,
 };
 use jmap_tools::{JsonPointerItem, Key, Map, Value};
-use registry::schema::enums::StorageQuota;
+use registry::schema::enums::{AuditEventType, ServiceProtocol, StorageQuota};
 use std::future::Future;
 use store::{
     ValueKey,
@@ -55,6 +57,7 @@ pub trait MailboxSet: Sync + Send {
         &self,
         request: SetRequest<'_, mailbox::Mailbox>,
         access_token: &AccessToken,
+        session: &HttpSessionData,
     ) -> impl Future<Output = trc::Result<SetResponse<mailbox::Mailbox>>> + Send;
 
     fn mailbox_set_item(
@@ -75,6 +78,7 @@ impl MailboxSet for Server {
         &self,
         mut request: SetRequest<'_, mailbox::Mailbox>,
         access_token: &AccessToken,
+        session: &HttpSessionData,
     ) -> trc::Result<SetResponse<mailbox::Mailbox>> {
         // Prepare response
         let account_id = request.account_id.document_id();
@@ -93,6 +97,11 @@ impl MailboxSet for Server {
         };
         let mut change_id = None;
         let account_info = self.account(account_id).await?;
+        let audit_source = AuditSource {
+            access_token,
+            protocol: ServiceProtocol::Jmap,
+            remote_ip: Some(session.remote_ip),
+        };
 
         // Process creates
         let mut batch = BatchBuilder::new();
@@ -162,6 +171,7 @@ impl MailboxSet for Server {
 
         // Process updates
         let mut will_update = Vec::with_capacity(request.update.as_ref().map_or(0, |u| u.len()));
+        let mut acl_changes = Vec::new();
         let mut batch = BatchBuilder::new();
         'update: for (id, object) in request.unwrap_update() {
             let id = match id {
@@ -253,6 +263,12 @@ impl MailboxSet for Server {
                                 .assert_value(MailboxField::Archive, AssertValue::Some);
                         }
 
+                        if builder.changes().map(|m| m.acls.as_slice())
+                            != builder.current().map(|m| m.inner.acls.as_slice())
+                        {
+                            acl_changes.push(Id::from(document_id));
+                        }
+
                         batch
                             .with_document(document_id)
                             .custom(builder)
@@ -281,6 +297,16 @@ impl MailboxSet for Server {
                     for id in will_update {
                         ctx.response.updated.append(id, None);
                     }
+
+                    if !acl_changes.is_empty() {
+                        self.audit(
+                            audit_source,
+                            account_id,
+                            AuditEventType::AclChange,
+                            acl_changes,
+                        )
+                        .await;
+                    }
                 }
                 Err(err) if err.is_assertion_failure() => {
                     for id in will_update {
@@ -345,6 +371,19 @@ impl MailboxSet for Server {
             }
         }
 
+        if !ctx.response.destroyed.is_empty() {
+            self.audit(
+                audit_source,
+                account_id,
+                AuditEventType::MailboxDelete,
+                ctx.response
+                    .destroyed
+                    .iter()
+                    .map(|id| Id::from(id.document_id())),
+            )
+            .await;
+        }
+
         // Write changes
         if let Some(change_id) = change_id {
             ctx.response.new_state = State::Exact(change_id).into();
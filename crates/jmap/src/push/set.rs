@@ -9,7 +9,7 @@ use base64::{
     engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
 };
 use common::{Server, auth::AccessToken, ipc::PushEvent};
-use email::push::{Keys, PushSubscription, PushSubscriptions};
+use email::push::{Keys, PUSH_EXPIRES_MAX, PushSubscription, PushSubscriptions};
 use jmap_proto::{
     error::set::{SetError, SetErrorType},
     method::set::{SetRequest, SetResponse},
@@ -31,7 +31,7 @@ use trc::{AddContext, ServerEvent};
 use types::{collection::Collection, field::PrincipalField, id::Id};
 use utils::map::bitmap::Bitmap;
 
-const EXPIRES_MAX: i64 = 7 * 24 * 3600; // 7 days
+const EXPIRES_MAX: i64 = PUSH_EXPIRES_MAX as i64;
 const VERIFICATION_CODE_LEN: usize = 32;
 const URL_SAFE_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
     &alphabet::URL_SAFE,
@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::quota::{TENANT_QUOTA_ID, quota_ids};
 use common::{Server, auth::AccessToken};
 use jmap_proto::{
     method::get::{GetRequest, GetResponse},
@@ -11,7 +12,7 @@ use jmap_proto::{
     types::state::State,
 };
 use jmap_tools::{Map, Value};
-use std::{borrow::Cow, future::Future};
+use std::future::Future;
 use trc::AddContext;
 use types::{id::Id, type_state::DataType};
 
@@ -44,11 +45,9 @@ impl QuotaGet for Server {
         ]);
         let account_id = request.account_id.document_id();
         let account = self.account(account_id).await.caused_by(trc::location!())?;
-        let quota_ids = if account.disk_quota() > 0 {
-            vec![0u32]
-        } else {
-            vec![]
-        };
+        let (quota_ids, tenant) = quota_ids(self, &account, access_token)
+            .await
+            .caused_by(trc::location!())?;
         let ids = if let Some(ids) = ids {
             ids
         } else {
@@ -61,19 +60,17 @@ impl QuotaGet for Server {
             not_found: not_found_ids,
         };
 
-        let account = if account_id == access_token.account_id() {
-            Cow::Borrowed(&account)
-        } else {
-            Cow::Owned(self.account(account_id).await.caused_by(trc::location!())?)
-        };
-
         for id in ids {
-            // Obtain the sieve script object
+            // Obtain the quota object
             let document_id = id.document_id();
             if !quota_ids.contains(&document_id) {
                 response.push_not_found(id);
                 continue;
             }
+            let tenant = tenant
+                .as_ref()
+                .filter(|_| document_id == TENANT_QUOTA_ID)
+                .zip(account.id_tenant);
 
             let mut result = Map::with_capacity(properties.len());
             for property in &properties {
@@ -81,17 +78,34 @@ impl QuotaGet for Server {
                     QuotaProperty::Id => Value::Element(id.into()),
                     QuotaProperty::ResourceType => "octets".to_string().into(),
                     QuotaProperty::Used => {
-                        (self.get_used_quota_account(account_id).await?.max(0) as u64).into()
+                        let used = if let Some((_, tenant_id)) = tenant {
+                            self.get_used_quota_tenant(tenant_id).await?
+                        } else {
+                            self.get_used_quota_account(account_id).await?
+                        };
+                        (used.max(0) as u64).into()
                     }
-                    QuotaProperty::HardLimit => account.as_ref().disk_quota().into(),
-                    QuotaProperty::Scope => "account".to_string().into(),
-                    QuotaProperty::Name => account.as_ref().name().to_string().into(),
-                    QuotaProperty::Description => account
-                        .as_ref()
-                        .description
-                        .as_ref()
-                        .map(|s| s.to_string())
+                    QuotaProperty::HardLimit => tenant
+                        .map_or(account.disk_quota(), |(tenant, _)| tenant.quota_disk)
                         .into(),
+                    QuotaProperty::Scope => if tenant.is_some() {
+                        "domain"
+                    } else {
+                        "account"
+                    }
+                    .to_string()
+                    .into(),
+                    QuotaProperty::Name => tenant
+                        .map_or(account.name(), |(tenant, _)| tenant.name.as_ref())
+                        .to_string()
+                        .into(),
+                    QuotaProperty::Description => {
+                        if tenant.is_some() {
+                            Value::Null
+                        } else {
+                            account.description.as_ref().map(|s| s.to_string()).into()
+                        }
+                    }
                     QuotaProperty::Types => vec![
                         Value::Element(QuotaValue::Types(DataType::Email)),
                         Value::Element(QuotaValue::Types(DataType::SieveScript)),
@@ -4,5 +4,68 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use common::{
+    Server,
+    auth::{AccessToken, AccountCache, TenantCache},
+};
+use std::sync::Arc;
+
 pub mod get;
 pub mod query;
+
+pub const ACCOUNT_QUOTA_ID: u32 = 0;
+pub const TENANT_QUOTA_ID: u32 = 1;
+
+/// Returns the ids of the quotas that apply to an account, along with the
+/// tenant whose aggregate quota is visible to the requester.
+pub(crate) async fn quota_ids(
+    server: &Server,
+    account: &AccountCache,
+    access_token: &AccessToken,
+) -> trc::Result<(Vec<u32>, Option<Arc<TenantCache>>)> {
+    let mut ids = Vec::with_capacity(2);
+    if account.disk_quota() > 0 {
+        ids.push(ACCOUNT_QUOTA_ID);
+    }
+
+    let tenant = tenant_quota(server, account, access_token).await?;
+    if tenant.is_some() {
+        ids.push(TENANT_QUOTA_ID);
+    }
+
+    Ok((ids, tenant))
+}
+
+// SPDX-SnippetBegin
+// SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+// SPDX-License-Identifier: LicenseRef-SEL
+#[cfg(feature = "enterprise")]
+async fn tenant_quota(
+    server: &Server,
+    account: &AccountCache,
+    access_token: &AccessToken,
+) -> trc::Result<Option<Arc<TenantCache>>> {
+    // The tenant aggregate is only disclosed to administrators of the tenant
+    if server.core.is_enterprise_edition()
+        && let Some(tenant_id) = account.id_tenant
+        && access_token.tenant_id() == Some(tenant_id)
+        && access_token.has_permission(registry::schema::enums::Permission::SysAccountQuery)
+    {
+        let tenant = server.tenant(tenant_id).await?;
+        if tenant.quota_disk > 0 {
+            return Ok(Some(tenant));
+        }
+    }
+
+    Ok(None)
+}
+// SPDX-SnippetEnd
+
+#[cfg(not(feature = "enterprise"))]
+async fn tenant_quota(
+    _server: &Server,
+    _account: &AccountCache,
+    _access_token: &AccessToken,
+) -> trc::Result<Option<Arc<TenantCache>>> {
+    Ok(None)
+}
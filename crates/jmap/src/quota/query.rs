@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::quota::quota_ids;
 use common::{Server, auth::AccessToken};
 use jmap_proto::{
     method::query::{QueryRequest, QueryResponse},
@@ -11,6 +12,7 @@ use jmap_proto::{
     types::state::State,
 };
 use std::future::Future;
+use trc::AddContext;
 use types::id::Id;
 
 pub trait QuotaQuery: Sync + Send {
@@ -27,17 +29,21 @@ impl QuotaQuery for Server {
         request: QueryRequest<Quota>,
         access_token: &AccessToken,
     ) -> trc::Result<QueryResponse> {
+        let account = self
+            .account(request.account_id.document_id())
+            .await
+            .caused_by(trc::location!())?;
+        let (quota_ids, _) = quota_ids(self, &account, access_token)
+            .await
+            .caused_by(trc::location!())?;
+
         Ok(QueryResponse {
             account_id: request.account_id,
             query_state: State::Initial,
             can_calculate_changes: false,
             position: 0,
-            ids: if self.account(access_token.account_id()).await?.disk_quota() > 0 {
-                vec![Id::new(0)]
-            } else {
-                vec![]
-            },
-            total: Some(1),
+            total: Some(quota_ids.len()),
+            ids: quota_ids.into_iter().map(Id::from).collect(),
             limit: None,
         })
     }
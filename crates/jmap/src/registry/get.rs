@@ -7,9 +7,10 @@
 use crate::registry::{
     EnterpriseRegistry,
     mapping::{
-        RegistryGetResponse, account::account_get, bootstrap::bootstrap_get,
-        cluster::cluster_node_get, log::log_get, queued_message::queued_message_get,
-        report::report_get, spam_sample::spam_sample_get, task::task_get,
+        RegistryGetResponse, account::account_get, audit::audit_event_get,
+        bootstrap::bootstrap_get, cluster::cluster_node_get, log::log_get,
+        queued_message::queued_message_get, report::report_get, spam_sample::spam_sample_get,
+        task::task_get,
     },
 };
 use common::{Server, auth::AccessToken, network::dkim::generate_dkim_public_key};
@@ -137,6 +138,7 @@ impl RegistryGet for Server {
             | ObjectType::MtaDeliverySchedule
             | ObjectType::MtaExtensions
             | ObjectType::MtaHook
+            | ObjectType::MtaResponse
             | ObjectType::MtaInboundSession
             | ObjectType::MtaInboundThrottle
             | ObjectType::MtaMilter
@@ -152,6 +154,7 @@ impl RegistryGet for Server {
             | ObjectType::MtaStageRcpt
             | ObjectType::MtaSts
             | ObjectType::MtaTlsStrategy
+            | ObjectType::MtaUnsubscribe
             | ObjectType::MtaVirtualQueue
             | ObjectType::NetworkListener
             | ObjectType::ClusterRole
@@ -166,6 +169,7 @@ impl RegistryGet for Server {
             | ObjectType::SieveSystemScript
             | ObjectType::SieveUserInterpreter
             | ObjectType::SieveUserScript
+            | ObjectType::SpamClamAv
             | ObjectType::SpamClassifier
             | ObjectType::SpamDnsblServer
             | ObjectType::SpamDnsblSettings
@@ -185,13 +189,17 @@ impl RegistryGet for Server {
             | ObjectType::WebHook
             | ObjectType::Account
             | ObjectType::DsnReportSettings
+            | ObjectType::MailCategory
             | ObjectType::MailingList
+            | ObjectType::MessageTemplate
             | ObjectType::OAuthClient
             | ObjectType::Role
             | ObjectType::Tenant
             | ObjectType::MaskedEmail
             | ObjectType::PublicKey
+            | ObjectType::DeliveryCallback
             | ObjectType::DkimSignature
+            | ObjectType::UnsubscribeEvent
             | ObjectType::Domain => {
                 let is_singleton = (get.object_flags & OBJ_SINGLETON) != 0;
 
@@ -352,6 +360,7 @@ impl RegistryGet for Server {
                 spam_sample_get(get).await.map(|get| get.into_response())
             }
             ObjectType::Log => log_get(get).await.map(|get| get.into_response()),
+            ObjectType::AuditEvent => audit_event_get(get).await.map(|get| get.into_response()),
             ObjectType::Bootstrap => bootstrap_get(get).await.map(|get| get.into_response()),
             ObjectType::AccountSettings
             | ObjectType::ApiKey
@@ -9,7 +9,7 @@ use crate::{
     registry::{
         mapping::{
             RegistryGetResponse, RegistryQueryResponse, RegistrySetResponse,
-            principal::build_set_error,
+            principal::{build_set_error, needs_re_encryption, schedule_account_re_encryption},
         },
         query::RegistryQueryFilters,
         set::map_write_error,
@@ -87,7 +87,9 @@ pub(crate) async fn account_set(
                         property @ (Property::EncryptionAtRest
                         | Property::Locale
                         | Property::Description
-                        | Property::TimeZone),
+                        | Property::TimeZone
+                        | Property::CalendarInvitations
+                        | Property::SieveLogging),
                     ) = key
                     {
                         let ptr =
@@ -239,8 +241,14 @@ pub(crate) async fn account_set(
                                 )
                                 .await?
                                 {
+                                    SecretVerificationResult::ValidTotp(step)
+                                        if set
+                                            .server
+                                            .accept_totp_step(set.account_id, step)
+                                            .await? => {}
                                     SecretVerificationResult::Valid => {}
-                                    SecretVerificationResult::Invalid => {
+                                    SecretVerificationResult::Invalid
+                                    | SecretVerificationResult::ValidTotp(_) => {
                                         let account = set.server.account(set.account_id).await?;
                                         if set.server.has_auth_fail2ban()
                                             && set
@@ -315,6 +323,8 @@ pub(crate) async fn account_set(
                                     )
                                     .await
                                     .caused_by(trc::location!())?;
+                                    account.credential_generation =
+                                        old_account.credential_generation + 1;
                                 }
 
                                 if account_pass.otp_auth.otp_url != old_credential.otp_auth {
@@ -639,9 +649,16 @@ pub(crate) async fn account_set(
         {
             cache_invalidator.invalidate(CacheInvalidation::Account(set.account_id));
         }
-        if account.credentials != old_account.credentials {
+        if account.credentials != old_account.credentials
+            || account.credential_generation != old_account.credential_generation
+        {
             cache_invalidator.invalidate(CacheInvalidation::AccessToken(set.account_id));
         }
+        if account.credential_generation != old_account.credential_generation {
+            cache_invalidator.invalidate(CacheInvalidation::Sessions(set.account_id));
+        }
+        let re_encrypt =
+            needs_re_encryption(&account.encryption_at_rest, &old_account.encryption_at_rest);
 
         let object = Object::new(ObjectInner::Account(Account::User(account)));
         let old_object = Object::with_revision(
@@ -662,6 +679,11 @@ pub(crate) async fn account_set(
             RegistryWriteResult::Success(_) => {
                 // Invalidate caches
                 set.server.invalidate_caches(cache_invalidator).await?;
+
+                // Re-encrypt stored messages with the new key
+                if re_encrypt {
+                    schedule_account_re_encryption(set.server, item_id).await?;
+                }
             }
             err => {
                 let err = map_write_error(err);
@@ -726,6 +748,8 @@ pub(crate) async fn account_get(
                             locale: account.locale,
                             description: account.description,
                             time_zone: account.time_zone,
+                            calendar_invitations: account.calendar_invitations,
+                            sieve_logging: account.sieve_logging,
                         }
                         .into_value(),
                     );
@@ -20,19 +20,32 @@ use mail_parser::MessageParser;
 use registry::{
     jmap::{IntoValue, JsonPointerPatch, RegistryJsonPatch},
     schema::{
-        enums::{SpamClassifyParameters, SpamClassifyResult, SpamClassifyTagDisposition},
+        enums::{
+            Permission, SpamClassifyParameters, SpamClassifyResult, SpamClassifyTagDisposition,
+            TaskStoreMaintenanceType,
+        },
         prelude::{ObjectType, Property},
-        structs::{Action, DmarcTroubleshoot, SpamClassify, SpamClassifyTag},
+        structs::{
+            Action, DmarcTroubleshoot, SpamClassify, SpamClassifyTag, Task, TaskStatus,
+            TaskStoreMaintenance,
+        },
     },
     types::{EnumImpl, ObjectImpl},
 };
 use smtp_proto::{MAIL_BODY_7BIT, MAIL_BODY_8BITMIME, MAIL_BODY_BINARYMIME, MAIL_SMTPUTF8};
 use spam_filter::{
     SpamFilterInput,
-    analysis::{init::SpamFilterInit, score::SpamFilterAnalyzeScore},
+    analysis::{
+        init::SpamFilterInit,
+        score::{SpamFilterAnalyzeScore, SpamFilterScore},
+    },
+};
+use std::{net::IpAddr, time::Instant};
+use store::{
+    ahash::AHashSet,
+    registry::bootstrap::Bootstrap,
+    write::{BatchBuilder, now},
 };
-use std::time::Instant;
-use store::{registry::bootstrap::Bootstrap, write::now};
 use utils::map::vec_map::VecMap;
 
 pub(crate) async fn action_set(
@@ -63,7 +76,10 @@ pub(crate) async fn action_set(
             continue 'outer;
         }
 
-        if !set.access_token.has_permission(action.permission()) {
+        if !set.access_token.has_permission(action.permission())
+            || (!matches!(action, Action::SignOutEverywhere)
+                && !set.access_token.has_permission(Permission::SysActionCreate))
+        {
             set.response.not_created.append(
                 id,
                 SetError::forbidden().with_description(format!(
@@ -95,6 +111,21 @@ pub(crate) async fn action_set(
                             object,
                         )))
                         .await;
+
+                    // Quota usage depends on how shared contents are charged
+                    if object == ObjectType::DataStore
+                        && set.server.inner.shared_core.load().email.shared_blob_quota
+                            != set.server.core.email.shared_blob_quota
+                    {
+                        let mut batch = BatchBuilder::new();
+                        batch.schedule_task(Task::StoreMaintenance(TaskStoreMaintenance {
+                            maintenance_type: TaskStoreMaintenanceType::ResetUserQuotas,
+                            shard_index: None,
+                            status: TaskStatus::now(),
+                        }));
+                        set.server.store().write(batch.build_all()).await?;
+                        set.server.notify_task_queue();
+                    }
                     set.response.created(id, now());
                 } else {
                     set.response
@@ -142,6 +173,16 @@ pub(crate) async fn action_set(
                     .await;
                 set.response.created(id, now());
             }
+            Action::DrainServer => {
+                set.server.start_drain();
+                set.response.created(id, now());
+            }
+            Action::SignOutEverywhere => {
+                set.server
+                    .revoke_credentials(set.access_token.account_id())
+                    .await?;
+                set.response.created(id, now());
+            }
             Action::TroubleshootDmarc(troubleshoot) => {
                 if let Some(result) = dmarc_troubleshoot(set.server, troubleshoot).await {
                     let mut result = result.into_value();
@@ -235,16 +276,92 @@ pub(crate) async fn action_set(
     Ok(set)
 }
 
+pub struct SpamCheck<'x> {
+    pub raw_message: &'x [u8],
+    pub remote_ip: IpAddr,
+    pub ehlo_domain: &'x str,
+    pub env_from: &'x str,
+    pub env_from_flags: u64,
+    pub env_rcpt_to: &'x [String],
+    pub authenticated_as: Option<&'x str>,
+    pub is_tls: bool,
+}
+
+pub struct SpamCheckResult {
+    pub action: SpamFilterAction<SpamFilterScore>,
+    pub score: f32,
+    pub tags: AHashSet<String>,
+}
+
 async fn classify_spam(server: &Server, mut request: SpamClassify) -> Option<SpamClassify> {
+    let result = spam_check(
+        server,
+        SpamCheck {
+            raw_message: request.message.as_bytes(),
+            remote_ip: request.remote_ip.into_inner(),
+            ehlo_domain: &request.ehlo_domain,
+            env_from: &request.env_from,
+            env_from_flags: match request.env_from_parameters {
+                Some(SpamClassifyParameters::Bit7) => MAIL_BODY_7BIT,
+                Some(SpamClassifyParameters::Bit8Mime8BitMIMEMessageContent) => {
+                    MAIL_BODY_BINARYMIME
+                }
+                Some(SpamClassifyParameters::BinaryMime) => MAIL_BODY_8BITMIME,
+                Some(SpamClassifyParameters::SmtpUtf8) => MAIL_SMTPUTF8,
+                None => 0,
+            },
+            env_rcpt_to: request.env_rcpt_to.as_slice(),
+            authenticated_as: request.authenticated_as.as_deref(),
+            is_tls: request.is_tls,
+        },
+    )
+    .await?;
+
+    // Build response
+    request.result = match result.action {
+        SpamFilterAction::Allow(result) => {
+            request.score = (result.score as f64).into();
+            if result.is_spam {
+                SpamClassifyResult::Spam
+            } else {
+                SpamClassifyResult::Ham
+            }
+        }
+        SpamFilterAction::Discard => SpamClassifyResult::Discard,
+        SpamFilterAction::Reject | SpamFilterAction::Disabled => SpamClassifyResult::Reject,
+    };
+
+    request.tags = VecMap::with_capacity(result.tags.len());
+    for tag in result.tags {
+        let (score, disposition) = match server.core.spam.lists.scores.get(&tag) {
+            Some(SpamFilterAction::Allow(score)) => (*score, SpamClassifyTagDisposition::Score),
+            Some(SpamFilterAction::Discard) => (0.0, SpamClassifyTagDisposition::Discard),
+            _ => (0.0, SpamClassifyTagDisposition::Reject),
+        };
+        request.tags.append(
+            tag,
+            SpamClassifyTag {
+                disposition,
+                score: (score as f64).into(),
+            },
+        );
+    }
+
+    Some(request)
+}
+
+/// Authenticates and classifies a message as if it had been received over
+/// SMTP with the provided envelope.
+pub async fn spam_check(server: &Server, check: SpamCheck<'_>) -> Option<SpamCheckResult> {
     // Built spam filter input
-    let raw_message = request.message.as_bytes();
+    let raw_message = check.raw_message;
     let message = MessageParser::new()
         .parse(raw_message)
         .filter(|m| m.root_part().headers().iter().any(|h| !h.name.is_other()))?;
 
-    let remote_ip = request.remote_ip.into_inner();
-    let ehlo_domain = request.ehlo_domain.to_lowercase();
-    let mail_from = request.env_from.to_lowercase();
+    let remote_ip = check.remote_ip;
+    let ehlo_domain = check.ehlo_domain.to_lowercase();
+    let mail_from = check.env_from.to_lowercase();
     let mail_from_domain = mail_from.rsplit_once('@').map(|(_, domain)| domain);
     let local_host = &server.core.network.server_name;
 
@@ -330,7 +447,7 @@ async fn classify_spam(server: &Server, mut request: SpamClassify) -> Option<Spa
             server.inner.cache.build_auth_parameters(&auth_message),
             Dkim2Envelope {
                 mail_from: &mail_from,
-                rcpt_to: request.env_rcpt_to.iter(),
+                rcpt_to: check.env_rcpt_to.iter(),
             },
         )
         .await;
@@ -374,61 +491,33 @@ async fn classify_spam(server: &Server, mut request: SpamClassify) -> Option<Spa
         dmarc_result: Some(&dmarc_result),
         dmarc_policy: Some(&dmarc_policy),
         iprev_result: Some(&iprev_result),
+        sender_verify_result: None,
         remote_ip,
         ehlo_domain: Some(ehlo_domain.as_str()),
-        authenticated_as: request.authenticated_as.as_deref(),
+        authenticated_as: check.authenticated_as,
         asn: asn_geo.asn.as_ref().map(|a| a.id),
         country: asn_geo.country.as_ref().map(|c| c.as_str()),
-        is_tls: request.is_tls,
-        env_from: &request.env_from,
-        env_from_flags: match request.env_from_parameters {
-            Some(SpamClassifyParameters::Bit7) => MAIL_BODY_7BIT,
-            Some(SpamClassifyParameters::Bit8Mime8BitMIMEMessageContent) => MAIL_BODY_BINARYMIME,
-            Some(SpamClassifyParameters::BinaryMime) => MAIL_BODY_8BITMIME,
-            Some(SpamClassifyParameters::SmtpUtf8) => MAIL_SMTPUTF8,
-            None => 0,
-        },
-        env_rcpt_orig_to: request.env_rcpt_to.iter().map(String::as_str).collect(),
-        env_rcpt_rewritten_to: request.env_rcpt_to.iter().map(String::as_str).collect(),
+        is_tls: check.is_tls,
+        env_from: check.env_from,
+        env_from_flags: check.env_from_flags,
+        env_rcpt_orig_to: check.env_rcpt_to.iter().map(String::as_str).collect(),
+        env_rcpt_rewritten_to: check.env_rcpt_to.iter().map(String::as_str).collect(),
         is_test: true,
         is_train: false,
     };
 
     // Classify
     let mut ctx = server.spam_filter_init(input);
-    let result = server.spam_filter_classify(&mut ctx).await;
-
-    // Build response
-    request.result = match result {
-        SpamFilterAction::Allow(result) => {
-            request.score = (result.score as f64).into();
-            if result.is_spam {
-                SpamClassifyResult::Spam
-            } else {
-                SpamClassifyResult::Ham
-            }
-        }
-        SpamFilterAction::Discard => SpamClassifyResult::Discard,
-        SpamFilterAction::Reject | SpamFilterAction::Disabled => SpamClassifyResult::Reject,
-    };
-
-    request.tags = VecMap::with_capacity(ctx.result.tags.len());
-    for tag in ctx.result.tags {
-        let (score, disposition) = match server.core.spam.lists.scores.get(&tag) {
-            Some(SpamFilterAction::Allow(score)) => (*score, SpamClassifyTagDisposition::Score),
-            Some(SpamFilterAction::Discard) => (0.0, SpamClassifyTagDisposition::Discard),
-            _ => (0.0, SpamClassifyTagDisposition::Reject),
-        };
-        request.tags.append(
-            tag,
-            SpamClassifyTag {
-                disposition,
-                score: (score as f64).into(),
-            },
-        );
-    }
+    let action = server.spam_filter_classify(&mut ctx).await;
 
-    Some(request)
+    Some(SpamCheckResult {
+        score: match &action {
+            SpamFilterAction::Allow(result) => result.score,
+            _ => ctx.result.score,
+        },
+        tags: std::mem::take(&mut ctx.result.tags),
+        action,
+    })
 }
 
 async fn dmarc_troubleshoot(
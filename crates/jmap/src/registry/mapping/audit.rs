@@ -0,0 +1,266 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    api::query::QueryResponseBuilder,
+    registry::{
+        mapping::{RegistryGetResponse, RegistryQueryResponse},
+        query::RegistryQueryFilters,
+    },
+};
+use common::Server;
+use jmap_proto::types::state::State;
+use registry::{
+    jmap::IntoValue,
+    schema::{enums::AuditEventType, prelude::Property, structs::AuditEvent},
+    types::{EnumImpl, datetime::UTCDateTime},
+};
+use std::str::FromStr;
+use store::{
+    Deserialize, IterateParams, U64_LEN, ValueKey,
+    registry::RegistryFilterOp,
+    write::{AuditClass, ValueClass, key::DeserializeBigEndian},
+};
+use trc::AddContext;
+use types::id::Id;
+use utils::snowflake::SnowflakeIdGenerator;
+
+pub(crate) async fn audit_event_get(
+    mut get: RegistryGetResponse<'_>,
+) -> trc::Result<RegistryGetResponse<'_>> {
+    let ids = if let Some(ids) = get.ids.take() {
+        ids
+    } else {
+        audit_event_ids(get.server, get.server.core.jmap.get_max_objects).await?
+    };
+    let tenant_id = get.access_token.tenant_id();
+
+    for id in ids {
+        if let Some(event) = get
+            .server
+            .store()
+            .get_value::<AuditEvent>(ValueKey::from(ValueClass::Audit(AuditClass::Event(
+                id.id(),
+            ))))
+            .await?
+            && (tenant_id.is_none()
+                || account_tenant_id(get.server, event.account_id.document_id()).await?
+                    == tenant_id)
+        {
+            get.insert(id, event.into_value());
+        } else {
+            get.not_found(id);
+        }
+    }
+
+    Ok(get)
+}
+
+pub(crate) async fn audit_event_query(
+    mut req: RegistryQueryResponse<'_>,
+) -> trc::Result<QueryResponseBuilder> {
+    let mut ts_from = 0u64;
+    let mut ts_to = u64::MAX;
+    let mut account_id = None;
+    let mut event_type = None;
+
+    req.request
+        .extract_filters(|property, op, value| match property {
+            Property::AccountId => {
+                if let Some(id) = value.as_str().and_then(|s| Id::from_str(s).ok()) {
+                    account_id = Some(id.document_id());
+                    true
+                } else {
+                    false
+                }
+            }
+            Property::Timestamp => {
+                if let Some(ts) = value.as_str().and_then(|s| UTCDateTime::from_str(s).ok()) {
+                    let ts = ts.timestamp() as u64;
+                    let (from, to) = match op {
+                        RegistryFilterOp::Equal => (ts, ts),
+                        RegistryFilterOp::GreaterThan => (ts + 1, u64::MAX),
+                        RegistryFilterOp::GreaterEqualThan => (ts, u64::MAX),
+                        RegistryFilterOp::LowerThan => (0, ts.saturating_sub(1)),
+                        RegistryFilterOp::LowerEqualThan => (0, ts),
+                        _ => return false,
+                    };
+
+                    // Intersect with existing range
+                    ts_from = ts_from.max(from);
+                    ts_to = ts_to.min(to);
+
+                    true
+                } else {
+                    false
+                }
+            }
+            Property::Event => {
+                if let Some(typ) = value.as_str().and_then(AuditEventType::parse) {
+                    event_type = Some(typ);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        })?;
+
+    let params = req
+        .request
+        .extract_parameters(req.server.core.jmap.query_max_results, None)?;
+
+    if !matches!(params.sort_by, Property::Id | Property::Timestamp) {
+        return Err(trc::JmapEvent::UnsupportedSort.into_err().details(format!(
+            "Property {} is not supported for sorting",
+            params.sort_by
+        )));
+    }
+
+    // Tenant administrators are limited to the accounts of their tenant
+    if let Some(tenant_id) = req.access_token.tenant_id() {
+        let is_member = match account_id {
+            Some(account_id) => account_tenant_id(req.server, account_id).await? == Some(tenant_id),
+            None => false,
+        };
+
+        if !is_member {
+            return Err(trc::JmapEvent::Forbidden
+                .into_err()
+                .details("Audit events must be filtered by an account of your tenant"));
+        }
+    }
+
+    if ts_from != 0 {
+        ts_from = SnowflakeIdGenerator::from_timestamp(ts_from).unwrap_or(u64::MAX);
+    }
+    if ts_to != u64::MAX {
+        // Include every event generated during the last second of the range
+        ts_to = SnowflakeIdGenerator::from_timestamp(ts_to + 1)
+            .map(|id| id.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+    }
+
+    if let Some(anchor) = req.request.anchor {
+        let anchor = anchor.id();
+        if params.sort_ascending {
+            if anchor > ts_from {
+                ts_from = anchor;
+            }
+        } else if anchor < ts_to {
+            ts_to = anchor;
+        }
+    }
+
+    let (from_key, to_key) = if let Some(account_id) = account_id {
+        (
+            ValueKey::from(ValueClass::Audit(AuditClass::Index {
+                account_id,
+                event_id: ts_from,
+            })),
+            ValueKey::from(ValueClass::Audit(AuditClass::Index {
+                account_id,
+                event_id: ts_to,
+            })),
+        )
+    } else {
+        (
+            ValueKey::from(ValueClass::Audit(AuditClass::Event(ts_from))),
+            ValueKey::from(ValueClass::Audit(AuditClass::Event(ts_to))),
+        )
+    };
+
+    // Build response
+    let mut response = QueryResponseBuilder::new(
+        req.server.core.jmap.query_max_results + 1,
+        req.server.core.jmap.query_max_results,
+        State::Initial,
+        &req.request,
+    );
+
+    let mut total = 0;
+
+    req.server
+        .store()
+        .iterate(
+            IterateParams::new(from_key, to_key)
+                .set_ascending(params.sort_ascending)
+                .set_values(event_type.is_some())
+                .read_only(),
+            |key, value| {
+                let id = key.deserialize_be_u64(key.len() - U64_LEN)?;
+
+                if let Some(event_type) = event_type {
+                    // Index entries hold the event type, full records are decoded otherwise
+                    let typ = if account_id.is_some() {
+                        value
+                            .first()
+                            .and_then(|typ| AuditEventType::from_id(*typ as u16))
+                    } else {
+                        Some(AuditEvent::deserialize(value)?.event)
+                    };
+                    if typ != Some(event_type) {
+                        return Ok(true);
+                    }
+                }
+
+                total += 1;
+                if response.response.total.is_some() {
+                    if !response.is_full() {
+                        response.add_id(id.into());
+                    }
+                    Ok(true)
+                } else {
+                    Ok(response.add_id(id.into()))
+                }
+            },
+        )
+        .await
+        .caused_by(trc::location!())?;
+
+    if response.response.total.is_some() {
+        response.response.total = Some(total);
+    }
+
+    if let Some(limit) = response.response.limit
+        && total < limit
+    {
+        response.response.limit = None;
+    }
+
+    Ok(response)
+}
+
+async fn audit_event_ids(server: &Server, max_results: usize) -> trc::Result<Vec<Id>> {
+    let mut events = Vec::with_capacity(8);
+
+    let from_key = ValueKey::from(ValueClass::Audit(AuditClass::Event(0)));
+    let to_key = ValueKey::from(ValueClass::Audit(AuditClass::Event(u64::MAX)));
+
+    server
+        .store()
+        .iterate(
+            IterateParams::new(from_key, to_key)
+                .descending()
+                .no_values()
+                .read_only(),
+            |key, _| {
+                events.push(key.deserialize_be_u64(key.len() - U64_LEN)?.into());
+
+                Ok(events.len() < max_results)
+            },
+        )
+        .await
+        .caused_by(trc::location!())
+        .map(|_| events)
+}
+
+async fn account_tenant_id(server: &Server, account_id: u32) -> trc::Result<Option<u32>> {
+    server
+        .try_account(account_id)
+        .await
+        .map(|account| account.and_then(|account| account.id_tenant))
+}
@@ -404,6 +404,7 @@ pub(crate) async fn bootstrap_set(
                 CertificateManagement::Automatic(CertificateManagementProperties {
                     acme_provider_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 })
             } else {
                 CertificateManagement::Manual
@@ -0,0 +1,66 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::registry::mapping::{ObjectResponse, RegistrySetResponse, ValidationResult};
+use jmap_proto::error::set::SetError;
+use registry::schema::{
+    prelude::{ObjectType, Property},
+    structs::DeliveryCallback,
+};
+use store::registry::{RegistryObjectCounter, RegistryQuery};
+use utils::http::is_internal_url;
+
+pub(crate) async fn validate_delivery_callback(
+    set: &RegistrySetResponse<'_>,
+    callback: &mut DeliveryCallback,
+    is_create: bool,
+) -> ValidationResult {
+    if is_create && let Some(max_callbacks) = set.server.core.email.max_delivery_callbacks {
+        // Validate quotas
+        let num_callbacks = set
+            .server
+            .registry()
+            .query::<RegistryObjectCounter>(
+                RegistryQuery::new(ObjectType::DeliveryCallback).with_account(set.account_id),
+            )
+            .await?
+            .0 as u32;
+        if num_callbacks >= max_callbacks {
+            return Ok(Err(SetError::over_quota().with_description(format!(
+                "You have exceeded your quota of {} delivery callbacks.",
+                max_callbacks
+            ))));
+        }
+    }
+
+    let url = callback.url.to_ascii_lowercase();
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return Ok(Err(SetError::invalid_properties()
+            .with_property(Property::Url)
+            .with_description(
+                "Callback URL must use the http or https scheme.",
+            )));
+    }
+
+    // Callbacks must not be used to reach internal services
+    match is_internal_url(&callback.url).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return Ok(Err(SetError::invalid_properties()
+                .with_property(Property::Url)
+                .with_description(
+                    "Callback URL must not point to a private, loopback or link-local address.",
+                )));
+        }
+        Err(err) => {
+            return Ok(Err(SetError::invalid_properties()
+                .with_property(Property::Url)
+                .with_description(err)));
+        }
+    }
+
+    Ok(Ok(ObjectResponse::default()))
+}
@@ -23,8 +23,10 @@ use utils::map::vec_map::VecMap;
 
 pub mod account;
 pub mod action;
+pub mod audit;
 pub mod bootstrap;
 pub mod cluster;
+pub mod delivery_callback;
 pub mod dkim;
 pub mod domain;
 pub mod log;
@@ -14,22 +14,28 @@ use jmap_proto::error::set::SetError;
 use registry::{schema::structs::TaskStatus, types::datetime::UTCDateTime};
 use registry::{
     schema::{
-        enums::{AccountType, Permission, TenantStorageQuota},
+        enums::{AccountType, Permission, TaskTenantMaintenanceType, TaskType, TenantStorageQuota},
         prelude::{MASKED_PASSWORD, ObjectType, Property},
-        structs::{Account, Credential, Role, Task, TaskDestroyAccount},
+        structs::{
+            Account, Credential, EncryptionAtRest, Role, Task, TaskDestroyAccount,
+            TaskReEncryptAccount, TaskTenantMaintenance, UserAccount,
+        },
     },
     types::EnumImpl,
 };
 use store::{
+    ValueKey,
     registry::{RegistryObjectCounter, RegistryQuery},
-    write::{BatchBuilder, RegistryClass, ValueClass, now},
+    write::{BatchBuilder, RegistryClass, TaskQueueClass, ValueClass, now},
 };
 use trc::AddContext;
 use types::id::Id;
 
+const RE_ENCRYPT_DELAY: i64 = 5;
+
 #[derive(Clone, Copy)]
 pub enum AccountUpdate<'x> {
-    Update(&'x Account),
+    Update(Id, &'x Account),
     Create(&'x str),
 }
 
@@ -37,6 +43,7 @@ pub(crate) async fn validate_account(
     set: &RegistrySetResponse<'_>,
     mut account: &mut Account,
     old_account: AccountUpdate<'_>,
+    tasks: &mut Vec<Task>,
 ) -> ValidationResult {
     // SPDX-SnippetBegin
     // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
@@ -74,13 +81,14 @@ pub(crate) async fn validate_account(
     };
 
     let validate_permissions = match (&mut account, old_account) {
-        (Account::User(account), AccountUpdate::Update(Account::User(old_account))) => {
+        (Account::User(account), AccountUpdate::Update(account_id, Account::User(old_account))) => {
             // Validate credentials
             let has_password = account.credentials.values().any(|credential| {
                 matches!(credential, Credential::Password(credential) if credential.credential_id.is_valid())
             });
             let mut max_credential_id = 0;
             let mut has_new_credentials = false;
+            let mut password_changed = false;
             for credential in account.credentials.values_mut() {
                 let credential_id = credential.credential_id();
 
@@ -118,6 +126,7 @@ pub(crate) async fn validate_account(
                                 }
 
                                 if credential.secret != old_credential.secret {
+                                    password_changed = true;
                                     if credential.expires_at == old_credential.expires_at
                                         && credential
                                             .expires_at
@@ -209,9 +218,33 @@ pub(crate) async fn validate_account(
                 }
             }
 
+            // Revoke existing sessions and tokens after a password change
+            if password_changed {
+                account.credential_generation = old_account.credential_generation + 1;
+            }
+
+            // Re-encrypt stored messages with the new key
+            if needs_re_encryption(&account.encryption_at_rest, &old_account.encryption_at_rest) {
+                tasks.push(Task::ReEncryptAccount(TaskReEncryptAccount::default()));
+            }
+
+            // Move the account's usage between the tenant aggregates
+            if account.member_tenant_id != old_account.member_tenant_id {
+                if let Err(err) = validate_tenant_move(set, account_id, account).await? {
+                    return Ok(Err(err));
+                }
+
+                tasks.extend(
+                    [old_account.member_tenant_id, account.member_tenant_id]
+                        .into_iter()
+                        .flatten()
+                        .map(tenant_quota_task),
+                );
+            }
+
             account.permissions != old_account.permissions || account.roles != old_account.roles
         }
-        (Account::Group(account), AccountUpdate::Update(Account::Group(old_account))) => {
+        (Account::Group(account), AccountUpdate::Update(_, Account::Group(old_account))) => {
             account.permissions != old_account.permissions || account.roles != old_account.roles
         }
         (Account::User(account), AccountUpdate::Create(_)) => {
@@ -245,8 +278,8 @@ pub(crate) async fn validate_account(
 
             true
         }
-        (Account::User(_), AccountUpdate::Update(Account::Group(_)))
-        | (Account::Group(_), AccountUpdate::Update(Account::User(_))) => {
+        (Account::User(_), AccountUpdate::Update(_, Account::Group(_)))
+        | (Account::Group(_), AccountUpdate::Update(_, Account::User(_))) => {
             return Ok(Err(SetError::invalid_properties()
                 .with_property(Property::Type)
                 .with_description(
@@ -372,55 +405,124 @@ pub(crate) async fn validate_tenant_quota(
     quota: TenantStorageQuota,
 ) -> ValidationResult {
     if let Some(tenant_id) = set.access_token.tenant_id() {
-        let tenant = set.server.tenant(tenant_id).await?;
-        if let Some(quotas) = tenant
-            .quota_objects
-            .as_ref()
-            .map(|quotas| quotas.get(quota))
-            .filter(|quota| *quota != u32::MAX)
+        validate_tenant_quota_for(set, tenant_id, quota).await
+    } else {
+        Ok(Ok(ObjectResponse::default()))
+    }
+}
+
+#[cfg(feature = "enterprise")]
+pub(crate) async fn validate_tenant_quota_for(
+    set: &RegistrySetResponse<'_>,
+    tenant_id: u32,
+    quota: TenantStorageQuota,
+) -> ValidationResult {
+    let tenant = set.server.tenant(tenant_id).await?;
+    if let Some(quotas) = tenant
+        .quota_objects
+        .as_ref()
+        .map(|quotas| quotas.get(quota))
+        .filter(|quota| *quota != u32::MAX)
+    {
+        let (object_type, type_filter, description) = match quota {
+            TenantStorageQuota::MaxAccounts => {
+                (ObjectType::Account, Some(AccountType::User), "accounts")
+            }
+            TenantStorageQuota::MaxGroups => {
+                (ObjectType::Account, Some(AccountType::Group), "groups")
+            }
+            TenantStorageQuota::MaxDomains => (ObjectType::Domain, None, "domains"),
+            TenantStorageQuota::MaxMailingLists => (ObjectType::MailingList, None, "mailing lists"),
+            TenantStorageQuota::MaxRoles => (ObjectType::Role, None, "roles"),
+            TenantStorageQuota::MaxOauthClients => (ObjectType::OAuthClient, None, "OAuth clients"),
+            TenantStorageQuota::MaxDkimKeys => (ObjectType::DkimSignature, None, "DKIM keys"),
+            TenantStorageQuota::MaxDnsServers => (ObjectType::DnsServer, None, "DNS servers"),
+            TenantStorageQuota::MaxDirectories => (ObjectType::Directory, None, "directories"),
+            TenantStorageQuota::MaxAcmeProviders => {
+                (ObjectType::AcmeProvider, None, "ACME providers")
+            }
+            TenantStorageQuota::MaxDiskQuota => unreachable!(),
+        };
+        let query = RegistryQuery::new(object_type).with_tenant(tenant_id.into());
+        let count = if let Some(type_filter) = type_filter {
+            set.server
+                .registry()
+                .query::<Vec<Id>>(query.equal(Property::Type, type_filter.to_id()))
+                .await?
+                .len() as u32
+        } else {
+            set.server
+                .registry()
+                .query::<RegistryObjectCounter>(query)
+                .await?
+                .0 as u32
+        };
+
+        if count >= quotas {
+            return Ok(Err(SetError::over_quota().with_description(format!(
+                "You have exceeded your quota of {} {}.",
+                quotas, description
+            ))));
+        }
+    }
+
+    Ok(Ok(ObjectResponse::default()))
+}
+// SPDX-SnippetEnd
+
+#[cfg(not(feature = "enterprise"))]
+pub(crate) async fn validate_tenant_quota(
+    _set: &RegistrySetResponse<'_>,
+    _quota: TenantStorageQuota,
+) -> ValidationResult {
+    ValidationResult::Ok(Ok(ObjectResponse::default()))
+}
+
+// SPDX-SnippetBegin
+// SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+// SPDX-License-Identifier: LicenseRef-SEL
+#[cfg(feature = "enterprise")]
+pub(crate) async fn validate_tenant_move(
+    set: &RegistrySetResponse<'_>,
+    account_id: Id,
+    account: &UserAccount,
+) -> ValidationResult {
+    // The domains of the account have to belong to its new tenant
+    let tenant_id = account.member_tenant_id.map(|id| id.document_id());
+    for domain_id in std::iter::once(account.domain_id)
+        .chain(account.aliases.values().map(|alias| alias.domain_id))
+    {
+        if let Some(domain) = set.server.domain_by_id(domain_id.document_id()).await?
+            && domain.id_tenant != tenant_id
         {
-            let (object_type, type_filter, description) = match quota {
-                TenantStorageQuota::MaxAccounts => {
-                    (ObjectType::Account, Some(AccountType::User), "accounts")
-                }
-                TenantStorageQuota::MaxGroups => {
-                    (ObjectType::Account, Some(AccountType::Group), "groups")
-                }
-                TenantStorageQuota::MaxDomains => (ObjectType::Domain, None, "domains"),
-                TenantStorageQuota::MaxMailingLists => {
-                    (ObjectType::MailingList, None, "mailing lists")
-                }
-                TenantStorageQuota::MaxRoles => (ObjectType::Role, None, "roles"),
-                TenantStorageQuota::MaxOauthClients => {
-                    (ObjectType::OAuthClient, None, "OAuth clients")
-                }
-                TenantStorageQuota::MaxDkimKeys => (ObjectType::DkimSignature, None, "DKIM keys"),
-                TenantStorageQuota::MaxDnsServers => (ObjectType::DnsServer, None, "DNS servers"),
-                TenantStorageQuota::MaxDirectories => (ObjectType::Directory, None, "directories"),
-                TenantStorageQuota::MaxAcmeProviders => {
-                    (ObjectType::AcmeProvider, None, "ACME providers")
-                }
-                TenantStorageQuota::MaxDiskQuota => unreachable!(),
-            };
-            let query = RegistryQuery::new(object_type).with_tenant(tenant_id.into());
-            let count = if let Some(type_filter) = type_filter {
-                set.server
-                    .registry()
-                    .query::<Vec<Id>>(query.equal(Property::Type, type_filter.to_id()))
-                    .await?
-                    .len() as u32
-            } else {
-                set.server
-                    .registry()
-                    .query::<RegistryObjectCounter>(query)
-                    .await?
-                    .0 as u32
-            };
-
-            if count >= quotas {
+            return Ok(Err(SetError::invalid_properties()
+                .with_property(Property::MemberTenantId)
+                .with_description(format!(
+                    "Domain {} does not belong to the account's tenant.",
+                    domain.names.first().map_or("", |name| name.as_ref())
+                ))));
+        }
+    }
+
+    if let Some(tenant_id) = tenant_id {
+        if let Err(err) =
+            validate_tenant_quota_for(set, tenant_id, TenantStorageQuota::MaxAccounts).await?
+        {
+            return Ok(Err(err));
+        }
+
+        // The usage of the account has to fit in the tenant's disk quota
+        let tenant = set.server.tenant(tenant_id).await?;
+        if tenant.quota_disk > 0 {
+            let used_quota = set.server.get_used_quota_tenant(tenant_id).await?
+                + set
+                    .server
+                    .get_used_quota_account(account_id.document_id())
+                    .await?;
+            if used_quota.max(0) as u64 > tenant.quota_disk {
                 return Ok(Err(SetError::over_quota().with_description(format!(
-                    "You have exceeded your quota of {} {}.",
-                    quotas, description
+                    "Moving this account would exceed the tenant's disk quota of {} bytes.",
+                    tenant.quota_disk
                 ))));
             }
         }
@@ -431,9 +533,10 @@ pub(crate) async fn validate_tenant_quota(
 // SPDX-SnippetEnd
 
 #[cfg(not(feature = "enterprise"))]
-pub(crate) async fn validate_tenant_quota(
+pub(crate) async fn validate_tenant_move(
     _set: &RegistrySetResponse<'_>,
-    _quota: TenantStorageQuota,
+    _account_id: Id,
+    _account: &UserAccount,
 ) -> ValidationResult {
     ValidationResult::Ok(Ok(ObjectResponse::default()))
 }
@@ -491,6 +594,67 @@ pub(crate) async fn schedule_account_destruction(
     Ok(())
 }
 
+pub(crate) fn needs_re_encryption(new: &EncryptionAtRest, old: &EncryptionAtRest) -> bool {
+    let public_key = |value: &EncryptionAtRest| match value {
+        EncryptionAtRest::Disabled => None,
+        EncryptionAtRest::Aes128(settings)
+        | EncryptionAtRest::Aes256(settings)
+        | EncryptionAtRest::Aes256Gcm(settings)
+        | EncryptionAtRest::ChaCha20Poly1305(settings) => Some(settings.public_key),
+    };
+
+    // Changing only the spam training or append flags does not require a new pass
+    public_key(new).is_some_and(|key| {
+        public_key(old) != Some(key) || std::mem::discriminant(new) != std::mem::discriminant(old)
+    })
+}
+
+pub(crate) async fn schedule_account_re_encryption(
+    server: &Server,
+    account_id: Id,
+) -> trc::Result<()> {
+    // Each account has a single re-encryption task, a new rotation restarts
+    // the pending one instead of queueing another pass over the mailbox
+    let id = ((TaskType::ReEncryptAccount.to_id() as u64) << 32) | account_id.id();
+    let pending = server
+        .store()
+        .get_value::<Task>(ValueKey::from(ValueClass::TaskQueue(
+            TaskQueueClass::Task { id },
+        )))
+        .await
+        .caused_by(trc::location!())?;
+    let task = match pending {
+        Some(Task::ReEncryptAccount(pending)) => TaskReEncryptAccount {
+            account_id,
+            concurrency: pending.concurrency,
+            status: pending.status,
+            ..Default::default()
+        },
+        _ => TaskReEncryptAccount {
+            account_id,
+            // Delay the task slightly so the account cache is invalidated on all nodes
+            // before the new encryption key is loaded
+            status: TaskStatus::at(now() as i64 + RE_ENCRYPT_DELAY),
+            ..Default::default()
+        },
+    };
+
+    let mut batch = BatchBuilder::new();
+    batch.schedule_task_with_id(id, Task::ReEncryptAccount(task));
+    server.store().write(batch.build_all()).await?;
+    server.notify_task_queue();
+
+    Ok(())
+}
+
+pub(crate) fn tenant_quota_task(tenant_id: Id) -> Task {
+    Task::TenantMaintenance(TaskTenantMaintenance {
+        tenant_id,
+        maintenance_type: TaskTenantMaintenanceType::RecalculateQuota,
+        status: TaskStatus::now(),
+    })
+}
+
 pub(crate) fn build_set_error(permissions: Vec<Permission>) -> SetError<Property> {
     let mut missing_permissions = String::with_capacity(16);
     let mut total_missing = permissions.len();
@@ -33,12 +33,12 @@ use registry::{
 use smtp::queue::{
     self, ArchivedError, ArchivedErrorDetails, ArchivedMessage, ArchivedStatus, ErrorDetails,
     FROM_AUTHENTICATED, FROM_AUTOGENERATED, FROM_DSN, FROM_REPORT, FROM_UNAUTHENTICATED,
-    FROM_UNAUTHENTICATED_DMARC, Message, MessageWrapper, RCPT_DSN_SENT, RCPT_SPAM_PAYLOAD,
-    Schedule, Status, spool::SmtpSpool,
+    FROM_UNAUTHENTICATED_DMARC, Message, MessageWrapper, RCPT_DSN_SENT, RCPT_EXPIRED_ATTEMPTS,
+    RCPT_EXPIRED_TTL, RCPT_SPAM_PAYLOAD, RCPT_SPLIT, Schedule, Status, spool::SmtpSpool,
 };
 use std::str::FromStr;
 use store::{
-    Deserialize, IterateParams, U64_LEN, ValueKey,
+    Deserialize, IterateParams, ValueKey,
     ahash::AHashSet,
     registry::{RegistryFilterOp, RegistryQuery},
     write::{AlignedBytes, Archive, QueueClass, ValueClass, key::DeserializeBigEndian, now},
@@ -145,9 +145,17 @@ pub(crate) async fn queued_message_set(
                 changed = true;
             }
             let expiry = match rcpt.expires {
-                QueueExpiry::Ttl(ttl) => common::config::smtp::queue::QueueExpiry::Ttl(
-                    (ttl.expires_at.timestamp() as u64).saturating_sub(queued_message.created),
-                ),
+                QueueExpiry::Ttl(ttl) => {
+                    let expires =
+                        (ttl.expires_at.timestamp() as u64).saturating_sub(queued_message.created);
+                    match ttl.expires_attempts {
+                        Some(attempts) => common::config::smtp::queue::QueueExpiry::TtlOrAttempts {
+                            ttl: expires.min(u32::MAX as u64) as u32,
+                            attempts: attempts as u32,
+                        },
+                        None => common::config::smtp::queue::QueueExpiry::Ttl(expires),
+                    }
+                }
                 QueueExpiry::Attempts(attempts) => {
                     common::config::smtp::queue::QueueExpiry::Attempts(
                         attempts.expires_attempts as u32,
@@ -506,50 +514,27 @@ pub(crate) async fn queued_message_query(
             }
         }
 
-        let from_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: due_from,
-                queue_id: 0,
-                queue_name: [0; 8],
-            },
-        )));
-        let to_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: due_to,
-                queue_id: u64::MAX,
-                queue_name: [u8::MAX; 8],
-            },
-        )));
-
         let mut seen_ids = AHashSet::with_capacity(8);
-        req.server
+        let mut events = req
+            .server
             .store()
-            .iterate(
-                IterateParams::new(from_key, to_key)
-                    .set_ascending(params.sort_ascending)
-                    .no_values(),
-                |key, _| {
-                    let id = key.deserialize_be_u64(U64_LEN)?;
-                    if queue_name.is_none_or(|queue_name| {
-                        queue_name.as_slice() == key.get(U64_LEN * 2..).unwrap_or_default()
-                    }) && seen_ids.insert(id)
-                    {
-                        total += 1;
-                        if response.response.total.is_some() {
-                            if !response.is_full() {
-                                response.add_id(id.into());
-                            }
-                            Ok(true)
-                        } else {
-                            Ok(response.add_id(id.into()))
-                        }
-                    } else {
-                        Ok(true)
+            .queue_events(due_from, due_to, params.sort_ascending);
+        while let Some(event) = events.next().await.caused_by(trc::location!())? {
+            let id = event.queue_id;
+            if queue_name
+                .is_none_or(|queue_name| queue_name.as_slice() == event.queue_name.as_slice())
+                && seen_ids.insert(id)
+            {
+                total += 1;
+                if response.response.total.is_some() {
+                    if !response.is_full() {
+                        response.add_id(id.into());
                     }
-                },
-            )
-            .await
-            .caused_by(trc::location!())?;
+                } else if !response.add_id(id.into()) {
+                    break;
+                }
+            }
+        }
 
         if response.response.total.is_some() {
             response.response.total = Some(total);
@@ -569,7 +554,7 @@ pub(crate) async fn queued_message_query(
 // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
 // SPDX-License-Identifier: LicenseRef-SEL
 #[cfg(feature = "enterprise")]
-async fn tenant_domains(server: &Server, tenant_id: u32) -> trc::Result<AHashSet<String>> {
+pub async fn tenant_domains(server: &Server, tenant_id: u32) -> trc::Result<AHashSet<String>> {
     let domain_ids = server
         .registry()
         .query::<Vec<Id>>(RegistryQuery::new(ObjectType::Domain).with_tenant(tenant_id.into()))
@@ -588,7 +573,7 @@ async fn tenant_domains(server: &Server, tenant_id: u32) -> trc::Result<AHashSet
 // SPDX-SnippetEnd
 
 #[cfg(not(feature = "enterprise"))]
-async fn tenant_domains(_server: &Server, _tenant_id: u32) -> trc::Result<AHashSet<String>> {
+pub async fn tenant_domains(_server: &Server, _tenant_id: u32) -> trc::Result<AHashSet<String>> {
     Ok(AHashSet::new())
 }
 
@@ -618,6 +603,11 @@ fn map_message(message_in: &ArchivedMessage) -> QueuedMessage {
         next_notify: message_in
             .next_notify_event(None)
             .map(|ts| UTCDateTime::from_timestamp(ts.cast_signed())),
+        split_from: message_in.split_from().map(Id::from),
+        metadata: message_in
+            .queue_meta()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
     };
 
     // Parse flags
@@ -646,7 +636,16 @@ fn map_message(message_in: &ArchivedMessage) -> QueuedMessage {
                     expires_at: UTCDateTime::from_timestamp(
                         message_in.created.to_native() as i64 + ttl.to_native() as i64,
                     ),
+                    expires_attempts: None,
                 }),
+                ArchivedQueueExpiry::TtlOrAttempts { ttl, attempts } => {
+                    QueueExpiry::Ttl(QueueExpiryTtl {
+                        expires_at: UTCDateTime::from_timestamp(
+                            message_in.created.to_native() as i64 + ttl.to_native() as i64,
+                        ),
+                        expires_attempts: Some(attempts.to_native() as u64),
+                    })
+                }
                 ArchivedQueueExpiry::Attempts(attempts) => {
                     QueueExpiry::Attempts(QueueExpiryAttempts {
                         expires_attempts: attempts.to_native() as u64,
@@ -682,6 +681,9 @@ fn map_message(message_in: &ArchivedMessage) -> QueuedMessage {
         for (bit, flag) in [
             (RCPT_DSN_SENT, RecipientFlag::DsnSent),
             (RCPT_SPAM_PAYLOAD, RecipientFlag::SpamPayload),
+            (RCPT_EXPIRED_TTL, RecipientFlag::ExpiredTtl),
+            (RCPT_EXPIRED_ATTEMPTS, RecipientFlag::ExpiredAttempts),
+            (RCPT_SPLIT, RecipientFlag::Split),
         ] {
             if rcpt_flags & bit != 0 {
                 rcpt_out.flags.push(flag);
@@ -751,33 +753,14 @@ fn build_enhanced_code(esc: &[u8; 3]) -> String {
 
 async fn queued_ids(server: &Server, max_results: usize) -> trc::Result<AHashSet<u64>> {
     let mut events = AHashSet::with_capacity(8);
+    let mut stream = server.store().queue_events(0, u64::MAX, true);
 
-    let from_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-        store::write::QueueEvent {
-            due: 0,
-            queue_id: 0,
-            queue_name: [0; 8],
-        },
-    )));
-    let to_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-        store::write::QueueEvent {
-            due: u64::MAX,
-            queue_id: u64::MAX,
-            queue_name: [u8::MAX; 8],
-        },
-    )));
-
-    server
-        .store()
-        .iterate(
-            IterateParams::new(from_key, to_key).ascending().no_values(),
-            |key, _| {
-                events.insert(key.deserialize_be_u64(U64_LEN)?);
+    while let Some(event) = stream.next().await.caused_by(trc::location!())? {
+        events.insert(event.queue_id);
+        if events.len() >= max_results {
+            break;
+        }
+    }
 
-                Ok(events.len() < max_results)
-            },
-        )
-        .await
-        .caused_by(trc::location!())
-        .map(|_| events)
+    Ok(events)
 }
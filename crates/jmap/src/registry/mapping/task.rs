@@ -6,6 +6,7 @@
 
 use crate::{
     api::query::QueryResponseBuilder,
+    blob::download::BlobDownload,
     registry::{
         mapping::{RegistryGetResponse, RegistryQueryResponse, RegistrySetResponse},
         query::RegistryQueryFilters,
@@ -81,6 +82,23 @@ pub(crate) async fn task_set(
             continue 'outer;
         }
 
+        // Uploaded files must be accessible by the caller
+        if let Task::ImportMessages(task) = &task
+            && let Some(blob_id) = &task.blob_id
+            && !set
+                .server
+                .has_access_blob(blob_id, set.access_token)
+                .await
+                .caused_by(trc::location!())?
+        {
+            set.response.not_created.append(
+                id,
+                SetError::forbidden()
+                    .with_description(format!("You do not have access to blobId {blob_id}.")),
+            );
+            continue 'outer;
+        }
+
         let task_type = task.object_type();
         match task_type {
             TaskType::IndexDocument
@@ -92,7 +110,11 @@ pub(crate) async fn task_set(
             | TaskType::SpamFilterMaintenance
             | TaskType::AcmeRenewal
             | TaskType::DkimManagement
-            | TaskType::DnsManagement => {
+            | TaskType::DnsManagement
+            | TaskType::DaneRollover
+            | TaskType::ReEncryptAccount
+            | TaskType::ReindexAccount
+            | TaskType::ImportMessages => {
                 let mut index = IndexBuilder::default();
                 task.index(&mut index);
 
@@ -131,6 +153,11 @@ pub(crate) async fn task_set(
             | TaskType::CalendarAlarmNotification
             | TaskType::CalendarItipMessage
             | TaskType::MergeThreads
+            | TaskType::QuotaWarning
+            | TaskType::EmailSubmission
+            | TaskType::RestoreSnoozedEmail
+            | TaskType::FollowUpReminder
+            | TaskType::DeliveryCallback
             | TaskType::DmarcReport
             | TaskType::TlsReport
             | TaskType::DestroyAccount
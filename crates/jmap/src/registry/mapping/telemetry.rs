@@ -444,7 +444,8 @@ pub(crate) async fn metric_query(
         .iterate(
             IterateParams::new(from_key, to_key)
                 .set_ascending(params.sort_ascending)
-                .set_values(metric_type.is_some()),
+                .set_values(metric_type.is_some())
+                .read_only(),
             |key, value| {
                 let id = key.deserialize_be_u64(0)?;
 
@@ -495,7 +496,10 @@ async fn metric_ids(server: &Server, max_results: usize) -> trc::Result<Vec<Id>>
     server
         .metrics_store()
         .iterate(
-            IterateParams::new(from_key, to_key).ascending().no_values(),
+            IterateParams::new(from_key, to_key)
+                .ascending()
+                .no_values()
+                .read_only(),
             |key, _| {
                 events.push(key.deserialize_be_u64(0)?.into());
 
@@ -9,9 +9,9 @@ use crate::{
     registry::{
         EnterpriseRegistry,
         mapping::{
-            RegistryQueryResponse, account::credential_query, cluster::cluster_node_query,
-            log::log_query, queued_message::queued_message_query, report::report_query,
-            spam_sample::spam_sample_query, task::task_query,
+            RegistryQueryResponse, account::credential_query, audit::audit_event_query,
+            cluster::cluster_node_query, log::log_query, queued_message::queued_message_query,
+            report::report_query, spam_sample::spam_sample_query, task::task_query,
         },
     },
 };
@@ -166,6 +166,15 @@ impl RegistryQuery for Server {
             .await
             .and_then(|response| response.build()),
 
+            ObjectType::AuditEvent => audit_event_query(RegistryQueryResponse {
+                server: self,
+                access_token,
+                object_type,
+                request,
+            })
+            .await
+            .and_then(|response| response.build()),
+
             ObjectType::Action => Err(trc::JmapEvent::InvalidArguments
                 .into_err()
                 .details("Actions cannot be queried")),
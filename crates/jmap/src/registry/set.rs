@@ -11,12 +11,13 @@ use crate::registry::{
         account::account_set,
         action::action_set,
         bootstrap::bootstrap_set,
+        delivery_callback::validate_delivery_callback,
         dkim::validate_dkim_signature,
         domain::{validate_dns_server, validate_domain},
         map_bootstrap_error,
         principal::{
-            AccountUpdate, schedule_account_destruction, validate_account, validate_role,
-            validate_tenant_quota,
+            AccountUpdate, schedule_account_destruction, schedule_account_re_encryption,
+            validate_account, validate_role, validate_tenant_quota,
         },
         public_key::validate_public_key,
         queued_message::queued_message_set,
@@ -32,6 +33,7 @@ use common::{
     expr::if_block::BootstrapExprExt, ipc::CacheInvalidation,
 };
 use directory::core::secret::{hash_secret, is_password_hash};
+use email::message::category::EmailCategoryFnc;
 use http_proto::HttpSessionData;
 use jmap_proto::{
     error::set::{SetError, SetErrorType},
@@ -50,8 +52,8 @@ use registry::{
             Property,
         },
         structs::{
-            Certificate, DkimSignature, DnsServer, Domain, PublicKey, Role, SieveSystemScript,
-            SieveUserScript, Task,
+            Account, Certificate, DkimSignature, DnsServer, Domain, PublicKey, Role,
+            SieveSystemScript, SieveUserScript, Task,
         },
     },
     types::id::ObjectId,
@@ -204,6 +206,7 @@ impl RegistrySet for Server {
             | ObjectType::MtaStageMail
             | ObjectType::MtaStageRcpt
             | ObjectType::MtaSts
+            | ObjectType::MtaUnsubscribe
             | ObjectType::OidcProvider
             | ObjectType::ReportSettings
             | ObjectType::Search
@@ -213,6 +216,7 @@ impl RegistrySet for Server {
             | ObjectType::Sharing
             | ObjectType::SieveSystemInterpreter
             | ObjectType::SieveUserInterpreter
+            | ObjectType::SpamClamAv
             | ObjectType::SpamClassifier
             | ObjectType::SpamDnsblSettings
             | ObjectType::SpamLlm
@@ -245,6 +249,7 @@ impl RegistrySet for Server {
             | ObjectType::MtaTlsStrategy
             | ObjectType::MtaMilter
             | ObjectType::MtaHook
+            | ObjectType::MtaResponse
             | ObjectType::NetworkListener
             | ObjectType::ClusterRole
             | ObjectType::SieveSystemScript
@@ -257,14 +262,23 @@ impl RegistrySet for Server {
             | ObjectType::Tracer
             | ObjectType::WebHook
             | ObjectType::PublicKey
+            | ObjectType::DeliveryCallback
             | ObjectType::DkimSignature
             | ObjectType::MaskedEmail
             | ObjectType::Account
+            | ObjectType::MailCategory
             | ObjectType::MailingList
+            | ObjectType::MessageTemplate
             | ObjectType::OAuthClient
             | ObjectType::Role
             | ObjectType::Tenant
+            | ObjectType::UnsubscribeEvent
             | ObjectType::Domain => {
+                if object_type == ObjectType::UnsubscribeEvent {
+                    set.fail_all_create("Unsubscribe events are recorded by the server");
+                    set.fail_all_update("Unsubscribe events cannot be modified");
+                }
+
                 // Bundle modifications together
                 let mut modifications = Vec::with_capacity(set.create.len() + set.update.len());
                 for (id, value) in set.create.drain() {
@@ -430,7 +444,8 @@ impl RegistrySet for Server {
                     let mut tasks = Vec::new();
                     let result = match &mut new_object.inner {
                         ObjectInner::Account(account) => {
-                            validate_account(&set, account, modification.as_account()).await?
+                            validate_account(&set, account, modification.as_account(), &mut tasks)
+                                .await?
                         }
                         ObjectInner::Role(role) => {
                             validate_role(&set, role, modification.as_role()).await?
@@ -452,6 +467,9 @@ impl RegistrySet for Server {
                         ObjectInner::PublicKey(key) => {
                             validate_public_key(&set, key, modification.as_public_key()).await?
                         }
+                        ObjectInner::DeliveryCallback(callback) => {
+                            validate_delivery_callback(&set, callback, is_create).await?
+                        }
                         ObjectInner::DkimSignature(key) => {
                             validate_dkim_signature(&set, key, modification.as_dkim_signature())
                                 .await?
@@ -588,6 +606,20 @@ impl RegistrySet for Server {
                     let object_id = match (modification, result) {
                         (Modification::Update { id, object }, RegistryWriteResult::Success(_)) => {
                             cache_invalidator.process_update(id, &object, &new_object);
+
+                            // Forget the sender categories learned while categorization was enabled
+                            if let (
+                                ObjectInner::Account(Account::User(old_account)),
+                                ObjectInner::Account(Account::User(account)),
+                            ) = (&object.inner, &new_object.inner)
+                                && old_account.categorize_mail
+                                && !account.categorize_mail
+                            {
+                                self.email_category_reset(id.document_id())
+                                    .await
+                                    .caused_by(trc::location!())?;
+                            }
+
                             set.response.updated.append(
                                 id,
                                 if !response.object.is_empty() {
@@ -631,6 +663,11 @@ impl RegistrySet for Server {
                                 Task::AcmeRenewal(task) => task.domain_id = object_id,
                                 Task::DkimManagement(task) => task.domain_id = object_id,
                                 Task::DnsManagement(task) => task.domain_id = object_id,
+                                Task::ReEncryptAccount(_) => {
+                                    schedule_account_re_encryption(set.server, object_id).await?;
+                                    continue;
+                                }
+                                Task::TenantMaintenance(_) => {}
                                 _ => unreachable!(),
                             }
                             batch.schedule_task(task);
@@ -662,7 +699,11 @@ impl RegistrySet for Server {
                                 object_id,
                                 object: Some(&object),
                                 allowed_orphan_types: if object_type == ObjectType::Account {
-                                    &[ObjectType::PublicKey, ObjectType::MaskedEmail]
+                                    &[
+                                        ObjectType::PublicKey,
+                                        ObjectType::MaskedEmail,
+                                        ObjectType::DeliveryCallback,
+                                    ]
                                 } else {
                                     &[]
                                 },
@@ -738,6 +779,13 @@ impl RegistrySet for Server {
                 .await
                 .map(|set| set.into_response()),
 
+            ObjectType::AuditEvent => {
+                set.fail_all_create("Audit events are recorded by the server");
+                set.fail_all_update("Audit events cannot be modified");
+                set.fail_all_destroy("Audit events cannot be deleted");
+                Ok(set.into_response())
+            }
+
             ObjectType::Log | ObjectType::Metric | ObjectType::Trace | ObjectType::ClusterNode => {
                 set.fail_all_create("Telemetry objects cannot be created");
                 set.fail_all_update("Telemetry objects cannot be modified");
@@ -814,8 +862,8 @@ impl Modification {
     fn as_account(&self) -> AccountUpdate<'_> {
         match self {
             Modification::Create { client_id, .. } => AccountUpdate::Create(client_id),
-            Modification::Update { object, .. } => match &object.inner {
-                ObjectInner::Account(account) => AccountUpdate::Update(account),
+            Modification::Update { id, object } => match &object.inner {
+                ObjectInner::Account(account) => AccountUpdate::Update(*id, account),
                 _ => unreachable!(),
             },
         }
@@ -90,7 +90,8 @@ impl ShareNotificationQuery for Server {
                         change_id: to_change_id,
                     },
                 )
-                .descending(),
+                .descending()
+                .read_only(),
                 |key, value| {
                     let change_id = key.deserialize_be_u64(key.len() - U64_LEN)?;
 
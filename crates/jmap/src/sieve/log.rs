@@ -0,0 +1,73 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::Server;
+use email::sieve::log::SieveLog;
+use jmap_proto::method::sieve_log::{
+    SieveLogAction, SieveLogEntry, SieveLogQueryRequest, SieveLogQueryResponse, SieveLogTest,
+};
+use std::future::Future;
+
+pub trait SieveLogQuery: Sync + Send {
+    fn sieve_log_query(
+        &self,
+        request: SieveLogQueryRequest,
+    ) -> impl Future<Output = trc::Result<SieveLogQueryResponse>> + Send;
+}
+
+impl SieveLogQuery for Server {
+    async fn sieve_log_query(
+        &self,
+        request: SieveLogQueryRequest,
+    ) -> trc::Result<SieveLogQueryResponse> {
+        let entries = self.sieve_log_get(request.account_id.document_id()).await?;
+        let total = entries.len();
+        let limit = request
+            .limit
+            .filter(|limit| *limit > 0)
+            .unwrap_or(usize::MAX)
+            .min(self.core.jmap.query_max_results);
+
+        // Most recent entries first
+        let list = entries
+            .into_iter()
+            .rev()
+            .skip(request.position)
+            .take(limit)
+            .map(|entry| SieveLogEntry {
+                received_at: entry.received_at.into(),
+                script_name: entry.script_name,
+                message_id: entry.message_id,
+                envelope_from: entry.envelope_from,
+                tests: entry
+                    .tests
+                    .into_iter()
+                    .map(|test| SieveLogTest {
+                        name: test.name,
+                        argument: test.argument,
+                    })
+                    .collect(),
+                actions: entry
+                    .actions
+                    .into_iter()
+                    .map(|action| SieveLogAction {
+                        action: action.action.as_str(),
+                        target: action.target,
+                        error: action.error,
+                    })
+                    .collect(),
+                errors: entry.errors,
+            })
+            .collect();
+
+        Ok(SieveLogQueryResponse {
+            account_id: request.account_id,
+            position: request.position,
+            total,
+            list,
+        })
+    }
+}
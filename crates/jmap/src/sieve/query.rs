@@ -67,7 +67,8 @@ impl SieveScriptQuery for Server {
                         field: u8::from(SieveField::Name) + 1,
                     },
                 )
-                .no_values(),
+                .no_values()
+                .read_only(),
                 |key, _| {
                     let document_id = key.deserialize_be_u32(key.len() - U32_LEN)?;
 
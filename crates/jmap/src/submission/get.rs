@@ -16,7 +16,9 @@ use jmap_proto::{
     types::date::UTCDate,
 };
 use jmap_tools::{Key, Map, Value};
-use smtp::queue::{ArchivedError, ArchivedErrorDetails, ArchivedStatus, Message, spool::SmtpSpool};
+use smtp::queue::{
+    ArchivedError, ArchivedErrorDetails, ArchivedStatus, Message, RCPT_SPLIT, spool::SmtpSpool,
+};
 use smtp_proto::ArchivedResponse;
 use std::future::Future;
 use store::{
@@ -138,19 +140,38 @@ impl EmailSubmissionGet for Server {
                 .map(|(k, v)| (k.to_string(), DeliveryStatus::from(v)))
                 .collect::<VecMap<_, _>>();
             let mut is_pending = false;
-            if let Some(queue_id) = submission.queue_id.as_ref().map(u64::from)
-                && let Some(queued_message_) = self
+            let mut queue_ids = submission
+                .queue_id
+                .as_ref()
+                .map(u64::from)
+                .into_iter()
+                .collect::<Vec<_>>();
+            let mut seen_ids = Vec::with_capacity(queue_ids.len());
+            while let Some(queue_id) = queue_ids.pop() {
+                if seen_ids.contains(&queue_id) {
+                    continue;
+                }
+                seen_ids.push(queue_id);
+                let Some(queued_message_) = self
                     .read_message_archive(queue_id)
                     .await
                     .caused_by(trc::location!())?
-            {
+                else {
+                    continue;
+                };
                 let queued_message = queued_message_
                     .unarchive::<Message>()
                     .caused_by(trc::location!())?;
+
+                // Recipients moved to a separate queued message are in transit until
+                // that message, which is read after this one, reports their status
+                queue_ids.extend(queued_message.split_into());
                 for rcpt in queued_message.recipients.iter() {
+                    let is_split = rcpt.flags.to_native() & RCPT_SPLIT != 0;
                     *delivery_status.get_mut_or_insert(rcpt.address().to_string()) =
                         DeliveryStatus {
                             smtp_reply: match &rcpt.status {
+                                _ if is_split => "250 2.1.5 Queued".to_string(),
                                 ArchivedStatus::Completed(reply) => {
                                     format_archived_response(&reply.response)
                                 }
@@ -161,6 +182,7 @@ impl EmailSubmissionGet for Server {
                                 ArchivedStatus::Scheduled => "250 2.1.5 Queued".to_string(),
                             },
                             delivered: match &rcpt.status {
+                                _ if is_split => Delivered::Queued,
                                 ArchivedStatus::Scheduled | ArchivedStatus::TemporaryFailure(_) => {
                                     Delivered::Queued
                                 }
@@ -74,7 +74,8 @@ impl EmailSubmissionQuery for Server {
                         }),
                     },
                 )
-                .ascending(),
+                .ascending()
+                .read_only(),
                 |key, value| {
                     let document_id = key.deserialize_be_u32(key.len() - U32_LEN)?;
 
@@ -13,7 +13,7 @@ use common::{
 use email::{
     identity::Identity,
     message::metadata::{ArchivedMetadataHeaderName, ArchivedMetadataHeaderValue, MessageMetadata},
-    submission::{Address, Delivered, DeliveryStatus, EmailSubmission, UndoStatus},
+    submission::{Address, EmailSubmission, UndoStatus},
 };
 use jmap_proto::{
     error::set::{SetError, SetErrorType},
@@ -28,13 +28,20 @@ use jmap_proto::{
     types::{date::UTCDate, state::State},
 };
 use jmap_tools::{Key, Map, Value};
+use registry::schema::structs::{DeliveryCallback, Task, TaskEmailSubmission, TaskStatus};
 use smtp::{
     core::{Session, SessionData},
+    inbound::submit::{
+        LocalSubmissionError, from_into_static, rcpt_into_static, submission_message,
+    },
     queue::spool::SmtpSpool,
 };
 use smtp_proto::{MailFrom, RcptTo, request::parser::Rfc5321Parser};
-use std::{borrow::Cow, future::Future};
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{borrow::Cow, future::Future, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use store::{
     ValueKey,
     write::{AlignedBytes, Archive, BatchBuilder, now},
@@ -75,6 +82,8 @@ impl EmailSubmissionSet for Server {
 
         // Process creates
         let mut success_email_ids = HashMap::new();
+        let mut held_email_ids = HashSet::new();
+        let mut has_scheduled = false;
         let mut batch = BatchBuilder::new();
         for (id, object) in request.unwrap_create() {
             match self
@@ -83,12 +92,11 @@ impl EmailSubmissionSet for Server {
             {
                 Ok(submission) => {
                     // Add id mapping
-                    success_email_ids.insert(
-                        id.clone(),
-                        Id::from_parts(submission.thread_id, submission.email_id),
-                    );
+                    let email_id = Id::from_parts(submission.thread_id, submission.email_id);
+                    success_email_ids.insert(id.clone(), email_id);
 
                     let send_at = submission.send_at;
+                    let is_scheduled = submission.is_scheduled();
                     let undo_status = match submission.undo_status {
                         UndoStatus::Pending => email_submission::UndoStatus::Pending,
                         UndoStatus::Final => email_submission::UndoStatus::Final,
@@ -106,8 +114,19 @@ impl EmailSubmissionSet for Server {
                         .with_collection(Collection::EmailSubmission)
                         .with_document(document_id)
                         .custom(ObjectIndexBuilder::<(), _>::new().with_changes(submission))
-                        .caused_by(trc::location!())?
-                        .commit_point();
+                        .caused_by(trc::location!())?;
+
+                    // Hold the submission until its sendAt time
+                    if is_scheduled {
+                        batch.schedule_task(Task::EmailSubmission(TaskEmailSubmission {
+                            account_id: account_id.into(),
+                            document_id: document_id.into(),
+                            status: TaskStatus::at(send_at as i64),
+                        }));
+                        held_email_ids.insert(email_id);
+                        has_scheduled = true;
+                    }
+                    batch.commit_point();
 
                     response.created.insert(
                         id,
@@ -170,8 +189,9 @@ impl EmailSubmissionSet for Server {
                 continue 'update;
             };
 
-            let mut queue_id = u64::MAX;
             let mut undo_status = None;
+            let mut send_at = None;
+            let is_scheduled = submission.inner.is_scheduled();
 
             for (property, mut value) in object.into_expanded_object() {
                 if let Err(err) = response.resolve_self_references(&mut value, 0, false) {
@@ -179,42 +199,82 @@ impl EmailSubmissionSet for Server {
                     continue 'update;
                 };
 
-                if matches!(&property, Key::Property(EmailSubmissionProperty::Id)) {
-                    if !crate::matches_id(&value, id) {
+                match (&property, value) {
+                    (Key::Property(EmailSubmissionProperty::Id), value) => {
+                        if !crate::matches_id(&value, id) {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailSubmissionProperty::Id)
+                                    .with_description("The id property is immutable."),
+                            );
+                            continue 'update;
+                        }
+                    }
+                    (
+                        Key::Property(EmailSubmissionProperty::UndoStatus),
+                        Value::Element(EmailSubmissionValue::UndoStatus(undo_status_)),
+                    ) if is_scheduled || submission.inner.queue_id.is_some() => {
+                        undo_status = undo_status_.into();
+                    }
+                    (
+                        Key::Property(EmailSubmissionProperty::SendAt),
+                        Value::Element(EmailSubmissionValue::Date(send_at_)),
+                    ) if is_scheduled => {
+                        // Submissions moved to the past are sent right away
+                        let send_at_ = (send_at_.timestamp().max(0) as u64).max(now());
+                        if send_at_.saturating_sub(now())
+                            > self.core.email.email_submission_max_delay.as_secs()
+                        {
+                            response.not_updated.append(
+                                id,
+                                SetError::invalid_properties()
+                                    .with_property(EmailSubmissionProperty::SendAt)
+                                    .with_description(
+                                        "sendAt exceeds the maximum delayed send period.",
+                                    ),
+                            );
+                            continue 'update;
+                        }
+                        send_at = send_at_.into();
+                    }
+                    (property, _) => {
                         response.not_updated.append(
                             id,
                             SetError::invalid_properties()
-                                .with_property(EmailSubmissionProperty::Id)
-                                .with_description("The id property is immutable."),
+                                .with_property(property.clone().into_owned())
+                                .with_description("Field could not be set."),
                         );
                         continue 'update;
                     }
-                    continue;
-                }
-
-                if let (
-                    Key::Property(EmailSubmissionProperty::UndoStatus),
-                    Value::Element(EmailSubmissionValue::UndoStatus(undo_status_)),
-                    Some(queue_id_),
-                ) = (&property, value, submission.inner.queue_id)
-                {
-                    undo_status = undo_status_.into();
-                    queue_id = queue_id_;
-                } else {
-                    response.not_updated.append(
-                        id,
-                        SetError::invalid_properties()
-                            .with_property(property.into_owned())
-                            .with_description("Field could not be set."),
-                    );
-                    continue 'update;
                 }
             }
 
-            match undo_status {
-                Some(email_submission::UndoStatus::Canceled) => {
-                    if let Some(queue_message) =
-                        self.read_message(queue_id, QueueName::default()).await
+            match (undo_status, send_at) {
+                (Some(email_submission::UndoStatus::Canceled), _) if is_scheduled => {
+                    // Scheduled submissions have not been queued yet
+                    let mut new_submission = submission.inner.clone();
+                    new_submission.undo_status = UndoStatus::Canceled;
+                    batch
+                        .with_account_id(account_id)
+                        .with_collection(Collection::EmailSubmission)
+                        .with_document(document_id)
+                        .custom(
+                            ObjectIndexBuilder::new()
+                                .with_current(submission)
+                                .with_changes(new_submission),
+                        )
+                        .caused_by(trc::location!())?
+                        .commit_point();
+                    response.updated.append(id, None);
+                }
+                (Some(email_submission::UndoStatus::Canceled), _) => {
+                    if let Some(queue_message) = self
+                        .read_message(
+                            submission.inner.queue_id.unwrap_or(u64::MAX),
+                            QueueName::default(),
+                        )
+                        .await
                     {
                         // Delete message from queue
                         queue_message.remove(self, None).await;
@@ -243,7 +303,33 @@ impl EmailSubmissionSet for Server {
                         );
                     }
                 }
-                Some(_) => {
+                (None | Some(email_submission::UndoStatus::Pending), Some(send_at))
+                    if is_scheduled =>
+                {
+                    // Reschedule, tasks due at the previous sendAt are ignored
+                    let mut new_submission = submission.inner.clone();
+                    new_submission.send_at = send_at;
+                    batch
+                        .with_account_id(account_id)
+                        .with_collection(Collection::EmailSubmission)
+                        .with_document(document_id)
+                        .custom(
+                            ObjectIndexBuilder::new()
+                                .with_current(submission)
+                                .with_changes(new_submission),
+                        )
+                        .caused_by(trc::location!())?;
+                    batch
+                        .schedule_task(Task::EmailSubmission(TaskEmailSubmission {
+                            account_id: account_id.into(),
+                            document_id: document_id.into(),
+                            status: TaskStatus::at(send_at as i64),
+                        }))
+                        .commit_point();
+                    has_scheduled = true;
+                    response.updated.append(id, None);
+                }
+                (Some(_), _) => {
                     response.not_updated.append(
                         id,
                         SetError::invalid_properties()
@@ -251,7 +337,7 @@ impl EmailSubmissionSet for Server {
                             .with_description("Email submissions can only be cancelled."),
                     );
                 }
-                None => {
+                (None, _) => {
                     response.not_updated.append(
                         id,
                         SetError::invalid_properties()
@@ -301,6 +387,10 @@ impl EmailSubmissionSet for Server {
                 .and_then(|ids| ids.last_change_id(account_id))
                 .caused_by(trc::location!())?;
             response.new_state = State::Exact(change_id).into();
+
+            if has_scheduled {
+                self.notify_task_queue();
+            }
         }
 
         // On success
@@ -351,6 +441,8 @@ impl EmailSubmissionSet for Server {
                                     }
                                     MaybeIdReference::Invalid(_) => None,
                                 })
+                                // Held messages are read from the mailbox when sent
+                                .filter(|id| !held_email_ids.contains(id))
                                 .map(MaybeInvalid::Value)
                                 .collect(),
                         )
@@ -379,6 +471,8 @@ impl EmailSubmissionSet for Server {
         };
         let mut mail_from: Option<MailFrom<Cow<'_, str>>> = None;
         let mut rcpt_to: Vec<RcptTo<Cow<'_, str>>> = Vec::new();
+        let mut delivery_callback: Option<Id> = None;
+        let mut send_at: Option<u64> = None;
 
         for (property, mut value) in object.into_expanded_object() {
             if let Err(err) = response.resolve_self_references(&mut value, 0, false) {
@@ -405,6 +499,32 @@ impl EmailSubmissionSet for Server {
                             (Key::Property(EmailSubmissionProperty::MailFrom), value) => {
                                 match parse_envelope_address(value) {
                                     Ok((addr, params, smtp_params)) => {
+                                        if let Some((_, value)) =
+                                            params.as_ref().and_then(|params| {
+                                                params.iter().find(|(k, _)| {
+                                                    k.eq_ignore_ascii_case("X-CALLBACK")
+                                                })
+                                            })
+                                        {
+                                            match value
+                                                .as_deref()
+                                                .and_then(|v| Id::from_str(v).ok())
+                                            {
+                                                Some(id) => {
+                                                    delivery_callback = Some(id);
+                                                }
+                                                None => {
+                                                    return Ok(Err(SetError::invalid_properties()
+                                                        .with_property(
+                                                            EmailSubmissionProperty::Envelope,
+                                                        )
+                                                        .with_description(
+                                                            "Invalid X-CALLBACK parameter.",
+                                                        )));
+                                                }
+                                            }
+                                        }
+
                                         match Rfc5321Parser::new(
                                             &mut smtp_params
                                                 .as_ref()
@@ -490,6 +610,15 @@ impl EmailSubmissionSet for Server {
                 (Key::Property(EmailSubmissionProperty::Envelope), Value::Null) => {
                     continue;
                 }
+                (
+                    Key::Property(EmailSubmissionProperty::SendAt),
+                    Value::Element(EmailSubmissionValue::Date(value)),
+                ) => {
+                    send_at = Some(value.timestamp().max(0) as u64);
+                }
+                (Key::Property(EmailSubmissionProperty::SendAt), Value::Null) => {
+                    continue;
+                }
                 (Key::Property(EmailSubmissionProperty::UndoStatus), Value::Element(_)) => {
                     continue;
                 }
@@ -554,6 +683,20 @@ impl EmailSubmissionSet for Server {
             }
         };
 
+        // Make sure the delivery callback belongs to the account
+        if let Some(id) = delivery_callback
+            && !self
+                .registry()
+                .object::<DeliveryCallback>(id)
+                .await
+                .caused_by(trc::location!())?
+                .is_some_and(|callback| callback.account_id.document_id() == account_id)
+        {
+            return Ok(Err(SetError::invalid_properties()
+                .with_property(EmailSubmissionProperty::Envelope)
+                .with_description("Unknown delivery callback.")));
+        }
+
         // Obtain message metadata
         let metadata_ = if let Some(metadata) = self
             .store()
@@ -576,7 +719,6 @@ impl EmailSubmissionSet for Server {
             .caused_by(trc::location!())?;
 
         // Add recipients to envelope if missing
-        let mut bcc_header = None;
         if rcpt_to.is_empty() {
             for header in metadata.contents[0].parts[0].headers.iter() {
                 if matches!(
@@ -585,9 +727,6 @@ impl EmailSubmissionSet for Server {
                         | ArchivedMetadataHeaderName::Cc
                         | ArchivedMetadataHeaderName::Bcc
                 ) {
-                    if matches!(header.name, ArchivedMetadataHeaderName::Bcc) {
-                        bcc_header = Some(header);
-                    }
                     match &header.value {
                         ArchivedMetadataHeaderValue::AddressList(addr) => {
                             for address in addr.iter() {
@@ -640,11 +779,30 @@ impl EmailSubmissionSet for Server {
                 return Ok(Err(SetError::new(SetErrorType::NoRecipients)
                     .with_description("No recipients found in email.")));
             }
-        } else {
-            bcc_header = metadata.contents[0].parts[0]
-                .headers
-                .iter()
-                .find(|header| matches!(header.name, ArchivedMetadataHeaderName::Bcc));
+        }
+
+        // Hold submissions with a future sendAt, the message is read from
+        // the mailbox and sent through the local SMTP session when due
+        if let Some(send_at) = send_at.filter(|send_at| *send_at > now()) {
+            if mail_from.hold_until > 0 || mail_from.hold_for > 0 {
+                return Ok(Err(SetError::invalid_properties()
+                    .with_property(EmailSubmissionProperty::SendAt)
+                    .with_description(
+                        "sendAt cannot be combined with the HOLDUNTIL or HOLDFOR parameters.",
+                    )));
+            } else if send_at.saturating_sub(now())
+                > self.core.email.email_submission_max_delay.as_secs()
+            {
+                return Ok(Err(SetError::invalid_properties()
+                    .with_property(EmailSubmissionProperty::SendAt)
+                    .with_description(
+                        "sendAt exceeds the maximum delayed send period.",
+                    )));
+            }
+
+            submission.send_at = send_at;
+            submission.undo_status = UndoStatus::Pending;
+            return Ok(Ok(submission));
         }
 
         // Update sendAt
@@ -657,37 +815,24 @@ impl EmailSubmissionSet for Server {
         };
 
         // Obtain raw message
-        let mut message = if let Some(message) = self
-            .blob_store()
-            .get_blob(metadata.blob_hash.0.as_slice(), 0..usize::MAX)
-            .await?
-        {
-            if message.len() > self.core.email.mail_max_size {
-                return Ok(Err(SetError::new(SetErrorType::InvalidEmail)
-                    .with_description(format!(
-                        "Message exceeds maximum size of {} bytes.",
-                        self.core.email.mail_max_size
-                    ))));
-            }
-
-            message
-        } else {
+        let Some(message) = submission_message(self, metadata)
+            .await
+            .caused_by(trc::location!())?
+        else {
             return Ok(Err(SetError::invalid_properties()
                 .with_property(EmailSubmissionProperty::EmailId)
                 .with_description("Blob for email not found.")));
         };
-
-        // Remove BCC header if present
-        if let Some(bcc_header) = bcc_header {
-            let mut new_message = Vec::with_capacity(message.len());
-            let range = bcc_header.name_value_range();
-            new_message.extend_from_slice(&message[..range.start]);
-            new_message.extend_from_slice(&message[range.end..]);
-            message = new_message;
+        if message.len() > self.core.email.mail_max_size {
+            return Ok(Err(SetError::new(SetErrorType::InvalidEmail)
+                .with_description(format!(
+                    "Message exceeds maximum size of {} bytes.",
+                    self.core.email.mail_max_size
+                ))));
         }
 
-        // Begin local SMTP session
-        let mut session = Session::<NullIo>::local(
+        // Submit the message through a local SMTP session
+        match Session::<NullIo>::local(
             self.clone(),
             instance.clone(),
             SessionData::local(
@@ -699,90 +844,35 @@ impl EmailSubmissionSet for Server {
                 vec![],
                 0,
             ),
-        );
-
-        // Spawn SMTP session to avoid overflowing the stack
-        let handle = tokio::spawn(async move {
-            // MAIL FROM
-            let _ = session.handle_mail_from(mail_from).await;
-            if let Some(error) = session.has_failed() {
-                return Err(SetError::new(SetErrorType::ForbiddenMailFrom)
-                    .with_description(format!("Server rejected MAIL-FROM: {}", error.trim())));
-            }
-
-            // RCPT TO
-            let mut responses = Vec::new();
-            let mut has_success = false;
-            session.params.rcpt_errors_wait = Duration::from_secs(0);
-            for rcpt in rcpt_to {
-                let addr = rcpt.address.clone();
-                let _ = session.handle_rcpt_to(rcpt).await;
-                let response = session.has_failed();
-                if response.is_none() {
-                    has_success = true;
-                }
-                responses.push((addr, response));
-            }
-
-            // DATA
-            if has_success {
-                session.data.message = message;
-                let response = session.queue_message().await;
-                if let smtp::core::State::Accepted(queue_id) = session.state {
-                    Ok((true, responses, Some(queue_id)))
-                } else {
-                    Err(
-                        SetError::new(SetErrorType::ForbiddenToSend).with_description(format!(
-                            "Server rejected DATA: {}",
-                            std::str::from_utf8(&response).unwrap().trim()
-                        )),
-                    )
-                }
-            } else {
-                Ok((false, responses, None))
-            }
-        });
-
-        match handle.await {
-            Ok(Ok((has_success, responses, queue_id))) => {
-                // Set queue ID
-                if let Some(queue_id) = queue_id {
-                    submission.queue_id = Some(queue_id);
-                }
-
-                // Set responses
-                submission.undo_status = if has_success {
+        )
+        .submit_message(
+            mail_from,
+            rcpt_to,
+            delivery_callback.map(|id| id.id()),
+            message,
+        )
+        .await?
+        {
+            Ok(result) => {
+                // Set queue ID and responses
+                submission.undo_status = if result.queue_id.is_some() {
                     UndoStatus::Final
                 } else {
                     UndoStatus::Pending
                 };
-                submission.delivery_status = responses
-                    .into_iter()
-                    .map(|(addr, response)| {
-                        (
-                            addr.to_string(),
-                            DeliveryStatus {
-                                delivered: if response.is_none() {
-                                    Delivered::Unknown
-                                } else {
-                                    Delivered::No
-                                },
-                                smtp_reply: response
-                                    .map(|s| s.to_string())
-                                    .unwrap_or_else(|| "250 2.1.5 Queued".to_string()),
-                                displayed: false,
-                            },
-                        )
-                    })
-                    .collect();
+                submission.queue_id = result.queue_id;
+                submission.set_delivery_status(result.rcpt_responses);
 
                 Ok(Ok(submission))
             }
-            Ok(Err(err)) => Ok(Err(err)),
-            Err(err) => Err(trc::EventType::Server(trc::ServerEvent::ThreadError)
-                .reason(err)
-                .caused_by(trc::location!())
-                .details("Join Error")),
+            Err(LocalSubmissionError::MailFrom(reason)) => Ok(Err(SetError::new(
+                SetErrorType::ForbiddenMailFrom,
+            )
+            .with_description(reason))),
+            Err(LocalSubmissionError::Data(reason)) => Ok(Err(SetError::new(
+                SetErrorType::ForbiddenToSend,
+            )
+            .with_description(reason))),
         }
     }
 }
@@ -811,7 +901,12 @@ fn parse_envelope_address(
 
                     for (k, v) in params.into_vec() {
                         let k = k.into_string();
-                        if !k.is_empty() {
+                        if k.eq_ignore_ascii_case("X-CALLBACK") {
+                            // Handled internally, not passed to the SMTP parser
+                            if let Value::Str(v) = v {
+                                params_list.append(k, Some(v.into_owned()));
+                            }
+                        } else if !k.is_empty() {
                             if !params_text.is_empty() {
                                 params_text.push(' ');
                             }
@@ -847,35 +942,3 @@ fn parse_envelope_address(
             .with_description("Invalid envelope object."))
     }
 }
-
-fn from_into_static(from: MailFrom<Cow<'_, str>>) -> MailFrom<Cow<'static, str>> {
-    MailFrom {
-        address: from.address.into_owned().into(),
-        flags: from.flags,
-        size: from.size,
-        trans_id: from.trans_id.map(Cow::into_owned).map(Cow::Owned),
-        by: from.by,
-        env_id: from.env_id.map(Cow::into_owned).map(Cow::Owned),
-        solicit: from.solicit.map(Cow::into_owned).map(Cow::Owned),
-        mtrk: from
-            .mtrk
-            .map(smtp_proto::Mtrk::into_owned)
-            .map(|v| smtp_proto::Mtrk {
-                certifier: Cow::Owned(v.certifier),
-                timeout: v.timeout,
-            }),
-        auth: from.auth.map(Cow::into_owned).map(Cow::Owned),
-        hold_for: from.hold_for,
-        hold_until: from.hold_until,
-        mt_priority: from.mt_priority,
-    }
-}
-
-fn rcpt_into_static(rcpt: RcptTo<Cow<'_, str>>) -> RcptTo<Cow<'static, str>> {
-    RcptTo {
-        address: rcpt.address.into_owned().into(),
-        orcpt: rcpt.orcpt.map(Cow::into_owned).map(Cow::Owned),
-        rrvs: rcpt.rrvs,
-        flags: rcpt.flags,
-    }
-}
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::api::{IntoPushObject, ToRequestError, request::RequestHandler};
+use crate::api::{IntoPushObject, ToRequestError, auth::JmapStepUp, request::RequestHandler};
 use common::{Server, auth::AccessToken, ipc::PushNotification};
 use futures_util::{SinkExt, StreamExt};
 use http_proto::HttpSessionData;
@@ -16,8 +16,8 @@ use jmap_proto::{
         WebSocketMessage, WebSocketPushObject, WebSocketRequestError, WebSocketResponse,
     },
 };
-use std::future::Future;
-use std::time::Instant;
+use std::{future::Future, sync::Arc, time::Instant};
+use tokio::task::JoinSet;
 use tokio_tungstenite::WebSocketStream;
 use trc::JmapEvent;
 use tungstenite::Message;
@@ -31,6 +31,18 @@ pub trait WebSocketHandler: Sync + Send {
         access_token: AccessToken,
         session: HttpSessionData,
     ) -> impl Future<Output = ()> + Send;
+
+    fn handle_websocket_message(
+        &self,
+        message: &[u8],
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+    ) -> impl Future<Output = WebSocketOutcome> + Send;
+}
+
+pub enum WebSocketOutcome {
+    Response(String),
+    Push(Bitmap<DataType>),
 }
 
 impl WebSocketHandler for Server {
@@ -80,55 +92,35 @@ impl WebSocketHandler for Server {
 
         let mut notifications = Vec::new();
         let mut change_types: Bitmap<DataType> = Bitmap::new();
+        let mut change_floor = self
+            .acquire_change_floor(access_token.member_ids().collect())
+            .await;
+
+        // Requests are processed concurrently, responses carry the client request id
+        // so they can be sent in any order. Tasks still running when the connection
+        // is closed are aborted once the JoinSet is dropped.
+        let max_concurrent = self.core.jmap.web_socket_max_concurrent;
+        let access_token = Arc::new(access_token);
+        let session = Arc::new(session);
+        let mut in_flight = JoinSet::new();
+        let mut last_seq = 0u64;
+        let mut push_seq = 0u64;
 
         loop {
             tokio::select! {
-                event = tokio::time::timeout(next_event, stream.next()) => {
+                event = tokio::time::timeout(next_event, stream.next()), if in_flight.len() < max_concurrent => {
                     match event {
                         Ok(Some(Ok(event))) => {
                             match event {
                                 Message::Text(text) => {
-                                    let response = match WebSocketMessage::parse(
-                                        text.as_bytes(),
-                                        self.core.jmap.request_max_calls,
-                                        self.core.jmap.request_max_size,
-                                    ) {
-                                        Ok(WebSocketMessage::Request(request)) => {
-                                            let response = self
-                                                .handle_jmap_request(
-                                                    request.request,
-                                                    &access_token,
-                                                    &session,
-                                                )
-                                                .await;
-                                            WebSocketResponse::from_response(response, request.id)
-                                            .to_json()
-                                        }
-                                        Ok(WebSocketMessage::PushEnable(push_enable)) => {
-                                            change_types = if !push_enable.data_types.is_empty() {
-                                                push_enable.data_types.into()
-                                            } else {
-                                                Bitmap::all()
-                                            };
-                                            continue;
-                                        }
-                                        Ok(WebSocketMessage::PushDisable) => {
-                                            change_types = Bitmap::new();
-                                            continue;
-                                        }
-                                        Err(err) => {
-                                            let response = WebSocketRequestError::from(err.to_request_error()).to_json();
-                                            trc::error!(err.details("Failed to parse WebSocket message").span_id(session.session_id));
-                                            response
-                                        },
-                                    };
-                                    if let Err(err) = stream.send(Message::Text(response.into())).await {
-                                        trc::event!(Jmap(JmapEvent::WebsocketError),
-                                                    Details = "Failed to send text message",
-                                                    SpanId = session.session_id,
-                                                    Reason = err.to_string()
-                                        );
-                                    }
+                                    let server = self.clone();
+                                    let access_token = access_token.clone();
+                                    let session = session.clone();
+                                    last_seq += 1;
+                                    let seq = last_seq;
+                                    in_flight.spawn(async move {
+                                        (seq, server.handle_websocket_message(text.as_bytes(), &access_token, &session).await)
+                                    });
                                 }
                                 Message::Ping(bytes) => {
                                     if let Err(err) = stream.send(Message::Pong(bytes)).await {
@@ -172,6 +164,49 @@ impl WebSocketHandler for Server {
                         }
                     }
                 }
+                Some(result) = in_flight.join_next() => {
+                    match result {
+                        Ok((_, WebSocketOutcome::Response(response))) => {
+                            if let Err(err) = stream.send(Message::Text(response.into())).await {
+                                trc::event!(Jmap(JmapEvent::WebsocketError),
+                                            Details = "Failed to send text message",
+                                            SpanId = session.session_id,
+                                            Reason = err.to_string()
+                                );
+                            }
+                            last_heartbeat = Instant::now();
+                        }
+                        Ok((seq, WebSocketOutcome::Push(types))) => {
+                            // Push requests may complete out of order, only the last one sent
+                            // applies. Queued changes are filtered with the new types so that
+                            // both take effect together.
+                            if seq > push_seq {
+                                push_seq = seq;
+                                change_types = types;
+                                notifications.retain_mut(|notification| match notification {
+                                    PushNotification::StateChange(state_change) => {
+                                        state_change.types.intersection(&change_types);
+                                        !state_change.types.is_empty()
+                                    }
+                                    PushNotification::CalendarAlert(_) => {
+                                        change_types.contains(DataType::CalendarAlert)
+                                    }
+                                    PushNotification::EmailPush(_) => false,
+                                });
+                            }
+                        }
+                        Err(err) => {
+                            trc::event!(Jmap(JmapEvent::WebsocketError),
+                                        Details = "WebSocket request task failed",
+                                        SpanId = session.session_id,
+                                        Reason = err.to_string()
+                            );
+                        }
+                    }
+                }
+                _ = tokio::time::sleep(next_event), if in_flight.len() >= max_concurrent => {
+                    // Stop reading from the client until a response is sent
+                }
                 push_notification = push_rx.recv() => {
                     if let Some(push_notification) = push_notification {
                         match push_notification {
@@ -229,6 +264,13 @@ impl WebSocketHandler for Server {
                 // Send any queued changes
                 let elapsed = last_changes_sent.elapsed();
                 if elapsed >= throttle {
+                    for notification in &notifications {
+                        if let PushNotification::StateChange(state_change) = notification {
+                            change_floor
+                                .notified(state_change.account_id, state_change.change_id)
+                                .await;
+                        }
+                    }
                     let payload = WebSocketPushObject {
                         push: std::mem::take(&mut notifications).into_push_object(),
                         push_state: None,
@@ -262,4 +304,51 @@ impl WebSocketHandler for Server {
             }
         }
     }
+
+    async fn handle_websocket_message(
+        &self,
+        message: &[u8],
+        access_token: &AccessToken,
+        session: &HttpSessionData,
+    ) -> WebSocketOutcome {
+        match WebSocketMessage::parse(
+            message,
+            self.core.jmap.request_max_calls,
+            self.core.jmap.request_max_size,
+        ) {
+            Ok(WebSocketMessage::Request(request)) => {
+                // Reject the whole request if it contains protected operations
+                if let Err(err) = self.assert_jmap_request_step_up(&request.request, access_token) {
+                    let response =
+                        WebSocketRequestError::from_error(err.to_request_error(), request.id)
+                            .to_json();
+                    trc::error!(err.span_id(session.session_id));
+                    return WebSocketOutcome::Response(response);
+                }
+
+                let response = self
+                    .handle_jmap_request(request.request, access_token, session)
+                    .await;
+                WebSocketOutcome::Response(
+                    WebSocketResponse::from_response(response, request.id).to_json(),
+                )
+            }
+            Ok(WebSocketMessage::PushEnable(push_enable)) => {
+                WebSocketOutcome::Push(if !push_enable.data_types.is_empty() {
+                    push_enable.data_types.into()
+                } else {
+                    Bitmap::all()
+                })
+            }
+            Ok(WebSocketMessage::PushDisable) => WebSocketOutcome::Push(Bitmap::new()),
+            Err(err) => {
+                let response = WebSocketRequestError::from(err.to_request_error()).to_json();
+                trc::error!(
+                    err.details("Failed to parse WebSocket message")
+                        .span_id(session.session_id)
+                );
+                WebSocketOutcome::Response(response)
+            }
+        }
+    }
 }
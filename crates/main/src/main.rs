@@ -110,10 +110,21 @@ async fn main() -> std::io::Result<()> {
     });
 
     // Start broadcast subscriber
+    let inner = init.inner.clone();
     spawn_broadcast_subscriber(init.inner, shutdown_rx);
 
-    // Wait for shutdown signal
-    wait_for_shutdown().await;
+    // Wait for shutdown signal or drain request
+    let mut drain_rx = inner.data.drain.subscribe();
+    tokio::select! {
+        _ = wait_for_shutdown() => {}
+        _ = drain_rx.wait_for(|is_draining| *is_draining) => {}
+    }
+
+    // Stop accepting SMTP sessions and wait for in-progress transactions and
+    // queue deliveries to complete
+    let server = inner.build_server();
+    server.start_drain();
+    server.wait_for_drain().await;
 
     // Shutdown collector
     Collector::shutdown();
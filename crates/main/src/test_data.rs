@@ -494,7 +494,9 @@ fn sample_dmarc_internal_reports() -> Vec<DmarcInternalReport> {
                     evaluated_dkim: DmarcResult::Pass,
                     evaluated_spf: DmarcResult::Pass,
                     extensions: List::from(vec![]),
+                    first_seen: None,
                     header_from: "trusted-sender.com".to_string(),
+                    last_seen: None,
                     policy_override_reasons: List::from(vec![]),
                     source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                         93, 184, 216, 34,
@@ -547,7 +549,9 @@ fn sample_dmarc_internal_reports() -> Vec<DmarcInternalReport> {
                     evaluated_dkim: DmarcResult::Fail,
                     evaluated_spf: DmarcResult::Pass,
                     extensions: List::from(vec![]),
+                    first_seen: None,
                     header_from: "strict-domain.org".to_string(),
+                    last_seen: None,
                     policy_override_reasons: List::from(vec![]),
                     source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                         198, 51, 100, 50,
@@ -601,7 +605,9 @@ fn sample_dmarc_internal_reports() -> Vec<DmarcInternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Pass,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "new-policy.io".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             203, 0, 113, 5,
@@ -627,7 +633,9 @@ fn sample_dmarc_internal_reports() -> Vec<DmarcInternalReport> {
                         evaluated_dkim: DmarcResult::Fail,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "new-policy.io".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(192, 0, 2, 99)))),
                         spf_results: List::from(vec![DmarcSpfResult {
@@ -944,7 +952,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Pass,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))),
                         spf_results: List::from(vec![DmarcSpfResult {
@@ -968,7 +978,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Pass,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2)))),
                         spf_results: List::from(vec![DmarcSpfResult {
@@ -992,7 +1004,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3)))),
                         spf_results: List::from(vec![DmarcSpfResult {
@@ -1048,7 +1062,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Fail,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             198, 51, 100, 222,
@@ -1076,7 +1092,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Fail,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             198, 51, 100, 100,
@@ -1110,7 +1128,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Fail,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             198, 51, 100, 33,
@@ -1166,7 +1186,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             203, 0, 113, 100,
@@ -1192,7 +1214,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Pass,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)))),
                         spf_results: List::from(vec![DmarcSpfResult {
@@ -1218,7 +1242,9 @@ fn sample_dmarc_external_reports() -> Vec<DmarcExternalReport> {
                         evaluated_dkim: DmarcResult::Pass,
                         evaluated_spf: DmarcResult::Fail,
                         extensions: List::from(vec![]),
+                        first_seen: None,
                         header_from: "myserver.com".to_string(),
+                        last_seen: None,
                         policy_override_reasons: List::from(vec![]),
                         source_ip: Some(IpAddr(std::net::IpAddr::V4(Ipv4Addr::new(
                             203, 0, 113, 200,
@@ -12,7 +12,11 @@ use std::{borrow::Cow, net::IpAddr, sync::Arc};
 use common::{
     Inner, Server,
     auth::AccessToken,
-    network::{ServerInstance, limiter::InFlight},
+    network::{
+        ServerInstance,
+        limiter::InFlight,
+        sessions::{SessionLease, SessionSignal},
+    },
 };
 
 use compact_str::CompactString;
@@ -37,6 +41,7 @@ pub enum State {
     Authenticated {
         access_token: AccessToken,
         in_flight: Option<InFlight>,
+        session_lease: SessionLease,
     },
 }
 
@@ -47,6 +52,13 @@ impl State {
             State::NotAuthenticated { .. } => unreachable!("Not authenticated"),
         }
     }
+
+    pub fn session_signal(&self) -> SessionSignal {
+        match self {
+            State::Authenticated { session_lease, .. } => session_lease.signal(),
+            State::NotAuthenticated { .. } => SessionSignal::default(),
+        }
+    }
 }
 
 #[derive(Clone)]
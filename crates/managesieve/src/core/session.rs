@@ -62,6 +62,8 @@ impl<T: SessionStream> Session<T> {
         let mut shutdown_rx = self.instance.shutdown_rx.clone();
 
         loop {
+            let mut session_signal = self.state.session_signal();
+
             tokio::select! {
                 result = tokio::time::timeout(
                     if !matches!(self.state, State::NotAuthenticated {..}) {
@@ -78,7 +80,7 @@ impl<T: SessionStream> Session<T> {
                                         SessionResult::UpgradeTls => {
                                             return true;
                                         }
-                                        SessionResult::Close => {
+                                        SessionResult::Close | SessionResult::UpgradeCompress => {
                                             break;
                                         }
                                     }
@@ -124,6 +126,16 @@ impl<T: SessionStream> Session<T> {
                     self.write(b"BYE \"Server shutting down.\"\r\n").await.ok();
                     break;
                 }
+                _ = session_signal.terminated() => {
+                    trc::event!(
+                        Network(trc::NetworkEvent::Closed),
+                        SpanId = self.session_id,
+                        Reason = "Session terminated by administrator",
+                        CausedBy = trc::location!()
+                    );
+                    self.write(b"BYE \"Session terminated by administrator.\"\r\n").await.ok();
+                    break;
+                }
             };
         }
 
@@ -15,7 +15,7 @@ use imap_proto::{
     receiver::{self, Request},
 };
 use mail_parser::decoders::base64::base64_decode;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{Permission, ServiceProtocol};
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_authenticate(&mut self, request: Request<Command>) -> trc::Result<Vec<u8>> {
@@ -72,6 +72,7 @@ impl<T: SessionStream> Session<T> {
                 credentials,
                 self.session_id,
                 self.remote_addr,
+                ServiceProtocol::Managesieve,
             ))
             .await
             .map_err(|err| {
@@ -92,7 +93,8 @@ impl<T: SessionStream> Session<T> {
 
                 err
             })
-            .and_then(|token| token.assert_has_permission(Permission::SieveAuthenticate))?;
+            .and_then(|token| token.assert_has_permission(Permission::SieveAuthenticate))
+            .and_then(|token| token.assert_protocol_enabled(ServiceProtocol::Managesieve))?;
 
         // Enforce concurrency limits
         let in_flight = match access_token.is_imap_request_allowed() {
@@ -103,10 +105,22 @@ impl<T: SessionStream> Session<T> {
             LimiterResult::Disabled => None,
         };
 
+        // Enforce session limits
+        let session_lease = self
+            .server
+            .acquire_session(
+                &access_token,
+                ServiceProtocol::Managesieve,
+                self.session_id,
+                self.remote_addr,
+            )
+            .await?;
+
         // Create session
         self.state = State::Authenticated {
             access_token,
             in_flight,
+            session_lease,
         };
 
         Ok(StatusResponse::ok("Authentication successful").into_bytes())
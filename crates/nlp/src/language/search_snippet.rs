@@ -69,6 +69,53 @@ pub fn generate_snippet(
             }
         }
     }
+
+    build_snippet(text, &terms)
+}
+
+pub fn generate_substring_snippet(text: &str, needles: &[impl AsRef<str>]) -> Option<String> {
+    let mut terms: Vec<Term> = Vec::new();
+    let mut offset = 0;
+
+    // Case-insensitive matching, used for filters that are not tokenized by the index
+    while offset < text.len() {
+        let haystack = text.get(offset..)?;
+        if let Some(len) = needles
+            .iter()
+            .filter_map(|needle| substring_match_len(haystack, needle.as_ref()))
+            .max()
+        {
+            terms.push(Term { offset, len });
+            offset += len;
+        } else {
+            offset += haystack.chars().next()?.len_utf8();
+        }
+    }
+
+    build_snippet(text, &terms)
+}
+
+fn substring_match_len(haystack: &str, needle: &str) -> Option<usize> {
+    let mut needle = needle.chars().flat_map(char::to_lowercase).peekable();
+    needle.peek()?;
+    let mut len = 0;
+
+    for char in haystack.chars() {
+        for lower in char.to_lowercase() {
+            if needle.next() != Some(lower) {
+                return None;
+            }
+        }
+        len += char.len_utf8();
+        if needle.peek().is_none() {
+            return Some(len);
+        }
+    }
+
+    None
+}
+
+fn build_snippet(text: &str, terms: &[Term]) -> Option<String> {
     if terms.is_empty() {
         return None;
     }
@@ -124,7 +171,9 @@ pub fn generate_snippet(
         }
 
         snippet.push_str("<mark>");
-        snippet.push_str(text.get(term.offset..term.offset + term.len)?);
+        for char in text.get(term.offset..term.offset + term.len)?.chars() {
+            escape_char(char, &mut snippet);
+        }
         snippet.push_str("</mark>");
 
         let next_offset = if let Some(next_term) = terms.peek() {
@@ -157,7 +206,10 @@ pub fn generate_snippet(
 
 #[cfg(test)]
 mod tests {
-    use crate::language::{Language, search_snippet::generate_snippet};
+    use crate::language::{
+        Language,
+        search_snippet::{generate_snippet, generate_substring_snippet},
+    };
 
     #[test]
     fn search_snippets() {
@@ -268,4 +320,31 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn search_substring_snippets() {
+        for (text, needles, expected) in [
+            (
+                "[Fwd: Map of Argentina with Description]",
+                vec!["argent"],
+                Some("[Fwd: Map of <mark>Argent</mark>ina with Description]"),
+            ),
+            (
+                "Re: <Urgent> ÜBERWEISUNG & Überweisung",
+                vec!["<urgent>", "überweisung"],
+                Some(concat!(
+                    "Re: <mark>&lt;Urgent&gt;</mark> <mark>ÜBERWEISUNG</mark> &amp; ",
+                    "<mark>Überweisung</mark>"
+                )),
+            ),
+            ("Quarterly report", vec!["invoice"], None),
+            ("Quarterly report", vec![""], None),
+        ] {
+            assert_eq!(
+                generate_substring_snippet(text, &needles).as_deref(),
+                expected,
+                "{text:?} {needles:?}"
+            );
+        }
+    }
 }
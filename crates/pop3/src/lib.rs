@@ -11,10 +11,16 @@ use std::{net::IpAddr, sync::Arc};
 use common::{
     Inner, Server,
     auth::AccessToken,
-    network::{ServerInstance, SessionStream, limiter::InFlight},
+    network::{
+        ServerInstance, SessionStream,
+        limiter::InFlight,
+        sessions::{SessionLease, SessionSignal},
+    },
+    telemetry::audit::AuditSource,
 };
 use mailbox::Mailbox;
 use protocol::request::Parser;
+use registry::schema::enums::ServiceProtocol;
 
 pub mod client;
 pub mod mailbox;
@@ -44,6 +50,7 @@ pub struct Session<T: SessionStream> {
     pub in_flight: InFlight,
     pub remote_addr: IpAddr,
     pub session_id: u64,
+    pub write_buf: Vec<u8>,
 }
 
 pub enum State {
@@ -54,6 +61,7 @@ pub enum State {
     Authenticated {
         mailbox: Mailbox,
         in_flight: Option<InFlight>,
+        session_lease: SessionLease,
         access_token: AccessToken,
     },
 }
@@ -79,4 +87,21 @@ impl State {
             _ => unreachable!(),
         }
     }
+
+    pub fn session_signal(&self) -> SessionSignal {
+        match self {
+            State::Authenticated { session_lease, .. } => session_lease.signal(),
+            State::NotAuthenticated { .. } => SessionSignal::default(),
+        }
+    }
+}
+
+impl<T: SessionStream> Session<T> {
+    pub fn audit_source(&self) -> AuditSource<'_> {
+        AuditSource {
+            access_token: self.state.access_token(),
+            protocol: ServiceProtocol::Pop3,
+            remote_ip: Some(self.remote_addr),
+        }
+    }
 }
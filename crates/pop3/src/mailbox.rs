@@ -10,9 +10,11 @@ use email::{
     cache::{MessageCacheFetch, mailbox::MailboxCacheAccess},
     mailbox::INBOX_ID,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use trc::AddContext;
-use types::special_use::SpecialUse;
+use types::{blob_hash::BlobHash, special_use::SpecialUse};
+
+const MAX_HEADER_CACHE_SIZE: usize = 4 * 1024 * 1024;
 
 #[derive(Default)]
 pub struct Mailbox {
@@ -21,6 +23,20 @@ pub struct Mailbox {
     pub uid_validity: u32,
     pub total: u32,
     pub size: u32,
+    pub headers: HeaderCache,
+}
+
+#[derive(Default)]
+pub struct HeaderCache {
+    entries: BTreeMap<u32, CachedHeaders>,
+    order: VecDeque<u32>,
+    size: usize,
+}
+
+pub struct CachedHeaders {
+    pub blob_hash: BlobHash,
+    pub body_offset: usize,
+    pub raw_headers: Box<[u8]>,
 }
 
 pub struct Message {
@@ -83,3 +99,29 @@ impl<T: SessionStream> Session<T> {
         Ok(mailbox)
     }
 }
+
+impl HeaderCache {
+    pub fn get(&self, id: u32) -> Option<&CachedHeaders> {
+        self.entries.get(&id)
+    }
+
+    pub fn insert(&mut self, id: u32, headers: CachedHeaders) {
+        self.size += headers.raw_headers.len();
+        if let Some(old) = self.entries.insert(id, headers) {
+            self.size -= old.raw_headers.len();
+        } else {
+            self.order.push_back(id);
+        }
+
+        // Evict the oldest entries, always keeping the most recent one
+        while self.size > MAX_HEADER_CACHE_SIZE && self.order.len() > 1 {
+            if let Some(evicted) = self
+                .order
+                .pop_front()
+                .and_then(|id| self.entries.remove(&id))
+            {
+                self.size -= evicted.raw_headers.len();
+            }
+        }
+    }
+}
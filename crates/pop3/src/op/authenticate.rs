@@ -14,7 +14,7 @@ use common::{
 };
 use directory::Credentials;
 use mail_parser::decoders::base64::base64_decode;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{Permission, ServiceProtocol};
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_sasl(
@@ -68,6 +68,7 @@ impl<T: SessionStream> Session<T> {
                 credentials,
                 self.session_id,
                 self.remote_addr,
+                ServiceProtocol::Pop3,
             ))
             .await
             .map_err(|err| {
@@ -90,7 +91,8 @@ impl<T: SessionStream> Session<T> {
 
                 err
             })
-            .and_then(|token| token.assert_has_permission(Permission::Pop3Authenticate))?;
+            .and_then(|token| token.assert_has_permission(Permission::Pop3Authenticate))
+            .and_then(|token| token.assert_protocol_enabled(ServiceProtocol::Pop3))?;
 
         // Enforce concurrency limits
         let in_flight = match access_token.is_imap_request_allowed() {
@@ -101,12 +103,24 @@ impl<T: SessionStream> Session<T> {
             LimiterResult::Disabled => None,
         };
 
+        // Enforce session limits
+        let session_lease = self
+            .server
+            .acquire_session(
+                &access_token,
+                ServiceProtocol::Pop3,
+                self.session_id,
+                self.remote_addr,
+            )
+            .await?;
+
         // Fetch mailbox
         let mailbox = self.fetch_mailbox(access_token.account_id()).await?;
 
         // Create session
         self.state = State::Authenticated {
             in_flight,
+            session_lease,
             mailbox,
             access_token,
         };
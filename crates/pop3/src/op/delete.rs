@@ -8,9 +8,10 @@ use std::time::Instant;
 
 use common::network::SessionStream;
 use email::message::delete::EmailDeletion;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission};
 use store::{roaring::RoaringBitmap, write::BatchBuilder};
 use trc::AddContext;
+use types::id::Id;
 
 use crate::{Session, State, protocol::response::Response};
 
@@ -86,6 +87,7 @@ impl<T: SessionStream> Session<T> {
 
             if !deleted.is_empty() {
                 let num_deleted = deleted.len();
+                let deleted_ids = deleted.iter().map(Id::from).collect::<Vec<_>>();
                 let mut batch = BatchBuilder::new();
                 let not_deleted = self
                     .server
@@ -105,6 +107,17 @@ impl<T: SessionStream> Session<T> {
                         .caused_by(trc::location!())?;
                     self.server.notify_task_queue();
                 }
+                self.server
+                    .audit(
+                        self.audit_source(),
+                        mailbox.account_id,
+                        AuditEventType::MessageDelete,
+                        deleted_ids
+                            .iter()
+                            .filter(|id| !not_deleted.contains(id.document_id()))
+                            .copied(),
+                    )
+                    .await;
                 if not_deleted.is_empty() {
                     self.write_ok(format!(
                         "Stalwart POP3 bids you farewell ({num_deleted} messages deleted)."
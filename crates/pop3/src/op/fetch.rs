@@ -4,18 +4,18 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::{Session, protocol::response::Response};
+use crate::{Session, mailbox::CachedHeaders, protocol::response::Response};
 use common::network::SessionStream;
 use email::message::metadata::MessageMetadata;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{AuditEventType, Permission};
 use std::time::Instant;
 use store::{
     ValueKey,
     write::{AlignedBytes, Archive},
 };
 use trc::AddContext;
-use types::{collection::Collection, field::EmailField};
-use utils::chained_bytes::ChainedBytes;
+use types::{blob_hash::BlobHash, collection::Collection, field::EmailField, id::Id};
+use utils::chained_bytes::{ChainedBytes, SliceRange};
 
 impl<T: SessionStream> Session<T> {
     pub async fn handle_fetch(&mut self, msg: u32, lines: Option<u32>) -> trc::Result<()> {
@@ -26,66 +26,107 @@ impl<T: SessionStream> Session<T> {
 
         let op_start = Instant::now();
         let mailbox = self.state.mailbox();
-        if let Some(message) = mailbox.messages.get(msg.saturating_sub(1) as usize) {
-            if let Some(metadata_) = self
-                .server
-                .store()
-                .get_value::<Archive<AlignedBytes>>(ValueKey::property(
-                    mailbox.account_id,
-                    Collection::Email,
-                    message.id,
-                    EmailField::Metadata,
-                ))
-                .await
-                .caused_by(trc::location!())?
-            {
-                let metadata = metadata_
-                    .unarchive::<MessageMetadata>()
-                    .caused_by(trc::location!())?;
-                if let Some(bytes) = self
-                    .server
-                    .blob_store()
-                    .get_blob(metadata.blob_hash.0.as_slice(), 0..usize::MAX)
-                    .await
-                    .caused_by(trc::location!())?
-                {
-                    trc::event!(
-                        Pop3(trc::Pop3Event::Fetch),
-                        SpanId = self.session_id,
-                        DocumentId = message.id,
-                        Elapsed = op_start.elapsed()
-                    );
+        let account_id = mailbox.account_id;
+        let (message_id, message_size) =
+            if let Some(message) = mailbox.messages.get(msg.saturating_sub(1) as usize) {
+                (message.id, message.size)
+            } else {
+                return Err(trc::Pop3Event::Error.into_err().details("No such message."));
+            };
 
-                    let bytes = ChainedBytes::new(metadata.raw_headers.as_ref())
-                        .with_last(
-                            bytes
-                                .get(metadata.blob_body_offset.to_native() as usize..)
-                                .unwrap_or_default(),
-                        )
-                        .get_full_range();
+        // Headers are cached for the lifetime of the session
+        if mailbox.headers.get(message_id).is_none() {
+            let headers = self.fetch_headers(account_id, message_id).await?;
+            self.state.mailbox_mut().headers.insert(message_id, headers);
+        }
+        let Some(headers) = self.state.mailbox().headers.get(message_id) else {
+            return Err(message_not_found());
+        };
 
-                    self.write_bytes(
-                        Response::Message::<u32> {
-                            bytes,
-                            lines: lines.unwrap_or(0),
-                        }
-                        .serialize(),
-                    )
-                    .await
-                } else {
-                    Err(trc::Pop3Event::Error
-                        .into_err()
-                        .details("Failed to fetch message. Perhaps another session deleted it?")
-                        .caused_by(trc::location!()))
-                }
-            } else {
-                Err(trc::Pop3Event::Error
-                    .into_err()
-                    .details("Failed to fetch message. Perhaps another session deleted it?")
-                    .caused_by(trc::location!()))
+        // TOP requests that fall within the headers do not need the message body
+        let response = if let Some(lines) = lines.filter(|&lines| {
+            lines == 0
+                || headers
+                    .raw_headers
+                    .iter()
+                    .filter(|&&byte| byte == b'\n')
+                    .count()
+                    >= lines as usize
+        }) {
+            Response::Message::<u32> {
+                bytes: SliceRange::Single(&headers.raw_headers),
+                lines,
+                size: message_size as usize,
             }
+            .serialize()
+        } else if let Some(bytes) = self
+            .server
+            .blob_store()
+            .get_blob(headers.blob_hash.as_slice(), 0..usize::MAX)
+            .await
+            .caused_by(trc::location!())?
+        {
+            let bytes = ChainedBytes::new(headers.raw_headers.as_ref())
+                .with_last(bytes.get(headers.body_offset..).unwrap_or_default())
+                .get_full_range();
+
+            Response::Message::<u32> {
+                size: bytes.len(),
+                bytes,
+                lines: lines.unwrap_or(0),
+            }
+            .serialize()
         } else {
-            Err(trc::Pop3Event::Error.into_err().details("No such message."))
-        }
+            return Err(message_not_found());
+        };
+
+        trc::event!(
+            Pop3(trc::Pop3Event::Fetch),
+            SpanId = self.session_id,
+            DocumentId = message_id,
+            Elapsed = op_start.elapsed()
+        );
+
+        self.server
+            .audit(
+                self.audit_source(),
+                account_id,
+                AuditEventType::MessageRead,
+                [Id::from(message_id)],
+            )
+            .await;
+
+        self.write_bytes(response).await
     }
+
+    async fn fetch_headers(&self, account_id: u32, message_id: u32) -> trc::Result<CachedHeaders> {
+        let metadata_ = self
+            .server
+            .store()
+            .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+                account_id,
+                Collection::Email,
+                message_id,
+                EmailField::Metadata,
+            ))
+            .await
+            .caused_by(trc::location!())?
+            .ok_or_else(message_not_found)?;
+        let metadata = metadata_
+            .unarchive::<MessageMetadata>()
+            .caused_by(trc::location!())?;
+
+        Ok(CachedHeaders {
+            blob_hash: BlobHash::from(&metadata.blob_hash),
+            body_offset: metadata.blob_body_offset.to_native() as usize,
+            raw_headers: metadata.raw_headers.as_ref().into(),
+        })
+    }
+}
+
+fn message_not_found() -> trc::Error {
+    trc::Pop3Event::Error
+        .into_err()
+        .details("Failed to fetch message. Perhaps another session deleted it?")
+        .caused_by(trc::location!())
 }
@@ -15,6 +15,7 @@ pub enum Response<'x, T> {
     Message {
         bytes: SliceRange<'x>,
         lines: u32,
+        size: usize,
     },
     Capability {
         mechanisms: Vec<Mechanism>,
@@ -51,10 +52,10 @@ impl<'x, T: Display> Response<'x, T> {
                 buf.extend_from_slice(b".\r\n");
                 buf
             }
-            Response::Message { bytes, lines } => {
+            Response::Message { bytes, lines, size } => {
                 let mut buf = Vec::with_capacity(bytes.len() + 10);
                 buf.extend_from_slice(b"+OK ");
-                buf.extend_from_slice(bytes.len().to_string().as_bytes());
+                buf.extend_from_slice(size.to_string().as_bytes());
                 buf.extend_from_slice(b" octets\r\n");
 
                 let mut line_count = 0;
@@ -157,6 +158,9 @@ impl SerializeResponse for trc::Error {
             .unwrap_or_else(|| self.as_ref().message());
         let mut buf = Vec::with_capacity(message.len() + 6);
         buf.extend_from_slice(b"-ERR ");
+        if self.matches(trc::EventType::Auth(trc::AuthEvent::ProtocolDisabled)) {
+            buf.extend_from_slice(b"[SYS/PERM] ");
+        }
         buf.extend_from_slice(message.as_bytes());
         buf.extend_from_slice(b"\r\n");
         buf
@@ -207,9 +211,18 @@ mod tests {
                 Response::Message {
                     bytes: SliceRange::Split(b"Subject: test\r\n\r\n.\r\n", b"test.\r\n.test\r\na"),
                     lines: 0,
+                    size: 35,
                 },
                 "+OK 35 octets\r\nSubject: test\r\n\r\n..\r\ntest.\r\n..test\r\na\r\n.\r\n",
             ),
+            (
+                Response::Message {
+                    bytes: SliceRange::Single(b"From: a\r\nSubject: test\r\n\r\n"),
+                    lines: 1,
+                    size: 100,
+                },
+                "+OK 100 octets\r\nFrom: a\r\n.\r\n",
+            ),
         ] {
             assert_eq!(expected, String::from_utf8(cmd.serialize()).unwrap());
         }
@@ -19,6 +19,8 @@ use std::borrow::Cow;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio_rustls::server::TlsStream;
 
+const MAX_WRITE_BUFFER_SIZE: usize = 64 * 1024;
+
 impl SessionManager for Pop3SessionManager {
     #[allow(clippy::manual_async_fn)]
     fn handle<T: SessionStream>(
@@ -38,12 +40,14 @@ impl SessionManager for Pop3SessionManager {
                 in_flight: session.in_flight,
                 remote_addr: session.remote_ip,
                 session_id: session.session_id,
+                write_buf: Vec::new(),
             };
 
             if session
                 .write_bytes(SERVER_GREETING.as_bytes())
                 .await
                 .is_ok()
+                && session.flush().await.is_ok()
                 && session.handle_conn().await
                 && session.instance.acceptor.is_tls()
                 && let Ok(mut session) = session.into_tls().await
@@ -65,6 +69,8 @@ impl<T: SessionStream> Session<T> {
         let mut shutdown_rx = self.instance.shutdown_rx.clone();
 
         loop {
+            let mut session_signal = self.state.session_signal();
+
             tokio::select! {
                 result = tokio::time::timeout(
                     if !matches!(self.state, State::NotAuthenticated {..}) {
@@ -76,12 +82,19 @@ impl<T: SessionStream> Session<T> {
                     match result {
                         Ok(Ok(bytes_read)) => {
                             if bytes_read > 0 {
-                                match self.ingest(&buf[..bytes_read]).await {
+                                // Responses to pipelined commands are flushed together
+                                let result = self.ingest(&buf[..bytes_read]).await;
+                                if let Err(err) = self.flush().await {
+                                    trc::error!(err.span_id(self.session_id));
+                                    break;
+                                }
+
+                                match result {
                                     SessionResult::Continue => (),
                                     SessionResult::UpgradeTls => {
                                         return true;
                                     }
-                                    SessionResult::Close => {
+                                    SessionResult::Close | SessionResult::UpgradeCompress => {
                                         break;
                                     }
                                 }
@@ -111,6 +124,7 @@ impl<T: SessionStream> Session<T> {
                             );
 
                             self.write_bytes(&b"-ERR Connection timed out.\r\n"[..]).await.ok();
+                            self.flush().await.ok();
                             break;
                         }
                     }
@@ -124,6 +138,19 @@ impl<T: SessionStream> Session<T> {
                     );
 
                     self.write_bytes(&b"* BYE Server shutting down.\r\n"[..]).await.ok();
+                    self.flush().await.ok();
+                    break;
+                }
+                _ = session_signal.terminated() => {
+                    trc::event!(
+                        Network(trc::NetworkEvent::Closed),
+                        SpanId = self.session_id,
+                        Reason = "Session terminated by administrator",
+                        CausedBy = trc::location!()
+                    );
+
+                    self.write_bytes(&b"-ERR Session terminated by administrator.\r\n"[..]).await.ok();
+                    self.flush().await.ok();
                     break;
                 }
             };
@@ -145,6 +172,7 @@ impl<T: SessionStream> Session<T> {
             session_id: self.session_id,
             in_flight: self.in_flight,
             remote_addr: self.remote_addr,
+            write_buf: self.write_buf,
         })
     }
 }
@@ -160,12 +188,23 @@ impl<T: SessionStream> Session<T> {
             Contents = trc::Value::from_maybe_string(bytes),
         );
 
-        self.stream.write_all(bytes.as_ref()).await.map_err(|err| {
-            trc::NetworkEvent::WriteError
-                .into_err()
-                .reason(err)
-                .caused_by(trc::location!())
-        })?;
+        // Small responses are buffered until the session is flushed
+        if self.write_buf.len() + bytes.len() <= MAX_WRITE_BUFFER_SIZE {
+            self.write_buf.extend_from_slice(bytes);
+            Ok(())
+        } else {
+            self.write_buffered().await?;
+            self.stream.write_all(bytes).await.map_err(|err| {
+                trc::NetworkEvent::WriteError
+                    .into_err()
+                    .reason(err)
+                    .caused_by(trc::location!())
+            })
+        }
+    }
+
+    pub async fn flush(&mut self) -> trc::Result<()> {
+        self.write_buffered().await?;
         self.stream.flush().await.map_err(|err| {
             trc::NetworkEvent::WriteError
                 .into_err()
@@ -174,6 +213,21 @@ impl<T: SessionStream> Session<T> {
         })
     }
 
+    async fn write_buffered(&mut self) -> trc::Result<()> {
+        if !self.write_buf.is_empty() {
+            let result = self.stream.write_all(&self.write_buf).await;
+            self.write_buf.clear();
+            result.map_err(|err| {
+                trc::NetworkEvent::WriteError
+                    .into_err()
+                    .reason(err)
+                    .caused_by(trc::location!())
+            })
+        } else {
+            Ok(())
+        }
+    }
+
     pub async fn write_ok(&mut self, message: impl Into<Cow<'static, str>>) -> trc::Result<()> {
         self.write_bytes(Response::Ok::<u32>(message.into()).serialize())
             .await
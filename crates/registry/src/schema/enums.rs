@@ -49,6 +49,8 @@ pub enum ActionType {
     InvalidateNegativeCaches = 8,
     PauseMtaQueue = 9,
     ResumeMtaQueue = 10,
+    DrainServer = 11,
+    SignOutEverywhere = 12,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -151,6 +153,19 @@ pub enum AsnType {
     Dns = 2,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum AuditEventType {
+    #[default]
+    Login = 0,
+    LoginFailed = 1,
+    MessageRead = 2,
+    MessageDelete = 3,
+    MailboxDelete = 4,
+    AclChange = 5,
+    Export = 6,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum AzureEnvironment {
@@ -198,6 +213,15 @@ pub enum BlockReason {
     Other = 5,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum CalendarInvitationPolicy {
+    #[default]
+    Default = 0,
+    AutoAdd = 1,
+    Disabled = 2,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum CertificateManagementType {
@@ -206,6 +230,21 @@ pub enum CertificateManagementType {
     Automatic = 1,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum ChangeLogCollection {
+    #[default]
+    Email = 0,
+    Thread = 1,
+    Calendar = 2,
+    AddressBook = 3,
+    FileNode = 4,
+    Identity = 5,
+    EmailSubmission = 6,
+    SieveScript = 7,
+    CalendarEventNotification = 8,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum ClusterListenerGroupType {
@@ -336,6 +375,14 @@ pub enum DirectoryBootstrapType {
     Oidc = 3,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum DirectoryLookupMode {
+    #[default]
+    FirstMatch = 0,
+    Merge = 1,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum DirectoryType {
@@ -696,6 +743,15 @@ pub enum DnsServerType {
     YandexCloud = 69,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum DuplicateDelivery {
+    #[default]
+    Disabled = 0,
+    Discard = 1,
+    Flag = 2,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum EncryptionAtRestType {
@@ -738,6 +794,9 @@ pub enum ExpressionConstant {
     Mixer = 16,
     Stanag4406 = 17,
     Nsep = 18,
+    Reject = 19,
+    Rewrite = 20,
+    StrictDryRun = 21,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -761,6 +820,10 @@ pub enum ExpressionVariable {
     CcDomain = 14,
     CcLocal = 15,
     CcName = 16,
+    CertDns = 93,
+    CertEmail = 94,
+    CertFingerprint = 95,
+    CertSubject = 96,
     Country = 17,
     Domain = 18,
     Email = 19,
@@ -822,6 +885,9 @@ pub enum ExpressionVariable {
     Scheme = 75,
     Sender = 76,
     SenderDomain = 77,
+    SenderReputation = 97,
+    SenderVerify = 91,
+    SessionId = 92,
     Size = 78,
     Sld = 79,
     Source = 80,
@@ -847,6 +913,16 @@ pub enum FailureReportingOption {
     SpfFailure = 3,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum HealthCheckDependency {
+    #[default]
+    DataStore = 0,
+    BlobStore = 1,
+    InMemoryStore = 2,
+    SearchStore = 3,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum HttpAuthType {
@@ -861,6 +937,7 @@ pub enum HttpAuthType {
 pub enum HttpLookupFormatType {
     #[default]
     Csv = 0,
+    Json = 2,
     List = 1,
 }
 
@@ -1304,6 +1381,25 @@ pub enum MessageFlag {
     Autogenerated = 5,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MessageImportFormat {
+    #[default]
+    Maildir = 0,
+    Mbox = 1,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MessageTemplateId {
+    #[default]
+    DsnFailure = 0,
+    DsnDelay = 1,
+    DsnSuccess = 2,
+    QuotaWarning = 3,
+    AutoReply = 4,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum MetricType {
@@ -1384,6 +1480,15 @@ pub enum MtaDeliveryScheduleIntervalsOrDefaultType {
     Custom = 1,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MtaHookProtocol {
+    Grpc = 2,
+    #[default]
+    Json = 0,
+    Stream = 1,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum MtaInboundThrottleKey {
@@ -1399,6 +1504,14 @@ pub enum MtaInboundThrottleKey {
     RcptDomain = 8,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MtaIpSelection {
+    #[default]
+    Weighted = 0,
+    RoundRobin = 1,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum MtaIpStrategy {
@@ -1448,6 +1561,32 @@ pub enum MtaRequiredOrOptional {
     Disable = 2,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum MtaResponseId {
+    #[default]
+    ArcReject = 0,
+    ArcTempFail = 1,
+    DkimReject = 2,
+    DkimTempFail = 3,
+    DmarcReject = 4,
+    DmarcTempFail = 5,
+    FromNotAuthorized = 6,
+    IprevReject = 7,
+    IprevTempFail = 8,
+    LoopDetected = 9,
+    MailboxNotFound = 10,
+    QueueTempFail = 11,
+    QuotaFull = 12,
+    RateLimited = 13,
+    RelayDenied = 14,
+    ReverseIpRequired = 19,
+    SenderNotAllowed = 15,
+    SpamReject = 16,
+    SpfReject = 17,
+    SpfTempFail = 18,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum MtaRouteType {
@@ -1517,14 +1656,27 @@ pub enum PasswordStrength {
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum Permission {
+    ActionSignOutEverywhere = 689,
     #[default]
     Authenticate = 0,
     AuthenticateWithAlias = 1,
+    ImapUrlAuth = 688,
     InteractAi = 2,
     Impersonate = 3,
+    JmapEmailRecall = 715,
+    JmapSieveLogQuery = 690,
+    OAuthDeviceAuthorize = 692,
+    SenderReputationGet = 693,
+    SenderReputationReset = 694,
+    SessionList = 684,
+    SessionTerminate = 685,
+    SieveRedirectGet = 686,
+    SieveRedirectReset = 687,
+    StoreBackup = 691,
     UnlimitedRequests = 4,
     UnlimitedUploads = 5,
     FetchAnyBlob = 6,
+    QueuedMessagePreview = 682,
     EmailSend = 7,
     EmailReceive = 8,
     CalendarAlarmsSend = 9,
@@ -1763,6 +1915,7 @@ pub enum Permission {
     ActionInvalidateNegativeCaches = 241,
     ActionPauseMtaQueue = 242,
     ActionResumeMtaQueue = 243,
+    ActionDrainServer = 681,
     SysActionGet = 244,
     SysActionCreate = 245,
     SysActionUpdate = 246,
@@ -1812,6 +1965,11 @@ pub enum Permission {
     SysArfExternalReportQuery = 290,
     SysAsnGet = 291,
     SysAsnUpdate = 292,
+    SysAuditEventGet = 667,
+    SysAuditEventCreate = 668,
+    SysAuditEventUpdate = 669,
+    SysAuditEventDestroy = 670,
+    SysAuditEventQuery = 671,
     SysAuthenticationGet = 293,
     SysAuthenticationUpdate = 294,
     SysBlobStoreGet = 295,
@@ -1852,6 +2010,11 @@ pub enum Permission {
     SysDataRetentionUpdate = 330,
     SysDataStoreGet = 331,
     SysDataStoreUpdate = 332,
+    SysDeliveryCallbackGet = 660,
+    SysDeliveryCallbackCreate = 661,
+    SysDeliveryCallbackUpdate = 662,
+    SysDeliveryCallbackDestroy = 663,
+    SysDeliveryCallbackQuery = 664,
     SysDirectoryGet = 333,
     SysDirectoryCreate = 334,
     SysDirectoryUpdate = 335,
@@ -1921,6 +2084,11 @@ pub enum Permission {
     SysLogUpdate = 399,
     SysLogDestroy = 400,
     SysLogQuery = 401,
+    SysMailCategoryGet = 710,
+    SysMailCategoryCreate = 711,
+    SysMailCategoryUpdate = 712,
+    SysMailCategoryDestroy = 713,
+    SysMailCategoryQuery = 714,
     SysMailingListGet = 402,
     SysMailingListCreate = 403,
     SysMailingListUpdate = 404,
@@ -1941,6 +2109,11 @@ pub enum Permission {
     SysMemoryLookupKeyValueUpdate = 419,
     SysMemoryLookupKeyValueDestroy = 420,
     SysMemoryLookupKeyValueQuery = 421,
+    SysMessageTemplateGet = 695,
+    SysMessageTemplateCreate = 696,
+    SysMessageTemplateUpdate = 697,
+    SysMessageTemplateDestroy = 698,
+    SysMessageTemplateQuery = 699,
     SysMetricGet = 422,
     SysMetricCreate = 423,
     SysMetricUpdate = 424,
@@ -1991,6 +2164,11 @@ pub enum Permission {
     SysMtaQueueQuotaUpdate = 469,
     SysMtaQueueQuotaDestroy = 470,
     SysMtaQueueQuotaQuery = 471,
+    SysMtaResponseGet = 676,
+    SysMtaResponseCreate = 677,
+    SysMtaResponseUpdate = 678,
+    SysMtaResponseDestroy = 679,
+    SysMtaResponseQuery = 680,
     SysMtaRouteGet = 472,
     SysMtaRouteCreate = 473,
     SysMtaRouteUpdate = 474,
@@ -2015,6 +2193,8 @@ pub enum Permission {
     SysMtaTlsStrategyUpdate = 493,
     SysMtaTlsStrategyDestroy = 494,
     SysMtaTlsStrategyQuery = 495,
+    SysMtaUnsubscribeGet = 701,
+    SysMtaUnsubscribeUpdate = 702,
     SysMtaVirtualQueueGet = 496,
     SysMtaVirtualQueueCreate = 497,
     SysMtaVirtualQueueUpdate = 498,
@@ -2073,6 +2253,8 @@ pub enum Permission {
     SysSieveUserScriptUpdate = 551,
     SysSieveUserScriptDestroy = 552,
     SysSieveUserScriptQuery = 553,
+    SysSpamClamAvGet = 665,
+    SysSpamClamAvUpdate = 666,
     SysSpamClassifierGet = 554,
     SysSpamClassifierUpdate = 555,
     SysSpamDnsblServerGet = 556,
@@ -2135,6 +2317,15 @@ pub enum Permission {
     TaskAcmeRenewal = 613,
     TaskDkimManagement = 614,
     TaskDnsManagement = 615,
+    TaskDaneRollover = 708,
+    TaskFollowUpReminder = 709,
+    TaskDeliveryCallback = 716,
+    TaskReEncryptAccount = 672,
+    TaskReindexAccount = 673,
+    TaskImportMessages = 674,
+    TaskQuotaWarning = 675,
+    TaskEmailSubmission = 683,
+    TaskRestoreSnoozedEmail = 700,
     SysTaskGet = 616,
     SysTaskCreate = 617,
     SysTaskUpdate = 618,
@@ -2169,6 +2360,11 @@ pub enum Permission {
     SysTracerUpdate = 647,
     SysTracerDestroy = 648,
     SysTracerQuery = 649,
+    SysUnsubscribeEventGet = 703,
+    SysUnsubscribeEventCreate = 704,
+    SysUnsubscribeEventUpdate = 705,
+    SysUnsubscribeEventDestroy = 706,
+    SysUnsubscribeEventQuery = 707,
     SysTracingStoreGet = 650,
     SysTracingStoreUpdate = 651,
     SysWebDavGet = 652,
@@ -2243,7 +2439,10 @@ pub enum QueueExpiryType {
 pub enum RecipientFlag {
     #[default]
     DsnSent = 0,
+    ExpiredAttempts = 3,
+    ExpiredTtl = 2,
     SpamPayload = 1,
+    Split = 4,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -2450,6 +2649,14 @@ pub enum ServiceProtocol {
     Managesieve = 7,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SharedBlobQuota {
+    #[default]
+    ChargeEachReference = 0,
+    ChargeOnce = 1,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum SieveCapability {
@@ -2505,6 +2712,14 @@ pub enum SieveCapability {
     VndStalwartExpressions = 48,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SieveRedirectSender {
+    Original = 0,
+    #[default]
+    Account = 1,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum Sig0Algorithm {
@@ -2595,6 +2810,15 @@ pub enum SpamTagType {
     Reject = 2,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SpamVirusAction {
+    #[default]
+    Reject = 0,
+    Quarantine = 1,
+    Tag = 2,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum SpecialUse {
@@ -2686,6 +2910,7 @@ pub enum TaskAccountMaintenanceType {
     Reindex = 1,
     RecalculateImapUid = 2,
     RecalculateQuota = 3,
+    RecountBlobReferences = 4,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -2719,6 +2944,8 @@ pub enum TaskStatusType {
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[repr(u16)]
 pub enum TaskStoreMaintenanceType {
+    FullBackup = 17,
+    IncrementalBackup = 18,
     #[default]
     ReindexAccounts = 0,
     ReindexTelemetry = 1,
@@ -2735,6 +2962,8 @@ pub enum TaskStoreMaintenanceType {
     RemoveLockDav = 12,
     RemoveSieveId = 13,
     RemoveGreylist = 14,
+    ReconcileQuotas = 15,
+    RecountBlobReferences = 16,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -2766,6 +2995,15 @@ pub enum TaskType {
     AcmeRenewal = 15,
     DkimManagement = 16,
     DnsManagement = 17,
+    ReEncryptAccount = 18,
+    ReindexAccount = 19,
+    ImportMessages = 20,
+    QuotaWarning = 21,
+    EmailSubmission = 22,
+    RestoreSnoozedEmail = 23,
+    DaneRollover = 24,
+    FollowUpReminder = 25,
+    DeliveryCallback = 26,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -3541,6 +3779,21 @@ pub static HTTP_VARIABLE: &[ExpressionVariable] = &[
     ExpressionVariable::Method,
 ];
 
+pub static MTA_CLIENT_CERT_VARIABLE: &[ExpressionVariable] = &[
+    ExpressionVariable::Listener,
+    ExpressionVariable::RemoteIp,
+    ExpressionVariable::RemotePort,
+    ExpressionVariable::LocalIp,
+    ExpressionVariable::LocalPort,
+    ExpressionVariable::Protocol,
+    ExpressionVariable::CertSubject,
+    ExpressionVariable::CertEmail,
+    ExpressionVariable::CertDns,
+    ExpressionVariable::CertFingerprint,
+    ExpressionVariable::Asn,
+    ExpressionVariable::Country,
+];
+
 pub static MTA_CONNECTION_VARIABLE: &[ExpressionVariable] = &[
     ExpressionVariable::Listener,
     ExpressionVariable::RemoteIp,
@@ -3640,6 +3893,7 @@ pub static MTA_RCPT_DOMAIN_VARIABLE: &[ExpressionVariable] = &[ExpressionVariabl
 pub static MTA_RCPT_TO_VARIABLE: &[ExpressionVariable] = &[
     ExpressionVariable::Sender,
     ExpressionVariable::SenderDomain,
+    ExpressionVariable::SenderVerify,
     ExpressionVariable::Recipients,
     ExpressionVariable::Rcpt,
     ExpressionVariable::RcptDomain,
@@ -3655,6 +3909,22 @@ pub static MTA_RCPT_TO_VARIABLE: &[ExpressionVariable] = &[
     ExpressionVariable::HeloDomain,
     ExpressionVariable::Asn,
     ExpressionVariable::Country,
+    ExpressionVariable::SenderReputation,
+];
+
+pub static MTA_RESPONSE_VARIABLE: &[ExpressionVariable] = &[
+    ExpressionVariable::Sender,
+    ExpressionVariable::SenderDomain,
+    ExpressionVariable::Recipients,
+    ExpressionVariable::Rcpt,
+    ExpressionVariable::RcptDomain,
+    ExpressionVariable::AuthenticatedAs,
+    ExpressionVariable::Listener,
+    ExpressionVariable::RemoteIp,
+    ExpressionVariable::LocalIp,
+    ExpressionVariable::Protocol,
+    ExpressionVariable::HeloDomain,
+    ExpressionVariable::SessionId,
 ];
 
 pub static MTA_RCPT_VARIABLE: &[ExpressionVariable] = &[ExpressionVariable::Rcpt];
@@ -3700,6 +3970,7 @@ pub static SPAM_DEFAULT_VARIABLE: &[ExpressionVariable] = &[
     ExpressionVariable::SubjectThread,
     ExpressionVariable::SubjectWords,
     ExpressionVariable::Location,
+    ExpressionVariable::SenderReputation,
 ];
 
 pub static SPAM_EMAIL_VARIABLE: &[ExpressionVariable] = &[
@@ -3962,6 +4233,12 @@ pub static MTA_AUTH_TYPE_CONSTANT: &[ExpressionConstant] = &[
     ExpressionConstant::Oauthbearer,
 ];
 
+pub static MTA_FROM_ALIGNMENT_CONSTANT: &[ExpressionConstant] = &[
+    ExpressionConstant::Reject,
+    ExpressionConstant::Rewrite,
+    ExpressionConstant::Disable,
+];
+
 pub static MTA_IP_STRATEGY_CONSTANT: &[ExpressionConstant] = &[
     ExpressionConstant::Ipv4Only,
     ExpressionConstant::Ipv6Only,
@@ -3984,5 +4261,19 @@ pub static MTA_REQUIRE_CONSTANT: &[ExpressionConstant] = &[
 pub static MTA_VERIFY_CONSTANT: &[ExpressionConstant] = &[
     ExpressionConstant::Relaxed,
     ExpressionConstant::Strict,
+    ExpressionConstant::StrictDryRun,
     ExpressionConstant::Disable,
 ];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum Weekday {
+    #[default]
+    Monday = 0,
+    Tuesday = 1,
+    Wednesday = 2,
+    Thursday = 3,
+    Friday = 4,
+    Saturday = 5,
+    Sunday = 6,
+}
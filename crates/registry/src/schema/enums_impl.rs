@@ -185,6 +185,8 @@ impl EnumImpl for ActionType {
             b"InvalidateNegativeCaches" => ActionType::InvalidateNegativeCaches,
             b"PauseMtaQueue" => ActionType::PauseMtaQueue,
             b"ResumeMtaQueue" => ActionType::ResumeMtaQueue,
+            b"DrainServer" => ActionType::DrainServer,
+            b"SignOutEverywhere" => ActionType::SignOutEverywhere,
         }
     }
 
@@ -201,6 +203,8 @@ impl EnumImpl for ActionType {
             ActionType::InvalidateNegativeCaches => "InvalidateNegativeCaches",
             ActionType::PauseMtaQueue => "PauseMtaQueue",
             ActionType::ResumeMtaQueue => "ResumeMtaQueue",
+            ActionType::DrainServer => "DrainServer",
+            ActionType::SignOutEverywhere => "SignOutEverywhere",
         }
     }
 
@@ -221,11 +225,13 @@ impl EnumImpl for ActionType {
             8 => Some(ActionType::InvalidateNegativeCaches),
             9 => Some(ActionType::PauseMtaQueue),
             10 => Some(ActionType::ResumeMtaQueue),
+            11 => Some(ActionType::DrainServer),
+            12 => Some(ActionType::SignOutEverywhere),
             _ => None,
         }
     }
 
-    const COUNT: usize = 11;
+    const COUNT: usize = 13;
 }
 
 impl serde::Serialize for ActionType {
@@ -807,6 +813,71 @@ impl<'de> serde::Deserialize<'de> for AsnType {
     }
 }
 
+impl EnumImpl for AuditEventType {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"login" => AuditEventType::Login,
+            b"loginFailed" => AuditEventType::LoginFailed,
+            b"messageRead" => AuditEventType::MessageRead,
+            b"messageDelete" => AuditEventType::MessageDelete,
+            b"mailboxDelete" => AuditEventType::MailboxDelete,
+            b"aclChange" => AuditEventType::AclChange,
+            b"export" => AuditEventType::Export,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::Login => "login",
+            AuditEventType::LoginFailed => "loginFailed",
+            AuditEventType::MessageRead => "messageRead",
+            AuditEventType::MessageDelete => "messageDelete",
+            AuditEventType::MailboxDelete => "mailboxDelete",
+            AuditEventType::AclChange => "aclChange",
+            AuditEventType::Export => "export",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(AuditEventType::Login),
+            1 => Some(AuditEventType::LoginFailed),
+            2 => Some(AuditEventType::MessageRead),
+            3 => Some(AuditEventType::MessageDelete),
+            4 => Some(AuditEventType::MailboxDelete),
+            5 => Some(AuditEventType::AclChange),
+            6 => Some(AuditEventType::Export),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 7;
+}
+
+impl serde::Serialize for AuditEventType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AuditEventType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for AzureEnvironment {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -1052,6 +1123,59 @@ impl<'de> serde::Deserialize<'de> for BlockReason {
     }
 }
 
+impl EnumImpl for CalendarInvitationPolicy {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"default" => CalendarInvitationPolicy::Default,
+            b"autoAdd" => CalendarInvitationPolicy::AutoAdd,
+            b"disabled" => CalendarInvitationPolicy::Disabled,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            CalendarInvitationPolicy::Default => "default",
+            CalendarInvitationPolicy::AutoAdd => "autoAdd",
+            CalendarInvitationPolicy::Disabled => "disabled",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(CalendarInvitationPolicy::Default),
+            1 => Some(CalendarInvitationPolicy::AutoAdd),
+            2 => Some(CalendarInvitationPolicy::Disabled),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 3;
+}
+
+impl serde::Serialize for CalendarInvitationPolicy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for CalendarInvitationPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for CertificateManagementType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -1102,6 +1226,77 @@ impl<'de> serde::Deserialize<'de> for CertificateManagementType {
     }
 }
 
+impl EnumImpl for ChangeLogCollection {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"email" => ChangeLogCollection::Email,
+            b"thread" => ChangeLogCollection::Thread,
+            b"calendar" => ChangeLogCollection::Calendar,
+            b"addressBook" => ChangeLogCollection::AddressBook,
+            b"fileNode" => ChangeLogCollection::FileNode,
+            b"identity" => ChangeLogCollection::Identity,
+            b"emailSubmission" => ChangeLogCollection::EmailSubmission,
+            b"sieveScript" => ChangeLogCollection::SieveScript,
+            b"calendarEventNotification" => ChangeLogCollection::CalendarEventNotification,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ChangeLogCollection::Email => "email",
+            ChangeLogCollection::Thread => "thread",
+            ChangeLogCollection::Calendar => "calendar",
+            ChangeLogCollection::AddressBook => "addressBook",
+            ChangeLogCollection::FileNode => "fileNode",
+            ChangeLogCollection::Identity => "identity",
+            ChangeLogCollection::EmailSubmission => "emailSubmission",
+            ChangeLogCollection::SieveScript => "sieveScript",
+            ChangeLogCollection::CalendarEventNotification => "calendarEventNotification",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(ChangeLogCollection::Email),
+            1 => Some(ChangeLogCollection::Thread),
+            2 => Some(ChangeLogCollection::Calendar),
+            3 => Some(ChangeLogCollection::AddressBook),
+            4 => Some(ChangeLogCollection::FileNode),
+            5 => Some(ChangeLogCollection::Identity),
+            6 => Some(ChangeLogCollection::EmailSubmission),
+            7 => Some(ChangeLogCollection::SieveScript),
+            8 => Some(ChangeLogCollection::CalendarEventNotification),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 9;
+}
+
+impl serde::Serialize for ChangeLogCollection {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ChangeLogCollection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for ClusterListenerGroupType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -1804,6 +1999,56 @@ impl<'de> serde::Deserialize<'de> for DirectoryBootstrapType {
     }
 }
 
+impl EnumImpl for DirectoryLookupMode {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"firstMatch" => DirectoryLookupMode::FirstMatch,
+            b"merge" => DirectoryLookupMode::Merge,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            DirectoryLookupMode::FirstMatch => "firstMatch",
+            DirectoryLookupMode::Merge => "merge",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(DirectoryLookupMode::FirstMatch),
+            1 => Some(DirectoryLookupMode::Merge),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for DirectoryLookupMode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DirectoryLookupMode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for DirectoryType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -3460,6 +3705,59 @@ impl<'de> serde::Deserialize<'de> for DnsServerType {
     }
 }
 
+impl EnumImpl for DuplicateDelivery {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"disabled" => DuplicateDelivery::Disabled,
+            b"discard" => DuplicateDelivery::Discard,
+            b"flag" => DuplicateDelivery::Flag,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            DuplicateDelivery::Disabled => "disabled",
+            DuplicateDelivery::Discard => "discard",
+            DuplicateDelivery::Flag => "flag",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(DuplicateDelivery::Disabled),
+            1 => Some(DuplicateDelivery::Discard),
+            2 => Some(DuplicateDelivery::Flag),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 3;
+}
+
+impl serde::Serialize for DuplicateDelivery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DuplicateDelivery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for EncryptionAtRestType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -3592,6 +3890,9 @@ impl EnumImpl for ExpressionConstant {
             b"mixer" => ExpressionConstant::Mixer,
             b"stanag4406" => ExpressionConstant::Stanag4406,
             b"nsep" => ExpressionConstant::Nsep,
+            b"reject" => ExpressionConstant::Reject,
+            b"rewrite" => ExpressionConstant::Rewrite,
+            b"strict_dry_run" => ExpressionConstant::StrictDryRun,
         }
     }
 
@@ -3616,6 +3917,9 @@ impl EnumImpl for ExpressionConstant {
             ExpressionConstant::Mixer => "mixer",
             ExpressionConstant::Stanag4406 => "stanag4406",
             ExpressionConstant::Nsep => "nsep",
+            ExpressionConstant::Reject => "reject",
+            ExpressionConstant::Rewrite => "rewrite",
+            ExpressionConstant::StrictDryRun => "strict_dry_run",
         }
     }
 
@@ -3644,11 +3948,14 @@ impl EnumImpl for ExpressionConstant {
             16 => Some(ExpressionConstant::Mixer),
             17 => Some(ExpressionConstant::Stanag4406),
             18 => Some(ExpressionConstant::Nsep),
+            19 => Some(ExpressionConstant::Reject),
+            20 => Some(ExpressionConstant::Rewrite),
+            21 => Some(ExpressionConstant::StrictDryRun),
             _ => None,
         }
     }
 
-    const COUNT: usize = 19;
+    const COUNT: usize = 22;
 }
 
 impl serde::Serialize for ExpressionConstant {
@@ -3692,6 +3999,10 @@ impl EnumImpl for ExpressionVariable {
             b"cc.domain" => ExpressionVariable::CcDomain,
             b"cc.local" => ExpressionVariable::CcLocal,
             b"cc.name" => ExpressionVariable::CcName,
+            b"cert_dns" => ExpressionVariable::CertDns,
+            b"cert_email" => ExpressionVariable::CertEmail,
+            b"cert_fingerprint" => ExpressionVariable::CertFingerprint,
+            b"cert_subject" => ExpressionVariable::CertSubject,
             b"country" => ExpressionVariable::Country,
             b"domain" => ExpressionVariable::Domain,
             b"email" => ExpressionVariable::Email,
@@ -3753,6 +4064,9 @@ impl EnumImpl for ExpressionVariable {
             b"scheme" => ExpressionVariable::Scheme,
             b"sender" => ExpressionVariable::Sender,
             b"sender_domain" => ExpressionVariable::SenderDomain,
+            b"sender_reputation" => ExpressionVariable::SenderReputation,
+            b"sender_verify" => ExpressionVariable::SenderVerify,
+            b"session_id" => ExpressionVariable::SessionId,
             b"size" => ExpressionVariable::Size,
             b"sld" => ExpressionVariable::Sld,
             b"source" => ExpressionVariable::Source,
@@ -3789,6 +4103,10 @@ impl EnumImpl for ExpressionVariable {
             ExpressionVariable::CcDomain => "cc.domain",
             ExpressionVariable::CcLocal => "cc.local",
             ExpressionVariable::CcName => "cc.name",
+            ExpressionVariable::CertDns => "cert_dns",
+            ExpressionVariable::CertEmail => "cert_email",
+            ExpressionVariable::CertFingerprint => "cert_fingerprint",
+            ExpressionVariable::CertSubject => "cert_subject",
             ExpressionVariable::Country => "country",
             ExpressionVariable::Domain => "domain",
             ExpressionVariable::Email => "email",
@@ -3850,6 +4168,9 @@ impl EnumImpl for ExpressionVariable {
             ExpressionVariable::Scheme => "scheme",
             ExpressionVariable::Sender => "sender",
             ExpressionVariable::SenderDomain => "sender_domain",
+            ExpressionVariable::SenderReputation => "sender_reputation",
+            ExpressionVariable::SenderVerify => "sender_verify",
+            ExpressionVariable::SessionId => "session_id",
             ExpressionVariable::Size => "size",
             ExpressionVariable::Sld => "sld",
             ExpressionVariable::Source => "source",
@@ -3963,11 +4284,18 @@ impl EnumImpl for ExpressionVariable {
             88 => Some(ExpressionVariable::Url),
             89 => Some(ExpressionVariable::Value),
             90 => Some(ExpressionVariable::ValueLower),
+            91 => Some(ExpressionVariable::SenderVerify),
+            92 => Some(ExpressionVariable::SessionId),
+            93 => Some(ExpressionVariable::CertDns),
+            94 => Some(ExpressionVariable::CertEmail),
+            95 => Some(ExpressionVariable::CertFingerprint),
+            96 => Some(ExpressionVariable::CertSubject),
+            97 => Some(ExpressionVariable::SenderReputation),
             _ => None,
         }
     }
 
-    const COUNT: usize = 91;
+    const COUNT: usize = 98;
 }
 
 impl serde::Serialize for ExpressionVariable {
@@ -4045,6 +4373,62 @@ impl<'de> serde::Deserialize<'de> for FailureReportingOption {
     }
 }
 
+impl EnumImpl for HealthCheckDependency {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"dataStore" => HealthCheckDependency::DataStore,
+            b"blobStore" => HealthCheckDependency::BlobStore,
+            b"inMemoryStore" => HealthCheckDependency::InMemoryStore,
+            b"searchStore" => HealthCheckDependency::SearchStore,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            HealthCheckDependency::DataStore => "dataStore",
+            HealthCheckDependency::BlobStore => "blobStore",
+            HealthCheckDependency::InMemoryStore => "inMemoryStore",
+            HealthCheckDependency::SearchStore => "searchStore",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(HealthCheckDependency::DataStore),
+            1 => Some(HealthCheckDependency::BlobStore),
+            2 => Some(HealthCheckDependency::InMemoryStore),
+            3 => Some(HealthCheckDependency::SearchStore),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 4;
+}
+
+impl serde::Serialize for HealthCheckDependency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for HealthCheckDependency {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for HttpAuthType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -4103,6 +4487,7 @@ impl EnumImpl for HttpLookupFormatType {
         hashify::tiny_map! {
             value.as_bytes(),
             b"Csv" => HttpLookupFormatType::Csv,
+            b"Json" => HttpLookupFormatType::Json,
             b"List" => HttpLookupFormatType::List,
         }
     }
@@ -4110,6 +4495,7 @@ impl EnumImpl for HttpLookupFormatType {
     fn as_str(&self) -> &'static str {
         match self {
             HttpLookupFormatType::Csv => "Csv",
+            HttpLookupFormatType::Json => "Json",
             HttpLookupFormatType::List => "List",
         }
     }
@@ -4122,11 +4508,12 @@ impl EnumImpl for HttpLookupFormatType {
         match id {
             0 => Some(HttpLookupFormatType::Csv),
             1 => Some(HttpLookupFormatType::List),
+            2 => Some(HttpLookupFormatType::Json),
             _ => None,
         }
     }
 
-    const COUNT: usize = 2;
+    const COUNT: usize = 3;
 }
 
 impl serde::Serialize for HttpLookupFormatType {
@@ -5730,6 +6117,115 @@ impl<'de> serde::Deserialize<'de> for MessageFlag {
     }
 }
 
+impl EnumImpl for MessageImportFormat {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"maildir" => MessageImportFormat::Maildir,
+            b"mbox" => MessageImportFormat::Mbox,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MessageImportFormat::Maildir => "maildir",
+            MessageImportFormat::Mbox => "mbox",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(MessageImportFormat::Maildir),
+            1 => Some(MessageImportFormat::Mbox),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for MessageImportFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MessageImportFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
+impl EnumImpl for MessageTemplateId {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"dsnFailure" => MessageTemplateId::DsnFailure,
+            b"dsnDelay" => MessageTemplateId::DsnDelay,
+            b"dsnSuccess" => MessageTemplateId::DsnSuccess,
+            b"quotaWarning" => MessageTemplateId::QuotaWarning,
+            b"autoReply" => MessageTemplateId::AutoReply,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MessageTemplateId::DsnFailure => "dsnFailure",
+            MessageTemplateId::DsnDelay => "dsnDelay",
+            MessageTemplateId::DsnSuccess => "dsnSuccess",
+            MessageTemplateId::QuotaWarning => "quotaWarning",
+            MessageTemplateId::AutoReply => "autoReply",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(MessageTemplateId::DsnFailure),
+            1 => Some(MessageTemplateId::DsnDelay),
+            2 => Some(MessageTemplateId::DsnSuccess),
+            3 => Some(MessageTemplateId::QuotaWarning),
+            4 => Some(MessageTemplateId::AutoReply),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 5;
+}
+
+impl serde::Serialize for MessageTemplateId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MessageTemplateId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for MetricType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -6150,16 +6646,69 @@ impl EnumImpl for MtaDeliveryScheduleIntervalsOrDefaultType {
 
     fn from_id(id: u16) -> Option<Self> {
         match id {
-            0 => Some(MtaDeliveryScheduleIntervalsOrDefaultType::Default),
-            1 => Some(MtaDeliveryScheduleIntervalsOrDefaultType::Custom),
+            0 => Some(MtaDeliveryScheduleIntervalsOrDefaultType::Default),
+            1 => Some(MtaDeliveryScheduleIntervalsOrDefaultType::Custom),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for MtaDeliveryScheduleIntervalsOrDefaultType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MtaDeliveryScheduleIntervalsOrDefaultType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
+impl EnumImpl for MtaHookProtocol {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"grpc" => MtaHookProtocol::Grpc,
+            b"json" => MtaHookProtocol::Json,
+            b"stream" => MtaHookProtocol::Stream,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MtaHookProtocol::Grpc => "grpc",
+            MtaHookProtocol::Json => "json",
+            MtaHookProtocol::Stream => "stream",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(MtaHookProtocol::Json),
+            1 => Some(MtaHookProtocol::Stream),
+            2 => Some(MtaHookProtocol::Grpc),
             _ => None,
         }
     }
 
-    const COUNT: usize = 2;
+    const COUNT: usize = 3;
 }
 
-impl serde::Serialize for MtaDeliveryScheduleIntervalsOrDefaultType {
+impl serde::Serialize for MtaHookProtocol {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
@@ -6168,7 +6717,7 @@ impl serde::Serialize for MtaDeliveryScheduleIntervalsOrDefaultType {
     }
 }
 
-impl<'de> serde::Deserialize<'de> for MtaDeliveryScheduleIntervalsOrDefaultType {
+impl<'de> serde::Deserialize<'de> for MtaHookProtocol {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
@@ -6249,6 +6798,56 @@ impl<'de> serde::Deserialize<'de> for MtaInboundThrottleKey {
     }
 }
 
+impl EnumImpl for MtaIpSelection {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"weighted" => MtaIpSelection::Weighted,
+            b"roundRobin" => MtaIpSelection::RoundRobin,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MtaIpSelection::Weighted => "weighted",
+            MtaIpSelection::RoundRobin => "roundRobin",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(MtaIpSelection::Weighted),
+            1 => Some(MtaIpSelection::RoundRobin),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for MtaIpSelection {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MtaIpSelection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for MtaIpStrategy {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -6526,6 +7125,110 @@ impl<'de> serde::Deserialize<'de> for MtaRequiredOrOptional {
     }
 }
 
+impl EnumImpl for MtaResponseId {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"arcReject" => MtaResponseId::ArcReject,
+            b"arcTempFail" => MtaResponseId::ArcTempFail,
+            b"dkimReject" => MtaResponseId::DkimReject,
+            b"dkimTempFail" => MtaResponseId::DkimTempFail,
+            b"dmarcReject" => MtaResponseId::DmarcReject,
+            b"dmarcTempFail" => MtaResponseId::DmarcTempFail,
+            b"fromNotAuthorized" => MtaResponseId::FromNotAuthorized,
+            b"iprevReject" => MtaResponseId::IprevReject,
+            b"iprevTempFail" => MtaResponseId::IprevTempFail,
+            b"loopDetected" => MtaResponseId::LoopDetected,
+            b"mailboxNotFound" => MtaResponseId::MailboxNotFound,
+            b"queueTempFail" => MtaResponseId::QueueTempFail,
+            b"quotaFull" => MtaResponseId::QuotaFull,
+            b"rateLimited" => MtaResponseId::RateLimited,
+            b"relayDenied" => MtaResponseId::RelayDenied,
+            b"reverseIpRequired" => MtaResponseId::ReverseIpRequired,
+            b"senderNotAllowed" => MtaResponseId::SenderNotAllowed,
+            b"spamReject" => MtaResponseId::SpamReject,
+            b"spfReject" => MtaResponseId::SpfReject,
+            b"spfTempFail" => MtaResponseId::SpfTempFail,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            MtaResponseId::ArcReject => "arcReject",
+            MtaResponseId::ArcTempFail => "arcTempFail",
+            MtaResponseId::DkimReject => "dkimReject",
+            MtaResponseId::DkimTempFail => "dkimTempFail",
+            MtaResponseId::DmarcReject => "dmarcReject",
+            MtaResponseId::DmarcTempFail => "dmarcTempFail",
+            MtaResponseId::FromNotAuthorized => "fromNotAuthorized",
+            MtaResponseId::IprevReject => "iprevReject",
+            MtaResponseId::IprevTempFail => "iprevTempFail",
+            MtaResponseId::LoopDetected => "loopDetected",
+            MtaResponseId::MailboxNotFound => "mailboxNotFound",
+            MtaResponseId::QueueTempFail => "queueTempFail",
+            MtaResponseId::QuotaFull => "quotaFull",
+            MtaResponseId::RateLimited => "rateLimited",
+            MtaResponseId::RelayDenied => "relayDenied",
+            MtaResponseId::ReverseIpRequired => "reverseIpRequired",
+            MtaResponseId::SenderNotAllowed => "senderNotAllowed",
+            MtaResponseId::SpamReject => "spamReject",
+            MtaResponseId::SpfReject => "spfReject",
+            MtaResponseId::SpfTempFail => "spfTempFail",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(MtaResponseId::ArcReject),
+            1 => Some(MtaResponseId::ArcTempFail),
+            2 => Some(MtaResponseId::DkimReject),
+            3 => Some(MtaResponseId::DkimTempFail),
+            4 => Some(MtaResponseId::DmarcReject),
+            5 => Some(MtaResponseId::DmarcTempFail),
+            6 => Some(MtaResponseId::FromNotAuthorized),
+            7 => Some(MtaResponseId::IprevReject),
+            8 => Some(MtaResponseId::IprevTempFail),
+            9 => Some(MtaResponseId::LoopDetected),
+            10 => Some(MtaResponseId::MailboxNotFound),
+            11 => Some(MtaResponseId::QueueTempFail),
+            12 => Some(MtaResponseId::QuotaFull),
+            13 => Some(MtaResponseId::RateLimited),
+            14 => Some(MtaResponseId::RelayDenied),
+            15 => Some(MtaResponseId::SenderNotAllowed),
+            16 => Some(MtaResponseId::SpamReject),
+            17 => Some(MtaResponseId::SpfReject),
+            18 => Some(MtaResponseId::SpfTempFail),
+            19 => Some(MtaResponseId::ReverseIpRequired),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 20;
+}
+
+impl serde::Serialize for MtaResponseId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MtaResponseId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for MtaRouteType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -6885,13 +7588,26 @@ impl EnumImpl for Permission {
         hashify::map! {
             value.as_bytes(),
             Permission,
+            b"actionSignOutEverywhere" => Permission::ActionSignOutEverywhere,
             b"authenticate" => Permission::Authenticate,
             b"authenticateWithAlias" => Permission::AuthenticateWithAlias,
+            b"imapUrlAuth" => Permission::ImapUrlAuth,
             b"interactAi" => Permission::InteractAi,
             b"impersonate" => Permission::Impersonate,
+            b"jmapEmailRecall" => Permission::JmapEmailRecall,
+            b"jmapSieveLogQuery" => Permission::JmapSieveLogQuery,
+            b"oAuthDeviceAuthorize" => Permission::OAuthDeviceAuthorize,
+            b"senderReputationGet" => Permission::SenderReputationGet,
+            b"senderReputationReset" => Permission::SenderReputationReset,
+            b"sessionList" => Permission::SessionList,
+            b"sessionTerminate" => Permission::SessionTerminate,
+            b"sieveRedirectGet" => Permission::SieveRedirectGet,
+            b"sieveRedirectReset" => Permission::SieveRedirectReset,
+            b"storeBackup" => Permission::StoreBackup,
             b"unlimitedRequests" => Permission::UnlimitedRequests,
             b"unlimitedUploads" => Permission::UnlimitedUploads,
             b"fetchAnyBlob" => Permission::FetchAnyBlob,
+            b"queuedMessagePreview" => Permission::QueuedMessagePreview,
             b"emailSend" => Permission::EmailSend,
             b"emailReceive" => Permission::EmailReceive,
             b"calendarAlarmsSend" => Permission::CalendarAlarmsSend,
@@ -7130,6 +7846,7 @@ impl EnumImpl for Permission {
             b"actionInvalidateNegativeCaches" => Permission::ActionInvalidateNegativeCaches,
             b"actionPauseMtaQueue" => Permission::ActionPauseMtaQueue,
             b"actionResumeMtaQueue" => Permission::ActionResumeMtaQueue,
+            b"actionDrainServer" => Permission::ActionDrainServer,
             b"sysActionGet" => Permission::SysActionGet,
             b"sysActionCreate" => Permission::SysActionCreate,
             b"sysActionUpdate" => Permission::SysActionUpdate,
@@ -7179,6 +7896,11 @@ impl EnumImpl for Permission {
             b"sysArfExternalReportQuery" => Permission::SysArfExternalReportQuery,
             b"sysAsnGet" => Permission::SysAsnGet,
             b"sysAsnUpdate" => Permission::SysAsnUpdate,
+            b"sysAuditEventGet" => Permission::SysAuditEventGet,
+            b"sysAuditEventCreate" => Permission::SysAuditEventCreate,
+            b"sysAuditEventUpdate" => Permission::SysAuditEventUpdate,
+            b"sysAuditEventDestroy" => Permission::SysAuditEventDestroy,
+            b"sysAuditEventQuery" => Permission::SysAuditEventQuery,
             b"sysAuthenticationGet" => Permission::SysAuthenticationGet,
             b"sysAuthenticationUpdate" => Permission::SysAuthenticationUpdate,
             b"sysBlobStoreGet" => Permission::SysBlobStoreGet,
@@ -7219,6 +7941,11 @@ impl EnumImpl for Permission {
             b"sysDataRetentionUpdate" => Permission::SysDataRetentionUpdate,
             b"sysDataStoreGet" => Permission::SysDataStoreGet,
             b"sysDataStoreUpdate" => Permission::SysDataStoreUpdate,
+            b"sysDeliveryCallbackGet" => Permission::SysDeliveryCallbackGet,
+            b"sysDeliveryCallbackCreate" => Permission::SysDeliveryCallbackCreate,
+            b"sysDeliveryCallbackUpdate" => Permission::SysDeliveryCallbackUpdate,
+            b"sysDeliveryCallbackDestroy" => Permission::SysDeliveryCallbackDestroy,
+            b"sysDeliveryCallbackQuery" => Permission::SysDeliveryCallbackQuery,
             b"sysDirectoryGet" => Permission::SysDirectoryGet,
             b"sysDirectoryCreate" => Permission::SysDirectoryCreate,
             b"sysDirectoryUpdate" => Permission::SysDirectoryUpdate,
@@ -7288,6 +8015,11 @@ impl EnumImpl for Permission {
             b"sysLogUpdate" => Permission::SysLogUpdate,
             b"sysLogDestroy" => Permission::SysLogDestroy,
             b"sysLogQuery" => Permission::SysLogQuery,
+            b"sysMailCategoryGet" => Permission::SysMailCategoryGet,
+            b"sysMailCategoryCreate" => Permission::SysMailCategoryCreate,
+            b"sysMailCategoryUpdate" => Permission::SysMailCategoryUpdate,
+            b"sysMailCategoryDestroy" => Permission::SysMailCategoryDestroy,
+            b"sysMailCategoryQuery" => Permission::SysMailCategoryQuery,
             b"sysMailingListGet" => Permission::SysMailingListGet,
             b"sysMailingListCreate" => Permission::SysMailingListCreate,
             b"sysMailingListUpdate" => Permission::SysMailingListUpdate,
@@ -7308,6 +8040,11 @@ impl EnumImpl for Permission {
             b"sysMemoryLookupKeyValueUpdate" => Permission::SysMemoryLookupKeyValueUpdate,
             b"sysMemoryLookupKeyValueDestroy" => Permission::SysMemoryLookupKeyValueDestroy,
             b"sysMemoryLookupKeyValueQuery" => Permission::SysMemoryLookupKeyValueQuery,
+            b"sysMessageTemplateGet" => Permission::SysMessageTemplateGet,
+            b"sysMessageTemplateCreate" => Permission::SysMessageTemplateCreate,
+            b"sysMessageTemplateUpdate" => Permission::SysMessageTemplateUpdate,
+            b"sysMessageTemplateDestroy" => Permission::SysMessageTemplateDestroy,
+            b"sysMessageTemplateQuery" => Permission::SysMessageTemplateQuery,
             b"sysMetricGet" => Permission::SysMetricGet,
             b"sysMetricCreate" => Permission::SysMetricCreate,
             b"sysMetricUpdate" => Permission::SysMetricUpdate,
@@ -7358,6 +8095,11 @@ impl EnumImpl for Permission {
             b"sysMtaQueueQuotaUpdate" => Permission::SysMtaQueueQuotaUpdate,
             b"sysMtaQueueQuotaDestroy" => Permission::SysMtaQueueQuotaDestroy,
             b"sysMtaQueueQuotaQuery" => Permission::SysMtaQueueQuotaQuery,
+            b"sysMtaResponseGet" => Permission::SysMtaResponseGet,
+            b"sysMtaResponseCreate" => Permission::SysMtaResponseCreate,
+            b"sysMtaResponseUpdate" => Permission::SysMtaResponseUpdate,
+            b"sysMtaResponseDestroy" => Permission::SysMtaResponseDestroy,
+            b"sysMtaResponseQuery" => Permission::SysMtaResponseQuery,
             b"sysMtaRouteGet" => Permission::SysMtaRouteGet,
             b"sysMtaRouteCreate" => Permission::SysMtaRouteCreate,
             b"sysMtaRouteUpdate" => Permission::SysMtaRouteUpdate,
@@ -7382,6 +8124,8 @@ impl EnumImpl for Permission {
             b"sysMtaTlsStrategyUpdate" => Permission::SysMtaTlsStrategyUpdate,
             b"sysMtaTlsStrategyDestroy" => Permission::SysMtaTlsStrategyDestroy,
             b"sysMtaTlsStrategyQuery" => Permission::SysMtaTlsStrategyQuery,
+            b"sysMtaUnsubscribeGet" => Permission::SysMtaUnsubscribeGet,
+            b"sysMtaUnsubscribeUpdate" => Permission::SysMtaUnsubscribeUpdate,
             b"sysMtaVirtualQueueGet" => Permission::SysMtaVirtualQueueGet,
             b"sysMtaVirtualQueueCreate" => Permission::SysMtaVirtualQueueCreate,
             b"sysMtaVirtualQueueUpdate" => Permission::SysMtaVirtualQueueUpdate,
@@ -7440,6 +8184,8 @@ impl EnumImpl for Permission {
             b"sysSieveUserScriptUpdate" => Permission::SysSieveUserScriptUpdate,
             b"sysSieveUserScriptDestroy" => Permission::SysSieveUserScriptDestroy,
             b"sysSieveUserScriptQuery" => Permission::SysSieveUserScriptQuery,
+            b"sysSpamClamAvGet" => Permission::SysSpamClamAvGet,
+            b"sysSpamClamAvUpdate" => Permission::SysSpamClamAvUpdate,
             b"sysSpamClassifierGet" => Permission::SysSpamClassifierGet,
             b"sysSpamClassifierUpdate" => Permission::SysSpamClassifierUpdate,
             b"sysSpamDnsblServerGet" => Permission::SysSpamDnsblServerGet,
@@ -7502,6 +8248,15 @@ impl EnumImpl for Permission {
             b"taskAcmeRenewal" => Permission::TaskAcmeRenewal,
             b"taskDkimManagement" => Permission::TaskDkimManagement,
             b"taskDnsManagement" => Permission::TaskDnsManagement,
+            b"taskDaneRollover" => Permission::TaskDaneRollover,
+            b"taskFollowUpReminder" => Permission::TaskFollowUpReminder,
+            b"taskDeliveryCallback" => Permission::TaskDeliveryCallback,
+            b"taskReEncryptAccount" => Permission::TaskReEncryptAccount,
+            b"taskReindexAccount" => Permission::TaskReindexAccount,
+            b"taskImportMessages" => Permission::TaskImportMessages,
+            b"taskQuotaWarning" => Permission::TaskQuotaWarning,
+            b"taskEmailSubmission" => Permission::TaskEmailSubmission,
+            b"taskRestoreSnoozedEmail" => Permission::TaskRestoreSnoozedEmail,
             b"sysTaskGet" => Permission::SysTaskGet,
             b"sysTaskCreate" => Permission::SysTaskCreate,
             b"sysTaskUpdate" => Permission::SysTaskUpdate,
@@ -7536,6 +8291,11 @@ impl EnumImpl for Permission {
             b"sysTracerUpdate" => Permission::SysTracerUpdate,
             b"sysTracerDestroy" => Permission::SysTracerDestroy,
             b"sysTracerQuery" => Permission::SysTracerQuery,
+            b"sysUnsubscribeEventGet" => Permission::SysUnsubscribeEventGet,
+            b"sysUnsubscribeEventCreate" => Permission::SysUnsubscribeEventCreate,
+            b"sysUnsubscribeEventUpdate" => Permission::SysUnsubscribeEventUpdate,
+            b"sysUnsubscribeEventDestroy" => Permission::SysUnsubscribeEventDestroy,
+            b"sysUnsubscribeEventQuery" => Permission::SysUnsubscribeEventQuery,
             b"sysTracingStoreGet" => Permission::SysTracingStoreGet,
             b"sysTracingStoreUpdate" => Permission::SysTracingStoreUpdate,
             b"sysWebDavGet" => Permission::SysWebDavGet,
@@ -7551,13 +8311,26 @@ impl EnumImpl for Permission {
 
     fn as_str(&self) -> &'static str {
         match self {
+            Permission::ActionSignOutEverywhere => "actionSignOutEverywhere",
             Permission::Authenticate => "authenticate",
             Permission::AuthenticateWithAlias => "authenticateWithAlias",
+            Permission::ImapUrlAuth => "imapUrlAuth",
             Permission::InteractAi => "interactAi",
             Permission::Impersonate => "impersonate",
+            Permission::JmapEmailRecall => "jmapEmailRecall",
+            Permission::JmapSieveLogQuery => "jmapSieveLogQuery",
+            Permission::OAuthDeviceAuthorize => "oAuthDeviceAuthorize",
+            Permission::SenderReputationGet => "senderReputationGet",
+            Permission::SenderReputationReset => "senderReputationReset",
+            Permission::SessionList => "sessionList",
+            Permission::SessionTerminate => "sessionTerminate",
+            Permission::SieveRedirectGet => "sieveRedirectGet",
+            Permission::SieveRedirectReset => "sieveRedirectReset",
+            Permission::StoreBackup => "storeBackup",
             Permission::UnlimitedRequests => "unlimitedRequests",
             Permission::UnlimitedUploads => "unlimitedUploads",
             Permission::FetchAnyBlob => "fetchAnyBlob",
+            Permission::QueuedMessagePreview => "queuedMessagePreview",
             Permission::EmailSend => "emailSend",
             Permission::EmailReceive => "emailReceive",
             Permission::CalendarAlarmsSend => "calendarAlarmsSend",
@@ -7806,6 +8579,7 @@ impl EnumImpl for Permission {
             Permission::ActionInvalidateNegativeCaches => "actionInvalidateNegativeCaches",
             Permission::ActionPauseMtaQueue => "actionPauseMtaQueue",
             Permission::ActionResumeMtaQueue => "actionResumeMtaQueue",
+            Permission::ActionDrainServer => "actionDrainServer",
             Permission::SysActionGet => "sysActionGet",
             Permission::SysActionCreate => "sysActionCreate",
             Permission::SysActionUpdate => "sysActionUpdate",
@@ -7855,6 +8629,11 @@ impl EnumImpl for Permission {
             Permission::SysArfExternalReportQuery => "sysArfExternalReportQuery",
             Permission::SysAsnGet => "sysAsnGet",
             Permission::SysAsnUpdate => "sysAsnUpdate",
+            Permission::SysAuditEventGet => "sysAuditEventGet",
+            Permission::SysAuditEventCreate => "sysAuditEventCreate",
+            Permission::SysAuditEventUpdate => "sysAuditEventUpdate",
+            Permission::SysAuditEventDestroy => "sysAuditEventDestroy",
+            Permission::SysAuditEventQuery => "sysAuditEventQuery",
             Permission::SysAuthenticationGet => "sysAuthenticationGet",
             Permission::SysAuthenticationUpdate => "sysAuthenticationUpdate",
             Permission::SysBlobStoreGet => "sysBlobStoreGet",
@@ -7895,6 +8674,11 @@ impl EnumImpl for Permission {
             Permission::SysDataRetentionUpdate => "sysDataRetentionUpdate",
             Permission::SysDataStoreGet => "sysDataStoreGet",
             Permission::SysDataStoreUpdate => "sysDataStoreUpdate",
+            Permission::SysDeliveryCallbackGet => "sysDeliveryCallbackGet",
+            Permission::SysDeliveryCallbackCreate => "sysDeliveryCallbackCreate",
+            Permission::SysDeliveryCallbackUpdate => "sysDeliveryCallbackUpdate",
+            Permission::SysDeliveryCallbackDestroy => "sysDeliveryCallbackDestroy",
+            Permission::SysDeliveryCallbackQuery => "sysDeliveryCallbackQuery",
             Permission::SysDirectoryGet => "sysDirectoryGet",
             Permission::SysDirectoryCreate => "sysDirectoryCreate",
             Permission::SysDirectoryUpdate => "sysDirectoryUpdate",
@@ -7964,6 +8748,11 @@ impl EnumImpl for Permission {
             Permission::SysLogUpdate => "sysLogUpdate",
             Permission::SysLogDestroy => "sysLogDestroy",
             Permission::SysLogQuery => "sysLogQuery",
+            Permission::SysMailCategoryGet => "sysMailCategoryGet",
+            Permission::SysMailCategoryCreate => "sysMailCategoryCreate",
+            Permission::SysMailCategoryUpdate => "sysMailCategoryUpdate",
+            Permission::SysMailCategoryDestroy => "sysMailCategoryDestroy",
+            Permission::SysMailCategoryQuery => "sysMailCategoryQuery",
             Permission::SysMailingListGet => "sysMailingListGet",
             Permission::SysMailingListCreate => "sysMailingListCreate",
             Permission::SysMailingListUpdate => "sysMailingListUpdate",
@@ -7984,6 +8773,11 @@ impl EnumImpl for Permission {
             Permission::SysMemoryLookupKeyValueUpdate => "sysMemoryLookupKeyValueUpdate",
             Permission::SysMemoryLookupKeyValueDestroy => "sysMemoryLookupKeyValueDestroy",
             Permission::SysMemoryLookupKeyValueQuery => "sysMemoryLookupKeyValueQuery",
+            Permission::SysMessageTemplateGet => "sysMessageTemplateGet",
+            Permission::SysMessageTemplateCreate => "sysMessageTemplateCreate",
+            Permission::SysMessageTemplateUpdate => "sysMessageTemplateUpdate",
+            Permission::SysMessageTemplateDestroy => "sysMessageTemplateDestroy",
+            Permission::SysMessageTemplateQuery => "sysMessageTemplateQuery",
             Permission::SysMetricGet => "sysMetricGet",
             Permission::SysMetricCreate => "sysMetricCreate",
             Permission::SysMetricUpdate => "sysMetricUpdate",
@@ -8034,6 +8828,11 @@ impl EnumImpl for Permission {
             Permission::SysMtaQueueQuotaUpdate => "sysMtaQueueQuotaUpdate",
             Permission::SysMtaQueueQuotaDestroy => "sysMtaQueueQuotaDestroy",
             Permission::SysMtaQueueQuotaQuery => "sysMtaQueueQuotaQuery",
+            Permission::SysMtaResponseGet => "sysMtaResponseGet",
+            Permission::SysMtaResponseCreate => "sysMtaResponseCreate",
+            Permission::SysMtaResponseUpdate => "sysMtaResponseUpdate",
+            Permission::SysMtaResponseDestroy => "sysMtaResponseDestroy",
+            Permission::SysMtaResponseQuery => "sysMtaResponseQuery",
             Permission::SysMtaRouteGet => "sysMtaRouteGet",
             Permission::SysMtaRouteCreate => "sysMtaRouteCreate",
             Permission::SysMtaRouteUpdate => "sysMtaRouteUpdate",
@@ -8058,6 +8857,8 @@ impl EnumImpl for Permission {
             Permission::SysMtaTlsStrategyUpdate => "sysMtaTlsStrategyUpdate",
             Permission::SysMtaTlsStrategyDestroy => "sysMtaTlsStrategyDestroy",
             Permission::SysMtaTlsStrategyQuery => "sysMtaTlsStrategyQuery",
+            Permission::SysMtaUnsubscribeGet => "sysMtaUnsubscribeGet",
+            Permission::SysMtaUnsubscribeUpdate => "sysMtaUnsubscribeUpdate",
             Permission::SysMtaVirtualQueueGet => "sysMtaVirtualQueueGet",
             Permission::SysMtaVirtualQueueCreate => "sysMtaVirtualQueueCreate",
             Permission::SysMtaVirtualQueueUpdate => "sysMtaVirtualQueueUpdate",
@@ -8116,6 +8917,8 @@ impl EnumImpl for Permission {
             Permission::SysSieveUserScriptUpdate => "sysSieveUserScriptUpdate",
             Permission::SysSieveUserScriptDestroy => "sysSieveUserScriptDestroy",
             Permission::SysSieveUserScriptQuery => "sysSieveUserScriptQuery",
+            Permission::SysSpamClamAvGet => "sysSpamClamAvGet",
+            Permission::SysSpamClamAvUpdate => "sysSpamClamAvUpdate",
             Permission::SysSpamClassifierGet => "sysSpamClassifierGet",
             Permission::SysSpamClassifierUpdate => "sysSpamClassifierUpdate",
             Permission::SysSpamDnsblServerGet => "sysSpamDnsblServerGet",
@@ -8178,6 +8981,15 @@ impl EnumImpl for Permission {
             Permission::TaskAcmeRenewal => "taskAcmeRenewal",
             Permission::TaskDkimManagement => "taskDkimManagement",
             Permission::TaskDnsManagement => "taskDnsManagement",
+            Permission::TaskDaneRollover => "taskDaneRollover",
+            Permission::TaskFollowUpReminder => "taskFollowUpReminder",
+            Permission::TaskDeliveryCallback => "taskDeliveryCallback",
+            Permission::TaskReEncryptAccount => "taskReEncryptAccount",
+            Permission::TaskReindexAccount => "taskReindexAccount",
+            Permission::TaskImportMessages => "taskImportMessages",
+            Permission::TaskQuotaWarning => "taskQuotaWarning",
+            Permission::TaskEmailSubmission => "taskEmailSubmission",
+            Permission::TaskRestoreSnoozedEmail => "taskRestoreSnoozedEmail",
             Permission::SysTaskGet => "sysTaskGet",
             Permission::SysTaskCreate => "sysTaskCreate",
             Permission::SysTaskUpdate => "sysTaskUpdate",
@@ -8212,6 +9024,11 @@ impl EnumImpl for Permission {
             Permission::SysTracerUpdate => "sysTracerUpdate",
             Permission::SysTracerDestroy => "sysTracerDestroy",
             Permission::SysTracerQuery => "sysTracerQuery",
+            Permission::SysUnsubscribeEventGet => "sysUnsubscribeEventGet",
+            Permission::SysUnsubscribeEventCreate => "sysUnsubscribeEventCreate",
+            Permission::SysUnsubscribeEventUpdate => "sysUnsubscribeEventUpdate",
+            Permission::SysUnsubscribeEventDestroy => "sysUnsubscribeEventDestroy",
+            Permission::SysUnsubscribeEventQuery => "sysUnsubscribeEventQuery",
             Permission::SysTracingStoreGet => "sysTracingStoreGet",
             Permission::SysTracingStoreUpdate => "sysTracingStoreUpdate",
             Permission::SysWebDavGet => "sysWebDavGet",
@@ -8475,6 +9292,7 @@ impl EnumImpl for Permission {
             241 => Some(Permission::ActionInvalidateNegativeCaches),
             242 => Some(Permission::ActionPauseMtaQueue),
             243 => Some(Permission::ActionResumeMtaQueue),
+            681 => Some(Permission::ActionDrainServer),
             244 => Some(Permission::SysActionGet),
             245 => Some(Permission::SysActionCreate),
             246 => Some(Permission::SysActionUpdate),
@@ -8564,6 +9382,36 @@ impl EnumImpl for Permission {
             330 => Some(Permission::SysDataRetentionUpdate),
             331 => Some(Permission::SysDataStoreGet),
             332 => Some(Permission::SysDataStoreUpdate),
+            660 => Some(Permission::SysDeliveryCallbackGet),
+            661 => Some(Permission::SysDeliveryCallbackCreate),
+            662 => Some(Permission::SysDeliveryCallbackUpdate),
+            663 => Some(Permission::SysDeliveryCallbackDestroy),
+            664 => Some(Permission::SysDeliveryCallbackQuery),
+            665 => Some(Permission::SysSpamClamAvGet),
+            666 => Some(Permission::SysSpamClamAvUpdate),
+            667 => Some(Permission::SysAuditEventGet),
+            668 => Some(Permission::SysAuditEventCreate),
+            669 => Some(Permission::SysAuditEventUpdate),
+            670 => Some(Permission::SysAuditEventDestroy),
+            671 => Some(Permission::SysAuditEventQuery),
+            672 => Some(Permission::TaskReEncryptAccount),
+            673 => Some(Permission::TaskReindexAccount),
+            674 => Some(Permission::TaskImportMessages),
+            675 => Some(Permission::TaskQuotaWarning),
+            683 => Some(Permission::TaskEmailSubmission),
+            700 => Some(Permission::TaskRestoreSnoozedEmail),
+            701 => Some(Permission::SysMtaUnsubscribeGet),
+            702 => Some(Permission::SysMtaUnsubscribeUpdate),
+            703 => Some(Permission::SysUnsubscribeEventGet),
+            704 => Some(Permission::SysUnsubscribeEventCreate),
+            705 => Some(Permission::SysUnsubscribeEventUpdate),
+            706 => Some(Permission::SysUnsubscribeEventDestroy),
+            707 => Some(Permission::SysUnsubscribeEventQuery),
+            676 => Some(Permission::SysMtaResponseGet),
+            677 => Some(Permission::SysMtaResponseCreate),
+            678 => Some(Permission::SysMtaResponseUpdate),
+            679 => Some(Permission::SysMtaResponseDestroy),
+            680 => Some(Permission::SysMtaResponseQuery),
             333 => Some(Permission::SysDirectoryGet),
             334 => Some(Permission::SysDirectoryCreate),
             335 => Some(Permission::SysDirectoryUpdate),
@@ -8847,6 +9695,14 @@ impl EnumImpl for Permission {
             613 => Some(Permission::TaskAcmeRenewal),
             614 => Some(Permission::TaskDkimManagement),
             615 => Some(Permission::TaskDnsManagement),
+            708 => Some(Permission::TaskDaneRollover),
+            709 => Some(Permission::TaskFollowUpReminder),
+            716 => Some(Permission::TaskDeliveryCallback),
+            710 => Some(Permission::SysMailCategoryGet),
+            711 => Some(Permission::SysMailCategoryCreate),
+            712 => Some(Permission::SysMailCategoryUpdate),
+            713 => Some(Permission::SysMailCategoryDestroy),
+            714 => Some(Permission::SysMailCategoryQuery),
             616 => Some(Permission::SysTaskGet),
             617 => Some(Permission::SysTaskCreate),
             618 => Some(Permission::SysTaskUpdate),
@@ -8890,11 +9746,29 @@ impl EnumImpl for Permission {
             656 => Some(Permission::SysWebHookUpdate),
             657 => Some(Permission::SysWebHookDestroy),
             658 => Some(Permission::SysWebHookQuery),
+            682 => Some(Permission::QueuedMessagePreview),
+            684 => Some(Permission::SessionList),
+            685 => Some(Permission::SessionTerminate),
+            686 => Some(Permission::SieveRedirectGet),
+            687 => Some(Permission::SieveRedirectReset),
+            688 => Some(Permission::ImapUrlAuth),
+            689 => Some(Permission::ActionSignOutEverywhere),
+            690 => Some(Permission::JmapSieveLogQuery),
+            691 => Some(Permission::StoreBackup),
+            692 => Some(Permission::OAuthDeviceAuthorize),
+            693 => Some(Permission::SenderReputationGet),
+            694 => Some(Permission::SenderReputationReset),
+            695 => Some(Permission::SysMessageTemplateGet),
+            696 => Some(Permission::SysMessageTemplateCreate),
+            697 => Some(Permission::SysMessageTemplateUpdate),
+            698 => Some(Permission::SysMessageTemplateDestroy),
+            699 => Some(Permission::SysMessageTemplateQuery),
+            715 => Some(Permission::JmapEmailRecall),
             _ => None,
         }
     }
 
-    const COUNT: usize = 660;
+    const COUNT: usize = 717;
 }
 
 impl serde::Serialize for Permission {
@@ -9251,14 +10125,20 @@ impl EnumImpl for RecipientFlag {
         hashify::tiny_map! {
             value.as_bytes(),
             b"dsnSent" => RecipientFlag::DsnSent,
+            b"expiredAttempts" => RecipientFlag::ExpiredAttempts,
+            b"expiredTtl" => RecipientFlag::ExpiredTtl,
             b"spamPayload" => RecipientFlag::SpamPayload,
+            b"split" => RecipientFlag::Split,
         }
     }
 
     fn as_str(&self) -> &'static str {
         match self {
             RecipientFlag::DsnSent => "dsnSent",
+            RecipientFlag::ExpiredAttempts => "expiredAttempts",
+            RecipientFlag::ExpiredTtl => "expiredTtl",
             RecipientFlag::SpamPayload => "spamPayload",
+            RecipientFlag::Split => "split",
         }
     }
 
@@ -9270,11 +10150,14 @@ impl EnumImpl for RecipientFlag {
         match id {
             0 => Some(RecipientFlag::DsnSent),
             1 => Some(RecipientFlag::SpamPayload),
+            2 => Some(RecipientFlag::ExpiredTtl),
+            3 => Some(RecipientFlag::ExpiredAttempts),
+            4 => Some(RecipientFlag::Split),
             _ => None,
         }
     }
 
-    const COUNT: usize = 2;
+    const COUNT: usize = 5;
 }
 
 impl serde::Serialize for RecipientFlag {
@@ -10298,6 +11181,56 @@ impl<'de> serde::Deserialize<'de> for ServiceProtocol {
     }
 }
 
+impl EnumImpl for SharedBlobQuota {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"chargeEachReference" => SharedBlobQuota::ChargeEachReference,
+            b"chargeOnce" => SharedBlobQuota::ChargeOnce,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SharedBlobQuota::ChargeEachReference => "chargeEachReference",
+            SharedBlobQuota::ChargeOnce => "chargeOnce",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(SharedBlobQuota::ChargeEachReference),
+            1 => Some(SharedBlobQuota::ChargeOnce),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for SharedBlobQuota {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SharedBlobQuota {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for SieveCapability {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -10489,6 +11422,56 @@ impl<'de> serde::Deserialize<'de> for SieveCapability {
     }
 }
 
+impl EnumImpl for SieveRedirectSender {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"original" => SieveRedirectSender::Original,
+            b"account" => SieveRedirectSender::Account,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SieveRedirectSender::Original => "original",
+            SieveRedirectSender::Account => "account",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(SieveRedirectSender::Original),
+            1 => Some(SieveRedirectSender::Account),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 2;
+}
+
+impl serde::Serialize for SieveRedirectSender {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SieveRedirectSender {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for Sig0Algorithm {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -10995,6 +11978,59 @@ impl<'de> serde::Deserialize<'de> for SpamTagType {
     }
 }
 
+impl EnumImpl for SpamVirusAction {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"reject" => SpamVirusAction::Reject,
+            b"quarantine" => SpamVirusAction::Quarantine,
+            b"tag" => SpamVirusAction::Tag,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            SpamVirusAction::Reject => "reject",
+            SpamVirusAction::Quarantine => "quarantine",
+            SpamVirusAction::Tag => "tag",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(SpamVirusAction::Reject),
+            1 => Some(SpamVirusAction::Quarantine),
+            2 => Some(SpamVirusAction::Tag),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 3;
+}
+
+impl serde::Serialize for SpamVirusAction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SpamVirusAction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
+
 impl EnumImpl for SpecialUse {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
@@ -11405,6 +12441,7 @@ impl EnumImpl for TaskAccountMaintenanceType {
         hashify::tiny_map! {
             value.as_bytes(),
             b"purge" => TaskAccountMaintenanceType::Purge,
+            b"recountBlobReferences" => TaskAccountMaintenanceType::RecountBlobReferences,
             b"reindex" => TaskAccountMaintenanceType::Reindex,
             b"recalculateImapUid" => TaskAccountMaintenanceType::RecalculateImapUid,
             b"recalculateQuota" => TaskAccountMaintenanceType::RecalculateQuota,
@@ -11414,6 +12451,7 @@ impl EnumImpl for TaskAccountMaintenanceType {
     fn as_str(&self) -> &'static str {
         match self {
             TaskAccountMaintenanceType::Purge => "purge",
+            TaskAccountMaintenanceType::RecountBlobReferences => "recountBlobReferences",
             TaskAccountMaintenanceType::Reindex => "reindex",
             TaskAccountMaintenanceType::RecalculateImapUid => "recalculateImapUid",
             TaskAccountMaintenanceType::RecalculateQuota => "recalculateQuota",
@@ -11430,11 +12468,12 @@ impl EnumImpl for TaskAccountMaintenanceType {
             1 => Some(TaskAccountMaintenanceType::Reindex),
             2 => Some(TaskAccountMaintenanceType::RecalculateImapUid),
             3 => Some(TaskAccountMaintenanceType::RecalculateQuota),
+            4 => Some(TaskAccountMaintenanceType::RecountBlobReferences),
             _ => None,
         }
     }
 
-    const COUNT: usize = 4;
+    const COUNT: usize = 5;
 }
 
 impl serde::Serialize for TaskAccountMaintenanceType {
@@ -11622,6 +12661,9 @@ impl EnumImpl for TaskStoreMaintenanceType {
     fn parse(value: &str) -> Option<Self> {
         hashify::tiny_map! {
             value.as_bytes(),
+            b"fullBackup" => TaskStoreMaintenanceType::FullBackup,
+            b"incrementalBackup" => TaskStoreMaintenanceType::IncrementalBackup,
+            b"recountBlobReferences" => TaskStoreMaintenanceType::RecountBlobReferences,
             b"reindexAccounts" => TaskStoreMaintenanceType::ReindexAccounts,
             b"reindexTelemetry" => TaskStoreMaintenanceType::ReindexTelemetry,
             b"purgeAccounts" => TaskStoreMaintenanceType::PurgeAccounts,
@@ -11637,11 +12679,15 @@ impl EnumImpl for TaskStoreMaintenanceType {
             b"removeLockDav" => TaskStoreMaintenanceType::RemoveLockDav,
             b"removeSieveId" => TaskStoreMaintenanceType::RemoveSieveId,
             b"removeGreylist" => TaskStoreMaintenanceType::RemoveGreylist,
+            b"reconcileQuotas" => TaskStoreMaintenanceType::ReconcileQuotas,
         }
     }
 
     fn as_str(&self) -> &'static str {
         match self {
+            TaskStoreMaintenanceType::FullBackup => "fullBackup",
+            TaskStoreMaintenanceType::IncrementalBackup => "incrementalBackup",
+            TaskStoreMaintenanceType::RecountBlobReferences => "recountBlobReferences",
             TaskStoreMaintenanceType::ReindexAccounts => "reindexAccounts",
             TaskStoreMaintenanceType::ReindexTelemetry => "reindexTelemetry",
             TaskStoreMaintenanceType::PurgeAccounts => "purgeAccounts",
@@ -11657,6 +12703,7 @@ impl EnumImpl for TaskStoreMaintenanceType {
             TaskStoreMaintenanceType::RemoveLockDav => "removeLockDav",
             TaskStoreMaintenanceType::RemoveSieveId => "removeSieveId",
             TaskStoreMaintenanceType::RemoveGreylist => "removeGreylist",
+            TaskStoreMaintenanceType::ReconcileQuotas => "reconcileQuotas",
         }
     }
 
@@ -11681,11 +12728,15 @@ impl EnumImpl for TaskStoreMaintenanceType {
             12 => Some(TaskStoreMaintenanceType::RemoveLockDav),
             13 => Some(TaskStoreMaintenanceType::RemoveSieveId),
             14 => Some(TaskStoreMaintenanceType::RemoveGreylist),
+            15 => Some(TaskStoreMaintenanceType::ReconcileQuotas),
+            16 => Some(TaskStoreMaintenanceType::RecountBlobReferences),
+            17 => Some(TaskStoreMaintenanceType::FullBackup),
+            18 => Some(TaskStoreMaintenanceType::IncrementalBackup),
             _ => None,
         }
     }
 
-    const COUNT: usize = 15;
+    const COUNT: usize = 19;
 }
 
 impl serde::Serialize for TaskStoreMaintenanceType {
@@ -11776,6 +12827,15 @@ impl EnumImpl for TaskType {
             b"AcmeRenewal" => TaskType::AcmeRenewal,
             b"DkimManagement" => TaskType::DkimManagement,
             b"DnsManagement" => TaskType::DnsManagement,
+            b"ReEncryptAccount" => TaskType::ReEncryptAccount,
+            b"ReindexAccount" => TaskType::ReindexAccount,
+            b"ImportMessages" => TaskType::ImportMessages,
+            b"QuotaWarning" => TaskType::QuotaWarning,
+            b"EmailSubmission" => TaskType::EmailSubmission,
+            b"RestoreSnoozedEmail" => TaskType::RestoreSnoozedEmail,
+            b"DaneRollover" => TaskType::DaneRollover,
+            b"FollowUpReminder" => TaskType::FollowUpReminder,
+            b"DeliveryCallback" => TaskType::DeliveryCallback,
         }
     }
 
@@ -11799,6 +12859,15 @@ impl EnumImpl for TaskType {
             TaskType::AcmeRenewal => "AcmeRenewal",
             TaskType::DkimManagement => "DkimManagement",
             TaskType::DnsManagement => "DnsManagement",
+            TaskType::ReEncryptAccount => "ReEncryptAccount",
+            TaskType::ReindexAccount => "ReindexAccount",
+            TaskType::ImportMessages => "ImportMessages",
+            TaskType::QuotaWarning => "QuotaWarning",
+            TaskType::EmailSubmission => "EmailSubmission",
+            TaskType::RestoreSnoozedEmail => "RestoreSnoozedEmail",
+            TaskType::DaneRollover => "DaneRollover",
+            TaskType::FollowUpReminder => "FollowUpReminder",
+            TaskType::DeliveryCallback => "DeliveryCallback",
         }
     }
 
@@ -11826,11 +12895,20 @@ impl EnumImpl for TaskType {
             15 => Some(TaskType::AcmeRenewal),
             16 => Some(TaskType::DkimManagement),
             17 => Some(TaskType::DnsManagement),
+            18 => Some(TaskType::ReEncryptAccount),
+            19 => Some(TaskType::ReindexAccount),
+            20 => Some(TaskType::ImportMessages),
+            21 => Some(TaskType::QuotaWarning),
+            22 => Some(TaskType::EmailSubmission),
+            23 => Some(TaskType::RestoreSnoozedEmail),
+            24 => Some(TaskType::DaneRollover),
+            25 => Some(TaskType::FollowUpReminder),
+            26 => Some(TaskType::DeliveryCallback),
             _ => None,
         }
     }
 
-    const COUNT: usize = 18;
+    const COUNT: usize = 27;
 }
 
 impl serde::Serialize for TaskType {
@@ -14480,3 +15558,68 @@ impl<'de> serde::Deserialize<'de> for UserRolesType {
         Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
     }
 }
+
+impl EnumImpl for Weekday {
+    fn parse(value: &str) -> Option<Self> {
+        hashify::tiny_map! {
+            value.as_bytes(),
+            b"monday" => Weekday::Monday,
+            b"tuesday" => Weekday::Tuesday,
+            b"wednesday" => Weekday::Wednesday,
+            b"thursday" => Weekday::Thursday,
+            b"friday" => Weekday::Friday,
+            b"saturday" => Weekday::Saturday,
+            b"sunday" => Weekday::Sunday,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "monday",
+            Weekday::Tuesday => "tuesday",
+            Weekday::Wednesday => "wednesday",
+            Weekday::Thursday => "thursday",
+            Weekday::Friday => "friday",
+            Weekday::Saturday => "saturday",
+            Weekday::Sunday => "sunday",
+        }
+    }
+
+    fn to_id(&self) -> u16 {
+        *self as u16
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0 => Some(Weekday::Monday),
+            1 => Some(Weekday::Tuesday),
+            2 => Some(Weekday::Wednesday),
+            3 => Some(Weekday::Thursday),
+            4 => Some(Weekday::Friday),
+            5 => Some(Weekday::Saturday),
+            6 => Some(Weekday::Sunday),
+            _ => None,
+        }
+    }
+
+    const COUNT: usize = 7;
+}
+
+impl serde::Serialize for Weekday {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Weekday {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = Cow::<str>::deserialize(deserializer)?;
+        Self::parse(&s).ok_or_else(|| serde::de::Error::unknown_variant(&s, &[]))
+    }
+}
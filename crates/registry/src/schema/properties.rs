@@ -25,6 +25,7 @@ pub enum ObjectInner {
     ArchivedItem(ArchivedItem),
     ArfExternalReport(ArfExternalReport),
     Asn(Asn),
+    AuditEvent(AuditEvent),
     Authentication(Authentication),
     BlobStore(BlobStore),
     BlockedIp(BlockedIp),
@@ -39,6 +40,7 @@ pub enum ObjectInner {
     Coordinator(Coordinator),
     DataRetention(DataRetention),
     DataStore(DataStore),
+    DeliveryCallback(DeliveryCallback),
     Directory(Directory),
     DkimReportSettings(DkimReportSettings),
     DkimSignature(DkimSignature),
@@ -60,10 +62,12 @@ pub enum ObjectInner {
     InMemoryStore(InMemoryStore),
     Jmap(Jmap),
     Log(Log),
+    MailCategory(MailCategory),
     MailingList(MailingList),
     MaskedEmail(MaskedEmail),
     MemoryLookupKey(MemoryLookupKey),
     MemoryLookupKeyValue(MemoryLookupKeyValue),
+    MessageTemplate(MessageTemplate),
     Metric(Metric),
     Metrics(Metrics),
     MetricsStore(MetricsStore),
@@ -77,6 +81,7 @@ pub enum ObjectInner {
     MtaOutboundStrategy(MtaOutboundStrategy),
     MtaOutboundThrottle(MtaOutboundThrottle),
     MtaQueueQuota(MtaQueueQuota),
+    MtaResponse(MtaResponse),
     MtaRoute(MtaRoute),
     MtaStageAuth(MtaStageAuth),
     MtaStageConnect(MtaStageConnect),
@@ -86,6 +91,7 @@ pub enum ObjectInner {
     MtaStageRcpt(MtaStageRcpt),
     MtaSts(MtaSts),
     MtaTlsStrategy(MtaTlsStrategy),
+    MtaUnsubscribe(MtaUnsubscribe),
     MtaVirtualQueue(MtaVirtualQueue),
     NetworkListener(NetworkListener),
     OAuthClient(OAuthClient),
@@ -103,6 +109,7 @@ pub enum ObjectInner {
     SieveSystemScript(SieveSystemScript),
     SieveUserInterpreter(SieveUserInterpreter),
     SieveUserScript(SieveUserScript),
+    SpamClamAv(SpamClamAv),
     SpamClassifier(SpamClassifier),
     SpamDnsblServer(SpamDnsblServer),
     SpamDnsblSettings(SpamDnsblSettings),
@@ -125,6 +132,7 @@ pub enum ObjectInner {
     Trace(Trace),
     Tracer(Tracer),
     TracingStore(TracingStore),
+    UnsubscribeEvent(UnsubscribeEvent),
     WebDav(WebDav),
     WebHook(WebHook),
 }
@@ -147,6 +155,7 @@ pub enum ObjectType {
     ArchivedItem = 12,
     ArfExternalReport = 13,
     Asn = 14,
+    AuditEvent = 119,
     Authentication = 15,
     BlobStore = 16,
     BlockedIp = 17,
@@ -161,6 +170,7 @@ pub enum ObjectType {
     Coordinator = 26,
     DataRetention = 27,
     DataStore = 28,
+    DeliveryCallback = 117,
     Directory = 29,
     DkimReportSettings = 30,
     DkimSignature = 31,
@@ -182,10 +192,12 @@ pub enum ObjectType {
     InMemoryStore = 47,
     Jmap = 48,
     Log = 49,
+    MailCategory = 124,
     MailingList = 50,
     MaskedEmail = 51,
     MemoryLookupKey = 52,
     MemoryLookupKeyValue = 53,
+    MessageTemplate = 121,
     Metric = 54,
     Metrics = 55,
     MetricsStore = 56,
@@ -199,6 +211,7 @@ pub enum ObjectType {
     MtaOutboundStrategy = 64,
     MtaOutboundThrottle = 65,
     MtaQueueQuota = 66,
+    MtaResponse = 120,
     MtaRoute = 67,
     MtaStageAuth = 68,
     MtaStageConnect = 69,
@@ -208,6 +221,7 @@ pub enum ObjectType {
     MtaStageRcpt = 73,
     MtaSts = 74,
     MtaTlsStrategy = 75,
+    MtaUnsubscribe = 122,
     MtaVirtualQueue = 76,
     NetworkListener = 77,
     OAuthClient = 78,
@@ -225,6 +239,7 @@ pub enum ObjectType {
     SieveSystemScript = 90,
     SieveUserInterpreter = 91,
     SieveUserScript = 92,
+    SpamClamAv = 118,
     SpamClassifier = 93,
     SpamDnsblServer = 94,
     SpamDnsblSettings = 95,
@@ -247,6 +262,7 @@ pub enum ObjectType {
     Trace = 112,
     Tracer = 113,
     TracingStore = 114,
+    UnsubscribeEvent = 123,
     WebDav = 115,
     WebHook = 116,
 }
@@ -272,6 +288,8 @@ pub enum Property {
     AccountUri = 16,
     Accounts = 151,
     AcmeProviderId = 182,
+    Action = 941,
+    ActorId = 943,
     AddAuthResultsHeader = 554,
     AddDateHeader = 555,
     AddDeliveredToHeader = 556,
@@ -279,6 +297,7 @@ pub enum Property {
     AddReceivedHeader = 558,
     AddReceivedSpfHeader = 559,
     AddReturnPathHeader = 560,
+    AdditionalHosts = 1033,
     AdditionalInformation = 838,
     Address = 44,
     Addresses = 579,
@@ -286,6 +305,7 @@ pub enum Property {
     AggregateDkimSignDomain = 274,
     AggregateFromAddress = 269,
     AggregateFromName = 270,
+    AggregateMaxCompressedSize = 1029,
     AggregateMaxReportSize = 271,
     AggregateOrgName = 272,
     AggregateSendFrequency = 273,
@@ -293,6 +313,7 @@ pub enum Property {
     AlarmId = 798,
     Algorithms = 225,
     Aliases = 339,
+    AllColumns = 1025,
     AllowCount = 768,
     AllowDirectoryQueries = 695,
     AllowExternalRcpts = 164,
@@ -314,6 +335,11 @@ pub enum Property {
     ArcVerify = 690,
     ArchiveDeletedAccountsFor = 203,
     ArchiveDeletedItemsFor = 202,
+    ArchiveMaxDepth = 997,
+    ArchiveMaxEntries = 998,
+    ArchiveMaxRatio = 999,
+    ArchiveMaxSize = 1000,
+    ArchiveScanTimeout = 1001,
     ArchivedAt = 58,
     ArchivedItemType = 820,
     ArchivedUntil = 59,
@@ -321,13 +347,17 @@ pub enum Property {
     AsnUrls = 102,
     AttemptNumber = 829,
     Attempts = 303,
+    AttrCategorizeMail = 1099,
     AttrClass = 470,
     AttrDescription = 471,
     AttrEmail = 472,
     AttrEmailAlias = 473,
+    AttrEnabledProtocols = 1076,
     AttrMemberOf = 474,
     AttrSecret = 475,
     AttrSecretChanged = 476,
+    AuditLog = 945,
+    AuditMessageReads = 946,
     Auid = 215,
     Auth = 897,
     AuthBanPeriod = 680,
@@ -343,8 +373,14 @@ pub enum Property {
     AuthenticationResults = 69,
     AutoAddInvitations = 171,
     AutoUpdateFrequency = 53,
+    BackupDirectory = 1094,
+    BackupIncludeBlobs = 1096,
+    BackupMaxIncrements = 1095,
     BaseDn = 463,
     BaseUrl = 882,
+    BatvKey = 1055,
+    BatvPreviousKey = 1056,
+    BatvValidity = 1057,
     BearerToken = 403,
     Beta = 389,
     Bind = 589,
@@ -361,29 +397,39 @@ pub enum Property {
     Bucket = 658,
     BufferSize = 656,
     Buffered = 863,
+    CalendarInvitations = 1011,
+    CallbackId = 1089,
+    CampaignId = 1066,
     Canonicalization = 216,
     CapacityClient = 584,
     CapacityReadBuffer = 585,
     CapacitySubscription = 586,
     CatchAllAddress = 346,
     Categories = 759,
+    CategorizeMail = 1086,
     Certificate = 176,
     CertificateManagement = 342,
     ChallengeType = 10,
     ChangesMaxResults = 435,
     Chunking = 517,
+    ClaimCategorizeMail = 1101,
+    ClaimEnabledProtocols = 1098,
     ClaimGroups = 612,
     ClaimName = 611,
     ClaimUsername = 609,
     Cleartext = 693,
+    ClientCertAccount = 1014,
+    ClientCertRevocationList = 1105,
     ClientId = 604,
     ClientIp = 898,
     ClientSecret = 878,
     ClientToken = 889,
     ClusterFile = 382,
+    ColumnCategorizeMail = 1100,
     ColumnClass = 781,
     ColumnDescription = 782,
     ColumnEmail = 779,
+    ColumnEnabledProtocols = 1077,
     ColumnSecret = 780,
     Comment = 240,
     CompartmentOcid = 905,
@@ -408,6 +454,7 @@ pub enum Property {
     Create = 367,
     CreatedAt = 46,
     CreatedBy = 486,
+    CredentialGeneration = 1031,
     CredentialId = 627,
     Credentials = 588,
     CurrentSecret = 4,
@@ -416,6 +463,8 @@ pub enum Property {
     CustomRule = 787,
     CustomerNumber = 899,
     Dane = 569,
+    DaneMaxDelay = 1072,
+    DaneRollover = 1071,
     DataCleanupSchedule = 199,
     DataStore = 125,
     DataTimeout = 506,
@@ -426,6 +475,7 @@ pub enum Property {
     DateRangeStart = 845,
     Day = 192,
     DeadPropertyMaxSize = 868,
+    Deduplicate = 967,
     DefaultAdminRoleIds = 108,
     DefaultCertificateId = 790,
     DefaultDisplayName = 20,
@@ -453,12 +503,21 @@ pub enum Property {
     DeliverAt = 238,
     DeliverBy = 518,
     DeliverTo = 404,
+    DeliveryCallback = 1102,
+    DeliveryDays = 948,
+    DeliveryHourEnd = 950,
+    DeliveryHourStart = 949,
     DeliveryResult = 82,
+    DeliveryTimeZone = 951,
     Depth = 381,
     Description = 6,
     Details = 297,
     Directory = 12,
+    DirectoryCacheTtl = 1040,
+    DirectoryChain = 1038,
     DirectoryId = 104,
+    DirectoryLookupMode = 1039,
+    DirectoryPrincipals = 1041,
     DisableCapabilities = 711,
     DisabledPermissions = 629,
     DiscardAfter = 872,
@@ -506,6 +565,8 @@ pub enum Property {
     Domains = 146,
     Dsn = 519,
     Due = 797,
+    DuplicateDelivery = 987,
+    DuplicateDeliveryWindow = 988,
     DuplicateExpiry = 699,
     Duration = 515,
     EabHmacKey = 13,
@@ -529,10 +590,12 @@ pub enum Property {
     EnableEdns = 305,
     EnableHsts = 399,
     EnableLogExporter = 860,
+    EnableRspamdApi = 972,
     EnableSpamFilter = 562,
     EnableSpanExporter = 861,
     Enabled = 50,
     EnabledPermissions = 628,
+    EnabledProtocols = 1075,
     EncryptAtRest = 358,
     EncryptOnAppend = 357,
     EncryptionAtRest = 9,
@@ -563,6 +626,7 @@ pub enum Property {
     EventStartTz = 802,
     Events = 142,
     EventsPolicy = 855,
+    ExpansionTraceHeader = 996,
     Expire = 217,
     Expires = 100,
     ExpiresAt = 47,
@@ -579,9 +643,11 @@ pub enum Property {
     ExtraContactInfo = 243,
     Factor = 821,
     FailOnTimeout = 490,
+    FailOpen = 942,
     FailedAt = 826,
     FailedAttemptNumber = 827,
     FailedSessionCount = 837,
+    FailureCoolOff = 1036,
     FailureDetails = 851,
     FailureDkimSignDomain = 279,
     FailureFromAddress = 276,
@@ -590,6 +656,7 @@ pub enum Property {
     FailureReasonCode = 839,
     FailureSendFrequency = 278,
     FailureSubject = 280,
+    FailureThreshold = 1035,
     FeatureL2Normalize = 738,
     FeatureLogScale = 739,
     FeedbackType = 67,
@@ -603,6 +670,7 @@ pub enum Property {
     FilterMailbox = 468,
     FilterMemberOf = 469,
     Fingerprint = 902,
+    FirstSeen = 1092,
     Flags = 638,
     FlagsAction = 537,
     FlagsProtocol = 538,
@@ -611,6 +679,7 @@ pub enum Property {
     Format = 415,
     From = 62,
     FromAddress = 39,
+    FromAlignment = 958,
     FromEmail = 165,
     FromName = 40,
     FutureRelease = 521,
@@ -624,14 +693,19 @@ pub enum Property {
     GroupId = 460,
     HeaderFrom = 265,
     Headers = 93,
+    HealthCheckInterval = 1037,
+    HiddenExtensions = 979,
+    HoldAuditEventsFor = 947,
     HoldMetricsFor = 206,
     HoldMtaReportsFor = 204,
     HoldSamplesFor = 730,
     HoldTracesFor = 205,
     Host = 333,
+    HostSelection = 1034,
     HostedZoneId = 331,
     Hostname = 185,
     Hour = 190,
+    HtmlBody = 1060,
     HttpAuth = 32,
     HttpHeaders = 33,
     HttpRsvpEnable = 168,
@@ -644,9 +718,12 @@ pub enum Property {
     Id = 1,
     IdTokenExpiry = 621,
     IdentityAlignment = 91,
+    IdleNotifyDelay = 965,
     If = 376,
     ImpersonateServiceAccount = 320,
     ImplicitTls = 546,
+    ImportErrors = 970,
+    ImportRoot = 971,
     InMemoryStore = 128,
     InboundReportAddresses = 651,
     InboundReportForwarding = 652,
@@ -695,7 +772,9 @@ pub enum Property {
     KeyValues = 853,
     L1Ratio = 391,
     L2Ratio = 392,
+    LastDocumentId = 952,
     LastRenewal = 186,
+    LastSeen = 1093,
     LearnHamFromCard = 727,
     LearnHamFromReply = 735,
     LearnSpamFromRblHits = 728,
@@ -706,7 +785,12 @@ pub enum Property {
     ListenerIds = 183,
     Listeners = 188,
     LivePropertyMaxSize = 869,
+    LmtpLocalDelivery = 1079,
     Locale = 7,
+    LogEnabled = 1043,
+    LogHeader = 1046,
+    LogMaxEntries = 1044,
+    LogRetention = 1045,
     Logo = 341,
     LogoUrl = 371,
     LoiterBanPeriod = 682,
@@ -717,10 +801,14 @@ pub enum Property {
     MailFrom = 284,
     MailFromTimeout = 509,
     MailRua = 841,
+    MailboxId = 959,
+    MailboxName = 1081,
     MailingLists = 154,
     MaintenanceType = 796,
     ManagedZone = 318,
     Match = 374,
+    MatchKeywords = 1083,
+    MatchTags = 1082,
     MaxAddressBooks = 23,
     MaxAge = 566,
     MaxAllowedPacket = 576,
@@ -731,6 +819,8 @@ pub enum Property {
     MaxAttendees = 157,
     MaxAuthFailures = 425,
     MaxCalendars = 160,
+    MaxChangesAge = 1047,
+    MaxChangesAgePerCollection = 1048,
     MaxChangesHistory = 201,
     MaxConcurrent = 426,
     MaxConcurrentRequests = 439,
@@ -739,14 +829,20 @@ pub enum Property {
     MaxContacts = 24,
     MaxCpuCycles = 702,
     MaxDelay = 823,
+    MaxDelayedSend = 1019,
+    MaxDeliveryCallbacks = 938,
     MaxDuration = 530,
     MaxEntries = 417,
     MaxEntrySize = 418,
     MaxEventNotifications = 163,
     MaxEvents = 161,
+    MaxExpansionDepth = 994,
+    MaxExpansionRecipients = 995,
+    MaxExpiryDuplicate = 991,
     MaxFailures = 547,
     MaxFiles = 378,
     MaxFolders = 379,
+    MaxFollowUps = 1080,
     MaxHeaderSize = 715,
     MaxICalendarSize = 159,
     MaxIdentities = 363,
@@ -775,7 +871,10 @@ pub enum Property {
     MaxRecipients = 173,
     MaxReconnects = 580,
     MaxRecurrenceExpansions = 158,
+    MaxRedirectDestinations = 1027,
+    MaxRedirectPasses = 1008,
     MaxRedirects = 705,
+    MaxRedirectsPerDay = 1026,
     MaxReportSize = 852,
     MaxRequestRate = 427,
     MaxRequestSize = 428,
@@ -786,6 +885,8 @@ pub enum Property {
     MaxScriptNameLength = 719,
     MaxScriptSize = 723,
     MaxScripts = 726,
+    MaxSessions = 1020,
+    MaxSessionsPerProtocol = 1021,
     MaxShares = 696,
     MaxSize = 101,
     MaxStringLength = 724,
@@ -802,12 +903,23 @@ pub enum Property {
     MessageIdHostname = 698,
     MessageIds = 819,
     Messages = 145,
+    MessagesFailed = 969,
+    MessagesImported = 968,
+    MessagesIndexed = 962,
+    MessagesProcessed = 954,
+    MessagesReEncrypted = 955,
+    MessagesSkipped = 956,
+    MessagesTotal = 953,
+    Metadata = 1078,
+    MethodProfiling = 1010,
     Metric = 493,
     Metrics = 497,
     MetricsCollectionInterval = 207,
     MetricsPolicy = 498,
     MinHamSamples = 731,
+    MinMatches = 1085,
     MinRetryWait = 649,
+    MinSenderMessages = 1084,
     MinSpamSamples = 732,
     MinTriggerInterval = 166,
     Minute = 191,
@@ -817,6 +929,9 @@ pub enum Property {
     ModelType = 30,
     MtPriority = 522,
     MtaSts = 570,
+    MtaStsMaxAge = 1024,
+    MtaStsMode = 1022,
+    MtaStsMxHosts = 1023,
     MtaStsTimeout = 572,
     Multiline = 859,
     MustMatchSender = 550,
@@ -839,6 +954,7 @@ pub enum Property {
     NumFeatures = 390,
     NumReplicas = 350,
     NumShards = 351,
+    ObjectIds = 944,
     OnSuccessRenewCertificate = 813,
     OpenTelemetry = 495,
     Options = 630,
@@ -917,6 +1033,10 @@ pub enum Property {
     Protocol = 298,
     ProtocolVersion = 533,
     ProviderInfo = 795,
+    ProvisionFolders = 975,
+    ProvisionSieveScript = 977,
+    ProvisionSignature = 978,
+    ProvisionSubscribe = 976,
     ProxyTrustedNetworks = 792,
     PublicKey = 218,
     PublishRecords = 302,
@@ -934,6 +1054,13 @@ pub enum Property {
     QueryRecipient = 784,
     QueueId = 514,
     QueueName = 644,
+    QueuePreviewSize = 1015,
+    QueueTopDomains = 990,
+    QuotaReconcileInterval = 1005,
+    QuotaReconcileSampleSize = 1006,
+    QuotaWarningInterval = 984,
+    QuotaWarningNotify = 983,
+    QuotaWarningThresholds = 982,
     Quotas = 394,
     Rate = 532,
     RateLimit = 410,
@@ -941,33 +1068,55 @@ pub enum Property {
     RateLimitAuthenticated = 396,
     Ratio = 767,
     RcptToTimeout = 510,
+    ReEncryptConcurrency = 957,
     ReadFromReplicas = 650,
+    ReadReplicaStickiness = 1106,
     ReadReplicas = 578,
+    ReadinessCacheTtl = 1097,
+    ReadinessCheckTimeout = 1061,
+    ReadinessOptional = 1063,
+    ReadinessTimeout = 1062,
     Reason = 45,
+    RecallNotice = 1088,
+    RecallWindow = 1087,
+    ReceivedAfter = 960,
     ReceivedAt = 63,
+    ReceivedBefore = 961,
     ReceivedFromIp = 636,
     ReceivedViaPort = 637,
     ReceivingIp = 836,
     ReceivingMxHelo = 835,
     ReceivingMxHostname = 834,
+    Recipient = 1068,
     Recipients = 484,
     Records = 256,
     RecurrenceId = 805,
+    RedirectEnvelopeSender = 1007,
     RedirectRoot = 911,
     RedirectUris = 605,
     Refresh = 419,
     RefreshTokenExpiry = 617,
     RefreshTokenRenewal = 618,
     Region = 330,
+    ReindexConcurrency = 963,
+    ReindexRateLimit = 964,
+    RejectHiddenExtensions = 980,
     RejectNonFqdn = 563,
     RemoteIp = 282,
     RenewBefore = 17,
     Report = 66,
     ReportAddressUri = 349,
+    ReportFromAddress = 1003,
     ReportId = 244,
     ReportedDomains = 74,
     ReportedUris = 75,
     ReportingMta = 76,
+    ReputationBadScore = 1053,
+    ReputationEnable = 1049,
+    ReputationGoodScore = 1052,
+    ReputationMinMessages = 1051,
+    ReputationSkipGreylist = 1054,
+    ReputationWindow = 1050,
     RequestMaxSize = 870,
     RequestTlsCertificate = 123,
     Require = 551,
@@ -982,18 +1131,23 @@ pub enum Property {
     ResponseEnhanced = 213,
     ResponseHeaders = 401,
     ResponseHostname = 211,
+    ResponseId = 989,
     ResponseMessage = 214,
     ResponsePosCategory = 761,
     ResponsePosConfidence = 762,
     ResponsePosExplanation = 763,
     Result = 233,
     ResultType = 832,
+    ResumeAfter = 1103,
     RetireAfter = 228,
     Retry = 420,
     RetryCount = 640,
     RetryDue = 641,
     ReturnPath = 635,
     ReuseKey = 912,
+    ReverseIpRequire = 1016,
+    ReverseIpRequireTempFail = 1017,
+    ReverseIpTimeout = 1018,
     ReverseIpVerify = 692,
     Rewrite = 565,
     RoleIds = 193,
@@ -1001,7 +1155,10 @@ pub enum Property {
     Rotate = 857,
     RotateAfter = 227,
     Route = 540,
+    RspamdApiMaxConcurrent = 974,
+    RspamdApiSecret = 973,
     Rua = 236,
+    RuaMaxSize = 1030,
     Sandbox = 896,
     SasToken = 119,
     SaslMechanisms = 549,
@@ -1017,6 +1174,8 @@ pub enum Property {
     ScoreReject = 772,
     ScoreSpam = 773,
     Script = 553,
+    SearchCacheSize = 923,
+    SearchCacheTtl = 924,
     SearchStore = 127,
     Secret = 3,
     SecretAccessKey = 328,
@@ -1027,6 +1186,14 @@ pub enum Property {
     Selector = 222,
     SelectorTemplate = 226,
     SendFrequency = 230,
+    Sender = 1067,
+    SenderVerify = 931,
+    SenderVerifyFailTtl = 934,
+    SenderVerifyMaxConcurrent = 936,
+    SenderVerifyPassTtl = 933,
+    SenderVerifyRate = 935,
+    SenderVerifySkipDomains = 932,
+    SenderVerifyTimeout = 937,
     SendingMtaIp = 833,
     SentinelSecret = 915,
     SentinelUsername = 914,
@@ -1039,7 +1206,10 @@ pub enum Property {
     SessionToken = 329,
     SetMaxObjects = 440,
     ShardIndex = 830,
+    SharedBlobQuota = 1064,
     SharedSecret = 895,
+    ShutdownGracePeriod = 993,
+    SieveLogging = 1042,
     Sig0Algorithm = 336,
     SignatureAlgorithm = 623,
     SignatureKey = 624,
@@ -1047,6 +1217,8 @@ pub enum Property {
     Size = 64,
     SkipDeploy = 885,
     SkipFirst = 423,
+    SlowMethodThreshold = 1009,
+    SlowMethodThresholdPerMethod = 1091,
     SmtpGreeting = 552,
     SnippetMaxResults = 441,
     SocketBacklog = 591,
@@ -1058,9 +1230,11 @@ pub enum Property {
     SocketTosV4 = 597,
     SocketTtl = 598,
     SourceIp = 77,
+    SourceIpSelection = 925,
     SourceIps = 504,
     SourcePort = 78,
     SpamFilterRulesUrl = 775,
+    SpamThreshold = 1004,
     SpfDns = 90,
     SpfEhloDomain = 285,
     SpfEhloResult = 286,
@@ -1069,15 +1243,22 @@ pub enum Property {
     SpfMailFromDomain = 287,
     SpfMailFromResult = 288,
     SpfResults = 267,
+    SplitFrom = 1032,
     Stage = 224,
+    StagedUntil = 1070,
     Stages = 529,
     StartTime = 56,
     StartTls = 571,
     Status = 61,
+    StepUpMaxAge = 986,
+    StepUpPermissions = 985,
     StorageAccount = 116,
     Store = 778,
     Stores = 694,
     Strategy = 816,
+    StreamChunkSize = 1065,
+    Strict = 1104,
+    StrictReferences = 1028,
     SubAddressing = 347,
     SubAuthId = 887,
     Subject = 41,
@@ -1087,8 +1268,11 @@ pub enum Property {
     Sum = 494,
     Summary = 808,
     SupportedLanguages = 666,
+    SuppressNoMx = 1073,
+    SuppressionList = 1074,
     Tag = 748,
     Tags = 746,
+    TargetMailbox = 966,
     TaskTypes = 189,
     Tasks = 187,
     TcpOnError = 307,
@@ -1096,16 +1280,19 @@ pub enum Property {
     TempFailOnError = 528,
     Temperature = 27,
     Template = 167,
+    TemplateId = 1058,
     TenancyOcid = 900,
     TenantId = 831,
     Tenants = 153,
     Text = 2,
+    TextBody = 1059,
     Then = 377,
     ThirdParty = 219,
     ThirdPartyHash = 220,
     ThreadName = 818,
     ThreadPoolSize = 791,
     ThreadsPerNode = 574,
+    Threshold = 981,
     Throttle = 862,
     TimeZone = 8,
     Timeout = 29,
@@ -1122,13 +1309,17 @@ pub enum Property {
     Timestamp = 482,
     Title = 55,
     Tls = 542,
+    TlsClientCaCertificates = 1013,
     TlsDisableCipherSuites = 599,
     TlsDisableProtocols = 600,
     TlsIgnoreClientOrder = 601,
     TlsImplicit = 602,
+    TlsRequestClientCert = 992,
+    TlsRequireClientCert = 1012,
     TlsTimeout = 573,
     To = 42,
     Token = 888,
+    TokenValidity = 1069,
     TotalDeadline = 817,
     TotalFailedSessions = 850,
     TotalSuccessfulSessions = 849,
@@ -1141,6 +1332,9 @@ pub enum Property {
     TransferLimit = 531,
     TrustContacts = 769,
     TrustReplies = 774,
+    TrustedForwarderDepth = 940,
+    TrustedForwarders = 939,
+    TrustedRedirectHosts = 1090,
     TsigAlgorithm = 338,
     Ttl = 310,
     UnpackDirectory = 54,
@@ -1169,11 +1363,17 @@ pub enum Property {
     Vrfy = 526,
     WaitOnFail = 548,
     WapiVersion = 893,
+    WarmupDays = 928,
+    WarmupInitialLimit = 929,
+    WarmupStart = 927,
+    WarmupTargetLimit = 930,
     WebPushContact = 922,
     WebPushKey = 921,
     WebsocketHeartbeat = 455,
+    WebsocketMaxConcurrent = 1002,
     WebsocketThrottle = 456,
     WebsocketTimeout = 457,
+    Weight = 926,
     Zone = 749,
     ZoneIpV4 = 98,
     ZoneIpV6 = 99,
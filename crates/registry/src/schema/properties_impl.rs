@@ -27,6 +27,7 @@ impl EnumImpl for ObjectType {
             b"ArchivedItem" => ObjectType::ArchivedItem,
             b"ArfExternalReport" => ObjectType::ArfExternalReport,
             b"Asn" => ObjectType::Asn,
+            b"AuditEvent" => ObjectType::AuditEvent,
             b"Authentication" => ObjectType::Authentication,
             b"BlobStore" => ObjectType::BlobStore,
             b"BlockedIp" => ObjectType::BlockedIp,
@@ -41,6 +42,7 @@ impl EnumImpl for ObjectType {
             b"Coordinator" => ObjectType::Coordinator,
             b"DataRetention" => ObjectType::DataRetention,
             b"DataStore" => ObjectType::DataStore,
+            b"DeliveryCallback" => ObjectType::DeliveryCallback,
             b"Directory" => ObjectType::Directory,
             b"DkimReportSettings" => ObjectType::DkimReportSettings,
             b"DkimSignature" => ObjectType::DkimSignature,
@@ -62,10 +64,12 @@ impl EnumImpl for ObjectType {
             b"InMemoryStore" => ObjectType::InMemoryStore,
             b"Jmap" => ObjectType::Jmap,
             b"Log" => ObjectType::Log,
+            b"MailCategory" => ObjectType::MailCategory,
             b"MailingList" => ObjectType::MailingList,
             b"MaskedEmail" => ObjectType::MaskedEmail,
             b"MemoryLookupKey" => ObjectType::MemoryLookupKey,
             b"MemoryLookupKeyValue" => ObjectType::MemoryLookupKeyValue,
+            b"MessageTemplate" => ObjectType::MessageTemplate,
             b"Metric" => ObjectType::Metric,
             b"Metrics" => ObjectType::Metrics,
             b"MetricsStore" => ObjectType::MetricsStore,
@@ -79,6 +83,7 @@ impl EnumImpl for ObjectType {
             b"MtaOutboundStrategy" => ObjectType::MtaOutboundStrategy,
             b"MtaOutboundThrottle" => ObjectType::MtaOutboundThrottle,
             b"MtaQueueQuota" => ObjectType::MtaQueueQuota,
+            b"MtaResponse" => ObjectType::MtaResponse,
             b"MtaRoute" => ObjectType::MtaRoute,
             b"MtaStageAuth" => ObjectType::MtaStageAuth,
             b"MtaStageConnect" => ObjectType::MtaStageConnect,
@@ -88,6 +93,7 @@ impl EnumImpl for ObjectType {
             b"MtaStageRcpt" => ObjectType::MtaStageRcpt,
             b"MtaSts" => ObjectType::MtaSts,
             b"MtaTlsStrategy" => ObjectType::MtaTlsStrategy,
+            b"MtaUnsubscribe" => ObjectType::MtaUnsubscribe,
             b"MtaVirtualQueue" => ObjectType::MtaVirtualQueue,
             b"NetworkListener" => ObjectType::NetworkListener,
             b"OAuthClient" => ObjectType::OAuthClient,
@@ -105,6 +111,7 @@ impl EnumImpl for ObjectType {
             b"SieveSystemScript" => ObjectType::SieveSystemScript,
             b"SieveUserInterpreter" => ObjectType::SieveUserInterpreter,
             b"SieveUserScript" => ObjectType::SieveUserScript,
+            b"SpamClamAv" => ObjectType::SpamClamAv,
             b"SpamClassifier" => ObjectType::SpamClassifier,
             b"SpamDnsblServer" => ObjectType::SpamDnsblServer,
             b"SpamDnsblSettings" => ObjectType::SpamDnsblSettings,
@@ -127,6 +134,7 @@ impl EnumImpl for ObjectType {
             b"Trace" => ObjectType::Trace,
             b"Tracer" => ObjectType::Tracer,
             b"TracingStore" => ObjectType::TracingStore,
+            b"UnsubscribeEvent" => ObjectType::UnsubscribeEvent,
             b"WebDav" => ObjectType::WebDav,
             b"WebHook" => ObjectType::WebHook,
         }
@@ -149,6 +157,7 @@ impl EnumImpl for ObjectType {
             ObjectType::ArchivedItem => "ArchivedItem",
             ObjectType::ArfExternalReport => "ArfExternalReport",
             ObjectType::Asn => "Asn",
+            ObjectType::AuditEvent => "AuditEvent",
             ObjectType::Authentication => "Authentication",
             ObjectType::BlobStore => "BlobStore",
             ObjectType::BlockedIp => "BlockedIp",
@@ -163,6 +172,7 @@ impl EnumImpl for ObjectType {
             ObjectType::Coordinator => "Coordinator",
             ObjectType::DataRetention => "DataRetention",
             ObjectType::DataStore => "DataStore",
+            ObjectType::DeliveryCallback => "DeliveryCallback",
             ObjectType::Directory => "Directory",
             ObjectType::DkimReportSettings => "DkimReportSettings",
             ObjectType::DkimSignature => "DkimSignature",
@@ -184,10 +194,12 @@ impl EnumImpl for ObjectType {
             ObjectType::InMemoryStore => "InMemoryStore",
             ObjectType::Jmap => "Jmap",
             ObjectType::Log => "Log",
+            ObjectType::MailCategory => "MailCategory",
             ObjectType::MailingList => "MailingList",
             ObjectType::MaskedEmail => "MaskedEmail",
             ObjectType::MemoryLookupKey => "MemoryLookupKey",
             ObjectType::MemoryLookupKeyValue => "MemoryLookupKeyValue",
+            ObjectType::MessageTemplate => "MessageTemplate",
             ObjectType::Metric => "Metric",
             ObjectType::Metrics => "Metrics",
             ObjectType::MetricsStore => "MetricsStore",
@@ -201,6 +213,7 @@ impl EnumImpl for ObjectType {
             ObjectType::MtaOutboundStrategy => "MtaOutboundStrategy",
             ObjectType::MtaOutboundThrottle => "MtaOutboundThrottle",
             ObjectType::MtaQueueQuota => "MtaQueueQuota",
+            ObjectType::MtaResponse => "MtaResponse",
             ObjectType::MtaRoute => "MtaRoute",
             ObjectType::MtaStageAuth => "MtaStageAuth",
             ObjectType::MtaStageConnect => "MtaStageConnect",
@@ -210,6 +223,7 @@ impl EnumImpl for ObjectType {
             ObjectType::MtaStageRcpt => "MtaStageRcpt",
             ObjectType::MtaSts => "MtaSts",
             ObjectType::MtaTlsStrategy => "MtaTlsStrategy",
+            ObjectType::MtaUnsubscribe => "MtaUnsubscribe",
             ObjectType::MtaVirtualQueue => "MtaVirtualQueue",
             ObjectType::NetworkListener => "NetworkListener",
             ObjectType::OAuthClient => "OAuthClient",
@@ -227,6 +241,7 @@ impl EnumImpl for ObjectType {
             ObjectType::SieveSystemScript => "SieveSystemScript",
             ObjectType::SieveUserInterpreter => "SieveUserInterpreter",
             ObjectType::SieveUserScript => "SieveUserScript",
+            ObjectType::SpamClamAv => "SpamClamAv",
             ObjectType::SpamClassifier => "SpamClassifier",
             ObjectType::SpamDnsblServer => "SpamDnsblServer",
             ObjectType::SpamDnsblSettings => "SpamDnsblSettings",
@@ -249,6 +264,7 @@ impl EnumImpl for ObjectType {
             ObjectType::Trace => "Trace",
             ObjectType::Tracer => "Tracer",
             ObjectType::TracingStore => "TracingStore",
+            ObjectType::UnsubscribeEvent => "UnsubscribeEvent",
             ObjectType::WebDav => "WebDav",
             ObjectType::WebHook => "WebHook",
         }
@@ -377,11 +393,19 @@ impl EnumImpl for ObjectType {
             114 => Some(ObjectType::TracingStore),
             115 => Some(ObjectType::WebDav),
             116 => Some(ObjectType::WebHook),
+            117 => Some(ObjectType::DeliveryCallback),
+            118 => Some(ObjectType::SpamClamAv),
+            119 => Some(ObjectType::AuditEvent),
+            120 => Some(ObjectType::MtaResponse),
+            121 => Some(ObjectType::MessageTemplate),
+            122 => Some(ObjectType::MtaUnsubscribe),
+            123 => Some(ObjectType::UnsubscribeEvent),
+            124 => Some(ObjectType::MailCategory),
             _ => None,
         }
     }
 
-    const COUNT: usize = 117;
+    const COUNT: usize = 125;
 }
 
 impl serde::Serialize for ObjectType {
@@ -425,6 +449,8 @@ impl EnumImpl for Property {
             b"accountUri" => Property::AccountUri,
             b"accounts" => Property::Accounts,
             b"acmeProviderId" => Property::AcmeProviderId,
+            b"action" => Property::Action,
+            b"actorId" => Property::ActorId,
             b"addAuthResultsHeader" => Property::AddAuthResultsHeader,
             b"addDateHeader" => Property::AddDateHeader,
             b"addDeliveredToHeader" => Property::AddDeliveredToHeader,
@@ -432,6 +458,7 @@ impl EnumImpl for Property {
             b"addReceivedHeader" => Property::AddReceivedHeader,
             b"addReceivedSpfHeader" => Property::AddReceivedSpfHeader,
             b"addReturnPathHeader" => Property::AddReturnPathHeader,
+            b"additionalHosts" => Property::AdditionalHosts,
             b"additionalInformation" => Property::AdditionalInformation,
             b"address" => Property::Address,
             b"addresses" => Property::Addresses,
@@ -439,6 +466,7 @@ impl EnumImpl for Property {
             b"aggregateDkimSignDomain" => Property::AggregateDkimSignDomain,
             b"aggregateFromAddress" => Property::AggregateFromAddress,
             b"aggregateFromName" => Property::AggregateFromName,
+            b"aggregateMaxCompressedSize" => Property::AggregateMaxCompressedSize,
             b"aggregateMaxReportSize" => Property::AggregateMaxReportSize,
             b"aggregateOrgName" => Property::AggregateOrgName,
             b"aggregateSendFrequency" => Property::AggregateSendFrequency,
@@ -446,6 +474,7 @@ impl EnumImpl for Property {
             b"alarmId" => Property::AlarmId,
             b"algorithms" => Property::Algorithms,
             b"aliases" => Property::Aliases,
+            b"allColumns" => Property::AllColumns,
             b"allowCount" => Property::AllowCount,
             b"allowDirectoryQueries" => Property::AllowDirectoryQueries,
             b"allowExternalRcpts" => Property::AllowExternalRcpts,
@@ -467,6 +496,11 @@ impl EnumImpl for Property {
             b"arcVerify" => Property::ArcVerify,
             b"archiveDeletedAccountsFor" => Property::ArchiveDeletedAccountsFor,
             b"archiveDeletedItemsFor" => Property::ArchiveDeletedItemsFor,
+            b"archiveMaxDepth" => Property::ArchiveMaxDepth,
+            b"archiveMaxEntries" => Property::ArchiveMaxEntries,
+            b"archiveMaxRatio" => Property::ArchiveMaxRatio,
+            b"archiveMaxSize" => Property::ArchiveMaxSize,
+            b"archiveScanTimeout" => Property::ArchiveScanTimeout,
             b"archivedAt" => Property::ArchivedAt,
             b"archivedItemType" => Property::ArchivedItemType,
             b"archivedUntil" => Property::ArchivedUntil,
@@ -474,13 +508,17 @@ impl EnumImpl for Property {
             b"asnUrls" => Property::AsnUrls,
             b"attemptNumber" => Property::AttemptNumber,
             b"attempts" => Property::Attempts,
+            b"attrCategorizeMail" => Property::AttrCategorizeMail,
             b"attrClass" => Property::AttrClass,
             b"attrDescription" => Property::AttrDescription,
             b"attrEmail" => Property::AttrEmail,
             b"attrEmailAlias" => Property::AttrEmailAlias,
+            b"attrEnabledProtocols" => Property::AttrEnabledProtocols,
             b"attrMemberOf" => Property::AttrMemberOf,
             b"attrSecret" => Property::AttrSecret,
             b"attrSecretChanged" => Property::AttrSecretChanged,
+            b"auditLog" => Property::AuditLog,
+            b"auditMessageReads" => Property::AuditMessageReads,
             b"auid" => Property::Auid,
             b"auth" => Property::Auth,
             b"authBanPeriod" => Property::AuthBanPeriod,
@@ -496,8 +534,14 @@ impl EnumImpl for Property {
             b"authenticationResults" => Property::AuthenticationResults,
             b"autoAddInvitations" => Property::AutoAddInvitations,
             b"autoUpdateFrequency" => Property::AutoUpdateFrequency,
+            b"backupDirectory" => Property::BackupDirectory,
+            b"backupIncludeBlobs" => Property::BackupIncludeBlobs,
+            b"backupMaxIncrements" => Property::BackupMaxIncrements,
             b"baseDn" => Property::BaseDn,
             b"baseUrl" => Property::BaseUrl,
+            b"batvKey" => Property::BatvKey,
+            b"batvPreviousKey" => Property::BatvPreviousKey,
+            b"batvValidity" => Property::BatvValidity,
             b"bearerToken" => Property::BearerToken,
             b"beta" => Property::Beta,
             b"bind" => Property::Bind,
@@ -514,29 +558,39 @@ impl EnumImpl for Property {
             b"bucket" => Property::Bucket,
             b"bufferSize" => Property::BufferSize,
             b"buffered" => Property::Buffered,
+            b"calendarInvitations" => Property::CalendarInvitations,
+            b"callbackId" => Property::CallbackId,
+            b"campaignId" => Property::CampaignId,
             b"canonicalization" => Property::Canonicalization,
             b"capacityClient" => Property::CapacityClient,
             b"capacityReadBuffer" => Property::CapacityReadBuffer,
             b"capacitySubscription" => Property::CapacitySubscription,
             b"catchAllAddress" => Property::CatchAllAddress,
             b"categories" => Property::Categories,
+            b"categorizeMail" => Property::CategorizeMail,
             b"certificate" => Property::Certificate,
             b"certificateManagement" => Property::CertificateManagement,
             b"challengeType" => Property::ChallengeType,
             b"changesMaxResults" => Property::ChangesMaxResults,
             b"chunking" => Property::Chunking,
+            b"claimCategorizeMail" => Property::ClaimCategorizeMail,
+            b"claimEnabledProtocols" => Property::ClaimEnabledProtocols,
             b"claimGroups" => Property::ClaimGroups,
             b"claimName" => Property::ClaimName,
             b"claimUsername" => Property::ClaimUsername,
             b"cleartext" => Property::Cleartext,
+            b"clientCertAccount" => Property::ClientCertAccount,
+            b"clientCertRevocationList" => Property::ClientCertRevocationList,
             b"clientId" => Property::ClientId,
             b"clientIp" => Property::ClientIp,
             b"clientSecret" => Property::ClientSecret,
             b"clientToken" => Property::ClientToken,
             b"clusterFile" => Property::ClusterFile,
+            b"columnCategorizeMail" => Property::ColumnCategorizeMail,
             b"columnClass" => Property::ColumnClass,
             b"columnDescription" => Property::ColumnDescription,
             b"columnEmail" => Property::ColumnEmail,
+            b"columnEnabledProtocols" => Property::ColumnEnabledProtocols,
             b"columnSecret" => Property::ColumnSecret,
             b"comment" => Property::Comment,
             b"compartmentOcid" => Property::CompartmentOcid,
@@ -561,6 +615,7 @@ impl EnumImpl for Property {
             b"create" => Property::Create,
             b"createdAt" => Property::CreatedAt,
             b"createdBy" => Property::CreatedBy,
+            b"credentialGeneration" => Property::CredentialGeneration,
             b"credentialId" => Property::CredentialId,
             b"credentials" => Property::Credentials,
             b"currentSecret" => Property::CurrentSecret,
@@ -569,6 +624,8 @@ impl EnumImpl for Property {
             b"customRule" => Property::CustomRule,
             b"customerNumber" => Property::CustomerNumber,
             b"dane" => Property::Dane,
+            b"daneMaxDelay" => Property::DaneMaxDelay,
+            b"daneRollover" => Property::DaneRollover,
             b"dataCleanupSchedule" => Property::DataCleanupSchedule,
             b"dataStore" => Property::DataStore,
             b"dataTimeout" => Property::DataTimeout,
@@ -579,6 +636,7 @@ impl EnumImpl for Property {
             b"dateRangeStart" => Property::DateRangeStart,
             b"day" => Property::Day,
             b"deadPropertyMaxSize" => Property::DeadPropertyMaxSize,
+            b"deduplicate" => Property::Deduplicate,
             b"defaultAdminRoleIds" => Property::DefaultAdminRoleIds,
             b"defaultCertificateId" => Property::DefaultCertificateId,
             b"defaultDisplayName" => Property::DefaultDisplayName,
@@ -605,13 +663,22 @@ impl EnumImpl for Property {
             b"deleteAfterUse" => Property::DeleteAfterUse,
             b"deliverAt" => Property::DeliverAt,
             b"deliverBy" => Property::DeliverBy,
+            b"deliveryCallback" => Property::DeliveryCallback,
             b"deliverTo" => Property::DeliverTo,
+            b"deliveryDays" => Property::DeliveryDays,
+            b"deliveryHourEnd" => Property::DeliveryHourEnd,
+            b"deliveryHourStart" => Property::DeliveryHourStart,
             b"deliveryResult" => Property::DeliveryResult,
+            b"deliveryTimeZone" => Property::DeliveryTimeZone,
             b"depth" => Property::Depth,
             b"description" => Property::Description,
             b"details" => Property::Details,
             b"directory" => Property::Directory,
+            b"directoryCacheTtl" => Property::DirectoryCacheTtl,
+            b"directoryChain" => Property::DirectoryChain,
             b"directoryId" => Property::DirectoryId,
+            b"directoryLookupMode" => Property::DirectoryLookupMode,
+            b"directoryPrincipals" => Property::DirectoryPrincipals,
             b"disableCapabilities" => Property::DisableCapabilities,
             b"disabledPermissions" => Property::DisabledPermissions,
             b"discardAfter" => Property::DiscardAfter,
@@ -659,6 +726,8 @@ impl EnumImpl for Property {
             b"domains" => Property::Domains,
             b"dsn" => Property::Dsn,
             b"due" => Property::Due,
+            b"duplicateDelivery" => Property::DuplicateDelivery,
+            b"duplicateDeliveryWindow" => Property::DuplicateDeliveryWindow,
             b"duplicateExpiry" => Property::DuplicateExpiry,
             b"duration" => Property::Duration,
             b"eabHmacKey" => Property::EabHmacKey,
@@ -682,10 +751,12 @@ impl EnumImpl for Property {
             b"enableEdns" => Property::EnableEdns,
             b"enableHsts" => Property::EnableHsts,
             b"enableLogExporter" => Property::EnableLogExporter,
+            b"enableRspamdApi" => Property::EnableRspamdApi,
             b"enableSpamFilter" => Property::EnableSpamFilter,
             b"enableSpanExporter" => Property::EnableSpanExporter,
             b"enabled" => Property::Enabled,
             b"enabledPermissions" => Property::EnabledPermissions,
+            b"enabledProtocols" => Property::EnabledProtocols,
             b"encryptAtRest" => Property::EncryptAtRest,
             b"encryptOnAppend" => Property::EncryptOnAppend,
             b"encryptionAtRest" => Property::EncryptionAtRest,
@@ -716,6 +787,7 @@ impl EnumImpl for Property {
             b"eventStartTz" => Property::EventStartTz,
             b"events" => Property::Events,
             b"eventsPolicy" => Property::EventsPolicy,
+            b"expansionTraceHeader" => Property::ExpansionTraceHeader,
             b"expire" => Property::Expire,
             b"expires" => Property::Expires,
             b"expiresAt" => Property::ExpiresAt,
@@ -732,9 +804,11 @@ impl EnumImpl for Property {
             b"extraContactInfo" => Property::ExtraContactInfo,
             b"factor" => Property::Factor,
             b"failOnTimeout" => Property::FailOnTimeout,
+            b"failOpen" => Property::FailOpen,
             b"failedAt" => Property::FailedAt,
             b"failedAttemptNumber" => Property::FailedAttemptNumber,
             b"failedSessionCount" => Property::FailedSessionCount,
+            b"failureCoolOff" => Property::FailureCoolOff,
             b"failureDetails" => Property::FailureDetails,
             b"failureDkimSignDomain" => Property::FailureDkimSignDomain,
             b"failureFromAddress" => Property::FailureFromAddress,
@@ -743,6 +817,7 @@ impl EnumImpl for Property {
             b"failureReasonCode" => Property::FailureReasonCode,
             b"failureSendFrequency" => Property::FailureSendFrequency,
             b"failureSubject" => Property::FailureSubject,
+            b"failureThreshold" => Property::FailureThreshold,
             b"featureL2Normalize" => Property::FeatureL2Normalize,
             b"featureLogScale" => Property::FeatureLogScale,
             b"feedbackType" => Property::FeedbackType,
@@ -756,6 +831,7 @@ impl EnumImpl for Property {
             b"filterMailbox" => Property::FilterMailbox,
             b"filterMemberOf" => Property::FilterMemberOf,
             b"fingerprint" => Property::Fingerprint,
+            b"firstSeen" => Property::FirstSeen,
             b"flags" => Property::Flags,
             b"flagsAction" => Property::FlagsAction,
             b"flagsProtocol" => Property::FlagsProtocol,
@@ -764,6 +840,7 @@ impl EnumImpl for Property {
             b"format" => Property::Format,
             b"from" => Property::From,
             b"fromAddress" => Property::FromAddress,
+            b"fromAlignment" => Property::FromAlignment,
             b"fromEmail" => Property::FromEmail,
             b"fromName" => Property::FromName,
             b"futureRelease" => Property::FutureRelease,
@@ -777,14 +854,19 @@ impl EnumImpl for Property {
             b"groupId" => Property::GroupId,
             b"headerFrom" => Property::HeaderFrom,
             b"headers" => Property::Headers,
+            b"healthCheckInterval" => Property::HealthCheckInterval,
+            b"hiddenExtensions" => Property::HiddenExtensions,
+            b"holdAuditEventsFor" => Property::HoldAuditEventsFor,
             b"holdMetricsFor" => Property::HoldMetricsFor,
             b"holdMtaReportsFor" => Property::HoldMtaReportsFor,
             b"holdSamplesFor" => Property::HoldSamplesFor,
             b"holdTracesFor" => Property::HoldTracesFor,
             b"host" => Property::Host,
+            b"hostSelection" => Property::HostSelection,
             b"hostedZoneId" => Property::HostedZoneId,
             b"hostname" => Property::Hostname,
             b"hour" => Property::Hour,
+            b"htmlBody" => Property::HtmlBody,
             b"httpAuth" => Property::HttpAuth,
             b"httpHeaders" => Property::HttpHeaders,
             b"httpRsvpEnable" => Property::HttpRsvpEnable,
@@ -797,9 +879,12 @@ impl EnumImpl for Property {
             b"id" => Property::Id,
             b"idTokenExpiry" => Property::IdTokenExpiry,
             b"identityAlignment" => Property::IdentityAlignment,
+            b"idleNotifyDelay" => Property::IdleNotifyDelay,
             b"if" => Property::If,
             b"impersonateServiceAccount" => Property::ImpersonateServiceAccount,
             b"implicitTls" => Property::ImplicitTls,
+            b"importErrors" => Property::ImportErrors,
+            b"importRoot" => Property::ImportRoot,
             b"inMemoryStore" => Property::InMemoryStore,
             b"inboundReportAddresses" => Property::InboundReportAddresses,
             b"inboundReportForwarding" => Property::InboundReportForwarding,
@@ -848,7 +933,9 @@ impl EnumImpl for Property {
             b"keyValues" => Property::KeyValues,
             b"l1Ratio" => Property::L1Ratio,
             b"l2Ratio" => Property::L2Ratio,
+            b"lastDocumentId" => Property::LastDocumentId,
             b"lastRenewal" => Property::LastRenewal,
+            b"lastSeen" => Property::LastSeen,
             b"learnHamFromCard" => Property::LearnHamFromCard,
             b"learnHamFromReply" => Property::LearnHamFromReply,
             b"learnSpamFromRblHits" => Property::LearnSpamFromRblHits,
@@ -859,7 +946,12 @@ impl EnumImpl for Property {
             b"listenerIds" => Property::ListenerIds,
             b"listeners" => Property::Listeners,
             b"livePropertyMaxSize" => Property::LivePropertyMaxSize,
+            b"lmtpLocalDelivery" => Property::LmtpLocalDelivery,
             b"locale" => Property::Locale,
+            b"logEnabled" => Property::LogEnabled,
+            b"logHeader" => Property::LogHeader,
+            b"logMaxEntries" => Property::LogMaxEntries,
+            b"logRetention" => Property::LogRetention,
             b"logo" => Property::Logo,
             b"logoUrl" => Property::LogoUrl,
             b"loiterBanPeriod" => Property::LoiterBanPeriod,
@@ -870,10 +962,14 @@ impl EnumImpl for Property {
             b"mailFrom" => Property::MailFrom,
             b"mailFromTimeout" => Property::MailFromTimeout,
             b"mailRua" => Property::MailRua,
+            b"mailboxId" => Property::MailboxId,
+            b"mailboxName" => Property::MailboxName,
             b"mailingLists" => Property::MailingLists,
             b"maintenanceType" => Property::MaintenanceType,
             b"managedZone" => Property::ManagedZone,
             b"match" => Property::Match,
+            b"matchKeywords" => Property::MatchKeywords,
+            b"matchTags" => Property::MatchTags,
             b"maxAddressBooks" => Property::MaxAddressBooks,
             b"maxAge" => Property::MaxAge,
             b"maxAllowedPacket" => Property::MaxAllowedPacket,
@@ -884,6 +980,8 @@ impl EnumImpl for Property {
             b"maxAttendees" => Property::MaxAttendees,
             b"maxAuthFailures" => Property::MaxAuthFailures,
             b"maxCalendars" => Property::MaxCalendars,
+            b"maxChangesAge" => Property::MaxChangesAge,
+            b"maxChangesAgePerCollection" => Property::MaxChangesAgePerCollection,
             b"maxChangesHistory" => Property::MaxChangesHistory,
             b"maxConcurrent" => Property::MaxConcurrent,
             b"maxConcurrentRequests" => Property::MaxConcurrentRequests,
@@ -892,14 +990,20 @@ impl EnumImpl for Property {
             b"maxContacts" => Property::MaxContacts,
             b"maxCpuCycles" => Property::MaxCpuCycles,
             b"maxDelay" => Property::MaxDelay,
+            b"maxDelayedSend" => Property::MaxDelayedSend,
+            b"maxDeliveryCallbacks" => Property::MaxDeliveryCallbacks,
             b"maxDuration" => Property::MaxDuration,
             b"maxEntries" => Property::MaxEntries,
             b"maxEntrySize" => Property::MaxEntrySize,
             b"maxEventNotifications" => Property::MaxEventNotifications,
             b"maxEvents" => Property::MaxEvents,
+            b"maxExpansionDepth" => Property::MaxExpansionDepth,
+            b"maxExpansionRecipients" => Property::MaxExpansionRecipients,
+            b"maxExpiryDuplicate" => Property::MaxExpiryDuplicate,
             b"maxFailures" => Property::MaxFailures,
             b"maxFiles" => Property::MaxFiles,
             b"maxFolders" => Property::MaxFolders,
+            b"maxFollowUps" => Property::MaxFollowUps,
             b"maxHeaderSize" => Property::MaxHeaderSize,
             b"maxICalendarSize" => Property::MaxICalendarSize,
             b"maxIdentities" => Property::MaxIdentities,
@@ -928,7 +1032,10 @@ impl EnumImpl for Property {
             b"maxRecipients" => Property::MaxRecipients,
             b"maxReconnects" => Property::MaxReconnects,
             b"maxRecurrenceExpansions" => Property::MaxRecurrenceExpansions,
+            b"maxRedirectDestinations" => Property::MaxRedirectDestinations,
+            b"maxRedirectPasses" => Property::MaxRedirectPasses,
             b"maxRedirects" => Property::MaxRedirects,
+            b"maxRedirectsPerDay" => Property::MaxRedirectsPerDay,
             b"maxReportSize" => Property::MaxReportSize,
             b"maxRequestRate" => Property::MaxRequestRate,
             b"maxRequestSize" => Property::MaxRequestSize,
@@ -939,6 +1046,8 @@ impl EnumImpl for Property {
             b"maxScriptNameLength" => Property::MaxScriptNameLength,
             b"maxScriptSize" => Property::MaxScriptSize,
             b"maxScripts" => Property::MaxScripts,
+            b"maxSessions" => Property::MaxSessions,
+            b"maxSessionsPerProtocol" => Property::MaxSessionsPerProtocol,
             b"maxShares" => Property::MaxShares,
             b"maxSize" => Property::MaxSize,
             b"maxStringLength" => Property::MaxStringLength,
@@ -955,12 +1064,23 @@ impl EnumImpl for Property {
             b"messageIdHostname" => Property::MessageIdHostname,
             b"messageIds" => Property::MessageIds,
             b"messages" => Property::Messages,
+            b"messagesFailed" => Property::MessagesFailed,
+            b"messagesImported" => Property::MessagesImported,
+            b"messagesIndexed" => Property::MessagesIndexed,
+            b"messagesProcessed" => Property::MessagesProcessed,
+            b"messagesReEncrypted" => Property::MessagesReEncrypted,
+            b"messagesSkipped" => Property::MessagesSkipped,
+            b"messagesTotal" => Property::MessagesTotal,
+            b"metadata" => Property::Metadata,
+            b"methodProfiling" => Property::MethodProfiling,
             b"metric" => Property::Metric,
             b"metrics" => Property::Metrics,
             b"metricsCollectionInterval" => Property::MetricsCollectionInterval,
             b"metricsPolicy" => Property::MetricsPolicy,
             b"minHamSamples" => Property::MinHamSamples,
+            b"minMatches" => Property::MinMatches,
             b"minRetryWait" => Property::MinRetryWait,
+            b"minSenderMessages" => Property::MinSenderMessages,
             b"minSpamSamples" => Property::MinSpamSamples,
             b"minTriggerInterval" => Property::MinTriggerInterval,
             b"minute" => Property::Minute,
@@ -970,6 +1090,9 @@ impl EnumImpl for Property {
             b"modelType" => Property::ModelType,
             b"mtPriority" => Property::MtPriority,
             b"mtaSts" => Property::MtaSts,
+            b"mtaStsMaxAge" => Property::MtaStsMaxAge,
+            b"mtaStsMode" => Property::MtaStsMode,
+            b"mtaStsMxHosts" => Property::MtaStsMxHosts,
             b"mtaStsTimeout" => Property::MtaStsTimeout,
             b"multiline" => Property::Multiline,
             b"mustMatchSender" => Property::MustMatchSender,
@@ -992,6 +1115,7 @@ impl EnumImpl for Property {
             b"numFeatures" => Property::NumFeatures,
             b"numReplicas" => Property::NumReplicas,
             b"numShards" => Property::NumShards,
+            b"objectIds" => Property::ObjectIds,
             b"onSuccessRenewCertificate" => Property::OnSuccessRenewCertificate,
             b"openTelemetry" => Property::OpenTelemetry,
             b"options" => Property::Options,
@@ -1070,6 +1194,10 @@ impl EnumImpl for Property {
             b"protocol" => Property::Protocol,
             b"protocolVersion" => Property::ProtocolVersion,
             b"providerInfo" => Property::ProviderInfo,
+            b"provisionFolders" => Property::ProvisionFolders,
+            b"provisionSieveScript" => Property::ProvisionSieveScript,
+            b"provisionSignature" => Property::ProvisionSignature,
+            b"provisionSubscribe" => Property::ProvisionSubscribe,
             b"proxyTrustedNetworks" => Property::ProxyTrustedNetworks,
             b"publicKey" => Property::PublicKey,
             b"publishRecords" => Property::PublishRecords,
@@ -1087,6 +1215,13 @@ impl EnumImpl for Property {
             b"queryRecipient" => Property::QueryRecipient,
             b"queueId" => Property::QueueId,
             b"queueName" => Property::QueueName,
+            b"queuePreviewSize" => Property::QueuePreviewSize,
+            b"queueTopDomains" => Property::QueueTopDomains,
+            b"quotaReconcileInterval" => Property::QuotaReconcileInterval,
+            b"quotaReconcileSampleSize" => Property::QuotaReconcileSampleSize,
+            b"quotaWarningInterval" => Property::QuotaWarningInterval,
+            b"quotaWarningNotify" => Property::QuotaWarningNotify,
+            b"quotaWarningThresholds" => Property::QuotaWarningThresholds,
             b"quotas" => Property::Quotas,
             b"rate" => Property::Rate,
             b"rateLimit" => Property::RateLimit,
@@ -1094,33 +1229,55 @@ impl EnumImpl for Property {
             b"rateLimitAuthenticated" => Property::RateLimitAuthenticated,
             b"ratio" => Property::Ratio,
             b"rcptToTimeout" => Property::RcptToTimeout,
+            b"reEncryptConcurrency" => Property::ReEncryptConcurrency,
             b"readFromReplicas" => Property::ReadFromReplicas,
+            b"readReplicaStickiness" => Property::ReadReplicaStickiness,
             b"readReplicas" => Property::ReadReplicas,
+            b"readinessCacheTtl" => Property::ReadinessCacheTtl,
+            b"readinessCheckTimeout" => Property::ReadinessCheckTimeout,
+            b"readinessOptional" => Property::ReadinessOptional,
+            b"readinessTimeout" => Property::ReadinessTimeout,
             b"reason" => Property::Reason,
+            b"recallNotice" => Property::RecallNotice,
+            b"recallWindow" => Property::RecallWindow,
+            b"receivedAfter" => Property::ReceivedAfter,
             b"receivedAt" => Property::ReceivedAt,
+            b"receivedBefore" => Property::ReceivedBefore,
             b"receivedFromIp" => Property::ReceivedFromIp,
             b"receivedViaPort" => Property::ReceivedViaPort,
             b"receivingIp" => Property::ReceivingIp,
             b"receivingMxHelo" => Property::ReceivingMxHelo,
             b"receivingMxHostname" => Property::ReceivingMxHostname,
+            b"recipient" => Property::Recipient,
             b"recipients" => Property::Recipients,
             b"records" => Property::Records,
             b"recurrenceId" => Property::RecurrenceId,
+            b"redirectEnvelopeSender" => Property::RedirectEnvelopeSender,
             b"redirectRoot" => Property::RedirectRoot,
             b"redirectUris" => Property::RedirectUris,
             b"refresh" => Property::Refresh,
             b"refreshTokenExpiry" => Property::RefreshTokenExpiry,
             b"refreshTokenRenewal" => Property::RefreshTokenRenewal,
             b"region" => Property::Region,
+            b"reindexConcurrency" => Property::ReindexConcurrency,
+            b"reindexRateLimit" => Property::ReindexRateLimit,
+            b"rejectHiddenExtensions" => Property::RejectHiddenExtensions,
             b"rejectNonFqdn" => Property::RejectNonFqdn,
             b"remoteIp" => Property::RemoteIp,
             b"renewBefore" => Property::RenewBefore,
             b"report" => Property::Report,
             b"reportAddressUri" => Property::ReportAddressUri,
+            b"reportFromAddress" => Property::ReportFromAddress,
             b"reportId" => Property::ReportId,
             b"reportedDomains" => Property::ReportedDomains,
             b"reportedUris" => Property::ReportedUris,
             b"reportingMta" => Property::ReportingMta,
+            b"reputationBadScore" => Property::ReputationBadScore,
+            b"reputationEnable" => Property::ReputationEnable,
+            b"reputationGoodScore" => Property::ReputationGoodScore,
+            b"reputationMinMessages" => Property::ReputationMinMessages,
+            b"reputationSkipGreylist" => Property::ReputationSkipGreylist,
+            b"reputationWindow" => Property::ReputationWindow,
             b"requestMaxSize" => Property::RequestMaxSize,
             b"requestTlsCertificate" => Property::RequestTlsCertificate,
             b"require" => Property::Require,
@@ -1135,18 +1292,23 @@ impl EnumImpl for Property {
             b"responseEnhanced" => Property::ResponseEnhanced,
             b"responseHeaders" => Property::ResponseHeaders,
             b"responseHostname" => Property::ResponseHostname,
+            b"responseId" => Property::ResponseId,
             b"responseMessage" => Property::ResponseMessage,
             b"responsePosCategory" => Property::ResponsePosCategory,
             b"responsePosConfidence" => Property::ResponsePosConfidence,
             b"responsePosExplanation" => Property::ResponsePosExplanation,
             b"result" => Property::Result,
             b"resultType" => Property::ResultType,
+            b"resumeAfter" => Property::ResumeAfter,
             b"retireAfter" => Property::RetireAfter,
             b"retry" => Property::Retry,
             b"retryCount" => Property::RetryCount,
             b"retryDue" => Property::RetryDue,
             b"returnPath" => Property::ReturnPath,
             b"reuseKey" => Property::ReuseKey,
+            b"reverseIpRequire" => Property::ReverseIpRequire,
+            b"reverseIpRequireTempFail" => Property::ReverseIpRequireTempFail,
+            b"reverseIpTimeout" => Property::ReverseIpTimeout,
             b"reverseIpVerify" => Property::ReverseIpVerify,
             b"rewrite" => Property::Rewrite,
             b"roleIds" => Property::RoleIds,
@@ -1154,7 +1316,10 @@ impl EnumImpl for Property {
             b"rotate" => Property::Rotate,
             b"rotateAfter" => Property::RotateAfter,
             b"route" => Property::Route,
+            b"rspamdApiMaxConcurrent" => Property::RspamdApiMaxConcurrent,
+            b"rspamdApiSecret" => Property::RspamdApiSecret,
             b"rua" => Property::Rua,
+            b"ruaMaxSize" => Property::RuaMaxSize,
             b"sandbox" => Property::Sandbox,
             b"sasToken" => Property::SasToken,
             b"saslMechanisms" => Property::SaslMechanisms,
@@ -1170,6 +1335,8 @@ impl EnumImpl for Property {
             b"scoreReject" => Property::ScoreReject,
             b"scoreSpam" => Property::ScoreSpam,
             b"script" => Property::Script,
+            b"searchCacheSize" => Property::SearchCacheSize,
+            b"searchCacheTtl" => Property::SearchCacheTtl,
             b"searchStore" => Property::SearchStore,
             b"secret" => Property::Secret,
             b"secretAccessKey" => Property::SecretAccessKey,
@@ -1180,6 +1347,14 @@ impl EnumImpl for Property {
             b"selector" => Property::Selector,
             b"selectorTemplate" => Property::SelectorTemplate,
             b"sendFrequency" => Property::SendFrequency,
+            b"sender" => Property::Sender,
+            b"senderVerify" => Property::SenderVerify,
+            b"senderVerifyFailTtl" => Property::SenderVerifyFailTtl,
+            b"senderVerifyMaxConcurrent" => Property::SenderVerifyMaxConcurrent,
+            b"senderVerifyPassTtl" => Property::SenderVerifyPassTtl,
+            b"senderVerifyRate" => Property::SenderVerifyRate,
+            b"senderVerifySkipDomains" => Property::SenderVerifySkipDomains,
+            b"senderVerifyTimeout" => Property::SenderVerifyTimeout,
             b"sendingMtaIp" => Property::SendingMtaIp,
             b"sentinelSecret" => Property::SentinelSecret,
             b"sentinelUsername" => Property::SentinelUsername,
@@ -1192,7 +1367,10 @@ impl EnumImpl for Property {
             b"sessionToken" => Property::SessionToken,
             b"setMaxObjects" => Property::SetMaxObjects,
             b"shardIndex" => Property::ShardIndex,
+            b"sharedBlobQuota" => Property::SharedBlobQuota,
             b"sharedSecret" => Property::SharedSecret,
+            b"shutdownGracePeriod" => Property::ShutdownGracePeriod,
+            b"sieveLogging" => Property::SieveLogging,
             b"sig0Algorithm" => Property::Sig0Algorithm,
             b"signatureAlgorithm" => Property::SignatureAlgorithm,
             b"signatureKey" => Property::SignatureKey,
@@ -1200,6 +1378,8 @@ impl EnumImpl for Property {
             b"size" => Property::Size,
             b"skipDeploy" => Property::SkipDeploy,
             b"skipFirst" => Property::SkipFirst,
+            b"slowMethodThreshold" => Property::SlowMethodThreshold,
+            b"slowMethodThresholdPerMethod" => Property::SlowMethodThresholdPerMethod,
             b"smtpGreeting" => Property::SmtpGreeting,
             b"snippetMaxResults" => Property::SnippetMaxResults,
             b"socketBacklog" => Property::SocketBacklog,
@@ -1211,9 +1391,11 @@ impl EnumImpl for Property {
             b"socketTosV4" => Property::SocketTosV4,
             b"socketTtl" => Property::SocketTtl,
             b"sourceIp" => Property::SourceIp,
+            b"sourceIpSelection" => Property::SourceIpSelection,
             b"sourceIps" => Property::SourceIps,
             b"sourcePort" => Property::SourcePort,
             b"spamFilterRulesUrl" => Property::SpamFilterRulesUrl,
+            b"spamThreshold" => Property::SpamThreshold,
             b"spfDns" => Property::SpfDns,
             b"spfEhloDomain" => Property::SpfEhloDomain,
             b"spfEhloResult" => Property::SpfEhloResult,
@@ -1222,15 +1404,22 @@ impl EnumImpl for Property {
             b"spfMailFromDomain" => Property::SpfMailFromDomain,
             b"spfMailFromResult" => Property::SpfMailFromResult,
             b"spfResults" => Property::SpfResults,
+            b"splitFrom" => Property::SplitFrom,
             b"stage" => Property::Stage,
+            b"stagedUntil" => Property::StagedUntil,
             b"stages" => Property::Stages,
             b"startTime" => Property::StartTime,
             b"startTls" => Property::StartTls,
             b"status" => Property::Status,
+            b"stepUpMaxAge" => Property::StepUpMaxAge,
+            b"stepUpPermissions" => Property::StepUpPermissions,
             b"storageAccount" => Property::StorageAccount,
             b"store" => Property::Store,
             b"stores" => Property::Stores,
             b"strategy" => Property::Strategy,
+            b"streamChunkSize" => Property::StreamChunkSize,
+            b"strict" => Property::Strict,
+            b"strictReferences" => Property::StrictReferences,
             b"subAddressing" => Property::SubAddressing,
             b"subAuthId" => Property::SubAuthId,
             b"subject" => Property::Subject,
@@ -1240,8 +1429,11 @@ impl EnumImpl for Property {
             b"sum" => Property::Sum,
             b"summary" => Property::Summary,
             b"supportedLanguages" => Property::SupportedLanguages,
+            b"suppressNoMx" => Property::SuppressNoMx,
+            b"suppressionList" => Property::SuppressionList,
             b"tag" => Property::Tag,
             b"tags" => Property::Tags,
+            b"targetMailbox" => Property::TargetMailbox,
             b"taskTypes" => Property::TaskTypes,
             b"tasks" => Property::Tasks,
             b"tcpOnError" => Property::TcpOnError,
@@ -1249,16 +1441,19 @@ impl EnumImpl for Property {
             b"tempFailOnError" => Property::TempFailOnError,
             b"temperature" => Property::Temperature,
             b"template" => Property::Template,
+            b"templateId" => Property::TemplateId,
             b"tenancyOcid" => Property::TenancyOcid,
             b"tenantId" => Property::TenantId,
             b"tenants" => Property::Tenants,
             b"text" => Property::Text,
+            b"textBody" => Property::TextBody,
             b"then" => Property::Then,
             b"thirdParty" => Property::ThirdParty,
             b"thirdPartyHash" => Property::ThirdPartyHash,
             b"threadName" => Property::ThreadName,
             b"threadPoolSize" => Property::ThreadPoolSize,
             b"threadsPerNode" => Property::ThreadsPerNode,
+            b"threshold" => Property::Threshold,
             b"throttle" => Property::Throttle,
             b"timeZone" => Property::TimeZone,
             b"timeout" => Property::Timeout,
@@ -1275,13 +1470,17 @@ impl EnumImpl for Property {
             b"timestamp" => Property::Timestamp,
             b"title" => Property::Title,
             b"tls" => Property::Tls,
+            b"tlsClientCaCertificates" => Property::TlsClientCaCertificates,
             b"tlsDisableCipherSuites" => Property::TlsDisableCipherSuites,
             b"tlsDisableProtocols" => Property::TlsDisableProtocols,
             b"tlsIgnoreClientOrder" => Property::TlsIgnoreClientOrder,
             b"tlsImplicit" => Property::TlsImplicit,
+            b"tlsRequestClientCert" => Property::TlsRequestClientCert,
+            b"tlsRequireClientCert" => Property::TlsRequireClientCert,
             b"tlsTimeout" => Property::TlsTimeout,
             b"to" => Property::To,
             b"token" => Property::Token,
+            b"tokenValidity" => Property::TokenValidity,
             b"totalDeadline" => Property::TotalDeadline,
             b"totalFailedSessions" => Property::TotalFailedSessions,
             b"totalSuccessfulSessions" => Property::TotalSuccessfulSessions,
@@ -1294,6 +1493,9 @@ impl EnumImpl for Property {
             b"transferLimit" => Property::TransferLimit,
             b"trustContacts" => Property::TrustContacts,
             b"trustReplies" => Property::TrustReplies,
+            b"trustedForwarderDepth" => Property::TrustedForwarderDepth,
+            b"trustedForwarders" => Property::TrustedForwarders,
+            b"trustedRedirectHosts" => Property::TrustedRedirectHosts,
             b"tsigAlgorithm" => Property::TsigAlgorithm,
             b"ttl" => Property::Ttl,
             b"unpackDirectory" => Property::UnpackDirectory,
@@ -1322,11 +1524,17 @@ impl EnumImpl for Property {
             b"vrfy" => Property::Vrfy,
             b"waitOnFail" => Property::WaitOnFail,
             b"wapiVersion" => Property::WapiVersion,
+            b"warmupDays" => Property::WarmupDays,
+            b"warmupInitialLimit" => Property::WarmupInitialLimit,
+            b"warmupStart" => Property::WarmupStart,
+            b"warmupTargetLimit" => Property::WarmupTargetLimit,
             b"webPushContact" => Property::WebPushContact,
             b"webPushKey" => Property::WebPushKey,
             b"websocketHeartbeat" => Property::WebsocketHeartbeat,
+            b"websocketMaxConcurrent" => Property::WebsocketMaxConcurrent,
             b"websocketThrottle" => Property::WebsocketThrottle,
             b"websocketTimeout" => Property::WebsocketTimeout,
+            b"weight" => Property::Weight,
             b"zone" => Property::Zone,
             b"zoneIpV4" => Property::ZoneIpV4,
             b"zoneIpV6" => Property::ZoneIpV6,
@@ -1353,6 +1561,8 @@ impl EnumImpl for Property {
             Property::AccountUri => "accountUri",
             Property::Accounts => "accounts",
             Property::AcmeProviderId => "acmeProviderId",
+            Property::Action => "action",
+            Property::ActorId => "actorId",
             Property::AddAuthResultsHeader => "addAuthResultsHeader",
             Property::AddDateHeader => "addDateHeader",
             Property::AddDeliveredToHeader => "addDeliveredToHeader",
@@ -1360,6 +1570,7 @@ impl EnumImpl for Property {
             Property::AddReceivedHeader => "addReceivedHeader",
             Property::AddReceivedSpfHeader => "addReceivedSpfHeader",
             Property::AddReturnPathHeader => "addReturnPathHeader",
+            Property::AdditionalHosts => "additionalHosts",
             Property::AdditionalInformation => "additionalInformation",
             Property::Address => "address",
             Property::Addresses => "addresses",
@@ -1367,6 +1578,7 @@ impl EnumImpl for Property {
             Property::AggregateDkimSignDomain => "aggregateDkimSignDomain",
             Property::AggregateFromAddress => "aggregateFromAddress",
             Property::AggregateFromName => "aggregateFromName",
+            Property::AggregateMaxCompressedSize => "aggregateMaxCompressedSize",
             Property::AggregateMaxReportSize => "aggregateMaxReportSize",
             Property::AggregateOrgName => "aggregateOrgName",
             Property::AggregateSendFrequency => "aggregateSendFrequency",
@@ -1374,6 +1586,7 @@ impl EnumImpl for Property {
             Property::AlarmId => "alarmId",
             Property::Algorithms => "algorithms",
             Property::Aliases => "aliases",
+            Property::AllColumns => "allColumns",
             Property::AllowCount => "allowCount",
             Property::AllowDirectoryQueries => "allowDirectoryQueries",
             Property::AllowExternalRcpts => "allowExternalRcpts",
@@ -1395,6 +1608,11 @@ impl EnumImpl for Property {
             Property::ArcVerify => "arcVerify",
             Property::ArchiveDeletedAccountsFor => "archiveDeletedAccountsFor",
             Property::ArchiveDeletedItemsFor => "archiveDeletedItemsFor",
+            Property::ArchiveMaxDepth => "archiveMaxDepth",
+            Property::ArchiveMaxEntries => "archiveMaxEntries",
+            Property::ArchiveMaxRatio => "archiveMaxRatio",
+            Property::ArchiveMaxSize => "archiveMaxSize",
+            Property::ArchiveScanTimeout => "archiveScanTimeout",
             Property::ArchivedAt => "archivedAt",
             Property::ArchivedItemType => "archivedItemType",
             Property::ArchivedUntil => "archivedUntil",
@@ -1402,13 +1620,17 @@ impl EnumImpl for Property {
             Property::AsnUrls => "asnUrls",
             Property::AttemptNumber => "attemptNumber",
             Property::Attempts => "attempts",
+            Property::AttrCategorizeMail => "attrCategorizeMail",
             Property::AttrClass => "attrClass",
             Property::AttrDescription => "attrDescription",
             Property::AttrEmail => "attrEmail",
             Property::AttrEmailAlias => "attrEmailAlias",
+            Property::AttrEnabledProtocols => "attrEnabledProtocols",
             Property::AttrMemberOf => "attrMemberOf",
             Property::AttrSecret => "attrSecret",
             Property::AttrSecretChanged => "attrSecretChanged",
+            Property::AuditLog => "auditLog",
+            Property::AuditMessageReads => "auditMessageReads",
             Property::Auid => "auid",
             Property::Auth => "auth",
             Property::AuthBanPeriod => "authBanPeriod",
@@ -1424,8 +1646,14 @@ impl EnumImpl for Property {
             Property::AuthenticationResults => "authenticationResults",
             Property::AutoAddInvitations => "autoAddInvitations",
             Property::AutoUpdateFrequency => "autoUpdateFrequency",
+            Property::BackupDirectory => "backupDirectory",
+            Property::BackupIncludeBlobs => "backupIncludeBlobs",
+            Property::BackupMaxIncrements => "backupMaxIncrements",
             Property::BaseDn => "baseDn",
             Property::BaseUrl => "baseUrl",
+            Property::BatvKey => "batvKey",
+            Property::BatvPreviousKey => "batvPreviousKey",
+            Property::BatvValidity => "batvValidity",
             Property::BearerToken => "bearerToken",
             Property::Beta => "beta",
             Property::Bind => "bind",
@@ -1442,29 +1670,39 @@ impl EnumImpl for Property {
             Property::Bucket => "bucket",
             Property::BufferSize => "bufferSize",
             Property::Buffered => "buffered",
+            Property::CalendarInvitations => "calendarInvitations",
+            Property::CallbackId => "callbackId",
+            Property::CampaignId => "campaignId",
             Property::Canonicalization => "canonicalization",
             Property::CapacityClient => "capacityClient",
             Property::CapacityReadBuffer => "capacityReadBuffer",
             Property::CapacitySubscription => "capacitySubscription",
             Property::CatchAllAddress => "catchAllAddress",
             Property::Categories => "categories",
+            Property::CategorizeMail => "categorizeMail",
             Property::Certificate => "certificate",
             Property::CertificateManagement => "certificateManagement",
             Property::ChallengeType => "challengeType",
             Property::ChangesMaxResults => "changesMaxResults",
             Property::Chunking => "chunking",
+            Property::ClaimCategorizeMail => "claimCategorizeMail",
+            Property::ClaimEnabledProtocols => "claimEnabledProtocols",
             Property::ClaimGroups => "claimGroups",
             Property::ClaimName => "claimName",
             Property::ClaimUsername => "claimUsername",
             Property::Cleartext => "cleartext",
+            Property::ClientCertAccount => "clientCertAccount",
+            Property::ClientCertRevocationList => "clientCertRevocationList",
             Property::ClientId => "clientId",
             Property::ClientIp => "clientIp",
             Property::ClientSecret => "clientSecret",
             Property::ClientToken => "clientToken",
             Property::ClusterFile => "clusterFile",
+            Property::ColumnCategorizeMail => "columnCategorizeMail",
             Property::ColumnClass => "columnClass",
             Property::ColumnDescription => "columnDescription",
             Property::ColumnEmail => "columnEmail",
+            Property::ColumnEnabledProtocols => "columnEnabledProtocols",
             Property::ColumnSecret => "columnSecret",
             Property::Comment => "comment",
             Property::CompartmentOcid => "compartmentOcid",
@@ -1489,6 +1727,7 @@ impl EnumImpl for Property {
             Property::Create => "create",
             Property::CreatedAt => "createdAt",
             Property::CreatedBy => "createdBy",
+            Property::CredentialGeneration => "credentialGeneration",
             Property::CredentialId => "credentialId",
             Property::Credentials => "credentials",
             Property::CurrentSecret => "currentSecret",
@@ -1497,6 +1736,8 @@ impl EnumImpl for Property {
             Property::CustomRule => "customRule",
             Property::CustomerNumber => "customerNumber",
             Property::Dane => "dane",
+            Property::DaneMaxDelay => "daneMaxDelay",
+            Property::DaneRollover => "daneRollover",
             Property::DataCleanupSchedule => "dataCleanupSchedule",
             Property::DataStore => "dataStore",
             Property::DataTimeout => "dataTimeout",
@@ -1507,6 +1748,7 @@ impl EnumImpl for Property {
             Property::DateRangeStart => "dateRangeStart",
             Property::Day => "day",
             Property::DeadPropertyMaxSize => "deadPropertyMaxSize",
+            Property::Deduplicate => "deduplicate",
             Property::DefaultAdminRoleIds => "defaultAdminRoleIds",
             Property::DefaultCertificateId => "defaultCertificateId",
             Property::DefaultDisplayName => "defaultDisplayName",
@@ -1533,13 +1775,22 @@ impl EnumImpl for Property {
             Property::DeleteAfterUse => "deleteAfterUse",
             Property::DeliverAt => "deliverAt",
             Property::DeliverBy => "deliverBy",
+            Property::DeliveryCallback => "deliveryCallback",
             Property::DeliverTo => "deliverTo",
+            Property::DeliveryDays => "deliveryDays",
+            Property::DeliveryHourEnd => "deliveryHourEnd",
+            Property::DeliveryHourStart => "deliveryHourStart",
             Property::DeliveryResult => "deliveryResult",
+            Property::DeliveryTimeZone => "deliveryTimeZone",
             Property::Depth => "depth",
             Property::Description => "description",
             Property::Details => "details",
             Property::Directory => "directory",
+            Property::DirectoryCacheTtl => "directoryCacheTtl",
+            Property::DirectoryChain => "directoryChain",
             Property::DirectoryId => "directoryId",
+            Property::DirectoryLookupMode => "directoryLookupMode",
+            Property::DirectoryPrincipals => "directoryPrincipals",
             Property::DisableCapabilities => "disableCapabilities",
             Property::DisabledPermissions => "disabledPermissions",
             Property::DiscardAfter => "discardAfter",
@@ -1587,6 +1838,8 @@ impl EnumImpl for Property {
             Property::Domains => "domains",
             Property::Dsn => "dsn",
             Property::Due => "due",
+            Property::DuplicateDelivery => "duplicateDelivery",
+            Property::DuplicateDeliveryWindow => "duplicateDeliveryWindow",
             Property::DuplicateExpiry => "duplicateExpiry",
             Property::Duration => "duration",
             Property::EabHmacKey => "eabHmacKey",
@@ -1610,10 +1863,12 @@ impl EnumImpl for Property {
             Property::EnableEdns => "enableEdns",
             Property::EnableHsts => "enableHsts",
             Property::EnableLogExporter => "enableLogExporter",
+            Property::EnableRspamdApi => "enableRspamdApi",
             Property::EnableSpamFilter => "enableSpamFilter",
             Property::EnableSpanExporter => "enableSpanExporter",
             Property::Enabled => "enabled",
             Property::EnabledPermissions => "enabledPermissions",
+            Property::EnabledProtocols => "enabledProtocols",
             Property::EncryptAtRest => "encryptAtRest",
             Property::EncryptOnAppend => "encryptOnAppend",
             Property::EncryptionAtRest => "encryptionAtRest",
@@ -1644,6 +1899,7 @@ impl EnumImpl for Property {
             Property::EventStartTz => "eventStartTz",
             Property::Events => "events",
             Property::EventsPolicy => "eventsPolicy",
+            Property::ExpansionTraceHeader => "expansionTraceHeader",
             Property::Expire => "expire",
             Property::Expires => "expires",
             Property::ExpiresAt => "expiresAt",
@@ -1660,9 +1916,11 @@ impl EnumImpl for Property {
             Property::ExtraContactInfo => "extraContactInfo",
             Property::Factor => "factor",
             Property::FailOnTimeout => "failOnTimeout",
+            Property::FailOpen => "failOpen",
             Property::FailedAt => "failedAt",
             Property::FailedAttemptNumber => "failedAttemptNumber",
             Property::FailedSessionCount => "failedSessionCount",
+            Property::FailureCoolOff => "failureCoolOff",
             Property::FailureDetails => "failureDetails",
             Property::FailureDkimSignDomain => "failureDkimSignDomain",
             Property::FailureFromAddress => "failureFromAddress",
@@ -1671,6 +1929,7 @@ impl EnumImpl for Property {
             Property::FailureReasonCode => "failureReasonCode",
             Property::FailureSendFrequency => "failureSendFrequency",
             Property::FailureSubject => "failureSubject",
+            Property::FailureThreshold => "failureThreshold",
             Property::FeatureL2Normalize => "featureL2Normalize",
             Property::FeatureLogScale => "featureLogScale",
             Property::FeedbackType => "feedbackType",
@@ -1684,6 +1943,7 @@ impl EnumImpl for Property {
             Property::FilterMailbox => "filterMailbox",
             Property::FilterMemberOf => "filterMemberOf",
             Property::Fingerprint => "fingerprint",
+            Property::FirstSeen => "firstSeen",
             Property::Flags => "flags",
             Property::FlagsAction => "flagsAction",
             Property::FlagsProtocol => "flagsProtocol",
@@ -1692,6 +1952,7 @@ impl EnumImpl for Property {
             Property::Format => "format",
             Property::From => "from",
             Property::FromAddress => "fromAddress",
+            Property::FromAlignment => "fromAlignment",
             Property::FromEmail => "fromEmail",
             Property::FromName => "fromName",
             Property::FutureRelease => "futureRelease",
@@ -1705,14 +1966,19 @@ impl EnumImpl for Property {
             Property::GroupId => "groupId",
             Property::HeaderFrom => "headerFrom",
             Property::Headers => "headers",
+            Property::HealthCheckInterval => "healthCheckInterval",
+            Property::HiddenExtensions => "hiddenExtensions",
+            Property::HoldAuditEventsFor => "holdAuditEventsFor",
             Property::HoldMetricsFor => "holdMetricsFor",
             Property::HoldMtaReportsFor => "holdMtaReportsFor",
             Property::HoldSamplesFor => "holdSamplesFor",
             Property::HoldTracesFor => "holdTracesFor",
             Property::Host => "host",
+            Property::HostSelection => "hostSelection",
             Property::HostedZoneId => "hostedZoneId",
             Property::Hostname => "hostname",
             Property::Hour => "hour",
+            Property::HtmlBody => "htmlBody",
             Property::HttpAuth => "httpAuth",
             Property::HttpHeaders => "httpHeaders",
             Property::HttpRsvpEnable => "httpRsvpEnable",
@@ -1725,9 +1991,12 @@ impl EnumImpl for Property {
             Property::Id => "id",
             Property::IdTokenExpiry => "idTokenExpiry",
             Property::IdentityAlignment => "identityAlignment",
+            Property::IdleNotifyDelay => "idleNotifyDelay",
             Property::If => "if",
             Property::ImpersonateServiceAccount => "impersonateServiceAccount",
             Property::ImplicitTls => "implicitTls",
+            Property::ImportErrors => "importErrors",
+            Property::ImportRoot => "importRoot",
             Property::InMemoryStore => "inMemoryStore",
             Property::InboundReportAddresses => "inboundReportAddresses",
             Property::InboundReportForwarding => "inboundReportForwarding",
@@ -1776,7 +2045,9 @@ impl EnumImpl for Property {
             Property::KeyValues => "keyValues",
             Property::L1Ratio => "l1Ratio",
             Property::L2Ratio => "l2Ratio",
+            Property::LastDocumentId => "lastDocumentId",
             Property::LastRenewal => "lastRenewal",
+            Property::LastSeen => "lastSeen",
             Property::LearnHamFromCard => "learnHamFromCard",
             Property::LearnHamFromReply => "learnHamFromReply",
             Property::LearnSpamFromRblHits => "learnSpamFromRblHits",
@@ -1787,7 +2058,12 @@ impl EnumImpl for Property {
             Property::ListenerIds => "listenerIds",
             Property::Listeners => "listeners",
             Property::LivePropertyMaxSize => "livePropertyMaxSize",
+            Property::LmtpLocalDelivery => "lmtpLocalDelivery",
             Property::Locale => "locale",
+            Property::LogEnabled => "logEnabled",
+            Property::LogHeader => "logHeader",
+            Property::LogMaxEntries => "logMaxEntries",
+            Property::LogRetention => "logRetention",
             Property::Logo => "logo",
             Property::LogoUrl => "logoUrl",
             Property::LoiterBanPeriod => "loiterBanPeriod",
@@ -1798,10 +2074,14 @@ impl EnumImpl for Property {
             Property::MailFrom => "mailFrom",
             Property::MailFromTimeout => "mailFromTimeout",
             Property::MailRua => "mailRua",
+            Property::MailboxId => "mailboxId",
+            Property::MailboxName => "mailboxName",
             Property::MailingLists => "mailingLists",
             Property::MaintenanceType => "maintenanceType",
             Property::ManagedZone => "managedZone",
             Property::Match => "match",
+            Property::MatchKeywords => "matchKeywords",
+            Property::MatchTags => "matchTags",
             Property::MaxAddressBooks => "maxAddressBooks",
             Property::MaxAge => "maxAge",
             Property::MaxAllowedPacket => "maxAllowedPacket",
@@ -1812,6 +2092,8 @@ impl EnumImpl for Property {
             Property::MaxAttendees => "maxAttendees",
             Property::MaxAuthFailures => "maxAuthFailures",
             Property::MaxCalendars => "maxCalendars",
+            Property::MaxChangesAge => "maxChangesAge",
+            Property::MaxChangesAgePerCollection => "maxChangesAgePerCollection",
             Property::MaxChangesHistory => "maxChangesHistory",
             Property::MaxConcurrent => "maxConcurrent",
             Property::MaxConcurrentRequests => "maxConcurrentRequests",
@@ -1820,14 +2102,20 @@ impl EnumImpl for Property {
             Property::MaxContacts => "maxContacts",
             Property::MaxCpuCycles => "maxCpuCycles",
             Property::MaxDelay => "maxDelay",
+            Property::MaxDelayedSend => "maxDelayedSend",
+            Property::MaxDeliveryCallbacks => "maxDeliveryCallbacks",
             Property::MaxDuration => "maxDuration",
             Property::MaxEntries => "maxEntries",
             Property::MaxEntrySize => "maxEntrySize",
             Property::MaxEventNotifications => "maxEventNotifications",
             Property::MaxEvents => "maxEvents",
+            Property::MaxExpansionDepth => "maxExpansionDepth",
+            Property::MaxExpansionRecipients => "maxExpansionRecipients",
+            Property::MaxExpiryDuplicate => "maxExpiryDuplicate",
             Property::MaxFailures => "maxFailures",
             Property::MaxFiles => "maxFiles",
             Property::MaxFolders => "maxFolders",
+            Property::MaxFollowUps => "maxFollowUps",
             Property::MaxHeaderSize => "maxHeaderSize",
             Property::MaxICalendarSize => "maxICalendarSize",
             Property::MaxIdentities => "maxIdentities",
@@ -1856,7 +2144,10 @@ impl EnumImpl for Property {
             Property::MaxRecipients => "maxRecipients",
             Property::MaxReconnects => "maxReconnects",
             Property::MaxRecurrenceExpansions => "maxRecurrenceExpansions",
+            Property::MaxRedirectDestinations => "maxRedirectDestinations",
+            Property::MaxRedirectPasses => "maxRedirectPasses",
             Property::MaxRedirects => "maxRedirects",
+            Property::MaxRedirectsPerDay => "maxRedirectsPerDay",
             Property::MaxReportSize => "maxReportSize",
             Property::MaxRequestRate => "maxRequestRate",
             Property::MaxRequestSize => "maxRequestSize",
@@ -1867,6 +2158,8 @@ impl EnumImpl for Property {
             Property::MaxScriptNameLength => "maxScriptNameLength",
             Property::MaxScriptSize => "maxScriptSize",
             Property::MaxScripts => "maxScripts",
+            Property::MaxSessions => "maxSessions",
+            Property::MaxSessionsPerProtocol => "maxSessionsPerProtocol",
             Property::MaxShares => "maxShares",
             Property::MaxSize => "maxSize",
             Property::MaxStringLength => "maxStringLength",
@@ -1883,12 +2176,23 @@ impl EnumImpl for Property {
             Property::MessageIdHostname => "messageIdHostname",
             Property::MessageIds => "messageIds",
             Property::Messages => "messages",
+            Property::MessagesFailed => "messagesFailed",
+            Property::MessagesImported => "messagesImported",
+            Property::MessagesIndexed => "messagesIndexed",
+            Property::MessagesProcessed => "messagesProcessed",
+            Property::MessagesReEncrypted => "messagesReEncrypted",
+            Property::MessagesSkipped => "messagesSkipped",
+            Property::MessagesTotal => "messagesTotal",
+            Property::Metadata => "metadata",
+            Property::MethodProfiling => "methodProfiling",
             Property::Metric => "metric",
             Property::Metrics => "metrics",
             Property::MetricsCollectionInterval => "metricsCollectionInterval",
             Property::MetricsPolicy => "metricsPolicy",
             Property::MinHamSamples => "minHamSamples",
+            Property::MinMatches => "minMatches",
             Property::MinRetryWait => "minRetryWait",
+            Property::MinSenderMessages => "minSenderMessages",
             Property::MinSpamSamples => "minSpamSamples",
             Property::MinTriggerInterval => "minTriggerInterval",
             Property::Minute => "minute",
@@ -1898,6 +2202,9 @@ impl EnumImpl for Property {
             Property::ModelType => "modelType",
             Property::MtPriority => "mtPriority",
             Property::MtaSts => "mtaSts",
+            Property::MtaStsMaxAge => "mtaStsMaxAge",
+            Property::MtaStsMode => "mtaStsMode",
+            Property::MtaStsMxHosts => "mtaStsMxHosts",
             Property::MtaStsTimeout => "mtaStsTimeout",
             Property::Multiline => "multiline",
             Property::MustMatchSender => "mustMatchSender",
@@ -1920,6 +2227,7 @@ impl EnumImpl for Property {
             Property::NumFeatures => "numFeatures",
             Property::NumReplicas => "numReplicas",
             Property::NumShards => "numShards",
+            Property::ObjectIds => "objectIds",
             Property::OnSuccessRenewCertificate => "onSuccessRenewCertificate",
             Property::OpenTelemetry => "openTelemetry",
             Property::Options => "options",
@@ -1998,6 +2306,10 @@ impl EnumImpl for Property {
             Property::Protocol => "protocol",
             Property::ProtocolVersion => "protocolVersion",
             Property::ProviderInfo => "providerInfo",
+            Property::ProvisionFolders => "provisionFolders",
+            Property::ProvisionSieveScript => "provisionSieveScript",
+            Property::ProvisionSignature => "provisionSignature",
+            Property::ProvisionSubscribe => "provisionSubscribe",
             Property::ProxyTrustedNetworks => "proxyTrustedNetworks",
             Property::PublicKey => "publicKey",
             Property::PublishRecords => "publishRecords",
@@ -2015,6 +2327,13 @@ impl EnumImpl for Property {
             Property::QueryRecipient => "queryRecipient",
             Property::QueueId => "queueId",
             Property::QueueName => "queueName",
+            Property::QueuePreviewSize => "queuePreviewSize",
+            Property::QueueTopDomains => "queueTopDomains",
+            Property::QuotaReconcileInterval => "quotaReconcileInterval",
+            Property::QuotaReconcileSampleSize => "quotaReconcileSampleSize",
+            Property::QuotaWarningInterval => "quotaWarningInterval",
+            Property::QuotaWarningNotify => "quotaWarningNotify",
+            Property::QuotaWarningThresholds => "quotaWarningThresholds",
             Property::Quotas => "quotas",
             Property::Rate => "rate",
             Property::RateLimit => "rateLimit",
@@ -2022,33 +2341,55 @@ impl EnumImpl for Property {
             Property::RateLimitAuthenticated => "rateLimitAuthenticated",
             Property::Ratio => "ratio",
             Property::RcptToTimeout => "rcptToTimeout",
+            Property::ReEncryptConcurrency => "reEncryptConcurrency",
             Property::ReadFromReplicas => "readFromReplicas",
+            Property::ReadReplicaStickiness => "readReplicaStickiness",
             Property::ReadReplicas => "readReplicas",
+            Property::ReadinessCacheTtl => "readinessCacheTtl",
+            Property::ReadinessCheckTimeout => "readinessCheckTimeout",
+            Property::ReadinessOptional => "readinessOptional",
+            Property::ReadinessTimeout => "readinessTimeout",
             Property::Reason => "reason",
+            Property::RecallNotice => "recallNotice",
+            Property::RecallWindow => "recallWindow",
+            Property::ReceivedAfter => "receivedAfter",
             Property::ReceivedAt => "receivedAt",
+            Property::ReceivedBefore => "receivedBefore",
             Property::ReceivedFromIp => "receivedFromIp",
             Property::ReceivedViaPort => "receivedViaPort",
             Property::ReceivingIp => "receivingIp",
             Property::ReceivingMxHelo => "receivingMxHelo",
             Property::ReceivingMxHostname => "receivingMxHostname",
+            Property::Recipient => "recipient",
             Property::Recipients => "recipients",
             Property::Records => "records",
             Property::RecurrenceId => "recurrenceId",
+            Property::RedirectEnvelopeSender => "redirectEnvelopeSender",
             Property::RedirectRoot => "redirectRoot",
             Property::RedirectUris => "redirectUris",
             Property::Refresh => "refresh",
             Property::RefreshTokenExpiry => "refreshTokenExpiry",
             Property::RefreshTokenRenewal => "refreshTokenRenewal",
             Property::Region => "region",
+            Property::ReindexConcurrency => "reindexConcurrency",
+            Property::ReindexRateLimit => "reindexRateLimit",
+            Property::RejectHiddenExtensions => "rejectHiddenExtensions",
             Property::RejectNonFqdn => "rejectNonFqdn",
             Property::RemoteIp => "remoteIp",
             Property::RenewBefore => "renewBefore",
             Property::Report => "report",
             Property::ReportAddressUri => "reportAddressUri",
+            Property::ReportFromAddress => "reportFromAddress",
             Property::ReportId => "reportId",
             Property::ReportedDomains => "reportedDomains",
             Property::ReportedUris => "reportedUris",
             Property::ReportingMta => "reportingMta",
+            Property::ReputationBadScore => "reputationBadScore",
+            Property::ReputationEnable => "reputationEnable",
+            Property::ReputationGoodScore => "reputationGoodScore",
+            Property::ReputationMinMessages => "reputationMinMessages",
+            Property::ReputationSkipGreylist => "reputationSkipGreylist",
+            Property::ReputationWindow => "reputationWindow",
             Property::RequestMaxSize => "requestMaxSize",
             Property::RequestTlsCertificate => "requestTlsCertificate",
             Property::Require => "require",
@@ -2063,18 +2404,23 @@ impl EnumImpl for Property {
             Property::ResponseEnhanced => "responseEnhanced",
             Property::ResponseHeaders => "responseHeaders",
             Property::ResponseHostname => "responseHostname",
+            Property::ResponseId => "responseId",
             Property::ResponseMessage => "responseMessage",
             Property::ResponsePosCategory => "responsePosCategory",
             Property::ResponsePosConfidence => "responsePosConfidence",
             Property::ResponsePosExplanation => "responsePosExplanation",
             Property::Result => "result",
             Property::ResultType => "resultType",
+            Property::ResumeAfter => "resumeAfter",
             Property::RetireAfter => "retireAfter",
             Property::Retry => "retry",
             Property::RetryCount => "retryCount",
             Property::RetryDue => "retryDue",
             Property::ReturnPath => "returnPath",
             Property::ReuseKey => "reuseKey",
+            Property::ReverseIpRequire => "reverseIpRequire",
+            Property::ReverseIpRequireTempFail => "reverseIpRequireTempFail",
+            Property::ReverseIpTimeout => "reverseIpTimeout",
             Property::ReverseIpVerify => "reverseIpVerify",
             Property::Rewrite => "rewrite",
             Property::RoleIds => "roleIds",
@@ -2082,7 +2428,10 @@ impl EnumImpl for Property {
             Property::Rotate => "rotate",
             Property::RotateAfter => "rotateAfter",
             Property::Route => "route",
+            Property::RspamdApiMaxConcurrent => "rspamdApiMaxConcurrent",
+            Property::RspamdApiSecret => "rspamdApiSecret",
             Property::Rua => "rua",
+            Property::RuaMaxSize => "ruaMaxSize",
             Property::Sandbox => "sandbox",
             Property::SasToken => "sasToken",
             Property::SaslMechanisms => "saslMechanisms",
@@ -2098,6 +2447,8 @@ impl EnumImpl for Property {
             Property::ScoreReject => "scoreReject",
             Property::ScoreSpam => "scoreSpam",
             Property::Script => "script",
+            Property::SearchCacheSize => "searchCacheSize",
+            Property::SearchCacheTtl => "searchCacheTtl",
             Property::SearchStore => "searchStore",
             Property::Secret => "secret",
             Property::SecretAccessKey => "secretAccessKey",
@@ -2108,6 +2459,14 @@ impl EnumImpl for Property {
             Property::Selector => "selector",
             Property::SelectorTemplate => "selectorTemplate",
             Property::SendFrequency => "sendFrequency",
+            Property::Sender => "sender",
+            Property::SenderVerify => "senderVerify",
+            Property::SenderVerifyFailTtl => "senderVerifyFailTtl",
+            Property::SenderVerifyMaxConcurrent => "senderVerifyMaxConcurrent",
+            Property::SenderVerifyPassTtl => "senderVerifyPassTtl",
+            Property::SenderVerifyRate => "senderVerifyRate",
+            Property::SenderVerifySkipDomains => "senderVerifySkipDomains",
+            Property::SenderVerifyTimeout => "senderVerifyTimeout",
             Property::SendingMtaIp => "sendingMtaIp",
             Property::SentinelSecret => "sentinelSecret",
             Property::SentinelUsername => "sentinelUsername",
@@ -2120,7 +2479,10 @@ impl EnumImpl for Property {
             Property::SessionToken => "sessionToken",
             Property::SetMaxObjects => "setMaxObjects",
             Property::ShardIndex => "shardIndex",
+            Property::SharedBlobQuota => "sharedBlobQuota",
             Property::SharedSecret => "sharedSecret",
+            Property::ShutdownGracePeriod => "shutdownGracePeriod",
+            Property::SieveLogging => "sieveLogging",
             Property::Sig0Algorithm => "sig0Algorithm",
             Property::SignatureAlgorithm => "signatureAlgorithm",
             Property::SignatureKey => "signatureKey",
@@ -2128,6 +2490,8 @@ impl EnumImpl for Property {
             Property::Size => "size",
             Property::SkipDeploy => "skipDeploy",
             Property::SkipFirst => "skipFirst",
+            Property::SlowMethodThreshold => "slowMethodThreshold",
+            Property::SlowMethodThresholdPerMethod => "slowMethodThresholdPerMethod",
             Property::SmtpGreeting => "smtpGreeting",
             Property::SnippetMaxResults => "snippetMaxResults",
             Property::SocketBacklog => "socketBacklog",
@@ -2139,9 +2503,11 @@ impl EnumImpl for Property {
             Property::SocketTosV4 => "socketTosV4",
             Property::SocketTtl => "socketTtl",
             Property::SourceIp => "sourceIp",
+            Property::SourceIpSelection => "sourceIpSelection",
             Property::SourceIps => "sourceIps",
             Property::SourcePort => "sourcePort",
             Property::SpamFilterRulesUrl => "spamFilterRulesUrl",
+            Property::SpamThreshold => "spamThreshold",
             Property::SpfDns => "spfDns",
             Property::SpfEhloDomain => "spfEhloDomain",
             Property::SpfEhloResult => "spfEhloResult",
@@ -2150,15 +2516,22 @@ impl EnumImpl for Property {
             Property::SpfMailFromDomain => "spfMailFromDomain",
             Property::SpfMailFromResult => "spfMailFromResult",
             Property::SpfResults => "spfResults",
+            Property::SplitFrom => "splitFrom",
             Property::Stage => "stage",
+            Property::StagedUntil => "stagedUntil",
             Property::Stages => "stages",
             Property::StartTime => "startTime",
             Property::StartTls => "startTls",
             Property::Status => "status",
+            Property::StepUpMaxAge => "stepUpMaxAge",
+            Property::StepUpPermissions => "stepUpPermissions",
             Property::StorageAccount => "storageAccount",
             Property::Store => "store",
             Property::Stores => "stores",
             Property::Strategy => "strategy",
+            Property::StreamChunkSize => "streamChunkSize",
+            Property::Strict => "strict",
+            Property::StrictReferences => "strictReferences",
             Property::SubAddressing => "subAddressing",
             Property::SubAuthId => "subAuthId",
             Property::Subject => "subject",
@@ -2168,8 +2541,11 @@ impl EnumImpl for Property {
             Property::Sum => "sum",
             Property::Summary => "summary",
             Property::SupportedLanguages => "supportedLanguages",
+            Property::SuppressNoMx => "suppressNoMx",
+            Property::SuppressionList => "suppressionList",
             Property::Tag => "tag",
             Property::Tags => "tags",
+            Property::TargetMailbox => "targetMailbox",
             Property::TaskTypes => "taskTypes",
             Property::Tasks => "tasks",
             Property::TcpOnError => "tcpOnError",
@@ -2177,16 +2553,19 @@ impl EnumImpl for Property {
             Property::TempFailOnError => "tempFailOnError",
             Property::Temperature => "temperature",
             Property::Template => "template",
+            Property::TemplateId => "templateId",
             Property::TenancyOcid => "tenancyOcid",
             Property::TenantId => "tenantId",
             Property::Tenants => "tenants",
             Property::Text => "text",
+            Property::TextBody => "textBody",
             Property::Then => "then",
             Property::ThirdParty => "thirdParty",
             Property::ThirdPartyHash => "thirdPartyHash",
             Property::ThreadName => "threadName",
             Property::ThreadPoolSize => "threadPoolSize",
             Property::ThreadsPerNode => "threadsPerNode",
+            Property::Threshold => "threshold",
             Property::Throttle => "throttle",
             Property::TimeZone => "timeZone",
             Property::Timeout => "timeout",
@@ -2203,13 +2582,17 @@ impl EnumImpl for Property {
             Property::Timestamp => "timestamp",
             Property::Title => "title",
             Property::Tls => "tls",
+            Property::TlsClientCaCertificates => "tlsClientCaCertificates",
             Property::TlsDisableCipherSuites => "tlsDisableCipherSuites",
             Property::TlsDisableProtocols => "tlsDisableProtocols",
             Property::TlsIgnoreClientOrder => "tlsIgnoreClientOrder",
             Property::TlsImplicit => "tlsImplicit",
+            Property::TlsRequestClientCert => "tlsRequestClientCert",
+            Property::TlsRequireClientCert => "tlsRequireClientCert",
             Property::TlsTimeout => "tlsTimeout",
             Property::To => "to",
             Property::Token => "token",
+            Property::TokenValidity => "tokenValidity",
             Property::TotalDeadline => "totalDeadline",
             Property::TotalFailedSessions => "totalFailedSessions",
             Property::TotalSuccessfulSessions => "totalSuccessfulSessions",
@@ -2222,6 +2605,9 @@ impl EnumImpl for Property {
             Property::TransferLimit => "transferLimit",
             Property::TrustContacts => "trustContacts",
             Property::TrustReplies => "trustReplies",
+            Property::TrustedForwarderDepth => "trustedForwarderDepth",
+            Property::TrustedForwarders => "trustedForwarders",
+            Property::TrustedRedirectHosts => "trustedRedirectHosts",
             Property::TsigAlgorithm => "tsigAlgorithm",
             Property::Ttl => "ttl",
             Property::UnpackDirectory => "unpackDirectory",
@@ -2250,11 +2636,17 @@ impl EnumImpl for Property {
             Property::Vrfy => "vrfy",
             Property::WaitOnFail => "waitOnFail",
             Property::WapiVersion => "wapiVersion",
+            Property::WarmupDays => "warmupDays",
+            Property::WarmupInitialLimit => "warmupInitialLimit",
+            Property::WarmupStart => "warmupStart",
+            Property::WarmupTargetLimit => "warmupTargetLimit",
             Property::WebPushContact => "webPushContact",
             Property::WebPushKey => "webPushKey",
             Property::WebsocketHeartbeat => "websocketHeartbeat",
+            Property::WebsocketMaxConcurrent => "websocketMaxConcurrent",
             Property::WebsocketThrottle => "websocketThrottle",
             Property::WebsocketTimeout => "websocketTimeout",
+            Property::Weight => "weight",
             Property::Zone => "zone",
             Property::ZoneIpV4 => "zoneIpV4",
             Property::ZoneIpV6 => "zoneIpV6",
@@ -2285,6 +2677,8 @@ impl EnumImpl for Property {
             16 => Some(Property::AccountUri),
             151 => Some(Property::Accounts),
             182 => Some(Property::AcmeProviderId),
+            941 => Some(Property::Action),
+            943 => Some(Property::ActorId),
             554 => Some(Property::AddAuthResultsHeader),
             555 => Some(Property::AddDateHeader),
             556 => Some(Property::AddDeliveredToHeader),
@@ -2292,6 +2686,7 @@ impl EnumImpl for Property {
             558 => Some(Property::AddReceivedHeader),
             559 => Some(Property::AddReceivedSpfHeader),
             560 => Some(Property::AddReturnPathHeader),
+            1033 => Some(Property::AdditionalHosts),
             838 => Some(Property::AdditionalInformation),
             44 => Some(Property::Address),
             579 => Some(Property::Addresses),
@@ -2299,6 +2694,7 @@ impl EnumImpl for Property {
             274 => Some(Property::AggregateDkimSignDomain),
             269 => Some(Property::AggregateFromAddress),
             270 => Some(Property::AggregateFromName),
+            1029 => Some(Property::AggregateMaxCompressedSize),
             271 => Some(Property::AggregateMaxReportSize),
             272 => Some(Property::AggregateOrgName),
             273 => Some(Property::AggregateSendFrequency),
@@ -2306,6 +2702,7 @@ impl EnumImpl for Property {
             798 => Some(Property::AlarmId),
             225 => Some(Property::Algorithms),
             339 => Some(Property::Aliases),
+            1025 => Some(Property::AllColumns),
             768 => Some(Property::AllowCount),
             695 => Some(Property::AllowDirectoryQueries),
             164 => Some(Property::AllowExternalRcpts),
@@ -2327,6 +2724,11 @@ impl EnumImpl for Property {
             690 => Some(Property::ArcVerify),
             203 => Some(Property::ArchiveDeletedAccountsFor),
             202 => Some(Property::ArchiveDeletedItemsFor),
+            997 => Some(Property::ArchiveMaxDepth),
+            998 => Some(Property::ArchiveMaxEntries),
+            999 => Some(Property::ArchiveMaxRatio),
+            1000 => Some(Property::ArchiveMaxSize),
+            1001 => Some(Property::ArchiveScanTimeout),
             58 => Some(Property::ArchivedAt),
             820 => Some(Property::ArchivedItemType),
             59 => Some(Property::ArchivedUntil),
@@ -2334,13 +2736,17 @@ impl EnumImpl for Property {
             102 => Some(Property::AsnUrls),
             829 => Some(Property::AttemptNumber),
             303 => Some(Property::Attempts),
+            1099 => Some(Property::AttrCategorizeMail),
             470 => Some(Property::AttrClass),
             471 => Some(Property::AttrDescription),
             472 => Some(Property::AttrEmail),
             473 => Some(Property::AttrEmailAlias),
+            1076 => Some(Property::AttrEnabledProtocols),
             474 => Some(Property::AttrMemberOf),
             475 => Some(Property::AttrSecret),
             476 => Some(Property::AttrSecretChanged),
+            945 => Some(Property::AuditLog),
+            946 => Some(Property::AuditMessageReads),
             215 => Some(Property::Auid),
             897 => Some(Property::Auth),
             680 => Some(Property::AuthBanPeriod),
@@ -2356,8 +2762,14 @@ impl EnumImpl for Property {
             69 => Some(Property::AuthenticationResults),
             171 => Some(Property::AutoAddInvitations),
             53 => Some(Property::AutoUpdateFrequency),
+            1094 => Some(Property::BackupDirectory),
+            1096 => Some(Property::BackupIncludeBlobs),
+            1095 => Some(Property::BackupMaxIncrements),
             463 => Some(Property::BaseDn),
             882 => Some(Property::BaseUrl),
+            1055 => Some(Property::BatvKey),
+            1056 => Some(Property::BatvPreviousKey),
+            1057 => Some(Property::BatvValidity),
             403 => Some(Property::BearerToken),
             389 => Some(Property::Beta),
             589 => Some(Property::Bind),
@@ -2374,29 +2786,39 @@ impl EnumImpl for Property {
             658 => Some(Property::Bucket),
             656 => Some(Property::BufferSize),
             863 => Some(Property::Buffered),
+            1011 => Some(Property::CalendarInvitations),
+            1089 => Some(Property::CallbackId),
+            1066 => Some(Property::CampaignId),
             216 => Some(Property::Canonicalization),
             584 => Some(Property::CapacityClient),
             585 => Some(Property::CapacityReadBuffer),
             586 => Some(Property::CapacitySubscription),
             346 => Some(Property::CatchAllAddress),
             759 => Some(Property::Categories),
+            1086 => Some(Property::CategorizeMail),
             176 => Some(Property::Certificate),
             342 => Some(Property::CertificateManagement),
             10 => Some(Property::ChallengeType),
             435 => Some(Property::ChangesMaxResults),
             517 => Some(Property::Chunking),
+            1101 => Some(Property::ClaimCategorizeMail),
+            1098 => Some(Property::ClaimEnabledProtocols),
             612 => Some(Property::ClaimGroups),
             611 => Some(Property::ClaimName),
             609 => Some(Property::ClaimUsername),
             693 => Some(Property::Cleartext),
+            1014 => Some(Property::ClientCertAccount),
+            1105 => Some(Property::ClientCertRevocationList),
             604 => Some(Property::ClientId),
             898 => Some(Property::ClientIp),
             878 => Some(Property::ClientSecret),
             889 => Some(Property::ClientToken),
             382 => Some(Property::ClusterFile),
+            1100 => Some(Property::ColumnCategorizeMail),
             781 => Some(Property::ColumnClass),
             782 => Some(Property::ColumnDescription),
             779 => Some(Property::ColumnEmail),
+            1077 => Some(Property::ColumnEnabledProtocols),
             780 => Some(Property::ColumnSecret),
             240 => Some(Property::Comment),
             905 => Some(Property::CompartmentOcid),
@@ -2421,6 +2843,7 @@ impl EnumImpl for Property {
             367 => Some(Property::Create),
             46 => Some(Property::CreatedAt),
             486 => Some(Property::CreatedBy),
+            1031 => Some(Property::CredentialGeneration),
             627 => Some(Property::CredentialId),
             588 => Some(Property::Credentials),
             4 => Some(Property::CurrentSecret),
@@ -2429,6 +2852,8 @@ impl EnumImpl for Property {
             787 => Some(Property::CustomRule),
             899 => Some(Property::CustomerNumber),
             569 => Some(Property::Dane),
+            1072 => Some(Property::DaneMaxDelay),
+            1071 => Some(Property::DaneRollover),
             199 => Some(Property::DataCleanupSchedule),
             125 => Some(Property::DataStore),
             506 => Some(Property::DataTimeout),
@@ -2439,6 +2864,7 @@ impl EnumImpl for Property {
             845 => Some(Property::DateRangeStart),
             192 => Some(Property::Day),
             868 => Some(Property::DeadPropertyMaxSize),
+            967 => Some(Property::Deduplicate),
             108 => Some(Property::DefaultAdminRoleIds),
             790 => Some(Property::DefaultCertificateId),
             20 => Some(Property::DefaultDisplayName),
@@ -2465,13 +2891,22 @@ impl EnumImpl for Property {
             777 => Some(Property::DeleteAfterUse),
             238 => Some(Property::DeliverAt),
             518 => Some(Property::DeliverBy),
+            1102 => Some(Property::DeliveryCallback),
             404 => Some(Property::DeliverTo),
+            948 => Some(Property::DeliveryDays),
+            950 => Some(Property::DeliveryHourEnd),
+            949 => Some(Property::DeliveryHourStart),
             82 => Some(Property::DeliveryResult),
+            951 => Some(Property::DeliveryTimeZone),
             381 => Some(Property::Depth),
             6 => Some(Property::Description),
             297 => Some(Property::Details),
             12 => Some(Property::Directory),
+            1040 => Some(Property::DirectoryCacheTtl),
+            1038 => Some(Property::DirectoryChain),
             104 => Some(Property::DirectoryId),
+            1039 => Some(Property::DirectoryLookupMode),
+            1041 => Some(Property::DirectoryPrincipals),
             711 => Some(Property::DisableCapabilities),
             629 => Some(Property::DisabledPermissions),
             872 => Some(Property::DiscardAfter),
@@ -2519,6 +2954,8 @@ impl EnumImpl for Property {
             146 => Some(Property::Domains),
             519 => Some(Property::Dsn),
             797 => Some(Property::Due),
+            987 => Some(Property::DuplicateDelivery),
+            988 => Some(Property::DuplicateDeliveryWindow),
             699 => Some(Property::DuplicateExpiry),
             515 => Some(Property::Duration),
             13 => Some(Property::EabHmacKey),
@@ -2542,10 +2979,12 @@ impl EnumImpl for Property {
             305 => Some(Property::EnableEdns),
             399 => Some(Property::EnableHsts),
             860 => Some(Property::EnableLogExporter),
+            972 => Some(Property::EnableRspamdApi),
             562 => Some(Property::EnableSpamFilter),
             861 => Some(Property::EnableSpanExporter),
             50 => Some(Property::Enabled),
             628 => Some(Property::EnabledPermissions),
+            1075 => Some(Property::EnabledProtocols),
             358 => Some(Property::EncryptAtRest),
             357 => Some(Property::EncryptOnAppend),
             9 => Some(Property::EncryptionAtRest),
@@ -2576,6 +3015,7 @@ impl EnumImpl for Property {
             802 => Some(Property::EventStartTz),
             142 => Some(Property::Events),
             855 => Some(Property::EventsPolicy),
+            996 => Some(Property::ExpansionTraceHeader),
             217 => Some(Property::Expire),
             100 => Some(Property::Expires),
             47 => Some(Property::ExpiresAt),
@@ -2592,9 +3032,11 @@ impl EnumImpl for Property {
             243 => Some(Property::ExtraContactInfo),
             821 => Some(Property::Factor),
             490 => Some(Property::FailOnTimeout),
+            942 => Some(Property::FailOpen),
             826 => Some(Property::FailedAt),
             827 => Some(Property::FailedAttemptNumber),
             837 => Some(Property::FailedSessionCount),
+            1036 => Some(Property::FailureCoolOff),
             851 => Some(Property::FailureDetails),
             279 => Some(Property::FailureDkimSignDomain),
             276 => Some(Property::FailureFromAddress),
@@ -2603,6 +3045,7 @@ impl EnumImpl for Property {
             839 => Some(Property::FailureReasonCode),
             278 => Some(Property::FailureSendFrequency),
             280 => Some(Property::FailureSubject),
+            1035 => Some(Property::FailureThreshold),
             738 => Some(Property::FeatureL2Normalize),
             739 => Some(Property::FeatureLogScale),
             67 => Some(Property::FeedbackType),
@@ -2616,6 +3059,7 @@ impl EnumImpl for Property {
             468 => Some(Property::FilterMailbox),
             469 => Some(Property::FilterMemberOf),
             902 => Some(Property::Fingerprint),
+            1092 => Some(Property::FirstSeen),
             638 => Some(Property::Flags),
             537 => Some(Property::FlagsAction),
             538 => Some(Property::FlagsProtocol),
@@ -2624,6 +3068,7 @@ impl EnumImpl for Property {
             415 => Some(Property::Format),
             62 => Some(Property::From),
             39 => Some(Property::FromAddress),
+            958 => Some(Property::FromAlignment),
             165 => Some(Property::FromEmail),
             40 => Some(Property::FromName),
             521 => Some(Property::FutureRelease),
@@ -2637,14 +3082,19 @@ impl EnumImpl for Property {
             460 => Some(Property::GroupId),
             265 => Some(Property::HeaderFrom),
             93 => Some(Property::Headers),
+            1037 => Some(Property::HealthCheckInterval),
+            979 => Some(Property::HiddenExtensions),
+            947 => Some(Property::HoldAuditEventsFor),
             206 => Some(Property::HoldMetricsFor),
             204 => Some(Property::HoldMtaReportsFor),
             730 => Some(Property::HoldSamplesFor),
             205 => Some(Property::HoldTracesFor),
             333 => Some(Property::Host),
+            1034 => Some(Property::HostSelection),
             331 => Some(Property::HostedZoneId),
             185 => Some(Property::Hostname),
             190 => Some(Property::Hour),
+            1060 => Some(Property::HtmlBody),
             32 => Some(Property::HttpAuth),
             33 => Some(Property::HttpHeaders),
             168 => Some(Property::HttpRsvpEnable),
@@ -2657,9 +3107,12 @@ impl EnumImpl for Property {
             1 => Some(Property::Id),
             621 => Some(Property::IdTokenExpiry),
             91 => Some(Property::IdentityAlignment),
+            965 => Some(Property::IdleNotifyDelay),
             376 => Some(Property::If),
             320 => Some(Property::ImpersonateServiceAccount),
             546 => Some(Property::ImplicitTls),
+            970 => Some(Property::ImportErrors),
+            971 => Some(Property::ImportRoot),
             128 => Some(Property::InMemoryStore),
             651 => Some(Property::InboundReportAddresses),
             652 => Some(Property::InboundReportForwarding),
@@ -2708,7 +3161,9 @@ impl EnumImpl for Property {
             853 => Some(Property::KeyValues),
             391 => Some(Property::L1Ratio),
             392 => Some(Property::L2Ratio),
+            952 => Some(Property::LastDocumentId),
             186 => Some(Property::LastRenewal),
+            1093 => Some(Property::LastSeen),
             727 => Some(Property::LearnHamFromCard),
             735 => Some(Property::LearnHamFromReply),
             728 => Some(Property::LearnSpamFromRblHits),
@@ -2719,7 +3174,12 @@ impl EnumImpl for Property {
             183 => Some(Property::ListenerIds),
             188 => Some(Property::Listeners),
             869 => Some(Property::LivePropertyMaxSize),
+            1079 => Some(Property::LmtpLocalDelivery),
             7 => Some(Property::Locale),
+            1043 => Some(Property::LogEnabled),
+            1046 => Some(Property::LogHeader),
+            1044 => Some(Property::LogMaxEntries),
+            1045 => Some(Property::LogRetention),
             341 => Some(Property::Logo),
             371 => Some(Property::LogoUrl),
             682 => Some(Property::LoiterBanPeriod),
@@ -2730,10 +3190,14 @@ impl EnumImpl for Property {
             284 => Some(Property::MailFrom),
             509 => Some(Property::MailFromTimeout),
             841 => Some(Property::MailRua),
+            959 => Some(Property::MailboxId),
+            1081 => Some(Property::MailboxName),
             154 => Some(Property::MailingLists),
             796 => Some(Property::MaintenanceType),
             318 => Some(Property::ManagedZone),
             374 => Some(Property::Match),
+            1083 => Some(Property::MatchKeywords),
+            1082 => Some(Property::MatchTags),
             23 => Some(Property::MaxAddressBooks),
             566 => Some(Property::MaxAge),
             576 => Some(Property::MaxAllowedPacket),
@@ -2744,6 +3208,8 @@ impl EnumImpl for Property {
             157 => Some(Property::MaxAttendees),
             425 => Some(Property::MaxAuthFailures),
             160 => Some(Property::MaxCalendars),
+            1047 => Some(Property::MaxChangesAge),
+            1048 => Some(Property::MaxChangesAgePerCollection),
             201 => Some(Property::MaxChangesHistory),
             426 => Some(Property::MaxConcurrent),
             439 => Some(Property::MaxConcurrentRequests),
@@ -2752,14 +3218,20 @@ impl EnumImpl for Property {
             24 => Some(Property::MaxContacts),
             702 => Some(Property::MaxCpuCycles),
             823 => Some(Property::MaxDelay),
+            1019 => Some(Property::MaxDelayedSend),
+            938 => Some(Property::MaxDeliveryCallbacks),
             530 => Some(Property::MaxDuration),
             417 => Some(Property::MaxEntries),
             418 => Some(Property::MaxEntrySize),
             163 => Some(Property::MaxEventNotifications),
             161 => Some(Property::MaxEvents),
+            994 => Some(Property::MaxExpansionDepth),
+            995 => Some(Property::MaxExpansionRecipients),
+            991 => Some(Property::MaxExpiryDuplicate),
             547 => Some(Property::MaxFailures),
             378 => Some(Property::MaxFiles),
             379 => Some(Property::MaxFolders),
+            1080 => Some(Property::MaxFollowUps),
             715 => Some(Property::MaxHeaderSize),
             159 => Some(Property::MaxICalendarSize),
             363 => Some(Property::MaxIdentities),
@@ -2788,7 +3260,10 @@ impl EnumImpl for Property {
             173 => Some(Property::MaxRecipients),
             580 => Some(Property::MaxReconnects),
             158 => Some(Property::MaxRecurrenceExpansions),
+            1027 => Some(Property::MaxRedirectDestinations),
+            1008 => Some(Property::MaxRedirectPasses),
             705 => Some(Property::MaxRedirects),
+            1026 => Some(Property::MaxRedirectsPerDay),
             852 => Some(Property::MaxReportSize),
             427 => Some(Property::MaxRequestRate),
             428 => Some(Property::MaxRequestSize),
@@ -2799,6 +3274,8 @@ impl EnumImpl for Property {
             719 => Some(Property::MaxScriptNameLength),
             723 => Some(Property::MaxScriptSize),
             726 => Some(Property::MaxScripts),
+            1020 => Some(Property::MaxSessions),
+            1021 => Some(Property::MaxSessionsPerProtocol),
             696 => Some(Property::MaxShares),
             101 => Some(Property::MaxSize),
             724 => Some(Property::MaxStringLength),
@@ -2815,12 +3292,23 @@ impl EnumImpl for Property {
             698 => Some(Property::MessageIdHostname),
             819 => Some(Property::MessageIds),
             145 => Some(Property::Messages),
+            969 => Some(Property::MessagesFailed),
+            968 => Some(Property::MessagesImported),
+            962 => Some(Property::MessagesIndexed),
+            954 => Some(Property::MessagesProcessed),
+            955 => Some(Property::MessagesReEncrypted),
+            956 => Some(Property::MessagesSkipped),
+            953 => Some(Property::MessagesTotal),
+            1078 => Some(Property::Metadata),
+            1010 => Some(Property::MethodProfiling),
             493 => Some(Property::Metric),
             497 => Some(Property::Metrics),
             207 => Some(Property::MetricsCollectionInterval),
             498 => Some(Property::MetricsPolicy),
             731 => Some(Property::MinHamSamples),
+            1085 => Some(Property::MinMatches),
             649 => Some(Property::MinRetryWait),
+            1084 => Some(Property::MinSenderMessages),
             732 => Some(Property::MinSpamSamples),
             166 => Some(Property::MinTriggerInterval),
             191 => Some(Property::Minute),
@@ -2830,6 +3318,9 @@ impl EnumImpl for Property {
             30 => Some(Property::ModelType),
             522 => Some(Property::MtPriority),
             570 => Some(Property::MtaSts),
+            1024 => Some(Property::MtaStsMaxAge),
+            1022 => Some(Property::MtaStsMode),
+            1023 => Some(Property::MtaStsMxHosts),
             572 => Some(Property::MtaStsTimeout),
             859 => Some(Property::Multiline),
             550 => Some(Property::MustMatchSender),
@@ -2852,6 +3343,7 @@ impl EnumImpl for Property {
             390 => Some(Property::NumFeatures),
             350 => Some(Property::NumReplicas),
             351 => Some(Property::NumShards),
+            944 => Some(Property::ObjectIds),
             813 => Some(Property::OnSuccessRenewCertificate),
             495 => Some(Property::OpenTelemetry),
             630 => Some(Property::Options),
@@ -2930,6 +3422,10 @@ impl EnumImpl for Property {
             298 => Some(Property::Protocol),
             533 => Some(Property::ProtocolVersion),
             795 => Some(Property::ProviderInfo),
+            975 => Some(Property::ProvisionFolders),
+            977 => Some(Property::ProvisionSieveScript),
+            978 => Some(Property::ProvisionSignature),
+            976 => Some(Property::ProvisionSubscribe),
             792 => Some(Property::ProxyTrustedNetworks),
             218 => Some(Property::PublicKey),
             302 => Some(Property::PublishRecords),
@@ -2947,6 +3443,13 @@ impl EnumImpl for Property {
             784 => Some(Property::QueryRecipient),
             514 => Some(Property::QueueId),
             644 => Some(Property::QueueName),
+            1015 => Some(Property::QueuePreviewSize),
+            990 => Some(Property::QueueTopDomains),
+            1005 => Some(Property::QuotaReconcileInterval),
+            1006 => Some(Property::QuotaReconcileSampleSize),
+            984 => Some(Property::QuotaWarningInterval),
+            983 => Some(Property::QuotaWarningNotify),
+            982 => Some(Property::QuotaWarningThresholds),
             394 => Some(Property::Quotas),
             532 => Some(Property::Rate),
             410 => Some(Property::RateLimit),
@@ -2954,33 +3457,55 @@ impl EnumImpl for Property {
             396 => Some(Property::RateLimitAuthenticated),
             767 => Some(Property::Ratio),
             510 => Some(Property::RcptToTimeout),
+            957 => Some(Property::ReEncryptConcurrency),
             650 => Some(Property::ReadFromReplicas),
+            1106 => Some(Property::ReadReplicaStickiness),
             578 => Some(Property::ReadReplicas),
+            1097 => Some(Property::ReadinessCacheTtl),
+            1061 => Some(Property::ReadinessCheckTimeout),
+            1063 => Some(Property::ReadinessOptional),
+            1062 => Some(Property::ReadinessTimeout),
             45 => Some(Property::Reason),
+            1088 => Some(Property::RecallNotice),
+            1087 => Some(Property::RecallWindow),
+            960 => Some(Property::ReceivedAfter),
             63 => Some(Property::ReceivedAt),
+            961 => Some(Property::ReceivedBefore),
             636 => Some(Property::ReceivedFromIp),
             637 => Some(Property::ReceivedViaPort),
             836 => Some(Property::ReceivingIp),
             835 => Some(Property::ReceivingMxHelo),
             834 => Some(Property::ReceivingMxHostname),
+            1068 => Some(Property::Recipient),
             484 => Some(Property::Recipients),
             256 => Some(Property::Records),
             805 => Some(Property::RecurrenceId),
+            1007 => Some(Property::RedirectEnvelopeSender),
             911 => Some(Property::RedirectRoot),
             605 => Some(Property::RedirectUris),
             419 => Some(Property::Refresh),
             617 => Some(Property::RefreshTokenExpiry),
             618 => Some(Property::RefreshTokenRenewal),
             330 => Some(Property::Region),
+            963 => Some(Property::ReindexConcurrency),
+            964 => Some(Property::ReindexRateLimit),
+            980 => Some(Property::RejectHiddenExtensions),
             563 => Some(Property::RejectNonFqdn),
             282 => Some(Property::RemoteIp),
             17 => Some(Property::RenewBefore),
             66 => Some(Property::Report),
             349 => Some(Property::ReportAddressUri),
+            1003 => Some(Property::ReportFromAddress),
             244 => Some(Property::ReportId),
             74 => Some(Property::ReportedDomains),
             75 => Some(Property::ReportedUris),
             76 => Some(Property::ReportingMta),
+            1053 => Some(Property::ReputationBadScore),
+            1049 => Some(Property::ReputationEnable),
+            1052 => Some(Property::ReputationGoodScore),
+            1051 => Some(Property::ReputationMinMessages),
+            1054 => Some(Property::ReputationSkipGreylist),
+            1050 => Some(Property::ReputationWindow),
             870 => Some(Property::RequestMaxSize),
             123 => Some(Property::RequestTlsCertificate),
             551 => Some(Property::Require),
@@ -2995,18 +3520,23 @@ impl EnumImpl for Property {
             213 => Some(Property::ResponseEnhanced),
             401 => Some(Property::ResponseHeaders),
             211 => Some(Property::ResponseHostname),
+            989 => Some(Property::ResponseId),
             214 => Some(Property::ResponseMessage),
             761 => Some(Property::ResponsePosCategory),
             762 => Some(Property::ResponsePosConfidence),
             763 => Some(Property::ResponsePosExplanation),
             233 => Some(Property::Result),
             832 => Some(Property::ResultType),
+            1103 => Some(Property::ResumeAfter),
             228 => Some(Property::RetireAfter),
             420 => Some(Property::Retry),
             640 => Some(Property::RetryCount),
             641 => Some(Property::RetryDue),
             635 => Some(Property::ReturnPath),
             912 => Some(Property::ReuseKey),
+            1016 => Some(Property::ReverseIpRequire),
+            1017 => Some(Property::ReverseIpRequireTempFail),
+            1018 => Some(Property::ReverseIpTimeout),
             692 => Some(Property::ReverseIpVerify),
             565 => Some(Property::Rewrite),
             193 => Some(Property::RoleIds),
@@ -3014,7 +3544,10 @@ impl EnumImpl for Property {
             857 => Some(Property::Rotate),
             227 => Some(Property::RotateAfter),
             540 => Some(Property::Route),
+            974 => Some(Property::RspamdApiMaxConcurrent),
+            973 => Some(Property::RspamdApiSecret),
             236 => Some(Property::Rua),
+            1030 => Some(Property::RuaMaxSize),
             896 => Some(Property::Sandbox),
             119 => Some(Property::SasToken),
             549 => Some(Property::SaslMechanisms),
@@ -3030,6 +3563,8 @@ impl EnumImpl for Property {
             772 => Some(Property::ScoreReject),
             773 => Some(Property::ScoreSpam),
             553 => Some(Property::Script),
+            923 => Some(Property::SearchCacheSize),
+            924 => Some(Property::SearchCacheTtl),
             127 => Some(Property::SearchStore),
             3 => Some(Property::Secret),
             328 => Some(Property::SecretAccessKey),
@@ -3040,6 +3575,14 @@ impl EnumImpl for Property {
             222 => Some(Property::Selector),
             226 => Some(Property::SelectorTemplate),
             230 => Some(Property::SendFrequency),
+            1067 => Some(Property::Sender),
+            931 => Some(Property::SenderVerify),
+            934 => Some(Property::SenderVerifyFailTtl),
+            936 => Some(Property::SenderVerifyMaxConcurrent),
+            933 => Some(Property::SenderVerifyPassTtl),
+            935 => Some(Property::SenderVerifyRate),
+            932 => Some(Property::SenderVerifySkipDomains),
+            937 => Some(Property::SenderVerifyTimeout),
             833 => Some(Property::SendingMtaIp),
             915 => Some(Property::SentinelSecret),
             914 => Some(Property::SentinelUsername),
@@ -3052,7 +3595,10 @@ impl EnumImpl for Property {
             329 => Some(Property::SessionToken),
             440 => Some(Property::SetMaxObjects),
             830 => Some(Property::ShardIndex),
+            1064 => Some(Property::SharedBlobQuota),
             895 => Some(Property::SharedSecret),
+            993 => Some(Property::ShutdownGracePeriod),
+            1042 => Some(Property::SieveLogging),
             336 => Some(Property::Sig0Algorithm),
             623 => Some(Property::SignatureAlgorithm),
             624 => Some(Property::SignatureKey),
@@ -3060,6 +3606,8 @@ impl EnumImpl for Property {
             64 => Some(Property::Size),
             885 => Some(Property::SkipDeploy),
             423 => Some(Property::SkipFirst),
+            1009 => Some(Property::SlowMethodThreshold),
+            1091 => Some(Property::SlowMethodThresholdPerMethod),
             552 => Some(Property::SmtpGreeting),
             441 => Some(Property::SnippetMaxResults),
             591 => Some(Property::SocketBacklog),
@@ -3071,9 +3619,11 @@ impl EnumImpl for Property {
             597 => Some(Property::SocketTosV4),
             598 => Some(Property::SocketTtl),
             77 => Some(Property::SourceIp),
+            925 => Some(Property::SourceIpSelection),
             504 => Some(Property::SourceIps),
             78 => Some(Property::SourcePort),
             775 => Some(Property::SpamFilterRulesUrl),
+            1004 => Some(Property::SpamThreshold),
             90 => Some(Property::SpfDns),
             285 => Some(Property::SpfEhloDomain),
             286 => Some(Property::SpfEhloResult),
@@ -3082,15 +3632,22 @@ impl EnumImpl for Property {
             287 => Some(Property::SpfMailFromDomain),
             288 => Some(Property::SpfMailFromResult),
             267 => Some(Property::SpfResults),
+            1032 => Some(Property::SplitFrom),
             224 => Some(Property::Stage),
+            1070 => Some(Property::StagedUntil),
             529 => Some(Property::Stages),
             56 => Some(Property::StartTime),
             571 => Some(Property::StartTls),
             61 => Some(Property::Status),
+            986 => Some(Property::StepUpMaxAge),
+            985 => Some(Property::StepUpPermissions),
             116 => Some(Property::StorageAccount),
             778 => Some(Property::Store),
             694 => Some(Property::Stores),
             816 => Some(Property::Strategy),
+            1065 => Some(Property::StreamChunkSize),
+            1104 => Some(Property::Strict),
+            1028 => Some(Property::StrictReferences),
             347 => Some(Property::SubAddressing),
             887 => Some(Property::SubAuthId),
             41 => Some(Property::Subject),
@@ -3100,8 +3657,11 @@ impl EnumImpl for Property {
             494 => Some(Property::Sum),
             808 => Some(Property::Summary),
             666 => Some(Property::SupportedLanguages),
+            1073 => Some(Property::SuppressNoMx),
+            1074 => Some(Property::SuppressionList),
             748 => Some(Property::Tag),
             746 => Some(Property::Tags),
+            966 => Some(Property::TargetMailbox),
             189 => Some(Property::TaskTypes),
             187 => Some(Property::Tasks),
             307 => Some(Property::TcpOnError),
@@ -3109,16 +3669,19 @@ impl EnumImpl for Property {
             528 => Some(Property::TempFailOnError),
             27 => Some(Property::Temperature),
             167 => Some(Property::Template),
+            1058 => Some(Property::TemplateId),
             900 => Some(Property::TenancyOcid),
             831 => Some(Property::TenantId),
             153 => Some(Property::Tenants),
             2 => Some(Property::Text),
+            1059 => Some(Property::TextBody),
             377 => Some(Property::Then),
             219 => Some(Property::ThirdParty),
             220 => Some(Property::ThirdPartyHash),
             818 => Some(Property::ThreadName),
             791 => Some(Property::ThreadPoolSize),
             574 => Some(Property::ThreadsPerNode),
+            981 => Some(Property::Threshold),
             862 => Some(Property::Throttle),
             8 => Some(Property::TimeZone),
             29 => Some(Property::Timeout),
@@ -3135,13 +3698,17 @@ impl EnumImpl for Property {
             482 => Some(Property::Timestamp),
             55 => Some(Property::Title),
             542 => Some(Property::Tls),
+            1013 => Some(Property::TlsClientCaCertificates),
             599 => Some(Property::TlsDisableCipherSuites),
             600 => Some(Property::TlsDisableProtocols),
             601 => Some(Property::TlsIgnoreClientOrder),
             602 => Some(Property::TlsImplicit),
+            992 => Some(Property::TlsRequestClientCert),
+            1012 => Some(Property::TlsRequireClientCert),
             573 => Some(Property::TlsTimeout),
             42 => Some(Property::To),
             888 => Some(Property::Token),
+            1069 => Some(Property::TokenValidity),
             817 => Some(Property::TotalDeadline),
             850 => Some(Property::TotalFailedSessions),
             849 => Some(Property::TotalSuccessfulSessions),
@@ -3154,6 +3721,9 @@ impl EnumImpl for Property {
             531 => Some(Property::TransferLimit),
             769 => Some(Property::TrustContacts),
             774 => Some(Property::TrustReplies),
+            940 => Some(Property::TrustedForwarderDepth),
+            939 => Some(Property::TrustedForwarders),
+            1090 => Some(Property::TrustedRedirectHosts),
             338 => Some(Property::TsigAlgorithm),
             310 => Some(Property::Ttl),
             54 => Some(Property::UnpackDirectory),
@@ -3182,11 +3752,17 @@ impl EnumImpl for Property {
             526 => Some(Property::Vrfy),
             548 => Some(Property::WaitOnFail),
             893 => Some(Property::WapiVersion),
+            928 => Some(Property::WarmupDays),
+            929 => Some(Property::WarmupInitialLimit),
+            927 => Some(Property::WarmupStart),
+            930 => Some(Property::WarmupTargetLimit),
             922 => Some(Property::WebPushContact),
             921 => Some(Property::WebPushKey),
             455 => Some(Property::WebsocketHeartbeat),
+            1002 => Some(Property::WebsocketMaxConcurrent),
             456 => Some(Property::WebsocketThrottle),
             457 => Some(Property::WebsocketTimeout),
+            926 => Some(Property::Weight),
             749 => Some(Property::Zone),
             98 => Some(Property::ZoneIpV4),
             99 => Some(Property::ZoneIpV6),
@@ -3194,7 +3770,7 @@ impl EnumImpl for Property {
         }
     }
 
-    const COUNT: usize = 923;
+    const COUNT: usize = 1107;
 }
 
 impl serde::Serialize for Property {
@@ -3234,6 +3810,7 @@ impl ObjectType {
             ObjectType::ArchivedItem => ArchivedItem::FLAGS,
             ObjectType::ArfExternalReport => ArfExternalReport::FLAGS,
             ObjectType::Asn => Asn::FLAGS,
+            ObjectType::AuditEvent => AuditEvent::FLAGS,
             ObjectType::Authentication => Authentication::FLAGS,
             ObjectType::BlobStore => BlobStore::FLAGS,
             ObjectType::BlockedIp => BlockedIp::FLAGS,
@@ -3248,6 +3825,7 @@ impl ObjectType {
             ObjectType::Coordinator => Coordinator::FLAGS,
             ObjectType::DataRetention => DataRetention::FLAGS,
             ObjectType::DataStore => DataStore::FLAGS,
+            ObjectType::DeliveryCallback => DeliveryCallback::FLAGS,
             ObjectType::Directory => Directory::FLAGS,
             ObjectType::DkimReportSettings => DkimReportSettings::FLAGS,
             ObjectType::DkimSignature => DkimSignature::FLAGS,
@@ -3269,10 +3847,12 @@ impl ObjectType {
             ObjectType::InMemoryStore => InMemoryStore::FLAGS,
             ObjectType::Jmap => Jmap::FLAGS,
             ObjectType::Log => Log::FLAGS,
+            ObjectType::MailCategory => MailCategory::FLAGS,
             ObjectType::MailingList => MailingList::FLAGS,
             ObjectType::MaskedEmail => MaskedEmail::FLAGS,
             ObjectType::MemoryLookupKey => MemoryLookupKey::FLAGS,
             ObjectType::MemoryLookupKeyValue => MemoryLookupKeyValue::FLAGS,
+            ObjectType::MessageTemplate => MessageTemplate::FLAGS,
             ObjectType::Metric => Metric::FLAGS,
             ObjectType::Metrics => Metrics::FLAGS,
             ObjectType::MetricsStore => MetricsStore::FLAGS,
@@ -3286,6 +3866,7 @@ impl ObjectType {
             ObjectType::MtaOutboundStrategy => MtaOutboundStrategy::FLAGS,
             ObjectType::MtaOutboundThrottle => MtaOutboundThrottle::FLAGS,
             ObjectType::MtaQueueQuota => MtaQueueQuota::FLAGS,
+            ObjectType::MtaResponse => MtaResponse::FLAGS,
             ObjectType::MtaRoute => MtaRoute::FLAGS,
             ObjectType::MtaStageAuth => MtaStageAuth::FLAGS,
             ObjectType::MtaStageConnect => MtaStageConnect::FLAGS,
@@ -3295,6 +3876,7 @@ impl ObjectType {
             ObjectType::MtaStageRcpt => MtaStageRcpt::FLAGS,
             ObjectType::MtaSts => MtaSts::FLAGS,
             ObjectType::MtaTlsStrategy => MtaTlsStrategy::FLAGS,
+            ObjectType::MtaUnsubscribe => MtaUnsubscribe::FLAGS,
             ObjectType::MtaVirtualQueue => MtaVirtualQueue::FLAGS,
             ObjectType::NetworkListener => NetworkListener::FLAGS,
             ObjectType::OAuthClient => OAuthClient::FLAGS,
@@ -3312,6 +3894,7 @@ impl ObjectType {
             ObjectType::SieveSystemScript => SieveSystemScript::FLAGS,
             ObjectType::SieveUserInterpreter => SieveUserInterpreter::FLAGS,
             ObjectType::SieveUserScript => SieveUserScript::FLAGS,
+            ObjectType::SpamClamAv => SpamClamAv::FLAGS,
             ObjectType::SpamClassifier => SpamClassifier::FLAGS,
             ObjectType::SpamDnsblServer => SpamDnsblServer::FLAGS,
             ObjectType::SpamDnsblSettings => SpamDnsblSettings::FLAGS,
@@ -3334,6 +3917,7 @@ impl ObjectType {
             ObjectType::Trace => Trace::FLAGS,
             ObjectType::Tracer => Tracer::FLAGS,
             ObjectType::TracingStore => TracingStore::FLAGS,
+            ObjectType::UnsubscribeEvent => UnsubscribeEvent::FLAGS,
             ObjectType::WebDav => WebDav::FLAGS,
             ObjectType::WebHook => WebHook::FLAGS,
         }
@@ -3410,6 +3994,11 @@ impl ObjectType {
                 IndexSchemaType::Unique,
                 IndexSchemaValueType::Keyword,
             )],
+            ObjectType::DeliveryCallback => vec![IndexSchema::new(
+                Property::AccountId,
+                IndexSchemaType::Search,
+                IndexSchemaValueType::Id,
+            )],
             ObjectType::Directory => vec![IndexSchema::new(
                 Property::MemberTenantId,
                 IndexSchemaType::Search,
@@ -3449,6 +4038,11 @@ impl ObjectType {
                     IndexSchemaValueType::Keyword,
                 ),
             ],
+            ObjectType::MailCategory => vec![IndexSchema::new(
+                Property::Name,
+                IndexSchemaType::Unique,
+                IndexSchemaValueType::Keyword,
+            )],
             ObjectType::MailingList => vec![
                 IndexSchema::new(
                     Property::Text,
@@ -3476,6 +4070,11 @@ impl ObjectType {
                 IndexSchemaType::Search,
                 IndexSchemaValueType::Keyword,
             )],
+            ObjectType::MessageTemplate => vec![IndexSchema::new(
+                Property::MemberTenantId,
+                IndexSchemaType::Search,
+                IndexSchemaValueType::Id,
+            )],
             ObjectType::MtaConnectionStrategy => vec![IndexSchema::new(
                 Property::Name,
                 IndexSchemaType::Unique,
@@ -3580,6 +4179,18 @@ impl ObjectType {
                 IndexSchemaType::Search,
                 IndexSchemaValueType::Text,
             )],
+            ObjectType::UnsubscribeEvent => vec![
+                IndexSchema::new(
+                    Property::Sender,
+                    IndexSchemaType::Search,
+                    IndexSchemaValueType::Keyword,
+                ),
+                IndexSchema::new(
+                    Property::MemberTenantId,
+                    IndexSchemaType::Search,
+                    IndexSchemaValueType::Id,
+                ),
+            ],
             _ => vec![],
         }
     }
@@ -3601,6 +4212,7 @@ impl ObjectType {
             ObjectType::ArchivedItem => Permission::SysArchivedItemGet,
             ObjectType::ArfExternalReport => Permission::SysArfExternalReportGet,
             ObjectType::Asn => Permission::SysAsnGet,
+            ObjectType::AuditEvent => Permission::SysAuditEventGet,
             ObjectType::Authentication => Permission::SysAuthenticationGet,
             ObjectType::BlobStore => Permission::SysBlobStoreGet,
             ObjectType::BlockedIp => Permission::SysBlockedIpGet,
@@ -3615,6 +4227,7 @@ impl ObjectType {
             ObjectType::Coordinator => Permission::SysCoordinatorGet,
             ObjectType::DataRetention => Permission::SysDataRetentionGet,
             ObjectType::DataStore => Permission::SysDataStoreGet,
+            ObjectType::DeliveryCallback => Permission::SysDeliveryCallbackGet,
             ObjectType::Directory => Permission::SysDirectoryGet,
             ObjectType::DkimReportSettings => Permission::SysDkimReportSettingsGet,
             ObjectType::DkimSignature => Permission::SysDkimSignatureGet,
@@ -3636,10 +4249,12 @@ impl ObjectType {
             ObjectType::InMemoryStore => Permission::SysInMemoryStoreGet,
             ObjectType::Jmap => Permission::SysJmapGet,
             ObjectType::Log => Permission::SysLogGet,
+            ObjectType::MailCategory => Permission::SysMailCategoryGet,
             ObjectType::MailingList => Permission::SysMailingListGet,
             ObjectType::MaskedEmail => Permission::SysMaskedEmailGet,
             ObjectType::MemoryLookupKey => Permission::SysMemoryLookupKeyGet,
             ObjectType::MemoryLookupKeyValue => Permission::SysMemoryLookupKeyValueGet,
+            ObjectType::MessageTemplate => Permission::SysMessageTemplateGet,
             ObjectType::Metric => Permission::SysMetricGet,
             ObjectType::Metrics => Permission::SysMetricsGet,
             ObjectType::MetricsStore => Permission::SysMetricsStoreGet,
@@ -3653,6 +4268,7 @@ impl ObjectType {
             ObjectType::MtaOutboundStrategy => Permission::SysMtaOutboundStrategyGet,
             ObjectType::MtaOutboundThrottle => Permission::SysMtaOutboundThrottleGet,
             ObjectType::MtaQueueQuota => Permission::SysMtaQueueQuotaGet,
+            ObjectType::MtaResponse => Permission::SysMtaResponseGet,
             ObjectType::MtaRoute => Permission::SysMtaRouteGet,
             ObjectType::MtaStageAuth => Permission::SysMtaStageAuthGet,
             ObjectType::MtaStageConnect => Permission::SysMtaStageConnectGet,
@@ -3662,6 +4278,7 @@ impl ObjectType {
             ObjectType::MtaStageRcpt => Permission::SysMtaStageRcptGet,
             ObjectType::MtaSts => Permission::SysMtaStsGet,
             ObjectType::MtaTlsStrategy => Permission::SysMtaTlsStrategyGet,
+            ObjectType::MtaUnsubscribe => Permission::SysMtaUnsubscribeGet,
             ObjectType::MtaVirtualQueue => Permission::SysMtaVirtualQueueGet,
             ObjectType::NetworkListener => Permission::SysNetworkListenerGet,
             ObjectType::OAuthClient => Permission::SysOAuthClientGet,
@@ -3679,6 +4296,7 @@ impl ObjectType {
             ObjectType::SieveSystemScript => Permission::SysSieveSystemScriptGet,
             ObjectType::SieveUserInterpreter => Permission::SysSieveUserInterpreterGet,
             ObjectType::SieveUserScript => Permission::SysSieveUserScriptGet,
+            ObjectType::SpamClamAv => Permission::SysSpamClamAvGet,
             ObjectType::SpamClassifier => Permission::SysSpamClassifierGet,
             ObjectType::SpamDnsblServer => Permission::SysSpamDnsblServerGet,
             ObjectType::SpamDnsblSettings => Permission::SysSpamDnsblSettingsGet,
@@ -3701,6 +4319,7 @@ impl ObjectType {
             ObjectType::Trace => Permission::SysTraceGet,
             ObjectType::Tracer => Permission::SysTracerGet,
             ObjectType::TracingStore => Permission::SysTracingStoreGet,
+            ObjectType::UnsubscribeEvent => Permission::SysUnsubscribeEventGet,
             ObjectType::WebDav => Permission::SysWebDavGet,
             ObjectType::WebHook => Permission::SysWebHookGet,
         }
@@ -3719,10 +4338,12 @@ impl ObjectType {
             ObjectType::Application => Permission::SysApplicationQuery,
             ObjectType::ArchivedItem => Permission::SysArchivedItemQuery,
             ObjectType::ArfExternalReport => Permission::SysArfExternalReportQuery,
+            ObjectType::AuditEvent => Permission::SysAuditEventQuery,
             ObjectType::BlockedIp => Permission::SysBlockedIpQuery,
             ObjectType::Certificate => Permission::SysCertificateQuery,
             ObjectType::ClusterNode => Permission::SysClusterNodeQuery,
             ObjectType::ClusterRole => Permission::SysClusterRoleQuery,
+            ObjectType::DeliveryCallback => Permission::SysDeliveryCallbackQuery,
             ObjectType::Directory => Permission::SysDirectoryQuery,
             ObjectType::DkimSignature => Permission::SysDkimSignatureQuery,
             ObjectType::DmarcExternalReport => Permission::SysDmarcExternalReportQuery,
@@ -3732,10 +4353,12 @@ impl ObjectType {
             ObjectType::EventTracingLevel => Permission::SysEventTracingLevelQuery,
             ObjectType::HttpLookup => Permission::SysHttpLookupQuery,
             ObjectType::Log => Permission::SysLogQuery,
+            ObjectType::MailCategory => Permission::SysMailCategoryQuery,
             ObjectType::MailingList => Permission::SysMailingListQuery,
             ObjectType::MaskedEmail => Permission::SysMaskedEmailQuery,
             ObjectType::MemoryLookupKey => Permission::SysMemoryLookupKeyQuery,
             ObjectType::MemoryLookupKeyValue => Permission::SysMemoryLookupKeyValueQuery,
+            ObjectType::MessageTemplate => Permission::SysMessageTemplateQuery,
             ObjectType::Metric => Permission::SysMetricQuery,
             ObjectType::MtaConnectionStrategy => Permission::SysMtaConnectionStrategyQuery,
             ObjectType::MtaDeliverySchedule => Permission::SysMtaDeliveryScheduleQuery,
@@ -3744,6 +4367,7 @@ impl ObjectType {
             ObjectType::MtaMilter => Permission::SysMtaMilterQuery,
             ObjectType::MtaOutboundThrottle => Permission::SysMtaOutboundThrottleQuery,
             ObjectType::MtaQueueQuota => Permission::SysMtaQueueQuotaQuery,
+            ObjectType::MtaResponse => Permission::SysMtaResponseQuery,
             ObjectType::MtaRoute => Permission::SysMtaRouteQuery,
             ObjectType::MtaTlsStrategy => Permission::SysMtaTlsStrategyQuery,
             ObjectType::MtaVirtualQueue => Permission::SysMtaVirtualQueueQuery,
@@ -3766,6 +4390,7 @@ impl ObjectType {
             ObjectType::TlsInternalReport => Permission::SysTlsInternalReportQuery,
             ObjectType::Trace => Permission::SysTraceQuery,
             ObjectType::Tracer => Permission::SysTracerQuery,
+            ObjectType::UnsubscribeEvent => Permission::SysUnsubscribeEventQuery,
             ObjectType::WebHook => Permission::SysWebHookQuery,
             _ => unreachable!(),
         }
@@ -3848,6 +4473,11 @@ impl ObjectType {
                 Permission::SysAsnUpdate,
                 Permission::SysAsnUpdate,
             ],
+            ObjectType::AuditEvent => [
+                Permission::SysAuditEventCreate,
+                Permission::SysAuditEventUpdate,
+                Permission::SysAuditEventDestroy,
+            ],
             ObjectType::Authentication => [
                 Permission::SysAuthenticationUpdate,
                 Permission::SysAuthenticationUpdate,
@@ -3918,6 +4548,11 @@ impl ObjectType {
                 Permission::SysDataStoreUpdate,
                 Permission::SysDataStoreUpdate,
             ],
+            ObjectType::DeliveryCallback => [
+                Permission::SysDeliveryCallbackCreate,
+                Permission::SysDeliveryCallbackUpdate,
+                Permission::SysDeliveryCallbackDestroy,
+            ],
             ObjectType::Directory => [
                 Permission::SysDirectoryCreate,
                 Permission::SysDirectoryUpdate,
@@ -4023,6 +4658,11 @@ impl ObjectType {
                 Permission::SysLogUpdate,
                 Permission::SysLogDestroy,
             ],
+            ObjectType::MailCategory => [
+                Permission::SysMailCategoryCreate,
+                Permission::SysMailCategoryUpdate,
+                Permission::SysMailCategoryDestroy,
+            ],
             ObjectType::MailingList => [
                 Permission::SysMailingListCreate,
                 Permission::SysMailingListUpdate,
@@ -4043,6 +4683,11 @@ impl ObjectType {
                 Permission::SysMemoryLookupKeyValueUpdate,
                 Permission::SysMemoryLookupKeyValueDestroy,
             ],
+            ObjectType::MessageTemplate => [
+                Permission::SysMessageTemplateCreate,
+                Permission::SysMessageTemplateUpdate,
+                Permission::SysMessageTemplateDestroy,
+            ],
             ObjectType::Metric => [
                 Permission::SysMetricCreate,
                 Permission::SysMetricUpdate,
@@ -4108,6 +4753,11 @@ impl ObjectType {
                 Permission::SysMtaQueueQuotaUpdate,
                 Permission::SysMtaQueueQuotaDestroy,
             ],
+            ObjectType::MtaResponse => [
+                Permission::SysMtaResponseCreate,
+                Permission::SysMtaResponseUpdate,
+                Permission::SysMtaResponseDestroy,
+            ],
             ObjectType::MtaRoute => [
                 Permission::SysMtaRouteCreate,
                 Permission::SysMtaRouteUpdate,
@@ -4153,6 +4803,11 @@ impl ObjectType {
                 Permission::SysMtaTlsStrategyUpdate,
                 Permission::SysMtaTlsStrategyDestroy,
             ],
+            ObjectType::MtaUnsubscribe => [
+                Permission::SysMtaUnsubscribeUpdate,
+                Permission::SysMtaUnsubscribeUpdate,
+                Permission::SysMtaUnsubscribeUpdate,
+            ],
             ObjectType::MtaVirtualQueue => [
                 Permission::SysMtaVirtualQueueCreate,
                 Permission::SysMtaVirtualQueueUpdate,
@@ -4238,6 +4893,11 @@ impl ObjectType {
                 Permission::SysSieveUserScriptUpdate,
                 Permission::SysSieveUserScriptDestroy,
             ],
+            ObjectType::SpamClamAv => [
+                Permission::SysSpamClamAvUpdate,
+                Permission::SysSpamClamAvUpdate,
+                Permission::SysSpamClamAvUpdate,
+            ],
             ObjectType::SpamClassifier => [
                 Permission::SysSpamClassifierUpdate,
                 Permission::SysSpamClassifierUpdate,
@@ -4348,6 +5008,11 @@ impl ObjectType {
                 Permission::SysTracingStoreUpdate,
                 Permission::SysTracingStoreUpdate,
             ],
+            ObjectType::UnsubscribeEvent => [
+                Permission::SysUnsubscribeEventCreate,
+                Permission::SysUnsubscribeEventUpdate,
+                Permission::SysUnsubscribeEventDestroy,
+            ],
             ObjectType::WebDav => [
                 Permission::SysWebDavUpdate,
                 Permission::SysWebDavUpdate,
@@ -4452,9 +5117,11 @@ impl ObjectInner {
             ObjectInner::DnsServer(DnsServer::YandexCloud(obj)) => obj.member_tenant_id,
             ObjectInner::Domain(obj) => obj.member_tenant_id,
             ObjectInner::MailingList(obj) => obj.member_tenant_id,
+            ObjectInner::MessageTemplate(obj) => obj.member_tenant_id,
             ObjectInner::OAuthClient(obj) => obj.member_tenant_id,
             ObjectInner::Role(obj) => obj.member_tenant_id,
             ObjectInner::TlsExternalReport(obj) => obj.member_tenant_id,
+            ObjectInner::UnsubscribeEvent(obj) => obj.member_tenant_id,
             _ => None,
         }
     }
@@ -4554,9 +5221,11 @@ impl ObjectInner {
             ObjectInner::DnsServer(DnsServer::YandexCloud(obj)) => obj.member_tenant_id = Some(id),
             ObjectInner::Domain(obj) => obj.member_tenant_id = Some(id),
             ObjectInner::MailingList(obj) => obj.member_tenant_id = Some(id),
+            ObjectInner::MessageTemplate(obj) => obj.member_tenant_id = Some(id),
             ObjectInner::OAuthClient(obj) => obj.member_tenant_id = Some(id),
             ObjectInner::Role(obj) => obj.member_tenant_id = Some(id),
             ObjectInner::TlsExternalReport(obj) => obj.member_tenant_id = Some(id),
+            ObjectInner::UnsubscribeEvent(obj) => obj.member_tenant_id = Some(id),
             _ => {}
         }
     }
@@ -4568,6 +5237,7 @@ impl ObjectInner {
             ObjectInner::ArchivedItem(ArchivedItem::CalendarEvent(obj)) => Some(obj.account_id),
             ObjectInner::ArchivedItem(ArchivedItem::ContactCard(obj)) => Some(obj.account_id),
             ObjectInner::ArchivedItem(ArchivedItem::SieveScript(obj)) => Some(obj.account_id),
+            ObjectInner::DeliveryCallback(obj) => Some(obj.account_id),
             ObjectInner::MaskedEmail(obj) => Some(obj.account_id),
             ObjectInner::PublicKey(obj) => Some(obj.account_id),
             ObjectInner::SpamTrainingSample(obj) => obj.account_id,
@@ -4580,6 +5250,13 @@ impl ObjectInner {
             ObjectInner::Task(Task::RestoreArchivedItem(obj)) => Some(obj.account_id),
             ObjectInner::Task(Task::DestroyAccount(obj)) => Some(obj.account_id),
             ObjectInner::Task(Task::AccountMaintenance(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::ReEncryptAccount(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::ReindexAccount(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::ImportMessages(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::QuotaWarning(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::EmailSubmission(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::RestoreSnoozedEmail(obj)) => Some(obj.account_id),
+            ObjectInner::Task(Task::FollowUpReminder(obj)) => Some(obj.account_id),
             _ => None,
         }
     }
@@ -4591,6 +5268,7 @@ impl ObjectInner {
             ObjectInner::ArchivedItem(ArchivedItem::CalendarEvent(obj)) => obj.account_id = id,
             ObjectInner::ArchivedItem(ArchivedItem::ContactCard(obj)) => obj.account_id = id,
             ObjectInner::ArchivedItem(ArchivedItem::SieveScript(obj)) => obj.account_id = id,
+            ObjectInner::DeliveryCallback(obj) => obj.account_id = id,
             ObjectInner::MaskedEmail(obj) => obj.account_id = id,
             ObjectInner::PublicKey(obj) => obj.account_id = id,
             ObjectInner::SpamTrainingSample(obj) => obj.account_id = Some(id),
@@ -4603,6 +5281,13 @@ impl ObjectInner {
             ObjectInner::Task(Task::RestoreArchivedItem(obj)) => obj.account_id = id,
             ObjectInner::Task(Task::DestroyAccount(obj)) => obj.account_id = id,
             ObjectInner::Task(Task::AccountMaintenance(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::ReEncryptAccount(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::ReindexAccount(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::ImportMessages(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::QuotaWarning(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::EmailSubmission(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::RestoreSnoozedEmail(obj)) => obj.account_id = id,
+            ObjectInner::Task(Task::FollowUpReminder(obj)) => obj.account_id = id,
             _ => {}
         }
     }
@@ -4624,6 +5309,7 @@ impl ObjectInner {
             ObjectInner::ArchivedItem(obj) => obj.to_pickled_vec(),
             ObjectInner::ArfExternalReport(obj) => obj.to_pickled_vec(),
             ObjectInner::Asn(obj) => obj.to_pickled_vec(),
+            ObjectInner::AuditEvent(obj) => obj.to_pickled_vec(),
             ObjectInner::Authentication(obj) => obj.to_pickled_vec(),
             ObjectInner::BlobStore(obj) => obj.to_pickled_vec(),
             ObjectInner::BlockedIp(obj) => obj.to_pickled_vec(),
@@ -4638,6 +5324,7 @@ impl ObjectInner {
             ObjectInner::Coordinator(obj) => obj.to_pickled_vec(),
             ObjectInner::DataRetention(obj) => obj.to_pickled_vec(),
             ObjectInner::DataStore(obj) => obj.to_pickled_vec(),
+            ObjectInner::DeliveryCallback(obj) => obj.to_pickled_vec(),
             ObjectInner::Directory(obj) => obj.to_pickled_vec(),
             ObjectInner::DkimReportSettings(obj) => obj.to_pickled_vec(),
             ObjectInner::DkimSignature(obj) => obj.to_pickled_vec(),
@@ -4659,10 +5346,12 @@ impl ObjectInner {
             ObjectInner::InMemoryStore(obj) => obj.to_pickled_vec(),
             ObjectInner::Jmap(obj) => obj.to_pickled_vec(),
             ObjectInner::Log(obj) => obj.to_pickled_vec(),
+            ObjectInner::MailCategory(obj) => obj.to_pickled_vec(),
             ObjectInner::MailingList(obj) => obj.to_pickled_vec(),
             ObjectInner::MaskedEmail(obj) => obj.to_pickled_vec(),
             ObjectInner::MemoryLookupKey(obj) => obj.to_pickled_vec(),
             ObjectInner::MemoryLookupKeyValue(obj) => obj.to_pickled_vec(),
+            ObjectInner::MessageTemplate(obj) => obj.to_pickled_vec(),
             ObjectInner::Metric(obj) => obj.to_pickled_vec(),
             ObjectInner::Metrics(obj) => obj.to_pickled_vec(),
             ObjectInner::MetricsStore(obj) => obj.to_pickled_vec(),
@@ -4676,6 +5365,7 @@ impl ObjectInner {
             ObjectInner::MtaOutboundStrategy(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaOutboundThrottle(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaQueueQuota(obj) => obj.to_pickled_vec(),
+            ObjectInner::MtaResponse(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaRoute(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaStageAuth(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaStageConnect(obj) => obj.to_pickled_vec(),
@@ -4685,6 +5375,7 @@ impl ObjectInner {
             ObjectInner::MtaStageRcpt(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaSts(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaTlsStrategy(obj) => obj.to_pickled_vec(),
+            ObjectInner::MtaUnsubscribe(obj) => obj.to_pickled_vec(),
             ObjectInner::MtaVirtualQueue(obj) => obj.to_pickled_vec(),
             ObjectInner::NetworkListener(obj) => obj.to_pickled_vec(),
             ObjectInner::OAuthClient(obj) => obj.to_pickled_vec(),
@@ -4702,6 +5393,7 @@ impl ObjectInner {
             ObjectInner::SieveSystemScript(obj) => obj.to_pickled_vec(),
             ObjectInner::SieveUserInterpreter(obj) => obj.to_pickled_vec(),
             ObjectInner::SieveUserScript(obj) => obj.to_pickled_vec(),
+            ObjectInner::SpamClamAv(obj) => obj.to_pickled_vec(),
             ObjectInner::SpamClassifier(obj) => obj.to_pickled_vec(),
             ObjectInner::SpamDnsblServer(obj) => obj.to_pickled_vec(),
             ObjectInner::SpamDnsblSettings(obj) => obj.to_pickled_vec(),
@@ -4724,6 +5416,7 @@ impl ObjectInner {
             ObjectInner::Trace(obj) => obj.to_pickled_vec(),
             ObjectInner::Tracer(obj) => obj.to_pickled_vec(),
             ObjectInner::TracingStore(obj) => obj.to_pickled_vec(),
+            ObjectInner::UnsubscribeEvent(obj) => obj.to_pickled_vec(),
             ObjectInner::WebDav(obj) => obj.to_pickled_vec(),
             ObjectInner::WebHook(obj) => obj.to_pickled_vec(),
         }
@@ -4755,6 +5448,7 @@ impl ObjectInner {
                 Pickle::unpickle(stream).map(ObjectInner::ArfExternalReport)
             }
             ObjectType::Asn => Pickle::unpickle(stream).map(ObjectInner::Asn),
+            ObjectType::AuditEvent => Pickle::unpickle(stream).map(ObjectInner::AuditEvent),
             ObjectType::Authentication => Pickle::unpickle(stream).map(ObjectInner::Authentication),
             ObjectType::BlobStore => Pickle::unpickle(stream).map(ObjectInner::BlobStore),
             ObjectType::BlockedIp => Pickle::unpickle(stream).map(ObjectInner::BlockedIp),
@@ -4771,6 +5465,9 @@ impl ObjectInner {
             ObjectType::Coordinator => Pickle::unpickle(stream).map(ObjectInner::Coordinator),
             ObjectType::DataRetention => Pickle::unpickle(stream).map(ObjectInner::DataRetention),
             ObjectType::DataStore => Pickle::unpickle(stream).map(ObjectInner::DataStore),
+            ObjectType::DeliveryCallback => {
+                Pickle::unpickle(stream).map(ObjectInner::DeliveryCallback)
+            }
             ObjectType::Directory => Pickle::unpickle(stream).map(ObjectInner::Directory),
             ObjectType::DkimReportSettings => {
                 Pickle::unpickle(stream).map(ObjectInner::DkimReportSettings)
@@ -4804,6 +5501,7 @@ impl ObjectInner {
             ObjectType::InMemoryStore => Pickle::unpickle(stream).map(ObjectInner::InMemoryStore),
             ObjectType::Jmap => Pickle::unpickle(stream).map(ObjectInner::Jmap),
             ObjectType::Log => Pickle::unpickle(stream).map(ObjectInner::Log),
+            ObjectType::MailCategory => Pickle::unpickle(stream).map(ObjectInner::MailCategory),
             ObjectType::MailingList => Pickle::unpickle(stream).map(ObjectInner::MailingList),
             ObjectType::MaskedEmail => Pickle::unpickle(stream).map(ObjectInner::MaskedEmail),
             ObjectType::MemoryLookupKey => {
@@ -4812,6 +5510,9 @@ impl ObjectInner {
             ObjectType::MemoryLookupKeyValue => {
                 Pickle::unpickle(stream).map(ObjectInner::MemoryLookupKeyValue)
             }
+            ObjectType::MessageTemplate => {
+                Pickle::unpickle(stream).map(ObjectInner::MessageTemplate)
+            }
             ObjectType::Metric => Pickle::unpickle(stream).map(ObjectInner::Metric),
             ObjectType::Metrics => Pickle::unpickle(stream).map(ObjectInner::Metrics),
             ObjectType::MetricsStore => Pickle::unpickle(stream).map(ObjectInner::MetricsStore),
@@ -4837,6 +5538,7 @@ impl ObjectInner {
                 Pickle::unpickle(stream).map(ObjectInner::MtaOutboundThrottle)
             }
             ObjectType::MtaQueueQuota => Pickle::unpickle(stream).map(ObjectInner::MtaQueueQuota),
+            ObjectType::MtaResponse => Pickle::unpickle(stream).map(ObjectInner::MtaResponse),
             ObjectType::MtaRoute => Pickle::unpickle(stream).map(ObjectInner::MtaRoute),
             ObjectType::MtaStageAuth => Pickle::unpickle(stream).map(ObjectInner::MtaStageAuth),
             ObjectType::MtaStageConnect => {
@@ -4848,6 +5550,7 @@ impl ObjectInner {
             ObjectType::MtaStageRcpt => Pickle::unpickle(stream).map(ObjectInner::MtaStageRcpt),
             ObjectType::MtaSts => Pickle::unpickle(stream).map(ObjectInner::MtaSts),
             ObjectType::MtaTlsStrategy => Pickle::unpickle(stream).map(ObjectInner::MtaTlsStrategy),
+            ObjectType::MtaUnsubscribe => Pickle::unpickle(stream).map(ObjectInner::MtaUnsubscribe),
             ObjectType::MtaVirtualQueue => {
                 Pickle::unpickle(stream).map(ObjectInner::MtaVirtualQueue)
             }
@@ -4877,6 +5580,7 @@ impl ObjectInner {
             ObjectType::SieveUserScript => {
                 Pickle::unpickle(stream).map(ObjectInner::SieveUserScript)
             }
+            ObjectType::SpamClamAv => Pickle::unpickle(stream).map(ObjectInner::SpamClamAv),
             ObjectType::SpamClassifier => Pickle::unpickle(stream).map(ObjectInner::SpamClassifier),
             ObjectType::SpamDnsblServer => {
                 Pickle::unpickle(stream).map(ObjectInner::SpamDnsblServer)
@@ -4915,6 +5619,9 @@ impl ObjectInner {
             ObjectType::Trace => Pickle::unpickle(stream).map(ObjectInner::Trace),
             ObjectType::Tracer => Pickle::unpickle(stream).map(ObjectInner::Tracer),
             ObjectType::TracingStore => Pickle::unpickle(stream).map(ObjectInner::TracingStore),
+            ObjectType::UnsubscribeEvent => {
+                Pickle::unpickle(stream).map(ObjectInner::UnsubscribeEvent)
+            }
             ObjectType::WebDav => Pickle::unpickle(stream).map(ObjectInner::WebDav),
             ObjectType::WebHook => Pickle::unpickle(stream).map(ObjectInner::WebHook),
         }
@@ -4958,6 +5665,9 @@ impl ObjectInner {
                 ArfExternalReport::deserialize(deserializer).map(ObjectInner::ArfExternalReport)
             }
             ObjectType::Asn => Asn::deserialize(deserializer).map(ObjectInner::Asn),
+            ObjectType::AuditEvent => {
+                AuditEvent::deserialize(deserializer).map(ObjectInner::AuditEvent)
+            }
             ObjectType::Authentication => {
                 Authentication::deserialize(deserializer).map(ObjectInner::Authentication)
             }
@@ -4996,6 +5706,9 @@ impl ObjectInner {
             ObjectType::DataStore => {
                 DataStore::deserialize(deserializer).map(ObjectInner::DataStore)
             }
+            ObjectType::DeliveryCallback => {
+                DeliveryCallback::deserialize(deserializer).map(ObjectInner::DeliveryCallback)
+            }
             ObjectType::Directory => {
                 Directory::deserialize(deserializer).map(ObjectInner::Directory)
             }
@@ -5045,6 +5758,9 @@ impl ObjectInner {
             }
             ObjectType::Jmap => Jmap::deserialize(deserializer).map(ObjectInner::Jmap),
             ObjectType::Log => Log::deserialize(deserializer).map(ObjectInner::Log),
+            ObjectType::MailCategory => {
+                MailCategory::deserialize(deserializer).map(ObjectInner::MailCategory)
+            }
             ObjectType::MailingList => {
                 MailingList::deserialize(deserializer).map(ObjectInner::MailingList)
             }
@@ -5056,6 +5772,9 @@ impl ObjectInner {
             }
             ObjectType::MemoryLookupKeyValue => MemoryLookupKeyValue::deserialize(deserializer)
                 .map(ObjectInner::MemoryLookupKeyValue),
+            ObjectType::MessageTemplate => {
+                MessageTemplate::deserialize(deserializer).map(ObjectInner::MessageTemplate)
+            }
             ObjectType::Metric => Metric::deserialize(deserializer).map(ObjectInner::Metric),
             ObjectType::Metrics => Metrics::deserialize(deserializer).map(ObjectInner::Metrics),
             ObjectType::MetricsStore => {
@@ -5088,6 +5807,9 @@ impl ObjectInner {
             ObjectType::MtaQueueQuota => {
                 MtaQueueQuota::deserialize(deserializer).map(ObjectInner::MtaQueueQuota)
             }
+            ObjectType::MtaResponse => {
+                MtaResponse::deserialize(deserializer).map(ObjectInner::MtaResponse)
+            }
             ObjectType::MtaRoute => MtaRoute::deserialize(deserializer).map(ObjectInner::MtaRoute),
             ObjectType::MtaStageAuth => {
                 MtaStageAuth::deserialize(deserializer).map(ObjectInner::MtaStageAuth)
@@ -5111,6 +5833,9 @@ impl ObjectInner {
             ObjectType::MtaTlsStrategy => {
                 MtaTlsStrategy::deserialize(deserializer).map(ObjectInner::MtaTlsStrategy)
             }
+            ObjectType::MtaUnsubscribe => {
+                MtaUnsubscribe::deserialize(deserializer).map(ObjectInner::MtaUnsubscribe)
+            }
             ObjectType::MtaVirtualQueue => {
                 MtaVirtualQueue::deserialize(deserializer).map(ObjectInner::MtaVirtualQueue)
             }
@@ -5152,6 +5877,9 @@ impl ObjectInner {
             ObjectType::SieveUserScript => {
                 SieveUserScript::deserialize(deserializer).map(ObjectInner::SieveUserScript)
             }
+            ObjectType::SpamClamAv => {
+                SpamClamAv::deserialize(deserializer).map(ObjectInner::SpamClamAv)
+            }
             ObjectType::SpamClassifier => {
                 SpamClassifier::deserialize(deserializer).map(ObjectInner::SpamClassifier)
             }
@@ -5204,6 +5932,9 @@ impl ObjectInner {
             ObjectType::TracingStore => {
                 TracingStore::deserialize(deserializer).map(ObjectInner::TracingStore)
             }
+            ObjectType::UnsubscribeEvent => {
+                UnsubscribeEvent::deserialize(deserializer).map(ObjectInner::UnsubscribeEvent)
+            }
             ObjectType::WebDav => WebDav::deserialize(deserializer).map(ObjectInner::WebDav),
             ObjectType::WebHook => WebHook::deserialize(deserializer).map(ObjectInner::WebHook),
         }
@@ -5224,12 +5955,15 @@ impl ObjectInner {
             ObjectInner::MtaOutboundStrategy(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaOutboundThrottle(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaQueueQuota(obj) => Some(obj.expression_ctxs()),
+            ObjectInner::MtaResponse(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageAuth(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageConnect(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageData(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageEhlo(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageMail(obj) => Some(obj.expression_ctxs()),
             ObjectInner::MtaStageRcpt(obj) => Some(obj.expression_ctxs()),
+            ObjectInner::MtaUnsubscribe(obj) => Some(obj.expression_ctxs()),
+            ObjectInner::MtaVirtualQueue(obj) => Some(obj.expression_ctxs()),
             ObjectInner::ReportSettings(obj) => Some(obj.expression_ctxs()),
             ObjectInner::SenderAuth(obj) => Some(obj.expression_ctxs()),
             ObjectInner::SieveSystemInterpreter(obj) => Some(obj.expression_ctxs()),
@@ -5260,6 +5994,7 @@ impl Object {
             ObjectInner::ArchivedItem(_) => ArchivedItem::FLAGS,
             ObjectInner::ArfExternalReport(_) => ArfExternalReport::FLAGS,
             ObjectInner::Asn(_) => Asn::FLAGS,
+            ObjectInner::AuditEvent(_) => AuditEvent::FLAGS,
             ObjectInner::Authentication(_) => Authentication::FLAGS,
             ObjectInner::BlobStore(_) => BlobStore::FLAGS,
             ObjectInner::BlockedIp(_) => BlockedIp::FLAGS,
@@ -5274,6 +6009,7 @@ impl Object {
             ObjectInner::Coordinator(_) => Coordinator::FLAGS,
             ObjectInner::DataRetention(_) => DataRetention::FLAGS,
             ObjectInner::DataStore(_) => DataStore::FLAGS,
+            ObjectInner::DeliveryCallback(_) => DeliveryCallback::FLAGS,
             ObjectInner::Directory(_) => Directory::FLAGS,
             ObjectInner::DkimReportSettings(_) => DkimReportSettings::FLAGS,
             ObjectInner::DkimSignature(_) => DkimSignature::FLAGS,
@@ -5295,10 +6031,12 @@ impl Object {
             ObjectInner::InMemoryStore(_) => InMemoryStore::FLAGS,
             ObjectInner::Jmap(_) => Jmap::FLAGS,
             ObjectInner::Log(_) => Log::FLAGS,
+            ObjectInner::MailCategory(_) => MailCategory::FLAGS,
             ObjectInner::MailingList(_) => MailingList::FLAGS,
             ObjectInner::MaskedEmail(_) => MaskedEmail::FLAGS,
             ObjectInner::MemoryLookupKey(_) => MemoryLookupKey::FLAGS,
             ObjectInner::MemoryLookupKeyValue(_) => MemoryLookupKeyValue::FLAGS,
+            ObjectInner::MessageTemplate(_) => MessageTemplate::FLAGS,
             ObjectInner::Metric(_) => Metric::FLAGS,
             ObjectInner::Metrics(_) => Metrics::FLAGS,
             ObjectInner::MetricsStore(_) => MetricsStore::FLAGS,
@@ -5312,6 +6050,7 @@ impl Object {
             ObjectInner::MtaOutboundStrategy(_) => MtaOutboundStrategy::FLAGS,
             ObjectInner::MtaOutboundThrottle(_) => MtaOutboundThrottle::FLAGS,
             ObjectInner::MtaQueueQuota(_) => MtaQueueQuota::FLAGS,
+            ObjectInner::MtaResponse(_) => MtaResponse::FLAGS,
             ObjectInner::MtaRoute(_) => MtaRoute::FLAGS,
             ObjectInner::MtaStageAuth(_) => MtaStageAuth::FLAGS,
             ObjectInner::MtaStageConnect(_) => MtaStageConnect::FLAGS,
@@ -5321,6 +6060,7 @@ impl Object {
             ObjectInner::MtaStageRcpt(_) => MtaStageRcpt::FLAGS,
             ObjectInner::MtaSts(_) => MtaSts::FLAGS,
             ObjectInner::MtaTlsStrategy(_) => MtaTlsStrategy::FLAGS,
+            ObjectInner::MtaUnsubscribe(_) => MtaUnsubscribe::FLAGS,
             ObjectInner::MtaVirtualQueue(_) => MtaVirtualQueue::FLAGS,
             ObjectInner::NetworkListener(_) => NetworkListener::FLAGS,
             ObjectInner::OAuthClient(_) => OAuthClient::FLAGS,
@@ -5338,6 +6078,7 @@ impl Object {
             ObjectInner::SieveSystemScript(_) => SieveSystemScript::FLAGS,
             ObjectInner::SieveUserInterpreter(_) => SieveUserInterpreter::FLAGS,
             ObjectInner::SieveUserScript(_) => SieveUserScript::FLAGS,
+            ObjectInner::SpamClamAv(_) => SpamClamAv::FLAGS,
             ObjectInner::SpamClassifier(_) => SpamClassifier::FLAGS,
             ObjectInner::SpamDnsblServer(_) => SpamDnsblServer::FLAGS,
             ObjectInner::SpamDnsblSettings(_) => SpamDnsblSettings::FLAGS,
@@ -5360,6 +6101,7 @@ impl Object {
             ObjectInner::Trace(_) => Trace::FLAGS,
             ObjectInner::Tracer(_) => Tracer::FLAGS,
             ObjectInner::TracingStore(_) => TracingStore::FLAGS,
+            ObjectInner::UnsubscribeEvent(_) => UnsubscribeEvent::FLAGS,
             ObjectInner::WebDav(_) => WebDav::FLAGS,
             ObjectInner::WebHook(_) => WebHook::FLAGS,
         }
@@ -5382,6 +6124,7 @@ impl Object {
             ObjectInner::ArchivedItem(_) => ObjectType::ArchivedItem,
             ObjectInner::ArfExternalReport(_) => ObjectType::ArfExternalReport,
             ObjectInner::Asn(_) => ObjectType::Asn,
+            ObjectInner::AuditEvent(_) => ObjectType::AuditEvent,
             ObjectInner::Authentication(_) => ObjectType::Authentication,
             ObjectInner::BlobStore(_) => ObjectType::BlobStore,
             ObjectInner::BlockedIp(_) => ObjectType::BlockedIp,
@@ -5396,6 +6139,7 @@ impl Object {
             ObjectInner::Coordinator(_) => ObjectType::Coordinator,
             ObjectInner::DataRetention(_) => ObjectType::DataRetention,
             ObjectInner::DataStore(_) => ObjectType::DataStore,
+            ObjectInner::DeliveryCallback(_) => ObjectType::DeliveryCallback,
             ObjectInner::Directory(_) => ObjectType::Directory,
             ObjectInner::DkimReportSettings(_) => ObjectType::DkimReportSettings,
             ObjectInner::DkimSignature(_) => ObjectType::DkimSignature,
@@ -5417,10 +6161,12 @@ impl Object {
             ObjectInner::InMemoryStore(_) => ObjectType::InMemoryStore,
             ObjectInner::Jmap(_) => ObjectType::Jmap,
             ObjectInner::Log(_) => ObjectType::Log,
+            ObjectInner::MailCategory(_) => ObjectType::MailCategory,
             ObjectInner::MailingList(_) => ObjectType::MailingList,
             ObjectInner::MaskedEmail(_) => ObjectType::MaskedEmail,
             ObjectInner::MemoryLookupKey(_) => ObjectType::MemoryLookupKey,
             ObjectInner::MemoryLookupKeyValue(_) => ObjectType::MemoryLookupKeyValue,
+            ObjectInner::MessageTemplate(_) => ObjectType::MessageTemplate,
             ObjectInner::Metric(_) => ObjectType::Metric,
             ObjectInner::Metrics(_) => ObjectType::Metrics,
             ObjectInner::MetricsStore(_) => ObjectType::MetricsStore,
@@ -5434,6 +6180,7 @@ impl Object {
             ObjectInner::MtaOutboundStrategy(_) => ObjectType::MtaOutboundStrategy,
             ObjectInner::MtaOutboundThrottle(_) => ObjectType::MtaOutboundThrottle,
             ObjectInner::MtaQueueQuota(_) => ObjectType::MtaQueueQuota,
+            ObjectInner::MtaResponse(_) => ObjectType::MtaResponse,
             ObjectInner::MtaRoute(_) => ObjectType::MtaRoute,
             ObjectInner::MtaStageAuth(_) => ObjectType::MtaStageAuth,
             ObjectInner::MtaStageConnect(_) => ObjectType::MtaStageConnect,
@@ -5443,6 +6190,7 @@ impl Object {
             ObjectInner::MtaStageRcpt(_) => ObjectType::MtaStageRcpt,
             ObjectInner::MtaSts(_) => ObjectType::MtaSts,
             ObjectInner::MtaTlsStrategy(_) => ObjectType::MtaTlsStrategy,
+            ObjectInner::MtaUnsubscribe(_) => ObjectType::MtaUnsubscribe,
             ObjectInner::MtaVirtualQueue(_) => ObjectType::MtaVirtualQueue,
             ObjectInner::NetworkListener(_) => ObjectType::NetworkListener,
             ObjectInner::OAuthClient(_) => ObjectType::OAuthClient,
@@ -5460,6 +6208,7 @@ impl Object {
             ObjectInner::SieveSystemScript(_) => ObjectType::SieveSystemScript,
             ObjectInner::SieveUserInterpreter(_) => ObjectType::SieveUserInterpreter,
             ObjectInner::SieveUserScript(_) => ObjectType::SieveUserScript,
+            ObjectInner::SpamClamAv(_) => ObjectType::SpamClamAv,
             ObjectInner::SpamClassifier(_) => ObjectType::SpamClassifier,
             ObjectInner::SpamDnsblServer(_) => ObjectType::SpamDnsblServer,
             ObjectInner::SpamDnsblSettings(_) => ObjectType::SpamDnsblSettings,
@@ -5482,6 +6231,7 @@ impl Object {
             ObjectInner::Trace(_) => ObjectType::Trace,
             ObjectInner::Tracer(_) => ObjectType::Tracer,
             ObjectInner::TracingStore(_) => ObjectType::TracingStore,
+            ObjectInner::UnsubscribeEvent(_) => ObjectType::UnsubscribeEvent,
             ObjectInner::WebDav(_) => ObjectType::WebDav,
             ObjectInner::WebHook(_) => ObjectType::WebHook,
         }
@@ -5533,6 +6283,7 @@ impl Object {
             ObjectInner::ArchivedItem(obj) => obj.validate(errors),
             ObjectInner::ArfExternalReport(obj) => obj.validate(errors),
             ObjectInner::Asn(obj) => obj.validate(errors),
+            ObjectInner::AuditEvent(obj) => obj.validate(errors),
             ObjectInner::Authentication(obj) => obj.validate(errors),
             ObjectInner::BlobStore(obj) => obj.validate(errors),
             ObjectInner::BlockedIp(obj) => obj.validate(errors),
@@ -5547,6 +6298,7 @@ impl Object {
             ObjectInner::Coordinator(obj) => obj.validate(errors),
             ObjectInner::DataRetention(obj) => obj.validate(errors),
             ObjectInner::DataStore(obj) => obj.validate(errors),
+            ObjectInner::DeliveryCallback(obj) => obj.validate(errors),
             ObjectInner::Directory(obj) => obj.validate(errors),
             ObjectInner::DkimReportSettings(obj) => obj.validate(errors),
             ObjectInner::DkimSignature(obj) => obj.validate(errors),
@@ -5568,10 +6320,12 @@ impl Object {
             ObjectInner::InMemoryStore(obj) => obj.validate(errors),
             ObjectInner::Jmap(obj) => obj.validate(errors),
             ObjectInner::Log(obj) => obj.validate(errors),
+            ObjectInner::MailCategory(obj) => obj.validate(errors),
             ObjectInner::MailingList(obj) => obj.validate(errors),
             ObjectInner::MaskedEmail(obj) => obj.validate(errors),
             ObjectInner::MemoryLookupKey(obj) => obj.validate(errors),
             ObjectInner::MemoryLookupKeyValue(obj) => obj.validate(errors),
+            ObjectInner::MessageTemplate(obj) => obj.validate(errors),
             ObjectInner::Metric(obj) => obj.validate(errors),
             ObjectInner::Metrics(obj) => obj.validate(errors),
             ObjectInner::MetricsStore(obj) => obj.validate(errors),
@@ -5585,6 +6339,7 @@ impl Object {
             ObjectInner::MtaOutboundStrategy(obj) => obj.validate(errors),
             ObjectInner::MtaOutboundThrottle(obj) => obj.validate(errors),
             ObjectInner::MtaQueueQuota(obj) => obj.validate(errors),
+            ObjectInner::MtaResponse(obj) => obj.validate(errors),
             ObjectInner::MtaRoute(obj) => obj.validate(errors),
             ObjectInner::MtaStageAuth(obj) => obj.validate(errors),
             ObjectInner::MtaStageConnect(obj) => obj.validate(errors),
@@ -5594,6 +6349,7 @@ impl Object {
             ObjectInner::MtaStageRcpt(obj) => obj.validate(errors),
             ObjectInner::MtaSts(obj) => obj.validate(errors),
             ObjectInner::MtaTlsStrategy(obj) => obj.validate(errors),
+            ObjectInner::MtaUnsubscribe(obj) => obj.validate(errors),
             ObjectInner::MtaVirtualQueue(obj) => obj.validate(errors),
             ObjectInner::NetworkListener(obj) => obj.validate(errors),
             ObjectInner::OAuthClient(obj) => obj.validate(errors),
@@ -5611,6 +6367,7 @@ impl Object {
             ObjectInner::SieveSystemScript(obj) => obj.validate(errors),
             ObjectInner::SieveUserInterpreter(obj) => obj.validate(errors),
             ObjectInner::SieveUserScript(obj) => obj.validate(errors),
+            ObjectInner::SpamClamAv(obj) => obj.validate(errors),
             ObjectInner::SpamClassifier(obj) => obj.validate(errors),
             ObjectInner::SpamDnsblServer(obj) => obj.validate(errors),
             ObjectInner::SpamDnsblSettings(obj) => obj.validate(errors),
@@ -5633,6 +6390,7 @@ impl Object {
             ObjectInner::Trace(obj) => obj.validate(errors),
             ObjectInner::Tracer(obj) => obj.validate(errors),
             ObjectInner::TracingStore(obj) => obj.validate(errors),
+            ObjectInner::UnsubscribeEvent(obj) => obj.validate(errors),
             ObjectInner::WebDav(obj) => obj.validate(errors),
             ObjectInner::WebHook(obj) => obj.validate(errors),
         }
@@ -5655,6 +6413,7 @@ impl Object {
             ObjectInner::ArchivedItem(obj) => obj.index(i),
             ObjectInner::ArfExternalReport(obj) => obj.index(i),
             ObjectInner::Asn(obj) => obj.index(i),
+            ObjectInner::AuditEvent(obj) => obj.index(i),
             ObjectInner::Authentication(obj) => obj.index(i),
             ObjectInner::BlobStore(obj) => obj.index(i),
             ObjectInner::BlockedIp(obj) => obj.index(i),
@@ -5669,6 +6428,7 @@ impl Object {
             ObjectInner::Coordinator(obj) => obj.index(i),
             ObjectInner::DataRetention(obj) => obj.index(i),
             ObjectInner::DataStore(obj) => obj.index(i),
+            ObjectInner::DeliveryCallback(obj) => obj.index(i),
             ObjectInner::Directory(obj) => obj.index(i),
             ObjectInner::DkimReportSettings(obj) => obj.index(i),
             ObjectInner::DkimSignature(obj) => obj.index(i),
@@ -5690,10 +6450,12 @@ impl Object {
             ObjectInner::InMemoryStore(obj) => obj.index(i),
             ObjectInner::Jmap(obj) => obj.index(i),
             ObjectInner::Log(obj) => obj.index(i),
+            ObjectInner::MailCategory(obj) => obj.index(i),
             ObjectInner::MailingList(obj) => obj.index(i),
             ObjectInner::MaskedEmail(obj) => obj.index(i),
             ObjectInner::MemoryLookupKey(obj) => obj.index(i),
             ObjectInner::MemoryLookupKeyValue(obj) => obj.index(i),
+            ObjectInner::MessageTemplate(obj) => obj.index(i),
             ObjectInner::Metric(obj) => obj.index(i),
             ObjectInner::Metrics(obj) => obj.index(i),
             ObjectInner::MetricsStore(obj) => obj.index(i),
@@ -5707,6 +6469,7 @@ impl Object {
             ObjectInner::MtaOutboundStrategy(obj) => obj.index(i),
             ObjectInner::MtaOutboundThrottle(obj) => obj.index(i),
             ObjectInner::MtaQueueQuota(obj) => obj.index(i),
+            ObjectInner::MtaResponse(obj) => obj.index(i),
             ObjectInner::MtaRoute(obj) => obj.index(i),
             ObjectInner::MtaStageAuth(obj) => obj.index(i),
             ObjectInner::MtaStageConnect(obj) => obj.index(i),
@@ -5716,6 +6479,7 @@ impl Object {
             ObjectInner::MtaStageRcpt(obj) => obj.index(i),
             ObjectInner::MtaSts(obj) => obj.index(i),
             ObjectInner::MtaTlsStrategy(obj) => obj.index(i),
+            ObjectInner::MtaUnsubscribe(obj) => obj.index(i),
             ObjectInner::MtaVirtualQueue(obj) => obj.index(i),
             ObjectInner::NetworkListener(obj) => obj.index(i),
             ObjectInner::OAuthClient(obj) => obj.index(i),
@@ -5733,6 +6497,7 @@ impl Object {
             ObjectInner::SieveSystemScript(obj) => obj.index(i),
             ObjectInner::SieveUserInterpreter(obj) => obj.index(i),
             ObjectInner::SieveUserScript(obj) => obj.index(i),
+            ObjectInner::SpamClamAv(obj) => obj.index(i),
             ObjectInner::SpamClassifier(obj) => obj.index(i),
             ObjectInner::SpamDnsblServer(obj) => obj.index(i),
             ObjectInner::SpamDnsblSettings(obj) => obj.index(i),
@@ -5755,6 +6520,7 @@ impl Object {
             ObjectInner::Trace(obj) => obj.index(i),
             ObjectInner::Tracer(obj) => obj.index(i),
             ObjectInner::TracingStore(obj) => obj.index(i),
+            ObjectInner::UnsubscribeEvent(obj) => obj.index(i),
             ObjectInner::WebDav(obj) => obj.index(i),
             ObjectInner::WebHook(obj) => obj.index(i),
         }
@@ -5781,6 +6547,7 @@ impl Object {
             ObjectInner::ArchivedItem(obj) => obj.patch(pointer, value),
             ObjectInner::ArfExternalReport(obj) => obj.patch(pointer, value),
             ObjectInner::Asn(obj) => obj.patch(pointer, value),
+            ObjectInner::AuditEvent(obj) => obj.patch(pointer, value),
             ObjectInner::Authentication(obj) => obj.patch(pointer, value),
             ObjectInner::BlobStore(obj) => obj.patch(pointer, value),
             ObjectInner::BlockedIp(obj) => obj.patch(pointer, value),
@@ -5795,6 +6562,7 @@ impl Object {
             ObjectInner::Coordinator(obj) => obj.patch(pointer, value),
             ObjectInner::DataRetention(obj) => obj.patch(pointer, value),
             ObjectInner::DataStore(obj) => obj.patch(pointer, value),
+            ObjectInner::DeliveryCallback(obj) => obj.patch(pointer, value),
             ObjectInner::Directory(obj) => obj.patch(pointer, value),
             ObjectInner::DkimReportSettings(obj) => obj.patch(pointer, value),
             ObjectInner::DkimSignature(obj) => obj.patch(pointer, value),
@@ -5816,10 +6584,12 @@ impl Object {
             ObjectInner::InMemoryStore(obj) => obj.patch(pointer, value),
             ObjectInner::Jmap(obj) => obj.patch(pointer, value),
             ObjectInner::Log(obj) => obj.patch(pointer, value),
+            ObjectInner::MailCategory(obj) => obj.patch(pointer, value),
             ObjectInner::MailingList(obj) => obj.patch(pointer, value),
             ObjectInner::MaskedEmail(obj) => obj.patch(pointer, value),
             ObjectInner::MemoryLookupKey(obj) => obj.patch(pointer, value),
             ObjectInner::MemoryLookupKeyValue(obj) => obj.patch(pointer, value),
+            ObjectInner::MessageTemplate(obj) => obj.patch(pointer, value),
             ObjectInner::Metric(obj) => obj.patch(pointer, value),
             ObjectInner::Metrics(obj) => obj.patch(pointer, value),
             ObjectInner::MetricsStore(obj) => obj.patch(pointer, value),
@@ -5833,6 +6603,7 @@ impl Object {
             ObjectInner::MtaOutboundStrategy(obj) => obj.patch(pointer, value),
             ObjectInner::MtaOutboundThrottle(obj) => obj.patch(pointer, value),
             ObjectInner::MtaQueueQuota(obj) => obj.patch(pointer, value),
+            ObjectInner::MtaResponse(obj) => obj.patch(pointer, value),
             ObjectInner::MtaRoute(obj) => obj.patch(pointer, value),
             ObjectInner::MtaStageAuth(obj) => obj.patch(pointer, value),
             ObjectInner::MtaStageConnect(obj) => obj.patch(pointer, value),
@@ -5842,6 +6613,7 @@ impl Object {
             ObjectInner::MtaStageRcpt(obj) => obj.patch(pointer, value),
             ObjectInner::MtaSts(obj) => obj.patch(pointer, value),
             ObjectInner::MtaTlsStrategy(obj) => obj.patch(pointer, value),
+            ObjectInner::MtaUnsubscribe(obj) => obj.patch(pointer, value),
             ObjectInner::MtaVirtualQueue(obj) => obj.patch(pointer, value),
             ObjectInner::NetworkListener(obj) => obj.patch(pointer, value),
             ObjectInner::OAuthClient(obj) => obj.patch(pointer, value),
@@ -5859,6 +6631,7 @@ impl Object {
             ObjectInner::SieveSystemScript(obj) => obj.patch(pointer, value),
             ObjectInner::SieveUserInterpreter(obj) => obj.patch(pointer, value),
             ObjectInner::SieveUserScript(obj) => obj.patch(pointer, value),
+            ObjectInner::SpamClamAv(obj) => obj.patch(pointer, value),
             ObjectInner::SpamClassifier(obj) => obj.patch(pointer, value),
             ObjectInner::SpamDnsblServer(obj) => obj.patch(pointer, value),
             ObjectInner::SpamDnsblSettings(obj) => obj.patch(pointer, value),
@@ -5881,6 +6654,7 @@ impl Object {
             ObjectInner::Trace(obj) => obj.patch(pointer, value),
             ObjectInner::Tracer(obj) => obj.patch(pointer, value),
             ObjectInner::TracingStore(obj) => obj.patch(pointer, value),
+            ObjectInner::UnsubscribeEvent(obj) => obj.patch(pointer, value),
             ObjectInner::WebDav(obj) => obj.patch(pointer, value),
             ObjectInner::WebHook(obj) => obj.patch(pointer, value),
         }
@@ -5905,6 +6679,7 @@ impl IntoValue for Object {
             ObjectInner::ArchivedItem(obj) => obj.into_value(),
             ObjectInner::ArfExternalReport(obj) => obj.into_value(),
             ObjectInner::Asn(obj) => obj.into_value(),
+            ObjectInner::AuditEvent(obj) => obj.into_value(),
             ObjectInner::Authentication(obj) => obj.into_value(),
             ObjectInner::BlobStore(obj) => obj.into_value(),
             ObjectInner::BlockedIp(obj) => obj.into_value(),
@@ -5919,6 +6694,7 @@ impl IntoValue for Object {
             ObjectInner::Coordinator(obj) => obj.into_value(),
             ObjectInner::DataRetention(obj) => obj.into_value(),
             ObjectInner::DataStore(obj) => obj.into_value(),
+            ObjectInner::DeliveryCallback(obj) => obj.into_value(),
             ObjectInner::Directory(obj) => obj.into_value(),
             ObjectInner::DkimReportSettings(obj) => obj.into_value(),
             ObjectInner::DkimSignature(obj) => obj.into_value(),
@@ -5940,10 +6716,12 @@ impl IntoValue for Object {
             ObjectInner::InMemoryStore(obj) => obj.into_value(),
             ObjectInner::Jmap(obj) => obj.into_value(),
             ObjectInner::Log(obj) => obj.into_value(),
+            ObjectInner::MailCategory(obj) => obj.into_value(),
             ObjectInner::MailingList(obj) => obj.into_value(),
             ObjectInner::MaskedEmail(obj) => obj.into_value(),
             ObjectInner::MemoryLookupKey(obj) => obj.into_value(),
             ObjectInner::MemoryLookupKeyValue(obj) => obj.into_value(),
+            ObjectInner::MessageTemplate(obj) => obj.into_value(),
             ObjectInner::Metric(obj) => obj.into_value(),
             ObjectInner::Metrics(obj) => obj.into_value(),
             ObjectInner::MetricsStore(obj) => obj.into_value(),
@@ -5957,6 +6735,7 @@ impl IntoValue for Object {
             ObjectInner::MtaOutboundStrategy(obj) => obj.into_value(),
             ObjectInner::MtaOutboundThrottle(obj) => obj.into_value(),
             ObjectInner::MtaQueueQuota(obj) => obj.into_value(),
+            ObjectInner::MtaResponse(obj) => obj.into_value(),
             ObjectInner::MtaRoute(obj) => obj.into_value(),
             ObjectInner::MtaStageAuth(obj) => obj.into_value(),
             ObjectInner::MtaStageConnect(obj) => obj.into_value(),
@@ -5966,6 +6745,7 @@ impl IntoValue for Object {
             ObjectInner::MtaStageRcpt(obj) => obj.into_value(),
             ObjectInner::MtaSts(obj) => obj.into_value(),
             ObjectInner::MtaTlsStrategy(obj) => obj.into_value(),
+            ObjectInner::MtaUnsubscribe(obj) => obj.into_value(),
             ObjectInner::MtaVirtualQueue(obj) => obj.into_value(),
             ObjectInner::NetworkListener(obj) => obj.into_value(),
             ObjectInner::OAuthClient(obj) => obj.into_value(),
@@ -5983,6 +6763,7 @@ impl IntoValue for Object {
             ObjectInner::SieveSystemScript(obj) => obj.into_value(),
             ObjectInner::SieveUserInterpreter(obj) => obj.into_value(),
             ObjectInner::SieveUserScript(obj) => obj.into_value(),
+            ObjectInner::SpamClamAv(obj) => obj.into_value(),
             ObjectInner::SpamClassifier(obj) => obj.into_value(),
             ObjectInner::SpamDnsblServer(obj) => obj.into_value(),
             ObjectInner::SpamDnsblSettings(obj) => obj.into_value(),
@@ -6005,6 +6786,7 @@ impl IntoValue for Object {
             ObjectInner::Trace(obj) => obj.into_value(),
             ObjectInner::Tracer(obj) => obj.into_value(),
             ObjectInner::TracingStore(obj) => obj.into_value(),
+            ObjectInner::UnsubscribeEvent(obj) => obj.into_value(),
             ObjectInner::WebDav(obj) => obj.into_value(),
             ObjectInner::WebHook(obj) => obj.into_value(),
         }
@@ -6029,6 +6811,7 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::ArchivedItem => ObjectInner::ArchivedItem(Default::default()),
             ObjectType::ArfExternalReport => ObjectInner::ArfExternalReport(Default::default()),
             ObjectType::Asn => ObjectInner::Asn(Default::default()),
+            ObjectType::AuditEvent => ObjectInner::AuditEvent(Default::default()),
             ObjectType::Authentication => ObjectInner::Authentication(Default::default()),
             ObjectType::BlobStore => ObjectInner::BlobStore(Default::default()),
             ObjectType::BlockedIp => ObjectInner::BlockedIp(Default::default()),
@@ -6043,6 +6826,7 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::Coordinator => ObjectInner::Coordinator(Default::default()),
             ObjectType::DataRetention => ObjectInner::DataRetention(Default::default()),
             ObjectType::DataStore => ObjectInner::DataStore(Default::default()),
+            ObjectType::DeliveryCallback => ObjectInner::DeliveryCallback(Default::default()),
             ObjectType::Directory => ObjectInner::Directory(Default::default()),
             ObjectType::DkimReportSettings => ObjectInner::DkimReportSettings(Default::default()),
             ObjectType::DkimSignature => ObjectInner::DkimSignature(Default::default()),
@@ -6064,12 +6848,14 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::InMemoryStore => ObjectInner::InMemoryStore(Default::default()),
             ObjectType::Jmap => ObjectInner::Jmap(Default::default()),
             ObjectType::Log => ObjectInner::Log(Default::default()),
+            ObjectType::MailCategory => ObjectInner::MailCategory(Default::default()),
             ObjectType::MailingList => ObjectInner::MailingList(Default::default()),
             ObjectType::MaskedEmail => ObjectInner::MaskedEmail(Default::default()),
             ObjectType::MemoryLookupKey => ObjectInner::MemoryLookupKey(Default::default()),
             ObjectType::MemoryLookupKeyValue => {
                 ObjectInner::MemoryLookupKeyValue(Default::default())
             }
+            ObjectType::MessageTemplate => ObjectInner::MessageTemplate(Default::default()),
             ObjectType::Metric => ObjectInner::Metric(Default::default()),
             ObjectType::Metrics => ObjectInner::Metrics(Default::default()),
             ObjectType::MetricsStore => ObjectInner::MetricsStore(Default::default()),
@@ -6085,6 +6871,7 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::MtaOutboundStrategy => ObjectInner::MtaOutboundStrategy(Default::default()),
             ObjectType::MtaOutboundThrottle => ObjectInner::MtaOutboundThrottle(Default::default()),
             ObjectType::MtaQueueQuota => ObjectInner::MtaQueueQuota(Default::default()),
+            ObjectType::MtaResponse => ObjectInner::MtaResponse(Default::default()),
             ObjectType::MtaRoute => ObjectInner::MtaRoute(Default::default()),
             ObjectType::MtaStageAuth => ObjectInner::MtaStageAuth(Default::default()),
             ObjectType::MtaStageConnect => ObjectInner::MtaStageConnect(Default::default()),
@@ -6094,6 +6881,7 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::MtaStageRcpt => ObjectInner::MtaStageRcpt(Default::default()),
             ObjectType::MtaSts => ObjectInner::MtaSts(Default::default()),
             ObjectType::MtaTlsStrategy => ObjectInner::MtaTlsStrategy(Default::default()),
+            ObjectType::MtaUnsubscribe => ObjectInner::MtaUnsubscribe(Default::default()),
             ObjectType::MtaVirtualQueue => ObjectInner::MtaVirtualQueue(Default::default()),
             ObjectType::NetworkListener => ObjectInner::NetworkListener(Default::default()),
             ObjectType::OAuthClient => ObjectInner::OAuthClient(Default::default()),
@@ -6115,6 +6903,7 @@ impl From<ObjectType> for ObjectInner {
                 ObjectInner::SieveUserInterpreter(Default::default())
             }
             ObjectType::SieveUserScript => ObjectInner::SieveUserScript(Default::default()),
+            ObjectType::SpamClamAv => ObjectInner::SpamClamAv(Default::default()),
             ObjectType::SpamClassifier => ObjectInner::SpamClassifier(Default::default()),
             ObjectType::SpamDnsblServer => ObjectInner::SpamDnsblServer(Default::default()),
             ObjectType::SpamDnsblSettings => ObjectInner::SpamDnsblSettings(Default::default()),
@@ -6137,6 +6926,7 @@ impl From<ObjectType> for ObjectInner {
             ObjectType::Trace => ObjectInner::Trace(Default::default()),
             ObjectType::Tracer => ObjectInner::Tracer(Default::default()),
             ObjectType::TracingStore => ObjectInner::TracingStore(Default::default()),
+            ObjectType::UnsubscribeEvent => ObjectInner::UnsubscribeEvent(Default::default()),
             ObjectType::WebDav => ObjectInner::WebDav(Default::default()),
             ObjectType::WebHook => ObjectInner::WebHook(Default::default()),
         }
@@ -6368,6 +7158,21 @@ impl From<Object> for Asn {
     }
 }
 
+impl From<AuditEvent> for ObjectInner {
+    fn from(value: AuditEvent) -> Self {
+        ObjectInner::AuditEvent(value)
+    }
+}
+
+impl From<Object> for AuditEvent {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::AuditEvent(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<Authentication> for ObjectInner {
     fn from(value: Authentication) -> Self {
         ObjectInner::Authentication(value)
@@ -6578,6 +7383,21 @@ impl From<Object> for DataStore {
     }
 }
 
+impl From<DeliveryCallback> for ObjectInner {
+    fn from(value: DeliveryCallback) -> Self {
+        ObjectInner::DeliveryCallback(value)
+    }
+}
+
+impl From<Object> for DeliveryCallback {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::DeliveryCallback(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<Directory> for ObjectInner {
     fn from(value: Directory) -> Self {
         ObjectInner::Directory(value)
@@ -6893,6 +7713,21 @@ impl From<Object> for Log {
     }
 }
 
+impl From<MailCategory> for ObjectInner {
+    fn from(value: MailCategory) -> Self {
+        ObjectInner::MailCategory(value)
+    }
+}
+
+impl From<Object> for MailCategory {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::MailCategory(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<MailingList> for ObjectInner {
     fn from(value: MailingList) -> Self {
         ObjectInner::MailingList(value)
@@ -6953,6 +7788,21 @@ impl From<Object> for MemoryLookupKeyValue {
     }
 }
 
+impl From<MessageTemplate> for ObjectInner {
+    fn from(value: MessageTemplate) -> Self {
+        ObjectInner::MessageTemplate(value)
+    }
+}
+
+impl From<Object> for MessageTemplate {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::MessageTemplate(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<Metric> for ObjectInner {
     fn from(value: Metric) -> Self {
         ObjectInner::Metric(value)
@@ -7148,6 +7998,21 @@ impl From<Object> for MtaQueueQuota {
     }
 }
 
+impl From<MtaResponse> for ObjectInner {
+    fn from(value: MtaResponse) -> Self {
+        ObjectInner::MtaResponse(value)
+    }
+}
+
+impl From<Object> for MtaResponse {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::MtaResponse(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<MtaRoute> for ObjectInner {
     fn from(value: MtaRoute) -> Self {
         ObjectInner::MtaRoute(value)
@@ -7283,6 +8148,21 @@ impl From<Object> for MtaTlsStrategy {
     }
 }
 
+impl From<MtaUnsubscribe> for ObjectInner {
+    fn from(value: MtaUnsubscribe) -> Self {
+        ObjectInner::MtaUnsubscribe(value)
+    }
+}
+
+impl From<Object> for MtaUnsubscribe {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::MtaUnsubscribe(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<MtaVirtualQueue> for ObjectInner {
     fn from(value: MtaVirtualQueue) -> Self {
         ObjectInner::MtaVirtualQueue(value)
@@ -7538,6 +8418,21 @@ impl From<Object> for SieveUserScript {
     }
 }
 
+impl From<SpamClamAv> for ObjectInner {
+    fn from(value: SpamClamAv) -> Self {
+        ObjectInner::SpamClamAv(value)
+    }
+}
+
+impl From<Object> for SpamClamAv {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::SpamClamAv(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<SpamClassifier> for ObjectInner {
     fn from(value: SpamClassifier) -> Self {
         ObjectInner::SpamClassifier(value)
@@ -7868,6 +8763,21 @@ impl From<Object> for TracingStore {
     }
 }
 
+impl From<UnsubscribeEvent> for ObjectInner {
+    fn from(value: UnsubscribeEvent) -> Self {
+        ObjectInner::UnsubscribeEvent(value)
+    }
+}
+
+impl From<Object> for UnsubscribeEvent {
+    fn from(obj: Object) -> Self {
+        match obj.inner {
+            ObjectInner::UnsubscribeEvent(obj) => obj,
+            _ => unreachable!(),
+        }
+    }
+}
+
 impl From<WebDav> for ObjectInner {
     fn from(value: WebDav) -> Self {
         ObjectInner::WebDav(value)
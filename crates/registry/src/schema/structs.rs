@@ -36,6 +36,10 @@ pub struct AccountSettings {
     pub time_zone: Option<TimeZone>,
     #[serde(rename = "encryptionAtRest")]
     pub encryption_at_rest: EncryptionAtRest,
+    #[serde(rename = "calendarInvitations")]
+    pub calendar_invitations: CalendarInvitationPolicy,
+    #[serde(rename = "sieveLogging")]
+    pub sieve_logging: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -77,6 +81,8 @@ pub enum Action {
     InvalidateNegativeCaches,
     PauseMtaQueue,
     ResumeMtaQueue,
+    DrainServer,
+    SignOutEverywhere,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -455,6 +461,27 @@ pub struct AsnResource {
     pub http_headers: VecMap<String, String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditEvent {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "actorId")]
+    pub actor_id: Id,
+    #[serde(rename = "timestamp")]
+    pub timestamp: UTCDateTime,
+    #[serde(rename = "event")]
+    pub event: AuditEventType,
+    #[serde(rename = "protocol")]
+    pub protocol: ServiceProtocol,
+    #[serde(rename = "remoteIp")]
+    pub remote_ip: Option<IpAddr>,
+    #[serde(rename = "objectIds")]
+    pub object_ids: Map<String>,
+    #[serde(rename = "details")]
+    pub details: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Authentication {
@@ -482,6 +509,20 @@ pub struct Authentication {
     pub max_app_passwords: Option<u64>,
     #[serde(rename = "maxApiKeys")]
     pub max_api_keys: Option<u64>,
+    #[serde(rename = "stepUpPermissions")]
+    pub step_up_permissions: Map<Permission>,
+    #[serde(rename = "stepUpMaxAge")]
+    pub step_up_max_age: Duration,
+    #[serde(rename = "maxSessions")]
+    pub max_sessions: Option<u64>,
+    #[serde(rename = "maxSessionsPerProtocol")]
+    pub max_sessions_per_protocol: VecMap<ServiceProtocol, u64>,
+    #[serde(rename = "directoryChain")]
+    pub directory_chain: List<DirectoryChainEntry>,
+    #[serde(rename = "directoryLookupMode")]
+    pub directory_lookup_mode: DirectoryLookupMode,
+    #[serde(rename = "directoryCacheTtl")]
+    pub directory_cache_ttl: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -622,6 +663,8 @@ pub struct Cache {
     pub dkim_signatures: u64,
     #[serde(rename = "negativeTtl")]
     pub negative_ttl: Duration,
+    #[serde(rename = "directoryPrincipals")]
+    pub directory_principals: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -702,6 +745,8 @@ pub struct Certificate {
     pub not_valid_before: UTCDateTime,
     #[serde(rename = "issuer")]
     pub issuer: String,
+    #[serde(rename = "stagedUntil")]
+    pub staged_until: Option<UTCDateTime>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -718,6 +763,10 @@ pub struct CertificateManagementProperties {
     pub acme_provider_id: Id,
     #[serde(rename = "subjectAlternativeNames")]
     pub subject_alternative_names: Map<String>,
+    #[serde(rename = "daneRollover")]
+    pub dane_rollover: bool,
+    #[serde(rename = "daneMaxDelay")]
+    pub dane_max_delay: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -887,6 +936,22 @@ pub struct DataRetention {
     pub hold_metrics_for: Option<Duration>,
     #[serde(rename = "metricsCollectionInterval")]
     pub metrics_collection_interval: Cron,
+    #[serde(rename = "holdAuditEventsFor")]
+    pub hold_audit_events_for: Option<Duration>,
+    #[serde(rename = "quotaReconcileInterval")]
+    pub quota_reconcile_interval: Duration,
+    #[serde(rename = "quotaReconcileSampleSize")]
+    pub quota_reconcile_sample_size: u64,
+    #[serde(rename = "maxChangesAge")]
+    pub max_changes_age: Option<Duration>,
+    #[serde(rename = "maxChangesAgePerCollection")]
+    pub max_changes_age_per_collection: VecMap<ChangeLogCollection, Duration>,
+    #[serde(rename = "backupDirectory")]
+    pub backup_directory: Option<String>,
+    #[serde(rename = "backupMaxIncrements")]
+    pub backup_max_increments: u64,
+    #[serde(rename = "backupIncludeBlobs")]
+    pub backup_include_blobs: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -899,6 +964,27 @@ pub enum DataStore {
     MySql(MySqlStore),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeliveryCallback {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+    #[serde(rename = "url")]
+    pub url: String,
+    #[serde(rename = "signatureKey")]
+    pub signature_key: SecretKeyOptional,
+    #[serde(rename = "allowInvalidCerts")]
+    pub allow_invalid_certs: bool,
+    #[serde(rename = "timeout")]
+    pub timeout: Duration,
+    #[serde(rename = "throttle")]
+    pub throttle: Duration,
+    #[serde(rename = "discardAfter")]
+    pub discard_after: Duration,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct DeliveryError {
@@ -935,6 +1021,13 @@ pub enum DirectoryBootstrap {
     Oidc(OidcDirectory),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectoryChainEntry {
+    #[serde(rename = "directoryId")]
+    pub directory_id: Option<Id>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Dkim1Signature {
@@ -1091,6 +1184,8 @@ pub struct DmarcInternalReport {
     pub created_at: UTCDateTime,
     #[serde(rename = "deliverAt")]
     pub deliver_at: UTCDateTime,
+    #[serde(rename = "ruaMaxSize")]
+    pub rua_max_size: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -1176,6 +1271,10 @@ pub struct DmarcReportRecord {
     pub spf_results: List<DmarcSpfResult>,
     #[serde(rename = "extensions")]
     pub extensions: List<DmarcExtension>,
+    #[serde(rename = "firstSeen")]
+    pub first_seen: Option<UTCDateTime>,
+    #[serde(rename = "lastSeen")]
+    pub last_seen: Option<UTCDateTime>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -1207,6 +1306,8 @@ pub struct DmarcReportSettings {
     pub failure_dkim_sign_domain: Expression,
     #[serde(rename = "failureSubject")]
     pub failure_subject: Expression,
+    #[serde(rename = "aggregateMaxCompressedSize")]
+    pub aggregate_max_compressed_size: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -2722,6 +2823,44 @@ pub struct Domain {
     pub allow_relaying: bool,
     #[serde(rename = "reportAddressUri")]
     pub report_address_uri: Option<String>,
+    #[serde(rename = "auditLog")]
+    pub audit_log: bool,
+    #[serde(rename = "auditMessageReads")]
+    pub audit_message_reads: bool,
+    #[serde(rename = "provisionFolders")]
+    pub provision_folders: Map<String>,
+    #[serde(rename = "provisionSubscribe")]
+    pub provision_subscribe: bool,
+    #[serde(rename = "provisionSieveScript")]
+    pub provision_sieve_script: Option<String>,
+    #[serde(rename = "provisionSignature")]
+    pub provision_signature: Option<String>,
+    #[serde(rename = "quotaWarningThresholds")]
+    pub quota_warning_thresholds: Map<u64>,
+    #[serde(rename = "dkimSignDomain")]
+    pub dkim_sign_domain: Option<String>,
+    #[serde(rename = "reportFromAddress")]
+    pub report_from_address: Option<String>,
+    #[serde(rename = "maxMessageSize")]
+    pub max_message_size: Option<u64>,
+    #[serde(rename = "spamThreshold")]
+    pub spam_threshold: Option<Float>,
+    #[serde(rename = "mtaStsMode")]
+    pub mta_sts_mode: Option<PolicyEnforcement>,
+    #[serde(rename = "mtaStsMxHosts")]
+    pub mta_sts_mx_hosts: Map<String>,
+    #[serde(rename = "mtaStsMaxAge")]
+    pub mta_sts_max_age: Option<Duration>,
+    #[serde(rename = "directoryChain")]
+    pub directory_chain: List<DirectoryChainEntry>,
+    #[serde(rename = "addDeliveredToHeader")]
+    pub add_delivered_to_header: bool,
+    #[serde(rename = "batvKey")]
+    pub batv_key: SecretKeyOptional,
+    #[serde(rename = "batvPreviousKey")]
+    pub batv_previous_key: SecretKeyOptional,
+    #[serde(rename = "batvValidity")]
+    pub batv_validity: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -2733,6 +2872,14 @@ pub struct DsnReportSettings {
     pub from_name: Expression,
     #[serde(rename = "dkimSignDomain")]
     pub dkim_sign_domain: Expression,
+    #[serde(rename = "spamThreshold")]
+    pub spam_threshold: Option<Float>,
+    #[serde(rename = "suppressNoMx")]
+    pub suppress_no_mx: bool,
+    #[serde(rename = "suppressionList")]
+    pub suppression_list: Option<String>,
+    #[serde(rename = "rateLimit")]
+    pub rate_limit: Option<Rate>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -2787,6 +2934,40 @@ pub struct Email {
     pub max_masked_addresses: Option<u64>,
     #[serde(rename = "maxPublicKeys")]
     pub max_public_keys: Option<u64>,
+    #[serde(rename = "maxDeliveryCallbacks")]
+    pub max_delivery_callbacks: Option<u64>,
+    #[serde(rename = "reEncryptConcurrency")]
+    pub re_encrypt_concurrency: u64,
+    #[serde(rename = "importRoot")]
+    pub import_root: Option<String>,
+    #[serde(rename = "provisionFolders")]
+    pub provision_folders: Map<String>,
+    #[serde(rename = "provisionSubscribe")]
+    pub provision_subscribe: bool,
+    #[serde(rename = "provisionSieveScript")]
+    pub provision_sieve_script: Option<String>,
+    #[serde(rename = "provisionSignature")]
+    pub provision_signature: Option<String>,
+    #[serde(rename = "quotaWarningThresholds")]
+    pub quota_warning_thresholds: Map<u64>,
+    #[serde(rename = "quotaWarningNotify")]
+    pub quota_warning_notify: bool,
+    #[serde(rename = "quotaWarningInterval")]
+    pub quota_warning_interval: Duration,
+    #[serde(rename = "duplicateDelivery")]
+    pub duplicate_delivery: DuplicateDelivery,
+    #[serde(rename = "duplicateDeliveryWindow")]
+    pub duplicate_delivery_window: Duration,
+    #[serde(rename = "maxDelayedSend")]
+    pub max_delayed_send: Duration,
+    #[serde(rename = "sharedBlobQuota")]
+    pub shared_blob_quota: SharedBlobQuota,
+    #[serde(rename = "maxFollowUps")]
+    pub max_follow_ups: Option<u64>,
+    #[serde(rename = "recallWindow")]
+    pub recall_window: Duration,
+    #[serde(rename = "recallNotice")]
+    pub recall_notice: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -2863,6 +3044,8 @@ pub struct Expression {
     pub match_: List<ExpressionMatch>,
     #[serde(rename = "else")]
     pub else_: String,
+    #[serde(rename = "strict")]
+    pub strict: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -2972,6 +3155,16 @@ pub struct Http {
     pub use_x_forwarded: bool,
     #[serde(rename = "redirectRoot")]
     pub redirect_root: Option<String>,
+    #[serde(rename = "queuePreviewSize")]
+    pub queue_preview_size: u64,
+    #[serde(rename = "readinessCheckTimeout")]
+    pub readiness_check_timeout: Duration,
+    #[serde(rename = "readinessTimeout")]
+    pub readiness_timeout: Duration,
+    #[serde(rename = "readinessOptional")]
+    pub readiness_optional: Map<HealthCheckDependency>,
+    #[serde(rename = "readinessCacheTtl")]
+    pub readiness_cache_ttl: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3065,12 +3258,15 @@ pub struct HttpLookupCsv {
     pub separator: String,
     #[serde(rename = "skipFirst")]
     pub skip_first: bool,
+    #[serde(rename = "allColumns")]
+    pub all_columns: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "@type")]
 pub enum HttpLookupFormat {
     Csv(HttpLookupCsv),
+    Json,
     List,
 }
 
@@ -3102,6 +3298,12 @@ pub struct Imap {
     pub timeout_authenticated: Duration,
     #[serde(rename = "timeoutIdle")]
     pub timeout_idle: Duration,
+    #[serde(rename = "searchCacheSize")]
+    pub search_cache_size: u64,
+    #[serde(rename = "searchCacheTtl")]
+    pub search_cache_ttl: Duration,
+    #[serde(rename = "idleNotifyDelay")]
+    pub idle_notify_delay: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3185,6 +3387,14 @@ pub struct Jmap {
     pub web_push_key: SecretTextOptional,
     #[serde(rename = "webPushContact")]
     pub web_push_contact: Option<String>,
+    #[serde(rename = "websocketMaxConcurrent")]
+    pub websocket_max_concurrent: u64,
+    #[serde(rename = "slowMethodThreshold")]
+    pub slow_method_threshold: Option<Duration>,
+    #[serde(rename = "methodProfiling")]
+    pub method_profiling: bool,
+    #[serde(rename = "slowMethodThresholdPerMethod")]
+    pub slow_method_threshold_per_method: VecMap<String, Duration>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3276,6 +3486,10 @@ pub struct LdapDirectory {
     pub pool_timeout_wait: Duration,
     #[serde(rename = "memberTenantId")]
     pub member_tenant_id: Option<Id>,
+    #[serde(rename = "attrEnabledProtocols")]
+    pub attr_enabled_protocols: Map<String>,
+    #[serde(rename = "attrCategorizeMail")]
+    pub attr_categorize_mail: Map<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3312,6 +3526,27 @@ pub struct MailExchanger {
     pub priority: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailCategory {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "description")]
+    pub description: Option<String>,
+    #[serde(rename = "enable")]
+    pub enable: bool,
+    #[serde(rename = "mailboxName")]
+    pub mailbox_name: Option<String>,
+    #[serde(rename = "matchTags")]
+    pub match_tags: Map<String>,
+    #[serde(rename = "matchKeywords")]
+    pub match_keywords: Map<String>,
+    #[serde(rename = "minSenderMessages")]
+    pub min_sender_messages: Option<u64>,
+    #[serde(rename = "minMatches")]
+    pub min_matches: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct MailingList {
@@ -3397,6 +3632,25 @@ pub struct MemoryLookupKeyValue {
     pub is_glob_pattern: bool,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTemplate {
+    #[serde(rename = "templateId")]
+    pub template_id: MessageTemplateId,
+    #[serde(rename = "locale")]
+    pub locale: Option<Locale>,
+    #[serde(rename = "subject")]
+    pub subject: String,
+    #[serde(rename = "textBody")]
+    pub text_body: String,
+    #[serde(rename = "htmlBody")]
+    pub html_body: Option<String>,
+    #[serde(rename = "memberTenantId")]
+    pub member_tenant_id: Option<Id>,
+    #[serde(rename = "domainId")]
+    pub domain_id: Option<Id>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "@type")]
 pub enum Metric {
@@ -3436,6 +3690,8 @@ pub struct Metrics {
     pub metrics: Map<trc::MetricType>,
     #[serde(rename = "metricsPolicy")]
     pub metrics_policy: EventPolicy,
+    #[serde(rename = "queueTopDomains")]
+    pub queue_top_domains: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3505,6 +3761,16 @@ pub struct MtaConnectionIpHost {
     pub ehlo_hostname: Option<String>,
     #[serde(rename = "sourceIp")]
     pub source_ip: IpAddr,
+    #[serde(rename = "weight")]
+    pub weight: u64,
+    #[serde(rename = "warmupStart")]
+    pub warmup_start: Option<UTCDateTime>,
+    #[serde(rename = "warmupDays")]
+    pub warmup_days: u64,
+    #[serde(rename = "warmupInitialLimit")]
+    pub warmup_initial_limit: u64,
+    #[serde(rename = "warmupTargetLimit")]
+    pub warmup_target_limit: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3530,6 +3796,8 @@ pub struct MtaConnectionStrategy {
     pub mail_from_timeout: Duration,
     #[serde(rename = "rcptToTimeout")]
     pub rcpt_to_timeout: Duration,
+    #[serde(rename = "sourceIpSelection")]
+    pub source_ip_selection: MtaIpSelection,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3551,6 +3819,8 @@ pub struct MtaDeliveryExpirationAttempts {
 pub struct MtaDeliveryExpirationTtl {
     #[serde(rename = "expire")]
     pub expire: Duration,
+    #[serde(rename = "maxAttempts")]
+    pub max_attempts: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3568,6 +3838,14 @@ pub struct MtaDeliverySchedule {
     pub queue_id: Id,
     #[serde(rename = "retry")]
     pub retry: MtaDeliveryScheduleIntervalsOrDefault,
+    #[serde(rename = "deliveryDays")]
+    pub delivery_days: Map<Weekday>,
+    #[serde(rename = "deliveryHourStart")]
+    pub delivery_hour_start: Option<u64>,
+    #[serde(rename = "deliveryHourEnd")]
+    pub delivery_hour_end: Option<u64>,
+    #[serde(rename = "deliveryTimeZone")]
+    pub delivery_time_zone: Option<TimeZone>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3614,6 +3892,12 @@ pub struct MtaExtensions {
     pub require_tls: Expression,
     #[serde(rename = "vrfy")]
     pub vrfy: Expression,
+    #[serde(rename = "hiddenExtensions")]
+    pub hidden_extensions: Expression,
+    #[serde(rename = "rejectHiddenExtensions")]
+    pub reject_hidden_extensions: Expression,
+    #[serde(rename = "deliveryCallback")]
+    pub delivery_callback: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3637,6 +3921,10 @@ pub struct MtaHook {
     pub http_auth: HttpAuth,
     #[serde(rename = "httpHeaders")]
     pub http_headers: VecMap<String, String>,
+    #[serde(rename = "protocol")]
+    pub protocol: MtaHookProtocol,
+    #[serde(rename = "streamChunkSize")]
+    pub stream_chunk_size: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3743,6 +4031,28 @@ pub struct MtaQueueQuota {
     pub size: Option<u64>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MtaRelayHost {
+    #[serde(rename = "address")]
+    pub address: String,
+    #[serde(rename = "port")]
+    pub port: u64,
+    #[serde(rename = "weight")]
+    pub weight: u64,
+    #[serde(rename = "maxConnections")]
+    pub max_connections: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MtaResponse {
+    #[serde(rename = "responseId")]
+    pub response_id: MtaResponseId,
+    #[serde(rename = "message")]
+    pub message: Expression,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "@type")]
 pub enum MtaRoute {
@@ -3796,6 +4106,20 @@ pub struct MtaRouteRelay {
     pub name: String,
     #[serde(rename = "description")]
     pub description: Option<String>,
+    #[serde(rename = "weight")]
+    pub weight: u64,
+    #[serde(rename = "maxConnections")]
+    pub max_connections: Option<u64>,
+    #[serde(rename = "additionalHosts")]
+    pub additional_hosts: List<MtaRelayHost>,
+    #[serde(rename = "hostSelection")]
+    pub host_selection: MtaIpSelection,
+    #[serde(rename = "failureThreshold")]
+    pub failure_threshold: u64,
+    #[serde(rename = "failureCoolOff")]
+    pub failure_cool_off: Duration,
+    #[serde(rename = "healthCheckInterval")]
+    pub health_check_interval: Option<Duration>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3811,6 +4135,10 @@ pub struct MtaStageAuth {
     pub must_match_sender: Expression,
     #[serde(rename = "require")]
     pub require: Expression,
+    #[serde(rename = "clientCertAccount")]
+    pub client_cert_account: Expression,
+    #[serde(rename = "clientCertRevocationList")]
+    pub client_cert_revocation_list: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3851,6 +4179,10 @@ pub struct MtaStageData {
     pub script: Expression,
     #[serde(rename = "enableSpamFilter")]
     pub enable_spam_filter: Expression,
+    #[serde(rename = "fromAlignment")]
+    pub from_alignment: Expression,
+    #[serde(rename = "lmtpLocalDelivery")]
+    pub lmtp_local_delivery: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3890,6 +4222,12 @@ pub struct MtaStageRcpt {
     pub rewrite: Expression,
     #[serde(rename = "script")]
     pub script: Expression,
+    #[serde(rename = "maxExpansionDepth")]
+    pub max_expansion_depth: u64,
+    #[serde(rename = "maxExpansionRecipients")]
+    pub max_expansion_recipients: u64,
+    #[serde(rename = "expansionTraceHeader")]
+    pub expansion_trace_header: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3924,6 +4262,21 @@ pub struct MtaTlsStrategy {
     pub tls_timeout: Duration,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MtaUnsubscribe {
+    #[serde(rename = "enable")]
+    pub enable: Expression,
+    #[serde(rename = "campaignId")]
+    pub campaign_id: Expression,
+    #[serde(rename = "signatureKey")]
+    pub signature_key: SecretKeyOptional,
+    #[serde(rename = "tokenValidity")]
+    pub token_validity: Duration,
+    #[serde(rename = "rateLimit")]
+    pub rate_limit: Option<Rate>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct MtaVirtualQueue {
@@ -3933,6 +4286,8 @@ pub struct MtaVirtualQueue {
     pub description: Option<String>,
     #[serde(rename = "threadsPerNode")]
     pub threads_per_node: u64,
+    #[serde(rename = "rateLimit")]
+    pub rate_limit: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -3967,6 +4322,8 @@ pub struct MySqlStore {
     pub pool_min_connections: Option<u64>,
     #[serde(rename = "readReplicas")]
     pub read_replicas: List<MySqlSettings>,
+    #[serde(rename = "readReplicaStickiness")]
+    pub read_replica_stickiness: Duration,
     #[serde(rename = "host")]
     pub host: String,
     #[serde(rename = "port")]
@@ -4051,6 +4408,12 @@ pub struct NetworkListener {
     pub tls_timeout: Option<Duration>,
     #[serde(rename = "maxConnections")]
     pub max_connections: Option<u64>,
+    #[serde(rename = "tlsRequestClientCert")]
+    pub tls_request_client_cert: bool,
+    #[serde(rename = "tlsRequireClientCert")]
+    pub tls_require_client_cert: bool,
+    #[serde(rename = "tlsClientCaCertificates")]
+    pub tls_client_ca_certificates: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4097,6 +4460,10 @@ pub struct OidcDirectory {
     pub claim_groups: Option<String>,
     #[serde(rename = "memberTenantId")]
     pub member_tenant_id: Option<Id>,
+    #[serde(rename = "claimEnabledProtocols")]
+    pub claim_enabled_protocols: Option<String>,
+    #[serde(rename = "claimCategorizeMail")]
+    pub claim_categorize_mail: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4201,6 +4568,8 @@ pub struct PostgreSqlStore {
     pub pool_recycling_method: PostgreSqlRecyclingMethod,
     #[serde(rename = "readReplicas")]
     pub read_replicas: List<PostgreSqlSettings>,
+    #[serde(rename = "readReplicaStickiness")]
+    pub read_replica_stickiness: Duration,
     #[serde(rename = "host")]
     pub host: String,
     #[serde(rename = "port")]
@@ -4266,6 +4635,8 @@ pub struct QueueExpiryAttempts {
 pub struct QueueExpiryTtl {
     #[serde(rename = "expiresAt")]
     pub expires_at: UTCDateTime,
+    #[serde(rename = "expiresAttempts")]
+    pub expires_attempts: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4295,6 +4666,10 @@ pub struct QueuedMessage {
     pub priority: i64,
     #[serde(rename = "size")]
     pub size: u64,
+    #[serde(rename = "splitFrom")]
+    pub split_from: Option<Id>,
+    #[serde(rename = "metadata")]
+    pub metadata: VecMap<String, String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4574,6 +4949,10 @@ pub struct Search {
     pub index_telemetry: bool,
     #[serde(rename = "indexTracingFields")]
     pub index_tracing_fields: Map<SearchTracingField>,
+    #[serde(rename = "reindexConcurrency")]
+    pub reindex_concurrency: u64,
+    #[serde(rename = "reindexRateLimit")]
+    pub reindex_rate_limit: Option<Rate>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4710,6 +5089,26 @@ pub struct SenderAuth {
     pub dmarc_verify: Expression,
     #[serde(rename = "reverseIpVerify")]
     pub reverse_ip_verify: Expression,
+    #[serde(rename = "senderVerify")]
+    pub sender_verify: Expression,
+    #[serde(rename = "senderVerifySkipDomains")]
+    pub sender_verify_skip_domains: Map<String>,
+    #[serde(rename = "senderVerifyPassTtl")]
+    pub sender_verify_pass_ttl: Duration,
+    #[serde(rename = "senderVerifyFailTtl")]
+    pub sender_verify_fail_ttl: Duration,
+    #[serde(rename = "senderVerifyRate")]
+    pub sender_verify_rate: Option<Rate>,
+    #[serde(rename = "senderVerifyMaxConcurrent")]
+    pub sender_verify_max_concurrent: u64,
+    #[serde(rename = "senderVerifyTimeout")]
+    pub sender_verify_timeout: Duration,
+    #[serde(rename = "reverseIpRequire")]
+    pub reverse_ip_require: Expression,
+    #[serde(rename = "reverseIpRequireTempFail")]
+    pub reverse_ip_require_temp_fail: bool,
+    #[serde(rename = "reverseIpTimeout")]
+    pub reverse_ip_timeout: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4786,6 +5185,10 @@ pub struct SieveSystemInterpreter {
     pub max_redirects: u64,
     #[serde(rename = "maxVarSize")]
     pub max_var_size: u64,
+    #[serde(rename = "maxRedirectsPerDay")]
+    pub max_redirects_per_day: u64,
+    #[serde(rename = "maxRedirectDestinations")]
+    pub max_redirect_destinations: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4854,6 +5257,26 @@ pub struct SieveUserInterpreter {
     pub max_var_size: u64,
     #[serde(rename = "maxScripts")]
     pub max_scripts: Option<u64>,
+    #[serde(rename = "maxExpiryDuplicate")]
+    pub max_expiry_duplicate: Duration,
+    #[serde(rename = "redirectEnvelopeSender")]
+    pub redirect_envelope_sender: SieveRedirectSender,
+    #[serde(rename = "maxRedirectPasses")]
+    pub max_redirect_passes: u64,
+    #[serde(rename = "maxRedirectsPerDay")]
+    pub max_redirects_per_day: u64,
+    #[serde(rename = "maxRedirectDestinations")]
+    pub max_redirect_destinations: u64,
+    #[serde(rename = "logEnabled")]
+    pub log_enabled: bool,
+    #[serde(rename = "logMaxEntries")]
+    pub log_max_entries: u64,
+    #[serde(rename = "logRetention")]
+    pub log_retention: Duration,
+    #[serde(rename = "logHeader")]
+    pub log_header: bool,
+    #[serde(rename = "trustedRedirectHosts")]
+    pub trusted_redirect_hosts: Map<IpAddrOrMask>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -4869,6 +5292,25 @@ pub struct SieveUserScript {
     pub contents: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpamClamAv {
+    #[serde(rename = "enable")]
+    pub enable: bool,
+    #[serde(rename = "address")]
+    pub address: String,
+    #[serde(rename = "timeout")]
+    pub timeout: Duration,
+    #[serde(rename = "maxSize")]
+    pub max_size: u64,
+    #[serde(rename = "poolMaxConnections")]
+    pub pool_max_connections: u64,
+    #[serde(rename = "action")]
+    pub action: SpamVirusAction,
+    #[serde(rename = "failOpen")]
+    pub fail_open: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct SpamClassifier {
@@ -5292,6 +5734,38 @@ pub struct SpamSettings {
     pub trust_replies: bool,
     #[serde(rename = "spamFilterRulesUrl")]
     pub spam_filter_rules_url: Option<String>,
+    #[serde(rename = "trustedForwarders")]
+    pub trusted_forwarders: Map<IpAddrOrMask>,
+    #[serde(rename = "trustedForwarderDepth")]
+    pub trusted_forwarder_depth: u64,
+    #[serde(rename = "enableRspamdApi")]
+    pub enable_rspamd_api: bool,
+    #[serde(rename = "rspamdApiSecret")]
+    pub rspamd_api_secret: SecretKeyOptional,
+    #[serde(rename = "rspamdApiMaxConcurrent")]
+    pub rspamd_api_max_concurrent: u64,
+    #[serde(rename = "archiveMaxDepth")]
+    pub archive_max_depth: u64,
+    #[serde(rename = "archiveMaxEntries")]
+    pub archive_max_entries: u64,
+    #[serde(rename = "archiveMaxRatio")]
+    pub archive_max_ratio: u64,
+    #[serde(rename = "archiveMaxSize")]
+    pub archive_max_size: u64,
+    #[serde(rename = "archiveScanTimeout")]
+    pub archive_scan_timeout: Duration,
+    #[serde(rename = "reputationEnable")]
+    pub reputation_enable: bool,
+    #[serde(rename = "reputationWindow")]
+    pub reputation_window: Duration,
+    #[serde(rename = "reputationMinMessages")]
+    pub reputation_min_messages: u64,
+    #[serde(rename = "reputationGoodScore")]
+    pub reputation_good_score: Float,
+    #[serde(rename = "reputationBadScore")]
+    pub reputation_bad_score: Float,
+    #[serde(rename = "reputationSkipGreylist")]
+    pub reputation_skip_greylist: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -5386,6 +5860,10 @@ pub struct SqlDirectory {
     pub query_email_aliases: Option<String>,
     #[serde(rename = "memberTenantId")]
     pub member_tenant_id: Option<Id>,
+    #[serde(rename = "columnEnabledProtocols")]
+    pub column_enabled_protocols: Option<String>,
+    #[serde(rename = "columnCategorizeMail")]
+    pub column_categorize_mail: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -5444,6 +5922,10 @@ pub struct SystemSettings {
     pub services: VecMap<ServiceProtocol, Service>,
     #[serde(rename = "providerInfo")]
     pub provider_info: VecMap<ProviderInfo, String>,
+    #[serde(rename = "shutdownGracePeriod")]
+    pub shutdown_grace_period: Duration,
+    #[serde(rename = "strictReferences")]
+    pub strict_references: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -5467,6 +5949,15 @@ pub enum Task {
     AcmeRenewal(TaskDomainManagement),
     DkimManagement(TaskDomainManagement),
     DnsManagement(TaskDnsManagement),
+    ReEncryptAccount(TaskReEncryptAccount),
+    ReindexAccount(TaskReindexAccount),
+    ImportMessages(TaskImportMessages),
+    QuotaWarning(TaskQuotaWarning),
+    EmailSubmission(TaskEmailSubmission),
+    RestoreSnoozedEmail(TaskRestoreSnoozedEmail),
+    DaneRollover(TaskDomainManagement),
+    FollowUpReminder(TaskFollowUpReminder),
+    DeliveryCallback(TaskDeliveryCallback),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -5594,6 +6085,83 @@ pub struct TaskDomainManagement {
     pub status: TaskStatus,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskEmailSubmission {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "documentId")]
+    pub document_id: Id,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskRestoreSnoozedEmail {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "documentId")]
+    pub document_id: Id,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskFollowUpReminder {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "documentId")]
+    pub document_id: Id,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskDeliveryCallback {
+    #[serde(rename = "callbackId")]
+    pub callback_id: Id,
+    #[serde(rename = "body")]
+    pub body: String,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskImportMessages {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "format")]
+    pub format: MessageImportFormat,
+    #[serde(rename = "path")]
+    pub path: Option<String>,
+    #[serde(rename = "blobId")]
+    pub blob_id: Option<BlobId>,
+    #[serde(rename = "targetMailbox")]
+    pub target_mailbox: Option<String>,
+    #[serde(rename = "deduplicate")]
+    pub deduplicate: bool,
+    #[serde(rename = "messagesTotal")]
+    pub messages_total: u64,
+    #[serde(rename = "messagesProcessed")]
+    pub messages_processed: u64,
+    #[serde(rename = "messagesImported")]
+    pub messages_imported: u64,
+    #[serde(rename = "messagesSkipped")]
+    pub messages_skipped: u64,
+    #[serde(rename = "messagesFailed")]
+    pub messages_failed: u64,
+    #[serde(rename = "importErrors")]
+    pub import_errors: Map<String>,
+    #[serde(rename = "resumeAfter")]
+    pub resume_after: Option<String>,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TaskIndexDocument {
@@ -5640,6 +6208,61 @@ pub struct TaskMergeThreads {
     pub status: TaskStatus,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskQuotaWarning {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "threshold")]
+    pub threshold: u64,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskReEncryptAccount {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "lastDocumentId")]
+    pub last_document_id: Option<u64>,
+    #[serde(rename = "messagesTotal")]
+    pub messages_total: u64,
+    #[serde(rename = "messagesProcessed")]
+    pub messages_processed: u64,
+    #[serde(rename = "messagesReEncrypted")]
+    pub messages_re_encrypted: u64,
+    #[serde(rename = "messagesSkipped")]
+    pub messages_skipped: u64,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+    #[serde(rename = "concurrency")]
+    pub concurrency: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskReindexAccount {
+    #[serde(rename = "accountId")]
+    pub account_id: Id,
+    #[serde(rename = "mailboxId")]
+    pub mailbox_id: Option<Id>,
+    #[serde(rename = "receivedAfter")]
+    pub received_after: Option<UTCDateTime>,
+    #[serde(rename = "receivedBefore")]
+    pub received_before: Option<UTCDateTime>,
+    #[serde(rename = "lastDocumentId")]
+    pub last_document_id: Option<u64>,
+    #[serde(rename = "messagesTotal")]
+    pub messages_total: u64,
+    #[serde(rename = "messagesProcessed")]
+    pub messages_processed: u64,
+    #[serde(rename = "messagesIndexed")]
+    pub messages_indexed: u64,
+    #[serde(rename = "status")]
+    pub status: TaskStatus,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct TaskRestoreArchivedItem {
@@ -5782,6 +6405,14 @@ pub struct Tenant {
     pub permissions: Permissions,
     #[serde(rename = "quotas")]
     pub quotas: VecMap<TenantStorageQuota, u64>,
+    #[serde(rename = "dkimSignDomain")]
+    pub dkim_sign_domain: Option<String>,
+    #[serde(rename = "reportFromAddress")]
+    pub report_from_address: Option<String>,
+    #[serde(rename = "maxMessageSize")]
+    pub max_message_size: Option<u64>,
+    #[serde(rename = "spamThreshold")]
+    pub spam_threshold: Option<Float>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -6185,6 +6816,22 @@ pub struct UserAccount {
     pub time_zone: Option<TimeZone>,
     #[serde(rename = "encryptionAtRest")]
     pub encryption_at_rest: EncryptionAtRest,
+    #[serde(rename = "calendarInvitations")]
+    pub calendar_invitations: CalendarInvitationPolicy,
+    #[serde(rename = "maxSessions")]
+    pub max_sessions: Option<u64>,
+    #[serde(rename = "maxSessionsPerProtocol")]
+    pub max_sessions_per_protocol: VecMap<ServiceProtocol, u64>,
+    #[serde(rename = "credentialGeneration")]
+    pub credential_generation: u64,
+    #[serde(rename = "sieveLogging")]
+    pub sieve_logging: bool,
+    #[serde(rename = "maxMessageSize")]
+    pub max_message_size: Option<u64>,
+    #[serde(rename = "enabledProtocols")]
+    pub enabled_protocols: Map<ServiceProtocol>,
+    #[serde(rename = "categorizeMail")]
+    pub categorize_mail: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -6195,6 +6842,23 @@ pub enum UserRoles {
     Custom(CustomRoles),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnsubscribeEvent {
+    #[serde(rename = "sender")]
+    pub sender: String,
+    #[serde(rename = "recipient")]
+    pub recipient: String,
+    #[serde(rename = "campaignId")]
+    pub campaign_id: Option<String>,
+    #[serde(rename = "timestamp")]
+    pub timestamp: UTCDateTime,
+    #[serde(rename = "remoteIp")]
+    pub remote_ip: Option<IpAddr>,
+    #[serde(rename = "memberTenantId")]
+    pub member_tenant_id: Option<Id>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct WebDav {
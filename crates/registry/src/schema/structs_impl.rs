@@ -10,7 +10,7 @@ use crate::schema::prelude::*;
 
 impl ObjectImpl for Account {
     const FLAGS: u64 = OBJ_FILTER_TENANT | OBJ_SEQ_ID;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 7;
     const OBJECT: ObjectType = ObjectType::Account;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -201,7 +201,7 @@ impl RegistryJsonPropertyPatch for AccountPassword {
 
 impl ObjectImpl for AccountSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 2;
     const OBJECT: ObjectType = ObjectType::AccountSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -230,6 +230,8 @@ impl Pickle for AccountSettings {
         self.locale.pickle(out);
         self.time_zone.pickle(out);
         self.encryption_at_rest.pickle(out);
+        self.calendar_invitations.pickle(out);
+        self.sieve_logging.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -238,6 +240,12 @@ impl Pickle for AccountSettings {
         this.locale = Pickle::unpickle(stream)?;
         this.time_zone = Pickle::unpickle(stream)?;
         this.encryption_at_rest = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.calendar_invitations = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.sieve_logging = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -249,13 +257,15 @@ impl Default for AccountSettings {
             locale: Locale::EnUS,
             time_zone: Default::default(),
             encryption_at_rest: Default::default(),
+            calendar_invitations: CalendarInvitationPolicy::Default,
+            sieve_logging: false,
         }
     }
 }
 
 impl IntoValue for AccountSettings {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(6);
+        let mut map = jmap_tools::Map::with_capacity(8);
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::Locale, self.locale.into_value());
         map.insert_unchecked(Property::TimeZone, self.time_zone.into_value());
@@ -263,6 +273,11 @@ impl IntoValue for AccountSettings {
             Property::EncryptionAtRest,
             self.encryption_at_rest.into_value(),
         );
+        map.insert_unchecked(
+            Property::CalendarInvitations,
+            self.calendar_invitations.into_value(),
+        );
+        map.insert_unchecked(Property::SieveLogging, self.sieve_logging.into_value());
         JmapValue::Object(map)
     }
 }
@@ -278,6 +293,8 @@ impl RegistryJsonPropertyPatch for AccountSettings {
             Some(Property::Locale) => self.locale.patch(pointer, value),
             Some(Property::TimeZone) => self.time_zone.patch(pointer, value),
             Some(Property::EncryptionAtRest) => self.encryption_at_rest.patch(pointer, value),
+            Some(Property::CalendarInvitations) => self.calendar_invitations.patch(pointer, value),
+            Some(Property::SieveLogging) => self.sieve_logging.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -469,6 +486,8 @@ impl ObjectImpl for Action {
             Action::InvalidateNegativeCaches => true,
             Action::PauseMtaQueue => true,
             Action::ResumeMtaQueue => true,
+            Action::DrainServer => true,
+            Action::SignOutEverywhere => true,
         }
     }
 
@@ -519,6 +538,12 @@ impl Pickle for Action {
             Action::ResumeMtaQueue => {
                 10u16.pickle(out);
             }
+            Action::DrainServer => {
+                11u16.pickle(out);
+            }
+            Action::SignOutEverywhere => {
+                12u16.pickle(out);
+            }
         }
     }
 
@@ -535,6 +560,8 @@ impl Pickle for Action {
             8 => Some(Action::InvalidateNegativeCaches),
             9 => Some(Action::PauseMtaQueue),
             10 => Some(Action::ResumeMtaQueue),
+            11 => Some(Action::DrainServer),
+            12 => Some(Action::SignOutEverywhere),
             _ => None,
         }
     }
@@ -608,6 +635,16 @@ impl IntoValue for Action {
                 obj.insert_unchecked(Property::Type, JmapValue::Str("ResumeMtaQueue".into()));
                 JmapValue::Object(obj)
             }
+            Action::DrainServer => {
+                let mut obj = jmap_tools::Map::new();
+                obj.insert_unchecked(Property::Type, JmapValue::Str("DrainServer".into()));
+                JmapValue::Object(obj)
+            }
+            Action::SignOutEverywhere => {
+                let mut obj = jmap_tools::Map::new();
+                obj.insert_unchecked(Property::Type, JmapValue::Str("SignOutEverywhere".into()));
+                JmapValue::Object(obj)
+            }
         }
     }
 }
@@ -633,6 +670,8 @@ impl RegistryJsonPatch for Action {
                 ActionType::InvalidateNegativeCaches => *self = Action::InvalidateNegativeCaches,
                 ActionType::PauseMtaQueue => *self = Action::PauseMtaQueue,
                 ActionType::ResumeMtaQueue => *self = Action::ResumeMtaQueue,
+                ActionType::DrainServer => *self = Action::DrainServer,
+                ActionType::SignOutEverywhere => *self = Action::SignOutEverywhere,
             }
         }
         match self {
@@ -647,6 +686,8 @@ impl RegistryJsonPatch for Action {
             Action::InvalidateNegativeCaches => pointer.assert_eof(),
             Action::PauseMtaQueue => pointer.assert_eof(),
             Action::ResumeMtaQueue => pointer.assert_eof(),
+            Action::DrainServer => pointer.assert_eof(),
+            Action::SignOutEverywhere => pointer.assert_eof(),
         }
     }
 }
@@ -665,6 +706,8 @@ impl Action {
             Action::InvalidateNegativeCaches => ActionType::InvalidateNegativeCaches,
             Action::PauseMtaQueue => ActionType::PauseMtaQueue,
             Action::ResumeMtaQueue => ActionType::ResumeMtaQueue,
+            Action::DrainServer => ActionType::DrainServer,
+            Action::SignOutEverywhere => ActionType::SignOutEverywhere,
         }
     }
 }
@@ -918,7 +961,7 @@ impl RegistryJsonPropertyPatch for AiModel {
 
 impl ObjectImpl for Alert {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::Alert;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -3280,9 +3323,114 @@ impl RegistryJsonPropertyPatch for AsnResource {
     }
 }
 
+impl ObjectImpl for AuditEvent {
+    const FLAGS: u64 = 0;
+    const VERSION: u8 = 0;
+    const OBJECT: ObjectType = ObjectType::AuditEvent;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.actor_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::ActorId));
+        }
+        let value = &self.timestamp;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::Timestamp, value));
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, _: &mut IndexBuilder<'x>) {}
+}
+
+impl Pickle for AuditEvent {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.actor_id.pickle(out);
+        self.timestamp.pickle(out);
+        self.event.pickle(out);
+        self.protocol.pickle(out);
+        self.remote_ip.pickle(out);
+        self.object_ids.pickle(out);
+        self.details.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.actor_id = Pickle::unpickle(stream)?;
+        this.timestamp = Pickle::unpickle(stream)?;
+        this.event = Pickle::unpickle(stream)?;
+        this.protocol = Pickle::unpickle(stream)?;
+        this.remote_ip = Pickle::unpickle(stream)?;
+        this.object_ids = Pickle::unpickle(stream)?;
+        this.details = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for AuditEvent {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            actor_id: Default::default(),
+            timestamp: Default::default(),
+            event: Default::default(),
+            protocol: Default::default(),
+            remote_ip: Default::default(),
+            object_ids: Default::default(),
+            details: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for AuditEvent {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(10);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::ActorId, self.actor_id.into_value());
+        map.insert_unchecked(Property::Timestamp, self.timestamp.into_value());
+        map.insert_unchecked(Property::Event, self.event.into_value());
+        map.insert_unchecked(Property::Protocol, self.protocol.into_value());
+        map.insert_unchecked(Property::RemoteIp, self.remote_ip.into_value());
+        map.insert_unchecked(Property::ObjectIds, self.object_ids.into_value());
+        map.insert_unchecked(Property::Details, self.details.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for AuditEvent {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => self.account_id.patch(pointer, value),
+            Some(Property::ActorId) => self.actor_id.patch(pointer, value),
+            Some(Property::Timestamp) => self.timestamp.patch(pointer, value),
+            Some(Property::Event) => self.event.patch(pointer, value),
+            Some(Property::Protocol) => self.protocol.patch(pointer, value),
+            Some(Property::RemoteIp) => self.remote_ip.patch(pointer, value),
+            Some(Property::ObjectIds) => self.object_ids.patch(pointer, value),
+            Some(Property::Details) => self.details.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for Authentication {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 3;
     const OBJECT: ObjectType = ObjectType::Authentication;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -3343,11 +3491,23 @@ impl ObjectImpl for Authentication {
                 errors.push(ValidationError::min_value(Property::MaxApiKeys, 1));
             }
         }
+        if let Some(value) = &self.max_sessions {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxSessions, 1));
+            }
+        }
+        let value = &self.directory_chain;
+        for value in value.values() {
+            value.validate(errors);
+        }
         errors.len() == neb
     }
 
     fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
         i.foreign_key(ObjectType::Directory, self.directory_id, None);
+        for entry in self.directory_chain.values() {
+            i.foreign_key(ObjectType::Directory, entry.directory_id, None);
+        }
         for id in self.default_user_role_ids.iter() {
             i.foreign_key(ObjectType::Role, Some(*id), None);
         }
@@ -3377,6 +3537,13 @@ impl Pickle for Authentication {
         self.password_default_expiry.pickle(out);
         self.max_app_passwords.pickle(out);
         self.max_api_keys.pickle(out);
+        self.step_up_permissions.pickle(out);
+        self.step_up_max_age.pickle(out);
+        self.max_sessions.pickle(out);
+        self.max_sessions_per_protocol.pickle(out);
+        self.directory_chain.pickle(out);
+        self.directory_lookup_mode.pickle(out);
+        self.directory_cache_ttl.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -3393,6 +3560,27 @@ impl Pickle for Authentication {
         this.password_default_expiry = Pickle::unpickle(stream)?;
         this.max_app_passwords = Pickle::unpickle(stream)?;
         this.max_api_keys = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.step_up_permissions = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.step_up_max_age = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.max_sessions = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.max_sessions_per_protocol = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.directory_chain = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.directory_lookup_mode = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.directory_cache_ttl = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -3412,13 +3600,20 @@ impl Default for Authentication {
             password_default_expiry: Default::default(),
             max_app_passwords: Some(5u64),
             max_api_keys: Some(5u64),
+            step_up_permissions: Default::default(),
+            step_up_max_age: Duration::from_millis(300000),
+            max_sessions: None,
+            max_sessions_per_protocol: Default::default(),
+            directory_chain: Default::default(),
+            directory_lookup_mode: DirectoryLookupMode::FirstMatch,
+            directory_cache_ttl: Duration::from_millis(60000),
         }
     }
 }
 
 impl IntoValue for Authentication {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(14);
+        let mut map = jmap_tools::Map::with_capacity(21);
         map.insert_unchecked(Property::DirectoryId, self.directory_id.into_value());
         map.insert_unchecked(
             Property::DefaultUserRoleIds,
@@ -3461,6 +3656,25 @@ impl IntoValue for Authentication {
             self.max_app_passwords.into_value(),
         );
         map.insert_unchecked(Property::MaxApiKeys, self.max_api_keys.into_value());
+        map.insert_unchecked(
+            Property::StepUpPermissions,
+            self.step_up_permissions.into_value(),
+        );
+        map.insert_unchecked(Property::StepUpMaxAge, self.step_up_max_age.into_value());
+        map.insert_unchecked(Property::MaxSessions, self.max_sessions.into_value());
+        map.insert_unchecked(
+            Property::MaxSessionsPerProtocol,
+            self.max_sessions_per_protocol.into_value(),
+        );
+        map.insert_unchecked(Property::DirectoryChain, self.directory_chain.into_value());
+        map.insert_unchecked(
+            Property::DirectoryLookupMode,
+            self.directory_lookup_mode.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DirectoryCacheTtl,
+            self.directory_cache_ttl.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -3494,6 +3708,15 @@ impl RegistryJsonPropertyPatch for Authentication {
             }
             Some(Property::MaxAppPasswords) => self.max_app_passwords.patch(pointer, value),
             Some(Property::MaxApiKeys) => self.max_api_keys.patch(pointer, value),
+            Some(Property::StepUpPermissions) => self.step_up_permissions.patch(pointer, value),
+            Some(Property::StepUpMaxAge) => self.step_up_max_age.patch(pointer, value),
+            Some(Property::MaxSessions) => self.max_sessions.patch(pointer, value),
+            Some(Property::MaxSessionsPerProtocol) => {
+                self.max_sessions_per_protocol.patch(pointer, value)
+            }
+            Some(Property::DirectoryChain) => self.directory_chain.patch(pointer, value),
+            Some(Property::DirectoryLookupMode) => self.directory_lookup_mode.patch(pointer, value),
+            Some(Property::DirectoryCacheTtl) => self.directory_cache_ttl.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -3617,7 +3840,7 @@ impl RegistryJsonPropertyPatch for AzureStore {
 
 impl ObjectImpl for BlobStore {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::BlobStore;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -4048,7 +4271,7 @@ impl RegistryJsonPropertyPatch for BlockedIp {
 
 impl ObjectImpl for Bootstrap {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::Bootstrap;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -4194,7 +4417,7 @@ impl RegistryJsonPropertyPatch for Bootstrap {
 
 impl ObjectImpl for Cache {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::Cache;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -4305,6 +4528,13 @@ impl ObjectImpl for Cache {
         if *value < 2048 {
             errors.push(ValidationError::min_value(Property::DkimSignatures, 2048));
         }
+        let value = &self.directory_principals;
+        if *value < 2048 {
+            errors.push(ValidationError::min_value(
+                Property::DirectoryPrincipals,
+                2048,
+            ));
+        }
         errors.len() == neb
     }
 
@@ -4339,6 +4569,7 @@ impl Pickle for Cache {
         self.mailing_lists.pickle(out);
         self.dkim_signatures.pickle(out);
         self.negative_ttl.pickle(out);
+        self.directory_principals.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -4369,6 +4600,9 @@ impl Pickle for Cache {
         this.mailing_lists = Pickle::unpickle(stream)?;
         this.dkim_signatures = Pickle::unpickle(stream)?;
         this.negative_ttl = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.directory_principals = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -4402,13 +4636,14 @@ impl Default for Cache {
             mailing_lists: 2097152,
             dkim_signatures: 10485760,
             negative_ttl: Duration::from_millis(3600000),
+            directory_principals: 1048576,
         }
     }
 }
 
 impl IntoValue for Cache {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(28);
+        let mut map = jmap_tools::Map::with_capacity(29);
         map.insert_unchecked(Property::AccessTokens, self.access_tokens.into_value());
         map.insert_unchecked(Property::Contacts, self.contacts.into_value());
         map.insert_unchecked(Property::DnsIpv4, self.dns_ipv4.into_value());
@@ -4441,6 +4676,10 @@ impl IntoValue for Cache {
         map.insert_unchecked(Property::MailingLists, self.mailing_lists.into_value());
         map.insert_unchecked(Property::DkimSignatures, self.dkim_signatures.into_value());
         map.insert_unchecked(Property::NegativeTtl, self.negative_ttl.into_value());
+        map.insert_unchecked(
+            Property::DirectoryPrincipals,
+            self.directory_principals.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -4480,6 +4719,7 @@ impl RegistryJsonPropertyPatch for Cache {
             Some(Property::MailingLists) => self.mailing_lists.patch(pointer, value),
             Some(Property::DkimSignatures) => self.dkim_signatures.patch(pointer, value),
             Some(Property::NegativeTtl) => self.negative_ttl.patch(pointer, value),
+            Some(Property::DirectoryPrincipals) => self.directory_principals.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -4896,7 +5136,7 @@ impl RegistryJsonPropertyPatch for CalendarScheduling {
 
 impl ObjectImpl for Certificate {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::Certificate;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -4923,6 +5163,11 @@ impl ObjectImpl for Certificate {
         if value.is_empty() {
             errors.push(ValidationError::required(Property::Issuer));
         }
+        if let Some(value) = &self.staged_until {
+            if !value.is_valid() {
+                errors.push(ValidationError::invalid(Property::StagedUntil, value));
+            }
+        }
         errors.len() == neb
     }
 
@@ -4941,6 +5186,7 @@ impl Pickle for Certificate {
         self.not_valid_after.pickle(out);
         self.not_valid_before.pickle(out);
         self.issuer.pickle(out);
+        self.staged_until.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -4951,6 +5197,9 @@ impl Pickle for Certificate {
         this.not_valid_after = Pickle::unpickle(stream)?;
         this.not_valid_before = Pickle::unpickle(stream)?;
         this.issuer = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.staged_until = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -4964,13 +5213,14 @@ impl Default for Certificate {
             not_valid_after: Default::default(),
             not_valid_before: Default::default(),
             issuer: Default::default(),
+            staged_until: Default::default(),
         }
     }
 }
 
 impl IntoValue for Certificate {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(8);
+        let mut map = jmap_tools::Map::with_capacity(9);
         map.insert_unchecked(Property::Certificate, self.certificate.into_value());
         map.insert_unchecked(Property::PrivateKey, self.private_key.into_value());
         map.insert_unchecked(
@@ -4980,6 +5230,7 @@ impl IntoValue for Certificate {
         map.insert_unchecked(Property::NotValidAfter, self.not_valid_after.into_value());
         map.insert_unchecked(Property::NotValidBefore, self.not_valid_before.into_value());
         map.insert_unchecked(Property::Issuer, self.issuer.into_value());
+        map.insert_unchecked(Property::StagedUntil, self.staged_until.into_value());
         JmapValue::Object(map)
     }
 }
@@ -4997,6 +5248,7 @@ impl RegistryJsonPropertyPatch for Certificate {
             Some(Property::NotValidAfter) => pointer.assert_server_set(),
             Some(Property::NotValidBefore) => pointer.assert_server_set(),
             Some(Property::Issuer) => pointer.assert_server_set(),
+            Some(Property::StagedUntil) => self.staged_until.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -5114,6 +5366,16 @@ impl CertificateManagementProperties {
                 errors.push(ValidationError::required(Property::SubjectAlternativeNames));
             }
         }
+        let value = &self.dane_max_delay;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::DaneMaxDelay, value));
+        }
+        if *value < Duration::from_millis(3600000) {
+            errors.push(ValidationError::min_value(Property::DaneMaxDelay, 3600000));
+        }
+        if *value > Duration::from_millis(2592000000) {
+            errors.push(ValidationError::max_value(Property::DaneMaxDelay, 2592000000u64));
+        }
         errors.len() == neb
     }
 
@@ -5126,12 +5388,20 @@ impl Pickle for CertificateManagementProperties {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.acme_provider_id.pickle(out);
         self.subject_alternative_names.pickle(out);
+        self.dane_rollover.pickle(out);
+        self.dane_max_delay.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
         let mut this = Self::default();
         this.acme_provider_id = Pickle::unpickle(stream)?;
         this.subject_alternative_names = Pickle::unpickle(stream)?;
+        if stream.version() >= 9 {
+            this.dane_rollover = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 9 {
+            this.dane_max_delay = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -5141,18 +5411,22 @@ impl Default for CertificateManagementProperties {
         Self {
             acme_provider_id: Default::default(),
             subject_alternative_names: Default::default(),
+            dane_rollover: false,
+            dane_max_delay: Duration::from_millis(172800000),
         }
     }
 }
 
 impl IntoValue for CertificateManagementProperties {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(4);
+        let mut map = jmap_tools::Map::with_capacity(6);
         map.insert_unchecked(Property::AcmeProviderId, self.acme_provider_id.into_value());
         map.insert_unchecked(
             Property::SubjectAlternativeNames,
             self.subject_alternative_names.into_value(),
         );
+        map.insert_unchecked(Property::DaneRollover, self.dane_rollover.into_value());
+        map.insert_unchecked(Property::DaneMaxDelay, self.dane_max_delay.into_value());
         JmapValue::Object(map)
     }
 }
@@ -5168,6 +5442,8 @@ impl RegistryJsonPropertyPatch for CertificateManagementProperties {
             Some(Property::SubjectAlternativeNames) => self
                 .subject_alternative_names
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::DaneRollover) => self.dane_rollover.patch(pointer, value),
+            Some(Property::DaneMaxDelay) => self.dane_max_delay.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -6516,7 +6792,7 @@ impl RegistryJsonPropertyPatch for CustomRoles {
 
 impl ObjectImpl for DataRetention {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::DataRetention;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -6551,6 +6827,14 @@ impl Pickle for DataRetention {
         self.hold_traces_for.pickle(out);
         self.hold_metrics_for.pickle(out);
         self.metrics_collection_interval.pickle(out);
+        self.hold_audit_events_for.pickle(out);
+        self.quota_reconcile_interval.pickle(out);
+        self.quota_reconcile_sample_size.pickle(out);
+        self.max_changes_age.pickle(out);
+        self.max_changes_age_per_collection.pickle(out);
+        self.backup_directory.pickle(out);
+        self.backup_max_increments.pickle(out);
+        self.backup_include_blobs.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -6569,6 +6853,30 @@ impl Pickle for DataRetention {
         this.hold_traces_for = Pickle::unpickle(stream)?;
         this.hold_metrics_for = Pickle::unpickle(stream)?;
         this.metrics_collection_interval = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.hold_audit_events_for = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.quota_reconcile_interval = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.quota_reconcile_sample_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.max_changes_age = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.max_changes_age_per_collection = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.backup_directory = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.backup_max_increments = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.backup_include_blobs = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -6599,13 +6907,21 @@ impl Default for DataRetention {
             hold_traces_for: Some(Duration::from_millis(2592000000)),
             hold_metrics_for: Some(Duration::from_millis(7776000000)),
             metrics_collection_interval: Cron::Hourly(CronHourly { minute: 0u64 }),
+            hold_audit_events_for: Some(Duration::from_millis(15552000000)),
+            quota_reconcile_interval: Duration::from_millis(3600000),
+            quota_reconcile_sample_size: 100u64,
+            max_changes_age: None,
+            max_changes_age_per_collection: Default::default(),
+            backup_directory: Default::default(),
+            backup_max_increments: 6u64,
+            backup_include_blobs: true,
         }
     }
 }
 
 impl IntoValue for DataRetention {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(16);
+        let mut map = jmap_tools::Map::with_capacity(24);
         map.insert_unchecked(
             Property::ExpungeTrashAfter,
             self.expunge_trash_after.into_value(),
@@ -6656,6 +6972,26 @@ impl IntoValue for DataRetention {
             Property::MetricsCollectionInterval,
             self.metrics_collection_interval.into_value(),
         );
+        map.insert_unchecked(
+            Property::HoldAuditEventsFor,
+            self.hold_audit_events_for.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaReconcileInterval,
+            self.quota_reconcile_interval.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaReconcileSampleSize,
+            self.quota_reconcile_sample_size.into_value(),
+        );
+        map.insert_unchecked(Property::MaxChangesAge, self.max_changes_age.into_value());
+        map.insert_unchecked(
+            Property::MaxChangesAgePerCollection,
+            self.max_changes_age_per_collection.into_value(),
+        );
+        map.insert_unchecked(Property::BackupDirectory, self.backup_directory.into_value());
+        map.insert_unchecked(Property::BackupMaxIncrements, self.backup_max_increments.into_value());
+        map.insert_unchecked(Property::BackupIncludeBlobs, self.backup_include_blobs.into_value());
         JmapValue::Object(map)
     }
 }
@@ -6693,6 +7029,22 @@ impl RegistryJsonPropertyPatch for DataRetention {
             Some(Property::MetricsCollectionInterval) => {
                 self.metrics_collection_interval.patch(pointer, value)
             }
+            Some(Property::HoldAuditEventsFor) => self.hold_audit_events_for.patch(pointer, value),
+            Some(Property::QuotaReconcileInterval) => {
+                self.quota_reconcile_interval.patch(pointer, value)
+            }
+            Some(Property::QuotaReconcileSampleSize) => {
+                self.quota_reconcile_sample_size.patch(pointer, value)
+            }
+            Some(Property::MaxChangesAge) => self.max_changes_age.patch(pointer, value),
+            Some(Property::MaxChangesAgePerCollection) => {
+                self.max_changes_age_per_collection.patch(pointer, value)
+            }
+            Some(Property::BackupDirectory) => self
+                .backup_directory
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::BackupMaxIncrements) => self.backup_max_increments.patch(pointer, value),
+            Some(Property::BackupIncludeBlobs) => self.backup_include_blobs.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -6704,7 +7056,7 @@ impl RegistryJsonPropertyPatch for DataRetention {
 
 impl ObjectImpl for DataStore {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::DataStore;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -6843,6 +7195,124 @@ impl DataStore {
     }
 }
 
+impl ObjectImpl for DeliveryCallback {
+    const FLAGS: u64 = OBJ_FILTER_ACCOUNT;
+    const VERSION: u8 = 0;
+    const OBJECT: ObjectType = ObjectType::DeliveryCallback;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        if let Some(value) = &self.description {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::Description));
+            }
+        }
+        let value = &self.url;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Url));
+        }
+        let value = &self.signature_key;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+        i.search(Property::AccountId, &self.account_id);
+    }
+}
+
+impl Pickle for DeliveryCallback {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.description.pickle(out);
+        self.url.pickle(out);
+        self.signature_key.pickle(out);
+        self.allow_invalid_certs.pickle(out);
+        self.timeout.pickle(out);
+        self.throttle.pickle(out);
+        self.discard_after.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.description = Pickle::unpickle(stream)?;
+        this.url = Pickle::unpickle(stream)?;
+        this.signature_key = Pickle::unpickle(stream)?;
+        this.allow_invalid_certs = Pickle::unpickle(stream)?;
+        this.timeout = Pickle::unpickle(stream)?;
+        this.throttle = Pickle::unpickle(stream)?;
+        this.discard_after = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for DeliveryCallback {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            description: Default::default(),
+            url: Default::default(),
+            signature_key: Default::default(),
+            allow_invalid_certs: false,
+            timeout: Duration::from_millis(30000),
+            throttle: Duration::from_millis(1000),
+            discard_after: Duration::from_millis(86400000),
+        }
+    }
+}
+
+impl IntoValue for DeliveryCallback {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(10);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::Description, self.description.into_value());
+        map.insert_unchecked(Property::Url, self.url.into_value());
+        map.insert_unchecked(Property::SignatureKey, self.signature_key.into_value());
+        map.insert_unchecked(
+            Property::AllowInvalidCerts,
+            self.allow_invalid_certs.into_value(),
+        );
+        map.insert_unchecked(Property::Timeout, self.timeout.into_value());
+        map.insert_unchecked(Property::Throttle, self.throttle.into_value());
+        map.insert_unchecked(Property::DiscardAfter, self.discard_after.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for DeliveryCallback {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => self
+                .account_id
+                .patch(pointer.assert_read_only()?.assert_can_set_account()?, value),
+            Some(Property::Description) => self.description.patch(pointer, value),
+            Some(Property::Url) => self
+                .url
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::SignatureKey) => self.signature_key.patch(pointer, value),
+            Some(Property::AllowInvalidCerts) => self.allow_invalid_certs.patch(pointer, value),
+            Some(Property::Timeout) => self.timeout.patch(pointer, value),
+            Some(Property::Throttle) => self.throttle.patch(pointer, value),
+            Some(Property::DiscardAfter) => self.discard_after.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl DeliveryError {
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
         let neb = errors.len();
@@ -6969,7 +7439,7 @@ impl RegistryJsonPropertyPatch for DeliveryError {
 
 impl ObjectImpl for Directory {
     const FLAGS: u64 = OBJ_FILTER_TENANT;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::Directory;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -7208,6 +7678,63 @@ impl DirectoryBootstrap {
     }
 }
 
+impl DirectoryChainEntry {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        if let Some(value) = &self.directory_id {
+            if !value.is_valid() {
+                errors.push(ValidationError::required(Property::DirectoryId));
+            }
+        }
+        errors.len() == neb
+    }
+}
+
+impl Pickle for DirectoryChainEntry {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.directory_id.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.directory_id = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for DirectoryChainEntry {
+    fn default() -> Self {
+        Self {
+            directory_id: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for DirectoryChainEntry {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(1);
+        map.insert_unchecked(Property::DirectoryId, self.directory_id.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for DirectoryChainEntry {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::DirectoryId) => self.directory_id.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl Dkim1Signature {
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
         let neb = errors.len();
@@ -7697,7 +8224,7 @@ impl RegistryJsonPropertyPatch for DkimManagementProperties {
 
 impl ObjectImpl for DkimReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::DkimReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -8172,7 +8699,7 @@ impl RegistryJsonPropertyPatch for DmarcExtension {
 
 impl ObjectImpl for DmarcExternalReport {
     const FLAGS: u64 = OBJ_FILTER_TENANT;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 3;
     const OBJECT: ObjectType = ObjectType::DmarcExternalReport;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -8297,7 +8824,7 @@ impl RegistryJsonPropertyPatch for DmarcExternalReport {
 
 impl ObjectImpl for DmarcInternalReport {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 3;
     const OBJECT: ObjectType = ObjectType::DmarcInternalReport;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -8336,6 +8863,7 @@ impl Pickle for DmarcInternalReport {
         self.domain.pickle(out);
         self.created_at.pickle(out);
         self.deliver_at.pickle(out);
+        self.rua_max_size.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -8346,6 +8874,9 @@ impl Pickle for DmarcInternalReport {
         this.domain = Pickle::unpickle(stream)?;
         this.created_at = Pickle::unpickle(stream)?;
         this.deliver_at = Pickle::unpickle(stream)?;
+        if stream.version() >= 2 {
+            this.rua_max_size = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -8359,13 +8890,14 @@ impl Default for DmarcInternalReport {
             domain: Default::default(),
             created_at: Default::default(),
             deliver_at: Default::default(),
+            rua_max_size: 0,
         }
     }
 }
 
 impl IntoValue for DmarcInternalReport {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(8);
+        let mut map = jmap_tools::Map::with_capacity(9);
         map.insert_unchecked(Property::Rua, self.rua.into_value());
         map.insert_unchecked(
             Property::PolicyIdentifier,
@@ -8375,6 +8907,7 @@ impl IntoValue for DmarcInternalReport {
         map.insert_unchecked(Property::Domain, self.domain.into_value());
         map.insert_unchecked(Property::CreatedAt, self.created_at.into_value());
         map.insert_unchecked(Property::DeliverAt, self.deliver_at.into_value());
+        map.insert_unchecked(Property::RuaMaxSize, self.rua_max_size.into_value());
         JmapValue::Object(map)
     }
 }
@@ -8396,6 +8929,7 @@ impl RegistryJsonPropertyPatch for DmarcInternalReport {
                 .patch(pointer.with_validators(&[StringValidator::Domain]), value),
             Some(Property::CreatedAt) => self.created_at.patch(pointer, value),
             Some(Property::DeliverAt) => self.deliver_at.patch(pointer, value),
+            Some(Property::RuaMaxSize) => self.rua_max_size.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -8759,6 +9293,8 @@ impl Pickle for DmarcReportRecord {
         self.dkim_results.pickle(out);
         self.spf_results.pickle(out);
         self.extensions.pickle(out);
+        self.first_seen.pickle(out);
+        self.last_seen.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -8775,6 +9311,12 @@ impl Pickle for DmarcReportRecord {
         this.dkim_results = Pickle::unpickle(stream)?;
         this.spf_results = Pickle::unpickle(stream)?;
         this.extensions = Pickle::unpickle(stream)?;
+        if stream.version() >= 3 {
+            this.first_seen = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.last_seen = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -8794,13 +9336,15 @@ impl Default for DmarcReportRecord {
             dkim_results: Default::default(),
             spf_results: Default::default(),
             extensions: Default::default(),
+            first_seen: Default::default(),
+            last_seen: Default::default(),
         }
     }
 }
 
 impl IntoValue for DmarcReportRecord {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(14);
+        let mut map = jmap_tools::Map::with_capacity(16);
         map.insert_unchecked(Property::SourceIp, self.source_ip.into_value());
         map.insert_unchecked(Property::Count, self.count.into_value());
         map.insert_unchecked(
@@ -8819,6 +9363,8 @@ impl IntoValue for DmarcReportRecord {
         map.insert_unchecked(Property::DkimResults, self.dkim_results.into_value());
         map.insert_unchecked(Property::SpfResults, self.spf_results.into_value());
         map.insert_unchecked(Property::Extensions, self.extensions.into_value());
+        map.insert_unchecked(Property::FirstSeen, self.first_seen.into_value());
+        map.insert_unchecked(Property::LastSeen, self.last_seen.into_value());
         JmapValue::Object(map)
     }
 }
@@ -8846,6 +9392,8 @@ impl RegistryJsonPropertyPatch for DmarcReportRecord {
             Some(Property::DkimResults) => self.dkim_results.patch(pointer, value),
             Some(Property::SpfResults) => self.spf_results.patch(pointer, value),
             Some(Property::Extensions) => self.extensions.patch(pointer, value),
+            Some(Property::FirstSeen) => self.first_seen.patch(pointer, value),
+            Some(Property::LastSeen) => self.last_seen.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -8857,7 +9405,7 @@ impl RegistryJsonPropertyPatch for DmarcReportRecord {
 
 impl ObjectImpl for DmarcReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::DmarcReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -8888,6 +9436,8 @@ impl ObjectImpl for DmarcReportSettings {
         value.validate(errors);
         let value = &self.failure_subject;
         value.validate(errors);
+        let value = &self.aggregate_max_compressed_size;
+        value.validate(errors);
         errors.len() == neb
     }
 
@@ -9064,6 +9614,19 @@ impl DmarcReportSettings {
         }
     }
 
+    pub fn ctx_aggregate_max_compressed_size(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.aggregate_max_compressed_size,
+            default: Some(Expression {
+                else_: "10485760".to_string(),
+                ..Default::default()
+            }),
+            property: Property::AggregateMaxCompressedSize,
+            allowed_variables: MTA_RCPT_DOMAIN_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
     pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
         vec![
             self.ctx_aggregate_contact_info(),
@@ -9079,6 +9642,7 @@ impl DmarcReportSettings {
             self.ctx_failure_send_frequency(),
             self.ctx_failure_dkim_sign_domain(),
             self.ctx_failure_subject(),
+            self.ctx_aggregate_max_compressed_size(),
         ]
     }
 }
@@ -9098,6 +9662,7 @@ impl Pickle for DmarcReportSettings {
         self.failure_send_frequency.pickle(out);
         self.failure_dkim_sign_domain.pickle(out);
         self.failure_subject.pickle(out);
+        self.aggregate_max_compressed_size.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -9115,6 +9680,9 @@ impl Pickle for DmarcReportSettings {
         this.failure_send_frequency = Pickle::unpickle(stream)?;
         this.failure_dkim_sign_domain = Pickle::unpickle(stream)?;
         this.failure_subject = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.aggregate_max_compressed_size = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -9174,13 +9742,17 @@ impl Default for DmarcReportSettings {
                 else_: "'DMARC Authentication Failure Report'".to_string(),
                 ..Default::default()
             },
+            aggregate_max_compressed_size: Expression {
+                else_: "10485760".to_string(),
+                ..Default::default()
+            },
         }
     }
 }
 
 impl IntoValue for DmarcReportSettings {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(15);
+        let mut map = jmap_tools::Map::with_capacity(16);
         map.insert_unchecked(
             Property::AggregateContactInfo,
             self.aggregate_contact_info.into_value(),
@@ -9230,6 +9802,10 @@ impl IntoValue for DmarcReportSettings {
             self.failure_dkim_sign_domain.into_value(),
         );
         map.insert_unchecked(Property::FailureSubject, self.failure_subject.into_value());
+        map.insert_unchecked(
+            Property::AggregateMaxCompressedSize,
+            self.aggregate_max_compressed_size.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -9268,6 +9844,9 @@ impl RegistryJsonPropertyPatch for DmarcReportSettings {
                 self.failure_dkim_sign_domain.patch(pointer, value)
             }
             Some(Property::FailureSubject) => self.failure_subject.patch(pointer, value),
+            Some(Property::AggregateMaxCompressedSize) => {
+                self.aggregate_max_compressed_size.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -19638,7 +20217,7 @@ impl RegistryJsonPropertyPatch for DnsServerYandexCloud {
 
 impl ObjectImpl for Domain {
     const FLAGS: u64 = OBJ_FILTER_TENANT | OBJ_SEQ_ID;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::Domain;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -19695,6 +20274,61 @@ impl ObjectImpl for Domain {
                 errors.push(ValidationError::required(Property::ReportAddressUri));
             }
         }
+        for value in self.quota_warning_thresholds.iter() {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(
+                    Property::QuotaWarningThresholds,
+                    1,
+                ));
+            }
+            if *value > 100 {
+                errors.push(ValidationError::max_value(
+                    Property::QuotaWarningThresholds,
+                    100,
+                ));
+            }
+        }
+        if let Some(value) = &self.dkim_sign_domain {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::DkimSignDomain));
+            }
+        }
+        if let Some(value) = &self.report_from_address {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::ReportFromAddress));
+            }
+        }
+        if let Some(value) = &self.max_message_size {
+            if *value < 1024 {
+                errors.push(ValidationError::min_value(Property::MaxMessageSize, 1024));
+            }
+        }
+        if let Some(value) = &self.spam_threshold {
+            if *value > Float::new(100.0) {
+                errors.push(ValidationError::max_value(Property::SpamThreshold, 100));
+            }
+            if *value < Float::new(-100.0) {
+                errors.push(ValidationError::min_value(Property::SpamThreshold, -100));
+            }
+        }
+        let value = &self.directory_chain;
+        for value in value.values() {
+            value.validate(errors);
+        }
+        let value = &self.batv_key;
+        value.validate(errors);
+        let value = &self.batv_previous_key;
+        value.validate(errors);
+        let value = &self.batv_validity;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::BatvValidity, value));
+        }
+        if *value < Duration::from_millis(86400000) {
+            errors.push(ValidationError::min_value(Property::BatvValidity, 86400000));
+        }
+        if *value > Duration::from_millis(31536000000) {
+            errors.push(ValidationError::max_value(Property::BatvValidity, 31536000000));
+        }
         errors.len() == neb
     }
 
@@ -19714,6 +20348,9 @@ impl ObjectImpl for Domain {
             i.search(Property::MemberTenantId, value);
         }
         i.foreign_key(ObjectType::Directory, self.directory_id, None);
+        for entry in self.directory_chain.values() {
+            i.foreign_key(ObjectType::Directory, entry.directory_id, None);
+        }
     }
 }
 
@@ -19734,6 +20371,25 @@ impl Pickle for Domain {
         self.sub_addressing.pickle(out);
         self.allow_relaying.pickle(out);
         self.report_address_uri.pickle(out);
+        self.audit_log.pickle(out);
+        self.audit_message_reads.pickle(out);
+        self.provision_folders.pickle(out);
+        self.provision_subscribe.pickle(out);
+        self.provision_sieve_script.pickle(out);
+        self.provision_signature.pickle(out);
+        self.quota_warning_thresholds.pickle(out);
+        self.dkim_sign_domain.pickle(out);
+        self.report_from_address.pickle(out);
+        self.max_message_size.pickle(out);
+        self.spam_threshold.pickle(out);
+        self.mta_sts_mode.pickle(out);
+        self.mta_sts_mx_hosts.pickle(out);
+        self.mta_sts_max_age.pickle(out);
+        self.directory_chain.pickle(out);
+        self.add_delivered_to_header.pickle(out);
+        self.batv_key.pickle(out);
+        self.batv_previous_key.pickle(out);
+        self.batv_validity.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -19753,6 +20409,63 @@ impl Pickle for Domain {
         this.sub_addressing = Pickle::unpickle(stream)?;
         this.allow_relaying = Pickle::unpickle(stream)?;
         this.report_address_uri = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.audit_log = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.audit_message_reads = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.provision_folders = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.provision_subscribe = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.provision_sieve_script = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.provision_signature = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.quota_warning_thresholds = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.dkim_sign_domain = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.report_from_address = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.max_message_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.spam_threshold = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.mta_sts_mode = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.mta_sts_mx_hosts = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.mta_sts_max_age = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 6 {
+            this.directory_chain = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 7 {
+            this.add_delivered_to_header = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 8 {
+            this.batv_key = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 8 {
+            this.batv_previous_key = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 8 {
+            this.batv_validity = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -19775,13 +20488,32 @@ impl Default for Domain {
             sub_addressing: Default::default(),
             allow_relaying: false,
             report_address_uri: Some("mailto:postmaster".to_string()),
+            audit_log: false,
+            audit_message_reads: false,
+            provision_folders: Default::default(),
+            provision_subscribe: true,
+            provision_sieve_script: Default::default(),
+            provision_signature: Default::default(),
+            quota_warning_thresholds: Default::default(),
+            dkim_sign_domain: None,
+            report_from_address: None,
+            max_message_size: None,
+            spam_threshold: None,
+            mta_sts_mode: None,
+            mta_sts_mx_hosts: Map::default(),
+            mta_sts_max_age: None,
+            directory_chain: Default::default(),
+            add_delivered_to_header: true,
+            batv_key: Default::default(),
+            batv_previous_key: Default::default(),
+            batv_validity: Duration::from_millis(604800000),
         }
     }
 }
 
 impl IntoValue for Domain {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(17);
+        let mut map = jmap_tools::Map::with_capacity(36);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Aliases, self.aliases.into_value());
         map.insert_unchecked(Property::IsEnabled, self.is_enabled.into_value());
@@ -19806,6 +20538,49 @@ impl IntoValue for Domain {
             Property::ReportAddressUri,
             self.report_address_uri.into_value(),
         );
+        map.insert_unchecked(Property::AuditLog, self.audit_log.into_value());
+        map.insert_unchecked(
+            Property::AuditMessageReads,
+            self.audit_message_reads.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionFolders,
+            self.provision_folders.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSubscribe,
+            self.provision_subscribe.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSieveScript,
+            self.provision_sieve_script.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSignature,
+            self.provision_signature.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaWarningThresholds,
+            self.quota_warning_thresholds.into_value(),
+        );
+        map.insert_unchecked(Property::DkimSignDomain, self.dkim_sign_domain.into_value());
+        map.insert_unchecked(
+            Property::ReportFromAddress,
+            self.report_from_address.into_value(),
+        );
+        map.insert_unchecked(Property::MaxMessageSize, self.max_message_size.into_value());
+        map.insert_unchecked(Property::SpamThreshold, self.spam_threshold.into_value());
+        map.insert_unchecked(Property::MtaStsMode, self.mta_sts_mode.into_value());
+        map.insert_unchecked(Property::MtaStsMxHosts, self.mta_sts_mx_hosts.into_value());
+        map.insert_unchecked(Property::MtaStsMaxAge, self.mta_sts_max_age.into_value());
+        map.insert_unchecked(Property::DirectoryChain, self.directory_chain.into_value());
+        map.insert_unchecked(
+            Property::AddDeliveredToHeader,
+            self.add_delivered_to_header.into_value(),
+        );
+        map.insert_unchecked(Property::BatvKey, self.batv_key.into_value());
+        map.insert_unchecked(Property::BatvPreviousKey, self.batv_previous_key.into_value());
+        map.insert_unchecked(Property::BatvValidity, self.batv_validity.into_value());
         JmapValue::Object(map)
     }
 }
@@ -19845,6 +20620,37 @@ impl RegistryJsonPropertyPatch for Domain {
             Some(Property::ReportAddressUri) => self
                 .report_address_uri
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::AuditLog) => self.audit_log.patch(pointer, value),
+            Some(Property::AuditMessageReads) => self.audit_message_reads.patch(pointer, value),
+            Some(Property::ProvisionFolders) => self
+                .provision_folders
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::ProvisionSubscribe) => self.provision_subscribe.patch(pointer, value),
+            Some(Property::ProvisionSieveScript) => {
+                self.provision_sieve_script.patch(pointer, value)
+            }
+            Some(Property::ProvisionSignature) => self.provision_signature.patch(pointer, value),
+            Some(Property::QuotaWarningThresholds) => {
+                self.quota_warning_thresholds.patch(pointer, value)
+            }
+            Some(Property::DkimSignDomain) => self
+                .dkim_sign_domain
+                .patch(pointer.with_validators(&[StringValidator::Domain]), value),
+            Some(Property::ReportFromAddress) => self
+                .report_from_address
+                .patch(pointer.with_validators(&[StringValidator::Email]), value),
+            Some(Property::MaxMessageSize) => self.max_message_size.patch(pointer, value),
+            Some(Property::SpamThreshold) => self.spam_threshold.patch(pointer, value),
+            Some(Property::MtaStsMode) => self.mta_sts_mode.patch(pointer, value),
+            Some(Property::MtaStsMxHosts) => self.mta_sts_mx_hosts.patch(pointer, value),
+            Some(Property::MtaStsMaxAge) => self.mta_sts_max_age.patch(pointer, value),
+            Some(Property::DirectoryChain) => self.directory_chain.patch(pointer, value),
+            Some(Property::AddDeliveredToHeader) => {
+                self.add_delivered_to_header.patch(pointer, value)
+            }
+            Some(Property::BatvKey) => self.batv_key.patch(pointer, value),
+            Some(Property::BatvPreviousKey) => self.batv_previous_key.patch(pointer, value),
+            Some(Property::BatvValidity) => self.batv_validity.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -19856,7 +20662,7 @@ impl RegistryJsonPropertyPatch for Domain {
 
 impl ObjectImpl for DsnReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::DsnReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -19867,6 +20673,17 @@ impl ObjectImpl for DsnReportSettings {
         value.validate(errors);
         let value = &self.dkim_sign_domain;
         value.validate(errors);
+        if let Some(value) = &self.spam_threshold {
+            if *value > Float::new(100.0) {
+                errors.push(ValidationError::max_value(Property::SpamThreshold, 100));
+            }
+            if *value < Float::new(-100.0) {
+                errors.push(ValidationError::min_value(Property::SpamThreshold, -100));
+            }
+        }
+        if let Some(value) = &self.rate_limit {
+            value.validate(errors);
+        }
         errors.len() == neb
     }
 
@@ -19927,6 +20744,10 @@ impl Pickle for DsnReportSettings {
         self.from_address.pickle(out);
         self.from_name.pickle(out);
         self.dkim_sign_domain.pickle(out);
+        self.spam_threshold.pickle(out);
+        self.suppress_no_mx.pickle(out);
+        self.suppression_list.pickle(out);
+        self.rate_limit.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -19934,6 +20755,18 @@ impl Pickle for DsnReportSettings {
         this.from_address = Pickle::unpickle(stream)?;
         this.from_name = Pickle::unpickle(stream)?;
         this.dkim_sign_domain = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.spam_threshold = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.suppress_no_mx = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.suppression_list = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.rate_limit = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -19953,16 +20786,24 @@ impl Default for DsnReportSettings {
                 else_: "system('domain')".to_string(),
                 ..Default::default()
             },
+            spam_threshold: Default::default(),
+            suppress_no_mx: false,
+            suppression_list: Default::default(),
+            rate_limit: Default::default(),
         }
     }
 }
 
 impl IntoValue for DsnReportSettings {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(5);
+        let mut map = jmap_tools::Map::with_capacity(9);
         map.insert_unchecked(Property::FromAddress, self.from_address.into_value());
         map.insert_unchecked(Property::FromName, self.from_name.into_value());
         map.insert_unchecked(Property::DkimSignDomain, self.dkim_sign_domain.into_value());
+        map.insert_unchecked(Property::SpamThreshold, self.spam_threshold.into_value());
+        map.insert_unchecked(Property::SuppressNoMx, self.suppress_no_mx.into_value());
+        map.insert_unchecked(Property::SuppressionList, self.suppression_list.into_value());
+        map.insert_unchecked(Property::RateLimit, self.rate_limit.into_value());
         JmapValue::Object(map)
     }
 }
@@ -19977,6 +20818,10 @@ impl RegistryJsonPropertyPatch for DsnReportSettings {
             Some(Property::FromAddress) => self.from_address.patch(pointer, value),
             Some(Property::FromName) => self.from_name.patch(pointer, value),
             Some(Property::DkimSignDomain) => self.dkim_sign_domain.patch(pointer, value),
+            Some(Property::SpamThreshold) => self.spam_threshold.patch(pointer, value),
+            Some(Property::SuppressNoMx) => self.suppress_no_mx.patch(pointer, value),
+            Some(Property::SuppressionList) => self.suppression_list.patch(pointer, value),
+            Some(Property::RateLimit) => self.rate_limit.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -20105,7 +20950,7 @@ impl RegistryJsonPropertyPatch for ElasticSearchStore {
 
 impl ObjectImpl for Email {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 9;
     const OBJECT: ObjectType = ObjectType::Email;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -20163,6 +21008,46 @@ impl ObjectImpl for Email {
                 errors.push(ValidationError::min_value(Property::MaxPublicKeys, 1));
             }
         }
+        if let Some(value) = &self.max_delivery_callbacks {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(
+                    Property::MaxDeliveryCallbacks,
+                    1,
+                ));
+            }
+        }
+        let value = &self.re_encrypt_concurrency;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::ReEncryptConcurrency,
+                1,
+            ));
+        }
+        if *value > 64 {
+            errors.push(ValidationError::max_value(
+                Property::ReEncryptConcurrency,
+                64,
+            ));
+        }
+        for value in self.quota_warning_thresholds.iter() {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(
+                    Property::QuotaWarningThresholds,
+                    1,
+                ));
+            }
+            if *value > 100 {
+                errors.push(ValidationError::max_value(
+                    Property::QuotaWarningThresholds,
+                    100,
+                ));
+            }
+        }
+        if let Some(value) = &self.max_follow_ups {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxFollowUps, 1));
+            }
+        }
         errors.len() == neb
     }
 
@@ -20185,6 +21070,23 @@ impl Pickle for Email {
         self.max_mailboxes.pickle(out);
         self.max_masked_addresses.pickle(out);
         self.max_public_keys.pickle(out);
+        self.max_delivery_callbacks.pickle(out);
+        self.re_encrypt_concurrency.pickle(out);
+        self.import_root.pickle(out);
+        self.provision_folders.pickle(out);
+        self.provision_subscribe.pickle(out);
+        self.provision_sieve_script.pickle(out);
+        self.provision_signature.pickle(out);
+        self.quota_warning_thresholds.pickle(out);
+        self.quota_warning_notify.pickle(out);
+        self.quota_warning_interval.pickle(out);
+        self.duplicate_delivery.pickle(out);
+        self.duplicate_delivery_window.pickle(out);
+        self.max_delayed_send.pickle(out);
+        self.shared_blob_quota.pickle(out);
+        self.max_follow_ups.pickle(out);
+        self.recall_window.pickle(out);
+        self.recall_notice.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -20203,6 +21105,55 @@ impl Pickle for Email {
         this.max_mailboxes = Pickle::unpickle(stream)?;
         this.max_masked_addresses = Pickle::unpickle(stream)?;
         this.max_public_keys = Pickle::unpickle(stream)?;
+        this.max_delivery_callbacks = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.re_encrypt_concurrency = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.import_root = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.provision_folders = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.provision_subscribe = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.provision_sieve_script = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.provision_signature = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.quota_warning_thresholds = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.quota_warning_notify = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.quota_warning_interval = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.duplicate_delivery = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.duplicate_delivery_window = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 6 {
+            this.max_delayed_send = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 7 {
+            this.shared_blob_quota = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 8 {
+            this.max_follow_ups = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 9 {
+            this.recall_window = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 9 {
+            this.recall_notice = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -20224,13 +21175,30 @@ impl Default for Email {
             max_mailboxes: Some(250u64),
             max_masked_addresses: Some(5u64),
             max_public_keys: Some(5u64),
+            max_delivery_callbacks: Some(10u64),
+            re_encrypt_concurrency: 4,
+            import_root: Default::default(),
+            provision_folders: Default::default(),
+            provision_subscribe: true,
+            provision_sieve_script: Default::default(),
+            provision_signature: Default::default(),
+            quota_warning_thresholds: Default::default(),
+            quota_warning_notify: false,
+            quota_warning_interval: Duration::from_millis(86400000),
+            duplicate_delivery: DuplicateDelivery::Disabled,
+            duplicate_delivery_window: Duration::from_millis(3600000),
+            max_delayed_send: Duration::from_millis(2592000000),
+            shared_blob_quota: SharedBlobQuota::ChargeEachReference,
+            max_follow_ups: Some(100u64),
+            recall_window: Duration::from_millis(1800000),
+            recall_notice: false,
         }
     }
 }
 
 impl IntoValue for Email {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(16);
+        let mut map = jmap_tools::Map::with_capacity(33);
         map.insert_unchecked(
             Property::MaxAttachmentSize,
             self.max_attachment_size.into_value(),
@@ -20263,6 +21231,56 @@ impl IntoValue for Email {
             self.max_masked_addresses.into_value(),
         );
         map.insert_unchecked(Property::MaxPublicKeys, self.max_public_keys.into_value());
+        map.insert_unchecked(
+            Property::MaxDeliveryCallbacks,
+            self.max_delivery_callbacks.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReEncryptConcurrency,
+            self.re_encrypt_concurrency.into_value(),
+        );
+        map.insert_unchecked(Property::ImportRoot, self.import_root.into_value());
+        map.insert_unchecked(
+            Property::ProvisionFolders,
+            self.provision_folders.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSubscribe,
+            self.provision_subscribe.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSieveScript,
+            self.provision_sieve_script.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ProvisionSignature,
+            self.provision_signature.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaWarningThresholds,
+            self.quota_warning_thresholds.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaWarningNotify,
+            self.quota_warning_notify.into_value(),
+        );
+        map.insert_unchecked(
+            Property::QuotaWarningInterval,
+            self.quota_warning_interval.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DuplicateDelivery,
+            self.duplicate_delivery.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DuplicateDeliveryWindow,
+            self.duplicate_delivery_window.into_value(),
+        );
+        map.insert_unchecked(Property::MaxDelayedSend, self.max_delayed_send.into_value());
+        map.insert_unchecked(Property::SharedBlobQuota, self.shared_blob_quota.into_value());
+        map.insert_unchecked(Property::MaxFollowUps, self.max_follow_ups.into_value());
+        map.insert_unchecked(Property::RecallWindow, self.recall_window.into_value());
+        map.insert_unchecked(Property::RecallNotice, self.recall_notice.into_value());
         JmapValue::Object(map)
     }
 }
@@ -20292,6 +21310,39 @@ impl RegistryJsonPropertyPatch for Email {
             Some(Property::MaxMailboxes) => self.max_mailboxes.patch(pointer, value),
             Some(Property::MaxMaskedAddresses) => self.max_masked_addresses.patch(pointer, value),
             Some(Property::MaxPublicKeys) => self.max_public_keys.patch(pointer, value),
+            Some(Property::MaxDeliveryCallbacks) => {
+                self.max_delivery_callbacks.patch(pointer, value)
+            }
+            Some(Property::ReEncryptConcurrency) => {
+                self.re_encrypt_concurrency.patch(pointer, value)
+            }
+            Some(Property::ImportRoot) => self
+                .import_root
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::ProvisionFolders) => self
+                .provision_folders
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::ProvisionSubscribe) => self.provision_subscribe.patch(pointer, value),
+            Some(Property::ProvisionSieveScript) => {
+                self.provision_sieve_script.patch(pointer, value)
+            }
+            Some(Property::ProvisionSignature) => self.provision_signature.patch(pointer, value),
+            Some(Property::QuotaWarningThresholds) => {
+                self.quota_warning_thresholds.patch(pointer, value)
+            }
+            Some(Property::QuotaWarningNotify) => self.quota_warning_notify.patch(pointer, value),
+            Some(Property::QuotaWarningInterval) => {
+                self.quota_warning_interval.patch(pointer, value)
+            }
+            Some(Property::DuplicateDelivery) => self.duplicate_delivery.patch(pointer, value),
+            Some(Property::DuplicateDeliveryWindow) => {
+                self.duplicate_delivery_window.patch(pointer, value)
+            }
+            Some(Property::MaxDelayedSend) => self.max_delayed_send.patch(pointer, value),
+            Some(Property::SharedBlobQuota) => self.shared_blob_quota.patch(pointer, value),
+            Some(Property::MaxFollowUps) => self.max_follow_ups.patch(pointer, value),
+            Some(Property::RecallWindow) => self.recall_window.patch(pointer, value),
+            Some(Property::RecallNotice) => self.recall_notice.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -20867,12 +21918,16 @@ impl Pickle for Expression {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.match_.pickle(out);
         self.else_.pickle(out);
+        self.strict.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
         let mut this = Self::default();
         this.match_ = Pickle::unpickle(stream)?;
         this.else_ = Pickle::unpickle(stream)?;
+        if stream.version() >= 10 {
+            this.strict = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -20882,6 +21937,7 @@ impl Default for Expression {
         Self {
             match_: Default::default(),
             else_: Default::default(),
+            strict: true,
         }
     }
 }
@@ -20891,6 +21947,7 @@ impl IntoValue for Expression {
         let mut map = jmap_tools::Map::with_capacity(4);
         map.insert_unchecked(Property::Match, self.match_.into_value());
         map.insert_unchecked(Property::Else, self.else_.into_value());
+        map.insert_unchecked(Property::Strict, self.strict.into_value());
         JmapValue::Object(map)
     }
 }
@@ -20904,6 +21961,7 @@ impl RegistryJsonPropertyPatch for Expression {
         match pointer.next_property() {
             Some(Property::Match) => self.match_.patch(pointer, value),
             Some(Property::Else) => self.else_.patch(pointer, value),
+            Some(Property::Strict) => self.strict.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -21496,7 +22554,7 @@ impl RegistryJsonPropertyPatch for GroupAccount {
 
 impl ObjectImpl for Http {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::Http;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -21520,6 +22578,9 @@ impl ObjectImpl for Http {
                 errors.push(ValidationError::required(Property::RedirectRoot));
             }
         }
+        if self.queue_preview_size < 1 {
+            errors.push(ValidationError::min_value(Property::QueuePreviewSize, 1));
+        }
         errors.len() == neb
     }
 
@@ -21555,6 +22616,11 @@ impl Pickle for Http {
         self.response_headers.pickle(out);
         self.use_x_forwarded.pickle(out);
         self.redirect_root.pickle(out);
+        self.queue_preview_size.pickle(out);
+        self.readiness_check_timeout.pickle(out);
+        self.readiness_timeout.pickle(out);
+        self.readiness_optional.pickle(out);
+        self.readiness_cache_ttl.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -21569,6 +22635,21 @@ impl Pickle for Http {
         if stream.version() >= 1 {
             this.redirect_root = Pickle::unpickle(stream)?;
         }
+        if stream.version() >= 2 {
+            this.queue_preview_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.readiness_check_timeout = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.readiness_timeout = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.readiness_optional = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.readiness_cache_ttl = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -21593,13 +22674,18 @@ impl Default for Http {
             response_headers: Default::default(),
             use_x_forwarded: false,
             redirect_root: Some("/account".to_string()),
+            queue_preview_size: 1024,
+            readiness_check_timeout: Duration::from_millis(1000),
+            readiness_timeout: Duration::from_millis(3000),
+            readiness_optional: Map::new(vec![HealthCheckDependency::SearchStore]),
+            readiness_cache_ttl: Duration::from_millis(1000),
         }
     }
 }
 
 impl IntoValue for Http {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(10);
+        let mut map = jmap_tools::Map::with_capacity(15);
         map.insert_unchecked(
             Property::RateLimitAuthenticated,
             self.rate_limit_authenticated.into_value(),
@@ -21623,6 +22709,23 @@ impl IntoValue for Http {
         );
         map.insert_unchecked(Property::UseXForwarded, self.use_x_forwarded.into_value());
         map.insert_unchecked(Property::RedirectRoot, self.redirect_root.into_value());
+        map.insert_unchecked(
+            Property::QueuePreviewSize,
+            self.queue_preview_size.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReadinessCheckTimeout,
+            self.readiness_check_timeout.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReadinessTimeout,
+            self.readiness_timeout.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReadinessOptional,
+            self.readiness_optional.into_value(),
+        );
+        map.insert_unchecked(Property::ReadinessCacheTtl, self.readiness_cache_ttl.into_value());
         JmapValue::Object(map)
     }
 }
@@ -21648,6 +22751,13 @@ impl RegistryJsonPropertyPatch for Http {
             Some(Property::RedirectRoot) => self
                 .redirect_root
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::QueuePreviewSize) => self.queue_preview_size.patch(pointer, value),
+            Some(Property::ReadinessCheckTimeout) => {
+                self.readiness_check_timeout.patch(pointer, value)
+            }
+            Some(Property::ReadinessTimeout) => self.readiness_timeout.patch(pointer, value),
+            Some(Property::ReadinessOptional) => self.readiness_optional.patch(pointer, value),
+            Some(Property::ReadinessCacheTtl) => self.readiness_cache_ttl.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -22054,7 +23164,7 @@ impl RegistryJsonPropertyPatch for HttpForm {
 
 impl ObjectImpl for HttpLookup {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::HttpLookup;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -22217,6 +23327,7 @@ impl Pickle for HttpLookupCsv {
         self.index_value.pickle(out);
         self.separator.pickle(out);
         self.skip_first.pickle(out);
+        self.all_columns.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -22225,6 +23336,9 @@ impl Pickle for HttpLookupCsv {
         this.index_value = Pickle::unpickle(stream)?;
         this.separator = Pickle::unpickle(stream)?;
         this.skip_first = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.all_columns = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -22236,17 +23350,19 @@ impl Default for HttpLookupCsv {
             index_value: Default::default(),
             separator: ",".to_string(),
             skip_first: false,
+            all_columns: false,
         }
     }
 }
 
 impl IntoValue for HttpLookupCsv {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(6);
+        let mut map = jmap_tools::Map::with_capacity(7);
         map.insert_unchecked(Property::IndexKey, self.index_key.into_value());
         map.insert_unchecked(Property::IndexValue, self.index_value.into_value());
         map.insert_unchecked(Property::Separator, self.separator.into_value());
         map.insert_unchecked(Property::SkipFirst, self.skip_first.into_value());
+        map.insert_unchecked(Property::AllColumns, self.all_columns.into_value());
         JmapValue::Object(map)
     }
 }
@@ -22264,6 +23380,7 @@ impl RegistryJsonPropertyPatch for HttpLookupCsv {
                 .separator
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
             Some(Property::SkipFirst) => self.skip_first.patch(pointer, value),
+            Some(Property::AllColumns) => self.all_columns.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -22277,6 +23394,7 @@ impl HttpLookupFormat {
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
         match self {
             HttpLookupFormat::Csv(inner) => inner.validate(errors),
+            HttpLookupFormat::Json => true,
             HttpLookupFormat::List => true,
         }
     }
@@ -22295,6 +23413,9 @@ impl Pickle for HttpLookupFormat {
                 0u16.pickle(out);
                 inner.pickle(out);
             }
+            HttpLookupFormat::Json => {
+                2u16.pickle(out);
+            }
             HttpLookupFormat::List => {
                 1u16.pickle(out);
             }
@@ -22305,6 +23426,7 @@ impl Pickle for HttpLookupFormat {
         match u16::unpickle(stream)? {
             0 => Pickle::unpickle(stream).map(HttpLookupFormat::Csv),
             1 => Some(HttpLookupFormat::List),
+            2 => Some(HttpLookupFormat::Json),
             _ => None,
         }
     }
@@ -22320,6 +23442,11 @@ impl IntoValue for HttpLookupFormat {
                     .insert_unchecked(Property::Type, JmapValue::Str("Csv".into()));
                 obj
             }
+            HttpLookupFormat::Json => {
+                let mut obj = jmap_tools::Map::new();
+                obj.insert_unchecked(Property::Type, JmapValue::Str("Json".into()));
+                JmapValue::Object(obj)
+            }
             HttpLookupFormat::List => {
                 let mut obj = jmap_tools::Map::new();
                 obj.insert_unchecked(Property::Type, JmapValue::Str("List".into()));
@@ -22338,11 +23465,13 @@ impl RegistryJsonPatch for HttpLookupFormat {
         if !pointer.has_next() {
             match object_type(&pointer, &value)? {
                 HttpLookupFormatType::Csv => *self = HttpLookupFormat::Csv(Default::default()),
+                HttpLookupFormatType::Json => *self = HttpLookupFormat::Json,
                 HttpLookupFormatType::List => *self = HttpLookupFormat::List,
             }
         }
         match self {
             HttpLookupFormat::Csv(inner) => inner.patch(pointer, value),
+            HttpLookupFormat::Json => pointer.assert_eof(),
             HttpLookupFormat::List => pointer.assert_eof(),
         }
     }
@@ -22352,6 +23481,7 @@ impl HttpLookupFormat {
     pub fn object_type(&self) -> HttpLookupFormatType {
         match self {
             HttpLookupFormat::Csv(_) => HttpLookupFormatType::Csv,
+            HttpLookupFormat::Json => HttpLookupFormatType::Json,
             HttpLookupFormat::List => HttpLookupFormatType::List,
         }
     }
@@ -22425,7 +23555,7 @@ impl RegistryJsonPropertyPatch for HurricaneCredential {
 
 impl ObjectImpl for Imap {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 2;
     const OBJECT: ObjectType = ObjectType::Imap;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -22458,6 +23588,9 @@ impl Pickle for Imap {
         self.timeout_anonymous.pickle(out);
         self.timeout_authenticated.pickle(out);
         self.timeout_idle.pickle(out);
+        self.search_cache_size.pickle(out);
+        self.search_cache_ttl.pickle(out);
+        self.idle_notify_delay.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -22470,6 +23603,15 @@ impl Pickle for Imap {
         this.timeout_anonymous = Pickle::unpickle(stream)?;
         this.timeout_authenticated = Pickle::unpickle(stream)?;
         this.timeout_idle = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.search_cache_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.search_cache_ttl = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.idle_notify_delay = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -22488,13 +23630,16 @@ impl Default for Imap {
             timeout_anonymous: Duration::from_millis(60000),
             timeout_authenticated: Duration::from_millis(1800000),
             timeout_idle: Duration::from_millis(1800000),
+            search_cache_size: 16u64,
+            search_cache_ttl: Duration::from_millis(300000),
+            idle_notify_delay: Duration::from_millis(1000),
         }
     }
 }
 
 impl IntoValue for Imap {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(10);
+        let mut map = jmap_tools::Map::with_capacity(13);
         map.insert_unchecked(
             Property::AllowPlainTextAuth,
             self.allow_plain_text_auth.into_value(),
@@ -22515,6 +23660,15 @@ impl IntoValue for Imap {
             self.timeout_authenticated.into_value(),
         );
         map.insert_unchecked(Property::TimeoutIdle, self.timeout_idle.into_value());
+        map.insert_unchecked(
+            Property::SearchCacheSize,
+            self.search_cache_size.into_value(),
+        );
+        map.insert_unchecked(Property::SearchCacheTtl, self.search_cache_ttl.into_value());
+        map.insert_unchecked(
+            Property::IdleNotifyDelay,
+            self.idle_notify_delay.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -22536,6 +23690,9 @@ impl RegistryJsonPropertyPatch for Imap {
                 self.timeout_authenticated.patch(pointer, value)
             }
             Some(Property::TimeoutIdle) => self.timeout_idle.patch(pointer, value),
+            Some(Property::SearchCacheSize) => self.search_cache_size.patch(pointer, value),
+            Some(Property::SearchCacheTtl) => self.search_cache_ttl.patch(pointer, value),
+            Some(Property::IdleNotifyDelay) => self.idle_notify_delay.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -22798,7 +23955,7 @@ impl InMemoryStoreBase {
 
 impl ObjectImpl for Jmap {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 1;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::Jmap;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -22891,6 +24048,19 @@ impl ObjectImpl for Jmap {
                 errors.push(ValidationError::required(Property::WebPushContact));
             }
         }
+        let value = &self.websocket_max_concurrent;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::WebsocketMaxConcurrent,
+                1,
+            ));
+        }
+        if *value > 64 {
+            errors.push(ValidationError::max_value(
+                Property::WebsocketMaxConcurrent,
+                64,
+            ));
+        }
         errors.len() == neb
     }
 
@@ -22929,6 +24099,10 @@ impl Pickle for Jmap {
         self.max_subscriptions.pickle(out);
         self.web_push_key.pickle(out);
         self.web_push_contact.pickle(out);
+        self.websocket_max_concurrent.pickle(out);
+        self.slow_method_threshold.pickle(out);
+        self.method_profiling.pickle(out);
+        self.slow_method_threshold_per_method.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -22967,6 +24141,18 @@ impl Pickle for Jmap {
         if stream.version() >= 1 {
             this.web_push_contact = Pickle::unpickle(stream)?;
         }
+        if stream.version() >= 2 {
+            this.websocket_max_concurrent = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.slow_method_threshold = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.method_profiling = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.slow_method_threshold_per_method = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -23004,13 +24190,17 @@ impl Default for Jmap {
             max_subscriptions: Some(15u64),
             web_push_key: Default::default(),
             web_push_contact: Default::default(),
+            websocket_max_concurrent: 4u64,
+            slow_method_threshold: Some(Duration::from_millis(1000)),
+            method_profiling: false,
+            slow_method_threshold_per_method: Default::default(),
         }
     }
 }
 
 impl IntoValue for Jmap {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(32);
+        let mut map = jmap_tools::Map::with_capacity(36);
         map.insert_unchecked(
             Property::ParseLimitEvent,
             self.parse_limit_event.into_value(),
@@ -23095,6 +24285,22 @@ impl IntoValue for Jmap {
         );
         map.insert_unchecked(Property::WebPushKey, self.web_push_key.into_value());
         map.insert_unchecked(Property::WebPushContact, self.web_push_contact.into_value());
+        map.insert_unchecked(
+            Property::WebsocketMaxConcurrent,
+            self.websocket_max_concurrent.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SlowMethodThreshold,
+            self.slow_method_threshold.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MethodProfiling,
+            self.method_profiling.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SlowMethodThresholdPerMethod,
+            self.slow_method_threshold_per_method.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -23142,6 +24348,14 @@ impl RegistryJsonPropertyPatch for Jmap {
             Some(Property::WebPushContact) => self
                 .web_push_contact
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::WebsocketMaxConcurrent) => {
+                self.websocket_max_concurrent.patch(pointer, value)
+            }
+            Some(Property::SlowMethodThreshold) => self.slow_method_threshold.patch(pointer, value),
+            Some(Property::MethodProfiling) => self.method_profiling.patch(pointer, value),
+            Some(Property::SlowMethodThresholdPerMethod) => {
+                self.slow_method_threshold_per_method.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -23572,6 +24786,8 @@ impl Pickle for LdapDirectory {
         self.pool_timeout_recycle.pickle(out);
         self.pool_timeout_wait.pickle(out);
         self.member_tenant_id.pickle(out);
+        self.attr_enabled_protocols.pickle(out);
+        self.attr_categorize_mail.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -23601,6 +24817,12 @@ impl Pickle for LdapDirectory {
         this.pool_timeout_recycle = Pickle::unpickle(stream)?;
         this.pool_timeout_wait = Pickle::unpickle(stream)?;
         this.member_tenant_id = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.attr_enabled_protocols = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.attr_categorize_mail = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -23633,13 +24855,15 @@ impl Default for LdapDirectory {
             pool_timeout_recycle: Duration::from_millis(30000),
             pool_timeout_wait: Duration::from_millis(30000),
             member_tenant_id: Default::default(),
+            attr_enabled_protocols: Default::default(),
+            attr_categorize_mail: Default::default(),
         }
     }
 }
 
 impl IntoValue for LdapDirectory {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(27);
+        let mut map = jmap_tools::Map::with_capacity(29);
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::Url, self.url.into_value());
         map.insert_unchecked(Property::Timeout, self.timeout.into_value());
@@ -23689,6 +24913,8 @@ impl IntoValue for LdapDirectory {
             self.pool_timeout_wait.into_value(),
         );
         map.insert_unchecked(Property::MemberTenantId, self.member_tenant_id.into_value());
+        map.insert_unchecked(Property::AttrEnabledProtocols, self.attr_enabled_protocols.into_value());
+        map.insert_unchecked(Property::AttrCategorizeMail, self.attr_categorize_mail.into_value());
         JmapValue::Object(map)
     }
 }
@@ -23757,6 +24983,8 @@ impl RegistryJsonPropertyPatch for LdapDirectory {
             Some(Property::MemberTenantId) => self
                 .member_tenant_id
                 .patch(pointer.assert_can_set_tenant()?, value),
+            Some(Property::AttrEnabledProtocols) => self.attr_enabled_protocols.patch(pointer, value),
+            Some(Property::AttrCategorizeMail) => self.attr_categorize_mail.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -24089,6 +25317,153 @@ impl RegistryJsonPropertyPatch for MailExchanger {
     }
 }
 
+impl ObjectImpl for MailCategory {
+    const FLAGS: u64 = 0;
+    const VERSION: u8 = 0;
+    const OBJECT: ObjectType = ObjectType::MailCategory;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.name;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Name));
+        }
+        if let Some(value) = &self.description {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::Description));
+            }
+        }
+        if let Some(value) = &self.mailbox_name {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::MailboxName));
+            }
+        }
+        let value = &self.match_tags;
+        for value in value.iter() {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::MatchTags));
+            }
+        }
+        let value = &self.match_keywords;
+        for value in value.iter() {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::MatchKeywords));
+            }
+        }
+        if let Some(value) = &self.min_sender_messages {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MinSenderMessages, 1));
+            }
+        }
+        let value = &self.min_matches;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::MinMatches, 1));
+        }
+        if *value > 100 {
+            errors.push(ValidationError::max_value(Property::MinMatches, 100));
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.unique(Property::Name, &self.name);
+    }
+}
+
+impl Pickle for MailCategory {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.name.pickle(out);
+        self.description.pickle(out);
+        self.enable.pickle(out);
+        self.mailbox_name.pickle(out);
+        self.match_tags.pickle(out);
+        self.match_keywords.pickle(out);
+        self.min_sender_messages.pickle(out);
+        self.min_matches.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.name = Pickle::unpickle(stream)?;
+        this.description = Pickle::unpickle(stream)?;
+        this.enable = Pickle::unpickle(stream)?;
+        this.mailbox_name = Pickle::unpickle(stream)?;
+        this.match_tags = Pickle::unpickle(stream)?;
+        this.match_keywords = Pickle::unpickle(stream)?;
+        this.min_sender_messages = Pickle::unpickle(stream)?;
+        this.min_matches = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for MailCategory {
+    fn default() -> Self {
+        Self {
+            name: Default::default(),
+            description: Default::default(),
+            enable: true,
+            mailbox_name: Default::default(),
+            match_tags: Default::default(),
+            match_keywords: Default::default(),
+            min_sender_messages: Default::default(),
+            min_matches: 1u64,
+        }
+    }
+}
+
+impl IntoValue for MailCategory {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(10);
+        map.insert_unchecked(Property::Name, self.name.into_value());
+        map.insert_unchecked(Property::Description, self.description.into_value());
+        map.insert_unchecked(Property::Enable, self.enable.into_value());
+        map.insert_unchecked(Property::MailboxName, self.mailbox_name.into_value());
+        map.insert_unchecked(Property::MatchTags, self.match_tags.into_value());
+        map.insert_unchecked(Property::MatchKeywords, self.match_keywords.into_value());
+        map.insert_unchecked(
+            Property::MinSenderMessages,
+            self.min_sender_messages.into_value(),
+        );
+        map.insert_unchecked(Property::MinMatches, self.min_matches.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for MailCategory {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::Name) => self.name.patch(
+                pointer.with_validators(&[StringValidator::RemoveSpaces, StringValidator::Lowercase]),
+                value,
+            ),
+            Some(Property::Description) => self.description.patch(pointer, value),
+            Some(Property::Enable) => self.enable.patch(pointer, value),
+            Some(Property::MailboxName) => self
+                .mailbox_name
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::MatchTags) => self.match_tags.patch(
+                pointer.with_validators(&[StringValidator::RemoveSpaces, StringValidator::Uppercase]),
+                value,
+            ),
+            Some(Property::MatchKeywords) => self.match_keywords.patch(
+                pointer.with_validators(&[StringValidator::Trim, StringValidator::Lowercase]),
+                value,
+            ),
+            Some(Property::MinSenderMessages) => self.min_sender_messages.patch(pointer, value),
+            Some(Property::MinMatches) => self.min_matches.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for MailingList {
     const FLAGS: u64 = OBJ_FILTER_TENANT | OBJ_SEQ_ID;
     const VERSION: u8 = 0;
@@ -24657,6 +26032,127 @@ impl RegistryJsonPropertyPatch for MemoryLookupKeyValue {
     }
 }
 
+impl ObjectImpl for MessageTemplate {
+    const FLAGS: u64 = OBJ_FILTER_TENANT;
+    const VERSION: u8 = 1;
+    const OBJECT: ObjectType = ObjectType::MessageTemplate;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.subject;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Subject));
+        }
+        let value = &self.text_body;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::TextBody));
+        }
+        if let Some(value) = &self.html_body {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::HtmlBody));
+            }
+        }
+        if let Some(value) = &self.member_tenant_id {
+            if !value.is_valid() {
+                errors.push(ValidationError::required(Property::MemberTenantId));
+            }
+        }
+        if let Some(value) = &self.domain_id {
+            if !value.is_valid() {
+                errors.push(ValidationError::required(Property::DomainId));
+            }
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Domain, self.domain_id, None);
+        i.foreign_key(ObjectType::Tenant, self.member_tenant_id, None);
+        if let Some(value) = &self.member_tenant_id {
+            i.search(Property::MemberTenantId, value);
+        }
+    }
+}
+
+impl Pickle for MessageTemplate {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.template_id.pickle(out);
+        self.locale.pickle(out);
+        self.subject.pickle(out);
+        self.text_body.pickle(out);
+        self.html_body.pickle(out);
+        self.member_tenant_id.pickle(out);
+        self.domain_id.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.template_id = Pickle::unpickle(stream)?;
+        this.locale = Pickle::unpickle(stream)?;
+        this.subject = Pickle::unpickle(stream)?;
+        this.text_body = Pickle::unpickle(stream)?;
+        this.html_body = Pickle::unpickle(stream)?;
+        this.member_tenant_id = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.domain_id = Pickle::unpickle(stream)?;
+        }
+        Some(this)
+    }
+}
+
+impl Default for MessageTemplate {
+    fn default() -> Self {
+        Self {
+            template_id: Default::default(),
+            locale: Default::default(),
+            subject: Default::default(),
+            text_body: Default::default(),
+            html_body: Default::default(),
+            member_tenant_id: Default::default(),
+            domain_id: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for MessageTemplate {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(9);
+        map.insert_unchecked(Property::TemplateId, self.template_id.into_value());
+        map.insert_unchecked(Property::Locale, self.locale.into_value());
+        map.insert_unchecked(Property::Subject, self.subject.into_value());
+        map.insert_unchecked(Property::TextBody, self.text_body.into_value());
+        map.insert_unchecked(Property::HtmlBody, self.html_body.into_value());
+        map.insert_unchecked(Property::MemberTenantId, self.member_tenant_id.into_value());
+        map.insert_unchecked(Property::DomainId, self.domain_id.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for MessageTemplate {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::TemplateId) => self.template_id.patch(pointer, value),
+            Some(Property::Locale) => self.locale.patch(pointer, value),
+            Some(Property::Subject) => self.subject.patch(pointer, value),
+            Some(Property::TextBody) => self.text_body.patch(pointer, value),
+            Some(Property::HtmlBody) => self.html_body.patch(pointer, value),
+            Some(Property::MemberTenantId) => self
+                .member_tenant_id
+                .patch(pointer.assert_can_set_tenant()?, value),
+            Some(Property::DomainId) => self.domain_id.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for Metric {
     const FLAGS: u64 = 0;
     const VERSION: u8 = 0;
@@ -24891,7 +26387,7 @@ impl RegistryJsonPropertyPatch for MetricSum {
 
 impl ObjectImpl for Metrics {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::Metrics;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -24900,6 +26396,10 @@ impl ObjectImpl for Metrics {
         value.validate(errors);
         let value = &self.prometheus;
         value.validate(errors);
+        let value = &self.queue_top_domains;
+        if *value > 1000 {
+            errors.push(ValidationError::max_value(Property::QueueTopDomains, 1000));
+        }
         errors.len() == neb
     }
 
@@ -24912,6 +26412,7 @@ impl Pickle for Metrics {
         self.prometheus.pickle(out);
         self.metrics.pickle(out);
         self.metrics_policy.pickle(out);
+        self.queue_top_domains.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -24920,6 +26421,9 @@ impl Pickle for Metrics {
         this.prometheus = Pickle::unpickle(stream)?;
         this.metrics = Pickle::unpickle(stream)?;
         this.metrics_policy = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.queue_top_domains = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -24931,17 +26435,22 @@ impl Default for Metrics {
             prometheus: Default::default(),
             metrics: Default::default(),
             metrics_policy: EventPolicy::Exclude,
+            queue_top_domains: 20u64,
         }
     }
 }
 
 impl IntoValue for Metrics {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(6);
+        let mut map = jmap_tools::Map::with_capacity(7);
         map.insert_unchecked(Property::OpenTelemetry, self.open_telemetry.into_value());
         map.insert_unchecked(Property::Prometheus, self.prometheus.into_value());
         map.insert_unchecked(Property::Metrics, self.metrics.into_value());
         map.insert_unchecked(Property::MetricsPolicy, self.metrics_policy.into_value());
+        map.insert_unchecked(
+            Property::QueueTopDomains,
+            self.queue_top_domains.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -24957,6 +26466,7 @@ impl RegistryJsonPropertyPatch for Metrics {
             Some(Property::Prometheus) => self.prometheus.patch(pointer, value),
             Some(Property::Metrics) => self.metrics.patch(pointer, value),
             Some(Property::MetricsPolicy) => self.metrics_policy.patch(pointer, value),
+            Some(Property::QueueTopDomains) => self.queue_top_domains.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -25377,7 +26887,7 @@ impl RegistryJsonPropertyPatch for MetricsPrometheusProperties {
 
 impl ObjectImpl for MetricsStore {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::MetricsStore;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -25526,6 +27036,27 @@ impl MtaConnectionIpHost {
         if !value.is_valid() {
             errors.push(ValidationError::invalid(Property::SourceIp, value));
         }
+        let value = &self.weight;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::Weight, 1));
+        }
+        if let Some(value) = &self.warmup_start {
+            if !value.is_valid() {
+                errors.push(ValidationError::invalid(Property::WarmupStart, value));
+            }
+        }
+        let value = &self.warmup_days;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::WarmupDays, 1));
+        }
+        let value = &self.warmup_initial_limit;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::WarmupInitialLimit, 1));
+        }
+        let value = &self.warmup_target_limit;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::WarmupTargetLimit, 1));
+        }
         errors.len() == neb
     }
 }
@@ -25534,12 +27065,32 @@ impl Pickle for MtaConnectionIpHost {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.ehlo_hostname.pickle(out);
         self.source_ip.pickle(out);
+        self.weight.pickle(out);
+        self.warmup_start.pickle(out);
+        self.warmup_days.pickle(out);
+        self.warmup_initial_limit.pickle(out);
+        self.warmup_target_limit.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
         let mut this = Self::default();
         this.ehlo_hostname = Pickle::unpickle(stream)?;
         this.source_ip = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.weight = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.warmup_start = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.warmup_days = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.warmup_initial_limit = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.warmup_target_limit = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -25549,15 +27100,31 @@ impl Default for MtaConnectionIpHost {
         Self {
             ehlo_hostname: Default::default(),
             source_ip: Default::default(),
+            weight: 1u64,
+            warmup_start: Default::default(),
+            warmup_days: 30u64,
+            warmup_initial_limit: 50u64,
+            warmup_target_limit: 10000u64,
         }
     }
 }
 
 impl IntoValue for MtaConnectionIpHost {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(4);
+        let mut map = jmap_tools::Map::with_capacity(9);
         map.insert_unchecked(Property::EhloHostname, self.ehlo_hostname.into_value());
         map.insert_unchecked(Property::SourceIp, self.source_ip.into_value());
+        map.insert_unchecked(Property::Weight, self.weight.into_value());
+        map.insert_unchecked(Property::WarmupStart, self.warmup_start.into_value());
+        map.insert_unchecked(Property::WarmupDays, self.warmup_days.into_value());
+        map.insert_unchecked(
+            Property::WarmupInitialLimit,
+            self.warmup_initial_limit.into_value(),
+        );
+        map.insert_unchecked(
+            Property::WarmupTargetLimit,
+            self.warmup_target_limit.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -25573,6 +27140,11 @@ impl RegistryJsonPropertyPatch for MtaConnectionIpHost {
                 .ehlo_hostname
                 .patch(pointer.with_validators(&[StringValidator::Hostname]), value),
             Some(Property::SourceIp) => self.source_ip.patch(pointer, value),
+            Some(Property::Weight) => self.weight.patch(pointer, value),
+            Some(Property::WarmupStart) => self.warmup_start.patch(pointer, value),
+            Some(Property::WarmupDays) => self.warmup_days.patch(pointer, value),
+            Some(Property::WarmupInitialLimit) => self.warmup_initial_limit.patch(pointer, value),
+            Some(Property::WarmupTargetLimit) => self.warmup_target_limit.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -25584,7 +27156,7 @@ impl RegistryJsonPropertyPatch for MtaConnectionIpHost {
 
 impl ObjectImpl for MtaConnectionStrategy {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::MtaConnectionStrategy;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -25627,6 +27199,7 @@ impl Pickle for MtaConnectionStrategy {
         self.greeting_timeout.pickle(out);
         self.mail_from_timeout.pickle(out);
         self.rcpt_to_timeout.pickle(out);
+        self.source_ip_selection.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -25641,6 +27214,9 @@ impl Pickle for MtaConnectionStrategy {
         this.greeting_timeout = Pickle::unpickle(stream)?;
         this.mail_from_timeout = Pickle::unpickle(stream)?;
         this.rcpt_to_timeout = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.source_ip_selection = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -25658,13 +27234,14 @@ impl Default for MtaConnectionStrategy {
             greeting_timeout: Duration::from_millis(300000),
             mail_from_timeout: Duration::from_millis(300000),
             rcpt_to_timeout: Duration::from_millis(300000),
+            source_ip_selection: MtaIpSelection::Weighted,
         }
     }
 }
 
 impl IntoValue for MtaConnectionStrategy {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(12);
+        let mut map = jmap_tools::Map::with_capacity(13);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::EhloHostname, self.ehlo_hostname.into_value());
@@ -25681,6 +27258,10 @@ impl IntoValue for MtaConnectionStrategy {
             self.mail_from_timeout.into_value(),
         );
         map.insert_unchecked(Property::RcptToTimeout, self.rcpt_to_timeout.into_value());
+        map.insert_unchecked(
+            Property::SourceIpSelection,
+            self.source_ip_selection.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -25704,6 +27285,7 @@ impl RegistryJsonPropertyPatch for MtaConnectionStrategy {
             Some(Property::GreetingTimeout) => self.greeting_timeout.patch(pointer, value),
             Some(Property::MailFromTimeout) => self.mail_from_timeout.patch(pointer, value),
             Some(Property::RcptToTimeout) => self.rcpt_to_timeout.patch(pointer, value),
+            Some(Property::SourceIpSelection) => self.source_ip_selection.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -25859,19 +27441,29 @@ impl RegistryJsonPropertyPatch for MtaDeliveryExpirationAttempts {
 }
 
 impl MtaDeliveryExpirationTtl {
-    fn validate(&self, _: &mut Vec<ValidationError>) -> bool {
-        true
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        if let Some(value) = &self.max_attempts {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxAttempts, 1));
+            }
+        }
+        errors.len() == neb
     }
 }
 
 impl Pickle for MtaDeliveryExpirationTtl {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.expire.pickle(out);
+        self.max_attempts.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
         let mut this = Self::default();
         this.expire = Pickle::unpickle(stream)?;
+        if stream.version() >= 2 {
+            this.max_attempts = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -25880,14 +27472,16 @@ impl Default for MtaDeliveryExpirationTtl {
     fn default() -> Self {
         Self {
             expire: Duration::from_millis(259200000),
+            max_attempts: Default::default(),
         }
     }
 }
 
 impl IntoValue for MtaDeliveryExpirationTtl {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(3);
+        let mut map = jmap_tools::Map::with_capacity(4);
         map.insert_unchecked(Property::Expire, self.expire.into_value());
+        map.insert_unchecked(Property::MaxAttempts, self.max_attempts.into_value());
         JmapValue::Object(map)
     }
 }
@@ -25900,6 +27494,7 @@ impl RegistryJsonPropertyPatch for MtaDeliveryExpirationTtl {
     ) -> PatchResult<'x> {
         match pointer.next_property() {
             Some(Property::Expire) => self.expire.patch(pointer, value),
+            Some(Property::MaxAttempts) => self.max_attempts.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -25911,7 +27506,7 @@ impl RegistryJsonPropertyPatch for MtaDeliveryExpirationTtl {
 
 impl ObjectImpl for MtaDeliverySchedule {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 2;
     const OBJECT: ObjectType = ObjectType::MtaDeliverySchedule;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -25935,6 +27530,19 @@ impl ObjectImpl for MtaDeliverySchedule {
         }
         let value = &self.retry;
         value.validate(errors);
+        if let Some(value) = &self.delivery_hour_start {
+            if *value > 23 {
+                errors.push(ValidationError::max_value(Property::DeliveryHourStart, 23));
+            }
+        }
+        if let Some(value) = &self.delivery_hour_end {
+            if *value > 24 {
+                errors.push(ValidationError::max_value(Property::DeliveryHourEnd, 24));
+            }
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::DeliveryHourEnd, 1));
+            }
+        }
         errors.len() == neb
     }
 
@@ -25952,6 +27560,10 @@ impl Pickle for MtaDeliverySchedule {
         self.notify.pickle(out);
         self.queue_id.pickle(out);
         self.retry.pickle(out);
+        self.delivery_days.pickle(out);
+        self.delivery_hour_start.pickle(out);
+        self.delivery_hour_end.pickle(out);
+        self.delivery_time_zone.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -25962,6 +27574,18 @@ impl Pickle for MtaDeliverySchedule {
         this.notify = Pickle::unpickle(stream)?;
         this.queue_id = Pickle::unpickle(stream)?;
         this.retry = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.delivery_days = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.delivery_hour_start = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.delivery_hour_end = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.delivery_time_zone = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -25975,19 +27599,36 @@ impl Default for MtaDeliverySchedule {
             notify: Default::default(),
             queue_id: Default::default(),
             retry: Default::default(),
+            delivery_days: Default::default(),
+            delivery_hour_start: Default::default(),
+            delivery_hour_end: Default::default(),
+            delivery_time_zone: Default::default(),
         }
     }
 }
 
 impl IntoValue for MtaDeliverySchedule {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(8);
+        let mut map = jmap_tools::Map::with_capacity(12);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::Expiry, self.expiry.into_value());
         map.insert_unchecked(Property::Notify, self.notify.into_value());
         map.insert_unchecked(Property::QueueId, self.queue_id.into_value());
         map.insert_unchecked(Property::Retry, self.retry.into_value());
+        map.insert_unchecked(Property::DeliveryDays, self.delivery_days.into_value());
+        map.insert_unchecked(
+            Property::DeliveryHourStart,
+            self.delivery_hour_start.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DeliveryHourEnd,
+            self.delivery_hour_end.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DeliveryTimeZone,
+            self.delivery_time_zone.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -26005,6 +27646,10 @@ impl RegistryJsonPropertyPatch for MtaDeliverySchedule {
             Some(Property::Notify) => self.notify.patch(pointer, value),
             Some(Property::QueueId) => self.queue_id.patch(pointer, value),
             Some(Property::Retry) => self.retry.patch(pointer, value),
+            Some(Property::DeliveryDays) => self.delivery_days.patch(pointer, value),
+            Some(Property::DeliveryHourStart) => self.delivery_hour_start.patch(pointer, value),
+            Some(Property::DeliveryHourEnd) => self.delivery_hour_end.patch(pointer, value),
+            Some(Property::DeliveryTimeZone) => self.delivery_time_zone.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -26218,7 +27863,7 @@ impl MtaDeliveryScheduleIntervalsOrDefault {
 
 impl ObjectImpl for MtaExtensions {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaExtensions;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -26243,6 +27888,8 @@ impl ObjectImpl for MtaExtensions {
         value.validate(errors);
         let value = &self.vrfy;
         value.validate(errors);
+        let value = &self.delivery_callback;
+        value.validate(errors);
         errors.len() == neb
     }
 
@@ -26272,6 +27919,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "15d".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::DeliverBy,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26288,6 +27936,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::Dsn,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26304,6 +27953,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::Expn,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26320,6 +27970,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "7d".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::FutureRelease,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26336,6 +27987,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "mixer".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::MtPriority,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26391,6 +28043,7 @@ impl MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::Vrfy,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -26398,6 +28051,45 @@ impl MtaExtensions {
         }
     }
 
+    pub fn ctx_hidden_extensions(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.hidden_extensions,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            }),
+            property: Property::HiddenExtensions,
+            allowed_variables: MTA_CONNECTION_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn ctx_reject_hidden_extensions(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.reject_hidden_extensions,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            }),
+            property: Property::RejectHiddenExtensions,
+            allowed_variables: MTA_CONNECTION_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn ctx_delivery_callback(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.delivery_callback,
+            default: Some(Expression {
+                else_: "local_port != 25".to_string(),
+                ..Default::default()
+            }),
+            property: Property::DeliveryCallback,
+            allowed_variables: MTA_CONNECTION_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
     pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
         vec![
             self.ctx_chunking(),
@@ -26410,6 +28102,9 @@ impl MtaExtensions {
             self.ctx_pipelining(),
             self.ctx_require_tls(),
             self.ctx_vrfy(),
+            self.ctx_hidden_extensions(),
+            self.ctx_reject_hidden_extensions(),
+            self.ctx_delivery_callback(),
         ]
     }
 }
@@ -26426,6 +28121,9 @@ impl Pickle for MtaExtensions {
         self.pipelining.pickle(out);
         self.require_tls.pickle(out);
         self.vrfy.pickle(out);
+        self.hidden_extensions.pickle(out);
+        self.reject_hidden_extensions.pickle(out);
+        self.delivery_callback.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -26440,6 +28138,11 @@ impl Pickle for MtaExtensions {
         this.pipelining = Pickle::unpickle(stream)?;
         this.require_tls = Pickle::unpickle(stream)?;
         this.vrfy = Pickle::unpickle(stream)?;
+        this.hidden_extensions = Pickle::unpickle(stream)?;
+        this.reject_hidden_extensions = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.delivery_callback = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -26457,6 +28160,7 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "15d".to_string(),
                 }]),
+                ..Default::default()
             },
             dsn: Expression {
                 else_: "false".to_string(),
@@ -26464,6 +28168,7 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             expn: Expression {
                 else_: "false".to_string(),
@@ -26471,6 +28176,7 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             future_release: Expression {
                 else_: "false".to_string(),
@@ -26478,6 +28184,7 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "7d".to_string(),
                 }]),
+                ..Default::default()
             },
             mt_priority: Expression {
                 else_: "false".to_string(),
@@ -26485,6 +28192,7 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "mixer".to_string(),
                 }]),
+                ..Default::default()
             },
             no_soliciting: Expression {
                 else_: "''".to_string(),
@@ -26504,6 +28212,19 @@ impl Default for MtaExtensions {
                     if_: "!is_empty(authenticated_as)".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
+            },
+            hidden_extensions: Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            },
+            reject_hidden_extensions: Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            },
+            delivery_callback: Expression {
+                else_: "local_port != 25".to_string(),
+                ..Default::default()
             },
         }
     }
@@ -26511,7 +28232,7 @@ impl Default for MtaExtensions {
 
 impl IntoValue for MtaExtensions {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(12);
+        let mut map = jmap_tools::Map::with_capacity(15);
         map.insert_unchecked(Property::Chunking, self.chunking.into_value());
         map.insert_unchecked(Property::DeliverBy, self.deliver_by.into_value());
         map.insert_unchecked(Property::Dsn, self.dsn.into_value());
@@ -26522,6 +28243,18 @@ impl IntoValue for MtaExtensions {
         map.insert_unchecked(Property::Pipelining, self.pipelining.into_value());
         map.insert_unchecked(Property::RequireTls, self.require_tls.into_value());
         map.insert_unchecked(Property::Vrfy, self.vrfy.into_value());
+        map.insert_unchecked(
+            Property::HiddenExtensions,
+            self.hidden_extensions.into_value(),
+        );
+        map.insert_unchecked(
+            Property::RejectHiddenExtensions,
+            self.reject_hidden_extensions.into_value(),
+        );
+        map.insert_unchecked(
+            Property::DeliveryCallback,
+            self.delivery_callback.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -26543,6 +28276,11 @@ impl RegistryJsonPropertyPatch for MtaExtensions {
             Some(Property::Pipelining) => self.pipelining.patch(pointer, value),
             Some(Property::RequireTls) => self.require_tls.patch(pointer, value),
             Some(Property::Vrfy) => self.vrfy.patch(pointer, value),
+            Some(Property::HiddenExtensions) => self.hidden_extensions.patch(pointer, value),
+            Some(Property::RejectHiddenExtensions) => {
+                self.reject_hidden_extensions.patch(pointer, value)
+            }
+            Some(Property::DeliveryCallback) => self.delivery_callback.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -26554,7 +28292,7 @@ impl RegistryJsonPropertyPatch for MtaExtensions {
 
 impl ObjectImpl for MtaHook {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaHook;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -26573,6 +28311,10 @@ impl ObjectImpl for MtaHook {
                 errors.push(ValidationError::required(Property::HttpHeaders));
             }
         }
+        let value = &self.stream_chunk_size;
+        if *value < 1024 {
+            errors.push(ValidationError::min_value(Property::StreamChunkSize, 1024));
+        }
         errors.len() == neb
     }
 
@@ -26609,6 +28351,8 @@ impl Pickle for MtaHook {
         self.url.pickle(out);
         self.http_auth.pickle(out);
         self.http_headers.pickle(out);
+        self.protocol.pickle(out);
+        self.stream_chunk_size.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -26622,6 +28366,12 @@ impl Pickle for MtaHook {
         this.url = Pickle::unpickle(stream)?;
         this.http_auth = Pickle::unpickle(stream)?;
         this.http_headers = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.protocol = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.stream_chunk_size = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -26641,13 +28391,15 @@ impl Default for MtaHook {
             url: Default::default(),
             http_auth: Default::default(),
             http_headers: Default::default(),
+            protocol: MtaHookProtocol::Json,
+            stream_chunk_size: 65536,
         }
     }
 }
 
 impl IntoValue for MtaHook {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(11);
+        let mut map = jmap_tools::Map::with_capacity(13);
         map.insert_unchecked(
             Property::AllowInvalidCerts,
             self.allow_invalid_certs.into_value(),
@@ -26666,6 +28418,8 @@ impl IntoValue for MtaHook {
         map.insert_unchecked(Property::Url, self.url.into_value());
         map.insert_unchecked(Property::HttpAuth, self.http_auth.into_value());
         map.insert_unchecked(Property::HttpHeaders, self.http_headers.into_value());
+        map.insert_unchecked(Property::Protocol, self.protocol.into_value());
+        map.insert_unchecked(Property::StreamChunkSize, self.stream_chunk_size.into_value());
         JmapValue::Object(map)
     }
 }
@@ -26690,6 +28444,8 @@ impl RegistryJsonPropertyPatch for MtaHook {
             Some(Property::HttpHeaders) => self
                 .http_headers
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::Protocol) => self.protocol.patch(pointer, value),
+            Some(Property::StreamChunkSize) => self.stream_chunk_size.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -26701,7 +28457,7 @@ impl RegistryJsonPropertyPatch for MtaHook {
 
 impl ObjectImpl for MtaInboundSession {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaInboundSession;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -26833,7 +28589,7 @@ impl RegistryJsonPropertyPatch for MtaInboundSession {
 
 impl ObjectImpl for MtaInboundThrottle {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaInboundThrottle;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -26943,7 +28699,7 @@ impl RegistryJsonPropertyPatch for MtaInboundThrottle {
 
 impl ObjectImpl for MtaMilter {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaMilter;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27119,7 +28875,7 @@ impl RegistryJsonPropertyPatch for MtaMilter {
 
 impl ObjectImpl for MtaOutboundStrategy {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaOutboundStrategy;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27161,6 +28917,7 @@ impl MtaOutboundStrategy {
                     if_: "is_local_domain(rcpt_domain)".to_string(),
                     then: "'local'".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::Route,
             allowed_variables: MTA_QUEUE_RCPT_VARIABLE,
@@ -27187,6 +28944,7 @@ impl MtaOutboundStrategy {
                         then: "'report'".to_string(),
                     },
                 ]),
+                ..Default::default()
             }),
             property: Property::Schedule,
             allowed_variables: MTA_QUEUE_RCPT_VARIABLE,
@@ -27203,6 +28961,7 @@ impl MtaOutboundStrategy {
                     if_: "retry_num > 0 && last_error == 'tls'".to_string(),
                     then: "'invalid-tls'".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::Tls,
             allowed_variables: MTA_QUEUE_HOST_VARIABLE,
@@ -27251,6 +29010,7 @@ impl Default for MtaOutboundStrategy {
                     if_: "is_local_domain(rcpt_domain)".to_string(),
                     then: "'local'".to_string(),
                 }]),
+                ..Default::default()
             },
             schedule: Expression {
                 else_: "'remote'".to_string(),
@@ -27268,6 +29028,7 @@ impl Default for MtaOutboundStrategy {
                         then: "'report'".to_string(),
                     },
                 ]),
+                ..Default::default()
             },
             tls: Expression {
                 else_: "'default'".to_string(),
@@ -27275,6 +29036,7 @@ impl Default for MtaOutboundStrategy {
                     if_: "retry_num > 0 && last_error == 'tls'".to_string(),
                     then: "'invalid-tls'".to_string(),
                 }]),
+                ..Default::default()
             },
         }
     }
@@ -27313,7 +29075,7 @@ impl RegistryJsonPropertyPatch for MtaOutboundStrategy {
 
 impl ObjectImpl for MtaOutboundThrottle {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaOutboundThrottle;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27421,7 +29183,7 @@ impl RegistryJsonPropertyPatch for MtaOutboundThrottle {
 
 impl ObjectImpl for MtaQueueQuota {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaQueueQuota;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27538,9 +29300,183 @@ impl RegistryJsonPropertyPatch for MtaQueueQuota {
     }
 }
 
+impl MtaRelayHost {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.address;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Address));
+        }
+        let value = &self.port;
+        if *value > 65535 {
+            errors.push(ValidationError::max_value(Property::Port, 65535));
+        }
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::Port, 1));
+        }
+        let value = &self.weight;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::Weight, 1));
+        }
+        if let Some(value) = &self.max_connections {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxConnections, 1));
+            }
+        }
+        errors.len() == neb
+    }
+}
+
+impl Pickle for MtaRelayHost {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.address.pickle(out);
+        self.port.pickle(out);
+        self.weight.pickle(out);
+        self.max_connections.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.address = Pickle::unpickle(stream)?;
+        this.port = Pickle::unpickle(stream)?;
+        this.weight = Pickle::unpickle(stream)?;
+        this.max_connections = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for MtaRelayHost {
+    fn default() -> Self {
+        Self {
+            address: Default::default(),
+            port: 25u64,
+            weight: 1u64,
+            max_connections: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for MtaRelayHost {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(6);
+        map.insert_unchecked(Property::Address, self.address.into_value());
+        map.insert_unchecked(Property::Port, self.port.into_value());
+        map.insert_unchecked(Property::Weight, self.weight.into_value());
+        map.insert_unchecked(Property::MaxConnections, self.max_connections.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for MtaRelayHost {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::Address) => self
+                .address
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::Port) => self.port.patch(pointer, value),
+            Some(Property::Weight) => self.weight.patch(pointer, value),
+            Some(Property::MaxConnections) => self.max_connections.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl ObjectImpl for MtaResponse {
+    const FLAGS: u64 = 0;
+    const VERSION: u8 = 10;
+    const OBJECT: ObjectType = ObjectType::MtaResponse;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.message;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.unique(Property::ResponseId, self.response_id.to_id() as u64);
+    }
+}
+
+impl MtaResponse {
+    pub fn ctx_message(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.message,
+            default: None,
+            property: Property::Message,
+            allowed_variables: MTA_RESPONSE_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
+        vec![self.ctx_message()]
+    }
+}
+
+impl Pickle for MtaResponse {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.response_id.pickle(out);
+        self.message.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.response_id = Pickle::unpickle(stream)?;
+        this.message = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for MtaResponse {
+    fn default() -> Self {
+        Self {
+            response_id: Default::default(),
+            message: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for MtaResponse {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(4);
+        map.insert_unchecked(Property::ResponseId, self.response_id.into_value());
+        map.insert_unchecked(Property::Message, self.message.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for MtaResponse {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::ResponseId) => {
+                self.response_id.patch(pointer.assert_read_only()?, value)
+            }
+            Some(Property::Message) => self.message.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for MtaRoute {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::MtaRoute;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27855,6 +29791,23 @@ impl MtaRouteRelay {
                 errors.push(ValidationError::required(Property::Description));
             }
         }
+        let value = &self.weight;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::Weight, 1));
+        }
+        if let Some(value) = &self.max_connections {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxConnections, 1));
+            }
+        }
+        let value = &self.additional_hosts;
+        for value in value.values() {
+            value.validate(errors);
+        }
+        let value = &self.failure_threshold;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::FailureThreshold, 1));
+        }
         errors.len() == neb
     }
 
@@ -27874,6 +29827,13 @@ impl Pickle for MtaRouteRelay {
         self.implicit_tls.pickle(out);
         self.name.pickle(out);
         self.description.pickle(out);
+        self.weight.pickle(out);
+        self.max_connections.pickle(out);
+        self.additional_hosts.pickle(out);
+        self.host_selection.pickle(out);
+        self.failure_threshold.pickle(out);
+        self.failure_cool_off.pickle(out);
+        self.health_check_interval.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -27887,6 +29847,27 @@ impl Pickle for MtaRouteRelay {
         this.implicit_tls = Pickle::unpickle(stream)?;
         this.name = Pickle::unpickle(stream)?;
         this.description = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.weight = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.max_connections = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.additional_hosts = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.host_selection = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.failure_threshold = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.failure_cool_off = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.health_check_interval = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -27903,13 +29884,20 @@ impl Default for MtaRouteRelay {
             implicit_tls: false,
             name: Default::default(),
             description: Default::default(),
+            weight: 1u64,
+            max_connections: Default::default(),
+            additional_hosts: Default::default(),
+            host_selection: MtaIpSelection::Weighted,
+            failure_threshold: 3u64,
+            failure_cool_off: Duration::from_millis(60000),
+            health_check_interval: Default::default(),
         }
     }
 }
 
 impl IntoValue for MtaRouteRelay {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(11);
+        let mut map = jmap_tools::Map::with_capacity(18);
         map.insert_unchecked(Property::Address, self.address.into_value());
         map.insert_unchecked(Property::AuthSecret, self.auth_secret.into_value());
         map.insert_unchecked(Property::AuthUsername, self.auth_username.into_value());
@@ -27922,6 +29910,22 @@ impl IntoValue for MtaRouteRelay {
         map.insert_unchecked(Property::ImplicitTls, self.implicit_tls.into_value());
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Description, self.description.into_value());
+        map.insert_unchecked(Property::Weight, self.weight.into_value());
+        map.insert_unchecked(Property::MaxConnections, self.max_connections.into_value());
+        map.insert_unchecked(
+            Property::AdditionalHosts,
+            self.additional_hosts.into_value(),
+        );
+        map.insert_unchecked(Property::HostSelection, self.host_selection.into_value());
+        map.insert_unchecked(
+            Property::FailureThreshold,
+            self.failure_threshold.into_value(),
+        );
+        map.insert_unchecked(Property::FailureCoolOff, self.failure_cool_off.into_value());
+        map.insert_unchecked(
+            Property::HealthCheckInterval,
+            self.health_check_interval.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -27944,6 +29948,13 @@ impl RegistryJsonPropertyPatch for MtaRouteRelay {
             Some(Property::ImplicitTls) => self.implicit_tls.patch(pointer, value),
             Some(Property::Name) => self.name.patch(pointer.assert_read_only()?, value),
             Some(Property::Description) => self.description.patch(pointer, value),
+            Some(Property::Weight) => self.weight.patch(pointer, value),
+            Some(Property::MaxConnections) => self.max_connections.patch(pointer, value),
+            Some(Property::AdditionalHosts) => self.additional_hosts.patch(pointer, value),
+            Some(Property::HostSelection) => self.host_selection.patch(pointer, value),
+            Some(Property::FailureThreshold) => self.failure_threshold.patch(pointer, value),
+            Some(Property::FailureCoolOff) => self.failure_cool_off.patch(pointer, value),
+            Some(Property::HealthCheckInterval) => self.health_check_interval.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -27955,7 +29966,7 @@ impl RegistryJsonPropertyPatch for MtaRouteRelay {
 
 impl ObjectImpl for MtaStageAuth {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 11;
     const OBJECT: ObjectType = ObjectType::MtaStageAuth;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -27970,6 +29981,8 @@ impl ObjectImpl for MtaStageAuth {
         value.validate(errors);
         let value = &self.require;
         value.validate(errors);
+        let value = &self.client_cert_account;
+        value.validate(errors);
         errors.len() == neb
     }
 
@@ -28018,6 +30031,7 @@ impl MtaStageAuth {
                         then: "[oauthbearer, xoauth2]".to_string(),
                     },
                 ]),
+                ..Default::default()
             }),
             property: Property::SaslMechanisms,
             allowed_variables: MTA_EHLO_VARIABLE,
@@ -28051,6 +30065,19 @@ impl MtaStageAuth {
         }
     }
 
+    pub fn ctx_client_cert_account(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.client_cert_account,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            }),
+            property: Property::ClientCertAccount,
+            allowed_variables: MTA_CLIENT_CERT_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
     pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
         vec![
             self.ctx_max_failures(),
@@ -28058,6 +30085,7 @@ impl MtaStageAuth {
             self.ctx_sasl_mechanisms(),
             self.ctx_must_match_sender(),
             self.ctx_require(),
+            self.ctx_client_cert_account(),
         ]
     }
 }
@@ -28069,6 +30097,8 @@ impl Pickle for MtaStageAuth {
         self.sasl_mechanisms.pickle(out);
         self.must_match_sender.pickle(out);
         self.require.pickle(out);
+        self.client_cert_account.pickle(out);
+        self.client_cert_revocation_list.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -28078,6 +30108,12 @@ impl Pickle for MtaStageAuth {
         this.sasl_mechanisms = Pickle::unpickle(stream)?;
         this.must_match_sender = Pickle::unpickle(stream)?;
         this.require = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.client_cert_account = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 11 {
+            this.client_cert_revocation_list = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -28105,6 +30141,7 @@ impl Default for MtaStageAuth {
                         then: "[oauthbearer, xoauth2]".to_string(),
                     },
                 ]),
+                ..Default::default()
             },
             must_match_sender: Expression {
                 else_: "true".to_string(),
@@ -28114,13 +30151,18 @@ impl Default for MtaStageAuth {
                 else_: "local_port != 25".to_string(),
                 ..Default::default()
             },
+            client_cert_account: Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            },
+            client_cert_revocation_list: Default::default(),
         }
     }
 }
 
 impl IntoValue for MtaStageAuth {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(7);
+        let mut map = jmap_tools::Map::with_capacity(8);
         map.insert_unchecked(Property::MaxFailures, self.max_failures.into_value());
         map.insert_unchecked(Property::WaitOnFail, self.wait_on_fail.into_value());
         map.insert_unchecked(Property::SaslMechanisms, self.sasl_mechanisms.into_value());
@@ -28129,6 +30171,14 @@ impl IntoValue for MtaStageAuth {
             self.must_match_sender.into_value(),
         );
         map.insert_unchecked(Property::Require, self.require.into_value());
+        map.insert_unchecked(
+            Property::ClientCertAccount,
+            self.client_cert_account.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ClientCertRevocationList,
+            self.client_cert_revocation_list.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -28145,6 +30195,10 @@ impl RegistryJsonPropertyPatch for MtaStageAuth {
             Some(Property::SaslMechanisms) => self.sasl_mechanisms.patch(pointer, value),
             Some(Property::MustMatchSender) => self.must_match_sender.patch(pointer, value),
             Some(Property::Require) => self.require.patch(pointer, value),
+            Some(Property::ClientCertAccount) => self.client_cert_account.patch(pointer, value),
+            Some(Property::ClientCertRevocationList) => {
+                self.client_cert_revocation_list.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -28156,7 +30210,7 @@ impl RegistryJsonPropertyPatch for MtaStageAuth {
 
 impl ObjectImpl for MtaStageConnect {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaStageConnect;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -28288,7 +30342,7 @@ impl RegistryJsonPropertyPatch for MtaStageConnect {
 
 impl ObjectImpl for MtaStageData {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaStageData;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -28315,6 +30369,10 @@ impl ObjectImpl for MtaStageData {
         value.validate(errors);
         let value = &self.enable_spam_filter;
         value.validate(errors);
+        let value = &self.from_alignment;
+        value.validate(errors);
+        let value = &self.lmtp_local_delivery;
+        value.validate(errors);
         errors.len() == neb
     }
 
@@ -28331,6 +30389,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddAuthResultsHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28347,6 +30406,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddDateHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28363,6 +30423,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddMessageIdHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28379,6 +30440,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddReceivedHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28395,6 +30457,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddReceivedSpfHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28411,6 +30474,7 @@ impl MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::AddReturnPathHeader,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -28483,6 +30547,33 @@ impl MtaStageData {
         }
     }
 
+    pub fn ctx_from_alignment(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.from_alignment,
+            default: Some(Expression {
+                else_: "disable".to_string(),
+                match_: List::from_iter([]),
+                ..Default::default()
+            }),
+            property: Property::FromAlignment,
+            allowed_variables: MTA_RCPT_TO_VARIABLE,
+            allowed_constants: MTA_FROM_ALIGNMENT_CONSTANT,
+        }
+    }
+
+    pub fn ctx_lmtp_local_delivery(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.lmtp_local_delivery,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            }),
+            property: Property::LmtpLocalDelivery,
+            allowed_variables: MTA_RCPT_TO_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
     pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
         vec![
             self.ctx_add_auth_results_header(),
@@ -28496,6 +30587,8 @@ impl MtaStageData {
             self.ctx_max_message_size(),
             self.ctx_script(),
             self.ctx_enable_spam_filter(),
+            self.ctx_from_alignment(),
+            self.ctx_lmtp_local_delivery(),
         ]
     }
 }
@@ -28514,6 +30607,8 @@ impl Pickle for MtaStageData {
         self.max_message_size.pickle(out);
         self.script.pickle(out);
         self.enable_spam_filter.pickle(out);
+        self.from_alignment.pickle(out);
+        self.lmtp_local_delivery.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -28530,6 +30625,12 @@ impl Pickle for MtaStageData {
         this.max_message_size = Pickle::unpickle(stream)?;
         this.script = Pickle::unpickle(stream)?;
         this.enable_spam_filter = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.from_alignment = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.lmtp_local_delivery = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -28543,6 +30644,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             add_date_header: Expression {
                 else_: "false".to_string(),
@@ -28550,6 +30652,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             add_delivered_to_header: true,
             add_message_id_header: Expression {
@@ -28558,6 +30661,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             add_received_header: Expression {
                 else_: "false".to_string(),
@@ -28565,6 +30669,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             add_received_spf_header: Expression {
                 else_: "false".to_string(),
@@ -28572,6 +30677,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             add_return_path_header: Expression {
                 else_: "false".to_string(),
@@ -28579,6 +30685,7 @@ impl Default for MtaStageData {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             max_messages: Expression {
                 else_: "10".to_string(),
@@ -28600,13 +30707,21 @@ impl Default for MtaStageData {
                 else_: "is_empty(authenticated_as)".to_string(),
                 ..Default::default()
             },
+            from_alignment: Expression {
+                else_: "disable".to_string(),
+                ..Default::default()
+            },
+            lmtp_local_delivery: Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            },
         }
     }
 }
 
 impl IntoValue for MtaStageData {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(14);
+        let mut map = jmap_tools::Map::with_capacity(16);
         map.insert_unchecked(
             Property::AddAuthResultsHeader,
             self.add_auth_results_header.into_value(),
@@ -28643,6 +30758,8 @@ impl IntoValue for MtaStageData {
             Property::EnableSpamFilter,
             self.enable_spam_filter.into_value(),
         );
+        map.insert_unchecked(Property::FromAlignment, self.from_alignment.into_value());
+        map.insert_unchecked(Property::LmtpLocalDelivery, self.lmtp_local_delivery.into_value());
         JmapValue::Object(map)
     }
 }
@@ -28674,6 +30791,8 @@ impl RegistryJsonPropertyPatch for MtaStageData {
             Some(Property::MaxMessageSize) => self.max_message_size.patch(pointer, value),
             Some(Property::Script) => self.script.patch(pointer, value),
             Some(Property::EnableSpamFilter) => self.enable_spam_filter.patch(pointer, value),
+            Some(Property::FromAlignment) => self.from_alignment.patch(pointer, value),
+            Some(Property::LmtpLocalDelivery) => self.lmtp_local_delivery.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -28685,7 +30804,7 @@ impl RegistryJsonPropertyPatch for MtaStageData {
 
 impl ObjectImpl for MtaStageEhlo {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaStageEhlo;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -28712,6 +30831,7 @@ impl MtaStageEhlo {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::RejectNonFqdn,
             allowed_variables: MTA_CONNECTION_VARIABLE,
@@ -28779,6 +30899,7 @@ impl Default for MtaStageEhlo {
                     if_: "local_port == 25".to_string(),
                     then: "true".to_string(),
                 }]),
+                ..Default::default()
             },
             require: Expression {
                 else_: "true".to_string(),
@@ -28823,7 +30944,7 @@ impl RegistryJsonPropertyPatch for MtaStageEhlo {
 
 impl ObjectImpl for MtaStageMail {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaStageMail;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -28848,6 +30969,7 @@ impl MtaStageMail {
                 else_: "!is_empty(authenticated_as) || !key_exists('spam-block', sender_domain)"
                     .to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             }),
             property: Property::IsSenderAllowed,
             allowed_variables: MTA_MAIL_FROM_VARIABLE,
@@ -28913,6 +31035,7 @@ impl Default for MtaStageMail {
                 else_: "!is_empty(authenticated_as) || !key_exists('spam-block', sender_domain)"
                     .to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             },
             rewrite: Expression {
                 else_: "false".to_string(),
@@ -28960,7 +31083,7 @@ impl RegistryJsonPropertyPatch for MtaStageMail {
 
 impl ObjectImpl for MtaStageRcpt {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaStageRcpt;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -28977,6 +31100,17 @@ impl ObjectImpl for MtaStageRcpt {
         value.validate(errors);
         let value = &self.script;
         value.validate(errors);
+        let value = &self.max_expansion_depth;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::MaxExpansionDepth, 1));
+        }
+        let value = &self.max_expansion_recipients;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::MaxExpansionRecipients,
+                1,
+            ));
+        }
         errors.len() == neb
     }
 
@@ -29082,6 +31216,9 @@ impl Pickle for MtaStageRcpt {
         self.allow_relaying.pickle(out);
         self.rewrite.pickle(out);
         self.script.pickle(out);
+        self.max_expansion_depth.pickle(out);
+        self.max_expansion_recipients.pickle(out);
+        self.expansion_trace_header.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -29092,6 +31229,15 @@ impl Pickle for MtaStageRcpt {
         this.allow_relaying = Pickle::unpickle(stream)?;
         this.rewrite = Pickle::unpickle(stream)?;
         this.script = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.max_expansion_depth = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.max_expansion_recipients = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.expansion_trace_header = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -29123,19 +31269,34 @@ impl Default for MtaStageRcpt {
                 else_: "false".to_string(),
                 ..Default::default()
             },
+            max_expansion_depth: 10u64,
+            max_expansion_recipients: 1000u64,
+            expansion_trace_header: false,
         }
     }
 }
 
 impl IntoValue for MtaStageRcpt {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(8);
+        let mut map = jmap_tools::Map::with_capacity(11);
         map.insert_unchecked(Property::MaxFailures, self.max_failures.into_value());
         map.insert_unchecked(Property::WaitOnFail, self.wait_on_fail.into_value());
         map.insert_unchecked(Property::MaxRecipients, self.max_recipients.into_value());
         map.insert_unchecked(Property::AllowRelaying, self.allow_relaying.into_value());
         map.insert_unchecked(Property::Rewrite, self.rewrite.into_value());
         map.insert_unchecked(Property::Script, self.script.into_value());
+        map.insert_unchecked(
+            Property::MaxExpansionDepth,
+            self.max_expansion_depth.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MaxExpansionRecipients,
+            self.max_expansion_recipients.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ExpansionTraceHeader,
+            self.expansion_trace_header.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -29153,6 +31314,13 @@ impl RegistryJsonPropertyPatch for MtaStageRcpt {
             Some(Property::AllowRelaying) => self.allow_relaying.patch(pointer, value),
             Some(Property::Rewrite) => self.rewrite.patch(pointer, value),
             Some(Property::Script) => self.script.patch(pointer, value),
+            Some(Property::MaxExpansionDepth) => self.max_expansion_depth.patch(pointer, value),
+            Some(Property::MaxExpansionRecipients) => {
+                self.max_expansion_recipients.patch(pointer, value)
+            }
+            Some(Property::ExpansionTraceHeader) => {
+                self.expansion_trace_header.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -29345,9 +31513,150 @@ impl RegistryJsonPropertyPatch for MtaTlsStrategy {
     }
 }
 
+impl ObjectImpl for MtaUnsubscribe {
+    const FLAGS: u64 = OBJ_SINGLETON;
+    const VERSION: u8 = 10;
+    const OBJECT: ObjectType = ObjectType::MtaUnsubscribe;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.enable;
+        value.validate(errors);
+        let value = &self.campaign_id;
+        value.validate(errors);
+        let value = &self.signature_key;
+        value.validate(errors);
+        let value = &self.token_validity;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::TokenValidity, value));
+        }
+        if *value < Duration::from_millis(86400000) {
+            errors.push(ValidationError::min_value(Property::TokenValidity, 86400000));
+        }
+        if *value > Duration::from_millis(31536000000) {
+            errors.push(ValidationError::max_value(
+                Property::TokenValidity,
+                31536000000u64,
+            ));
+        }
+        if let Some(value) = &self.rate_limit {
+            value.validate(errors);
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, _: &mut IndexBuilder<'x>) {}
+}
+
+impl MtaUnsubscribe {
+    pub fn ctx_enable(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.enable,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            }),
+            property: Property::Enable,
+            allowed_variables: MTA_QUEUE_RCPT_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn ctx_campaign_id(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.campaign_id,
+            default: Some(Expression {
+                else_: "header('X-Campaign-Id')".to_string(),
+                ..Default::default()
+            }),
+            property: Property::CampaignId,
+            allowed_variables: MTA_QUEUE_RCPT_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
+        vec![self.ctx_enable(), self.ctx_campaign_id()]
+    }
+}
+
+impl Pickle for MtaUnsubscribe {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.enable.pickle(out);
+        self.campaign_id.pickle(out);
+        self.signature_key.pickle(out);
+        self.token_validity.pickle(out);
+        self.rate_limit.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.enable = Pickle::unpickle(stream)?;
+        this.campaign_id = Pickle::unpickle(stream)?;
+        this.signature_key = Pickle::unpickle(stream)?;
+        this.token_validity = Pickle::unpickle(stream)?;
+        this.rate_limit = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for MtaUnsubscribe {
+    fn default() -> Self {
+        Self {
+            enable: Expression {
+                else_: "false".to_string(),
+                ..Default::default()
+            },
+            campaign_id: Expression {
+                else_: "header('X-Campaign-Id')".to_string(),
+                ..Default::default()
+            },
+            signature_key: Default::default(),
+            token_validity: Duration::from_millis(7776000000),
+            rate_limit: Some(Rate {
+                count: 30u64,
+                period: Duration::from_millis(60000),
+            }),
+        }
+    }
+}
+
+impl IntoValue for MtaUnsubscribe {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(7);
+        map.insert_unchecked(Property::Enable, self.enable.into_value());
+        map.insert_unchecked(Property::CampaignId, self.campaign_id.into_value());
+        map.insert_unchecked(Property::SignatureKey, self.signature_key.into_value());
+        map.insert_unchecked(Property::TokenValidity, self.token_validity.into_value());
+        map.insert_unchecked(Property::RateLimit, self.rate_limit.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for MtaUnsubscribe {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::Enable) => self.enable.patch(pointer, value),
+            Some(Property::CampaignId) => self.campaign_id.patch(pointer, value),
+            Some(Property::SignatureKey) => self.signature_key.patch(pointer, value),
+            Some(Property::TokenValidity) => self.token_validity.patch(pointer, value),
+            Some(Property::RateLimit) => self.rate_limit.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for MtaVirtualQueue {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::MtaVirtualQueue;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -29368,6 +31677,10 @@ impl ObjectImpl for MtaVirtualQueue {
         if *value < 1 {
             errors.push(ValidationError::min_value(Property::ThreadsPerNode, 1));
         }
+        let value = &self.rate_limit;
+        if !value.match_.is_empty() || !value.else_.is_empty() {
+            value.validate(errors);
+        }
         errors.len() == neb
     }
 
@@ -29376,11 +31689,28 @@ impl ObjectImpl for MtaVirtualQueue {
     }
 }
 
+impl MtaVirtualQueue {
+    pub fn ctx_rate_limit(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.rate_limit,
+            default: None,
+            property: Property::RateLimit,
+            allowed_variables: &[],
+            allowed_constants: &[],
+        }
+    }
+
+    pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
+        vec![self.ctx_rate_limit()]
+    }
+}
+
 impl Pickle for MtaVirtualQueue {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.name.pickle(out);
         self.description.pickle(out);
         self.threads_per_node.pickle(out);
+        self.rate_limit.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -29388,6 +31718,9 @@ impl Pickle for MtaVirtualQueue {
         this.name = Pickle::unpickle(stream)?;
         this.description = Pickle::unpickle(stream)?;
         this.threads_per_node = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.rate_limit = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -29398,16 +31731,18 @@ impl Default for MtaVirtualQueue {
             name: Default::default(),
             description: Default::default(),
             threads_per_node: 25u64,
+            rate_limit: Default::default(),
         }
     }
 }
 
 impl IntoValue for MtaVirtualQueue {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(5);
+        let mut map = jmap_tools::Map::with_capacity(6);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::ThreadsPerNode, self.threads_per_node.into_value());
+        map.insert_unchecked(Property::RateLimit, self.rate_limit.into_value());
         JmapValue::Object(map)
     }
 }
@@ -29427,6 +31762,7 @@ impl RegistryJsonPropertyPatch for MtaVirtualQueue {
             ),
             Some(Property::Description) => self.description.patch(pointer, value),
             Some(Property::ThreadsPerNode) => self.threads_per_node.patch(pointer, value),
+            Some(Property::RateLimit) => self.rate_limit.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -29616,6 +31952,7 @@ impl Pickle for MySqlStore {
         self.database.pickle(out);
         self.auth_username.pickle(out);
         self.auth_secret.pickle(out);
+        self.read_replica_stickiness.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -29632,6 +31969,9 @@ impl Pickle for MySqlStore {
         this.database = Pickle::unpickle(stream)?;
         this.auth_username = Pickle::unpickle(stream)?;
         this.auth_secret = Pickle::unpickle(stream)?;
+        if stream.version() >= 4 {
+            this.read_replica_stickiness = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -29646,6 +31986,7 @@ impl Default for MySqlStore {
             pool_max_connections: Some(10u64),
             pool_min_connections: Some(5u64),
             read_replicas: Default::default(),
+            read_replica_stickiness: Duration::from_millis(5000),
             host: Default::default(),
             port: 3306u64,
             database: "stalwart".to_string(),
@@ -29677,6 +32018,10 @@ impl IntoValue for MySqlStore {
             self.pool_min_connections.into_value(),
         );
         map.insert_unchecked(Property::ReadReplicas, self.read_replicas.into_value());
+        map.insert_unchecked(
+            Property::ReadReplicaStickiness,
+            self.read_replica_stickiness.into_value(),
+        );
         map.insert_unchecked(Property::Host, self.host.into_value());
         map.insert_unchecked(Property::Port, self.port.into_value());
         map.insert_unchecked(Property::Database, self.database.into_value());
@@ -29700,6 +32045,9 @@ impl RegistryJsonPropertyPatch for MySqlStore {
             Some(Property::PoolMaxConnections) => self.pool_max_connections.patch(pointer, value),
             Some(Property::PoolMinConnections) => self.pool_min_connections.patch(pointer, value),
             Some(Property::ReadReplicas) => self.read_replicas.patch(pointer, value),
+            Some(Property::ReadReplicaStickiness) => {
+                self.read_replica_stickiness.patch(pointer, value)
+            }
             Some(Property::Host) => self
                 .host
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
@@ -29880,7 +32228,7 @@ impl RegistryJsonPropertyPatch for NatsCoordinator {
 
 impl ObjectImpl for NetworkListener {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 2;
     const OBJECT: ObjectType = ObjectType::NetworkListener;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -29972,6 +32320,9 @@ impl Pickle for NetworkListener {
         self.tls_implicit.pickle(out);
         self.tls_timeout.pickle(out);
         self.max_connections.pickle(out);
+        self.tls_request_client_cert.pickle(out);
+        self.tls_require_client_cert.pickle(out);
+        self.tls_client_ca_certificates.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -29995,6 +32346,13 @@ impl Pickle for NetworkListener {
         this.tls_implicit = Pickle::unpickle(stream)?;
         this.tls_timeout = Pickle::unpickle(stream)?;
         this.max_connections = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.tls_request_client_cert = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.tls_require_client_cert = Pickle::unpickle(stream)?;
+            this.tls_client_ca_certificates = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -30021,13 +32379,16 @@ impl Default for NetworkListener {
             tls_implicit: false,
             tls_timeout: Some(Duration::from_millis(60000)),
             max_connections: Some(8192u64),
+            tls_request_client_cert: false,
+            tls_require_client_cert: false,
+            tls_client_ca_certificates: None,
         }
     }
 }
 
 impl IntoValue for NetworkListener {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(21);
+        let mut map = jmap_tools::Map::with_capacity(24);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::Bind, self.bind.into_value());
         map.insert_unchecked(Property::Protocol, self.protocol.into_value());
@@ -30071,6 +32432,18 @@ impl IntoValue for NetworkListener {
         map.insert_unchecked(Property::TlsImplicit, self.tls_implicit.into_value());
         map.insert_unchecked(Property::TlsTimeout, self.tls_timeout.into_value());
         map.insert_unchecked(Property::MaxConnections, self.max_connections.into_value());
+        map.insert_unchecked(
+            Property::TlsRequestClientCert,
+            self.tls_request_client_cert.into_value(),
+        );
+        map.insert_unchecked(
+            Property::TlsRequireClientCert,
+            self.tls_require_client_cert.into_value(),
+        );
+        map.insert_unchecked(
+            Property::TlsClientCaCertificates,
+            self.tls_client_ca_certificates.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -30113,6 +32486,15 @@ impl RegistryJsonPropertyPatch for NetworkListener {
             Some(Property::TlsImplicit) => self.tls_implicit.patch(pointer, value),
             Some(Property::TlsTimeout) => self.tls_timeout.patch(pointer, value),
             Some(Property::MaxConnections) => self.max_connections.patch(pointer, value),
+            Some(Property::TlsRequestClientCert) => {
+                self.tls_request_client_cert.patch(pointer, value)
+            }
+            Some(Property::TlsRequireClientCert) => {
+                self.tls_require_client_cert.patch(pointer, value)
+            }
+            Some(Property::TlsClientCaCertificates) => {
+                self.tls_client_ca_certificates.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -30353,6 +32735,8 @@ impl Pickle for OidcDirectory {
         self.claim_name.pickle(out);
         self.claim_groups.pickle(out);
         self.member_tenant_id.pickle(out);
+        self.claim_enabled_protocols.pickle(out);
+        self.claim_categorize_mail.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -30366,6 +32750,12 @@ impl Pickle for OidcDirectory {
         this.claim_name = Pickle::unpickle(stream)?;
         this.claim_groups = Pickle::unpickle(stream)?;
         this.member_tenant_id = Pickle::unpickle(stream)?;
+        if stream.version() >= 2 {
+            this.claim_enabled_protocols = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.claim_categorize_mail = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -30382,13 +32772,15 @@ impl Default for OidcDirectory {
             claim_name: Some("name".to_string()),
             claim_groups: Default::default(),
             member_tenant_id: Default::default(),
+            claim_enabled_protocols: None,
+            claim_categorize_mail: None,
         }
     }
 }
 
 impl IntoValue for OidcDirectory {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(11);
+        let mut map = jmap_tools::Map::with_capacity(13);
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::IssuerUrl, self.issuer_url.into_value());
         map.insert_unchecked(
@@ -30401,6 +32793,8 @@ impl IntoValue for OidcDirectory {
         map.insert_unchecked(Property::ClaimName, self.claim_name.into_value());
         map.insert_unchecked(Property::ClaimGroups, self.claim_groups.into_value());
         map.insert_unchecked(Property::MemberTenantId, self.member_tenant_id.into_value());
+        map.insert_unchecked(Property::ClaimEnabledProtocols, self.claim_enabled_protocols.into_value());
+        map.insert_unchecked(Property::ClaimCategorizeMail, self.claim_categorize_mail.into_value());
         JmapValue::Object(map)
     }
 }
@@ -30435,6 +32829,8 @@ impl RegistryJsonPropertyPatch for OidcDirectory {
             Some(Property::MemberTenantId) => self
                 .member_tenant_id
                 .patch(pointer.assert_can_set_tenant()?, value),
+            Some(Property::ClaimEnabledProtocols) => self.claim_enabled_protocols.patch(pointer, value),
+            Some(Property::ClaimCategorizeMail) => self.claim_categorize_mail.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -31104,6 +33500,7 @@ impl Pickle for PostgreSqlStore {
         self.auth_username.pickle(out);
         self.auth_secret.pickle(out);
         self.options.pickle(out);
+        self.read_replica_stickiness.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -31120,6 +33517,9 @@ impl Pickle for PostgreSqlStore {
         this.auth_username = Pickle::unpickle(stream)?;
         this.auth_secret = Pickle::unpickle(stream)?;
         this.options = Pickle::unpickle(stream)?;
+        if stream.version() >= 4 {
+            this.read_replica_stickiness = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -31133,6 +33533,7 @@ impl Default for PostgreSqlStore {
             pool_max_connections: Some(10u64),
             pool_recycling_method: PostgreSqlRecyclingMethod::Fast,
             read_replicas: Default::default(),
+            read_replica_stickiness: Duration::from_millis(5000),
             host: Default::default(),
             port: 5432u64,
             database: "stalwart".to_string(),
@@ -31161,6 +33562,10 @@ impl IntoValue for PostgreSqlStore {
             self.pool_recycling_method.into_value(),
         );
         map.insert_unchecked(Property::ReadReplicas, self.read_replicas.into_value());
+        map.insert_unchecked(
+            Property::ReadReplicaStickiness,
+            self.read_replica_stickiness.into_value(),
+        );
         map.insert_unchecked(Property::Host, self.host.into_value());
         map.insert_unchecked(Property::Port, self.port.into_value());
         map.insert_unchecked(Property::Database, self.database.into_value());
@@ -31184,6 +33589,9 @@ impl RegistryJsonPropertyPatch for PostgreSqlStore {
             Some(Property::PoolMaxConnections) => self.pool_max_connections.patch(pointer, value),
             Some(Property::PoolRecyclingMethod) => self.pool_recycling_method.patch(pointer, value),
             Some(Property::ReadReplicas) => self.read_replicas.patch(pointer, value),
+            Some(Property::ReadReplicaStickiness) => {
+                self.read_replica_stickiness.patch(pointer, value)
+            }
             Some(Property::Host) => self
                 .host
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
@@ -31642,11 +34050,13 @@ impl QueueExpiryTtl {
 impl Pickle for QueueExpiryTtl {
     fn pickle(&self, out: &mut Vec<u8>) {
         self.expires_at.pickle(out);
+        self.expires_attempts.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
         let mut this = Self::default();
         this.expires_at = Pickle::unpickle(stream)?;
+        this.expires_attempts = Pickle::unpickle(stream)?;
         Some(this)
     }
 }
@@ -31655,14 +34065,19 @@ impl Default for QueueExpiryTtl {
     fn default() -> Self {
         Self {
             expires_at: Default::default(),
+            expires_attempts: Default::default(),
         }
     }
 }
 
 impl IntoValue for QueueExpiryTtl {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(3);
+        let mut map = jmap_tools::Map::with_capacity(4);
         map.insert_unchecked(Property::ExpiresAt, self.expires_at.into_value());
+        map.insert_unchecked(
+            Property::ExpiresAttempts,
+            self.expires_attempts.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -31675,6 +34090,7 @@ impl RegistryJsonPropertyPatch for QueueExpiryTtl {
     ) -> PatchResult<'x> {
         match pointer.next_property() {
             Some(Property::ExpiresAt) => self.expires_at.patch(pointer, value),
+            Some(Property::ExpiresAttempts) => self.expires_attempts.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -31760,6 +34176,8 @@ impl Pickle for QueuedMessage {
         self.env_id.pickle(out);
         self.priority.pickle(out);
         self.size.pickle(out);
+        self.split_from.pickle(out);
+        self.metadata.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -31776,6 +34194,8 @@ impl Pickle for QueuedMessage {
         this.env_id = Pickle::unpickle(stream)?;
         this.priority = Pickle::unpickle(stream)?;
         this.size = Pickle::unpickle(stream)?;
+        this.split_from = Pickle::unpickle(stream)?;
+        this.metadata = Pickle::unpickle(stream)?;
         Some(this)
     }
 }
@@ -31795,13 +34215,15 @@ impl Default for QueuedMessage {
             env_id: Default::default(),
             priority: 0i64,
             size: 0u64,
+            split_from: Default::default(),
+            metadata: Default::default(),
         }
     }
 }
 
 impl IntoValue for QueuedMessage {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(14);
+        let mut map = jmap_tools::Map::with_capacity(16);
         map.insert_unchecked(Property::CreatedAt, self.created_at.into_value());
         map.insert_unchecked(Property::NextRetry, self.next_retry.into_value());
         map.insert_unchecked(Property::NextNotify, self.next_notify.into_value());
@@ -31817,6 +34239,8 @@ impl IntoValue for QueuedMessage {
         map.insert_unchecked(Property::EnvId, self.env_id.into_value());
         map.insert_unchecked(Property::Priority, self.priority.into_value());
         map.insert_unchecked(Property::Size, self.size.into_value());
+        map.insert_unchecked(Property::SplitFrom, self.split_from.into_value());
+        map.insert_unchecked(Property::Metadata, self.metadata.into_value());
         JmapValue::Object(map)
     }
 }
@@ -31840,6 +34264,8 @@ impl RegistryJsonPropertyPatch for QueuedMessage {
             Some(Property::EnvId) => self.env_id.patch(pointer, value),
             Some(Property::Priority) => self.priority.patch(pointer, value),
             Some(Property::Size) => pointer.assert_server_set(),
+            Some(Property::SplitFrom) => pointer.assert_server_set(),
+            Some(Property::Metadata) => pointer.assert_server_set(),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -32619,7 +35045,7 @@ impl RegistryJsonPropertyPatch for RedisStore {
 
 impl ObjectImpl for ReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::ReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -33888,7 +36314,7 @@ impl S3StoreRegion {
 
 impl ObjectImpl for Search {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::Search;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -33897,6 +36323,16 @@ impl ObjectImpl for Search {
         if *value < 1 {
             errors.push(ValidationError::min_value(Property::IndexBatchSize, 1));
         }
+        let value = &self.reindex_concurrency;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::ReindexConcurrency, 1));
+        }
+        if *value > 64 {
+            errors.push(ValidationError::max_value(Property::ReindexConcurrency, 64));
+        }
+        if let Some(value) = &self.reindex_rate_limit {
+            value.validate(errors);
+        }
         errors.len() == neb
     }
 
@@ -33916,6 +36352,8 @@ impl Pickle for Search {
         self.index_email_fields.pickle(out);
         self.index_telemetry.pickle(out);
         self.index_tracing_fields.pickle(out);
+        self.reindex_concurrency.pickle(out);
+        self.reindex_rate_limit.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -33931,6 +36369,12 @@ impl Pickle for Search {
         this.index_email_fields = Pickle::unpickle(stream)?;
         this.index_telemetry = Pickle::unpickle(stream)?;
         this.index_tracing_fields = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.reindex_concurrency = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.reindex_rate_limit = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -33985,13 +36429,15 @@ impl Default for Search {
                 SearchTracingField::QueueId,
                 SearchTracingField::Keywords,
             ]),
+            reindex_concurrency: 4,
+            reindex_rate_limit: Default::default(),
         }
     }
 }
 
 impl IntoValue for Search {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(13);
+        let mut map = jmap_tools::Map::with_capacity(15);
         map.insert_unchecked(Property::IndexBatchSize, self.index_batch_size.into_value());
         map.insert_unchecked(
             Property::DefaultLanguage,
@@ -34021,6 +36467,14 @@ impl IntoValue for Search {
             Property::IndexTracingFields,
             self.index_tracing_fields.into_value(),
         );
+        map.insert_unchecked(
+            Property::ReindexConcurrency,
+            self.reindex_concurrency.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReindexRateLimit,
+            self.reindex_rate_limit.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -34043,6 +36497,8 @@ impl RegistryJsonPropertyPatch for Search {
             Some(Property::IndexEmailFields) => self.index_email_fields.patch(pointer, value),
             Some(Property::IndexTelemetry) => self.index_telemetry.patch(pointer, value),
             Some(Property::IndexTracingFields) => self.index_tracing_fields.patch(pointer, value),
+            Some(Property::ReindexConcurrency) => self.reindex_concurrency.patch(pointer, value),
+            Some(Property::ReindexRateLimit) => self.reindex_rate_limit.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -34054,7 +36510,7 @@ impl RegistryJsonPropertyPatch for Search {
 
 impl ObjectImpl for SearchStore {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::SearchStore;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -35149,7 +37605,7 @@ impl RegistryJsonPropertyPatch for Security {
 
 impl ObjectImpl for SenderAuth {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::SenderAuth;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -35168,6 +37624,20 @@ impl ObjectImpl for SenderAuth {
         value.validate(errors);
         let value = &self.reverse_ip_verify;
         value.validate(errors);
+        let value = &self.sender_verify;
+        value.validate(errors);
+        if let Some(value) = &self.sender_verify_rate {
+            value.validate(errors);
+        }
+        let value = &self.sender_verify_max_concurrent;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::SenderVerifyMaxConcurrent,
+                1,
+            ));
+        }
+        let value = &self.reverse_ip_require;
+        value.validate(errors);
         errors.len() == neb
     }
 
@@ -35185,6 +37655,7 @@ impl SenderAuth {
                         .to_string(),
                     then: "sender_domain".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::DkimSignDomain,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -35198,6 +37669,7 @@ impl SenderAuth {
             default: Some(Expression {
                 else_: "relaxed".to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             }),
             property: Property::DkimVerify,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -35214,6 +37686,7 @@ impl SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::SpfEhloVerify,
             allowed_variables: MTA_CONNECTION_VARIABLE,
@@ -35230,6 +37703,7 @@ impl SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::SpfFromVerify,
             allowed_variables: MTA_CONNECTION_VARIABLE,
@@ -35243,6 +37717,7 @@ impl SenderAuth {
             default: Some(Expression {
                 else_: "disable".to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             }),
             property: Property::ArcVerify,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -35259,6 +37734,7 @@ impl SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::DmarcVerify,
             allowed_variables: MTA_RCPT_TO_VARIABLE,
@@ -35275,6 +37751,7 @@ impl SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             }),
             property: Property::ReverseIpVerify,
             allowed_variables: MTA_CONNECTION_VARIABLE,
@@ -35282,6 +37759,34 @@ impl SenderAuth {
         }
     }
 
+    pub fn ctx_sender_verify(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.sender_verify,
+            default: Some(Expression {
+                else_: "disable".to_string(),
+                match_: List::from_iter([]),
+                ..Default::default()
+            }),
+            property: Property::SenderVerify,
+            allowed_variables: MTA_MAIL_FROM_VARIABLE,
+            allowed_constants: MTA_VERIFY_CONSTANT,
+        }
+    }
+
+    pub fn ctx_reverse_ip_require(&self) -> ExpressionContext<'_> {
+        ExpressionContext {
+            expr: &self.reverse_ip_require,
+            default: Some(Expression {
+                else_: "false".to_string(),
+                match_: List::from_iter([]),
+                ..Default::default()
+            }),
+            property: Property::ReverseIpRequire,
+            allowed_variables: MTA_EHLO_VARIABLE,
+            allowed_constants: &[],
+        }
+    }
+
     pub fn expression_ctxs(&self) -> Vec<ExpressionContext<'_>> {
         vec![
             self.ctx_dkim_sign_domain(),
@@ -35291,6 +37796,8 @@ impl SenderAuth {
             self.ctx_arc_verify(),
             self.ctx_dmarc_verify(),
             self.ctx_reverse_ip_verify(),
+            self.ctx_sender_verify(),
+            self.ctx_reverse_ip_require(),
         ]
     }
 }
@@ -35305,6 +37812,16 @@ impl Pickle for SenderAuth {
         self.arc_verify.pickle(out);
         self.dmarc_verify.pickle(out);
         self.reverse_ip_verify.pickle(out);
+        self.sender_verify.pickle(out);
+        self.sender_verify_skip_domains.pickle(out);
+        self.sender_verify_pass_ttl.pickle(out);
+        self.sender_verify_fail_ttl.pickle(out);
+        self.sender_verify_rate.pickle(out);
+        self.sender_verify_max_concurrent.pickle(out);
+        self.sender_verify_timeout.pickle(out);
+        self.reverse_ip_require.pickle(out);
+        self.reverse_ip_require_temp_fail.pickle(out);
+        self.reverse_ip_timeout.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -35317,6 +37834,36 @@ impl Pickle for SenderAuth {
         this.arc_verify = Pickle::unpickle(stream)?;
         this.dmarc_verify = Pickle::unpickle(stream)?;
         this.reverse_ip_verify = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.sender_verify = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_skip_domains = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_pass_ttl = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_fail_ttl = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_rate = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_max_concurrent = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.sender_verify_timeout = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.reverse_ip_require = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.reverse_ip_require_temp_fail = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.reverse_ip_timeout = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -35331,11 +37878,13 @@ impl Default for SenderAuth {
                         .to_string(),
                     then: "sender_domain".to_string(),
                 }]),
+                ..Default::default()
             },
             dkim_strict: true,
             dkim_verify: Expression {
                 else_: "relaxed".to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             },
             spf_ehlo_verify: Expression {
                 else_: "disable".to_string(),
@@ -35343,6 +37892,7 @@ impl Default for SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             },
             spf_from_verify: Expression {
                 else_: "disable".to_string(),
@@ -35350,10 +37900,12 @@ impl Default for SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             },
             arc_verify: Expression {
                 else_: "disable".to_string(),
                 match_: List::from_iter([]),
+                ..Default::default()
             },
             dmarc_verify: Expression {
                 else_: "disable".to_string(),
@@ -35361,6 +37913,7 @@ impl Default for SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             },
             reverse_ip_verify: Expression {
                 else_: "disable".to_string(),
@@ -35368,14 +37921,36 @@ impl Default for SenderAuth {
                     if_: "local_port == 25".to_string(),
                     then: "relaxed".to_string(),
                 }]),
+                ..Default::default()
             },
+            sender_verify: Expression {
+                else_: "disable".to_string(),
+                match_: List::from_iter([]),
+                ..Default::default()
+            },
+            sender_verify_skip_domains: Default::default(),
+            sender_verify_pass_ttl: Duration::from_millis(86400000),
+            sender_verify_fail_ttl: Duration::from_millis(7200000),
+            sender_verify_rate: Some(Rate {
+                count: 10u64,
+                period: Duration::from_millis(60000),
+            }),
+            sender_verify_max_concurrent: 16u64,
+            sender_verify_timeout: Duration::from_millis(30000),
+            reverse_ip_require: Expression {
+                else_: "false".to_string(),
+                match_: List::from_iter([]),
+                ..Default::default()
+            },
+            reverse_ip_require_temp_fail: false,
+            reverse_ip_timeout: Duration::from_millis(10000),
         }
     }
 }
 
 impl IntoValue for SenderAuth {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(10);
+        let mut map = jmap_tools::Map::with_capacity(20);
         map.insert_unchecked(Property::DkimSignDomain, self.dkim_sign_domain.into_value());
         map.insert_unchecked(Property::DkimStrict, self.dkim_strict.into_value());
         map.insert_unchecked(Property::DkimVerify, self.dkim_verify.into_value());
@@ -35387,6 +37962,43 @@ impl IntoValue for SenderAuth {
             Property::ReverseIpVerify,
             self.reverse_ip_verify.into_value(),
         );
+        map.insert_unchecked(Property::SenderVerify, self.sender_verify.into_value());
+        map.insert_unchecked(
+            Property::SenderVerifySkipDomains,
+            self.sender_verify_skip_domains.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SenderVerifyPassTtl,
+            self.sender_verify_pass_ttl.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SenderVerifyFailTtl,
+            self.sender_verify_fail_ttl.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SenderVerifyRate,
+            self.sender_verify_rate.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SenderVerifyMaxConcurrent,
+            self.sender_verify_max_concurrent.into_value(),
+        );
+        map.insert_unchecked(
+            Property::SenderVerifyTimeout,
+            self.sender_verify_timeout.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReverseIpRequire,
+            self.reverse_ip_require.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReverseIpRequireTempFail,
+            self.reverse_ip_require_temp_fail.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReverseIpTimeout,
+            self.reverse_ip_timeout.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -35406,6 +38018,26 @@ impl RegistryJsonPropertyPatch for SenderAuth {
             Some(Property::ArcVerify) => self.arc_verify.patch(pointer, value),
             Some(Property::DmarcVerify) => self.dmarc_verify.patch(pointer, value),
             Some(Property::ReverseIpVerify) => self.reverse_ip_verify.patch(pointer, value),
+            Some(Property::SenderVerify) => self.sender_verify.patch(pointer, value),
+            Some(Property::SenderVerifySkipDomains) => self
+                .sender_verify_skip_domains
+                .patch(pointer.with_validators(&[StringValidator::Domain]), value),
+            Some(Property::SenderVerifyPassTtl) => {
+                self.sender_verify_pass_ttl.patch(pointer, value)
+            }
+            Some(Property::SenderVerifyFailTtl) => {
+                self.sender_verify_fail_ttl.patch(pointer, value)
+            }
+            Some(Property::SenderVerifyRate) => self.sender_verify_rate.patch(pointer, value),
+            Some(Property::SenderVerifyMaxConcurrent) => {
+                self.sender_verify_max_concurrent.patch(pointer, value)
+            }
+            Some(Property::SenderVerifyTimeout) => self.sender_verify_timeout.patch(pointer, value),
+            Some(Property::ReverseIpRequire) => self.reverse_ip_require.patch(pointer, value),
+            Some(Property::ReverseIpRequireTempFail) => {
+                self.reverse_ip_require_temp_fail.patch(pointer, value)
+            }
+            Some(Property::ReverseIpTimeout) => self.reverse_ip_timeout.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -35770,7 +38402,7 @@ impl RegistryJsonPropertyPatch for Sharing {
 
 impl ObjectImpl for SieveSystemInterpreter {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::SieveSystemInterpreter;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -35887,6 +38519,8 @@ impl Pickle for SieveSystemInterpreter {
         self.max_received_headers.pickle(out);
         self.max_redirects.pickle(out);
         self.max_var_size.pickle(out);
+        self.max_redirects_per_day.pickle(out);
+        self.max_redirect_destinations.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -35904,6 +38538,12 @@ impl Pickle for SieveSystemInterpreter {
         this.max_received_headers = Pickle::unpickle(stream)?;
         this.max_redirects = Pickle::unpickle(stream)?;
         this.max_var_size = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.max_redirects_per_day = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.max_redirect_destinations = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -35933,13 +38573,15 @@ impl Default for SieveSystemInterpreter {
             max_received_headers: 50u64,
             max_redirects: 3u64,
             max_var_size: 52428800u64,
+            max_redirects_per_day: 1000u64,
+            max_redirect_destinations: 100u64,
         }
     }
 }
 
 impl IntoValue for SieveSystemInterpreter {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(15);
+        let mut map = jmap_tools::Map::with_capacity(17);
         map.insert_unchecked(
             Property::DefaultFromAddress,
             self.default_from_address.into_value(),
@@ -35977,6 +38619,14 @@ impl IntoValue for SieveSystemInterpreter {
         );
         map.insert_unchecked(Property::MaxRedirects, self.max_redirects.into_value());
         map.insert_unchecked(Property::MaxVarSize, self.max_var_size.into_value());
+        map.insert_unchecked(
+            Property::MaxRedirectsPerDay,
+            self.max_redirects_per_day.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MaxRedirectDestinations,
+            self.max_redirect_destinations.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -36003,6 +38653,10 @@ impl RegistryJsonPropertyPatch for SieveSystemInterpreter {
             Some(Property::MaxReceivedHeaders) => self.max_received_headers.patch(pointer, value),
             Some(Property::MaxRedirects) => self.max_redirects.patch(pointer, value),
             Some(Property::MaxVarSize) => self.max_var_size.patch(pointer, value),
+            Some(Property::MaxRedirectsPerDay) => self.max_redirects_per_day.patch(pointer, value),
+            Some(Property::MaxRedirectDestinations) => {
+                self.max_redirect_destinations.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -36108,7 +38762,7 @@ impl RegistryJsonPropertyPatch for SieveSystemScript {
 
 impl ObjectImpl for SieveUserInterpreter {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 5;
     const OBJECT: ObjectType = ObjectType::SieveUserInterpreter;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -36198,6 +38852,19 @@ impl ObjectImpl for SieveUserInterpreter {
                 errors.push(ValidationError::min_value(Property::MaxScripts, 1));
             }
         }
+        let value = &self.log_max_entries;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::LogMaxEntries, 1));
+        }
+        if *value > 10000 {
+            errors.push(ValidationError::max_value(Property::LogMaxEntries, 10000));
+        }
+        let value = &self.trusted_redirect_hosts;
+        for value in value.iter() {
+            if !value.is_valid() {
+                errors.push(ValidationError::invalid(Property::TrustedRedirectHosts, value));
+            }
+        }
         errors.len() == neb
     }
 
@@ -36231,6 +38898,16 @@ impl Pickle for SieveUserInterpreter {
         self.max_var_name_length.pickle(out);
         self.max_var_size.pickle(out);
         self.max_scripts.pickle(out);
+        self.max_expiry_duplicate.pickle(out);
+        self.redirect_envelope_sender.pickle(out);
+        self.max_redirect_passes.pickle(out);
+        self.max_redirects_per_day.pickle(out);
+        self.max_redirect_destinations.pickle(out);
+        self.log_enabled.pickle(out);
+        self.log_max_entries.pickle(out);
+        self.log_retention.pickle(out);
+        self.log_header.pickle(out);
+        self.trusted_redirect_hosts.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -36260,6 +38937,36 @@ impl Pickle for SieveUserInterpreter {
         this.max_var_name_length = Pickle::unpickle(stream)?;
         this.max_var_size = Pickle::unpickle(stream)?;
         this.max_scripts = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.max_expiry_duplicate = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.redirect_envelope_sender = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.max_redirect_passes = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.max_redirects_per_day = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.max_redirect_destinations = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.log_enabled = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.log_max_entries = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.log_retention = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.log_header = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.trusted_redirect_hosts = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -36297,13 +39004,23 @@ impl Default for SieveUserInterpreter {
             max_var_name_length: 32u64,
             max_var_size: 4096u64,
             max_scripts: Some(100u64),
+            max_expiry_duplicate: Duration::from_millis(7776000000),
+            redirect_envelope_sender: SieveRedirectSender::Account,
+            max_redirect_passes: 2u64,
+            max_redirects_per_day: 100u64,
+            max_redirect_destinations: 10u64,
+            log_enabled: false,
+            log_max_entries: 100u64,
+            log_retention: Duration::from_millis(604800000),
+            log_header: false,
+            trusted_redirect_hosts: Default::default(),
         }
     }
 }
 
 impl IntoValue for SieveUserInterpreter {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(27);
+        let mut map = jmap_tools::Map::with_capacity(37);
         map.insert_unchecked(
             Property::DefaultExpiryDuplicate,
             self.default_expiry_duplicate.into_value(),
@@ -36368,6 +39085,31 @@ impl IntoValue for SieveUserInterpreter {
         );
         map.insert_unchecked(Property::MaxVarSize, self.max_var_size.into_value());
         map.insert_unchecked(Property::MaxScripts, self.max_scripts.into_value());
+        map.insert_unchecked(
+            Property::MaxExpiryDuplicate,
+            self.max_expiry_duplicate.into_value(),
+        );
+        map.insert_unchecked(
+            Property::RedirectEnvelopeSender,
+            self.redirect_envelope_sender.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MaxRedirectPasses,
+            self.max_redirect_passes.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MaxRedirectsPerDay,
+            self.max_redirects_per_day.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MaxRedirectDestinations,
+            self.max_redirect_destinations.into_value(),
+        );
+        map.insert_unchecked(Property::LogEnabled, self.log_enabled.into_value());
+        map.insert_unchecked(Property::LogMaxEntries, self.log_max_entries.into_value());
+        map.insert_unchecked(Property::LogRetention, self.log_retention.into_value());
+        map.insert_unchecked(Property::LogHeader, self.log_header.into_value());
+        map.insert_unchecked(Property::TrustedRedirectHosts, self.trusted_redirect_hosts.into_value());
         JmapValue::Object(map)
     }
 }
@@ -36418,6 +39160,22 @@ impl RegistryJsonPropertyPatch for SieveUserInterpreter {
             Some(Property::MaxVarNameLength) => self.max_var_name_length.patch(pointer, value),
             Some(Property::MaxVarSize) => self.max_var_size.patch(pointer, value),
             Some(Property::MaxScripts) => self.max_scripts.patch(pointer, value),
+            Some(Property::MaxExpiryDuplicate) => self.max_expiry_duplicate.patch(pointer, value),
+            Some(Property::RedirectEnvelopeSender) => {
+                self.redirect_envelope_sender.patch(pointer, value)
+            }
+            Some(Property::MaxRedirectPasses) => self.max_redirect_passes.patch(pointer, value),
+            Some(Property::MaxRedirectsPerDay) => self.max_redirects_per_day.patch(pointer, value),
+            Some(Property::MaxRedirectDestinations) => {
+                self.max_redirect_destinations.patch(pointer, value)
+            }
+            Some(Property::LogEnabled) => self.log_enabled.patch(pointer, value),
+            Some(Property::LogMaxEntries) => self.log_max_entries.patch(pointer, value),
+            Some(Property::LogRetention) => self.log_retention.patch(pointer, value),
+            Some(Property::LogHeader) => self.log_header.patch(pointer, value),
+            Some(Property::TrustedRedirectHosts) => self
+                .trusted_redirect_hosts
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -36521,6 +39279,117 @@ impl RegistryJsonPropertyPatch for SieveUserScript {
     }
 }
 
+impl ObjectImpl for SpamClamAv {
+    const FLAGS: u64 = OBJ_SINGLETON;
+    const VERSION: u8 = 0;
+    const OBJECT: ObjectType = ObjectType::SpamClamAv;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.address;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Address));
+        }
+        let value = &self.max_size;
+        if *value < 1024 {
+            errors.push(ValidationError::min_value(Property::MaxSize, 1024));
+        }
+        let value = &self.pool_max_connections;
+        if *value > 8192 {
+            errors.push(ValidationError::max_value(
+                Property::PoolMaxConnections,
+                8192,
+            ));
+        }
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::PoolMaxConnections, 1));
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, _: &mut IndexBuilder<'x>) {}
+}
+
+impl Pickle for SpamClamAv {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.enable.pickle(out);
+        self.address.pickle(out);
+        self.timeout.pickle(out);
+        self.max_size.pickle(out);
+        self.pool_max_connections.pickle(out);
+        self.action.pickle(out);
+        self.fail_open.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.enable = Pickle::unpickle(stream)?;
+        this.address = Pickle::unpickle(stream)?;
+        this.timeout = Pickle::unpickle(stream)?;
+        this.max_size = Pickle::unpickle(stream)?;
+        this.pool_max_connections = Pickle::unpickle(stream)?;
+        this.action = Pickle::unpickle(stream)?;
+        this.fail_open = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for SpamClamAv {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            address: "127.0.0.1:3310".to_string(),
+            timeout: Duration::from_millis(30000),
+            max_size: 26214400u64,
+            pool_max_connections: 10u64,
+            action: SpamVirusAction::Reject,
+            fail_open: true,
+        }
+    }
+}
+
+impl IntoValue for SpamClamAv {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(9);
+        map.insert_unchecked(Property::Enable, self.enable.into_value());
+        map.insert_unchecked(Property::Address, self.address.into_value());
+        map.insert_unchecked(Property::Timeout, self.timeout.into_value());
+        map.insert_unchecked(Property::MaxSize, self.max_size.into_value());
+        map.insert_unchecked(
+            Property::PoolMaxConnections,
+            self.pool_max_connections.into_value(),
+        );
+        map.insert_unchecked(Property::Action, self.action.into_value());
+        map.insert_unchecked(Property::FailOpen, self.fail_open.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for SpamClamAv {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::Enable) => self.enable.patch(pointer, value),
+            Some(Property::Address) => self
+                .address
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::Timeout) => self.timeout.patch(pointer, value),
+            Some(Property::MaxSize) => self.max_size.patch(pointer, value),
+            Some(Property::PoolMaxConnections) => self.pool_max_connections.patch(pointer, value),
+            Some(Property::Action) => self.action.patch(pointer, value),
+            Some(Property::FailOpen) => self.fail_open.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for SpamClassifier {
     const FLAGS: u64 = OBJ_SINGLETON;
     const VERSION: u8 = 0;
@@ -37138,7 +40007,7 @@ impl RegistryJsonPropertyPatch for SpamClassifyTag {
 
 impl ObjectImpl for SpamDnsblServer {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::SpamDnsblServer;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -38746,7 +41615,7 @@ impl RegistryJsonPropertyPatch for SpamPyzor {
 
 impl ObjectImpl for SpamRule {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::SpamRule;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -39760,7 +42629,7 @@ impl RegistryJsonPropertyPatch for SpamRuleUrl {
 
 impl ObjectImpl for SpamSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::SpamSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -39791,6 +42660,72 @@ impl ObjectImpl for SpamSettings {
                 errors.push(ValidationError::required(Property::SpamFilterRulesUrl));
             }
         }
+        let value = &self.trusted_forwarders;
+        for value in value.iter() {
+            if !value.is_valid() {
+                errors.push(ValidationError::invalid(Property::TrustedForwarders, value));
+            }
+        }
+        let value = &self.trusted_forwarder_depth;
+        if *value > 16 {
+            errors.push(ValidationError::max_value(
+                Property::TrustedForwarderDepth,
+                16,
+            ));
+        }
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::TrustedForwarderDepth,
+                1,
+            ));
+        }
+        self.rspamd_api_secret.validate(errors);
+        if self.rspamd_api_max_concurrent < 1 {
+            errors.push(ValidationError::min_value(
+                Property::RspamdApiMaxConcurrent,
+                1,
+            ));
+        }
+        let value = &self.archive_max_depth;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::ArchiveMaxDepth, 1));
+        }
+        if *value > 10 {
+            errors.push(ValidationError::max_value(Property::ArchiveMaxDepth, 10));
+        }
+        let value = &self.archive_max_entries;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(Property::ArchiveMaxEntries, 1));
+        }
+        let value = &self.archive_max_ratio;
+        if *value < 2 {
+            errors.push(ValidationError::min_value(Property::ArchiveMaxRatio, 2));
+        }
+        let value = &self.archive_max_size;
+        if *value < 1024 {
+            errors.push(ValidationError::min_value(Property::ArchiveMaxSize, 1024));
+        }
+        let value = &self.reputation_min_messages;
+        if *value < 1 {
+            errors.push(ValidationError::min_value(
+                Property::ReputationMinMessages,
+                1,
+            ));
+        }
+        let value = &self.reputation_good_score;
+        if *value > Float::new(1.0) {
+            errors.push(ValidationError::max_value(Property::ReputationGoodScore, 1));
+        }
+        if *value < Float::new(0.0) {
+            errors.push(ValidationError::min_value(Property::ReputationGoodScore, 0));
+        }
+        let value = &self.reputation_bad_score;
+        if *value > Float::new(1.0) {
+            errors.push(ValidationError::max_value(Property::ReputationBadScore, 1));
+        }
+        if *value < Float::new(0.0) {
+            errors.push(ValidationError::min_value(Property::ReputationBadScore, 0));
+        }
         errors.len() == neb
     }
 
@@ -39807,6 +42742,22 @@ impl Pickle for SpamSettings {
         self.score_spam.pickle(out);
         self.trust_replies.pickle(out);
         self.spam_filter_rules_url.pickle(out);
+        self.trusted_forwarders.pickle(out);
+        self.trusted_forwarder_depth.pickle(out);
+        self.enable_rspamd_api.pickle(out);
+        self.rspamd_api_secret.pickle(out);
+        self.rspamd_api_max_concurrent.pickle(out);
+        self.archive_max_depth.pickle(out);
+        self.archive_max_entries.pickle(out);
+        self.archive_max_ratio.pickle(out);
+        self.archive_max_size.pickle(out);
+        self.archive_scan_timeout.pickle(out);
+        self.reputation_enable.pickle(out);
+        self.reputation_window.pickle(out);
+        self.reputation_min_messages.pickle(out);
+        self.reputation_good_score.pickle(out);
+        self.reputation_bad_score.pickle(out);
+        self.reputation_skip_greylist.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -39819,6 +42770,54 @@ impl Pickle for SpamSettings {
         this.score_spam = Pickle::unpickle(stream)?;
         this.trust_replies = Pickle::unpickle(stream)?;
         this.spam_filter_rules_url = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.trusted_forwarders = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.trusted_forwarder_depth = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.enable_rspamd_api = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.rspamd_api_secret = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.rspamd_api_max_concurrent = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.archive_max_depth = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.archive_max_entries = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.archive_max_ratio = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.archive_max_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.archive_scan_timeout = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_enable = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_window = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_min_messages = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_good_score = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_bad_score = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.reputation_skip_greylist = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -39834,13 +42833,29 @@ impl Default for SpamSettings {
             score_spam: Float::new(5.0f64),
             trust_replies: true,
             spam_filter_rules_url: Some("https://github.com/stalwartlabs/spam-filter/releases/latest/download/spam-filter-rules.json.gz".to_string()),
+            trusted_forwarders: Default::default(),
+            trusted_forwarder_depth: 1,
+            enable_rspamd_api: false,
+            rspamd_api_secret: SecretKeyOptional::None,
+            rspamd_api_max_concurrent: 16,
+            archive_max_depth: 3u64,
+            archive_max_entries: 1000u64,
+            archive_max_ratio: 100u64,
+            archive_max_size: 10485760u64,
+            archive_scan_timeout: Duration::from_millis(1000),
+            reputation_enable: false,
+            reputation_window: Duration::from_millis(2592000000),
+            reputation_min_messages: 10u64,
+            reputation_good_score: Float::new(0.9f64),
+            reputation_bad_score: Float::new(0.3f64),
+            reputation_skip_greylist: true,
         }
     }
 }
 
 impl IntoValue for SpamSettings {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(10);
+        let mut map = jmap_tools::Map::with_capacity(26);
         map.insert_unchecked(Property::TrustContacts, self.trust_contacts.into_value());
         map.insert_unchecked(Property::Enable, self.enable.into_value());
         map.insert_unchecked(Property::GreylistFor, self.greylist_for.into_value());
@@ -39852,6 +42867,67 @@ impl IntoValue for SpamSettings {
             Property::SpamFilterRulesUrl,
             self.spam_filter_rules_url.into_value(),
         );
+        map.insert_unchecked(
+            Property::TrustedForwarders,
+            self.trusted_forwarders.into_value(),
+        );
+        map.insert_unchecked(
+            Property::TrustedForwarderDepth,
+            self.trusted_forwarder_depth.into_value(),
+        );
+        map.insert_unchecked(
+            Property::EnableRspamdApi,
+            self.enable_rspamd_api.into_value(),
+        );
+        map.insert_unchecked(
+            Property::RspamdApiSecret,
+            self.rspamd_api_secret.into_value(),
+        );
+        map.insert_unchecked(
+            Property::RspamdApiMaxConcurrent,
+            self.rspamd_api_max_concurrent.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ArchiveMaxDepth,
+            self.archive_max_depth.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ArchiveMaxEntries,
+            self.archive_max_entries.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ArchiveMaxRatio,
+            self.archive_max_ratio.into_value(),
+        );
+        map.insert_unchecked(Property::ArchiveMaxSize, self.archive_max_size.into_value());
+        map.insert_unchecked(
+            Property::ArchiveScanTimeout,
+            self.archive_scan_timeout.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationEnable,
+            self.reputation_enable.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationWindow,
+            self.reputation_window.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationMinMessages,
+            self.reputation_min_messages.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationGoodScore,
+            self.reputation_good_score.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationBadScore,
+            self.reputation_bad_score.into_value(),
+        );
+        map.insert_unchecked(
+            Property::ReputationSkipGreylist,
+            self.reputation_skip_greylist.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -39873,6 +42949,32 @@ impl RegistryJsonPropertyPatch for SpamSettings {
             Some(Property::SpamFilterRulesUrl) => self
                 .spam_filter_rules_url
                 .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::TrustedForwarders) => self
+                .trusted_forwarders
+                .patch(pointer.with_validators(&[StringValidator::Trim]), value),
+            Some(Property::TrustedForwarderDepth) => {
+                self.trusted_forwarder_depth.patch(pointer, value)
+            }
+            Some(Property::EnableRspamdApi) => self.enable_rspamd_api.patch(pointer, value),
+            Some(Property::RspamdApiSecret) => self.rspamd_api_secret.patch(pointer, value),
+            Some(Property::RspamdApiMaxConcurrent) => {
+                self.rspamd_api_max_concurrent.patch(pointer, value)
+            }
+            Some(Property::ArchiveMaxDepth) => self.archive_max_depth.patch(pointer, value),
+            Some(Property::ArchiveMaxEntries) => self.archive_max_entries.patch(pointer, value),
+            Some(Property::ArchiveMaxRatio) => self.archive_max_ratio.patch(pointer, value),
+            Some(Property::ArchiveMaxSize) => self.archive_max_size.patch(pointer, value),
+            Some(Property::ArchiveScanTimeout) => self.archive_scan_timeout.patch(pointer, value),
+            Some(Property::ReputationEnable) => self.reputation_enable.patch(pointer, value),
+            Some(Property::ReputationWindow) => self.reputation_window.patch(pointer, value),
+            Some(Property::ReputationMinMessages) => {
+                self.reputation_min_messages.patch(pointer, value)
+            }
+            Some(Property::ReputationGoodScore) => self.reputation_good_score.patch(pointer, value),
+            Some(Property::ReputationBadScore) => self.reputation_bad_score.patch(pointer, value),
+            Some(Property::ReputationSkipGreylist) => {
+                self.reputation_skip_greylist.patch(pointer, value)
+            }
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -40263,7 +43365,7 @@ impl RegistryJsonPropertyPatch for SpamTrainingSample {
 
 impl ObjectImpl for SpfReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::SpfReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -40633,6 +43735,8 @@ impl Pickle for SqlDirectory {
         self.query_member_of.pickle(out);
         self.query_email_aliases.pickle(out);
         self.member_tenant_id.pickle(out);
+        self.column_enabled_protocols.pickle(out);
+        self.column_categorize_mail.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -40648,6 +43752,12 @@ impl Pickle for SqlDirectory {
         this.query_member_of = Pickle::unpickle(stream)?;
         this.query_email_aliases = Pickle::unpickle(stream)?;
         this.member_tenant_id = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.column_enabled_protocols = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.column_categorize_mail = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -40666,13 +43776,15 @@ impl Default for SqlDirectory {
             query_member_of: Some("SELECT member_of FROM group_members WHERE name = $1".to_string()),
             query_email_aliases: Some("SELECT address FROM emails WHERE name = $1".to_string()),
             member_tenant_id: Default::default(),
+            column_enabled_protocols: None,
+            column_categorize_mail: None,
         }
     }
 }
 
 impl IntoValue for SqlDirectory {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(13);
+        let mut map = jmap_tools::Map::with_capacity(15);
         map.insert_unchecked(Property::Description, self.description.into_value());
         map.insert_unchecked(Property::Store, self.store.into_value());
         map.insert_unchecked(Property::ColumnEmail, self.column_email.into_value());
@@ -40690,6 +43802,8 @@ impl IntoValue for SqlDirectory {
             self.query_email_aliases.into_value(),
         );
         map.insert_unchecked(Property::MemberTenantId, self.member_tenant_id.into_value());
+        map.insert_unchecked(Property::ColumnEnabledProtocols, self.column_enabled_protocols.into_value());
+        map.insert_unchecked(Property::ColumnCategorizeMail, self.column_categorize_mail.into_value());
         JmapValue::Object(map)
     }
 }
@@ -40732,6 +43846,8 @@ impl RegistryJsonPropertyPatch for SqlDirectory {
             Some(Property::MemberTenantId) => self
                 .member_tenant_id
                 .patch(pointer.assert_can_set_tenant()?, value),
+            Some(Property::ColumnEnabledProtocols) => self.column_enabled_protocols.patch(pointer, value),
+            Some(Property::ColumnCategorizeMail) => self.column_categorize_mail.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -40832,7 +43948,7 @@ impl RegistryJsonPropertyPatch for SqliteStore {
 
 impl ObjectImpl for StoreLookup {
     const FLAGS: u64 = 0;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::StoreLookup;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -41082,7 +44198,7 @@ impl RegistryJsonPropertyPatch for SubAddressingCustom {
 
 impl ObjectImpl for SystemSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 2;
     const OBJECT: ObjectType = ObjectType::SystemSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -41152,6 +44268,8 @@ impl Pickle for SystemSettings {
         self.mail_exchangers.pickle(out);
         self.services.pickle(out);
         self.provider_info.pickle(out);
+        self.shutdown_grace_period.pickle(out);
+        self.strict_references.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -41165,6 +44283,12 @@ impl Pickle for SystemSettings {
         this.mail_exchangers = Pickle::unpickle(stream)?;
         this.services = Pickle::unpickle(stream)?;
         this.provider_info = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.shutdown_grace_period = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.strict_references = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -41241,13 +44365,15 @@ impl Default for SystemSettings {
                 ),
             ]),
             provider_info: Default::default(),
+            shutdown_grace_period: Duration::from_millis(30000),
+            strict_references: true,
         }
     }
 }
 
 impl IntoValue for SystemSettings {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(11);
+        let mut map = jmap_tools::Map::with_capacity(13);
         map.insert_unchecked(
             Property::DefaultHostname,
             self.default_hostname.into_value(),
@@ -41269,6 +44395,14 @@ impl IntoValue for SystemSettings {
         map.insert_unchecked(Property::MailExchangers, self.mail_exchangers.into_value());
         map.insert_unchecked(Property::Services, self.services.into_value());
         map.insert_unchecked(Property::ProviderInfo, self.provider_info.into_value());
+        map.insert_unchecked(
+            Property::ShutdownGracePeriod,
+            self.shutdown_grace_period.into_value(),
+        );
+        map.insert_unchecked(
+            Property::StrictReferences,
+            self.strict_references.into_value(),
+        );
         JmapValue::Object(map)
     }
 }
@@ -41295,6 +44429,8 @@ impl RegistryJsonPropertyPatch for SystemSettings {
             Some(Property::MailExchangers) => self.mail_exchangers.patch(pointer, value),
             Some(Property::Services) => self.services.patch(pointer, value),
             Some(Property::ProviderInfo) => self.provider_info.patch(pointer, value),
+            Some(Property::ShutdownGracePeriod) => self.shutdown_grace_period.patch(pointer, value),
+            Some(Property::StrictReferences) => self.strict_references.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -41329,6 +44465,15 @@ impl ObjectImpl for Task {
             Task::AcmeRenewal(inner) => inner.validate(errors),
             Task::DkimManagement(inner) => inner.validate(errors),
             Task::DnsManagement(inner) => inner.validate(errors),
+            Task::ReEncryptAccount(inner) => inner.validate(errors),
+            Task::ReindexAccount(inner) => inner.validate(errors),
+            Task::ImportMessages(inner) => inner.validate(errors),
+            Task::QuotaWarning(inner) => inner.validate(errors),
+            Task::EmailSubmission(inner) => inner.validate(errors),
+            Task::RestoreSnoozedEmail(inner) => inner.validate(errors),
+            Task::DaneRollover(inner) => inner.validate(errors),
+            Task::FollowUpReminder(inner) => inner.validate(errors),
+            Task::DeliveryCallback(inner) => inner.validate(errors),
         }
     }
 
@@ -41384,6 +44529,31 @@ impl ObjectImpl for Task {
             Task::DnsManagement(object) => {
                 object.index(i);
             }
+            Task::ReEncryptAccount(object) => {
+                object.index(i);
+            }
+            Task::ReindexAccount(object) => {
+                object.index(i);
+            }
+            Task::ImportMessages(object) => {
+                object.index(i);
+            }
+            Task::QuotaWarning(object) => {
+                object.index(i);
+            }
+            Task::EmailSubmission(object) => {
+                object.index(i);
+            }
+            Task::RestoreSnoozedEmail(object) => {
+                object.index(i);
+            }
+            Task::DaneRollover(object) => {
+                object.index(i);
+            }
+            Task::FollowUpReminder(object) => {
+                object.index(i);
+            }
+            Task::DeliveryCallback(_) => {}
         }
     }
 }
@@ -41469,6 +44639,42 @@ impl Pickle for Task {
                 17u16.pickle(out);
                 inner.pickle(out);
             }
+            Task::ReEncryptAccount(inner) => {
+                18u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::ReindexAccount(inner) => {
+                19u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::ImportMessages(inner) => {
+                20u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::QuotaWarning(inner) => {
+                21u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::EmailSubmission(inner) => {
+                22u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::RestoreSnoozedEmail(inner) => {
+                23u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::DaneRollover(inner) => {
+                24u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::FollowUpReminder(inner) => {
+                25u16.pickle(out);
+                inner.pickle(out);
+            }
+            Task::DeliveryCallback(inner) => {
+                26u16.pickle(out);
+                inner.pickle(out);
+            }
         }
     }
 
@@ -41492,6 +44698,15 @@ impl Pickle for Task {
             15 => Pickle::unpickle(stream).map(Task::AcmeRenewal),
             16 => Pickle::unpickle(stream).map(Task::DkimManagement),
             17 => Pickle::unpickle(stream).map(Task::DnsManagement),
+            18 => Pickle::unpickle(stream).map(Task::ReEncryptAccount),
+            19 => Pickle::unpickle(stream).map(Task::ReindexAccount),
+            20 => Pickle::unpickle(stream).map(Task::ImportMessages),
+            21 => Pickle::unpickle(stream).map(Task::QuotaWarning),
+            22 => Pickle::unpickle(stream).map(Task::EmailSubmission),
+            23 => Pickle::unpickle(stream).map(Task::RestoreSnoozedEmail),
+            24 => Pickle::unpickle(stream).map(Task::DaneRollover),
+            25 => Pickle::unpickle(stream).map(Task::FollowUpReminder),
+            26 => Pickle::unpickle(stream).map(Task::DeliveryCallback),
             _ => None,
         }
     }
@@ -41628,6 +44843,69 @@ impl IntoValue for Task {
                     .insert_unchecked(Property::Type, JmapValue::Str("DnsManagement".into()));
                 obj
             }
+            Task::ReEncryptAccount(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("ReEncryptAccount".into()));
+                obj
+            }
+            Task::ReindexAccount(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("ReindexAccount".into()));
+                obj
+            }
+            Task::ImportMessages(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("ImportMessages".into()));
+                obj
+            }
+            Task::QuotaWarning(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("QuotaWarning".into()));
+                obj
+            }
+            Task::EmailSubmission(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("EmailSubmission".into()));
+                obj
+            }
+            Task::RestoreSnoozedEmail(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("RestoreSnoozedEmail".into()));
+                obj
+            }
+            Task::DaneRollover(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("DaneRollover".into()));
+                obj
+            }
+            Task::FollowUpReminder(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("FollowUpReminder".into()));
+                obj
+            }
+            Task::DeliveryCallback(obj) => {
+                let mut obj = obj.into_value();
+                obj.as_object_mut()
+                    .unwrap()
+                    .insert_unchecked(Property::Type, JmapValue::Str("DeliveryCallback".into()));
+                obj
+            }
         }
     }
 }
@@ -41670,6 +44948,17 @@ impl RegistryJsonPatch for Task {
                 TaskType::AcmeRenewal => *self = Task::AcmeRenewal(Default::default()),
                 TaskType::DkimManagement => *self = Task::DkimManagement(Default::default()),
                 TaskType::DnsManagement => *self = Task::DnsManagement(Default::default()),
+                TaskType::ReEncryptAccount => *self = Task::ReEncryptAccount(Default::default()),
+                TaskType::ReindexAccount => *self = Task::ReindexAccount(Default::default()),
+                TaskType::ImportMessages => *self = Task::ImportMessages(Default::default()),
+                TaskType::QuotaWarning => *self = Task::QuotaWarning(Default::default()),
+                TaskType::EmailSubmission => *self = Task::EmailSubmission(Default::default()),
+                TaskType::RestoreSnoozedEmail => {
+                    *self = Task::RestoreSnoozedEmail(Default::default())
+                }
+                TaskType::DaneRollover => *self = Task::DaneRollover(Default::default()),
+                TaskType::FollowUpReminder => *self = Task::FollowUpReminder(Default::default()),
+                TaskType::DeliveryCallback => *self = Task::DeliveryCallback(Default::default()),
             }
         }
         match self {
@@ -41691,6 +44980,15 @@ impl RegistryJsonPatch for Task {
             Task::AcmeRenewal(inner) => inner.patch(pointer, value),
             Task::DkimManagement(inner) => inner.patch(pointer, value),
             Task::DnsManagement(inner) => inner.patch(pointer, value),
+            Task::ReEncryptAccount(inner) => inner.patch(pointer, value),
+            Task::ReindexAccount(inner) => inner.patch(pointer, value),
+            Task::ImportMessages(inner) => inner.patch(pointer, value),
+            Task::QuotaWarning(inner) => inner.patch(pointer, value),
+            Task::EmailSubmission(inner) => inner.patch(pointer, value),
+            Task::RestoreSnoozedEmail(inner) => inner.patch(pointer, value),
+            Task::DaneRollover(inner) => inner.patch(pointer, value),
+            Task::FollowUpReminder(inner) => inner.patch(pointer, value),
+            Task::DeliveryCallback(inner) => inner.patch(pointer, value),
         }
     }
 }
@@ -41716,6 +45014,15 @@ impl Task {
             Task::AcmeRenewal(_) => TaskType::AcmeRenewal,
             Task::DkimManagement(_) => TaskType::DkimManagement,
             Task::DnsManagement(_) => TaskType::DnsManagement,
+            Task::ReEncryptAccount(_) => TaskType::ReEncryptAccount,
+            Task::ReindexAccount(_) => TaskType::ReindexAccount,
+            Task::ImportMessages(_) => TaskType::ImportMessages,
+            Task::QuotaWarning(_) => TaskType::QuotaWarning,
+            Task::EmailSubmission(_) => TaskType::EmailSubmission,
+            Task::RestoreSnoozedEmail(_) => TaskType::RestoreSnoozedEmail,
+            Task::DaneRollover(_) => TaskType::DaneRollover,
+            Task::FollowUpReminder(_) => TaskType::FollowUpReminder,
+            Task::DeliveryCallback(_) => TaskType::DeliveryCallback,
         }
     }
 }
@@ -42512,6 +45819,471 @@ impl RegistryJsonPropertyPatch for TaskDomainManagement {
     }
 }
 
+impl TaskEmailSubmission {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.document_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::DocumentId, value));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskEmailSubmission {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.document_id.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.document_id = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskEmailSubmission {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            document_id: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskEmailSubmission {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(5);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::DocumentId, self.document_id.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskEmailSubmission {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => pointer.assert_server_set(),
+            Some(Property::DocumentId) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskRestoreSnoozedEmail {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.document_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::DocumentId, value));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskRestoreSnoozedEmail {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.document_id.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.document_id = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskRestoreSnoozedEmail {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            document_id: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskRestoreSnoozedEmail {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(5);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::DocumentId, self.document_id.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskRestoreSnoozedEmail {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => pointer.assert_server_set(),
+            Some(Property::DocumentId) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskFollowUpReminder {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.document_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::DocumentId, value));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskFollowUpReminder {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.document_id.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.document_id = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskFollowUpReminder {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            document_id: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskFollowUpReminder {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(5);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::DocumentId, self.document_id.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskFollowUpReminder {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => pointer.assert_server_set(),
+            Some(Property::DocumentId) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskDeliveryCallback {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.callback_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::CallbackId));
+        }
+        let value = &self.body;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Body));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+}
+
+impl Pickle for TaskDeliveryCallback {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.callback_id.pickle(out);
+        self.body.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.callback_id = Pickle::unpickle(stream)?;
+        this.body = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskDeliveryCallback {
+    fn default() -> Self {
+        Self {
+            callback_id: Default::default(),
+            body: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskDeliveryCallback {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(5);
+        map.insert_unchecked(Property::CallbackId, self.callback_id.into_value());
+        map.insert_unchecked(Property::Body, self.body.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskDeliveryCallback {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::CallbackId) => pointer.assert_server_set(),
+            Some(Property::Body) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskImportMessages {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        if let Some(value) = &self.path
+            && value.is_empty()
+        {
+            errors.push(ValidationError::min_length(Property::Path, 1));
+        }
+        if let Some(value) = &self.target_mailbox
+            && value.is_empty()
+        {
+            errors.push(ValidationError::min_length(Property::TargetMailbox, 1));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskImportMessages {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.format.pickle(out);
+        self.path.pickle(out);
+        self.blob_id.pickle(out);
+        self.target_mailbox.pickle(out);
+        self.deduplicate.pickle(out);
+        self.messages_total.pickle(out);
+        self.messages_processed.pickle(out);
+        self.messages_imported.pickle(out);
+        self.messages_skipped.pickle(out);
+        self.messages_failed.pickle(out);
+        self.import_errors.pickle(out);
+        self.resume_after.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.format = Pickle::unpickle(stream)?;
+        this.path = Pickle::unpickle(stream)?;
+        this.blob_id = Pickle::unpickle(stream)?;
+        this.target_mailbox = Pickle::unpickle(stream)?;
+        this.deduplicate = Pickle::unpickle(stream)?;
+        this.messages_total = Pickle::unpickle(stream)?;
+        this.messages_processed = Pickle::unpickle(stream)?;
+        this.messages_imported = Pickle::unpickle(stream)?;
+        this.messages_skipped = Pickle::unpickle(stream)?;
+        this.messages_failed = Pickle::unpickle(stream)?;
+        this.import_errors = Pickle::unpickle(stream)?;
+        this.resume_after = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskImportMessages {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            format: Default::default(),
+            path: Default::default(),
+            blob_id: Default::default(),
+            target_mailbox: Default::default(),
+            deduplicate: true,
+            messages_total: Default::default(),
+            messages_processed: Default::default(),
+            messages_imported: Default::default(),
+            messages_skipped: Default::default(),
+            messages_failed: Default::default(),
+            import_errors: Default::default(),
+            resume_after: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskImportMessages {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(16);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::Format, self.format.into_value());
+        map.insert_unchecked(Property::Path, self.path.into_value());
+        map.insert_unchecked(Property::BlobId, self.blob_id.into_value());
+        map.insert_unchecked(Property::TargetMailbox, self.target_mailbox.into_value());
+        map.insert_unchecked(Property::Deduplicate, self.deduplicate.into_value());
+        map.insert_unchecked(Property::MessagesTotal, self.messages_total.into_value());
+        map.insert_unchecked(
+            Property::MessagesProcessed,
+            self.messages_processed.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MessagesImported,
+            self.messages_imported.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MessagesSkipped,
+            self.messages_skipped.into_value(),
+        );
+        map.insert_unchecked(Property::MessagesFailed, self.messages_failed.into_value());
+        map.insert_unchecked(Property::ImportErrors, self.import_errors.into_value());
+        map.insert_unchecked(Property::ResumeAfter, self.resume_after.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskImportMessages {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => self
+                .account_id
+                .patch(pointer.assert_read_only()?.assert_can_set_account()?, value),
+            Some(Property::Format) => self.format.patch(pointer.assert_read_only()?, value),
+            Some(Property::Path) => self.path.patch(
+                pointer
+                    .assert_read_only()?
+                    .with_validators(&[StringValidator::Trim]),
+                value,
+            ),
+            Some(Property::BlobId) => self.blob_id.patch(pointer.assert_read_only()?, value),
+            Some(Property::TargetMailbox) => self.target_mailbox.patch(
+                pointer
+                    .assert_read_only()?
+                    .with_validators(&[StringValidator::Trim]),
+                value,
+            ),
+            Some(Property::Deduplicate) => {
+                self.deduplicate.patch(pointer.assert_read_only()?, value)
+            }
+            Some(Property::MessagesTotal) => pointer.assert_server_set(),
+            Some(Property::MessagesProcessed) => pointer.assert_server_set(),
+            Some(Property::MessagesImported) => pointer.assert_server_set(),
+            Some(Property::MessagesSkipped) => pointer.assert_server_set(),
+            Some(Property::MessagesFailed) => pointer.assert_server_set(),
+            Some(Property::ImportErrors) => pointer.assert_server_set(),
+            Some(Property::ResumeAfter) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl TaskIndexDocument {
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
         let neb = errors.len();
@@ -42830,6 +46602,316 @@ impl RegistryJsonPropertyPatch for TaskMergeThreads {
     }
 }
 
+impl TaskQuotaWarning {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskQuotaWarning {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.threshold.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.threshold = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskQuotaWarning {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            threshold: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskQuotaWarning {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(5);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::Threshold, self.threshold.into_value());
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskQuotaWarning {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => pointer.assert_server_set(),
+            Some(Property::Threshold) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskReEncryptAccount {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        if let Some(value) = &self.concurrency {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::Concurrency, 1));
+            }
+            if *value > 64 {
+                errors.push(ValidationError::max_value(Property::Concurrency, 64));
+            }
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskReEncryptAccount {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.last_document_id.pickle(out);
+        self.messages_total.pickle(out);
+        self.messages_processed.pickle(out);
+        self.messages_re_encrypted.pickle(out);
+        self.messages_skipped.pickle(out);
+        self.status.pickle(out);
+        self.concurrency.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.last_document_id = Pickle::unpickle(stream)?;
+        this.messages_total = Pickle::unpickle(stream)?;
+        this.messages_processed = Pickle::unpickle(stream)?;
+        this.messages_re_encrypted = Pickle::unpickle(stream)?;
+        this.messages_skipped = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        this.concurrency = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskReEncryptAccount {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            last_document_id: Default::default(),
+            messages_total: Default::default(),
+            messages_processed: Default::default(),
+            messages_re_encrypted: Default::default(),
+            messages_skipped: Default::default(),
+            status: Default::default(),
+            concurrency: None,
+        }
+    }
+}
+
+impl IntoValue for TaskReEncryptAccount {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(10);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::LastDocumentId, self.last_document_id.into_value());
+        map.insert_unchecked(Property::MessagesTotal, self.messages_total.into_value());
+        map.insert_unchecked(
+            Property::MessagesProcessed,
+            self.messages_processed.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MessagesReEncrypted,
+            self.messages_re_encrypted.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MessagesSkipped,
+            self.messages_skipped.into_value(),
+        );
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        map.insert_unchecked(Property::Concurrency, self.concurrency.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskReEncryptAccount {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => self
+                .account_id
+                .patch(pointer.assert_read_only()?.assert_can_set_account()?, value),
+            Some(Property::LastDocumentId) => pointer.assert_server_set(),
+            Some(Property::MessagesTotal) => pointer.assert_server_set(),
+            Some(Property::MessagesProcessed) => pointer.assert_server_set(),
+            Some(Property::MessagesReEncrypted) => pointer.assert_server_set(),
+            Some(Property::MessagesSkipped) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Concurrency) => self.concurrency.patch(pointer, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
+impl TaskReindexAccount {
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.account_id;
+        if !value.is_valid() {
+            errors.push(ValidationError::required(Property::AccountId));
+        }
+        if let Some(value) = &self.mailbox_id
+            && !value.is_valid()
+        {
+            errors.push(ValidationError::invalid(Property::MailboxId, value));
+        }
+        let value = &self.status;
+        value.validate(errors);
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Account, self.account_id.into(), None);
+    }
+}
+
+impl Pickle for TaskReindexAccount {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.account_id.pickle(out);
+        self.mailbox_id.pickle(out);
+        self.received_after.pickle(out);
+        self.received_before.pickle(out);
+        self.last_document_id.pickle(out);
+        self.messages_total.pickle(out);
+        self.messages_processed.pickle(out);
+        self.messages_indexed.pickle(out);
+        self.status.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.account_id = Pickle::unpickle(stream)?;
+        this.mailbox_id = Pickle::unpickle(stream)?;
+        this.received_after = Pickle::unpickle(stream)?;
+        this.received_before = Pickle::unpickle(stream)?;
+        this.last_document_id = Pickle::unpickle(stream)?;
+        this.messages_total = Pickle::unpickle(stream)?;
+        this.messages_processed = Pickle::unpickle(stream)?;
+        this.messages_indexed = Pickle::unpickle(stream)?;
+        this.status = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for TaskReindexAccount {
+    fn default() -> Self {
+        Self {
+            account_id: Default::default(),
+            mailbox_id: Default::default(),
+            received_after: Default::default(),
+            received_before: Default::default(),
+            last_document_id: Default::default(),
+            messages_total: Default::default(),
+            messages_processed: Default::default(),
+            messages_indexed: Default::default(),
+            status: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for TaskReindexAccount {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(11);
+        map.insert_unchecked(Property::AccountId, self.account_id.into_value());
+        map.insert_unchecked(Property::MailboxId, self.mailbox_id.into_value());
+        map.insert_unchecked(Property::ReceivedAfter, self.received_after.into_value());
+        map.insert_unchecked(Property::ReceivedBefore, self.received_before.into_value());
+        map.insert_unchecked(Property::LastDocumentId, self.last_document_id.into_value());
+        map.insert_unchecked(Property::MessagesTotal, self.messages_total.into_value());
+        map.insert_unchecked(
+            Property::MessagesProcessed,
+            self.messages_processed.into_value(),
+        );
+        map.insert_unchecked(
+            Property::MessagesIndexed,
+            self.messages_indexed.into_value(),
+        );
+        map.insert_unchecked(Property::Status, self.status.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for TaskReindexAccount {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::AccountId) => self
+                .account_id
+                .patch(pointer.assert_read_only()?.assert_can_set_account()?, value),
+            Some(Property::MailboxId) => self.mailbox_id.patch(pointer.assert_read_only()?, value),
+            Some(Property::ReceivedAfter) => self
+                .received_after
+                .patch(pointer.assert_read_only()?, value),
+            Some(Property::ReceivedBefore) => self
+                .received_before
+                .patch(pointer.assert_read_only()?, value),
+            Some(Property::LastDocumentId) => pointer.assert_server_set(),
+            Some(Property::MessagesTotal) => pointer.assert_server_set(),
+            Some(Property::MessagesProcessed) => pointer.assert_server_set(),
+            Some(Property::MessagesIndexed) => pointer.assert_server_set(),
+            Some(Property::Status) => self.status.patch(pointer, value),
+            Some(Property::Due) => pointer.assert_server_set(),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl TaskRestoreArchivedItem {
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
         let neb = errors.len();
@@ -43758,7 +47840,7 @@ impl RegistryJsonPropertyPatch for TaskTlsReport {
 
 impl ObjectImpl for Tenant {
     const FLAGS: u64 = OBJ_SEQ_ID;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 1;
     const OBJECT: ObjectType = ObjectType::Tenant;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -43780,6 +47862,29 @@ impl ObjectImpl for Tenant {
         value.validate(errors);
         let value = &self.permissions;
         value.validate(errors);
+        if let Some(value) = &self.dkim_sign_domain {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::DkimSignDomain));
+            }
+        }
+        if let Some(value) = &self.report_from_address {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::ReportFromAddress));
+            }
+        }
+        if let Some(value) = &self.max_message_size {
+            if *value < 1024 {
+                errors.push(ValidationError::min_value(Property::MaxMessageSize, 1024));
+            }
+        }
+        if let Some(value) = &self.spam_threshold {
+            if *value > Float::new(100.0) {
+                errors.push(ValidationError::max_value(Property::SpamThreshold, 100));
+            }
+            if *value < Float::new(-100.0) {
+                errors.push(ValidationError::min_value(Property::SpamThreshold, -100));
+            }
+        }
         errors.len() == neb
     }
 
@@ -43797,6 +47902,10 @@ impl Pickle for Tenant {
         self.roles.pickle(out);
         self.permissions.pickle(out);
         self.quotas.pickle(out);
+        self.dkim_sign_domain.pickle(out);
+        self.report_from_address.pickle(out);
+        self.max_message_size.pickle(out);
+        self.spam_threshold.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -43807,6 +47916,18 @@ impl Pickle for Tenant {
         this.roles = Pickle::unpickle(stream)?;
         this.permissions = Pickle::unpickle(stream)?;
         this.quotas = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.dkim_sign_domain = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.report_from_address = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.max_message_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 1 {
+            this.spam_threshold = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -43820,19 +47941,30 @@ impl Default for Tenant {
             roles: Default::default(),
             permissions: Default::default(),
             quotas: Default::default(),
+            dkim_sign_domain: None,
+            report_from_address: None,
+            max_message_size: None,
+            spam_threshold: None,
         }
     }
 }
 
 impl IntoValue for Tenant {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(8);
+        let mut map = jmap_tools::Map::with_capacity(12);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::CreatedAt, self.created_at.into_value());
         map.insert_unchecked(Property::Logo, self.logo.into_value());
         map.insert_unchecked(Property::Roles, self.roles.into_value());
         map.insert_unchecked(Property::Permissions, self.permissions.into_value());
         map.insert_unchecked(Property::Quotas, self.quotas.into_value());
+        map.insert_unchecked(Property::DkimSignDomain, self.dkim_sign_domain.into_value());
+        map.insert_unchecked(
+            Property::ReportFromAddress,
+            self.report_from_address.into_value(),
+        );
+        map.insert_unchecked(Property::MaxMessageSize, self.max_message_size.into_value());
+        map.insert_unchecked(Property::SpamThreshold, self.spam_threshold.into_value());
         JmapValue::Object(map)
     }
 }
@@ -43851,6 +47983,14 @@ impl RegistryJsonPropertyPatch for Tenant {
             Some(Property::Permissions) => self.permissions.patch(pointer, value),
             Some(Property::Quotas) => self.quotas.patch(pointer, value),
             Some(Property::UsedDiskQuota) => pointer.assert_server_set(),
+            Some(Property::DkimSignDomain) => self
+                .dkim_sign_domain
+                .patch(pointer.with_validators(&[StringValidator::Domain]), value),
+            Some(Property::ReportFromAddress) => self
+                .report_from_address
+                .patch(pointer.with_validators(&[StringValidator::Email]), value),
+            Some(Property::MaxMessageSize) => self.max_message_size.patch(pointer, value),
+            Some(Property::SpamThreshold) => self.spam_threshold.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -44460,7 +48600,7 @@ impl RegistryJsonPropertyPatch for TlsReportPolicy {
 
 impl ObjectImpl for TlsReportSettings {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 10;
     const OBJECT: ObjectType = ObjectType::TlsReportSettings;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -46333,7 +50473,7 @@ impl RegistryJsonPropertyPatch for TracerStdout {
 
 impl ObjectImpl for TracingStore {
     const FLAGS: u64 = OBJ_SINGLETON;
-    const VERSION: u8 = 0;
+    const VERSION: u8 = 4;
     const OBJECT: ObjectType = ObjectType::TracingStore;
 
     fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
@@ -46515,6 +50655,16 @@ impl UserAccount {
         }
         let value = &self.encryption_at_rest;
         value.validate(errors);
+        if let Some(value) = &self.max_sessions {
+            if *value < 1 {
+                errors.push(ValidationError::min_value(Property::MaxSessions, 1));
+            }
+        }
+        if let Some(value) = &self.max_message_size {
+            if *value < 1024 {
+                errors.push(ValidationError::min_value(Property::MaxMessageSize, 1024));
+            }
+        }
         errors.len() == neb
     }
 
@@ -46565,6 +50715,14 @@ impl Pickle for UserAccount {
         self.locale.pickle(out);
         self.time_zone.pickle(out);
         self.encryption_at_rest.pickle(out);
+        self.calendar_invitations.pickle(out);
+        self.max_sessions.pickle(out);
+        self.max_sessions_per_protocol.pickle(out);
+        self.credential_generation.pickle(out);
+        self.sieve_logging.pickle(out);
+        self.max_message_size.pickle(out);
+        self.enabled_protocols.pickle(out);
+        self.categorize_mail.pickle(out);
     }
 
     fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
@@ -46583,6 +50741,30 @@ impl Pickle for UserAccount {
         this.locale = Pickle::unpickle(stream)?;
         this.time_zone = Pickle::unpickle(stream)?;
         this.encryption_at_rest = Pickle::unpickle(stream)?;
+        if stream.version() >= 1 {
+            this.calendar_invitations = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.max_sessions = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 2 {
+            this.max_sessions_per_protocol = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 3 {
+            this.credential_generation = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 4 {
+            this.sieve_logging = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 5 {
+            this.max_message_size = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 6 {
+            this.enabled_protocols = Pickle::unpickle(stream)?;
+        }
+        if stream.version() >= 7 {
+            this.categorize_mail = Pickle::unpickle(stream)?;
+        }
         Some(this)
     }
 }
@@ -46604,13 +50786,21 @@ impl Default for UserAccount {
             locale: Locale::EnUS,
             time_zone: Default::default(),
             encryption_at_rest: Default::default(),
+            calendar_invitations: CalendarInvitationPolicy::Default,
+            max_sessions: None,
+            max_sessions_per_protocol: Default::default(),
+            credential_generation: 0,
+            sieve_logging: false,
+            max_message_size: None,
+            enabled_protocols: Default::default(),
+            categorize_mail: false,
         }
     }
 }
 
 impl IntoValue for UserAccount {
     fn into_value(self) -> JmapValue<'static> {
-        let mut map = jmap_tools::Map::with_capacity(16);
+        let mut map = jmap_tools::Map::with_capacity(24);
         map.insert_unchecked(Property::Name, self.name.into_value());
         map.insert_unchecked(Property::DomainId, self.domain_id.into_value());
         map.insert_unchecked(Property::Credentials, self.credentials.into_value());
@@ -46628,6 +50818,23 @@ impl IntoValue for UserAccount {
             Property::EncryptionAtRest,
             self.encryption_at_rest.into_value(),
         );
+        map.insert_unchecked(
+            Property::CalendarInvitations,
+            self.calendar_invitations.into_value(),
+        );
+        map.insert_unchecked(Property::MaxSessions, self.max_sessions.into_value());
+        map.insert_unchecked(
+            Property::MaxSessionsPerProtocol,
+            self.max_sessions_per_protocol.into_value(),
+        );
+        map.insert_unchecked(
+            Property::CredentialGeneration,
+            self.credential_generation.into_value(),
+        );
+        map.insert_unchecked(Property::SieveLogging, self.sieve_logging.into_value());
+        map.insert_unchecked(Property::MaxMessageSize, self.max_message_size.into_value());
+        map.insert_unchecked(Property::EnabledProtocols, self.enabled_protocols.into_value());
+        map.insert_unchecked(Property::CategorizeMail, self.categorize_mail.into_value());
         JmapValue::Object(map)
     }
 }
@@ -46660,6 +50867,16 @@ impl RegistryJsonPropertyPatch for UserAccount {
             Some(Property::Locale) => self.locale.patch(pointer, value),
             Some(Property::TimeZone) => self.time_zone.patch(pointer, value),
             Some(Property::EncryptionAtRest) => self.encryption_at_rest.patch(pointer, value),
+            Some(Property::CalendarInvitations) => self.calendar_invitations.patch(pointer, value),
+            Some(Property::MaxSessions) => self.max_sessions.patch(pointer, value),
+            Some(Property::MaxSessionsPerProtocol) => {
+                self.max_sessions_per_protocol.patch(pointer, value)
+            }
+            Some(Property::CredentialGeneration) => pointer.assert_server_set(),
+            Some(Property::SieveLogging) => self.sieve_logging.patch(pointer, value),
+            Some(Property::MaxMessageSize) => self.max_message_size.patch(pointer, value),
+            Some(Property::EnabledProtocols) => self.enabled_protocols.patch(pointer, value),
+            Some(Property::CategorizeMail) => self.categorize_mail.patch(pointer, value),
             Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
                 property: Property::Type,
                 value,
@@ -46776,6 +50993,119 @@ impl UserRoles {
     }
 }
 
+impl ObjectImpl for UnsubscribeEvent {
+    const FLAGS: u64 = OBJ_FILTER_TENANT;
+    const VERSION: u8 = 0;
+    const OBJECT: ObjectType = ObjectType::UnsubscribeEvent;
+
+    fn validate(&self, errors: &mut Vec<ValidationError>) -> bool {
+        let neb = errors.len();
+        let value = &self.sender;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Sender));
+        }
+        let value = &self.recipient;
+        if value.is_empty() {
+            errors.push(ValidationError::required(Property::Recipient));
+        }
+        if let Some(value) = &self.campaign_id {
+            if value.is_empty() {
+                errors.push(ValidationError::required(Property::CampaignId));
+            }
+        }
+        let value = &self.timestamp;
+        if !value.is_valid() {
+            errors.push(ValidationError::invalid(Property::Timestamp, value));
+        }
+        if let Some(value) = &self.member_tenant_id {
+            if !value.is_valid() {
+                errors.push(ValidationError::required(Property::MemberTenantId));
+            }
+        }
+        errors.len() == neb
+    }
+
+    fn index<'x>(&'x self, i: &mut IndexBuilder<'x>) {
+        i.foreign_key(ObjectType::Tenant, self.member_tenant_id, None);
+        i.search(Property::Sender, &self.sender);
+        if let Some(value) = &self.member_tenant_id {
+            i.search(Property::MemberTenantId, value);
+        }
+    }
+}
+
+impl Pickle for UnsubscribeEvent {
+    fn pickle(&self, out: &mut Vec<u8>) {
+        self.sender.pickle(out);
+        self.recipient.pickle(out);
+        self.campaign_id.pickle(out);
+        self.timestamp.pickle(out);
+        self.remote_ip.pickle(out);
+        self.member_tenant_id.pickle(out);
+    }
+
+    fn unpickle(stream: &mut crate::pickle::PickledStream<'_>) -> Option<Self> {
+        let mut this = Self::default();
+        this.sender = Pickle::unpickle(stream)?;
+        this.recipient = Pickle::unpickle(stream)?;
+        this.campaign_id = Pickle::unpickle(stream)?;
+        this.timestamp = Pickle::unpickle(stream)?;
+        this.remote_ip = Pickle::unpickle(stream)?;
+        this.member_tenant_id = Pickle::unpickle(stream)?;
+        Some(this)
+    }
+}
+
+impl Default for UnsubscribeEvent {
+    fn default() -> Self {
+        Self {
+            sender: Default::default(),
+            recipient: Default::default(),
+            campaign_id: Default::default(),
+            timestamp: Default::default(),
+            remote_ip: Default::default(),
+            member_tenant_id: Default::default(),
+        }
+    }
+}
+
+impl IntoValue for UnsubscribeEvent {
+    fn into_value(self) -> JmapValue<'static> {
+        let mut map = jmap_tools::Map::with_capacity(8);
+        map.insert_unchecked(Property::Sender, self.sender.into_value());
+        map.insert_unchecked(Property::Recipient, self.recipient.into_value());
+        map.insert_unchecked(Property::CampaignId, self.campaign_id.into_value());
+        map.insert_unchecked(Property::Timestamp, self.timestamp.into_value());
+        map.insert_unchecked(Property::RemoteIp, self.remote_ip.into_value());
+        map.insert_unchecked(Property::MemberTenantId, self.member_tenant_id.into_value());
+        JmapValue::Object(map)
+    }
+}
+
+impl RegistryJsonPropertyPatch for UnsubscribeEvent {
+    fn patch_property<'x>(
+        &mut self,
+        mut pointer: JsonPointerPatch<'_>,
+        value: JmapValue<'x>,
+    ) -> PatchResult<'x> {
+        match pointer.next_property() {
+            Some(Property::Sender) => self.sender.patch(pointer, value),
+            Some(Property::Recipient) => self.recipient.patch(pointer, value),
+            Some(Property::CampaignId) => self.campaign_id.patch(pointer, value),
+            Some(Property::Timestamp) => self.timestamp.patch(pointer, value),
+            Some(Property::RemoteIp) => self.remote_ip.patch(pointer, value),
+            Some(Property::MemberTenantId) => self
+                .member_tenant_id
+                .patch(pointer.assert_can_set_tenant()?, value),
+            Some(Property::Type) => Ok(MaybeUnpatched::Unpatched {
+                property: Property::Type,
+                value,
+            }),
+            _ => Err(PatchError::new(pointer, "Invalid property")),
+        }
+    }
+}
+
 impl ObjectImpl for WebDav {
     const FLAGS: u64 = OBJ_SINGLETON;
     const VERSION: u8 = 0;
@@ -7,7 +7,8 @@
 use types::id::Id;
 
 use crate::schema::prelude::{
-    Account, Credential, GroupAccount, PasswordCredential, SecondaryCredential, UserAccount,
+    Account, Credential, GroupAccount, PasswordCredential, SecondaryCredential, StorageQuota,
+    UserAccount, VecMap,
 };
 
 impl Account {
@@ -26,6 +27,13 @@ impl Account {
             None
         }
     }
+
+    pub fn quotas_mut(&mut self) -> &mut VecMap<StorageQuota, u64> {
+        match self {
+            Account::User(user) => &mut user.quotas,
+            Account::Group(group) => &mut group.quotas,
+        }
+    }
 }
 
 impl UserAccount {
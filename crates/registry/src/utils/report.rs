@@ -361,6 +361,8 @@ impl From<Record> for structs::DmarcReportRecord {
             dkim_results: List::from_iter(value.auth_results.dkim.into_iter().map(Into::into)),
             spf_results: List::from_iter(value.auth_results.spf.into_iter().map(Into::into)),
             extensions: List::from_iter(value.extensions.into_iter().map(Into::into)),
+            first_seen: None,
+            last_seen: None,
         }
     }
 }
@@ -986,7 +988,23 @@ impl From<&mail_auth::dmarc::Policy> for enums::DmarcDisposition {
 
 impl DmarcReportRecord {
     pub fn eq_except_count(&self, other: &Self) -> bool {
-        self.dkim_results == other.dkim_results
+        // Human readable results are informational and ignored when merging rows
+        self.dkim_results.len() == other.dkim_results.len()
+            && self.dkim_results.iter().all(|result| {
+                other.dkim_results.iter().any(|other| {
+                    result.domain == other.domain
+                        && result.selector == other.selector
+                        && result.result == other.result
+                })
+            })
+            && self.spf_results.len() == other.spf_results.len()
+            && self.spf_results.iter().all(|result| {
+                other.spf_results.iter().any(|other| {
+                    result.domain == other.domain
+                        && result.scope == other.scope
+                        && result.result == other.result
+                })
+            })
             && self.envelope_from == other.envelope_from
             && self.envelope_to == other.envelope_to
             && self.evaluated_disposition == other.evaluated_disposition
@@ -996,7 +1014,6 @@ impl DmarcReportRecord {
             && self.header_from == other.header_from
             && self.policy_override_reasons == other.policy_override_reasons
             && self.source_ip == other.source_ip
-            && self.spf_results == other.spf_results
     }
 }
 
@@ -29,7 +29,16 @@ impl Task {
             Task::AcmeRenewal(task) => task.status = status,
             Task::DkimManagement(task) => task.status = status,
             Task::DnsManagement(task) => task.status = status,
+            Task::DaneRollover(task) => task.status = status,
             Task::TenantMaintenance(task) => task.status = status,
+            Task::ReEncryptAccount(task) => task.status = status,
+            Task::ReindexAccount(task) => task.status = status,
+            Task::ImportMessages(task) => task.status = status,
+            Task::QuotaWarning(task) => task.status = status,
+            Task::EmailSubmission(task) => task.status = status,
+            Task::RestoreSnoozedEmail(task) => task.status = status,
+            Task::FollowUpReminder(task) => task.status = status,
+            Task::DeliveryCallback(task) => task.status = status,
         }
     }
 
@@ -52,7 +61,16 @@ impl Task {
             Task::AcmeRenewal(task) => &task.status,
             Task::DkimManagement(task) => &task.status,
             Task::DnsManagement(task) => &task.status,
+            Task::DaneRollover(task) => &task.status,
             Task::TenantMaintenance(task) => &task.status,
+            Task::ReEncryptAccount(task) => &task.status,
+            Task::ReindexAccount(task) => &task.status,
+            Task::ImportMessages(task) => &task.status,
+            Task::QuotaWarning(task) => &task.status,
+            Task::EmailSubmission(task) => &task.status,
+            Task::RestoreSnoozedEmail(task) => &task.status,
+            Task::FollowUpReminder(task) => &task.status,
+            Task::DeliveryCallback(task) => &task.status,
         }
     }
 
@@ -91,7 +109,16 @@ impl Task {
             Task::AcmeRenewal(_) => Permission::TaskAcmeRenewal,
             Task::DkimManagement(_) => Permission::TaskDkimManagement,
             Task::DnsManagement(_) => Permission::TaskDnsManagement,
+            Task::DaneRollover(_) => Permission::TaskDaneRollover,
             Task::TenantMaintenance(_) => Permission::TaskTenantMaintenance,
+            Task::ReEncryptAccount(_) => Permission::TaskReEncryptAccount,
+            Task::ReindexAccount(_) => Permission::TaskReindexAccount,
+            Task::ImportMessages(_) => Permission::TaskImportMessages,
+            Task::QuotaWarning(_) => Permission::TaskQuotaWarning,
+            Task::EmailSubmission(_) => Permission::TaskEmailSubmission,
+            Task::RestoreSnoozedEmail(_) => Permission::TaskRestoreSnoozedEmail,
+            Task::FollowUpReminder(_) => Permission::TaskFollowUpReminder,
+            Task::DeliveryCallback(_) => Permission::TaskDeliveryCallback,
         }
     }
 }
@@ -109,6 +136,8 @@ impl Action {
             Action::InvalidateNegativeCaches => Permission::ActionInvalidateNegativeCaches,
             Action::PauseMtaQueue => Permission::ActionPauseMtaQueue,
             Action::ResumeMtaQueue => Permission::ActionResumeMtaQueue,
+            Action::DrainServer => Permission::ActionDrainServer,
+            Action::SignOutEverywhere => Permission::ActionSignOutEverywhere,
             Action::UpdateApps => Permission::ActionUpdateApps,
         }
     }
@@ -8,7 +8,7 @@ use common::ipc::{
     BroadcastEvent, CacheInvalidation, CalendarAlert, EmailPush, PushNotification, RegistryChange,
 };
 use registry::{
-    schema::prelude::ObjectType,
+    schema::{enums::ServiceProtocol, prelude::ObjectType},
     types::{EnumImpl, id::ObjectId},
 };
 use std::{borrow::Borrow, io::Write};
@@ -107,6 +107,7 @@ impl BroadcastBatch<Vec<BroadcastEvent>> {
                             CacheInvalidation::List(id) => (7u8, *id),
                             CacheInvalidation::DomainLogo(id) => (8u8, *id),
                             CacheInvalidation::TenantLogo(id) => (9u8, *id),
+                            CacheInvalidation::Sessions(id) => (11u8, *id),
                             CacheInvalidation::EmailNegative {
                                 domain_id,
                                 local_part_hash,
@@ -138,6 +139,21 @@ impl BroadcastBatch<Vec<BroadcastEvent>> {
                 BroadcastEvent::QueueRefresh => {
                     serialized.push(12u8);
                 }
+                BroadcastEvent::SessionTerminate {
+                    account_id,
+                    protocol,
+                } => {
+                    serialized.push(13u8);
+                    let _ = serialized.write_leb128(*account_id);
+                    let _ = serialized.write_leb128(protocol.map_or(0, |p| p.to_id() + 1));
+                }
+                BroadcastEvent::SessionList { request_id } => {
+                    serialized.push(14u8);
+                    let _ = serialized.write_leb128(*request_id);
+                }
+                BroadcastEvent::TraceOverridesUpdate => {
+                    serialized.push(15u8);
+                }
             }
         }
         serialized
@@ -260,6 +276,7 @@ where
                                     local_part_hash,
                                 }
                             }
+                            11 => CacheInvalidation::Sessions(id),
                             _ => return Err(()),
                         });
                     }
@@ -270,6 +287,21 @@ where
                 10 => Ok(Some(BroadcastEvent::MtaQueueStatus { is_running: true })),
                 11 => Ok(Some(BroadcastEvent::MtaQueueStatus { is_running: false })),
                 12 => Ok(Some(BroadcastEvent::QueueRefresh)),
+                13 => {
+                    let account_id = self.messages.next_leb128().ok_or(())?;
+                    let protocol = match self.messages.next_leb128::<u16>().ok_or(())? {
+                        0 => None,
+                        id => Some(ServiceProtocol::from_id(id - 1).ok_or(())?),
+                    };
+                    Ok(Some(BroadcastEvent::SessionTerminate {
+                        account_id,
+                        protocol,
+                    }))
+                }
+                14 => Ok(Some(BroadcastEvent::SessionList {
+                    request_id: self.messages.next_leb128().ok_or(())?,
+                })),
+                15 => Ok(Some(BroadcastEvent::TraceOverridesUpdate)),
                 _ => Err(()),
             }
         } else {
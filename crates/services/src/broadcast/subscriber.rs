@@ -162,6 +162,17 @@ pub fn spawn_broadcast_subscriber(inner: Arc<Inner>, mut shutdown_rx: watch::Rec
                                                             .await;
                                                 }
                                             }
+                                            BroadcastEvent::SessionTerminate { account_id, protocol } => {
+                                                inner.build_server().terminate_sessions(account_id, protocol);
+                                            }
+                                            BroadcastEvent::SessionList { request_id } => {
+                                                if let Err(err) = inner.build_server().publish_session_list(request_id).await {
+                                                    trc::error!(err.details("Failed to publish session list."));
+                                                }
+                                            }
+                                            BroadcastEvent::TraceOverridesUpdate => {
+                                                inner.build_server().apply_trace_overrides().await;
+                                            }
                                             BroadcastEvent::RegistryChange(change) => {
                                                 match Box::pin(inner.build_server().reload_registry(change)).await {
                                                     Ok(result) => {
@@ -265,5 +276,17 @@ fn log_event(event: &BroadcastEvent) -> trc::Value {
             }
         }
         BroadcastEvent::QueueRefresh => "QueueRefresh".into(),
+        BroadcastEvent::SessionTerminate {
+            account_id,
+            protocol,
+        } => trc::Value::Array(vec![
+            "SessionTerminate".into(),
+            (*account_id).into(),
+            protocol.map_or("all", |p| p.as_str()).into(),
+        ]),
+        BroadcastEvent::SessionList { request_id } => {
+            trc::Value::Array(vec!["SessionList".into(), (*request_id).into()])
+        }
+        BroadcastEvent::TraceOverridesUpdate => "TraceOverridesUpdate".into(),
     }
 }
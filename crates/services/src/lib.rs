@@ -10,6 +10,7 @@ use broadcast::publisher::spawn_broadcast_publisher;
 use common::{
     BuildServer, Inner,
     manager::boot::{BootManager, IpcReceivers},
+    telemetry::audit::spawn_audit_writer,
 };
 use state_manager::manager::spawn_push_router;
 use std::sync::Arc;
@@ -39,6 +40,9 @@ impl StartServices for BootManager {
             .await;
 
         if !server.registry().is_recovery_mode() {
+            // Apply tracing overrides created before this node started
+            server.apply_trace_overrides().await;
+
             self.ipc_rxs.spawn_services(self.inner.clone());
         }
     }
@@ -56,6 +60,11 @@ impl SpawnServices for IpcReceivers {
                 spawn_broadcast_publisher(inner.clone(), event_rx);
             }
 
+            // Spawn audit log writer
+            if let Some(audit_rx) = self.audit_rx.take() {
+                spawn_audit_writer(inner.clone(), audit_rx);
+            }
+
             // Spawn task manager
             spawn_task_manager(inner.clone());
 
@@ -78,6 +78,13 @@ async fn acme_management(server: &Server, task: &TaskDomainManagement) -> trc::R
                         })
                     };
                 }
+                AcmeError::Locked { wait } => {
+                    return Ok(TaskResult::Failure {
+                        typ: TaskFailureType::Retry(now() + wait.as_secs()),
+                        message: err.to_string(),
+                        max_attempts: None,
+                    });
+                }
                 AcmeError::Internal(error) => return Err(error),
             },
         };
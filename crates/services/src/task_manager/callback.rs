@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::{TaskFailureType, TaskResult};
+use common::Server;
+use registry::schema::structs::{TaskDeliveryCallback, TaskStatus};
+use smtp::queue::callback::{DeliveryCallbackResult, SendDeliveryCallback};
+use store::write::now;
+use trc::TelemetryEvent;
+
+pub(crate) trait DeliveryCallbackTask: Sync + Send {
+    fn delivery_callback(
+        &self,
+        task: &TaskDeliveryCallback,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl DeliveryCallbackTask for Server {
+    async fn delivery_callback(&self, task: &TaskDeliveryCallback) -> TaskResult {
+        let callback_id = task.callback_id.id();
+
+        match self
+            .post_delivery_callback(callback_id, task.body.clone())
+            .await
+        {
+            Ok(DeliveryCallbackResult::Delivered) => TaskResult::Success(vec![]),
+            Ok(DeliveryCallbackResult::NotFound) => {
+                trc::event!(
+                    Telemetry(TelemetryEvent::WebhookError),
+                    Id = callback_id,
+                    Details = "Delivery callback not found",
+                );
+                TaskResult::Ignored
+            }
+            Ok(DeliveryCallbackResult::Rejected(reason)) => {
+                trc::event!(
+                    Telemetry(TelemetryEvent::WebhookError),
+                    Id = callback_id,
+                    Details = reason.clone(),
+                );
+                TaskResult::permanent(reason)
+            }
+            Ok(DeliveryCallbackResult::Failed {
+                reason,
+                throttle,
+                discard_after,
+            }) => {
+                trc::event!(
+                    Telemetry(TelemetryEvent::WebhookError),
+                    Id = callback_id,
+                    Details = reason.clone(),
+                );
+
+                // Retry failed requests until the events become stale
+                let created_at = match &task.status {
+                    TaskStatus::Pending(status) => status.created_at,
+                    TaskStatus::Retry(status) => status.created_at,
+                    TaskStatus::Failed(status) => status.created_at,
+                }
+                .timestamp() as u64;
+                let retry_at = now() + throttle.as_secs().max(1);
+                if retry_at.saturating_sub(created_at) >= discard_after.as_secs() {
+                    TaskResult::permanent(format!("Discarded stale events: {reason}"))
+                } else {
+                    TaskResult::Failure {
+                        typ: TaskFailureType::Retry(retry_at),
+                        message: reason,
+                        max_attempts: Some(u64::MAX),
+                    }
+                }
+            }
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.id(callback_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to process delivery callback")
+                );
+                result
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::{
+    Server,
+    network::dane::{UpcomingCertificate, certificate_covers},
+};
+use registry::schema::structs::{Domain, Task, TaskDomainManagement, TaskStatus};
+use smtp::outbound::dane::rollover::{DanePublishedTlsa, PublishedTlsa};
+use store::write::now;
+use trc::DaneEvent;
+
+#[cfg(not(feature = "test_mode"))]
+const CHECK_INTERVAL: u64 = 3600;
+#[cfg(feature = "test_mode")]
+const CHECK_INTERVAL: u64 = 1;
+
+pub(crate) trait DaneRolloverTask: Sync + Send {
+    fn dane_rollover(&self, task: &TaskDomainManagement)
+    -> impl Future<Output = TaskResult> + Send;
+}
+
+impl DaneRolloverTask for Server {
+    async fn dane_rollover(&self, task: &TaskDomainManagement) -> TaskResult {
+        match dane_rollover(self, task).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.caused_by(trc::location!())
+                        .details("Failed to run DANE rollover task")
+                );
+                result
+            }
+        }
+    }
+}
+
+async fn dane_rollover(server: &Server, task: &TaskDomainManagement) -> trc::Result<TaskResult> {
+    let Some(domain) = server.registry().object::<Domain>(task.domain_id).await? else {
+        return Ok(TaskResult::permanent("Domain not found".to_string()));
+    };
+
+    let now = now();
+    let hostnames = server.dane_mx_hostnames();
+    let mut next_check = None;
+
+    for UpcomingCertificate {
+        id,
+        certificate,
+        chain,
+    } in server.upcoming_certificates().await?
+    {
+        let Some(staged_until) = certificate
+            .staged_until
+            .map(|until| until.timestamp() as u64)
+        else {
+            continue;
+        };
+        let sans = certificate.subject_alternative_names.into_inner();
+        if !sans.iter().any(|san| {
+            let san = san.strip_prefix("*.").unwrap_or(san);
+            san == domain.name
+                || san
+                    .strip_suffix(domain.name.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        }) {
+            continue;
+        }
+
+        // Wait until every MX hostname served by the certificate either
+        // publishes a matching TLSA record or does not use DANE at all
+        let mut pending = Vec::new();
+        for hostname in &hostnames {
+            if certificate_covers(sans.iter().map(String::as_str), hostname) {
+                let published = server.published_tlsa(hostname).await;
+                if published.matches(&chain) == Some(false) {
+                    if let PublishedTlsa::Error(err) = &published {
+                        trc::event!(
+                            Dane(DaneEvent::TlsaRecordFetchError),
+                            Hostname = hostname.clone(),
+                            Reason = err.clone(),
+                        );
+                    }
+                    pending.push(hostname.clone());
+                }
+            }
+        }
+
+        if pending.is_empty() || staged_until <= now {
+            server.activate_staged_certificate(id).await?;
+
+            if pending.is_empty() {
+                trc::event!(
+                    Dane(DaneEvent::RolloverActivated),
+                    Id = id.id(),
+                    Domain = domain.name.clone(),
+                    Hostname = sans,
+                );
+            } else {
+                trc::event!(
+                    Dane(DaneEvent::RolloverTimeout),
+                    Id = id.id(),
+                    Domain = domain.name.clone(),
+                    Hostname = pending,
+                );
+            }
+        } else {
+            trc::event!(
+                Dane(DaneEvent::RolloverMismatch),
+                Id = id.id(),
+                Domain = domain.name.clone(),
+                Hostname = pending,
+                Expires = trc::Value::Timestamp(staged_until),
+            );
+
+            let check_at = (now + CHECK_INTERVAL).min(staged_until);
+            if next_check.is_none_or(|next| check_at < next) {
+                next_check = Some(check_at);
+            }
+        }
+    }
+
+    let tasks = if let Some(next_check) = next_check {
+        vec![Task::DaneRollover(TaskDomainManagement {
+            domain_id: task.domain_id,
+            status: TaskStatus::at(next_check as i64),
+        })]
+    } else {
+        vec![]
+    };
+
+    Ok(TaskResult::Success(tasks))
+}
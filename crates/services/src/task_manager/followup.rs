@@ -0,0 +1,40 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::Server;
+use email::message::followup::{EmailFollowUpFnc, FollowUpResult};
+use registry::schema::structs::TaskFollowUpReminder;
+
+pub(crate) trait FollowUpReminderTask: Sync + Send {
+    fn follow_up_reminder(
+        &self,
+        task: &TaskFollowUpReminder,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl FollowUpReminderTask for Server {
+    async fn follow_up_reminder(&self, task: &TaskFollowUpReminder) -> TaskResult {
+        let account_id = task.account_id.document_id();
+        let document_id = task.document_id.document_id();
+
+        // Messages that were deleted, replied to or rescheduled are skipped
+        match self.email_follow_up_expire(account_id, document_id).await {
+            Ok(FollowUpResult::Flagged) => TaskResult::Success(vec![]),
+            Ok(FollowUpResult::NotWatched | FollowUpResult::NotDue) => TaskResult::Ignored,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(account_id)
+                        .document_id(document_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to process follow-up reminder")
+                );
+                result
+            }
+        }
+    }
+}
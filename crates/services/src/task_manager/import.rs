@@ -0,0 +1,979 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::{
+    Server,
+    auth::{AccessToken, BuildAccessToken},
+};
+use email::{
+    mailbox::{INBOX_ID, manage::MailboxFnc},
+    message::ingest::{EmailIngest, IngestEmail, IngestSource},
+};
+use imap_proto::utf7::utf7_decode;
+use mail_parser::{HeaderName, Message, MessageParser};
+use registry::{
+    schema::{
+        enums::MessageImportFormat,
+        structs::{Task, TaskImportMessages, TaskStatus, TaskStatusFailed},
+    },
+    types::{EnumImpl, ObjectImpl, datetime::UTCDateTime},
+};
+use std::{
+    io::{Cursor, Read},
+    path::{Component, Path, PathBuf},
+};
+use store::{
+    SerializeInfallible,
+    ahash::AHashMap,
+    write::{Operation, TaskQueueClass, ValueClass, ValueOp, now},
+};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+use trc::{AddContext, TaskManagerEvent};
+use types::{blob_hash::BlobHash, keyword::Keyword};
+use zip::ZipArchive;
+
+const MAX_MESSAGES_PER_RUN: u64 = 250;
+const MAX_REPORTED_ERRORS: usize = 100;
+const MBOX_SEPARATOR: &[u8] = b"From ";
+
+pub(crate) trait ImportMessagesTask: Sync + Send {
+    fn import_messages(
+        &self,
+        id: u64,
+        task: &TaskImportMessages,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl ImportMessagesTask for Server {
+    async fn import_messages(&self, id: u64, task: &TaskImportMessages) -> TaskResult {
+        match import_messages(self, id, task).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(task.account_id.document_id())
+                        .details("Failed to import messages")
+                );
+                result
+            }
+        }
+    }
+}
+
+enum ImportSource {
+    Path(PathBuf),
+    Blob(Vec<u8>),
+}
+
+enum ImportProgress {
+    Completed,
+    Paused,
+    Aborted(String),
+}
+
+struct Importer<'x> {
+    server: &'x Server,
+    access_token: &'x AccessToken,
+    task: TaskImportMessages,
+    mailbox_ids: AHashMap<String, Option<u32>>,
+    processed: u64,
+}
+
+struct ImportMessage {
+    folder: String,
+    name: String,
+    contents: Vec<u8>,
+    keywords: Vec<Keyword>,
+    received_at: Option<u64>,
+}
+
+struct MaildirEntry {
+    folder: String,
+    name: String,
+    keywords: Vec<Keyword>,
+    received_at: Option<u64>,
+    location: MaildirLocation,
+}
+
+enum MaildirLocation {
+    File(PathBuf),
+    Archive(String),
+}
+
+struct MboxReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    max_size: usize,
+}
+
+struct MboxMessage {
+    contents: Vec<u8>,
+    received_at: Option<u64>,
+    oversized: bool,
+}
+
+async fn import_messages(
+    server: &Server,
+    id: u64,
+    task: &TaskImportMessages,
+) -> trc::Result<TaskResult> {
+    let account_id = task.account_id.document_id();
+    let source = match (&task.path, &task.blob_id) {
+        (Some(path), None) => match resolve_import_path(server, path).await {
+            Ok(path) => ImportSource::Path(path),
+            Err(reason) => return Ok(TaskResult::permanent(reason)),
+        },
+        (None, Some(blob_id)) => {
+            let Some(bytes) = server
+                .blob_store()
+                .get_blob(blob_id.hash.as_slice(), 0..usize::MAX)
+                .await
+                .caused_by(trc::location!())?
+            else {
+                return Ok(TaskResult::permanent(format!("Blob {blob_id} not found")));
+            };
+            ImportSource::Blob(bytes)
+        }
+        _ => {
+            return Ok(TaskResult::permanent(
+                "Either a path or a blobId must be provided",
+            ));
+        }
+    };
+
+    let access_token = server
+        .access_token(account_id)
+        .await
+        .caused_by(trc::location!())?
+        .build();
+    let mut importer = Importer {
+        server,
+        access_token: &access_token,
+        task: task.clone(),
+        mailbox_ids: AHashMap::new(),
+        processed: 0,
+    };
+    let progress = match task.format {
+        MessageImportFormat::Maildir => importer.import_maildir(source).await?,
+        MessageImportFormat::Mbox => importer.import_mbox(source).await?,
+    };
+
+    let mut task = importer.task;
+    let failure_reason = match progress {
+        ImportProgress::Paused => {
+            // Reschedule to continue with the next batch of messages
+            let due = now() + 1;
+            task.status = TaskStatus::at(due as i64);
+            return Ok(update_task(id, due, task));
+        }
+        ImportProgress::Completed if task.messages_failed == 0 => None,
+        ImportProgress::Completed => Some(format!(
+            "{} of {} messages could not be imported",
+            task.messages_failed, task.messages_total
+        )),
+        ImportProgress::Aborted(reason) => Some(reason),
+    };
+
+    trc::event!(
+        TaskManager(TaskManagerEvent::ImportCompleted),
+        AccountId = account_id,
+        Total = task.messages_total,
+        TotalSuccesses = task.messages_imported,
+        TotalFailures = task.messages_failed,
+        Details = task.messages_skipped,
+        Reason = failure_reason.clone(),
+    );
+
+    if let Some(failure_reason) = failure_reason {
+        // Keep the task and its error report so it can be reviewed
+        let created_at = match &task.status {
+            TaskStatus::Pending(status) => status.created_at,
+            TaskStatus::Retry(status) => status.created_at,
+            TaskStatus::Failed(status) => status.created_at,
+        };
+        task.status = TaskStatus::Failed(TaskStatusFailed {
+            created_at,
+            failed_at: UTCDateTime::now(),
+            failed_attempt_number: 0,
+            failure_reason,
+        });
+        Ok(update_task(id, u64::MAX, task))
+    } else {
+        Ok(TaskResult::Success(vec![]))
+    }
+}
+
+fn update_task(id: u64, due: u64, task: TaskImportMessages) -> TaskResult {
+    let task = Task::ImportMessages(task);
+
+    TaskResult::Update([
+        Operation::Value {
+            class: ValueClass::TaskQueue(TaskQueueClass::Due { id, due }),
+            op: ValueOp::Set(task.object_type().to_id().serialize()),
+        },
+        Operation::Value {
+            class: ValueClass::TaskQueue(TaskQueueClass::Task { id }),
+            op: ValueOp::Set(task.to_pickled_vec()),
+        },
+    ])
+}
+
+async fn resolve_import_path(server: &Server, path: &str) -> Result<PathBuf, String> {
+    let Some(import_root) = &server.core.email.import_root else {
+        return Err("Imports from the filesystem are disabled".into());
+    };
+
+    // Paths are always relative to the import root
+    let relative_path = Path::new(path);
+    if relative_path
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("Invalid import path {path:?}"));
+    }
+
+    let import_root = tokio::fs::canonicalize(import_root)
+        .await
+        .map_err(|err| format!("Failed to access import root: {err}"))?;
+    let import_path = tokio::fs::canonicalize(import_root.join(relative_path))
+        .await
+        .map_err(|err| format!("Failed to access {path:?}: {err}"))?;
+    if import_path.starts_with(&import_root) {
+        Ok(import_path)
+    } else {
+        Err(format!("Import path {path:?} is outside the import root"))
+    }
+}
+
+impl Importer<'_> {
+    async fn import_maildir(&mut self, source: ImportSource) -> trc::Result<ImportProgress> {
+        let (entries, mut archive) = match source {
+            ImportSource::Path(path) => match scan_maildir(&path).await {
+                Ok(entries) => (entries, None),
+                Err(err) => {
+                    return Ok(ImportProgress::Aborted(format!(
+                        "Failed to read Maildir: {err}"
+                    )));
+                }
+            },
+            ImportSource::Blob(bytes) => {
+                match ZipArchive::new(Cursor::new(bytes))
+                    .map_err(|err| err.to_string())
+                    .and_then(|mut archive| {
+                        scan_maildir_archive(&mut archive).map(|entries| (entries, Some(archive)))
+                    }) {
+                    Ok(result) => result,
+                    Err(err) => {
+                        return Ok(ImportProgress::Aborted(format!(
+                            "Failed to read Maildir archive: {err}"
+                        )));
+                    }
+                }
+            }
+        };
+
+        let max_size = self.server.core.email.mail_max_size;
+        self.task.messages_total = entries.len() as u64;
+
+        // Entries are sorted by folder and unique name, so the import resumes after the
+        // last processed message even if files were added or removed in the meantime
+        let resume_after = self
+            .task
+            .resume_after
+            .as_deref()
+            .and_then(|key| key.rsplit_once('/'));
+        for entry in entries {
+            if resume_after.is_some_and(|(folder, unique_name)| {
+                (entry.folder.as_str(), entry.unique_name()) <= (folder, unique_name)
+            }) {
+                continue;
+            } else if self.processed >= MAX_MESSAGES_PER_RUN {
+                return Ok(ImportProgress::Paused);
+            }
+
+            let resume_key = format!("{}/{}", entry.folder, entry.unique_name());
+
+            let contents = match (&entry.location, archive.as_mut()) {
+                (MaildirLocation::File(path), _) => read_message(path, max_size).await,
+                (MaildirLocation::Archive(name), Some(archive)) => {
+                    read_archived_message(archive, name, max_size)
+                }
+                (MaildirLocation::Archive(name), None) => Err(format!("{name} not found")),
+            };
+            match contents {
+                Ok(contents) => {
+                    if let Some(reason) = self
+                        .import_message(ImportMessage {
+                            folder: entry.folder,
+                            name: entry.name,
+                            contents,
+                            keywords: entry.keywords,
+                            received_at: entry.received_at,
+                        })
+                        .await?
+                    {
+                        return Ok(ImportProgress::Aborted(reason));
+                    }
+                }
+                Err(reason) => {
+                    self.add_error(&entry.name, reason);
+                }
+            }
+
+            self.task.messages_processed += 1;
+            self.task.resume_after = Some(resume_key);
+            self.processed += 1;
+        }
+
+        Ok(ImportProgress::Completed)
+    }
+
+    async fn import_mbox(&mut self, source: ImportSource) -> trc::Result<ImportProgress> {
+        let max_size = self.server.core.email.mail_max_size;
+
+        match source {
+            ImportSource::Path(path) => {
+                let reader: std::io::Result<_> = async {
+                    if self.task.messages_total == 0 {
+                        self.task.messages_total =
+                            count_mbox_messages(open_mbox(&path).await?).await?;
+                    }
+                    open_mbox(&path).await
+                }
+                .await;
+
+                match reader {
+                    Ok(reader) => {
+                        self.import_mbox_messages(MboxReader::new(reader, max_size))
+                            .await
+                    }
+                    Err(err) => Ok(ImportProgress::Aborted(format!(
+                        "Failed to read mbox: {err}"
+                    ))),
+                }
+            }
+            ImportSource::Blob(bytes) => {
+                if self.task.messages_total == 0 {
+                    self.task.messages_total = count_mbox_messages(bytes.as_slice())
+                        .await
+                        .unwrap_or_default();
+                }
+
+                self.import_mbox_messages(MboxReader::new(bytes.as_slice(), max_size))
+                    .await
+            }
+        }
+    }
+
+    async fn import_mbox_messages<R: AsyncBufRead + Unpin + Send>(
+        &mut self,
+        mut reader: MboxReader<R>,
+    ) -> trc::Result<ImportProgress> {
+        let mut position = 0;
+        let mut resume_after = self.task.resume_after.clone();
+
+        loop {
+            let message = match reader.next_message().await {
+                Ok(Some(message)) => message,
+                Ok(None) if resume_after.is_some() => {
+                    return Ok(ImportProgress::Aborted(
+                        "Failed to resume import: the last processed message is no longer in the mbox"
+                            .into(),
+                    ));
+                }
+                Ok(None) => return Ok(ImportProgress::Completed),
+                Err(err) => {
+                    return Ok(ImportProgress::Aborted(format!(
+                        "Failed to read mbox: {err}"
+                    )));
+                }
+            };
+
+            // Messages imported in previous runs are skipped up to the last processed one
+            position += 1;
+            let resume_key = BlobHash::generate(&message.contents).to_hex();
+            if let Some(last_key) = &resume_after {
+                if *last_key == resume_key {
+                    resume_after = None;
+                }
+                continue;
+            } else if self.processed >= MAX_MESSAGES_PER_RUN {
+                return Ok(ImportProgress::Paused);
+            }
+
+            let name = format!("message #{position}");
+            if message.oversized {
+                self.add_error(
+                    &name,
+                    format!(
+                        "Message exceeds the maximum size of {} bytes",
+                        reader.max_size
+                    ),
+                );
+            } else if let Some(reason) = self
+                .import_message(ImportMessage {
+                    folder: String::new(),
+                    name,
+                    contents: message.contents,
+                    keywords: vec![],
+                    received_at: message.received_at,
+                })
+                .await?
+            {
+                return Ok(ImportProgress::Aborted(reason));
+            }
+
+            self.task.messages_processed += 1;
+            self.task.resume_after = Some(resume_key);
+            self.processed += 1;
+        }
+    }
+
+    async fn import_message(&mut self, message: ImportMessage) -> trc::Result<Option<String>> {
+        let Some(mailbox_id) = self.mailbox_id(&message.folder).await? else {
+            self.add_error(
+                &message.name,
+                format!("Failed to create mailbox {:?}", message.folder),
+            );
+            return Ok(None);
+        };
+
+        let parsed = MessageParser::new().parse(&message.contents);
+        let received_at = message
+            .received_at
+            .or_else(|| parsed.as_ref().and_then(received_date));
+        let mut keywords = message.keywords;
+        if self.task.format == MessageImportFormat::Mbox
+            && let Some(parsed) = &parsed
+        {
+            keywords = mbox_keywords(parsed);
+        }
+
+        match self
+            .server
+            .email_ingest(IngestEmail {
+                raw_message: &message.contents,
+                blob_hash: None,
+                message: parsed,
+                access_token: self.access_token,
+                mailbox_ids: vec![mailbox_id],
+                keywords,
+                received_at,
+                source: IngestSource::Import {
+                    deduplicate: self.task.deduplicate,
+                },
+                session_id: 0,
+            })
+            .await
+        {
+            Ok(ingested) if ingested.change_id == u64::MAX => {
+                self.task.messages_skipped += 1;
+            }
+            Ok(_) => {
+                self.task.messages_imported += 1;
+            }
+            Err(err) if err.matches(trc::EventType::Limit(trc::LimitEvent::Quota)) => {
+                return Ok(Some("Account quota exceeded".into()));
+            }
+            Err(mut err)
+                if err.matches(trc::EventType::MessageIngest(
+                    trc::MessageIngestEvent::Error,
+                )) =>
+            {
+                let reason = err
+                    .take_value(trc::Key::Reason)
+                    .and_then(|reason| reason.into_string())
+                    .map(|reason| reason.to_string())
+                    .unwrap_or_else(|| "Failed to parse message".into());
+                self.add_error(&message.name, reason);
+            }
+            Err(err) => return Err(err.caused_by(trc::location!())),
+        }
+
+        Ok(None)
+    }
+
+    async fn mailbox_id(&mut self, folder: &str) -> trc::Result<Option<u32>> {
+        if let Some(mailbox_id) = self.mailbox_ids.get(folder) {
+            return Ok(*mailbox_id);
+        }
+
+        let path = match (self.task.target_mailbox.as_deref(), folder) {
+            (None, "") => None,
+            (None, folder) if folder.eq_ignore_ascii_case("INBOX") => None,
+            (None, folder) => Some(folder.to_string()),
+            (Some(target), "") => Some(target.to_string()),
+            (Some(target), folder) => Some(format!("{target}/{folder}")),
+        };
+        let mailbox_id = if let Some(path) = path {
+            self.server
+                .mailbox_create_path(self.access_token.account_id(), &path)
+                .await
+                .caused_by(trc::location!())?
+        } else {
+            Some(INBOX_ID)
+        };
+        self.mailbox_ids.insert(folder.to_string(), mailbox_id);
+
+        Ok(mailbox_id)
+    }
+
+    fn add_error(&mut self, name: &str, reason: String) {
+        trc::event!(
+            TaskManager(TaskManagerEvent::ImportFailed),
+            AccountId = self.access_token.account_id(),
+            Details = name.to_string(),
+            Reason = reason.clone(),
+        );
+
+        self.task.messages_failed += 1;
+        if self.task.import_errors.len() < MAX_REPORTED_ERRORS {
+            self.task
+                .import_errors
+                .push_unchecked(format!("{name}: {reason}"));
+        }
+    }
+}
+
+async fn scan_maildir(root: &Path) -> std::io::Result<Vec<MaildirEntry>> {
+    let mut entries = Vec::new();
+    let mut folders = vec![(root.to_path_buf(), Vec::<String>::new())];
+
+    while let Some((path, dirs)) = folders.pop() {
+        let mut custom_keywords = Vec::new();
+        let mut files = Vec::new();
+        let mut dir = tokio::fs::read_dir(&path).await?;
+
+        while let Some(entry) = dir.next_entry().await? {
+            // Symbolic links are not followed
+            let file_type = entry.file_type().await?;
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                match name.as_str() {
+                    "cur" | "new" => {
+                        let mut messages = tokio::fs::read_dir(entry.path()).await?;
+                        while let Some(message) = messages.next_entry().await? {
+                            if message.file_type().await?.is_file()
+                                && let Some(name) = message.file_name().to_str()
+                                && !name.starts_with('.')
+                            {
+                                files.push((name.to_string(), message.path()));
+                            }
+                        }
+                    }
+                    "tmp" => {}
+                    _ if name.starts_with('.') && !dirs.is_empty() => {}
+                    _ => {
+                        let mut dirs = dirs.clone();
+                        dirs.push(name);
+                        folders.push((entry.path(), dirs));
+                    }
+                }
+            } else if file_type.is_file() && name == "dovecot-keywords" {
+                custom_keywords =
+                    parse_dovecot_keywords(&tokio::fs::read_to_string(entry.path()).await?);
+            }
+        }
+
+        let folder = maildir_folder_name(&dirs);
+        for (name, path) in files {
+            entries.push(MaildirEntry::new(
+                folder.clone(),
+                name,
+                &custom_keywords,
+                MaildirLocation::File(path),
+            ));
+        }
+    }
+
+    entries
+        .sort_unstable_by(|a, b| (&a.folder, a.unique_name()).cmp(&(&b.folder, b.unique_name())));
+
+    Ok(entries)
+}
+
+fn scan_maildir_archive(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+) -> Result<Vec<MaildirEntry>, String> {
+    let mut messages = Vec::new();
+    let mut keyword_files = Vec::new();
+
+    for name in archive.file_names() {
+        let components = name.split('/').collect::<Vec<_>>();
+        let Some((file_name, parents)) = components
+            .split_last()
+            .filter(|(file_name, _)| !file_name.is_empty())
+        else {
+            continue;
+        };
+        if matches!(parents.last(), Some(&"cur" | &"new")) && !file_name.starts_with('.') {
+            messages.push((
+                parents[..parents.len() - 1]
+                    .iter()
+                    .map(|dir| dir.to_string())
+                    .collect::<Vec<_>>(),
+                file_name.to_string(),
+                name.to_string(),
+            ));
+        } else if *file_name == "dovecot-keywords" {
+            keyword_files.push((
+                parents
+                    .iter()
+                    .map(|dir| dir.to_string())
+                    .collect::<Vec<_>>(),
+                name.to_string(),
+            ));
+        }
+    }
+
+    // Archives often wrap the Maildir in a top-level directory
+    let mut root_len = messages
+        .iter()
+        .map(|(dirs, _, _)| dirs.as_slice())
+        .reduce(|a, b| {
+            let len = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+            &a[..len]
+        })
+        .map_or(0, |root| root.len());
+    if let Some((dirs, _, _)) = messages.first()
+        && root_len > 0
+        && dirs[root_len - 1].starts_with('.')
+    {
+        root_len -= 1;
+    }
+
+    let mut custom_keywords = AHashMap::new();
+    for (dirs, name) in keyword_files {
+        let mut contents = String::new();
+        archive
+            .by_name(&name)
+            .map_err(|err| err.to_string())?
+            .read_to_string(&mut contents)
+            .map_err(|err| err.to_string())?;
+        custom_keywords.insert(dirs, parse_dovecot_keywords(&contents));
+    }
+
+    let mut entries = messages
+        .into_iter()
+        .map(|(dirs, file_name, name)| {
+            MaildirEntry::new(
+                maildir_folder_name(dirs.get(root_len..).unwrap_or_default()),
+                file_name,
+                custom_keywords
+                    .get(&dirs)
+                    .map(|keywords: &Vec<String>| keywords.as_slice())
+                    .unwrap_or_default(),
+                MaildirLocation::Archive(name),
+            )
+        })
+        .collect::<Vec<_>>();
+    entries
+        .sort_unstable_by(|a, b| (&a.folder, a.unique_name()).cmp(&(&b.folder, b.unique_name())));
+
+    Ok(entries)
+}
+
+impl MaildirEntry {
+    // Unique name of the message without the flags, which change over time
+    fn unique_name(&self) -> &str {
+        self.name
+            .rsplit_once(":2,")
+            .or_else(|| self.name.rsplit_once("!2,"))
+            .map_or(self.name.as_str(), |(unique_name, _)| unique_name)
+    }
+
+    fn new(
+        folder: String,
+        name: String,
+        custom_keywords: &[String],
+        location: MaildirLocation,
+    ) -> Self {
+        let mut keywords = Vec::new();
+
+        // Flags are stored after the info separator, for example "1234.host:2,FS"
+        if let Some((_, flags)) = name.rsplit_once(":2,").or_else(|| name.rsplit_once("!2,")) {
+            for ch in flags.chars() {
+                let keyword = match ch {
+                    'D' => Keyword::Draft,
+                    'F' => Keyword::Flagged,
+                    'P' => Keyword::Forwarded,
+                    'R' => Keyword::Answered,
+                    'S' => Keyword::Seen,
+                    'T' => Keyword::Deleted,
+                    'a'..='z' => {
+                        match custom_keywords
+                            .get((ch as u8 - b'a') as usize)
+                            .filter(|keyword| !keyword.is_empty())
+                        {
+                            Some(keyword) => Keyword::parse(keyword),
+                            None => continue,
+                        }
+                    }
+                    _ => continue,
+                };
+                if !keywords.contains(&keyword) {
+                    keywords.push(keyword);
+                }
+            }
+        }
+
+        // The unique name starts with the delivery timestamp
+        let received_at = name
+            .split('.')
+            .next()
+            .and_then(|timestamp| timestamp.parse::<u64>().ok())
+            .filter(|timestamp| *timestamp > 0);
+
+        MaildirEntry {
+            folder,
+            name,
+            keywords,
+            received_at,
+            location,
+        }
+    }
+}
+
+fn maildir_folder_name(dirs: &[String]) -> String {
+    let mut folder = String::new();
+
+    for (pos, dir) in dirs.iter().enumerate() {
+        // Maildir++ folders are stored in the root as dot-separated names
+        let (names, separator) = match dir.strip_prefix('.') {
+            Some(names) if pos == 0 => (names, '.'),
+            _ => (dir.as_str(), '/'),
+        };
+        for name in names.split(separator).filter(|name| !name.is_empty()) {
+            if !folder.is_empty() {
+                folder.push('/');
+            }
+            folder.push_str(&utf7_decode(name).unwrap_or_else(|| name.to_string()));
+        }
+    }
+
+    folder
+}
+
+fn parse_dovecot_keywords(contents: &str) -> Vec<String> {
+    let mut keywords = vec![String::new(); 26];
+
+    for line in contents.lines() {
+        if let Some((idx, name)) = line.split_once(' ')
+            && let Ok(idx) = idx.parse::<usize>()
+            && idx < keywords.len()
+        {
+            keywords[idx] = name.trim().to_string();
+        }
+    }
+
+    keywords
+}
+
+async fn read_message(path: &Path, max_size: usize) -> Result<Vec<u8>, String> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|err| err.to_string())?;
+    if metadata.len() > max_size as u64 {
+        return Err(format!(
+            "Message exceeds the maximum size of {max_size} bytes"
+        ));
+    }
+
+    tokio::fs::read(path).await.map_err(|err| err.to_string())
+}
+
+fn read_archived_message(
+    archive: &mut ZipArchive<Cursor<Vec<u8>>>,
+    name: &str,
+    max_size: usize,
+) -> Result<Vec<u8>, String> {
+    let file = archive.by_name(name).map_err(|err| err.to_string())?;
+    let mut contents = Vec::with_capacity(std::cmp::min(file.size() as usize, max_size));
+
+    // Declared sizes are not trusted
+    file.take(max_size as u64 + 1)
+        .read_to_end(&mut contents)
+        .map_err(|err| err.to_string())?;
+    if contents.len() > max_size {
+        Err(format!(
+            "Message exceeds the maximum size of {max_size} bytes"
+        ))
+    } else {
+        Ok(contents)
+    }
+}
+
+fn mbox_keywords(message: &Message<'_>) -> Vec<Keyword> {
+    let mut keywords = Vec::new();
+
+    for header in message.root_part().headers() {
+        let HeaderName::Other(name) = &header.name else {
+            continue;
+        };
+        let flags = header.value().as_text().unwrap_or_default().trim();
+        if name.eq_ignore_ascii_case("Status") {
+            if flags.contains('R') {
+                keywords.push(Keyword::Seen);
+            }
+        } else if name.eq_ignore_ascii_case("X-Status") {
+            for ch in flags.chars() {
+                let keyword = match ch {
+                    'A' => Keyword::Answered,
+                    'F' => Keyword::Flagged,
+                    'T' => Keyword::Draft,
+                    'D' => Keyword::Deleted,
+                    _ => continue,
+                };
+                if !keywords.contains(&keyword) {
+                    keywords.push(keyword);
+                }
+            }
+        }
+    }
+
+    keywords
+}
+
+// Date of the most recent Received header, used when the source has no delivery time
+fn received_date(message: &Message<'_>) -> Option<u64> {
+    message
+        .root_part()
+        .headers()
+        .iter()
+        .filter(|header| header.name == HeaderName::Received)
+        .find_map(|header| header.value().as_received()?.date())
+        .and_then(|date| u64::try_from(date.to_timestamp()).ok())
+}
+
+async fn open_mbox(path: &Path) -> std::io::Result<BufReader<tokio::fs::File>> {
+    tokio::fs::File::open(path).await.map(BufReader::new)
+}
+
+async fn count_mbox_messages<R: AsyncBufRead + Unpin>(mut reader: R) -> std::io::Result<u64> {
+    let mut line = Vec::new();
+    let mut count = 0;
+
+    while read_line(&mut reader, &mut line, MBOX_SEPARATOR.len()).await? > 0 {
+        if line.starts_with(MBOX_SEPARATOR) {
+            count += 1;
+        }
+        line.clear();
+    }
+
+    Ok(count)
+}
+
+impl<R: AsyncBufRead + Unpin> MboxReader<R> {
+    fn new(reader: R, max_size: usize) -> Self {
+        MboxReader {
+            reader,
+            line: Vec::new(),
+            max_size,
+        }
+    }
+
+    async fn next_message(&mut self) -> std::io::Result<Option<MboxMessage>> {
+        // Locate the separator line of the next message
+        while !self.line.starts_with(MBOX_SEPARATOR) {
+            self.line.clear();
+            if self.read_line().await? == 0 {
+                return Ok(None);
+            }
+        }
+
+        let received_at = parse_mbox_separator(&self.line);
+        let mut contents = Vec::new();
+        let mut oversized = false;
+
+        loop {
+            self.line.clear();
+            if self.read_line().await? == 0 || self.line.starts_with(MBOX_SEPARATOR) {
+                break;
+            } else if oversized {
+                continue;
+            }
+
+            // Lines starting with ">From " are unescaped by removing one '>'
+            let line = if self.line.starts_with(b">")
+                && self
+                    .line
+                    .iter()
+                    .skip_while(|ch| **ch == b'>')
+                    .take(5)
+                    .eq(b"From ".iter())
+            {
+                &self.line[1..]
+            } else {
+                &self.line[..]
+            };
+            contents.extend_from_slice(line);
+            oversized = contents.len() > self.max_size;
+        }
+
+        // Remove the blank line that separates messages
+        if contents.ends_with(b"\r\n\r\n") {
+            contents.truncate(contents.len() - 2);
+        } else if contents.ends_with(b"\n\n") {
+            contents.truncate(contents.len() - 1);
+        }
+
+        Ok(Some(MboxMessage {
+            contents,
+            received_at,
+            oversized,
+        }))
+    }
+
+    // Lines longer than the maximum message size only need to be detected as oversized
+    async fn read_line(&mut self) -> std::io::Result<usize> {
+        read_line(&mut self.reader, &mut self.line, self.max_size + 1).await
+    }
+}
+
+// Reads a line keeping at most `max_len` bytes of it, returns the number of bytes consumed
+async fn read_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    line: &mut Vec<u8>,
+    max_len: usize,
+) -> std::io::Result<usize> {
+    let mut consumed = 0;
+
+    loop {
+        let buf = reader.fill_buf().await?;
+        if buf.is_empty() {
+            return Ok(consumed);
+        }
+        let (len, is_eol) = match buf.iter().position(|ch| *ch == b'\n') {
+            Some(pos) => (pos + 1, true),
+            None => (buf.len(), false),
+        };
+        line.extend_from_slice(&buf[..len.min(max_len.saturating_sub(line.len()))]);
+        reader.consume(len);
+        consumed += len;
+        if is_eol {
+            return Ok(consumed);
+        }
+    }
+}
+
+fn parse_mbox_separator(line: &[u8]) -> Option<u64> {
+    // From sender@example.org Mon Jan  1 00:00:00 2024
+    let date = std::str::from_utf8(line)
+        .ok()?
+        .split_ascii_whitespace()
+        .skip(2)
+        .take(5)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    chrono::NaiveDateTime::parse_from_str(&date, "%a %b %d %H:%M:%S %Y")
+        .ok()
+        .and_then(|dt| u64::try_from(dt.and_utc().timestamp()).ok())
+}
@@ -6,7 +6,13 @@
 
 use crate::task_manager::{Task, TaskDetails, TaskResult};
 use common::Server;
-use email::{cache::MessageCacheFetch, message::metadata::MessageMetadata};
+use email::{
+    cache::MessageCacheFetch,
+    message::{
+        metadata::{MESSAGE_RECEIVED_MASK, MessageMetadata},
+        shared::{BlobReferences, EmailSharedBlob, MessageReferences},
+    },
+};
 use groupware::{cache::GroupwareCache, calendar::CalendarEvent, contact::ContactCard};
 use registry::{
     schema::{
@@ -16,7 +22,7 @@ use registry::{
     },
     types::EnumImpl,
 };
-use std::cmp::Ordering;
+use std::{cmp::Ordering, ops::RangeBounds, time::Instant};
 use store::{
     IterateParams, ValueKey,
     ahash::AHashMap,
@@ -27,7 +33,7 @@ use store::{
         key::DeserializeBigEndian, now,
     },
 };
-use trc::{AddContext, TaskManagerEvent};
+use trc::{AddContext, MessageIngestEvent, TaskManagerEvent};
 use types::{
     blob_hash::BlobHash,
     collection::{Collection, SyncCollection},
@@ -60,16 +66,18 @@ impl SearchIndexTask for Server {
         let mut document_insertions = Vec::new();
         let mut document_deletions: [AHashMap<u32, Vec<u32>>; NUM_INDEXES] =
             std::array::from_fn(|_| AHashMap::new());
+        let mut blob_references = AHashMap::new();
 
         for task in tasks {
             match &task.task {
                 Task::IndexDocument(task) => {
                     let account_id = task.account_id.document_id();
                     let document_id = task.document_id.document_id();
+                    let op_start = Instant::now();
 
                     let document = match task.document_type {
                         IndexDocumentType::Email => {
-                            build_email_document(self, account_id, document_id).await
+                            build_email_document(self, account_id, document_id, ..).await
                         }
                         IndexDocumentType::Calendar => {
                             build_calendar_document(self, account_id, document_id).await
@@ -85,6 +93,14 @@ impl SearchIndexTask for Server {
 
                     let result = match document {
                         Ok(Some(doc)) if !doc.is_empty() => {
+                            if matches!(task.document_type, IndexDocumentType::Email) {
+                                trc::event!(
+                                    MessageIngest(MessageIngestEvent::SearchIndex),
+                                    AccountId = account_id,
+                                    DocumentId = document_id,
+                                    Elapsed = op_start.elapsed(),
+                                );
+                            }
                             document_insertions.push(doc);
                             TaskResult::Success(vec![])
                         }
@@ -153,9 +169,14 @@ impl SearchIndexTask for Server {
                     let document_id = task.document_id.document_id();
                     let idx = match task.document_type {
                         IndexDocumentType::Email => {
-                            if let Err(err) =
-                                delete_email_metadata(self, &mut batch, account_id, document_id)
-                                    .await
+                            if let Err(err) = delete_email_metadata(
+                                self,
+                                &mut batch,
+                                &mut blob_references,
+                                account_id,
+                                document_id,
+                            )
+                            .await
                             {
                                 trc::error!(
                                     err.account_id(account_id)
@@ -194,10 +215,19 @@ impl SearchIndexTask for Server {
             }
         }
 
-        // Commit deletion batch to data store
-        if !batch.is_empty()
-            && let Err(err) = self.store().write(batch.build_all()).await
-        {
+        // Release the blobs referenced by deleted messages and commit the
+        // deletion batch to data store
+        let mut result = Ok(());
+        for references in blob_references.into_values() {
+            result = self.write_blob_references(&mut batch, references).await;
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() && !batch.is_empty() {
+            result = self.store().write(batch.build_all()).await.map(|_| ());
+        }
+        if let Err(err) = result {
             trc::error!(
                 err.caused_by(trc::location!())
                     .details("Failed to commit index deletions to data store")
@@ -394,10 +424,11 @@ pub(crate) async fn reindex_account(server: &Server, account_id: u32) -> trc::Re
     Ok(())
 }
 
-async fn build_email_document(
+pub(crate) async fn build_email_document(
     server: &Server,
     account_id: u32,
     document_id: u32,
+    received_at: impl RangeBounds<u64>,
 ) -> trc::Result<Option<IndexDocument>> {
     let Some(index_fields) = server.core.email.index_fields.get(&SearchIndex::Email) else {
         return Ok(None);
@@ -417,6 +448,9 @@ async fn build_email_document(
             let metadata = metadata_
                 .unarchive::<MessageMetadata>()
                 .caused_by(trc::location!())?;
+            if !received_at.contains(&(metadata.rcvd_attach.to_native() & MESSAGE_RECEIVED_MASK)) {
+                return Ok(None);
+            }
 
             let raw_message = server
                 .blob_store()
@@ -538,9 +572,40 @@ async fn build_tracing_span_document(_: &Server, _: u64) -> trc::Result<Option<I
 async fn delete_email_metadata(
     server: &Server,
     batch: &mut BatchBuilder,
+    blob_references: &mut AHashMap<u32, BlobReferences>,
     account_id: u32,
     document_id: u32,
 ) -> trc::Result<()> {
+    // Release the contents referenced by the message
+    if let Some(references) = server
+        .store()
+        .get_value::<MessageReferences>(ValueKey::property(
+            account_id,
+            Collection::Email,
+            document_id,
+            EmailField::References,
+        ))
+        .await?
+    {
+        if !blob_references.contains_key(&account_id) {
+            let tenant_id = server
+                .try_account(account_id)
+                .await
+                .caused_by(trc::location!())?
+                .and_then(|account| account.id_tenant);
+            blob_references.insert(account_id, BlobReferences::new(account_id, tenant_id));
+        }
+        blob_references
+            .get_mut(&account_id)
+            .unwrap()
+            .remove(&references);
+        batch
+            .with_account_id(account_id)
+            .with_collection(Collection::Email)
+            .with_document(document_id)
+            .clear(EmailField::References);
+    }
+
     match server
         .store()
         .get_value::<Archive<AlignedBytes>>(ValueKey::property(
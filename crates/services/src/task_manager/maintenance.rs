@@ -12,14 +12,20 @@ use crate::task_manager::{
 };
 use common::{
     KV_ACME, KV_GREYLIST, KV_LOCK_DAV, KV_LOCK_QUEUE_MESSAGE, KV_LOCK_TASK, KV_OAUTH,
-    KV_QUOTA_BLOB, KV_RATE_LIMIT_AUTH, KV_RATE_LIMIT_CONTACT, KV_RATE_LIMIT_HTTP_ANONYMOUS,
-    KV_RATE_LIMIT_HTTP_AUTHENTICATED, KV_RATE_LIMIT_IMAP, KV_RATE_LIMIT_LOITER, KV_RATE_LIMIT_RCPT,
-    KV_RATE_LIMIT_SCAN, KV_RATE_LIMIT_SMTP, KV_SIEVE_ID, Server,
-    storage::index::ObjectIndexBuilder,
+    KV_QUOTA_BLOB, KV_RATE_LIMIT_AUTH, KV_RATE_LIMIT_CONTACT, KV_RATE_LIMIT_DSN,
+    KV_RATE_LIMIT_HTTP_ANONYMOUS, KV_RATE_LIMIT_HTTP_AUTHENTICATED, KV_RATE_LIMIT_IMAP,
+    KV_RATE_LIMIT_LOITER, KV_RATE_LIMIT_RCPT, KV_RATE_LIMIT_SCAN, KV_RATE_LIMIT_SENDER_VERIFY,
+    KV_RATE_LIMIT_SMTP, KV_SIEVE_ID, Server, manager::incremental::IncrementalBackupParams,
+    storage::index::ObjectIndexBuilder, telemetry::audit::AuditStore,
 };
 use email::{
     cache::MessageCacheFetch,
-    message::{delete::EmailDeletion, ingest::EmailIngest, metadata::MessageData},
+    message::{
+        delete::EmailDeletion,
+        ingest::EmailIngest,
+        metadata::{MessageData, MessageMetadata},
+        shared::{BlobReference, EmailSharedBlob, MessageReferences},
+    },
     sieve::SieveScript,
 };
 use groupware::{
@@ -29,7 +35,10 @@ use groupware::{
 };
 use registry::{
     schema::{
-        enums::{TaskAccountMaintenanceType, TaskStoreMaintenanceType, TaskTenantMaintenanceType},
+        enums::{
+            SharedBlobQuota, TaskAccountMaintenanceType, TaskStoreMaintenanceType,
+            TaskTenantMaintenanceType,
+        },
         prelude::{Object, ObjectInner, ObjectType, Property},
         structs::{
             Task, TaskAccountMaintenance, TaskStatus, TaskStoreMaintenance, TaskTenantMaintenance,
@@ -39,19 +48,26 @@ use registry::{
 };
 use smtp::reporting::index::ExternalReportIndex;
 use store::{
-    Serialize, ValueKey,
-    rand::{self},
+    Serialize, SerializeInfallible, ValueKey,
+    ahash::{AHashMap, AHashSet},
+    rand::{self, seq::IteratorRandom},
     registry::{RegistryFilter, RegistryQuery},
     roaring::RoaringBitmap,
-    write::{AlignedBytes, Archive, Archiver, BatchBuilder, RegistryClass, ValueClass, now},
+    write::{
+        AlignedBytes, Archive, Archiver, BatchBuilder, BlobLink, BlobOp, RegistryClass, ValueClass,
+        assert::ToAssertValue, now,
+    },
 };
-use trc::{AddContext, StoreEvent};
+use trc::{AddContext, StoreEvent, TaskManagerEvent};
 use types::{
+    blob_hash::BlobHash,
     collection::Collection,
     field::{EmailField, MailboxField},
     id::Id,
 };
 
+const QUOTA_RECONCILE_ATTEMPTS: usize = 3;
+
 pub(crate) trait MaintenanceTask: Sync + Send {
     fn store_maintenance(
         &self,
@@ -112,22 +128,42 @@ async fn store_maintenance(
     match task.maintenance_type {
         TaskStoreMaintenanceType::ReindexAccounts
         | TaskStoreMaintenanceType::PurgeAccounts
-        | TaskStoreMaintenanceType::ResetUserQuotas => {
+        | TaskStoreMaintenanceType::ResetUserQuotas
+        | TaskStoreMaintenanceType::ReconcileQuotas
+        | TaskStoreMaintenanceType::RecountBlobReferences => {
             let mut batch = BatchBuilder::new();
             let now = now() as i64;
             let maintenance_type = match task.maintenance_type {
                 TaskStoreMaintenanceType::ReindexAccounts => TaskAccountMaintenanceType::Reindex,
                 TaskStoreMaintenanceType::PurgeAccounts => TaskAccountMaintenanceType::Purge,
-                TaskStoreMaintenanceType::ResetUserQuotas => {
+                TaskStoreMaintenanceType::ResetUserQuotas
+                | TaskStoreMaintenanceType::ReconcileQuotas => {
                     TaskAccountMaintenanceType::RecalculateQuota
                 }
+                TaskStoreMaintenanceType::RecountBlobReferences => {
+                    TaskAccountMaintenanceType::RecountBlobReferences
+                }
                 _ => unreachable!(),
             };
-            for account_id in server
+            let mut account_ids = server
                 .registry()
                 .query::<RoaringBitmap>(RegistryQuery::new(ObjectType::Account))
-                .await?
+                .await?;
+
+            // Only a random sample of accounts is checked for drift on each run
+            let sample_size = server.core.email.quota_reconcile_sample_size;
+            if task.maintenance_type == TaskStoreMaintenanceType::ReconcileQuotas
+                && sample_size > 0
+                && account_ids.len() > sample_size as u64
             {
+                account_ids = account_ids
+                    .iter()
+                    .choose_multiple(&mut rand::rng(), sample_size)
+                    .into_iter()
+                    .collect();
+            }
+
+            for account_id in account_ids {
                 #[cfg(feature = "test_mode")]
                 let status = TaskStatus::at(now);
 
@@ -221,6 +257,14 @@ async fn store_maintenance(
                 .await
                 .caused_by(trc::location!())?;
 
+            if let Some(audit_retention) = server.core.email.audit_log_max_history {
+                server
+                    .store()
+                    .purge_audit_events(audit_retention)
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+
             // SPDX-SnippetBegin
             // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
             // SPDX-License-Identifier: LicenseRef-SEL
@@ -346,6 +390,8 @@ async fn store_maintenance(
                     KV_RATE_LIMIT_HTTP_AUTHENTICATED,
                     KV_RATE_LIMIT_HTTP_ANONYMOUS,
                     KV_RATE_LIMIT_IMAP,
+                    KV_RATE_LIMIT_SENDER_VERIFY,
+                    KV_RATE_LIMIT_DSN,
                 ][..],
                 TaskStoreMaintenanceType::ResetBlobQuotas => &[KV_QUOTA_BLOB][..],
                 TaskStoreMaintenanceType::RemoveAuthTokens => &[KV_ACME, KV_OAUTH][..],
@@ -359,6 +405,21 @@ async fn store_maintenance(
                     .await?;
             }
         }
+        TaskStoreMaintenanceType::IncrementalBackup | TaskStoreMaintenanceType::FullBackup => {
+            let Some(path) = server.core.backup.root.clone() else {
+                return Ok(TaskResult::permanent("No backup directory is configured"));
+            };
+            server
+                .core
+                .incremental_backup(IncrementalBackupParams {
+                    path,
+                    max_increments: server.core.backup.max_increments,
+                    full: task.maintenance_type == TaskStoreMaintenanceType::FullBackup,
+                    include_blobs: server.core.backup.include_blobs,
+                })
+                .await
+                .caused_by(trc::location!())?;
+        }
         TaskStoreMaintenanceType::ResetTenantQuotas => {
             // SPDX-SnippetBegin
             // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
@@ -422,6 +483,9 @@ async fn account_maintenance(
         TaskAccountMaintenanceType::RecalculateQuota => {
             recalculate_quota(server, task.account_id.document_id()).await?;
         }
+        TaskAccountMaintenanceType::RecountBlobReferences => {
+            recount_blob_references(server, task.account_id.document_id()).await?;
+        }
     }
 
     Ok(TaskResult::Success(vec![]))
@@ -440,8 +504,124 @@ async fn tenant_maintenance(
     Ok(TaskResult::Success(vec![]))
 }
 
-async fn recalculate_quota(server: &Server, account_id: u32) -> trc::Result<()> {
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaReconciliation {
+    pub used: i64,
+    pub drift: i64,
+    pub mailboxes: usize,
+}
+
+pub async fn recalculate_quota(
+    server: &Server,
+    account_id: u32,
+) -> trc::Result<QuotaReconciliation> {
+    // Counters are corrected with a delta rather than overwritten, so deliveries
+    // committed while the account is being scanned are not lost. The scan is
+    // retried if any counter changes while it is running.
+    for _ in 0..QUOTA_RECONCILE_ATTEMPTS {
+        let mut mailbox_ids = server
+            .get_cached_messages(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .mailboxes
+            .items
+            .iter()
+            .map(|mailbox| mailbox.document_id)
+            .collect::<AHashSet<_>>();
+        let counters = quota_counters(server, account_id, &mailbox_ids).await?;
+        let (used, mailbox_used) = calculate_used_quota(server, account_id).await?;
+        mailbox_ids.extend(mailbox_used.keys().copied());
+        let counters_after = quota_counters(server, account_id, &mailbox_ids).await?;
+        if counters.0 != counters_after.0
+            || counters
+                .1
+                .iter()
+                .any(|(mailbox_id, value)| counters_after.1.get(mailbox_id) != Some(value))
+        {
+            continue;
+        }
+        let (counter, mailbox_counters) = counters_after;
+
+        let mut batch = BatchBuilder::new();
+        batch.with_account_id(account_id);
+        let drift = used - counter;
+        if drift != 0 {
+            batch.add(ValueClass::Quota, drift);
+            if let Some(tenant_id) = server
+                .account(account_id)
+                .await
+                .caused_by(trc::location!())?
+                .id_tenant
+            {
+                batch.add(ValueClass::TenantQuota(tenant_id), drift);
+            }
+        }
+        let mut mailboxes = 0;
+        for (mailbox_id, counter) in mailbox_counters {
+            let drift = mailbox_used.get(&mailbox_id).copied().unwrap_or_default() - counter;
+            if drift != 0 {
+                batch.add(ValueClass::MailboxSize(mailbox_id), drift);
+                mailboxes += 1;
+            }
+        }
+
+        if !batch.is_empty() {
+            server
+                .store()
+                .write(batch.build_all())
+                .await
+                .caused_by(trc::location!())?;
+
+            trc::event!(
+                TaskManager(TaskManagerEvent::QuotaReconciled),
+                AccountId = account_id,
+                Size = drift,
+                Total = used,
+                Value = mailboxes,
+            );
+        }
+
+        return Ok(QuotaReconciliation {
+            used,
+            drift,
+            mailboxes,
+        });
+    }
+
+    Err(trc::TaskManagerEvent::TaskRetry
+        .into_err()
+        .account_id(account_id)
+        .details("Quota counters changed during reconciliation"))
+}
+
+async fn quota_counters(
+    server: &Server,
+    account_id: u32,
+    mailbox_ids: &AHashSet<u32>,
+) -> trc::Result<(i64, AHashMap<u32, i64>)> {
+    let mut mailbox_counters = AHashMap::with_capacity(mailbox_ids.len());
+    for mailbox_id in mailbox_ids {
+        mailbox_counters.insert(
+            *mailbox_id,
+            server
+                .get_used_quota_mailbox(account_id, *mailbox_id)
+                .await?,
+        );
+    }
+
+    Ok((
+        server.get_used_quota_account(account_id).await?,
+        mailbox_counters,
+    ))
+}
+
+async fn calculate_used_quota(
+    server: &Server,
+    account_id: u32,
+) -> trc::Result<(i64, AHashMap<u32, i64>)> {
     let mut quota = 0;
+    let mut mailbox_used = AHashMap::new();
 
     for collection in [
         Collection::Email,
@@ -457,7 +637,14 @@ async fn recalculate_quota(server: &Server, account_id: u32) -> trc::Result<()>
             .archives(account_id, collection, &(), |_, archive| {
                 match collection {
                     Collection::Email => {
-                        quota += archive.unarchive::<MessageData>()?.size.to_native() as i64;
+                        let message = archive.unarchive::<MessageData>()?;
+                        let size = message.size.to_native() as i64;
+                        for mailbox in message.mailboxes.iter() {
+                            *mailbox_used
+                                .entry(mailbox.mailbox_id.to_native())
+                                .or_default() += size;
+                        }
+                        quota += size;
                     }
                     Collection::Calendar => {
                         quota += archive.unarchive::<Calendar>()?.size() as i64;
@@ -488,17 +675,220 @@ async fn recalculate_quota(server: &Server, account_id: u32) -> trc::Result<()>
             .caused_by(trc::location!())?;
     }
 
+    // Contents shared by several messages are only charged once if configured,
+    // messages tracking their references are charged through them
+    if server.core.email.shared_blob_quota == SharedBlobQuota::ChargeOnce {
+        quota += server
+            .account_blob_references(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .values()
+            .map(|reference| reference.charged as i64)
+            .sum::<i64>();
+        quota -= server
+            .account_message_references(account_id)
+            .await
+            .caused_by(trc::location!())?
+            .values()
+            .map(|references| references.size as i64)
+            .sum::<i64>();
+    }
+
+    Ok((quota, mailbox_used))
+}
+
+async fn recount_blob_references(server: &Server, account_id: u32) -> trc::Result<()> {
+    for _ in 0..QUOTA_RECONCILE_ATTEMPTS {
+        match recount_account_blob_references(server, account_id).await {
+            Ok(_) => {
+                // Quota usage depends on the number of references
+                return recalculate_quota(server, account_id).await.map(|_| ());
+            }
+            Err(err) if err.is_assertion_failure() => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(trc::TaskManagerEvent::TaskRetry
+        .into_err()
+        .account_id(account_id)
+        .details("Blob references changed during recount"))
+}
+
+async fn recount_account_blob_references(server: &Server, account_id: u32) -> trc::Result<()> {
+    // Obtain the messages, the blob each one is stored in and the contents
+    // they reference
+    let mut sizes = AHashMap::new();
+    server
+        .archives(
+            account_id,
+            Collection::Email,
+            &(),
+            |document_id, archive| {
+                sizes.insert(
+                    document_id,
+                    archive.unarchive::<MessageData>()?.size.to_native(),
+                );
+                Ok(true)
+            },
+        )
+        .await
+        .caused_by(trc::location!())?;
+    let mut blob_hashes = AHashMap::new();
+    server
+        .all_archives(
+            account_id,
+            Collection::Email,
+            EmailField::Metadata.into(),
+            |document_id, archive| {
+                blob_hashes.insert(
+                    document_id,
+                    (
+                        BlobHash::from(&archive.unarchive::<MessageMetadata>()?.blob_hash),
+                        archive.to_assert_value(),
+                    ),
+                );
+                Ok(())
+            },
+        )
+        .await
+        .caused_by(trc::location!())?;
+    let mut references = server
+        .account_message_references(account_id)
+        .await
+        .caused_by(trc::location!())?;
+    let records = server
+        .account_blob_references(account_id)
+        .await
+        .caused_by(trc::location!())?;
+
     let mut batch = BatchBuilder::new();
+    let mut repaired = 0u64;
     batch
         .with_account_id(account_id)
-        .clear(ValueClass::Quota)
-        .add(ValueClass::Quota, quota);
-    server
-        .store()
-        .write(batch.build_all())
-        .await
-        .caused_by(trc::location!())
-        .map(|_| ())
+        .with_collection(Collection::Email);
+
+    // Messages stored before references were tracked only reference their blob
+    for (document_id, size) in &sizes {
+        if !references.contains_key(document_id)
+            && let Some((hash, _)) = blob_hashes.get(document_id)
+        {
+            let message_references = MessageReferences::message(hash.clone(), *size);
+            batch
+                .with_document(*document_id)
+                .assert_value(EmailField::References, ())
+                .set(EmailField::References, message_references.serialize());
+            references.insert(*document_id, message_references);
+            repaired += 1;
+        }
+    }
+
+    // Messages pending to be unindexed still hold their references
+    let mut expected: AHashMap<BlobHash, BlobReference> = AHashMap::new();
+    for message_references in references.values() {
+        for (hash, charged) in &message_references.blobs {
+            expected
+                .entry(hash.clone())
+                .or_insert_with(|| BlobReference {
+                    count: 0,
+                    charged: records.get(hash).map_or(*charged, |record| record.charged),
+                })
+                .count += 1;
+        }
+    }
+    for (hash, reference) in &expected {
+        let class = ValueClass::BlobReference(hash.clone());
+        match records.get(hash) {
+            Some(record) if record == reference => continue,
+            Some(record) => batch.assert_value(class.clone(), *record),
+            None => batch.assert_value(class.clone(), ()),
+        };
+        batch.set(class, reference.serialize());
+        repaired += 1;
+    }
+    for (hash, record) in &records {
+        if !expected.contains_key(hash) {
+            batch
+                .assert_value(ValueClass::BlobReference(hash.clone()), *record)
+                .clear(ValueClass::BlobReference(hash.clone()));
+            repaired += 1;
+        }
+    }
+
+    // Restore any missing links between messages and their blobs, otherwise
+    // a blob could be purged while still being referenced, and remove the
+    // links of messages that no longer exist
+    let mut hashes = records.into_keys().collect::<AHashSet<_>>();
+    hashes.extend(expected.into_keys());
+    hashes.extend(blob_hashes.values().map(|(hash, _)| hash.clone()));
+    let mut linked = AHashSet::with_capacity(blob_hashes.len());
+    for hash in hashes {
+        for document_id in server
+            .store()
+            .blob_document_links(&hash, account_id, Collection::Email.into())
+            .await
+            .caused_by(trc::location!())?
+        {
+            match blob_hashes.get(&document_id) {
+                Some((blob_hash, _)) if blob_hash == &hash => {
+                    linked.insert(document_id);
+                }
+                Some((_, version)) => {
+                    batch
+                        .with_document(document_id)
+                        .assert_value(EmailField::Metadata, *version)
+                        .clear(BlobOp::Link {
+                            hash: hash.clone(),
+                            to: BlobLink::Document,
+                        });
+                    repaired += 1;
+                }
+                None => {
+                    batch
+                        .with_document(document_id)
+                        .assert_value(EmailField::Metadata, ())
+                        .clear(BlobOp::Link {
+                            hash: hash.clone(),
+                            to: BlobLink::Document,
+                        });
+                    repaired += 1;
+                }
+            }
+        }
+    }
+    for (document_id, (hash, version)) in &blob_hashes {
+        if !linked.contains(document_id) {
+            batch
+                .with_document(*document_id)
+                .assert_value(EmailField::Metadata, *version)
+                .set(
+                    BlobOp::Link {
+                        hash: hash.clone(),
+                        to: BlobLink::Document,
+                    },
+                    Vec::new(),
+                );
+            repaired += 1;
+        }
+    }
+
+    if !batch.is_empty() {
+        server
+            .store()
+            .write(batch.build_all())
+            .await
+            .caused_by(trc::location!())?;
+    }
+
+    trc::event!(
+        TaskManager(TaskManagerEvent::BlobReferencesRecounted),
+        AccountId = account_id,
+        Total = references.len(),
+        Value = blob_hashes.len(),
+        Details = repaired
+    );
+
+    Ok(())
 }
 
 // SPDX-SnippetBegin
@@ -6,17 +6,26 @@
 
 use crate::task_manager::acme::AcmeTask;
 use crate::task_manager::alarm::SendAlarmTask;
+use crate::task_manager::callback::DeliveryCallbackTask;
+use crate::task_manager::dane::DaneRolloverTask;
 use crate::task_manager::destroy_account::DestroyAccountTask;
 use crate::task_manager::dkim::DkimManagementTask;
 use crate::task_manager::dns::DnsManagementTask;
+use crate::task_manager::followup::FollowUpReminderTask;
 use crate::task_manager::imip::SendImipTask;
+use crate::task_manager::import::ImportMessagesTask;
 use crate::task_manager::index::SearchIndexTask;
 use crate::task_manager::lock::TaskLockManager;
 use crate::task_manager::maintenance::MaintenanceTask;
 use crate::task_manager::merge_threads::MergeThreadsTask;
+use crate::task_manager::quota::QuotaWarningTask;
+use crate::task_manager::re_encrypt::ReEncryptAccountTask;
+use crate::task_manager::reindex::ReindexAccountTask;
 use crate::task_manager::report::{self, SubmitReportTask};
 use crate::task_manager::restore_item::RestoreItemTask;
+use crate::task_manager::snooze::RestoreSnoozedEmailTask;
 use crate::task_manager::spam_classifier::SpamFilterMaintenanceTask;
+use crate::task_manager::submission::EmailSubmissionTask;
 use crate::task_manager::{
     DEFAULT_LOCK_EXPIRY, Locked, QUEUE_REFRESH_INTERVAL, TaskDetails, TaskFailureType, TaskInfo,
     TaskJob, TaskManagerIpc, TaskResult,
@@ -91,6 +100,9 @@ pub fn spawn_task_manager(inner: Arc<Inner>) {
                 )
             }
             TaskType::DestroyAccount
+            | TaskType::ReEncryptAccount
+            | TaskType::ReindexAccount
+            | TaskType::ImportMessages
             | TaskType::AccountMaintenance
             | TaskType::TenantMaintenance
             | TaskType::StoreMaintenance => 1,
@@ -99,12 +111,18 @@ pub fn spawn_task_manager(inner: Arc<Inner>) {
             | TaskType::CalendarAlarmNotification
             | TaskType::CalendarItipMessage
             | TaskType::MergeThreads
+            | TaskType::QuotaWarning
+            | TaskType::EmailSubmission
+            | TaskType::RestoreSnoozedEmail
+            | TaskType::FollowUpReminder
+            | TaskType::DeliveryCallback
             | TaskType::DmarcReport
             | TaskType::TlsReport
             | TaskType::RestoreArchivedItem
             | TaskType::AcmeRenewal
             | TaskType::DkimManagement
-            | TaskType::DnsManagement => TASK_QUEUE_BUFFER,
+            | TaskType::DnsManagement
+            | TaskType::DaneRollover => TASK_QUEUE_BUFFER,
         };
 
         let (tx, mut rx) = mpsc::channel::<TaskJob>(channel_capacity);
@@ -219,6 +237,21 @@ pub fn spawn_task_manager(inner: Arc<Inner>) {
                                     server.send_imip(task, server_instance.clone()).await
                                 }
                                 Task::MergeThreads(task) => server.merge_threads(task).await,
+                                Task::QuotaWarning(task) => server.send_quota_warning(task).await,
+                                Task::EmailSubmission(task) => {
+                                    server
+                                        .send_email_submission(task, server_instance.clone())
+                                        .await
+                                }
+                                Task::RestoreSnoozedEmail(task) => {
+                                    server.restore_snoozed_email(task).await
+                                }
+                                Task::FollowUpReminder(task) => {
+                                    server.follow_up_reminder(task).await
+                                }
+                                Task::DeliveryCallback(task) => {
+                                    server.delivery_callback(task).await
+                                }
                                 Task::DmarcReport(task) => {
                                     server
                                         .submit_report(report::ReportId::Dmarc(task.report_id.id()))
@@ -231,6 +264,15 @@ pub fn spawn_task_manager(inner: Arc<Inner>) {
                                 }
                                 Task::RestoreArchivedItem(task) => server.restore_item(task).await,
                                 Task::DestroyAccount(task) => server.destroy_account(task).await,
+                                Task::ReEncryptAccount(task) => {
+                                    server.re_encrypt_account(job.id, task).await
+                                }
+                                Task::ReindexAccount(task) => {
+                                    server.reindex_account(job.id, task).await
+                                }
+                                Task::ImportMessages(task) => {
+                                    server.import_messages(job.id, task).await
+                                }
                                 Task::AccountMaintenance(task) => {
                                     server.account_maintenance(task).await
                                 }
@@ -250,6 +292,7 @@ pub fn spawn_task_manager(inner: Arc<Inner>) {
                                 Task::DnsManagement(task_dns_management) => {
                                     server.dns_management(task_dns_management).await
                                 }
+                                Task::DaneRollover(task) => server.dane_rollover(task).await,
                                 Task::IndexDocument(_)
                                 | Task::UnindexDocument(_)
                                 | Task::IndexTrace(_) => unreachable!(),
@@ -360,19 +403,28 @@ impl TaskQueueManager for Server {
                                 | TaskType::IndexTrace => roles.search_indexing,
                                 TaskType::AccountMaintenance
                                 | TaskType::TenantMaintenance
-                                | TaskType::DestroyAccount => roles.account_maintenance,
+                                | TaskType::DestroyAccount
+                                | TaskType::ReEncryptAccount
+                                | TaskType::ReindexAccount
+                                | TaskType::ImportMessages => roles.account_maintenance,
                                 TaskType::StoreMaintenance => roles.store_maintenance,
                                 TaskType::SpamFilterMaintenance => roles.spam_training,
                                 TaskType::CalendarAlarmEmail
                                 | TaskType::CalendarAlarmNotification
                                 | TaskType::CalendarItipMessage
                                 | TaskType::MergeThreads
+                                | TaskType::QuotaWarning
+                                | TaskType::EmailSubmission
+                                | TaskType::RestoreSnoozedEmail
+                                | TaskType::FollowUpReminder
+                                | TaskType::DeliveryCallback
                                 | TaskType::DmarcReport
                                 | TaskType::TlsReport
                                 | TaskType::RestoreArchivedItem
                                 | TaskType::AcmeRenewal
                                 | TaskType::DkimManagement
-                                | TaskType::DnsManagement => true,
+                                | TaskType::DnsManagement
+                                | TaskType::DaneRollover => true,
                             };
 
                             if !enabled {
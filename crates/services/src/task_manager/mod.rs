@@ -17,19 +17,28 @@ use trc::TaskManagerEvent;
 
 pub mod acme;
 pub mod alarm;
+pub mod callback;
+pub mod dane;
 pub mod destroy_account;
 pub mod dkim;
 pub mod dns;
+pub mod followup;
 pub mod imip;
+pub mod import;
 pub mod index;
 pub mod lock;
 pub mod maintenance;
 pub mod manager;
 pub mod merge_threads;
+pub mod quota;
+pub mod re_encrypt;
+pub mod reindex;
 pub mod report;
 pub mod restore_item;
 pub mod scheduler;
+pub mod snooze;
 pub mod spam_classifier;
+pub mod submission;
 
 const QUEUE_REFRESH_INTERVAL: u64 = 60 * 5; // 5 minutes
 const DEFAULT_LOCK_EXPIRY: u64 = 60 * 60; // 1 hour
@@ -94,16 +103,25 @@ impl TaskInfo for Task {
             Task::CalendarAlarmNotification(_) => "CalendarAlarmNotification",
             Task::CalendarItipMessage(_) => "CalendarItipMessage",
             Task::MergeThreads(_) => "MergeThreads",
+            Task::QuotaWarning(_) => "QuotaWarning",
+            Task::EmailSubmission(_) => "EmailSubmission",
+            Task::RestoreSnoozedEmail(_) => "RestoreSnoozedEmail",
+            Task::FollowUpReminder(_) => "FollowUpReminder",
+            Task::DeliveryCallback(_) => "DeliveryCallback",
             Task::DmarcReport(_) => "DmarcReport",
             Task::TlsReport(_) => "TlsReport",
             Task::RestoreArchivedItem(_) => "RestoreArchivedItem",
             Task::DestroyAccount(_) => "DestroyAccount",
+            Task::ReEncryptAccount(_) => "ReEncryptAccount",
+            Task::ReindexAccount(_) => "ReindexAccount",
+            Task::ImportMessages(_) => "ImportMessages",
             Task::AccountMaintenance(_) => "AccountMaintenance",
             Task::StoreMaintenance(_) => "StoreMaintenance",
             Task::SpamFilterMaintenance(_) => "SpamFilterMaintenance",
             Task::AcmeRenewal(_) => "AcmeRenewal",
             Task::DkimManagement(_) => "DkimManagement",
             Task::DnsManagement(_) => "DnsManagement",
+            Task::DaneRollover(_) => "DaneRollover",
             Task::TenantMaintenance(_) => "TenantMaintenance",
         }
     }
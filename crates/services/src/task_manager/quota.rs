@@ -0,0 +1,161 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::{
+    Server,
+    auth::BuildAccessToken,
+    config::templates::{MessageTemplateVariable, format_size},
+    i18n,
+};
+use email::{
+    mailbox::INBOX_ID,
+    message::ingest::{EmailIngest, IngestEmail, IngestSource},
+};
+use mail_builder::{MessageBuilder, headers::HeaderType};
+use mail_parser::MessageParser;
+use registry::{
+    schema::{enums::MessageTemplateId, structs::TaskQuotaWarning},
+    types::EnumImpl,
+};
+use store::write::now;
+use trc::{AddContext, TaskManagerEvent};
+use utils::template::Variables;
+
+pub(crate) trait QuotaWarningTask: Sync + Send {
+    fn send_quota_warning(
+        &self,
+        task: &TaskQuotaWarning,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl QuotaWarningTask for Server {
+    async fn send_quota_warning(&self, task: &TaskQuotaWarning) -> TaskResult {
+        match send_quota_warning(self, task).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(task.account_id.document_id())
+                        .caused_by(trc::location!())
+                        .details("Failed to deliver quota warning")
+                );
+                result
+            }
+        }
+    }
+}
+
+async fn send_quota_warning(server: &Server, task: &TaskQuotaWarning) -> trc::Result<TaskResult> {
+    let account_id = task.account_id.document_id();
+    let account_info = server
+        .account_info(account_id)
+        .await
+        .caused_by(trc::location!())?;
+    let quota = account_info.account().quota_disk;
+    if quota == 0 {
+        return Ok(TaskResult::permanent("Account has no disk quota"));
+    }
+    let used = server
+        .get_used_quota_account(account_id)
+        .await
+        .caused_by(trc::location!())?
+        .max(0) as u64;
+
+    // Build message, using the domain's or tenant's template when one is configured
+    let percent = task.threshold.to_string();
+    let rcpt_to = account_info
+        .addresses()
+        .first()
+        .map(|address| address.as_str())
+        .unwrap_or_else(|| account_info.name());
+    let domain = rcpt_to
+        .rsplit_once('@')
+        .map(|(_, domain)| domain)
+        .unwrap_or(server.core.email.default_domain_name.as_str());
+    let mail_from = format!("postmaster@{domain}");
+    let message = MessageBuilder::new()
+        .from(mail_from.as_str())
+        .to(rcpt_to)
+        .header("Auto-Submitted", HeaderType::Text("auto-generated".into()))
+        .message_id(format!("quota.{account_id}.{}@{domain}", now()));
+    let scope = server.account_template_scope(account_id).await;
+    let message = if let Some(template) = server
+        .core
+        .templates
+        .get(MessageTemplateId::QuotaWarning, &scope)
+    {
+        let mut variables = Variables::new();
+        variables.insert_single(MessageTemplateVariable::Recipient, rcpt_to.to_string());
+        variables.insert_single(MessageTemplateVariable::Percent, percent);
+        variables.insert_single(MessageTemplateVariable::Used, format_size(used));
+        variables.insert_single(MessageTemplateVariable::Limit, format_size(quota));
+        let rendered = template.render(&variables);
+        let message = message
+            .subject(rendered.subject)
+            .text_body(rendered.text_body);
+        if let Some(html_body) = rendered.html_body {
+            message.html_body(html_body)
+        } else {
+            message
+        }
+    } else {
+        let locale = i18n::locale_or_default(account_info.locale().as_str());
+        message
+            .subject(locale.quota_warning_subject.replace("$percent", &percent))
+            .text_body(
+                locale
+                    .quota_warning_body
+                    .replace("$percent", &percent)
+                    .replace("$used", &format_size(used))
+                    .replace("$limit", &format_size(quota)),
+            )
+    }
+    .write_to_vec()
+    .unwrap_or_default();
+
+    // Deliver to the account's inbox
+    let access_token = server
+        .access_token(account_id)
+        .await
+        .caused_by(trc::location!())?
+        .build();
+    match server
+        .email_ingest(IngestEmail {
+            raw_message: &message,
+            blob_hash: None,
+            message: MessageParser::new().parse(&message),
+            access_token: &access_token,
+            mailbox_ids: vec![INBOX_ID],
+            keywords: vec![],
+            received_at: None,
+            source: IngestSource::Smtp {
+                deliver_to: rcpt_to,
+                is_sender_authenticated: true,
+                is_spam: false,
+                is_duplicate: false,
+                spam_tags: &[],
+            },
+            session_id: 0,
+        })
+        .await
+    {
+        Ok(_) => {
+            trc::event!(
+                TaskManager(TaskManagerEvent::QuotaWarningSent),
+                AccountId = account_id,
+                Limit = task.threshold,
+                Size = used,
+            );
+
+            Ok(TaskResult::Success(vec![]))
+        }
+        Err(err) if err.matches(trc::EventType::Limit(trc::LimitEvent::Quota)) => {
+            Ok(TaskResult::permanent("Account quota exceeded"))
+        }
+        Err(err) => Err(err),
+    }
+}
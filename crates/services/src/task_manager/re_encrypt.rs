@@ -0,0 +1,237 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::{Server, auth::EncryptionKeys, storage::encryption::parse_public_key};
+use email::{
+    cache::MessageCacheFetch,
+    message::re_encrypt::{EmailReEncrypt, ReEncryptResult},
+};
+use registry::{
+    schema::{
+        prelude::ObjectType,
+        structs::{PublicKey, Task, TaskReEncryptAccount, TaskStatus},
+    },
+    types::{EnumImpl, ObjectImpl},
+};
+use std::{sync::Arc, time::Duration};
+use store::{
+    SerializeInfallible,
+    rand::Rng,
+    registry::RegistryQuery,
+    write::{Operation, TaskQueueClass, ValueClass, ValueOp, now},
+};
+use tokio::task::JoinSet;
+use trc::{AddContext, TaskManagerEvent};
+use types::id::Id;
+
+const MAX_RETRIES: usize = 5;
+const MAX_MESSAGES_PER_RUN: u64 = 250;
+const MAX_MESSAGES_PER_CHECKPOINT: usize = 50;
+
+pub(crate) trait ReEncryptAccountTask: Sync + Send {
+    fn re_encrypt_account(
+        &self,
+        id: u64,
+        task: &TaskReEncryptAccount,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl ReEncryptAccountTask for Server {
+    async fn re_encrypt_account(&self, id: u64, task: &TaskReEncryptAccount) -> TaskResult {
+        match re_encrypt_account(self, id, task).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(task.account_id.document_id())
+                        .details("Failed to re-encrypt account")
+                );
+                result
+            }
+        }
+    }
+}
+
+async fn re_encrypt_account(
+    server: &Server,
+    id: u64,
+    task: &TaskReEncryptAccount,
+) -> trc::Result<TaskResult> {
+    let account_id = task.account_id.document_id();
+    let account = server
+        .account(account_id)
+        .await
+        .caused_by(trc::location!())?;
+    let Some(keys) = account
+        .encryption_key
+        .as_ref()
+        .filter(|_| server.core.email.encrypt)
+    else {
+        trc::event!(
+            TaskManager(TaskManagerEvent::TaskIgnored),
+            Reason = "Encryption at rest is disabled for this account",
+            AccountId = account_id,
+        );
+        return Ok(TaskResult::Ignored);
+    };
+
+    // Obtain the messages that are pending to be processed
+    let mut document_ids = server
+        .get_cached_messages(account_id)
+        .await
+        .caused_by(trc::location!())?
+        .emails
+        .items
+        .iter()
+        .map(|v| v.document_id)
+        .filter(|document_id| {
+            task.last_document_id
+                .is_none_or(|last_id| *document_id as u64 > last_id)
+        })
+        .collect::<Vec<_>>();
+    document_ids.sort_unstable();
+
+    // Private keys held by the server are used to decrypt messages encrypted
+    // with a previous key, the current key is excluded
+    let decryption_keys = Arc::new(
+        server_held_keys(server, account_id, keys)
+            .await
+            .caused_by(trc::location!())?,
+    );
+
+    let mut task = task.clone();
+    task.messages_total = task.messages_processed + document_ids.len() as u64;
+    let concurrency = task
+        .concurrency
+        .map(|concurrency| concurrency as usize)
+        .unwrap_or(server.core.email.re_encrypt_concurrency)
+        .max(1);
+    let mut processed = 0;
+
+    for chunk in document_ids.chunks(MAX_MESSAGES_PER_CHECKPOINT) {
+        // Keep at most `concurrency` messages of this account in flight
+        let mut tasks = JoinSet::new();
+        let mut pending = chunk.iter().copied();
+        loop {
+            while tasks.len() < concurrency
+                && let Some(document_id) = pending.next()
+            {
+                let server = server.clone();
+                let keys = keys.clone();
+                let decryption_keys = decryption_keys.clone();
+                let tenant_id = account.id_tenant;
+                let flags = account.flags;
+                tasks.spawn(async move {
+                    let mut try_count = 0;
+                    loop {
+                        match server
+                            .re_encrypt_message(
+                                account_id,
+                                tenant_id,
+                                document_id,
+                                &keys,
+                                flags,
+                                &decryption_keys,
+                            )
+                            .await
+                        {
+                            Err(err) if err.is_assertion_failure() && try_count < MAX_RETRIES => {
+                                let backoff = store::rand::rng().random_range(50..=300);
+                                tokio::time::sleep(Duration::from_millis(backoff)).await;
+                                try_count += 1;
+                            }
+                            result => return (document_id, result),
+                        }
+                    }
+                });
+            }
+
+            let Some(result) = tasks.join_next().await else {
+                break;
+            };
+            let (document_id, result) = result.map_err(|err| {
+                trc::EventType::Server(trc::ServerEvent::ThreadError)
+                    .reason(err)
+                    .caused_by(trc::location!())
+                    .details("Join Error")
+            })?;
+            match result? {
+                ReEncryptResult::ReEncrypted => {
+                    task.messages_re_encrypted += 1;
+                }
+                ReEncryptResult::Skipped(reason) => {
+                    task.messages_skipped += 1;
+                    trc::event!(
+                        TaskManager(TaskManagerEvent::ReEncryptSkipped),
+                        AccountId = account_id,
+                        DocumentId = document_id,
+                        Reason = reason,
+                    );
+                }
+                ReEncryptResult::NotFound => {}
+            }
+        }
+
+        // Keep track of progress so the task can be resumed
+        task.messages_processed += chunk.len() as u64;
+        task.last_document_id = chunk.last().map(|document_id| *document_id as u64);
+        processed += chunk.len() as u64;
+
+        if processed >= MAX_MESSAGES_PER_RUN && task.messages_processed < task.messages_total {
+            // Reschedule to continue with the next batch of messages
+            let due = now() + 1;
+            task.status = TaskStatus::at(due as i64);
+            let task = Task::ReEncryptAccount(task);
+
+            return Ok(TaskResult::Update([
+                Operation::Value {
+                    class: ValueClass::TaskQueue(TaskQueueClass::Due { id, due }),
+                    op: ValueOp::Set(task.object_type().to_id().serialize()),
+                },
+                Operation::Value {
+                    class: ValueClass::TaskQueue(TaskQueueClass::Task { id }),
+                    op: ValueOp::Set(task.to_pickled_vec()),
+                },
+            ]));
+        }
+    }
+
+    trc::event!(
+        TaskManager(TaskManagerEvent::ReEncryptCompleted),
+        AccountId = account_id,
+        Total = task.messages_total,
+        TotalSuccesses = task.messages_re_encrypted,
+        TotalFailures = task.messages_skipped,
+    );
+
+    Ok(TaskResult::Success(vec![]))
+}
+
+async fn server_held_keys(
+    server: &Server,
+    account_id: u32,
+    current_keys: &EncryptionKeys,
+) -> trc::Result<Vec<String>> {
+    let mut keys = Vec::new();
+    for id in server
+        .registry()
+        .query::<Vec<Id>>(RegistryQuery::new(ObjectType::PublicKey).with_account(account_id))
+        .await?
+    {
+        if let Some(key) = server.registry().object::<PublicKey>(id).await?
+            && key.key.contains("PRIVATE KEY")
+            && parse_public_key(&key)
+                .ok()
+                .flatten()
+                .is_none_or(|params| params.certs != *current_keys)
+        {
+            keys.push(key.key);
+        }
+    }
+
+    Ok(keys)
+}
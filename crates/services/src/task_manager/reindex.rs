@@ -0,0 +1,174 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::{TaskResult, index::build_email_document};
+use common::Server;
+use email::cache::MessageCacheFetch;
+use registry::{
+    schema::structs::{Task, TaskReindexAccount, TaskStatus},
+    types::{EnumImpl, ObjectImpl},
+};
+use std::time::Instant;
+use store::{
+    SerializeInfallible,
+    write::{Operation, SearchIndex, TaskQueueClass, ValueClass, ValueOp, now},
+};
+use tokio::task::JoinSet;
+use trc::{AddContext, TaskManagerEvent};
+
+const MAX_MESSAGES_PER_RUN: u64 = 1000;
+
+pub(crate) trait ReindexAccountTask: Sync + Send {
+    fn reindex_account(
+        &self,
+        id: u64,
+        task: &TaskReindexAccount,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl ReindexAccountTask for Server {
+    async fn reindex_account(&self, id: u64, task: &TaskReindexAccount) -> TaskResult {
+        match reindex_account(self, id, task).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(task.account_id.document_id())
+                        .details("Failed to reindex account")
+                );
+                result
+            }
+        }
+    }
+}
+
+async fn reindex_account(
+    server: &Server,
+    id: u64,
+    task: &TaskReindexAccount,
+) -> trc::Result<TaskResult> {
+    let account_id = task.account_id.document_id();
+    if !server
+        .core
+        .email
+        .index_fields
+        .contains_key(&SearchIndex::Email)
+    {
+        trc::event!(
+            TaskManager(TaskManagerEvent::TaskIgnored),
+            Reason = "Email indexing is disabled",
+            AccountId = account_id,
+        );
+        return Ok(TaskResult::Ignored);
+    }
+
+    // Obtain the messages in scope that are pending to be processed
+    let mailbox_id = task.mailbox_id.map(|id| id.document_id());
+    let mut document_ids = server
+        .get_cached_messages(account_id)
+        .await
+        .caused_by(trc::location!())?
+        .emails
+        .items
+        .iter()
+        .filter(|item| {
+            mailbox_id
+                .is_none_or(|mailbox_id| item.mailboxes.iter().any(|m| m.mailbox_id == mailbox_id))
+        })
+        .map(|item| item.document_id)
+        .filter(|document_id| {
+            task.last_document_id
+                .is_none_or(|last_id| *document_id as u64 > last_id)
+        })
+        .collect::<Vec<_>>();
+    document_ids.sort_unstable();
+
+    let received_from = task.received_after.map_or(0, |dt| dt.timestamp() as u64);
+    let received_to = task
+        .received_before
+        .map_or(u64::MAX, |dt| dt.timestamp() as u64);
+    let mut task = task.clone();
+    task.messages_total = task.messages_processed + document_ids.len() as u64;
+    let concurrency = server.core.email.reindex_concurrency.max(1);
+    let rate = server.core.email.reindex_rate.as_ref();
+    let started = Instant::now();
+    let mut processed = 0;
+
+    for chunk in document_ids.chunks(concurrency) {
+        let mut tasks = JoinSet::new();
+        for &document_id in chunk {
+            let server = server.clone();
+            tasks.spawn(async move {
+                build_email_document(&server, account_id, document_id, received_from..received_to)
+                    .await
+            });
+        }
+
+        // Documents are replaced in place, so messages that have not been
+        // reindexed yet remain searchable using their previous entries
+        let mut documents = Vec::with_capacity(chunk.len());
+        for document in tasks.join_all().await {
+            if let Some(document) = document?.filter(|document| !document.is_empty()) {
+                documents.push(document.with_replace());
+            }
+        }
+        if !documents.is_empty() {
+            task.messages_indexed += documents.len() as u64;
+            server
+                .search_store()
+                .index(documents)
+                .await
+                .caused_by(trc::location!())?;
+        }
+
+        // Keep track of progress so the task can be resumed
+        task.messages_processed += chunk.len() as u64;
+        task.last_document_id = chunk.last().map(|document_id| *document_id as u64);
+        processed += chunk.len() as u64;
+
+        if task.messages_processed >= task.messages_total {
+            break;
+        } else if processed >= MAX_MESSAGES_PER_RUN {
+            // Reschedule to continue with the next batch of messages
+            let due = now() + 1;
+            task.status = TaskStatus::at(due as i64);
+            let task = Task::ReindexAccount(task);
+
+            return Ok(TaskResult::Update([
+                Operation::Value {
+                    class: ValueClass::TaskQueue(TaskQueueClass::Due { id, due }),
+                    op: ValueOp::Set(task.object_type().to_id().serialize()),
+                },
+                Operation::Value {
+                    class: ValueClass::TaskQueue(TaskQueueClass::Task { id }),
+                    op: ValueOp::Set(task.to_pickled_vec()),
+                },
+            ]));
+        }
+
+        // Throttle to the configured rate
+        if let Some(rate) = rate.filter(|rate| rate.count > 0) {
+            let expected = rate
+                .period
+                .into_inner()
+                .mul_f64(processed as f64 / rate.count as f64);
+            let elapsed = started.elapsed();
+            if expected > elapsed {
+                tokio::time::sleep(expected - elapsed).await;
+            }
+        }
+    }
+
+    trc::event!(
+        TaskManager(TaskManagerEvent::ReindexCompleted),
+        AccountId = account_id,
+        Total = task.messages_total,
+        Details = task.messages_indexed,
+        Elapsed = started.elapsed(),
+    );
+
+    Ok(TaskResult::Success(vec![]))
+}
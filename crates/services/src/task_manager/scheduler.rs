@@ -36,6 +36,7 @@ enum Event {
     PurgeAccount,
     PurgeDataStore,
     PurgeBlobStore,
+    ReconcileQuotas,
     OtelMetrics,
     CalculateMetrics,
     TrainSpamClassifier,
@@ -88,6 +89,14 @@ pub fn spawn_task_scheduler(inner: Arc<Inner>) {
                 Event::PurgeBlobStore,
             );
 
+            // Quota drift checks
+            if server.core.email.quota_reconcile_sample_size > 0 {
+                queue.schedule(
+                    Instant::now() + server.core.email.quota_reconcile_interval,
+                    Event::ReconcileQuotas,
+                );
+            }
+
             // Node ID lease renewal
             if server.core.storage.coordinator.is_enabled() {
                 queue.schedule(
@@ -229,6 +238,27 @@ pub fn spawn_task_scheduler(inner: Arc<Inner>) {
                             }));
                         }
                     }
+                    Event::ReconcileQuotas => {
+                        if server.core.email.quota_reconcile_sample_size > 0 {
+                            queue.schedule(
+                                Instant::now() + server.core.email.quota_reconcile_interval,
+                                Event::ReconcileQuotas,
+                            );
+
+                            if let Some(batch) = batch.as_mut() {
+                                trc::event!(
+                                    TaskManager(TaskManagerEvent::TaskQueued),
+                                    Type = TaskStoreMaintenanceType::ReconcileQuotas.as_str()
+                                );
+
+                                batch.schedule_task(Task::StoreMaintenance(TaskStoreMaintenance {
+                                    maintenance_type: TaskStoreMaintenanceType::ReconcileQuotas,
+                                    status: TaskStatus::now(),
+                                    shard_index: None,
+                                }));
+                            }
+                        }
+                    }
                     Event::RenewNodeIdLease => {
                         queue.schedule(
                             Instant::now() + server.registry().refresh_node_id_interval(),
@@ -567,6 +597,7 @@ impl Event {
             Event::PurgeAccount => "purgeAccount",
             Event::PurgeDataStore => "purgeDataStore",
             Event::PurgeBlobStore => "purgeBlobStore",
+            Event::ReconcileQuotas => "reconcileQuotas",
             Event::OtelMetrics => "otelMetrics",
             Event::CalculateMetrics => "calculateMetrics",
             Event::TrainSpamClassifier => "trainSpamClassifier",
@@ -0,0 +1,40 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::Server;
+use email::message::snooze::{EmailSnoozeFnc, UnsnoozeResult};
+use registry::schema::structs::TaskRestoreSnoozedEmail;
+
+pub(crate) trait RestoreSnoozedEmailTask: Sync + Send {
+    fn restore_snoozed_email(
+        &self,
+        task: &TaskRestoreSnoozedEmail,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl RestoreSnoozedEmailTask for Server {
+    async fn restore_snoozed_email(&self, task: &TaskRestoreSnoozedEmail) -> TaskResult {
+        let account_id = task.account_id.document_id();
+        let document_id = task.document_id.document_id();
+
+        // Messages that were deleted, unsnoozed or snoozed again are skipped
+        match self.email_unsnooze(account_id, document_id).await {
+            Ok(UnsnoozeResult::Restored) => TaskResult::Success(vec![]),
+            Ok(UnsnoozeResult::NotSnoozed | UnsnoozeResult::NotDue) => TaskResult::Ignored,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(account_id)
+                        .document_id(document_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to restore snoozed email")
+                );
+                result
+            }
+        }
+    }
+}
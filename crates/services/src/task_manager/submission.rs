@@ -0,0 +1,267 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::task_manager::TaskResult;
+use common::{
+    Server,
+    config::smtp::queue::QueueName,
+    network::{ServerInstance, stream::NullIo},
+    storage::index::ObjectIndexBuilder,
+};
+use email::{
+    message::metadata::MessageMetadata,
+    submission::{Address, EmailSubmission, UndoStatus},
+};
+use registry::schema::structs::TaskEmailSubmission;
+use smtp::{
+    core::{Session, SessionData},
+    inbound::submit::{
+        LocalSubmission, LocalSubmissionError, from_into_static, rcpt_into_static,
+        submission_message,
+    },
+    queue::spool::SmtpSpool,
+};
+use smtp_proto::request::parser::Rfc5321Parser;
+use std::{str::FromStr, sync::Arc};
+use store::{
+    ValueKey,
+    write::{AlignedBytes, Archive, BatchBuilder, now},
+};
+use trc::AddContext;
+use types::{collection::Collection, field::EmailField, id::Id};
+
+pub(crate) trait EmailSubmissionTask: Sync + Send {
+    fn send_email_submission(
+        &self,
+        task: &TaskEmailSubmission,
+        server_instance: Arc<ServerInstance>,
+    ) -> impl Future<Output = TaskResult> + Send;
+}
+
+impl EmailSubmissionTask for Server {
+    async fn send_email_submission(
+        &self,
+        task: &TaskEmailSubmission,
+        server_instance: Arc<ServerInstance>,
+    ) -> TaskResult {
+        match send_email_submission(self, task, server_instance).await {
+            Ok(result) => result,
+            Err(err) => {
+                let result = TaskResult::temporary(err.to_string());
+                trc::error!(
+                    err.account_id(task.account_id.document_id())
+                        .document_id(task.document_id.document_id())
+                        .caused_by(trc::location!())
+                        .details("Failed to send scheduled email submission")
+                );
+                result
+            }
+        }
+    }
+}
+
+async fn send_email_submission(
+    server: &Server,
+    task: &TaskEmailSubmission,
+    server_instance: Arc<ServerInstance>,
+) -> trc::Result<TaskResult> {
+    let account_id = task.account_id.document_id();
+    let document_id = task.document_id.document_id();
+
+    // Submissions that were destroyed, cancelled or rescheduled are skipped
+    let Some(submission) = fetch_submission(server, account_id, document_id).await? else {
+        return Ok(TaskResult::Ignored);
+    };
+    if !submission.inner.is_scheduled() || submission.inner.send_at > now() {
+        return Ok(TaskResult::Ignored);
+    }
+
+    // Run the submission through the local SMTP session
+    let mut new_submission = submission.inner.clone();
+    match dispatch(server, account_id, &new_submission, server_instance).await? {
+        Ok(result) => {
+            if result.queue_id.is_some() {
+                new_submission.undo_status = UndoStatus::Final;
+            }
+            new_submission.queue_id = result.queue_id;
+            new_submission.set_delivery_status(result.rcpt_responses);
+        }
+        Err(reason) => {
+            new_submission.set_delivery_status(
+                new_submission
+                    .envelope
+                    .rcpt_to
+                    .iter()
+                    .map(|rcpt| (rcpt.email.clone(), Some(reason.clone())))
+                    .collect(),
+            );
+        }
+    }
+
+    // Record the delivery status, unless the submission was cancelled or
+    // rescheduled while it was being sent
+    let queue_id = new_submission.queue_id;
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(account_id)
+        .with_collection(Collection::EmailSubmission)
+        .with_document(document_id)
+        .custom(
+            ObjectIndexBuilder::new()
+                .with_current(submission)
+                .with_changes(new_submission),
+        )
+        .caused_by(trc::location!())?;
+    match server.commit_batch(batch).await {
+        Ok(_) => Ok(TaskResult::Success(vec![])),
+        Err(err) if err.is_assertion_failure() => {
+            // Withdraw the message from the queue
+            if let Some(queue_id) = queue_id
+                && let Some(message) = server.read_message(queue_id, QueueName::default()).await
+            {
+                message.remove(server, None).await;
+            }
+            Ok(TaskResult::Ignored)
+        }
+        Err(err) => Err(err.caused_by(trc::location!())),
+    }
+}
+
+async fn dispatch(
+    server: &Server,
+    account_id: u32,
+    submission: &EmailSubmission,
+    server_instance: Arc<ServerInstance>,
+) -> trc::Result<Result<LocalSubmission, String>> {
+    // Obtain message metadata
+    let Some(metadata_) = server
+        .store()
+        .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+            account_id,
+            Collection::Email,
+            submission.email_id,
+            EmailField::Metadata,
+        ))
+        .await
+        .caused_by(trc::location!())?
+    else {
+        return Ok(Err("Email not found.".to_string()));
+    };
+    let metadata = metadata_
+        .unarchive::<MessageMetadata>()
+        .caused_by(trc::location!())?;
+
+    // Obtain raw message
+    let Some(message) = submission_message(server, metadata).await? else {
+        return Ok(Err("Blob for email not found.".to_string()));
+    };
+    if message.len() > server.core.email.mail_max_size {
+        return Ok(Err(format!(
+            "Message exceeds maximum size of {} bytes.",
+            server.core.email.mail_max_size
+        )));
+    }
+
+    // Parse the envelope
+    let envelope = &submission.envelope;
+    let mail_from =
+        match Rfc5321Parser::new(&mut smtp_parameters(&envelope.mail_from).as_bytes().iter())
+            .mail_from_parameters(envelope.mail_from.email.as_str().into())
+        {
+            Ok(mail_from) => from_into_static(mail_from),
+            Err(err) => {
+                return Ok(Err(format!("Failed to parse mailFrom parameters: {err}.")));
+            }
+        };
+    let mut rcpt_to = Vec::with_capacity(envelope.rcpt_to.len());
+    for rcpt in &envelope.rcpt_to {
+        match Rfc5321Parser::new(&mut smtp_parameters(rcpt).as_bytes().iter())
+            .rcpt_to_parameters(rcpt.email.as_str().into())
+        {
+            Ok(rcpt) => rcpt_to.push(rcpt_into_static(rcpt)),
+            Err(err) => {
+                return Ok(Err(format!("Failed to parse rcptTo parameters: {err}.")));
+            }
+        }
+    }
+    let delivery_callback = envelope
+        .mail_from
+        .parameters
+        .as_ref()
+        .and_then(|params| {
+            params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("X-CALLBACK"))
+        })
+        .and_then(|(_, value)| value.as_deref())
+        .and_then(|value| Id::from_str(value).ok())
+        .map(|id| id.id());
+
+    // Submit the message through a local SMTP session
+    Session::<NullIo>::local(
+        server.clone(),
+        server_instance,
+        SessionData::local(
+            server
+                .account_info(account_id)
+                .await
+                .caused_by(trc::location!())?,
+            None,
+            vec![],
+            vec![],
+            0,
+        ),
+    )
+    .submit_message(mail_from, rcpt_to, delivery_callback, message)
+    .await
+    .map(|result| {
+        result.map_err(|err| match err {
+            LocalSubmissionError::MailFrom(reason) | LocalSubmissionError::Data(reason) => reason,
+        })
+    })
+}
+
+async fn fetch_submission(
+    server: &Server,
+    account_id: u32,
+    document_id: u32,
+) -> trc::Result<Option<Archive<EmailSubmission>>> {
+    server
+        .store()
+        .get_value::<Archive<AlignedBytes>>(ValueKey::archive(
+            account_id,
+            Collection::EmailSubmission,
+            document_id,
+        ))
+        .await
+        .caused_by(trc::location!())?
+        .map(|submission| {
+            submission
+                .into_deserialized::<EmailSubmission>()
+                .caused_by(trc::location!())
+        })
+        .transpose()
+}
+
+// Rebuilds the SMTP parameters of an envelope address, X-CALLBACK is
+// handled internally and not passed to the SMTP parser
+fn smtp_parameters(address: &Address) -> String {
+    let mut params_text = String::new();
+    for (k, v) in address.parameters.iter().flat_map(|params| params.iter()) {
+        if !k.eq_ignore_ascii_case("X-CALLBACK") {
+            if !params_text.is_empty() {
+                params_text.push(' ');
+            }
+            params_text.push_str(k);
+            if let Some(v) = v {
+                params_text.push('=');
+                params_text.push_str(v);
+            }
+        }
+    }
+    params_text.push('\n');
+    params_text
+}
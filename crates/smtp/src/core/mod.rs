@@ -4,17 +4,27 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::{inbound::auth::SaslToken, queue::QueueId};
+use crate::{
+    inbound::{auth::SaslToken, spool::BdatSpool},
+    queue::QueueId,
+};
 use common::{
     Inner, Server,
     auth::AccountInfo,
-    config::smtp::auth::VerifyStrategy,
-    network::{ServerInstance, asn::AsnGeoLookupResult},
+    config::smtp::{
+        auth::{SenderVerifyResult, VerifyStrategy},
+        session::EhloExtensions,
+    },
+    expr::functions::header::MessageHeaders,
+    network::{
+        ServerInstance, asn::AsnGeoLookupResult, sessions::SessionLease, tls::ClientCertificate,
+    },
 };
 use mail_auth::{IprevOutput, SpfOutput};
 use smtp_proto::request::receiver::{
     BdatReceiver, DataReceiver, DummyDataReceiver, DummyLineReceiver, LineReceiver, RequestReceiver,
 };
+use spam_filter::modules::reputation::SenderReputation;
 use std::{
     hash::Hash,
     net::IpAddr,
@@ -44,6 +54,7 @@ pub enum State {
     Data(DataReceiver),
     Sasl(LineReceiver<SaslToken>),
     DataTooLarge(DummyDataReceiver),
+    BdatRejected(DummyDataReceiver),
     RequestTooLarge(DummyLineReceiver),
     Accepted(QueueId),
     None,
@@ -69,15 +80,23 @@ pub struct SessionData {
     pub remote_port: u16,
     pub asn_geo_data: AsnGeoLookupResult,
     pub helo_domain: String,
+    pub chunking: bool,
 
     pub mail_from: Option<SessionAddress>,
     pub rcpt_to: Vec<SessionAddress>,
+    pub expanded_from: Vec<Box<str>>,
     pub rcpt_errors: usize,
     pub rcpt_oks: usize,
+    pub lmtp_rcpts: Vec<Vec<String>>,
     pub message: Vec<u8>,
+    pub bdat_spool: Option<BdatSpool>,
+    pub message_headers: Option<Arc<MessageHeaders>>,
+    pub declared_size: usize,
 
     pub authenticated_as: Option<AccountInfo>,
     pub auth_errors: usize,
+    pub client_cert: Option<ClientCertificate>,
+    pub session_lease: Option<SessionLease>,
 
     pub priority: i16,
     pub delivery_by: i64,
@@ -90,6 +109,11 @@ pub struct SessionData {
     pub iprev: Option<IprevOutput>,
     pub spf_ehlo: Option<SpfOutput>,
     pub spf_mail_from: Option<SpfOutput>,
+    pub sender_verify: Option<SenderVerifyResult>,
+    pub sender_reputation: Option<SenderReputation>,
+    pub delivery_callback: Option<u64>,
+    pub partial_request: bool,
+    pub queue_meta: Vec<(String, String)>,
     pub dnsbl_error: Option<Vec<u8>>,
 }
 
@@ -110,6 +134,9 @@ pub struct SessionParameters {
     // Ehlo parameters
     pub ehlo_require: bool,
     pub ehlo_reject_non_fqdn: bool,
+    pub ehlo_hidden: EhloExtensions,
+    pub ehlo_reject_hidden: bool,
+    pub ehlo_callback: bool,
 
     // Auth parameters
     pub auth_require: bool,
@@ -127,6 +154,7 @@ pub struct SessionParameters {
 
     // Mail authentication parameters
     pub iprev: VerifyStrategy,
+    pub iprev_require: bool,
     pub spf_ehlo: VerifyStrategy,
     pub spf_mail_from: VerifyStrategy,
 }
@@ -150,15 +178,23 @@ impl SessionData {
             remote_port,
             asn_geo_data,
             helo_domain: String::new(),
+            chunking: false,
             mail_from: None,
             rcpt_to: Vec::new(),
+            expanded_from: Vec::new(),
             authenticated_as: None,
             priority: 0,
             valid_until: Instant::now(),
             rcpt_errors: 0,
             rcpt_oks: 0,
+            lmtp_rcpts: Vec::new(),
             message: Vec::with_capacity(0),
+            bdat_spool: None,
+            message_headers: None,
+            declared_size: 0,
             auth_errors: 0,
+            client_cert: None,
+            session_lease: None,
             messages_sent: 0,
             bytes_left: 0,
             delivery_by: 0,
@@ -166,9 +202,19 @@ impl SessionData {
             iprev: None,
             spf_ehlo: None,
             spf_mail_from: None,
+            sender_verify: None,
+            sender_reputation: None,
+            delivery_callback: None,
+            partial_request: false,
+            queue_meta: Vec::new(),
             dnsbl_error: None,
         }
     }
+
+    // Size of the message received so far, including spooled BDAT chunks
+    pub fn message_len(&self) -> usize {
+        self.message.len() + self.bdat_spool.as_ref().map_or(0, |spool| spool.len())
+    }
 }
 
 impl Default for State {
@@ -223,6 +269,9 @@ impl Session<common::network::stream::NullIo> {
                 timeout: Default::default(),
                 ehlo_require: Default::default(),
                 ehlo_reject_non_fqdn: Default::default(),
+                ehlo_hidden: Default::default(),
+                ehlo_reject_hidden: Default::default(),
+                ehlo_callback: Default::default(),
                 auth_require: Default::default(),
                 auth_errors_max: Default::default(),
                 auth_errors_wait: Default::default(),
@@ -232,6 +281,7 @@ impl Session<common::network::stream::NullIo> {
                 rcpt_dsn: Default::default(),
                 max_message_size: Default::default(),
                 iprev: VerifyStrategy::Disable,
+                iprev_require: false,
                 spf_ehlo: VerifyStrategy::Disable,
                 spf_mail_from: VerifyStrategy::Disable,
                 can_expn: false,
@@ -273,13 +323,21 @@ impl SessionData {
             session_id,
             asn_geo_data: AsnGeoLookupResult::default(),
             helo_domain: "localhost".into(),
+            chunking: false,
             mail_from,
             rcpt_to,
+            expanded_from: Vec::new(),
             rcpt_errors: 0,
             rcpt_oks: 0,
+            lmtp_rcpts: Vec::new(),
             message,
+            bdat_spool: None,
+            message_headers: None,
+            declared_size: 0,
             authenticated_as: Some(authenticated_as),
             auth_errors: 0,
+            client_cert: None,
+            session_lease: None,
             priority: 0,
             delivery_by: 0,
             future_release: 0,
@@ -289,6 +347,11 @@ impl SessionData {
             iprev: None,
             spf_ehlo: None,
             spf_mail_from: None,
+            sender_verify: None,
+            sender_reputation: None,
+            delivery_callback: None,
+            partial_request: false,
+            queue_meta: Vec::new(),
             dnsbl_error: None,
         }
     }
@@ -54,6 +54,15 @@ impl<T: SessionStream> Session<T> {
             )
             .await
             .unwrap_or(VerifyStrategy::Relaxed);
+        self.params.iprev_require = self
+            .server
+            .eval_if(
+                &self.server.core.smtp.mail_auth.iprev.require,
+                self,
+                self.data.session_id,
+            )
+            .await
+            .unwrap_or(false);
 
         // Ehlo parameters
         let ec = &self.server.core.smtp.session.ehlo;
@@ -68,6 +77,24 @@ impl<T: SessionStream> Session<T> {
             .await
             .unwrap_or(true);
 
+        // Advertised extensions
+        let ec = &self.server.core.smtp.session.extensions;
+        self.params.ehlo_hidden = self
+            .server
+            .eval_if(&ec.hidden, self, self.data.session_id)
+            .await
+            .unwrap_or_default();
+        self.params.ehlo_reject_hidden = self
+            .server
+            .eval_if(&ec.reject_hidden, self, self.data.session_id)
+            .await
+            .unwrap_or(false);
+        self.params.ehlo_callback = self
+            .server
+            .eval_if(&ec.delivery_callback, self, self.data.session_id)
+            .await
+            .unwrap_or(false);
+
         // Auth parameters
         let ac = &self.server.core.smtp.session.auth;
         self.params.auth_require = self
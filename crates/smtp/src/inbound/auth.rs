@@ -5,12 +5,17 @@
  */
 
 use crate::core::Session;
-use common::{auth::AuthRequest, network::SessionStream};
+use common::{
+    auth::{AccessToken, AccountInfo, AuthRequest},
+    network::{SessionStream, TcpAcceptor, sessions::SessionLease, tls::ClientCertificate},
+};
 use directory::Credentials;
 use mail_parser::decoders::base64::base64_decode;
-use registry::schema::enums::Permission;
+use registry::schema::enums::{Permission, ServiceProtocol};
+use rustls::server::danger::ClientCertVerifier;
+use rustls_pki_types::UnixTime;
 use smtp_proto::{AUTH_LOGIN, AUTH_OAUTHBEARER, AUTH_PLAIN, AUTH_XOAUTH2, IntoString};
-use trc::AuthEvent;
+use trc::{AddContext, AuthEvent, SmtpEvent};
 
 pub struct SaslToken {
     mechanism: u64,
@@ -115,18 +120,37 @@ impl<T: SessionStream> Session<T> {
                 credentials,
                 self.data.session_id,
                 self.data.remote_ip,
+                ServiceProtocol::Smtp,
             ))
             .await
-            .and_then(|access_token| access_token.assert_has_permission(Permission::EmailSend));
+            .and_then(|access_token| access_token.assert_has_permission(Permission::EmailSend))
+            .and_then(|access_token| access_token.assert_protocol_enabled(ServiceProtocol::Smtp));
 
         let result = match result {
-            Ok(access_token) => self.server.account_info(access_token.account_id()).await,
+            Ok(access_token) => match self
+                .server
+                .acquire_session(
+                    &access_token,
+                    ServiceProtocol::Smtp,
+                    self.data.session_id,
+                    self.data.remote_ip,
+                )
+                .await
+            {
+                Ok(session_lease) => self
+                    .server
+                    .account_info(access_token.account_id())
+                    .await
+                    .map(|account_info| (account_info, session_lease)),
+                Err(err) => Err(err),
+            },
             Err(err) => Err(err),
         };
 
         match result {
-            Ok(account_info) => {
+            Ok((account_info, session_lease)) => {
                 self.data.authenticated_as = account_info.into();
+                self.data.session_lease = session_lease.into();
                 self.eval_post_auth_params().await;
                 self.write(b"235 2.7.0 Authentication succeeded.\r\n")
                     .await?;
@@ -146,6 +170,13 @@ impl<T: SessionStream> Session<T> {
                     trc::EventType::Auth(trc::AuthEvent::TokenExpired) => {
                         return self.auth_error(b"535 5.7.8 OAuth token expired.\r\n").await;
                     }
+                    trc::EventType::Auth(trc::AuthEvent::ProtocolDisabled) => {
+                        return self
+                            .auth_error(
+                                b"535 5.7.8 SMTP submission is disabled for this account.\r\n",
+                            )
+                            .await;
+                    }
                     trc::EventType::Auth(trc::AuthEvent::MfaRequired) => {
                         return self
                             .auth_error(
@@ -171,6 +202,12 @@ impl<T: SessionStream> Session<T> {
                     trc::EventType::Security(_) => {
                         return Err(());
                     }
+                    trc::EventType::Limit(trc::LimitEvent::ConcurrentConnection) => {
+                        let _ = self
+                            .write(b"421 4.7.0 Too many connections, try again later.\r\n")
+                            .await;
+                        return Err(());
+                    }
                     _ => (),
                 }
             }
@@ -182,6 +219,200 @@ impl<T: SessionStream> Session<T> {
         Ok(false)
     }
 
+    pub async fn authenticate_client_cert(&mut self) -> Result<(), ()> {
+        // Certificates are only mapped to accounts when the listener has a client CA
+        // and the mapping is enabled
+        let TcpAcceptor::Tls {
+            client_cert_verifier: Some(verifier),
+            ..
+        } = &self.instance.acceptor
+        else {
+            return Ok(());
+        };
+        if self.server.core.smtp.session.auth.client_cert.is_false() {
+            return Ok(());
+        }
+        let Some((end_entity, intermediates)) = self
+            .stream
+            .peer_certificates()
+            .and_then(|certs| certs.split_first())
+        else {
+            return Ok(());
+        };
+        let Some(cert) = ClientCertificate::parse(end_entity.as_ref()) else {
+            return Ok(());
+        };
+        let sans = cert
+            .emails
+            .iter()
+            .chain(cert.dns_names.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // The chain must lead to one of the configured authorities
+        if let Err(err) = verifier.verify_client_cert(end_entity, intermediates, UnixTime::now()) {
+            trc::event!(
+                Smtp(SmtpEvent::ClientCertRejected),
+                SpanId = self.data.session_id,
+                Id = cert.fingerprint,
+                Details = cert.subject,
+                Value = sans,
+                Reason = err.to_string(),
+            );
+
+            return Ok(());
+        }
+
+        // Revoked certificates are not allowed to connect
+        let revocation_list = self
+            .server
+            .core
+            .smtp
+            .session
+            .auth
+            .client_cert_revocation_list
+            .clone();
+        if let Some(list) = revocation_list {
+            match self.server.get_lookup_store(&list) {
+                Some(store) => match store.key_exists(cert.fingerprint.as_str()).await {
+                    Ok(true) => {
+                        trc::event!(
+                            Smtp(SmtpEvent::ClientCertRejected),
+                            SpanId = self.data.session_id,
+                            Id = cert.fingerprint,
+                            Details = cert.subject,
+                            Value = sans,
+                            Reason = "Certificate has been revoked",
+                        );
+
+                        let _ = self
+                            .write(b"421 4.7.1 Client certificate has been revoked.\r\n")
+                            .await;
+                        return Err(());
+                    }
+                    Ok(false) => (),
+                    Err(err) => {
+                        trc::error!(
+                            err.span_id(self.data.session_id)
+                                .caused_by(trc::location!())
+                        );
+                    }
+                },
+                None => {
+                    trc::error!(
+                        trc::StoreEvent::NotConfigured
+                            .into_err()
+                            .span_id(self.data.session_id)
+                            .id(list)
+                            .details("Client certificate revocation list not found")
+                    );
+                }
+            }
+        }
+
+        // Map the certificate to an account
+        let fingerprint = cert.fingerprint.clone();
+        let subject = cert.subject.clone();
+        self.data.client_cert = Some(cert);
+        let result = match self
+            .server
+            .eval_if::<String, _>(
+                &self.server.core.smtp.session.auth.client_cert,
+                self,
+                self.data.session_id,
+            )
+            .await
+        {
+            Some(account_name) => self.client_cert_account(&account_name).await,
+            None => Ok(None),
+        };
+
+        match result {
+            Ok(Some((account_info, session_lease))) => {
+                trc::event!(
+                    Smtp(SmtpEvent::ClientCertAuthenticated),
+                    SpanId = self.data.session_id,
+                    AccountName = account_info.name().to_string(),
+                    Id = fingerprint,
+                    Details = subject,
+                    Value = sans,
+                );
+
+                self.data.authenticated_as = account_info.into();
+                self.data.session_lease = session_lease.into();
+                self.eval_post_auth_params().await;
+            }
+            Ok(None) => {
+                trc::event!(
+                    Smtp(SmtpEvent::ClientCertRejected),
+                    SpanId = self.data.session_id,
+                    Id = fingerprint,
+                    Details = subject,
+                    Value = sans,
+                    Reason = "Certificate does not map to an account",
+                );
+            }
+            Err(err) => {
+                let reason = *err.as_ref();
+
+                trc::event!(
+                    Smtp(SmtpEvent::ClientCertRejected),
+                    SpanId = self.data.session_id,
+                    Id = fingerprint,
+                    Details = subject,
+                    Value = sans,
+                    CausedBy = err,
+                );
+
+                if reason == trc::EventType::Limit(trc::LimitEvent::ConcurrentConnection) {
+                    let _ = self
+                        .write(b"421 4.7.0 Too many connections, try again later.\r\n")
+                        .await;
+                    return Err(());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn client_cert_account(
+        &self,
+        account_name: &str,
+    ) -> trc::Result<Option<(AccountInfo, SessionLease)>> {
+        let Some(account_id) = self
+            .server
+            .account_id_from_email(account_name, false)
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(None);
+        };
+        let access_token = AccessToken::new(
+            self.server
+                .access_token(account_id)
+                .await
+                .caused_by(trc::location!())?,
+            self.data.remote_ip,
+        )?
+        .assert_has_permissions(&[Permission::Authenticate, Permission::EmailSend])?
+        .assert_protocol_enabled(ServiceProtocol::Smtp)?;
+        let session_lease = self
+            .server
+            .acquire_session(
+                &access_token,
+                ServiceProtocol::Smtp,
+                self.data.session_id,
+                self.data.remote_ip,
+            )
+            .await?;
+
+        self.server
+            .account_info(access_token.account_id())
+            .await
+            .map(|account_info| Some((account_info, session_lease)))
+    }
+
     pub async fn auth_error(&mut self, response: &[u8]) -> Result<bool, ()> {
         tokio::time::sleep(self.params.auth_errors_wait).await;
         self.data.auth_errors += 1;
@@ -0,0 +1,104 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::core::Session;
+use common::network::SessionStream;
+use email::message::urlauth::UrlAuthorization;
+use std::{borrow::Cow, time::Instant};
+use trc::{EventType, ResourceEvent, SecurityEvent, SmtpEvent};
+
+impl<T: SessionStream> Session<T> {
+    pub async fn handle_burl(&mut self, url: Cow<'_, str>, is_last: bool) -> Result<(), ()> {
+        let Some(account) = self.data.authenticated_as.as_ref() else {
+            trc::event!(
+                Smtp(SmtpEvent::BurlFailed),
+                SpanId = self.data.session_id,
+                Url = url.as_ref().to_string(),
+                Reason = "Not authenticated",
+            );
+
+            return self.write(b"530 5.7.0 Authentication required.\r\n").await;
+        };
+        let account_id = account.account_id();
+        let account_name = account.name().to_string();
+
+        if !self.can_send_data().await? {
+            self.data.message = Vec::with_capacity(0);
+            return Ok(());
+        }
+
+        // Resolve the URL in-process
+        let op_start = Instant::now();
+        let contents = match self
+            .server
+            .urlauth_fetch(account_id, &account_name, url.as_ref())
+            .await
+        {
+            Ok(contents) => contents,
+            Err(err) => {
+                let response: &[u8] = match err.event_type() {
+                    EventType::Resource(ResourceEvent::BadParameters) => {
+                        b"554 5.5.4 Invalid IMAP URL.\r\n"
+                    }
+                    EventType::Security(SecurityEvent::Unauthorized) => {
+                        b"554 5.7.0 IMAP URLAUTH validation failed.\r\n"
+                    }
+                    _ => b"554 5.6.6 IMAP URL resolution failed.\r\n",
+                };
+
+                trc::event!(
+                    Smtp(SmtpEvent::BurlFailed),
+                    SpanId = self.data.session_id,
+                    AccountId = account_id,
+                    Url = url.as_ref().to_string(),
+                    CausedBy = err,
+                );
+
+                // A failed URL fails the entire transaction
+                self.reset();
+                return self.write(response).await;
+            }
+        };
+
+        if self.data.message.len() + contents.len() >= self.params.max_message_size {
+            trc::event!(
+                Smtp(SmtpEvent::MessageTooLarge),
+                SpanId = self.data.session_id,
+                Size = self.data.message.len() + contents.len(),
+                Limit = self.params.max_message_size,
+            );
+
+            self.reset();
+            return self
+                .write(b"552 5.3.4 Message too big for system.\r\n")
+                .await;
+        }
+
+        trc::event!(
+            Smtp(SmtpEvent::Burl),
+            SpanId = self.data.session_id,
+            AccountId = account_id,
+            Url = url.as_ref().to_string(),
+            Size = contents.len(),
+            Elapsed = op_start.elapsed(),
+        );
+
+        self.data.message.extend_from_slice(&contents);
+        if is_last {
+            let message = self.queue_message().await;
+            if !message.is_empty() {
+                self.write(message.as_ref()).await?;
+                self.reset();
+                Ok(())
+            } else {
+                // Disconnect requested
+                Err(())
+            }
+        } else {
+            self.write(b"250 2.5.0 URL content appended.\r\n").await
+        }
+    }
+}
@@ -6,11 +6,11 @@
 
 use super::AuthResult;
 use crate::{
-    core::{Session, SessionAddress, State},
-    inbound::{dkim::DkimSign, milter::Modification},
+    core::{Session, SessionAddress, SessionData, State},
+    inbound::{dkim::DkimSign, from_alignment::FromAlignmentResult, milter::Modification},
     queue::{
-        self, Message, MessageSource, MessageWrapper, QueueEnvelope, RCPT_SPAM_PAYLOAD,
-        quota::HasQueueQuota, spool::QueueParams,
+        self, Error, ErrorDetails, Message, MessageSource, MessageWrapper, Metadata, QueueEnvelope,
+        RCPT_SPAM_PAYLOAD, Status, UnexpectedResponse, quota::HasQueueQuota, spool::QueueParams,
     },
     reporting::analysis::AnalyzeReport,
     scripts::ScriptResult,
@@ -18,15 +18,14 @@ use crate::{
 use common::{
     config::{
         mailstore::spamfilter::SpamFilterAction,
-        smtp::{
-            auth::VerifyStrategy,
-            queue::{QueueExpiry, QueueName},
-            session::Stage,
-        },
+        server::ServerProtocol,
+        smtp::{auth::VerifyStrategy, queue::QueueName, session::Stage},
     },
+    expr::functions::header::MessageHeaders,
     network::SessionStream,
     scripts::ScriptModification,
 };
+use hyper::body::Bytes;
 use mail_auth::{
     AuthenticatedMessage, AuthenticationResults, Dkim2Result, DkimResult, DmarcResult, ReceivedSpf,
     SpfOutput, SpfResult,
@@ -41,24 +40,27 @@ use mail_auth::{
 };
 use mail_builder::headers::{date::Date, message_id::generate_message_id_header};
 use mail_parser::{MessageParser, MimeHeaders, parsers::fields::thread::thread_name};
-use registry::schema::structs::Rate;
+use registry::schema::{enums::MtaResponseId, structs::Rate};
 use sieve::{SpamStatus, runtime::Variable};
 use smtp_proto::{
     MAIL_BY_RETURN, RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_NEVER, RCPT_NOTIFY_SUCCESS,
+    Response,
 };
+use spam_filter::{analysis::score::SpamFilterAnalyzeScore, modules::reputation::ReputationEvent};
 use std::{
     borrow::Cow,
+    sync::Arc,
     time::{Instant, SystemTime},
 };
-use trc::{SmtpEvent, SpamEvent};
+use trc::{MtaHookEvent, SmtpEvent};
 use utils::DomainPart;
 
 impl<T: SessionStream> Session<T> {
     pub async fn queue_message(&mut self) -> Cow<'static, [u8]> {
         // Parse message
-        let raw_message = std::mem::take(&mut self.data.message);
+        let raw_message = Bytes::from(std::mem::take(&mut self.data.message));
         let parsed_message = match MessageParser::new()
-            .parse(&raw_message)
+            .parse(&raw_message[..])
             .filter(|p| p.headers().iter().any(|h| !h.name.is_other()))
         {
             Some(parsed_message) => parsed_message,
@@ -72,10 +74,16 @@ impl<T: SessionStream> Session<T> {
             }
         };
 
+        // Make the message headers available to expressions
+        self.data.message_headers = Some(Arc::new(MessageHeaders::new(
+            parsed_message.headers(),
+            &raw_message[..],
+        )));
+
         // Authenticate message
         let mut auth_message = AuthenticatedMessage::from_parsed(
             &parsed_message,
-            &raw_message,
+            &raw_message[..],
             self.server.core.smtp.mail_auth.dkim.strict,
         );
         let has_date_header = auth_message.has_date_header();
@@ -98,8 +106,51 @@ impl<T: SessionStream> Session<T> {
                 Total = auth_message.received_headers_count(),
             );
 
-            return (&b"450 4.4.6 Too many Received headers. Possible loop detected.\r\n"[..])
-                .into();
+            return self
+                .build_response(
+                    MtaResponseId::LoopDetected,
+                    &b"450 4.4.6 Too many Received headers. Possible loop detected.\r\n"[..],
+                )
+                .await;
+        }
+
+        // Verify From header alignment
+        let from_rewrite = match self
+            .verify_from_alignment(&parsed_message, &auth_message)
+            .await
+        {
+            FromAlignmentResult::Aligned => None,
+            FromAlignmentResult::Rewritten(modifications) => Some(modifications),
+            FromAlignmentResult::Rejected => {
+                return self
+                    .build_response(
+                        MtaResponseId::FromNotAuthorized,
+                        &b"550 5.7.1 From address not authorized.\r\n"[..],
+                    )
+                    .await;
+            }
+        };
+
+        // Messages that would have been rejected by policies in dry-run mode are
+        // tagged, SPF failures are counted once per message
+        let mut dry_run_tags = Vec::new();
+        if let Some(spf_output) = [
+            (self.params.spf_ehlo, self.data.spf_ehlo.as_ref()),
+            (self.params.spf_mail_from, self.data.spf_mail_from.as_ref()),
+        ]
+        .into_iter()
+        .filter(|(strategy, _)| strategy.is_dry_run())
+        .filter_map(|(_, spf_output)| spf_output)
+        .find(|spf_output| !matches!(spf_output.result(), SpfResult::Pass))
+        {
+            trc::event!(
+                Smtp(SmtpEvent::SpfDryRunReject),
+                SpanId = self.data.session_id,
+                Domain = spf_output.domain().to_string(),
+                Result = trc::Error::from(spf_output),
+                Reason = format!("SPF validation failed, status: {}.", spf_output.result()),
+            );
+            dry_run_tags.push("DRYRUN_SPF_REJECT");
         }
 
         // Verify DKIM
@@ -146,6 +197,7 @@ impl<T: SessionStream> Session<T> {
                 .any(|d| matches!(d.result(), DkimResult::Pass));
             let strict = dkim.is_strict();
             let rejected = strict && !pass;
+            let dry_run_rejected = dkim.is_dry_run() && !pass;
 
             // Send reports for failed signatures
             if let Some(rate) = self
@@ -155,8 +207,14 @@ impl<T: SessionStream> Session<T> {
             {
                 for output in &dkim_output {
                     if let Some(rcpt) = output.failure_report_addr() {
-                        self.send_dkim_report(rcpt, &auth_message, &rate, rejected, output)
-                            .await;
+                        self.send_dkim_report(
+                            rcpt,
+                            &auth_message,
+                            &rate,
+                            rejected || dry_run_rejected,
+                            output,
+                        )
+                        .await;
                     }
                 }
             }
@@ -179,10 +237,27 @@ impl<T: SessionStream> Session<T> {
                     .iter()
                     .any(|d| matches!(d.result(), DkimResult::TempError(_)))
                 {
-                    (&b"451 4.7.20 No passing DKIM signatures found.\r\n"[..]).into()
+                    self.build_response(
+                        MtaResponseId::DkimTempFail,
+                        &b"451 4.7.20 No passing DKIM signatures found.\r\n"[..],
+                    )
+                    .await
                 } else {
-                    (&b"550 5.7.20 No passing DKIM signatures found.\r\n"[..]).into()
+                    self.build_response(
+                        MtaResponseId::DkimReject,
+                        &b"550 5.7.20 No passing DKIM signatures found.\r\n"[..],
+                    )
+                    .await
                 };
+            } else if dry_run_rejected {
+                trc::event!(
+                    Smtp(SmtpEvent::DkimDryRunReject),
+                    SpanId = self.data.session_id,
+                    From = auth_message.from().to_string(),
+                    Result = dkim_output.iter().map(trc::Error::from).collect::<Vec<_>>(),
+                    Reason = "No passing DKIM signatures found.",
+                );
+                dry_run_tags.push("DRYRUN_DKIM_REJECT");
             }
 
             dkim_output
@@ -224,10 +299,26 @@ impl<T: SessionStream> Session<T> {
 
             if strict && !pass {
                 return if matches!(arc_output.result(), DkimResult::TempError(_)) {
-                    (&b"451 4.7.29 ARC validation failed.\r\n"[..]).into()
+                    self.build_response(
+                        MtaResponseId::ArcTempFail,
+                        &b"451 4.7.29 ARC validation failed.\r\n"[..],
+                    )
+                    .await
                 } else {
-                    (&b"550 5.7.29 ARC validation failed.\r\n"[..]).into()
+                    self.build_response(
+                        MtaResponseId::ArcReject,
+                        &b"550 5.7.29 ARC validation failed.\r\n"[..],
+                    )
+                    .await
                 };
+            } else if arc.is_dry_run() && !pass {
+                trc::event!(
+                    Smtp(SmtpEvent::ArcDryRunReject),
+                    SpanId = self.data.session_id,
+                    Result = trc::Error::from(arc_output.result()),
+                    Reason = "ARC validation failed.",
+                );
+                dry_run_tags.push("DRYRUN_ARC_REJECT");
             }
 
             arc_output.into()
@@ -243,8 +334,7 @@ impl<T: SessionStream> Session<T> {
             // Discard forged DKIM2-signed delivery status notifications
             if dkim.verify()
                 && !auth_message.dkim2_signatures.is_empty()
-                && let Some(dsn) =
-                    parse_dkim2_dsn(&parsed_message, &auth_message, raw_message.as_slice())
+                && let Some(dsn) = parse_dkim2_dsn(&parsed_message, &auth_message, &raw_message[..])
                 && let Err(failure) = self
                     .server
                     .core
@@ -385,7 +475,9 @@ impl<T: SessionStream> Session<T> {
                 let pass = matches!(dmarc_output.spf_result(), DmarcResult::Pass)
                     || matches!(dmarc_output.dkim_result(), DmarcResult::Pass);
                 let strict = dmarc.is_strict();
-                let rejected = strict && dmarc_output.policy() == dmarc::Policy::Reject && !pass;
+                let would_reject = dmarc_output.policy() == dmarc::Policy::Reject && !pass;
+                let rejected = strict && would_reject;
+                let dry_run_rejected = dmarc.is_dry_run() && would_reject;
                 let is_temp_fail = rejected
                     && matches!(dmarc_output.spf_result(), DmarcResult::TempError(_))
                     || matches!(dmarc_output.dkim_result(), DmarcResult::TempError(_));
@@ -417,12 +509,24 @@ impl<T: SessionStream> Session<T> {
                     Elapsed = time.elapsed(),
                 );
 
+                if dry_run_rejected {
+                    trc::event!(
+                        Smtp(SmtpEvent::DmarcDryRunReject),
+                        SpanId = self.data.session_id,
+                        Domain = dmarc_output.domain().to_string(),
+                        Policy = dmarc_policy.to_string(),
+                        Result = trc::Error::from(&dmarc_result),
+                        Reason = "Email rejected per DMARC policy.",
+                    );
+                    dry_run_tags.push("DRYRUN_DMARC_REJECT");
+                }
+
                 // Send DMARC report
                 if dmarc_output.requested_reports() && !is_report {
                     self.send_dmarc_report(
                         &auth_message,
                         &auth_results,
-                        rejected,
+                        rejected || dry_run_rejected,
                         dmarc_output,
                         &dkim_output,
                         dkim2_output.as_ref(),
@@ -433,9 +537,17 @@ impl<T: SessionStream> Session<T> {
 
                 if rejected {
                     return if is_temp_fail {
-                        (&b"451 4.7.1 Email temporarily rejected per DMARC policy.\r\n"[..]).into()
+                        self.build_response(
+                            MtaResponseId::DmarcTempFail,
+                            &b"451 4.7.1 Email temporarily rejected per DMARC policy.\r\n"[..],
+                        )
+                        .await
                     } else {
-                        (&b"550 5.7.1 Email rejected per DMARC policy.\r\n"[..]).into()
+                        self.build_response(
+                            MtaResponseId::DmarcReject,
+                            &b"550 5.7.1 Email rejected per DMARC policy.\r\n"[..],
+                        )
+                        .await
                     };
                 }
 
@@ -504,6 +616,13 @@ impl<T: SessionStream> Session<T> {
             auth_results.write_header(&mut headers);
         }
 
+        // Add dry-run results header, also when the spam filter is disabled
+        if !dry_run_tags.is_empty() {
+            headers.extend_from_slice(b"X-Dry-Run-Result: ");
+            headers.extend_from_slice(dry_run_tags.join(", ").as_bytes());
+            headers.extend_from_slice(b"\r\n");
+        }
+
         // Add Received-SPF header
         if let Some(spf_output) = &self.data.spf_mail_from
             && self
@@ -522,9 +641,10 @@ impl<T: SessionStream> Session<T> {
             .write_header(&mut headers);
         }
 
-        // Run SPAM filter
-        let mut train_spam = None;
-        let mut spam_status = None;
+        // Run SPAM filter analysis, the score is recalculated after the
+        // DATA stage Sieve script if it adds its own tags
+        let mut spam_result = None;
+        let mut spam_thread_name = None;
         if self.server.core.spam.enabled
             && self
                 .server
@@ -532,8 +652,8 @@ impl<T: SessionStream> Session<T> {
                 .await
                 .unwrap_or(true)
         {
-            match self
-                .spam_classify(
+            spam_result = self
+                .spam_analyze(
                     &parsed_message,
                     &dkim_output,
                     dkim2_output.as_ref(),
@@ -541,56 +661,35 @@ impl<T: SessionStream> Session<T> {
                     dmarc_result.as_ref(),
                     dmarc_policy.as_ref(),
                 )
+                .await;
+            if let Some(spam_result) = &mut spam_result {
+                spam_result
+                    .tags
+                    .extend(dry_run_tags.into_iter().map(String::from));
+                spam_thread_name =
+                    Some(thread_name(parsed_message.subject().unwrap_or_default()).to_string());
+            }
+        }
+
+        // Calculate the SPAM score before running any filters, so that
+        // rejected messages never reach the DATA stage script
+        let spam_headers_start = headers.len();
+        let mut spam_score = None;
+        if let Some(spam_result) = &spam_result {
+            match self
+                .server
+                .spam_filter_finalize_result(&mut spam_result.clone(), self.data.rcpt_to.len())
                 .await
             {
                 SpamFilterAction::Allow(score) => {
-                    // Add headers
                     headers.extend_from_slice(score.headers.as_bytes());
-                    train_spam = score.train_spam.map(|is_spam| {
-                        (
-                            is_spam,
-                            thread_name(parsed_message.subject().unwrap_or_default()).to_string(),
-                        )
-                    });
-                    spam_status = Some(if score.is_spam {
-                        SpamStatus::Spam
-                    } else {
-                        SpamStatus::Ham
-                    });
-
-                    // Add scores for local recipients
-                    for (is_spam, recipient) in
-                        score.results.into_iter().zip(self.data.rcpt_to.iter_mut())
-                    {
-                        if is_spam {
-                            recipient.flags |= RCPT_SPAM_PAYLOAD;
-                        }
-                    }
+                    spam_score = Some(score);
                 }
                 SpamFilterAction::Discard => {
-                    trc::event!(
-                        Spam(SpamEvent::Classify),
-                        SpanId = self.data.session_id,
-                        QueueId = message_id,
-                        Result = "discard",
-                        Reason = "Message discarded due to excessive spam score.",
-                    );
-
-                    self.data.messages_sent += 1;
-                    return (b"250 2.0.0 Message queued for delivery.\r\n"[..]).into();
+                    return self.spam_filter_reject(true, message_id).await;
                 }
                 SpamFilterAction::Reject => {
-                    trc::event!(
-                        Spam(SpamEvent::Classify),
-                        SpanId = self.data.session_id,
-                        QueueId = message_id,
-                        Result = "reject",
-                        Reason = "Message rejected due to excessive spam score.",
-                    );
-
-                    self.data.messages_sent += 1;
-                    return (b"550 5.7.1 Message rejected due to excessive spam score.\r\n"[..])
-                        .into();
+                    return self.spam_filter_reject(false, message_id).await;
                 }
                 SpamFilterAction::Disabled => {}
             }
@@ -614,13 +713,26 @@ impl<T: SessionStream> Session<T> {
 
         // Run MTA Hooks
         match self
-            .run_mta_hooks(Stage::Data, (&auth_message).into(), message_id.into())
+            .run_mta_hooks(
+                Stage::Data,
+                Some((&auth_message, &raw_message)),
+                message_id.into(),
+            )
             .await
         {
             Ok(modifications_) => {
-                if !modifications_.is_empty() {
+                if !modifications_.message.is_empty() {
                     modifications.retain(|m| !matches!(m, Modification::ReplaceBody { .. }));
-                    modifications.extend(modifications_);
+                    modifications.extend(modifications_.message);
+                }
+                for (key, value) in modifications_.queue_meta {
+                    if !self.data.set_queue_meta(key.to_ascii_lowercase(), value) {
+                        trc::event!(
+                            MtaHook(MtaHookEvent::Error),
+                            SpanId = self.data.session_id,
+                            Details = "Invalid or excess queue metadata",
+                        );
+                    }
                 }
             }
             Err(response) => {
@@ -628,6 +740,28 @@ impl<T: SessionStream> Session<T> {
             }
         };
 
+        // Rewrite From header
+        if let Some(from_rewrite) = from_rewrite {
+            modifications.extend(from_rewrite);
+        }
+
+        // Remove dry-run results added by other hosts, only ours are trusted
+        let spoofed_dry_run = auth_message
+            .raw_parsed_headers()
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(b"X-Dry-Run-Result"))
+            .count();
+        if spoofed_dry_run > 0 {
+            modifications.splice(
+                0..0,
+                (0..spoofed_dry_run).map(|_| Modification::ChangeHeader {
+                    index: 1,
+                    name: "X-Dry-Run-Result".into(),
+                    value: String::new(),
+                }),
+            );
+        }
+
         // Apply modifications
         let mut edited_message = if !modifications.is_empty() {
             self.data
@@ -635,6 +769,9 @@ impl<T: SessionStream> Session<T> {
         } else {
             None
         };
+        if let Some(edited_message) = &edited_message {
+            self.data.capture_message_headers(edited_message);
+        }
 
         // Sieve filtering
         if let Some((script, script_id)) = self
@@ -650,8 +787,14 @@ impl<T: SessionStream> Session<T> {
             let mut params = self
                 .build_script_parameters("data")
                 .with_auth_headers(&headers);
-            if let Some(spam_status) = spam_status {
-                params = params.with_spam_status(spam_status);
+            if let Some(score) = &spam_score {
+                params = params
+                    .with_spam_status(if score.is_spam {
+                        SpamStatus::Spam
+                    } else {
+                        SpamStatus::Ham
+                    })
+                    .with_spam_score(score.score);
             }
             let params = params
                 .set_variable(
@@ -706,6 +849,7 @@ impl<T: SessionStream> Session<T> {
                     message,
                     modifications,
                 } => {
+                    self.data.capture_message_headers(&message);
                     edited_message = message.into();
                     modifications
                 }
@@ -717,6 +861,40 @@ impl<T: SessionStream> Session<T> {
                 }
             };
 
+            // Rescore the message with the script tags before applying
+            // any of the script modifications
+            if let Some(spam_result) = &mut spam_result
+                && modifications
+                    .iter()
+                    .any(|m| matches!(m, ScriptModification::AddSpamTag { .. }))
+            {
+                for modification in &modifications {
+                    if let ScriptModification::AddSpamTag { name, weight } = modification {
+                        spam_result.add_custom_tag(name.clone(), *weight);
+                    }
+                }
+
+                match self
+                    .server
+                    .spam_filter_finalize_result(&mut spam_result.clone(), self.data.rcpt_to.len())
+                    .await
+                {
+                    SpamFilterAction::Allow(score) => {
+                        let spam_headers_end = spam_headers_start
+                            + spam_score.as_ref().map_or(0, |score| score.headers.len());
+                        headers.splice(spam_headers_start..spam_headers_end, score.headers.bytes());
+                        spam_score = Some(score);
+                    }
+                    SpamFilterAction::Discard => {
+                        return self.spam_filter_reject(true, message_id).await;
+                    }
+                    SpamFilterAction::Reject => {
+                        return self.spam_filter_reject(false, message_id).await;
+                    }
+                    SpamFilterAction::Disabled => {}
+                }
+            }
+
             // Apply modifications
             for modification in modifications {
                 match modification {
@@ -731,16 +909,75 @@ impl<T: SessionStream> Session<T> {
                     ScriptModification::SetEnvelope { name, value } => {
                         self.data.apply_envelope_modification(name, value);
                     }
+                    ScriptModification::AddSpamTag { .. } => {}
+                    ScriptModification::SetQueueMeta { key, value } => {
+                        self.data.set_queue_meta(key, value);
+                    }
                 }
             }
         }
 
+        // Keep the spam filter tags referenced by mail categories, tags found
+        // in the headers of the message are never trusted
+        let spam_tags = spam_result
+            .as_ref()
+            .filter(|_| spam_score.is_some())
+            .map(|spam_result| {
+                spam_result
+                    .tags
+                    .iter()
+                    .filter(|tag| {
+                        self.server
+                            .core
+                            .email
+                            .categories
+                            .iter()
+                            .any(|rule| rule.tags.contains(*tag))
+                    })
+                    .map(|tag| tag.as_str().into())
+                    .collect::<Box<[Box<str>]>>()
+            })
+            .unwrap_or_default();
+
+        // Apply the SPAM filter verdict
+        let mut train_spam = None;
+        let spam_score = if let Some(score) = spam_score {
+            // Update sender reputation
+            self.record_sender_reputation(if score.is_spam {
+                ReputationEvent::Spam
+            } else {
+                ReputationEvent::Ham
+            })
+            .await;
+
+            train_spam = score
+                .train_spam
+                .map(|is_spam| (is_spam, spam_thread_name.unwrap_or_default()));
+
+            // Add scores for local recipients
+            for (is_spam, recipient) in score.results.into_iter().zip(self.data.rcpt_to.iter_mut())
+            {
+                if is_spam {
+                    recipient.flags |= RCPT_SPAM_PAYLOAD;
+                }
+            }
+
+            Some(score.score)
+        } else {
+            None
+        };
+
         // Build message
         let mail_from = self.data.mail_from.clone().unwrap();
         let rcpt_to = std::mem::take(&mut self.data.rcpt_to);
         let mut message = self
             .build_message(mail_from, rcpt_to, message_id, self.data.session_id)
             .await;
+        if !spam_tags.is_empty() {
+            let mut metadata = std::mem::take(&mut message.message.metadata).into_vec();
+            metadata.push(Metadata::SpamTags { tags: spam_tags });
+            message.message.metadata = metadata.into_boxed_slice();
+        }
 
         // Add Return-Path
         if self
@@ -754,6 +991,13 @@ impl<T: SessionStream> Session<T> {
             headers.extend_from_slice(b">\r\n");
         }
 
+        // Add expansion trace
+        for expanded_from in std::mem::take(&mut self.data.expanded_from) {
+            headers.extend_from_slice(b"X-Expanded-From: ");
+            headers.extend_from_slice(expanded_from.as_bytes());
+            headers.extend_from_slice(b"\r\n");
+        }
+
         // Add any missing headers
         if !has_date_header
             && self
@@ -779,35 +1023,100 @@ impl<T: SessionStream> Session<T> {
         }
 
         // Update size
-        let original_message = raw_message.as_slice();
-        let raw_message = edited_message.as_deref().unwrap_or(raw_message.as_slice());
+        let original_message = &raw_message[..];
+        let raw_message = edited_message.as_deref().unwrap_or(&raw_message[..]);
         message.message.size = (raw_message.len() + headers.len()) as u64;
 
-        // Verify queue quota
-        if let Some(metadata) = self.server.has_quota(&mut message).await {
-            // Queue message
-            let queue_id = message.queue_id;
-            let source = if !self.is_authenticated() {
-                let dmarc_pass = dmarc_result.is_some_and(|result| result == DmarcResult::Pass);
+        // Recipients with a smaller size limit than the final message are bounced,
+        // the message is rejected if none of them can accept it
+        let mut num_oversized = 0;
+        for rcpt in &mut message.message.recipients {
+            if let Some(max_message_size) = self
+                .rcpt_max_message_size(&rcpt.address.to_lowercase())
+                .await
+                && message.message.size > max_message_size
+            {
+                trc::event!(
+                    Smtp(SmtpEvent::MessageTooLarge),
+                    SpanId = self.data.session_id,
+                    To = rcpt.address.to_string(),
+                    Size = message.message.size,
+                    Limit = max_message_size,
+                );
 
-                #[cfg(feature = "test_mode")]
-                {
-                    MessageSource::Unauthenticated {
-                        dmarc_pass: dmarc_pass || message.message.return_path.starts_with("dmarc-"),
-                        train_spam,
-                    }
+                rcpt.status = Status::PermanentFailure(ErrorDetails {
+                    entity: self.hostname.as_str().into(),
+                    details: Error::UnexpectedResponse(UnexpectedResponse {
+                        command: format!("RCPT TO:<{}>", rcpt.address).into_boxed_str(),
+                        response: Response {
+                            code: 552,
+                            esc: [5, 2, 3],
+                            message: "Message exceeds the maximum size for this recipient".into(),
+                        },
+                    }),
+                });
+                num_oversized += 1;
+            }
+        }
+        if num_oversized > 0 && num_oversized == message.message.recipients.len() {
+            return (&b"552 5.2.3 Message exceeds the maximum size for its recipients.\r\n"[..])
+                .into();
+        }
+
+        let source = if !self.is_authenticated() {
+            let dmarc_pass = dmarc_result.is_some_and(|result| result == DmarcResult::Pass);
+
+            #[cfg(feature = "test_mode")]
+            {
+                MessageSource::Unauthenticated {
+                    dmarc_pass: dmarc_pass || message.message.return_path.starts_with("dmarc-"),
+                    train_spam,
                 }
+            }
 
-                #[cfg(not(feature = "test_mode"))]
-                {
-                    MessageSource::Unauthenticated {
-                        dmarc_pass,
-                        train_spam,
-                    }
+            #[cfg(not(feature = "test_mode"))]
+            {
+                MessageSource::Unauthenticated {
+                    dmarc_pass,
+                    train_spam,
                 }
-            } else {
-                MessageSource::Authenticated
-            };
+            }
+        } else {
+            MessageSource::Authenticated
+        };
+
+        // Deliver LMTP messages to local mailboxes instead of queueing them
+        if self.instance.protocol == ServerProtocol::Lmtp
+            && self
+                .server
+                .eval_if(&dc.lmtp_local_delivery, self, self.data.session_id)
+                .await
+                .unwrap_or(false)
+        {
+            return self
+                .deliver_lmtp(message, raw_message, &headers, source)
+                .await;
+        }
+
+        // Verify queue quota
+        if let Some(mut metadata) = self.server.has_quota(&mut message).await {
+            // Attach delivery status callback
+            if let Some(id) = self.data.delivery_callback {
+                metadata.push(Metadata::DeliveryCallback { id });
+            }
+
+            // Keep the spam score for the DSN suppression checks
+            if let Some(score) = spam_score {
+                metadata.push(Metadata::SpamScore {
+                    score: (score * 100.0).round() as i64,
+                });
+            }
+
+            // Keep any metadata set by scripts and MTA hooks
+            metadata.extend(message.message.metadata.iter().cloned());
+
+            // Queue message
+            let queue_id = message.queue_id;
             let dkim_signers = self
                 .server
                 .eval_signers(&ac.dkim.sign, self, self.data.session_id)
@@ -829,10 +1138,18 @@ impl<T: SessionStream> Session<T> {
                     .into_bytes()
                     .into()
             } else {
-                (b"451 4.3.5 Unable to accept message at this time.\r\n"[..]).into()
+                self.build_response(
+                    MtaResponseId::QueueTempFail,
+                    &b"451 4.3.5 Unable to accept message at this time.\r\n"[..],
+                )
+                .await
             }
         } else {
-            (b"452 4.3.1 Mail system full, try again later.\r\n"[..]).into()
+            self.build_response(
+                MtaResponseId::QuotaFull,
+                &b"452 4.3.1 Mail system full, try again later.\r\n"[..],
+            )
+            .await
         }
     }
 
@@ -859,7 +1176,15 @@ impl<T: SessionStream> Session<T> {
             size: 0,
             env_id: mail_from.dsn_info.map(|i| i.into_boxed_str()),
             blob_hash: Default::default(),
-            metadata: Default::default(),
+            metadata: self
+                .data
+                .queue_meta
+                .iter()
+                .map(|(key, value)| Metadata::Custom {
+                    key: key.as_str().into(),
+                    value: value.as_str().into(),
+                })
+                .collect(),
             received_from_ip: self.data.remote_ip,
             received_via_port: self.data.local_port,
         };
@@ -886,7 +1211,8 @@ impl<T: SessionStream> Session<T> {
                     .with_orcpt(rcpt.dsn_info.map(|v| v.into_boxed_str())),
             );
 
-            let envelope = QueueEnvelope::new(&message, message.recipients.last().unwrap());
+            let envelope = QueueEnvelope::new(&message, message.recipients.last().unwrap())
+                .with_headers(self.data.message_headers.clone());
 
             // Set next retry time
             let retry = if self.data.future_release == 0 {
@@ -915,19 +1241,19 @@ impl<T: SessionStream> Session<T> {
             let (notify, expires) = if self.data.delivery_by == 0 {
                 (
                     queue::Schedule::later(future_release + next_notify),
-                    match queue.expiry {
-                        QueueExpiry::Ttl(time) => QueueExpiry::Ttl(future_release + time),
-                        QueueExpiry::Attempts(count) => QueueExpiry::Attempts(count),
+                    match queue.expiry.ttl() {
+                        Some(time) => queue.expiry.with_ttl(future_release + time),
+                        None => queue.expiry,
                     },
                 )
             } else if (message.flags & MAIL_BY_RETURN) != 0 {
                 (
                     queue::Schedule::later(future_release + next_notify),
-                    QueueExpiry::Ttl(self.data.delivery_by as u64),
+                    queue.expiry.with_ttl(self.data.delivery_by as u64),
                 )
             } else {
-                let (notify, expires) = match queue.expiry {
-                    QueueExpiry::Ttl(expire_secs) => (
+                let (notify, expires) = match queue.expiry.ttl() {
+                    Some(expire_secs) => (
                         (if self.data.delivery_by.is_positive() {
                             let notify_at = self.data.delivery_by as u64;
                             if expire_secs > notify_at {
@@ -943,11 +1269,11 @@ impl<T: SessionStream> Session<T> {
                                 next_notify
                             }
                         }),
-                        QueueExpiry::Ttl(expire_secs),
+                        queue.expiry,
                     ),
-                    QueueExpiry::Attempts(_) => (
+                    None => (
                         next_notify,
-                        QueueExpiry::Ttl(self.data.delivery_by.unsigned_abs()),
+                        queue.expiry.with_ttl(self.data.delivery_by.unsigned_abs()),
                     ),
                 };
 
@@ -1070,6 +1396,18 @@ impl<T: SessionStream> Session<T> {
     }
 }
 
+impl SessionData {
+    // Expressions evaluated after a milter, hook or script changed the message see its new headers
+    pub fn capture_message_headers(&mut self, raw_message: &[u8]) {
+        if let Some(message) = MessageParser::new().parse_headers(raw_message) {
+            self.message_headers = Some(Arc::new(MessageHeaders::new(
+                message.headers(),
+                raw_message,
+            )));
+        }
+    }
+}
+
 fn parse_dkim2_dsn<'x, 'r>(
     parsed_message: &mail_parser::Message<'x>,
     raw: &'r AuthenticatedMessage<'x>,
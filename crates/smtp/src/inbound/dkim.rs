@@ -19,12 +19,21 @@ use mail_parser::{Address, parsers::MessageStream};
 use std::{collections::HashSet, sync::Arc};
 use utils::sanitize_email;
 
+// Signature headers added to the stored message. DKIM1 signatures are kept
+// apart as recipients receiving headers of their own get their own DKIM1
+// signatures instead.
+#[derive(Default)]
+pub(crate) struct DkimHeaders {
+    pub dkim1: Vec<u8>,
+    pub dkim2: Vec<u8>,
+}
+
 pub(crate) trait DkimSign: Sync + Send {
     fn sign_message(
         &self,
         message: &mut MessageWrapper,
         params: &mut QueueParams<'_, '_>,
-    ) -> impl Future<Output = Option<Vec<u8>>> + Send;
+    ) -> impl Future<Output = DkimHeaders> + Send;
 
     fn eval_signers(
         &self,
@@ -39,12 +48,12 @@ impl DkimSign for Server {
         &self,
         message: &mut MessageWrapper,
         params: &mut QueueParams<'_, '_>,
-    ) -> Option<Vec<u8>> {
+    ) -> DkimHeaders {
         let signers = params.dkim_signers.as_ref().unwrap();
         let raw_message = params.raw_message;
 
         // DKIM1 signing
-        let mut headers = Vec::with_capacity(64);
+        let mut dkim1_headers = Vec::with_capacity(64);
         for signer in &signers.dkim1 {
             let result = match (signer, params.raw_headers) {
                 (Dkim1Signer::RsaSha256(signer), None) => signer.sign(raw_message),
@@ -59,7 +68,7 @@ impl DkimSign for Server {
 
             match result {
                 Ok(signature) => {
-                    signature.write_header(&mut headers);
+                    signature.write_header(&mut dkim1_headers);
                 }
                 Err(err) => {
                     trc::error!(
@@ -73,6 +82,7 @@ impl DkimSign for Server {
         }
 
         // DKIM2 signing
+        let mut headers = Vec::new();
         if let Some(signer) = &signers.dkim2
             && let Some(modified) =
                 AuthenticatedMessage::parse_with_opts(raw_message, params.raw_headers, true)
@@ -152,7 +162,10 @@ impl DkimSign for Server {
             }
         }
 
-        (!headers.is_empty()).then_some(headers)
+        DkimHeaders {
+            dkim1: dkim1_headers,
+            dkim2: headers,
+        }
     }
 
     async fn eval_signers(
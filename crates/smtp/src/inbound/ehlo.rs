@@ -6,7 +6,10 @@
 
 use crate::{core::Session, scripts::ScriptResult};
 use common::{
-    config::smtp::session::{Mechanism, Stage},
+    config::{
+        server::ServerProtocol,
+        smtp::session::{Mechanism, Stage},
+    },
     network::SessionStream,
 };
 use mail_auth::{
@@ -74,10 +77,7 @@ impl<T: SessionStream> Session<T> {
                     Elapsed = time.elapsed(),
                 );
 
-                if self
-                    .handle_spf(&spf_output, self.params.spf_ehlo.is_strict())
-                    .await?
-                {
+                if self.handle_spf(&spf_output, self.params.spf_ehlo).await? {
                     self.data.spf_ehlo = spf_output.into();
                 } else {
                     self.data.mail_from = None;
@@ -129,6 +129,17 @@ impl<T: SessionStream> Session<T> {
                 self.data.spf_ehlo = None;
                 return self.write(message.message.as_bytes()).await;
             }
+
+            // Exemptions from FCrDNS enforcement may depend on the EHLO domain
+            self.params.iprev_require = self
+                .server
+                .eval_if(
+                    &self.server.core.smtp.mail_auth.iprev.require,
+                    self,
+                    self.data.session_id,
+                )
+                .await
+                .unwrap_or(false);
         }
 
         // Reset
@@ -137,6 +148,7 @@ impl<T: SessionStream> Session<T> {
         }
 
         if !is_extended {
+            self.data.chunking = false;
             return self
                 .write(format!("250 {} you had me at HELO\r\n", self.hostname).as_bytes())
                 .await;
@@ -225,6 +237,13 @@ impl<T: SessionStream> Session<T> {
             }
         }
 
+        // Message composition from IMAP URLs, resolved for authenticated users only
+        if self.instance.protocol == ServerProtocol::Smtp
+            && (self.is_authenticated() || response.auth_mechanisms != 0)
+        {
+            response.capabilities |= EXT_BURL;
+        }
+
         // Future release
         if let Some(value) = self
             .server
@@ -284,9 +303,24 @@ impl<T: SessionStream> Session<T> {
             };
         }
 
+        // Hidden extensions
+        response.capabilities &= !u32::from(self.params.ehlo_hidden);
+        self.data.chunking = (response.capabilities & EXT_CHUNKING) != 0;
+
         // Generate response
         let mut buf = Vec::with_capacity(64);
         response.write(&mut buf).ok();
+
+        // Delivery status callbacks are not known to the EHLO encoder, so the
+        // keyword is appended as the last capability
+        if self.params.ehlo_callback && self.instance.protocol == ServerProtocol::Smtp {
+            let last_line = buf[..buf.len() - 2]
+                .iter()
+                .rposition(|&ch| ch == b'\n')
+                .map_or(0, |pos| pos + 1);
+            buf[last_line + 3] = b'-';
+            buf.extend_from_slice(b"250 X-CALLBACK\r\n");
+        }
         self.write(&buf).await
     }
 }
@@ -0,0 +1,120 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{core::Session, inbound::milter::Modification};
+use common::{
+    config::{server::ServerProtocol, smtp::session::FromAlignment},
+    network::SessionStream,
+};
+use mail_auth::AuthenticatedMessage;
+use mail_builder::headers::{Header, address::Address};
+use mail_parser::Message;
+use trc::SmtpEvent;
+
+pub enum FromAlignmentResult {
+    Aligned,
+    Rejected,
+    Rewritten(Vec<Modification>),
+}
+
+impl<T: SessionStream> Session<T> {
+    /// Verifies that the From header addresses belong to the authenticated
+    /// sender, returning the header changes to apply in rewrite mode.
+    pub async fn verify_from_alignment(
+        &self,
+        message: &Message<'_>,
+        auth_message: &AuthenticatedMessage<'_>,
+    ) -> FromAlignmentResult {
+        // Only submissions are checked, port 25 is reserved for relaying
+        if self.instance.protocol != ServerProtocol::Smtp || self.data.local_port == 25 {
+            return FromAlignmentResult::Aligned;
+        }
+        let Some(authenticated_as) = self.authenticated_as() else {
+            return FromAlignmentResult::Aligned;
+        };
+        let policy = self
+            .server
+            .eval_if(
+                &self.server.core.smtp.session.data.from_alignment,
+                self,
+                self.data.session_id,
+            )
+            .await
+            .unwrap_or(FromAlignment::Disable);
+        if policy == FromAlignment::Disable {
+            return FromAlignmentResult::Aligned;
+        }
+        let Some(from) = message.from() else {
+            return FromAlignmentResult::Aligned;
+        };
+
+        // Addresses include those of any groups the sender is a member of
+        let authenticated_emails = self.authenticated_emails();
+        let Some(address) = from
+            .iter()
+            .filter_map(|addr| addr.address())
+            .map(|address| address.trim().to_lowercase())
+            .find(|address| {
+                address != authenticated_as && !authenticated_emails.iter().any(|e| e == address)
+            })
+        else {
+            return FromAlignmentResult::Aligned;
+        };
+
+        match (policy, authenticated_emails.first()) {
+            (FromAlignment::Rewrite, Some(primary_address)) => {
+                let mut new_from = Vec::with_capacity(primary_address.len() + 32);
+                let _ = Address::new_address(
+                    from.first().and_then(|addr| addr.name()),
+                    primary_address.as_str(),
+                )
+                .write_header(&mut new_from, "From: ".len());
+                let original_from = auth_message
+                    .raw_parsed_headers()
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(b"From"))
+                    .map(|(_, value)| String::from_utf8_lossy(value).into_owned())
+                    .unwrap_or_else(|| format!(" <{address}>\r\n"));
+
+                trc::event!(
+                    Smtp(SmtpEvent::FromHeaderRewritten),
+                    SpanId = self.data.session_id,
+                    From = address,
+                    To = primary_address.to_string(),
+                );
+
+                FromAlignmentResult::Rewritten(vec![
+                    Modification::ChangeHeader {
+                        index: 1,
+                        name: "From".into(),
+                        value: String::from_utf8(new_from).unwrap_or_default(),
+                    },
+                    Modification::AddHeader {
+                        name: "X-Original-From".into(),
+                        value: original_from,
+                    },
+                ])
+            }
+            _ => {
+                trc::event!(
+                    Smtp(SmtpEvent::FromHeaderUnauthorized),
+                    SpanId = self.data.session_id,
+                    From = address,
+                    Details = [trc::Value::String(authenticated_as.into())]
+                        .into_iter()
+                        .chain(
+                            authenticated_emails
+                                .iter()
+                                .map(|e| trc::Value::String(e.into()))
+                        )
+                        .collect::<Vec<_>>()
+                );
+
+                FromAlignmentResult::Rejected
+            }
+        }
+    }
+}
@@ -4,15 +4,145 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use ahash::AHashMap;
 use common::config::smtp::session::MTAHook;
+use hyper::body::Bytes;
+use parking_lot::Mutex;
+use std::{sync::LazyLock, time::Duration};
+use tonic::{
+    codegen::http::uri::PathAndQuery,
+    metadata::MetadataMap,
+    transport::{Channel, ClientTlsConfig, Endpoint},
+};
+use tonic_prost::ProstCodec;
 use utils::HttpLimitResponse;
 
-use super::{Request, Response};
+use super::{
+    Request, Response, StreamRequest,
+    grpc::{FILTER_PATH, FilterChunk, FilterVerdict},
+};
+
+// Lets the hook server know how long it has to reply before the request is
+// abandoned and the failure policy applies
+pub const HEADER_FILTER_TIMEOUT: &str = "X-Filter-Timeout";
+
+// Channels multiplex requests over a single HTTP/2 connection and reconnect
+// on their own, so one is kept per hook instead of connecting on every message
+static GRPC_CHANNELS: LazyLock<Mutex<AHashMap<(String, Duration), Channel>>> =
+    LazyLock::new(|| Mutex::new(AHashMap::new()));
 
 pub(super) async fn send_mta_hook_request(
     mta_hook: &MTAHook,
     request: Request,
 ) -> Result<Response, String> {
+    send_request(
+        mta_hook,
+        reqwest::Body::from(
+            serde_json::to_string(&request)
+                .map_err(|err| format!("Failed to serialize Hook request: {}", err))?,
+        ),
+    )
+    .await
+}
+
+pub(super) async fn send_mta_hook_stream_request(
+    mta_hook: &MTAHook,
+    request: StreamRequest,
+    body: Option<Bytes>,
+) -> Result<Response, String> {
+    let mut header = serde_json::to_vec(&request)
+        .map_err(|err| format!("Failed to serialize Hook request: {}", err))?;
+    header.push(b'\n');
+
+    // The body is sent in chunks as it is consumed by the HTTP client
+    let chunks = std::iter::once(Bytes::from(header)).chain(body_chunks(mta_hook, body));
+
+    send_request(
+        mta_hook,
+        reqwest::Body::wrap_stream(futures::stream::iter(chunks.map(Ok::<_, std::io::Error>))),
+    )
+    .await
+}
+
+pub(super) async fn send_mta_hook_grpc_request(
+    mta_hook: &MTAHook,
+    request: StreamRequest,
+    body: Option<Bytes>,
+) -> Result<Response, String> {
+    let request = serde_json::to_vec(&request)
+        .map_err(|err| format!("Failed to serialize Hook request: {}", err))?;
+
+    let mut client = tonic::client::Grpc::new(grpc_channel(mta_hook)?)
+        .max_decoding_message_size(mta_hook.max_response_size);
+    client
+        .ready()
+        .await
+        .map_err(|err| format!("gRPC hook not ready: {err}"))?;
+
+    // The request is followed by the body chunks, the deadline is propagated
+    // in the grpc-timeout header
+    let chunks = std::iter::once(FilterChunk {
+        request: Bytes::from(request),
+        body: Bytes::new(),
+    })
+    .chain(body_chunks(mta_hook, body).map(|body| FilterChunk {
+        request: Bytes::new(),
+        body,
+    }));
+    let mut request = tonic::Request::new(futures::stream::iter(chunks));
+    *request.metadata_mut() = MetadataMap::from_headers(mta_hook.headers.clone());
+    request.set_timeout(mta_hook.timeout);
+
+    let verdict: FilterVerdict = client
+        .client_streaming(
+            request,
+            PathAndQuery::from_static(FILTER_PATH),
+            ProstCodec::default(),
+        )
+        .await
+        .map_err(|status| {
+            format!(
+                "Hook request failed with status {:?}: {}",
+                status.code(),
+                status.message()
+            )
+        })?
+        .into_inner();
+
+    serde_json::from_slice(&verdict.response)
+        .map_err(|err| format!("Failed to parse Hook response: {}", err))
+}
+
+fn grpc_channel(mta_hook: &MTAHook) -> Result<Channel, String> {
+    let key = (mta_hook.url.clone(), mta_hook.timeout);
+    if let Some(channel) = GRPC_CHANNELS.lock().get(&key) {
+        return Ok(channel.clone());
+    }
+
+    let mut endpoint = Endpoint::from_shared(mta_hook.url.clone())
+        .map_err(|err| format!("Invalid gRPC hook URL: {err}"))?
+        .connect_timeout(mta_hook.timeout)
+        .timeout(mta_hook.timeout);
+    if mta_hook.url.starts_with("https://") {
+        endpoint = endpoint
+            .tls_config(ClientTlsConfig::new().with_enabled_roots())
+            .map_err(|err| format!("Failed to configure gRPC TLS: {err}"))?;
+    }
+    let channel = endpoint.connect_lazy();
+    GRPC_CHANNELS.lock().insert(key, channel.clone());
+
+    Ok(channel)
+}
+
+fn body_chunks(mta_hook: &MTAHook, body: Option<Bytes>) -> impl Iterator<Item = Bytes> + use<> {
+    let body = body.unwrap_or_default();
+    let chunk_size = mta_hook.stream_chunk_size.max(1);
+    (0..body.len())
+        .step_by(chunk_size)
+        .map(move |offset| body.slice(offset..(offset + chunk_size).min(body.len())))
+}
+
+async fn send_request(mta_hook: &MTAHook, body: reqwest::Body) -> Result<Response, String> {
     let response = reqwest::Client::builder()
         .timeout(mta_hook.timeout)
         .danger_accept_invalid_certs(mta_hook.tls_allow_invalid_certs)
@@ -20,10 +150,11 @@ pub(super) async fn send_mta_hook_request(
         .map_err(|err| format!("Failed to create HTTP client: {}", err))?
         .post(&mta_hook.url)
         .headers(mta_hook.headers.clone())
-        .body(
-            serde_json::to_string(&request)
-                .map_err(|err| format!("Failed to serialize Hook request: {}", err))?,
+        .header(
+            HEADER_FILTER_TIMEOUT,
+            mta_hook.timeout.as_millis().to_string(),
         )
+        .body(body)
         .send()
         .await
         .map_err(|err| format!("Hook request failed: {err}"))?;
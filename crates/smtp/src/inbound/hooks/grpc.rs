@@ -0,0 +1,31 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use hyper::body::Bytes;
+
+// Client streaming method implemented by gRPC hook servers:
+//
+// service Filter {
+//   rpc Filter(stream FilterChunk) returns (FilterVerdict);
+// }
+pub const FILTER_PATH: &str = "/stalwart.hook.v1.Filter/Filter";
+
+// The first message holds the JSON request of the streaming protocol, the
+// following ones the message body
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FilterChunk {
+    #[prost(bytes = "bytes", tag = "1")]
+    pub request: Bytes,
+    #[prost(bytes = "bytes", tag = "2")]
+    pub body: Bytes,
+}
+
+// JSON response, as returned by the other protocols
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FilterVerdict {
+    #[prost(bytes = "bytes", tag = "1")]
+    pub response: Bytes,
+}
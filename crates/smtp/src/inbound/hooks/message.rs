@@ -4,13 +4,17 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{Action, Queue, Response, client::send_mta_hook_request};
+use super::{
+    Action, Queue, Response, StreamMessage, StreamRequest,
+    client::{send_mta_hook_grpc_request, send_mta_hook_request, send_mta_hook_stream_request},
+};
 use crate::{
     core::Session,
     inbound::{
         FilterResponse,
         hooks::{
-            Address, Client, Context, Envelope, Message, Protocol, Request, Sasl, Server, Tls,
+            Address, Client, Context, Envelope, HookModifications, Message, Protocol, Request,
+            Sasl, Server, Tls,
         },
         milter::Modification,
     },
@@ -22,7 +26,9 @@ use common::{
     config::smtp::session::{MTAHook, Stage},
     network::SessionStream,
 };
+use hyper::body::Bytes;
 use mail_auth::AuthenticatedMessage;
+use registry::schema::enums::MtaHookProtocol;
 use std::time::Instant;
 use trc::MtaHookEvent;
 
@@ -30,15 +36,16 @@ impl<T: SessionStream> Session<T> {
     pub async fn run_mta_hooks(
         &self,
         stage: Stage,
-        message: Option<&AuthenticatedMessage<'_>>,
+        message: Option<(&AuthenticatedMessage<'_>, &Bytes)>,
         queue_id: Option<QueueId>,
-    ) -> Result<Vec<Modification>, FilterResponse> {
+    ) -> Result<HookModifications, FilterResponse> {
         let mta_hooks = &self.server.core.smtp.session.hooks;
         if mta_hooks.is_empty() {
-            return Ok(Vec::new());
+            return Ok(HookModifications::default());
         }
 
         let mut modifications = Vec::new();
+        let mut queue_meta = Vec::new();
         for mta_hook in mta_hooks {
             if !mta_hook.run_on_stage.contains(&stage)
                 || !self
@@ -50,8 +57,16 @@ impl<T: SessionStream> Session<T> {
                 continue;
             }
 
+            // The deadline is enforced here as well, hook servers are not
+            // trusted to honour the timeout they are sent
             let time = Instant::now();
-            match self.run_mta_hook(stage, mta_hook, message, queue_id).await {
+            match tokio::time::timeout(
+                mta_hook.timeout,
+                self.run_mta_hook(stage, mta_hook, message, queue_id),
+            )
+            .await
+            .unwrap_or_else(|_| Err("Hook request timed out".to_string()))
+            {
                 Ok(response) => {
                     trc::event!(
                         MtaHook(match response.action {
@@ -105,6 +120,10 @@ impl<T: SessionStream> Session<T> {
                                     value: String::new(),
                                 }
                             }
+                            super::Modification::SetQueueMeta { key, value } => {
+                                queue_meta.push((key, value));
+                                continue;
+                            }
                         });
                     }
 
@@ -165,89 +184,133 @@ impl<T: SessionStream> Session<T> {
             }
         }
 
-        Ok(modifications)
+        Ok(HookModifications {
+            message: modifications,
+            queue_meta,
+        })
     }
 
     pub async fn run_mta_hook(
         &self,
         stage: Stage,
         mta_hook: &MTAHook,
-        message: Option<&AuthenticatedMessage<'_>>,
+        message: Option<(&AuthenticatedMessage<'_>, &Bytes)>,
         queue_id: Option<QueueId>,
     ) -> Result<Response, String> {
-        // Build request
+        let context = self.mta_hook_context(stage, queue_id);
+        let envelope = self.mta_hook_envelope();
+        let (message, raw_message) = message.unzip();
+        let headers = message.map(|message| {
+            message
+                .raw_parsed_headers()
+                .iter()
+                .map(|(k, v)| {
+                    (
+                        String::from_utf8_lossy(k).into_owned(),
+                        String::from_utf8_lossy(v).into_owned(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+
+        match mta_hook.protocol {
+            MtaHookProtocol::Json => {
+                let request = Request {
+                    context,
+                    envelope,
+                    message: message.zip(headers).map(|(message, headers)| Message {
+                        headers,
+                        server_headers: vec![],
+                        contents: String::from_utf8_lossy(message.raw_body()).into_owned(),
+                        size: message.raw_message().len(),
+                    }),
+                };
+
+                send_mta_hook_request(mta_hook, request).await
+            }
+            MtaHookProtocol::Stream | MtaHookProtocol::Grpc => {
+                let request = StreamRequest {
+                    context,
+                    envelope,
+                    message: message
+                        .zip(headers)
+                        .map(|(message, headers)| StreamMessage {
+                            headers,
+                            size: message.raw_message().len(),
+                            body_size: message.raw_body().len(),
+                        }),
+                };
+
+                // The body is sent from the buffer holding the message
+                let body = message
+                    .zip(raw_message)
+                    .map(|(message, raw_message)| raw_message.slice_ref(message.raw_body()));
+
+                if mta_hook.protocol == MtaHookProtocol::Stream {
+                    send_mta_hook_stream_request(mta_hook, request, body).await
+                } else {
+                    send_mta_hook_grpc_request(mta_hook, request, body).await
+                }
+            }
+        }
+    }
+
+    fn mta_hook_context(&self, stage: Stage, queue_id: Option<QueueId>) -> Context {
         let (tls_version, tls_cipher) = self.stream.tls_version_and_cipher();
-        let request = Request {
-            context: Context {
-                stage: stage.into(),
-                client: Client {
-                    ip: self.data.remote_ip.to_string(),
-                    port: self.data.remote_port,
-                    ptr: self
-                        .data
-                        .iprev
-                        .as_ref()
-                        .and_then(|ip_rev| ip_rev.ptr.as_ref())
-                        .and_then(|ptrs| ptrs.first())
-                        .map(|ip| ip.to_string()),
-                    helo: (!self.data.helo_domain.is_empty())
-                        .then(|| self.data.helo_domain.clone()),
-                    active_connections: 1,
-                },
-                sasl: self.authenticated_as().map(|name| Sasl {
-                    login: name.into(),
-                    method: None,
-                }),
-                tls: (!tls_version.is_empty()).then(|| Tls {
-                    version: tls_version.as_ref().into(),
-                    cipher: tls_cipher.as_ref().into(),
-                    bits: None,
-                    issuer: None,
-                    subject: None,
-                }),
-                server: Server {
-                    name: Some(DAEMON_NAME.into()),
-                    port: self.data.local_port,
-                    ip: self.data.local_ip.to_string().into(),
-                },
-                queue: queue_id.map(|id| Queue {
-                    id: format!("{:x}", id),
-                }),
-                protocol: Protocol { version: 1 },
-            },
-            envelope: self.data.mail_from.as_ref().map(|from| Envelope {
-                from: Address {
-                    address: from.address_lcase.clone(),
-                    parameters: None,
-                },
-                to: self
+        Context {
+            stage: stage.into(),
+            client: Client {
+                ip: self.data.remote_ip.to_string(),
+                port: self.data.remote_port,
+                ptr: self
                     .data
-                    .rcpt_to
-                    .iter()
-                    .map(|to| Address {
-                        address: to.address_lcase.clone(),
-                        parameters: None,
-                    })
-                    .collect(),
+                    .iprev
+                    .as_ref()
+                    .and_then(|ip_rev| ip_rev.ptr.as_ref())
+                    .and_then(|ptrs| ptrs.first())
+                    .map(|ip| ip.to_string()),
+                helo: (!self.data.helo_domain.is_empty()).then(|| self.data.helo_domain.clone()),
+                active_connections: 1,
+            },
+            sasl: self.authenticated_as().map(|name| Sasl {
+                login: name.into(),
+                method: None,
             }),
-            message: message.map(|message| Message {
-                headers: message
-                    .raw_parsed_headers()
-                    .iter()
-                    .map(|(k, v)| {
-                        (
-                            String::from_utf8_lossy(k).into_owned(),
-                            String::from_utf8_lossy(v).into_owned(),
-                        )
-                    })
-                    .collect(),
-                server_headers: vec![],
-                contents: String::from_utf8_lossy(message.raw_body()).into_owned(),
-                size: message.raw_message().len(),
+            tls: (!tls_version.is_empty()).then(|| Tls {
+                version: tls_version.as_ref().into(),
+                cipher: tls_cipher.as_ref().into(),
+                bits: None,
+                issuer: None,
+                subject: None,
             }),
-        };
+            server: Server {
+                name: Some(DAEMON_NAME.into()),
+                port: self.data.local_port,
+                ip: self.data.local_ip.to_string().into(),
+            },
+            queue: queue_id.map(|id| Queue {
+                id: format!("{:x}", id),
+            }),
+            protocol: Protocol { version: 1 },
+        }
+    }
 
-        send_mta_hook_request(mta_hook, request).await
+    fn mta_hook_envelope(&self) -> Option<Envelope> {
+        self.data.mail_from.as_ref().map(|from| Envelope {
+            from: Address {
+                address: from.address_lcase.clone(),
+                parameters: None,
+            },
+            to: self
+                .data
+                .rcpt_to
+                .iter()
+                .map(|to| Address {
+                    address: to.address_lcase.clone(),
+                    parameters: None,
+                })
+                .collect(),
+        })
     }
 }
 
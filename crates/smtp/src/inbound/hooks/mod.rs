@@ -5,6 +5,7 @@
  */
 
 pub mod client;
+pub mod grpc;
 pub mod message;
 
 use ahash::AHashMap;
@@ -20,6 +21,17 @@ pub struct Request {
     pub message: Option<Message>,
 }
 
+// Sent as the first line of a streamed request, followed by the raw
+// message body
+#[derive(Serialize, Deserialize)]
+pub struct StreamRequest {
+    pub context: Context,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<Envelope>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<StreamMessage>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Context {
     pub stage: Stage,
@@ -123,6 +135,14 @@ pub struct Message {
     pub size: usize,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct StreamMessage {
+    pub headers: Vec<(String, String)>,
+    pub size: usize,
+    #[serde(rename = "bodySize")]
+    pub body_size: usize,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Response {
     pub action: Action,
@@ -191,6 +211,16 @@ pub enum Modification {
     },
     #[serde(rename = "deleteHeader")]
     DeleteHeader { index: u32, name: String },
+    #[serde(rename = "setQueueMeta")]
+    SetQueueMeta { key: String, value: String },
+}
+
+// Changes requested by the MTA hooks of a stage. Queue metadata has no
+// milter equivalent and is kept apart from the message modifications.
+#[derive(Debug, Default)]
+pub struct HookModifications {
+    pub message: Vec<crate::inbound::milter::Modification>,
+    pub queue_meta: Vec<(String, String)>,
 }
 
 impl From<common::config::smtp::session::Stage> for Stage {
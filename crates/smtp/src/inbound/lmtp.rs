@@ -0,0 +1,120 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    core::Session,
+    outbound::DeliveryResult,
+    queue::{
+        Error, FROM_AUTHENTICATED, FROM_UNAUTHENTICATED, FROM_UNAUTHENTICATED_DMARC, MessageSource,
+        MessageWrapper, Status, UnexpectedResponse,
+    },
+};
+use common::network::SessionStream;
+use registry::schema::enums::MtaResponseId;
+use std::borrow::Cow;
+use store::write::now;
+use types::blob_hash::BlobHash;
+use utils::DomainPart;
+
+impl<T: SessionStream> Session<T> {
+    pub(super) async fn deliver_lmtp(
+        &mut self,
+        mut message: MessageWrapper,
+        raw_message: &[u8],
+        raw_headers: &[u8],
+        source: MessageSource,
+    ) -> Cow<'static, [u8]> {
+        message.message.flags |= match source {
+            MessageSource::Authenticated => FROM_AUTHENTICATED,
+            MessageSource::Unauthenticated {
+                dmarc_pass: true, ..
+            } => FROM_UNAUTHENTICATED_DMARC,
+            _ => FROM_UNAUTHENTICATED,
+        };
+
+        // Write blob
+        let mut contents = Vec::with_capacity(raw_headers.len() + raw_message.len());
+        contents.extend_from_slice(raw_headers);
+        contents.extend_from_slice(raw_message);
+        message.message.blob_hash = BlobHash::generate(&contents);
+        if !message
+            .write_blob(&contents, now() + 120, self.data.session_id, &self.server)
+            .await
+        {
+            return self
+                .build_response(
+                    MtaResponseId::QueueTempFail,
+                    &b"451 4.3.5 Unable to accept message at this time.\r\n"[..],
+                )
+                .await;
+        }
+
+        // Deliver to all recipients that were not rejected after DATA
+        let rcpt_idxs = message
+            .message
+            .recipients
+            .iter()
+            .enumerate()
+            .filter(|(_, rcpt)| matches!(rcpt.status, Status::Scheduled))
+            .map(|(rcpt_idx, _)| rcpt_idx)
+            .collect::<Vec<_>>();
+        let mut results = Vec::with_capacity(rcpt_idxs.len());
+        message
+            .deliver_local(&rcpt_idxs, &mut results, &self.server)
+            .await;
+        for result in results {
+            if let DeliveryResult::Account { status, rcpt_idx } = result {
+                message.message.recipients[rcpt_idx].status = status;
+            }
+        }
+        self.data.messages_sent += 1;
+
+        // Reply once per accepted recipient in RCPT order, recipients expanded
+        // from a list share a reply with the first failure among its members
+        let mut replies = Vec::new();
+        for addresses in std::mem::take(&mut self.data.lmtp_rcpts) {
+            let failure = addresses.iter().find_map(|address| {
+                message
+                    .message
+                    .recipients
+                    .iter()
+                    .find(|rcpt| rcpt.address().to_lowercase_address(true) == *address)
+                    .and_then(|rcpt| match &rcpt.status {
+                        Status::TemporaryFailure(err) | Status::PermanentFailure(err) => {
+                            Some(&err.details)
+                        }
+                        Status::Scheduled | Status::Completed(_) => None,
+                    })
+            });
+
+            match failure {
+                None => {
+                    replies.extend_from_slice(b"250 2.1.5 OK\r\n");
+                }
+                Some(Error::UnexpectedResponse(UnexpectedResponse { response, .. })) => {
+                    replies.extend_from_slice(
+                        format!(
+                            "{} {}.{}.{} {}\r\n",
+                            response.code,
+                            response.esc[0],
+                            response.esc[1],
+                            response.esc[2],
+                            response.message
+                        )
+                        .as_bytes(),
+                    );
+                }
+                Some(_) => {
+                    replies.extend_from_slice(
+                        b"451 4.3.0 Unable to deliver message at this time.\r\n",
+                    );
+                }
+            }
+        }
+
+        replies.into()
+    }
+}
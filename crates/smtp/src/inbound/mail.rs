@@ -8,20 +8,51 @@ use crate::{
     core::{Session, SessionAddress},
     scripts::ScriptResult,
 };
-use common::{config::smtp::session::Stage, network::SessionStream, scripts::ScriptModification};
+use common::{
+    config::smtp::{
+        auth::{SenderVerifyResult, VerifyStrategy},
+        session::{EhloExtensions, Stage},
+    },
+    network::SessionStream,
+    scripts::ScriptModification,
+};
 use mail_auth::{IprevOutput, IprevResult, SpfOutput, SpfResult, spf::verify::SpfParameters};
-use registry::schema::structs::Rate;
-use smtp_proto::{MAIL_BY_NOTIFY, MAIL_BY_RETURN, MAIL_REQUIRETLS, MailFrom, MtPriority};
+use registry::schema::{
+    enums::MtaResponseId,
+    structs::{DeliveryCallback, Rate},
+};
+use smtp_proto::{
+    EXT_8BIT_MIME, EXT_BINARY_MIME, EXT_DELIVER_BY, EXT_DSN, EXT_FUTURE_RELEASE, EXT_MT_PRIORITY,
+    EXT_REQUIRE_TLS, EXT_SIZE, EXT_SMTP_UTF8, MAIL_BODY_8BITMIME, MAIL_BODY_BINARYMIME,
+    MAIL_BY_NOTIFY, MAIL_BY_RETURN, MAIL_REQUIRETLS, MAIL_RET_FULL, MAIL_RET_HDRS, MAIL_SMTPUTF8,
+    MailFrom, MtPriority,
+};
 use std::{
     borrow::Cow,
+    net::IpAddr,
+    str::FromStr,
     time::{Duration, Instant, SystemTime},
 };
 use trc::SmtpEvent;
+use types::id::Id;
 use utils::DomainPart;
 
+const X_CALLBACK: &[u8] = b"X-CALLBACK";
+
 impl<T: SessionStream> Session<T> {
     pub async fn handle_mail_from(&mut self, from: MailFrom<Cow<'_, str>>) -> Result<(), ()> {
-        if self.data.helo_domain.is_empty()
+        if self.server.is_draining() {
+            trc::event!(
+                Network(trc::NetworkEvent::Closed),
+                SpanId = self.data.session_id,
+                Reason = "Server draining",
+                CausedBy = trc::location!()
+            );
+
+            self.write(format!("421 4.3.0 {} Server shutting down.\r\n", self.hostname).as_bytes())
+                .await?;
+            return Err(());
+        } else if self.data.helo_domain.is_empty()
             && (self.params.ehlo_require
                 || self.params.spf_ehlo.verify()
                 || self.params.spf_mail_from.verify())
@@ -52,21 +83,28 @@ impl<T: SessionStream> Session<T> {
             return self
                 .write(b"503 5.5.1 You must authenticate first.\r\n")
                 .await;
-        } else if self.data.iprev.is_none() && self.params.iprev.verify() {
+        }
+
+        // Forward-confirmed reverse DNS is only enforced on unauthenticated sessions
+        let iprev_require = self.params.iprev_require && !self.is_authenticated();
+        if self.data.iprev.is_none() && (self.params.iprev.verify() || iprev_require) {
             let time = Instant::now();
-            let iprev = self
-                .server
-                .core
-                .smtp
-                .resolvers
-                .dns
-                .verify_iprev(
+            let iprev = tokio::time::timeout(
+                self.server.core.smtp.mail_auth.iprev.timeout,
+                self.server.core.smtp.resolvers.dns.verify_iprev(
                     self.server
                         .inner
                         .cache
                         .build_auth_parameters(self.data.remote_ip),
-                )
-                .await;
+                ),
+            )
+            .await
+            .unwrap_or_else(|_| IprevOutput {
+                result: IprevResult::TempError(mail_auth::Error::Dns(
+                    mail_auth::DnsError::Resolver("Reverse DNS lookup timed out".into()),
+                )),
+                ptr: None,
+            });
 
             trc::event!(
                 Smtp(if matches!(iprev.result(), IprevResult::Pass) {
@@ -83,6 +121,56 @@ impl<T: SessionStream> Session<T> {
             self.data.iprev = iprev.into();
         }
 
+        // Reject unauthenticated sessions from hosts that fail FCrDNS
+        if iprev_require
+            && let Some(iprev) = &self.data.iprev
+            && !matches!(iprev.result(), IprevResult::Pass)
+        {
+            let forward_addresses = self.iprev_forward_addresses(iprev).await;
+            trc::event!(
+                Smtp(SmtpEvent::IprevRequired),
+                SpanId = self.data.session_id,
+                RemoteIp = self.data.remote_ip,
+                Result = trc::Error::from(iprev),
+                Hostname = iprev
+                    .ptr
+                    .as_ref()
+                    .map(|ptr| {
+                        ptr.iter()
+                            .map(|host| trc::Value::String(host.as_ref().into()))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default(),
+                Details = forward_addresses,
+            );
+
+            let message = match iprev.result() {
+                IprevResult::TempError(_) => {
+                    self.build_response(
+                        MtaResponseId::IprevTempFail,
+                        &b"451 4.7.25 Temporary error validating reverse DNS.\r\n"[..],
+                    )
+                    .await
+                }
+                _ if self.server.core.smtp.mail_auth.iprev.require_temp_fail => {
+                    self.build_response(
+                        MtaResponseId::ReverseIpRequired,
+                        &b"450 4.7.25 Forward-confirmed reverse DNS is required.\r\n"[..],
+                    )
+                    .await
+                }
+                _ => {
+                    self.build_response(
+                        MtaResponseId::ReverseIpRequired,
+                        &b"550 5.7.25 Forward-confirmed reverse DNS is required.\r\n"[..],
+                    )
+                    .await
+                }
+            };
+
+            return self.write(&message).await;
+        }
+
         // In strict mode reject messages from hosts that fail the reverse DNS lookup check
         if self.params.iprev.is_strict()
             && !matches!(
@@ -100,12 +188,62 @@ impl<T: SessionStream> Session<T> {
                     ..
                 })
             ) {
-                &b"451 4.7.25 Temporary error validating reverse DNS.\r\n"[..]
+                self.build_response(
+                    MtaResponseId::IprevTempFail,
+                    &b"451 4.7.25 Temporary error validating reverse DNS.\r\n"[..],
+                )
+                .await
             } else {
-                &b"550 5.7.25 Reverse DNS validation failed.\r\n"[..]
+                self.build_response(
+                    MtaResponseId::IprevReject,
+                    &b"550 5.7.25 Reverse DNS validation failed.\r\n"[..],
+                )
+                .await
             };
 
-            return self.write(message).await;
+            return self.write(&message).await;
+        }
+
+        // Reject parameters of extensions that were not advertised
+        if self.params.ehlo_reject_hidden {
+            let hidden = self.params.ehlo_hidden;
+            if let Some(extension) = [
+                (EXT_SIZE, from.size > 0),
+                (
+                    EXT_DSN,
+                    (from.flags & (MAIL_RET_FULL | MAIL_RET_HDRS)) != 0 || from.env_id.is_some(),
+                ),
+                (EXT_REQUIRE_TLS, (from.flags & MAIL_REQUIRETLS) != 0),
+                (
+                    EXT_DELIVER_BY,
+                    (from.flags & (MAIL_BY_NOTIFY | MAIL_BY_RETURN)) != 0,
+                ),
+                (EXT_MT_PRIORITY, from.mt_priority != 0),
+                (
+                    EXT_FUTURE_RELEASE,
+                    from.hold_for != 0 || from.hold_until != 0,
+                ),
+                (EXT_SMTP_UTF8, (from.flags & MAIL_SMTPUTF8) != 0),
+                (EXT_8BIT_MIME, (from.flags & MAIL_BODY_8BITMIME) != 0),
+                (EXT_BINARY_MIME, (from.flags & MAIL_BODY_BINARYMIME) != 0),
+            ]
+            .into_iter()
+            .find_map(|(extension, is_used)| {
+                (is_used && hidden.contains(extension)).then_some(extension)
+            }) {
+                let keyword = EhloExtensions::keyword(extension);
+                trc::event!(
+                    Smtp(SmtpEvent::ExtensionNotOffered),
+                    SpanId = self.data.session_id,
+                    Details = keyword,
+                );
+                return self
+                    .write(
+                        format!("501 5.5.4 {keyword} extension has not been offered.\r\n")
+                            .as_bytes(),
+                    )
+                    .await;
+            }
         }
 
         let (address, address_lcase, domain) = if !from.address.is_empty() {
@@ -125,6 +263,7 @@ impl<T: SessionStream> Session<T> {
             dsn_info: from.env_id.map(|e| e.into_owned()),
         }
         .into();
+        self.data.declared_size = from.size;
 
         // Check whether the address is allowed
         if !self
@@ -137,15 +276,19 @@ impl<T: SessionStream> Session<T> {
             .await
             .unwrap_or(true)
         {
+            let message = self
+                .build_response(
+                    MtaResponseId::SenderNotAllowed,
+                    &b"550 5.7.1 Sender address not allowed.\r\n"[..],
+                )
+                .await;
             let mail_from = self.data.mail_from.take().unwrap();
             trc::event!(
                 Smtp(SmtpEvent::MailFromNotAllowed),
                 From = mail_from.address_lcase,
                 SpanId = self.data.session_id,
             );
-            return self
-                .write(b"550 5.7.1 Sender address not allowed.\r\n")
-                .await;
+            return self.write(&message).await;
         }
 
         // Sieve filtering
@@ -493,7 +636,7 @@ impl<T: SessionStream> Session<T> {
                 );
 
                 if self
-                    .handle_spf(&spf_output, self.params.spf_mail_from.is_strict())
+                    .handle_spf(&spf_output, self.params.spf_mail_from)
                     .await?
                 {
                     self.data.spf_mail_from = spf_output.into();
@@ -503,6 +646,43 @@ impl<T: SessionStream> Session<T> {
                 }
             }
 
+            // Verify sender address
+            let sender_verify = self
+                .server
+                .eval_if::<VerifyStrategy, _>(
+                    &self.server.core.smtp.mail_auth.sender_verify.verify,
+                    self,
+                    self.data.session_id,
+                )
+                .await
+                .unwrap_or(VerifyStrategy::Disable);
+            if sender_verify.verify()
+                && let Some(result) = self.verify_sender().await
+            {
+                self.data.sender_verify = result.into();
+
+                if sender_verify.is_strict() {
+                    match result {
+                        SenderVerifyResult::Pass => (),
+                        SenderVerifyResult::Fail => {
+                            self.data.mail_from = None;
+                            return self
+                                .write(b"550 5.1.7 Sender address could not be verified.\r\n")
+                                .await;
+                        }
+                        SenderVerifyResult::TempError => {
+                            self.data.mail_from = None;
+                            return self
+                                .write(b"451 4.1.7 Unable to verify sender address.\r\n")
+                                .await;
+                        }
+                    }
+                }
+            }
+
+            // Obtain sender reputation
+            self.lookup_sender_reputation().await;
+
             trc::event!(
                 Smtp(SmtpEvent::MailFrom),
                 SpanId = self.data.session_id,
@@ -518,27 +698,116 @@ impl<T: SessionStream> Session<T> {
                 From = self.data.mail_from.as_ref().unwrap().address_lcase.clone(),
             );
 
+            let message = self
+                .build_response(
+                    MtaResponseId::RateLimited,
+                    &b"452 4.4.5 Rate limit exceeded, try again later.\r\n"[..],
+                )
+                .await;
             self.data.mail_from = None;
-            self.write(b"452 4.4.5 Rate limit exceeded, try again later.\r\n")
+            self.write(&message).await
+        }
+    }
+
+    pub async fn handle_delivery_callback(&mut self, value: &[u8]) -> Result<Option<u64>, ()> {
+        let Some(account_id) = self
+            .data
+            .authenticated_as
+            .as_ref()
+            .map(|account| account.account_id())
+        else {
+            trc::event!(
+                Smtp(SmtpEvent::InvalidParameter),
+                SpanId = self.data.session_id,
+                Details = "X-CALLBACK",
+                Reason = "Session is not authenticated",
+            );
+
+            self.write(b"503 5.5.1 You must authenticate to use X-CALLBACK.\r\n")
+                .await?;
+            return Ok(None);
+        };
+
+        let result = match std::str::from_utf8(value)
+            .ok()
+            .and_then(|value| Id::from_str(value).ok())
+        {
+            Some(id) => self
+                .server
+                .registry()
+                .object::<DeliveryCallback>(id)
                 .await
+                .map(|callback| {
+                    callback
+                        .filter(|callback| callback.account_id.document_id() == account_id)
+                        .map(|_| id.id())
+                }),
+            None => Ok(None),
+        };
+
+        match result {
+            Ok(Some(callback_id)) => Ok(Some(callback_id)),
+            Ok(None) => {
+                trc::event!(
+                    Smtp(SmtpEvent::InvalidParameter),
+                    SpanId = self.data.session_id,
+                    Details = "X-CALLBACK",
+                    Reason = "Unknown delivery callback",
+                );
+
+                self.write(b"501 5.5.4 Invalid parameter \"X-CALLBACK\".\r\n")
+                    .await?;
+                Ok(None)
+            }
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .details("Failed to obtain delivery callback")
+                        .caused_by(trc::location!())
+                );
+
+                self.write(b"451 4.3.5 Unable to verify delivery callback.\r\n")
+                    .await?;
+                Ok(None)
+            }
         }
     }
 
-    pub async fn handle_spf(&mut self, spf_output: &SpfOutput, strict: bool) -> Result<bool, ()> {
+    pub async fn handle_spf(
+        &mut self,
+        spf_output: &SpfOutput,
+        strategy: VerifyStrategy,
+    ) -> Result<bool, ()> {
+        let strict = strategy.is_strict();
+        let mut rejected = false;
         let result = match spf_output.result() {
             SpfResult::Pass => true,
+            _ if strategy.is_dry_run() => {
+                // Evaluate as strict would but let the message through, the
+                // failure is logged once per message at the DATA stage
+                rejected = true;
+                true
+            }
             SpfResult::TempError if strict => {
-                self.write(b"451 4.7.24 Temporary SPF validation error.\r\n")
-                    .await?;
+                let message = self
+                    .build_response(
+                        MtaResponseId::SpfTempFail,
+                        &b"451 4.7.24 Temporary SPF validation error.\r\n"[..],
+                    )
+                    .await;
+                self.write(&message).await?;
                 false
             }
             result => {
                 if strict {
-                    self.write(
-                        format!("550 5.7.23 SPF validation failed, status: {result}.\r\n")
-                            .as_bytes(),
-                    )
-                    .await?;
+                    let message = self
+                        .build_response(
+                            MtaResponseId::SpfReject,
+                            format!("550 5.7.23 SPF validation failed, status: {result}.\r\n")
+                                .into_bytes(),
+                        )
+                        .await;
+                    self.write(&message).await?;
                     false
                 } else {
                     true
@@ -570,10 +839,134 @@ impl<T: SessionStream> Session<T> {
                 }
             }
 
-            self.send_spf_report(recipient, &rate, !result, spf_output)
+            self.send_spf_report(recipient, &rate, rejected || !result, spf_output)
                 .await;
         }
 
         Ok(result)
     }
+
+    // Resolves the PTR names of the client so the forward lookup results can be logged
+    async fn iprev_forward_addresses(&self, iprev: &IprevOutput) -> Vec<trc::Value> {
+        let mut addresses = Vec::new();
+        let resolver = &self.server.core.smtp.resolvers.dns;
+        let cache = &self.server.inner.cache;
+
+        for host in iprev
+            .ptr
+            .as_ref()
+            .into_iter()
+            .flat_map(|ptr| ptr.iter())
+            .take(2)
+        {
+            let result = match self.data.remote_ip {
+                IpAddr::V4(_) => resolver
+                    .ipv4_lookup(host.as_ref(), Some(&cache.dns_ipv4))
+                    .await
+                    .map(|ips| {
+                        ips.rrset
+                            .iter()
+                            .map(|ip| ip.to_string())
+                            .collect::<Vec<_>>()
+                    }),
+                IpAddr::V6(_) => resolver
+                    .ipv6_lookup(host.as_ref(), Some(&cache.dns_ipv6))
+                    .await
+                    .map(|ips| {
+                        ips.rrset
+                            .iter()
+                            .map(|ip| ip.to_string())
+                            .collect::<Vec<_>>()
+                    }),
+            };
+            addresses.push(trc::Value::String(
+                match result {
+                    Ok(ips) if !ips.is_empty() => format!("{host} -> {}", ips.join(", ")),
+                    Ok(_) => format!("{host} -> no addresses"),
+                    Err(err) => format!("{host} -> {err}"),
+                }
+                .into(),
+            ));
+        }
+
+        addresses
+    }
+}
+
+// X-CALLBACK is not known to the SMTP parser, so the ESMTP parameters of
+// complete MAIL FROM lines are scanned here and the parameter is removed
+// before the remaining command is parsed
+pub(crate) fn split_callback_param(bytes: &[u8]) -> Option<(usize, Vec<u8>, Vec<u8>)> {
+    let line_len = bytes.iter().position(|&ch| ch == b'\n')? + 1;
+    let line = &bytes[..line_len];
+    if !line
+        .get(..10)
+        .is_some_and(|cmd| cmd.eq_ignore_ascii_case(b"MAIL FROM:"))
+    {
+        return None;
+    }
+
+    // Skip the reverse-path, which may contain quoted strings
+    let mut pos = 10;
+    while line.get(pos) == Some(&b' ') {
+        pos += 1;
+    }
+    if line.get(pos) != Some(&b'<') {
+        return None;
+    }
+    let mut in_quote = false;
+    let mut escaped = false;
+    loop {
+        pos += 1;
+        match *line.get(pos)? {
+            _ if escaped => escaped = false,
+            b'\\' if in_quote => escaped = true,
+            b'"' => in_quote = !in_quote,
+            b'>' if !in_quote => break,
+            b'\r' | b'\n' => return None,
+            _ => {}
+        }
+    }
+    pos += 1;
+
+    // Find the X-CALLBACK keyword among the space separated parameters
+    let params_end = line[..line_len - 1]
+        .strip_suffix(b"\r")
+        .map_or(line_len - 1, |line| line.len());
+    while pos < params_end {
+        let param_start = pos;
+        let param_end = line[param_start + 1..params_end]
+            .iter()
+            .position(|&ch| ch == b' ')
+            .map_or(params_end, |end| param_start + 1 + end);
+        let param = line[param_start..param_end].trim_ascii_start();
+        pos = param_end;
+
+        let (keyword, value) = match param.iter().position(|&ch| ch == b'=') {
+            Some(eq) => (&param[..eq], &param[eq + 1..]),
+            None => (param, &b""[..]),
+        };
+        if keyword.eq_ignore_ascii_case(X_CALLBACK) {
+            let mut request = Vec::with_capacity(line_len);
+            request.extend_from_slice(&line[..param_start]);
+            request.extend_from_slice(&line[param_end..]);
+            return Some((line_len, request, decode_xtext(value)?));
+        }
+    }
+
+    None
+}
+
+fn decode_xtext(value: &[u8]) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(value.len());
+    let mut iter = value.iter();
+    while let Some(&ch) = iter.next() {
+        if ch == b'+' {
+            let hex = [*iter.next()?, *iter.next()?];
+            result.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            result.push(ch);
+        }
+    }
+    Some(result)
 }
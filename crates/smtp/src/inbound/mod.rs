@@ -8,16 +8,23 @@ use mail_auth::{DkimResult, DmarcResult, IprevResult, SpfResult, dmarc::Policy};
 use std::borrow::Cow;
 
 pub mod auth;
+pub mod burl;
 pub mod data;
 pub mod dkim;
 pub mod ehlo;
+pub mod from_alignment;
 pub mod hooks;
+pub mod lmtp;
 pub mod mail;
 pub mod milter;
 pub mod rcpt;
+pub mod response;
+pub mod sender_verify;
 pub mod session;
 pub mod spam;
 pub mod spawn;
+pub mod spool;
+pub mod submit;
 pub mod vrfy;
 
 #[derive(Debug, Default)]
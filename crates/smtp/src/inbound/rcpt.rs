@@ -10,12 +10,13 @@ use crate::{
 };
 use common::{
     KV_GREYLIST,
-    config::smtp::session::Stage,
-    network::{RcptResolution, SessionStream},
+    config::{server::ServerProtocol, smtp::session::Stage},
+    network::{RcptResolution, SessionStream, batv::BatvResult},
     scripts::ScriptModification,
 };
+use registry::schema::enums::MtaResponseId;
 use smtp_proto::{
-    RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_NEVER, RCPT_NOTIFY_SUCCESS, RcptTo,
+    EXT_DSN, RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_NEVER, RCPT_NOTIFY_SUCCESS, RcptTo,
 };
 use std::borrow::Cow;
 use store::dispatch::lookup::KeyValue;
@@ -67,14 +68,74 @@ impl<T: SessionStream> Session<T> {
             return self
                 .write(b"501 5.5.4 DSN extension has been disabled.\r\n")
                 .await;
+        } else if ((to.flags
+            & (RCPT_NOTIFY_DELAY | RCPT_NOTIFY_NEVER | RCPT_NOTIFY_SUCCESS | RCPT_NOTIFY_FAILURE)
+            != 0)
+            || to.orcpt.is_some())
+            && self.params.ehlo_reject_hidden
+            && self.params.ehlo_hidden.contains(EXT_DSN)
+        {
+            trc::event!(
+                Smtp(SmtpEvent::ExtensionNotOffered),
+                SpanId = self.data.session_id,
+                Details = "DSN",
+            );
+            return self
+                .write(b"501 5.5.4 DSN extension has not been offered.\r\n")
+                .await;
+        }
+
+        // Remove BATV tags, bounces are only accepted with a valid signature
+        let mut address = to.address;
+        match self.server.batv_verify(&address).await {
+            Ok(Some((result, untagged))) => {
+                if result != BatvResult::Valid
+                    && self
+                        .data
+                        .mail_from
+                        .as_ref()
+                        .is_some_and(|from| from.address.is_empty())
+                {
+                    trc::event!(
+                        Smtp(SmtpEvent::BatvInvalid),
+                        SpanId = self.data.session_id,
+                        To = address.to_lowercase(),
+                        Details = if result == BatvResult::Expired {
+                            "Expired tag"
+                        } else {
+                            "Invalid signature"
+                        },
+                    );
+
+                    return self
+                        .rcpt_error(
+                            b"550 5.7.1 Invalid or expired BATV signature.\r\n",
+                            address.to_lowercase(),
+                        )
+                        .await;
+                }
+                address = Cow::Owned(untagged.to_string());
+            }
+            Ok(None) => {}
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to verify BATV signature.")
+                );
+
+                return self
+                    .write(b"451 4.4.3 Unable to verify address at this time.\r\n")
+                    .await;
+            }
         }
 
         // Build RCPT
-        let address_lcase = to.address.to_lowercase_address(true);
+        let address_lcase = address.to_lowercase_address(true);
         let rcpt = SessionAddress {
             domain: address_lcase.domain_part().into(),
             address_lcase,
-            address: to.address.into_owned(),
+            address: address.into_owned(),
             flags: to.flags,
             dsn_info: to.orcpt.map(|e| e.into_owned()),
         };
@@ -83,9 +144,9 @@ impl<T: SessionStream> Session<T> {
             trc::event!(
                 Smtp(SmtpEvent::RcptToDuplicate),
                 SpanId = self.data.session_id,
-                To = rcpt.address_lcase,
+                To = rcpt.address_lcase.clone(),
             );
-            self.data.rcpt_oks += 1;
+            self.rcpt_accepted(vec![rcpt.address_lcase]);
             return self.write(b"250 2.1.5 OK\r\n").await;
         }
         self.data.rcpt_to.push(rcpt);
@@ -181,8 +242,8 @@ impl<T: SessionStream> Session<T> {
                     SpanId = self.data.session_id,
                     To = rcpt.address_lcase.clone(),
                 );
-                self.data.rcpt_to.pop();
-                self.data.rcpt_oks += 1;
+                let rcpt = self.data.rcpt_to.pop().unwrap();
+                self.rcpt_accepted(vec![rcpt.address_lcase]);
                 return self.write(b"250 2.1.5 OK\r\n").await;
             }
         }
@@ -211,7 +272,7 @@ impl<T: SessionStream> Session<T> {
                         SpanId = self.data.session_id,
                         To = new_addr.address_lcase.clone(),
                     );
-                    self.data.rcpt_oks += 1;
+                    self.rcpt_accepted(vec![new_addr.address_lcase]);
                     return self.write(b"250 2.1.5 OK\r\n").await;
                 }
             }
@@ -225,10 +286,14 @@ impl<T: SessionStream> Session<T> {
                     To = rcpt.address_lcase.clone(),
                 );
 
-                let rcpt_to = self.data.rcpt_to.pop().unwrap().address_lcase;
-                return self
-                    .rcpt_error(b"550 5.1.2 Mailbox does not exist.\r\n", rcpt_to)
+                let message = self
+                    .build_response(
+                        MtaResponseId::MailboxNotFound,
+                        &b"550 5.1.2 Mailbox does not exist.\r\n"[..],
+                    )
                     .await;
+                let rcpt_to = self.data.rcpt_to.pop().unwrap().address_lcase;
+                return self.rcpt_error(&message, rcpt_to).await;
             }
             Ok(RcptResolution::UnknownDomain) => {
                 if !self
@@ -243,10 +308,14 @@ impl<T: SessionStream> Session<T> {
                         To = rcpt.address_lcase.clone(),
                     );
 
-                    let rcpt_to = self.data.rcpt_to.pop().unwrap().address_lcase;
-                    return self
-                        .rcpt_error(b"550 5.1.2 Relay not allowed.\r\n", rcpt_to)
+                    let message = self
+                        .build_response(
+                            MtaResponseId::RelayDenied,
+                            &b"550 5.1.2 Relay not allowed.\r\n"[..],
+                        )
                         .await;
+                    let rcpt_to = self.data.rcpt_to.pop().unwrap().address_lcase;
+                    return self.rcpt_error(&message, rcpt_to).await;
                 }
             }
             Err(err) => {
@@ -263,6 +332,29 @@ impl<T: SessionStream> Session<T> {
             }
         }
 
+        // Reject recipients that cannot accept a message of the declared size
+        if self.data.declared_size > 0
+            && rcpt_members.is_none()
+            && let Some(max_message_size) = self
+                .rcpt_max_message_size(&self.data.rcpt_to.last().unwrap().address_lcase)
+                .await
+            && self.data.declared_size as u64 > max_message_size
+        {
+            let rcpt = self.data.rcpt_to.pop().unwrap();
+
+            trc::event!(
+                Smtp(SmtpEvent::MessageTooLarge),
+                SpanId = self.data.session_id,
+                To = rcpt.address_lcase,
+                Size = self.data.declared_size,
+                Limit = max_message_size,
+            );
+
+            return self
+                .write(b"552 5.2.3 Message exceeds the maximum size for this recipient.\r\n")
+                .await;
+        }
+
         if self.is_allowed().await {
             // Greylist
             if let Some(greylist_duration) = self
@@ -270,7 +362,7 @@ impl<T: SessionStream> Session<T> {
                 .core
                 .spam
                 .grey_list_expiry
-                .filter(|_| self.data.authenticated_as.is_none())
+                .filter(|_| self.data.authenticated_as.is_none() && !self.skips_greylist())
             {
                 let from_addr = self
                     .data
@@ -344,42 +436,58 @@ impl<T: SessionStream> Session<T> {
                 To = self.data.rcpt_to.last().unwrap().address_lcase.clone(),
             );
 
-            self.data.rcpt_to.pop();
-            return self
-                .write(b"452 4.4.5 Rate limit exceeded, try again later.\r\n")
+            let message = self
+                .build_response(
+                    MtaResponseId::RateLimited,
+                    &b"452 4.4.5 Rate limit exceeded, try again later.\r\n"[..],
+                )
                 .await;
+            self.data.rcpt_to.pop();
+            return self.write(&message).await;
         }
 
         // Expand list
+        let mut rcpt_addresses = Vec::new();
         if let Some(members) = rcpt_members {
             let list_addr = self.data.rcpt_to.pop().unwrap();
+            let expansion = match self
+                .server
+                .rcpt_expand(&list_addr.address_lcase, &members, self.data.session_id)
+                .await
+            {
+                Ok(expansion) => expansion,
+                Err(err) => {
+                    trc::error!(
+                        err.span_id(self.data.session_id)
+                            .caused_by(trc::location!())
+                            .details("Failed to expand address.")
+                    );
+
+                    return self
+                        .write(b"451 4.4.3 Unable to verify address at this time.\r\n")
+                        .await;
+                }
+            };
+
+            // Authenticated senders are told when a list expands to too many recipients,
+            // inbound messages are delivered to the first recipients only.
+            if expansion.is_truncated && self.is_authenticated() {
+                return self
+                    .write(b"550 5.5.3 Too many recipients in list expansion.\r\n")
+                    .await;
+            }
+
             let orcpt = format!("rfc822;{}", list_addr.address_lcase);
-            for member in members.as_ref() {
-                let mut member_addr = SessionAddress::new(member.to_string());
-                if !self.data.rcpt_to.contains(&member_addr)
-                    && member_addr.address_lcase != list_addr.address_lcase
-                {
-                    // Force external directory synchronization
-                    if let Ok(Some(member_domain)) = self.server.domain(&member_addr.domain).await
-                        && self
-                            .server
-                            .get_directory_for_cached_domain(&member_domain)
-                            .is_some_and(|directory| directory.can_lookup_recipients())
-                        && matches!(
-                            self.server
-                                .account_id_from_email(&member_addr.address_lcase, false)
-                                .await,
-                            Ok(None)
-                        )
-                        && let Err(err) = self
-                            .server
-                            .rcpt_resolve(&member_addr.address_lcase, self.data.session_id)
-                            .await
-                    {
-                        trc::error!(
-                            err.span_id(self.data.session_id)
-                                .caused_by(trc::location!())
-                        );
+            let add_trace = self.server.core.smtp.session.rcpt.expansion_trace_header;
+            for member in expansion.recipients {
+                let mut member_addr = SessionAddress::new(member.address);
+                if member_addr.address_lcase == list_addr.address_lcase {
+                    continue;
+                }
+                rcpt_addresses.push(member_addr.address_lcase.clone());
+                if !self.data.rcpt_to.contains(&member_addr) {
+                    if add_trace && !self.data.expanded_from.contains(&member.expanded_from) {
+                        self.data.expanded_from.push(member.expanded_from);
                     }
 
                     member_addr.dsn_info = orcpt.clone().into();
@@ -387,12 +495,36 @@ impl<T: SessionStream> Session<T> {
                     self.data.rcpt_to.push(member_addr);
                 }
             }
+        } else {
+            rcpt_addresses.push(self.data.rcpt_to.last().unwrap().address_lcase.clone());
         }
 
-        self.data.rcpt_oks += 1;
+        self.rcpt_accepted(rcpt_addresses);
         self.write(b"250 2.1.5 OK\r\n").await
     }
 
+    fn rcpt_accepted(&mut self, addresses: Vec<String>) {
+        // LMTP replies to DATA once per accepted recipient, in order
+        if self.instance.protocol == ServerProtocol::Lmtp {
+            self.data.lmtp_rcpts.push(addresses);
+        }
+        self.data.rcpt_oks += 1;
+    }
+
+    pub async fn rcpt_max_message_size(&self, rcpt: &str) -> Option<u64> {
+        match self.server.rcpt_max_message_size(rcpt).await {
+            Ok(max_message_size) => max_message_size,
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to obtain the maximum message size of a recipient.")
+                );
+                None
+            }
+        }
+    }
+
     async fn rcpt_error(&mut self, response: &[u8], rcpt: String) -> Result<(), ()> {
         tokio::time::sleep(self.params.rcpt_errors_wait).await;
         self.data.rcpt_errors += 1;
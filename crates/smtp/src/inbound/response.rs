@@ -0,0 +1,75 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::core::Session;
+use common::network::SessionStream;
+use registry::schema::enums::MtaResponseId;
+use std::borrow::Cow;
+
+const MAX_RESPONSE_TEXT_LEN: usize = 256;
+
+impl<T: SessionStream> Session<T> {
+    // Returns the configured text for a response, keeping the status and
+    // enhanced status codes of the built-in response.
+    pub async fn build_response(
+        &self,
+        id: MtaResponseId,
+        default: impl Into<Cow<'static, [u8]>>,
+    ) -> Cow<'static, [u8]> {
+        let default = default.into();
+
+        if let Some(if_block) = self.server.core.smtp.session.responses.get(&id)
+            && let Some(status) = response_status(&default)
+            && let Some(text) = self
+                .server
+                .eval_if::<String, _>(if_block, self, self.data.session_id)
+                .await
+                .and_then(|text| sanitize_response_text(&text))
+        {
+            let mut response = Vec::with_capacity(status.len() + text.len() + 3);
+            response.extend_from_slice(status);
+            response.push(b' ');
+            response.extend_from_slice(text.as_bytes());
+            response.extend_from_slice(b"\r\n");
+            response.into()
+        } else {
+            default
+        }
+    }
+}
+
+// Returns the status and enhanced status codes, i.e. "550 5.7.1"
+fn response_status(response: &[u8]) -> Option<&[u8]> {
+    let code_end = response.iter().position(|&ch| ch == b' ')?;
+    let status_end = response[code_end + 1..].iter().position(|&ch| ch == b' ')? + code_end + 1;
+
+    (code_end == 3 && response[..code_end].iter().all(u8::is_ascii_digit))
+        .then_some(&response[..status_end])
+}
+
+fn sanitize_response_text(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len().min(MAX_RESPONSE_TEXT_LEN));
+
+    for word in text.split(|ch: char| ch.is_whitespace() || ch.is_control()) {
+        if word.is_empty() {
+            continue;
+        }
+        if !result.is_empty() {
+            if result.len() + 1 >= MAX_RESPONSE_TEXT_LEN {
+                break;
+            }
+            result.push(' ');
+        }
+        for ch in word.chars() {
+            if result.len() == MAX_RESPONSE_TEXT_LEN {
+                break;
+            }
+            result.push(if ch.is_ascii_graphic() { ch } else { '?' });
+        }
+    }
+
+    (!result.is_empty()).then_some(result)
+}
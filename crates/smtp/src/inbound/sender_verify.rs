@@ -0,0 +1,275 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    core::Session,
+    outbound::{
+        client::SmtpClient,
+        dane::dnssec::TlsaLookup,
+        error::{AssertReply, ClientError, ClientResult},
+        lookup::DnsLookup,
+    },
+};
+use common::{
+    KV_RATE_LIMIT_SENDER_VERIFY, KV_SENDER_VERIFY,
+    config::smtp::auth::SenderVerifyResult,
+    network::{SessionStream, limiter::LimiterResult},
+};
+use mail_auth::{IpLookupStrategy, SpfResult};
+use smtp_proto::Response;
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Instant,
+};
+use store::dispatch::lookup::KeyValue;
+use trc::SmtpEvent;
+
+impl<T: SessionStream> Session<T> {
+    /// Verifies the envelope sender by probing its MX with a RCPT TO command.
+    /// Only senders whose SPF check failed are probed, `None` is returned
+    /// for any other sender.
+    pub async fn verify_sender(&self) -> Option<SenderVerifyResult> {
+        let config = &self.server.core.smtp.mail_auth.sender_verify;
+        let mail_from = self.data.mail_from.as_ref()?;
+        if mail_from.address.is_empty()
+            || self.is_authenticated()
+            || config.skip_domains.contains(&mail_from.domain)
+            || !self
+                .data
+                .spf_mail_from
+                .as_ref()
+                .is_some_and(|spf| matches!(spf.result(), SpfResult::Fail | SpfResult::SoftFail))
+        {
+            return None;
+        }
+
+        // Check the cache first
+        let time = Instant::now();
+        let cache_key = KeyValue::<()>::build_key(KV_SENDER_VERIFY, &mail_from.address_lcase);
+        match self
+            .server
+            .in_memory_store()
+            .key_get::<String>(cache_key.clone())
+            .await
+        {
+            Ok(Some(cached)) => {
+                let result = if cached == "pass" {
+                    SenderVerifyResult::Pass
+                } else {
+                    SenderVerifyResult::Fail
+                };
+
+                trc::event!(
+                    Smtp(result.event()),
+                    SpanId = self.data.session_id,
+                    From = mail_from.address_lcase.clone(),
+                    Details = "cached",
+                    Elapsed = time.elapsed(),
+                );
+
+                return Some(result);
+            }
+            Ok(None) => (),
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .caused_by(trc::location!())
+                );
+            }
+        }
+
+        let (result, hostname, details) = self
+            .sender_callout(&mail_from.address, &mail_from.domain)
+            .await;
+
+        trc::event!(
+            Smtp(result.event()),
+            SpanId = self.data.session_id,
+            From = mail_from.address_lcase.clone(),
+            Hostname = hostname,
+            Details = details,
+            Elapsed = time.elapsed(),
+        );
+
+        // Temporary failures are not cached
+        let ttl = match result {
+            SenderVerifyResult::Pass => config.pass_ttl,
+            SenderVerifyResult::Fail => config.fail_ttl,
+            SenderVerifyResult::TempError => return Some(result),
+        };
+        if let Err(err) = self
+            .server
+            .in_memory_store()
+            .key_set(
+                KeyValue::new(cache_key, result.as_str().as_bytes().to_vec())
+                    .expires(ttl.as_secs()),
+            )
+            .await
+        {
+            trc::error!(
+                err.span_id(self.data.session_id)
+                    .caused_by(trc::location!())
+            );
+        }
+
+        Some(result)
+    }
+
+    async fn sender_callout(
+        &self,
+        address: &str,
+        domain: &str,
+    ) -> (SenderVerifyResult, String, String) {
+        let config = &self.server.core.smtp.mail_auth.sender_verify;
+
+        // Obtain the preferred MX, falling back to the implicit MX
+        let hostname = match self.server.mx_lookup(domain).await {
+            Ok(mx_list) => match mx_list.rrset.first().and_then(|mx| mx.exchanges.first()) {
+                Some(host) if host.as_ref() == "." => {
+                    return (
+                        SenderVerifyResult::Fail,
+                        domain.to_string(),
+                        "Domain does not accept mail (null MX)".to_string(),
+                    );
+                }
+                Some(host) => host.trim_end_matches('.').to_string(),
+                None => domain.to_string(),
+            },
+            Err(mail_auth::Error::Dns(mail_auth::DnsError::RecordNotFound(_))) => {
+                domain.to_string()
+            }
+            Err(err) => {
+                return (
+                    SenderVerifyResult::TempError,
+                    domain.to_string(),
+                    err.to_string(),
+                );
+            }
+        };
+
+        // Limit the number of callouts per MX host
+        if let Some(rate) = &config.rate {
+            match self
+                .server
+                .in_memory_store()
+                .is_rate_allowed(
+                    KV_RATE_LIMIT_SENDER_VERIFY,
+                    hostname.as_bytes(),
+                    rate,
+                    false,
+                )
+                .await
+            {
+                Ok(None) => (),
+                Ok(Some(_)) => {
+                    return (
+                        SenderVerifyResult::TempError,
+                        hostname,
+                        "Callout rate limit exceeded".to_string(),
+                    );
+                }
+                Err(err) => {
+                    trc::error!(
+                        err.span_id(self.data.session_id)
+                            .caused_by(trc::location!())
+                    );
+                }
+            }
+        }
+
+        let _in_flight = match config.limiter.is_allowed() {
+            LimiterResult::Allowed(in_flight) => Some(in_flight),
+            LimiterResult::Forbidden => {
+                return (
+                    SenderVerifyResult::TempError,
+                    hostname,
+                    "Too many concurrent callouts".to_string(),
+                );
+            }
+            LimiterResult::Disabled => None,
+        };
+
+        let remote_ip = match self
+            .server
+            .ip_lookup(&hostname, IpLookupStrategy::Ipv4thenIpv6, 1)
+            .await
+        {
+            Ok(ips) if !ips.is_empty() => ips[0],
+            Ok(_) => {
+                return (
+                    SenderVerifyResult::TempError,
+                    hostname,
+                    "No IP addresses found for MX host".to_string(),
+                );
+            }
+            Err(err) => {
+                return (SenderVerifyResult::TempError, hostname, err.to_string());
+            }
+        };
+
+        match self.callout_rcpt(remote_ip, address).await {
+            Ok(response) => (
+                match response.code() {
+                    200..=299 => SenderVerifyResult::Pass,
+                    500..=599 => SenderVerifyResult::Fail,
+                    _ => SenderVerifyResult::TempError,
+                },
+                hostname,
+                response.to_string(),
+            ),
+            Err(err) => (SenderVerifyResult::TempError, hostname, err.to_string()),
+        }
+    }
+
+    async fn callout_rcpt(
+        &self,
+        remote_ip: IpAddr,
+        address: &str,
+    ) -> ClientResult<Response<String>> {
+        #[cfg(feature = "test_mode")]
+        let port = 9925;
+        #[cfg(not(feature = "test_mode"))]
+        let port = 25;
+
+        let timeout = self.server.core.smtp.mail_auth.sender_verify.timeout;
+        let mut client = SmtpClient::connect(
+            SocketAddr::new(remote_ip, port),
+            timeout,
+            self.data.session_id,
+        )
+        .await?;
+        tokio::time::timeout(timeout, client.read())
+            .await
+            .map_err(|_| ClientError::Timeout)??
+            .assert_code(220)?;
+        client
+            .cmd(format!("EHLO {}\r\n", self.hostname))
+            .await?
+            .assert_positive_completion()?;
+        client
+            .cmd(b"MAIL FROM:<>\r\n")
+            .await?
+            .assert_positive_completion()?;
+        let response = client.cmd(format!("RCPT TO:<{address}>\r\n")).await?;
+        client.quit().await;
+
+        Ok(response)
+    }
+}
+
+trait SenderVerifyEvent {
+    fn event(&self) -> SmtpEvent;
+}
+
+impl SenderVerifyEvent for SenderVerifyResult {
+    fn event(&self) -> SmtpEvent {
+        match self {
+            SenderVerifyResult::Pass => SmtpEvent::SenderVerifyPass,
+            SenderVerifyResult::Fail => SmtpEvent::SenderVerifyFail,
+            SenderVerifyResult::TempError => SmtpEvent::SenderVerifyTempFail,
+        }
+    }
+}
@@ -5,8 +5,13 @@
  */
 
 use common::{
+    Server,
     config::{server::ServerProtocol, smtp::session::Mechanism},
-    expr::{self, functions::ResolveVariable, *},
+    expr::{
+        self,
+        functions::{ResolveVariable, header::missing_header},
+        *,
+    },
     network::SessionStream,
 };
 
@@ -23,6 +28,12 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use trc::{NetworkEvent, SecurityEvent, SmtpEvent};
 
 use crate::core::{Session, State};
+use crate::inbound::{
+    mail::split_callback_param,
+    spool::{BDAT_SPOOL_THRESHOLD, BdatSpool},
+};
+
+const BDAT_MAX_PREALLOC: usize = 1024 * 1024;
 
 use super::auth::SaslToken;
 
@@ -34,13 +45,52 @@ impl<T: SessionStream> Session<T> {
         'outer: loop {
             match &mut state {
                 State::Request(receiver) => loop {
-                    match receiver.ingest(&mut iter) {
+                    let mut callback_param = None;
+                    let callback_request;
+                    let result = match (self.params.ehlo_callback && !self.data.partial_request)
+                        .then(|| split_callback_param(iter.as_slice()))
+                        .flatten()
+                    {
+                        Some((line_len, request, param)) => {
+                            iter.nth(line_len - 1);
+                            callback_param = Some(param);
+                            callback_request = request;
+                            receiver.ingest(&mut callback_request.iter())
+                        }
+                        None => {
+                            // Track whether the receiver holds an incomplete line
+                            let pending = iter.as_slice();
+                            let result = receiver.ingest(&mut iter);
+                            self.data.partial_request =
+                                matches!(result, Err(Error::NeedsMoreData { .. }))
+                                    && match pending.iter().rposition(|&ch| ch == b'\n') {
+                                        Some(pos) => pos + 1 < pending.len(),
+                                        None => self.data.partial_request || !pending.is_empty(),
+                                    };
+                            result
+                        }
+                    };
+
+                    match result {
                         Ok(request) => match request {
                             Request::Rcpt { to } => {
                                 self.handle_rcpt_to(to).await?;
                             }
                             Request::Mail { from } => {
-                                self.handle_mail_from(from).await?;
+                                if let Some(param) =
+                                    callback_param.filter(|_| self.data.mail_from.is_none())
+                                {
+                                    if let Some(callback_id) =
+                                        self.handle_delivery_callback(&param).await?
+                                    {
+                                        self.handle_mail_from(from).await?;
+                                        if self.data.mail_from.is_some() {
+                                            self.data.delivery_callback = Some(callback_id);
+                                        }
+                                    }
+                                } else {
+                                    self.handle_mail_from(from).await?;
+                                }
                             }
                             Request::Ehlo { host } => {
                                 if self.instance.protocol == ServerProtocol::Smtp {
@@ -55,7 +105,20 @@ impl<T: SessionStream> Session<T> {
                                 }
                             }
                             Request::Data => {
-                                if self.can_send_data().await? {
+                                if self.data.message_len() > 0 {
+                                    // DATA cannot be mixed with BDAT or BURL chunks
+                                    trc::event!(
+                                        Smtp(SmtpEvent::InvalidCommand),
+                                        SpanId = self.data.session_id,
+                                        Details = "DATA received during a BDAT transaction",
+                                    );
+
+                                    self.reset();
+                                    self.write(
+                                        b"503 5.5.1 DATA not allowed during a BDAT transaction.\r\n",
+                                    )
+                                    .await?;
+                                } else if self.can_send_data().await? {
                                     self.write(b"354 Start mail input; end with <CRLF>.<CRLF>\r\n")
                                         .await?;
                                     self.data.message = Vec::with_capacity(1024);
@@ -67,20 +130,30 @@ impl<T: SessionStream> Session<T> {
                                 chunk_size,
                                 is_last,
                             } => {
-                                state = if chunk_size.saturating_add(self.data.message.len())
-                                    < self.params.max_message_size
+                                if !self.data.chunking || self.data.rcpt_to.is_empty() {
+                                    // CHUNKING was not offered, or there is no transaction
+                                    // or an aborted one, discard the chunk
+                                    state = State::BdatRejected(DummyDataReceiver::new_bdat(
+                                        chunk_size,
+                                    ));
+                                    continue 'outer;
+                                } else if chunk_size.saturating_add(self.data.message_len())
+                                    >= self.params.max_message_size
                                 {
-                                    if self.data.message.is_empty() {
-                                        self.data.message = Vec::with_capacity(chunk_size);
-                                    } else {
-                                        self.data.message.reserve(chunk_size);
-                                    }
-                                    State::Bdat(BdatReceiver::new(chunk_size, is_last))
+                                    // Chunk is too large, discard it and abort the transaction
+                                    state = State::DataTooLarge(DummyDataReceiver::new_bdat(
+                                        chunk_size,
+                                    ));
+                                    continue 'outer;
+                                } else if chunk_size > 0 {
+                                    // Do not trust the announced size for preallocation
+                                    self.data.message.reserve(chunk_size.min(BDAT_MAX_PREALLOC));
+                                    state = State::Bdat(BdatReceiver::new(chunk_size, is_last));
+                                    continue 'outer;
                                 } else {
-                                    // Chunk is too large, ignore.
-                                    State::DataTooLarge(DummyDataReceiver::new_bdat(chunk_size))
-                                };
-                                continue 'outer;
+                                    // Empty chunks, such as "BDAT 0 LAST", carry no data
+                                    self.handle_bdat_chunk(is_last).await?;
+                                }
                             }
                             Request::Auth {
                                 mechanism,
@@ -223,9 +296,10 @@ impl<T: SessionStream> Session<T> {
                                         .await?;
                                 }
                             }
-                            cmd @ (Request::Etrn { .. }
-                            | Request::Atrn { .. }
-                            | Request::Burl { .. }) => {
+                            Request::Burl { uri, is_last } => {
+                                self.handle_burl(uri, is_last).await?;
+                            }
+                            cmd @ (Request::Etrn { .. } | Request::Atrn { .. }) => {
                                 trc::event!(
                                     Smtp(SmtpEvent::CommandNotImplemented),
                                     SpanId = self.data.session_id,
@@ -348,11 +422,7 @@ impl<T: SessionStream> Session<T> {
                     if self.data.message.len() + bytes.len() < self.params.max_message_size {
                         if receiver.ingest(&mut iter, &mut self.data.message) {
                             let message = self.queue_message().await;
-                            let num_responses = if self.instance.protocol == ServerProtocol::Smtp {
-                                1
-                            } else {
-                                self.data.rcpt_oks
-                            };
+                            let num_responses = self.num_data_responses();
                             if !message.is_empty() {
                                 for _ in 0..num_responses {
                                     self.write(message.as_ref()).await?;
@@ -371,30 +441,30 @@ impl<T: SessionStream> Session<T> {
                     }
                 }
                 State::Bdat(receiver) => {
-                    if receiver.ingest(&mut iter, &mut self.data.message) {
-                        if self.can_send_data().await? {
-                            if receiver.is_last {
-                                let message = self.queue_message().await;
-                                if !message.is_empty() {
-                                    let num_responses =
-                                        if self.instance.protocol == ServerProtocol::Smtp {
-                                            1
-                                        } else {
-                                            self.data.rcpt_oks
-                                        };
-                                    for _ in 0..num_responses {
-                                        self.write(message.as_ref()).await?;
-                                    }
-                                    self.reset();
-                                } else {
-                                    // Disconnect requested
-                                    return Err(());
-                                }
-                            } else {
-                                self.write(b"250 2.6.0 Chunk accepted.\r\n").await?;
-                            }
+                    let is_done = receiver.ingest(&mut iter, &mut self.data.message);
+                    if self.data.message.len() >= BDAT_SPOOL_THRESHOLD {
+                        self.spool_bdat_data().await;
+                    }
+                    if is_done {
+                        self.handle_bdat_chunk(receiver.is_last).await?;
+                        state = State::default();
+                    } else {
+                        break 'outer;
+                    }
+                }
+                State::BdatRejected(receiver) => {
+                    if receiver.ingest(&mut iter) {
+                        if self.data.chunking {
+                            self.can_send_data().await?;
                         } else {
-                            self.data.message = Vec::with_capacity(0);
+                            trc::event!(
+                                Smtp(SmtpEvent::ExtensionNotOffered),
+                                SpanId = self.data.session_id,
+                                Details = "CHUNKING",
+                            );
+
+                            self.write(b"502 5.5.1 CHUNKING was not offered.\r\n")
+                                .await?;
                         }
                         state = State::default();
                     } else {
@@ -435,7 +505,8 @@ impl<T: SessionStream> Session<T> {
                             SpanId = self.data.session_id,
                         );
 
-                        self.data.message = Vec::with_capacity(0);
+                        // An oversized message fails the entire transaction
+                        self.reset();
                         self.write(b"552 5.3.4 Message too big for system.\r\n")
                             .await?;
                         state = State::default();
@@ -465,16 +536,100 @@ impl<T: SessionStream> Session<T> {
     }
 }
 
+impl<T: SessionStream> Session<T> {
+    async fn handle_bdat_chunk(&mut self, is_last: bool) -> Result<(), ()> {
+        if self
+            .data
+            .bdat_spool
+            .as_ref()
+            .is_some_and(|spool| spool.is_failed())
+        {
+            // Part of the message was lost, fail the entire transaction
+            self.reset();
+            self.write(b"451 4.3.0 Unable to accept message at this time.\r\n")
+                .await?;
+        } else if !self.can_send_data().await? {
+            self.reset();
+        } else if is_last {
+            if let Some(spool) = self.data.bdat_spool.take() {
+                match spool.read_all(&self.data.message).await {
+                    Ok(message) => {
+                        self.data.message = message;
+                    }
+                    Err(err) => {
+                        trc::error!(
+                            err.span_id(self.data.session_id)
+                                .details("Failed to read spooled BDAT chunks")
+                        );
+                        self.reset();
+                        return self
+                            .write(b"451 4.3.0 Unable to accept message at this time.\r\n")
+                            .await;
+                    }
+                }
+            }
+            let message = self.queue_message().await;
+            if message.is_empty() {
+                // Disconnect requested
+                return Err(());
+            }
+            for _ in 0..self.num_data_responses() {
+                self.write(message.as_ref()).await?;
+            }
+            self.reset();
+        } else {
+            self.write(b"250 2.6.0 Chunk accepted.\r\n").await?;
+        }
+
+        Ok(())
+    }
+
+    // Moves the BDAT data buffered so far to disk to keep memory usage bounded
+    async fn spool_bdat_data(&mut self) {
+        let session_id = self.data.session_id;
+        if let Err(err) = self
+            .data
+            .bdat_spool
+            .get_or_insert_with(|| BdatSpool::new(session_id))
+            .append(&self.data.message)
+            .await
+        {
+            trc::error!(
+                err.span_id(session_id)
+                    .details("Failed to spool BDAT chunk")
+            );
+        }
+        self.data.message.clear();
+    }
+
+    fn num_data_responses(&self) -> usize {
+        // LMTP messages delivered locally already include one reply per recipient
+        if self.instance.protocol == ServerProtocol::Smtp || self.data.lmtp_rcpts.is_empty() {
+            1
+        } else {
+            self.data.rcpt_oks
+        }
+    }
+}
+
 impl<T: AsyncWrite + AsyncRead + Unpin> Session<T> {
     pub fn reset(&mut self) {
         self.data.mail_from = None;
         self.data.spf_mail_from = None;
+        self.data.sender_verify = None;
+        self.data.sender_reputation = None;
+        self.data.delivery_callback = None;
+        self.data.queue_meta.clear();
         self.data.rcpt_to.clear();
+        self.data.expanded_from.clear();
         self.data.message = Vec::with_capacity(0);
+        self.data.bdat_spool = None;
+        self.data.message_headers = None;
         self.data.priority = 0;
         self.data.delivery_by = 0;
         self.data.future_release = 0;
         self.data.rcpt_oks = 0;
+        self.data.lmtp_rcpts.clear();
     }
 
     #[inline(always)]
@@ -577,6 +732,12 @@ impl<T: SessionStream> ResolveVariable for Session<T> {
                 .map(|m| m.domain.as_str())
                 .unwrap_or_default()
                 .into(),
+            ExpressionVariable::SenderVerify => self
+                .data
+                .sender_verify
+                .map(|r| r.as_str())
+                .unwrap_or("none")
+                .into(),
             ExpressionVariable::HeloDomain => self.data.helo_domain.as_str().into(),
             ExpressionVariable::AuthenticatedAs => {
                 self.authenticated_as().unwrap_or_default().into()
@@ -589,6 +750,37 @@ impl<T: SessionStream> ResolveVariable for Session<T> {
             ExpressionVariable::IsTls => self.stream.is_tls().into(),
             ExpressionVariable::Priority => self.data.priority.to_compact_string().into(),
             ExpressionVariable::Protocol => self.instance.protocol.as_str().into(),
+            ExpressionVariable::SessionId => self.data.session_id.to_compact_string().into(),
+            ExpressionVariable::CertSubject => self
+                .data
+                .client_cert
+                .as_ref()
+                .map(|c| c.subject.as_str())
+                .unwrap_or_default()
+                .into(),
+            ExpressionVariable::CertEmail => self
+                .data
+                .client_cert
+                .as_ref()
+                .and_then(|c| c.emails.first())
+                .map(|e| e.as_str())
+                .unwrap_or_default()
+                .into(),
+            ExpressionVariable::CertDns => self
+                .data
+                .client_cert
+                .as_ref()
+                .and_then(|c| c.dns_names.first())
+                .map(|d| d.as_str())
+                .unwrap_or_default()
+                .into(),
+            ExpressionVariable::CertFingerprint => self
+                .data
+                .client_cert
+                .as_ref()
+                .map(|c| c.fingerprint.as_str())
+                .unwrap_or_default()
+                .into(),
             ExpressionVariable::Asn => self
                 .data
                 .asn_geo_data
@@ -605,6 +797,9 @@ impl<T: SessionStream> ResolveVariable for Session<T> {
                 .map(|c| c.as_str())
                 .unwrap_or_default()
                 .into(),
+            ExpressionVariable::SenderReputation => {
+                self.data.sender_reputation.map_or(1.0, |r| r.score).into()
+            }
             _ => expr::Variable::default(),
         }
     }
@@ -612,4 +807,11 @@ impl<T: SessionStream> ResolveVariable for Session<T> {
     fn resolve_global(&self, _: &str) -> Variable<'_> {
         Variable::Integer(0)
     }
+
+    async fn resolve_header(&self, _: &Server, name: &str, all: bool) -> Variable<'_> {
+        match &self.data.message_headers {
+            Some(headers) => headers.get(name, all),
+            None => missing_header(all),
+        }
+    }
 }
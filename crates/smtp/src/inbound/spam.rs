@@ -6,15 +6,24 @@
 
 use crate::core::Session;
 use common::{config::mailstore::spamfilter::SpamFilterAction, network::SessionStream};
-use mail_auth::{ArcOutput, DkimOutput, DmarcResult, dkim2::Dkim2Output, dmarc::Policy};
-use mail_parser::Message;
+use mail_auth::{ArcOutput, DkimOutput, DmarcResult, SpfResult, dkim2::Dkim2Output, dmarc::Policy};
+use mail_parser::{HeaderName, HeaderValue, Host, Message};
+use registry::schema::enums::MtaResponseId;
 use spam_filter::{
-    SpamFilterInput,
+    SpamFilterInput, SpamFilterResult,
     analysis::{
         init::SpamFilterInit,
         score::{SpamFilterAnalyzeScore, SpamFilterScore},
     },
+    modules::reputation::{ReputationEvent, SenderReputationStore},
 };
+use std::{borrow::Cow, net::IpAddr};
+use trc::SpamEvent;
+
+pub struct ForwardedClient<'x> {
+    pub ip: IpAddr,
+    pub helo: Option<&'x str>,
+}
 
 impl<T: SessionStream> Session<T> {
     pub async fn spam_classify<'x>(
@@ -26,25 +35,197 @@ impl<T: SessionStream> Session<T> {
         dmarc_result: Option<&'x DmarcResult>,
         dmarc_policy: Option<&'x Policy>,
     ) -> SpamFilterAction<SpamFilterScore> {
+        if let Some(mut result) = self
+            .spam_analyze(
+                message,
+                dkim_result,
+                dkim2_result,
+                arc_result,
+                dmarc_result,
+                dmarc_policy,
+            )
+            .await
+        {
+            self.server
+                .spam_filter_finalize_result(&mut result, self.data.rcpt_to.len())
+                .await
+        } else {
+            SpamFilterAction::Disabled
+        }
+    }
+
+    pub async fn spam_analyze<'x>(
+        &'x self,
+        message: &'x Message<'x>,
+        dkim_result: &'x [DkimOutput<'x>],
+        dkim2_result: Option<&'x Dkim2Output<'x>>,
+        arc_result: Option<&'x ArcOutput<'x>>,
+        dmarc_result: Option<&'x DmarcResult>,
+        dmarc_policy: Option<&'x Policy>,
+    ) -> Option<SpamFilterResult> {
         let server = &self.server;
-        let mut ctx = server.spam_filter_init(self.build_spam_input(
+        let forwarded = self.forwarded_client(message);
+        let forwarded_asn_geo = if let Some(client) = &forwarded {
+            Some(server.lookup_asn_country(client.ip).await)
+        } else {
+            None
+        };
+
+        let mut input = self.build_spam_input(
             message,
             dkim_result,
             dkim2_result,
             arc_result,
             dmarc_result,
             dmarc_policy,
-        ));
+        );
+        if let (Some(client), Some(asn_geo)) = (forwarded, &forwarded_asn_geo) {
+            // Analyze the original client rather than the trusted forwarder
+            input.remote_ip = client.ip;
+            if let Some(helo) = client.helo {
+                input.ehlo_domain = Some(helo);
+            }
+            input.asn = asn_geo.asn.as_ref().map(|a| a.id);
+            input.country = asn_geo.country.as_ref().map(|c| c.as_str());
+        }
+        let mut ctx = server.spam_filter_init(input);
 
         if !self.is_authenticated() {
-            // Spam classification
-            server.spam_filter_classify(&mut ctx).await
+            // Spam analysis, the final score is calculated by the caller
+            server.spam_filter_analyze(&mut ctx).await;
+            ctx.result.rcpt_spam_thresholds = self.rcpt_spam_thresholds().await;
+            Some(ctx.result)
         } else {
             // Do not classify authenticated sessions
-            SpamFilterAction::Disabled
+            None
+        }
+    }
+
+    /// Returns the spam threshold configured for the domain of each
+    /// recipient, if any.
+    async fn rcpt_spam_thresholds(&self) -> Vec<Option<f32>> {
+        let mut thresholds = Vec::with_capacity(self.data.rcpt_to.len());
+        for rcpt in &self.data.rcpt_to {
+            thresholds.push(match self.server.domain_settings(&rcpt.domain).await {
+                Ok(settings) => settings
+                    .and_then(|settings| settings.spam_threshold)
+                    .map(|threshold| threshold as f32),
+                Err(err) => {
+                    trc::error!(
+                        err.span_id(self.data.session_id)
+                            .caused_by(trc::location!())
+                            .details("Failed to obtain domain settings")
+                    );
+                    None
+                }
+            });
+        }
+        thresholds
+    }
+
+    pub async fn spam_filter_reject(
+        &mut self,
+        discard: bool,
+        message_id: u64,
+    ) -> Cow<'static, [u8]> {
+        self.record_sender_reputation(ReputationEvent::Spam).await;
+        self.data.messages_sent += 1;
+
+        if discard {
+            trc::event!(
+                Spam(SpamEvent::Classify),
+                SpanId = self.data.session_id,
+                QueueId = message_id,
+                Result = "discard",
+                Reason = "Message discarded due to excessive spam score.",
+            );
+
+            (b"250 2.0.0 Message queued for delivery.\r\n"[..]).into()
+        } else {
+            trc::event!(
+                Spam(SpamEvent::Classify),
+                SpanId = self.data.session_id,
+                QueueId = message_id,
+                Result = "reject",
+                Reason = "Message rejected due to excessive spam score.",
+            );
+
+            self.build_response(
+                MtaResponseId::SpamReject,
+                &b"550 5.7.1 Message rejected due to excessive spam score.\r\n"[..],
+            )
+            .await
+        }
+    }
+
+    pub async fn lookup_sender_reputation(&mut self) {
+        if self.server.core.spam.reputation.is_none() || self.is_authenticated() {
+            return;
+        }
+
+        let result = self
+            .server
+            .sender_reputation(self.sender_reputation_domain(), self.data.remote_ip)
+            .await;
+        match result {
+            Ok(reputation) => {
+                self.data.sender_reputation = reputation;
+            }
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to obtain sender reputation.")
+                );
+            }
+        }
+    }
+
+    pub async fn record_sender_reputation(&self, event: ReputationEvent) {
+        if self.server.core.spam.reputation.is_some()
+            && !self.is_authenticated()
+            && let Err(err) = self
+                .server
+                .sender_reputation_record(
+                    self.sender_reputation_domain(),
+                    self.data.remote_ip,
+                    event,
+                )
+                .await
+        {
+            trc::error!(
+                err.span_id(self.data.session_id)
+                    .caused_by(trc::location!())
+                    .details("Failed to update sender reputation.")
+            );
         }
     }
 
+    pub fn skips_greylist(&self) -> bool {
+        self.server
+            .core
+            .spam
+            .reputation
+            .as_ref()
+            .is_some_and(|config| {
+                config.skip_greylist
+                    && self
+                        .data
+                        .sender_reputation
+                        .is_some_and(|reputation| reputation.is_trusted(config))
+            })
+    }
+
+    // Senders are only attributed to their domain when authorized by SPF
+    fn sender_reputation_domain(&self) -> Option<&str> {
+        self.data
+            .spf_mail_from
+            .as_ref()
+            .filter(|spf| matches!(spf.result(), SpfResult::Pass))
+            .and(self.data.mail_from.as_ref())
+            .map(|mail_from| mail_from.domain.as_str())
+    }
+
     pub fn build_spam_input<'x>(
         &'x self,
         message: &'x Message<'x>,
@@ -65,6 +246,7 @@ impl<T: SessionStream> Session<T> {
             dmarc_result,
             dmarc_policy,
             iprev_result: self.data.iprev.as_ref(),
+            sender_verify_result: self.data.sender_verify,
             remote_ip: self.data.remote_ip,
             ehlo_domain: self.data.helo_domain.as_str().into(),
             authenticated_as: self.data.authenticated_as.as_ref().map(|a| a.name()),
@@ -104,4 +286,46 @@ impl<T: SessionStream> Session<T> {
             is_train: false,
         }
     }
+
+    pub fn forwarded_client<'x>(&self, message: &'x Message<'x>) -> Option<ForwardedClient<'x>> {
+        let config = &self.server.core.spam;
+        let is_trusted = |ip: &IpAddr| {
+            config
+                .trusted_forwarders
+                .iter()
+                .any(|network| network.matches(ip))
+        };
+        if !is_trusted(&self.data.remote_ip) {
+            return None;
+        }
+
+        // Walk the Received headers added by trusted forwarders, topmost first
+        let mut client = None;
+        for header in message
+            .headers()
+            .iter()
+            .filter(|header| header.name == HeaderName::Received)
+            .take(config.trusted_forwarder_depth)
+        {
+            let HeaderValue::Received(received) = &header.value else {
+                break;
+            };
+            let Some(ip) = received.from_ip() else {
+                break;
+            };
+            client = Some(ForwardedClient {
+                ip,
+                helo: match received.from().or_else(|| received.helo()) {
+                    Some(Host::Name(helo)) => Some(helo.as_ref()),
+                    _ => None,
+                },
+            });
+
+            if !is_trusted(&ip) {
+                break;
+            }
+        }
+
+        client
+    }
 }
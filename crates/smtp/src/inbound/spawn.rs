@@ -22,6 +22,7 @@ impl SessionManager for SmtpSessionManager {
         // Build server and create session
         let server = self.inner.build_server();
         let _in_flight = session.in_flight;
+        let _draining = server.inner.data.drain.track_session();
         let mut session = Session {
             data: SessionData::new(
                 session.local_ip,
@@ -45,6 +46,7 @@ impl SessionManager for SmtpSessionManager {
             && session.handle_conn().await
             && session.instance.acceptor.is_tls()
             && let Ok(mut session) = session.into_tls().await
+            && session.authenticate_client_cert().await.is_ok()
         {
             session.handle_conn().await;
         }
@@ -73,6 +75,11 @@ impl<T: SessionStream> Session<T> {
     pub async fn init_conn(&mut self) -> bool {
         self.eval_session_params().await;
 
+        // Implicit TLS connections authenticate with the client certificate before the greeting
+        if self.authenticate_client_cert().await.is_err() {
+            return false;
+        }
+
         let config = &self.server.core.smtp.session.connect;
 
         // Sieve filtering
@@ -142,8 +149,16 @@ impl<T: SessionStream> Session<T> {
     pub async fn handle_conn(&mut self) -> bool {
         let mut buf = vec![0; 8192];
         let mut shutdown_rx = self.instance.shutdown_rx.clone();
+        let mut drain_rx = self.server.inner.data.drain.subscribe();
 
         loop {
+            let mut session_signal = self
+                .data
+                .session_lease
+                .as_ref()
+                .map(|lease| lease.signal())
+                .unwrap_or_default();
+
             tokio::select! {
                 result = tokio::time::timeout(
                     self.params.timeout,
@@ -234,6 +249,16 @@ impl<T: SessionStream> Session<T> {
                             }
                         }
                 },
+                _ = drain_rx.wait_for(|is_draining| *is_draining), if self.data.mail_from.is_none() => {
+                    trc::event!(
+                        Network(trc::NetworkEvent::Closed),
+                        SpanId = self.data.session_id,
+                        Reason = "Server draining",
+                        CausedBy = trc::location!()
+                    );
+                    self.write(format!("421 4.3.0 {} Server shutting down.\r\n", self.hostname).as_bytes()).await.ok();
+                    break;
+                },
                 _ = shutdown_rx.changed() => {
                     trc::event!(
                         Network(trc::NetworkEvent::Closed),
@@ -244,6 +269,16 @@ impl<T: SessionStream> Session<T> {
                     self.write(format!("421 4.3.0 {} Server shutting down.\r\n", self.hostname).as_bytes()).await.ok();
                     break;
                 }
+                _ = session_signal.terminated() => {
+                    trc::event!(
+                        Network(trc::NetworkEvent::Closed),
+                        SpanId = self.data.session_id,
+                        Reason = "Session terminated by administrator",
+                        CausedBy = trc::location!()
+                    );
+                    self.write(format!("421 4.7.0 {} Session terminated by administrator.\r\n", self.hostname).as_bytes()).await.ok();
+                    break;
+                }
             };
         }
 
@@ -0,0 +1,118 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+
+// BDAT data buffered in memory before it is moved to the spool
+pub const BDAT_SPOOL_THRESHOLD: usize = 1024 * 1024;
+
+static SPOOL_ID: AtomicU64 = AtomicU64::new(0);
+
+// Temporary file holding the BDAT chunks received so far, removed on drop
+pub struct BdatSpool {
+    path: PathBuf,
+    file: Option<File>,
+    size: usize,
+    failed: bool,
+}
+
+impl BdatSpool {
+    pub fn new(session_id: u64) -> Self {
+        BdatSpool {
+            path: std::env::temp_dir().join(format!(
+                "stalwart-bdat-{}-{session_id:x}-{:x}",
+                std::process::id(),
+                SPOOL_ID.fetch_add(1, Ordering::Relaxed)
+            )),
+            file: None,
+            size: 0,
+            failed: false,
+        }
+    }
+
+    // Once a write fails the remaining chunks are discarded and the transaction fails
+    pub async fn append(&mut self, bytes: &[u8]) -> trc::Result<()> {
+        if self.failed {
+            return Ok(());
+        }
+        let result = match &mut self.file {
+            Some(file) => file.write_all(bytes).await,
+            None => match File::options()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&self.path)
+                .await
+            {
+                Ok(file) => self.file.insert(file).write_all(bytes).await,
+                Err(err) => Err(err),
+            },
+        };
+        match result {
+            Ok(_) => {
+                self.size += bytes.len();
+                Ok(())
+            }
+            Err(err) => {
+                self.failed = true;
+                Err(spool_error(err, &self.path))
+            }
+        }
+    }
+
+    // Reads back the spooled chunks followed by the data still in memory, the
+    // buffer is sized from the tracked spool length and the file is never read past it
+    pub async fn read_all(mut self, tail: &[u8]) -> trc::Result<Vec<u8>> {
+        let mut message = vec![0u8; self.size + tail.len()];
+        if let Some(file) = &mut self.file {
+            file.flush()
+                .await
+                .map_err(|err| spool_error(err, &self.path))?;
+            file.rewind()
+                .await
+                .map_err(|err| spool_error(err, &self.path))?;
+            file.read_exact(&mut message[..self.size])
+                .await
+                .map_err(|err| spool_error(err, &self.path))?;
+        }
+        message[self.size..].copy_from_slice(tail);
+        Ok(message)
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+}
+
+impl Drop for BdatSpool {
+    fn drop(&mut self) {
+        if self.file.is_some() {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn spool_error(err: std::io::Error, path: &std::path::Path) -> trc::Error {
+    trc::StoreEvent::FilesystemError
+        .reason(err)
+        .ctx(trc::Key::Path, path.display().to_string())
+        .caused_by(trc::location!())
+}
@@ -0,0 +1,154 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::core::{Session, State};
+use common::{Server, network::stream::NullIo};
+use email::message::metadata::{ArchivedMessageMetadata, ArchivedMetadataHeaderName};
+use smtp_proto::{MailFrom, RcptTo};
+use std::{borrow::Cow, time::Duration};
+use trc::AddContext;
+
+pub struct LocalSubmission {
+    // Server reply for each rejected recipient, None if it was accepted
+    pub rcpt_responses: Vec<(String, Option<String>)>,
+    pub queue_id: Option<u64>,
+}
+
+pub enum LocalSubmissionError {
+    MailFrom(String),
+    Data(String),
+}
+
+impl Session<NullIo> {
+    /// Submits a message on behalf of the session account, the message is
+    /// queued when at least one recipient is accepted. The session runs on
+    /// its own task to avoid overflowing the stack.
+    pub async fn submit_message(
+        mut self,
+        mail_from: MailFrom<Cow<'static, str>>,
+        rcpt_to: Vec<RcptTo<Cow<'static, str>>>,
+        delivery_callback: Option<u64>,
+        message: Vec<u8>,
+    ) -> trc::Result<Result<LocalSubmission, LocalSubmissionError>> {
+        tokio::spawn(async move {
+            // MAIL FROM
+            let _ = self.handle_mail_from(mail_from).await;
+            if let Some(error) = self.has_failed() {
+                return Err(LocalSubmissionError::MailFrom(format!(
+                    "Server rejected MAIL-FROM: {}",
+                    error.trim()
+                )));
+            }
+            self.data.delivery_callback = delivery_callback;
+
+            // RCPT TO
+            let mut rcpt_responses = Vec::with_capacity(rcpt_to.len());
+            let mut has_success = false;
+            self.params.rcpt_errors_wait = Duration::from_secs(0);
+            for rcpt in rcpt_to {
+                let address = rcpt.address.to_string();
+                let _ = self.handle_rcpt_to(rcpt).await;
+                let response = self.has_failed();
+                if response.is_none() {
+                    has_success = true;
+                }
+                rcpt_responses.push((address, response));
+            }
+
+            // DATA
+            if has_success {
+                self.data.message = message;
+                let response = self.queue_message().await;
+                if let State::Accepted(queue_id) = self.state {
+                    Ok(LocalSubmission {
+                        rcpt_responses,
+                        queue_id: Some(queue_id),
+                    })
+                } else {
+                    Err(LocalSubmissionError::Data(format!(
+                        "Server rejected DATA: {}",
+                        std::str::from_utf8(&response).unwrap_or_default().trim()
+                    )))
+                }
+            } else {
+                Ok(LocalSubmission {
+                    rcpt_responses,
+                    queue_id: None,
+                })
+            }
+        })
+        .await
+        .map_err(|err| {
+            trc::EventType::Server(trc::ServerEvent::ThreadError)
+                .reason(err)
+                .caused_by(trc::location!())
+                .details("Join Error")
+        })
+    }
+}
+
+// Fetches the message to submit, without its Bcc header
+pub async fn submission_message(
+    server: &Server,
+    metadata: &ArchivedMessageMetadata,
+) -> trc::Result<Option<Vec<u8>>> {
+    let Some(message) = server
+        .blob_store()
+        .get_blob(metadata.blob_hash.0.as_slice(), 0..usize::MAX)
+        .await
+        .caused_by(trc::location!())?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(
+        if let Some(bcc_header) = metadata.contents[0].parts[0]
+            .headers
+            .iter()
+            .find(|header| matches!(header.name, ArchivedMetadataHeaderName::Bcc))
+        {
+            let range = bcc_header.name_value_range();
+            let mut new_message = Vec::with_capacity(message.len());
+            new_message.extend_from_slice(&message[..range.start]);
+            new_message.extend_from_slice(&message[range.end..]);
+            new_message
+        } else {
+            message
+        },
+    ))
+}
+
+pub fn from_into_static(from: MailFrom<Cow<'_, str>>) -> MailFrom<Cow<'static, str>> {
+    MailFrom {
+        address: from.address.into_owned().into(),
+        flags: from.flags,
+        size: from.size,
+        trans_id: from.trans_id.map(Cow::into_owned).map(Cow::Owned),
+        by: from.by,
+        env_id: from.env_id.map(Cow::into_owned).map(Cow::Owned),
+        solicit: from.solicit.map(Cow::into_owned).map(Cow::Owned),
+        mtrk: from
+            .mtrk
+            .map(smtp_proto::Mtrk::into_owned)
+            .map(|v| smtp_proto::Mtrk {
+                certifier: Cow::Owned(v.certifier),
+                timeout: v.timeout,
+            }),
+        auth: from.auth.map(Cow::into_owned).map(Cow::Owned),
+        hold_for: from.hold_for,
+        hold_until: from.hold_until,
+        mt_priority: from.mt_priority,
+    }
+}
+
+pub fn rcpt_into_static(rcpt: RcptTo<Cow<'_, str>>) -> RcptTo<Cow<'static, str>> {
+    RcptTo {
+        address: rcpt.address.into_owned().into(),
+        orcpt: rcpt.orcpt.map(Cow::into_owned).map(Cow::Owned),
+        rrvs: rcpt.rrvs,
+        flags: rcpt.flags,
+    }
+}
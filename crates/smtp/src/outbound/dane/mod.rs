@@ -5,4 +5,5 @@
  */
 
 pub mod dnssec;
+pub mod rollover;
 pub mod verify;
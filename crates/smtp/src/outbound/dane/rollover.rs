@@ -0,0 +1,78 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::dnssec::{TlsaLookup, TlsaResult};
+use common::{
+    Server,
+    config::smtp::resolver::{Tlsa, TlsaEntry, TlsaMatching},
+    network::dane::tlsa_matches_chain,
+};
+use rustls_pki_types::CertificateDer;
+use std::{future::Future, sync::Arc};
+use utils::HexEncode;
+
+// TLSA records published for one of our own hostnames
+#[derive(Debug, Clone)]
+pub enum PublishedTlsa {
+    Records(Arc<Tlsa>),
+    NotFound,
+    Bogus,
+    Error(String),
+}
+
+pub trait DanePublishedTlsa: Sync + Send {
+    fn published_tlsa(&self, hostname: &str) -> impl Future<Output = PublishedTlsa> + Send;
+}
+
+impl DanePublishedTlsa for Server {
+    async fn published_tlsa(&self, hostname: &str) -> PublishedTlsa {
+        match self.tlsa_lookup(format!("_25._tcp.{hostname}.")).await {
+            Ok(TlsaResult::Secure(tlsa)) if !tlsa.entries.is_empty() => {
+                PublishedTlsa::Records(tlsa)
+            }
+            // Records that are not DNSSEC signed are ignored by DANE clients
+            Ok(TlsaResult::Secure(_) | TlsaResult::Missing) => PublishedTlsa::NotFound,
+            Ok(TlsaResult::Bogus) => PublishedTlsa::Bogus,
+            Err(mail_auth::Error::Dns(mail_auth::DnsError::RecordNotFound(_))) => {
+                PublishedTlsa::NotFound
+            }
+            Err(err) => PublishedTlsa::Error(err.to_string()),
+        }
+    }
+}
+
+impl PublishedTlsa {
+    // Returns whether a DANE client would authenticate the chain, or None
+    // when DANE is not in use for the hostname
+    pub fn matches(&self, chain: &[CertificateDer<'_>]) -> Option<bool> {
+        match self {
+            PublishedTlsa::Records(tlsa) => Some(tlsa_matches_chain(tlsa, chain)),
+            PublishedTlsa::NotFound => None,
+            PublishedTlsa::Bogus | PublishedTlsa::Error(_) => Some(false),
+        }
+    }
+
+    pub fn records(&self) -> Vec<String> {
+        match self {
+            PublishedTlsa::Records(tlsa) => tlsa.entries.iter().map(tlsa_entry_to_string).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn tlsa_entry_to_string(entry: &TlsaEntry) -> String {
+    format!(
+        "{} {} {} {}",
+        if entry.is_end_entity { 3 } else { 2 },
+        u8::from(entry.is_spki),
+        match entry.matching {
+            TlsaMatching::Full => 0,
+            TlsaMatching::Sha256 => 1,
+            TlsaMatching::Sha512 => 2,
+        },
+        entry.data.hex_encode()
+    )
+}
@@ -11,16 +11,19 @@ use crate::outbound::client::{
 };
 use crate::outbound::dane::dnssec::{DnssecStatus, TlsaLookup, TlsaResult};
 use crate::outbound::error::ClientError;
-use crate::outbound::lookup::{DnsLookup, SourceIp};
+use crate::outbound::lookup::{DnsLookup, RelayHosts, SelectSourceIp};
 use crate::outbound::mta_sts::lookup::MtaStsLookup;
 use crate::outbound::mta_sts::verify::VerifyPolicy;
 use crate::outbound::{client::StartTlsResult, dane::verify::TlsaVerify};
+use crate::queue::callback::SendDeliveryCallback;
 use crate::queue::dsn::SendDsn;
+use crate::queue::manager::ExpiryReason;
 use crate::queue::spool::SmtpSpool;
 use crate::queue::throttle::IsAllowed;
 use crate::queue::{
-    Error, FROM_REPORT, HostResponse, MessageWrapper, Metadata, QueueEnvelope, QueuedMessage,
-    Status,
+    Error, FROM_AUTHENTICATED, FROM_REPORT, HostResponse, Message, MessageWrapper, Metadata,
+    QueueEnvelope, QueuedMessage, RCPT_DSN_SENT, RCPT_EXPIRED_ATTEMPTS, RCPT_EXPIRED_TTL,
+    RCPT_SPLIT, Status,
 };
 use crate::reporting::send::MtaReportSend;
 use crate::{queue::ErrorDetails, reporting::tls::TlsRptOptions};
@@ -29,13 +32,15 @@ use common::Server;
 use common::config::smtp::queue::RoutingStrategy;
 use common::config::{server::ServerProtocol, smtp::report::AggregateFrequency};
 use common::ipc::{PolicyType, QueueEvent, QueueEventStatus, TlsEvent};
+use common::network::limiter::LimiterResult;
+use common::telemetry::metrics::queue::DeliveryOutcome;
 use compact_str::ToCompactString;
 use mail_auth::RecordSet;
 use mail_auth::{
     mta_sts::TlsRpt,
     report::tlsrpt::{FailureDetails, ResultType},
 };
-use smtp_proto::MAIL_REQUIRETLS;
+use smtp_proto::{MAIL_REQUIRETLS, Response};
 use std::sync::Arc;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -46,7 +51,10 @@ use trc::{DaneEvent, DeliveryEvent, MtaStsEvent, ServerEvent, TlsRptEvent};
 
 impl QueuedMessage {
     pub fn try_deliver(self, server: Server) {
+        let in_flight = server.inner.data.drain.track_delivery();
         tokio::spawn(async move {
+            let _in_flight = in_flight;
+
             // Lock queue event
             let queue_id = self.queue_id;
             let status = if server.try_lock_event(queue_id, self.queue_name).await {
@@ -83,6 +91,7 @@ impl QueuedMessage {
                             .collect::<Vec<_>>(),
                         Size = message.message.size,
                         Total = message.message.recipients.len(),
+                        Metadata = message.message.queue_meta_trace(),
                     );
 
                     // Attempt delivery
@@ -181,6 +190,32 @@ impl QueuedMessage {
                 return QueueEventStatus::Deferred;
             }
         }
+        server
+            .inner
+            .data
+            .queue_metrics
+            .message_attempted(now().saturating_sub(message.message.created));
+
+        // Defer recipients that are due outside their delivery window
+        if server
+            .core
+            .smtp
+            .queue
+            .queue_strategy
+            .values()
+            .any(|queue| queue.window.is_some())
+            && message.defer_outside_window(&server).await
+        {
+            message.save_changes(&server, self.due.into()).await;
+            return QueueEventStatus::Deferred;
+        }
+
+        // Split recipients whose routes diverged into separate messages
+        let children = message.split_routes(&server).await;
+        if !children.is_empty() {
+            message.save_split(&server, self.due.into(), children).await;
+            return QueueEventStatus::Deferred;
+        }
 
         // Throttle sender
         for throttle in &server.core.smtp.queue.outbound_limiters.sender {
@@ -214,6 +249,24 @@ impl QueuedMessage {
             }
         }
 
+        // Sign the return path of locally originated messages, forwarded
+        // messages keep the envelope sender they were received with
+        let batv_return_path = if message.message.flags & FROM_AUTHENTICATED != 0 {
+            server
+                .batv_sign(&message.message.return_path)
+                .await
+                .unwrap_or_else(|err| {
+                    trc::error!(
+                        err.span_id(span_id)
+                            .caused_by(trc::location!())
+                            .details("Failed to sign return path.")
+                    );
+                    None
+                })
+        } else {
+            None
+        };
+
         // Group recipients by route
         let queue_config = &server.core.smtp.queue;
         let now_ = now();
@@ -274,6 +327,11 @@ impl QueuedMessage {
                 SpanId = message.span_id,
                 Domain = domain.to_string(),
             );
+            server.inner.data.queue_metrics.record_delivery(
+                domain,
+                DeliveryOutcome::Attempt,
+                server.core.metrics.queue_top_domains,
+            );
 
             // Build envelope
             let mut envelope =
@@ -307,11 +365,27 @@ impl QueuedMessage {
                     continue 'next_route;
                 }
                 RoutingStrategy::Mx(mx_config) => (Vec::with_capacity(0), Some(mx_config), true),
-                RoutingStrategy::Relay(relay_config) => (
-                    vec![NextHop::Relay(relay_config)],
-                    None,
-                    relay_config.protocol == ServerProtocol::Smtp,
-                ),
+                RoutingStrategy::Relay(relay_config) => {
+                    let remote_hosts = relay_config.relay_hosts();
+                    if remote_hosts.is_empty() {
+                        delivery_results.push(DeliveryResult::domain(
+                            Status::TemporaryFailure(ErrorDetails {
+                                entity: relay_config.hosts[0].hostname().into(),
+                                details: Error::ConnectionError(
+                                    "All relay hosts are marked as down".into(),
+                                ),
+                            }),
+                            rcpt_idxs,
+                        ));
+                        continue 'next_route;
+                    }
+
+                    (
+                        remote_hosts,
+                        None,
+                        relay_config.protocol == ServerProtocol::Smtp,
+                    )
+                }
             };
 
             // Prepare TLS strategy
@@ -598,6 +672,45 @@ impl QueuedMessage {
             // Try delivering message
             let mut last_status: Status<HostResponse<Box<str>>, ErrorDetails> = Status::Scheduled;
             'next_host: for remote_host in &remote_hosts {
+                // Enforce the concurrency limit of relay hosts
+                let _in_flight = if let NextHop::Relay { config, host } = remote_host {
+                    let in_flight = match &host.limiter {
+                        Some(limiter) => match limiter.is_allowed() {
+                            LimiterResult::Allowed(in_flight) => Some(in_flight),
+                            _ => {
+                                trc::event!(
+                                    Delivery(DeliveryEvent::ConcurrencyLimitExceeded),
+                                    SpanId = message.span_id,
+                                    Domain = domain.to_string(),
+                                    Hostname = host.hostname().to_string(),
+                                    Limit = limiter.max_concurrent(),
+                                );
+
+                                last_status = Status::TemporaryFailure(ErrorDetails {
+                                    entity: host.hostname().into(),
+                                    details: Error::ConcurrencyLimited,
+                                });
+                                continue 'next_host;
+                            }
+                        },
+                        None => None,
+                    };
+
+                    if config.hosts.len() > 1 {
+                        trc::event!(
+                            Delivery(DeliveryEvent::RelayHostSelected),
+                            SpanId = message.span_id,
+                            Domain = domain.to_string(),
+                            Hostname = host.hostname().to_string(),
+                            RemotePort = host.port,
+                        );
+                    }
+
+                    in_flight
+                } else {
+                    None
+                };
+
                 // Validate MTA-STS
                 envelope.mx = remote_host.hostname();
                 if let Some(mta_sts_policy) = &mta_sts_policy {
@@ -1035,7 +1148,17 @@ impl QueuedMessage {
                     );
 
                     // Set source IP, if any
-                    let ip_host = conn_strategy.source_ip(remote_ip.is_ipv4());
+                    let ip_host = match server
+                        .select_source_ip(conn_strategy, remote_ip.is_ipv4(), message.span_id)
+                        .await
+                    {
+                        Ok(ip_host) => ip_host,
+                        Err(retry_at) => {
+                            delivery_results
+                                .push(DeliveryResult::rate_limited(rcpt_idxs, retry_at));
+                            continue 'next_route;
+                        }
+                    };
 
                     // Connect
                     let time = Instant::now();
@@ -1090,6 +1213,7 @@ impl QueuedMessage {
                     };
 
                     // Obtain session parameters
+                    let num_results = delivery_results.len();
                     let local_hostname = ip_host
                         .and_then(|ip| ip.host.as_deref())
                         .or(conn_strategy.ehlo_hostname.as_deref())
@@ -1101,8 +1225,13 @@ impl QueuedMessage {
                         is_smtp: remote_host.is_smtp(),
                         hostname: envelope.mx,
                         local_hostname,
+                        local_ip: envelope.local_ip,
                         conn_strategy,
                         capabilities: None,
+                        return_path: batv_return_path
+                            .as_deref()
+                            .filter(|_| remote_host.is_smtp())
+                            .unwrap_or(&message.message.return_path),
                     };
 
                     // Prepare TLS connector
@@ -1131,6 +1260,7 @@ impl QueuedMessage {
                                 Details = status.to_string(),
                             );
 
+                            remote_host.record_failure(message.span_id);
                             last_status = status;
                             continue 'next_host;
                         }
@@ -1160,6 +1290,9 @@ impl QueuedMessage {
                                     Elapsed = time.elapsed(),
                                 );
 
+                                if matches!(status, Status::PermanentFailure(_)) {
+                                    remote_host.record_failure(message.span_id);
+                                }
                                 last_status = status;
                                 continue 'next_host;
                             }
@@ -1407,6 +1540,7 @@ impl QueuedMessage {
                                 Details = from_error_status(&status),
                             );
 
+                            remote_host.record_failure(message.span_id);
                             last_status = status;
                             continue 'next_host;
                         }
@@ -1423,9 +1557,27 @@ impl QueuedMessage {
                             .await
                     }
 
+                    // Permanent errors count towards the failure threshold of relay hosts
+                    match delivery_results[num_results..].iter().find_map(|result| {
+                        if let DeliveryResult::Domain { status, .. } = result {
+                            Some(status)
+                        } else {
+                            None
+                        }
+                    }) {
+                        None => remote_host.record_success(message.span_id),
+                        Some(Status::PermanentFailure(_)) => {
+                            remote_host.record_failure(message.span_id)
+                        }
+                        Some(_) => (),
+                    }
+
                     // Continue with the next domain/route
                     continue 'next_route;
                 }
+
+                // A host that refuses connections on all its addresses fails once
+                remote_host.record_failure(message.span_id);
             }
 
             // Update status
@@ -1433,17 +1585,22 @@ impl QueuedMessage {
         }
 
         // Apply status changes
+        let mut updated_rcpts = Vec::new();
         for delivery_result in delivery_results {
             match delivery_result {
                 DeliveryResult::Domain { status, rcpt_idxs } => {
                     for rcpt_idx in rcpt_idxs {
+                        message.record_delivery_status(&status, rcpt_idx, &server);
                         message
                             .set_rcpt_status(status.clone(), rcpt_idx, &server)
                             .await;
+                        updated_rcpts.push(rcpt_idx);
                     }
                 }
                 DeliveryResult::Account { status, rcpt_idx } => {
+                    message.record_delivery_status(&status, rcpt_idx, &server);
                     message.set_rcpt_status(status, rcpt_idx, &server).await;
+                    updated_rcpts.push(rcpt_idx);
                 }
                 DeliveryResult::RateLimited {
                     rcpt_idxs,
@@ -1456,6 +1613,11 @@ impl QueuedMessage {
             }
         }
 
+        // Notify delivery callbacks
+        server
+            .send_delivery_callback(&message, &updated_rcpts)
+            .await;
+
         // Send Delivery Status Notifications
         server.send_dsn(&mut message).await;
 
@@ -1505,8 +1667,18 @@ impl MessageWrapper {
         let mut matches_queue = false;
 
         for rcpt in self.message.recipients.iter_mut() {
+            let expiry_reason = rcpt.expiry_reason(self.message.created, now);
+            if let Some(reason) = expiry_reason
+                && matches!(rcpt.status, Status::TemporaryFailure(_) | Status::Scheduled)
+            {
+                rcpt.flags |= match reason {
+                    ExpiryReason::Ttl => RCPT_EXPIRED_TTL,
+                    ExpiryReason::Attempts => RCPT_EXPIRED_ATTEMPTS,
+                };
+            }
+
             match &rcpt.status {
-                Status::TemporaryFailure(err) if rcpt.is_expired(self.message.created, now) => {
+                Status::TemporaryFailure(err) if expiry_reason.is_some() => {
                     trc::event!(
                         Delivery(DeliveryEvent::Failed),
                         SpanId = self.span_id,
@@ -1525,7 +1697,7 @@ impl MessageWrapper {
                     rcpt.status =
                         std::mem::replace(&mut rcpt.status, Status::Scheduled).into_permanent();
                 }
-                Status::Scheduled if rcpt.is_expired(self.message.created, now) => {
+                Status::Scheduled if expiry_reason.is_some() => {
                     trc::event!(
                         Delivery(DeliveryEvent::Failed),
                         SpanId = self.span_id,
@@ -1581,15 +1753,270 @@ impl MessageWrapper {
                     .unwrap_or_else(|| "default".to_string()),
                 self.span_id,
             );
+
+            // Count the message in the queue the recipient is moved to
+            if self.message.recipients[rcpt_idx].queue != queue.virtual_queue
+                && self
+                    .message
+                    .next_event(queue.virtual_queue.into())
+                    .is_none()
+            {
+                server.inner.data.queue_metrics.message_queued(
+                    queue.virtual_queue,
+                    self.message.size,
+                    self.message.created,
+                );
+            }
+
             let rcpt = &mut self.message.recipients[rcpt_idx];
-            rcpt.retry.due = now()
+            let retry_due = now()
                 + queue.retry[std::cmp::min(rcpt.retry.inner as usize, queue.retry.len() - 1)];
+            rcpt.retry.due = queue
+                .window
+                .as_ref()
+                .and_then(|window| window.next_start(retry_due))
+                .unwrap_or(retry_due);
             rcpt.retry.inner += 1;
             rcpt.expires = queue.expiry;
             rcpt.queue = queue.virtual_queue;
         }
     }
 
+    /// Moves the due time of recipients scheduled outside their delivery
+    /// window to the start of the next window, without counting it as an attempt.
+    /// Returns `true` when no recipients are left to be delivered now.
+    pub async fn defer_outside_window(&mut self, server: &Server) -> bool {
+        let now = now();
+        let mut deferred = Vec::new();
+        let mut has_due = false;
+
+        for (rcpt_idx, rcpt) in self.message.recipients.iter().enumerate() {
+            if matches!(
+                &rcpt.status,
+                Status::Scheduled | Status::TemporaryFailure(_)
+            ) && rcpt.retry.due <= now
+                && rcpt.queue == self.queue_name
+            {
+                let envelope = QueueEnvelope::new(&self.message, rcpt);
+                let queue = server.get_queue_or_default(
+                    &server
+                        .eval_if::<String, _>(
+                            &server.core.smtp.queue.queue,
+                            &envelope,
+                            self.span_id,
+                        )
+                        .await
+                        .unwrap_or_else(|| "default".to_string()),
+                    self.span_id,
+                );
+
+                if let Some(next_start) = queue
+                    .window
+                    .as_ref()
+                    .and_then(|window| window.next_start(now))
+                {
+                    deferred.push((rcpt_idx, next_start));
+                } else {
+                    has_due = true;
+                }
+            }
+        }
+
+        for (rcpt_idx, next_start) in deferred {
+            let rcpt = &mut self.message.recipients[rcpt_idx];
+            rcpt.retry.due = next_start;
+
+            trc::event!(
+                Queue(trc::QueueEvent::OutsideDeliveryWindow),
+                SpanId = self.span_id,
+                To = rcpt.address().to_string(),
+                NextRetry = trc::Value::Timestamp(next_start),
+            );
+        }
+
+        !has_due
+    }
+
+    /// Moves due recipients being retried into child messages when they no longer
+    /// resolve to the same route, or when the message also has recipients pending
+    /// in other virtual queues. Recipients sharing the first route stay in this
+    /// message, unless other virtual queues are involved. Moved recipients are
+    /// marked as split in this message and keep their schedule in the child.
+    pub async fn split_routes(&mut self, server: &Server) -> Vec<MessageWrapper> {
+        let now = now();
+        let mut routes: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut has_other_queues = false;
+        let mut is_retry = false;
+
+        for (rcpt_idx, rcpt) in self.message.recipients.iter().enumerate() {
+            if !matches!(
+                &rcpt.status,
+                Status::Scheduled | Status::TemporaryFailure(_)
+            ) {
+                continue;
+            } else if rcpt.queue != self.queue_name {
+                has_other_queues = true;
+                continue;
+            } else if rcpt.retry.due > now {
+                continue;
+            }
+
+            let envelope = QueueEnvelope::new(&self.message, rcpt);
+            let route = server
+                .eval_if::<String, _>(&server.core.smtp.queue.route, &envelope, self.span_id)
+                .await
+                .unwrap_or_else(|| "default".to_string());
+            is_retry |= rcpt.retry.inner > 0;
+            if let Some((_, rcpt_idxs)) = routes.iter_mut().find(|(name, _)| *name == route) {
+                rcpt_idxs.push(rcpt_idx);
+            } else {
+                routes.push((route, vec![rcpt_idx]));
+            }
+        }
+
+        if !is_retry || routes.is_empty() || (routes.len() == 1 && !has_other_queues) {
+            return vec![];
+        }
+
+        let split_from = self.split_from().unwrap_or(self.queue_id);
+        let mut children = Vec::with_capacity(routes.len());
+        let mut split_into = Vec::with_capacity(routes.len());
+        for (_, rcpt_idxs) in routes.into_iter().skip(usize::from(!has_other_queues)) {
+            let queue_id = server.inner.data.queue_id_gen.generate();
+
+            // Per-recipient headers and quotas are remapped to the position of the
+            // recipient in the child
+            let remap_quota_id = |id: u64| {
+                if id == 0 {
+                    Some(0)
+                } else if id >> 32 != 0 {
+                    let domain = self.message.recipients[((id >> 32) - 1) as usize].domain_part();
+                    rcpt_idxs
+                        .iter()
+                        .position(|idx| self.message.recipients[*idx].domain_part() == domain)
+                        .map(|pos| ((pos + 1) as u64) << 32)
+                } else {
+                    rcpt_idxs
+                        .iter()
+                        .position(|idx| (*idx + 1) as u64 == id)
+                        .map(|pos| (pos + 1) as u64)
+                }
+            };
+            let mut metadata = Vec::with_capacity(2);
+            for entry in self.message.metadata.iter() {
+                match entry {
+                    Metadata::Headers { value, id } if *id != u64::MAX => {
+                        if let Some(pos) = rcpt_idxs.iter().position(|idx| *idx as u64 == *id) {
+                            metadata.push(Metadata::Headers {
+                                value: value.clone(),
+                                id: pos as u64,
+                            });
+                        }
+                    }
+                    Metadata::Headers { .. }
+                    | Metadata::DeliveryCallback { .. }
+                    | Metadata::SpamScore { .. }
+                    | Metadata::SpamTags { .. }
+                    | Metadata::Custom { .. } => {
+                        metadata.push(entry.clone());
+                    }
+                    Metadata::QueueSize { key, id } => {
+                        if let Some(id) = remap_quota_id(*id) {
+                            metadata.push(Metadata::QueueSize {
+                                key: key.clone(),
+                                id,
+                            });
+                        }
+                    }
+                    Metadata::QueueCount { key, id } => {
+                        if let Some(id) = remap_quota_id(*id) {
+                            metadata.push(Metadata::QueueCount {
+                                key: key.clone(),
+                                id,
+                            });
+                        }
+                    }
+                    Metadata::SplitFrom { .. } | Metadata::SplitInto { .. } => {}
+                }
+            }
+            metadata.push(Metadata::SplitFrom { id: split_from });
+
+            let mut recipients = Vec::with_capacity(rcpt_idxs.len());
+            for rcpt_idx in rcpt_idxs {
+                let rcpt = &mut self.message.recipients[rcpt_idx];
+                recipients.push(rcpt.clone());
+                rcpt.flags |= RCPT_SPLIT | RCPT_DSN_SENT;
+                rcpt.status = Status::Completed(HostResponse {
+                    hostname: "localhost".into(),
+                    response: Response {
+                        code: 250,
+                        esc: [2, 0, 0],
+                        message: format!("Moved to queued message {queue_id:x}").into(),
+                    },
+                });
+            }
+
+            trc::event!(
+                Queue(trc::QueueEvent::MessageSplit),
+                SpanId = self.span_id,
+                QueueId = self.queue_id,
+                Id = queue_id,
+                To = recipients
+                    .iter()
+                    .map(|rcpt| trc::Value::String(rcpt.address().into()))
+                    .collect::<Vec<_>>(),
+            );
+
+            split_into.push(Metadata::SplitInto { id: queue_id });
+            children.push(MessageWrapper {
+                queue_id,
+                queue_name: self.queue_name,
+                is_multi_queue: false,
+                span_id: self.span_id,
+                message: Message {
+                    created: self.message.created,
+                    blob_hash: self.message.blob_hash.clone(),
+                    return_path: self.message.return_path.clone(),
+                    recipients,
+                    received_from_ip: self.message.received_from_ip,
+                    received_via_port: self.message.received_via_port,
+                    flags: self.message.flags,
+                    env_id: self.message.env_id.clone(),
+                    priority: self.message.priority,
+                    size: self.message.size,
+                    metadata: metadata.into_boxed_slice(),
+                },
+            });
+        }
+
+        // Link the children so that their recipients can be looked up from this message
+        self.message.metadata = std::mem::take(&mut self.message.metadata)
+            .into_iter()
+            .chain(split_into)
+            .collect();
+
+        children
+    }
+
+    fn record_delivery_status(
+        &self,
+        status: &Status<HostResponse<Box<str>>, ErrorDetails>,
+        rcpt_idx: usize,
+        server: &Server,
+    ) {
+        let outcome = match status {
+            Status::TemporaryFailure(_) => DeliveryOutcome::Deferral,
+            Status::PermanentFailure(_) => DeliveryOutcome::Bounce,
+            Status::Scheduled | Status::Completed(_) => return,
+        };
+
+        server.inner.data.queue_metrics.record_delivery(
+            self.message.recipients[rcpt_idx].domain_part(),
+            outcome,
+            server.core.metrics.queue_top_domains,
+        );
+    }
+
     pub fn set_rcpt_rate_limit(&mut self, rcpt_idx: usize, retry_at: u64) {
         let rcpt = &mut self.message.recipients[rcpt_idx];
         rcpt.retry.due = retry_at;
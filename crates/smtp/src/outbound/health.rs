@@ -0,0 +1,209 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{NextHop, client::SmtpClient, lookup::DnsLookup, session::SessionParams};
+use crate::queue::{Error, ErrorDetails, HostResponse, Status};
+use common::{
+    Server,
+    config::smtp::queue::{HostOrIp, RoutingStrategy},
+};
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::{Duration, Instant},
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use trc::DeliveryEvent;
+
+pub trait RelayHealthCheck: Sync + Send {
+    fn schedule_relay_probes(&self) -> (Vec<(String, usize)>, Option<Duration>);
+
+    fn probe_relay_hosts(&self, hosts: Vec<(String, usize)>) -> impl Future<Output = ()> + Send;
+
+    fn probe_relay_host(
+        &self,
+        remote_host: &NextHop<'_>,
+        session_id: u64,
+    ) -> impl Future<Output = Result<(), Status<HostResponse<Box<str>>, ErrorDetails>>> + Send;
+}
+
+impl RelayHealthCheck for Server {
+    // Returns the relay hosts due for a probe along with the time left until
+    // the next one, hosts shared by several routes are probed once
+    fn schedule_relay_probes(&self) -> (Vec<(String, usize)>, Option<Duration>) {
+        let mut due = Vec::new();
+        let mut next_probe: Option<Duration> = None;
+        for (name, route) in &self.core.smtp.queue.routing_strategy {
+            if let RoutingStrategy::Relay(config) = route
+                && let Some(interval) = config.health_check_interval
+            {
+                for (host_idx, host) in config.hosts.iter().enumerate() {
+                    if host.health.try_schedule_probe(interval) {
+                        due.push((name.clone(), host_idx));
+                    }
+                    let probe_in = host.health.next_probe_in();
+                    next_probe = Some(next_probe.map_or(probe_in, |next| next.min(probe_in)));
+                }
+            }
+        }
+
+        (due, next_probe)
+    }
+
+    async fn probe_relay_hosts(&self, hosts: Vec<(String, usize)>) {
+        let mut probes = Vec::with_capacity(hosts.len());
+        for (name, host_idx) in hosts {
+            let server = self.clone();
+            probes.push(tokio::spawn(async move {
+                let Some(RoutingStrategy::Relay(config)) =
+                    server.core.smtp.queue.routing_strategy.get(&name)
+                else {
+                    return;
+                };
+                let remote_host = NextHop::Relay {
+                    config,
+                    host: &config.hosts[host_idx],
+                };
+                let span_id = server.inner.data.span_id_gen.generate();
+                let time = Instant::now();
+
+                match server.probe_relay_host(&remote_host, span_id).await {
+                    Ok(()) => remote_host.record_success(span_id),
+                    Err(status) => {
+                        trc::event!(
+                            Delivery(DeliveryEvent::RelayHostProbeFailed),
+                            SpanId = span_id,
+                            Id = name,
+                            Hostname = remote_host.hostname().to_string(),
+                            RemotePort = remote_host.port(),
+                            Details = status.to_string(),
+                            Elapsed = time.elapsed(),
+                        );
+
+                        remote_host.record_failure(span_id);
+                    }
+                }
+            }));
+        }
+
+        for probe in probes {
+            let _ = probe.await;
+        }
+    }
+
+    async fn probe_relay_host(
+        &self,
+        remote_host: &NextHop<'_>,
+        session_id: u64,
+    ) -> Result<(), Status<HostResponse<Box<str>>, ErrorDetails>> {
+        let hostname = remote_host.hostname();
+        let remote_ip = match remote_host.fqdn_hostname() {
+            HostOrIp::Host(host) => self
+                .ip_lookup(host.as_ref(), remote_host.ip_lookup_strategy(), 1)
+                .await
+                .map_err(|err| Status::from_mail_auth_error(hostname, err))?
+                .into_iter()
+                .next()
+                .ok_or_else(|| {
+                    Status::TemporaryFailure(ErrorDetails {
+                        entity: hostname.into(),
+                        details: Error::DnsError(
+                            format!("No IP addresses found for {hostname:?}.").into_boxed_str(),
+                        ),
+                    })
+                })?,
+            HostOrIp::Ip(ip) => ip,
+        };
+
+        // Connect using the default connection strategy
+        let conn_strategy = self.get_connection_or_default("default", session_id);
+        let smtp_client = SmtpClient::connect(
+            SocketAddr::new(remote_ip, remote_host.port()),
+            conn_strategy.timeout_connect,
+            session_id,
+        )
+        .await
+        .map_err(|err| Status::from_smtp_error(hostname, "", err))?;
+        let params = SessionParams {
+            session_id,
+            server: self,
+            credentials: None,
+            is_smtp: remote_host.is_smtp(),
+            hostname,
+            local_hostname: conn_strategy
+                .ehlo_hostname
+                .as_deref()
+                .unwrap_or(self.core.network.server_name.as_str()),
+            local_ip: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            conn_strategy,
+            capabilities: None,
+            return_path: "",
+        };
+
+        if remote_host.implicit_tls() {
+            let tls_connector = if remote_host.allow_invalid_certs() {
+                &self.inner.data.smtp_connectors.dummy_verify
+            } else {
+                &self.inner.data.smtp_connectors.pki_verify
+            };
+            let smtp_client = smtp_client
+                .into_tls(tls_connector, hostname)
+                .await
+                .map_err(|err| Status::from_tls_error(hostname, err))?;
+            say_hello(smtp_client, &params).await
+        } else {
+            say_hello(smtp_client, &params).await
+        }
+    }
+}
+
+async fn say_hello<T: AsyncRead + AsyncWrite + Unpin>(
+    mut smtp_client: SmtpClient<T>,
+    params: &SessionParams<'_>,
+) -> Result<(), Status<HostResponse<Box<str>>, ErrorDetails>> {
+    smtp_client.timeout = params.conn_strategy.timeout_greeting;
+    smtp_client.read_greeting(params.hostname).await?;
+    let result = smtp_client.say_helo(params).await.map(|_| ());
+    smtp_client.quit().await;
+    result
+}
+
+impl NextHop<'_> {
+    // Counts a connection failure or a permanent error towards the failure
+    // threshold of a relay host, marking it as down once it is reached
+    pub fn record_failure(&self, session_id: u64) {
+        if let NextHop::Relay { config, host } = self
+            && host
+                .health
+                .record_failure(config.failure_threshold, config.failure_cool_off)
+        {
+            trc::event!(
+                Delivery(DeliveryEvent::RelayHostDown),
+                SpanId = session_id,
+                Hostname = host.hostname().to_string(),
+                RemotePort = host.port,
+                TotalFailures = host.health.failures(),
+                Expires = host
+                    .health
+                    .down_until()
+                    .map(|until| trc::Value::Timestamp(until / 1000)),
+            );
+        }
+    }
+
+    pub fn record_success(&self, session_id: u64) {
+        if let NextHop::Relay { host, .. } = self
+            && host.health.record_success()
+        {
+            trc::event!(
+                Delivery(DeliveryEvent::RelayHostUp),
+                SpanId = session_id,
+                Hostname = host.hostname().to_string(),
+                RemotePort = host.port,
+            );
+        }
+    }
+}
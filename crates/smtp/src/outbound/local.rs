@@ -9,7 +9,7 @@ use crate::{
     outbound::DeliveryResult,
     queue::{
         Error, ErrorDetails, FROM_AUTHENTICATED, FROM_UNAUTHENTICATED_DMARC, HostResponse,
-        MessageSource, MessageWrapper, RCPT_SPAM_PAYLOAD, Status, UnexpectedResponse,
+        MessageSource, MessageWrapper, Metadata, RCPT_SPAM_PAYLOAD, Status, UnexpectedResponse,
         quota::HasQueueQuota,
         spool::{QueueParams, SmtpSpool},
     },
@@ -20,7 +20,7 @@ use smtp_proto::Response;
 use trc::SieveEvent;
 
 impl MessageWrapper {
-    pub(super) async fn deliver_local(
+    pub(crate) async fn deliver_local(
         &self,
         rcpt_idxs: &[usize],
         statuses: &mut Vec<DeliveryResult>,
@@ -40,6 +40,19 @@ impl MessageWrapper {
             pending_recipients.push((rcpt_idx, rcpt_addr));
         }
 
+        // Only the tags added by the local spam filter are used for categorization
+        let spam_tags = self
+            .message
+            .metadata
+            .iter()
+            .find_map(|metadata| match metadata {
+                Metadata::SpamTags { tags } => {
+                    Some(tags.iter().map(|tag| tag.to_string()).collect())
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+
         // Deliver message
         let delivery_result = server
             .deliver_message(IngestMessage {
@@ -47,9 +60,11 @@ impl MessageWrapper {
                 sender_authenticated: self.message.flags
                     & (FROM_UNAUTHENTICATED_DMARC | FROM_AUTHENTICATED)
                     != 0,
+                remote_ip: Some(self.message.received_from_ip),
                 recipients,
                 message_blob: self.message.blob_hash.clone(),
                 message_size: self.message.size,
+                spam_tags,
                 session_id: self.span_id,
             })
             .await;
@@ -67,14 +82,15 @@ impl MessageWrapper {
                         message: "OK".into(),
                     },
                 }),
-                LocalDeliveryStatus::TemporaryFailure { reason } => {
+                LocalDeliveryStatus::TemporaryFailure { code, reason } => {
                     Status::TemporaryFailure(ErrorDetails {
                         entity: "localhost".into(),
                         details: Error::UnexpectedResponse(UnexpectedResponse {
                             command: format!("RCPT TO:<{rcpt_addr}>").into_boxed_str(),
                             response: Response {
-                                code: 451,
-                                esc: [4, 3, 0],
+                                // Storage errors use 452 as per RFC 5321
+                                code: if code[1] == 2 { 452 } else { 451 },
+                                esc: code,
                                 message: reason.into(),
                             },
                         }),
@@ -103,6 +119,12 @@ impl MessageWrapper {
             for rcpt in autogenerated.recipients {
                 message.expand_and_add_recipient(rcpt, server).await;
             }
+            if autogenerated.rcpt_flags != 0 {
+                for rcpt in &mut message.message.recipients {
+                    rcpt.flags |= autogenerated.rcpt_flags;
+                }
+            }
+            message.message.flags |= autogenerated.flags;
 
             // Queue Message
             message.message.size = autogenerated.message.len() as u64;
@@ -7,14 +7,23 @@
 use super::NextHop;
 use crate::queue::{Error, ErrorDetails, HostResponse, Status};
 use common::{
-    Server,
-    config::smtp::queue::{ConnectionStrategy, HostOrIp, IpAndHost, MxConfig},
+    KV_RATE_LIMIT_WARMUP, Server,
+    config::smtp::queue::{
+        ConnectionStrategy, HostOrIp, IpAndHost, IpSelection, MxConfig, RelayConfig,
+    },
     expr::functions::ResolveVariable,
 };
 use mail_auth::{IpLookupStrategy, MX, RecordSet};
 use rand::{Rng, seq::SliceRandom};
-use registry::schema::enums::ExpressionVariable;
-use std::{future::Future, net::IpAddr, sync::Arc};
+use registry::schema::{enums::ExpressionVariable, structs::Rate};
+use std::{
+    future::Future,
+    net::IpAddr,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
+use store::write::now;
+use trc::DeliveryEvent;
 
 pub trait DnsLookup: Sync + Send {
     fn ip_lookup(
@@ -178,20 +187,157 @@ impl DnsLookup for Server {
 
 pub trait SourceIp {
     fn source_ip(&self, is_v4: bool) -> Option<&IpAndHost>;
+    fn source_ips(&self, is_v4: bool) -> Vec<&IpAndHost>;
 }
 
 impl SourceIp for ConnectionStrategy {
     fn source_ip(&self, is_v4: bool) -> Option<&IpAndHost> {
+        self.source_ips(is_v4).into_iter().next()
+    }
+
+    fn source_ips(&self, is_v4: bool) -> Vec<&IpAndHost> {
         let ips = if is_v4 {
             &self.source_ipv4
         } else {
             &self.source_ipv6
         };
-        match ips.len().cmp(&1) {
-            std::cmp::Ordering::Equal => ips.first(),
-            std::cmp::Ordering::Greater => Some(&ips[rand::rng().random_range(0..ips.len())]),
-            std::cmp::Ordering::Less => None,
+        if ips.len() <= 1 {
+            return ips.iter().collect();
         }
+
+        match &self.source_ip_selection {
+            IpSelection::Weighted => {
+                // Weighted random order without replacement
+                let mut rng = rand::rng();
+                let mut ips = ips
+                    .iter()
+                    .map(|ip| (rng.random::<f64>().powf(1.0 / ip.weight.max(1) as f64), ip))
+                    .collect::<Vec<_>>();
+                ips.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+                ips.into_iter().map(|(_, ip)| ip).collect()
+            }
+            IpSelection::RoundRobin(next) => {
+                let start = next.fetch_add(1, Ordering::Relaxed) % ips.len();
+                ips[start..].iter().chain(ips[..start].iter()).collect()
+            }
+        }
+    }
+}
+
+pub trait RelayHosts {
+    fn relay_hosts(&self) -> Vec<NextHop<'_>>;
+}
+
+impl RelayHosts for RelayConfig {
+    fn relay_hosts(&self) -> Vec<NextHop<'_>> {
+        // Hosts marked as down are skipped until their cool-off period expires
+        let hosts = self
+            .hosts
+            .iter()
+            .filter(|host| host.health.is_available())
+            .collect::<Vec<_>>();
+        let hosts = if hosts.len() <= 1 {
+            hosts
+        } else {
+            match &self.host_selection {
+                IpSelection::Weighted => {
+                    // Weighted random order without replacement
+                    let mut rng = rand::rng();
+                    let mut hosts = hosts
+                        .into_iter()
+                        .map(|host| {
+                            (
+                                rng.random::<f64>().powf(1.0 / host.weight.max(1) as f64),
+                                host,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    hosts.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+                    hosts.into_iter().map(|(_, host)| host).collect()
+                }
+                IpSelection::RoundRobin(next) => {
+                    let start = next.fetch_add(1, Ordering::Relaxed) % hosts.len();
+                    hosts[start..]
+                        .iter()
+                        .chain(hosts[..start].iter())
+                        .copied()
+                        .collect()
+                }
+            }
+        };
+
+        hosts
+            .into_iter()
+            .map(|host| NextHop::Relay { config: self, host })
+            .collect()
+    }
+}
+
+pub trait SelectSourceIp: Sync + Send {
+    fn select_source_ip<'x>(
+        &self,
+        strategy: &'x ConnectionStrategy,
+        is_v4: bool,
+        session_id: u64,
+    ) -> impl Future<Output = Result<Option<&'x IpAndHost>, u64>> + Send;
+}
+
+impl SelectSourceIp for Server {
+    async fn select_source_ip<'x>(
+        &self,
+        strategy: &'x ConnectionStrategy,
+        is_v4: bool,
+        session_id: u64,
+    ) -> Result<Option<&'x IpAndHost>, u64> {
+        let ips = strategy.source_ips(is_v4);
+        if ips.is_empty() {
+            return Ok(None);
+        }
+
+        // Skip addresses that exhausted their warmup quota for the day
+        let now = now();
+        let mut retry_at = u64::MAX;
+        for ip_host in ips {
+            let Some(limit) = ip_host
+                .warmup
+                .as_ref()
+                .and_then(|warmup| warmup.daily_limit(now))
+            else {
+                return Ok(Some(ip_host));
+            };
+
+            match self
+                .in_memory_store()
+                .is_rate_allowed(
+                    KV_RATE_LIMIT_WARMUP,
+                    ip_host.ip.to_string().as_bytes(),
+                    &Rate {
+                        count: limit,
+                        period: Duration::from_secs(86400).into(),
+                    },
+                    false,
+                )
+                .await
+            {
+                Ok(None) => return Ok(Some(ip_host)),
+                Ok(Some(next_refill)) => {
+                    trc::event!(
+                        Delivery(DeliveryEvent::WarmupLimitExceeded),
+                        SpanId = session_id,
+                        LocalIp = ip_host.ip,
+                        Limit = limit,
+                    );
+
+                    retry_at = retry_at.min(now + next_refill);
+                }
+                Err(err) => {
+                    trc::error!(err.span_id(session_id).caused_by(trc::location!()));
+                    return Ok(Some(ip_host));
+                }
+            }
+        }
+
+        Err(retry_at)
     }
 }
 
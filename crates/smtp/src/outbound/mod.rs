@@ -10,7 +10,7 @@ use crate::{
 };
 use common::config::{
     server::ServerProtocol,
-    smtp::queue::{HostOrIp, MxConfig, RelayConfig},
+    smtp::queue::{HostOrIp, MxConfig, RelayConfig, RelayHost},
 };
 use directory::Credentials;
 use mail_auth::{DnssecStatus, IpLookupStrategy};
@@ -21,12 +21,13 @@ pub mod client;
 pub mod dane;
 pub mod delivery;
 pub mod error;
+pub mod health;
 pub mod local;
 pub mod lookup;
 pub mod mta_sts;
 pub mod session;
 
-pub(super) enum DeliveryResult {
+pub(crate) enum DeliveryResult {
     Domain {
         status: Status<HostResponse<Box<str>>, ErrorDetails>,
         rcpt_idxs: Vec<usize>,
@@ -234,7 +235,10 @@ impl Status<HostResponse<Box<str>>, ErrorDetails> {
 
 #[derive(Debug)]
 pub enum NextHop<'x> {
-    Relay(&'x RelayConfig),
+    Relay {
+        config: &'x RelayConfig,
+        host: &'x RelayHost,
+    },
     MX {
         is_implicit: bool,
         host: &'x str,
@@ -254,10 +258,7 @@ impl NextHop<'_> {
                     host
                 }
             }
-            NextHop::Relay(host) => match &host.address {
-                HostOrIp::Host(host) => host.as_ref(),
-                HostOrIp::Ip(ip) => ip.ip_str.as_ref(),
-            },
+            NextHop::Relay { host, .. } => host.hostname(),
         }
     }
 
@@ -271,7 +272,7 @@ impl NextHop<'_> {
                     HostOrIp::Host((*host).into())
                 }
             }
-            NextHop::Relay(host) => match &host.address {
+            NextHop::Relay { host, .. } => match &host.address {
                 HostOrIp::Host(host) => HostOrIp::Host(host.as_ref().into()),
                 HostOrIp::Ip(ip) => HostOrIp::Ip(ip.ip),
             },
@@ -282,7 +283,7 @@ impl NextHop<'_> {
     pub fn max_multi_homed(&self) -> usize {
         match self {
             NextHop::MX { config, .. } => config.max_multi_homed,
-            NextHop::Relay(_) => 10,
+            NextHop::Relay { .. } => 10,
         }
     }
 
@@ -290,7 +291,7 @@ impl NextHop<'_> {
     pub fn ip_lookup_strategy(&self) -> IpLookupStrategy {
         match self {
             NextHop::MX { config, .. } => config.ip_lookup_strategy,
-            NextHop::Relay(_) => IpLookupStrategy::Ipv4thenIpv6,
+            NextHop::Relay { .. } => IpLookupStrategy::Ipv4thenIpv6,
         }
     }
 
@@ -301,7 +302,7 @@ impl NextHop<'_> {
             NextHop::MX { .. } => 9925,
             #[cfg(not(feature = "test_mode"))]
             NextHop::MX { .. } => 25,
-            NextHop::Relay(host) => host.port,
+            NextHop::Relay { host, .. } => host.port,
         }
     }
 
@@ -309,7 +310,7 @@ impl NextHop<'_> {
     fn credentials(&self) -> Option<&Credentials> {
         match self {
             NextHop::MX { .. } => None,
-            NextHop::Relay(host) => host.auth.as_ref(),
+            NextHop::Relay { config, .. } => config.auth.as_ref(),
         }
     }
 
@@ -322,7 +323,7 @@ impl NextHop<'_> {
         #[cfg(not(feature = "test_mode"))]
         match self {
             NextHop::MX { .. } => false,
-            NextHop::Relay(host) => host.tls_allow_invalid_certs,
+            NextHop::Relay { config, .. } => config.tls_allow_invalid_certs,
         }
     }
 
@@ -330,7 +331,7 @@ impl NextHop<'_> {
     fn implicit_tls(&self) -> bool {
         match self {
             NextHop::MX { .. } => false,
-            NextHop::Relay(host) => host.tls_implicit,
+            NextHop::Relay { config, .. } => config.tls_implicit,
         }
     }
 
@@ -338,14 +339,14 @@ impl NextHop<'_> {
     fn is_smtp(&self) -> bool {
         match self {
             NextHop::MX { .. } => true,
-            NextHop::Relay(host) => host.protocol == ServerProtocol::Smtp,
+            NextHop::Relay { config, .. } => config.protocol == ServerProtocol::Smtp,
         }
     }
 
     fn dnssec_status(&self) -> DnssecStatus {
         match self {
             NextHop::MX { dnssec_status, .. } => *dnssec_status,
-            NextHop::Relay(_) => DnssecStatus::Indeterminate,
+            NextHop::Relay { .. } => DnssecStatus::Indeterminate,
         }
     }
 }
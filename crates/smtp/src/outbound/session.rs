@@ -18,7 +18,7 @@ use smtp_proto::{
     MAIL_RET_FULL, MAIL_RET_HDRS, MAIL_SMTPUTF8, RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE,
     RCPT_NOTIFY_NEVER, RCPT_NOTIFY_SUCCESS, Severity,
 };
-use std::{fmt::Write, time::Instant};
+use std::{fmt::Write, net::IpAddr, time::Instant};
 use tokio::io::{AsyncRead, AsyncWrite};
 use trc::DeliveryEvent;
 
@@ -29,8 +29,10 @@ pub struct SessionParams<'x> {
     pub capabilities: Option<EhloResponse<String>>,
     pub is_smtp: bool,
     pub local_hostname: &'x str,
+    pub local_ip: IpAddr,
     pub conn_strategy: &'x ConnectionStrategy,
     pub session_id: u64,
+    pub return_path: &'x str,
 }
 
 impl MessageWrapper {
@@ -122,7 +124,7 @@ impl MessageWrapper {
         // MAIL FROM
         let time = Instant::now();
         smtp_client.timeout = params.conn_strategy.timeout_mail;
-        let cmd = self.build_mail_from(&capabilities);
+        let cmd = self.build_mail_from(params.return_path, &capabilities);
         match smtp_client.cmd(cmd.as_bytes()).await.and_then(|r| {
             if r.is_positive_completion() {
                 Ok(r)
@@ -135,7 +137,7 @@ impl MessageWrapper {
                     Delivery(DeliveryEvent::MailFrom),
                     SpanId = params.session_id,
                     Hostname = params.hostname.to_string(),
-                    From = self.message.return_path.to_string(),
+                    From = params.return_path.to_string(),
                     Code = response.code,
                     Details = response.message.to_string(),
                     Elapsed = time.elapsed(),
@@ -280,6 +282,7 @@ impl MessageWrapper {
                                     Delivery(DeliveryEvent::Delivered),
                                     SpanId = params.session_id,
                                     Hostname = params.hostname.to_string(),
+                                    LocalIp = params.local_ip,
                                     To = rcpt.address().to_string(),
                                     Code = response.code,
                                     Details = response.message.to_string(),
@@ -341,6 +344,7 @@ impl MessageWrapper {
                                             Delivery(DeliveryEvent::Delivered),
                                             SpanId = params.session_id,
                                             Hostname = params.hostname.to_string(),
+                                            LocalIp = params.local_ip,
                                             To = rcpt.address().to_string(),
                                             Code = response.code,
                                             Details = response.message.to_string(),
@@ -406,9 +410,9 @@ impl MessageWrapper {
         smtp_client.quit().await;
     }
 
-    fn build_mail_from(&self, capabilities: &EhloResponse<String>) -> String {
-        let mut mail_from = String::with_capacity(self.message.return_path.len() + 60);
-        let _ = write!(mail_from, "MAIL FROM:<{}>", self.message.return_path);
+    fn build_mail_from(&self, return_path: &str, capabilities: &EhloResponse<String>) -> String {
+        let mut mail_from = String::with_capacity(return_path.len() + 60);
+        let _ = write!(mail_from, "MAIL FROM:<{return_path}>");
         if capabilities.has_capability(EXT_SIZE) {
             let _ = write!(mail_from, " SIZE={}", self.message.size);
         }
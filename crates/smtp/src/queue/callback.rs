@@ -0,0 +1,238 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{MessageWrapper, Metadata, QueueId, Status};
+use common::{Server, config::telemetry::WebhookTracer, telemetry::webhooks::post_webhook};
+use registry::schema::structs::{DeliveryCallback, Task, TaskDeliveryCallback, TaskStatus};
+use reqwest::header::{CONTENT_TYPE, HeaderMap, HeaderValue};
+use serde::Serialize;
+use std::{future::Future, time::Duration};
+use store::write::{BatchBuilder, now};
+use trc::{AddContext, TelemetryEvent};
+use types::id::Id;
+use utils::{http::is_internal_url, map::vec_map::VecMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DeliveryStatus {
+    #[serde(rename = "delivered")]
+    Delivered,
+    #[serde(rename = "deferred")]
+    Deferred,
+    #[serde(rename = "bounced")]
+    Bounced,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryStatusEvent {
+    #[serde(rename = "queueId")]
+    pub queue_id: String,
+    #[serde(rename = "recipient")]
+    pub recipient: String,
+    #[serde(rename = "status")]
+    pub status: DeliveryStatus,
+    #[serde(rename = "remoteHost")]
+    pub remote_host: String,
+    #[serde(rename = "response")]
+    pub response: String,
+    #[serde(rename = "timestamp")]
+    pub timestamp: u64,
+    #[serde(rename = "metadata", skip_serializing_if = "VecMap::is_empty")]
+    pub metadata: VecMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct EventWrapper<'x> {
+    events: &'x [DeliveryStatusEvent],
+}
+
+pub enum DeliveryCallbackResult {
+    Delivered,
+    NotFound,
+    Rejected(String),
+    Failed {
+        reason: String,
+        throttle: Duration,
+        discard_after: Duration,
+    },
+}
+
+pub trait SendDeliveryCallback: Sync + Send {
+    fn send_delivery_callback(
+        &self,
+        message: &MessageWrapper,
+        rcpt_idxs: &[usize],
+    ) -> impl Future<Output = ()> + Send;
+
+    fn post_delivery_callback(
+        &self,
+        callback_id: u64,
+        body: String,
+    ) -> impl Future<Output = trc::Result<DeliveryCallbackResult>> + Send;
+}
+
+impl SendDeliveryCallback for Server {
+    async fn send_delivery_callback(&self, message: &MessageWrapper, rcpt_idxs: &[usize]) {
+        let Some(callback_id) = message.delivery_callback() else {
+            return;
+        };
+        let events = rcpt_idxs
+            .iter()
+            .filter_map(|rcpt_idx| message.delivery_status_event(*rcpt_idx))
+            .collect::<Vec<_>>();
+        if events.is_empty() {
+            return;
+        }
+
+        let body = match serde_json::to_string(&EventWrapper { events: &events }) {
+            Ok(body) => body,
+            Err(err) => {
+                trc::event!(
+                    Telemetry(TelemetryEvent::WebhookError),
+                    SpanId = message.span_id,
+                    Id = callback_id,
+                    Details = format!("Failed to serialize events: {err}"),
+                );
+                return;
+            }
+        };
+
+        // Events are posted by the task manager so failed requests survive restarts
+        let mut batch = BatchBuilder::new();
+        batch.schedule_task(Task::DeliveryCallback(TaskDeliveryCallback {
+            callback_id: Id::new(callback_id),
+            body,
+            status: TaskStatus::now(),
+        }));
+        match self.store().write(batch.build_all()).await {
+            Ok(_) => self.notify_task_queue(),
+            Err(err) => {
+                trc::error!(
+                    err.span_id(message.span_id)
+                        .id(callback_id)
+                        .details("Failed to schedule delivery callback")
+                        .caused_by(trc::location!())
+                );
+            }
+        }
+    }
+
+    async fn post_delivery_callback(
+        &self,
+        callback_id: u64,
+        body: String,
+    ) -> trc::Result<DeliveryCallbackResult> {
+        let Some(callback) = self
+            .registry()
+            .object::<DeliveryCallback>(Id::new(callback_id))
+            .await
+            .caused_by(trc::location!())?
+        else {
+            return Ok(DeliveryCallbackResult::NotFound);
+        };
+
+        // The host is resolved again in case it was changed to point to an internal address
+        match is_internal_url(&callback.url).await {
+            Ok(false) => {}
+            Ok(true) => {
+                return Ok(DeliveryCallbackResult::Rejected(
+                    "Callback URL resolves to an internal address".to_string(),
+                ));
+            }
+            Err(reason) => {
+                return Ok(DeliveryCallbackResult::Failed {
+                    reason,
+                    throttle: callback.throttle.into_inner(),
+                    discard_after: callback.discard_after.into_inner(),
+                });
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        let settings = WebhookTracer {
+            key: callback
+                .signature_key
+                .secret()
+                .await
+                .unwrap_or_default()
+                .unwrap_or_default()
+                .into_owned(),
+            url: callback.url,
+            timeout: callback.timeout.into_inner(),
+            throttle: callback.throttle.into_inner(),
+            discard_after: callback.discard_after.into_inner(),
+            tls_allow_invalid_certs: callback.allow_invalid_certs,
+            headers,
+        };
+
+        match post_webhook(&settings, body).await {
+            Ok(_) => Ok(DeliveryCallbackResult::Delivered),
+            Err(reason) => Ok(DeliveryCallbackResult::Failed {
+                reason,
+                throttle: settings.throttle,
+                discard_after: settings.discard_after,
+            }),
+        }
+    }
+}
+
+impl MessageWrapper {
+    pub fn delivery_callback(&self) -> Option<u64> {
+        self.message
+            .metadata
+            .iter()
+            .find_map(|metadata| match metadata {
+                Metadata::DeliveryCallback { id } => Some(*id),
+                _ => None,
+            })
+    }
+
+    pub fn split_from(&self) -> Option<QueueId> {
+        self.message
+            .metadata
+            .iter()
+            .find_map(|metadata| match metadata {
+                Metadata::SplitFrom { id } => Some(*id),
+                _ => None,
+            })
+    }
+
+    pub fn delivery_status_event(&self, rcpt_idx: usize) -> Option<DeliveryStatusEvent> {
+        let rcpt = self.message.recipients.get(rcpt_idx)?;
+        let (status, remote_host, response) = match &rcpt.status {
+            Status::Completed(response) => (
+                DeliveryStatus::Delivered,
+                response.hostname.to_string(),
+                response.response.to_string(),
+            ),
+            Status::TemporaryFailure(err) => (
+                DeliveryStatus::Deferred,
+                err.entity.to_string(),
+                err.details.to_string(),
+            ),
+            Status::PermanentFailure(err) => (
+                DeliveryStatus::Bounced,
+                err.entity.to_string(),
+                err.details.to_string(),
+            ),
+            Status::Scheduled => return None,
+        };
+
+        Some(DeliveryStatusEvent {
+            queue_id: format!("{:x}", self.split_from().unwrap_or(self.queue_id)),
+            recipient: rcpt.address().to_string(),
+            status,
+            remote_host,
+            response,
+            timestamp: now(),
+            metadata: self
+                .message
+                .queue_meta()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        })
+    }
+}
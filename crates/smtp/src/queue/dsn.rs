@@ -6,24 +6,32 @@
 
 use super::spool::SmtpSpool;
 use super::{
-    Error, ErrorDetails, HostResponse, Message, MessageSource, QueueEnvelope, RCPT_DSN_SENT,
+    Error, ErrorDetails, FROM_UNAUTHENTICATED, FROM_UNAUTHENTICATED_DMARC, HostResponse, Message,
+    MessageSource, Metadata, QueueEnvelope, RCPT_DSN_SENT, RCPT_EXPIRED_ATTEMPTS, RCPT_EXPIRED_TTL,
     Recipient, Status,
 };
 use crate::inbound::dkim::DkimSign;
+use crate::outbound::dane::dnssec::TlsaLookup;
 use crate::queue::spool::QueueParams;
 use crate::queue::{MessageWrapper, UnexpectedResponse};
-use common::Server;
+use common::{
+    KV_RATE_LIMIT_DSN, Server,
+    config::templates::{MessageTemplateVariable, format_size},
+};
 use mail_builder::MessageBuilder;
 use mail_builder::headers::HeaderType;
 use mail_builder::headers::content_type::ContentType;
 use mail_builder::mime::{BodyPart, MimePart, make_boundary};
 use mail_parser::DateTime;
+use registry::schema::enums::MessageTemplateId;
 use smtp_proto::{
     RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_NEVER, RCPT_NOTIFY_SUCCESS, Response,
 };
+use spam_filter::modules::reputation::{ReputationEvent, SenderReputationStore};
 use std::fmt::Write;
 use std::future::Future;
 use store::write::now;
+use utils::{DomainPart, template::Variables};
 
 pub trait SendDsn: Sync + Send {
     fn send_dsn(&self, message: &mut MessageWrapper) -> impl Future<Output = ()> + Send;
@@ -35,28 +43,68 @@ impl SendDsn for Server {
         // Send DSN events
         self.log_dsn(message).await;
 
+        // Bounced messages count against the reputation of remote senders
+        if self.core.spam.reputation.is_some()
+            && message.message.flags & (FROM_UNAUTHENTICATED | FROM_UNAUTHENTICATED_DMARC) != 0
+            && message.message.recipients.iter().any(|rcpt| {
+                !rcpt.has_flag(RCPT_DSN_SENT) && matches!(rcpt.status, Status::PermanentFailure(_))
+            })
+        {
+            // Bounces are only attributed to sender domains that passed DMARC
+            let domain = (message.message.flags & FROM_UNAUTHENTICATED_DMARC != 0)
+                .then(|| message.message.return_path.domain_part());
+            if let Err(err) = self
+                .sender_reputation_record(
+                    domain,
+                    message.message.received_from_ip,
+                    ReputationEvent::Bounce,
+                )
+                .await
+            {
+                trc::error!(
+                    err.span_id(message.span_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to update sender reputation.")
+                );
+            }
+        }
+
         if !message.message.return_path.is_empty() {
             // Build DSN
+            let has_failures = message.message.recipients.iter().any(|rcpt| {
+                !rcpt.has_flag(RCPT_DSN_SENT | RCPT_NOTIFY_NEVER)
+                    && rcpt.has_flag(RCPT_NOTIFY_FAILURE)
+                    && matches!(rcpt.status, Status::PermanentFailure(_))
+            });
             if let Some(dsn) = message.build_dsn(self).await {
-                let mut dsn_message = self.new_message("", message.span_id);
-                dsn_message
-                    .expand_and_add_recipient(message.message.return_path.as_ref(), self)
-                    .await;
-
-                // Queue DSN
-                let dkim_signers = self
-                    .eval_signers(
-                        &self.core.smtp.queue.dsn.sign,
-                        &message.message,
-                        message.span_id,
-                    )
-                    .await;
-                dsn_message
-                    .queue(
-                        QueueParams::new(&dsn, message.span_id, self, MessageSource::Dsn)
-                            .with_dkim_signers(dkim_signers),
-                    )
-                    .await;
+                if let Some(reason) = message.dsn_suppression(self, has_failures).await {
+                    trc::event!(
+                        Delivery(trc::DeliveryEvent::DsnSuppressed),
+                        SpanId = message.span_id,
+                        To = message.message.return_path.to_string(),
+                        Reason = reason,
+                    );
+                } else {
+                    let mut dsn_message = self.new_message("", message.span_id);
+                    dsn_message
+                        .expand_and_add_recipient(message.message.return_path.as_ref(), self)
+                        .await;
+
+                    // Queue DSN
+                    let dkim_signers = self
+                        .eval_signers(
+                            &self.core.smtp.queue.dsn.sign,
+                            &message.message,
+                            message.span_id,
+                        )
+                        .await;
+                    dsn_message
+                        .queue(
+                            QueueParams::new(&dsn, message.span_id, self, MessageSource::Dsn)
+                                .with_dkim_signers(dkim_signers),
+                        )
+                        .await;
+                }
             }
         } else {
             // Handle double bounce
@@ -84,6 +132,7 @@ impl SendDsn for Server {
                         Hostname = response.hostname.clone(),
                         Code = response.response.code,
                         Details = response.response.message.to_string(),
+                        Metadata = message.message.queue_meta_trace(),
                     );
                 }
                 Status::TemporaryFailure(response) if rcpt.notify.due <= now => {
@@ -98,6 +147,7 @@ impl SendDsn for Server {
                             .expiration_time(message.message.created)
                             .map(trc::Value::Timestamp),
                         Total = rcpt.retry.inner,
+                        Metadata = message.message.queue_meta_trace(),
                     );
                 }
                 Status::PermanentFailure(response) => {
@@ -108,6 +158,7 @@ impl SendDsn for Server {
                         Hostname = response.entity.clone(),
                         Details = response.details.to_string(),
                         Total = rcpt.retry.inner,
+                        Metadata = message.message.queue_meta_trace(),
                     );
                 }
                 Status::Scheduled if rcpt.notify.due <= now => {
@@ -121,6 +172,7 @@ impl SendDsn for Server {
                             .expiration_time(message.message.created)
                             .map(trc::Value::Timestamp),
                         Total = rcpt.retry.inner,
+                        Metadata = message.message.queue_meta_trace(),
                     );
                 }
                 _ => continue,
@@ -132,6 +184,88 @@ impl SendDsn for Server {
 const MAX_HEADER_SIZE: usize = 4096;
 
 impl MessageWrapper {
+    pub fn spam_score(&self) -> Option<f32> {
+        self.message
+            .metadata
+            .iter()
+            .find_map(|metadata| match metadata {
+                Metadata::SpamScore { score } => Some(*score as f32 / 100.0),
+                _ => None,
+            })
+    }
+
+    // Returns why a DSN should not be sent to the return path. Messages with
+    // forged return paths would otherwise reflect DSNs to third parties.
+    // Spam is only checked for failure DSNs, success and delay notifications
+    // were requested by the sender and are still delivered.
+    async fn dsn_suppression(&self, server: &Server, has_failures: bool) -> Option<&'static str> {
+        let config = &server.core.smtp.queue.dsn;
+        let return_path = self.message.return_path.to_lowercase();
+        let domain = return_path.domain_part();
+
+        if has_failures
+            && let Some(threshold) = config.spam_threshold
+            && self.spam_score().is_some_and(|score| score >= threshold)
+        {
+            return Some("Message was classified as spam");
+        }
+
+        if let Some(list) = &config.suppression_list
+            && let Some(store) = server.get_lookup_store(list)
+        {
+            for key in [return_path.as_str(), domain] {
+                match store.key_exists(key).await {
+                    Ok(true) => return Some("Return path is on the suppression list"),
+                    Ok(false) => {}
+                    Err(err) => {
+                        trc::error!(
+                            err.span_id(self.span_id)
+                                .caused_by(trc::location!())
+                                .details("Failed to query DSN suppression list.")
+                        );
+                    }
+                }
+            }
+        }
+
+        // Local domains are delivered without an MX lookup
+        if config.suppress_no_mx && !matches!(server.domain(domain).await, Ok(Some(_))) {
+            match server.mx_lookup(domain).await {
+                Ok(mx_list) => match mx_list.rrset.first().and_then(|mx| mx.exchanges.first()) {
+                    Some(host) if host.as_ref() == "." => {
+                        return Some("Return path domain does not accept mail (null MX)");
+                    }
+                    Some(_) => {}
+                    None => return Some("Return path domain has no MX records"),
+                },
+                Err(mail_auth::Error::Dns(mail_auth::DnsError::RecordNotFound(_))) => {
+                    return Some("Return path domain has no MX records");
+                }
+                Err(_) => {}
+            }
+        }
+
+        if let Some(rate) = &config.rate {
+            match server
+                .in_memory_store()
+                .is_rate_allowed(KV_RATE_LIMIT_DSN, domain.as_bytes(), rate, false)
+                .await
+            {
+                Ok(None) => {}
+                Ok(Some(_)) => return Some("DSN rate limit exceeded for the return path domain"),
+                Err(err) => {
+                    trc::error!(
+                        err.span_id(self.span_id)
+                            .caused_by(trc::location!())
+                            .details("Failed to check DSN rate limit.")
+                    );
+                }
+            }
+        }
+
+        None
+    }
+
     pub async fn build_dsn(&mut self, server: &Server) -> Option<Vec<u8>> {
         let config = &server.core.smtp.queue;
         let now = now();
@@ -171,6 +305,7 @@ impl MessageWrapper {
                     rcpt.write_dsn(&mut dsn);
                     rcpt.status.write_dsn(&mut dsn);
                     response.write_dsn_text(&rcpt.address, &mut txt_failed);
+                    rcpt.write_dsn_expiry_text(&mut txt_failed);
                 }
                 Status::Scheduled if rcpt.notify.due <= now && rcpt.has_flag(RCPT_NOTIFY_DELAY) => {
                     // This case should not happen under normal circumstances
@@ -198,58 +333,66 @@ impl MessageWrapper {
         let has_delay = !txt_delay.is_empty();
         let has_failure = !txt_failed.is_empty();
 
-        let mut txt = String::with_capacity(txt_len + 128);
-        let (subject, is_mixed) = if has_success && !has_delay && !has_failure {
-            txt.push_str(
+        let (intro, subject, is_mixed) = if has_success && !has_delay && !has_failure {
+            (
                 "Your message has been successfully delivered to the following recipients:\r\n\r\n",
-            );
-            ("Successfully delivered message", false)
+                "Successfully delivered message",
+                false,
+            )
         } else if has_delay && !has_success && !has_failure {
-            txt.push_str("There was a temporary problem delivering your message to the following recipients:\r\n\r\n");
-            ("Warning: Delay in message delivery", false)
+            (
+                "There was a temporary problem delivering your message to the following recipients:\r\n\r\n",
+                "Warning: Delay in message delivery",
+                false,
+            )
         } else if has_failure && !has_success && !has_delay {
-            txt.push_str(
+            (
                 "Your message could not be delivered to the following recipients:\r\n\r\n",
-            );
-            ("Failed to deliver message", false)
+                "Failed to deliver message",
+                false,
+            )
         } else if has_success {
-            txt.push_str("Your message has been partially delivered:\r\n\r\n");
-            ("Partially delivered message", true)
+            (
+                "Your message has been partially delivered:\r\n\r\n",
+                "Partially delivered message",
+                true,
+            )
         } else {
-            txt.push_str("Your message could not be delivered to some recipients:\r\n\r\n");
             (
+                "Your message could not be delivered to some recipients:\r\n\r\n",
                 "Warning: Temporary and permanent failures during message delivery",
                 true,
             )
         };
 
+        let mut report = String::with_capacity(txt_len + 128);
         if has_success {
             if is_mixed {
-                txt.push_str(
+                report.push_str(
                     "    ----- Delivery to the following addresses was successful -----\r\n",
                 );
             }
 
-            txt.push_str(&txt_success);
-            txt.push_str("\r\n");
+            report.push_str(&txt_success);
+            report.push_str("\r\n");
         }
 
         if has_delay {
             if is_mixed {
-                txt.push_str(
+                report.push_str(
                     "    ----- There was a temporary problem delivering to these addresses -----\r\n",
                 );
             }
-            txt.push_str(&txt_delay);
-            txt.push_str("\r\n");
+            report.push_str(&txt_delay);
+            report.push_str("\r\n");
         }
 
         if has_failure {
             if is_mixed {
-                txt.push_str("    ----- Delivery to the following addresses failed -----\r\n");
+                report.push_str("    ----- Delivery to the following addresses failed -----\r\n");
             }
-            txt.push_str(&txt_failed);
-            txt.push_str("\r\n");
+            report.push_str(&txt_failed);
+            report.push_str("\r\n");
         }
 
         // Obtain hostname and sender addresses
@@ -270,6 +413,70 @@ impl MessageWrapper {
             .await
             .unwrap_or_else(|| String::from("localhost"));
 
+        // Render the template of the sender's domain, tenant and locale, the most
+        // severe status selects the template for mixed notifications
+        let template_id = if has_failure {
+            MessageTemplateId::DsnFailure
+        } else if has_delay {
+            MessageTemplateId::DsnDelay
+        } else {
+            MessageTemplateId::DsnSuccess
+        };
+        let scope = server
+            .recipient_template_scope(self.message.return_path.as_ref())
+            .await;
+        let rendered = server
+            .core
+            .templates
+            .get(template_id, &scope)
+            .map(|template| {
+                let mut variables = Variables::new();
+                variables.insert_single(
+                    MessageTemplateVariable::Recipient,
+                    self.message.return_path.to_string(),
+                );
+                variables
+                    .insert_single(MessageTemplateVariable::QueueId, self.queue_id.to_string());
+                variables.insert_single(
+                    MessageTemplateVariable::Size,
+                    format_size(self.message.size),
+                );
+                variables.insert_single(MessageTemplateVariable::Hostname, reporting_mta.clone());
+                variables.insert_single(MessageTemplateVariable::Reason, report.clone());
+                template.render(&variables)
+            });
+        let (subject, text_part) = if let Some(rendered) = rendered {
+            let text_part = MimePart::new(
+                ContentType::new("text/plain"),
+                BodyPart::Text(rendered.text_body.into()),
+            );
+            (
+                rendered.subject,
+                if let Some(html_body) = rendered.html_body {
+                    MimePart::new(
+                        ContentType::new("multipart/alternative"),
+                        BodyPart::Multipart(vec![
+                            text_part,
+                            MimePart::new(
+                                ContentType::new("text/html"),
+                                BodyPart::Text(html_body.into()),
+                            ),
+                        ]),
+                    )
+                } else {
+                    text_part
+                },
+            )
+        } else {
+            (
+                subject.to_string(),
+                MimePart::new(
+                    ContentType::new("text/plain"),
+                    BodyPart::Text(format!("{intro}{report}").into()),
+                ),
+            )
+        };
+
         // Prepare DSN
         let mut dsn_header = String::with_capacity(dsn.len() + 128);
         self.message
@@ -341,7 +548,7 @@ impl MessageWrapper {
             .body(MimePart::new(
                 ContentType::new("multipart/report").attribute("report-type", "delivery-status"),
                 BodyPart::Multipart(vec![
-                    MimePart::new(ContentType::new("text/plain"), BodyPart::Text(txt.into())),
+                    text_part,
                     MimePart::new(
                         ContentType::new("message/delivery-status"),
                         BodyPart::Text(dsn.into()),
@@ -528,6 +735,18 @@ impl Recipient {
         let _ = write!(dsn, "Final-Recipient: rfc822;{}\r\n", self.address);
     }
 
+    fn write_dsn_expiry_text(&self, dsn: &mut String) {
+        if self.has_flag(RCPT_EXPIRED_ATTEMPTS) {
+            let _ = write!(
+                dsn,
+                "    (giving up after {} delivery attempts)\r\n",
+                self.retry.inner
+            );
+        } else if self.has_flag(RCPT_EXPIRED_TTL) {
+            dsn.push_str("    (giving up, message expired before it could be delivered)\r\n");
+        }
+    }
+
     fn write_dsn_will_retry_until(&self, created: u64, dsn: &mut String) {
         if let Some(expires) = self.expiration_time(created)
             && expires > now()
@@ -4,22 +4,33 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::{Message, QueueId, Status, spool::SmtpSpool};
-use crate::queue::{Recipient, spool::LOCK_EXPIRY};
-use ahash::AHashMap;
+use super::{ArchivedStatus, Message, QueueId, Status, spool::SmtpSpool};
+use crate::{
+    outbound::health::RelayHealthCheck,
+    queue::{Recipient, spool::LOCK_EXPIRY},
+};
+use ahash::{AHashMap, AHashSet};
 use common::{
-    BuildServer, Inner,
-    config::smtp::queue::{QueueExpiry, QueueName},
+    BuildServer, Inner, KV_RATE_LIMIT_QUEUE, Server,
+    config::smtp::queue::QueueName,
+    expr::functions::EmptyResolver,
     ipc::{QueueEvent, QueueEventStatus},
+    telemetry::metrics::queue::QueueGauges,
 };
 use rand::{Rng, seq::SliceRandom};
+use registry::schema::structs::Rate;
 use std::{
     collections::hash_map::Entry,
     sync::{Arc, atomic::Ordering},
     time::{Duration, Instant},
 };
-use store::write::now;
+use store::{
+    Deserialize, IterateParams, ValueKey,
+    write::{AlignedBytes, Archive, QueueClass, ValueClass, now},
+};
 use tokio::sync::mpsc;
+use trc::AddContext;
+use utils::DomainPart;
 
 pub struct Queue {
     pub core: Arc<Inner>,
@@ -27,6 +38,9 @@ pub struct Queue {
     pub locked_revision: u64,
     pub stats: AHashMap<QueueName, QueueStats>,
     pub next_refresh: Instant,
+    pub next_reconcile: Instant,
+    pub next_health_check: Instant,
+    pub next_rate_refresh: Instant,
     pub rx: mpsc::Receiver<QueueEvent>,
     pub is_paused: bool,
 }
@@ -36,6 +50,10 @@ pub struct QueueStats {
     pub in_flight: usize,
     pub max_in_flight: usize,
     pub last_warning: Instant,
+    pub rate: Option<Rate>,
+    pub rate_tokens: u64,
+    pub rate_tokens_until: Instant,
+    pub throttled_until: Option<Instant>,
 }
 
 #[derive(Debug)]
@@ -53,6 +71,11 @@ impl SpawnQueue for mpsc::Receiver<QueueEvent> {
 }
 
 const BACK_PRESSURE_WARN_INTERVAL: Duration = Duration::from_secs(60);
+const METRICS_RECONCILE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const IDLE_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+const RATE_LIMIT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+const RATE_LIMIT_ERROR_BACKOFF: Duration = Duration::from_secs(5);
+const RATE_LIMIT_LEASE_MAX: u64 = 100;
 
 impl Queue {
     pub fn new(core: Arc<Inner>, rx: mpsc::Receiver<QueueEvent>) -> Self {
@@ -62,6 +85,9 @@ impl Queue {
             locked_revision: 0,
             stats: AHashMap::new(),
             next_refresh: Instant::now() + Duration::from_secs(1),
+            next_reconcile: Instant::now(),
+            next_health_check: Instant::now(),
+            next_rate_refresh: Instant::now(),
             is_paused: false,
             rx,
         }
@@ -74,7 +100,11 @@ impl Queue {
             let mut refresh_queue;
 
             match tokio::time::timeout(
-                self.next_refresh.duration_since(Instant::now()),
+                self.next_refresh
+                    .min(self.next_reconcile)
+                    .min(self.next_health_check)
+                    .min(self.next_rate_refresh)
+                    .duration_since(Instant::now()),
                 self.rx.recv(),
             )
             .await
@@ -94,7 +124,35 @@ impl Queue {
                 }
             };
 
-            if !self.is_paused {
+            // Correct any drift in the queue metrics
+            if self.next_reconcile <= Instant::now() {
+                self.next_reconcile = Instant::now() + METRICS_RECONCILE_INTERVAL;
+                let server = self.core.build_server();
+                tokio::spawn(async move {
+                    server.reconcile_queue_metrics().await;
+                });
+            }
+
+            // Probe relay hosts that are due for a health check
+            if self.next_health_check <= Instant::now() {
+                let server = self.core.build_server();
+                let (hosts, next_probe) = server.schedule_relay_probes();
+                self.next_health_check =
+                    Instant::now() + next_probe.unwrap_or(IDLE_HEALTH_CHECK_INTERVAL);
+                if !hosts.is_empty() {
+                    tokio::spawn(async move {
+                        server.probe_relay_hosts(hosts).await;
+                    });
+                }
+            }
+
+            // Evaluate the rate limits of virtual queues, which may change over time
+            if self.next_rate_refresh <= Instant::now() {
+                self.next_rate_refresh = Instant::now() + RATE_LIMIT_REFRESH_INTERVAL;
+                self.refresh_rate_limits().await;
+            }
+
+            if !self.is_paused && !self.core.data.drain.is_draining() {
                 // Deliver scheduled messages
                 if refresh_queue || self.next_refresh <= Instant::now() {
                     // Process queue events
@@ -122,9 +180,22 @@ impl Queue {
 
                         // Enforce concurrency limits
                         if stats.has_capacity() {
-                            // Deliver message
-                            stats.in_flight += 1;
-                            queue_event.try_deliver(server.clone());
+                            // Enforce rate limits, held back messages are not
+                            // attempted so their retry count is left unchanged
+                            if stats.acquire_rate(&server, queue_event.queue_name).await {
+                                // Deliver message
+                                stats.in_flight += 1;
+                                queue_event.try_deliver(server.clone());
+                            } else {
+                                if let Some(until) = stats.throttled_until {
+                                    server.inner.data.queue_metrics.message_throttled(
+                                        until.saturating_duration_since(Instant::now()).as_millis()
+                                            as u64,
+                                    );
+                                }
+                                self.locked
+                                    .remove(&(queue_event.queue_id, queue_event.queue_name));
+                            }
                         } else {
                             if stats.last_warning.elapsed() >= BACK_PRESSURE_WARN_INTERVAL {
                                 stats.last_warning = Instant::now();
@@ -148,9 +219,18 @@ impl Queue {
 
                     self.next_refresh = Instant::now()
                         + Duration::from_secs(queue_events.next_refresh.saturating_sub(now));
+
+                    // Wake up when the rate limit window of a throttled queue refills
+                    for stats in self.stats.values() {
+                        if stats.is_throttled()
+                            && let Some(until) = stats.throttled_until
+                        {
+                            self.next_refresh = self.next_refresh.min(until);
+                        }
+                    }
                 }
             } else {
-                // Queue is paused
+                // Queue is paused or the server is draining
                 self.next_refresh = Instant::now() + Duration::from_secs(86400);
             }
         }
@@ -212,6 +292,8 @@ impl Queue {
                         self.stats.insert(*name, QueueStats::new(settings.threads));
                     }
                 }
+                self.next_rate_refresh = Instant::now();
+                self.next_health_check = Instant::now();
 
                 false
             }
@@ -224,6 +306,36 @@ impl Queue {
     }
 }
 
+impl Queue {
+    async fn refresh_rate_limits(&mut self) {
+        let server = self.core.build_server();
+        let virtual_queues = &server.core.smtp.queue.virtual_queues;
+        for (name, stats) in self.stats.iter_mut() {
+            if !virtual_queues.contains_key(name) {
+                stats.rate = None;
+                stats.reset_rate();
+            }
+        }
+        for (name, settings) in virtual_queues {
+            let rate = if let Some(rate_limit) = &settings.rate_limit {
+                server
+                    .eval_if::<Rate, _>(rate_limit, &EmptyResolver, 0)
+                    .await
+            } else {
+                None
+            };
+            let stats = self
+                .stats
+                .entry(*name)
+                .or_insert_with(|| QueueStats::new(settings.threads));
+            if stats.rate != rate {
+                stats.reset_rate();
+            }
+            stats.rate = rate;
+        }
+    }
+}
+
 impl Message {
     pub fn next_event(&self, queue: Option<QueueName>) -> Option<u64> {
         let mut next_event = None;
@@ -339,18 +451,36 @@ impl Message {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryReason {
+    Ttl,
+    Attempts,
+}
+
 impl Recipient {
     pub fn expiration_time(&self, created: u64) -> Option<u64> {
-        match self.expires {
-            QueueExpiry::Ttl(time) => Some(created + time),
-            QueueExpiry::Attempts(_) => None,
-        }
+        self.expires.ttl().map(|ttl| created + ttl)
     }
 
     pub fn is_expired(&self, created: u64, now: u64) -> bool {
-        match self.expires {
-            QueueExpiry::Ttl(time) => created + time <= now,
-            QueueExpiry::Attempts(count) => self.retry.inner >= count,
+        self.expiry_reason(created, now).is_some()
+    }
+
+    /// Returns the limit that caused delivery to this recipient to expire.
+    pub fn expiry_reason(&self, created: u64, now: u64) -> Option<ExpiryReason> {
+        if self
+            .expires
+            .max_attempts()
+            .is_some_and(|attempts| self.retry.inner >= attempts)
+        {
+            Some(ExpiryReason::Attempts)
+        } else if self
+            .expiration_time(created)
+            .is_some_and(|expires| expires <= now)
+        {
+            Some(ExpiryReason::Ttl)
+        } else {
+            None
         }
     }
 }
@@ -359,12 +489,88 @@ pub trait SpawnQueue {
     fn spawn(self, core: Arc<Inner>);
 }
 
+pub trait ReconcileQueueMetrics: Sync + Send {
+    fn reconcile_queue_metrics(&self) -> impl Future<Output = ()> + Send;
+}
+
+impl ReconcileQueueMetrics for Server {
+    async fn reconcile_queue_metrics(&self) {
+        let mut queues: AHashMap<QueueName, QueueGauges> = AHashMap::new();
+        let mut domains: AHashMap<Box<str>, u64> = AHashMap::new();
+        let mut message_queues = AHashSet::new();
+
+        let result = self
+            .store()
+            .iterate(
+                IterateParams::new(
+                    ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+                    ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+                ),
+                |key, value| {
+                    let message_ = <Archive<AlignedBytes> as Deserialize>::deserialize(value)
+                        .add_context(|ctx| ctx.ctx(trc::Key::Key, key))?;
+                    let message = message_
+                        .unarchive::<Message>()
+                        .add_context(|ctx| ctx.ctx(trc::Key::Key, key))?;
+                    let created = message.created.to_native();
+                    let size = message.size.to_native();
+
+                    message_queues.clear();
+                    for rcpt in message.recipients.iter().filter(|rcpt| {
+                        matches!(
+                            rcpt.status,
+                            ArchivedStatus::Scheduled | ArchivedStatus::TemporaryFailure(_)
+                        )
+                    }) {
+                        let domain = rcpt.domain_part();
+                        if let Some(volume) = domains.get_mut(domain) {
+                            *volume += 1;
+                        } else {
+                            domains.insert(domain.into(), 1);
+                        }
+
+                        if let Some(queue_name) = QueueName::new(rcpt.queue.as_str())
+                            && message_queues.insert(queue_name)
+                        {
+                            let gauges = queues.entry(queue_name).or_default();
+                            gauges.messages += 1;
+                            gauges.bytes += size;
+                            gauges.oldest =
+                                Some(gauges.oldest.map_or(created, |oldest| oldest.min(created)));
+                        }
+                    }
+
+                    Ok(true)
+                },
+            )
+            .await
+            .caused_by(trc::location!());
+
+        match result {
+            Ok(_) => {
+                self.inner.data.queue_metrics.reconcile(
+                    queues,
+                    domains,
+                    self.core.metrics.queue_top_domains,
+                );
+            }
+            Err(err) => {
+                trc::error!(err.details("Failed to reconcile queue metrics."));
+            }
+        }
+    }
+}
+
 impl QueueStats {
     fn new(max_in_flight: usize) -> Self {
         QueueStats {
             in_flight: 0,
             max_in_flight,
             last_warning: Instant::now() - BACK_PRESSURE_WARN_INTERVAL,
+            rate: None,
+            rate_tokens: 0,
+            rate_tokens_until: Instant::now(),
+            throttled_until: None,
         }
     }
 
@@ -372,4 +578,73 @@ impl QueueStats {
     pub fn has_capacity(&self) -> bool {
         self.in_flight < self.max_in_flight
     }
+
+    #[inline]
+    pub fn is_throttled(&self) -> bool {
+        self.throttled_until
+            .is_some_and(|until| until > Instant::now())
+    }
+
+    fn reset_rate(&mut self) {
+        self.rate_tokens = 0;
+        self.throttled_until = None;
+    }
+
+    // Takes a token from the queue's rate limit window, which is shared by
+    // all nodes. Tokens are leased from the window in blocks so the store is
+    // only updated once per block rather than on every dispatch, unused
+    // tokens are given up when the window ends. Destination throttles are
+    // applied later during delivery, so the most restrictive of both limits
+    // wins.
+    async fn acquire_rate(&mut self, server: &Server, queue_name: QueueName) -> bool {
+        let Some(rate) = &self.rate else {
+            return true;
+        };
+        if self.rate_tokens > 0 && self.rate_tokens_until > Instant::now() {
+            self.rate_tokens -= 1;
+            return true;
+        }
+        if self.is_throttled() {
+            return false;
+        }
+
+        let lease = (rate.count / 10).clamp(1, RATE_LIMIT_LEASE_MAX);
+        match server
+            .in_memory_store()
+            .acquire_rate_tokens(
+                KV_RATE_LIMIT_QUEUE,
+                queue_name.as_str().as_bytes(),
+                rate,
+                lease,
+            )
+            .await
+        {
+            Ok((tokens, expires_in)) if tokens > 0 => {
+                self.rate_tokens = tokens - 1;
+                self.rate_tokens_until = Instant::now() + Duration::from_secs(expires_in);
+                true
+            }
+            Ok((_, retry_in)) => {
+                self.throttled_until = Some(Instant::now() + Duration::from_secs(retry_in));
+                trc::event!(
+                    Queue(trc::QueueEvent::RateLimitExceeded),
+                    QueueName = queue_name.to_string(),
+                    Limit = vec![
+                        trc::Value::from(rate.count),
+                        trc::Value::from(rate.period.into_inner())
+                    ],
+                );
+                false
+            }
+            Err(err) => {
+                // Messages are held back until the limit can be checked again
+                trc::error!(
+                    err.caused_by(trc::location!())
+                        .details("Failed to check queue rate limit")
+                );
+                self.throttled_until = Some(Instant::now() + RATE_LIMIT_ERROR_BACKOFF);
+                false
+            }
+        }
+    }
 }
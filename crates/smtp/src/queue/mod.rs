@@ -5,8 +5,16 @@
  */
 
 use common::{
+    Server,
     config::smtp::queue::{QueueExpiry, QueueName},
-    expr::{self, functions::ResolveVariable, *},
+    expr::{
+        self,
+        functions::{
+            ResolveVariable,
+            header::{MAX_EXPR_HEADERS_SIZE, MessageHeaders},
+        },
+        *,
+    },
 };
 use compact_str::ToCompactString;
 use registry::schema::enums::ExpressionVariable;
@@ -14,17 +22,20 @@ use smtp_proto::Response;
 use std::{
     fmt::Display,
     net::{IpAddr, Ipv4Addr},
+    sync::Arc,
     time::{Duration, Instant, SystemTime},
 };
 use store::write::now;
 use types::blob_hash::BlobHash;
 use utils::DomainPart;
 
+pub mod callback;
 pub mod dsn;
 pub mod manager;
 pub mod quota;
 pub mod spool;
 pub mod throttle;
+pub mod unsubscribe;
 
 pub type QueueId = u64;
 
@@ -95,6 +106,16 @@ pub enum Metadata {
     QueueSize { key: Box<[u8]>, id: u64 },
     QueueCount { key: Box<[u8]>, id: u64 },
     Headers { value: Box<[u8]>, id: u64 },
+    DeliveryCallback { id: u64 },
+    SplitFrom { id: QueueId },
+    // Spam filter score in hundredths, used to suppress backscatter DSNs
+    SpamScore { score: i64 },
+    // Custom metadata set by trusted scripts and MTA hooks
+    Custom { key: Box<str>, value: Box<str> },
+    // Queued message that some recipients of this message were moved to
+    SplitInto { id: QueueId },
+    // Local spam filter tags used to sort the message into mail categories
+    SpamTags { tags: Box<[Box<str>]> },
 }
 
 #[derive(
@@ -130,6 +151,9 @@ pub const FROM_AUTOGENERATED: u64 = 1 << 37;
 pub const RCPT_DSN_SENT: u64 = 1 << 32;
 //pub const RCPT_UNDISCLOSED: u64 = 1 << 33;
 pub const RCPT_SPAM_PAYLOAD: u64 = 1 << 34;
+pub const RCPT_EXPIRED_TTL: u64 = 1 << 35;
+pub const RCPT_EXPIRED_ATTEMPTS: u64 = 1 << 36;
+pub const RCPT_SPLIT: u64 = 1 << 37;
 
 #[derive(
     Debug,
@@ -266,6 +290,7 @@ pub struct QueueEnvelope<'x> {
     pub rcpt: &'x Recipient,
     pub remote_ip: IpAddr,
     pub local_ip: IpAddr,
+    pub headers: tokio::sync::OnceCell<Arc<MessageHeaders>>,
 }
 
 impl<'x> QueueEnvelope<'x> {
@@ -277,7 +302,38 @@ impl<'x> QueueEnvelope<'x> {
             mx: "",
             remote_ip: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
             local_ip: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
+            headers: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    /// Reuses the headers parsed during the SMTP session, avoiding a blob read.
+    pub fn with_headers(mut self, headers: Option<Arc<MessageHeaders>>) -> Self {
+        if let Some(headers) = headers {
+            self.headers = tokio::sync::OnceCell::new_with(Some(headers));
         }
+        self
+    }
+}
+
+impl Message {
+    pub fn queue_meta(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.metadata.iter().filter_map(|metadata| match metadata {
+            Metadata::Custom { key, value } => Some((key.as_ref(), value.as_ref())),
+            _ => None,
+        })
+    }
+
+    pub fn queue_meta_get(&self, name: &str) -> Option<&str> {
+        self.queue_meta()
+            .find_map(|(key, value)| (key == name).then_some(value))
+    }
+
+    pub fn queue_meta_trace(&self) -> Option<trc::Value> {
+        let values = self
+            .queue_meta()
+            .map(|(key, value)| trc::Value::String(format!("{key}={value}").into()))
+            .collect::<Vec<_>>();
+        (!values.is_empty()).then_some(trc::Value::Array(values))
     }
 }
 
@@ -302,6 +358,14 @@ impl<'x> ResolveVariable for QueueEnvelope<'x> {
                 QueueExpiry::Attempts(count) => {
                     (count.saturating_sub(self.rcpt.retry.inner)) as u64
                 }
+                // Remaining time, unless the attempt limit has already been reached
+                QueueExpiry::TtlOrAttempts { ttl, attempts } => {
+                    if self.rcpt.retry.inner < *attempts {
+                        (*ttl as u64 + self.message.created).saturating_sub(now())
+                    } else {
+                        0
+                    }
+                }
             }
             .into(),
             ExpressionVariable::LastStatus => self.rcpt.status.to_compact_string().into(),
@@ -356,6 +420,37 @@ impl<'x> ResolveVariable for QueueEnvelope<'x> {
     fn resolve_global(&self, _: &str) -> Variable<'_> {
         Variable::Integer(0)
     }
+
+    fn resolve_meta(&self, name: &str) -> Variable<'_> {
+        self.message
+            .queue_meta_get(name)
+            .map(Variable::from)
+            .unwrap_or_default()
+    }
+
+    async fn resolve_header(&self, server: &Server, name: &str, all: bool) -> Variable<'_> {
+        // Only the header block is read from the blob store, at most once per envelope
+        self.headers
+            .get_or_init(|| async {
+                match server
+                    .blob_store()
+                    .get_blob_prefix(self.message.blob_hash.as_slice(), MAX_EXPR_HEADERS_SIZE)
+                    .await
+                {
+                    Ok(Some(raw_message)) => Arc::new(MessageHeaders::parse(&raw_message)),
+                    Ok(None) => Arc::default(),
+                    Err(err) => {
+                        trc::error!(
+                            err.caused_by(trc::location!())
+                                .details("Failed to read message headers")
+                        );
+                        Arc::default()
+                    }
+                }
+            })
+            .await
+            .get(name, all)
+    }
 }
 
 impl ResolveVariable for Message {
@@ -377,6 +472,12 @@ impl ResolveVariable for Message {
     fn resolve_global(&self, _: &str) -> Variable<'_> {
         Variable::Integer(0)
     }
+
+    fn resolve_meta(&self, name: &str) -> Variable<'_> {
+        self.queue_meta_get(name)
+            .map(Variable::from)
+            .unwrap_or_default()
+    }
 }
 
 impl ResolveVariable for MessageWrapper {
@@ -422,6 +523,13 @@ impl ResolveVariable for MessageWrapper {
     fn resolve_global(&self, _: &str) -> Variable<'_> {
         Variable::Integer(0)
     }
+
+    fn resolve_meta(&self, name: &str) -> Variable<'_> {
+        self.message
+            .queue_meta_get(name)
+            .map(Variable::from)
+            .unwrap_or_default()
+    }
 }
 
 pub struct RecipientDomain<'x>(&'x str);
@@ -5,10 +5,10 @@
  */
 
 use super::{
-    ArchivedMessage, ArchivedStatus, Message, MessageSource, Metadata, QueueEnvelope, QueueId,
-    QueuedMessage, Recipient, Schedule, Status,
+    ArchivedMessage, ArchivedMetadata, ArchivedStatus, Message, MessageSource, Metadata,
+    QueueEnvelope, QueueId, QueuedMessage, Recipient, Schedule, Status,
 };
-use crate::inbound::dkim::DkimSign;
+use crate::inbound::dkim::{DkimHeaders, DkimSign};
 use crate::queue::manager::{LockedMessage, Queue};
 use crate::queue::{
     FROM_AUTHENTICATED, FROM_AUTOGENERATED, FROM_DSN, FROM_REPORT, FROM_UNAUTHENTICATED,
@@ -16,7 +16,7 @@ use crate::queue::{
 };
 use ahash::{AHashMap, AHashSet};
 use common::config::smtp::auth::DkimSigners;
-use common::config::smtp::queue::{ArchivedQueueExpiry, QueueName};
+use common::config::smtp::queue::QueueName;
 use common::ipc::{BroadcastEvent, QueueEvent};
 use common::network::RcptResolution;
 use common::{KV_LOCK_QUEUE_MESSAGE, Server};
@@ -32,15 +32,12 @@ use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr};
 use std::sync::Arc;
 use std::time::SystemTime;
-use store::write::key::DeserializeBigEndian;
 use store::write::serialize::rkyv_deserialize;
 use store::write::{
     AlignedBytes, Archive, Archiver, BatchBuilder, BlobLink, BlobOp, MergeResult, Params,
     QueueClass, RegistryClass, ValueClass, now,
 };
-use store::{
-    Deserialize, IterateParams, Serialize, SerializeInfallible, U32_LEN, U64_LEN, ValueKey,
-};
+use store::{Deserialize, Serialize, SerializeInfallible, U32_LEN, ValueKey};
 use trc::{AddContext, ServerEvent, SpamEvent};
 use types::blob::BlobId;
 use types::blob_hash::BlobHash;
@@ -113,98 +110,73 @@ impl SmtpSpool for Server {
 
     async fn next_event(&self, queue: &mut Queue) -> QueuedMessages {
         let now = now();
-        let from_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: 0,
-                queue_id: 0,
-                queue_name: [0; 8],
-            },
-        )));
-        let to_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: now + QUEUE_REFRESH,
-                queue_id: u64::MAX,
-                queue_name: [u8::MAX; 8],
-            },
-        )));
-
         let mut events = QueuedMessages {
             messages: Vec::new(),
             next_refresh: now + QUEUE_REFRESH,
         };
 
         queue.locked_revision += 1;
-        let result = self
-            .store()
-            .iterate(
-                IterateParams::new(from_key, to_key).ascending().no_values(),
-                |key, _| {
-                    let due = key.deserialize_be_u64(0)?;
-
-                    if due <= now {
-                        let queue_id = key.deserialize_be_u64(U64_LEN)?;
-                        let queue_name = key
-                            .get(U64_LEN + U64_LEN..)
-                            .and_then(QueueName::from_bytes)
-                            .ok_or_else(|| {
-                                trc::StoreEvent::DataCorruption
-                                    .caused_by(trc::location!())
-                                    .ctx(trc::Key::Key, key)
-                            })?;
-
-                        let add_event = queue
-                            .stats
-                            .get(&queue_name)
-                            .is_none_or(|stats| stats.has_capacity())
-                            && match queue.locked.entry((queue_id, queue_name)) {
-                                Entry::Occupied(mut entry) => {
-                                    let locked = entry.get_mut();
-                                    locked.revision = queue.locked_revision;
-                                    if locked.expires <= now {
-                                        locked.expires = now + INFINITE_LOCK;
-
-                                        true
-                                    } else {
-                                        if locked.expires < events.next_refresh {
-                                            events.next_refresh = locked.expires;
-                                        }
-
-                                        false
-                                    }
-                                }
-                                Entry::Vacant(entry) => {
-                                    entry.insert(LockedMessage {
-                                        expires: now + INFINITE_LOCK,
-                                        revision: queue.locked_revision,
-                                    });
-                                    true
+        let mut stream = self.store().queue_events(0, now + QUEUE_REFRESH, true);
+        loop {
+            let event = match stream.next().await {
+                Ok(Some(event)) => event,
+                Ok(None) => break,
+                Err(err) => {
+                    trc::error!(
+                        err.details("Failed to read queue.")
+                            .caused_by(trc::location!())
+                    );
+                    break;
+                }
+            };
+            let due = event.due;
+
+            if due <= now {
+                let queue_id = event.queue_id;
+                let queue_name = QueueName::from(event.queue_name);
+
+                let add_event = queue
+                    .stats
+                    .get(&queue_name)
+                    .is_none_or(|stats| stats.has_capacity() && !stats.is_throttled())
+                    && match queue.locked.entry((queue_id, queue_name)) {
+                        Entry::Occupied(mut entry) => {
+                            let locked = entry.get_mut();
+                            locked.revision = queue.locked_revision;
+                            if locked.expires <= now {
+                                locked.expires = now + INFINITE_LOCK;
+
+                                true
+                            } else {
+                                if locked.expires < events.next_refresh {
+                                    events.next_refresh = locked.expires;
                                 }
-                            };
 
-                        if add_event {
-                            events.messages.push(QueuedMessage {
-                                due,
-                                queue_id,
-                                queue_name,
-                            });
+                                false
+                            }
                         }
-
-                        Ok(true)
-                    } else {
-                        if due < events.next_refresh {
-                            events.next_refresh = due;
+                        Entry::Vacant(entry) => {
+                            entry.insert(LockedMessage {
+                                expires: now + INFINITE_LOCK,
+                                revision: queue.locked_revision,
+                            });
+                            true
                         }
-                        Ok(false)
-                    }
-                },
-            )
-            .await;
+                    };
 
-        if let Err(err) = result {
-            trc::error!(
-                err.details("Failed to read queue.")
-                    .caused_by(trc::location!())
-            );
+                if add_event {
+                    events.messages.push(QueuedMessage {
+                        due,
+                        queue_id,
+                        queue_name,
+                    });
+                }
+            } else {
+                if due < events.next_refresh {
+                    events.next_refresh = due;
+                }
+                break;
+            }
         }
 
         events
@@ -327,12 +299,21 @@ pub(crate) struct QueueParams<'x, 'y> {
 impl MessageWrapper {
     pub(crate) async fn queue<'x, 'y>(mut self, mut params: QueueParams<'x, 'y>) -> bool {
         // Add DKIM signatures
-        let dkim_headers = if params.dkim_signers.is_some() {
+        let mut dkim_headers = if params.dkim_signers.is_some() {
             params.server.sign_message(&mut self, &mut params).await
         } else {
-            None
+            DkimHeaders::default()
         };
 
+        // Add one-click unsubscribe headers
+        if matches!(
+            params.source,
+            MessageSource::Authenticated | MessageSource::Unauthenticated { .. }
+        ) {
+            self.add_unsubscribe_headers(&mut params, &mut dkim_headers.dkim1)
+                .await;
+        }
+
         // Fetch params
         let QueueParams {
             raw_message,
@@ -379,11 +360,11 @@ impl MessageWrapper {
 
         // Write blob
         let raw_headers = raw_headers.unwrap_or_default();
-        let dkim_headers = dkim_headers.as_deref().unwrap_or_default();
-        let message = if !raw_headers.is_empty() || !dkim_headers.is_empty() {
-            let mut message =
-                Vec::with_capacity(raw_headers.len() + dkim_headers.len() + raw_message.len());
-            message.extend_from_slice(dkim_headers);
+        let dkim_len = dkim_headers.dkim1.len() + dkim_headers.dkim2.len();
+        let message = if !raw_headers.is_empty() || dkim_len > 0 {
+            let mut message = Vec::with_capacity(raw_headers.len() + dkim_len + raw_message.len());
+            message.extend_from_slice(&dkim_headers.dkim1);
+            message.extend_from_slice(&dkim_headers.dkim2);
             message.extend_from_slice(raw_headers);
             message.extend_from_slice(raw_message);
             Cow::Owned(message)
@@ -399,42 +380,12 @@ impl MessageWrapper {
         self.message.metadata = metadata.into_boxed_slice();
 
         // Reserve and write blob
-        let mut batch = BatchBuilder::new();
         let now = now();
         let reserve_until = now + 120;
-        batch.set(
-            BlobOp::Link {
-                hash: self.message.blob_hash.clone(),
-                to: BlobLink::Temporary {
-                    until: reserve_until,
-                },
-            },
-            vec![],
-        );
-        if let Err(err) = server.store().write(batch.build_all()).await {
-            trc::error!(
-                err.details("Failed to write to store.")
-                    .span_id(session_id)
-                    .caused_by(trc::location!())
-            );
-
-            return false;
-        }
-        if let Err(err) = server
-            .blob_store()
-            .put_blob(
-                self.message.blob_hash.as_slice(),
-                message.as_ref(),
-                server.core.email.compression,
-            )
+        if !self
+            .write_blob(message.as_ref(), reserve_until, session_id, server)
             .await
         {
-            trc::error!(
-                err.details("Failed to write blob.")
-                    .span_id(session_id)
-                    .caused_by(trc::location!())
-            );
-
             return false;
         }
 
@@ -477,14 +428,22 @@ impl MessageWrapper {
                         self.message.size as i64,
                     );
                 }
-                Metadata::Headers { .. } => {}
+                Metadata::Headers { .. }
+                | Metadata::DeliveryCallback { .. }
+                | Metadata::SplitFrom { .. }
+                | Metadata::SplitInto { .. }
+                | Metadata::SpamScore { .. }
+                | Metadata::SpamTags { .. }
+                | Metadata::Custom { .. } => {}
             }
         }
 
-        for (queue_name, due) in self.message.next_events() {
+        let next_events = self.message.next_events();
+        let (size, created) = (self.message.size, self.message.created);
+        for (queue_name, due) in &next_events {
             batch.set(
                 ValueClass::Queue(QueueClass::MessageEvent(store::write::QueueEvent {
-                    due,
+                    due: *due,
                     queue_id: self.queue_id,
                     queue_name: queue_name.into_inner(),
                 })),
@@ -584,6 +543,15 @@ impl MessageWrapper {
             return false;
         }
 
+        // Update queue metrics
+        for queue_name in next_events.into_keys() {
+            server
+                .inner
+                .data
+                .queue_metrics
+                .message_queued(queue_name, size, created);
+        }
+
         // Queue the message
         if server
             .inner
@@ -616,8 +584,23 @@ impl MessageWrapper {
                 self.add_expanded_recipient(&rewritten, server).await;
             }
             Ok(RcptResolution::Expand(addrs)) => {
-                for addr in addrs.as_ref() {
-                    self.add_expanded_recipient(addr, server).await;
+                match server
+                    .rcpt_expand(&rcpt.to_lowercase(), &addrs, self.span_id)
+                    .await
+                {
+                    Ok(expansion) => {
+                        for addr in expansion.recipients {
+                            self.add_expanded_recipient(&addr.address, server).await;
+                        }
+                    }
+                    Err(err) => {
+                        trc::error!(
+                            err.span_id(self.span_id)
+                                .caused_by(trc::location!())
+                                .details("Failed to expand recipient.")
+                                .ctx(trc::Key::To, rcpt.to_string())
+                        );
+                    }
                 }
             }
             Ok(_) => {
@@ -655,13 +638,124 @@ impl MessageWrapper {
         recipient.notify = Schedule::later(queue.notify.first().copied().unwrap_or(86400) + now);
         recipient.expires = queue.expiry;
         recipient.queue = queue.virtual_queue;
+
+        // Hold the first attempt until the delivery window opens
+        if let Some(next_start) = queue
+            .window
+            .as_ref()
+            .and_then(|window| window.next_start(now))
+        {
+            recipient.retry.due = next_start;
+        }
     }
 
-    pub async fn save_changes(mut self, server: &Server, prev_event: Option<u64>) -> bool {
-        // Release quota for completed deliveries
+    pub async fn save_changes(self, server: &Server, prev_event: Option<u64>) -> bool {
+        self.write_changes(server, prev_event, BatchBuilder::new())
+            .await
+    }
+
+    /// Stores the child messages created after splitting this message by route
+    /// in the same transaction as the parent, so that a crash can neither lose
+    /// nor duplicate the moved recipients. Children hold their own link to the
+    /// message blob, which is only released once all of them are removed.
+    pub async fn save_split(
+        self,
+        server: &Server,
+        prev_event: Option<u64>,
+        children: Vec<MessageWrapper>,
+    ) -> bool {
         let mut batch = BatchBuilder::new();
+        let mut child_queues = Vec::with_capacity(children.len());
+        let (size, created) = (self.message.size, self.message.created);
+
+        for child in children {
+            let next_events = child.message.next_events();
+            for (queue_name, due) in &next_events {
+                batch.set(
+                    ValueClass::Queue(QueueClass::MessageEvent(store::write::QueueEvent {
+                        due: *due,
+                        queue_id: child.queue_id,
+                        queue_name: queue_name.into_inner(),
+                    })),
+                    Vec::new(),
+                );
+            }
+            batch.set(
+                BlobOp::Link {
+                    hash: child.message.blob_hash.clone(),
+                    to: BlobLink::Id { id: child.queue_id },
+                },
+                vec![],
+            );
+
+            // Children hold their own quotas, the parent releases those of the
+            // recipients moved out of it
+            for metadata in &child.message.metadata {
+                match metadata {
+                    Metadata::QueueCount { key, .. } => {
+                        batch.add(ValueClass::Queue(QueueClass::QuotaCount(key.to_vec())), 1);
+                    }
+                    Metadata::QueueSize { key, .. } => {
+                        batch.add(
+                            ValueClass::Queue(QueueClass::QuotaSize(key.to_vec())),
+                            child.message.size as i64,
+                        );
+                    }
+                    Metadata::Headers { .. }
+                    | Metadata::DeliveryCallback { .. }
+                    | Metadata::SplitFrom { .. }
+                    | Metadata::SplitInto { .. }
+                    | Metadata::SpamScore { .. }
+                    | Metadata::SpamTags { .. }
+                    | Metadata::Custom { .. } => {}
+                }
+            }
+
+            let span_id = child.span_id;
+            batch.set(
+                ValueClass::Queue(QueueClass::Message(child.queue_id)),
+                match Archiver::new(child.message).serialize() {
+                    Ok(data) => data,
+                    Err(err) => {
+                        trc::error!(
+                            err.details("Failed to serialize message.")
+                                .span_id(span_id)
+                                .caused_by(trc::location!())
+                        );
+                        return false;
+                    }
+                },
+            );
+            child_queues.extend(next_events.into_keys());
+        }
+
+        if self.write_changes(server, prev_event, batch).await {
+            for queue_name in child_queues {
+                server
+                    .inner
+                    .data
+                    .queue_metrics
+                    .message_queued(queue_name, size, created);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn write_changes(
+        mut self,
+        server: &Server,
+        prev_event: Option<u64>,
+        mut batch: BatchBuilder,
+    ) -> bool {
+        // Release quota for completed deliveries
         self.release_quota(&mut batch);
 
+        // Messages with no pending recipients left in this queue are no longer counted
+        let is_queue_done = self.message.next_event(self.queue_name.into()).is_none();
+        let size = self.message.size;
+
         // Update message queue
         if let Some(prev_event) = prev_event {
             batch.clear(ValueClass::Queue(QueueClass::MessageEvent(
@@ -763,12 +857,24 @@ impl MessageWrapper {
             );
             false
         } else {
+            if is_queue_done {
+                server
+                    .inner
+                    .data
+                    .queue_metrics
+                    .message_dequeued(self.queue_name, size);
+            }
             true
         }
     }
 
     pub async fn remove(self, server: &Server, prev_event: Option<u64>) -> bool {
         let mut batch = BatchBuilder::new();
+        let queue_names = if prev_event.is_some() {
+            vec![self.queue_name]
+        } else {
+            self.message.next_events().into_keys().collect()
+        };
 
         if let Some(prev_event) = prev_event {
             batch.clear(ValueClass::Queue(QueueClass::MessageEvent(
@@ -802,7 +908,13 @@ impl MessageWrapper {
                         -(self.message.size as i64),
                     );
                 }
-                Metadata::Headers { .. } => {}
+                Metadata::Headers { .. }
+                | Metadata::DeliveryCallback { .. }
+                | Metadata::SplitFrom { .. }
+                | Metadata::SplitInto { .. }
+                | Metadata::SpamScore { .. }
+                | Metadata::SpamTags { .. }
+                | Metadata::Custom { .. } => {}
             }
         }
 
@@ -821,6 +933,13 @@ impl MessageWrapper {
             );
             false
         } else {
+            for queue_name in queue_names {
+                server
+                    .inner
+                    .data
+                    .queue_metrics
+                    .message_dequeued(queue_name, self.message.size);
+            }
             true
         }
     }
@@ -943,6 +1062,7 @@ impl MessageWrapper {
         prev_events: AHashMap<QueueName, u64>,
     ) -> bool {
         let mut batch = BatchBuilder::new();
+        let queue_names = prev_events.keys().copied().collect::<Vec<_>>();
 
         for (queue_name, due) in prev_events {
             batch.clear(ValueClass::Queue(QueueClass::MessageEvent(
@@ -965,7 +1085,13 @@ impl MessageWrapper {
                         -(self.message.size as i64),
                     );
                 }
-                Metadata::Headers { .. } => {}
+                Metadata::Headers { .. }
+                | Metadata::DeliveryCallback { .. }
+                | Metadata::SplitFrom { .. }
+                | Metadata::SplitInto { .. }
+                | Metadata::SpamScore { .. }
+                | Metadata::SpamTags { .. }
+                | Metadata::Custom { .. } => {}
             }
         }
 
@@ -984,6 +1110,13 @@ impl MessageWrapper {
             );
             false
         } else {
+            for queue_name in queue_names {
+                server
+                    .inner
+                    .data
+                    .queue_metrics
+                    .message_dequeued(queue_name, self.message.size);
+            }
             true
         }
     }
@@ -1001,6 +1134,27 @@ impl MessageWrapper {
 }
 
 impl ArchivedMessage {
+    pub fn split_from(&self) -> Option<QueueId> {
+        self.metadata.iter().find_map(|metadata| match metadata {
+            ArchivedMetadata::SplitFrom { id } => Some(id.to_native()),
+            _ => None,
+        })
+    }
+
+    pub fn split_into(&self) -> impl Iterator<Item = QueueId> {
+        self.metadata.iter().filter_map(|metadata| match metadata {
+            ArchivedMetadata::SplitInto { id } => Some(id.to_native()),
+            _ => None,
+        })
+    }
+
+    pub fn queue_meta(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.metadata.iter().filter_map(|metadata| match metadata {
+            ArchivedMetadata::Custom { key, value } => Some((key.as_ref(), value.as_ref())),
+            _ => None,
+        })
+    }
+
     pub fn has_domain(&self, domains: &AHashSet<String>) -> bool {
         self.recipients.iter().any(|r| {
             let domain = r.address.domain_part();
@@ -1046,8 +1200,8 @@ impl ArchivedMessage {
             let mut earlier_event =
                 std::cmp::min(rcpt.retry.due.to_native(), rcpt.notify.due.to_native());
 
-            if let ArchivedQueueExpiry::Ttl(ttl) = &rcpt.expires {
-                earlier_event = std::cmp::min(earlier_event, created + ttl.to_native());
+            if let Some(ttl) = rcpt.expires.ttl() {
+                earlier_event = std::cmp::min(earlier_event, created + ttl);
             }
 
             if let Some(next_event) = &mut next_event {
@@ -1085,6 +1239,55 @@ impl ArchivedMessage {
     }
 }
 
+impl MessageWrapper {
+    pub(crate) async fn write_blob(
+        &self,
+        message: &[u8],
+        reserve_until: u64,
+        session_id: u64,
+        server: &Server,
+    ) -> bool {
+        let mut batch = BatchBuilder::new();
+        batch.set(
+            BlobOp::Link {
+                hash: self.message.blob_hash.clone(),
+                to: BlobLink::Temporary {
+                    until: reserve_until,
+                },
+            },
+            vec![],
+        );
+        if let Err(err) = server.store().write(batch.build_all()).await {
+            trc::error!(
+                err.details("Failed to write to store.")
+                    .span_id(session_id)
+                    .caused_by(trc::location!())
+            );
+
+            return false;
+        }
+        if let Err(err) = server
+            .blob_store()
+            .put_blob(
+                self.message.blob_hash.as_slice(),
+                message,
+                server.core.email.compression,
+            )
+            .await
+        {
+            trc::error!(
+                err.details("Failed to write blob.")
+                    .span_id(session_id)
+                    .caused_by(trc::location!())
+            );
+
+            return false;
+        }
+
+        true
+    }
+}
+
 impl<'x, 'y> QueueParams<'x, 'y> {
     pub fn new(
         raw_message: &'x [u8],
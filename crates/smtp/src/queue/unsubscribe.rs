@@ -0,0 +1,166 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{MessageWrapper, Metadata, QueueEnvelope, spool::QueueParams};
+use common::{
+    config::smtp::auth::Dkim1Signer, expr::functions::header::MessageHeaders,
+    network::unsubscribe::UnsubscribeToken,
+};
+use mail_auth::common::headers::HeaderWriter;
+use std::sync::Arc;
+use store::write::now;
+
+impl MessageWrapper {
+    // Adds per-recipient List-Unsubscribe headers (RFC 8058) to messages
+    // matching the unsubscribe expression. As the headers differ for each
+    // recipient, they are stored in the message metadata along with a DKIM
+    // signature covering them, which replaces the message's DKIM1 signature
+    // so that each copy carries a single signature per signer.
+    pub(crate) async fn add_unsubscribe_headers(
+        &self,
+        params: &mut QueueParams<'_, '_>,
+        dkim1_headers: &mut Vec<u8>,
+    ) {
+        let server = params.server;
+        let Some(config) = &server.core.smtp.queue.unsubscribe else {
+            return;
+        };
+        if self.message.return_path.is_empty() {
+            return;
+        }
+
+        // Do not override links provided by the sender
+        let headers = Arc::new(MessageHeaders::parse(params.raw_message));
+        if !headers.first("List-Unsubscribe-Post").is_empty() {
+            return;
+        }
+
+        let expires = now() + config.validity;
+        let mut unsubscribe_ids = Vec::new();
+        for (rcpt_idx, rcpt) in self.message.recipients.iter().enumerate() {
+            let envelope =
+                QueueEnvelope::new(&self.message, rcpt).with_headers(Some(headers.clone()));
+            if !server
+                .eval_if::<bool, _>(&config.enable, &envelope, params.session_id)
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let campaign_id = server
+                .eval_if::<String, _>(&config.campaign_id, &envelope, params.session_id)
+                .await;
+
+            let mut unsubscribe = Vec::with_capacity(256);
+            config.write_headers(
+                &server.core.network.http.url_https,
+                &UnsubscribeToken::new(
+                    self.message.return_path.as_ref(),
+                    rcpt.address.as_ref(),
+                    campaign_id.as_deref(),
+                    expires,
+                ),
+                &mut unsubscribe,
+            );
+
+            // Sign the unsubscribe headers along with the message
+            let mut signatures = Vec::with_capacity(256);
+            if let Some(signers) = &params.dkim_signers {
+                let raw_headers = params.raw_headers.unwrap_or_default();
+                let message = [unsubscribe.as_slice(), raw_headers, params.raw_message];
+                for signer in &signers.dkim1 {
+                    let result = match signer {
+                        Dkim1Signer::RsaSha256(signer) => {
+                            signer.sign_chained(message.iter().copied())
+                        }
+                        Dkim1Signer::Ed25519Sha256(signer) => {
+                            signer.sign_chained(message.iter().copied())
+                        }
+                    };
+
+                    match result {
+                        Ok(signature) => {
+                            signature.write_header(&mut signatures);
+                        }
+                        Err(err) => {
+                            trc::error!(
+                                trc::Error::from(err)
+                                    .span_id(params.session_id)
+                                    .details("Failed to sign unsubscribe headers")
+                                    .caused_by(trc::location!())
+                            );
+                        }
+                    }
+                }
+            }
+
+            // Per-recipient headers replace the default ones, merge them
+            let rcpt_id = rcpt_idx as u64;
+            unsubscribe_ids.push(rcpt_id);
+            let existing = params
+                .metadata
+                .iter()
+                .position(|metadata| metadata.is_headers(rcpt_id))
+                .or_else(|| {
+                    params
+                        .metadata
+                        .iter()
+                        .position(|metadata| metadata.is_headers(u64::MAX))
+                });
+            let mut value = Vec::with_capacity(signatures.len() + unsubscribe.len() + 128);
+            if let Some(Metadata::Headers { value: headers, .. }) =
+                existing.map(|pos| &params.metadata[pos])
+            {
+                value.extend_from_slice(headers);
+            }
+            value.extend_from_slice(&signatures);
+            value.extend_from_slice(&unsubscribe);
+
+            match existing.map(|pos| &mut params.metadata[pos]) {
+                Some(Metadata::Headers { value: headers, id }) if *id == rcpt_id => {
+                    *headers = value.into_boxed_slice();
+                }
+                _ => {
+                    params.metadata.push(Metadata::Headers {
+                        value: value.into_boxed_slice(),
+                        id: rcpt_id,
+                    });
+                }
+            }
+        }
+
+        // The message's DKIM1 signatures are only kept for the remaining
+        // recipients, so they are moved from the message to their headers
+        if unsubscribe_ids.is_empty() || dkim1_headers.is_empty() {
+            return;
+        }
+        let mut has_default = false;
+        for metadata in params.metadata.iter_mut() {
+            if let Metadata::Headers { value, id } = metadata
+                && !unsubscribe_ids.contains(id)
+            {
+                has_default |= *id == u64::MAX;
+                let mut headers = Vec::with_capacity(dkim1_headers.len() + value.len());
+                headers.extend_from_slice(dkim1_headers);
+                headers.extend_from_slice(value);
+                *value = headers.into_boxed_slice();
+            }
+        }
+        if !has_default && unsubscribe_ids.len() < self.message.recipients.len() {
+            params.metadata.push(Metadata::Headers {
+                value: dkim1_headers.as_slice().into(),
+                id: u64::MAX,
+            });
+        }
+        dkim1_headers.clear();
+    }
+}
+
+impl Metadata {
+    fn is_headers(&self, rcpt_id: u64) -> bool {
+        matches!(self, Metadata::Headers { id, .. } if *id == rcpt_id)
+    }
+}
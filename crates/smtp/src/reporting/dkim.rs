@@ -44,11 +44,14 @@ impl<T: SessionStream> Session<T> {
         }
 
         let config = &self.server.core.smtp.report.dkim;
-        let from_addr = self
-            .server
-            .eval_if(&config.address, self, self.data.session_id)
-            .await
-            .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_string());
+        let from_addr = match self.domain_report_address().await {
+            Some(from_addr) => from_addr,
+            None => self
+                .server
+                .eval_if(&config.address, self, self.data.session_id)
+                .await
+                .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_string()),
+        };
         let mut report = Vec::with_capacity(128);
         self.new_auth_failure(output.result().into(), rejected)
             .with_authentication_results(
@@ -16,13 +16,14 @@ use common::{
     ipc::{DmarcEvent, ToHash},
     network::SessionStream,
 };
-use compact_str::ToCompactString;
+use compact_str::{CompactString, ToCompactString};
 use mail_auth::{
     ArcOutput, AuthenticatedMessage, AuthenticationResults, DkimOutput, DkimResult, DmarcOutput,
     SpfResult,
     common::verify::VerifySignature,
     dkim2::Dkim2Output,
     dmarc::{self},
+    flate2::{Compression, write::GzEncoder},
     report::{AuthFailureType, IdentityAlignment, PolicyPublished, Record, SPFDomainScope},
 };
 use registry::{
@@ -31,7 +32,7 @@ use registry::{
         prelude::{ObjectType, Property},
         structs::{DmarcInternalReport, DmarcReport, DmarcReportRecord, Rate},
     },
-    types::{EnumImpl, ObjectImpl, datetime::UTCDateTime, map::Map},
+    types::{EnumImpl, ObjectImpl, datetime::UTCDateTime, list::List, map::Map},
 };
 use std::future::Future;
 use store::{
@@ -125,11 +126,14 @@ impl<T: SessionStream> Session<T> {
             // Throttle recipient
             if !rcpts.is_empty() {
                 let mut report = Vec::with_capacity(128);
-                let from_addr = self
-                    .server
-                    .eval_if(&config.address, self, self.data.session_id)
-                    .await
-                    .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_compact_string());
+                let from_addr = match self.domain_report_address().await {
+                    Some(from_addr) => CompactString::from(from_addr),
+                    None => self
+                        .server
+                        .eval_if(&config.address, self, self.data.session_id)
+                        .await
+                        .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_compact_string()),
+                };
                 let mut auth_failure = self
                     .new_auth_failure(AuthFailureType::Dmarc, rejected)
                     .with_authentication_results(auth_results.to_string())
@@ -417,41 +421,62 @@ impl DmarcReporting for Server {
             )
             .await
             .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_compact_string());
-        let mut message = Vec::with_capacity(2048);
-        let _ = mail_auth::report::Report::from(report.report).write_rfc5322(
-            &self
-                .eval_if(
-                    &self.core.smtp.report.submitter,
-                    &RecipientDomain::new(report.domain.as_str()),
-                    span_id,
-                )
-                .await
-                .unwrap_or_else(|| "localhost".to_compact_string()),
-            (
-                self.eval_if(
-                    &config.name,
-                    &RecipientDomain::new(report.domain.as_str()),
-                    span_id,
-                )
-                .await
-                .unwrap_or_else(|| "Mail Delivery Subsystem".to_compact_string())
-                .as_str(),
-                from_addr.as_str(),
-            ),
-            rua.iter().map(|a| a.as_str()),
-            &mut message,
-        );
+        let submitter = self
+            .eval_if(
+                &self.core.smtp.report.submitter,
+                &RecipientDomain::new(report.domain.as_str()),
+                span_id,
+            )
+            .await
+            .unwrap_or_else(|| "localhost".to_compact_string());
+        let from_name = self
+            .eval_if(
+                &config.name,
+                &RecipientDomain::new(report.domain.as_str()),
+                span_id,
+            )
+            .await
+            .unwrap_or_else(|| "Mail Delivery Subsystem".to_compact_string());
+
+        // Split the report if it exceeds the configured or requested size limits.
+        // The configured limit applies to the compressed XML report while the
+        // limit requested by the domain owner applies to the message as sent.
+        let max_compressed_size = self
+            .eval_if::<usize, _>(
+                &config.max_compressed_size,
+                &RecipientDomain::new(report.domain.as_str()),
+                span_id,
+            )
+            .await
+            .unwrap_or(0);
+        let max_message_size = report.rua_max_size as usize;
+        let write_message = |report: &mail_auth::report::Report| {
+            let mut message = Vec::with_capacity(2048);
+            let _ = report.write_rfc5322(
+                &submitter,
+                (from_name.as_str(), from_addr.as_str()),
+                rua.iter().map(|a| a.as_str()),
+                &mut message,
+            );
+            message
+        };
+        let reports = split_report(report.report, |report| {
+            (max_compressed_size == 0 || compressed_size(report) <= max_compressed_size)
+                && (max_message_size == 0 || write_message(report).len() <= max_message_size)
+        });
 
-        // Send report
-        self.send_report(
-            &from_addr,
-            rua.iter(),
-            message,
-            &config.sign,
-            false,
-            span_id,
-        )
-        .await;
+        for report in reports {
+            // Send report
+            self.send_report(
+                &from_addr,
+                rua.iter(),
+                write_message(&report),
+                &config.sign,
+                false,
+                span_id,
+            )
+            .await;
+        }
 
         Ok(())
     }
@@ -598,6 +623,14 @@ impl DmarcReporting for Server {
                             .map(|u| u.uri.clone())
                             .collect(),
                     ),
+                    rua_max_size: event
+                        .dmarc_record
+                        .rua()
+                        .iter()
+                        .map(|u| u.max_size as u64)
+                        .filter(|size| *size > 0)
+                        .min()
+                        .unwrap_or(0),
                 };
 
                 report.write_ops(&mut batch, item_id, true);
@@ -606,6 +639,7 @@ impl DmarcReporting for Server {
             };
 
             // Add record
+            let now = UTCDateTime::now();
             let mut record = DmarcReportRecord::from(event.report_record.clone());
             if let Some(idx) = report
                 .report
@@ -615,9 +649,13 @@ impl DmarcReporting for Server {
                 .iter()
                 .position(|d| d.value.eq_except_count(&record))
             {
-                report.report.records.0.inner[idx].value.count += 1;
+                let existing = &mut report.report.records.0.inner[idx].value;
+                existing.count += 1;
+                existing.last_seen = Some(now);
             } else {
                 record.count = 1;
+                record.first_seen = Some(now);
+                record.last_seen = Some(now);
                 report.report.records.push(record);
             }
 
@@ -666,3 +704,66 @@ impl DmarcReporting for Server {
         }
     }
 }
+
+// Splits a report into parts that fit the size limits by halving the parts
+// that exceed them. Each part covers the period in which its own records were
+// seen and is told apart by a numeric suffix on the report id.
+fn split_report(
+    report: DmarcReport,
+    fits: impl Fn(&mail_auth::report::Report) -> bool,
+) -> Vec<mail_auth::report::Report> {
+    let records = report.records.iter().cloned().collect::<Vec<_>>();
+    if records.len() <= 1 || fits(&mail_auth::report::Report::from(report.clone())) {
+        return vec![mail_auth::report::Report::from(report)];
+    }
+
+    // Parts are measured with the longest possible suffix
+    let mut ranges = Vec::new();
+    let mut pending = vec![(0, records.len())];
+    while let Some((from, to)) = pending.pop() {
+        let part = report_part(&report, &records[from..to], records.len());
+        if to - from == 1 || fits(&part) {
+            ranges.push((from, to));
+        } else {
+            let middle = from + (to - from) / 2;
+            pending.push((middle, to));
+            pending.push((from, middle));
+        }
+    }
+
+    ranges
+        .into_iter()
+        .enumerate()
+        .map(|(part_num, (from, to))| report_part(&report, &records[from..to], part_num + 1))
+        .collect()
+}
+
+fn report_part(
+    report: &DmarcReport,
+    records: &[DmarcReportRecord],
+    part_num: usize,
+) -> mail_auth::report::Report {
+    mail_auth::report::Report::from(DmarcReport {
+        report_id: format!("{}.{part_num}", report.report_id),
+        date_range_begin: records
+            .iter()
+            .filter_map(|record| record.first_seen)
+            .min()
+            .unwrap_or(report.date_range_begin),
+        date_range_end: records
+            .iter()
+            .filter_map(|record| record.last_seen)
+            .max()
+            .unwrap_or(report.date_range_end),
+        records: List::from_iter(records.iter().cloned()),
+        ..report.clone()
+    })
+}
+
+fn compressed_size(report: &mail_auth::report::Report) -> usize {
+    let xml = report.to_xml();
+    let mut e = GzEncoder::new(Vec::with_capacity(xml.len()), Compression::default());
+    std::io::Write::write_all(&mut e, xml.as_bytes())
+        .and_then(|_| e.finish())
+        .map_or(xml.len(), |bytes| bytes.len())
+}
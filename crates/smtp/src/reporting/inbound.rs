@@ -35,6 +35,24 @@ impl<T: AsyncWrite + AsyncRead + Unpin> Session<T> {
             })
     }
 
+    /// Returns the report address configured for the domain of the first
+    /// recipient, if any.
+    pub async fn domain_report_address(&self) -> Option<String> {
+        let rcpt = self.data.rcpt_to.first()?;
+
+        match self.server.domain_settings(&rcpt.domain).await {
+            Ok(settings) => settings?.report_from_address.map(String::from),
+            Err(err) => {
+                trc::error!(
+                    err.span_id(self.data.session_id)
+                        .caused_by(trc::location!())
+                        .details("Failed to obtain domain settings")
+                );
+                None
+            }
+        }
+    }
+
     pub fn is_report(&self) -> bool {
         for addr_match in &self.server.core.smtp.report.analysis.addresses {
             for addr in &self.data.rcpt_to {
@@ -59,15 +59,14 @@ impl MtaReportSend for Server {
         if !deliver_now {
             #[cfg(not(feature = "test_mode"))]
             {
-                use common::config::smtp::queue::QueueExpiry;
                 use rand::Rng;
 
                 let delivery_time = rand::rng().random_range(0u64..10800u64);
                 for rcpt in &mut message.message.recipients {
                     rcpt.retry.due += delivery_time;
                     rcpt.notify.due += delivery_time;
-                    if let QueueExpiry::Ttl(expires) = &mut rcpt.expires {
-                        *expires += delivery_time;
+                    if let Some(expires) = rcpt.expires.ttl() {
+                        rcpt.expires = rcpt.expires.with_ttl(expires + delivery_time);
                     }
                 }
             }
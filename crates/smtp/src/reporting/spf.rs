@@ -35,11 +35,14 @@ impl<T: SessionStream> Session<T> {
 
         // Generate report
         let config = &self.server.core.smtp.report.spf;
-        let from_addr = self
-            .server
-            .eval_if(&config.address, self, self.data.session_id)
-            .await
-            .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_string());
+        let from_addr = match self.domain_report_address().await {
+            Some(from_addr) => from_addr,
+            None => self
+                .server
+                .eval_if(&config.address, self, self.data.session_id)
+                .await
+                .unwrap_or_else(|| "MAILER-DAEMON@localhost".to_string()),
+        };
         let mut report = Vec::with_capacity(128);
         self.new_auth_failure(AuthFailureType::Spf, rejected)
             .with_authentication_results(
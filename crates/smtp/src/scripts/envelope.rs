@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use common::config::smtp::queue::{QUEUE_META_MAX_ENTRIES, is_valid_queue_meta};
 use sieve::Envelope;
 use smtp_proto::{
     MAIL_BY_NOTIFY, MAIL_BY_RETURN, MAIL_BY_TRACE, MAIL_RET_FULL, MAIL_RET_HDRS, RCPT_NOTIFY_DELAY,
@@ -122,4 +123,18 @@ impl SessionData {
             Envelope::ByTimeAbsolute | Envelope::ByTimeRelative => (),
         }
     }
+
+    pub fn set_queue_meta(&mut self, key: String, value: String) -> bool {
+        if !is_valid_queue_meta(&key, &value) {
+            false
+        } else if let Some((_, current)) = self.queue_meta.iter_mut().find(|(k, _)| *k == key) {
+            *current = value;
+            true
+        } else if self.queue_meta.len() < QUEUE_META_MAX_ENTRIES {
+            self.queue_meta.push((key, value));
+            true
+        } else {
+            false
+        }
+    }
 }
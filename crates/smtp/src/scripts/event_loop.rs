@@ -9,7 +9,8 @@ use crate::queue::{
     quota::HasQueueQuota,
     spool::{QueueParams, SmtpSpool},
 };
-use common::{Server, config::smtp::queue::QueueExpiry, scripts::plugins::PluginContext};
+use common::{Server, scripts::plugins::PluginContext};
+use email::sieve::redirect::{RedirectScope, SieveRedirectLimit};
 use mail_parser::{Encoding, Message, MessagePart, PartType};
 use sieve::{
     Event, Input, MatchAs, Recipient, Sieve,
@@ -73,6 +74,7 @@ impl RunScript for Server {
         let mut reject_reason = None;
         let mut modifications = vec![];
         let mut keep_id = usize::MAX;
+        let mut redirect_limited = false;
 
         // Start event loop
         while let Some(result) = instance.run(input) {
@@ -135,6 +137,7 @@ impl RunScript for Server {
                                     server: self,
                                     message: instance.message(),
                                     modifications: &mut modifications,
+                                    spam_score: params.spam_score,
                                     access_token: params.access_token,
                                     arguments,
                                 },
@@ -248,7 +251,7 @@ impl RunScript for Server {
                                             (alimit as u64).saturating_sub(message.message.created);
                                         if expires > 0 {
                                             for domain in &mut message.message.recipients {
-                                                domain.expires = QueueExpiry::Ttl(expires);
+                                                domain.expires = domain.expires.with_ttl(expires);
                                             }
                                         }
                                     }
@@ -269,6 +272,37 @@ impl RunScript for Server {
                             Ret::Default => (),
                         }
 
+                        // Limit the redirects sent on behalf of authenticated accounts
+                        if let Some(access_token) = params.access_token {
+                            let recipients = message
+                                .message
+                                .recipients
+                                .iter()
+                                .map(|r| r.address().to_string())
+                                .collect::<Vec<_>>();
+                            match self
+                                .sieve_redirect_allowed(
+                                    access_token.account_id(),
+                                    &recipients,
+                                    RedirectScope::Trusted,
+                                    session_id,
+                                )
+                                .await
+                            {
+                                Ok(true) => {}
+                                Ok(false) => {
+                                    redirect_limited = true;
+                                    input = true.into();
+                                    continue;
+                                }
+                                Err(err) => {
+                                    trc::error!(
+                                        err.span_id(session_id).caused_by(trc::location!())
+                                    );
+                                }
+                            }
+                        }
+
                         // Queue message
                         let is_forward = message_id == 0;
                         let raw_message = if !is_forward {
@@ -360,6 +394,11 @@ impl RunScript for Server {
             }
         }
 
+        // Messages that could not be redirected are not discarded
+        if redirect_limited && keep_id == usize::MAX - 1 {
+            keep_id = usize::MAX;
+        }
+
         // Keep id
         // 0 = use original message
         // MAX = implicit keep
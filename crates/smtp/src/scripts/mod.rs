@@ -42,6 +42,7 @@ pub struct ScriptParameters<'x> {
     sign_domain: Option<String>,
     access_token: Option<&'x AccessToken>,
     spam_status: Option<SpamStatus>,
+    spam_score: Option<f32>,
     session_id: u64,
 }
 
@@ -58,6 +59,7 @@ impl<'x> ScriptParameters<'x> {
             sign_domain: Default::default(),
             access_token: None,
             spam_status: None,
+            spam_score: None,
             session_id: Default::default(),
         }
     }
@@ -104,6 +106,13 @@ impl<'x> ScriptParameters<'x> {
         }
     }
 
+    pub fn with_spam_score(self, score: f32) -> Self {
+        Self {
+            spam_score: score.into(),
+            ..self
+        }
+    }
+
     pub fn set_variable(
         mut self,
         name: impl Into<Cow<'static, str>>,
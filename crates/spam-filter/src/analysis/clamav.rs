@@ -0,0 +1,74 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::SpamFilterContext;
+use common::{Server, network::clamd::ClamdVerdict};
+use std::{future::Future, time::Instant};
+
+pub trait SpamFilterAnalyzeClamAv: Sync + Send {
+    fn spam_filter_analyze_clamav(
+        &self,
+        ctx: &mut SpamFilterContext<'_>,
+    ) -> impl Future<Output = ()> + Send;
+}
+
+impl SpamFilterAnalyzeClamAv for Server {
+    async fn spam_filter_analyze_clamav(&self, ctx: &mut SpamFilterContext<'_>) {
+        let Some(config) = &self.core.spam.clamav else {
+            return;
+        };
+        let message = ctx.input.message.raw_message();
+        if message.len() > config.max_size {
+            trc::event!(
+                Spam(trc::SpamEvent::ClamAv),
+                Result = "skipped",
+                Size = message.len(),
+                Limit = config.max_size,
+                SpanId = ctx.input.span_id,
+            );
+
+            // Unscanned messages are treated as infected when failing closed
+            if !config.fail_open {
+                ctx.result.add_tag("CLAM_FAIL");
+            }
+            return;
+        }
+
+        let time = Instant::now();
+        match config.scan(message).await {
+            Ok(ClamdVerdict::Clean) => {
+                trc::event!(
+                    Spam(trc::SpamEvent::ClamAv),
+                    Result = "clean",
+                    SpanId = ctx.input.span_id,
+                    Elapsed = time.elapsed()
+                );
+            }
+            Ok(ClamdVerdict::Infected(signature)) => {
+                trc::event!(
+                    Spam(trc::SpamEvent::ClamAv),
+                    Result = "infected",
+                    Details = signature.clone(),
+                    SpanId = ctx.input.span_id,
+                    Elapsed = time.elapsed()
+                );
+                ctx.result.add_tag("CLAM_VIRUS");
+                ctx.result.virus_result = Some(signature);
+            }
+            Err(err) => {
+                trc::error!(
+                    err.span_id(ctx.input.span_id)
+                        .ctx(trc::Key::Elapsed, time.elapsed())
+                );
+
+                // Messages that could not be scanned are treated as infected when failing closed
+                if !config.fail_open {
+                    ctx.result.add_tag("CLAM_FAIL");
+                }
+            }
+        }
+    }
+}
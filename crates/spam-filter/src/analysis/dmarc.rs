@@ -6,7 +6,7 @@
 
 use std::future::Future;
 
-use common::Server;
+use common::{Server, config::smtp::auth::SenderVerifyResult};
 use mail_auth::{Dkim2Result, DkimResult, DmarcResult, SpfResult, dmarc::Policy};
 
 use crate::SpamFilterContext;
@@ -34,6 +34,14 @@ impl SpamFilterAnalyzeDmarc for Server {
                 }),
         );
 
+        if let Some(result) = ctx.input.sender_verify_result {
+            ctx.result.add_tag(match result {
+                SenderVerifyResult::Pass => "SENDER_VERIFY_PASS",
+                SenderVerifyResult::Fail => "SENDER_VERIFY_FAIL",
+                SenderVerifyResult::TempError => "SENDER_VERIFY_TEMPFAIL",
+            });
+        }
+
         ctx.result.add_tag(
             match ctx
                 .input
@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use std::{collections::HashSet, future::Future, vec};
+use std::{collections::HashSet, future::Future, time::Instant, vec};
 
 use common::{
     Server,
@@ -16,7 +16,7 @@ use common::{
 use mail_parser::{HeaderName, MimeHeaders, PartType};
 use nlp::tokenizers::types::TokenType;
 
-use crate::{SpamFilterContext, TextPart};
+use crate::{SpamFilterContext, TextPart, modules::archive::inspect_archive};
 
 pub trait SpamFilterAnalyzeMime: Sync + Send {
     fn spam_filter_analyze_mime(
@@ -97,6 +97,7 @@ impl SpamFilterAnalyzeMime for Server {
 
         let mut num_parts = 0;
         let mut num_parts_size = 0;
+        let mut archive_deadline = None;
 
         for (part_id, part) in ctx.input.message.parts.iter().enumerate() {
             let part_id = part_id as u32;
@@ -376,6 +377,37 @@ impl SpamFilterAnalyzeMime for Server {
                         ctx.result.add_tag("MIME_BAD");
                     }
                 }
+
+                // Inspect archive contents, sharing a single time budget across all attachments
+                let deadline = *archive_deadline
+                    .get_or_insert_with(|| Instant::now() + self.core.spam.archive.timeout);
+                if let Some(report) = inspect_archive(
+                    part.contents(),
+                    &self.core.spam.archive,
+                    &self.core.spam.lists.file_extensions,
+                    deadline,
+                ) {
+                    if report.has_executable {
+                        // Archive contains an executable file
+                        ctx.result.add_tag("ARCHIVE_EXEC_INSIDE");
+                    }
+                    if report.has_double_extension {
+                        // Archive contains a file with a double extension
+                        ctx.result.add_tag("ARCHIVE_DOUBLE_EXTENSION");
+                    }
+                    if report.has_encrypted {
+                        // Archive contains encrypted entries
+                        ctx.result.add_tag("ARCHIVE_ENCRYPTED");
+                    }
+                    if report.is_bomb_suspect {
+                        // Archive has a suspicious compression ratio
+                        ctx.result.add_tag("ARCHIVE_BOMB_SUSPECT");
+                    }
+                    if report.is_corrupt {
+                        // Archive could not be parsed
+                        ctx.result.add_tag("ARCHIVE_CORRUPT");
+                    }
+                }
             }
 
             // Analyze attachment name
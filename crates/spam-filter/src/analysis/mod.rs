@@ -14,6 +14,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
+pub mod clamav;
 pub mod classifier;
 pub mod date;
 pub mod dmarc;
@@ -30,6 +31,7 @@ pub mod pyzor;
 pub mod received;
 pub mod recipient;
 pub mod replyto;
+pub mod reputation;
 pub mod rules;
 pub mod score;
 pub mod subject;
@@ -87,6 +89,12 @@ impl SpamFilterResult {
     pub fn has_tag(&self, tag: impl AsRef<str>) -> bool {
         self.tags.contains(tag.as_ref())
     }
+
+    pub fn add_custom_tag(&mut self, tag: impl Into<String>, score: f32) {
+        let tag = tag.into();
+        *self.custom_scores.entry(tag.clone()).or_default() += score;
+        self.tags.insert(tag);
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,56 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{SpamFilterContext, modules::reputation::SenderReputationStore};
+use common::Server;
+use mail_auth::SpfResult;
+use std::future::Future;
+
+pub trait SpamFilterAnalyzeReputation: Sync + Send {
+    fn spam_filter_analyze_reputation(
+        &self,
+        ctx: &mut SpamFilterContext<'_>,
+    ) -> impl Future<Output = ()> + Send;
+}
+
+impl SpamFilterAnalyzeReputation for Server {
+    async fn spam_filter_analyze_reputation(&self, ctx: &mut SpamFilterContext<'_>) {
+        let Some(config) = &self.core.spam.reputation else {
+            return;
+        };
+        if ctx.input.authenticated_as.is_some() {
+            return;
+        }
+
+        // Only senders authorized by SPF are attributed to their domain
+        let domain = ctx
+            .input
+            .spf_mail_from_result
+            .filter(|spf| matches!(spf.result(), SpfResult::Pass))
+            .map(|_| ctx.output.env_from_addr.domain_part.fqdn.as_str());
+        match self.sender_reputation(domain, ctx.input.remote_ip).await {
+            Ok(Some(reputation)) => {
+                let tag = reputation.tag(config);
+                trc::event!(
+                    Spam(trc::SpamEvent::SenderReputation),
+                    Result = reputation.score,
+                    Details = tag,
+                    Domain = domain.map(|domain| domain.to_string()),
+                    RemoteIp = ctx.input.remote_ip,
+                    SpanId = ctx.input.span_id,
+                );
+                if let Some(tag) = tag {
+                    ctx.result.add_tag(tag);
+                }
+                ctx.result.sender_reputation = Some(reputation.score);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                trc::error!(err.span_id(ctx.input.span_id).caused_by(trc::location!()));
+            }
+        }
+    }
+}
@@ -5,20 +5,21 @@
  */
 
 use crate::{
-    SpamFilterContext,
+    SpamFilterContext, SpamFilterResult,
     analysis::{
-        classifier::SpamFilterAnalyzeClassify, date::SpamFilterAnalyzeDate,
-        dmarc::SpamFilterAnalyzeDmarc, domain::SpamFilterAnalyzeDomain,
-        ehlo::SpamFilterAnalyzeEhlo, from::SpamFilterAnalyzeFrom,
+        clamav::SpamFilterAnalyzeClamAv, classifier::SpamFilterAnalyzeClassify,
+        date::SpamFilterAnalyzeDate, dmarc::SpamFilterAnalyzeDmarc,
+        domain::SpamFilterAnalyzeDomain, ehlo::SpamFilterAnalyzeEhlo, from::SpamFilterAnalyzeFrom,
         headers::SpamFilterAnalyzeHeaders, html::SpamFilterAnalyzeHtml, ip::SpamFilterAnalyzeIp,
         messageid::SpamFilterAnalyzeMid, mime::SpamFilterAnalyzeMime,
         pyzor::SpamFilterAnalyzePyzor, received::SpamFilterAnalyzeReceived,
         recipient::SpamFilterAnalyzeRecipient, replyto::SpamFilterAnalyzeReplyTo,
-        rules::SpamFilterAnalyzeRules, subject::SpamFilterAnalyzeSubject,
-        url::SpamFilterAnalyzeUrl,
+        reputation::SpamFilterAnalyzeReputation, rules::SpamFilterAnalyzeRules,
+        subject::SpamFilterAnalyzeSubject, url::SpamFilterAnalyzeUrl,
     },
 };
 use common::{Server, config::mailstore::spamfilter::SpamFilterAction};
+use registry::schema::enums::SpamVirusAction;
 use std::{fmt::Write, future::Future, vec};
 
 // SPDX-SnippetBegin
@@ -34,6 +35,17 @@ pub trait SpamFilterAnalyzeScore: Sync + Send {
         ctx: &mut SpamFilterContext<'_>,
     ) -> impl Future<Output = SpamFilterAction<SpamFilterScore>> + Send;
 
+    fn spam_filter_finalize_result(
+        &self,
+        result: &mut SpamFilterResult,
+        num_recipients: usize,
+    ) -> impl Future<Output = SpamFilterAction<SpamFilterScore>> + Send;
+
+    fn spam_filter_analyze(
+        &self,
+        ctx: &mut SpamFilterContext<'_>,
+    ) -> impl Future<Output = ()> + Send;
+
     fn spam_filter_classify(
         &self,
         ctx: &mut SpamFilterContext<'_>,
@@ -54,44 +66,83 @@ impl SpamFilterAnalyzeScore for Server {
         &self,
         ctx: &mut SpamFilterContext<'_>,
     ) -> SpamFilterAction<SpamFilterScore> {
+        self.spam_filter_finalize_result(&mut ctx.result, ctx.input.env_rcpt_rewritten_to.len())
+            .await
+    }
+
+    async fn spam_filter_finalize_result(
+        &self,
+        result: &mut SpamFilterResult,
+        num_recipients: usize,
+    ) -> SpamFilterAction<SpamFilterScore> {
+        // Apply the antivirus action before scoring
+        let mut is_quarantined = false;
+        if let Some(config) = &self.core.spam.clamav
+            && (result.tags.contains("CLAM_VIRUS") || result.tags.contains("CLAM_FAIL"))
+        {
+            match config.action {
+                SpamVirusAction::Reject => return SpamFilterAction::Reject,
+                SpamVirusAction::Quarantine => is_quarantined = true,
+                SpamVirusAction::Tag => {}
+            }
+        }
+
         // Calculate final score
         let mut results = vec![];
         let mut header_len = 60;
         let mut is_spam_trap = false;
         let mut rbl_count = 0;
 
-        for tag in &ctx.result.tags {
-            let score = match self.core.spam.lists.scores.get(tag) {
-                Some(SpamFilterAction::Allow(score)) => *score,
-                Some(SpamFilterAction::Discard) => {
-                    return SpamFilterAction::Discard;
-                }
-                Some(SpamFilterAction::Reject) => {
-                    return SpamFilterAction::Reject;
-                }
-                None | Some(SpamFilterAction::Disabled) => 0.0,
+        for tag in &result.tags {
+            let score = match result.custom_scores.get(tag) {
+                // Tags added by trusted scripts carry their own score
+                Some(score) => *score,
+                None => match self.core.spam.lists.scores.get(tag) {
+                    Some(SpamFilterAction::Allow(score)) => *score,
+                    Some(SpamFilterAction::Discard) => {
+                        return SpamFilterAction::Discard;
+                    }
+                    Some(SpamFilterAction::Reject) => {
+                        return SpamFilterAction::Reject;
+                    }
+                    None | Some(SpamFilterAction::Disabled) => 0.0,
+                },
             };
             if tag == "SPAM_TRAP" {
                 is_spam_trap = true;
             } else if score > 1.0 && tag.starts_with("RBL_") {
                 rbl_count += 1;
             }
-            ctx.result.score += score;
+            result.score += score;
             header_len += tag.len() + 10;
             if score != 0.0 || !tag.starts_with("X_") {
                 results.push((tag.as_str(), score));
             }
         }
 
-        let mut final_score = ctx.result.score;
+        // Recipient domains may override the global spam threshold, the
+        // message as a whole is classified using the lowest one
+        let rcpt_threshold = |idx: usize| {
+            result
+                .rcpt_spam_thresholds
+                .get(idx)
+                .copied()
+                .flatten()
+                .unwrap_or(self.core.spam.scores.spam_threshold)
+        };
+        let spam_threshold = (0..num_recipients)
+            .map(rcpt_threshold)
+            .reduce(f32::min)
+            .unwrap_or(self.core.spam.scores.spam_threshold);
+
+        let mut final_score = result.score;
         let mut avg_confidence: f32 = 0.0;
         let mut total_results = 0;
-        let mut user_results = vec![
-            ctx.result.score >= self.core.spam.scores.spam_threshold;
-            ctx.input.env_rcpt_rewritten_to.len()
-        ];
-        if !ctx.result.classifier_confidence.is_empty() {
-            for (idx, &confidence) in ctx.result.classifier_confidence.iter().enumerate() {
+        let mut user_results = (0..num_recipients)
+            .map(|idx| is_quarantined || result.score >= rcpt_threshold(idx))
+            .collect::<Vec<_>>();
+        if !result.classifier_confidence.is_empty() {
+            for (idx, &confidence) in result.classifier_confidence.iter().enumerate() {
                 if let Some(confidence) = confidence {
                     avg_confidence += confidence;
                     total_results += 1;
@@ -107,7 +158,7 @@ impl SpamFilterAnalyzeScore for Server {
                         .unwrap_or_default();
 
                     user_results[idx] =
-                        ctx.result.score + user_score >= self.core.spam.scores.spam_threshold;
+                        is_quarantined || result.score + user_score >= rcpt_threshold(idx);
                 }
             }
 
@@ -149,11 +200,15 @@ impl SpamFilterAnalyzeScore for Server {
             }
             headers.push_str("\r\n");
 
-            if let Some((category, explanation)) = &ctx.result.llm_result {
+            if let Some((category, explanation)) = &result.llm_result {
                 let _ = write!(&mut headers, "X-Spam-LLM: {category} ({explanation})\r\n",);
             }
 
-            let is_spam = final_score >= self.core.spam.scores.spam_threshold;
+            if let Some(signature) = &result.virus_result {
+                let _ = write!(&mut headers, "X-Spam-Virus: {signature}\r\n",);
+            }
+
+            let is_spam = is_quarantined || final_score >= spam_threshold;
             let class = if is_spam { "spam" } else { "ham" };
 
             if avg_confidence != 0.0 {
@@ -194,6 +249,11 @@ impl SpamFilterAnalyzeScore for Server {
         &self,
         ctx: &mut SpamFilterContext<'_>,
     ) -> SpamFilterAction<SpamFilterScore> {
+        self.spam_filter_analyze(ctx).await;
+        self.spam_filter_finalize(ctx).await
+    }
+
+    async fn spam_filter_analyze(&self, ctx: &mut SpamFilterContext<'_>) {
         // IP address analysis
         self.spam_filter_analyze_ip(ctx).await;
 
@@ -252,17 +312,20 @@ impl SpamFilterAnalyzeScore for Server {
         // Spam trap
         self.spam_filter_analyze_spam_trap(ctx).await;
 
+        // Antivirus scan
+        self.spam_filter_analyze_clamav(ctx).await;
+
         // Pyzor checks
         self.spam_filter_analyze_pyzor(ctx).await;
 
+        // Sender reputation
+        self.spam_filter_analyze_reputation(ctx).await;
+
         // Model classification
         self.spam_filter_analyze_classify(ctx).await;
 
         // User-defined rules
         self.spam_filter_analyze_rules(ctx).await;
-
-        // Final score calculation
-        self.spam_filter_finalize(ctx).await
     }
 }
 
@@ -11,6 +11,7 @@ pub mod modules;
 
 use analysis::ElementLocation;
 use analysis::url::UrlParts;
+use common::config::smtp::auth::SenderVerifyResult;
 use mail_auth::{
     ArcOutput, DkimOutput, DmarcResult, IprevOutput, SpfOutput, dkim2::Dkim2Output, dmarc::Policy,
 };
@@ -21,7 +22,7 @@ use std::borrow::Cow;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::net::{IpAddr, Ipv4Addr};
-use store::ahash::AHashSet;
+use store::ahash::{AHashMap, AHashSet};
 
 pub struct SpamFilterInput<'x> {
     pub message: &'x Message<'x>,
@@ -36,6 +37,7 @@ pub struct SpamFilterInput<'x> {
     pub dmarc_result: Option<&'x DmarcResult>,
     pub dmarc_policy: Option<&'x Policy>,
     pub iprev_result: Option<&'x IprevOutput>,
+    pub sender_verify_result: Option<SenderVerifyResult>,
 
     // Session details
     pub remote_ip: IpAddr,
@@ -103,16 +105,20 @@ pub enum TextPart<'x> {
     None,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct SpamFilterResult {
     pub tags: AHashSet<String>,
+    pub custom_scores: AHashMap<String, f32>,
     pub classifier_confidence: Vec<Option<f32>>,
+    pub rcpt_spam_thresholds: Vec<Option<f32>>,
     pub score: f32,
     pub rbl_ip_checks: usize,
     pub rbl_domain_checks: usize,
     pub rbl_url_checks: usize,
     pub rbl_email_checks: usize,
     pub llm_result: Option<(String, String)>,
+    pub virus_result: Option<String>,
+    pub sender_reputation: Option<f64>,
 }
 
 pub struct SpamFilterContext<'x> {
@@ -154,6 +160,7 @@ impl<'x> SpamFilterInput<'x> {
             dmarc_result: None,
             dmarc_policy: None,
             iprev_result: None,
+            sender_verify_result: None,
             remote_ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
             ehlo_domain: None,
             authenticated_as: None,
@@ -0,0 +1,695 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::config::mailstore::spamfilter::{ArchiveConfig, FileExtension};
+use flate2::read::DeflateDecoder;
+use std::{borrow::Cow, io::Read, time::Instant};
+use utils::glob::GlobMap;
+
+// Entries smaller than this are never reported as decompression bombs
+const BOMB_MIN_SIZE: u64 = 1024 * 1024;
+
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_OF_CENTRAL_DIR: u32 = 0x06054b50;
+const ZIP64_END_OF_CENTRAL_DIR: u32 = 0x06064b50;
+const ZIP64_LOCATOR: u32 = 0x07064b50;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub entries: usize,
+    pub has_executable: bool,
+    pub has_encrypted: bool,
+    pub has_double_extension: bool,
+    pub is_bomb_suspect: bool,
+    pub is_corrupt: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    Rar4,
+    Rar5,
+    SevenZip,
+}
+
+struct ArchiveInspector<'x> {
+    config: &'x ArchiveConfig,
+    extensions: &'x GlobMap<FileExtension>,
+    deadline: Instant,
+    decompressed: usize,
+    report: ArchiveReport,
+}
+
+struct ArchiveEntry<'x> {
+    name: &'x [u8],
+    compressed_size: u64,
+    uncompressed_size: u64,
+    is_encrypted: bool,
+}
+
+/// Inspects the entries of a zip, rar or 7z archive, including archives nested
+/// inside zip files. Only the archive directories are read, entry contents are
+/// decompressed exclusively to open nested archives and always within the
+/// configured size budget. Returns `None` if the contents are not an archive.
+pub fn inspect_archive(
+    contents: &[u8],
+    config: &ArchiveConfig,
+    extensions: &GlobMap<FileExtension>,
+    deadline: Instant,
+) -> Option<ArchiveReport> {
+    let format = ArchiveFormat::detect(contents)?;
+    let mut inspector = ArchiveInspector {
+        config,
+        extensions,
+        deadline,
+        decompressed: 0,
+        report: ArchiveReport::default(),
+    };
+    inspector.inspect(format, contents, 1);
+    Some(inspector.report)
+}
+
+impl ArchiveFormat {
+    pub fn detect(contents: &[u8]) -> Option<Self> {
+        if contents.starts_with(b"PK\x03\x04") || contents.starts_with(b"PK\x05\x06") {
+            Some(ArchiveFormat::Zip)
+        } else if contents.starts_with(b"Rar!\x1a\x07\x01\x00") {
+            Some(ArchiveFormat::Rar5)
+        } else if contents.starts_with(b"Rar!\x1a\x07\x00") {
+            Some(ArchiveFormat::Rar4)
+        } else if contents.starts_with(b"7z\xbc\xaf\x27\x1c") {
+            Some(ArchiveFormat::SevenZip)
+        } else {
+            None
+        }
+    }
+}
+
+impl ArchiveInspector<'_> {
+    fn inspect(&mut self, format: ArchiveFormat, contents: &[u8], depth: usize) {
+        let result = match format {
+            ArchiveFormat::Zip => self.inspect_zip(contents, depth),
+            ArchiveFormat::Rar4 => self.inspect_rar4(contents),
+            ArchiveFormat::Rar5 => self.inspect_rar5(contents),
+            // 7z archives store their directory compressed (and often encrypted),
+            // decoding it would require a full LZMA implementation.
+            ArchiveFormat::SevenZip => Some(()),
+        };
+
+        if result.is_none() {
+            self.report.is_corrupt = true;
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.report.entries >= self.config.max_entries || Instant::now() >= self.deadline
+    }
+
+    /// Records an entry, returns `true` if its name has an archive extension.
+    fn add_entry(&mut self, entry: &ArchiveEntry<'_>) -> bool {
+        self.report.entries += 1;
+
+        if entry.is_encrypted {
+            self.report.has_encrypted = true;
+        }
+
+        if entry.uncompressed_size >= BOMB_MIN_SIZE
+            && entry.uncompressed_size / entry.compressed_size.max(1) > self.config.max_ratio
+        {
+            self.report.is_bomb_suspect = true;
+        }
+
+        let name = String::from_utf8_lossy(entry.name).to_lowercase();
+        let name = name
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .trim_end();
+        let Some((name, ext)) = name
+            .rsplit_once('.')
+            .and_then(|(name, ext)| Some((name, self.extensions.get(ext)?)))
+        else {
+            return false;
+        };
+
+        if ext.is_bad {
+            self.report.has_executable = true;
+
+            if name
+                .trim_end()
+                .rsplit_once('.')
+                .is_some_and(|(_, sub_ext)| self.extensions.get(sub_ext).is_some())
+            {
+                self.report.has_double_extension = true;
+            }
+        }
+
+        ext.is_archive
+    }
+
+    fn inspect_zip(&mut self, contents: &[u8], depth: usize) -> Option<()> {
+        // Locate the end of central directory record, which is followed by a comment of up to 64KB
+        let search_start = contents.len().saturating_sub(22 + u16::MAX as usize);
+        let eocd_pos = (search_start..=contents.len().checked_sub(22)?)
+            .rev()
+            .find(|&pos| read_u32(contents, pos) == Some(ZIP_END_OF_CENTRAL_DIR))?;
+        let mut num_entries = read_u16(contents, eocd_pos + 10)? as u64;
+        let mut cd_size = read_u32(contents, eocd_pos + 12)? as u64;
+        let mut cd_offset = read_u32(contents, eocd_pos + 16)? as u64;
+
+        if (num_entries == u16::MAX as u64
+            || cd_size == u32::MAX as u64
+            || cd_offset == u32::MAX as u64)
+            && eocd_pos >= 20
+            && read_u32(contents, eocd_pos - 20) == Some(ZIP64_LOCATOR)
+        {
+            let zip64_pos = usize::try_from(read_u64(contents, eocd_pos - 12)?).ok()?;
+            if read_u32(contents, zip64_pos)? != ZIP64_END_OF_CENTRAL_DIR {
+                return None;
+            }
+            num_entries = read_u64(contents, zip64_pos + 32)?;
+            cd_size = read_u64(contents, zip64_pos + 40)?;
+            cd_offset = read_u64(contents, zip64_pos + 48)?;
+        }
+
+        let cd_start = usize::try_from(cd_offset).ok()?;
+        let cd_end = cd_start.checked_add(usize::try_from(cd_size).ok()?)?;
+        if cd_end > eocd_pos {
+            return None;
+        }
+
+        let mut total_size = 0u64;
+        let mut pos = cd_start;
+        for _ in 0..num_entries {
+            if self.is_exhausted() {
+                break;
+            }
+
+            if read_u32(contents, pos)? != ZIP_CENTRAL_HEADER {
+                return None;
+            }
+            let flags = read_u16(contents, pos + 8)?;
+            let method = read_u16(contents, pos + 10)?;
+            let mut compressed_size = read_u32(contents, pos + 20)? as u64;
+            let mut uncompressed_size = read_u32(contents, pos + 24)? as u64;
+            let name_len = read_u16(contents, pos + 28)? as usize;
+            let extra_len = read_u16(contents, pos + 30)? as usize;
+            let comment_len = read_u16(contents, pos + 32)? as usize;
+            let mut local_offset = read_u32(contents, pos + 42)? as u64;
+            let name = contents.get(pos + 46..pos + 46 + name_len)?;
+            let extra = contents.get(pos + 46 + name_len..pos + 46 + name_len + extra_len)?;
+            pos += 46 + name_len + extra_len + comment_len;
+            if pos > cd_end {
+                return None;
+            }
+
+            // Obtain 64-bit sizes from the Zip64 extended information field
+            let mut extra_pos = 0;
+            while let (Some(id), Some(len)) =
+                (read_u16(extra, extra_pos), read_u16(extra, extra_pos + 2))
+            {
+                let len = len as usize;
+                if id == 0x0001 {
+                    let mut field_pos = extra_pos + 4;
+                    for value in [
+                        &mut uncompressed_size,
+                        &mut compressed_size,
+                        &mut local_offset,
+                    ] {
+                        if *value == u32::MAX as u64 {
+                            *value = read_u64(extra, field_pos)?;
+                            field_pos += 8;
+                        }
+                    }
+                    break;
+                }
+                extra_pos += 4 + len;
+            }
+
+            // Flag bit 0 is traditional PKWARE encryption, method 99 is WinZip AES
+            let entry = ArchiveEntry {
+                name,
+                compressed_size,
+                uncompressed_size,
+                is_encrypted: flags & 1 != 0 || method == 99,
+            };
+            total_size = total_size.saturating_add(uncompressed_size);
+            let is_archive = self.add_entry(&entry);
+
+            if depth < self.config.max_depth
+                && !entry.is_encrypted
+                && (is_archive || method == 0)
+                && let Some(data) = self.zip_entry_contents(contents, local_offset, method, &entry)
+                && let Some(format) = ArchiveFormat::detect(&data)
+            {
+                self.inspect(format, &data, depth + 1);
+            }
+        }
+
+        // Many small entries can also add up to a bomb
+        if total_size >= BOMB_MIN_SIZE
+            && total_size / (contents.len() as u64).max(1) > self.config.max_ratio
+        {
+            self.report.is_bomb_suspect = true;
+        }
+
+        Some(())
+    }
+
+    fn zip_entry_contents<'x>(
+        &mut self,
+        contents: &'x [u8],
+        local_offset: u64,
+        method: u16,
+        entry: &ArchiveEntry<'_>,
+    ) -> Option<Cow<'x, [u8]>> {
+        let pos = usize::try_from(local_offset).ok()?;
+        if read_u32(contents, pos)? != ZIP_LOCAL_HEADER {
+            return None;
+        }
+        let data_start = pos
+            + 30
+            + read_u16(contents, pos + 26)? as usize
+            + read_u16(contents, pos + 28)? as usize;
+        let data = contents.get(
+            data_start..data_start.checked_add(usize::try_from(entry.compressed_size).ok()?)?,
+        )?;
+
+        match method {
+            0 => Some(Cow::Borrowed(data)),
+            8 => {
+                let budget = self.config.max_size.saturating_sub(self.decompressed);
+                if entry.uncompressed_size > budget as u64 {
+                    return None;
+                }
+
+                // Never trust the declared size, cap the output to the remaining budget
+                let mut buf = Vec::with_capacity(entry.uncompressed_size as usize);
+                DeflateDecoder::new(data)
+                    .take(budget as u64)
+                    .read_to_end(&mut buf)
+                    .ok()?;
+                self.decompressed += buf.len();
+                Some(Cow::Owned(buf))
+            }
+            _ => None,
+        }
+    }
+
+    fn inspect_rar4(&mut self, contents: &[u8]) -> Option<()> {
+        let mut pos = 7;
+
+        while pos < contents.len() && !self.is_exhausted() {
+            let head_type = *contents.get(pos + 2)?;
+            let head_flags = read_u16(contents, pos + 3)?;
+            let head_size = read_u16(contents, pos + 5)? as usize;
+            if head_size < 7 {
+                return None;
+            }
+            let add_size = if head_flags & 0x8000 != 0 {
+                read_u32(contents, pos + 7)? as usize
+            } else {
+                0
+            };
+
+            match head_type {
+                // Main header, headers are encrypted
+                0x73 if head_flags & 0x0080 != 0 => {
+                    self.report.has_encrypted = true;
+                    break;
+                }
+                // File header
+                0x74 => {
+                    let mut compressed_size = read_u32(contents, pos + 7)? as u64;
+                    let mut uncompressed_size = read_u32(contents, pos + 11)? as u64;
+                    let name_len = read_u16(contents, pos + 26)? as usize;
+                    let mut name_pos = pos + 32;
+                    if head_flags & 0x0100 != 0 {
+                        compressed_size |= (read_u32(contents, pos + 32)? as u64) << 32;
+                        uncompressed_size |= (read_u32(contents, pos + 36)? as u64) << 32;
+                        name_pos += 8;
+                    }
+                    let name = contents.get(name_pos..name_pos + name_len)?;
+                    // Unicode names are stored after a zero byte
+                    let name = name.split(|&ch| ch == 0).next().unwrap_or(name);
+
+                    self.add_entry(&ArchiveEntry {
+                        name,
+                        compressed_size,
+                        uncompressed_size,
+                        is_encrypted: head_flags & 0x0004 != 0,
+                    });
+                }
+                // End of archive
+                0x7b => break,
+                _ => {}
+            }
+
+            pos = pos.checked_add(head_size)?.checked_add(add_size)?;
+        }
+
+        Some(())
+    }
+
+    fn inspect_rar5(&mut self, contents: &[u8]) -> Option<()> {
+        let mut reader = Reader {
+            data: contents,
+            pos: 8,
+        };
+
+        while reader.pos < contents.len() && !self.is_exhausted() {
+            reader.skip(4)?;
+            let header_size = usize::try_from(reader.vint()?).ok()?;
+            let header_end = reader.pos.checked_add(header_size)?;
+            if header_end > contents.len() {
+                return None;
+            }
+            let header_type = reader.vint()?;
+            let header_flags = reader.vint()?;
+            let extra_size = if header_flags & 0x0001 != 0 {
+                usize::try_from(reader.vint()?).ok()?
+            } else {
+                0
+            };
+            let data_size = if header_flags & 0x0002 != 0 {
+                reader.vint()?
+            } else {
+                0
+            };
+
+            match header_type {
+                // File header
+                2 => {
+                    let file_flags = reader.vint()?;
+                    let uncompressed_size = reader.vint()?;
+                    reader.vint()?;
+                    if file_flags & 0x0002 != 0 {
+                        reader.skip(4)?;
+                    }
+                    if file_flags & 0x0004 != 0 {
+                        reader.skip(4)?;
+                    }
+                    reader.vint()?;
+                    reader.vint()?;
+                    let name_len = usize::try_from(reader.vint()?).ok()?;
+                    let name = reader.bytes(name_len)?;
+
+                    // Encryption is signaled by a file encryption record in the extra area
+                    let mut is_encrypted = false;
+                    let mut extra = Reader {
+                        data: contents.get(header_end.checked_sub(extra_size)?..header_end)?,
+                        pos: 0,
+                    };
+                    while extra.pos < extra.data.len() {
+                        let record_size = usize::try_from(extra.vint()?).ok()?;
+                        let record_end = extra.pos.checked_add(record_size)?;
+                        if extra.vint()? == 0x01 {
+                            is_encrypted = true;
+                            break;
+                        }
+                        extra.pos = record_end;
+                    }
+
+                    self.add_entry(&ArchiveEntry {
+                        name,
+                        compressed_size: data_size,
+                        uncompressed_size,
+                        is_encrypted,
+                    });
+                }
+                // Archive encryption header, all headers are encrypted
+                4 => {
+                    self.report.has_encrypted = true;
+                    break;
+                }
+                // End of archive
+                5 => break,
+                _ => {}
+            }
+
+            reader.pos = header_end.checked_add(usize::try_from(data_size).ok()?)?;
+        }
+
+        Some(())
+    }
+}
+
+struct Reader<'x> {
+    data: &'x [u8],
+    pos: usize,
+}
+
+impl<'x> Reader<'x> {
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'x [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn vint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..70).step_by(7) {
+            let byte = *self.data.get(self.pos)?;
+            self.pos += 1;
+            value |= ((byte & 0x7f) as u64).checked_shl(shift)?;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos.checked_add(2)?)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos.checked_add(4)?)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+    data.get(pos..pos.checked_add(8)?)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct ZipBuilder {
+        data: Vec<u8>,
+        central_dir: Vec<u8>,
+        num_entries: u16,
+    }
+
+    impl ZipBuilder {
+        fn new() -> Self {
+            ZipBuilder {
+                data: Vec::new(),
+                central_dir: Vec::new(),
+                num_entries: 0,
+            }
+        }
+
+        fn entry(self, name: &str, contents: &[u8]) -> Self {
+            self.raw_entry(name, 0, 0, contents, contents.len() as u32)
+        }
+
+        fn raw_entry(
+            mut self,
+            name: &str,
+            flags: u16,
+            method: u16,
+            contents: &[u8],
+            uncompressed_size: u32,
+        ) -> Self {
+            let offset = self.data.len() as u32;
+            for (header, signature) in [
+                (&mut self.data, ZIP_LOCAL_HEADER),
+                (&mut self.central_dir, ZIP_CENTRAL_HEADER),
+            ] {
+                let is_central = signature == ZIP_CENTRAL_HEADER;
+                header.extend_from_slice(&signature.to_le_bytes());
+                if is_central {
+                    header.extend_from_slice(&20u16.to_le_bytes());
+                }
+                header.extend_from_slice(&20u16.to_le_bytes());
+                header.extend_from_slice(&flags.to_le_bytes());
+                header.extend_from_slice(&method.to_le_bytes());
+                header.extend_from_slice(&[0u8; 8]);
+                header.extend_from_slice(&(contents.len() as u32).to_le_bytes());
+                header.extend_from_slice(&uncompressed_size.to_le_bytes());
+                header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                header.extend_from_slice(&0u16.to_le_bytes());
+                if is_central {
+                    header.extend_from_slice(&[0u8; 10]);
+                    header.extend_from_slice(&offset.to_le_bytes());
+                }
+                header.extend_from_slice(name.as_bytes());
+            }
+            self.data.extend_from_slice(contents);
+            self.num_entries += 1;
+            self
+        }
+
+        fn build(mut self) -> Vec<u8> {
+            let cd_offset = self.data.len() as u32;
+            self.data.extend_from_slice(&self.central_dir);
+            self.data
+                .extend_from_slice(&ZIP_END_OF_CENTRAL_DIR.to_le_bytes());
+            self.data.extend_from_slice(&[0u8; 4]);
+            self.data.extend_from_slice(&self.num_entries.to_le_bytes());
+            self.data.extend_from_slice(&self.num_entries.to_le_bytes());
+            self.data
+                .extend_from_slice(&(self.central_dir.len() as u32).to_le_bytes());
+            self.data.extend_from_slice(&cd_offset.to_le_bytes());
+            self.data.extend_from_slice(&0u16.to_le_bytes());
+            self.data
+        }
+    }
+
+    fn extensions() -> GlobMap<FileExtension> {
+        let mut extensions = GlobMap::new();
+        for (ext, is_bad, is_archive) in [
+            ("exe", true, false),
+            ("pdf", false, false),
+            ("txt", false, false),
+            ("zip", false, true),
+        ] {
+            extensions.insert_entry(
+                ext.to_string(),
+                FileExtension {
+                    is_bad,
+                    is_archive,
+                    ..Default::default()
+                },
+            );
+        }
+        extensions
+    }
+
+    fn inspect(contents: &[u8], max_depth: usize) -> Option<ArchiveReport> {
+        inspect_archive(
+            contents,
+            &ArchiveConfig {
+                max_depth,
+                max_entries: 100,
+                max_ratio: 100,
+                max_size: 1024 * 1024,
+                timeout: Duration::from_secs(60),
+            },
+            &extensions(),
+            Instant::now() + Duration::from_secs(60),
+        )
+    }
+
+    #[test]
+    fn inspect_zip_archives() {
+        assert_eq!(inspect(b"not an archive", 3), None);
+
+        // Plain archive
+        let zip = ZipBuilder::new()
+            .entry("docs/readme.txt", b"hello")
+            .entry("report.pdf", b"%PDF")
+            .build();
+        assert_eq!(
+            inspect(&zip, 3),
+            Some(ArchiveReport {
+                entries: 2,
+                ..Default::default()
+            })
+        );
+
+        // Executables nested three levels deep are only found when the depth allows it
+        let nested = ZipBuilder::new()
+            .entry(
+                "level2.zip",
+                &ZipBuilder::new()
+                    .entry(
+                        "level3.zip",
+                        &ZipBuilder::new().entry("invoice.pdf.exe", b"MZ").build(),
+                    )
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            inspect(&nested, 3),
+            Some(ArchiveReport {
+                entries: 3,
+                has_executable: true,
+                has_double_extension: true,
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            inspect(&nested, 2),
+            Some(ArchiveReport {
+                entries: 2,
+                ..Default::default()
+            })
+        );
+
+        // Encrypted entries and bombs are reported from the central directory
+        let zip = ZipBuilder::new()
+            .raw_entry("secret.txt", 1, 8, &[0u8; 32], 32)
+            .raw_entry("zeros.txt", 0, 8, &[0u8; 1024], 10 * 1024 * 1024)
+            .build();
+        assert_eq!(
+            inspect(&zip, 3),
+            Some(ArchiveReport {
+                entries: 2,
+                has_encrypted: true,
+                is_bomb_suspect: true,
+                ..Default::default()
+            })
+        );
+
+        // Truncated archives are reported as corrupt
+        let zip = ZipBuilder::new().entry("readme.txt", b"hello").build();
+        assert_eq!(
+            inspect(&zip[..zip.len() - 30], 3),
+            Some(ArchiveReport {
+                is_corrupt: true,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn inspect_rar_archives() {
+        // RAR 5.0 archive containing an encrypted executable
+        let mut file_header = vec![
+            0x02, 0x03, 0x02, 0x40, 0x00, 0x80, 0x04, 0x00, 0x00, 0x00, 0x0b,
+        ];
+        file_header.extend_from_slice(b"invoice.exe");
+        file_header.extend_from_slice(&[0x01, 0x01]);
+        let mut rar = b"Rar!\x1a\x07\x01\x00".to_vec();
+        rar.extend_from_slice(&[0u8; 4]);
+        rar.push(file_header.len() as u8);
+        rar.extend_from_slice(&file_header);
+        rar.extend_from_slice(&[0u8; 0x40]);
+        rar.extend_from_slice(&[0u8, 0, 0, 0, 0x03, 0x05, 0x00, 0x00]);
+
+        assert_eq!(
+            inspect(&rar, 3),
+            Some(ArchiveReport {
+                entries: 1,
+                has_executable: true,
+                has_encrypted: true,
+                ..Default::default()
+            })
+        );
+    }
+}
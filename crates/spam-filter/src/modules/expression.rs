@@ -49,6 +49,9 @@ impl<T: ResolveVariable> ResolveVariable for SpamFilterResolver<'_, T> {
             ExpressionVariable::Asn => self.ctx.input.asn.unwrap_or_default().into(),
             ExpressionVariable::Country => self.ctx.input.country.unwrap_or_default().into(),
             ExpressionVariable::IsTls => self.ctx.input.is_tls.into(),
+            ExpressionVariable::SenderReputation => {
+                self.ctx.result.sender_reputation.unwrap_or(1.0).into()
+            }
             ExpressionVariable::EnvFrom => self.ctx.output.env_from_addr.address.as_str().into(),
             ExpressionVariable::EnvFromLocal => {
                 self.ctx.output.env_from_addr.local_part.as_str().into()
@@ -4,9 +4,11 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod archive;
 pub mod classifier;
 pub mod dnsbl;
 pub mod expression;
 pub mod html;
 pub mod pyzor;
+pub mod reputation;
 pub mod sanitize;
@@ -0,0 +1,264 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use common::{KV_SENDER_REPUTATION, Server, config::mailstore::spamfilter::SenderReputationConfig};
+use std::{future::Future, net::IpAddr};
+use store::{dispatch::lookup::KeyValue, write::now};
+use trc::AddContext;
+
+const COUNTER_MESSAGES: u8 = 0;
+const COUNTER_EVENTS: u8 = 1;
+
+// Spam verdicts and bounces share a counter, bounces are kept in the upper bits
+const BOUNCE_SHIFT: u32 = 32;
+const SPAM_MASK: i64 = (1 << BOUNCE_SHIFT) - 1;
+
+// Score of senders without any history
+pub const NEUTRAL_SCORE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationSubject<'x> {
+    Domain(&'x str),
+    Ip(IpAddr),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReputationEvent {
+    Ham,
+    Spam,
+    Bounce,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReputationStats {
+    pub messages: f64,
+    pub spam: f64,
+    pub bounces: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SenderReputation {
+    pub score: f64,
+    pub is_known: bool,
+}
+
+pub trait SenderReputationStore: Sync + Send {
+    fn sender_reputation(
+        &self,
+        domain: Option<&str>,
+        ip: IpAddr,
+    ) -> impl Future<Output = trc::Result<Option<SenderReputation>>> + Send;
+
+    fn sender_reputation_stats(
+        &self,
+        subject: ReputationSubject<'_>,
+    ) -> impl Future<Output = trc::Result<ReputationStats>> + Send;
+
+    fn sender_reputation_record(
+        &self,
+        domain: Option<&str>,
+        ip: IpAddr,
+        event: ReputationEvent,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+
+    fn sender_reputation_reset(
+        &self,
+        subject: ReputationSubject<'_>,
+    ) -> impl Future<Output = trc::Result<()>> + Send;
+}
+
+impl SenderReputationStore for Server {
+    async fn sender_reputation(
+        &self,
+        domain: Option<&str>,
+        ip: IpAddr,
+    ) -> trc::Result<Option<SenderReputation>> {
+        let Some(config) = &self.core.spam.reputation else {
+            return Ok(None);
+        };
+
+        // Subjects with enough history take precedence, the worst of them wins
+        let mut known: Option<f64> = None;
+        let mut unknown: Option<f64> = None;
+        for subject in domain
+            .filter(|domain| !domain.is_empty())
+            .map(ReputationSubject::Domain)
+            .into_iter()
+            .chain([ReputationSubject::Ip(ip)])
+        {
+            let stats = self.sender_reputation_stats(subject).await?;
+            let score = stats.score(config.min_messages);
+            if stats.messages >= config.min_messages as f64 {
+                known = Some(known.map_or(score, |known| known.min(score)));
+            } else {
+                unknown = Some(unknown.map_or(score, |unknown| unknown.min(score)));
+            }
+        }
+
+        Ok(Some(match known {
+            Some(score) => SenderReputation {
+                score,
+                is_known: true,
+            },
+            None => SenderReputation {
+                score: unknown.unwrap_or(NEUTRAL_SCORE),
+                is_known: false,
+            },
+        }))
+    }
+
+    async fn sender_reputation_stats(
+        &self,
+        subject: ReputationSubject<'_>,
+    ) -> trc::Result<ReputationStats> {
+        let Some(config) = &self.core.spam.reputation else {
+            return Ok(ReputationStats::default());
+        };
+
+        // Statistics from the previous window decay as the current one progresses
+        let now = now();
+        let period = now / config.window;
+        let decay = 1.0 - (now % config.window) as f64 / config.window as f64;
+        let store = self.in_memory_store();
+        let mut stats = ReputationStats::default();
+        for (period, weight) in [(period, 1.0), (period.saturating_sub(1), decay)] {
+            let messages = store
+                .counter_get(reputation_key(subject, period, COUNTER_MESSAGES))
+                .await
+                .caused_by(trc::location!())?
+                .max(0);
+            let events = store
+                .counter_get(reputation_key(subject, period, COUNTER_EVENTS))
+                .await
+                .caused_by(trc::location!())?
+                .max(0);
+            stats.messages += messages as f64 * weight;
+            stats.spam += (events & SPAM_MASK) as f64 * weight;
+            stats.bounces += (events >> BOUNCE_SHIFT) as f64 * weight;
+        }
+
+        Ok(stats)
+    }
+
+    async fn sender_reputation_record(
+        &self,
+        domain: Option<&str>,
+        ip: IpAddr,
+        event: ReputationEvent,
+    ) -> trc::Result<()> {
+        let Some(config) = &self.core.spam.reputation else {
+            return Ok(());
+        };
+
+        let now = now();
+        let period = now / config.window;
+        let expires = (period + 2) * config.window - now;
+        let store = self.in_memory_store();
+        for subject in domain
+            .filter(|domain| !domain.is_empty())
+            .map(ReputationSubject::Domain)
+            .into_iter()
+            .chain([ReputationSubject::Ip(ip)])
+        {
+            if matches!(event, ReputationEvent::Ham | ReputationEvent::Spam) {
+                store
+                    .counter_incr(
+                        KeyValue::new(reputation_key(subject, period, COUNTER_MESSAGES), 1)
+                            .expires(expires),
+                        false,
+                    )
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+
+            let events = match event {
+                ReputationEvent::Ham => continue,
+                ReputationEvent::Spam => 1,
+                ReputationEvent::Bounce => 1 << BOUNCE_SHIFT,
+            };
+            store
+                .counter_incr(
+                    KeyValue::new(reputation_key(subject, period, COUNTER_EVENTS), events)
+                        .expires(expires),
+                    false,
+                )
+                .await
+                .caused_by(trc::location!())?;
+        }
+
+        Ok(())
+    }
+
+    async fn sender_reputation_reset(&self, subject: ReputationSubject<'_>) -> trc::Result<()> {
+        let Some(config) = &self.core.spam.reputation else {
+            return Ok(());
+        };
+
+        let period = now() / config.window;
+        for period in [period, period.saturating_sub(1)] {
+            for counter in [COUNTER_MESSAGES, COUNTER_EVENTS] {
+                self.in_memory_store()
+                    .counter_delete(reputation_key(subject, period, counter))
+                    .await
+                    .caused_by(trc::location!())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ReputationStats {
+    // Ratio of clean messages, senders with little history start from a
+    // neutral score that fades out as they reach the minimum number of messages.
+    pub fn score(&self, min_messages: u64) -> f64 {
+        let min_messages = min_messages.max(1) as f64;
+        let clean = (self.messages - self.spam - self.bounces).max(0.0);
+        let prior = (min_messages - self.messages).max(0.0) * NEUTRAL_SCORE;
+        (clean + prior) / self.messages.max(min_messages)
+    }
+}
+
+impl SenderReputation {
+    pub fn tag(&self, config: &SenderReputationConfig) -> Option<&'static str> {
+        if !self.is_known {
+            Some("SENDER_REP_NEW")
+        } else if self.score >= config.good_score {
+            Some("SENDER_REP_GOOD")
+        } else if self.score <= config.bad_score {
+            Some("SENDER_REP_BAD")
+        } else {
+            None
+        }
+    }
+
+    pub fn is_trusted(&self, config: &SenderReputationConfig) -> bool {
+        self.is_known && self.score >= config.good_score
+    }
+}
+
+fn reputation_key(subject: ReputationSubject<'_>, period: u64, counter: u8) -> Vec<u8> {
+    let mut key = Vec::with_capacity(28);
+    key.push(KV_SENDER_REPUTATION);
+    key.push(counter);
+    key.extend_from_slice(&period.to_be_bytes());
+    match subject {
+        ReputationSubject::Domain(domain) => {
+            key.push(0);
+            key.extend_from_slice(domain.as_bytes());
+        }
+        ReputationSubject::Ip(IpAddr::V4(ip)) => {
+            key.push(1);
+            key.extend_from_slice(&ip.octets());
+        }
+        ReputationSubject::Ip(IpAddr::V6(ip)) => {
+            // IPv6 senders are tracked by their /64 network
+            key.push(2);
+            key.extend_from_slice(&ip.octets()[..8]);
+        }
+    }
+    key
+}
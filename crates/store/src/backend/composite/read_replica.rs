@@ -10,75 +10,98 @@
 
 use crate::{
     Deserialize, IterateParams, Key, Store, ValueKey,
-    search::{IndexDocument, SearchComparator, SearchDocumentId, SearchFilter, SearchQuery},
-    write::{AssignedIds, Batch, SearchIndex, ValueClass},
+    query::stream::RowSender,
+    search::{
+        IndexDocument, SearchComparator, SearchDocumentId, SearchField, SearchFilter,
+        SearchOperator, SearchQuery, SearchValue,
+    },
+    write::{AnyKey, AssignedIds, Batch, Operation, SearchIndex, ValueClass},
 };
+use ahash::AHashMap;
+use parking_lot::Mutex;
 use std::{
     future::Future,
     ops::Range,
     sync::{
-        Arc,
-        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
     },
+    time::{Duration, Instant},
 };
 
+// Replicas are probed this often, failed replicas stay out of rotation until
+// a probe succeeds
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+// Expired write timestamps are pruned once this many accounts are tracked
+const MAX_TRACKED_ACCOUNTS: usize = 1024;
+
 pub struct SQLReadReplica {
     primary: Store,
     replicas: Vec<Store>,
-    last_used_replica: AtomicUsize,
+    router: ReplicaRouter,
+}
+
+struct ReplicaRouter {
+    next_replica: AtomicUsize,
+    is_healthy: Box<[AtomicBool]>,
+    last_writes: Mutex<AHashMap<u32, Instant>>,
+    stickiness: Duration,
 }
 
 impl SQLReadReplica {
-    pub fn open(primary: Store, replicas: Vec<Store>) -> Result<Store, String> {
-        Ok(Store::SQLReadReplica(Arc::new(Self {
+    /// Reads for an account are served by the primary for `stickiness`
+    /// after the account was written to.
+    pub fn open(
+        primary: Store,
+        replicas: Vec<Store>,
+        stickiness: Duration,
+    ) -> Result<Store, String> {
+        let store = Arc::new(Self {
+            router: ReplicaRouter::new(replicas.len(), stickiness),
             primary,
             replicas,
-            last_used_replica: AtomicUsize::new(0),
-        })))
+        });
+        tokio::spawn(probe_replicas(Arc::downgrade(&store)));
+
+        Ok(Store::SQLReadReplica(store))
     }
 
-    async fn run_op<'x, F, T, R>(&'x self, f: F) -> trc::Result<T>
+    async fn run_op<'x, F, T, R>(&'x self, account_id: Option<u32>, f: F) -> trc::Result<T>
     where
         F: Fn(&'x Store) -> R,
         R: Future<Output = trc::Result<T>>,
         T: 'static,
     {
-        let mut last_error = None;
-        for store in [
-            &self.replicas
-                [self.last_used_replica.fetch_add(1, Ordering::Relaxed) % self.replicas.len()],
-            &self.primary,
-        ] {
-            match f(store).await {
+        if let Some(idx) = self.router.route(account_id) {
+            match f(&self.replicas[idx]).await {
                 Ok(result) => return Ok(result),
-                Err(err) => {
-                    if err.is_assertion_failure() {
-                        return Err(err);
-                    } else {
-                        last_error = Some(err);
-                    }
-                }
+                Err(err) if err.is_assertion_failure() => return Err(err),
+                Err(err) => self.replica_failed(idx, err),
             }
         }
 
-        Err(last_error.unwrap())
+        f(&self.primary).await
+    }
+
+    fn replica_failed(&self, idx: usize, err: trc::Error) {
+        self.router.set_healthy(idx, false);
+        trc::error!(
+            err.details("Read replica failed, falling back to primary")
+                .ctx(trc::Key::Id, idx)
+        );
     }
 
     pub async fn get_blob(&self, key: &[u8], range: Range<usize>) -> trc::Result<Option<Vec<u8>>> {
-        self.run_op(move |store| {
-            let range = range.clone();
-
-            async move {
-                match store {
-                    #[cfg(feature = "postgres")]
-                    Store::PostgreSQL(store) => store.get_blob(key, range).await,
-                    #[cfg(feature = "mysql")]
-                    Store::MySQL(store) => store.get_blob(key, range).await,
-                    _ => panic!("Invalid store type"),
-                }
+        if let Some(idx) = self.router.route(None) {
+            match get_blob(&self.replicas[idx], key, range.clone()).await {
+                // Recently written blobs might not have been replicated yet
+                Ok(Some(result)) => return Ok(Some(result)),
+                Ok(None) => (),
+                Err(err) => self.replica_failed(idx, err),
             }
-        })
-        .await
+        }
+
+        get_blob(&self.primary, key, range).await
     }
 
     pub async fn put_blob(&self, key: &[u8], data: &[u8]) -> trc::Result<()> {
@@ -101,41 +124,29 @@ impl SQLReadReplica {
         }
     }
 
+    // Point lookups are usually followed by a write that depends on them, so
+    // they are always served by the primary to avoid acting on stale data.
     pub async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
     where
         U: Deserialize + 'static,
     {
-        self.run_op(move |store| {
-            let key = key.clone();
-
-            async move {
-                match store {
-                    #[cfg(feature = "postgres")]
-                    Store::PostgreSQL(store) => store.get_value(key).await,
-                    #[cfg(feature = "mysql")]
-                    Store::MySQL(store) => store.get_value(key).await,
-                    _ => panic!("Invalid store type"),
-                }
-            }
-        })
-        .await
+        match &self.primary {
+            #[cfg(feature = "postgres")]
+            Store::PostgreSQL(store) => store.get_value(key).await,
+            #[cfg(feature = "mysql")]
+            Store::MySQL(store) => store.get_value(key).await,
+            _ => panic!("Invalid store type"),
+        }
     }
 
     pub(crate) async fn key_exists(&self, key: impl Key) -> trc::Result<bool> {
-        self.run_op(move |store| {
-            let key = key.clone();
-
-            async move {
-                match store {
-                    #[cfg(feature = "postgres")]
-                    Store::PostgreSQL(store) => store.key_exists(key).await,
-                    #[cfg(feature = "mysql")]
-                    Store::MySQL(store) => store.key_exists(key).await,
-                    _ => panic!("Invalid store type"),
-                }
-            }
-        })
-        .await
+        match &self.primary {
+            #[cfg(feature = "postgres")]
+            Store::PostgreSQL(store) => store.key_exists(key).await,
+            #[cfg(feature = "mysql")]
+            Store::MySQL(store) => store.key_exists(key).await,
+            _ => panic!("Invalid store type"),
+        }
     }
 
     pub async fn iterate<T: Key>(
@@ -143,68 +154,82 @@ impl SQLReadReplica {
         params: IterateParams<T>,
         mut cb: impl for<'x> FnMut(&'x [u8], &'x [u8]) -> trc::Result<bool> + Sync + Send,
     ) -> trc::Result<()> {
-        let mut last_error = None;
-        for store in [
-            &self.replicas
-                [self.last_used_replica.fetch_add(1, Ordering::Relaxed) % self.replicas.len()],
-            &self.primary,
-        ] {
-            match match store {
-                #[cfg(feature = "postgres")]
-                Store::PostgreSQL(store) => store.iterate(params.clone(), &mut cb).await,
-                #[cfg(feature = "mysql")]
-                Store::MySQL(store) => store.iterate(params.clone(), &mut cb).await,
-                _ => panic!("Invalid store type"),
-            } {
-                Ok(result) => return Ok(result),
-                Err(err) => {
-                    last_error = Some(err);
-                }
+        if params.read_only
+            && let Some(idx) = self.router.route(params.begin.account_id())
+        {
+            let mut has_rows = false;
+            match iterate(&self.replicas[idx], params.clone(), |key, value| {
+                has_rows = true;
+                cb(key, value)
+            })
+            .await
+            {
+                Ok(()) => return Ok(()),
+                // Rows already handed to the callback cannot be taken back
+                Err(err) if has_rows => return Err(err),
+                Err(err) => self.replica_failed(idx, err),
             }
         }
 
-        Err(last_error.unwrap())
+        iterate(&self.primary, params, cb).await
+    }
+
+    // Streams are served by the primary, rows handed to the caller could not be
+    // taken back if a replica failed halfway through the range
+    pub(crate) fn stream(&self, params: IterateParams<AnyKey<Vec<u8>>>, sender: RowSender) {
+        match &self.primary {
+            #[cfg(feature = "postgres")]
+            Store::PostgreSQL(store) => store.clone().stream(params, sender),
+            #[cfg(feature = "mysql")]
+            Store::MySQL(store) => store.clone().stream(params, sender),
+            _ => panic!("Invalid store type"),
+        }
     }
 
     pub async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
     ) -> trc::Result<i64> {
-        let key = key.into();
-        self.run_op(move |store| {
-            let key = key.clone();
-
-            async move {
-                match store {
-                    #[cfg(feature = "postgres")]
-                    Store::PostgreSQL(store) => store.get_counter(key).await,
-                    #[cfg(feature = "mysql")]
-                    Store::MySQL(store) => store.get_counter(key).await,
-                    _ => panic!("Invalid store type"),
-                }
-            }
-        })
-        .await
+        match &self.primary {
+            #[cfg(feature = "postgres")]
+            Store::PostgreSQL(store) => store.get_counter(key).await,
+            #[cfg(feature = "mysql")]
+            Store::MySQL(store) => store.get_counter(key).await,
+            _ => panic!("Invalid store type"),
+        }
     }
 
     pub async fn write(&self, batch: Batch<'_>) -> trc::Result<AssignedIds> {
-        match &self.primary {
+        let account_ids = batch
+            .ops
+            .iter()
+            .filter_map(|op| match op {
+                Operation::AccountId { account_id } => Some(*account_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let result = match &self.primary {
             #[cfg(feature = "postgres")]
             Store::PostgreSQL(store) => store.write(batch).await,
             #[cfg(feature = "mysql")]
             Store::MySQL(store) => store.write(batch).await,
             _ => panic!("Invalid store type"),
-        }
+        };
+        self.router.record_writes(account_ids);
+        result
     }
 
     pub async fn delete_range(&self, from: impl Key, to: impl Key) -> trc::Result<()> {
-        match &self.primary {
+        let account_id = from.account_id();
+        let result = match &self.primary {
             #[cfg(feature = "postgres")]
             Store::PostgreSQL(store) => store.delete_range(from, to).await,
             #[cfg(feature = "mysql")]
             Store::MySQL(store) => store.delete_range(from, to).await,
             _ => panic!("Invalid store type"),
-        }
+        };
+        self.router.record_writes(account_id);
+        result
     }
 
     pub async fn purge_store(&self) -> trc::Result<()> {
@@ -218,41 +243,232 @@ impl SQLReadReplica {
     }
 
     pub async fn index(&self, documents: Vec<IndexDocument>) -> trc::Result<()> {
-        match &self.primary {
+        let account_ids = documents
+            .iter()
+            .filter_map(
+                |document| match document.fields.get(&SearchField::AccountId) {
+                    Some(SearchValue::Uint(account_id)) => Some(*account_id as u32),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>();
+        let result = match &self.primary {
             #[cfg(feature = "postgres")]
             Store::PostgreSQL(store) => store.index(documents).await,
             #[cfg(feature = "mysql")]
             Store::MySQL(store) => store.index(documents).await,
             _ => panic!("Invalid store type"),
-        }
+        };
+        self.router.record_writes(account_ids);
+        result
     }
 
     pub async fn unindex(&self, query: SearchQuery) -> trc::Result<u64> {
-        match &self.primary {
+        let account_id = filter_account_id(&query.filters);
+        let result = match &self.primary {
             #[cfg(feature = "postgres")]
             Store::PostgreSQL(store) => store.unindex(query).await,
             #[cfg(feature = "mysql")]
             Store::MySQL(store) => store.unindex(query).await,
             _ => panic!("Invalid store type"),
-        }
+        };
+        self.router.record_writes(account_id);
+        result
     }
 
-    pub async fn query<R: SearchDocumentId>(
+    pub async fn query<R: SearchDocumentId + 'static>(
         &self,
         index: SearchIndex,
         filters: &[SearchFilter],
         sort: &[SearchComparator],
     ) -> trc::Result<Vec<R>> {
-        match &self.primary {
-            #[cfg(feature = "postgres")]
-            Store::PostgreSQL(store) => store.query(index, filters, sort).await,
-            #[cfg(feature = "mysql")]
-            Store::MySQL(store) => store.query(index, filters, sort).await,
-            _ => panic!("Invalid store type"),
-        }
+        self.run_op(filter_account_id(filters), move |store| async move {
+            match store {
+                #[cfg(feature = "postgres")]
+                Store::PostgreSQL(store) => store.query(index, filters, sort).await,
+                #[cfg(feature = "mysql")]
+                Store::MySQL(store) => store.query(index, filters, sort).await,
+                _ => panic!("Invalid store type"),
+            }
+        })
+        .await
     }
 
     pub fn primary_store(&self) -> &Store {
         &self.primary
     }
 }
+
+impl ReplicaRouter {
+    fn new(num_replicas: usize, stickiness: Duration) -> Self {
+        Self {
+            next_replica: AtomicUsize::new(0),
+            is_healthy: (0..num_replicas).map(|_| AtomicBool::new(true)).collect(),
+            last_writes: Mutex::new(AHashMap::new()),
+            stickiness,
+        }
+    }
+
+    // Returns the replica that should serve a read, or None if the primary should
+    fn route(&self, account_id: Option<u32>) -> Option<usize> {
+        if account_id.is_some_and(|account_id| self.is_sticky(account_id)) {
+            return None;
+        }
+
+        let num_replicas = self.is_healthy.len();
+        let start = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        (0..num_replicas)
+            .map(|offset| start.wrapping_add(offset) % num_replicas)
+            .find(|&idx| self.is_healthy[idx].load(Ordering::Relaxed))
+    }
+
+    fn is_sticky(&self, account_id: u32) -> bool {
+        self.last_writes
+            .lock()
+            .get(&account_id)
+            .is_some_and(|written_at| written_at.elapsed() < self.stickiness)
+    }
+
+    fn record_writes(&self, account_ids: impl IntoIterator<Item = u32>) {
+        let now = Instant::now();
+        let mut last_writes = self.last_writes.lock();
+        for account_id in account_ids {
+            last_writes.insert(account_id, now);
+        }
+        if last_writes.len() > MAX_TRACKED_ACCOUNTS {
+            last_writes.retain(|_, written_at| now.duration_since(*written_at) < self.stickiness);
+        }
+    }
+
+    // Returns whether the replica was healthy before the update
+    fn set_healthy(&self, idx: usize, is_healthy: bool) -> bool {
+        self.is_healthy[idx].swap(is_healthy, Ordering::Relaxed)
+    }
+}
+
+async fn probe_replicas(store: Weak<SQLReadReplica>) {
+    loop {
+        // The probe stops once the store is dropped
+        let Some(store) = store.upgrade() else {
+            break;
+        };
+        for (idx, replica) in store.replicas.iter().enumerate() {
+            match replica.health_check().await {
+                Ok(()) => {
+                    store.router.set_healthy(idx, true);
+                }
+                Err(err) => {
+                    if store.router.set_healthy(idx, false) {
+                        trc::error!(
+                            err.details("Read replica health check failed")
+                                .ctx(trc::Key::Id, idx)
+                        );
+                    }
+                }
+            }
+        }
+        drop(store);
+
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+    }
+}
+
+fn filter_account_id(filters: &[SearchFilter]) -> Option<u32> {
+    filters.iter().find_map(|filter| match filter {
+        SearchFilter::Operator {
+            field: SearchField::AccountId,
+            op: SearchOperator::Equal,
+            value: SearchValue::Uint(account_id),
+        } => Some(*account_id as u32),
+        _ => None,
+    })
+}
+
+async fn get_blob(store: &Store, key: &[u8], range: Range<usize>) -> trc::Result<Option<Vec<u8>>> {
+    match store {
+        #[cfg(feature = "postgres")]
+        Store::PostgreSQL(store) => store.get_blob(key, range).await,
+        #[cfg(feature = "mysql")]
+        Store::MySQL(store) => store.get_blob(key, range).await,
+        _ => panic!("Invalid store type"),
+    }
+}
+
+async fn iterate<T: Key>(
+    store: &Store,
+    params: IterateParams<T>,
+    cb: impl for<'x> FnMut(&'x [u8], &'x [u8]) -> trc::Result<bool> + Sync + Send,
+) -> trc::Result<()> {
+    match store {
+        #[cfg(feature = "postgres")]
+        Store::PostgreSQL(store) => store.iterate(params, cb).await,
+        #[cfg(feature = "mysql")]
+        Store::MySQL(store) => store.iterate(params, cb).await,
+        _ => panic!("Invalid store type"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn replica_rotation() {
+        let router = ReplicaRouter::new(2, WINDOW);
+        let routes = (0..4).map(|_| router.route(None)).collect::<Vec<_>>();
+        assert_eq!(routes, [Some(0), Some(1), Some(0), Some(1)]);
+
+        // Failed replicas are skipped until a health check succeeds
+        assert!(router.set_healthy(0, false));
+        assert!((0..4).all(|_| router.route(Some(1)) == Some(1)));
+        assert!(router.set_healthy(1, false));
+        assert_eq!(router.route(None), None);
+        assert!(!router.set_healthy(0, true));
+        assert_eq!(router.route(None), Some(0));
+
+        assert_eq!(ReplicaRouter::new(0, WINDOW).route(None), None);
+    }
+
+    #[test]
+    fn write_stickiness() {
+        let router = ReplicaRouter::new(1, WINDOW);
+        assert_eq!(router.route(Some(1)), Some(0));
+
+        // Reads go to the primary right after a write to the same account
+        router.record_writes([1, 2]);
+        assert_eq!(router.route(Some(1)), None);
+        assert_eq!(router.route(Some(2)), None);
+        assert_eq!(router.route(Some(3)), Some(0));
+        assert_eq!(router.route(None), Some(0));
+
+        // Replicas serve the account again once the window has passed
+        std::thread::sleep(WINDOW);
+        assert_eq!(router.route(Some(1)), Some(0));
+        assert_eq!(router.route(Some(2)), Some(0));
+
+        // Expired entries are pruned
+        router.record_writes(0..=MAX_TRACKED_ACCOUNTS as u32);
+        std::thread::sleep(WINDOW);
+        router.record_writes([u32::MAX]);
+        assert_eq!(router.last_writes.lock().len(), 1);
+    }
+
+    #[test]
+    fn search_account_id() {
+        assert_eq!(
+            filter_account_id(&[
+                SearchFilter::And,
+                SearchFilter::eq(SearchField::DocumentId, 7u32),
+                SearchFilter::eq(SearchField::AccountId, 42u32),
+                SearchFilter::End,
+            ]),
+            Some(42)
+        );
+        assert_eq!(
+            filter_account_id(&[SearchFilter::gt(SearchField::AccountId, 42u32)]),
+            None
+        );
+    }
+}
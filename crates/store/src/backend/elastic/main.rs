@@ -113,6 +113,17 @@ impl ElasticSearchStore {
         }
     }
 
+    pub async fn ping(&self) -> trc::Result<()> {
+        assert_success(
+            self.client
+                .get(format!("{}/_cluster/health?local=true", self.url))
+                .send()
+                .await,
+        )
+        .await
+        .map(|_| ())
+    }
+
     #[cfg(feature = "test_mode")]
     pub async fn drop_indexes(&self) -> trc::Result<()> {
         use crate::write::SearchIndex;
@@ -5,7 +5,12 @@
  */
 
 use super::EphemeralStore;
-use crate::{Deserialize, IterateParams, Key, ValueKey, write::ValueClass};
+use crate::{
+    Deserialize, IterateParams, Key, ValueKey,
+    query::stream::RowSender,
+    write::{AnyKey, ValueClass},
+};
+use std::{ops::Bound, sync::Arc};
 
 impl EphemeralStore {
     pub(crate) async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
@@ -64,6 +69,57 @@ impl EphemeralStore {
         Ok(())
     }
 
+    // The map is locked one batch at a time, each batch resumes after the
+    // last key handed to the stream
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::spawn(async move {
+            let mut last_key: Option<Vec<u8>> = None;
+            loop {
+                let last_read = {
+                    let state = self.state.read();
+                    let Some(map) = state.subspaces.get(&params.begin.subspace) else {
+                        break;
+                    };
+                    let (begin, end) = match (&last_key, params.ascending) {
+                        (Some(last_key), true) => {
+                            (Bound::Excluded(last_key), Bound::Included(&params.end.key))
+                        }
+                        (Some(last_key), false) => (
+                            Bound::Included(&params.begin.key),
+                            Bound::Excluded(last_key),
+                        ),
+                        (None, _) => (
+                            Bound::Included(&params.begin.key),
+                            Bound::Included(&params.end.key),
+                        ),
+                    };
+                    let rows = map.range::<Vec<u8>, _>((begin, end));
+                    if params.ascending {
+                        fill_batch(rows, &mut sender)
+                    } else {
+                        fill_batch(rows.rev(), &mut sender)
+                    }
+                };
+
+                match last_read {
+                    Some(key) if !params.first => {
+                        last_key = Some(key);
+                        if !sender.flush().await {
+                            return;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+
+            sender.finish(Ok(())).await;
+        });
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
@@ -84,3 +140,16 @@ impl EphemeralStore {
         }
     }
 }
+
+// Returns the last key read if the batch was filled before the range ended
+fn fill_batch<'x>(
+    rows: impl Iterator<Item = (&'x Vec<u8>, &'x Vec<u8>)>,
+    sender: &mut RowSender,
+) -> Option<Vec<u8>> {
+    for (key, value) in rows {
+        if sender.push(key, value) {
+            return Some(key.clone());
+        }
+    }
+    None
+}
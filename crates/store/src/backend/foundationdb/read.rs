@@ -10,7 +10,8 @@ use super::{
 use crate::{
     Deserialize, IterateParams, Key, ValueKey, WITH_SUBSPACE,
     backend::deserialize_i64_le,
-    write::{MAX_COMMIT_ATTEMPTS, MAX_COMMIT_TIME, ValueClass, key::KeySerializer},
+    query::stream::RowSender,
+    write::{AnyKey, MAX_COMMIT_ATTEMPTS, MAX_COMMIT_TIME, ValueClass, key::KeySerializer},
 };
 use foundationdb::{
     FdbError, KeySelector, RangeOption, Transaction,
@@ -18,7 +19,7 @@ use foundationdb::{
     options::{self},
 };
 use futures::TryStreamExt;
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 #[allow(dead_code)]
 pub(crate) enum ChunkedValue {
@@ -226,6 +227,135 @@ impl FdbStore {
         Ok(())
     }
 
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::spawn(async move {
+            let result = self.stream_range(&params, &mut sender).await;
+            sender.finish(result).await;
+        });
+    }
+
+    // Reads the range in a single transaction for as long as it is valid, once the
+    // caller is too slow for the transaction to stay open the read resumes in a new
+    // one after the last row handed to the stream.
+    async fn stream_range(
+        &self,
+        params: &IterateParams<AnyKey<Vec<u8>>>,
+        sender: &mut RowSender,
+    ) -> trc::Result<()> {
+        let begin = params.begin.serialize(WITH_SUBSPACE);
+        let end = params.end.serialize(WITH_SUBSPACE);
+        let mut retry_count = 0;
+        let start = Instant::now();
+        let mut last_key = vec![];
+
+        loop {
+            let (begin_selector, end_selector) = match (last_key.is_empty(), params.ascending) {
+                (true, _) => (
+                    KeySelector::first_greater_or_equal(&begin),
+                    KeySelector::first_greater_than(&end),
+                ),
+                (false, true) => (
+                    KeySelector::first_greater_than(&last_key),
+                    KeySelector::first_greater_than(&end),
+                ),
+                (false, false) => (
+                    KeySelector::first_greater_or_equal(&begin),
+                    KeySelector::first_greater_or_equal(&last_key),
+                ),
+            };
+
+            let trx = self.read_trx().await?;
+            let mut values = trx.get_ranges(
+                RangeOption {
+                    begin: begin_selector,
+                    end: end_selector,
+                    mode: options::StreamingMode::Iterator,
+                    reverse: !params.ascending,
+                    ..Default::default()
+                },
+                true,
+            );
+
+            let mut chunked_key: Option<ChunkedValueCollector> = None;
+            let mut chunk_end = vec![];
+            let mut has_progress = false;
+            let err = loop {
+                match values.try_next().await {
+                    Ok(Some(values)) => {
+                        for value in values.iter() {
+                            let key = value.key();
+                            let cb_key = key.get(1..).unwrap_or_default();
+                            let cb_value = value.value();
+
+                            if let Some(chunk) = &mut chunked_key {
+                                if chunk.key.len() + 1 == cb_key.len()
+                                    && cb_key[..chunk.key.len()] == chunk.key[..]
+                                {
+                                    // This is a chunk of the current value
+                                    chunk.bytes.extend_from_slice(cb_value);
+                                    chunk_end.clear();
+                                    chunk_end.extend_from_slice(key);
+                                    continue;
+                                } else if let Some(chunk) = chunked_key.take() {
+                                    if !push_row(sender, &chunk.key, &chunk.bytes, params.first)
+                                        .await
+                                    {
+                                        return Ok(());
+                                    }
+                                    std::mem::swap(&mut last_key, &mut chunk_end);
+                                    has_progress = true;
+                                }
+                            }
+
+                            if cb_value.len() < MAX_VALUE_SIZE {
+                                if !push_row(sender, cb_key, cb_value, params.first).await {
+                                    return Ok(());
+                                }
+                                last_key.clear();
+                                last_key.extend_from_slice(key);
+                                has_progress = true;
+                            } else {
+                                // Start collecting chunked value
+                                chunked_key = Some(ChunkedValueCollector {
+                                    key: cb_key.to_vec(),
+                                    bytes: cb_value.to_vec(),
+                                });
+                                chunk_end.clear();
+                                chunk_end.extend_from_slice(key);
+                            }
+                        }
+                    }
+                    Ok(None) => {
+                        if let Some(chunk) = chunked_key.take() {
+                            push_row(sender, &chunk.key, &chunk.bytes, params.first).await;
+                        }
+                        return Ok(());
+                    }
+                    Err(err) => break err,
+                }
+            };
+            drop(values);
+
+            if err.code() == 1007 && has_progress {
+                // Transaction is too old to perform reads, resume after the last row
+                continue;
+            } else if err.is_retryable()
+                && retry_count < MAX_COMMIT_ATTEMPTS
+                && start.elapsed() < MAX_COMMIT_TIME
+            {
+                self.version.expire();
+                trx.on_error(err).await.map_err(into_error)?;
+                retry_count += 1;
+            } else {
+                return Err(into_error(err));
+            }
+        }
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
@@ -322,3 +452,8 @@ pub(crate) async fn read_chunked_value(
         Ok(ChunkedValue::None)
     }
 }
+
+// Returns false once the stream was dropped or the only requested row was read
+async fn push_row(sender: &mut RowSender, key: &[u8], value: &[u8], first: bool) -> bool {
+    (!sender.push(key, value) || sender.flush().await) && !first
+}
@@ -6,13 +6,8 @@
 
 use super::{HttpStore, HttpStoreConfig, HttpStoreFormat};
 use crate::{InMemoryStore, LookupStores, registry::bootstrap::Bootstrap};
-use ahash::AHashMap;
-use arc_swap::ArcSwap;
 use registry::schema::structs::{self, HttpLookupFormat};
-use std::{
-    collections::hash_map::Entry,
-    sync::atomic::{AtomicBool, AtomicU64},
-};
+use std::collections::hash_map::Entry;
 
 impl LookupStores {
     pub async fn parse_http(&mut self, bp: &mut Bootstrap) {
@@ -35,11 +30,13 @@ impl LookupStores {
                 max_entry_size: http.max_entry_size as usize,
                 format: match http.format {
                     HttpLookupFormat::List => HttpStoreFormat::List,
+                    HttpLookupFormat::Json => HttpStoreFormat::Json,
                     HttpLookupFormat::Csv(csv) => HttpStoreFormat::Csv {
                         index_key: csv.index_key as u32,
                         index_value: csv.index_value.map(|v| v as u32),
                         separator: csv.separator.chars().next().unwrap_or(','),
                         skip_first: csv.skip_first,
+                        all_columns: csv.all_columns,
                     },
                 },
                 id: http.namespace,
@@ -47,14 +44,7 @@ impl LookupStores {
 
             match self.stores.entry(http_config.id.as_str().into()) {
                 Entry::Vacant(entry) => {
-                    let store = HttpStore {
-                        entries: ArcSwap::from_pointee(AHashMap::new()),
-                        expires: AtomicU64::new(0),
-                        in_flight: AtomicBool::new(false),
-                        config: http_config,
-                    };
-
-                    entry.insert(InMemoryStore::Http(store.into()));
+                    entry.insert(InMemoryStore::Http(HttpStore::new(http_config).into()));
                 }
                 Entry::Occupied(_) => {
                     bp.build_error(
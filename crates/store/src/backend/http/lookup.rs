@@ -5,7 +5,7 @@
  */
 
 use std::{
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
     sync::{Arc, atomic::Ordering},
     time::Instant,
 };
@@ -13,11 +13,15 @@ use std::{
 use ahash::AHashMap;
 use compact_str::ToCompactString;
 use rand::seq::IndexedRandom;
+use reqwest::{StatusCode, header};
 use utils::HttpLimitResponse;
 
 use crate::{Value, backend::http::HttpStoreFormat, write::now};
 
-use super::HttpStore;
+use super::{HttpStore, HttpStoreValidators};
+
+// Consecutive failed refreshes before the store is reported as unavailable
+const MAX_FAILURES: u32 = 3;
 
 const BROWSER_USER_AGENTS: [&str; 5] = [
     "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
@@ -70,11 +74,27 @@ impl HttpStoreGet for Arc<HttpStore> {
                 tokio::spawn(async move {
                     let expires = match this.try_refresh().await {
                         Ok(list) => {
-                            this.entries.store(list.into());
+                            // Keep the current entries if the resource was not modified
+                            if let Some(list) = list {
+                                this.entries.store(list.into());
+                            }
+                            this.failures.store(0, Ordering::Relaxed);
                             this.config.refresh
                         }
                         Err(err) => {
+                            // Keep serving the last known good entries
                             trc::error!(err);
+
+                            let failures = this.failures.fetch_add(1, Ordering::Relaxed) + 1;
+                            if failures == MAX_FAILURES {
+                                trc::event!(
+                                    Store(trc::StoreEvent::HttpStoreUnavailable),
+                                    Id = this.config.id.to_compact_string(),
+                                    Url = this.config.url.to_compact_string(),
+                                    Total = failures,
+                                );
+                            }
+
                             this.config.retry
                         }
                     };
@@ -88,26 +108,35 @@ impl HttpStoreGet for Arc<HttpStore> {
 }
 
 impl HttpStore {
-    async fn try_refresh(&self) -> trc::Result<AHashMap<String, Value<'static>>> {
+    async fn try_refresh(&self) -> trc::Result<Option<AHashMap<String, Value<'static>>>> {
         let time = Instant::now();
         let agent = BROWSER_USER_AGENTS.choose(&mut rand::rng()).unwrap();
-        let response = reqwest::Client::builder()
+        let mut request = reqwest::Client::builder()
             .timeout(self.config.timeout)
             .user_agent(*agent)
             .build()
             .unwrap_or_default()
-            .get(&self.config.url)
-            .send()
-            .await
-            .map_err(|err| {
-                trc::StoreEvent::HttpStoreError
-                    .into_err()
-                    .reason(err)
-                    .ctx(trc::Key::Url, self.config.url.to_compact_string())
-                    .details("Failed to build request")
-            })?;
+            .get(&self.config.url);
+        {
+            let validators = self.validators.lock();
+            if let Some(etag) = &validators.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = request.send().await.map_err(|err| {
+            trc::StoreEvent::HttpStoreError
+                .into_err()
+                .reason(err)
+                .ctx(trc::Key::Url, self.config.url.to_compact_string())
+                .details("Failed to build request")
+        })?;
 
-        if !response.status().is_success() {
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        } else if !response.status().is_success() {
             trc::bail!(
                 trc::StoreEvent::HttpStoreError
                     .into_err()
@@ -118,6 +147,17 @@ impl HttpStore {
             );
         }
 
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let last_modified = response
+            .headers()
+            .get(header::LAST_MODIFIED)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
         let bytes = response
             .bytes_with_limit(self.config.max_size)
             .await
@@ -143,16 +183,92 @@ impl HttpStore {
             Box::new(&bytes[..])
         };
 
-        let mut entries = AHashMap::new();
-        for (pos, line) in BufReader::new(reader).lines().enumerate() {
-            let line_ = line.map_err(|err| {
+        let entries = if let HttpStoreFormat::Json = &self.config.format {
+            self.parse_json(reader).map_err(|err| {
+                trc::StoreEvent::HttpStoreError
+                    .into_err()
+                    .reason(err)
+                    .ctx(trc::Key::Url, self.config.url.to_compact_string())
+                    .ctx(trc::Key::Elapsed, time.elapsed())
+                    .details("Failed to parse JSON")
+            })?
+        } else {
+            self.parse_lines(reader).map_err(|err| {
                 trc::StoreEvent::HttpStoreError
                     .into_err()
                     .reason(err)
                     .ctx(trc::Key::Url, self.config.url.to_compact_string())
                     .ctx(trc::Key::Elapsed, time.elapsed())
                     .details("Failed to read line")
-            })?;
+            })?
+        };
+
+        // Validators are only stored once the resource was parsed successfully
+        *self.validators.lock() = HttpStoreValidators {
+            etag,
+            last_modified,
+        };
+
+        trc::event!(
+            Store(trc::StoreEvent::HttpStoreFetch),
+            Url = self.config.url.to_compact_string(),
+            Total = entries.len(),
+            Elapsed = time.elapsed(),
+        );
+
+        Ok(Some(entries))
+    }
+
+    fn parse_json(
+        &self,
+        reader: impl Read,
+    ) -> serde_json::Result<AHashMap<String, Value<'static>>> {
+        let mut entries = AHashMap::new();
+        for (key, value) in
+            serde_json::from_reader::<_, serde_json::Map<String, serde_json::Value>>(reader)?
+        {
+            if key.is_empty() || key.len() > self.config.max_entry_size {
+                continue;
+            }
+            if let Some(value) = self.json_value(value) {
+                entries.insert(key, value);
+                if entries.len() == self.config.max_entries {
+                    break;
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn json_value(&self, value: serde_json::Value) -> Option<Value<'static>> {
+        match value {
+            serde_json::Value::Bool(value) => Some(Value::Bool(value)),
+            serde_json::Value::Number(value) => value
+                .as_i64()
+                .map(Value::Integer)
+                .or_else(|| value.as_f64().map(Value::Float)),
+            serde_json::Value::String(value) => {
+                (value.len() <= self.config.max_entry_size).then(|| Value::Text(value.into()))
+            }
+            serde_json::Value::Array(values) => Some(Value::Array(
+                values
+                    .into_iter()
+                    .filter_map(|value| self.json_value(value))
+                    .collect(),
+            )),
+            serde_json::Value::Object(_) => {
+                let value = value.to_string();
+                (value.len() <= self.config.max_entry_size).then(|| Value::Text(value.into()))
+            }
+            serde_json::Value::Null => None,
+        }
+    }
+
+    fn parse_lines(&self, reader: impl Read) -> std::io::Result<AHashMap<String, Value<'static>>> {
+        let mut entries = AHashMap::new();
+        for (pos, line) in BufReader::new(reader).lines().enumerate() {
+            let line_ = line?;
 
             match &self.config.format {
                 HttpStoreFormat::List => {
@@ -166,6 +282,7 @@ impl HttpStore {
                     index_value,
                     separator,
                     skip_first,
+                    all_columns,
                 } if pos > 0 || !*skip_first => {
                     let mut in_quote = false;
                     let mut col_num = 0;
@@ -173,6 +290,7 @@ impl HttpStore {
 
                     let mut entry_key: String = String::new();
                     let mut entry_value: String = String::new();
+                    let mut columns = Vec::new();
 
                     for ch in line_.chars() {
                         match ch {
@@ -182,9 +300,17 @@ impl HttpStore {
                             '\\' if last_ch != '\\' => (),
                             _ => {
                                 if ch == *separator && !in_quote {
-                                    if col_num == *index_key && index_value.is_none() {
+                                    if col_num == *index_key
+                                        && index_value.is_none()
+                                        && !*all_columns
+                                    {
                                         break;
                                     } else {
+                                        if *all_columns && col_num != *index_key {
+                                            columns.push(Value::Text(
+                                                std::mem::take(&mut entry_value).into(),
+                                            ));
+                                        }
                                         col_num += 1;
                                     }
                                 } else if col_num == *index_key {
@@ -192,7 +318,8 @@ impl HttpStore {
                                     if entry_key.len() > self.config.max_entry_size {
                                         break;
                                     }
-                                } else if index_value.is_some_and(|v| col_num == v) {
+                                } else if *all_columns || index_value.is_some_and(|v| col_num == v)
+                                {
                                     entry_value.push(ch);
                                     if entry_value.len() > self.config.max_entry_size {
                                         break;
@@ -205,7 +332,12 @@ impl HttpStore {
                     }
 
                     if !entry_key.is_empty() {
-                        let entry_value = if !entry_value.is_empty() {
+                        let entry_value = if *all_columns {
+                            if col_num != *index_key {
+                                columns.push(Value::Text(entry_value.into()));
+                            }
+                            Value::Array(columns)
+                        } else if !entry_value.is_empty() {
                             Value::Text(entry_value.into())
                         } else {
                             Value::Integer(1)
@@ -221,13 +353,6 @@ impl HttpStore {
             }
         }
 
-        trc::event!(
-            Store(trc::StoreEvent::HttpStoreFetch),
-            Url = self.config.url.to_compact_string(),
-            Total = entries.len(),
-            Elapsed = time.elapsed(),
-        );
-
         Ok(entries)
     }
 }
@@ -8,12 +8,13 @@ pub mod config;
 pub mod lookup;
 
 use std::{
-    sync::atomic::{AtomicBool, AtomicU64},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicU64},
     time::Duration,
 };
 
 use ahash::AHashMap;
 use arc_swap::ArcSwap;
+use parking_lot::Mutex;
 
 use crate::Value;
 
@@ -34,11 +35,13 @@ pub struct HttpStoreConfig {
 #[derive(Debug, Clone)]
 pub enum HttpStoreFormat {
     List,
+    Json,
     Csv {
         index_key: u32,
         index_value: Option<u32>,
         separator: char,
         skip_first: bool,
+        all_columns: bool,
     },
 }
 
@@ -47,5 +50,26 @@ pub struct HttpStore {
     pub entries: ArcSwap<AHashMap<String, Value<'static>>>,
     pub expires: AtomicU64,
     pub in_flight: AtomicBool,
+    pub failures: AtomicU32,
+    pub validators: Mutex<HttpStoreValidators>,
     pub config: HttpStoreConfig,
 }
+
+#[derive(Debug, Default)]
+pub struct HttpStoreValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl HttpStore {
+    pub fn new(config: HttpStoreConfig) -> Self {
+        HttpStore {
+            entries: ArcSwap::from_pointee(AHashMap::new()),
+            expires: AtomicU64::new(0),
+            in_flight: AtomicBool::new(false),
+            failures: AtomicU32::new(0),
+            validators: Mutex::new(HttpStoreValidators::default()),
+            config,
+        }
+    }
+}
@@ -153,6 +153,12 @@ impl MeiliSearchStore {
         self.wait_for_task(response).await
     }
 
+    pub async fn ping(&self) -> trc::Result<()> {
+        assert_success(self.client.get(format!("{}/health", self.url)).send().await)
+            .await
+            .map(|_| ())
+    }
+
     #[cfg(feature = "test_mode")]
     pub async fn drop_indexes(&self) -> trc::Result<()> {
         use crate::write::SearchIndex;
@@ -49,6 +49,7 @@ impl From<crate::Value<'_>> for mysql_async::Value {
             crate::Value::Float(f) => mysql_async::Value::Double(f),
             crate::Value::Text(t) => mysql_async::Value::Bytes(t.into_owned().into_bytes()),
             crate::Value::Blob(b) => mysql_async::Value::Bytes(b.into_owned()),
+            crate::Value::Array(_) => mysql_async::Value::Bytes(value.into_string().into_bytes()),
             crate::Value::Null => mysql_async::Value::NULL,
         }
     }
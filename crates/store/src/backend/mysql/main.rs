@@ -74,7 +74,11 @@ impl MysqlStore {
         // SPDX-License-Identifier: LicenseRef-SEL
         #[cfg(feature = "enterprise")]
         if !replicas.is_empty() {
-            return backend::composite::read_replica::SQLReadReplica::open(primary, replicas);
+            return backend::composite::read_replica::SQLReadReplica::open(
+                primary,
+                replicas,
+                config.read_replica_stickiness.into_inner(),
+            );
         }
         // SPDX-SnippetEnd
 
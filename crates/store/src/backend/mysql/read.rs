@@ -5,9 +5,14 @@
  */
 
 use super::{MysqlStore, into_error};
-use crate::{Deserialize, IterateParams, Key, ValueKey, write::ValueClass};
+use crate::{
+    Deserialize, IterateParams, Key, ValueKey,
+    query::stream::RowSender,
+    write::{AnyKey, ValueClass},
+};
 use futures::TryStreamExt;
 use mysql_async::{Row, prelude::Queryable};
+use std::sync::Arc;
 
 impl MysqlStore {
     pub(crate) async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
@@ -119,6 +124,56 @@ impl MysqlStore {
         Ok(())
     }
 
+    // The connection is held until the stream is consumed or dropped
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::spawn(async move {
+            let result = async {
+                let mut conn = self.conn_pool.get_conn().await.map_err(into_error)?;
+                let table = char::from(params.begin.subspace);
+                let keys = if params.values { "k, v" } else { "k" };
+                let order = if params.ascending { "ASC" } else { "DESC" };
+                let limit = if params.first { " LIMIT 1" } else { "" };
+                let s = conn
+                    .prep(&format!(
+                        "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k {order}{limit}"
+                    ))
+                    .await
+                    .map_err(into_error)?;
+                let mut rows = conn
+                    .exec_stream::<Row, _, _>(&s, (params.begin.key, params.end.key))
+                    .await
+                    .map_err(into_error)?;
+
+                while let Some(mut row) = rows.try_next().await.map_err(into_error)? {
+                    let key = row
+                        .take_opt::<Vec<u8>, _>(0)
+                        .unwrap_or_else(|| Ok(vec![]))
+                        .map_err(into_error)?;
+                    let value = if params.values {
+                        row.take_opt::<Vec<u8>, _>(1)
+                            .unwrap_or_else(|| Ok(vec![]))
+                            .map_err(into_error)?
+                    } else {
+                        vec![]
+                    };
+
+                    if sender.push(&key, &value) && !sender.flush().await {
+                        break;
+                    }
+                }
+
+                Ok::<_, trc::Error>(())
+            }
+            .await;
+
+            sender.finish(result).await;
+        });
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
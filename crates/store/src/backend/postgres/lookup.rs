@@ -77,6 +77,7 @@ impl ToSql for crate::Value<'_> {
             }
             crate::Value::Text(v) => v.to_sql(ty, out),
             crate::Value::Blob(v) => v.to_sql(ty, out),
+            crate::Value::Array(_) => self.to_str().to_sql(ty, out),
             crate::Value::Null => None::<String>.to_sql(ty, out),
         }
     }
@@ -110,6 +111,7 @@ impl ToSql for crate::Value<'_> {
             }
             crate::Value::Text(v) => v.to_sql_checked(ty, out),
             crate::Value::Blob(v) => v.to_sql_checked(ty, out),
+            crate::Value::Array(_) => self.to_str().to_sql_checked(ty, out),
             crate::Value::Null => None::<String>.to_sql_checked(ty, out),
         }
     }
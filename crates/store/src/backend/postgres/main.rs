@@ -78,7 +78,11 @@ impl PostgresStore {
         // SPDX-License-Identifier: LicenseRef-SEL
         #[cfg(feature = "enterprise")]
         if !replicas.is_empty() {
-            return backend::composite::read_replica::SQLReadReplica::open(primary, replicas);
+            return backend::composite::read_replica::SQLReadReplica::open(
+                primary,
+                replicas,
+                config.read_replica_stickiness.into_inner(),
+            );
         }
         // SPDX-SnippetEnd
 
@@ -6,10 +6,13 @@
 
 use super::{PostgresStore, into_error};
 use crate::{
-    Deserialize, IterateParams, Key, ValueKey, backend::postgres::into_pool_error,
-    write::ValueClass,
+    Deserialize, IterateParams, Key, ValueKey,
+    backend::postgres::into_pool_error,
+    query::stream::RowSender,
+    write::{AnyKey, ValueClass},
 };
 use futures::{TryStreamExt, pin_mut};
+use std::sync::Arc;
 
 impl PostgresStore {
     pub(crate) async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
@@ -111,6 +114,53 @@ impl PostgresStore {
         Ok(())
     }
 
+    // The connection is held until the stream is consumed or dropped
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::spawn(async move {
+            let result = async {
+                let conn = self.conn_pool.get().await.map_err(into_pool_error)?;
+                let table = char::from(params.begin.subspace);
+                let keys = if params.values { "k, v" } else { "k" };
+                let order = if params.ascending { "ASC" } else { "DESC" };
+                let limit = if params.first { " LIMIT 1" } else { "" };
+                let s = conn
+                    .prepare_cached(&format!(
+                        "SELECT {keys} FROM {table} WHERE k >= $1 AND k <= $2 ORDER BY k {order}{limit}"
+                    ))
+                    .await
+                    .map_err(into_error)?;
+                let rows = conn
+                    .query_raw(&s, &[&params.begin.key, &params.end.key])
+                    .await
+                    .map_err(into_error)?;
+
+                pin_mut!(rows);
+
+                while let Some(row) = rows.try_next().await.map_err(into_error)? {
+                    let key = row.try_get::<_, &[u8]>(0).map_err(into_error)?;
+                    let value: &[u8] = if params.values {
+                        row.try_get::<_, &[u8]>(1).map_err(into_error)?
+                    } else {
+                        &[]
+                    };
+
+                    if sender.push(key, value) && !sender.flush().await {
+                        break;
+                    }
+                }
+
+                Ok::<_, trc::Error>(())
+            }
+            .await;
+
+            sender.finish(result).await;
+        });
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
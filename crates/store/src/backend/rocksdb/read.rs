@@ -6,9 +6,12 @@
 
 use super::{RocksDbStore, into_error};
 use crate::{
-    Deserialize, IterateParams, Key, ValueKey, backend::rocksdb::CfHandle, write::ValueClass,
+    Deserialize, IterateParams, Key, ValueKey,
+    backend::rocksdb::CfHandle,
+    query::stream::RowSender,
+    write::{AnyKey, ValueClass},
 };
-use rocksdb::{Direction, IteratorMode};
+use std::sync::Arc;
 
 impl RocksDbStore {
     pub(crate) async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
@@ -63,28 +66,78 @@ impl RocksDbStore {
             let cf = db.subspace_handle(params.begin.subspace());
             let begin = params.begin.serialize(0);
             let end = params.end.serialize(0);
-            let it_mode = if params.ascending {
-                IteratorMode::From(&begin, Direction::Forward)
+
+            // Raw iterators borrow keys and values instead of copying each row
+            let mut it = db.raw_iterator_cf(&cf);
+            if params.ascending {
+                it.seek(&begin);
             } else {
-                IteratorMode::From(&end, Direction::Reverse)
-            };
-
-            for row in db.iterator_cf(&cf, it_mode) {
-                let (key, value) = row.map_err(into_error)?;
-                if key.as_ref() < begin.as_slice()
-                    || key.as_ref() > end.as_slice()
-                    || !cb(&key, &value)?
+                it.seek_for_prev(&end);
+            }
+
+            while let Some((key, value)) = it.item() {
+                if key < begin.as_slice()
+                    || key > end.as_slice()
+                    || !cb(key, value)?
                     || params.first
                 {
                     break;
                 }
+
+                if params.ascending {
+                    it.next();
+                } else {
+                    it.prev();
+                }
             }
 
-            Ok(())
+            it.status().map_err(into_error)
         })
         .await
     }
 
+    // Streams run on the blocking pool as they may stay open for as long as
+    // the caller takes to consume them
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::task::spawn_blocking(move || {
+            let db = &self.db;
+            let cf = db.subspace_handle(params.begin.subspace);
+            let begin = params.begin.key.as_slice();
+            let end = params.end.key.as_slice();
+
+            let mut it = db.raw_iterator_cf(&cf);
+            if params.ascending {
+                it.seek(begin);
+            } else {
+                it.seek_for_prev(end);
+            }
+
+            while let Some((key, value)) = it.item() {
+                if key < begin || key > end {
+                    break;
+                }
+                if sender.push(key, value) && !sender.blocking_flush() {
+                    return;
+                }
+                if params.first {
+                    break;
+                }
+
+                if params.ascending {
+                    it.next();
+                } else {
+                    it.prev();
+                }
+            }
+
+            sender.blocking_finish(it.status().map_err(into_error));
+        });
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
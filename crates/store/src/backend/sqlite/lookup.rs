@@ -54,6 +54,9 @@ impl ToSql for Value<'_> {
             Value::Float(value) => value.to_sql(),
             Value::Text(value) => value.to_sql(),
             Value::Blob(value) => value.to_sql(),
+            Value::Array(_) => Ok(rusqlite::types::ToSqlOutput::Owned(
+                rusqlite::types::Value::Text(self.to_str().into_owned()),
+            )),
             Value::Null => Ok(rusqlite::types::ToSqlOutput::Owned(
                 rusqlite::types::Value::Null,
             )),
@@ -5,8 +5,13 @@
  */
 
 use super::{SqliteStore, into_error};
-use crate::{Deserialize, IterateParams, Key, ValueKey, write::ValueClass};
+use crate::{
+    Deserialize, IterateParams, Key, ValueKey,
+    query::stream::RowSender,
+    write::{AnyKey, ValueClass},
+};
 use rusqlite::OptionalExtension;
+use std::sync::Arc;
 
 impl SqliteStore {
     pub(crate) async fn get_value<U>(&self, key: impl Key) -> trc::Result<Option<U>>
@@ -127,6 +132,56 @@ impl SqliteStore {
         .await
     }
 
+    // Streams run on the blocking pool as they may stay open for as long as
+    // the caller takes to consume them
+    pub(crate) fn stream(
+        self: Arc<Self>,
+        params: IterateParams<AnyKey<Vec<u8>>>,
+        mut sender: RowSender,
+    ) {
+        tokio::task::spawn_blocking(move || {
+            let result = (|| -> trc::Result<()> {
+                let conn = self.conn_pool.get().map_err(into_error)?;
+                let table = char::from(params.begin.subspace);
+                let keys = if params.values { "k, v" } else { "k" };
+                let order = if params.ascending { "ASC" } else { "DESC" };
+                let limit = if params.first { " LIMIT 1" } else { "" };
+                let mut query = conn
+                    .prepare_cached(&format!(
+                        "SELECT {keys} FROM {table} WHERE k >= ? AND k <= ? ORDER BY k {order}{limit}"
+                    ))
+                    .map_err(into_error)?;
+                let mut rows = query
+                    .query([&params.begin.key, &params.end.key])
+                    .map_err(into_error)?;
+
+                while let Some(row) = rows.next().map_err(into_error)? {
+                    let key = row
+                        .get_ref(0)
+                        .map_err(into_error)?
+                        .as_bytes()
+                        .map_err(into_error)?;
+                    let value: &[u8] = if params.values {
+                        row.get_ref(1)
+                            .map_err(into_error)?
+                            .as_bytes()
+                            .map_err(into_error)?
+                    } else {
+                        &[]
+                    };
+
+                    if sender.push(key, value) && !sender.blocking_flush() {
+                        break;
+                    }
+                }
+
+                Ok(())
+            })();
+
+            sender.blocking_finish(result);
+        });
+    }
+
     pub(crate) async fn get_counter(
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
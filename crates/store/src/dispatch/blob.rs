@@ -12,41 +12,75 @@ const MAGIC_MARKER: u8 = 0xa0;
 const LZ4_MARKER: u8 = MAGIC_MARKER | 0x01;
 //const ZSTD_MARKER: u8 = MAGIC_MARKER | 0x02;
 const NONE_MARKER: u8 = 0x00;
+const HEADER_MARKER: u8 = MAGIC_MARKER | 0x0f;
+
+// Blobs ending with HEADER_MARKER start with a header holding the format version
+// and the compression marker. The last header byte is the most significant byte
+// of the length prefix of blobs stored before headers were added, which is never
+// set as blobs are limited to 4GB.
+const BLOB_VERSION: u8 = 1;
+const HEADER_LEN: usize = 4;
 
 impl BlobStore {
     pub async fn get_blob(&self, key: &[u8], range: Range<usize>) -> trc::Result<Option<Vec<u8>>> {
+        match self.read_blob(key, 0..usize::MAX).await? {
+            Some(data) => decode_blob(key, data, range).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the first `len` bytes of a blob, fetching only that range from
+    /// the backend when the blob header states that it is stored uncompressed.
+    /// Compressed blobs and blobs without a header are read in full.
+    pub async fn get_blob_prefix(&self, key: &[u8], len: usize) -> trc::Result<Option<Vec<u8>>> {
+        let header_len = len.saturating_add(HEADER_LEN);
+        match self.read_blob(key, 0..header_len.saturating_add(1)).await? {
+            Some(mut data)
+                if data.len() > header_len && data.starts_with(&blob_header(NONE_MARKER)) =>
+            {
+                data.truncate(header_len);
+                data.drain(..HEADER_LEN);
+                Ok(Some(data))
+            }
+            Some(data) if data.len() <= header_len => decode_blob(key, data, 0..len).map(Some),
+            Some(_) => self.get_blob(key, 0..len).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn read_blob(&self, key: &[u8], range: Range<usize>) -> trc::Result<Option<Vec<u8>>> {
         let start_time = Instant::now();
         let result = match &self {
             BlobStore::Store(store) => match store {
                 #[cfg(feature = "sqlite")]
-                Store::SQLite(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::SQLite(store) => store.get_blob(key, range).await,
                 #[cfg(feature = "foundation")]
-                Store::FoundationDb(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::FoundationDb(store) => store.get_blob(key, range).await,
                 #[cfg(feature = "postgres")]
-                Store::PostgreSQL(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::PostgreSQL(store) => store.get_blob(key, range).await,
                 #[cfg(feature = "mysql")]
-                Store::MySQL(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::MySQL(store) => store.get_blob(key, range).await,
                 #[cfg(feature = "rocks")]
-                Store::RocksDb(store) => store.get_blob(key, 0..usize::MAX).await,
-                Store::Ephemeral(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::RocksDb(store) => store.get_blob(key, range).await,
+                Store::Ephemeral(store) => store.get_blob(key, range).await,
                 // SPDX-SnippetBegin
                 // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
                 // SPDX-License-Identifier: LicenseRef-SEL
                 #[cfg(all(feature = "enterprise", any(feature = "postgres", feature = "mysql")))]
-                Store::SQLReadReplica(store) => store.get_blob(key, 0..usize::MAX).await,
+                Store::SQLReadReplica(store) => store.get_blob(key, range).await,
                 // SPDX-SnippetEnd
                 Store::None => Err(trc::StoreEvent::NotConfigured.into()),
             },
-            BlobStore::Fs(store) => store.get_blob(key, 0..usize::MAX).await,
+            BlobStore::Fs(store) => store.get_blob(key, range).await,
             #[cfg(feature = "s3")]
-            BlobStore::S3(store) => store.get_blob(key, 0..usize::MAX).await,
+            BlobStore::S3(store) => store.get_blob(key, range).await,
             #[cfg(feature = "azure")]
-            BlobStore::Azure(store) => store.get_blob(key, 0..usize::MAX).await,
+            BlobStore::Azure(store) => store.get_blob(key, range).await,
             // SPDX-SnippetBegin
             // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
             // SPDX-License-Identifier: LicenseRef-SEL
             #[cfg(feature = "enterprise")]
-            BlobStore::Sharded(store) => store.get_blob(key, 0..usize::MAX).await,
+            BlobStore::Sharded(store) => store.get_blob(key, range).await,
             // SPDX-SnippetEnd
         }
         .caused_by(trc::location!())?;
@@ -58,50 +92,7 @@ impl BlobStore {
             Size = result.as_ref().map_or(0, |data| data.len()),
         );
 
-        let Some(mut data) = result else {
-            return Ok(None);
-        };
-
-        let mut data = match data.last().copied() {
-            Some(LZ4_MARKER) => {
-                lz4_flex::decompress_size_prepended(data.get(..data.len() - 1).unwrap_or_default())
-                    .map_err(|err| {
-                        trc::StoreEvent::DecompressError
-                            .reason(err)
-                            .ctx(trc::Key::Key, key)
-                            .ctx(trc::Key::CausedBy, trc::location!())
-                    })?
-            }
-            Some(NONE_MARKER) => {
-                if !data.is_empty() {
-                    data.truncate(data.len() - 1);
-                }
-                data
-            }
-            Some(_) => {
-                trc::event!(Store(StoreEvent::BlobMissingMarker), Key = key);
-
-                data
-            }
-            None => {
-                return Ok(Some(data));
-            }
-        };
-
-        if range.start == 0 {
-            if range.end > data.len() {
-                Ok(Some(data))
-            } else {
-                data.truncate(range.end);
-                Ok(Some(data))
-            }
-        } else {
-            Ok(Some(
-                data.get(range.start..range.end)
-                    .unwrap_or_default()
-                    .to_vec(),
-            ))
-        }
+        Ok(result)
     }
 
     pub async fn put_blob(
@@ -112,27 +103,30 @@ impl BlobStore {
     ) -> trc::Result<()> {
         let data = match compression {
             CompressionAlgo::None => {
-                let mut uncompressed = Vec::with_capacity(data.len() + 1);
+                let mut uncompressed = Vec::with_capacity(data.len() + HEADER_LEN + 1);
+                uncompressed.extend_from_slice(&blob_header(NONE_MARKER));
                 uncompressed.extend_from_slice(data);
-                uncompressed.push(NONE_MARKER);
+                uncompressed.push(HEADER_MARKER);
                 uncompressed
             }
             CompressionAlgo::Lz4 => {
+                let offset = HEADER_LEN + U32_LEN;
                 let mut compressed =
                     vec![
-                        LZ4_MARKER;
-                        lz4_flex::block::get_maximum_output_size(data.len()) + U32_LEN + 1
+                        HEADER_MARKER;
+                        lz4_flex::block::get_maximum_output_size(data.len()) + offset + 1
                     ];
 
                 // Compress the data
                 let compressed_len =
-                    lz4_flex::compress_into(data, &mut compressed[U32_LEN..]).unwrap();
+                    lz4_flex::compress_into(data, &mut compressed[offset..]).unwrap();
 
-                // Prepend the length of the uncompressed data
-                compressed[..U32_LEN].copy_from_slice(&(data.len() as u32).to_le_bytes());
+                // Prepend the header and the length of the uncompressed data
+                compressed[..HEADER_LEN].copy_from_slice(&blob_header(LZ4_MARKER));
+                compressed[HEADER_LEN..offset].copy_from_slice(&(data.len() as u32).to_le_bytes());
 
                 // Truncate to the actual size
-                compressed.truncate(compressed_len + U32_LEN + 1);
+                compressed.truncate(compressed_len + offset + 1);
                 compressed
             }
         };
@@ -229,3 +223,62 @@ impl BlobStore {
         result
     }
 }
+
+fn decode_blob(key: &[u8], mut data: Vec<u8>, range: Range<usize>) -> trc::Result<Vec<u8>> {
+    let mut data = match data.last().copied() {
+        Some(HEADER_MARKER) if data.len() > HEADER_LEN => {
+            let payload = HEADER_LEN..data.len() - 1;
+            if data[..HEADER_LEN] == blob_header(LZ4_MARKER) {
+                lz4_decompress(key, &data[payload])?
+            } else if data[..HEADER_LEN] == blob_header(NONE_MARKER) {
+                data.truncate(payload.end);
+                data.drain(..HEADER_LEN);
+                data
+            } else {
+                trc::event!(Store(StoreEvent::BlobMissingMarker), Key = key);
+
+                data
+            }
+        }
+        Some(LZ4_MARKER) => lz4_decompress(key, data.get(..data.len() - 1).unwrap_or_default())?,
+        Some(NONE_MARKER) => {
+            if !data.is_empty() {
+                data.truncate(data.len() - 1);
+            }
+            data
+        }
+        Some(_) => {
+            trc::event!(Store(StoreEvent::BlobMissingMarker), Key = key);
+
+            data
+        }
+        None => {
+            return Ok(data);
+        }
+    };
+
+    if range.start == 0 {
+        if range.end < data.len() {
+            data.truncate(range.end);
+        }
+        Ok(data)
+    } else {
+        Ok(data
+            .get(range.start..range.end)
+            .unwrap_or_default()
+            .to_vec())
+    }
+}
+
+fn lz4_decompress(key: &[u8], data: &[u8]) -> trc::Result<Vec<u8>> {
+    lz4_flex::decompress_size_prepended(data).map_err(|err| {
+        trc::StoreEvent::DecompressError
+            .reason(err)
+            .ctx(trc::Key::Key, key)
+            .ctx(trc::Key::CausedBy, trc::location!())
+    })
+}
+
+fn blob_header(compression: u8) -> [u8; HEADER_LEN] {
+    [MAGIC_MARKER, BLOB_VERSION, compression, u8::MAX]
+}
@@ -0,0 +1,56 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    BlobStore, InMemoryStore, SUBSPACE_PROPERTY, SearchStore, Store, ValueKey,
+    write::{AnyClass, ValueClass},
+};
+use trc::AddContext;
+
+// Key that is never written, looking it up exercises a full round trip
+// to the backend without reading any data
+const HEALTH_CHECK_KEY: &[u8] = b"_healthz";
+
+impl Store {
+    pub async fn health_check(&self) -> trc::Result<()> {
+        self.key_exists(ValueKey::from(ValueClass::Any(AnyClass {
+            subspace: SUBSPACE_PROPERTY,
+            key: HEALTH_CHECK_KEY.to_vec(),
+        })))
+        .await
+        .map(|_| ())
+        .caused_by(trc::location!())
+    }
+}
+
+impl BlobStore {
+    pub async fn health_check(&self) -> trc::Result<()> {
+        self.get_blob(HEALTH_CHECK_KEY, 0..1)
+            .await
+            .map(|_| ())
+            .caused_by(trc::location!())
+    }
+}
+
+impl InMemoryStore {
+    pub async fn health_check(&self) -> trc::Result<()> {
+        self.key_exists(HEALTH_CHECK_KEY)
+            .await
+            .map(|_| ())
+            .caused_by(trc::location!())
+    }
+}
+
+impl SearchStore {
+    pub async fn health_check(&self) -> trc::Result<()> {
+        match self {
+            SearchStore::Store(store) => store.health_check().await,
+            SearchStore::ElasticSearch(store) => store.ping().await,
+            SearchStore::MeiliSearch(store) => store.ping().await,
+        }
+        .caused_by(trc::location!())
+    }
+}
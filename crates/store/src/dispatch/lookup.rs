@@ -300,17 +300,7 @@ impl InMemoryStore {
         rate: &Rate,
         soft_check: bool,
     ) -> trc::Result<Option<u64>> {
-        let now = now();
-        let period = rate.period.as_secs().max(1);
-        let range_start = now / period;
-        let range_end = (range_start * period) + period;
-        let expires_in = range_end - now;
-
-        let mut bucket = Vec::with_capacity(key.len() + U64_LEN + 1);
-        bucket.push(prefix);
-        bucket.extend_from_slice(key);
-        bucket.extend_from_slice(range_start.to_be_bytes().as_slice());
-
+        let (bucket, expires_in) = rate_bucket(prefix, key, rate);
         let requests = if !soft_check {
             self.counter_incr(KeyValue::new(bucket, 1).expires(expires_in), true)
                 .await
@@ -326,6 +316,29 @@ impl InMemoryStore {
         }
     }
 
+    // Takes up to the requested number of tokens from the current rate window
+    // in a single update, returns the tokens granted and the seconds left until
+    // the window refills
+    pub async fn acquire_rate_tokens(
+        &self,
+        prefix: u8,
+        key: &[u8],
+        rate: &Rate,
+        tokens: u64,
+    ) -> trc::Result<(u64, u64)> {
+        let (bucket, expires_in) = rate_bucket(prefix, key, rate);
+        let requests = self
+            .counter_incr(
+                KeyValue::new(bucket, tokens as i64).expires(expires_in),
+                true,
+            )
+            .await
+            .caused_by(trc::location!())?;
+        let available = rate.count as i64 - (requests - tokens as i64);
+
+        Ok((available.clamp(0, tokens as i64) as u64, expires_in))
+    }
+
     pub async fn try_lock(&self, prefix: u8, key: &[u8], duration: u64) -> trc::Result<bool> {
         match self {
             InMemoryStore::Store(store) => {
@@ -679,9 +692,24 @@ impl From<Value<'static>> for String {
             Value::Text(string) => string.into_owned(),
             Value::Blob(bytes) => String::from_utf8_lossy(bytes.as_ref()).into_owned(),
             Value::Bool(boolean) => boolean.to_string(),
+            Value::Array(_) => value.into_string(),
             Value::Null => String::new(),
             Value::Integer(num) => num.to_string(),
             Value::Float(num) => num.to_string(),
         }
     }
 }
+
+fn rate_bucket(prefix: u8, key: &[u8], rate: &Rate) -> (Vec<u8>, u64) {
+    let now = now();
+    let period = rate.period.as_secs().max(1);
+    let range_start = now / period;
+    let range_end = (range_start * period) + period;
+
+    let mut bucket = Vec::with_capacity(key.len() + U64_LEN + 1);
+    bucket.push(prefix);
+    bucket.extend_from_slice(key);
+    bucket.extend_from_slice(range_start.to_be_bytes().as_slice());
+
+    (bucket, range_end - now)
+}
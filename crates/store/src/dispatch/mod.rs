@@ -8,9 +8,11 @@ use crate::Store;
 use roaring::RoaringBitmap;
 
 pub mod blob;
+pub mod health;
 pub mod lookup;
 pub mod search;
 pub mod store;
+pub mod timing;
 
 impl Store {
     pub fn id(&self) -> &'static str {
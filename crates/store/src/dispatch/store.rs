@@ -4,7 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use super::DocumentSet;
+use super::{DocumentSet, timing::StoreTiming};
 use crate::{
     Deserialize, IterateParams, Key, QueryResult, SUBSPACE_COUNTER, SUBSPACE_INDEXES,
     SUBSPACE_LOGS, Store, U32_LEN, Value, ValueKey,
@@ -23,7 +23,10 @@ impl Store {
     where
         U: Deserialize + 'static,
     {
-        match self {
+        let start_time = Instant::now();
+        #[cfg(feature = "test_mode")]
+        StoreTiming::read_delay().await;
+        let result = match self {
             #[cfg(feature = "sqlite")]
             Self::SQLite(store) => store.get_value(key).await,
             #[cfg(feature = "foundation")]
@@ -43,11 +46,18 @@ impl Store {
             // SPDX-SnippetEnd
             Self::None => Err(trc::StoreEvent::NotConfigured.into()),
         }
-        .caused_by(trc::location!())
+        .caused_by(trc::location!());
+
+        StoreTiming::record_read(start_time);
+
+        result
     }
 
     pub async fn key_exists(&self, key: impl Key) -> trc::Result<bool> {
-        match self {
+        let start_time = Instant::now();
+        #[cfg(feature = "test_mode")]
+        StoreTiming::read_delay().await;
+        let result = match self {
             #[cfg(feature = "sqlite")]
             Self::SQLite(store) => store.key_exists(key).await,
             #[cfg(feature = "foundation")]
@@ -67,7 +77,11 @@ impl Store {
             // SPDX-SnippetEnd
             Self::None => Err(trc::StoreEvent::NotConfigured.into()),
         }
-        .caused_by(trc::location!())
+        .caused_by(trc::location!());
+
+        StoreTiming::record_read(start_time);
+
+        result
     }
 
     pub async fn iterate<T: Key>(
@@ -76,6 +90,8 @@ impl Store {
         cb: impl for<'x> FnMut(&'x [u8], &'x [u8]) -> trc::Result<bool> + Sync + Send,
     ) -> trc::Result<()> {
         let start_time = Instant::now();
+        #[cfg(feature = "test_mode")]
+        StoreTiming::read_delay().await;
         let result = match self {
             #[cfg(feature = "sqlite")]
             Self::SQLite(store) => store.iterate(params, cb).await,
@@ -98,6 +114,8 @@ impl Store {
         }
         .caused_by(trc::location!());
 
+        StoreTiming::record_read(start_time);
+
         trc::event!(
             Store(StoreEvent::DataIterate),
             Elapsed = start_time.elapsed(),
@@ -110,7 +128,10 @@ impl Store {
         &self,
         key: impl Into<ValueKey<ValueClass>> + Sync + Send,
     ) -> trc::Result<i64> {
-        match self {
+        let start_time = Instant::now();
+        #[cfg(feature = "test_mode")]
+        StoreTiming::read_delay().await;
+        let result = match self {
             #[cfg(feature = "sqlite")]
             Self::SQLite(store) => store.get_counter(key).await,
             #[cfg(feature = "foundation")]
@@ -130,7 +151,11 @@ impl Store {
             // SPDX-SnippetEnd
             Self::None => Err(trc::StoreEvent::NotConfigured.into()),
         }
-        .caused_by(trc::location!())
+        .caused_by(trc::location!());
+
+        StoreTiming::record_read(start_time);
+
+        result
     }
 
     #[allow(unreachable_patterns)]
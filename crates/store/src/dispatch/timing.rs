@@ -0,0 +1,72 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+tokio::task_local! {
+    static STORE_TIMING: StoreTiming;
+}
+
+// Artificial delay added to every store read, used to test slow code paths
+#[cfg(feature = "test_mode")]
+pub static STORE_READ_DELAY: AtomicU64 = AtomicU64::new(0);
+
+// Number of store reads performed by all tasks, used to test caching
+#[cfg(feature = "test_mode")]
+pub static STORE_READ_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Time spent waiting on the data store by a task.
+#[derive(Debug, Clone, Default)]
+pub struct StoreTiming(Arc<StoreTimingInner>);
+
+#[derive(Debug, Default)]
+struct StoreTimingInner {
+    reads: AtomicU64,
+    read_time_us: AtomicU64,
+}
+
+impl StoreTiming {
+    /// Runs a future accounting all store reads it performs. Reads made by
+    /// tasks spawned from the future are not included.
+    pub async fn measure<F: Future>(&self, future: F) -> F::Output {
+        STORE_TIMING.scope(self.clone(), future).await
+    }
+
+    pub fn reads(&self) -> u64 {
+        self.0.reads.load(Ordering::Relaxed)
+    }
+
+    pub fn read_time(&self) -> Duration {
+        Duration::from_micros(self.0.read_time_us.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn record_read(started: Instant) {
+        #[cfg(feature = "test_mode")]
+        STORE_READ_COUNT.fetch_add(1, Ordering::Relaxed);
+        let _ = STORE_TIMING.try_with(|timing| {
+            timing.0.reads.fetch_add(1, Ordering::Relaxed);
+            timing
+                .0
+                .read_time_us
+                .fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+        });
+    }
+
+    #[cfg(feature = "test_mode")]
+    pub(crate) async fn read_delay() {
+        let delay = STORE_READ_DELAY.load(Ordering::Relaxed);
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+    }
+}
@@ -63,6 +63,9 @@ pub(crate) const WITH_SUBSPACE: u32 = 1;
 pub trait Key: Sync + Send + Clone {
     fn serialize(&self, flags: u32) -> Vec<u8>;
     fn subspace(&self) -> u8;
+    fn account_id(&self) -> Option<u32> {
+        None
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -136,6 +139,7 @@ pub struct IterateParams<T: Key> {
     first: bool,
     ascending: bool,
     values: bool,
+    read_only: bool,
 }
 
 #[derive(Clone, Default)]
@@ -324,6 +328,7 @@ pub enum Value<'x> {
     Float(f64),
     Text(Cow<'x, str>),
     Blob(Cow<'x, [u8]>),
+    Array(Vec<Value<'x>>),
     Null,
 }
 
@@ -337,6 +342,9 @@ impl<'x> Value<'x> {
             Value::Bool(b) => Cow::Owned(b.to_string()),
             Value::Float(f) => Cow::Owned(f.to_string()),
             Value::Blob(b) => String::from_utf8_lossy(b.as_ref()),
+            Value::Array(a) => {
+                Cow::Owned(a.iter().map(|v| v.to_str()).collect::<Vec<_>>().join(","))
+            }
             Value::Null => Cow::Borrowed(""),
         }
     }
@@ -575,6 +583,11 @@ impl Value<'_> {
             Value::Bool(b) => b.to_string(),
             Value::Float(f) => f.to_string(),
             Value::Blob(b) => String::from_utf8_lossy(b.as_ref()).into_owned(),
+            Value::Array(a) => a
+                .into_iter()
+                .map(|v| v.into_string())
+                .collect::<Vec<_>>()
+                .join(","),
             Value::Null => "".into(),
         }
     }
@@ -586,6 +599,11 @@ impl Value<'_> {
             Value::Bool(b) => b.to_string(),
             Value::Float(f) => f.to_string(),
             Value::Blob(b) => String::from_utf8_lossy(b.as_ref()).to_lowercase(),
+            Value::Array(a) => a
+                .into_iter()
+                .map(|v| v.into_lower_string())
+                .collect::<Vec<_>>()
+                .join(","),
             Value::Null => "".into(),
         }
     }
@@ -776,6 +794,7 @@ impl From<Value<'_>> for trc::Value {
                 Cow::Owned(v) => v.into(),
             }),
             Value::Blob(v) => trc::Value::Bytes(v.into_owned()),
+            Value::Array(v) => trc::Value::Array(v.into_iter().map(Into::into).collect()),
             Value::Null => trc::Value::None,
         }
     }
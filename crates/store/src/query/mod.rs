@@ -6,6 +6,8 @@
 
 pub mod acl;
 pub mod log;
+pub mod queue;
+pub mod stream;
 
 use crate::{IterateParams, Key};
 
@@ -17,6 +19,7 @@ impl<T: Key> IterateParams<T> {
             first: false,
             ascending: true,
             values: true,
+            read_only: false,
         }
     }
 
@@ -49,4 +52,10 @@ impl<T: Key> IterateParams<T> {
         self.values = false;
         self
     }
+
+    /// Marks the scan as safe to serve from a read replica.
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
 }
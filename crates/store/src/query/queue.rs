@@ -0,0 +1,86 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::stream::KeyValueStream;
+use crate::{
+    IterateParams, Store, U64_LEN, ValueKey,
+    write::{QueueClass, QueueEvent, ValueClass, key::DeserializeBigEndian},
+};
+
+const QUEUE_BATCH_SIZE: usize = 1024;
+
+// Queue events due within a range, read by key only
+pub struct QueueEventStream(KeyValueStream);
+
+// Ids of all queued messages, read without their archives
+pub struct QueueIdStream(KeyValueStream);
+
+impl Store {
+    pub fn queue_events(&self, due_from: u64, due_to: u64, ascending: bool) -> QueueEventStream {
+        let from_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(QueueEvent {
+            due: due_from,
+            queue_id: 0,
+            queue_name: [0; 8],
+        })));
+        let to_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(QueueEvent {
+            due: due_to,
+            queue_id: u64::MAX,
+            queue_name: [u8::MAX; 8],
+        })));
+
+        QueueEventStream(
+            self.stream(
+                IterateParams::new(from_key, to_key)
+                    .set_ascending(ascending)
+                    .no_values(),
+                QUEUE_BATCH_SIZE,
+            ),
+        )
+    }
+
+    pub fn queue_ids(&self) -> QueueIdStream {
+        QueueIdStream(
+            self.stream(
+                IterateParams::new(
+                    ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+                    ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+                )
+                .no_values(),
+                QUEUE_BATCH_SIZE,
+            ),
+        )
+    }
+}
+
+impl QueueEventStream {
+    pub async fn next(&mut self) -> trc::Result<Option<QueueEvent>> {
+        let Some((key, _)) = self.0.next().await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(QueueEvent {
+            due: key.deserialize_be_u64(0)?,
+            queue_id: key.deserialize_be_u64(U64_LEN)?,
+            queue_name: key
+                .get(U64_LEN * 2..)
+                .and_then(|name| name.try_into().ok())
+                .ok_or_else(|| {
+                    trc::StoreEvent::DataCorruption
+                        .caused_by(trc::location!())
+                        .ctx(trc::Key::Key, key)
+                })?,
+        }))
+    }
+}
+
+impl QueueIdStream {
+    pub async fn next(&mut self) -> trc::Result<Option<u64>> {
+        match self.0.next().await? {
+            Some((key, _)) => key.deserialize_be_u64(0).map(Some),
+            None => Ok(None),
+        }
+    }
+}
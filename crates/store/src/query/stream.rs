@@ -0,0 +1,207 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{IterateParams, Key, Store, write::AnyKey};
+use tokio::sync::mpsc;
+
+// Cursor over a key range. The backend keeps the range open on its own task
+// and hands over batches of rows, which allows callers to await between rows
+// without collecting the whole range in memory. Dropping the stream closes
+// the cursor.
+pub struct KeyValueStream {
+    rx: mpsc::Receiver<trc::Result<RowBatch>>,
+    batch: RowBatch,
+    pos: usize,
+    is_done: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct RowBatch {
+    buf: Vec<u8>,
+    rows: Vec<(usize, usize, usize)>,
+}
+
+// Producer side of a stream, owned by the backend task reading the range
+pub(crate) struct RowSender {
+    tx: mpsc::Sender<trc::Result<RowBatch>>,
+    batch: RowBatch,
+    batch_size: usize,
+    values: bool,
+}
+
+impl Store {
+    pub fn stream<T: Key>(&self, params: IterateParams<T>, batch_size: usize) -> KeyValueStream {
+        // One batch is read ahead while the caller processes the current one
+        let (tx, rx) = mpsc::channel(1);
+        let sender = RowSender {
+            tx,
+            batch: RowBatch::default(),
+            batch_size: if params.first { 1 } else { batch_size.max(1) },
+            values: params.values,
+        };
+        let params = IterateParams {
+            begin: AnyKey {
+                subspace: params.begin.subspace(),
+                key: params.begin.serialize(0),
+            },
+            end: AnyKey {
+                subspace: params.end.subspace(),
+                key: params.end.serialize(0),
+            },
+            first: params.first,
+            ascending: params.ascending,
+            values: params.values,
+            read_only: params.read_only,
+        };
+
+        match self {
+            #[cfg(feature = "sqlite")]
+            Self::SQLite(store) => store.clone().stream(params, sender),
+            #[cfg(feature = "foundation")]
+            Self::FoundationDb(store) => store.clone().stream(params, sender),
+            #[cfg(feature = "postgres")]
+            Self::PostgreSQL(store) => store.clone().stream(params, sender),
+            #[cfg(feature = "mysql")]
+            Self::MySQL(store) => store.clone().stream(params, sender),
+            #[cfg(feature = "rocks")]
+            Self::RocksDb(store) => store.clone().stream(params, sender),
+            Self::Ephemeral(store) => store.clone().stream(params, sender),
+            // SPDX-SnippetBegin
+            // SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+            // SPDX-License-Identifier: LicenseRef-SEL
+            #[cfg(all(feature = "enterprise", any(feature = "postgres", feature = "mysql")))]
+            Self::SQLReadReplica(store) => store.stream(params, sender),
+            // SPDX-SnippetEnd
+            Self::None => {
+                let _ = sender
+                    .tx
+                    .try_send(Err(trc::StoreEvent::NotConfigured.into()));
+            }
+        }
+
+        KeyValueStream {
+            rx,
+            batch: RowBatch::default(),
+            pos: 0,
+            is_done: false,
+        }
+    }
+}
+
+impl KeyValueStream {
+    // Fetches the next batch of rows, returns false once the range is exhausted
+    pub async fn next_batch(&mut self) -> trc::Result<bool> {
+        if self.is_done {
+            self.batch = RowBatch::default();
+            self.pos = 0;
+            return Ok(false);
+        }
+
+        match self.rx.recv().await {
+            Some(Ok(batch)) => {
+                self.batch = batch;
+                self.pos = self.batch.rows.len();
+                Ok(true)
+            }
+            Some(Err(err)) => {
+                self.is_done = true;
+                Err(err)
+            }
+            None => {
+                self.is_done = true;
+                self.batch = RowBatch::default();
+                self.pos = 0;
+                Ok(false)
+            }
+        }
+    }
+
+    // Rows of the current batch
+    pub fn rows(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.batch.rows()
+    }
+
+    // Returns the next row, fetching a new batch once the current one is consumed
+    pub async fn next(&mut self) -> trc::Result<Option<(&[u8], &[u8])>> {
+        if self.pos >= self.batch.rows.len() {
+            if !self.next_batch().await? {
+                return Ok(None);
+            }
+            self.pos = 0;
+        }
+
+        let (start, key_end, end) = self.batch.rows[self.pos];
+        self.pos += 1;
+        Ok(Some((
+            &self.batch.buf[start..key_end],
+            &self.batch.buf[key_end..end],
+        )))
+    }
+}
+
+impl RowBatch {
+    fn rows(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.rows
+            .iter()
+            .map(|&(start, key_end, end)| (&self.buf[start..key_end], &self.buf[key_end..end]))
+    }
+}
+
+impl RowSender {
+    // Adds a row to the current batch, returns true once the batch is full
+    pub(crate) fn push(&mut self, key: &[u8], value: &[u8]) -> bool {
+        let start = self.batch.buf.len();
+        self.batch.buf.extend_from_slice(key);
+        let key_end = self.batch.buf.len();
+        if self.values {
+            self.batch.buf.extend_from_slice(value);
+        }
+        self.batch.rows.push((start, key_end, self.batch.buf.len()));
+        self.batch.rows.len() >= self.batch_size
+    }
+
+    // Sends the current batch, returns false if the stream was dropped
+    pub(crate) async fn flush(&mut self) -> bool {
+        if !self.batch.rows.is_empty() {
+            let batch = std::mem::take(&mut self.batch);
+            self.tx.send(Ok(batch)).await.is_ok()
+        } else {
+            !self.tx.is_closed()
+        }
+    }
+
+    // Same as flush, for backends reading the range on a blocking thread
+    pub(crate) fn blocking_flush(&mut self) -> bool {
+        if !self.batch.rows.is_empty() {
+            let batch = std::mem::take(&mut self.batch);
+            self.tx.blocking_send(Ok(batch)).is_ok()
+        } else {
+            !self.tx.is_closed()
+        }
+    }
+
+    pub(crate) async fn finish(mut self, result: trc::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.flush().await;
+            }
+            Err(err) => {
+                let _ = self.tx.send(Err(err)).await;
+            }
+        }
+    }
+
+    pub(crate) fn blocking_finish(mut self, result: trc::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.blocking_flush();
+            }
+            Err(err) => {
+                let _ = self.tx.blocking_send(Err(err));
+            }
+        }
+    }
+}
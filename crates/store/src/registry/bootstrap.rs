@@ -25,6 +25,40 @@ pub struct Bootstrap {
     pub warnings: Vec<Warning>,
     pub has_fatal_errors: bool,
     pub role: Option<ClusterRole>,
+    pub references: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceKind {
+    DeliverySchedule,
+    Route,
+    ConnectionStrategy,
+    TlsStrategy,
+    SieveScript,
+    DkimSigner,
+    LookupStore,
+}
+
+impl ReferenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::DeliverySchedule => "delivery schedule",
+            ReferenceKind::Route => "route",
+            ReferenceKind::ConnectionStrategy => "connection strategy",
+            ReferenceKind::TlsStrategy => "TLS strategy",
+            ReferenceKind::SieveScript => "system Sieve script",
+            ReferenceKind::DkimSigner => "DKIM signing domain",
+            ReferenceKind::LookupStore => "lookup store",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub object_id: ObjectId,
+    pub property: Property,
+    pub kind: ReferenceKind,
+    pub name: String,
 }
 
 impl Bootstrap {
@@ -58,6 +92,7 @@ impl Bootstrap {
             warnings: Vec::new(),
             has_fatal_errors: false,
             role: None,
+            references: Vec::new(),
         }
     }
 
@@ -170,6 +205,21 @@ impl Bootstrap {
         });
     }
 
+    pub fn add_reference(
+        &mut self,
+        object_id: ObjectId,
+        property: Property,
+        kind: ReferenceKind,
+        name: impl Into<String>,
+    ) {
+        self.references.push(Reference {
+            object_id,
+            property,
+            kind,
+            name: name.into(),
+        });
+    }
+
     pub fn validate(&mut self, id: ObjectId, object: &impl ObjectImpl) -> bool {
         let mut errors = Vec::new();
         if object.validate(&mut errors) {
@@ -19,8 +19,8 @@ use registry::{
     schema::{
         prelude::{Object, ObjectInner, ObjectType, Property},
         structs::{
-            ArchivedItem, DmarcInternalReport, Metric, SpamTrainingSample, Task, TlsInternalReport,
-            Trace,
+            ArchivedItem, AuditEvent, DmarcInternalReport, Metric, SpamTrainingSample, Task,
+            TlsInternalReport, Trace,
         },
     },
     types::{EnumImpl, ObjectImpl, id::ObjectId},
@@ -236,3 +236,16 @@ impl Deserialize for Metric {
             })
     }
 }
+
+impl Deserialize for AuditEvent {
+    fn deserialize(bytes: &[u8]) -> trc::Result<Self> {
+        PickledStream::new(bytes)
+            .and_then(|mut stream| Self::unpickle(&mut stream))
+            .ok_or_else(|| {
+                trc::EventType::Registry(trc::RegistryEvent::DeserializationError)
+                    .into_err()
+                    .caused_by(trc::location!())
+                    .ctx(trc::Key::Value, bytes)
+            })
+    }
+}
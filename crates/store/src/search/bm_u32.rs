@@ -77,7 +77,10 @@ impl BitmapCache {
                     let mut documents = RoaringBitmap::new();
                     store
                         .iterate(
-                            IterateParams::new(from_key, to_key).no_values().ascending(),
+                            IterateParams::new(from_key, to_key)
+                                .no_values()
+                                .ascending()
+                                .read_only(),
                             |key, _| {
                                 if key.len() == key_len {
                                     documents.insert(key.deserialize_be_u32(key.len() - U32_LEN)?);
@@ -183,7 +186,10 @@ pub(crate) async fn range_to_bitmap(
 
     store
         .iterate(
-            IterateParams::new(begin, end).no_values().ascending(),
+            IterateParams::new(begin, end)
+                .no_values()
+                .ascending()
+                .read_only(),
             |key, _| {
                 if !key.starts_with(&prefix) {
                     return Ok(false);
@@ -257,7 +263,10 @@ pub(crate) async fn sort_order(
     let mut pos = 0;
     store
         .iterate(
-            IterateParams::new(begin, end).no_values().ascending(),
+            IterateParams::new(begin, end)
+                .no_values()
+                .ascending()
+                .read_only(),
             |key, _| {
                 let value = key
                     .get(U32_LEN + 2..key.len() - U32_LEN)
@@ -70,7 +70,10 @@ impl TreemapCache {
                     let mut documents = RoaringTreemap::new();
                     store
                         .iterate(
-                            IterateParams::new(from_key, to_key).no_values().ascending(),
+                            IterateParams::new(from_key, to_key)
+                                .no_values()
+                                .ascending()
+                                .read_only(),
                             |key, _| {
                                 if key.len() == key_len {
                                     documents.insert(key.deserialize_be_u64(key.len() - U64_LEN)?);
@@ -168,7 +171,10 @@ pub(crate) async fn range_to_treemap(
 
     store
         .iterate(
-            IterateParams::new(begin, end).no_values().ascending(),
+            IterateParams::new(begin, end)
+                .no_values()
+                .ascending()
+                .read_only(),
             |key, _| {
                 if !key.starts_with(&prefix) {
                     return Ok(false);
@@ -11,9 +11,17 @@ impl IndexDocument {
         Self {
             fields: Default::default(),
             index,
+            replace: false,
         }
     }
 
+    /// Removes any terms previously indexed for this document, used when
+    /// reindexing documents that are otherwise immutable.
+    pub fn with_replace(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+
     pub fn with_account_id(mut self, account_id: u32) -> Self {
         self.fields
             .insert(SearchField::AccountId, SearchValue::Uint(account_id as u64));
@@ -29,7 +29,7 @@ impl Store {
             let index = document.index;
             let mut old_term_index = None;
 
-            if matches!(index, SearchIndex::Calendar | SearchIndex::Contacts) {
+            if document.replace || matches!(index, SearchIndex::Calendar | SearchIndex::Contacts) {
                 let mut account_id = None;
                 let mut document_id = None;
                 for (field, value) in &document.fields {
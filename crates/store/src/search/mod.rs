@@ -158,6 +158,7 @@ pub enum SearchComparator {
 pub struct IndexDocument {
     pub(crate) index: SearchIndex,
     pub(crate) fields: AHashMap<SearchField, SearchValue>,
+    pub(crate) replace: bool,
 }
 
 #[derive(Debug)]
@@ -465,6 +465,49 @@ impl BatchBuilder {
         self.ops.as_slice()
     }
 
+    /// Returns the net disk quota change per account in this batch.
+    pub fn quota_changes(&self) -> VecMap<u32, i64> {
+        let mut account_id = None;
+        let mut changes = VecMap::new();
+        for op in &self.ops {
+            match op {
+                Operation::AccountId {
+                    account_id: op_account_id,
+                } => {
+                    account_id = Some(*op_account_id);
+                }
+                Operation::Value {
+                    class: ValueClass::Quota,
+                    op: ValueOp::AtomicAdd(value) | ValueOp::AddAndGet(value),
+                } => {
+                    if let Some(account_id) = account_id {
+                        *changes.get_mut_or_insert(account_id) += *value;
+                    }
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    /// Removes all quota and mailbox size counter updates from the batch,
+    /// simulating a crash between writing the data and its counters.
+    #[cfg(feature = "test_mode")]
+    pub fn discard_counters(&mut self) -> &mut Self {
+        self.ops.retain(|op| {
+            !matches!(
+                op,
+                Operation::Value {
+                    class: ValueClass::Quota
+                        | ValueClass::TenantQuota(_)
+                        | ValueClass::MailboxSize(_),
+                    op: ValueOp::AtomicAdd(_),
+                }
+            )
+        });
+        self
+    }
+
     pub fn len(&self) -> usize {
         self.batch_size
     }
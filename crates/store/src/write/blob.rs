@@ -78,6 +78,51 @@ impl Store {
         self.key_exists(key).await
     }
 
+    pub async fn blob_document_links(
+        &self,
+        hash: impl AsRef<BlobHash> + Sync + Send,
+        account_id: u32,
+        collection: u8,
+    ) -> trc::Result<Vec<u32>> {
+        const DOC_LINK: usize = BLOB_HASH_LEN + U32_LEN + 1 + U32_LEN;
+
+        let mut document_ids = Vec::new();
+        self.iterate(
+            IterateParams::new(
+                ValueKey {
+                    account_id,
+                    collection,
+                    document_id: 0,
+                    class: ValueClass::Blob(BlobOp::Link {
+                        hash: hash.as_ref().clone(),
+                        to: BlobLink::Document,
+                    }),
+                },
+                ValueKey {
+                    account_id,
+                    collection,
+                    document_id: u32::MAX,
+                    class: ValueClass::Blob(BlobOp::Link {
+                        hash: hash.as_ref().clone(),
+                        to: BlobLink::Document,
+                    }),
+                },
+            )
+            .no_values()
+            .ascending(),
+            |key, _| {
+                // Temporary links share the same prefix
+                if key.len() == DOC_LINK {
+                    document_ids.push(key.deserialize_be_u32(DOC_LINK - U32_LEN)?);
+                }
+                Ok(true)
+            },
+        )
+        .await
+        .caused_by(trc::location!())
+        .map(|_| document_ids)
+    }
+
     pub async fn purge_blobs_all_shards(&self, blob_store: BlobStore) -> trc::Result<()> {
         for shard_index in 0u8..=255 {
             self.purge_blobs(blob_store.clone(), shard_index).await?;
@@ -5,7 +5,8 @@
  */
 
 use super::{
-    AnyKey, BlobOp, InMemoryClass, QueueClass, TaskQueueClass, TelemetryClass, ValueClass,
+    AnyKey, AuditClass, BlobOp, InMemoryClass, QueueClass, TaskQueueClass, TelemetryClass,
+    ValueClass,
 };
 use crate::{
     IndexKey, IndexKeyPrefix, Key, LogKey, SUBSPACE_ACL, SUBSPACE_BLOB_LINK, SUBSPACE_COUNTER,
@@ -198,6 +199,10 @@ impl Key for IndexKeyPrefix {
     fn subspace(&self) -> u8 {
         SUBSPACE_INDEXES
     }
+
+    fn account_id(&self) -> Option<u32> {
+        Some(self.account_id)
+    }
 }
 
 impl IndexKeyPrefix {
@@ -224,6 +229,10 @@ impl Key for LogKey {
         .write(self.change_id)
         .finalize()
     }
+
+    fn account_id(&self) -> Option<u32> {
+        Some(self.account_id)
+    }
 }
 
 impl<T: AsRef<ValueClass> + Sync + Send + Clone> Key for ValueKey<T> {
@@ -236,6 +245,10 @@ impl<T: AsRef<ValueClass> + Sync + Send + Clone> Key for ValueKey<T> {
             .as_ref()
             .serialize(self.account_id, self.collection, self.document_id, flags)
     }
+
+    fn account_id(&self) -> Option<u32> {
+        Some(self.account_id)
+    }
 }
 
 impl ValueClass {
@@ -350,10 +363,32 @@ impl ValueClass {
                 TelemetryClass::Span(span_id) => serializer.write(*span_id),
                 TelemetryClass::Metric(metric_id) => serializer.write(*metric_id),
             },
+            ValueClass::Audit(audit) => match audit {
+                AuditClass::Event(event_id) => serializer
+                    .write(u32::MAX)
+                    .write(AUDIT_EVENT)
+                    .write(*event_id),
+                AuditClass::Index {
+                    account_id,
+                    event_id,
+                } => serializer
+                    .write(u32::MAX)
+                    .write(AUDIT_INDEX)
+                    .write(*account_id)
+                    .write(*event_id),
+            },
             ValueClass::DocumentId => serializer.write(account_id).write(collection),
             ValueClass::ChangeId => serializer.write(account_id),
             ValueClass::Quota => serializer.write(account_id).write(u8::MAX),
             ValueClass::TenantQuota(tenant_id) => serializer.write(*tenant_id).write(u8::MAX - 1),
+            ValueClass::MailboxSize(mailbox_id) => serializer
+                .write(account_id)
+                .write(u8::MAX - 2)
+                .write(*mailbox_id),
+            ValueClass::BlobReference(hash) => serializer
+                .write(account_id)
+                .write(u8::MAX)
+                .write::<&[u8]>(hash.as_ref()),
             ValueClass::NodeId(node_id) => serializer.write(u32::MAX).write(*node_id),
             ValueClass::ShareNotification {
                 notification_id,
@@ -445,6 +480,10 @@ impl<T: AsRef<[u8]> + Sync + Send + Clone> Key for IndexKey<T> {
         .write(self.document_id)
         .finalize()
     }
+
+    fn account_id(&self) -> Option<u32> {
+        Some(self.account_id)
+    }
 }
 
 impl<T: AsRef<[u8]> + Sync + Send + Clone> Key for AnyKey<T> {
@@ -465,6 +504,8 @@ impl<T: AsRef<[u8]> + Sync + Send + Clone> Key for AnyKey<T> {
 }
 
 const MAILBOX_COLLECTION: u8 = Collection::Mailbox as u8;
+const AUDIT_EVENT: u8 = u8::MAX;
+const AUDIT_INDEX: u8 = u8::MAX - 1;
 const MAILBOX_COUNTER_FIELD: u8 = MailboxField::UidCounter as u8;
 const REG_ARCHIVED_ITEM: u16 = ObjectType::ArchivedItem as u16;
 const REG_SPAM_SAMPLE: u16 = ObjectType::SpamTrainingSample as u16;
@@ -522,7 +563,13 @@ impl ValueClass {
             ValueClass::Telemetry(telemetry) => match telemetry {
                 TelemetryClass::Span(_) | TelemetryClass::Metric(_) => U64_LEN + 1,
             },
+            ValueClass::Audit(audit) => match audit {
+                AuditClass::Event(_) => U32_LEN + U64_LEN + 1,
+                AuditClass::Index { .. } => (U32_LEN * 2) + U64_LEN + 1,
+            },
             ValueClass::DocumentId | ValueClass::Quota | ValueClass::TenantQuota(_) => U32_LEN + 1,
+            ValueClass::MailboxSize(_) => (U32_LEN * 2) + 1,
+            ValueClass::BlobReference(_) => U32_LEN + BLOB_HASH_LEN + 1,
             ValueClass::ChangeId => U32_LEN,
             ValueClass::ShareNotification { .. } => U32_LEN + U64_LEN + 1,
             ValueClass::NodeId(_) => (U16_LEN * 3) + 1,
@@ -547,7 +594,7 @@ impl ValueClass {
                     SUBSPACE_PROPERTY
                 }
             }
-            ValueClass::IndexProperty { .. } => SUBSPACE_PROPERTY,
+            ValueClass::IndexProperty { .. } | ValueClass::BlobReference(_) => SUBSPACE_PROPERTY,
             ValueClass::Acl(_) => SUBSPACE_ACL,
             ValueClass::TaskQueue { .. } => SUBSPACE_TASK_QUEUE,
             ValueClass::Blob(op) => match op {
@@ -592,8 +639,9 @@ impl ValueClass {
             ValueClass::DocumentId
             | ValueClass::ChangeId
             | ValueClass::Quota
-            | ValueClass::TenantQuota(_) => SUBSPACE_COUNTER,
-            ValueClass::ShareNotification { .. } => SUBSPACE_LOGS,
+            | ValueClass::TenantQuota(_)
+            | ValueClass::MailboxSize(_) => SUBSPACE_COUNTER,
+            ValueClass::ShareNotification { .. } | ValueClass::Audit(_) => SUBSPACE_LOGS,
             ValueClass::SearchIndex(_) => SUBSPACE_SEARCH_INDEX,
             ValueClass::Any(any) => any.subspace,
         }
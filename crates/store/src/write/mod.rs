@@ -9,11 +9,7 @@ use crate::backend::MAX_TOKEN_LENGTH;
 use log::ChangeLogBuilder;
 use nlp::tokenizers::word::WordTokenizer;
 use rkyv::util::AlignedVec;
-use std::{
-    collections::HashSet,
-    hash::Hash,
-    time::{Duration, SystemTime},
-};
+use std::{collections::HashSet, hash::Hash, time::Duration};
 use types::{
     blob_hash::BlobHash,
     collection::{Collection, SyncCollection, VanishedCollection},
@@ -27,6 +23,8 @@ use utils::{
     map::{bitmap::Bitmap, vec_map::VecMap},
 };
 
+pub use utils::clock::now;
+
 pub mod assert;
 pub mod batch;
 pub mod bitpack;
@@ -173,6 +171,7 @@ pub enum ValueClass {
     Registry(RegistryClass),
     Queue(QueueClass),
     Telemetry(TelemetryClass),
+    Audit(AuditClass),
     SearchIndex(SearchIndexClass),
     Any(AnyClass),
     ShareNotification {
@@ -183,6 +182,8 @@ pub enum ValueClass {
     ChangeId,
     Quota,
     TenantQuota(u32),
+    MailboxSize(u32),
+    BlobReference(BlobHash),
     NodeId(u16),
 }
 
@@ -294,6 +295,12 @@ pub enum TelemetryClass {
     Metric(u64),
 }
 
+#[derive(Debug, PartialEq, Clone, Eq, Hash)]
+pub enum AuditClass {
+    Event(u64),
+    Index { account_id: u32, event_id: u64 },
+}
+
 #[derive(Debug, PartialEq, Clone, Eq, Hash)]
 pub struct QueueEvent {
     pub due: u64,
@@ -388,13 +395,6 @@ pub trait IntoOperations {
     fn build(self, batch: &mut BatchBuilder) -> trc::Result<()>;
 }
 
-#[inline(always)]
-pub fn now() -> u64 {
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .map_or(0, |d| d.as_secs())
-}
-
 impl AsRef<ValueClass> for ValueClass {
     fn as_ref(&self) -> &ValueClass {
         self
@@ -6,19 +6,17 @@
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use crate::MetricType;
+
 pub struct AtomicCounter {
-    id: &'static str,
-    description: &'static str,
-    unit: &'static str,
+    id: MetricType,
     value: AtomicU64,
 }
 
 impl AtomicCounter {
-    pub const fn new(id: &'static str, description: &'static str, unit: &'static str) -> Self {
+    pub const fn new(id: MetricType) -> Self {
         Self {
             id,
-            description,
-            unit,
             value: AtomicU64::new(0),
         }
     }
@@ -33,33 +31,15 @@ impl AtomicCounter {
         self.value.fetch_add(value, Ordering::Relaxed);
     }
 
-    #[inline(always)]
-    pub fn decrement(&self) {
-        self.value.fetch_sub(1, Ordering::Relaxed);
-    }
-
-    #[inline(always)]
-    pub fn decrement_by(&self, value: u64) {
-        self.value.fetch_sub(value, Ordering::Relaxed);
-    }
-
     #[inline(always)]
     pub fn get(&self) -> u64 {
         self.value.load(Ordering::Relaxed)
     }
 
-    pub fn id(&self) -> &'static str {
+    pub fn id(&self) -> MetricType {
         self.id
     }
 
-    pub fn description(&self) -> &'static str {
-        self.description
-    }
-
-    pub fn unit(&self) -> &'static str {
-        self.unit
-    }
-
     pub fn is_active(&self) -> bool {
         self.value.load(Ordering::Relaxed) > 0
     }
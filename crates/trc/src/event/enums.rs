@@ -6,8 +6,8 @@
 
 // This file is auto-generated. Do not edit directly.
 
-pub const TOTAL_EVENT_COUNT: usize = 633;
-pub const TOTAL_METRIC_COUNT: usize = 367;
+pub const TOTAL_EVENT_COUNT: usize = 697;
+pub const TOTAL_METRIC_COUNT: usize = 387;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EventType {
@@ -79,6 +79,7 @@ pub enum AcmeEvent {
     TlsAlpnError = 24,
     TokenNotFound = 26,
     Error = 15,
+    RenewLocked = 633,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -111,6 +112,8 @@ pub enum AuthEvent {
     Error = 34,
     Warning = 595,
     CredentialExpired = 276,
+    StepUpRequired = 653,
+    ProtocolDisabled = 696,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -157,6 +160,10 @@ pub enum DaneEvent {
     TlsaRecordNotDnssecSigned = 74,
     TlsaRecordInvalid = 72,
     BogusDnssecRecord = 605,
+    RolloverStaged = 690,
+    RolloverMismatch = 691,
+    RolloverActivated = 692,
+    RolloverTimeout = 693,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -200,6 +207,12 @@ pub enum DeliveryEvent {
     DsnPermFail = 87,
     RawInput = 105,
     RawOutput = 106,
+    WarmupLimitExceeded = 634,
+    RelayHostSelected = 678,
+    RelayHostDown = 679,
+    RelayHostUp = 680,
+    RelayHostProbeFailed = 681,
+    DsnSuppressed = 694,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -336,9 +349,13 @@ pub enum ImapEvent {
     Unsubscribe = 194,
     Thread = 193,
     GetQuota = 57,
+    SetQuota = 661,
     Error = 168,
     RawInput = 183,
     RawOutput = 184,
+    GenUrlAuth = 673,
+    ResetKey = 674,
+    Compress = 695,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -396,6 +413,8 @@ pub enum JmapEvent {
     WebsocketStart = 235,
     WebsocketStop = 236,
     WebsocketError = 234,
+    TooManyChanges = 638,
+    SlowMethodCall = 663,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -411,6 +430,7 @@ pub enum LimitEvent {
     BlobQuota = 237,
     TenantQuota = 553,
     TooManyRequests = 245,
+    QuotaWarning = 651,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -462,6 +482,7 @@ pub enum MessageIngestEvent {
     Duplicate = 281,
     Error = 282,
     SearchIndex = 142,
+    LoopDetected = 682,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -593,6 +614,9 @@ pub enum QueueEvent {
     ConcurrencyLimitExceeded = 375,
     QuotaExceeded = 383,
     BackPressure = 48,
+    OutsideDeliveryWindow = 642,
+    MessageSplit = 677,
+    Unsubscribe = 689,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -619,6 +643,7 @@ pub enum ResourceEvent {
     DownloadExternal = 387,
     ApplicationUpdated = 601,
     ApplicationUnpacked = 602,
+    TemplateError = 686,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -645,6 +670,9 @@ pub enum ServerEvent {
     Licensing = 391,
     RecoveryMode = 603,
     BootstrapMode = 604,
+    Draining = 656,
+    DrainTimeout = 657,
+    HealthCheckFailed = 687,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -662,6 +690,8 @@ pub enum SieveEvent {
     UnexpectedError = 407,
     NotSupported = 402,
     QuotaExceeded = 403,
+    VacationSuppressed = 654,
+    RedirectLimitExceeded = 672,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -752,6 +782,25 @@ pub enum SmtpEvent {
     UnsupportedParameter = 486,
     SyntaxError = 480,
     RequestTooLarge = 470,
+    SenderVerifyPass = 635,
+    SenderVerifyFail = 636,
+    SenderVerifyTempFail = 637,
+    FromHeaderUnauthorized = 645,
+    FromHeaderRewritten = 646,
+    ExtensionNotOffered = 650,
+    ExpansionLoop = 658,
+    ExpansionDepthExceeded = 659,
+    ExpansionLimitExceeded = 660,
+    ClientCertAuthenticated = 664,
+    ClientCertRejected = 665,
+    IprevRequired = 666,
+    SpfDryRunReject = 668,
+    DkimDryRunReject = 669,
+    ArcDryRunReject = 670,
+    DmarcDryRunReject = 671,
+    Burl = 675,
+    BurlFailed = 676,
+    BatvInvalid = 685,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -770,6 +819,9 @@ pub enum SpamEvent {
     ModelNotReady = 496,
     ModelNotFound = 497,
     RulesUpdated = 280,
+    ClamAv = 639,
+    ClamAvError = 640,
+    SenderReputation = 684,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -827,6 +879,8 @@ pub enum StoreEvent {
     AutoExpunge = 364,
     BlobStorePurged = 369,
     DataStorePurged = 368,
+    HttpStoreUnavailable = 667,
+    ChangesPruned = 683,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -843,6 +897,14 @@ pub enum TaskManagerEvent {
     MetadataNotFound = 145,
     SchedulerStarted = 150,
     ManagerStarted = 367,
+    ReEncryptSkipped = 643,
+    ReEncryptCompleted = 644,
+    ReindexCompleted = 647,
+    ImportCompleted = 648,
+    ImportFailed = 649,
+    QuotaWarningSent = 652,
+    QuotaReconciled = 662,
+    BlobReferencesRecounted = 688,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -859,6 +921,7 @@ pub enum TelemetryEvent {
     MetricsCollected = 151,
     MetricsStored = 366,
     MetricsPushed = 146,
+    AuditError = 641,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -871,6 +934,7 @@ pub enum TlsEvent {
     NoCertificatesAvailable = 546,
     MultipleCertificatesAvailable = 545,
     ExpiredCertificateRemoved = 277,
+    PolicyRejected = 655,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -1035,6 +1099,10 @@ pub enum MetricType {
     ImapActiveConnections = 18,
     ImapConnectionStart = 118,
     ImapConnectionEnd = 119,
+    ImapCommandTime = 367,
+    ImapCommandCount = 368,
+    ImapFetchBytes = 369,
+    ImapAppendBytes = 370,
     IncomingReportDmarcReport = 120,
     IncomingReportDmarcReportWithWarnings = 121,
     IncomingReportTlsReport = 122,
@@ -1138,6 +1206,14 @@ pub enum MetricType {
     PushSubscriptionError = 214,
     PushSubscriptionNotFound = 215,
     QueueCount = 24,
+    QueueMessages = 371,
+    QueueBytes = 372,
+    QueueOldestAge = 373,
+    QueueMessageAge = 374,
+    QueueThrottleDelay = 386,
+    QueueDomainAttempts = 375,
+    QueueDomainDeferrals = 376,
+    QueueDomainBounces = 377,
     QueueMessageQueued = 216,
     QueueAuthenticatedMessageQueued = 217,
     QueueReportQueued = 218,
@@ -1171,6 +1247,7 @@ pub enum MetricType {
     SieveUnexpectedError = 243,
     SieveNotSupported = 244,
     SieveQuotaExceeded = 245,
+    SieveRedirectLimitExceeded = 384,
     SmtpRequestTime = 15,
     SmtpActiveConnections = 20,
     SmtpConnectionStart = 246,
@@ -1196,6 +1273,10 @@ pub enum MetricType {
     SmtpSpfFromFail = 263,
     SmtpDmarcPass = 264,
     SmtpDmarcFail = 265,
+    SmtpSpfDryRunReject = 380,
+    SmtpDkimDryRunReject = 381,
+    SmtpArcDryRunReject = 382,
+    SmtpDmarcDryRunReject = 383,
     SmtpIprevPass = 266,
     SmtpIprevFail = 267,
     SmtpTooManyMessages = 268,
@@ -1233,6 +1314,8 @@ pub enum MetricType {
     StoreDataReadTime = 7,
     StoreDataWriteTime = 8,
     StoreBlobReadTime = 9,
+    StoreBlobReadBytes = 379,
+    StoreChangesPruned = 385,
     StoreBlobWriteTime = 10,
     StoreAssertValueFailed = 300,
     StoreFoundationdbError = 301,
@@ -1273,6 +1356,7 @@ pub enum MetricType {
     TelemetryPrometheusExporterError = 334,
     TelemetryJournalError = 335,
     TlsHandshakeError = 336,
+    TlsPolicyRejected = 378,
     UserCount = 25,
 }
 
@@ -1346,4 +1430,9 @@ pub enum Key {
     Value = 63,
     Version = 64,
     QueueName = 65,
+    StoreElapsed = 66,
+    StoreReads = 67,
+    SerializeElapsed = 68,
+    Metadata = 69,
+    ParseElapsed = 70,
 }
@@ -34,6 +34,7 @@ impl EventType {
             b"acme.tls-alpn-error" => EventType::Acme(AcmeEvent::TlsAlpnError),
             b"acme.token-not-found" => EventType::Acme(AcmeEvent::TokenNotFound),
             b"acme.error" => EventType::Acme(AcmeEvent::Error),
+            b"acme.renew-locked" => EventType::Acme(AcmeEvent::RenewLocked),
             b"ai.llm-response" => EventType::Ai(AiEvent::LlmResponse),
             b"ai.api-error" => EventType::Ai(AiEvent::ApiError),
             b"arc.chain-too-long" => EventType::Arc(ArcEvent::ChainTooLong),
@@ -51,6 +52,8 @@ impl EventType {
             b"auth.error" => EventType::Auth(AuthEvent::Error),
             b"auth.warning" => EventType::Auth(AuthEvent::Warning),
             b"auth.credential-expired" => EventType::Auth(AuthEvent::CredentialExpired),
+            b"auth.step-up-required" => EventType::Auth(AuthEvent::StepUpRequired),
+            b"auth.protocol-disabled" => EventType::Auth(AuthEvent::ProtocolDisabled),
             b"calendar.rule-expansion-error" => EventType::Calendar(CalendarEvent::RuleExpansionError),
             b"calendar.alarm-sent" => EventType::Calendar(CalendarEvent::AlarmSent),
             b"calendar.alarm-skipped" => EventType::Calendar(CalendarEvent::AlarmSkipped),
@@ -82,6 +85,10 @@ impl EventType {
             b"dane.tlsa-record-not-dnssec-signed" => EventType::Dane(DaneEvent::TlsaRecordNotDnssecSigned),
             b"dane.tlsa-record-invalid" => EventType::Dane(DaneEvent::TlsaRecordInvalid),
             b"dane.bogus-dnssec-record" => EventType::Dane(DaneEvent::BogusDnssecRecord),
+            b"dane.rollover-staged" => EventType::Dane(DaneEvent::RolloverStaged),
+            b"dane.rollover-mismatch" => EventType::Dane(DaneEvent::RolloverMismatch),
+            b"dane.rollover-activated" => EventType::Dane(DaneEvent::RolloverActivated),
+            b"dane.rollover-timeout" => EventType::Dane(DaneEvent::RolloverTimeout),
             b"delivery.attempt-start" => EventType::Delivery(DeliveryEvent::AttemptStart),
             b"delivery.attempt-end" => EventType::Delivery(DeliveryEvent::AttemptEnd),
             b"delivery.completed" => EventType::Delivery(DeliveryEvent::Completed),
@@ -120,6 +127,12 @@ impl EventType {
             b"delivery.dsn-perm-fail" => EventType::Delivery(DeliveryEvent::DsnPermFail),
             b"delivery.raw-input" => EventType::Delivery(DeliveryEvent::RawInput),
             b"delivery.raw-output" => EventType::Delivery(DeliveryEvent::RawOutput),
+            b"delivery.warmup-limit-exceeded" => EventType::Delivery(DeliveryEvent::WarmupLimitExceeded),
+            b"delivery.relay-host-selected" => EventType::Delivery(DeliveryEvent::RelayHostSelected),
+            b"delivery.relay-host-down" => EventType::Delivery(DeliveryEvent::RelayHostDown),
+            b"delivery.relay-host-up" => EventType::Delivery(DeliveryEvent::RelayHostUp),
+            b"delivery.relay-host-probe-failed" => EventType::Delivery(DeliveryEvent::RelayHostProbeFailed),
+            b"delivery.dsn-suppressed" => EventType::Delivery(DeliveryEvent::DsnSuppressed),
             b"dkim.pass" => EventType::Dkim(DkimEvent::Pass),
             b"dkim.neutral" => EventType::Dkim(DkimEvent::Neutral),
             b"dkim.fail" => EventType::Dkim(DkimEvent::Fail),
@@ -229,6 +242,10 @@ impl EventType {
             b"imap.error" => EventType::Imap(ImapEvent::Error),
             b"imap.raw-input" => EventType::Imap(ImapEvent::RawInput),
             b"imap.raw-output" => EventType::Imap(ImapEvent::RawOutput),
+            b"imap.set-quota" => EventType::Imap(ImapEvent::SetQuota),
+            b"imap.gen-url-auth" => EventType::Imap(ImapEvent::GenUrlAuth),
+            b"imap.reset-key" => EventType::Imap(ImapEvent::ResetKey),
+            b"imap.compress" => EventType::Imap(ImapEvent::Compress),
             b"incoming-report.dmarc-report" => EventType::IncomingReport(IncomingReportEvent::DmarcReport),
             b"incoming-report.dmarc-report-with-warnings" => EventType::IncomingReport(IncomingReportEvent::DmarcReportWithWarnings),
             b"incoming-report.tls-report" => EventType::IncomingReport(IncomingReportEvent::TlsReport),
@@ -271,6 +288,8 @@ impl EventType {
             b"jmap.websocket-start" => EventType::Jmap(JmapEvent::WebsocketStart),
             b"jmap.websocket-stop" => EventType::Jmap(JmapEvent::WebsocketStop),
             b"jmap.websocket-error" => EventType::Jmap(JmapEvent::WebsocketError),
+            b"jmap.too-many-changes" => EventType::Jmap(JmapEvent::TooManyChanges),
+            b"jmap.slow-method-call" => EventType::Jmap(JmapEvent::SlowMethodCall),
             b"limit.size-request" => EventType::Limit(LimitEvent::SizeRequest),
             b"limit.size-upload" => EventType::Limit(LimitEvent::SizeUpload),
             b"limit.calls-in" => EventType::Limit(LimitEvent::CallsIn),
@@ -281,6 +300,7 @@ impl EventType {
             b"limit.blob-quota" => EventType::Limit(LimitEvent::BlobQuota),
             b"limit.tenant-quota" => EventType::Limit(LimitEvent::TenantQuota),
             b"limit.too-many-requests" => EventType::Limit(LimitEvent::TooManyRequests),
+            b"limit.quota-warning" => EventType::Limit(LimitEvent::QuotaWarning),
             b"mail-auth.parse-error" => EventType::MailAuth(MailAuthEvent::ParseError),
             b"mail-auth.missing-parameters" => EventType::MailAuth(MailAuthEvent::MissingParameters),
             b"mail-auth.no-headers-found" => EventType::MailAuth(MailAuthEvent::NoHeadersFound),
@@ -317,6 +337,7 @@ impl EventType {
             b"message-ingest.duplicate" => EventType::MessageIngest(MessageIngestEvent::Duplicate),
             b"message-ingest.error" => EventType::MessageIngest(MessageIngestEvent::Error),
             b"message-ingest.search-index" => EventType::MessageIngest(MessageIngestEvent::SearchIndex),
+            b"message-ingest.loop-detected" => EventType::MessageIngest(MessageIngestEvent::LoopDetected),
             b"milter.read" => EventType::Milter(MilterEvent::Read),
             b"milter.write" => EventType::Milter(MilterEvent::Write),
             b"milter.action-accept" => EventType::Milter(MilterEvent::ActionAccept),
@@ -408,6 +429,9 @@ impl EventType {
             b"queue.concurrency-limit-exceeded" => EventType::Queue(QueueEvent::ConcurrencyLimitExceeded),
             b"queue.quota-exceeded" => EventType::Queue(QueueEvent::QuotaExceeded),
             b"queue.back-pressure" => EventType::Queue(QueueEvent::BackPressure),
+            b"queue.outside-delivery-window" => EventType::Queue(QueueEvent::OutsideDeliveryWindow),
+            b"queue.message-split" => EventType::Queue(QueueEvent::MessageSplit),
+            b"queue.unsubscribe" => EventType::Queue(QueueEvent::Unsubscribe),
             b"registry.local-read-error" => EventType::Registry(RegistryEvent::LocalReadError),
             b"registry.local-write-error" => EventType::Registry(RegistryEvent::LocalWriteError),
             b"registry.local-parse-error" => EventType::Registry(RegistryEvent::LocalParseError),
@@ -424,6 +448,7 @@ impl EventType {
             b"resource.download-external" => EventType::Resource(ResourceEvent::DownloadExternal),
             b"resource.application-updated" => EventType::Resource(ResourceEvent::ApplicationUpdated),
             b"resource.application-unpacked" => EventType::Resource(ResourceEvent::ApplicationUnpacked),
+            b"resource.template-error" => EventType::Resource(ResourceEvent::TemplateError),
             b"security.authentication-ban" => EventType::Security(SecurityEvent::AuthenticationBan),
             b"security.abuse-ban" => EventType::Security(SecurityEvent::AbuseBan),
             b"security.scan-ban" => EventType::Security(SecurityEvent::ScanBan),
@@ -440,6 +465,9 @@ impl EventType {
             b"server.licensing" => EventType::Server(ServerEvent::Licensing),
             b"server.recovery-mode" => EventType::Server(ServerEvent::RecoveryMode),
             b"server.bootstrap-mode" => EventType::Server(ServerEvent::BootstrapMode),
+            b"server.draining" => EventType::Server(ServerEvent::Draining),
+            b"server.drain-timeout" => EventType::Server(ServerEvent::DrainTimeout),
+            b"server.health-check-failed" => EventType::Server(ServerEvent::HealthCheckFailed),
             b"sieve.action-accept" => EventType::Sieve(SieveEvent::ActionAccept),
             b"sieve.action-accept-replace" => EventType::Sieve(SieveEvent::ActionAcceptReplace),
             b"sieve.action-discard" => EventType::Sieve(SieveEvent::ActionDiscard),
@@ -452,6 +480,8 @@ impl EventType {
             b"sieve.unexpected-error" => EventType::Sieve(SieveEvent::UnexpectedError),
             b"sieve.not-supported" => EventType::Sieve(SieveEvent::NotSupported),
             b"sieve.quota-exceeded" => EventType::Sieve(SieveEvent::QuotaExceeded),
+            b"sieve.vacation-suppressed" => EventType::Sieve(SieveEvent::VacationSuppressed),
+            b"sieve.redirect-limit-exceeded" => EventType::Sieve(SieveEvent::RedirectLimitExceeded),
             b"smtp.connection-start" => EventType::Smtp(SmtpEvent::ConnectionStart),
             b"smtp.connection-end" => EventType::Smtp(SmtpEvent::ConnectionEnd),
             b"smtp.error" => EventType::Smtp(SmtpEvent::Error),
@@ -537,6 +567,25 @@ impl EventType {
             b"smtp.unsupported-parameter" => EventType::Smtp(SmtpEvent::UnsupportedParameter),
             b"smtp.syntax-error" => EventType::Smtp(SmtpEvent::SyntaxError),
             b"smtp.request-too-large" => EventType::Smtp(SmtpEvent::RequestTooLarge),
+            b"smtp.sender-verify-pass" => EventType::Smtp(SmtpEvent::SenderVerifyPass),
+            b"smtp.sender-verify-fail" => EventType::Smtp(SmtpEvent::SenderVerifyFail),
+            b"smtp.sender-verify-temp-fail" => EventType::Smtp(SmtpEvent::SenderVerifyTempFail),
+            b"smtp.from-header-unauthorized" => EventType::Smtp(SmtpEvent::FromHeaderUnauthorized),
+            b"smtp.from-header-rewritten" => EventType::Smtp(SmtpEvent::FromHeaderRewritten),
+            b"smtp.extension-not-offered" => EventType::Smtp(SmtpEvent::ExtensionNotOffered),
+            b"smtp.expansion-loop" => EventType::Smtp(SmtpEvent::ExpansionLoop),
+            b"smtp.expansion-depth-exceeded" => EventType::Smtp(SmtpEvent::ExpansionDepthExceeded),
+            b"smtp.expansion-limit-exceeded" => EventType::Smtp(SmtpEvent::ExpansionLimitExceeded),
+            b"smtp.client-cert-authenticated" => EventType::Smtp(SmtpEvent::ClientCertAuthenticated),
+            b"smtp.client-cert-rejected" => EventType::Smtp(SmtpEvent::ClientCertRejected),
+            b"smtp.iprev-required" => EventType::Smtp(SmtpEvent::IprevRequired),
+            b"smtp.spf-dry-run-reject" => EventType::Smtp(SmtpEvent::SpfDryRunReject),
+            b"smtp.dkim-dry-run-reject" => EventType::Smtp(SmtpEvent::DkimDryRunReject),
+            b"smtp.arc-dry-run-reject" => EventType::Smtp(SmtpEvent::ArcDryRunReject),
+            b"smtp.dmarc-dry-run-reject" => EventType::Smtp(SmtpEvent::DmarcDryRunReject),
+            b"smtp.burl" => EventType::Smtp(SmtpEvent::Burl),
+            b"smtp.burl-failed" => EventType::Smtp(SmtpEvent::BurlFailed),
+            b"smtp.batv-invalid" => EventType::Smtp(SmtpEvent::BatvInvalid),
             b"spam.pyzor" => EventType::Spam(SpamEvent::Pyzor),
             b"spam.pyzor-error" => EventType::Spam(SpamEvent::PyzorError),
             b"spam.dnsbl" => EventType::Spam(SpamEvent::Dnsbl),
@@ -550,6 +599,9 @@ impl EventType {
             b"spam.model-not-ready" => EventType::Spam(SpamEvent::ModelNotReady),
             b"spam.model-not-found" => EventType::Spam(SpamEvent::ModelNotFound),
             b"spam.rules-updated" => EventType::Spam(SpamEvent::RulesUpdated),
+            b"spam.clamav" => EventType::Spam(SpamEvent::ClamAv),
+            b"spam.clamav-error" => EventType::Spam(SpamEvent::ClamAvError),
+            b"spam.sender-reputation" => EventType::Spam(SpamEvent::SenderReputation),
             b"spf.pass" => EventType::Spf(SpfEvent::Pass),
             b"spf.fail" => EventType::Spf(SpfEvent::Fail),
             b"spf.soft-fail" => EventType::Spf(SpfEvent::SoftFail),
@@ -597,6 +649,8 @@ impl EventType {
             b"store.auto-expunge" => EventType::Store(StoreEvent::AutoExpunge),
             b"store.blob-store-purged" => EventType::Store(StoreEvent::BlobStorePurged),
             b"store.data-store-purged" => EventType::Store(StoreEvent::DataStorePurged),
+            b"store.http-store-unavailable" => EventType::Store(StoreEvent::HttpStoreUnavailable),
+            b"store.changes-pruned" => EventType::Store(StoreEvent::ChangesPruned),
             b"task-manager.task-acquired" => EventType::TaskManager(TaskManagerEvent::TaskAcquired),
             b"task-manager.task-queued" => EventType::TaskManager(TaskManagerEvent::TaskQueued),
             b"task-manager.task-scheduled" => EventType::TaskManager(TaskManagerEvent::TaskScheduled),
@@ -608,6 +662,14 @@ impl EventType {
             b"task-manager.metadata-not-found" => EventType::TaskManager(TaskManagerEvent::MetadataNotFound),
             b"task-manager.scheduler-started" => EventType::TaskManager(TaskManagerEvent::SchedulerStarted),
             b"task-manager.manager-started" => EventType::TaskManager(TaskManagerEvent::ManagerStarted),
+            b"task-manager.re-encrypt-skipped" => EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped),
+            b"task-manager.re-encrypt-completed" => EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted),
+            b"task-manager.reindex-completed" => EventType::TaskManager(TaskManagerEvent::ReindexCompleted),
+            b"task-manager.import-completed" => EventType::TaskManager(TaskManagerEvent::ImportCompleted),
+            b"task-manager.import-failed" => EventType::TaskManager(TaskManagerEvent::ImportFailed),
+            b"task-manager.quota-warning-sent" => EventType::TaskManager(TaskManagerEvent::QuotaWarningSent),
+            b"task-manager.quota-reconciled" => EventType::TaskManager(TaskManagerEvent::QuotaReconciled),
+            b"task-manager.blob-references-recounted" => EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted),
             b"telemetry.alert-event" => EventType::Telemetry(TelemetryEvent::AlertEvent),
             b"telemetry.alert-message" => EventType::Telemetry(TelemetryEvent::AlertMessage),
             b"telemetry.log-error" => EventType::Telemetry(TelemetryEvent::LogError),
@@ -619,6 +681,7 @@ impl EventType {
             b"telemetry.metrics-collected" => EventType::Telemetry(TelemetryEvent::MetricsCollected),
             b"telemetry.metrics-stored" => EventType::Telemetry(TelemetryEvent::MetricsStored),
             b"telemetry.metrics-pushed" => EventType::Telemetry(TelemetryEvent::MetricsPushed),
+            b"telemetry.audit-error" => EventType::Telemetry(TelemetryEvent::AuditError),
             b"tls.handshake" => EventType::Tls(TlsEvent::Handshake),
             b"tls.handshake-error" => EventType::Tls(TlsEvent::HandshakeError),
             b"tls.not-configured" => EventType::Tls(TlsEvent::NotConfigured),
@@ -626,6 +689,7 @@ impl EventType {
             b"tls.no-certificates-available" => EventType::Tls(TlsEvent::NoCertificatesAvailable),
             b"tls.multiple-certificates-available" => EventType::Tls(TlsEvent::MultipleCertificatesAvailable),
             b"tls.expired-certificate-removed" => EventType::Tls(TlsEvent::ExpiredCertificateRemoved),
+            b"tls.policy-rejected" => EventType::Tls(TlsEvent::PolicyRejected),
             b"tls-rpt.record-fetch" => EventType::TlsRpt(TlsRptEvent::RecordFetch),
             b"tls-rpt.record-fetch-error" => EventType::TlsRpt(TlsRptEvent::RecordFetchError),
             b"tls-rpt.record-not-found" => EventType::TlsRpt(TlsRptEvent::RecordNotFound),
@@ -673,6 +737,7 @@ impl EventType {
             EventType::Acme(AcmeEvent::TlsAlpnError) => "acme.tls-alpn-error",
             EventType::Acme(AcmeEvent::TokenNotFound) => "acme.token-not-found",
             EventType::Acme(AcmeEvent::Error) => "acme.error",
+            EventType::Acme(AcmeEvent::RenewLocked) => "acme.renew-locked",
             EventType::Ai(AiEvent::LlmResponse) => "ai.llm-response",
             EventType::Ai(AiEvent::ApiError) => "ai.api-error",
             EventType::Arc(ArcEvent::ChainTooLong) => "arc.chain-too-long",
@@ -690,6 +755,8 @@ impl EventType {
             EventType::Auth(AuthEvent::Error) => "auth.error",
             EventType::Auth(AuthEvent::Warning) => "auth.warning",
             EventType::Auth(AuthEvent::CredentialExpired) => "auth.credential-expired",
+            EventType::Auth(AuthEvent::StepUpRequired) => "auth.step-up-required",
+            EventType::Auth(AuthEvent::ProtocolDisabled) => "auth.protocol-disabled",
             EventType::Calendar(CalendarEvent::RuleExpansionError) => {
                 "calendar.rule-expansion-error"
             }
@@ -731,6 +798,10 @@ impl EventType {
             }
             EventType::Dane(DaneEvent::TlsaRecordInvalid) => "dane.tlsa-record-invalid",
             EventType::Dane(DaneEvent::BogusDnssecRecord) => "dane.bogus-dnssec-record",
+            EventType::Dane(DaneEvent::RolloverStaged) => "dane.rollover-staged",
+            EventType::Dane(DaneEvent::RolloverMismatch) => "dane.rollover-mismatch",
+            EventType::Dane(DaneEvent::RolloverActivated) => "dane.rollover-activated",
+            EventType::Dane(DaneEvent::RolloverTimeout) => "dane.rollover-timeout",
             EventType::Delivery(DeliveryEvent::AttemptStart) => "delivery.attempt-start",
             EventType::Delivery(DeliveryEvent::AttemptEnd) => "delivery.attempt-end",
             EventType::Delivery(DeliveryEvent::Completed) => "delivery.completed",
@@ -777,6 +848,16 @@ impl EventType {
             EventType::Delivery(DeliveryEvent::DsnPermFail) => "delivery.dsn-perm-fail",
             EventType::Delivery(DeliveryEvent::RawInput) => "delivery.raw-input",
             EventType::Delivery(DeliveryEvent::RawOutput) => "delivery.raw-output",
+            EventType::Delivery(DeliveryEvent::WarmupLimitExceeded) => {
+                "delivery.warmup-limit-exceeded"
+            }
+            EventType::Delivery(DeliveryEvent::RelayHostSelected) => "delivery.relay-host-selected",
+            EventType::Delivery(DeliveryEvent::RelayHostDown) => "delivery.relay-host-down",
+            EventType::Delivery(DeliveryEvent::RelayHostUp) => "delivery.relay-host-up",
+            EventType::Delivery(DeliveryEvent::RelayHostProbeFailed) => {
+                "delivery.relay-host-probe-failed"
+            }
+            EventType::Delivery(DeliveryEvent::DsnSuppressed) => "delivery.dsn-suppressed",
             EventType::Dkim(DkimEvent::Pass) => "dkim.pass",
             EventType::Dkim(DkimEvent::Neutral) => "dkim.neutral",
             EventType::Dkim(DkimEvent::Fail) => "dkim.fail",
@@ -890,6 +971,10 @@ impl EventType {
             EventType::Imap(ImapEvent::Error) => "imap.error",
             EventType::Imap(ImapEvent::RawInput) => "imap.raw-input",
             EventType::Imap(ImapEvent::RawOutput) => "imap.raw-output",
+            EventType::Imap(ImapEvent::SetQuota) => "imap.set-quota",
+            EventType::Imap(ImapEvent::GenUrlAuth) => "imap.gen-url-auth",
+            EventType::Imap(ImapEvent::ResetKey) => "imap.reset-key",
+            EventType::Imap(ImapEvent::Compress) => "imap.compress",
             EventType::IncomingReport(IncomingReportEvent::DmarcReport) => {
                 "incoming-report.dmarc-report"
             }
@@ -964,6 +1049,8 @@ impl EventType {
             EventType::Jmap(JmapEvent::WebsocketStart) => "jmap.websocket-start",
             EventType::Jmap(JmapEvent::WebsocketStop) => "jmap.websocket-stop",
             EventType::Jmap(JmapEvent::WebsocketError) => "jmap.websocket-error",
+            EventType::Jmap(JmapEvent::TooManyChanges) => "jmap.too-many-changes",
+            EventType::Jmap(JmapEvent::SlowMethodCall) => "jmap.slow-method-call",
             EventType::Limit(LimitEvent::SizeRequest) => "limit.size-request",
             EventType::Limit(LimitEvent::SizeUpload) => "limit.size-upload",
             EventType::Limit(LimitEvent::CallsIn) => "limit.calls-in",
@@ -974,6 +1061,7 @@ impl EventType {
             EventType::Limit(LimitEvent::BlobQuota) => "limit.blob-quota",
             EventType::Limit(LimitEvent::TenantQuota) => "limit.tenant-quota",
             EventType::Limit(LimitEvent::TooManyRequests) => "limit.too-many-requests",
+            EventType::Limit(LimitEvent::QuotaWarning) => "limit.quota-warning",
             EventType::MailAuth(MailAuthEvent::ParseError) => "mail-auth.parse-error",
             EventType::MailAuth(MailAuthEvent::MissingParameters) => "mail-auth.missing-parameters",
             EventType::MailAuth(MailAuthEvent::NoHeadersFound) => "mail-auth.no-headers-found",
@@ -1026,6 +1114,9 @@ impl EventType {
             EventType::MessageIngest(MessageIngestEvent::SearchIndex) => {
                 "message-ingest.search-index"
             }
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected) => {
+                "message-ingest.loop-detected"
+            }
             EventType::Milter(MilterEvent::Read) => "milter.read",
             EventType::Milter(MilterEvent::Write) => "milter.write",
             EventType::Milter(MilterEvent::ActionAccept) => "milter.action-accept",
@@ -1155,6 +1246,9 @@ impl EventType {
             }
             EventType::Queue(QueueEvent::QuotaExceeded) => "queue.quota-exceeded",
             EventType::Queue(QueueEvent::BackPressure) => "queue.back-pressure",
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow) => "queue.outside-delivery-window",
+            EventType::Queue(QueueEvent::MessageSplit) => "queue.message-split",
+            EventType::Queue(QueueEvent::Unsubscribe) => "queue.unsubscribe",
             EventType::Registry(RegistryEvent::LocalReadError) => "registry.local-read-error",
             EventType::Registry(RegistryEvent::LocalWriteError) => "registry.local-write-error",
             EventType::Registry(RegistryEvent::LocalParseError) => "registry.local-parse-error",
@@ -1177,6 +1271,7 @@ impl EventType {
             EventType::Resource(ResourceEvent::ApplicationUnpacked) => {
                 "resource.application-unpacked"
             }
+            EventType::Resource(ResourceEvent::TemplateError) => "resource.template-error",
             EventType::Security(SecurityEvent::AuthenticationBan) => "security.authentication-ban",
             EventType::Security(SecurityEvent::AbuseBan) => "security.abuse-ban",
             EventType::Security(SecurityEvent::ScanBan) => "security.scan-ban",
@@ -1193,6 +1288,9 @@ impl EventType {
             EventType::Server(ServerEvent::Licensing) => "server.licensing",
             EventType::Server(ServerEvent::RecoveryMode) => "server.recovery-mode",
             EventType::Server(ServerEvent::BootstrapMode) => "server.bootstrap-mode",
+            EventType::Server(ServerEvent::Draining) => "server.draining",
+            EventType::Server(ServerEvent::DrainTimeout) => "server.drain-timeout",
+            EventType::Server(ServerEvent::HealthCheckFailed) => "server.health-check-failed",
             EventType::Sieve(SieveEvent::ActionAccept) => "sieve.action-accept",
             EventType::Sieve(SieveEvent::ActionAcceptReplace) => "sieve.action-accept-replace",
             EventType::Sieve(SieveEvent::ActionDiscard) => "sieve.action-discard",
@@ -1205,6 +1303,8 @@ impl EventType {
             EventType::Sieve(SieveEvent::UnexpectedError) => "sieve.unexpected-error",
             EventType::Sieve(SieveEvent::NotSupported) => "sieve.not-supported",
             EventType::Sieve(SieveEvent::QuotaExceeded) => "sieve.quota-exceeded",
+            EventType::Sieve(SieveEvent::VacationSuppressed) => "sieve.vacation-suppressed",
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded) => "sieve.redirect-limit-exceeded",
             EventType::Smtp(SmtpEvent::ConnectionStart) => "smtp.connection-start",
             EventType::Smtp(SmtpEvent::ConnectionEnd) => "smtp.connection-end",
             EventType::Smtp(SmtpEvent::Error) => "smtp.error",
@@ -1294,6 +1394,25 @@ impl EventType {
             EventType::Smtp(SmtpEvent::UnsupportedParameter) => "smtp.unsupported-parameter",
             EventType::Smtp(SmtpEvent::SyntaxError) => "smtp.syntax-error",
             EventType::Smtp(SmtpEvent::RequestTooLarge) => "smtp.request-too-large",
+            EventType::Smtp(SmtpEvent::SenderVerifyPass) => "smtp.sender-verify-pass",
+            EventType::Smtp(SmtpEvent::SenderVerifyFail) => "smtp.sender-verify-fail",
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail) => "smtp.sender-verify-temp-fail",
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized) => "smtp.from-header-unauthorized",
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten) => "smtp.from-header-rewritten",
+            EventType::Smtp(SmtpEvent::ExtensionNotOffered) => "smtp.extension-not-offered",
+            EventType::Smtp(SmtpEvent::ExpansionLoop) => "smtp.expansion-loop",
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded) => "smtp.expansion-depth-exceeded",
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded) => "smtp.expansion-limit-exceeded",
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated) => "smtp.client-cert-authenticated",
+            EventType::Smtp(SmtpEvent::ClientCertRejected) => "smtp.client-cert-rejected",
+            EventType::Smtp(SmtpEvent::IprevRequired) => "smtp.iprev-required",
+            EventType::Smtp(SmtpEvent::SpfDryRunReject) => "smtp.spf-dry-run-reject",
+            EventType::Smtp(SmtpEvent::DkimDryRunReject) => "smtp.dkim-dry-run-reject",
+            EventType::Smtp(SmtpEvent::ArcDryRunReject) => "smtp.arc-dry-run-reject",
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject) => "smtp.dmarc-dry-run-reject",
+            EventType::Smtp(SmtpEvent::Burl) => "smtp.burl",
+            EventType::Smtp(SmtpEvent::BurlFailed) => "smtp.burl-failed",
+            EventType::Smtp(SmtpEvent::BatvInvalid) => "smtp.batv-invalid",
             EventType::Spam(SpamEvent::Pyzor) => "spam.pyzor",
             EventType::Spam(SpamEvent::PyzorError) => "spam.pyzor-error",
             EventType::Spam(SpamEvent::Dnsbl) => "spam.dnsbl",
@@ -1307,6 +1426,9 @@ impl EventType {
             EventType::Spam(SpamEvent::ModelNotReady) => "spam.model-not-ready",
             EventType::Spam(SpamEvent::ModelNotFound) => "spam.model-not-found",
             EventType::Spam(SpamEvent::RulesUpdated) => "spam.rules-updated",
+            EventType::Spam(SpamEvent::ClamAv) => "spam.clamav",
+            EventType::Spam(SpamEvent::ClamAvError) => "spam.clamav-error",
+            EventType::Spam(SpamEvent::SenderReputation) => "spam.sender-reputation",
             EventType::Spf(SpfEvent::Pass) => "spf.pass",
             EventType::Spf(SpfEvent::Fail) => "spf.fail",
             EventType::Spf(SpfEvent::SoftFail) => "spf.soft-fail",
@@ -1354,6 +1476,8 @@ impl EventType {
             EventType::Store(StoreEvent::AutoExpunge) => "store.auto-expunge",
             EventType::Store(StoreEvent::BlobStorePurged) => "store.blob-store-purged",
             EventType::Store(StoreEvent::DataStorePurged) => "store.data-store-purged",
+            EventType::Store(StoreEvent::HttpStoreUnavailable) => "store.http-store-unavailable",
+            EventType::Store(StoreEvent::ChangesPruned) => "store.changes-pruned",
             EventType::TaskManager(TaskManagerEvent::TaskAcquired) => "task-manager.task-acquired",
             EventType::TaskManager(TaskManagerEvent::TaskQueued) => "task-manager.task-queued",
             EventType::TaskManager(TaskManagerEvent::TaskScheduled) => {
@@ -1373,6 +1497,28 @@ impl EventType {
             EventType::TaskManager(TaskManagerEvent::ManagerStarted) => {
                 "task-manager.manager-started"
             }
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped) => {
+                "task-manager.re-encrypt-skipped"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted) => {
+                "task-manager.re-encrypt-completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted) => {
+                "task-manager.reindex-completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted) => {
+                "task-manager.import-completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ImportFailed) => "task-manager.import-failed",
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent) => {
+                "task-manager.quota-warning-sent"
+            }
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled) => {
+                "task-manager.quota-reconciled"
+            }
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted) => {
+                "task-manager.blob-references-recounted"
+            }
             EventType::Telemetry(TelemetryEvent::AlertEvent) => "telemetry.alert-event",
             EventType::Telemetry(TelemetryEvent::AlertMessage) => "telemetry.alert-message",
             EventType::Telemetry(TelemetryEvent::LogError) => "telemetry.log-error",
@@ -1390,6 +1536,7 @@ impl EventType {
             EventType::Telemetry(TelemetryEvent::MetricsCollected) => "telemetry.metrics-collected",
             EventType::Telemetry(TelemetryEvent::MetricsStored) => "telemetry.metrics-stored",
             EventType::Telemetry(TelemetryEvent::MetricsPushed) => "telemetry.metrics-pushed",
+            EventType::Telemetry(TelemetryEvent::AuditError) => "telemetry.audit-error",
             EventType::Tls(TlsEvent::Handshake) => "tls.handshake",
             EventType::Tls(TlsEvent::HandshakeError) => "tls.handshake-error",
             EventType::Tls(TlsEvent::NotConfigured) => "tls.not-configured",
@@ -1401,6 +1548,7 @@ impl EventType {
             EventType::Tls(TlsEvent::ExpiredCertificateRemoved) => {
                 "tls.expired-certificate-removed"
             }
+            EventType::Tls(TlsEvent::PolicyRejected) => "tls.policy-rejected",
             EventType::TlsRpt(TlsRptEvent::RecordFetch) => "tls-rpt.record-fetch",
             EventType::TlsRpt(TlsRptEvent::RecordFetchError) => "tls-rpt.record-fetch-error",
             EventType::TlsRpt(TlsRptEvent::RecordNotFound) => "tls-rpt.record-not-found",
@@ -1447,6 +1595,7 @@ impl EventType {
             EventType::Acme(AcmeEvent::TlsAlpnError) => 24,
             EventType::Acme(AcmeEvent::TokenNotFound) => 26,
             EventType::Acme(AcmeEvent::Error) => 15,
+            EventType::Acme(AcmeEvent::RenewLocked) => 633,
             EventType::Ai(AiEvent::LlmResponse) => 556,
             EventType::Ai(AiEvent::ApiError) => 557,
             EventType::Arc(ArcEvent::ChainTooLong) => 28,
@@ -1464,6 +1613,8 @@ impl EventType {
             EventType::Auth(AuthEvent::Error) => 34,
             EventType::Auth(AuthEvent::Warning) => 595,
             EventType::Auth(AuthEvent::CredentialExpired) => 276,
+            EventType::Auth(AuthEvent::StepUpRequired) => 653,
+            EventType::Auth(AuthEvent::ProtocolDisabled) => 696,
             EventType::Calendar(CalendarEvent::RuleExpansionError) => 576,
             EventType::Calendar(CalendarEvent::AlarmSent) => 579,
             EventType::Calendar(CalendarEvent::AlarmSkipped) => 580,
@@ -1495,6 +1646,10 @@ impl EventType {
             EventType::Dane(DaneEvent::TlsaRecordNotDnssecSigned) => 74,
             EventType::Dane(DaneEvent::TlsaRecordInvalid) => 72,
             EventType::Dane(DaneEvent::BogusDnssecRecord) => 605,
+            EventType::Dane(DaneEvent::RolloverStaged) => 690,
+            EventType::Dane(DaneEvent::RolloverMismatch) => 691,
+            EventType::Dane(DaneEvent::RolloverActivated) => 692,
+            EventType::Dane(DaneEvent::RolloverTimeout) => 693,
             EventType::Delivery(DeliveryEvent::AttemptStart) => 77,
             EventType::Delivery(DeliveryEvent::AttemptEnd) => 76,
             EventType::Delivery(DeliveryEvent::Completed) => 80,
@@ -1533,6 +1688,12 @@ impl EventType {
             EventType::Delivery(DeliveryEvent::DsnPermFail) => 87,
             EventType::Delivery(DeliveryEvent::RawInput) => 105,
             EventType::Delivery(DeliveryEvent::RawOutput) => 106,
+            EventType::Delivery(DeliveryEvent::WarmupLimitExceeded) => 634,
+            EventType::Delivery(DeliveryEvent::RelayHostSelected) => 678,
+            EventType::Delivery(DeliveryEvent::RelayHostDown) => 679,
+            EventType::Delivery(DeliveryEvent::RelayHostUp) => 680,
+            EventType::Delivery(DeliveryEvent::RelayHostProbeFailed) => 681,
+            EventType::Delivery(DeliveryEvent::DsnSuppressed) => 694,
             EventType::Dkim(DkimEvent::Pass) => 121,
             EventType::Dkim(DkimEvent::Neutral) => 119,
             EventType::Dkim(DkimEvent::Fail) => 114,
@@ -1642,6 +1803,10 @@ impl EventType {
             EventType::Imap(ImapEvent::Error) => 168,
             EventType::Imap(ImapEvent::RawInput) => 183,
             EventType::Imap(ImapEvent::RawOutput) => 184,
+            EventType::Imap(ImapEvent::SetQuota) => 661,
+            EventType::Imap(ImapEvent::GenUrlAuth) => 673,
+            EventType::Imap(ImapEvent::ResetKey) => 674,
+            EventType::Imap(ImapEvent::Compress) => 695,
             EventType::IncomingReport(IncomingReportEvent::DmarcReport) => 200,
             EventType::IncomingReport(IncomingReportEvent::DmarcReportWithWarnings) => 201,
             EventType::IncomingReport(IncomingReportEvent::TlsReport) => 206,
@@ -1684,6 +1849,8 @@ impl EventType {
             EventType::Jmap(JmapEvent::WebsocketStart) => 235,
             EventType::Jmap(JmapEvent::WebsocketStop) => 236,
             EventType::Jmap(JmapEvent::WebsocketError) => 234,
+            EventType::Jmap(JmapEvent::TooManyChanges) => 638,
+            EventType::Jmap(JmapEvent::SlowMethodCall) => 663,
             EventType::Limit(LimitEvent::SizeRequest) => 243,
             EventType::Limit(LimitEvent::SizeUpload) => 244,
             EventType::Limit(LimitEvent::CallsIn) => 238,
@@ -1694,6 +1861,7 @@ impl EventType {
             EventType::Limit(LimitEvent::BlobQuota) => 237,
             EventType::Limit(LimitEvent::TenantQuota) => 553,
             EventType::Limit(LimitEvent::TooManyRequests) => 245,
+            EventType::Limit(LimitEvent::QuotaWarning) => 651,
             EventType::MailAuth(MailAuthEvent::ParseError) => 254,
             EventType::MailAuth(MailAuthEvent::MissingParameters) => 252,
             EventType::MailAuth(MailAuthEvent::NoHeadersFound) => 253,
@@ -1730,6 +1898,7 @@ impl EventType {
             EventType::MessageIngest(MessageIngestEvent::Duplicate) => 281,
             EventType::MessageIngest(MessageIngestEvent::Error) => 282,
             EventType::MessageIngest(MessageIngestEvent::SearchIndex) => 142,
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected) => 682,
             EventType::Milter(MilterEvent::Read) => 299,
             EventType::Milter(MilterEvent::Write) => 303,
             EventType::Milter(MilterEvent::ActionAccept) => 287,
@@ -1821,6 +1990,9 @@ impl EventType {
             EventType::Queue(QueueEvent::ConcurrencyLimitExceeded) => 375,
             EventType::Queue(QueueEvent::QuotaExceeded) => 383,
             EventType::Queue(QueueEvent::BackPressure) => 48,
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow) => 642,
+            EventType::Queue(QueueEvent::MessageSplit) => 677,
+            EventType::Queue(QueueEvent::Unsubscribe) => 689,
             EventType::Registry(RegistryEvent::LocalReadError) => 62,
             EventType::Registry(RegistryEvent::LocalWriteError) => 54,
             EventType::Registry(RegistryEvent::LocalParseError) => 60,
@@ -1837,6 +2009,7 @@ impl EventType {
             EventType::Resource(ResourceEvent::DownloadExternal) => 387,
             EventType::Resource(ResourceEvent::ApplicationUpdated) => 601,
             EventType::Resource(ResourceEvent::ApplicationUnpacked) => 602,
+            EventType::Resource(ResourceEvent::TemplateError) => 686,
             EventType::Security(SecurityEvent::AuthenticationBan) => 33,
             EventType::Security(SecurityEvent::AbuseBan) => 549,
             EventType::Security(SecurityEvent::ScanBan) => 558,
@@ -1853,6 +2026,9 @@ impl EventType {
             EventType::Server(ServerEvent::Licensing) => 391,
             EventType::Server(ServerEvent::RecoveryMode) => 603,
             EventType::Server(ServerEvent::BootstrapMode) => 604,
+            EventType::Server(ServerEvent::Draining) => 656,
+            EventType::Server(ServerEvent::DrainTimeout) => 657,
+            EventType::Server(ServerEvent::HealthCheckFailed) => 687,
             EventType::Sieve(SieveEvent::ActionAccept) => 396,
             EventType::Sieve(SieveEvent::ActionAcceptReplace) => 397,
             EventType::Sieve(SieveEvent::ActionDiscard) => 398,
@@ -1865,6 +2041,8 @@ impl EventType {
             EventType::Sieve(SieveEvent::UnexpectedError) => 407,
             EventType::Sieve(SieveEvent::NotSupported) => 402,
             EventType::Sieve(SieveEvent::QuotaExceeded) => 403,
+            EventType::Sieve(SieveEvent::VacationSuppressed) => 654,
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded) => 672,
             EventType::Smtp(SmtpEvent::ConnectionStart) => 417,
             EventType::Smtp(SmtpEvent::ConnectionEnd) => 416,
             EventType::Smtp(SmtpEvent::Error) => 428,
@@ -1950,6 +2128,25 @@ impl EventType {
             EventType::Smtp(SmtpEvent::UnsupportedParameter) => 486,
             EventType::Smtp(SmtpEvent::SyntaxError) => 480,
             EventType::Smtp(SmtpEvent::RequestTooLarge) => 470,
+            EventType::Smtp(SmtpEvent::SenderVerifyPass) => 635,
+            EventType::Smtp(SmtpEvent::SenderVerifyFail) => 636,
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail) => 637,
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized) => 645,
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten) => 646,
+            EventType::Smtp(SmtpEvent::ExtensionNotOffered) => 650,
+            EventType::Smtp(SmtpEvent::ExpansionLoop) => 658,
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded) => 659,
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded) => 660,
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated) => 664,
+            EventType::Smtp(SmtpEvent::ClientCertRejected) => 665,
+            EventType::Smtp(SmtpEvent::IprevRequired) => 666,
+            EventType::Smtp(SmtpEvent::SpfDryRunReject) => 668,
+            EventType::Smtp(SmtpEvent::DkimDryRunReject) => 669,
+            EventType::Smtp(SmtpEvent::ArcDryRunReject) => 670,
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject) => 671,
+            EventType::Smtp(SmtpEvent::Burl) => 675,
+            EventType::Smtp(SmtpEvent::BurlFailed) => 676,
+            EventType::Smtp(SmtpEvent::BatvInvalid) => 685,
             EventType::Spam(SpamEvent::Pyzor) => 564,
             EventType::Spam(SpamEvent::PyzorError) => 494,
             EventType::Spam(SpamEvent::Dnsbl) => 562,
@@ -1963,6 +2160,9 @@ impl EventType {
             EventType::Spam(SpamEvent::ModelNotReady) => 496,
             EventType::Spam(SpamEvent::ModelNotFound) => 497,
             EventType::Spam(SpamEvent::RulesUpdated) => 280,
+            EventType::Spam(SpamEvent::ClamAv) => 639,
+            EventType::Spam(SpamEvent::ClamAvError) => 640,
+            EventType::Spam(SpamEvent::SenderReputation) => 684,
             EventType::Spf(SpfEvent::Pass) => 501,
             EventType::Spf(SpfEvent::Fail) => 498,
             EventType::Spf(SpfEvent::SoftFail) => 503,
@@ -2010,6 +2210,8 @@ impl EventType {
             EventType::Store(StoreEvent::AutoExpunge) => 364,
             EventType::Store(StoreEvent::BlobStorePurged) => 369,
             EventType::Store(StoreEvent::DataStorePurged) => 368,
+            EventType::Store(StoreEvent::HttpStoreUnavailable) => 667,
+            EventType::Store(StoreEvent::ChangesPruned) => 683,
             EventType::TaskManager(TaskManagerEvent::TaskAcquired) => 578,
             EventType::TaskManager(TaskManagerEvent::TaskQueued) => 149,
             EventType::TaskManager(TaskManagerEvent::TaskScheduled) => 370,
@@ -2021,6 +2223,14 @@ impl EventType {
             EventType::TaskManager(TaskManagerEvent::MetadataNotFound) => 145,
             EventType::TaskManager(TaskManagerEvent::SchedulerStarted) => 150,
             EventType::TaskManager(TaskManagerEvent::ManagerStarted) => 367,
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped) => 643,
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted) => 644,
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted) => 647,
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted) => 648,
+            EventType::TaskManager(TaskManagerEvent::ImportFailed) => 649,
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent) => 652,
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled) => 662,
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted) => 688,
             EventType::Telemetry(TelemetryEvent::AlertEvent) => 548,
             EventType::Telemetry(TelemetryEvent::AlertMessage) => 365,
             EventType::Telemetry(TelemetryEvent::LogError) => 535,
@@ -2032,6 +2242,7 @@ impl EventType {
             EventType::Telemetry(TelemetryEvent::MetricsCollected) => 151,
             EventType::Telemetry(TelemetryEvent::MetricsStored) => 366,
             EventType::Telemetry(TelemetryEvent::MetricsPushed) => 146,
+            EventType::Telemetry(TelemetryEvent::AuditError) => 641,
             EventType::Tls(TlsEvent::Handshake) => 543,
             EventType::Tls(TlsEvent::HandshakeError) => 544,
             EventType::Tls(TlsEvent::NotConfigured) => 547,
@@ -2039,6 +2250,7 @@ impl EventType {
             EventType::Tls(TlsEvent::NoCertificatesAvailable) => 546,
             EventType::Tls(TlsEvent::MultipleCertificatesAvailable) => 545,
             EventType::Tls(TlsEvent::ExpiredCertificateRemoved) => 277,
+            EventType::Tls(TlsEvent::PolicyRejected) => 655,
             EventType::TlsRpt(TlsRptEvent::RecordFetch) => 540,
             EventType::TlsRpt(TlsRptEvent::RecordFetchError) => 541,
             EventType::TlsRpt(TlsRptEvent::RecordNotFound) => 560,
@@ -2085,6 +2297,7 @@ impl EventType {
             24 => Some(EventType::Acme(AcmeEvent::TlsAlpnError)),
             26 => Some(EventType::Acme(AcmeEvent::TokenNotFound)),
             15 => Some(EventType::Acme(AcmeEvent::Error)),
+            633 => Some(EventType::Acme(AcmeEvent::RenewLocked)),
             556 => Some(EventType::Ai(AiEvent::LlmResponse)),
             557 => Some(EventType::Ai(AiEvent::ApiError)),
             28 => Some(EventType::Arc(ArcEvent::ChainTooLong)),
@@ -2102,6 +2315,8 @@ impl EventType {
             34 => Some(EventType::Auth(AuthEvent::Error)),
             595 => Some(EventType::Auth(AuthEvent::Warning)),
             276 => Some(EventType::Auth(AuthEvent::CredentialExpired)),
+            653 => Some(EventType::Auth(AuthEvent::StepUpRequired)),
+            696 => Some(EventType::Auth(AuthEvent::ProtocolDisabled)),
             576 => Some(EventType::Calendar(CalendarEvent::RuleExpansionError)),
             579 => Some(EventType::Calendar(CalendarEvent::AlarmSent)),
             580 => Some(EventType::Calendar(CalendarEvent::AlarmSkipped)),
@@ -2133,6 +2348,10 @@ impl EventType {
             74 => Some(EventType::Dane(DaneEvent::TlsaRecordNotDnssecSigned)),
             72 => Some(EventType::Dane(DaneEvent::TlsaRecordInvalid)),
             605 => Some(EventType::Dane(DaneEvent::BogusDnssecRecord)),
+            690 => Some(EventType::Dane(DaneEvent::RolloverStaged)),
+            691 => Some(EventType::Dane(DaneEvent::RolloverMismatch)),
+            692 => Some(EventType::Dane(DaneEvent::RolloverActivated)),
+            693 => Some(EventType::Dane(DaneEvent::RolloverTimeout)),
             77 => Some(EventType::Delivery(DeliveryEvent::AttemptStart)),
             76 => Some(EventType::Delivery(DeliveryEvent::AttemptEnd)),
             80 => Some(EventType::Delivery(DeliveryEvent::Completed)),
@@ -2171,6 +2390,12 @@ impl EventType {
             87 => Some(EventType::Delivery(DeliveryEvent::DsnPermFail)),
             105 => Some(EventType::Delivery(DeliveryEvent::RawInput)),
             106 => Some(EventType::Delivery(DeliveryEvent::RawOutput)),
+            634 => Some(EventType::Delivery(DeliveryEvent::WarmupLimitExceeded)),
+            678 => Some(EventType::Delivery(DeliveryEvent::RelayHostSelected)),
+            679 => Some(EventType::Delivery(DeliveryEvent::RelayHostDown)),
+            680 => Some(EventType::Delivery(DeliveryEvent::RelayHostUp)),
+            681 => Some(EventType::Delivery(DeliveryEvent::RelayHostProbeFailed)),
+            694 => Some(EventType::Delivery(DeliveryEvent::DsnSuppressed)),
             121 => Some(EventType::Dkim(DkimEvent::Pass)),
             119 => Some(EventType::Dkim(DkimEvent::Neutral)),
             114 => Some(EventType::Dkim(DkimEvent::Fail)),
@@ -2280,6 +2505,10 @@ impl EventType {
             168 => Some(EventType::Imap(ImapEvent::Error)),
             183 => Some(EventType::Imap(ImapEvent::RawInput)),
             184 => Some(EventType::Imap(ImapEvent::RawOutput)),
+            661 => Some(EventType::Imap(ImapEvent::SetQuota)),
+            673 => Some(EventType::Imap(ImapEvent::GenUrlAuth)),
+            674 => Some(EventType::Imap(ImapEvent::ResetKey)),
+            695 => Some(EventType::Imap(ImapEvent::Compress)),
             200 => Some(EventType::IncomingReport(IncomingReportEvent::DmarcReport)),
             201 => Some(EventType::IncomingReport(
                 IncomingReportEvent::DmarcReportWithWarnings,
@@ -2340,6 +2569,8 @@ impl EventType {
             235 => Some(EventType::Jmap(JmapEvent::WebsocketStart)),
             236 => Some(EventType::Jmap(JmapEvent::WebsocketStop)),
             234 => Some(EventType::Jmap(JmapEvent::WebsocketError)),
+            638 => Some(EventType::Jmap(JmapEvent::TooManyChanges)),
+            663 => Some(EventType::Jmap(JmapEvent::SlowMethodCall)),
             243 => Some(EventType::Limit(LimitEvent::SizeRequest)),
             244 => Some(EventType::Limit(LimitEvent::SizeUpload)),
             238 => Some(EventType::Limit(LimitEvent::CallsIn)),
@@ -2350,6 +2581,7 @@ impl EventType {
             237 => Some(EventType::Limit(LimitEvent::BlobQuota)),
             553 => Some(EventType::Limit(LimitEvent::TenantQuota)),
             245 => Some(EventType::Limit(LimitEvent::TooManyRequests)),
+            651 => Some(EventType::Limit(LimitEvent::QuotaWarning)),
             254 => Some(EventType::MailAuth(MailAuthEvent::ParseError)),
             252 => Some(EventType::MailAuth(MailAuthEvent::MissingParameters)),
             253 => Some(EventType::MailAuth(MailAuthEvent::NoHeadersFound)),
@@ -2386,6 +2618,7 @@ impl EventType {
             281 => Some(EventType::MessageIngest(MessageIngestEvent::Duplicate)),
             282 => Some(EventType::MessageIngest(MessageIngestEvent::Error)),
             142 => Some(EventType::MessageIngest(MessageIngestEvent::SearchIndex)),
+            682 => Some(EventType::MessageIngest(MessageIngestEvent::LoopDetected)),
             299 => Some(EventType::Milter(MilterEvent::Read)),
             303 => Some(EventType::Milter(MilterEvent::Write)),
             287 => Some(EventType::Milter(MilterEvent::ActionAccept)),
@@ -2497,6 +2730,9 @@ impl EventType {
             375 => Some(EventType::Queue(QueueEvent::ConcurrencyLimitExceeded)),
             383 => Some(EventType::Queue(QueueEvent::QuotaExceeded)),
             48 => Some(EventType::Queue(QueueEvent::BackPressure)),
+            642 => Some(EventType::Queue(QueueEvent::OutsideDeliveryWindow)),
+            677 => Some(EventType::Queue(QueueEvent::MessageSplit)),
+            689 => Some(EventType::Queue(QueueEvent::Unsubscribe)),
             62 => Some(EventType::Registry(RegistryEvent::LocalReadError)),
             54 => Some(EventType::Registry(RegistryEvent::LocalWriteError)),
             60 => Some(EventType::Registry(RegistryEvent::LocalParseError)),
@@ -2513,6 +2749,7 @@ impl EventType {
             387 => Some(EventType::Resource(ResourceEvent::DownloadExternal)),
             601 => Some(EventType::Resource(ResourceEvent::ApplicationUpdated)),
             602 => Some(EventType::Resource(ResourceEvent::ApplicationUnpacked)),
+            686 => Some(EventType::Resource(ResourceEvent::TemplateError)),
             33 => Some(EventType::Security(SecurityEvent::AuthenticationBan)),
             549 => Some(EventType::Security(SecurityEvent::AbuseBan)),
             558 => Some(EventType::Security(SecurityEvent::ScanBan)),
@@ -2529,6 +2766,9 @@ impl EventType {
             391 => Some(EventType::Server(ServerEvent::Licensing)),
             603 => Some(EventType::Server(ServerEvent::RecoveryMode)),
             604 => Some(EventType::Server(ServerEvent::BootstrapMode)),
+            656 => Some(EventType::Server(ServerEvent::Draining)),
+            657 => Some(EventType::Server(ServerEvent::DrainTimeout)),
+            687 => Some(EventType::Server(ServerEvent::HealthCheckFailed)),
             396 => Some(EventType::Sieve(SieveEvent::ActionAccept)),
             397 => Some(EventType::Sieve(SieveEvent::ActionAcceptReplace)),
             398 => Some(EventType::Sieve(SieveEvent::ActionDiscard)),
@@ -2541,6 +2781,8 @@ impl EventType {
             407 => Some(EventType::Sieve(SieveEvent::UnexpectedError)),
             402 => Some(EventType::Sieve(SieveEvent::NotSupported)),
             403 => Some(EventType::Sieve(SieveEvent::QuotaExceeded)),
+            654 => Some(EventType::Sieve(SieveEvent::VacationSuppressed)),
+            672 => Some(EventType::Sieve(SieveEvent::RedirectLimitExceeded)),
             417 => Some(EventType::Smtp(SmtpEvent::ConnectionStart)),
             416 => Some(EventType::Smtp(SmtpEvent::ConnectionEnd)),
             428 => Some(EventType::Smtp(SmtpEvent::Error)),
@@ -2626,6 +2868,25 @@ impl EventType {
             486 => Some(EventType::Smtp(SmtpEvent::UnsupportedParameter)),
             480 => Some(EventType::Smtp(SmtpEvent::SyntaxError)),
             470 => Some(EventType::Smtp(SmtpEvent::RequestTooLarge)),
+            635 => Some(EventType::Smtp(SmtpEvent::SenderVerifyPass)),
+            636 => Some(EventType::Smtp(SmtpEvent::SenderVerifyFail)),
+            637 => Some(EventType::Smtp(SmtpEvent::SenderVerifyTempFail)),
+            645 => Some(EventType::Smtp(SmtpEvent::FromHeaderUnauthorized)),
+            646 => Some(EventType::Smtp(SmtpEvent::FromHeaderRewritten)),
+            650 => Some(EventType::Smtp(SmtpEvent::ExtensionNotOffered)),
+            658 => Some(EventType::Smtp(SmtpEvent::ExpansionLoop)),
+            659 => Some(EventType::Smtp(SmtpEvent::ExpansionDepthExceeded)),
+            660 => Some(EventType::Smtp(SmtpEvent::ExpansionLimitExceeded)),
+            664 => Some(EventType::Smtp(SmtpEvent::ClientCertAuthenticated)),
+            665 => Some(EventType::Smtp(SmtpEvent::ClientCertRejected)),
+            666 => Some(EventType::Smtp(SmtpEvent::IprevRequired)),
+            668 => Some(EventType::Smtp(SmtpEvent::SpfDryRunReject)),
+            669 => Some(EventType::Smtp(SmtpEvent::DkimDryRunReject)),
+            670 => Some(EventType::Smtp(SmtpEvent::ArcDryRunReject)),
+            671 => Some(EventType::Smtp(SmtpEvent::DmarcDryRunReject)),
+            675 => Some(EventType::Smtp(SmtpEvent::Burl)),
+            676 => Some(EventType::Smtp(SmtpEvent::BurlFailed)),
+            685 => Some(EventType::Smtp(SmtpEvent::BatvInvalid)),
             564 => Some(EventType::Spam(SpamEvent::Pyzor)),
             494 => Some(EventType::Spam(SpamEvent::PyzorError)),
             562 => Some(EventType::Spam(SpamEvent::Dnsbl)),
@@ -2639,6 +2900,9 @@ impl EventType {
             496 => Some(EventType::Spam(SpamEvent::ModelNotReady)),
             497 => Some(EventType::Spam(SpamEvent::ModelNotFound)),
             280 => Some(EventType::Spam(SpamEvent::RulesUpdated)),
+            639 => Some(EventType::Spam(SpamEvent::ClamAv)),
+            640 => Some(EventType::Spam(SpamEvent::ClamAvError)),
+            684 => Some(EventType::Spam(SpamEvent::SenderReputation)),
             501 => Some(EventType::Spf(SpfEvent::Pass)),
             498 => Some(EventType::Spf(SpfEvent::Fail)),
             503 => Some(EventType::Spf(SpfEvent::SoftFail)),
@@ -2686,6 +2950,8 @@ impl EventType {
             364 => Some(EventType::Store(StoreEvent::AutoExpunge)),
             369 => Some(EventType::Store(StoreEvent::BlobStorePurged)),
             368 => Some(EventType::Store(StoreEvent::DataStorePurged)),
+            667 => Some(EventType::Store(StoreEvent::HttpStoreUnavailable)),
+            683 => Some(EventType::Store(StoreEvent::ChangesPruned)),
             578 => Some(EventType::TaskManager(TaskManagerEvent::TaskAcquired)),
             149 => Some(EventType::TaskManager(TaskManagerEvent::TaskQueued)),
             370 => Some(EventType::TaskManager(TaskManagerEvent::TaskScheduled)),
@@ -2697,6 +2963,16 @@ impl EventType {
             145 => Some(EventType::TaskManager(TaskManagerEvent::MetadataNotFound)),
             150 => Some(EventType::TaskManager(TaskManagerEvent::SchedulerStarted)),
             367 => Some(EventType::TaskManager(TaskManagerEvent::ManagerStarted)),
+            643 => Some(EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped)),
+            644 => Some(EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted)),
+            647 => Some(EventType::TaskManager(TaskManagerEvent::ReindexCompleted)),
+            648 => Some(EventType::TaskManager(TaskManagerEvent::ImportCompleted)),
+            649 => Some(EventType::TaskManager(TaskManagerEvent::ImportFailed)),
+            652 => Some(EventType::TaskManager(TaskManagerEvent::QuotaWarningSent)),
+            662 => Some(EventType::TaskManager(TaskManagerEvent::QuotaReconciled)),
+            688 => Some(EventType::TaskManager(
+                TaskManagerEvent::BlobReferencesRecounted,
+            )),
             548 => Some(EventType::Telemetry(TelemetryEvent::AlertEvent)),
             365 => Some(EventType::Telemetry(TelemetryEvent::AlertMessage)),
             535 => Some(EventType::Telemetry(TelemetryEvent::LogError)),
@@ -2712,6 +2988,7 @@ impl EventType {
             151 => Some(EventType::Telemetry(TelemetryEvent::MetricsCollected)),
             366 => Some(EventType::Telemetry(TelemetryEvent::MetricsStored)),
             146 => Some(EventType::Telemetry(TelemetryEvent::MetricsPushed)),
+            641 => Some(EventType::Telemetry(TelemetryEvent::AuditError)),
             543 => Some(EventType::Tls(TlsEvent::Handshake)),
             544 => Some(EventType::Tls(TlsEvent::HandshakeError)),
             547 => Some(EventType::Tls(TlsEvent::NotConfigured)),
@@ -2719,6 +2996,7 @@ impl EventType {
             546 => Some(EventType::Tls(TlsEvent::NoCertificatesAvailable)),
             545 => Some(EventType::Tls(TlsEvent::MultipleCertificatesAvailable)),
             277 => Some(EventType::Tls(TlsEvent::ExpiredCertificateRemoved)),
+            655 => Some(EventType::Tls(TlsEvent::PolicyRejected)),
             540 => Some(EventType::TlsRpt(TlsRptEvent::RecordFetch)),
             541 => Some(EventType::TlsRpt(TlsRptEvent::RecordFetchError)),
             560 => Some(EventType::TlsRpt(TlsRptEvent::RecordNotFound)),
@@ -2793,6 +3071,7 @@ impl EventType {
             EventType::Store(StoreEvent::UnexpectedError) => Level::Error,
             EventType::Store(StoreEvent::CryptoError) => Level::Error,
             EventType::Tls(TlsEvent::NotConfigured) => Level::Error,
+            EventType::Resource(ResourceEvent::TemplateError) => Level::Error,
             EventType::Acme(AcmeEvent::AuthStart) => Level::Info,
             EventType::Acme(AcmeEvent::AuthPending) => Level::Info,
             EventType::Acme(AcmeEvent::AuthValid) => Level::Info,
@@ -3003,6 +3282,46 @@ impl EventType {
             EventType::TlsRpt(TlsRptEvent::RecordFetch) => Level::Info,
             EventType::TlsRpt(TlsRptEvent::RecordFetchError) => Level::Info,
             EventType::TlsRpt(TlsRptEvent::RecordNotFound) => Level::Info,
+            EventType::Acme(AcmeEvent::RenewLocked) => Level::Info,
+            EventType::Delivery(DeliveryEvent::WarmupLimitExceeded) => Level::Info,
+            EventType::Smtp(SmtpEvent::SenderVerifyPass) => Level::Info,
+            EventType::Smtp(SmtpEvent::SenderVerifyFail) => Level::Info,
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail) => Level::Info,
+            EventType::Spam(SpamEvent::ClamAv) => Level::Info,
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted) => Level::Info,
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized) => Level::Info,
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted) => Level::Info,
+            EventType::Limit(LimitEvent::QuotaWarning) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent) => Level::Info,
+            EventType::Tls(TlsEvent::PolicyRejected) => Level::Info,
+            EventType::Server(ServerEvent::Draining) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled) => Level::Info,
+            EventType::Jmap(JmapEvent::SlowMethodCall) => Level::Info,
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated) => Level::Info,
+            EventType::Smtp(SmtpEvent::ClientCertRejected) => Level::Info,
+            EventType::Smtp(SmtpEvent::IprevRequired) => Level::Info,
+            EventType::Smtp(SmtpEvent::SpfDryRunReject) => Level::Info,
+            EventType::Smtp(SmtpEvent::DkimDryRunReject) => Level::Info,
+            EventType::Smtp(SmtpEvent::ArcDryRunReject) => Level::Info,
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject) => Level::Info,
+            EventType::Smtp(SmtpEvent::Burl) => Level::Info,
+            EventType::Smtp(SmtpEvent::BurlFailed) => Level::Info,
+            EventType::Queue(QueueEvent::MessageSplit) => Level::Info,
+            EventType::Delivery(DeliveryEvent::RelayHostUp) => Level::Info,
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected) => Level::Info,
+            EventType::Store(StoreEvent::ChangesPruned) => Level::Info,
+            EventType::Spam(SpamEvent::SenderReputation) => Level::Info,
+            EventType::Smtp(SmtpEvent::BatvInvalid) => Level::Info,
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted) => Level::Info,
+            EventType::Queue(QueueEvent::Unsubscribe) => Level::Info,
+            EventType::Dane(DaneEvent::RolloverStaged) => Level::Info,
+            EventType::Dane(DaneEvent::RolloverActivated) => Level::Info,
+            EventType::Delivery(DeliveryEvent::DsnSuppressed) => Level::Info,
+            EventType::Auth(AuthEvent::ProtocolDisabled) => Level::Info,
             EventType::Ai(AiEvent::LlmResponse) => Level::Trace,
             EventType::Auth(AuthEvent::MfaRequired) => Level::Trace,
             EventType::Cluster(ClusterEvent::MessageReceived) => Level::Trace,
@@ -3090,6 +3409,18 @@ impl EventType {
             EventType::Telemetry(TelemetryEvent::JournalError) => Level::Warn,
             EventType::Tls(TlsEvent::NoCertificatesAvailable) => Level::Warn,
             EventType::Tls(TlsEvent::MultipleCertificatesAvailable) => Level::Warn,
+            EventType::Spam(SpamEvent::ClamAvError) => Level::Warn,
+            EventType::Telemetry(TelemetryEvent::AuditError) => Level::Warn,
+            EventType::Server(ServerEvent::DrainTimeout) => Level::Warn,
+            EventType::Smtp(SmtpEvent::ExpansionLoop) => Level::Warn,
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded) => Level::Warn,
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded) => Level::Warn,
+            EventType::Store(StoreEvent::HttpStoreUnavailable) => Level::Warn,
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded) => Level::Warn,
+            EventType::Delivery(DeliveryEvent::RelayHostDown) => Level::Warn,
+            EventType::Server(ServerEvent::HealthCheckFailed) => Level::Warn,
+            EventType::Dane(DaneEvent::RolloverMismatch) => Level::Warn,
+            EventType::Dane(DaneEvent::RolloverTimeout) => Level::Warn,
             _ => Level::Debug,
         }
     }
@@ -3118,6 +3449,7 @@ impl EventType {
             EventType::Acme(AcmeEvent::TlsAlpnError) => "ACME TLS ALPN error",
             EventType::Acme(AcmeEvent::TokenNotFound) => "ACME token not found",
             EventType::Acme(AcmeEvent::Error) => "ACME error",
+            EventType::Acme(AcmeEvent::RenewLocked) => "ACME renewal locked by another node",
             EventType::Ai(AiEvent::LlmResponse) => "LLM response",
             EventType::Ai(AiEvent::ApiError) => "AI API error",
             EventType::Arc(ArcEvent::ChainTooLong) => "ARC chain too long",
@@ -3135,6 +3467,10 @@ impl EventType {
             EventType::Auth(AuthEvent::Error) => "Authentication error",
             EventType::Auth(AuthEvent::Warning) => "Authentication warning",
             EventType::Auth(AuthEvent::CredentialExpired) => "Credential expired",
+            EventType::Auth(AuthEvent::StepUpRequired) => {
+                "Recent second factor verification required"
+            }
+            EventType::Auth(AuthEvent::ProtocolDisabled) => "Protocol disabled for account",
             EventType::Calendar(CalendarEvent::RuleExpansionError) => {
                 "Calendar rule expansion error"
             }
@@ -3178,6 +3514,18 @@ impl EventType {
             }
             EventType::Dane(DaneEvent::TlsaRecordInvalid) => "Invalid TLSA record",
             EventType::Dane(DaneEvent::BogusDnssecRecord) => "Bogus DNSSEC record",
+            EventType::Dane(DaneEvent::RolloverStaged) => {
+                "Renewed certificate staged until matching TLSA records are published"
+            }
+            EventType::Dane(DaneEvent::RolloverMismatch) => {
+                "Published TLSA records do not match the staged certificate"
+            }
+            EventType::Dane(DaneEvent::RolloverActivated) => {
+                "Staged certificate activated after matching TLSA records were observed"
+            }
+            EventType::Dane(DaneEvent::RolloverTimeout) => {
+                "Staged certificate activated without matching TLSA records"
+            }
             EventType::Delivery(DeliveryEvent::AttemptStart) => "Delivery attempt started",
             EventType::Delivery(DeliveryEvent::AttemptEnd) => "Delivery attempt ended",
             EventType::Delivery(DeliveryEvent::Completed) => "Delivery completed",
@@ -3226,6 +3574,12 @@ impl EventType {
             EventType::Delivery(DeliveryEvent::DsnPermFail) => "DSN permanent failure notification",
             EventType::Delivery(DeliveryEvent::RawInput) => "Raw SMTP input received",
             EventType::Delivery(DeliveryEvent::RawOutput) => "Raw SMTP output sent",
+            EventType::Delivery(DeliveryEvent::WarmupLimitExceeded) => "Warmup limit exceeded",
+            EventType::Delivery(DeliveryEvent::RelayHostSelected) => "Relay host selected",
+            EventType::Delivery(DeliveryEvent::RelayHostDown) => "Relay host marked down",
+            EventType::Delivery(DeliveryEvent::RelayHostUp) => "Relay host recovered",
+            EventType::Delivery(DeliveryEvent::RelayHostProbeFailed) => "Relay host probe failed",
+            EventType::Delivery(DeliveryEvent::DsnSuppressed) => "DSN suppressed",
             EventType::Dkim(DkimEvent::Pass) => "DKIM verification passed",
             EventType::Dkim(DkimEvent::Neutral) => "DKIM verification neutral",
             EventType::Dkim(DkimEvent::Fail) => "DKIM verification failed",
@@ -3345,6 +3699,10 @@ impl EventType {
             EventType::Imap(ImapEvent::Error) => "IMAP error occurred",
             EventType::Imap(ImapEvent::RawInput) => "Raw IMAP input received",
             EventType::Imap(ImapEvent::RawOutput) => "Raw IMAP output sent",
+            EventType::Imap(ImapEvent::SetQuota) => "IMAP SETQUOTA command",
+            EventType::Imap(ImapEvent::GenUrlAuth) => "IMAP GENURLAUTH command",
+            EventType::Imap(ImapEvent::ResetKey) => "IMAP RESETKEY command",
+            EventType::Imap(ImapEvent::Compress) => "IMAP COMPRESS command",
             EventType::IncomingReport(IncomingReportEvent::DmarcReport) => "DMARC report received",
             EventType::IncomingReport(IncomingReportEvent::DmarcReportWithWarnings) => {
                 "DMARC report received with warnings"
@@ -3409,6 +3767,8 @@ impl EventType {
             EventType::Jmap(JmapEvent::WebsocketStart) => "JMAP WebSocket connection started",
             EventType::Jmap(JmapEvent::WebsocketStop) => "JMAP WebSocket connection stopped",
             EventType::Jmap(JmapEvent::WebsocketError) => "JMAP WebSocket error",
+            EventType::Jmap(JmapEvent::TooManyChanges) => "Too many JMAP changes",
+            EventType::Jmap(JmapEvent::SlowMethodCall) => "Slow JMAP method call",
             EventType::Limit(LimitEvent::SizeRequest) => "Request size limit reached",
             EventType::Limit(LimitEvent::SizeUpload) => "Upload size limit reached",
             EventType::Limit(LimitEvent::CallsIn) => "Incoming calls limit reached",
@@ -3421,6 +3781,7 @@ impl EventType {
             EventType::Limit(LimitEvent::BlobQuota) => "Blob quota limit reached",
             EventType::Limit(LimitEvent::TenantQuota) => "Tenant quota limit reached",
             EventType::Limit(LimitEvent::TooManyRequests) => "Too many requests",
+            EventType::Limit(LimitEvent::QuotaWarning) => "Quota warning threshold crossed",
             EventType::MailAuth(MailAuthEvent::ParseError) => "Mail authentication parse error",
             EventType::MailAuth(MailAuthEvent::MissingParameters) => {
                 "Missing mail authentication parameters"
@@ -3479,6 +3840,7 @@ impl EventType {
             EventType::MessageIngest(MessageIngestEvent::Duplicate) => "Skipping duplicate message",
             EventType::MessageIngest(MessageIngestEvent::Error) => "Message ingestion error",
             EventType::MessageIngest(MessageIngestEvent::SearchIndex) => "Search index updated",
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected) => "Mail loop detected",
             EventType::Milter(MilterEvent::Read) => "Reading from Milter",
             EventType::Milter(MilterEvent::Write) => "Writing to Milter",
             EventType::Milter(MilterEvent::ActionAccept) => "Milter action: Accept",
@@ -3606,6 +3968,11 @@ impl EventType {
             EventType::Queue(QueueEvent::ConcurrencyLimitExceeded) => "Concurrency limit exceeded",
             EventType::Queue(QueueEvent::QuotaExceeded) => "Quota exceeded",
             EventType::Queue(QueueEvent::BackPressure) => "Queue backpressure detected",
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow) => {
+                "Delivery deferred until the delivery window opens"
+            }
+            EventType::Queue(QueueEvent::MessageSplit) => "Queued message split by route",
+            EventType::Queue(QueueEvent::Unsubscribe) => "Recipient unsubscribed",
             EventType::Registry(RegistryEvent::LocalReadError) => "Local registry read error",
             EventType::Registry(RegistryEvent::LocalWriteError) => "Local registry write error",
             EventType::Registry(RegistryEvent::LocalParseError) => "Local registry parse error",
@@ -3630,6 +3997,7 @@ impl EventType {
             EventType::Resource(ResourceEvent::ApplicationUnpacked) => {
                 "Application resource unpacked"
             }
+            EventType::Resource(ResourceEvent::TemplateError) => "Invalid message template",
             EventType::Security(SecurityEvent::AuthenticationBan) => {
                 "Banned due to authentication errors"
             }
@@ -3648,6 +4016,11 @@ impl EventType {
             EventType::Server(ServerEvent::Licensing) => "Server licensing event",
             EventType::Server(ServerEvent::RecoveryMode) => "Server started in recovery mode",
             EventType::Server(ServerEvent::BootstrapMode) => "Server started in bootstrap mode",
+            EventType::Server(ServerEvent::Draining) => {
+                "Server draining connections before shutdown"
+            }
+            EventType::Server(ServerEvent::DrainTimeout) => "Shutdown grace period expired",
+            EventType::Server(ServerEvent::HealthCheckFailed) => "Health check failed",
             EventType::Sieve(SieveEvent::ActionAccept) => "Sieve action: Accept",
             EventType::Sieve(SieveEvent::ActionAcceptReplace) => "Sieve action: Accept and replace",
             EventType::Sieve(SieveEvent::ActionDiscard) => "Sieve action: Discard",
@@ -3660,6 +4033,10 @@ impl EventType {
             EventType::Sieve(SieveEvent::UnexpectedError) => "Unexpected Sieve error",
             EventType::Sieve(SieveEvent::NotSupported) => "Sieve action not supported",
             EventType::Sieve(SieveEvent::QuotaExceeded) => "Sieve quota exceeded",
+            EventType::Sieve(SieveEvent::VacationSuppressed) => {
+                "Sieve vacation response suppressed"
+            }
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded) => "Sieve redirect limit exceeded",
             EventType::Smtp(SmtpEvent::ConnectionStart) => "SMTP connection started",
             EventType::Smtp(SmtpEvent::ConnectionEnd) => "SMTP connection ended",
             EventType::Smtp(SmtpEvent::Error) => "SMTP error occurred",
@@ -3749,6 +4126,33 @@ impl EventType {
             EventType::Smtp(SmtpEvent::UnsupportedParameter) => "Unsupported parameter",
             EventType::Smtp(SmtpEvent::SyntaxError) => "Syntax error",
             EventType::Smtp(SmtpEvent::RequestTooLarge) => "Request too large",
+            EventType::Smtp(SmtpEvent::SenderVerifyPass) => "Sender verification passed",
+            EventType::Smtp(SmtpEvent::SenderVerifyFail) => "Sender verification failed",
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail) => {
+                "Sender verification temporarily failed"
+            }
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized) => "From header not authorized",
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten) => "From header rewritten",
+            EventType::Smtp(SmtpEvent::ExtensionNotOffered) => "SMTP extension not offered",
+            EventType::Smtp(SmtpEvent::ExpansionLoop) => "Recipient expansion loop detected",
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded) => {
+                "Recipient expansion depth exceeded"
+            }
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded) => {
+                "Recipient expansion limit exceeded"
+            }
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated) => {
+                "Client authenticated with a TLS certificate"
+            }
+            EventType::Smtp(SmtpEvent::ClientCertRejected) => "TLS client certificate rejected",
+            EventType::Smtp(SmtpEvent::IprevRequired) => "Forward-confirmed reverse DNS required",
+            EventType::Smtp(SmtpEvent::SpfDryRunReject) => "SPF dry-run rejection",
+            EventType::Smtp(SmtpEvent::DkimDryRunReject) => "DKIM dry-run rejection",
+            EventType::Smtp(SmtpEvent::ArcDryRunReject) => "ARC dry-run rejection",
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject) => "DMARC dry-run rejection",
+            EventType::Smtp(SmtpEvent::Burl) => "BURL content retrieved",
+            EventType::Smtp(SmtpEvent::BurlFailed) => "BURL URL resolution failed",
+            EventType::Smtp(SmtpEvent::BatvInvalid) => "Invalid BATV signature",
             EventType::Spam(SpamEvent::Pyzor) => "Pyzor success",
             EventType::Spam(SpamEvent::PyzorError) => "Pyzor error",
             EventType::Spam(SpamEvent::Dnsbl) => "DNSBL query",
@@ -3762,6 +4166,9 @@ impl EventType {
             EventType::Spam(SpamEvent::ModelNotReady) => "Spam classifier model not ready",
             EventType::Spam(SpamEvent::ModelNotFound) => "Spam classifier model not found",
             EventType::Spam(SpamEvent::RulesUpdated) => "Spam filter rules updated",
+            EventType::Spam(SpamEvent::ClamAv) => "ClamAV scan",
+            EventType::Spam(SpamEvent::ClamAvError) => "ClamAV error",
+            EventType::Spam(SpamEvent::SenderReputation) => "Sender reputation",
             EventType::Spf(SpfEvent::Pass) => "SPF check passed",
             EventType::Spf(SpfEvent::Fail) => "SPF check failed",
             EventType::Spf(SpfEvent::SoftFail) => "SPF soft fail",
@@ -3811,6 +4218,8 @@ impl EventType {
             EventType::Store(StoreEvent::AutoExpunge) => "Auto-expunge executed",
             EventType::Store(StoreEvent::BlobStorePurged) => "Blob store purge completed",
             EventType::Store(StoreEvent::DataStorePurged) => "Data store purge completed",
+            EventType::Store(StoreEvent::HttpStoreUnavailable) => "HTTP store unavailable",
+            EventType::Store(StoreEvent::ChangesPruned) => "Change log pruned",
             EventType::TaskManager(TaskManagerEvent::TaskAcquired) => "Task acquired from queue",
             EventType::TaskManager(TaskManagerEvent::TaskQueued) => "Task queued for processing",
             EventType::TaskManager(TaskManagerEvent::TaskScheduled) => {
@@ -3830,6 +4239,24 @@ impl EventType {
             }
             EventType::TaskManager(TaskManagerEvent::SchedulerStarted) => "Task scheduler started",
             EventType::TaskManager(TaskManagerEvent::ManagerStarted) => "Task manager started",
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped) => {
+                "Message skipped during re-encryption"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted) => {
+                "Account re-encryption completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted) => {
+                "Account reindex completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted) => "Message import completed",
+            EventType::TaskManager(TaskManagerEvent::ImportFailed) => "Message import failed",
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent) => {
+                "Quota warning message delivered"
+            }
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled) => "Quota counter reconciled",
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted) => {
+                "Blob references recounted"
+            }
             EventType::Telemetry(TelemetryEvent::AlertEvent) => "Alert event triggered",
             EventType::Telemetry(TelemetryEvent::AlertMessage) => "Alert message sent",
             EventType::Telemetry(TelemetryEvent::LogError) => "Log collector error",
@@ -3847,6 +4274,7 @@ impl EventType {
             EventType::Telemetry(TelemetryEvent::MetricsCollected) => "Metrics collected",
             EventType::Telemetry(TelemetryEvent::MetricsStored) => "Metric store",
             EventType::Telemetry(TelemetryEvent::MetricsPushed) => "Metrics pushed",
+            EventType::Telemetry(TelemetryEvent::AuditError) => "Audit log error",
             EventType::Tls(TlsEvent::Handshake) => "TLS handshake",
             EventType::Tls(TlsEvent::HandshakeError) => "TLS handshake error",
             EventType::Tls(TlsEvent::NotConfigured) => "TLS not configured",
@@ -3858,6 +4286,9 @@ impl EventType {
             EventType::Tls(TlsEvent::ExpiredCertificateRemoved) => {
                 "Certificate expired and removed"
             }
+            EventType::Tls(TlsEvent::PolicyRejected) => {
+                "TLS connection rejected by listener policy"
+            }
             EventType::TlsRpt(TlsRptEvent::RecordFetch) => "Fetched TLS-RPT record",
             EventType::TlsRpt(TlsRptEvent::RecordFetchError) => "Error fetching TLS-RPT record",
             EventType::TlsRpt(TlsRptEvent::RecordNotFound) => "TLS-RPT record not found",
@@ -3892,6 +4323,12 @@ impl EventType {
             EventType::Auth(AuthEvent::ClientRegistration) => "Authentication error",
             EventType::Auth(AuthEvent::Error) => "Authentication error",
             EventType::Auth(AuthEvent::CredentialExpired) => "Credential expired",
+            EventType::Auth(AuthEvent::StepUpRequired) => {
+                "Operation requires step-up authentication"
+            }
+            EventType::Auth(AuthEvent::ProtocolDisabled) => {
+                "Protocol access is disabled for this account"
+            }
             EventType::Imap(ImapEvent::ConnectionStart) => "IMAP error",
             EventType::Imap(ImapEvent::ConnectionEnd) => "IMAP error",
             EventType::Imap(ImapEvent::GetAcl) => "IMAP error",
@@ -3929,6 +4366,10 @@ impl EventType {
             EventType::Imap(ImapEvent::Error) => "IMAP error",
             EventType::Imap(ImapEvent::RawInput) => "IMAP error",
             EventType::Imap(ImapEvent::RawOutput) => "IMAP error",
+            EventType::Imap(ImapEvent::SetQuota) => "IMAP error",
+            EventType::Imap(ImapEvent::GenUrlAuth) => "IMAP error",
+            EventType::Imap(ImapEvent::ResetKey) => "IMAP error",
+            EventType::Imap(ImapEvent::Compress) => "Compression enabled",
             EventType::Jmap(JmapEvent::MethodCall) => "Other message",
             EventType::Jmap(JmapEvent::InvalidArguments) => "Invalid arguments",
             EventType::Jmap(JmapEvent::RequestTooLarge) => "Request too large",
@@ -3951,6 +4392,10 @@ impl EventType {
             EventType::Jmap(JmapEvent::WebsocketStart) => "Other message",
             EventType::Jmap(JmapEvent::WebsocketStop) => "Other message",
             EventType::Jmap(JmapEvent::WebsocketError) => "Other message",
+            EventType::Jmap(JmapEvent::TooManyChanges) => "Too many changes",
+            EventType::Jmap(JmapEvent::SlowMethodCall) => {
+                "A JMAP method call exceeded the configured time threshold"
+            }
             EventType::Limit(LimitEvent::SizeRequest) => "Request too large",
             EventType::Limit(LimitEvent::SizeUpload) => "Upload too large",
             EventType::Limit(LimitEvent::CallsIn) => "Too many calls in",
@@ -3961,6 +4406,7 @@ impl EventType {
             EventType::Limit(LimitEvent::BlobQuota) => "Blob quota exceeded",
             EventType::Limit(LimitEvent::TenantQuota) => "Tenant quota exceeded",
             EventType::Limit(LimitEvent::TooManyRequests) => "Too many requests",
+            EventType::Limit(LimitEvent::QuotaWarning) => "Quota warning threshold crossed",
             EventType::ManageSieve(ManageSieveEvent::ConnectionStart) => "ManageSieve error",
             EventType::ManageSieve(ManageSieveEvent::ConnectionEnd) => "ManageSieve error",
             EventType::ManageSieve(ManageSieveEvent::CreateScript) => "ManageSieve error",
@@ -4015,6 +4461,9 @@ impl EventType {
             EventType::Resource(ResourceEvent::BadParameters) => "Bad parameters",
             EventType::Resource(ResourceEvent::Error) => "Resource error",
             EventType::Resource(ResourceEvent::DownloadExternal) => "Other status",
+            EventType::Resource(ResourceEvent::TemplateError) => {
+                "Invalid message template, using built-in template"
+            }
             EventType::Security(SecurityEvent::AuthenticationBan) => "Insufficient permissions",
             EventType::Security(SecurityEvent::AbuseBan) => "Insufficient permissions",
             EventType::Security(SecurityEvent::ScanBan) => "Insufficient permissions",
@@ -4109,6 +4558,43 @@ impl EventType {
             EventType::Smtp(SmtpEvent::UnsupportedParameter) => "SMTP error",
             EventType::Smtp(SmtpEvent::SyntaxError) => "SMTP error",
             EventType::Smtp(SmtpEvent::RequestTooLarge) => "SMTP error",
+            EventType::Smtp(SmtpEvent::SenderVerifyPass) => "SMTP error",
+            EventType::Smtp(SmtpEvent::SenderVerifyFail) => "SMTP error",
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail) => "SMTP error",
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized) => "From header not authorized",
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten) => "From header rewritten",
+            EventType::Smtp(SmtpEvent::ExtensionNotOffered) => "SMTP error",
+            EventType::Smtp(SmtpEvent::ExpansionLoop) => "Recipient expansion loop detected",
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded) => {
+                "Recipient expansion depth exceeded"
+            }
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded) => {
+                "Recipient expansion limit exceeded"
+            }
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated) => {
+                "Client authenticated with a TLS certificate"
+            }
+            EventType::Smtp(SmtpEvent::ClientCertRejected) => "TLS client certificate rejected",
+            EventType::Smtp(SmtpEvent::IprevRequired) => {
+                "Client IP failed forward-confirmed reverse DNS"
+            }
+            EventType::Smtp(SmtpEvent::SpfDryRunReject) => {
+                "Message would have been rejected by strict SPF"
+            }
+            EventType::Smtp(SmtpEvent::DkimDryRunReject) => {
+                "Message would have been rejected by strict DKIM"
+            }
+            EventType::Smtp(SmtpEvent::ArcDryRunReject) => {
+                "Message would have been rejected by strict ARC"
+            }
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject) => {
+                "Message would have been rejected by strict DMARC"
+            }
+            EventType::Smtp(SmtpEvent::Burl) => "SMTP error",
+            EventType::Smtp(SmtpEvent::BurlFailed) => "SMTP error",
+            EventType::Smtp(SmtpEvent::BatvInvalid) => {
+                "Bounce addressed to an invalid or expired BATV address"
+            }
             EventType::Store(StoreEvent::AssertValueFailed) => "Another process has modified the value",
             EventType::Store(StoreEvent::FoundationdbError) => "FoundationDB error",
             EventType::Store(StoreEvent::MysqlError) => "MySQL error",
@@ -4146,6 +4632,75 @@ impl EventType {
             EventType::Store(StoreEvent::LdapQuery) => "Store error",
             EventType::Store(StoreEvent::LdapWarning) => "Store error",
             EventType::Store(StoreEvent::HttpStoreFetch) => "Store error",
+            EventType::Store(StoreEvent::HttpStoreUnavailable) => {
+                "HTTP store failed to refresh repeatedly"
+            }
+            EventType::Store(StoreEvent::ChangesPruned) => "Change log pruned",
+            EventType::Spam(SpamEvent::ClamAv) => "ClamAV scan completed",
+            EventType::Spam(SpamEvent::ClamAvError) => "ClamAV scan failed",
+            EventType::Spam(SpamEvent::SenderReputation) => "Sender reputation evaluated",
+            EventType::Telemetry(TelemetryEvent::AuditError) => "Failed to record audit event",
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow) => {
+                "Message delivery deferred outside the delivery window"
+            }
+            EventType::Queue(QueueEvent::MessageSplit) => {
+                "Message split into separate queued messages"
+            }
+            EventType::Queue(QueueEvent::Unsubscribe) => "Recipient unsubscribed",
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped) => {
+                "Message was not re-encrypted"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted) => {
+                "Account re-encryption completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted) => {
+                "Account reindex completed"
+            }
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted) => "Message import completed",
+            EventType::TaskManager(TaskManagerEvent::ImportFailed) => "Failed to import message",
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent) => {
+                "Quota warning message delivered"
+            }
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled) => "Quota counter reconciled",
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted) => {
+                "Blob references recounted"
+            }
+            EventType::Sieve(SieveEvent::VacationSuppressed) => "Vacation response was not sent",
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded) => {
+                "The account exceeded its daily Sieve redirect limit"
+            }
+            EventType::Tls(TlsEvent::PolicyRejected) => {
+                "A TLS handshake was rejected because the client offered a protocol version or cipher suites not allowed by the listener"
+            }
+            EventType::Server(ServerEvent::Draining) => "Server draining connections",
+            EventType::Server(ServerEvent::DrainTimeout) => "Shutdown grace period expired",
+            EventType::Server(ServerEvent::HealthCheckFailed) => {
+                "A store failed to respond to the readiness probe"
+            }
+            EventType::Delivery(DeliveryEvent::RelayHostSelected) => {
+                "A host of a load-balanced relay route was selected"
+            }
+            EventType::Delivery(DeliveryEvent::RelayHostDown) => {
+                "A relay host was marked as down after consecutive failures"
+            }
+            EventType::Delivery(DeliveryEvent::RelayHostUp) => {
+                "A relay host marked as down accepted a connection again"
+            }
+            EventType::Delivery(DeliveryEvent::RelayHostProbeFailed) => {
+                "An EHLO health probe to a relay host failed"
+            }
+            EventType::Delivery(DeliveryEvent::DsnSuppressed) => {
+                "Delivery Status Notification was not sent"
+            }
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected) => "Mail loop detected",
+            EventType::Dane(DaneEvent::RolloverStaged) => "Certificate staged for DANE rollover",
+            EventType::Dane(DaneEvent::RolloverMismatch) => {
+                "TLSA records do not match staged certificate"
+            }
+            EventType::Dane(DaneEvent::RolloverActivated) => "Staged certificate activated",
+            EventType::Dane(DaneEvent::RolloverTimeout) => {
+                "Staged certificate activated after timeout"
+            }
             _ => "Internal Server Error",
         }
     }
@@ -4172,6 +4727,7 @@ impl EventType {
             EventType::Acme(AcmeEvent::TlsAlpnError),
             EventType::Acme(AcmeEvent::TokenNotFound),
             EventType::Acme(AcmeEvent::Error),
+            EventType::Acme(AcmeEvent::RenewLocked),
             EventType::Ai(AiEvent::LlmResponse),
             EventType::Ai(AiEvent::ApiError),
             EventType::Arc(ArcEvent::ChainTooLong),
@@ -4189,6 +4745,8 @@ impl EventType {
             EventType::Auth(AuthEvent::Error),
             EventType::Auth(AuthEvent::Warning),
             EventType::Auth(AuthEvent::CredentialExpired),
+            EventType::Auth(AuthEvent::StepUpRequired),
+            EventType::Auth(AuthEvent::ProtocolDisabled),
             EventType::Calendar(CalendarEvent::RuleExpansionError),
             EventType::Calendar(CalendarEvent::AlarmSent),
             EventType::Calendar(CalendarEvent::AlarmSkipped),
@@ -4220,6 +4778,10 @@ impl EventType {
             EventType::Dane(DaneEvent::TlsaRecordNotDnssecSigned),
             EventType::Dane(DaneEvent::TlsaRecordInvalid),
             EventType::Dane(DaneEvent::BogusDnssecRecord),
+            EventType::Dane(DaneEvent::RolloverStaged),
+            EventType::Dane(DaneEvent::RolloverMismatch),
+            EventType::Dane(DaneEvent::RolloverActivated),
+            EventType::Dane(DaneEvent::RolloverTimeout),
             EventType::Delivery(DeliveryEvent::AttemptStart),
             EventType::Delivery(DeliveryEvent::AttemptEnd),
             EventType::Delivery(DeliveryEvent::Completed),
@@ -4258,6 +4820,12 @@ impl EventType {
             EventType::Delivery(DeliveryEvent::DsnPermFail),
             EventType::Delivery(DeliveryEvent::RawInput),
             EventType::Delivery(DeliveryEvent::RawOutput),
+            EventType::Delivery(DeliveryEvent::WarmupLimitExceeded),
+            EventType::Delivery(DeliveryEvent::RelayHostSelected),
+            EventType::Delivery(DeliveryEvent::RelayHostDown),
+            EventType::Delivery(DeliveryEvent::RelayHostUp),
+            EventType::Delivery(DeliveryEvent::RelayHostProbeFailed),
+            EventType::Delivery(DeliveryEvent::DsnSuppressed),
             EventType::Dkim(DkimEvent::Pass),
             EventType::Dkim(DkimEvent::Neutral),
             EventType::Dkim(DkimEvent::Fail),
@@ -4367,6 +4935,10 @@ impl EventType {
             EventType::Imap(ImapEvent::Error),
             EventType::Imap(ImapEvent::RawInput),
             EventType::Imap(ImapEvent::RawOutput),
+            EventType::Imap(ImapEvent::SetQuota),
+            EventType::Imap(ImapEvent::GenUrlAuth),
+            EventType::Imap(ImapEvent::ResetKey),
+            EventType::Imap(ImapEvent::Compress),
             EventType::IncomingReport(IncomingReportEvent::DmarcReport),
             EventType::IncomingReport(IncomingReportEvent::DmarcReportWithWarnings),
             EventType::IncomingReport(IncomingReportEvent::TlsReport),
@@ -4409,6 +4981,8 @@ impl EventType {
             EventType::Jmap(JmapEvent::WebsocketStart),
             EventType::Jmap(JmapEvent::WebsocketStop),
             EventType::Jmap(JmapEvent::WebsocketError),
+            EventType::Jmap(JmapEvent::TooManyChanges),
+            EventType::Jmap(JmapEvent::SlowMethodCall),
             EventType::Limit(LimitEvent::SizeRequest),
             EventType::Limit(LimitEvent::SizeUpload),
             EventType::Limit(LimitEvent::CallsIn),
@@ -4419,6 +4993,7 @@ impl EventType {
             EventType::Limit(LimitEvent::BlobQuota),
             EventType::Limit(LimitEvent::TenantQuota),
             EventType::Limit(LimitEvent::TooManyRequests),
+            EventType::Limit(LimitEvent::QuotaWarning),
             EventType::MailAuth(MailAuthEvent::ParseError),
             EventType::MailAuth(MailAuthEvent::MissingParameters),
             EventType::MailAuth(MailAuthEvent::NoHeadersFound),
@@ -4455,6 +5030,7 @@ impl EventType {
             EventType::MessageIngest(MessageIngestEvent::Duplicate),
             EventType::MessageIngest(MessageIngestEvent::Error),
             EventType::MessageIngest(MessageIngestEvent::SearchIndex),
+            EventType::MessageIngest(MessageIngestEvent::LoopDetected),
             EventType::Milter(MilterEvent::Read),
             EventType::Milter(MilterEvent::Write),
             EventType::Milter(MilterEvent::ActionAccept),
@@ -4546,6 +5122,9 @@ impl EventType {
             EventType::Queue(QueueEvent::ConcurrencyLimitExceeded),
             EventType::Queue(QueueEvent::QuotaExceeded),
             EventType::Queue(QueueEvent::BackPressure),
+            EventType::Queue(QueueEvent::OutsideDeliveryWindow),
+            EventType::Queue(QueueEvent::MessageSplit),
+            EventType::Queue(QueueEvent::Unsubscribe),
             EventType::Registry(RegistryEvent::LocalReadError),
             EventType::Registry(RegistryEvent::LocalWriteError),
             EventType::Registry(RegistryEvent::LocalParseError),
@@ -4562,6 +5141,7 @@ impl EventType {
             EventType::Resource(ResourceEvent::DownloadExternal),
             EventType::Resource(ResourceEvent::ApplicationUpdated),
             EventType::Resource(ResourceEvent::ApplicationUnpacked),
+            EventType::Resource(ResourceEvent::TemplateError),
             EventType::Security(SecurityEvent::AuthenticationBan),
             EventType::Security(SecurityEvent::AbuseBan),
             EventType::Security(SecurityEvent::ScanBan),
@@ -4578,6 +5158,9 @@ impl EventType {
             EventType::Server(ServerEvent::Licensing),
             EventType::Server(ServerEvent::RecoveryMode),
             EventType::Server(ServerEvent::BootstrapMode),
+            EventType::Server(ServerEvent::Draining),
+            EventType::Server(ServerEvent::DrainTimeout),
+            EventType::Server(ServerEvent::HealthCheckFailed),
             EventType::Sieve(SieveEvent::ActionAccept),
             EventType::Sieve(SieveEvent::ActionAcceptReplace),
             EventType::Sieve(SieveEvent::ActionDiscard),
@@ -4590,6 +5173,8 @@ impl EventType {
             EventType::Sieve(SieveEvent::UnexpectedError),
             EventType::Sieve(SieveEvent::NotSupported),
             EventType::Sieve(SieveEvent::QuotaExceeded),
+            EventType::Sieve(SieveEvent::VacationSuppressed),
+            EventType::Sieve(SieveEvent::RedirectLimitExceeded),
             EventType::Smtp(SmtpEvent::ConnectionStart),
             EventType::Smtp(SmtpEvent::ConnectionEnd),
             EventType::Smtp(SmtpEvent::Error),
@@ -4675,6 +5260,25 @@ impl EventType {
             EventType::Smtp(SmtpEvent::UnsupportedParameter),
             EventType::Smtp(SmtpEvent::SyntaxError),
             EventType::Smtp(SmtpEvent::RequestTooLarge),
+            EventType::Smtp(SmtpEvent::SenderVerifyPass),
+            EventType::Smtp(SmtpEvent::SenderVerifyFail),
+            EventType::Smtp(SmtpEvent::SenderVerifyTempFail),
+            EventType::Smtp(SmtpEvent::FromHeaderUnauthorized),
+            EventType::Smtp(SmtpEvent::FromHeaderRewritten),
+            EventType::Smtp(SmtpEvent::ExtensionNotOffered),
+            EventType::Smtp(SmtpEvent::ExpansionLoop),
+            EventType::Smtp(SmtpEvent::ExpansionDepthExceeded),
+            EventType::Smtp(SmtpEvent::ExpansionLimitExceeded),
+            EventType::Smtp(SmtpEvent::ClientCertAuthenticated),
+            EventType::Smtp(SmtpEvent::ClientCertRejected),
+            EventType::Smtp(SmtpEvent::IprevRequired),
+            EventType::Smtp(SmtpEvent::SpfDryRunReject),
+            EventType::Smtp(SmtpEvent::DkimDryRunReject),
+            EventType::Smtp(SmtpEvent::ArcDryRunReject),
+            EventType::Smtp(SmtpEvent::DmarcDryRunReject),
+            EventType::Smtp(SmtpEvent::Burl),
+            EventType::Smtp(SmtpEvent::BurlFailed),
+            EventType::Smtp(SmtpEvent::BatvInvalid),
             EventType::Spam(SpamEvent::Pyzor),
             EventType::Spam(SpamEvent::PyzorError),
             EventType::Spam(SpamEvent::Dnsbl),
@@ -4688,6 +5292,9 @@ impl EventType {
             EventType::Spam(SpamEvent::ModelNotReady),
             EventType::Spam(SpamEvent::ModelNotFound),
             EventType::Spam(SpamEvent::RulesUpdated),
+            EventType::Spam(SpamEvent::ClamAv),
+            EventType::Spam(SpamEvent::ClamAvError),
+            EventType::Spam(SpamEvent::SenderReputation),
             EventType::Spf(SpfEvent::Pass),
             EventType::Spf(SpfEvent::Fail),
             EventType::Spf(SpfEvent::SoftFail),
@@ -4735,6 +5342,8 @@ impl EventType {
             EventType::Store(StoreEvent::AutoExpunge),
             EventType::Store(StoreEvent::BlobStorePurged),
             EventType::Store(StoreEvent::DataStorePurged),
+            EventType::Store(StoreEvent::HttpStoreUnavailable),
+            EventType::Store(StoreEvent::ChangesPruned),
             EventType::TaskManager(TaskManagerEvent::TaskAcquired),
             EventType::TaskManager(TaskManagerEvent::TaskQueued),
             EventType::TaskManager(TaskManagerEvent::TaskScheduled),
@@ -4746,6 +5355,14 @@ impl EventType {
             EventType::TaskManager(TaskManagerEvent::MetadataNotFound),
             EventType::TaskManager(TaskManagerEvent::SchedulerStarted),
             EventType::TaskManager(TaskManagerEvent::ManagerStarted),
+            EventType::TaskManager(TaskManagerEvent::ReEncryptSkipped),
+            EventType::TaskManager(TaskManagerEvent::ReEncryptCompleted),
+            EventType::TaskManager(TaskManagerEvent::ReindexCompleted),
+            EventType::TaskManager(TaskManagerEvent::ImportCompleted),
+            EventType::TaskManager(TaskManagerEvent::ImportFailed),
+            EventType::TaskManager(TaskManagerEvent::QuotaWarningSent),
+            EventType::TaskManager(TaskManagerEvent::QuotaReconciled),
+            EventType::TaskManager(TaskManagerEvent::BlobReferencesRecounted),
             EventType::Telemetry(TelemetryEvent::AlertEvent),
             EventType::Telemetry(TelemetryEvent::AlertMessage),
             EventType::Telemetry(TelemetryEvent::LogError),
@@ -4757,6 +5374,7 @@ impl EventType {
             EventType::Telemetry(TelemetryEvent::MetricsCollected),
             EventType::Telemetry(TelemetryEvent::MetricsStored),
             EventType::Telemetry(TelemetryEvent::MetricsPushed),
+            EventType::Telemetry(TelemetryEvent::AuditError),
             EventType::Tls(TlsEvent::Handshake),
             EventType::Tls(TlsEvent::HandshakeError),
             EventType::Tls(TlsEvent::NotConfigured),
@@ -4764,6 +5382,7 @@ impl EventType {
             EventType::Tls(TlsEvent::NoCertificatesAvailable),
             EventType::Tls(TlsEvent::MultipleCertificatesAvailable),
             EventType::Tls(TlsEvent::ExpiredCertificateRemoved),
+            EventType::Tls(TlsEvent::PolicyRejected),
             EventType::TlsRpt(TlsRptEvent::RecordFetch),
             EventType::TlsRpt(TlsRptEvent::RecordFetchError),
             EventType::TlsRpt(TlsRptEvent::RecordNotFound),
@@ -4922,6 +5541,10 @@ impl MetricType {
             b"imap.active-connections" => MetricType::ImapActiveConnections,
             b"imap.connection-start" => MetricType::ImapConnectionStart,
             b"imap.connection-end" => MetricType::ImapConnectionEnd,
+            b"imap.command-time" => MetricType::ImapCommandTime,
+            b"imap.command-count" => MetricType::ImapCommandCount,
+            b"imap.fetch-bytes" => MetricType::ImapFetchBytes,
+            b"imap.append-bytes" => MetricType::ImapAppendBytes,
             b"incoming-report.dmarc-report" => MetricType::IncomingReportDmarcReport,
             b"incoming-report.dmarc-report-with-warnings" => MetricType::IncomingReportDmarcReportWithWarnings,
             b"incoming-report.tls-report" => MetricType::IncomingReportTlsReport,
@@ -5025,6 +5648,14 @@ impl MetricType {
             b"push-subscription.error" => MetricType::PushSubscriptionError,
             b"push-subscription.not-found" => MetricType::PushSubscriptionNotFound,
             b"queue.count" => MetricType::QueueCount,
+            b"queue.messages" => MetricType::QueueMessages,
+            b"queue.bytes" => MetricType::QueueBytes,
+            b"queue.oldest-age" => MetricType::QueueOldestAge,
+            b"queue.message-age" => MetricType::QueueMessageAge,
+            b"queue.throttle-delay" => MetricType::QueueThrottleDelay,
+            b"queue.domain-attempts" => MetricType::QueueDomainAttempts,
+            b"queue.domain-deferrals" => MetricType::QueueDomainDeferrals,
+            b"queue.domain-bounces" => MetricType::QueueDomainBounces,
             b"queue.message-queued" => MetricType::QueueMessageQueued,
             b"queue.authenticated-message-queued" => MetricType::QueueAuthenticatedMessageQueued,
             b"queue.report-queued" => MetricType::QueueReportQueued,
@@ -5058,6 +5689,7 @@ impl MetricType {
             b"sieve.unexpected-error" => MetricType::SieveUnexpectedError,
             b"sieve.not-supported" => MetricType::SieveNotSupported,
             b"sieve.quota-exceeded" => MetricType::SieveQuotaExceeded,
+            b"sieve.redirect-limit-exceeded" => MetricType::SieveRedirectLimitExceeded,
             b"smtp.request-time" => MetricType::SmtpRequestTime,
             b"smtp.active-connections" => MetricType::SmtpActiveConnections,
             b"smtp.connection-start" => MetricType::SmtpConnectionStart,
@@ -5083,6 +5715,10 @@ impl MetricType {
             b"smtp.spf-from-fail" => MetricType::SmtpSpfFromFail,
             b"smtp.dmarc-pass" => MetricType::SmtpDmarcPass,
             b"smtp.dmarc-fail" => MetricType::SmtpDmarcFail,
+            b"smtp.spf-dry-run-reject" => MetricType::SmtpSpfDryRunReject,
+            b"smtp.dkim-dry-run-reject" => MetricType::SmtpDkimDryRunReject,
+            b"smtp.arc-dry-run-reject" => MetricType::SmtpArcDryRunReject,
+            b"smtp.dmarc-dry-run-reject" => MetricType::SmtpDmarcDryRunReject,
             b"smtp.iprev-pass" => MetricType::SmtpIprevPass,
             b"smtp.iprev-fail" => MetricType::SmtpIprevFail,
             b"smtp.too-many-messages" => MetricType::SmtpTooManyMessages,
@@ -5120,6 +5756,8 @@ impl MetricType {
             b"store.data-read-time" => MetricType::StoreDataReadTime,
             b"store.data-write-time" => MetricType::StoreDataWriteTime,
             b"store.blob-read-time" => MetricType::StoreBlobReadTime,
+            b"store.blob-read-bytes" => MetricType::StoreBlobReadBytes,
+            b"store.changes-pruned" => MetricType::StoreChangesPruned,
             b"store.blob-write-time" => MetricType::StoreBlobWriteTime,
             b"store.assert-value-failed" => MetricType::StoreAssertValueFailed,
             b"store.foundationdb-error" => MetricType::StoreFoundationdbError,
@@ -5160,6 +5798,7 @@ impl MetricType {
             b"telemetry.prometheus-exporter-error" => MetricType::TelemetryPrometheusExporterError,
             b"telemetry.journal-error" => MetricType::TelemetryJournalError,
             b"tls.handshake-error" => MetricType::TlsHandshakeError,
+            b"tls.policy-rejected" => MetricType::TlsPolicyRejected,
             b"user.count" => MetricType::UserCount,
         }
         .copied()
@@ -5295,6 +5934,10 @@ impl MetricType {
             MetricType::ImapActiveConnections => "imap.active-connections",
             MetricType::ImapConnectionStart => "imap.connection-start",
             MetricType::ImapConnectionEnd => "imap.connection-end",
+            MetricType::ImapCommandTime => "imap.command-time",
+            MetricType::ImapCommandCount => "imap.command-count",
+            MetricType::ImapFetchBytes => "imap.fetch-bytes",
+            MetricType::ImapAppendBytes => "imap.append-bytes",
             MetricType::IncomingReportDmarcReport => "incoming-report.dmarc-report",
             MetricType::IncomingReportDmarcReportWithWarnings => {
                 "incoming-report.dmarc-report-with-warnings"
@@ -5408,6 +6051,14 @@ impl MetricType {
             MetricType::PushSubscriptionError => "push-subscription.error",
             MetricType::PushSubscriptionNotFound => "push-subscription.not-found",
             MetricType::QueueCount => "queue.count",
+            MetricType::QueueMessages => "queue.messages",
+            MetricType::QueueBytes => "queue.bytes",
+            MetricType::QueueOldestAge => "queue.oldest-age",
+            MetricType::QueueMessageAge => "queue.message-age",
+            MetricType::QueueThrottleDelay => "queue.throttle-delay",
+            MetricType::QueueDomainAttempts => "queue.domain-attempts",
+            MetricType::QueueDomainDeferrals => "queue.domain-deferrals",
+            MetricType::QueueDomainBounces => "queue.domain-bounces",
             MetricType::QueueMessageQueued => "queue.message-queued",
             MetricType::QueueAuthenticatedMessageQueued => "queue.authenticated-message-queued",
             MetricType::QueueReportQueued => "queue.report-queued",
@@ -5441,6 +6092,7 @@ impl MetricType {
             MetricType::SieveUnexpectedError => "sieve.unexpected-error",
             MetricType::SieveNotSupported => "sieve.not-supported",
             MetricType::SieveQuotaExceeded => "sieve.quota-exceeded",
+            MetricType::SieveRedirectLimitExceeded => "sieve.redirect-limit-exceeded",
             MetricType::SmtpRequestTime => "smtp.request-time",
             MetricType::SmtpActiveConnections => "smtp.active-connections",
             MetricType::SmtpConnectionStart => "smtp.connection-start",
@@ -5466,6 +6118,10 @@ impl MetricType {
             MetricType::SmtpSpfFromFail => "smtp.spf-from-fail",
             MetricType::SmtpDmarcPass => "smtp.dmarc-pass",
             MetricType::SmtpDmarcFail => "smtp.dmarc-fail",
+            MetricType::SmtpSpfDryRunReject => "smtp.spf-dry-run-reject",
+            MetricType::SmtpDkimDryRunReject => "smtp.dkim-dry-run-reject",
+            MetricType::SmtpArcDryRunReject => "smtp.arc-dry-run-reject",
+            MetricType::SmtpDmarcDryRunReject => "smtp.dmarc-dry-run-reject",
             MetricType::SmtpIprevPass => "smtp.iprev-pass",
             MetricType::SmtpIprevFail => "smtp.iprev-fail",
             MetricType::SmtpTooManyMessages => "smtp.too-many-messages",
@@ -5503,6 +6159,8 @@ impl MetricType {
             MetricType::StoreDataReadTime => "store.data-read-time",
             MetricType::StoreDataWriteTime => "store.data-write-time",
             MetricType::StoreBlobReadTime => "store.blob-read-time",
+            MetricType::StoreBlobReadBytes => "store.blob-read-bytes",
+            MetricType::StoreChangesPruned => "store.changes-pruned",
             MetricType::StoreBlobWriteTime => "store.blob-write-time",
             MetricType::StoreAssertValueFailed => "store.assert-value-failed",
             MetricType::StoreFoundationdbError => "store.foundationdb-error",
@@ -5545,6 +6203,7 @@ impl MetricType {
             MetricType::TelemetryPrometheusExporterError => "telemetry.prometheus-exporter-error",
             MetricType::TelemetryJournalError => "telemetry.journal-error",
             MetricType::TlsHandshakeError => "tls.handshake-error",
+            MetricType::TlsPolicyRejected => "tls.policy-rejected",
             MetricType::UserCount => "user.count",
         }
     }
@@ -5679,6 +6338,10 @@ impl MetricType {
             MetricType::ImapActiveConnections => 18,
             MetricType::ImapConnectionStart => 118,
             MetricType::ImapConnectionEnd => 119,
+            MetricType::ImapCommandTime => 367,
+            MetricType::ImapCommandCount => 368,
+            MetricType::ImapFetchBytes => 369,
+            MetricType::ImapAppendBytes => 370,
             MetricType::IncomingReportDmarcReport => 120,
             MetricType::IncomingReportDmarcReportWithWarnings => 121,
             MetricType::IncomingReportTlsReport => 122,
@@ -5782,6 +6445,14 @@ impl MetricType {
             MetricType::PushSubscriptionError => 214,
             MetricType::PushSubscriptionNotFound => 215,
             MetricType::QueueCount => 24,
+            MetricType::QueueMessages => 371,
+            MetricType::QueueBytes => 372,
+            MetricType::QueueOldestAge => 373,
+            MetricType::QueueMessageAge => 374,
+            MetricType::QueueThrottleDelay => 386,
+            MetricType::QueueDomainAttempts => 375,
+            MetricType::QueueDomainDeferrals => 376,
+            MetricType::QueueDomainBounces => 377,
             MetricType::QueueMessageQueued => 216,
             MetricType::QueueAuthenticatedMessageQueued => 217,
             MetricType::QueueReportQueued => 218,
@@ -5815,6 +6486,7 @@ impl MetricType {
             MetricType::SieveUnexpectedError => 243,
             MetricType::SieveNotSupported => 244,
             MetricType::SieveQuotaExceeded => 245,
+            MetricType::SieveRedirectLimitExceeded => 384,
             MetricType::SmtpRequestTime => 15,
             MetricType::SmtpActiveConnections => 20,
             MetricType::SmtpConnectionStart => 246,
@@ -5840,6 +6512,10 @@ impl MetricType {
             MetricType::SmtpSpfFromFail => 263,
             MetricType::SmtpDmarcPass => 264,
             MetricType::SmtpDmarcFail => 265,
+            MetricType::SmtpSpfDryRunReject => 380,
+            MetricType::SmtpDkimDryRunReject => 381,
+            MetricType::SmtpArcDryRunReject => 382,
+            MetricType::SmtpDmarcDryRunReject => 383,
             MetricType::SmtpIprevPass => 266,
             MetricType::SmtpIprevFail => 267,
             MetricType::SmtpTooManyMessages => 268,
@@ -5877,6 +6553,8 @@ impl MetricType {
             MetricType::StoreDataReadTime => 7,
             MetricType::StoreDataWriteTime => 8,
             MetricType::StoreBlobReadTime => 9,
+            MetricType::StoreBlobReadBytes => 379,
+            MetricType::StoreChangesPruned => 385,
             MetricType::StoreBlobWriteTime => 10,
             MetricType::StoreAssertValueFailed => 300,
             MetricType::StoreFoundationdbError => 301,
@@ -5917,6 +6595,7 @@ impl MetricType {
             MetricType::TelemetryPrometheusExporterError => 334,
             MetricType::TelemetryJournalError => 335,
             MetricType::TlsHandshakeError => 336,
+            MetricType::TlsPolicyRejected => 378,
             MetricType::UserCount => 25,
         }
     }
@@ -6051,6 +6730,10 @@ impl MetricType {
             18 => Some(MetricType::ImapActiveConnections),
             118 => Some(MetricType::ImapConnectionStart),
             119 => Some(MetricType::ImapConnectionEnd),
+            367 => Some(MetricType::ImapCommandTime),
+            368 => Some(MetricType::ImapCommandCount),
+            369 => Some(MetricType::ImapFetchBytes),
+            370 => Some(MetricType::ImapAppendBytes),
             120 => Some(MetricType::IncomingReportDmarcReport),
             121 => Some(MetricType::IncomingReportDmarcReportWithWarnings),
             122 => Some(MetricType::IncomingReportTlsReport),
@@ -6154,6 +6837,14 @@ impl MetricType {
             214 => Some(MetricType::PushSubscriptionError),
             215 => Some(MetricType::PushSubscriptionNotFound),
             24 => Some(MetricType::QueueCount),
+            371 => Some(MetricType::QueueMessages),
+            372 => Some(MetricType::QueueBytes),
+            373 => Some(MetricType::QueueOldestAge),
+            374 => Some(MetricType::QueueMessageAge),
+            386 => Some(MetricType::QueueThrottleDelay),
+            375 => Some(MetricType::QueueDomainAttempts),
+            376 => Some(MetricType::QueueDomainDeferrals),
+            377 => Some(MetricType::QueueDomainBounces),
             216 => Some(MetricType::QueueMessageQueued),
             217 => Some(MetricType::QueueAuthenticatedMessageQueued),
             218 => Some(MetricType::QueueReportQueued),
@@ -6187,6 +6878,7 @@ impl MetricType {
             243 => Some(MetricType::SieveUnexpectedError),
             244 => Some(MetricType::SieveNotSupported),
             245 => Some(MetricType::SieveQuotaExceeded),
+            384 => Some(MetricType::SieveRedirectLimitExceeded),
             15 => Some(MetricType::SmtpRequestTime),
             20 => Some(MetricType::SmtpActiveConnections),
             246 => Some(MetricType::SmtpConnectionStart),
@@ -6212,6 +6904,10 @@ impl MetricType {
             263 => Some(MetricType::SmtpSpfFromFail),
             264 => Some(MetricType::SmtpDmarcPass),
             265 => Some(MetricType::SmtpDmarcFail),
+            380 => Some(MetricType::SmtpSpfDryRunReject),
+            381 => Some(MetricType::SmtpDkimDryRunReject),
+            382 => Some(MetricType::SmtpArcDryRunReject),
+            383 => Some(MetricType::SmtpDmarcDryRunReject),
             266 => Some(MetricType::SmtpIprevPass),
             267 => Some(MetricType::SmtpIprevFail),
             268 => Some(MetricType::SmtpTooManyMessages),
@@ -6249,6 +6945,8 @@ impl MetricType {
             7 => Some(MetricType::StoreDataReadTime),
             8 => Some(MetricType::StoreDataWriteTime),
             9 => Some(MetricType::StoreBlobReadTime),
+            379 => Some(MetricType::StoreBlobReadBytes),
+            385 => Some(MetricType::StoreChangesPruned),
             10 => Some(MetricType::StoreBlobWriteTime),
             300 => Some(MetricType::StoreAssertValueFailed),
             301 => Some(MetricType::StoreFoundationdbError),
@@ -6289,6 +6987,7 @@ impl MetricType {
             334 => Some(MetricType::TelemetryPrometheusExporterError),
             335 => Some(MetricType::TelemetryJournalError),
             336 => Some(MetricType::TlsHandshakeError),
+            378 => Some(MetricType::TlsPolicyRejected),
             25 => Some(MetricType::UserCount),
             _ => None,
         }
@@ -6540,6 +7239,7 @@ impl MetricType {
             MetricType::SieveUnexpectedError => 407,
             MetricType::SieveNotSupported => 402,
             MetricType::SieveQuotaExceeded => 403,
+            MetricType::SieveRedirectLimitExceeded => 672,
             MetricType::SmtpConnectionStart => 417,
             MetricType::SmtpConnectionEnd => 416,
             MetricType::SmtpError => 428,
@@ -6563,6 +7263,10 @@ impl MetricType {
             MetricType::SmtpSpfFromFail => 475,
             MetricType::SmtpDmarcPass => 424,
             MetricType::SmtpDmarcFail => 423,
+            MetricType::SmtpSpfDryRunReject => 668,
+            MetricType::SmtpDkimDryRunReject => 669,
+            MetricType::SmtpArcDryRunReject => 670,
+            MetricType::SmtpDmarcDryRunReject => 671,
             MetricType::SmtpIprevPass => 441,
             MetricType::SmtpIprevFail => 440,
             MetricType::SmtpTooManyMessages => 483,
@@ -6636,6 +7340,7 @@ impl MetricType {
             MetricType::TelemetryPrometheusExporterError => 538,
             MetricType::TelemetryJournalError => 534,
             MetricType::TlsHandshakeError => 544,
+            MetricType::TlsPolicyRejected => 655,
             _ => usize::MAX,
         }
     }
@@ -6772,6 +7477,10 @@ impl MetricType {
             MetricType::ImapActiveConnections => "Active IMAP connections",
             MetricType::ImapConnectionStart => "IMAP connection started",
             MetricType::ImapConnectionEnd => "IMAP connection ended",
+            MetricType::ImapCommandTime => "IMAP command duration",
+            MetricType::ImapCommandCount => "IMAP commands completed",
+            MetricType::ImapFetchBytes => "IMAP FETCH bytes sent",
+            MetricType::ImapAppendBytes => "IMAP APPEND bytes received",
             MetricType::IncomingReportDmarcReport => "DMARC report received",
             MetricType::IncomingReportDmarcReportWithWarnings => {
                 "DMARC report received with warnings"
@@ -6885,6 +7594,16 @@ impl MetricType {
             MetricType::PushSubscriptionError => "Push subscription error",
             MetricType::PushSubscriptionNotFound => "Push subscription not found",
             MetricType::QueueCount => "Total number of messages in the queue",
+            MetricType::QueueMessages => "Messages in each virtual queue",
+            MetricType::QueueBytes => "Size of the messages in each virtual queue",
+            MetricType::QueueOldestAge => "Age of the oldest message in each virtual queue",
+            MetricType::QueueMessageAge => "Age of queued messages at each delivery attempt",
+            MetricType::QueueThrottleDelay => {
+                "Delay imposed on queued messages by queue rate limits"
+            }
+            MetricType::QueueDomainAttempts => "Delivery attempts per destination domain",
+            MetricType::QueueDomainDeferrals => "Deferred recipients per destination domain",
+            MetricType::QueueDomainBounces => "Bounced recipients per destination domain",
             MetricType::QueueMessageQueued => "Queued message for delivery",
             MetricType::QueueAuthenticatedMessageQueued => "Queued message submission for delivery",
             MetricType::QueueReportQueued => "Queued report for delivery",
@@ -6918,6 +7637,7 @@ impl MetricType {
             MetricType::SieveUnexpectedError => "Unexpected Sieve error",
             MetricType::SieveNotSupported => "Sieve action not supported",
             MetricType::SieveQuotaExceeded => "Sieve quota exceeded",
+            MetricType::SieveRedirectLimitExceeded => "Sieve redirect limit exceeded",
             MetricType::SmtpRequestTime => "SMTP request duration",
             MetricType::SmtpActiveConnections => "Active SMTP connections",
             MetricType::SmtpConnectionStart => "SMTP connection started",
@@ -6943,6 +7663,18 @@ impl MetricType {
             MetricType::SmtpSpfFromFail => "SPF From check failed",
             MetricType::SmtpDmarcPass => "DMARC check passed",
             MetricType::SmtpDmarcFail => "DMARC check failed",
+            MetricType::SmtpSpfDryRunReject => {
+                "Messages that would have been rejected by strict SPF"
+            }
+            MetricType::SmtpDkimDryRunReject => {
+                "Messages that would have been rejected by strict DKIM"
+            }
+            MetricType::SmtpArcDryRunReject => {
+                "Messages that would have been rejected by strict ARC"
+            }
+            MetricType::SmtpDmarcDryRunReject => {
+                "Messages that would have been rejected by strict DMARC"
+            }
             MetricType::SmtpIprevPass => "IPREV check passed",
             MetricType::SmtpIprevFail => "IPREV check failed",
             MetricType::SmtpTooManyMessages => "Too many messages",
@@ -6980,6 +7712,8 @@ impl MetricType {
             MetricType::StoreDataReadTime => "Data store read time",
             MetricType::StoreDataWriteTime => "Data store write time",
             MetricType::StoreBlobReadTime => "Blob store read time",
+            MetricType::StoreBlobReadBytes => "Bytes read from the blob store",
+            MetricType::StoreChangesPruned => "Change log entries pruned",
             MetricType::StoreBlobWriteTime => "Blob store write time",
             MetricType::StoreAssertValueFailed => "Another process modified the record",
             MetricType::StoreFoundationdbError => "FoundationDB error",
@@ -7020,6 +7754,7 @@ impl MetricType {
             MetricType::TelemetryPrometheusExporterError => "Prometheus exporter error",
             MetricType::TelemetryJournalError => "Journal collector error",
             MetricType::TlsHandshakeError => "TLS handshake error",
+            MetricType::TlsPolicyRejected => "TLS connections rejected by listener policy",
             MetricType::UserCount => "Total number of users",
         }
     }
@@ -7029,6 +7764,10 @@ impl MetricType {
             MetricType::MessageSize
             | MetricType::MessageAuthenticatedSize
             | MetricType::OutgoingReportSize
+            | MetricType::ImapFetchBytes
+            | MetricType::ImapAppendBytes
+            | MetricType::StoreBlobReadBytes
+            | MetricType::QueueBytes
             | MetricType::ServerMemory => "bytes",
             MetricType::DeliveryActiveConnections
             | MetricType::HttpActiveConnections
@@ -7036,6 +7775,7 @@ impl MetricType {
             | MetricType::Pop3ActiveConnections
             | MetricType::SieveActiveConnections
             | MetricType::SmtpActiveConnections => "connections",
+            MetricType::StoreChangesPruned => "entries",
             MetricType::AcmeAuthError
             | MetricType::AcmeAuthTooManyAttempts
             | MetricType::AcmeOrderCompleted
@@ -7155,6 +7895,7 @@ impl MetricType {
             | MetricType::HttpXForwardedMissing
             | MetricType::ImapConnectionStart
             | MetricType::ImapConnectionEnd
+            | MetricType::ImapCommandCount
             | MetricType::IncomingReportDmarcReport
             | MetricType::IncomingReportDmarcReportWithWarnings
             | MetricType::IncomingReportTlsReport
@@ -7280,6 +8021,7 @@ impl MetricType {
             | MetricType::SieveUnexpectedError
             | MetricType::SieveNotSupported
             | MetricType::SieveQuotaExceeded
+            | MetricType::SieveRedirectLimitExceeded
             | MetricType::SmtpConnectionStart
             | MetricType::SmtpConnectionEnd
             | MetricType::SmtpError
@@ -7303,6 +8045,10 @@ impl MetricType {
             | MetricType::SmtpSpfFromFail
             | MetricType::SmtpDmarcPass
             | MetricType::SmtpDmarcFail
+            | MetricType::SmtpSpfDryRunReject
+            | MetricType::SmtpDkimDryRunReject
+            | MetricType::SmtpArcDryRunReject
+            | MetricType::SmtpDmarcDryRunReject
             | MetricType::SmtpIprevPass
             | MetricType::SmtpIprevFail
             | MetricType::SmtpTooManyMessages
@@ -7375,14 +8121,22 @@ impl MetricType {
             | MetricType::TelemetryOtelMetricsExporterError
             | MetricType::TelemetryPrometheusExporterError
             | MetricType::TelemetryJournalError
-            | MetricType::TlsHandshakeError => "count",
+            | MetricType::TlsHandshakeError
+            | MetricType::TlsPolicyRejected
+            | MetricType::QueueDomainAttempts
+            | MetricType::QueueDomainDeferrals
+            | MetricType::QueueDomainBounces => "count",
             MetricType::DomainCount => "domains",
-            MetricType::QueueCount => "messages",
+            MetricType::QueueCount | MetricType::QueueMessages => "messages",
             MetricType::DeliveryTotalTime
             | MetricType::DeliveryAttemptTime
             | MetricType::DnsLookupTime
             | MetricType::HttpRequestTime
             | MetricType::ImapRequestTime
+            | MetricType::ImapCommandTime
+            | MetricType::QueueOldestAge
+            | MetricType::QueueMessageAge
+            | MetricType::QueueThrottleDelay
             | MetricType::MessageIngestTime
             | MetricType::MessageIngestIndexTime
             | MetricType::Pop3RequestTime
@@ -7526,6 +8280,10 @@ impl MetricType {
             MetricType::ImapActiveConnections,
             MetricType::ImapConnectionStart,
             MetricType::ImapConnectionEnd,
+            MetricType::ImapCommandTime,
+            MetricType::ImapCommandCount,
+            MetricType::ImapFetchBytes,
+            MetricType::ImapAppendBytes,
             MetricType::IncomingReportDmarcReport,
             MetricType::IncomingReportDmarcReportWithWarnings,
             MetricType::IncomingReportTlsReport,
@@ -7629,6 +8387,14 @@ impl MetricType {
             MetricType::PushSubscriptionError,
             MetricType::PushSubscriptionNotFound,
             MetricType::QueueCount,
+            MetricType::QueueMessages,
+            MetricType::QueueBytes,
+            MetricType::QueueOldestAge,
+            MetricType::QueueMessageAge,
+            MetricType::QueueThrottleDelay,
+            MetricType::QueueDomainAttempts,
+            MetricType::QueueDomainDeferrals,
+            MetricType::QueueDomainBounces,
             MetricType::QueueMessageQueued,
             MetricType::QueueAuthenticatedMessageQueued,
             MetricType::QueueReportQueued,
@@ -7662,6 +8428,7 @@ impl MetricType {
             MetricType::SieveUnexpectedError,
             MetricType::SieveNotSupported,
             MetricType::SieveQuotaExceeded,
+            MetricType::SieveRedirectLimitExceeded,
             MetricType::SmtpRequestTime,
             MetricType::SmtpActiveConnections,
             MetricType::SmtpConnectionStart,
@@ -7687,6 +8454,10 @@ impl MetricType {
             MetricType::SmtpSpfFromFail,
             MetricType::SmtpDmarcPass,
             MetricType::SmtpDmarcFail,
+            MetricType::SmtpSpfDryRunReject,
+            MetricType::SmtpDkimDryRunReject,
+            MetricType::SmtpArcDryRunReject,
+            MetricType::SmtpDmarcDryRunReject,
             MetricType::SmtpIprevPass,
             MetricType::SmtpIprevFail,
             MetricType::SmtpTooManyMessages,
@@ -7724,6 +8495,8 @@ impl MetricType {
             MetricType::StoreDataReadTime,
             MetricType::StoreDataWriteTime,
             MetricType::StoreBlobReadTime,
+            MetricType::StoreBlobReadBytes,
+            MetricType::StoreChangesPruned,
             MetricType::StoreBlobWriteTime,
             MetricType::StoreAssertValueFailed,
             MetricType::StoreFoundationdbError,
@@ -7764,6 +8537,7 @@ impl MetricType {
             MetricType::TelemetryPrometheusExporterError,
             MetricType::TelemetryJournalError,
             MetricType::TlsHandshakeError,
+            MetricType::TlsPolicyRejected,
             MetricType::UserCount,
         ]
     }
@@ -7840,6 +8614,11 @@ impl Key {
             b"value" => Key::Value,
             b"version" => Key::Version,
             b"queueName" => Key::QueueName,
+            b"storeElapsed" => Key::StoreElapsed,
+            b"storeReads" => Key::StoreReads,
+            b"serializeElapsed" => Key::SerializeElapsed,
+            b"metadata" => Key::Metadata,
+            b"parseElapsed" => Key::ParseElapsed,
         }
         .copied()
     }
@@ -7912,6 +8691,11 @@ impl Key {
             Key::Value => "value",
             Key::Version => "version",
             Key::QueueName => "queueName",
+            Key::StoreElapsed => "storeElapsed",
+            Key::StoreReads => "storeReads",
+            Key::SerializeElapsed => "serializeElapsed",
+            Key::Metadata => "metadata",
+            Key::ParseElapsed => "parseElapsed",
         }
     }
 
@@ -7987,11 +8771,16 @@ impl Key {
             63 => Some(Key::Value),
             64 => Some(Key::Version),
             65 => Some(Key::QueueName),
+            66 => Some(Key::StoreElapsed),
+            67 => Some(Key::StoreReads),
+            68 => Some(Key::SerializeElapsed),
+            69 => Some(Key::Metadata),
+            70 => Some(Key::ParseElapsed),
             _ => None,
         }
     }
 
-    pub const COUNT: usize = 66;
+    pub const COUNT: usize = 71;
 }
 
 impl serde::Serialize for Key {
@@ -6,8 +6,8 @@
 
 use std::{
     sync::{Arc, LazyLock, atomic::Ordering},
-    thread::{Builder, JoinHandle, park},
-    time::SystemTime,
+    thread::{Builder, JoinHandle, park, park_timeout},
+    time::{Duration, SystemTime},
 };
 
 use ahash::AHashMap;
@@ -15,6 +15,7 @@ use atomics::bitset::AtomicBitset;
 use ipc::{
     USIZE_BITS,
     channel::{CHANNEL_FLAGS, CHANNEL_UPDATE_MARKER, Receiver},
+    overrides::{self, OVERRIDE_INTERESTS, TraceOverride},
     subscriber::{Interests, Subscriber},
 };
 use parking_lot::Mutex;
@@ -49,6 +50,9 @@ pub(crate) enum Update {
     UpdateLevels {
         levels: AHashMap<EventType, Level>,
     },
+    UpdateOverrides {
+        overrides: Vec<TraceOverride>,
+    },
     Shutdown,
 }
 
@@ -57,6 +61,7 @@ pub struct Collector {
     subscribers: Vec<Subscriber>,
     levels: [Level; TOTAL_EVENT_COUNT],
     active_spans: AHashMap<u64, Arc<Event<EventDetails>>>,
+    overrides: Vec<TraceOverride>,
 }
 
 const HTTP_CONN_START: usize = EventType::Http(HttpEvent::ConnectionStart).to_id() as usize;
@@ -98,7 +103,13 @@ impl Collector {
         while do_continue {
             match CHANNEL_FLAGS.swap(0, Ordering::Relaxed) {
                 0 => {
-                    park();
+                    // Wake up when the next override expires
+                    match self.overrides.iter().map(|rule| rule.expires).min() {
+                        Some(expires) => park_timeout(Duration::from_secs(
+                            expires.saturating_sub(overrides::now()),
+                        )),
+                        None => park(),
+                    }
                 }
                 CHANNEL_UPDATE_MARKER..=u64::MAX => {
                     do_continue = self.update();
@@ -106,12 +117,22 @@ impl Collector {
                 _ => {}
             }
 
+            // Revert expired overrides
+            if !self.overrides.is_empty() {
+                let timestamp = overrides::now();
+                if self.overrides.iter().any(|rule| rule.expires <= timestamp) {
+                    self.overrides.retain(|rule| rule.expires > timestamp);
+                    Collector::expire_overrides(timestamp);
+                }
+            }
+
             // Collect all events
             let mut closed_rxs = Vec::new();
             for (rx_idx, rx) in self.receivers.iter_mut().enumerate() {
                 let timestamp = SystemTime::now()
                     .duration_since(SystemTime::UNIX_EPOCH)
                     .map_or(0, |d| d.as_secs());
+                let override_timestamp = overrides::now();
 
                 loop {
                     match rx.try_recv() {
@@ -196,8 +217,21 @@ impl Collector {
                             };
 
                             // Send to subscribers
+                            let is_override = !self.overrides.is_empty()
+                                && self.overrides.iter().any(|rule| {
+                                    rule.matches(
+                                        event_id,
+                                        self.levels[event_id],
+                                        &event,
+                                        override_timestamp,
+                                    )
+                                });
                             for subscriber in self.subscribers.iter_mut() {
-                                subscriber.push_event(event_id, event.clone());
+                                if is_override && subscriber.overrides {
+                                    subscriber.push_override(event.clone());
+                                } else {
+                                    subscriber.push_event(event_id, event.clone());
+                                }
                             }
                         }
                         Ok(None) => {
@@ -274,6 +308,9 @@ impl Collector {
                         }
                     }
                 }
+                Update::UpdateOverrides { overrides } => {
+                    self.overrides = overrides;
+                }
                 Update::Shutdown => return false,
             }
         }
@@ -299,7 +336,8 @@ impl Collector {
 
     #[inline(always)]
     pub fn has_interest(event: impl Into<usize>) -> bool {
-        TRACE_INTERESTS.get(event)
+        let event = event.into();
+        TRACE_INTERESTS.get(event) || OVERRIDE_INTERESTS.get(event)
     }
 
     pub fn get_subscribers() -> Vec<String> {
@@ -332,7 +370,7 @@ impl Collector {
     }
 
     pub fn is_enabled() -> bool {
-        !TRACE_INTERESTS.is_empty()
+        !TRACE_INTERESTS.is_empty() || !OVERRIDE_INTERESTS.is_empty()
     }
 
     pub fn reload() {
@@ -348,6 +386,7 @@ impl Default for Collector {
             levels: [Level::Disable; TOTAL_EVENT_COUNT],
             active_spans: AHashMap::new(),
             receivers: Vec::new(),
+            overrides: Vec::new(),
         };
 
         for event in EVENT_TYPES.iter() {
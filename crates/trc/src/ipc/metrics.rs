@@ -6,7 +6,9 @@
 
 use std::sync::atomic::Ordering;
 
-use atomics::{array::AtomicU32Array, gauge::AtomicGauge, histogram::AtomicHistogram};
+use atomics::{
+    array::AtomicU32Array, counter::AtomicCounter, gauge::AtomicGauge, histogram::AtomicHistogram,
+};
 use ipc::{
     collector::{Collector, GlobalInterests},
     subscriber::Interests,
@@ -50,6 +52,13 @@ static QUEUE_COUNT: AtomicGauge = AtomicGauge::new(MetricType::QueueCount);
 static USER_COUNT: AtomicGauge = AtomicGauge::new(MetricType::UserCount);
 static DOMAIN_COUNT: AtomicGauge = AtomicGauge::new(MetricType::DomainCount);
 
+static IMAP_COMMAND_METRICS: [ImapCommandMetrics; TOTAL_IMAP_COMMANDS] =
+    init_imap_command_metrics();
+static IMAP_FETCH_BYTES: AtomicCounter = AtomicCounter::new(MetricType::ImapFetchBytes);
+static IMAP_APPEND_BYTES: AtomicCounter = AtomicCounter::new(MetricType::ImapAppendBytes);
+static STORE_BLOB_READ_BYTES: AtomicCounter = AtomicCounter::new(MetricType::StoreBlobReadBytes);
+static STORE_CHANGES_PRUNED: AtomicCounter = AtomicCounter::new(MetricType::StoreChangesPruned);
+
 const CONN_SMTP_IN: usize = 0;
 const CONN_SMTP_OUT: usize = 1;
 const CONN_IMAP: usize = 2;
@@ -58,11 +67,70 @@ const CONN_HTTP: usize = 4;
 const CONN_SIEVE: usize = 5;
 const TOTAL_CONN_TYPES: usize = 6;
 
+const IMAP_COMMANDS: [(ImapEvent, &str); 32] = [
+    (ImapEvent::Append, "APPEND"),
+    (ImapEvent::Capabilities, "CAPABILITY"),
+    (ImapEvent::Close, "CLOSE"),
+    (ImapEvent::Copy, "COPY"),
+    (ImapEvent::CreateMailbox, "CREATE"),
+    (ImapEvent::DeleteMailbox, "DELETE"),
+    (ImapEvent::Enable, "ENABLE"),
+    (ImapEvent::Expunge, "EXPUNGE"),
+    (ImapEvent::Fetch, "FETCH"),
+    (ImapEvent::GetAcl, "GETACL"),
+    (ImapEvent::GetQuota, "GETQUOTA"),
+    (ImapEvent::Id, "ID"),
+    (ImapEvent::IdleStop, "IDLE"),
+    (ImapEvent::List, "LIST"),
+    (ImapEvent::ListRights, "LISTRIGHTS"),
+    (ImapEvent::Logout, "LOGOUT"),
+    (ImapEvent::Lsub, "LSUB"),
+    (ImapEvent::Move, "MOVE"),
+    (ImapEvent::MyRights, "MYRIGHTS"),
+    (ImapEvent::Namespace, "NAMESPACE"),
+    (ImapEvent::Noop, "NOOP"),
+    (ImapEvent::RenameMailbox, "RENAME"),
+    (ImapEvent::Search, "SEARCH"),
+    (ImapEvent::Select, "SELECT"),
+    (ImapEvent::SetAcl, "SETACL"),
+    (ImapEvent::SetQuota, "SETQUOTA"),
+    (ImapEvent::Sort, "SORT"),
+    (ImapEvent::Status, "STATUS"),
+    (ImapEvent::Store, "STORE"),
+    (ImapEvent::Subscribe, "SUBSCRIBE"),
+    (ImapEvent::Thread, "THREAD"),
+    (ImapEvent::Unsubscribe, "UNSUBSCRIBE"),
+];
+const TOTAL_IMAP_COMMANDS: usize = IMAP_COMMANDS.len();
+
 pub struct ConnectionMetrics {
     pub active_connections: AtomicGauge,
     pub elapsed: AtomicHistogram<12>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandOutcome {
+    Ok = 0,
+    No = 1,
+    Bad = 2,
+}
+
+pub struct ImapCommandMetrics {
+    labels: [[(&'static str, &'static str); 2]; 3],
+    elapsed: [AtomicHistogram<12>; 3],
+}
+
+pub struct LabeledCounter {
+    id: MetricType,
+    labels: &'static [(&'static str, &'static str)],
+    value: u64,
+}
+
+pub struct LabeledHistogram {
+    labels: &'static [(&'static str, &'static str)],
+    histogram: &'static AtomicHistogram<12>,
+}
+
 pub struct EventCounter {
     id: EventType,
     value: u32,
@@ -78,10 +146,12 @@ impl Collector {
         // Extract variables
         let mut elapsed = 0;
         let mut size = 0;
+        let mut total = 0;
         for (key, value) in keys {
             match (key, value) {
                 (Key::Elapsed, Value::Duration(d)) => elapsed = *d,
                 (Key::Size, Value::UInt(s)) => size = *s,
+                (Key::Total, Value::UInt(t)) => total = *t,
                 _ => {}
             }
         }
@@ -186,6 +256,10 @@ impl Collector {
             }
             EventType::Store(StoreEvent::BlobRead) => {
                 STORE_BLOB_READ_TIME.observe(elapsed);
+                STORE_BLOB_READ_BYTES.increment_by(size);
+            }
+            EventType::Store(StoreEvent::ChangesPruned) => {
+                STORE_CHANGES_PRUNED.increment_by(total);
             }
             EventType::Store(StoreEvent::DataWrite) => {
                 STORE_DATA_WRITE_TIME.observe(elapsed);
@@ -193,11 +267,48 @@ impl Collector {
             EventType::Store(StoreEvent::DataIterate) => {
                 STORE_DATA_READ_TIME.observe(elapsed);
             }
+            EventType::Imap(event) => {
+                if let Some(metrics) = ImapCommandMetrics::get(event) {
+                    metrics.observe(CommandOutcome::Ok, elapsed);
+
+                    match event {
+                        ImapEvent::Fetch => IMAP_FETCH_BYTES.increment_by(size),
+                        ImapEvent::Append => IMAP_APPEND_BYTES.increment_by(size),
+                        _ => {}
+                    }
+                }
+            }
 
             _ => {}
         }
     }
 
+    pub fn record_imap_command(command: ImapEvent, outcome: CommandOutcome, elapsed: u64) {
+        if let Some(metrics) = ImapCommandMetrics::get(command) {
+            metrics.observe(outcome, elapsed);
+        }
+    }
+
+    pub fn metric_source_events(metric_type: MetricType) -> Vec<usize> {
+        match metric_type {
+            MetricType::ImapCommandTime | MetricType::ImapCommandCount => IMAP_COMMANDS
+                .iter()
+                .map(|(command, _)| EventType::Imap(*command).to_id() as usize)
+                .collect(),
+            MetricType::ImapFetchBytes => vec![EventType::Imap(ImapEvent::Fetch).to_id() as usize],
+            MetricType::ImapAppendBytes => {
+                vec![EventType::Imap(ImapEvent::Append).to_id() as usize]
+            }
+            MetricType::StoreBlobReadBytes => {
+                vec![EventType::Store(StoreEvent::BlobRead).to_id() as usize]
+            }
+            MetricType::StoreChangesPruned => {
+                vec![EventType::Store(StoreEvent::ChangesPruned).to_id() as usize]
+            }
+            _ => vec![],
+        }
+    }
+
     #[inline(always)]
     pub fn is_metric(event: impl Into<usize>) -> bool {
         METRIC_INTERESTS.get(event)
@@ -269,6 +380,49 @@ impl Collector {
         .filter(|h| h.is_active())
     }
 
+    pub fn collect_labeled_counters() -> impl Iterator<Item = LabeledCounter> {
+        IMAP_COMMAND_METRICS
+            .iter()
+            .flat_map(|metrics| {
+                metrics
+                    .labels
+                    .iter()
+                    .zip(metrics.elapsed.iter())
+                    .filter(|(_, histogram)| histogram.is_active())
+                    .map(|(labels, histogram)| LabeledCounter {
+                        id: MetricType::ImapCommandCount,
+                        labels,
+                        value: histogram.count(),
+                    })
+            })
+            .chain(
+                [
+                    &IMAP_FETCH_BYTES,
+                    &IMAP_APPEND_BYTES,
+                    &STORE_BLOB_READ_BYTES,
+                    &STORE_CHANGES_PRUNED,
+                ]
+                .into_iter()
+                .filter(|counter| counter.is_active())
+                .map(|counter| LabeledCounter {
+                    id: counter.id(),
+                    labels: &[],
+                    value: counter.get(),
+                }),
+            )
+    }
+
+    pub fn collect_labeled_histograms() -> impl Iterator<Item = LabeledHistogram> {
+        IMAP_COMMAND_METRICS.iter().flat_map(|metrics| {
+            metrics
+                .labels
+                .iter()
+                .zip(metrics.elapsed.iter())
+                .filter(|(_, histogram)| histogram.is_active())
+                .map(|(labels, histogram)| LabeledHistogram { labels, histogram })
+        })
+    }
+
     #[inline(always)]
     pub fn read_metric_counter(metric_id: usize) -> u32 {
         EVENT_COUNTERS.get(metric_id)
@@ -315,6 +469,28 @@ impl Collector {
             MetricType::SieveRequestTime => CONNECTION_METRICS[CONN_SIEVE].elapsed.average(),
             MetricType::UserCount => USER_COUNT.get() as f64,
             MetricType::DomainCount => DOMAIN_COUNT.get() as f64,
+            MetricType::ImapCommandCount => IMAP_COMMAND_METRICS
+                .iter()
+                .flat_map(|metrics| metrics.elapsed.iter())
+                .map(|histogram| histogram.count())
+                .sum::<u64>() as f64,
+            MetricType::ImapCommandTime => {
+                let (sum, count) = IMAP_COMMAND_METRICS
+                    .iter()
+                    .flat_map(|metrics| metrics.elapsed.iter())
+                    .fold((0, 0), |(sum, count), histogram| {
+                        (sum + histogram.sum(), count + histogram.count())
+                    });
+                if count > 0 {
+                    sum as f64 / count as f64
+                } else {
+                    0.0
+                }
+            }
+            MetricType::ImapFetchBytes => IMAP_FETCH_BYTES.get() as f64,
+            MetricType::ImapAppendBytes => IMAP_APPEND_BYTES.get() as f64,
+            MetricType::StoreBlobReadBytes => STORE_BLOB_READ_BYTES.get() as f64,
+            MetricType::StoreChangesPruned => STORE_CHANGES_PRUNED.get() as f64,
             _ => EVENT_COUNTERS.get(metric_type.event_id()) as f64,
         }
     }
@@ -361,6 +537,43 @@ impl EventCounter {
     }
 }
 
+impl LabeledCounter {
+    pub fn id(&self) -> MetricType {
+        self.id
+    }
+
+    pub fn labels(&self) -> &'static [(&'static str, &'static str)] {
+        self.labels
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl LabeledHistogram {
+    pub fn labels(&self) -> &'static [(&'static str, &'static str)] {
+        self.labels
+    }
+
+    pub fn histogram(&self) -> &'static AtomicHistogram<12> {
+        self.histogram
+    }
+}
+
+impl ImapCommandMetrics {
+    fn get(event: ImapEvent) -> Option<&'static ImapCommandMetrics> {
+        IMAP_COMMANDS
+            .iter()
+            .position(|(command, _)| *command == event)
+            .map(|idx| &IMAP_COMMAND_METRICS[idx])
+    }
+
+    fn observe(&self, outcome: CommandOutcome, elapsed: u64) {
+        self.elapsed[outcome as usize].observe(elapsed);
+    }
+}
+
 impl ConnectionMetrics {
     #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
@@ -413,3 +626,24 @@ const fn init_conn_metrics() -> [ConnectionMetrics; TOTAL_CONN_TYPES] {
     }
     array
 }
+
+const fn init_imap_command_metrics() -> [ImapCommandMetrics; TOTAL_IMAP_COMMANDS] {
+    let mut array = [const {
+        ImapCommandMetrics {
+            labels: [[("", ""); 2]; 3],
+            elapsed: [const { AtomicHistogram::<12>::new_short_durations(MetricType::ImapCommandTime) };
+                3],
+        }
+    }; TOTAL_IMAP_COMMANDS];
+    let mut i = 0;
+    while i < TOTAL_IMAP_COMMANDS {
+        let command = IMAP_COMMANDS[i].1;
+        array[i].labels = [
+            [("command", command), ("outcome", "OK")],
+            [("command", command), ("outcome", "NO")],
+            [("command", command), ("outcome", "BAD")],
+        ];
+        i += 1;
+    }
+    array
+}
@@ -8,6 +8,7 @@ pub mod bitset;
 pub mod channel;
 pub mod collector;
 pub mod metrics;
+pub mod overrides;
 pub mod subscriber;
 
 pub(crate) const USIZE_BITS: usize = std::mem::size_of::<usize>() * 8;
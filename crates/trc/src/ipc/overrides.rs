@@ -0,0 +1,250 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    time::SystemTime,
+};
+
+use compact_str::CompactString;
+use parking_lot::Mutex;
+
+use crate::*;
+
+use super::{
+    collector::{COLLECTOR_UPDATES, EVENT_TYPES, GlobalInterests, Update},
+    subscriber::Interests,
+};
+
+pub const MAX_OVERRIDES: usize = 16;
+pub const MAX_OVERRIDE_EVENTS: usize = 128;
+pub const MAX_OVERRIDE_TTL: u64 = 60 * 60 * 24; // 1 day
+
+pub(crate) static OVERRIDE_INTERESTS: GlobalInterests = GlobalInterests::new();
+// Overrides applied on this node, the cluster-wide list is kept by the server
+static ACTIVE_OVERRIDES: Mutex<Vec<TraceOverride>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "test_mode")]
+static TEST_CLOCK: std::sync::OnceLock<fn() -> u64> = std::sync::OnceLock::new();
+
+// Temporarily delivers events matching a name prefix to the active tracers,
+// regardless of the level they were configured with
+#[derive(Debug, Clone)]
+pub struct TraceOverride {
+    pub id: u64,
+    pub filter: String,
+    pub level: Level,
+    pub account_id: Option<u32>,
+    pub queue_id: Option<u64>,
+    pub key: Option<(Key, CompactString)>,
+    pub expires: u64,
+    pub events: Interests,
+}
+
+impl TraceOverride {
+    pub fn new(
+        id: u64,
+        filter: impl Into<String>,
+        level: Level,
+        expires: u64,
+    ) -> crate::Result<Self> {
+        let filter = filter.into().trim().to_ascii_lowercase();
+        if filter.is_empty() {
+            return Err(ResourceEvent::BadParameters
+                .into_err()
+                .details("An event name or prefix is required"));
+        } else if level == Level::Disable {
+            return Err(ResourceEvent::BadParameters
+                .into_err()
+                .details("Overrides cannot disable events"));
+        }
+
+        // Telemetry events are never overridden to avoid feedback loops in the tracers
+        let mut events = Interests::default();
+        let mut num_events = 0;
+        for event in EVENT_TYPES.iter() {
+            if !matches!(event, EventType::Telemetry(_)) && event.as_str().starts_with(&filter) {
+                events.set(*event);
+                num_events += 1;
+            }
+        }
+
+        if num_events == 0 {
+            Err(ResourceEvent::BadParameters
+                .into_err()
+                .details("No events match the filter")
+                .ctx(Key::Key, filter))
+        } else if num_events > MAX_OVERRIDE_EVENTS {
+            Err(ResourceEvent::BadParameters
+                .into_err()
+                .details(format!(
+                    "The filter matches {num_events} events, narrow it down to at most {MAX_OVERRIDE_EVENTS}"
+                ))
+                .ctx(Key::Key, filter))
+        } else {
+            Ok(TraceOverride {
+                id,
+                filter,
+                level,
+                account_id: None,
+                queue_id: None,
+                key: None,
+                expires,
+                events,
+            })
+        }
+    }
+
+    pub fn with_account_id(mut self, account_id: Option<u32>) -> Self {
+        self.account_id = account_id;
+        self
+    }
+
+    pub fn with_queue_id(mut self, queue_id: Option<u64>) -> Self {
+        self.queue_id = queue_id;
+        self
+    }
+
+    // Restricts the override to events carrying a key with the given value
+    pub fn with_key(mut self, key: Option<(Key, CompactString)>) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn num_events(&self) -> usize {
+        EVENT_TYPES
+            .iter()
+            .filter(|event| self.events.get(**event))
+            .count()
+    }
+
+    #[inline(always)]
+    pub(crate) fn matches(
+        &self,
+        event_id: usize,
+        level: Level,
+        event: &Event<EventDetails>,
+        timestamp: u64,
+    ) -> bool {
+        self.events.get(event_id)
+            && self.expires > timestamp
+            && self.level.is_contained(level)
+            && self
+                .account_id
+                .is_none_or(|id| event_value(event, Key::AccountId) == Some(id as u64))
+            && self
+                .queue_id
+                .is_none_or(|id| event_value(event, Key::QueueId) == Some(id))
+            && self
+                .key
+                .as_ref()
+                .is_none_or(|(key, expected)| event_key_matches(event, *key, expected))
+    }
+}
+
+// Account and queue ids are usually only present in the span start event
+fn event_value(event: &Event<EventDetails>, key: Key) -> Option<u64> {
+    event.value_as_uint(key).or_else(|| {
+        event
+            .inner
+            .span
+            .as_ref()
+            .and_then(|span| span.value_as_uint(key))
+    })
+}
+
+fn event_key_matches(event: &Event<EventDetails>, key: Key, expected: &str) -> bool {
+    event
+        .value(key)
+        .or_else(|| event.inner.span.as_ref().and_then(|span| span.value(key)))
+        .is_some_and(|value| value_matches(value, expected))
+}
+
+fn value_matches(value: &Value, expected: &str) -> bool {
+    match value {
+        Value::String(value) => value.eq_ignore_ascii_case(expected),
+        Value::UInt(value) => expected
+            .parse::<u64>()
+            .is_ok_and(|expected| expected == *value),
+        Value::Int(value) => expected
+            .parse::<i64>()
+            .is_ok_and(|expected| expected == *value),
+        Value::Bool(value) => expected
+            .parse::<bool>()
+            .is_ok_and(|expected| expected == *value),
+        Value::Ipv4(value) => expected
+            .parse::<Ipv4Addr>()
+            .is_ok_and(|expected| expected == *value),
+        Value::Ipv6(value) => expected
+            .parse::<Ipv6Addr>()
+            .is_ok_and(|expected| expected == *value),
+        Value::Array(values) => values.iter().any(|value| value_matches(value, expected)),
+        _ => false,
+    }
+}
+
+impl Collector {
+    // Replaces the overrides applied on this node
+    pub fn set_overrides(mut overrides: Vec<TraceOverride>) {
+        let now = now();
+        overrides.retain(|rule| rule.expires > now);
+        let mut active = ACTIVE_OVERRIDES.lock();
+        *active = overrides;
+        publish_overrides(&active);
+    }
+
+    pub fn get_overrides() -> Vec<TraceOverride> {
+        Collector::expire_overrides(now());
+        ACTIVE_OVERRIDES.lock().clone()
+    }
+
+    pub(crate) fn expire_overrides(timestamp: u64) {
+        let mut overrides = ACTIVE_OVERRIDES.lock();
+        if overrides.iter().any(|rule| rule.expires <= timestamp) {
+            overrides.retain(|rule| rule.expires > timestamp);
+            publish_overrides(&overrides);
+        }
+    }
+}
+
+// Event emission only checks the global bitset, while the collector
+// receives its own copy of the rules so that dispatching never takes a lock
+fn publish_overrides(overrides: &[TraceOverride]) {
+    let mut interests = Interests::default();
+    for rule in overrides {
+        interests.union(&rule.events);
+    }
+    if !interests.is_empty() {
+        for event_type in EVENT_TYPES.iter() {
+            if event_type.is_span_start() || event_type.is_span_end() {
+                interests.set(*event_type);
+            }
+        }
+    }
+    OVERRIDE_INTERESTS.update(interests);
+
+    COLLECTOR_UPDATES.lock().push(Update::UpdateOverrides {
+        overrides: overrides.to_vec(),
+    });
+    Collector::reload();
+}
+
+pub(crate) fn now() -> u64 {
+    #[cfg(feature = "test_mode")]
+    if let Some(clock) = TEST_CLOCK.get() {
+        return clock();
+    }
+
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// Expires overrides using the clock advanced by the tests
+#[cfg(feature = "test_mode")]
+pub fn set_test_clock(clock: fn() -> u64) {
+    let _ = TEST_CLOCK.set(clock);
+}
@@ -28,6 +28,7 @@ pub(crate) struct Subscriber {
     pub interests: Interests,
     pub tx: mpsc::Sender<EventBatch>,
     pub lossy: bool,
+    pub overrides: bool,
     pub batch: EventBatch,
 }
 
@@ -35,6 +36,7 @@ pub struct SubscriberBuilder {
     pub id: String,
     pub interests: Interests,
     pub lossy: bool,
+    pub overrides: bool,
 }
 
 impl Subscriber {
@@ -45,6 +47,13 @@ impl Subscriber {
         }
     }
 
+    // Events matching a tracing override are delivered even if the
+    // subscriber is not interested in them
+    #[inline(always)]
+    pub fn push_override(&mut self, trace: Arc<Event<EventDetails>>) {
+        self.batch.push(trace);
+    }
+
     pub fn send_batch(&mut self) -> Result<(), ChannelError> {
         if !self.batch.is_empty() {
             match self
@@ -76,6 +85,7 @@ impl SubscriberBuilder {
             id,
             interests: Default::default(),
             lossy: true,
+            overrides: false,
         }
     }
 
@@ -105,6 +115,11 @@ impl SubscriberBuilder {
         self
     }
 
+    pub fn with_overrides(mut self, overrides: bool) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
     pub fn register(self) -> (mpsc::Sender<EventBatch>, mpsc::Receiver<EventBatch>) {
         let (tx, rx) = mpsc::channel(8192);
 
@@ -114,6 +129,7 @@ impl SubscriberBuilder {
                 interests: self.interests,
                 tx: tx.clone(),
                 lossy: self.lossy,
+                overrides: self.overrides,
                 batch: Vec::new(),
             },
         });
@@ -42,6 +42,10 @@ pub enum EmailField {
     Metadata,
     Threading,
     DeletedAt,
+    Snooze,
+    FollowUp,
+    FollowUpMessageId,
+    References,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -81,7 +85,10 @@ pub enum PrincipalField {
     DefaultCalendarId = 47,
     DefaultAddressBookId = 48,
     ActiveScriptId = 49,
+    Provisioned = 46,
     PushSubscriptions = 44,
+    CategoryOverrides = 43,
+    SnoozedMailboxId = 42,
 }
 
 impl From<ContactField> for u8 {
@@ -119,6 +126,10 @@ impl From<EmailField> for u8 {
             EmailField::Metadata => 71,
             EmailField::Threading => 90,
             EmailField::DeletedAt => 91,
+            EmailField::Snooze => 92,
+            EmailField::FollowUp => 93,
+            EmailField::FollowUpMessageId => 94,
+            EmailField::References => 95,
             EmailField::Archive => ARCHIVE_FIELD,
         }
     }
@@ -159,7 +170,10 @@ impl From<PrincipalField> for u8 {
             PrincipalField::DefaultCalendarId => 47,
             PrincipalField::DefaultAddressBookId => 48,
             PrincipalField::ActiveScriptId => 49,
+            PrincipalField::Provisioned => 46,
             PrincipalField::PushSubscriptions => 44,
+            PrincipalField::CategoryOverrides => 43,
+            PrincipalField::SnoozedMailboxId => 42,
             PrincipalField::Archive => ARCHIVE_FIELD,
         }
     }
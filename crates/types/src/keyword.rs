@@ -5,7 +5,7 @@
  */
 
 use jmap_tools::{Element, Property, Value};
-use std::{fmt::Display, str::FromStr};
+use std::{borrow::Cow, fmt::Display, str::FromStr};
 
 pub const SEEN: usize = 0;
 pub const DRAFT: usize = 1;
@@ -125,6 +125,26 @@ impl Keyword {
             .unwrap_or_else(|| Keyword::Other(value.chars().take(Keyword::MAX_LENGTH).collect()))
     }
 
+    // Parses a keyword that was received in its escaped form over IMAP or JMAP
+    pub fn parse_escaped(value: &str) -> Self {
+        Self::try_parse(value)
+            .unwrap_or_else(|| Keyword::from_other(unescape_keyword(value).into_owned()))
+    }
+
+    pub fn to_jmap(&self) -> Cow<'_, str> {
+        match self {
+            Keyword::Other(value) => escape_keyword(value, true),
+            _ => self.to_string().into(),
+        }
+    }
+
+    pub fn is_equivalent(&self, other: &Keyword) -> bool {
+        match (self, other) {
+            (Keyword::Other(a), Keyword::Other(b)) => a.eq_ignore_ascii_case(b),
+            _ => self == other,
+        }
+    }
+
     pub fn from_other(value: String) -> Self {
         if value.len() <= Keyword::MAX_LENGTH {
             Keyword::Other(value.into_boxed_str())
@@ -286,6 +306,79 @@ impl Keyword {
     }
 }
 
+// Custom keywords are stored as received and escaped when exposed over IMAP
+// or JMAP. Bytes outside the set allowed in both IMAP atoms and JMAP keywords
+// are written as '~' followed by two lowercase hex digits ("café" becomes
+// "caf~c3~a9"), and JMAP additionally receives keywords in lowercase. A '~' is
+// only escaped ("~7e") when it would otherwise be read back as an escape
+// sequence, so keywords without special characters are exposed unchanged.
+pub fn escape_keyword(value: &str, lowercase: bool) -> Cow<'_, str> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let bytes = value.as_bytes();
+    let needs_escape =
+        |pos: usize| is_keyword_special(bytes[pos]) || escape_sequence(&bytes[pos..]).is_some();
+
+    if (0..bytes.len()).all(|pos| !needs_escape(pos))
+        && (!lowercase || !bytes.iter().any(u8::is_ascii_uppercase))
+    {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = String::with_capacity(bytes.len() + 8);
+    for (pos, &ch) in bytes.iter().enumerate() {
+        if needs_escape(pos) {
+            result.push('~');
+            result.push(HEX[(ch >> 4) as usize] as char);
+            result.push(HEX[(ch & 0x0f) as usize] as char);
+        } else if lowercase {
+            result.push(ch.to_ascii_lowercase() as char);
+        } else {
+            result.push(ch as char);
+        }
+    }
+    Cow::Owned(result)
+}
+
+pub fn unescape_keyword(value: &str) -> Cow<'_, str> {
+    let bytes = value.as_bytes();
+    if !bytes.contains(&b'~') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        if let Some(ch) = escape_sequence(&bytes[pos..]) {
+            result.push(ch);
+            pos += 3;
+        } else {
+            result.push(bytes[pos]);
+            pos += 1;
+        }
+    }
+
+    // Sequences that do not decode to valid UTF-8 are kept verbatim
+    String::from_utf8(result).map_or(Cow::Borrowed(value), Cow::Owned)
+}
+
+#[inline]
+fn is_keyword_special(ch: u8) -> bool {
+    !(0x21..=0x7e).contains(&ch)
+        || matches!(ch, b'(' | b')' | b'{' | b']' | b'%' | b'*' | b'"' | b'\\')
+}
+
+#[inline]
+fn escape_sequence(bytes: &[u8]) -> Option<u8> {
+    if let [b'~', hi, lo, ..] = bytes {
+        let hi = (*hi as char).to_digit(16)?;
+        let lo = (*lo as char).to_digit(16)?;
+        let ch = ((hi << 4) | lo) as u8;
+        (is_keyword_special(ch) || ch == b'~').then_some(ch)
+    } else {
+        None
+    }
+}
+
 impl From<String> for Keyword {
     fn from(value: String) -> Self {
         Keyword::try_parse(&value).unwrap_or_else(|| Keyword::from_other(value))
@@ -525,7 +618,7 @@ impl<'de> serde::Deserialize<'de> for Keyword {
     where
         D: serde::Deserializer<'de>,
     {
-        Ok(Keyword::parse(<&str>::deserialize(deserializer)?))
+        Ok(Keyword::parse_escaped(<&str>::deserialize(deserializer)?))
     }
 }
 
@@ -534,3 +627,35 @@ impl<'x, P: Property, E: Element + From<Keyword>> From<Keyword> for Value<'x, P,
         Value::Element(E::from(id))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Keyword, escape_keyword, unescape_keyword};
+
+    #[test]
+    fn keyword_escaping() {
+        for (raw, imap, jmap) in [
+            ("$MyFlag", "$MyFlag", "$myflag"),
+            ("tps report", "tps~20report", "tps~20report"),
+            ("caf\u{e9}", "caf~c3~a9", "caf~c3~a9"),
+            ("a(b)\\c*", "a~28b~29~5cc~2a", "a~28b~29~5cc~2a"),
+            ("x~y", "x~y", "x~y"),
+            ("x~20y", "x~7e20y", "x~7e20y"),
+            ("~~41", "~~41", "~~41"),
+        ] {
+            assert_eq!(escape_keyword(raw, false), imap, "{raw:?}");
+            assert_eq!(escape_keyword(raw, true), jmap, "{raw:?}");
+            assert_eq!(unescape_keyword(imap), raw, "{raw:?}");
+            assert!(
+                Keyword::parse_escaped(jmap).is_equivalent(&Keyword::Other(raw.into())),
+                "{raw:?}"
+            );
+        }
+
+        // Upper case hex digits are accepted, allowed bytes and invalid UTF-8
+        // are kept verbatim
+        assert_eq!(unescape_keyword("tps~2Areport"), "tps*report");
+        assert_eq!(unescape_keyword("tps~2freport"), "tps~2freport");
+        assert_eq!(unescape_keyword("bad~ff"), "bad~ff");
+    }
+}
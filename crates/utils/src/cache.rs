@@ -4,6 +4,7 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+use crate::clock::instant_now;
 use arcstr::ArcStr;
 use mail_auth::{DnssecStatus, MX, RecordSet, ResolverCache, Txt};
 use quick_cache::{
@@ -129,7 +130,7 @@ impl<K: Eq + Hash + CacheItemWeight, V: Clone + CacheItemWeight> CacheWithTtl<K,
         Q: Hash + Equivalent<K> + ?Sized,
     {
         self.0.get(key).and_then(|v| {
-            if v.expires > Instant::now() {
+            if v.expires > instant_now() {
                 Some(v.value)
             } else {
                 self.0.remove(key);
@@ -158,7 +159,7 @@ impl<K: Eq + Hash + CacheItemWeight, V: Clone + CacheItemWeight> CacheWithTtl<K,
     {
         match self.0.get_value_or_guard_async(key).await {
             Ok(value) => {
-                if value.expires > Instant::now() {
+                if value.expires > instant_now() {
                     Ok(value.value)
                 } else {
                     self.0.remove(key);
@@ -327,7 +328,7 @@ impl<T: Clone + CacheItemWeight> TtlEntry<T> {
     pub fn new(value: T, expires: Duration) -> Self {
         Self {
             value,
-            expires: Instant::now() + expires,
+            expires: instant_now() + expires,
         }
     }
 
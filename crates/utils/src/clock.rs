@@ -0,0 +1,47 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use std::time::{Duration, Instant, SystemTime};
+
+// Lets tests expire keys, cached entries and tracing overrides and move
+// schedules forward without sleeping
+#[cfg(feature = "test_mode")]
+static TEST_CLOCK_OFFSET: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+#[inline(always)]
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+        + test_clock_offset().as_secs()
+}
+
+#[inline(always)]
+pub fn instant_now() -> Instant {
+    Instant::now() + test_clock_offset()
+}
+
+#[inline(always)]
+pub fn test_clock_offset() -> Duration {
+    #[cfg(feature = "test_mode")]
+    {
+        Duration::from_secs(TEST_CLOCK_OFFSET.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    #[cfg(not(feature = "test_mode"))]
+    {
+        Duration::ZERO
+    }
+}
+
+#[cfg(feature = "test_mode")]
+pub fn advance_test_clock(seconds: u64) {
+    TEST_CLOCK_OFFSET.fetch_add(seconds, std::sync::atomic::Ordering::Relaxed);
+
+    // The collector cannot depend on this crate, hand it the clock instead
+    trc::ipc::overrides::set_test_clock(now);
+    trc::Collector::reload();
+}
@@ -9,7 +9,11 @@ use reqwest::{
     Client,
     header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, USER_AGENT},
 };
-use std::{str::FromStr, time::Duration};
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    str::FromStr,
+    time::Duration,
+};
 
 pub fn build_http_client(
     raw_headers: impl IntoIterator<Item = (String, String)>,
@@ -78,3 +82,75 @@ pub fn build_http_headers(
 
     Ok(headers)
 }
+
+// Returns whether a URL points to a loopback, private, link-local or otherwise
+// non-public address, resolving its host name if needed
+pub async fn is_internal_url(url: &str) -> Result<bool, String> {
+    let url = reqwest::Url::parse(url).map_err(|err| format!("Invalid URL: {err}"))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| "URL does not have a host".to_string())?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        Ok(is_internal_ip(ip))
+    } else if host.eq_ignore_ascii_case("localhost")
+        || host.to_ascii_lowercase().ends_with(".localhost")
+    {
+        Ok(is_internal_ip(Ipv4Addr::LOCALHOST.into()))
+    } else {
+        let port = url.port_or_known_default().unwrap_or(443);
+        let mut has_addrs = false;
+        for addr in tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|err| format!("Failed to resolve {host:?}: {err}"))?
+        {
+            if is_internal_ip(addr.ip()) {
+                return Ok(true);
+            }
+            has_addrs = true;
+        }
+
+        if has_addrs {
+            Ok(false)
+        } else {
+            Err(format!("Host {host:?} does not resolve to any address"))
+        }
+    }
+}
+
+pub fn is_internal_ip(ip: IpAddr) -> bool {
+    #[cfg(feature = "test_mode")]
+    if ip.is_loopback() {
+        return false;
+    }
+
+    match ip {
+        IpAddr::V4(ip) => is_internal_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                is_internal_ipv4(ip)
+            } else {
+                let segment = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || (segment & 0xfe00) == 0xfc00 // Unique local
+                    || (segment & 0xffc0) == 0xfe80 // Link-local
+            }
+        }
+    }
+}
+
+fn is_internal_ipv4(ip: Ipv4Addr) -> bool {
+    let octets = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || octets[0] == 0
+        || (octets[0] == 100 && (octets[1] & 0xc0) == 64) // Shared address space
+}
@@ -10,6 +10,7 @@ pub mod bimap;
 pub mod cache;
 pub mod chained_bytes;
 pub mod cheeky_hash;
+pub mod clock;
 pub mod codec;
 pub mod cron;
 pub mod glob;
@@ -429,6 +430,15 @@ pub fn is_valid_domain(domain: &str) -> bool {
             .is_some_and(|(_, tld)| RESERVED_TLDS.contains(&tld))
 }
 
+// Compares two secrets without returning early on the first mismatch
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let mut diff = (a.len() != b.len()) as u8;
+    for (idx, ch) in a.iter().enumerate() {
+        diff |= ch ^ b.get(idx).copied().unwrap_or_default();
+    }
+    diff == 0
+}
+
 #[cfg(test)]
 mod tests {
     use crate::DomainPart;
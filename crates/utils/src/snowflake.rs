@@ -64,6 +64,9 @@ impl SnowflakeIdGenerator {
             .elapsed()
             .ok()
             .map(|elapsed| {
+                #[cfg(feature = "test_mode")]
+                let elapsed = elapsed + crate::clock::test_clock_offset();
+
                 (elapsed.saturating_sub(period).as_millis() as u64) << (SEQUENCE_LEN + NODE_ID_LEN)
             })
     }
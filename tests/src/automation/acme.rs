@@ -48,6 +48,7 @@ pub async fn test(test: &TestServer) {
                 not_valid_after: UTCDateTime::from_timestamp(now + 86400),
                 not_valid_before: UTCDateTime::from_timestamp(now - 10),
                 subject_alternative_names: Map::new(vec!["mail.example.org".to_string()]),
+                ..Default::default()
             }
             .into(),
         ))
@@ -67,6 +68,7 @@ pub async fn test(test: &TestServer) {
                 not_valid_after: UTCDateTime::from_timestamp(now + (2 * 86400)),
                 not_valid_before: UTCDateTime::from_timestamp(now - 10),
                 subject_alternative_names: Map::new(vec!["mail.example.org".to_string()]),
+                ..Default::default()
             }
             .into(),
         ))
@@ -86,6 +88,7 @@ pub async fn test(test: &TestServer) {
                 not_valid_after: UTCDateTime::from_timestamp(now - 86400),
                 not_valid_before: UTCDateTime::from_timestamp(now - 100),
                 subject_alternative_names: Map::new(vec!["mail.example.org".to_string()]),
+                ..Default::default()
             }
             .into(),
         ))
@@ -197,6 +200,7 @@ pub async fn test(test: &TestServer) {
                 CertificateManagementProperties {
                     acme_provider_id: tls_acme_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 },
             ),
             dkim_management: DkimManagement::Manual,
@@ -303,6 +307,7 @@ pub async fn test(test: &TestServer) {
         Property::CertificateManagement: CertificateManagement::Automatic(CertificateManagementProperties {
             acme_provider_id: http_acme_id,
             subject_alternative_names: Default::default(),
+            ..Default::default()
         }),
     })).await;
     test.wait_for_tasks_skip_not_due().await;
@@ -349,6 +354,7 @@ pub async fn test(test: &TestServer) {
                 CertificateManagementProperties {
                     acme_provider_id: dns_acme_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 },
             ),
             dkim_management: DkimManagement::Manual,
@@ -390,6 +396,7 @@ pub async fn test(test: &TestServer) {
                 CertificateManagementProperties {
                     acme_provider_id: dns_acme_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 },
             ),
             dkim_management: DkimManagement::Manual,
@@ -440,6 +447,7 @@ pub async fn test(test: &TestServer) {
                 CertificateManagementProperties {
                     acme_provider_id: default_acme_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 },
             ),
             dkim_management: DkimManagement::Manual,
@@ -496,6 +504,7 @@ pub async fn test(test: &TestServer) {
                 CertificateManagementProperties {
                     acme_provider_id: preferred_acme_id,
                     subject_alternative_names: Default::default(),
+                    ..Default::default()
                 },
             ),
             dkim_management: DkimManagement::Manual,
@@ -559,6 +568,7 @@ pub async fn test(test: &TestServer) {
                     CertificateManagementProperties {
                         acme_provider_id: reuse_acme_id,
                         subject_alternative_names: Default::default(),
+                        ..Default::default()
                     },
                 ),
                 dkim_management: DkimManagement::Manual,
@@ -106,6 +106,7 @@ pub async fn test(test: &TestServer) {
     let cert = CertificateManagement::Automatic(CertificateManagementProperties {
         acme_provider_id,
         subject_alternative_names: Default::default(),
+        ..Default::default()
     });
     let dns = DnsManagement::Automatic(DnsManagementProperties {
         dns_server_id,
@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::TestServer;
+use registry::{
+    schema::{
+        enums::{AcmeChallengeType, DnsRecordType},
+        prelude::ObjectType,
+        structs::{
+            AcmeProvider, Certificate, CertificateManagement, CertificateManagementProperties,
+            DkimManagement, DnsManagement, DnsManagementProperties, DnsServer, DnsServerCloudflare,
+            Domain, SecretKey, SecretKeyValue,
+        },
+    },
+    types::map::Map,
+};
+use std::time::Duration;
+
+pub async fn test(servers: &[TestServer]) {
+    println!("Running cluster ACME renewal tests...");
+    crate::utils::containers::ensure_acme().await;
+    let admin = servers[0].account("admin");
+
+    let dns_id = admin
+        .registry_create_object(DnsServer::Cloudflare(DnsServerCloudflare {
+            secret: SecretKey::Value(SecretKeyValue {
+                secret: "test@pebble.org".into(),
+            }),
+            description: "Pebble DNS server".to_string(),
+            ..Default::default()
+        }))
+        .await;
+    let acme_id = admin
+        .registry_create_object(AcmeProvider {
+            directory: "https://localhost:14000/dir".to_string(),
+            contact: Map::new(vec!["mailto:hello@cluster.org".to_string()]),
+            challenge_type: AcmeChallengeType::Dns01,
+            ..Default::default()
+        })
+        .await;
+
+    // Creating the domain schedules a renewal task on one of the nodes,
+    // race it with a manual renewal on every node
+    let domain_id = admin
+        .registry_create_object(Domain {
+            name: "cluster.org".to_string(),
+            certificate_management: CertificateManagement::Automatic(
+                CertificateManagementProperties {
+                    acme_provider_id: acme_id,
+                    subject_alternative_names: Default::default(),
+                    ..Default::default()
+                },
+            ),
+            dkim_management: DkimManagement::Manual,
+            dns_management: DnsManagement::Automatic(DnsManagementProperties {
+                dns_server_id: dns_id,
+                publish_records: Map::new(vec![DnsRecordType::Caa]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .await;
+    let results = futures::future::join_all(
+        servers
+            .iter()
+            .map(|test| Box::pin(test.server.acme_renew(domain_id))),
+    )
+    .await;
+    assert!(
+        results.iter().filter(|result| result.is_ok()).count() <= 1,
+        "More than one node completed an ACME order"
+    );
+    servers[0].wait_for_tasks_skip_not_due().await;
+
+    // Only one order must have been placed for this renewal cycle
+    let certificates = admin
+        .registry_get_all::<Certificate>()
+        .await
+        .into_iter()
+        .filter(|(_, cert)| {
+            cert.subject_alternative_names
+                .iter()
+                .any(|name| name == "cluster.org")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        certificates.len(),
+        1,
+        "Expected a single certificate, found {}: {:?}",
+        certificates.len(),
+        certificates
+    );
+
+    // All nodes should have hot-reloaded the certificate
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    for test in servers {
+        assert!(
+            test.server.resolve_certificate("cluster.org").is_some(),
+            "Node {} did not load the renewed certificate",
+            test.server.registry().node_id()
+        );
+    }
+
+    // Subsequent renewals are not due on any node
+    for test in servers {
+        assert!(test.server.acme_renew(domain_id).await.is_err());
+    }
+
+    admin.registry_destroy_all(ObjectType::Certificate).await;
+    admin.registry_destroy_all(ObjectType::Task).await;
+}
@@ -5,6 +5,7 @@
  */
 
 use crate::{
+    cluster::acme,
     imap::idle,
     utils::{
         imap::{ImapConnection, Type},
@@ -208,6 +209,30 @@ pub async fn cluster_tests() {
     let mut node1_client = imap_client("jdoe@example.com", "this is john's secret", 1).await;
     let mut node2_client = imap_client("jdoe@example.com", "this is john's secret", 2).await;
     idle::test(&mut node1_client, &mut node2_client, true).await;
+
+    // Sessions open on other nodes are listed
+    let response = admin
+        .http_get_raw(&format!("{}/api/sessions", admin.base_url()), None)
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let sessions = response.json().unwrap();
+    let entry = sessions
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["accountId"] == account.id_string())
+        .unwrap_or_else(|| panic!("Account not listed: {sessions}"));
+    let mut node_ids = entry["sessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|session| session["nodeId"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    node_ids.sort_unstable();
+    assert_eq!(node_ids, [1, 2], "{entry}");
+
+    // Run coordinated ACME renewal tests
+    acme::test(&servers).await;
 }
 
 async fn imap_client(login: &str, secret: &str, node_id: u32) -> ImapConnection {
@@ -0,0 +1,197 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::{TestServer, TestServerBuilder};
+use common::auth::AuthRequest;
+use registry::{
+    schema::{
+        enums::ServiceProtocol,
+        prelude::Property,
+        structs::{
+            Authentication, Directory, DirectoryChainEntry, SqlAuthStore, SqlDirectory, SqliteStore,
+        },
+    },
+    types::{duration::Duration as RegistryDuration, list::List},
+};
+use store::Store;
+use types::id::Id;
+
+pub async fn test() {
+    println!("Running directory chain tests...");
+    let mut test = TestServerBuilder::new("directory_chain_test")
+        .await
+        .with_default_listeners()
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Create two SQL directories, chained in order
+    let store_a = create_test_store(&test, "a.db", &[("alice@example.org", "alice A")]).await;
+    let store_b = create_test_store(
+        &test,
+        "b.db",
+        &[
+            ("alice@example.org", "alice B"),
+            ("bob@example.org", "bob B"),
+        ],
+    )
+    .await;
+    let admin = test.account("admin");
+    let directory_a = admin
+        .registry_create_object(Directory::Sql(test_directory(&test, "a.db")))
+        .await;
+    let directory_b = admin
+        .registry_create_object(Directory::Sql(test_directory(&test, "b.db")))
+        .await;
+    admin
+        .registry_update_setting(
+            Authentication {
+                directory_chain: List::from_iter([directory_a, directory_b].map(|id| {
+                    DirectoryChainEntry {
+                        directory_id: Some(id),
+                    }
+                })),
+                directory_cache_ttl: RegistryDuration::from_millis(2000),
+                ..Default::default()
+            },
+            &[Property::DirectoryChain, Property::DirectoryCacheTtl],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+
+    // Accounts missing from the first directory are found in the second one
+    authenticate(&test, "bob@example.org", "bob B")
+        .await
+        .unwrap();
+    assert_source(
+        authenticate(&test, "bob@example.org", "bob A").await,
+        directory_b,
+    );
+
+    // A wrong password in the first directory does not fall through
+    assert_source(
+        authenticate(&test, "alice@example.org", "alice B").await,
+        directory_a,
+    );
+    authenticate(&test, "alice@example.org", "alice A")
+        .await
+        .unwrap();
+
+    // The directory that holds an account is cached
+    insert_account(&store_a, "bob@example.org", "bob A").await;
+    authenticate(&test, "bob@example.org", "bob B")
+        .await
+        .unwrap();
+    utils::clock::advance_test_clock(3);
+    assert_source(
+        authenticate(&test, "bob@example.org", "bob B").await,
+        directory_a,
+    );
+    authenticate(&test, "bob@example.org", "bob A")
+        .await
+        .unwrap();
+
+    // Unknown accounts are not cached, new accounts can log in right away
+    assert!(
+        authenticate(&test, "carol@example.org", "carol B")
+            .await
+            .is_err()
+    );
+    insert_account(&store_b, "carol@example.org", "carol B").await;
+    assert_eq!(
+        test.server
+            .inner
+            .cache
+            .directory_principals
+            .get("carol@example.org"),
+        None
+    );
+    authenticate(&test, "carol@example.org", "carol B")
+        .await
+        .unwrap();
+}
+
+async fn authenticate(test: &TestServer, username: &str, secret: &str) -> trc::Result<u32> {
+    test.server
+        .authenticate(&AuthRequest::from_plain(
+            username,
+            secret,
+            0,
+            "127.0.0.1".parse().unwrap(),
+            ServiceProtocol::Imap,
+        ))
+        .await
+        .map(|token| token.account_id())
+}
+
+fn assert_source(result: trc::Result<u32>, directory_id: Id) {
+    let err = result.expect_err("Authentication should have failed");
+    assert_eq!(
+        err.value_as_str(trc::Key::Source),
+        Some(directory_id.to_string().as_str()),
+        "{err:?}"
+    );
+}
+
+async fn create_test_store(test: &TestServer, name: &str, accounts: &[(&str, &str)]) -> Store {
+    let store = store::backend::sqlite::SqliteStore::open(sqlite_config(test, name)).unwrap();
+    store
+        .sql_query::<usize>(
+            concat!(
+                "CREATE TABLE accounts (name TEXT PRIMARY KEY, secret TEXT, ",
+                "description TEXT, type TEXT NOT NULL)"
+            ),
+            vec![],
+        )
+        .await
+        .unwrap();
+    for (name, secret) in accounts {
+        insert_account(&store, name, secret).await;
+    }
+    store
+}
+
+async fn insert_account(store: &Store, name: &str, secret: &str) {
+    store
+        .sql_query::<usize>(
+            concat!(
+                "INSERT INTO accounts (name, secret, type) ",
+                "VALUES ($1, $2, 'individual')"
+            ),
+            vec![name.into(), secret.into()],
+        )
+        .await
+        .unwrap();
+}
+
+fn test_directory(test: &TestServer, name: &str) -> SqlDirectory {
+    SqlDirectory {
+        description: format!("Chained directory {name}"),
+        query_login: "SELECT name, secret, description, type FROM accounts WHERE name = $1".into(),
+        query_recipient: "SELECT name, secret, description, type FROM accounts WHERE name = $1"
+            .into(),
+        query_email_aliases: None,
+        query_member_of: None,
+        column_class: "type".to_string().into(),
+        column_description: "description".to_string().into(),
+        column_enabled_protocols: None,
+        column_categorize_mail: None,
+        column_email: "name".into(),
+        column_secret: "secret".into(),
+        store: SqlAuthStore::Sqlite(sqlite_config(test, name)),
+        member_tenant_id: None,
+    }
+}
+
+fn sqlite_config(test: &TestServer, name: &str) -> SqliteStore {
+    SqliteStore {
+        path: format!("{}/{name}", test.tmp_dir()),
+        pool_workers: None,
+        pool_max_connections: 10,
+    }
+}
@@ -31,6 +31,8 @@ pub async fn test() {
             secret: Some("$app$8958830913002348890$".into()),
             groups: Some(vec!["sales@example.org".into()]),
             description: Some("John Doe".into()),
+            enabled_protocols: None,
+            categorize_mail: None,
         }
     );
     assert_eq!(
@@ -50,6 +52,8 @@ pub async fn test() {
                 "corporate@example.org".into()
             ]),
             description: Some("Jane Smith".into()),
+            enabled_protocols: None,
+            categorize_mail: None,
         }
     );
     assert!(
@@ -81,6 +85,8 @@ pub async fn test() {
             secret: Some("this is John's LDAP password".into()),
             groups: Some(vec!["sales@example.org".into()]),
             description: Some("John Doe".into()),
+            enabled_protocols: None,
+            categorize_mail: None,
         }
     );
     assert!(
@@ -101,7 +107,9 @@ pub async fn test() {
             email_aliases: vec!["john@example.org".into()],
             secret: Some("this is John's LDAP password".into()),
             groups: Some(vec!["sales@example.org".into()]),
-            description: Some("John Doe".into())
+            description: Some("John Doe".into()),
+            enabled_protocols: None,
+            categorize_mail: None
         })
     );
     assert_eq!(
@@ -114,7 +122,9 @@ pub async fn test() {
                 "sales@example.org".into(),
                 "corporate@example.org".into()
             ]),
-            description: Some("Jane Smith".into())
+            description: Some("Jane Smith".into()),
+            enabled_protocols: None,
+            categorize_mail: None
         })
     );
     assert_eq!(
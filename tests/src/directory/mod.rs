@@ -4,6 +4,8 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+#[cfg(feature = "sqlite")]
+pub mod chain;
 pub mod integration;
 pub mod ldap;
 pub mod oidc;
@@ -17,6 +19,8 @@ pub async fn directory_tests() {
     oidc::test().await;
     #[cfg(feature = "sqlite")]
     sql::test().await;
+    #[cfg(feature = "sqlite")]
+    chain::test().await;
     synchronization::test().await;
     integration::test().await;
 }
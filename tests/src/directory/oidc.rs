@@ -9,7 +9,10 @@
  */
 
 use directory::{Account, Credentials, Directory, backend::oidc::OpenIdDirectory};
-use registry::{schema::structs, types::map::Map};
+use registry::{
+    schema::{enums::ServiceProtocol, structs},
+    types::map::Map,
+};
 
 pub async fn test() {
     println!("Running OIDC directory tests...");
@@ -20,6 +23,8 @@ pub async fn test() {
         claim_username: "preferred_username".to_string(),
         claim_name: Some("name".to_string()),
         claim_groups: Some("groups".to_string()),
+        claim_enabled_protocols: None,
+        claim_categorize_mail: None,
         username_domain: None,
         require_audience: Some("stalwart".to_string()),
         require_scopes: Map::new(vec![
@@ -45,7 +50,9 @@ pub async fn test() {
             email_aliases: vec![],
             secret: None,
             groups: Some(vec!["sales@example.org".to_string()]),
-            description: Some("John Doe".to_string())
+            description: Some("John Doe".to_string()),
+            enabled_protocols: None,
+            categorize_mail: None
         }
     );
 
@@ -67,10 +74,29 @@ pub async fn test() {
             email_aliases: vec![],
             secret: None,
             groups: Some(vec!["sales@example.org".to_string()]),
-            description: Some("John Doe".to_string())
+            description: Some("John Doe".to_string()),
+            enabled_protocols: None,
+            categorize_mail: None
         }
     );
 
+    // Protocol entitlements are read from the configured claim
+    let mut config_protocols = config.clone();
+    config_protocols.claim_enabled_protocols = Some("enabled_protocols".to_string());
+    assert_eq!(
+        OpenIdDirectory::open(config_protocols)
+            .await
+            .unwrap()
+            .authenticate(&Credentials::Bearer {
+                username: None,
+                token: token.clone(),
+            })
+            .await
+            .unwrap()
+            .enabled_protocols,
+        Some(vec![ServiceProtocol::Imap, ServiceProtocol::Jmap])
+    );
+
     // Test ODIC userinfo fallback
     let mut config_userinfo_fallback = config.clone();
     config_userinfo_fallback.claim_username = "email".to_string();
@@ -117,6 +143,8 @@ pub async fn test() {
             secret: None,
             groups: Some(vec!["sales@example.org".to_string()]),
             description: None,
+            enabled_protocols: None,
+            categorize_mail: None,
         }
     );
 
@@ -77,6 +77,8 @@ pub async fn test() {
             .into(),
         column_class: "type".to_string().into(),
         column_description: "description".to_string().into(),
+        column_enabled_protocols: None,
+        column_categorize_mail: None,
         column_email: "name".into(),
         column_secret: "secret".into(),
         store: SqlAuthStore::Default,
@@ -84,7 +86,9 @@ pub async fn test() {
     };
 
     // Test authentication
-    let sql = SqlDirectory::open(config, &sql_store).await.unwrap();
+    let sql = SqlDirectory::open(config.clone(), &sql_store)
+        .await
+        .unwrap();
     assert_eq!(
         sql.authenticate(&Credentials::Basic {
             username: "john@example.org".to_string(),
@@ -99,6 +103,8 @@ pub async fn test() {
             secret: Some("john secret".to_string()),
             groups: Some(vec!["sales@example.org".to_string()]),
             description: Some("John Doe".to_string()),
+            enabled_protocols: None,
+            categorize_mail: None,
         }
     );
     assert!(
@@ -120,6 +126,8 @@ pub async fn test() {
             secret: Some("john secret".to_string()),
             groups: Some(vec!["sales@example.org".to_string()]),
             description: Some("John Doe".to_string()),
+            enabled_protocols: None,
+            categorize_mail: None,
         })
     );
     assert_eq!(
@@ -130,6 +138,8 @@ pub async fn test() {
             secret: Some("jane secret".to_string()),
             groups: Some(vec!["sales@example.org".to_string()]),
             description: Some("Jane Doe".to_string()),
+            enabled_protocols: None,
+            categorize_mail: None,
         })
     );
     assert_eq!(
@@ -144,4 +154,29 @@ pub async fn test() {
         sql.recipient("unknown@example.org").await.unwrap(),
         Recipient::Invalid
     );
+
+    // Mail categorization is read from the configured column
+    let sql = SqlDirectory::open(
+        structs::SqlDirectory {
+            query_recipient: concat!(
+                "SELECT name, secret, description, type, ",
+                "name = 'john@example.org' AS categorize FROM accounts ",
+                "WHERE name = $1 AND active = true"
+            )
+            .into(),
+            column_categorize_mail: "categorize".to_string().into(),
+            ..config
+        },
+        &sql_store,
+    )
+    .await
+    .unwrap();
+    for (name, categorize_mail) in [("john@example.org", true), ("jane@example.org", false)] {
+        match sql.recipient(name).await.unwrap() {
+            Recipient::Account(account) => {
+                assert_eq!(account.categorize_mail, Some(categorize_mail), "{name}");
+            }
+            recipient => panic!("Unexpected recipient {recipient:?}"),
+        }
+    }
 }
@@ -31,6 +31,8 @@ pub async fn test() {
                 secret: "supersecret".to_string().into(),
                 groups: Some(vec![]),
                 description: "John Doe".to_string().into(),
+                enabled_protocols: None,
+                categorize_mail: None,
             })
             .await
             .is_err()
@@ -49,6 +51,8 @@ pub async fn test() {
             "sales@example.org".to_string(),
         ]),
         description: "John Doe".to_string().into(),
+        enabled_protocols: None,
+        categorize_mail: None,
     };
     let result = test
         .server
@@ -7,6 +7,7 @@
 use super::{AssertResult, ImapConnection, Type, append::assert_append_message};
 use crate::utils::{server::TestServer, smtp::SmtpConnection};
 use imap_proto::ResponseType;
+use jmap_client::{mailbox, principal::ACL};
 
 pub async fn test(
     mut imap_john: &mut ImapConnection,
@@ -314,4 +315,128 @@ pub async fn test(
         .assert_read(Type::Tagged, ResponseType::Ok)
         .await
         .assert_count("Shared Folders", 3);
+
+    // JMAP myRights should match IMAP MYRIGHTS for each mailbox, including
+    // rights that differ between a folder and its children and rights granted
+    // to a group
+    for folder in ["Projects", "Projects/Budget"] {
+        imap_jane.send(&format!("CREATE \"{folder}\"")).await;
+        imap_jane.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+    imap_bill.send("CREATE \"Team\"").await;
+    imap_bill.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    let jane = test.account("jane.smith@example.com");
+    let bill = test.account("foobar@example.com");
+    let mut john_client = test.account("jdoe@example.com").jmap_client().await;
+    let mut jane_client = jane.jmap_client().await;
+    let mut prev_state = None;
+
+    for (folder, grantee, acl, expected, expected_jmap) in [
+        (
+            "Projects",
+            "jdoe@example.com",
+            "lr",
+            "rl",
+            &[ACL::ReadItems][..],
+        ),
+        (
+            "Projects/Budget",
+            "jdoe@example.com",
+            "lrswitekx",
+            "rliteswkx",
+            &[
+                ACL::ReadItems,
+                ACL::AddItems,
+                ACL::RemoveItems,
+                ACL::SetSeen,
+                ACL::SetKeywords,
+                ACL::CreateChild,
+                ACL::Delete,
+            ][..],
+        ),
+        (
+            "Projects/Budget",
+            "jdoe@example.com",
+            "lrs",
+            "rlsw",
+            &[ACL::ReadItems, ACL::SetSeen, ACL::SetKeywords][..],
+        ),
+        (
+            "Team",
+            "support@example.com",
+            "lrip",
+            "rlip",
+            &[ACL::ReadItems, ACL::AddItems, ACL::Submit][..],
+        ),
+    ] {
+        // Bill shares "Team" with a group Jane is a member of
+        let (owner, imap_owner, imap, client) = if folder == "Team" {
+            (bill, &mut imap_bill, &mut imap_jane, &mut jane_client)
+        } else {
+            (jane, &mut imap_jane, &mut *imap_john, &mut john_client)
+        };
+        imap_owner
+            .send(&format!("SETACL \"{folder}\" {grantee} {acl}"))
+            .await;
+        imap_owner.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+        let shared_name = format!("Shared Folders/{}/{folder}", owner.name());
+        imap.send(&format!("MYRIGHTS \"{shared_name}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok)
+            .await
+            .assert_equals(&format!("* MYRIGHTS \"{shared_name}\" {expected}"));
+
+        let mailbox_id = client
+            .set_default_account_id(owner.id_string())
+            .mailbox_query(
+                mailbox::query::Filter::name(folder.rsplit('/').next().unwrap()).into(),
+                None::<Vec<_>>,
+            )
+            .await
+            .unwrap()
+            .take_ids()
+            .pop()
+            .unwrap();
+        let mut request = client.build();
+        request
+            .get_mailbox()
+            .ids([mailbox_id])
+            .properties([mailbox::Property::MyRights]);
+        let mut response = request.send_get_mailbox().await.unwrap();
+        let rights = response
+            .take_list()
+            .pop()
+            .unwrap()
+            .my_rights()
+            .unwrap()
+            .acl_list();
+        assert_eq!(rights, expected_jmap, "{folder}");
+        assert_eq!(jmap_to_imap_rights(&rights), expected, "{folder}");
+
+        // ACL changes must produce a new Mailbox state
+        if folder == "Projects/Budget" {
+            let state = response.state().to_string();
+            assert_ne!(prev_state.as_ref(), Some(&state));
+            prev_state = Some(state);
+        }
+    }
+}
+
+fn jmap_to_imap_rights(rights: &[ACL]) -> String {
+    let mut imap_rights = String::new();
+    for right in rights {
+        imap_rights.push_str(match right {
+            ACL::ReadItems => "rl",
+            ACL::AddItems => "i",
+            ACL::RemoveItems => "te",
+            ACL::SetSeen => "sw",
+            ACL::SetKeywords => "",
+            ACL::CreateChild => "k",
+            ACL::Delete => "x",
+            ACL::Submit => "p",
+            _ => "",
+        });
+    }
+    imap_rights
 }
@@ -0,0 +1,271 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{AssertResult, ImapConnection, Type};
+use crate::utils::server::TestServer;
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use imap_proto::ResponseType;
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+pub async fn test(test: &TestServer) {
+    println!("Running COMPRESS tests...");
+
+    let john = test.account("jdoe@example.com");
+    let mut imap = ImapConnection::connect(b"_z ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+
+    // Not available before authentication
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_not_contains("COMPRESS=DEFLATE");
+    imap.send("COMPRESS DEFLATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    imap.authenticate(john.name(), john.secret()).await;
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("COMPRESS=DEFLATE");
+
+    // Unknown algorithms and pipelined commands are rejected
+    imap.send("COMPRESS LZ4").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+    imap.send_raw("_z COMPRESS DEFLATE\r\n_z NOOP\r\n").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Enable compression, the response itself is not compressed
+    imap.send("COMPRESS DEFLATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("DEFLATE active");
+    let mut imap = DeflateConnection::new(imap.into_inner());
+
+    // Append a large compressible message
+    let mut message = String::from(
+        "From: john@example.com\r\nTo: jane@example.com\r\nSubject: compress test\r\n\r\n",
+    );
+    for line in 0..5000 {
+        message.push_str(&format!(
+            "Line {line:04} of a highly compressible message body, repeating the same words.\r\n"
+        ));
+    }
+    imap.send(&format!(
+        "_z APPEND \"Compressed\" {{{}+}}\r\n{message}\r\n",
+        message.len()
+    ))
+    .await;
+    imap.read("_z ").await.assert_contains("_z NO");
+    imap.send("_z CREATE \"Compressed\"\r\n").await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    let bytes_sent = imap.bytes_sent;
+    imap.send(&format!(
+        "_z APPEND \"Compressed\" {{{}+}}\r\n{message}\r\n",
+        message.len()
+    ))
+    .await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    assert!(
+        imap.bytes_sent - bytes_sent < message.len() / 5,
+        "{} bytes sent for a {} bytes message",
+        imap.bytes_sent - bytes_sent,
+        message.len()
+    );
+
+    // Fetching the message uses a fraction of the bandwidth
+    imap.send("_z SELECT \"Compressed\"\r\n").await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    let bytes_received = imap.bytes_received;
+    imap.send("_z FETCH 1 BODY[]\r\n").await;
+    imap.read("_z ")
+        .await
+        .assert_contains("Subject: compress test")
+        .assert_contains(
+            "Line 4999 of a highly compressible message body, repeating the same words.",
+        )
+        .assert_contains("_z OK");
+    assert!(
+        imap.bytes_received - bytes_received < message.len() / 5,
+        "{} bytes received for a {} bytes message",
+        imap.bytes_received - bytes_received,
+        message.len()
+    );
+
+    // Highly compressible requests are accepted as long as they fit the
+    // maximum request size
+    let payload = vec![b'A'; 4 * 1024 * 1024];
+    let bytes_sent = imap.bytes_sent;
+    imap.send(&format!(
+        "_z APPEND \"Compressed\" {{{}+}}\r\n",
+        payload.len()
+    ))
+    .await;
+    imap.send_bytes(&payload).await;
+    imap.send("\r\n").await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    assert!(imap.bytes_sent - bytes_sent < payload.len() / 128);
+
+    // Small responses are flushed right away
+    for _ in 0..3 {
+        imap.send("_z NOOP\r\n").await;
+        imap.read("_z ").await.assert_contains("_z OK");
+    }
+
+    // Compression can only be enabled once and excludes STARTTLS
+    imap.send("_z COMPRESS DEFLATE\r\n").await;
+    imap.read("_z ")
+        .await
+        .assert_contains("_z NO [COMPRESSIONACTIVE]");
+    imap.send("_z STARTTLS\r\n").await;
+    imap.read("_z ").await.assert_contains("_z NO");
+    imap.send("_z CAPABILITY\r\n").await;
+    imap.read("_z ")
+        .await
+        .assert_not_contains("COMPRESS=DEFLATE")
+        .assert_not_contains("STARTTLS")
+        .assert_contains("_z OK");
+
+    imap.send("_z UNSELECT\r\n").await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    imap.send("_z DELETE \"Compressed\"\r\n").await;
+    imap.read("_z ").await.assert_contains("_z OK");
+    imap.send("_z LOGOUT\r\n").await;
+    imap.read("_z ").await.assert_contains("* BYE");
+
+    // Sessions inflating past the maximum request size are dropped
+    let mut imap = ImapConnection::connect(b"_z ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(john.name(), john.secret()).await;
+    imap.send_ok("COMPRESS DEFLATE").await;
+    let mut imap = DeflateConnection::new(imap.into_inner());
+    let payload = vec![b'A'; 25 * 1024 * 1024];
+    for _ in 0..3 {
+        let mut request =
+            format!("_z APPEND \"Nonexistent\" {{{}+}}\r\n", payload.len()).into_bytes();
+        request.extend_from_slice(&payload);
+        request.extend_from_slice(b"\r\n");
+        if !imap.try_send_bytes(&request).await {
+            break;
+        }
+    }
+    imap.assert_disconnect().await;
+}
+
+struct DeflateConnection {
+    stream: TcpStream,
+    compress: Compress,
+    decompress: Decompress,
+    buf: Vec<u8>,
+    bytes_sent: usize,
+    bytes_received: usize,
+}
+
+impl DeflateConnection {
+    fn new(stream: TcpStream) -> Self {
+        DeflateConnection {
+            stream,
+            compress: Compress::new(Compression::default(), false),
+            decompress: Decompress::new(false),
+            buf: Vec::new(),
+            bytes_sent: 0,
+            bytes_received: 0,
+        }
+    }
+
+    async fn send(&mut self, text: &str) {
+        self.send_bytes(text.as_bytes()).await;
+    }
+
+    async fn send_bytes(&mut self, bytes: &[u8]) {
+        assert!(self.try_send_bytes(bytes).await, "Failed to send data");
+    }
+
+    // Returns false if the server closed the connection
+    async fn try_send_bytes(&mut self, bytes: &[u8]) -> bool {
+        let mut output = Vec::with_capacity(bytes.len() + 1024);
+        let total_in = self.compress.total_in();
+        self.compress
+            .compress_vec(bytes, &mut output, FlushCompress::Sync)
+            .unwrap();
+        assert_eq!((self.compress.total_in() - total_in) as usize, bytes.len());
+        self.bytes_sent += output.len();
+        self.stream.write_all(&output).await.is_ok()
+    }
+
+    async fn read(&mut self, tag: &str) -> Vec<String> {
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            if let Some(end) = tagged_response_end(&self.buf, tag.as_bytes()) {
+                let response = self.buf.drain(..end).collect::<Vec<_>>();
+                return String::from_utf8_lossy(&response)
+                    .split("\r\n")
+                    .filter(|line| !line.is_empty())
+                    .map(|line| line.to_string())
+                    .collect();
+            }
+
+            let bytes_read = tokio::time::timeout(Duration::from_millis(1500), async {
+                self.stream.read(&mut chunk).await.unwrap()
+            })
+            .await
+            .unwrap_or_else(|_| {
+                panic!(
+                    "Timeout while waiting for server response: {:?}",
+                    String::from_utf8_lossy(&self.buf)
+                )
+            });
+            assert_ne!(bytes_read, 0, "Connection closed");
+            self.bytes_received += bytes_read;
+            self.inflate(&chunk[..bytes_read]);
+        }
+    }
+
+    fn inflate(&mut self, mut input: &[u8]) {
+        loop {
+            self.buf.reserve(64 * 1024);
+            let total_in = self.decompress.total_in();
+            self.decompress
+                .decompress_vec(input, &mut self.buf, FlushDecompress::None)
+                .unwrap();
+            input = &input[(self.decompress.total_in() - total_in) as usize..];
+            if input.is_empty() && self.buf.len() < self.buf.capacity() {
+                break;
+            }
+        }
+    }
+
+    async fn assert_disconnect(&mut self) {
+        let mut chunk = vec![0u8; 8192];
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), self.stream.read(&mut chunk)).await {
+                Ok(Ok(0)) | Ok(Err(_)) => break,
+                Ok(Ok(bytes_read)) => {
+                    // Discard any continuation or error responses
+                    self.bytes_received += bytes_read;
+                }
+                Err(_) => panic!("Expected connection to be closed."),
+            }
+        }
+    }
+}
+
+fn tagged_response_end(buf: &[u8], tag: &[u8]) -> Option<usize> {
+    let mut line_start = 0;
+    while let Some(pos) = buf[line_start..].windows(2).position(|w| w == b"\r\n") {
+        let line_end = line_start + pos + 2;
+        let line = &buf[line_start..line_end];
+        if line.starts_with(tag) {
+            return Some(line_end);
+        }
+        line_start = line_end;
+    }
+    None
+}
@@ -5,9 +5,11 @@
  */
 
 use super::{AssertResult, ImapConnection, Type};
+use crate::utils::server::TestServer;
 use imap_proto::ResponseType;
+use trc::{Collector, MetricType};
 
-pub async fn test(imap: &mut ImapConnection, imap_check: &mut ImapConnection) {
+pub async fn test(imap: &mut ImapConnection, imap_check: &mut ImapConnection, test: &TestServer) {
     println!("Running COPY/MOVE tests...");
 
     // Check status
@@ -174,4 +176,86 @@ pub async fn test(imap: &mut ImapConnection, imap_check: &mut ImapConnection) {
     imap.assert_read(Type::Tagged, ResponseType::Ok)
         .await
         .assert_contains("COPYUID");
+
+    // Moving a large folder within the same account does not touch
+    // the blobs or the search index
+    move_large_folder(imap, test).await;
+}
+
+async fn move_large_folder(imap: &mut ImapConnection, test: &TestServer) {
+    const NUM_MESSAGES: usize = 250;
+
+    for mailbox in ["Mozzarella di Bufala", "Stracciatella"] {
+        imap.send(&format!("CREATE \"{mailbox}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+    for num in 0..NUM_MESSAGES {
+        imap.append(
+            "Mozzarella di Bufala",
+            &format!(
+                concat!(
+                    "From: john@example.org\r\n",
+                    "Subject: Cheese {}\r\n",
+                    "Message-ID: <cheese-{}@example.org>\r\n",
+                    "\r\n",
+                    "Ripened for {} days.\r\n"
+                ),
+                num, num, num
+            ),
+        )
+        .await;
+    }
+    test.wait_for_tasks().await;
+
+    imap.send("SELECT \"Mozzarella di Bufala\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let object_id = fetch_object_id(imap, NUM_MESSAGES).await;
+
+    let metrics = [
+        MetricType::StoreBlobRead,
+        MetricType::StoreBlobWrite,
+        MetricType::MessageIngestSearchIndex,
+    ];
+    let counters = metrics.map(Collector::read_metric);
+
+    imap.send("MOVE 1:* \"Stracciatella\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("* OK [COPYUID")
+        .assert_contains(&format!("1:{NUM_MESSAGES}"))
+        .assert_count("EXPUNGE", NUM_MESSAGES);
+    imap.send("LIST \"\" % RETURN (STATUS (UIDNEXT MESSAGES))")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\"Mozzarella di Bufala\" (UIDNEXT 251 MESSAGES 0)")
+        .assert_contains("\"Stracciatella\" (UIDNEXT 251 MESSAGES 250)");
+
+    // Neither blobs nor search index entries were read or written
+    test.wait_for_tasks().await;
+    assert_eq!(metrics.map(Collector::read_metric), counters);
+
+    // Email and thread ids are preserved
+    imap.send("SELECT \"Stracciatella\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    assert_eq!(fetch_object_id(imap, NUM_MESSAGES).await, object_id);
+
+    imap.send("UNSELECT").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    for mailbox in ["Mozzarella di Bufala", "Stracciatella"] {
+        imap.send(&format!("DELETE \"{mailbox}\"")).await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    }
+}
+
+async fn fetch_object_id(imap: &mut ImapConnection, seqnum: usize) -> String {
+    imap.send(&format!("FETCH {seqnum} (OBJECTID)")).await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_iter()
+        .find_map(|line| {
+            line.split_once("OBJECTID (")
+                .map(|(_, value)| value.split_once(')').unwrap().0.to_string())
+        })
+        .expect("Missing OBJECTID")
 }
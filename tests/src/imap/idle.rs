@@ -7,10 +7,13 @@
 use crate::utils::smtp::SmtpConnection;
 
 use super::{AssertResult, ImapConnection, Type};
+use ahash::AHashSet;
 use imap_proto::ResponseType;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 const SLEEP: Duration = Duration::from_millis(200);
+const BULK_MESSAGES: usize = 25;
+const MAX_NOTIFY_LATENCY: Duration = Duration::from_secs(3);
 
 pub async fn test(
     imap: &mut ImapConnection,
@@ -197,4 +200,69 @@ pub async fn test(
 
     imap_check.send("NOOP").await;
     imap_check.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Bulk flag changes should be coalesced and delivered promptly
+    imap.send("CREATE Pecorino").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    for _ in 0..BULK_MESSAGES {
+        imap.append("Pecorino", message).await;
+    }
+    imap.send("SELECT Pecorino").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap_check.send("SELECT Pecorino").await;
+    imap_check.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap_check.send("IDLE").await;
+    imap_check
+        .assert_read(Type::Continuation, ResponseType::Ok)
+        .await;
+
+    let start = Instant::now();
+    imap.send("STORE 1:* +FLAGS (\\Seen)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("STORE 1:* +FLAGS (\\Flagged)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    if is_cluster_test {
+        tokio::time::sleep(SLEEP).await;
+    }
+    let mut uids = AHashSet::new();
+    while uids.len() < BULK_MESSAGES {
+        let line = imap_check
+            .assert_read(Type::Status, ResponseType::Ok)
+            .await
+            .pop()
+            .unwrap();
+        if line.contains(" FETCH ") {
+            assert!(
+                line.contains("\\Seen") && line.contains("\\Flagged"),
+                "unexpected FETCH response: {line}"
+            );
+            let uid = line
+                .rsplit_once("UID ")
+                .and_then(|(_, uid)| uid.trim_end_matches(')').parse::<u32>().ok())
+                .unwrap();
+            assert!(uids.insert(uid), "duplicate FETCH response for UID {uid}");
+        }
+    }
+    assert!(
+        start.elapsed() < MAX_NOTIFY_LATENCY,
+        "IDLE notifications took {:?}",
+        start.elapsed()
+    );
+
+    // No further FETCH responses should follow
+    imap_check.send_raw("DONE").await;
+    let lines = imap_check.assert_read(Type::Tagged, ResponseType::Ok).await;
+    assert!(
+        !lines.iter().any(|line| line.contains(" FETCH ")),
+        "unexpected FETCH responses: {lines:?}"
+    );
+
+    imap_check.send("UNSELECT").await;
+    imap_check.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("UNSELECT").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("DELETE Pecorino").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap_check.send("NOOP").await;
+    imap_check.assert_read(Type::Tagged, ResponseType::Ok).await;
 }
@@ -0,0 +1,138 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{AssertResult, ImapConnection, Type};
+use crate::utils::{account::Account, server::TestServer};
+use imap_proto::ResponseType;
+use serde_json::{Value, json};
+
+pub async fn test(test: &TestServer) {
+    println!("Running IMAP/JMAP keyword mapping tests...");
+
+    let admin = test.account("admin@example.com");
+    let account = admin
+        .create_user_account(
+            "keywords@example.com",
+            "keywords secret + extra safety",
+            "Keywords Test",
+            &[],
+            vec![],
+        )
+        .await;
+
+    let mut imap = ImapConnection::connect(b"_k ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(account.name(), account.secret()).await;
+    for subject in ["first", "second"] {
+        imap.append(
+            "INBOX",
+            &format!("From: keywords@example.com\r\nSubject: {subject}\r\n\r\ntest\r\n"),
+        )
+        .await;
+    }
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("2 EXISTS");
+    let email_id = fetch_email_id(&mut imap, 1).await;
+
+    // Flags set over IMAP are lowercased and escaped in JMAP
+    imap.send("UID STORE 1 +FLAGS ($MyFlag Tps~20Report caf~c3~a9 $Phishing)")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let keywords = jmap_keywords(&account, &email_id).await;
+    for keyword in ["$myflag", "tps~20report", "caf~c3~a9", "$phishing"] {
+        assert_eq!(keywords[keyword], true, "{keyword}: {keywords}");
+    }
+
+    // A JMAP read-modify-write keeps the IMAP spelling
+    let mut keywords = keywords;
+    keywords["label~28work~29"] = true.into();
+    account
+        .jmap_update(
+            "Email",
+            [(&email_id, json!({ "keywords": keywords }))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(&email_id);
+    imap.send("UID FETCH 1 (FLAGS)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("$MyFlag")
+        .assert_contains("Tps~20Report")
+        .assert_contains("caf~c3~a9")
+        .assert_contains("$Phishing")
+        .assert_contains("label~28work~29");
+
+    // Keywords set over JMAP round-trip through IMAP
+    imap.send("UID STORE 1 -FLAGS (label~28work~29 $Phishing)")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let keywords = jmap_keywords(&account, &email_id).await;
+    assert!(keywords.get("label~28work~29").is_none(), "{keywords}");
+    assert!(keywords.get("$phishing").is_none(), "{keywords}");
+    imap.send("UID STORE 1 +FLAGS (label~28work~29 $phishing)")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let keywords = jmap_keywords(&account, &email_id).await;
+    assert_eq!(keywords["label~28work~29"], true, "{keywords}");
+    assert_eq!(keywords["$phishing"], true, "{keywords}");
+
+    // Flags that only differ in case are stored once
+    imap.send("UID STORE 1 +FLAGS ($myflag TPS~20REPORT)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("UID STORE 2 +FLAGS ($MYFLAG)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("UID FETCH 1 (FLAGS)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("$MyFlag")
+        .assert_contains("Tps~20Report")
+        .assert_count("$myflag", 0)
+        .assert_count("TPS~20REPORT", 0);
+    imap.send("UID SEARCH KEYWORD $myFlag").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("SEARCH 1 2");
+    assert_eq!(
+        account
+            .jmap_query(
+                "Email",
+                [("hasKeyword", "$myflag")],
+                Vec::<String>::new(),
+                Vec::<(String, Value)>::new(),
+            )
+            .await
+            .ids()
+            .count(),
+        2
+    );
+
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
+
+async fn fetch_email_id(imap: &mut ImapConnection, uid: u32) -> String {
+    imap.send(&format!("UID FETCH {uid} (OBJECTID)")).await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_iter()
+        .find_map(|line| {
+            line.split_once("EMAILID ")
+                .and_then(|(_, value)| value.split([' ', ')']).next())
+                .map(|id| id.to_string())
+        })
+        .expect("Missing EMAILID")
+}
+
+async fn jmap_keywords(account: &Account, email_id: &str) -> Value {
+    account
+        .jmap_get("Email", ["keywords"], [email_id])
+        .await
+        .list()[0]["keywords"]
+        .clone()
+}
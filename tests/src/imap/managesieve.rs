@@ -16,7 +16,11 @@ pub async fn test(test: &TestServer) {
     sieve
         .assert_read(ResponseType::Ok)
         .await
-        .assert_contains("IMPLEMENTATION");
+        .assert_contains("IMPLEMENTATION")
+        .assert_contains("duplicate")
+        .assert_contains("ihave")
+        .assert_contains("environment")
+        .assert_contains("mailboxid");
 
     // Authenticate
     let account = test.account("jdoe@example.com");
@@ -28,6 +32,19 @@ pub async fn test(test: &TestServer) {
     sieve.send("CHECKSCRIPT \"keep :invalidtag;\"").await;
     sieve.assert_read(ResponseType::No).await;
 
+    // Unknown extensions are allowed inside ihave blocks
+    sieve
+        .send_literal(
+            "CHECKSCRIPT ",
+            concat!(
+                "require \"ihave\";\r\n",
+                "if ihave \"vnd.dovecot.unknown\" { unknown_command :tag \"value\"; }\r\n",
+                "keep;\r\n"
+            ),
+        )
+        .await;
+    sieve.assert_read(ResponseType::Ok).await;
+
     // PutScript
     sieve
         .send_literal("PUTSCRIPT \"simple script\" ", "if true { keep; }\r\n")
@@ -0,0 +1,48 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{ImapConnection, Type};
+use imap_proto::ResponseType;
+use trc::{Collector, MetricType};
+
+pub async fn test(imap: &mut ImapConnection) {
+    println!("Running IMAP metrics tests...");
+
+    let fetch_ok = command_count("FETCH", "OK");
+    let select_no = command_count("SELECT", "NO");
+    let fetch_bytes = Collector::read_metric(MetricType::ImapFetchBytes);
+    let append_bytes = Collector::read_metric(MetricType::ImapAppendBytes);
+
+    // Successful commands
+    imap.append(
+        "INBOX",
+        "From: john@example.org\r\nSubject: metrics\r\n\r\ntest\r\n",
+    )
+    .await;
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("FETCH 1 (BODY.PEEK[])").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+
+    // Failed commands
+    imap.send("SELECT \"Does not exist\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    assert_eq!(command_count("FETCH", "OK"), fetch_ok + 1);
+    assert_eq!(command_count("SELECT", "NO"), select_no + 1);
+    assert!(Collector::read_metric(MetricType::ImapFetchBytes) > fetch_bytes);
+    assert!(Collector::read_metric(MetricType::ImapAppendBytes) > append_bytes);
+}
+
+fn command_count(command: &str, outcome: &str) -> u64 {
+    Collector::collect_labeled_counters()
+        .find(|counter| {
+            counter.id() == MetricType::ImapCommandCount
+                && counter.labels() == [("command", command), ("outcome", outcome)]
+        })
+        .map(|counter| counter.value())
+        .unwrap_or_default()
+}
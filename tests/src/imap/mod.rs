@@ -9,17 +9,22 @@ pub mod antispam;
 pub mod append;
 pub mod basic;
 pub mod body_structure;
+pub mod compress;
 pub mod condstore;
 pub mod copy_move;
 pub mod fetch;
 pub mod idle;
+pub mod keywords;
 pub mod mailbox;
 pub mod managesieve;
+pub mod metrics;
 pub mod objectid;
 pub mod pop;
+pub mod quota;
 pub mod search;
 pub mod store;
 pub mod thread;
+pub mod unauthenticate;
 
 use crate::utils::{
     imap::{AssertResult, ImapConnection, Type},
@@ -252,11 +257,16 @@ pub async fn imap_tests() {
     fetch::test(&mut imap, &mut imap_check).await;
     objectid::test(&test).await;
     store::test(&mut imap, &mut imap_check, &test).await;
-    copy_move::test(&mut imap, &mut imap_check).await;
+    copy_move::test(&mut imap, &mut imap_check, &test).await;
     thread::test(&mut imap, &mut imap_check, &test).await;
     idle::test(&mut imap, &mut imap_check, false).await;
     condstore::test(&mut imap, &mut imap_check).await;
     acl::test(&mut imap, &mut imap_check, &test).await;
+    metrics::test(&mut imap).await;
+    unauthenticate::test(&test).await;
+    compress::test(&test).await;
+    quota::test(&test).await;
+    keywords::test(&test).await;
 
     // Logout
     for imap in [&mut imap, &mut imap_check] {
@@ -10,6 +10,9 @@ use crate::utils::{
     server::TestServer,
     smtp::SmtpConnection,
 };
+use std::sync::atomic::Ordering;
+use store::dispatch::timing::STORE_READ_COUNT;
+use trc::{Collector, MetricType};
 
 pub async fn test(test: &TestServer) {
     println!("Running POP3 tests...");
@@ -135,6 +138,64 @@ pub async fn test(test: &TestServer) {
         .assert_contains("Subject: TPS Report 2")
         .assert_not_contains("I'm going to need those TPS 2 reports ASAP.");
 
+    // TOP 0 returns only the headers
+    pop3.send("TOP 2 0").await;
+    pop3.assert_read(ResponseType::Multiline)
+        .await
+        .assert_contains("+OK 203 octets")
+        .assert_contains("Subject: TPS Report 1")
+        .assert_not_contains("I'm going to need those TPS 1 reports ASAP.");
+
+    // TOP n m within the header block returns the first m lines of the message
+    pop3.send("RETR 2").await;
+    let message = pop3.assert_read(ResponseType::Multiline).await;
+    pop3.send("TOP 2 3").await;
+    let top = pop3
+        .assert_read(ResponseType::Multiline)
+        .await
+        .assert_contains("+OK 203 octets")
+        .assert_not_contains("I'm going to need those TPS 1 reports ASAP.");
+    assert_eq!(top.len(), 5, "{top:?}");
+    assert_eq!(top[1..4], message[1..4]);
+
+    // UIDL and TOP sweeps are served from the session cache
+    test.wait_for_tasks().await;
+    let blob_reads = Collector::read_metric(MetricType::StoreBlobRead);
+    let mut store_reads = Vec::with_capacity(2);
+    for _ in 0..2 {
+        let reads = STORE_READ_COUNT.load(Ordering::Relaxed);
+        pop3.send("UIDL\r\nTOP 1 0\r\nTOP 2 0\r\nTOP 3 0").await;
+        pop3.assert_read(ResponseType::Multiline)
+            .await
+            .assert_contains("+OK 3 messages");
+        for i in 0..3 {
+            pop3.assert_read(ResponseType::Multiline)
+                .await
+                .assert_contains(&format!("Subject: TPS Report {i}"))
+                .assert_not_contains("So, if you could do that, that'd be great.");
+        }
+        store_reads.push(STORE_READ_COUNT.load(Ordering::Relaxed) - reads);
+    }
+    assert_eq!(
+        store_reads[1], 0,
+        "repeated UIDL and TOP sweep should not read the store: {store_reads:?}"
+    );
+    assert_eq!(
+        Collector::read_metric(MetricType::StoreBlobRead) - blob_reads,
+        0.0,
+        "TOP sweep should not read message blobs"
+    );
+
+    // Requests that need the message body still read the blob
+    pop3.send("TOP 2 100").await;
+    pop3.assert_read(ResponseType::Multiline)
+        .await
+        .assert_contains("I'm going to need those TPS 1 reports ASAP.");
+    assert_eq!(
+        Collector::read_metric(MetricType::StoreBlobRead) - blob_reads,
+        1.0
+    );
+
     // DELE + RSET + QUIT (should not delete messages)
     pop3.send("DELE 1").await;
     pop3.assert_read(ResponseType::Ok).await;
@@ -0,0 +1,119 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{AssertResult, ImapConnection, Type};
+use crate::utils::server::TestServer;
+use imap_proto::ResponseType;
+
+pub async fn test(test: &TestServer) {
+    println!("Running QUOTA tests...");
+
+    let admin = test.account("admin@example.com");
+    let account = admin
+        .create_user_account(
+            "quota@example.com",
+            "quota secret + extra safety",
+            "Quota Test",
+            &[],
+            vec![],
+        )
+        .await;
+    let root = format!("#{}", account.id().document_id());
+
+    let mut imap = ImapConnection::connect(b"_q ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(account.name(), account.secret()).await;
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("QUOTA=RES-STORAGE")
+        .assert_contains("QUOTA=RES-MESSAGE")
+        .assert_contains("QUOTA=SET");
+
+    // Accounts without limits have no quota resources
+    imap.send("GETQUOTAROOT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains(&format!("* QUOTAROOT \"INBOX\" \"{root}\""))
+        .assert_contains(&format!("* QUOTA \"{root}\" ()"));
+
+    // Users cannot change their own quota
+    imap.send(&format!("SETQUOTA \"{root}\" (STORAGE 1024)"))
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("NOPERM");
+
+    // Administrators can set the storage and message limits
+    let mut imap_admin = ImapConnection::connect(b"_a ").await;
+    imap_admin
+        .assert_read(Type::Untagged, ResponseType::Ok)
+        .await;
+    imap_admin.authenticate(admin.name(), admin.secret()).await;
+    imap_admin
+        .send(&format!("SETQUOTA \"{root}\" (STORAGE 1024 MESSAGE 10)"))
+        .await;
+    imap_admin
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains(&format!("* QUOTA \"{root}\" (STORAGE 0 1024 MESSAGE 0 10)"));
+
+    // Usage is reported after appending messages
+    let message = format!(
+        "From: quota@example.com\r\nSubject: quota test\r\n\r\n{}",
+        "0123456789abcdef".repeat(640)
+    );
+    for _ in 0..2 {
+        imap.append("INBOX", &message).await;
+    }
+    for (imap, command) in [
+        (&mut imap, "GETQUOTAROOT INBOX".to_string()),
+        (&mut imap_admin, format!("GETQUOTA \"{root}\"")),
+    ] {
+        imap.send(&command).await;
+        let lines = imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+        let quota = lines
+            .iter()
+            .find(|line| line.starts_with(&format!("* QUOTA \"{root}\"")))
+            .unwrap_or_else(|| panic!("QUOTA response not found: {lines:?}"));
+        assert!(quota.ends_with(" 1024 MESSAGE 2 10)"), "{quota}");
+        let used_storage = quota
+            .split_once("(STORAGE ")
+            .and_then(|(_, usage)| usage.split_once(' '))
+            .and_then(|(used, _)| used.parse::<u64>().ok())
+            .unwrap();
+        assert!(used_storage >= 20, "{quota}");
+    }
+
+    // Unsupported resources are rejected
+    imap_admin
+        .send(&format!("SETQUOTA \"{root}\" (MAILBOX 10)"))
+        .await;
+    imap_admin.assert_read(Type::Tagged, ResponseType::No).await;
+
+    // Resources not included are reset to unlimited
+    imap_admin
+        .send(&format!("SETQUOTA \"{root}\" (MESSAGE 0)"))
+        .await;
+    imap_admin
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains(&format!("* QUOTA \"{root}\" ()"));
+    imap.send(&format!("GETQUOTA \"{root}\"")).await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains(&format!("* QUOTA \"{root}\" ()"));
+
+    // Quota roots of other accounts are not accessible
+    imap.send(&format!("GETQUOTA \"#{}\"", admin.id().document_id()))
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    for imap in [&mut imap, &mut imap_admin] {
+        imap.send("LOGOUT").await;
+        imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+    }
+}
@@ -8,6 +8,7 @@ use crate::utils::server::TestServer;
 
 use super::{AssertResult, ImapConnection, Type};
 use imap_proto::ResponseType;
+use trc::{Collector, MetricType};
 
 pub async fn test(imap: &mut ImapConnection, imap_check: &mut ImapConnection, test: &TestServer) {
     println!("Running SEARCH tests...");
@@ -136,4 +137,71 @@ pub async fn test(imap: &mut ImapConnection, imap_check: &mut ImapConnection, te
         } else {
             "COUNT 10 ALL 9,3,7:8,2,6,4:5,1,10"
         }); //6,4:5,1,10,9,3,7:8,2");
+
+    // Repeated searches with equivalent criteria are served from the cache
+    let iterations = Collector::read_metric(MetricType::StoreDataIterate);
+    imap.send("UID SEARCH TEXT coffee FROM vandelay SUBJECT exporting SENTON 20-Nov-2021")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH 10");
+    let miss_iterations = Collector::read_metric(MetricType::StoreDataIterate) - iterations;
+
+    let iterations = Collector::read_metric(MetricType::StoreDataIterate);
+    imap.send("UID SEARCH SENTON 20-Nov-2021 SUBJECT Exporting FROM Vandelay TEXT COFFEE")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH 10");
+    let hit_iterations = Collector::read_metric(MetricType::StoreDataIterate) - iterations;
+    assert!(
+        hit_iterations < miss_iterations,
+        "Cached search iterated the store {hit_iterations} times, uncached {miss_iterations} times"
+    );
+
+    // Aliases of the same charset share the cached entry
+    for charset in ["UTF-8", "utf8"] {
+        let iterations = Collector::read_metric(MetricType::StoreDataIterate);
+        imap.send(&format!(
+            "UID SEARCH CHARSET {charset} SUBJECT exporting TEXT coffee FROM vandelay SENTON 20-Nov-2021"
+        ))
+        .await;
+        imap.assert_read(Type::Tagged, ResponseType::Ok)
+            .await
+            .assert_equals("* SEARCH 10");
+        assert!(
+            Collector::read_metric(MetricType::StoreDataIterate) - iterations < miss_iterations,
+            "Search with charset {charset} was not cached"
+        );
+    }
+
+    // ESEARCH and sequence number results share the cached entry
+    let iterations = Collector::read_metric(MetricType::StoreDataIterate);
+    imap.send(
+        "SEARCH RETURN (COUNT ALL) FROM vandelay TEXT coffee SENTON 20-Nov-2021 SUBJECT exporting",
+    )
+    .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("COUNT 1 ALL 10");
+    assert!(Collector::read_metric(MetricType::StoreDataIterate) - iterations < miss_iterations);
+
+    // Flag changes invalidate cached results
+    imap.send("UID SEARCH KEYWORD $Cached FROM vandelay SUBJECT exporting SENTON 20-Nov-2021")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH");
+    imap.send_ok("UID STORE 10 +FLAGS ($Cached)").await;
+    imap.send("UID SEARCH KEYWORD $Cached FROM vandelay SUBJECT exporting SENTON 20-Nov-2021")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH 10");
+    imap.send_ok("UID STORE 10 -FLAGS ($Cached)").await;
+    imap.send("UID SEARCH KEYWORD $Cached FROM vandelay SUBJECT exporting SENTON 20-Nov-2021")
+        .await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH");
 }
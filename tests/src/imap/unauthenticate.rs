@@ -0,0 +1,89 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use super::{AssertResult, ImapConnection, Type};
+use crate::utils::server::TestServer;
+use imap_proto::ResponseType;
+
+pub async fn test(test: &TestServer) {
+    println!("Running UNAUTHENTICATE tests...");
+
+    let john = test.account("jdoe@example.com");
+    let jane = test.account("jane.smith@example.com");
+    let mut imap = ImapConnection::connect(b"_u ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+
+    // Not available before authentication
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("AUTH=PLAIN")
+        .assert_not_contains("UNAUTHENTICATE");
+    imap.send("UNAUTHENTICATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+
+    // Authenticate as John, enable extensions and select a private mailbox
+    imap.authenticate(john.name(), john.secret()).await;
+    imap.send("CAPABILITY").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("UNAUTHENTICATE");
+    imap.send_ok("ENABLE OBJECTID+").await;
+    imap.send_ok("CREATE \"Unauth Private\"").await;
+    imap.append(
+        "Unauth Private",
+        "From: john@example.com\r\nSubject: unauthenticate secret\r\n\r\nhello",
+    )
+    .await;
+    imap.send("SELECT \"Unauth Private\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("MAILBOXID ");
+
+    // Return to the not authenticated state with the pre-auth capabilities
+    imap.send("UNAUTHENTICATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("[CAPABILITY ")
+        .assert_contains("AUTH=PLAIN")
+        .assert_not_contains("IDLE")
+        .assert_not_contains("QRESYNC");
+
+    // Selected mailbox and account state are gone
+    imap.send("UID FETCH 1 (FLAGS)").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+    imap.send("UNAUTHENTICATE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Bad).await;
+
+    // Authenticate as Jane on the same connection
+    imap.authenticate(jane.name(), jane.secret()).await;
+    imap.send("LIST \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_not_contains("Unauth Private");
+    imap.send("SELECT \"Unauth Private\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    // Extensions enabled by John are no longer active
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_not_contains("MAILBOXID")
+        .assert_not_contains("OBJECTID");
+    imap.send("SEARCH SUBJECT \"unauthenticate secret\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_equals("* SEARCH");
+
+    // Clean up
+    imap.send_ok("UNAUTHENTICATE").await;
+    imap.authenticate(john.name(), john.secret()).await;
+    imap.send_ok("DELETE \"Unauth Private\"").await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+}
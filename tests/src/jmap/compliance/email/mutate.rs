@@ -155,6 +155,11 @@ pub async fn run(ctx: &CompCtx<'_>) {
         query_changes_response_structure(ctx),
     )
     .await;
+    ctx.run(
+        "email/query-changes-mailbox-moves",
+        query_changes_mailbox_moves(ctx),
+    )
+    .await;
 }
 
 async fn destroy_email(ctx: &CompCtx<'_>, id: &str) {
@@ -1669,3 +1674,144 @@ async fn query_changes_response_structure(ctx: &CompCtx<'_>) -> TestOutcome {
     check(r["removed"].is_array(), "removed must be array")?;
     check(r["added"].is_array(), "added must be array")
 }
+
+async fn email_query_ids(ctx: &CompCtx<'_>, filter: Value) -> (Vec<String>, String) {
+    let resp = ctx
+        .primary
+        .jmap_method_call(
+            "Email/query",
+            json!({
+                "accountId": ctx.account_id(),
+                "filter": filter,
+                "sort": [{ "property": "receivedAt", "isAscending": false }]
+            }),
+        )
+        .await;
+    let r = resp.method_response();
+    (
+        r["ids"]
+            .as_array()
+            .map(|a| {
+                a.iter()
+                    .filter_map(|x| x.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        r["queryState"].as_str().unwrap_or("").to_string(),
+    )
+}
+
+fn apply_query_changes(mut ids: Vec<String>, changes: &Value) -> Vec<String> {
+    let removed = changes["removed"].as_array().cloned().unwrap_or_default();
+    ids.retain(|id| !removed.iter().any(|x| x.as_str() == Some(id)));
+
+    let mut added = changes["added"]
+        .as_array()
+        .map(|a| {
+            a.iter()
+                .filter_map(|x| {
+                    Some((x["index"].as_u64()? as usize, x["id"].as_str()?.to_string()))
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    added.sort_unstable();
+    for (index, id) in added {
+        ids.insert(index.min(ids.len()), id);
+    }
+    ids
+}
+
+async fn query_changes_mailbox_moves(ctx: &CompCtx<'_>) -> TestOutcome {
+    let filter = json!({ "inMailbox": ctx.mailbox("folderA") });
+
+    let create_resp = ctx
+        .primary
+        .jmap_create(
+            "Email",
+            [
+                plain_create(ctx, "Move test 1"),
+                plain_create(ctx, "Move test 2"),
+                plain_create(ctx, "Move test 3"),
+            ],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    let ids = (0..3)
+        .map(|i| create_resp.created(i).id().to_string())
+        .collect::<Vec<_>>();
+    let move_to = |mailbox: &str| json!({ "mailboxIds": { mailbox: true } });
+
+    ctx.primary
+        .jmap_update(
+            "Email",
+            [
+                (ids[0].as_str(), move_to(ctx.mailbox("folderA"))),
+                (ids[1].as_str(), move_to(ctx.mailbox("folderA"))),
+            ],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    let (old_ids, old_query_state) = email_query_ids(ctx, filter.clone()).await;
+
+    // Move one email out, one email in and update one that stays in the mailbox
+    ctx.primary
+        .jmap_update(
+            "Email",
+            [
+                (ids[0].as_str(), move_to(ctx.role("inbox"))),
+                (ids[1].as_str(), json!({ "keywords/$seen": true })),
+                (ids[2].as_str(), move_to(ctx.mailbox("folderA"))),
+            ],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+
+    let changes = ctx
+        .primary
+        .jmap_method_call(
+            "Email/queryChanges",
+            json!({
+                "accountId": ctx.account_id(),
+                "filter": filter,
+                "sort": [{ "property": "receivedAt", "isAscending": false }],
+                "sinceQueryState": old_query_state
+            }),
+        )
+        .await;
+    let (new_ids, _) = email_query_ids(ctx, filter.clone()).await;
+    let outcome = check(
+        !new_ids.contains(&ids[0]) && new_ids.contains(&ids[2]),
+        "moved emails must leave and join the results",
+    )
+    .and(check_eq(
+        apply_query_changes(old_ids, changes.method_response()),
+        new_ids,
+        "reconstructed ids must match Email/query",
+    ));
+
+    // Partial changes must be reported as tooManyChanges
+    let too_many = ctx
+        .primary
+        .jmap_method_call(
+            "Email/queryChanges",
+            json!({
+                "accountId": ctx.account_id(),
+                "filter": filter,
+                "sort": [{ "property": "receivedAt", "isAscending": false }],
+                "sinceQueryState": old_query_state,
+                "maxChanges": 1
+            }),
+        )
+        .await;
+    let outcome = outcome.and(check_eq(
+        too_many.error_type_at(0).unwrap_or(""),
+        "tooManyChanges",
+        "maxChanges overflow must return tooManyChanges",
+    ));
+
+    for id in &ids {
+        destroy_email(ctx, id).await;
+    }
+    outcome
+}
@@ -423,7 +423,294 @@ pub async fn test(test: &TestServer) {
         );
     }
 
+    // Blob/lookup should find every email and script that references a blob,
+    // including emails that include an uploaded blob as an attachment
+    let inbox_id = Id::from(INBOX_ID).to_string();
+    let response = account
+        .jmap_method_call(
+            "Blob/upload",
+            json!({
+             "accountId": account.id_string(),
+             "create": {
+              "message": {
+               "data" : [
+               {
+                "data:asText": concat!(
+                    "From: jdoe@example.com\r\n",
+                    "Subject: TPS Report\r\n",
+                    "Content-Type: multipart/mixed; boundary=\"tps\"\r\n",
+                    "\r\n",
+                    "--tps\r\n",
+                    "Content-Type: text/plain\r\n",
+                    "\r\n",
+                    "See attachment.\r\n",
+                    "--tps\r\n",
+                    "Content-Type: application/octet-stream\r\n",
+                    "Content-Disposition: attachment; filename=\"tps.txt\"\r\n",
+                    "\r\n",
+                    "Quarterly TPS report, please find the cover sheet attached.\r\n",
+                    "--tps--\r\n"
+                )
+               }
+              ],
+              "type": "message/rfc822"
+              },
+              "script": {
+               "data" : [
+               {
+                "data:asText": "require \"fileinto\";\r\nfileinto \"Reports\";\r\n"
+               }
+              ],
+              "type": "application/sieve"
+              },
+              "attachment": {
+               "data" : [
+               {
+                "data:asText": "TPS report cover sheet, version 2."
+               }
+              ],
+              "type": "application/octet-stream"
+              },
+              "unreferenced": {
+               "data" : [
+               {
+                "data:asText": "This blob is not referenced by any object."
+               }
+              ],
+              "type": "application/octet-stream"
+              }
+             }
+            }),
+        )
+        .await;
+    let [
+        message_blob_id,
+        script_upload_id,
+        attachment_blob_id,
+        unreferenced_blob_id,
+    ] = ["message", "script", "attachment", "unreferenced"].map(|name| {
+        response
+            .pointer(&format!("/methodResponses/0/1/created/{name}/id"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| panic!("Response: {response:?}"))
+            .to_string()
+    });
+    let response = account
+        .jmap_method_call(
+            "Email/import",
+            json!({
+              "accountId": account.id_string(),
+              "emails": {
+                "first": { "blobId": &message_blob_id, "mailboxIds": { &inbox_id: true } },
+                "second": { "blobId": &message_blob_id, "mailboxIds": { &inbox_id: true } }
+              }
+            }),
+        )
+        .await;
+    let mut email_ids = ["first", "second"].map(|name| {
+        response
+            .pointer(&format!("/methodResponses/0/1/created/{name}/id"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| panic!("Response: {response:?}"))
+            .to_string()
+    });
+    email_ids.sort();
+    let mut attached_email_ids = Vec::new();
+    for subject in ["TPS Report", "Fwd: TPS Report"] {
+        let response = account
+            .jmap_method_call(
+                "Email/set",
+                json!({
+                  "accountId": account.id_string(),
+                  "create": {
+                    "email": {
+                      "mailboxIds": { &inbox_id: true },
+                      "subject": subject,
+                      "from": [{ "email": "jdoe@example.com" }],
+                      "textBody": [{ "partId": "text", "type": "text/plain" }],
+                      "bodyValues": { "text": { "value": "See attachment." } },
+                      "attachments": [{
+                        "blobId": &attachment_blob_id,
+                        "type": "application/octet-stream",
+                        "name": "tps.txt"
+                      }]
+                    }
+                  }
+                }),
+            )
+            .await;
+        attached_email_ids.push(
+            response
+                .pointer("/methodResponses/0/1/created/email/id")
+                .and_then(|v| v.as_str())
+                .unwrap_or_else(|| panic!("Response: {response:?}"))
+                .to_string(),
+        );
+    }
+    attached_email_ids.sort();
+    let email_attachment_blob_id = account
+        .jmap_method_call(
+            "Email/get",
+            json!({
+              "accountId": account.id_string(),
+              "ids": [&email_ids[0]],
+              "properties": ["attachments"]
+            }),
+        )
+        .await
+        .pointer("/methodResponses/0/1/list/0/attachments/0/blobId")
+        .and_then(|v| v.as_str())
+        .unwrap()
+        .to_string();
+    let response = account
+        .jmap_method_call(
+            "SieveScript/set",
+            json!({
+              "accountId": account.id_string(),
+              "create": {
+                "script": { "name": "lookup", "blobId": &script_upload_id }
+              }
+            }),
+        )
+        .await;
+    let script_id = response
+        .pointer("/methodResponses/0/1/created/script/id")
+        .and_then(|v| v.as_str())
+        .unwrap_or_else(|| panic!("Response: {response:?}"))
+        .to_string();
+    let script_blob_id = response
+        .pointer("/methodResponses/0/1/created/script/blobId")
+        .and_then(|v| v.as_str())
+        .unwrap_or_else(|| panic!("Response: {response:?}"))
+        .to_string();
+
+    let response = account
+        .jmap_method_call(
+            "Blob/lookup",
+            json!({
+              "accountId": account.id_string(),
+              "typeNames": ["Email", "Mailbox", "SieveScript"],
+              "ids": [
+                &message_blob_id,
+                &email_attachment_blob_id,
+                &script_blob_id,
+                &unreferenced_blob_id,
+                &attachment_blob_id
+              ]
+            }),
+        )
+        .await;
+    for blob_num in 0..2 {
+        let mut matched_ids = response
+            .pointer(&format!(
+                "/methodResponses/0/1/list/{blob_num}/matchedIds/Email"
+            ))
+            .and_then(|v| v.as_array())
+            .unwrap_or_else(|| panic!("Response: {response:?}"))
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        matched_ids.sort();
+        assert_eq!(matched_ids, email_ids, "Response: {response:?}");
+        assert_eq!(
+            response.pointer(&format!(
+                "/methodResponses/0/1/list/{blob_num}/matchedIds/Mailbox"
+            )),
+            Some(&json!([&inbox_id])),
+            "Response: {response:?}"
+        );
+        assert_eq!(
+            response.pointer(&format!(
+                "/methodResponses/0/1/list/{blob_num}/matchedIds/SieveScript"
+            )),
+            Some(&json!([])),
+            "Response: {response:?}"
+        );
+    }
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/list/2/matchedIds"),
+        Some(&json!({"Email": [], "Mailbox": [], "SieveScript": [&script_id]})),
+        "Response: {response:?}"
+    );
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/list/3/matchedIds"),
+        Some(&json!({"Email": [], "Mailbox": [], "SieveScript": []})),
+        "Response: {response:?}"
+    );
+    let mut matched_ids = response
+        .pointer("/methodResponses/0/1/list/4/matchedIds/Email")
+        .and_then(|v| v.as_array())
+        .unwrap_or_else(|| panic!("Response: {response:?}"))
+        .iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    matched_ids.sort();
+    assert_eq!(matched_ids, attached_email_ids, "Response: {response:?}");
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/list/4/matchedIds/Mailbox"),
+        Some(&json!([&inbox_id])),
+        "Response: {response:?}"
+    );
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/notFound"),
+        Some(&json!([])),
+        "Response: {response:?}"
+    );
+
+    // Unknown blobs are reported as not found
+    test.blob_expire_all().await;
+    let response = account
+        .jmap_method_call(
+            "Blob/lookup",
+            json!({
+              "accountId": account.id_string(),
+              "typeNames": ["Email"],
+              "ids": [&unreferenced_blob_id, &email_attachment_blob_id]
+            }),
+        )
+        .await;
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/notFound"),
+        Some(&json!([&unreferenced_blob_id])),
+        "Response: {response:?}"
+    );
+    assert_eq!(
+        response.pointer("/methodResponses/0/1/list/0/id"),
+        Some(&json!(&email_attachment_blob_id)),
+        "Response: {response:?}"
+    );
+
+    // Unsupported type names are rejected
+    for type_name in ["CalendarEvent", "Foobar"] {
+        let response = account
+            .jmap_method_call(
+                "Blob/lookup",
+                json!({
+                  "accountId": account.id_string(),
+                  "typeNames": [type_name],
+                  "ids": [&message_blob_id]
+                }),
+            )
+            .await;
+        assert_eq!(
+            response
+                .pointer("/methodResponses/0/1/type")
+                .and_then(|v| v.as_str()),
+            Some("unknownDataType"),
+            "Response: {response:?}"
+        );
+    }
+
     // Remove test data
+    account
+        .jmap_method_call(
+            "SieveScript/set",
+            json!({
+              "accountId": account.id_string(),
+              "destroy": [&script_id]
+            }),
+        )
+        .await;
     test.destroy_all_mailboxes(account).await;
     test.assert_is_empty().await;
 }
@@ -7,4 +7,5 @@
 pub mod blob;
 pub mod event_source;
 pub mod push_subscription;
+pub mod session;
 pub mod websocket;
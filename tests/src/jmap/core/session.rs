@@ -0,0 +1,159 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::TestServer;
+use registry::{
+    schema::{
+        enums::Permission,
+        prelude::ObjectType,
+        structs::{Permissions, PermissionsList},
+    },
+    types::map::Map,
+};
+use serde_json::{Value, json};
+
+const MAIL: &str = "urn:ietf:params:jmap:mail";
+const SUBMISSION: &str = "urn:ietf:params:jmap:submission";
+const SIEVE: &str = "urn:ietf:params:jmap:sieve";
+const PRINCIPALS_OWNER: &str = "urn:ietf:params:jmap:principals:owner";
+
+pub async fn test(test: &TestServer) {
+    println!("Running session object tests...");
+    let admin = test.account("admin@example.com");
+    let john = test.account("jdoe@example.com");
+    let jane = test.account("jane.smith@example.com");
+    let jane_session = jane.jmap_session_object().await.into_inner();
+
+    // The principals owner capability is never advertised per account
+    assert!(
+        jane_session["accounts"][jane.id_string()]["accountCapabilities"]
+            .get(PRINCIPALS_OWNER)
+            .is_none(),
+        "{jane_session:?}"
+    );
+    assert!(
+        jane_session["primaryAccounts"]
+            .get(PRINCIPALS_OWNER)
+            .is_none(),
+        "{jane_session:?}"
+    );
+
+    // Changing a limit alone changes the session state
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                "maxMessageSize": 4096,
+            }),
+        )
+        .await;
+    let jane_resized_session = jane.jmap_session_object().await.into_inner();
+    assert_eq!(
+        max_attachment_size(&jane_resized_session, jane.id_string()),
+        4096
+    );
+    assert_ne!(jane_session["state"], jane_resized_session["state"]);
+    assert_eq!(
+        jane.jmap_get("Mailbox", ["id"], Vec::<&str>::new())
+            .await
+            .session_state(),
+        jane_resized_session["state"].as_str()
+    );
+
+    // Disable Sieve and submissions for Jane and lower her message size limit
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                "maxMessageSize": 2048,
+                "permissions": account_permissions(&[
+                    Permission::JmapSieveScriptGet,
+                    Permission::JmapEmailSubmissionCreate,
+                ]),
+            }),
+        )
+        .await;
+
+    let john_session = john.jmap_session_object().await.into_inner();
+    let jane_limited_session = jane.jmap_session_object().await.into_inner();
+    for capability in [SIEVE, SUBMISSION] {
+        assert!(
+            has_capability(&john_session, john.id_string(), capability),
+            "{capability} missing from {john_session:?}"
+        );
+        assert!(
+            !has_capability(&jane_limited_session, jane.id_string(), capability),
+            "{capability} advertised in {jane_limited_session:?}"
+        );
+    }
+    assert_eq!(
+        max_attachment_size(&jane_limited_session, jane.id_string()),
+        2048
+    );
+    assert_ne!(max_attachment_size(&john_session, john.id_string()), 2048);
+
+    // Clients are told to refetch the session object
+    assert_ne!(jane_session["state"], jane_limited_session["state"]);
+    assert_eq!(
+        jane.jmap_get("Mailbox", ["id"], Vec::<&str>::new())
+            .await
+            .session_state(),
+        jane_limited_session["state"].as_str()
+    );
+
+    // Restoring the permissions brings back the original session
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                "maxMessageSize": null,
+                "permissions": account_permissions(&[]),
+            }),
+        )
+        .await;
+    let jane_restored_session = jane.jmap_session_object().await.into_inner();
+    assert_eq!(jane_session["state"], jane_restored_session["state"]);
+    for capability in [SIEVE, SUBMISSION] {
+        assert!(
+            has_capability(&jane_restored_session, jane.id_string(), capability),
+            "{capability} missing from {jane_restored_session:?}"
+        );
+    }
+    assert_eq!(
+        max_attachment_size(&jane_restored_session, jane.id_string()),
+        max_attachment_size(&john_session, john.id_string())
+    );
+}
+
+fn account_permissions(disabled: &[Permission]) -> Permissions {
+    Permissions::Merge(PermissionsList {
+        enabled_permissions: Map::new(vec![
+            Permission::UnlimitedRequests,
+            Permission::UnlimitedUploads,
+        ]),
+        disabled_permissions: Map::new(disabled.to_vec()),
+    })
+}
+
+fn has_capability(session: &Value, account_id: &str, capability: &str) -> bool {
+    let is_session = session["capabilities"].get(capability).is_some();
+    let is_account = session["accounts"][account_id]["accountCapabilities"]
+        .get(capability)
+        .is_some();
+    let is_primary = session["primaryAccounts"].get(capability).is_some();
+    assert_eq!(is_session, is_account, "{session:?}");
+    assert_eq!(is_session, is_primary, "{session:?}");
+    is_session
+}
+
+fn max_attachment_size(session: &Value, account_id: &str) -> u64 {
+    session["accounts"][account_id]["accountCapabilities"][MAIL]["maxSizeAttachmentsPerEmail"]
+        .as_u64()
+        .unwrap_or_else(|| panic!("Missing mail capabilities in {session:?}"))
+}
@@ -55,6 +55,27 @@ pub async fn test(test: &TestServer) {
         .unwrap()
         .take_id();
 
+    // Slow requests do not delay faster requests sent after them
+    let mut request = client.build();
+    let set_request = request.set_mailbox();
+    for num in 0..100 {
+        set_request.create().name(format!("Concurrent {num}"));
+    }
+    let slow_request_id = request.send_ws().await.unwrap();
+    let mut request = client.build();
+    request.get_mailbox().ids([&mailbox_id]);
+    let fast_request_id = request.send_ws().await.unwrap();
+    let mut response_ids = Vec::new();
+    for _ in 0..2 {
+        match tokio::time::timeout(Duration::from_secs(5), stream_rx.recv()).await {
+            Ok(Some(WebSocketMessage::Response(response))) => {
+                response_ids.push(response.request_id().unwrap().to_string());
+            }
+            result => panic!("Expected response, got: {:?}", result),
+        }
+    }
+    assert_eq!(response_ids, [fast_request_id, slow_request_id]);
+
     // Enable push notifications
     client
         .enable_push_ws(None::<Vec<_>>, None::<&str>)
@@ -79,7 +100,11 @@ pub async fn test(test: &TestServer) {
     assert_state(&mut stream_rx, account.id_string(), &[DataType::Mailbox]).await;
     expect_nothing(&mut stream_rx).await;
 
-    // Disable push notifications
+    // Disable push notifications, the last push request sent takes effect
+    client
+        .enable_push_ws(None::<Vec<_>>, None::<&str>)
+        .await
+        .unwrap();
     client.disable_push_ws().await.unwrap();
 
     // No more changes should be received
@@ -0,0 +1,276 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer, smtp::SmtpConnection};
+use ::email::{mailbox::INBOX_ID, message::category::CategoryOverrides};
+use registry::{
+    schema::{prelude::ObjectType, structs::MailCategory},
+    types::map::Map,
+};
+use serde_json::{Value, json};
+use store::{
+    ValueKey,
+    write::{AlignedBytes, Archive},
+};
+use types::{collection::Collection, field::PrincipalField, id::Id};
+
+pub async fn test(test: &TestServer) {
+    println!("Running Email categorization tests...");
+    let admin = test.account("admin@example.com");
+    let category_id = admin
+        .registry_create_object(MailCategory {
+            name: "promotions".into(),
+            mailbox_name: Some("Promotions".into()),
+            match_tags: Map::new(vec!["HAS_LIST_ID".into(), "HAS_LIST_UNSUB".into()]),
+            match_keywords: Map::new(vec!["discount".into()]),
+            min_matches: 2,
+            ..Default::default()
+        })
+        .await;
+    let updates_id = admin
+        .registry_create_object(MailCategory {
+            name: "updates".into(),
+            min_sender_messages: Some(2),
+            min_matches: 1,
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+
+    // Categorization is enabled by the administrator or the directory
+    let account = test.account("jdoe@example.com");
+    let account_id = account.id().document_id();
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            account.id(),
+            json!({
+                "categorizeMail": true
+            }),
+        )
+        .await;
+    let inbox_id = Id::from(INBOX_ID).to_string();
+
+    // Newsletters are filed into the category folder, personal mail stays in the Inbox
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "news@shop.org",
+        &["jdoe@example.com"],
+        &newsletter("Weekly deals"),
+    )
+    .await;
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: bill@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Lunch tomorrow\r\n",
+            "\r\n",
+            "Are we still meeting for lunch? Ask for the discount."
+        ),
+    )
+    .await;
+
+    let newsletter_email = email_by_subject(account, "Weekly deals").await;
+    assert_eq!(
+        newsletter_email["keywords"],
+        json!({"$category:promotions": true}),
+        "{newsletter_email:?}"
+    );
+    let mailbox_ids = newsletter_email["mailboxIds"].as_object().unwrap();
+    assert_eq!(mailbox_ids.len(), 1, "{newsletter_email:?}");
+    let promotions_id = mailbox_ids.keys().next().unwrap().clone();
+    assert_ne!(promotions_id, inbox_id);
+    let mailbox = account
+        .jmap_get("Mailbox", ["name"], [&promotions_id])
+        .await;
+    assert_eq!(mailbox.list()[0]["name"], "Promotions");
+
+    let personal_email = email_by_subject(account, "Lunch tomorrow").await;
+    assert_eq!(
+        personal_email["mailboxIds"],
+        json!({&inbox_id: true}),
+        "{personal_email:?}"
+    );
+    assert_eq!(personal_email["keywords"], json!({}), "{personal_email:?}");
+
+    // Moving a newsletter back to the Inbox keeps future messages from the sender there
+    let newsletter_id = newsletter_email["id"].as_str().unwrap();
+    account
+        .jmap_update(
+            "Email",
+            [(newsletter_id, json!({"mailboxIds": {&inbox_id: true}}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(newsletter_id);
+    lmtp.ingest(
+        "news@shop.org",
+        &["jdoe@example.com"],
+        &newsletter("Monthly deals"),
+    )
+    .await;
+
+    let newsletter_email = email_by_subject(account, "Monthly deals").await;
+    assert_eq!(
+        newsletter_email["mailboxIds"],
+        json!({&inbox_id: true}),
+        "{newsletter_email:?}"
+    );
+    assert_eq!(
+        newsletter_email["keywords"],
+        json!({}),
+        "{newsletter_email:?}"
+    );
+
+    // The categories chosen for each sender are persisted
+    let overrides = category_overrides(test, account_id)
+        .await
+        .expect("Missing category overrides");
+    assert_eq!(overrides.senders.len(), 1, "{overrides:?}");
+    assert_eq!(overrides.senders[0].sender, "news@shop.org");
+    assert_eq!(overrides.senders[0].category, "");
+
+    // Spam filter results found in the message headers are not trusted
+    lmtp.ingest(
+        "offers@store.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: offers@store.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Exclusive offer\r\n",
+            "X-Spam-Result: HAS_LIST_ID (0.00), HAS_LIST_UNSUB (0.00)\r\n",
+            "\r\n",
+            "Get a discount on your next order."
+        ),
+    )
+    .await;
+    let offer_email = email_by_subject(account, "Exclusive offer").await;
+    assert_eq!(
+        offer_email["mailboxIds"],
+        json!({&inbox_id: true}),
+        "{offer_email:?}"
+    );
+    assert_eq!(offer_email["keywords"], json!({}), "{offer_email:?}");
+
+    // Sender frequency is counted for each sender address rather than its domain
+    for num in 1..=3 {
+        lmtp.ingest(
+            "alerts@bank.org",
+            &["jdoe@example.com"],
+            &format!(
+                concat!(
+                    "From: alerts@bank.org\r\n",
+                    "To: jdoe@example.com\r\n",
+                    "Subject: Account alert {}\r\n",
+                    "\r\n",
+                    "Your statement is ready."
+                ),
+                num
+            ),
+        )
+        .await;
+    }
+    lmtp.ingest(
+        "support@bank.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: support@bank.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Support ticket\r\n",
+            "\r\n",
+            "Your ticket was updated."
+        ),
+    )
+    .await;
+    lmtp.quit().await;
+    for (subject, keywords) in [
+        ("Account alert 1", json!({})),
+        ("Account alert 2", json!({})),
+        ("Account alert 3", json!({"$category:updates": true})),
+        ("Support ticket", json!({})),
+    ] {
+        let email = email_by_subject(account, subject).await;
+        assert_eq!(email["keywords"], keywords, "{email:?}");
+        assert_eq!(email["mailboxIds"], json!({&inbox_id: true}), "{email:?}");
+    }
+
+    // Disabling categorization forgets the categories learned for each sender
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            account.id(),
+            json!({
+                "categorizeMail": false
+            }),
+        )
+        .await;
+    assert!(category_overrides(test, account_id).await.is_none());
+
+    admin
+        .registry_destroy(ObjectType::MailCategory, [category_id, updates_id])
+        .await
+        .assert_destroyed(&[category_id, updates_id]);
+    admin.reload_settings().await;
+    test.destroy_all_mailboxes(test.account("jdoe@example.com"))
+        .await;
+    test.assert_is_empty().await;
+}
+
+async fn category_overrides(test: &TestServer, account_id: u32) -> Option<CategoryOverrides> {
+    test.server
+        .store()
+        .get_value::<Archive<AlignedBytes>>(ValueKey::property(
+            account_id,
+            Collection::Principal,
+            0,
+            PrincipalField::CategoryOverrides,
+        ))
+        .await
+        .unwrap()
+        .map(|archive| archive.deserialize::<CategoryOverrides>().unwrap())
+}
+
+fn newsletter(subject: &str) -> String {
+    format!(
+        concat!(
+            "From: Shop <news@shop.org>\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: {}\r\n",
+            "List-Id: <deals.shop.org>\r\n",
+            "List-Unsubscribe: <https://shop.org/unsubscribe>\r\n",
+            "\r\n",
+            "Our latest offers are here."
+        ),
+        subject
+    )
+}
+
+async fn email_by_subject(account: &Account, subject: &str) -> Value {
+    let ids = account
+        .jmap_query(
+            "Email",
+            Vec::<(&str, Value)>::new(),
+            Vec::<&str>::new(),
+            Vec::<(&str, Value)>::new(),
+        )
+        .await
+        .method_response()["ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    account
+        .jmap_get("Email", ["id", "subject", "mailboxIds", "keywords"], ids)
+        .await
+        .list()
+        .iter()
+        .find(|email| email["subject"] == subject)
+        .unwrap_or_else(|| panic!("Missing email with subject {subject:?}"))
+        .clone()
+}
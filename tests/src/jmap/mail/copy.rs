@@ -4,8 +4,9 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::utils::server::TestServer;
+use crate::utils::{jmap::JmapUtils, server::TestServer};
 use jmap_client::mailbox::Role;
+use serde_json::json;
 use types::id::Id;
 
 pub async fn test(test: &TestServer) {
@@ -95,6 +96,65 @@ pub async fn test(test: &TestServer) {
             .is_none()
     );
 
+    // Keywords and receivedAt are copied from the original unless provided
+    let ac1_email_id = client
+        .email_import(
+            concat!(
+                "From: bill@example.com\r\n",
+                "To: jdoe@example.com\r\n",
+                "Subject: TPS Report (copy)\r\n",
+                "\r\n",
+                "Did you get the memo?"
+            )
+            .as_bytes()
+            .to_vec(),
+            [&ac1_mailbox_id],
+            Some(["$seen", "Custom"]),
+            Some(311923920),
+        )
+        .await
+        .unwrap()
+        .take_id();
+    let create_id = Id::new(1234).to_string();
+    let response = account
+        .jmap_method_call(
+            "Email/copy",
+            json!({
+                "fromAccountId": Id::new(1).to_string(),
+                "accountId": Id::new(2).to_string(),
+                "onSuccessDestroyOriginal": true,
+                "create": {
+                    &create_id: {
+                        "id": &ac1_email_id,
+                        "mailboxIds": { &ac2_mailbox_id: true },
+                        "keywords/$flagged": true
+                    }
+                }
+            }),
+        )
+        .await;
+    let ac2_email_id = response.copied(&create_id).id().to_string();
+    assert_eq!(response.name_at(1), "Email/set");
+    assert_eq!(response.response_at(1)["destroyed"], json!([&ac1_email_id]));
+    let email = client
+        .set_default_account_id(Id::new(2).to_string())
+        .email_get(&ac2_email_id, None::<Vec<_>>)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut keywords = email.keywords();
+    keywords.sort_unstable();
+    assert_eq!(keywords, ["$flagged", "$seen", "Custom"]);
+    assert_eq!(email.received_at().unwrap(), 311923920);
+    assert!(
+        client
+            .set_default_account_id(Id::new(1).to_string())
+            .email_get(&ac1_email_id, None::<Vec<_>>)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
     // Empty store
     account.destroy_all_mailboxes_for_account(1).await;
     account.destroy_all_mailboxes_for_account(2).await;
@@ -0,0 +1,272 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{server::TestServer, smtp::SmtpConnection};
+use ::email::mailbox::INBOX_ID;
+use chrono::{DateTime, SecondsFormat};
+use jmap_client::mailbox::Role;
+use serde_json::{Value, json};
+use std::time::Duration;
+use types::id::Id;
+use utils::clock::{advance_test_clock, now};
+
+pub async fn test(test: &TestServer) {
+    println!("Running Email follow-up reminder tests...");
+    let account = test.account("jdoe@example.com");
+    let client = account.jmap_client().await;
+    let follow_up_at = |secs: i64| {
+        DateTime::from_timestamp(now() as i64 + secs, 0)
+            .unwrap()
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    };
+    let inbox_id = Id::from(INBOX_ID).to_string();
+    let pending_id = client
+        .mailbox_create("Awaiting Reply", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+
+    // Import two messages sent by the account owner
+    let mut email_ids = Vec::new();
+    for (num, subject) in ["Contract draft", "Budget review"].into_iter().enumerate() {
+        email_ids.push(
+            client
+                .email_import(
+                    format!(
+                        concat!(
+                            "From: jdoe@example.com\r\n",
+                            "To: bill@remote.org\r\n",
+                            "Subject: {}\r\n",
+                            "Message-ID: <followup-{}@example.com>\r\n",
+                            "\r\n",
+                            "Please let me know what you think."
+                        ),
+                        subject, num
+                    )
+                    .into_bytes(),
+                    [&inbox_id],
+                    Some(["$seen"]),
+                    None,
+                )
+                .await
+                .unwrap()
+                .take_id(),
+        );
+    }
+    let (replied_id, expired_id) = (&email_ids[0], &email_ids[1]);
+
+    // Follow-up properties and filters require the follow-up capability
+    let response = account
+        .jmap_request(
+            &["urn:ietf:params:jmap:core", "urn:ietf:params:jmap:mail"],
+            json!([
+                [
+                    "Email/get",
+                    {"ids": [replied_id], "properties": ["id", "followUpAt"]},
+                    "c0"
+                ],
+                [
+                    "Email/set",
+                    {"update": {replied_id: {"followUpMailboxId": &pending_id}}},
+                    "c1"
+                ],
+                ["Email/query", {"filter": {"hasFollowUp": true}}, "c2"],
+                [
+                    "Email/get",
+                    {"ids": [replied_id], "properties": ["id", "subject"]},
+                    "c3"
+                ]
+            ]),
+        )
+        .await;
+    for n in 0..3 {
+        assert_eq!(
+            response.error_type_at(n),
+            Some("invalidArguments"),
+            "{response:?}"
+        );
+    }
+    assert!(!response.is_error_at(3), "{response:?}");
+
+    // Reminders require a date in the future
+    let response = account
+        .jmap_update(
+            "Email",
+            [(replied_id, json!({"followUpAt": follow_up_at(-60)}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    assert_eq!(
+        response.not_updated(replied_id)["type"],
+        "invalidProperties",
+        "{response:?}"
+    );
+
+    // Watch both messages for replies
+    let response = account
+        .jmap_update(
+            "Email",
+            [
+                (replied_id, json!({"followUpAt": follow_up_at(3600)})),
+                (
+                    expired_id,
+                    json!({
+                        "followUpAt": follow_up_at(2),
+                        "followUpMailboxId": &pending_id
+                    }),
+                ),
+            ],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    response.updated(replied_id);
+    response.updated(expired_id);
+    let email = account
+        .jmap_get("Email", ["followUpAt", "followUpMailboxId"], [expired_id])
+        .await;
+    let email = &email.list()[0];
+    assert!(email["followUpAt"].is_string(), "{email:?}");
+    assert_eq!(email["followUpMailboxId"], json!(&pending_id), "{email:?}");
+    let mut watched = follow_up_ids(test).await;
+    watched.sort();
+    let mut expected = email_ids.clone();
+    expected.sort();
+    assert_eq!(watched, expected);
+
+    // A reply to the first message cancels its reminder
+    let email_state = account
+        .jmap_get("Email", ["id"], [replied_id])
+        .await
+        .state()
+        .to_string();
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: bill@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Re: Contract draft\r\n",
+            "Message-ID: <reply-0@remote.org>\r\n",
+            "In-Reply-To: <followup-0@example.com>\r\n",
+            "References: <followup-0@example.com>\r\n",
+            "\r\n",
+            "Looks good to me."
+        ),
+    )
+    .await;
+    lmtp.quit().await;
+    for _ in 0..20 {
+        if follow_up_ids(test).await.len() == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(follow_up_ids(test).await, vec![expired_id.to_string()]);
+    let email = account
+        .jmap_get("Email", ["keywords", "followUpAt"], [replied_id])
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(email["followUpAt"], Value::Null, "{email:?}");
+    assert_eq!(email["keywords"], json!({"$seen": true}), "{email:?}");
+    let changes = account.jmap_changes("Email", &email_state).await;
+    assert!(
+        changes.method_response()["updated"]
+            .as_array()
+            .unwrap()
+            .contains(&json!(replied_id)),
+        "{changes:?}"
+    );
+
+    // Messages without a reply are flagged and resurfaced once due
+    let email_state = account
+        .jmap_get("Email", ["id"], [expired_id])
+        .await
+        .state()
+        .to_string();
+    advance_test_clock(3);
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+    let email = account
+        .jmap_get(
+            "Email",
+            ["mailboxIds", "keywords", "followUpAt", "followUpMailboxId"],
+            [expired_id],
+        )
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(
+        email["mailboxIds"],
+        json!({&inbox_id: true, &pending_id: true}),
+        "{email:?}"
+    );
+    assert_eq!(
+        email["keywords"],
+        json!({"$seen": true, "$followup": true}),
+        "{email:?}"
+    );
+    assert_eq!(email["followUpAt"], Value::Null, "{email:?}");
+    assert_eq!(email["followUpMailboxId"], Value::Null, "{email:?}");
+    let changes = account.jmap_changes("Email", &email_state).await;
+    assert_eq!(
+        changes.method_response()["updated"],
+        json!([expired_id]),
+        "{changes:?}"
+    );
+    assert!(follow_up_ids(test).await.is_empty());
+
+    // Cancelled reminders are skipped
+    account
+        .jmap_update(
+            "Email",
+            [(expired_id, json!({"followUpAt": follow_up_at(2)}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(expired_id);
+    account
+        .jmap_update(
+            "Email",
+            [(expired_id, json!({"followUpAt": null}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(expired_id);
+    assert!(follow_up_ids(test).await.is_empty());
+    advance_test_clock(3);
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+    let email = account
+        .jmap_get("Email", ["mailboxIds", "followUpAt"], [expired_id])
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(
+        email["mailboxIds"],
+        json!({&inbox_id: true, &pending_id: true}),
+        "{email:?}"
+    );
+    assert_eq!(email["followUpAt"], Value::Null, "{email:?}");
+
+    test.destroy_all_mailboxes(account).await;
+    test.assert_is_empty().await;
+}
+
+async fn follow_up_ids(test: &TestServer) -> Vec<String> {
+    test.account("jdoe@example.com")
+        .jmap_query(
+            "Email",
+            [("hasFollowUp", Value::Bool(true))],
+            Vec::<&str>::new(),
+            Vec::<(&str, Value)>::new(),
+        )
+        .await
+        .method_response()["ids"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect()
+}
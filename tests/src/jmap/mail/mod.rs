@@ -5,16 +5,20 @@
  */
 
 pub mod acl;
+pub mod category;
 pub mod changes;
 pub mod copy;
+pub mod followup;
 pub mod get;
 pub mod mailbox;
 pub mod parse;
 pub mod query;
 pub mod query_changes;
+pub mod recall;
 pub mod search_snippet;
 pub mod set;
 pub mod sieve_script;
+pub mod snooze;
 pub mod submission;
 pub mod thread_get;
 pub mod thread_merge;
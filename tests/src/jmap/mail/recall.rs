@@ -0,0 +1,207 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer, smtp::SmtpConnection};
+use ::email::mailbox::{INBOX_ID, SENT_ID};
+use serde_json::{Value, json};
+use types::id::Id;
+
+const MESSAGE: &str = concat!(
+    "From: jdoe@example.com\r\n",
+    "To: jane.smith@example.com, bill@example.com\r\n",
+    "Cc: remote@remote.org\r\n",
+    "Subject: Quarterly numbers\r\n",
+    "Message-ID: <recall-1@example.com>\r\n",
+    "\r\n",
+    "These figures were sent by mistake."
+);
+
+pub async fn test(test: &TestServer) {
+    println!("Running Email recall tests...");
+    let sender = test.account("jdoe@example.com");
+    let jane = test.account("jane.smith@example.com");
+    let bill = test.account("bill@example.com");
+    let inbox_id = Id::from(INBOX_ID).to_string();
+    let sent_mailbox_id = Id::from(SENT_ID).to_string();
+    let unknown_id = Id::from(u32::MAX - 1).to_string();
+
+    // Store the sent copy and deliver it to the local recipients
+    let sent_id = sender
+        .jmap_client()
+        .await
+        .email_import(
+            MESSAGE.as_bytes().to_vec(),
+            [&sent_mailbox_id],
+            Some(["$seen"]),
+            None,
+        )
+        .await
+        .unwrap()
+        .take_id();
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "jdoe@example.com",
+        &["jane.smith@example.com", "bill@example.com"],
+        MESSAGE,
+    )
+    .await;
+    lmtp.quit().await;
+    test.wait_for_tasks().await;
+    let jane_id = email_ids(jane).await.pop().expect("jane's copy");
+    let bill_id = email_ids(bill).await.pop().expect("bill's copy");
+
+    // Bill reads his copy
+    bill.jmap_update(
+        "Email",
+        [(&bill_id, json!({"keywords/$seen": true}))],
+        Vec::<(String, Value)>::new(),
+    )
+    .await
+    .updated(&bill_id);
+
+    // Only the sender can recall a message
+    let response = jane
+        .jmap_method_call(
+            "Email/recall",
+            json!({"accountId": jane.id_string(), "ids": [&jane_id]}),
+        )
+        .await;
+    assert_eq!(
+        response.method_response()["notRecalled"][&jane_id]["type"],
+        "forbidden",
+        "{response:?}"
+    );
+    let response = jane
+        .jmap_method_call(
+            "Email/recall",
+            json!({"accountId": sender.id_string(), "ids": [&sent_id]}),
+        )
+        .await;
+    assert_eq!(response.error_type_at(0), Some("forbidden"), "{response:?}");
+
+    // Unread copies are recalled, read and remote copies are reported
+    let jane_state = jane
+        .jmap_get("Email", ["id"], [&jane_id])
+        .await
+        .state()
+        .to_string();
+    let response = sender
+        .jmap_method_call(
+            "Email/recall",
+            json!({"accountId": sender.id_string(), "ids": [&sent_id, &unknown_id]}),
+        )
+        .await;
+    let response = response.method_response();
+    assert_eq!(response["accountId"], json!(sender.id_string()));
+    assert_eq!(
+        response["notRecalled"][&unknown_id]["type"], "notFound",
+        "{response:?}"
+    );
+    let mut outcomes = response["recalled"][&sent_id]
+        .as_array()
+        .unwrap_or_else(|| panic!("Missing recall outcomes: {response:?}"))
+        .clone();
+    outcomes.sort_by(|a, b| a["email"].as_str().cmp(&b["email"].as_str()));
+    assert_eq!(
+        outcomes,
+        vec![
+            json!({"email": "bill@example.com", "status": "read"}),
+            json!({"email": "jane.smith@example.com", "status": "recalled"}),
+            json!({"email": "remote@remote.org", "status": "notLocal"}),
+        ]
+    );
+
+    // The recalled copy is removed through the change log
+    assert_eq!(
+        jane.jmap_get("Email", ["id"], [&jane_id])
+            .await
+            .not_found()
+            .collect::<Vec<_>>(),
+        vec![jane_id.as_str()]
+    );
+    let changes = jane.jmap_changes("Email", &jane_state).await;
+    assert_eq!(
+        changes.method_response()["destroyed"],
+        json!([&jane_id]),
+        "{changes:?}"
+    );
+    assert_eq!(email_ids(bill).await, vec![bill_id.clone()]);
+    assert_eq!(email_ids(sender).await, vec![sent_id.clone()]);
+
+    // Copies that were not sent by the sender are left alone, even when they
+    // share the Message-ID of the recalled message
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "mallory@remote.org",
+        &["jane.smith@example.com"],
+        &MESSAGE.replace("From: jdoe@example.com", "From: mallory@remote.org"),
+    )
+    .await;
+    lmtp.quit().await;
+    test.wait_for_tasks().await;
+    let spoofed_id = email_ids(jane).await.pop().expect("spoofed copy");
+    let response = sender
+        .jmap_method_call(
+            "Email/recall",
+            json!({"accountId": sender.id_string(), "ids": [&sent_id]}),
+        )
+        .await;
+    assert!(
+        response.method_response()["recalled"][&sent_id]
+            .as_array()
+            .unwrap()
+            .contains(&json!({"email": "jane.smith@example.com", "status": "notFound"})),
+        "{response:?}"
+    );
+    assert_eq!(email_ids(jane).await, vec![spoofed_id]);
+
+    // Messages that were not sent from the account cannot be recalled
+    let received_id = sender
+        .jmap_client()
+        .await
+        .email_import(
+            MESSAGE
+                .replace("recall-1@", "recall-2@")
+                .as_bytes()
+                .to_vec(),
+            [&inbox_id],
+            None::<Vec<&str>>,
+            None,
+        )
+        .await
+        .unwrap()
+        .take_id();
+    let response = sender
+        .jmap_method_call(
+            "Email/recall",
+            json!({"accountId": sender.id_string(), "ids": [&received_id]}),
+        )
+        .await;
+    assert_eq!(
+        response.method_response()["notRecalled"][&received_id]["type"],
+        "forbidden",
+        "{response:?}"
+    );
+
+    for account in [sender, jane, bill] {
+        test.destroy_all_mailboxes(account).await;
+    }
+    test.assert_is_empty().await;
+}
+
+async fn email_ids(account: &Account) -> Vec<String> {
+    account
+        .jmap_query(
+            "Email",
+            Vec::<(&str, Value)>::new(),
+            Vec::<&str>::new(),
+            Vec::<(&str, Value)>::new(),
+        )
+        .await
+        .ids()
+        .map(|id| id.to_string())
+        .collect()
+}
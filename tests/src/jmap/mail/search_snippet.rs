@@ -137,6 +137,27 @@ pub async fn test(test: &TestServer) {
                 "cualquier hexágono se "
             )),
         ),
+        (
+            Filter::header("Subject", Some("Argentina")).into(),
+            "subpart",
+            Some("[Fwd: Map of <mark>Argentina</mark> with Description]"),
+            None,
+        ),
+        (
+            query::Filter::and(vec![
+                Filter::in_mailbox(&mailbox_id),
+                Filter::subject("Babel"),
+            ]),
+            "mixed",
+            Some("Biblioteca de <mark>Babel</mark>"),
+            None,
+        ),
+        (
+            Filter::in_mailbox(&mailbox_id).into(),
+            "text_plain",
+            None,
+            None,
+        ),
     ] {
         let mut request = client.build();
         let result_ref = request
@@ -5,21 +5,35 @@
  */
 
 use crate::{
-    jmap::mail::submission::{MockMessage, assert_message_delivery, spawn_mock_smtp_server},
+    jmap::mail::submission::{
+        MockMessage, assert_message_delivery, expect_message_delivery, expect_nothing,
+        spawn_mock_smtp_server,
+    },
     utils::{dns::DnsCache, server::TestServer, smtp::SmtpConnection},
 };
+use email::sieve::log::{SieveLog, SieveLogEntry};
 use jmap_client::{
     Error,
     core::set::{SetError, SetErrorType},
     email, mailbox,
     sieve::query::{Comparator, Filter},
 };
-use registry::schema::{prelude::ObjectType, structs::SieveUserScript};
+use registry::{
+    schema::{
+        enums::SieveRedirectSender,
+        prelude::{ObjectType, Property},
+        structs::{AccountSettings, SieveUserInterpreter, SieveUserScript},
+    },
+    types::{ipmask::IpAddrOrMask, map::Map},
+};
+use serde_json::json;
 use std::{
     fs,
     path::PathBuf,
+    str::FromStr,
     time::{Duration, Instant},
 };
+use trc::{Collector, MetricType};
 
 pub async fn test(test: &TestServer) {
     println!("Running Sieve tests...");
@@ -458,6 +472,222 @@ pub async fn test(test: &TestServer) {
         "Redirected message was stored."
     );
 
+    // Run redirect DSN and loop prevention tests
+    client
+        .sieve_script_create("test_redirect_dsn", get_script("test_redirect_dsn"), true)
+        .await
+        .unwrap();
+    smtp_settings.lock().dsn = true;
+    let redirect_message = concat!(
+        "From: bill@remote.org\r\n",
+        "To: jdoe@example.com\r\n",
+        "Subject: TPS Report archive\r\n",
+        "\r\n",
+        "Please archive the TPS reports."
+    );
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+        .await;
+    let message = expect_message_delivery(&mut smtp_rx).await;
+    assert_eq!(message.mail_from, "<jdoe@example.com> RET=HDRS");
+    assert_eq!(message.rcpt_to, ["<archive@remote.org> NOTIFY=NEVER"]);
+    assert!(
+        message
+            .message
+            .contains("X-Sieve-Redirected-From: jdoe@example.com"),
+        "{}",
+        message.message
+    );
+
+    // Messages that already looped through the account are not redirected again
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        &format!(
+            concat!(
+                "X-Sieve-Redirected-From: jdoe@example.com\r\n",
+                "X-Sieve-Redirected-From: other@example.com\r\n",
+                "{}"
+            ),
+            redirect_message
+        ),
+    )
+    .await;
+    expect_message_delivery(&mut smtp_rx).await;
+    let looped_message = format!(
+        concat!(
+            "X-Sieve-Redirected-From: jdoe@example.com\r\n",
+            "X-Sieve-Redirected-From: JDOE@example.com\r\n",
+            "{}"
+        ),
+        redirect_message
+    );
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], &looped_message)
+        .await;
+    expect_nothing(&mut smtp_rx).await;
+
+    // Trusted hosts are allowed one additional pass
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter {
+                trusted_redirect_hosts: Map::new(vec![
+                    IpAddrOrMask::from_str("127.0.0.1").unwrap(),
+                ]),
+                ..Default::default()
+            },
+            &[Property::TrustedRedirectHosts],
+        )
+        .await;
+    admin.reload_settings().await;
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], &looped_message)
+        .await;
+    expect_message_delivery(&mut smtp_rx).await;
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        &format!("X-Sieve-Redirected-From: jdoe@example.com\r\n{looped_message}"),
+    )
+    .await;
+    expect_nothing(&mut smtp_rx).await;
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter::default(),
+            &[Property::TrustedRedirectHosts],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    // The envelope sender of the original message can be used for redirects
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter {
+                redirect_envelope_sender: SieveRedirectSender::Original,
+                ..Default::default()
+            },
+            &[Property::RedirectEnvelopeSender],
+        )
+        .await;
+    admin.reload_settings().await;
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+        .await;
+    let message = expect_message_delivery(&mut smtp_rx).await;
+    assert_eq!(message.mail_from, "<bill@remote.org> RET=HDRS");
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter::default(),
+            &[Property::RedirectEnvelopeSender],
+        )
+        .await;
+    admin.reload_settings().await;
+    smtp_settings.lock().dsn = false;
+    assert_eq!(
+        client
+            .email_query(None::<email::query::Filter>, None::<Vec<_>>)
+            .await
+            .unwrap()
+            .ids()
+            .len(),
+        1,
+        "Redirected message was stored."
+    );
+
+    // Redirects are limited per account and day
+    let limit_exceeded = Collector::read_metric(MetricType::SieveRedirectLimitExceeded);
+    let redirect_url = format!(
+        "{}/api/sieve/redirects/{}",
+        admin.base_url(),
+        account.id_string()
+    );
+    let response = admin.http_delete_raw(&redirect_url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter {
+                max_redirects_per_day: 2,
+                ..Default::default()
+            },
+            &[Property::MaxRedirectsPerDay],
+        )
+        .await;
+    admin.reload_settings().await;
+    for _ in 0..2 {
+        lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+            .await;
+        expect_message_delivery(&mut smtp_rx).await;
+    }
+    let response = admin.http_get_raw(&redirect_url, None).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let counters = response.json().unwrap();
+    assert_eq!(counters["untrusted"]["redirects"], 2, "{counters}");
+    assert_eq!(counters["untrusted"]["destinations"], 1, "{counters}");
+    assert_eq!(counters["untrusted"]["maxRedirects"], 2, "{counters}");
+    assert_eq!(counters["trusted"]["redirects"], 0, "{counters}");
+    assert_eq!(counters["trusted"]["maxRedirects"], 0, "{counters}");
+
+    // Messages exceeding the limit are delivered locally and flagged
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+        .await;
+    expect_nothing(&mut smtp_rx).await;
+    assert_eq!(
+        Collector::read_metric(MetricType::SieveRedirectLimitExceeded),
+        limit_exceeded + 1.0
+    );
+    let limited_ids = client
+        .email_query(
+            email::query::Filter::has_keyword("$redirect-limited").into(),
+            None::<Vec<_>>,
+        )
+        .await
+        .unwrap()
+        .take_ids();
+    assert_eq!(
+        limited_ids.len(),
+        1,
+        "Redirect limited message was not stored."
+    );
+    client.email_destroy(&limited_ids[0]).await.unwrap();
+
+    // Resetting the counters allows redirects again
+    let response = admin.http_delete_raw(&redirect_url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    assert_eq!(response.json().unwrap()["untrusted"]["redirects"], 0);
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+        .await;
+    expect_message_delivery(&mut smtp_rx).await;
+    let response = account.http_get_raw(&redirect_url, None).await;
+    assert_eq!(response.status, 403, "{}", response.text());
+    admin
+        .registry_update_setting(
+            SieveUserInterpreter::default(),
+            &[Property::MaxRedirectsPerDay],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    // Redirected messages forwarded back to the account are bounced once
+    lmtp.ingest("bill@remote.org", &["jdoe@example.com"], redirect_message)
+        .await;
+    let message = expect_message_delivery(&mut smtp_rx).await;
+    assert!(
+        message
+            .message
+            .contains("Delivered-To: jdoe@example.com\r\n")
+            && message.message.contains("X-Loop: jdoe@example.com\r\n"),
+        "{}",
+        message.message
+    );
+    lmtp.ingest(
+        "archive@remote.org",
+        &["jdoe@example.com"],
+        &message.message,
+    )
+    .await;
+    assert_message_delivery(
+        &mut smtp_rx,
+        MockMessage::new("<>", ["<archive@remote.org>"], "@Mail loop detected"),
+    )
+    .await;
+    expect_nothing(&mut smtp_rx).await;
+
     // Run notify + editheader + notify + fcc tests
     client
         .sieve_script_create("test_notify_fcc", get_script("test_notify_fcc"), true)
@@ -541,6 +771,264 @@ pub async fn test(test: &TestServer) {
         panic!("Email {:?} not found in: {:#?}", subject, emails);
     }
 
+    // Run duplicate :last and :handle tests
+    client
+        .sieve_script_create("test_duplicate", get_script("test_duplicate"), true)
+        .await
+        .unwrap();
+    for (num, expected_keywords) in [
+        &[][..],
+        &["$dup-first", "$dup-last"][..],
+        &["$dup-last"][..],
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        // Ids expire after 60 seconds unless refreshed by :last
+        if num > 0 {
+            utils::clock::advance_test_clock(40);
+        }
+        lmtp.ingest(
+            "list@remote.org",
+            &["jdoe@example.com"],
+            &format!(
+                concat!(
+                    "From: list@remote.org\r\n",
+                    "To: jdoe@example.com\r\n",
+                    "Message-ID: <duplicate-{}@remote.org>\r\n",
+                    "Subject: Cross-post {}\r\n",
+                    "\r\n",
+                    "Duplicate test.\r\n"
+                ),
+                num, num
+            ),
+        )
+        .await;
+        let email_id = client
+            .email_query(
+                email::query::Filter::header(
+                    "Message-Id",
+                    Some(format!("duplicate-{num}@remote.org")),
+                )
+                .into(),
+                None::<Vec<_>>,
+            )
+            .await
+            .unwrap()
+            .take_ids()
+            .pop()
+            .unwrap_or_else(|| panic!("Cross-post {num} was not delivered."));
+        let email = client
+            .email_get(&email_id, [email::Property::Keywords].into())
+            .await
+            .unwrap()
+            .unwrap();
+        let mut keywords = email.keywords();
+        keywords.sort_unstable();
+        assert_eq!(keywords, expected_keywords, "Cross-post {num}");
+    }
+
+    // Ids must not be recorded when the script is aborted
+    client
+        .sieve_script_create(
+            "test_duplicate_abort",
+            get_script("test_duplicate_abort"),
+            true,
+        )
+        .await
+        .unwrap();
+    for (subject, expected_total) in [("Abort", 7), ("Hello", 8), ("Hello again", 8)] {
+        lmtp.ingest(
+            "list@remote.org",
+            &["jdoe@example.com"],
+            &format!(
+                concat!(
+                    "From: list@remote.org\r\n",
+                    "To: jdoe@example.com\r\n",
+                    "Subject: {}\r\n",
+                    "\r\n",
+                    "Duplicate abort test.\r\n"
+                ),
+                subject
+            ),
+        )
+        .await;
+        assert_eq!(
+            client
+                .email_query(None::<email::query::Filter>, None::<Vec<_>>)
+                .await
+                .unwrap()
+                .ids()
+                .len(),
+            expected_total,
+            "Unexpected total after delivering {subject:?}."
+        );
+    }
+
+    // Execution logs record the tests, actions and failures of each run
+    account
+        .registry_update_setting(
+            AccountSettings {
+                sieve_logging: true,
+                ..Default::default()
+            },
+            &[Property::SieveLogging],
+        )
+        .await;
+    client
+        .sieve_script_create("test_sieve_log", get_script("test_sieve_log"), true)
+        .await
+        .unwrap();
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "X-Sieve-Redirected-From: jdoe@example.com\r\n",
+            "X-Sieve-Redirected-From: jdoe@example.com\r\n",
+            "From: bill@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Message-ID: <sieve-log@remote.org>\r\n",
+            "Subject: Logged message\r\n",
+            "\r\n",
+            "Please log this message.\r\n"
+        ),
+    )
+    .await;
+    expect_nothing(&mut smtp_rx).await;
+    let response = account
+        .jmap_method_call(
+            "SieveLog/query",
+            json!({
+                "accountId": account.id_string(),
+            }),
+        )
+        .await;
+    let result = response.method_response();
+    assert_eq!(result["total"], 1, "{result}");
+    let entry = &result["list"][0];
+    assert_eq!(entry["scriptName"], "test_sieve_log", "{entry}");
+    assert_eq!(entry["messageId"], "sieve-log@remote.org", "{entry}");
+    assert_eq!(entry["envelopeFrom"], "bill@remote.org", "{entry}");
+    assert_eq!(
+        entry["tests"],
+        json!([
+            { "name": "header", "argument": "Subject logged" },
+            { "name": "envelope", "argument": "from remote.org" },
+            { "name": "size", "argument": "under 1024" },
+            { "name": "mailboxexists", "argument": "Inbox" }
+        ]),
+        "{entry}"
+    );
+    assert_eq!(
+        entry["actions"],
+        json!([
+            { "action": "fileinto", "target": "Logged", "error": null },
+            {
+                "action": "redirect",
+                "target": "archive@remote.org",
+                "error": "Mail loop detected"
+            }
+        ]),
+        "{entry}"
+    );
+    assert_eq!(entry["errors"], json!([]), "{entry}");
+
+    // Concurrent runs do not overwrite each other's log entries
+    let account_id = account.id().document_id();
+    futures::future::join_all((0..5).map(|n| {
+        let server = server.clone();
+        async move {
+            server
+                .sieve_log_append(
+                    account_id,
+                    SieveLogEntry {
+                        received_at: store::write::now(),
+                        script_name: format!("concurrent_{n}"),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+        }
+    }))
+    .await;
+    let mut script_names = server
+        .sieve_log_get(account_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.script_name)
+        .collect::<Vec<_>>();
+    script_names.sort();
+    assert_eq!(
+        script_names,
+        [
+            "concurrent_0",
+            "concurrent_1",
+            "concurrent_2",
+            "concurrent_3",
+            "concurrent_4",
+            "test_sieve_log"
+        ]
+    );
+
+    // Portable scripts can test for extensions, read the environment
+    // and file into a mailbox by id after it has been renamed
+    let mailbox_id = client
+        .mailbox_create("Receipts", None::<String>, mailbox::Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    let mut request = client.build();
+    request
+        .set_mailbox()
+        .update(&mailbox_id)
+        .name("Receipts 2024");
+    assert!(
+        request
+            .send_set_mailbox()
+            .await
+            .unwrap()
+            .updated(&mailbox_id)
+            .is_ok()
+    );
+    client
+        .sieve_script_create(
+            "test_portability",
+            String::from_utf8(get_script("test_portability"))
+                .unwrap()
+                .replace("__MAILBOX_ID__", &mailbox_id)
+                .into_bytes(),
+            true,
+        )
+        .await
+        .unwrap();
+    lmtp.ingest(
+        "bill@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: bill@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Your receipt\r\n",
+            "\r\n",
+            "Thank you for your order.\r\n"
+        ),
+    )
+    .await;
+    assert_eq!(
+        client
+            .email_query(
+                email::query::Filter::in_mailbox(&mailbox_id).into(),
+                None::<Vec<_>>,
+            )
+            .await
+            .unwrap()
+            .ids()
+            .len(),
+        1,
+        "Message was not filed into the renamed mailbox."
+    );
+
     // Remove test data
     client.sieve_script_deactivate().await.unwrap();
     let mut request = client.build();
@@ -0,0 +1,269 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::TestServer;
+use ::email::mailbox::INBOX_ID;
+use chrono::{DateTime, SecondsFormat};
+use jmap_client::mailbox::Role;
+use serde_json::{Value, json};
+use types::id::Id;
+use utils::clock::{advance_test_clock, now};
+
+pub async fn test(test: &TestServer) {
+    println!("Running Email Snooze tests...");
+    let inbox_id = Id::from(INBOX_ID).to_string();
+    let account = test.account("jdoe@example.com");
+    let client = account.jmap_client().await;
+    let snooze_until = |secs: i64| {
+        DateTime::from_timestamp(now() as i64 + secs, 0)
+            .unwrap()
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    };
+    let projects_id = client
+        .mailbox_create("Projects", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+
+    // Import a read message into the Inbox and a second mailbox
+    let email_id = client
+        .email_import(
+            concat!(
+                "From: bill@example.com\r\n",
+                "To: jdoe@example.com\r\n",
+                "Subject: Remind me later\r\n",
+                "\r\n",
+                "Please take a look at this tomorrow."
+            )
+            .as_bytes()
+            .to_vec(),
+            [&inbox_id, &projects_id],
+            Some(["$seen"]),
+            None,
+        )
+        .await
+        .unwrap()
+        .take_id();
+    let email_state = account
+        .jmap_get("Email", ["id"], [&email_id])
+        .await
+        .state()
+        .to_string();
+    let mailbox_state = account
+        .jmap_get("Mailbox", ["id"], Vec::<String>::new())
+        .await
+        .state()
+        .to_string();
+
+    // Snoozing requires a date in the future
+    let response = account
+        .jmap_update(
+            "Email",
+            [(&email_id, json!({"snoozedUntil": snooze_until(-60)}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    assert_eq!(
+        response.not_updated(&email_id)["type"],
+        "invalidProperties",
+        "{response:?}"
+    );
+
+    // Snooze the message and make sure it is moved to the Snoozed mailbox,
+    // which is created along with the first snoozed message
+    account
+        .jmap_update(
+            "Email",
+            [(&email_id, json!({"snoozedUntil": snooze_until(2)}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(&email_id);
+    let snoozed_id = snoozed_mailbox_id(test).await;
+    let email = account
+        .jmap_get(
+            "Email",
+            ["mailboxIds", "keywords", "snoozedUntil", "restoreMailboxId"],
+            [&email_id],
+        )
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(email["mailboxIds"], json!({&snoozed_id: true}), "{email:?}");
+    assert_eq!(email["keywords"], json!({"$seen": true}), "{email:?}");
+    assert_eq!(email["restoreMailboxId"], json!(&inbox_id), "{email:?}");
+    assert!(email["snoozedUntil"].is_string(), "{email:?}");
+    assert!(
+        account
+            .jmap_query(
+                "Email",
+                [("inMailbox", Value::from(inbox_id.as_str()))],
+                Vec::<&str>::new(),
+                Vec::<(&str, Value)>::new(),
+            )
+            .await
+            .method_response()["ids"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    // The Snoozed mailbox cannot be used as the restore target
+    let response = account
+        .jmap_update(
+            "Email",
+            [(&email_id, json!({"restoreMailboxId": &snoozed_id}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    assert_eq!(
+        response.not_updated(&email_id)["type"],
+        "invalidProperties",
+        "{response:?}"
+    );
+
+    // Once the snooze expires the message is restored as unread to all the
+    // mailboxes it was snoozed from
+    advance_test_clock(3);
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+    let email = account
+        .jmap_get(
+            "Email",
+            ["mailboxIds", "keywords", "snoozedUntil", "restoreMailboxId"],
+            [&email_id],
+        )
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(
+        email["mailboxIds"],
+        json!({&inbox_id: true, &projects_id: true}),
+        "{email:?}"
+    );
+    assert_eq!(email["keywords"], json!({}), "{email:?}");
+    assert_eq!(email["snoozedUntil"], Value::Null, "{email:?}");
+    assert_eq!(email["restoreMailboxId"], Value::Null, "{email:?}");
+    let changes = account.jmap_changes("Email", &email_state).await;
+    assert_eq!(
+        changes.method_response()["updated"],
+        json!([&email_id]),
+        "{changes:?}"
+    );
+    let changes = account.jmap_changes("Mailbox", &mailbox_state).await;
+    assert!(
+        changes.method_response()["updated"]
+            .as_array()
+            .unwrap()
+            .contains(&json!(&inbox_id)),
+        "{changes:?}"
+    );
+
+    // Unsnoozing moves the message to its restore mailbox and the other
+    // mailboxes right away and the pending restore is skipped
+    let later_id = client
+        .mailbox_create("Later", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    account
+        .jmap_update(
+            "Email",
+            [(
+                &email_id,
+                json!({
+                    "snoozedUntil": snooze_until(2),
+                    "restoreMailboxId": &later_id,
+                    "keywords/$seen": true
+                }),
+            )],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(&email_id);
+    account
+        .jmap_update(
+            "Email",
+            [(&email_id, json!({"snoozedUntil": null}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await
+        .updated(&email_id);
+    advance_test_clock(3);
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+    let email = account
+        .jmap_get(
+            "Email",
+            ["mailboxIds", "keywords", "snoozedUntil"],
+            [&email_id],
+        )
+        .await;
+    let email = &email.list()[0];
+    assert_eq!(
+        email["mailboxIds"],
+        json!({&later_id: true, &inbox_id: true, &projects_id: true}),
+        "{email:?}"
+    );
+    assert_eq!(email["keywords"], json!({"$seen": true}), "{email:?}");
+    assert_eq!(email["snoozedUntil"], Value::Null, "{email:?}");
+
+    // Concurrent first snoozes never create more than one Snoozed mailbox
+    client.mailbox_destroy(&snoozed_id, true).await.unwrap();
+    let mut email_ids = Vec::new();
+    for subject in ["First", "Second"] {
+        email_ids.push(
+            client
+                .email_import(
+                    format!("From: bill@example.com\r\nSubject: {subject}\r\n\r\nHi.").into_bytes(),
+                    [&inbox_id],
+                    None::<Vec<&str>>,
+                    None,
+                )
+                .await
+                .unwrap()
+                .take_id(),
+        );
+    }
+    futures::future::join_all(email_ids.iter().map(|email_id| {
+        account.jmap_update(
+            "Email",
+            [(email_id, json!({"snoozedUntil": snooze_until(60)}))],
+            Vec::<(String, Value)>::new(),
+        )
+    }))
+    .await;
+    let response = account
+        .jmap_get("Mailbox", ["id", "role"], Vec::<String>::new())
+        .await;
+    assert_eq!(
+        response
+            .list()
+            .iter()
+            .filter(|mailbox| mailbox["role"] == "snoozed")
+            .count(),
+        1,
+        "{response:?}"
+    );
+    advance_test_clock(61);
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+
+    test.destroy_all_mailboxes(account).await;
+    test.assert_is_empty().await;
+}
+
+async fn snoozed_mailbox_id(test: &TestServer) -> String {
+    let response = test
+        .account("jdoe@example.com")
+        .jmap_get("Mailbox", ["id", "role"], Vec::<String>::new())
+        .await;
+    response
+        .list()
+        .iter()
+        .find(|mailbox| mailbox["role"] == "snoozed")
+        .and_then(|mailbox| mailbox["id"].as_str())
+        .unwrap_or_else(|| panic!("Missing snoozed mailbox: {response:?}"))
+        .to_string()
+}
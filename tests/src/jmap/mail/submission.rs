@@ -9,6 +9,7 @@ use crate::{
     utils::{dns::DnsCache, server::TestServer},
 };
 use ahash::AHashMap;
+use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
 use jmap_client::{
     Error,
     core::set::{SetError, SetErrorType, SetObject},
@@ -16,6 +17,7 @@ use jmap_client::{
     mailbox::Role,
 };
 use mail_parser::DateTime;
+use serde_json::{Value, json};
 use std::{
     sync::Arc,
     time::{Duration, Instant},
@@ -55,6 +57,7 @@ pub struct MockSMTPSettings {
     pub fail_rcpt_to: bool,
     pub fail_message: bool,
     pub do_stop: bool,
+    pub dsn: bool,
 }
 
 #[allow(clippy::disallowed_types)]
@@ -441,6 +444,130 @@ pub async fn test(test: &TestServer) {
         ),])
     );
 
+    // Submissions scheduled beyond the maximum delay should fail
+    let send_at = |secs: i64| {
+        (Utc::now() + ChronoDuration::seconds(secs)).to_rfc3339_opts(SecondsFormat::Secs, true)
+    };
+    let response = account
+        .jmap_create(
+            "EmailSubmission",
+            [json!({
+                "emailId": &email_id,
+                "identityId": &identity_id,
+                "sendAt": send_at(86400 * 31),
+            })],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    assert_eq!(
+        response.not_created(0)["type"],
+        "invalidProperties",
+        "{response:?}"
+    );
+
+    // Schedule a submission and make sure it is held
+    let response = account
+        .jmap_create(
+            "EmailSubmission",
+            [json!({
+                "emailId": &email_id,
+                "identityId": &identity_id,
+                "sendAt": send_at(60),
+                "envelope": {
+                    "mailFrom": {"email": "jdoe@example.com"},
+                    "rcptTo": [{"email": "jane_smith@remote.org"}]
+                }
+            })],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    assert_eq!(response.created(0)["undoStatus"], "pending", "{response:?}");
+    let scheduled_id = response.created_id(0).to_string();
+    expect_nothing(&mut smtp_rx).await;
+
+    // Scheduled submissions are listed as pending
+    let response = account
+        .jmap_query(
+            "EmailSubmission",
+            [
+                ("undoStatus", Value::from("pending")),
+                ("after", Value::from(send_at(30))),
+                ("before", Value::from(send_at(120))),
+            ],
+            Vec::<&str>::new(),
+            Vec::<(&str, Value)>::new(),
+        )
+        .await;
+    assert_eq!(
+        response.method_response()["ids"],
+        json!([&scheduled_id]),
+        "{response:?}"
+    );
+
+    // Move the submission forward and wait for it to be sent
+    let response = account
+        .jmap_update(
+            "EmailSubmission",
+            [(&scheduled_id, json!({"sendAt": send_at(2)}))],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    response.updated(&scheduled_id);
+    expect_nothing(&mut smtp_rx).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_message_delivery(
+        &mut smtp_rx,
+        MockMessage::new(
+            "<jdoe@example.com>",
+            ["<jane_smith@remote.org>"],
+            &email_body,
+        ),
+    )
+    .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let email_submission = client
+        .email_submission_get(&scheduled_id, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(email_submission.undo_status().unwrap(), &UndoStatus::Final);
+    assert_eq!(
+        email_submission.delivery_status().unwrap(),
+        &AHashMap::from_iter([(
+            "jane_smith@remote.org".to_string(),
+            DeliveryStatus::new("250 2.1.5 Queued", Delivered::Unknown, Displayed::Unknown)
+        )])
+    );
+
+    // Cancelled submissions are never sent
+    let response = account
+        .jmap_create(
+            "EmailSubmission",
+            [json!({
+                "emailId": &email_id,
+                "identityId": &identity_id,
+                "sendAt": send_at(2),
+            })],
+            Vec::<(String, Value)>::new(),
+        )
+        .await;
+    let scheduled_id = response.created_id(0).to_string();
+    client
+        .email_submission_change_status(&scheduled_id, UndoStatus::Canceled)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    expect_nothing(&mut smtp_rx).await;
+    let email_submission = client
+        .email_submission_get(&scheduled_id, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        email_submission.undo_status().unwrap(),
+        &UndoStatus::Canceled
+    );
+
     // Verify onSuccessUpdateEmail action
     let mut request = client.build();
     let set_request = request.set_email_submission();
@@ -531,9 +658,13 @@ pub fn spawn_mock_smtp_server() -> (mpsc::Receiver<MockMessage>, Arc<Mutex<MockS
             while rx.read_line(&mut buf).await.is_ok() {
                 //print!("-> {}", buf);
                 if buf.starts_with("EHLO") {
-                    tx.write_all(b"250 Hi there, but I have no extensions to offer :-(\r\n")
-                        .await
-                        .unwrap();
+                    if settings.lock().dsn {
+                        tx.write_all(b"250-Hi there\r\n250 DSN\r\n").await.unwrap();
+                    } else {
+                        tx.write_all(b"250 Hi there, but I have no extensions to offer :-(\r\n")
+                            .await
+                            .unwrap();
+                    }
                 } else if buf.starts_with("MAIL FROM") {
                     if settings.lock().fail_mail_from {
                         tx.write_all("552-I do not\r\n552 like that MAIL FROM.\r\n".as_bytes())
@@ -234,6 +234,8 @@ async fn test_multi_thread(test: &TestServer) {
                             deliver_to: "test@domain.org",
                             is_sender_authenticated: true,
                             is_spam: false,
+                            is_duplicate: false,
+                            spam_tags: &[],
                         },
                         session_id: 0,
                     })
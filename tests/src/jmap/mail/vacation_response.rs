@@ -6,11 +6,14 @@
 
 use crate::{
     jmap::mail::submission::{
-        MockMessage, assert_message_delivery, expect_nothing, spawn_mock_smtp_server,
+        MockMessage, assert_message_delivery, expect_message_delivery, expect_nothing,
+        spawn_mock_smtp_server,
     },
     utils::{dns::DnsCache, server::TestServer, smtp::SmtpConnection},
 };
 use chrono::{TimeDelta, Utc};
+use jmap_client::client::Client;
+use registry::schema::{enums::MessageTemplateId, prelude::ObjectType, structs::MessageTemplate};
 use std::time::Instant;
 
 pub async fn test(test: &TestServer) {
@@ -153,6 +156,13 @@ pub async fn test(test: &TestServer) {
     )
     .await;
 
+    // Auto-reply templates are applied when the response is sent
+    test_vacation_template(test, &client).await;
+
+    // Test RFC 5230 handling of custom vacation scripts
+    client.vacation_response_disable().await.unwrap();
+    test_vacation_rules(&client).await;
+
     // Remove test data
     client.vacation_response_disable().await.unwrap();
     client.sieve_script_deactivate().await.unwrap();
@@ -164,3 +174,219 @@ pub async fn test(test: &TestServer) {
     test.destroy_all_mailboxes(account).await;
     test.assert_is_empty().await;
 }
+
+async fn test_vacation_template(test: &TestServer, client: &Client) {
+    let (mut smtp_rx, smtp_settings) = spawn_mock_smtp_server();
+    client
+        .vacation_response_enable(
+            "Gone fishing",
+            "Back on Monday".into(),
+            "Back on <b>Monday</b>".into(),
+        )
+        .await
+        .unwrap();
+
+    // Templates created after the vacation response was set are used
+    let admin = test.account("admin@example.com");
+    let template_id = admin
+        .registry_create_object(MessageTemplate {
+            template_id: MessageTemplateId::AutoReply,
+            subject: "Auto: {{subject}}".into(),
+            text_body: "{{body}}\r\n-- \r\nSent on behalf of {{recipient}}\r\n".into(),
+            html_body: Some("<div>{{body}}</div><p>{{recipient}}</p>".into()),
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+
+    smtp_settings.lock().do_stop = true;
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "frank@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: frank@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Fishing trip\r\n",
+            "\r\n",
+            "Are you coming along?",
+        ),
+    )
+    .await;
+    lmtp.quit().await;
+
+    let message = expect_message_delivery(&mut smtp_rx).await;
+    assert_eq!(message.rcpt_to, ["<frank@remote.org>"]);
+    for needle in [
+        "Subject: Auto: Gone fishing",
+        "Auto-Submitted: auto-replied",
+        "Back on Monday\r\n-- \r\nSent on behalf of jdoe@example.com",
+        "<div>Back on <b>Monday</b></div><p>jdoe@example.com</p>",
+    ] {
+        assert!(
+            message.message.contains(needle),
+            "[{}] needle = {needle:?}",
+            message.message
+        );
+    }
+    assert!(
+        !message.message.contains("&lt;b&gt;"),
+        "{}",
+        message.message
+    );
+
+    admin
+        .registry_destroy(ObjectType::MessageTemplate, [template_id])
+        .await
+        .assert_destroyed(&[template_id]);
+    admin.reload_settings().await;
+}
+
+async fn test_vacation_rules(client: &Client) {
+    let (mut smtp_rx, smtp_settings) = spawn_mock_smtp_server();
+    let mut lmtp = SmtpConnection::connect().await;
+    client
+        .sieve_script_create("vacation_rules", vacation_script("tps", None), true)
+        .await
+        .unwrap();
+
+    // Messages addressed to an alias trigger a response from that alias
+    lmtp.ingest(
+        "carl@remote.org",
+        &["john.doe@example.com"],
+        concat!(
+            "From: carl@remote.org\r\n",
+            "To: john.doe@example.com\r\n",
+            "Subject: TPS Report\r\n",
+            "\r\n",
+            "Did you get the memo?",
+        ),
+    )
+    .await;
+    assert_message_delivery(
+        &mut smtp_rx,
+        MockMessage::new("<jdoe@example.com>", ["<carl@remote.org>"], "@Kokomo"),
+    )
+    .await;
+
+    // Responses are tracked per handle rather than per subject
+    lmtp.ingest(
+        "carl@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: carl@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: Another TPS Report\r\n",
+            "\r\n",
+            "Did you get the other memo?",
+        ),
+    )
+    .await;
+    expect_nothing(&mut smtp_rx).await;
+
+    // Addresses listed in ":addresses" are considered own addresses
+    lmtp.ingest(
+        "dave@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: dave@remote.org\r\n",
+            "To: jd@partner.org\r\n",
+            "Subject: Partner TPS Report\r\n",
+            "\r\n",
+            "Please review.",
+        ),
+    )
+    .await;
+    assert_message_delivery(
+        &mut smtp_rx,
+        MockMessage::new("<jdoe@example.com>", ["<dave@remote.org>"], "@Kokomo"),
+    )
+    .await;
+
+    // Messages from lists and bulk senders are not answered
+    for headers in [
+        "List-Id: <tps.lists.remote.org>\r\n",
+        "Precedence: bulk\r\n",
+        "Auto-Submitted: auto-generated\r\n",
+    ] {
+        lmtp.ingest(
+            "erin@remote.org",
+            &["jdoe@example.com"],
+            &format!(
+                concat!(
+                    "From: erin@remote.org\r\n",
+                    "To: jdoe@example.com\r\n",
+                    "{}",
+                    "Subject: TPS Newsletter\r\n",
+                    "\r\n",
+                    "This week in TPS reports."
+                ),
+                headers
+            ),
+        )
+        .await;
+        expect_nothing(&mut smtp_rx).await;
+    }
+
+    // A different handle starts a new suppression period
+    client
+        .sieve_script_create("vacation_rules_2", vacation_script("tps-2", None), true)
+        .await
+        .unwrap();
+    lmtp.ingest(
+        "carl@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: carl@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: TPS Report\r\n",
+            "\r\n",
+            "Did you get the memo?",
+        ),
+    )
+    .await;
+    assert_message_delivery(
+        &mut smtp_rx,
+        MockMessage::new("<jdoe@example.com>", ["<carl@remote.org>"], "@Kokomo"),
+    )
+    .await;
+
+    // Responses using a From address that is not an identity are not sent
+    client
+        .sieve_script_create(
+            "vacation_rules_3",
+            vacation_script("tps-3", "ceo@example.com".into()),
+            true,
+        )
+        .await
+        .unwrap();
+    smtp_settings.lock().do_stop = true;
+    lmtp.ingest(
+        "gina@remote.org",
+        &["jdoe@example.com"],
+        concat!(
+            "From: gina@remote.org\r\n",
+            "To: jdoe@example.com\r\n",
+            "Subject: TPS Report\r\n",
+            "\r\n",
+            "Where are the TPS reports?",
+        ),
+    )
+    .await;
+    expect_nothing(&mut smtp_rx).await;
+    lmtp.quit().await;
+}
+
+fn vacation_script(handle: &str, from: Option<&str>) -> Vec<u8> {
+    format!(
+        concat!(
+            "require \"vacation\";\r\n",
+            "vacation :days 7 :handle \"{}\" :addresses [\"jd@partner.org\"]{} ",
+            ":subject \"Away\" \"Off to Kokomo\";\r\n"
+        ),
+        handle,
+        from.map(|from| format!(" :from \"{from}\""))
+            .unwrap_or_default()
+    )
+    .into_bytes()
+}
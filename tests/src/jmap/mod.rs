@@ -150,6 +150,7 @@ pub async fn jmap_tests() {
                     },
                 ]),
                 else_: "'mx'".to_string(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -173,6 +174,7 @@ pub async fn jmap_tests() {
                     then: "99999999d".into(),
                 }]),
                 else_: "false".to_string(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -196,11 +198,16 @@ pub async fn jmap_tests() {
     mail::sieve_script::test(&test).await;
     mail::vacation_response::test(&test).await;
     mail::submission::test(&test).await;
+    mail::snooze::test(&test).await;
+    mail::followup::test(&test).await;
+    mail::category::test(&test).await;
+    mail::recall::test(&test).await;
 
     core::event_source::test(&test).await;
     core::websocket::test(&test).await;
     core::push_subscription::test(&test).await;
     core::blob::test(&test).await;
+    core::session::test(&test).await;
 
     contacts::addressbook::test(&test).await;
     contacts::contact::test(&test).await;
@@ -57,6 +57,7 @@ pub async fn test(test: &TestServer) {
         },
         "urn:ietf:params:jmap:blob": {},
         "urn:ietf:params:jmap:quota": {},
+        "urn:stalwart:jmap:followup": {},
         "urn:ietf:params:jmap:webpush-vapid": {
           "applicationServerKey": application_server_key
         },
@@ -242,7 +243,8 @@ pub async fn test(test: &TestServer) {
               "webWriteUrlTemplate": null
             },
             "urn:ietf:params:jmap:mail:share": {},
-            "urn:stalwart:jmap": {}
+            "urn:stalwart:jmap": {},
+            "urn:stalwart:jmap:followup": {}
           }
         }
       },
@@ -262,7 +264,8 @@ pub async fn test(test: &TestServer) {
         "urn:ietf:params:jmap:principals:availability": john_id,
         "urn:ietf:params:jmap:filenode": john_id,
         "urn:ietf:params:jmap:mail:share": john_id,
-        "urn:stalwart:jmap": john_id
+        "urn:stalwart:jmap": john_id,
+        "urn:stalwart:jmap:followup": john_id
       },
       "username": "jdoe@example.com",
       "apiUrl": "https://127.0.0.1:8899/jmap/",
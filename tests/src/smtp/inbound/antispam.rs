@@ -273,6 +273,7 @@ async fn antispam() {
         "url",
         "html",
         "mime",
+        "archive",
         "bounce",
         "dmarc",
         "rbl",
@@ -603,7 +604,7 @@ async fn antispam() {
                     server.spam_filter_analyze_rules(&mut spam_ctx).await;
                     spam_ctx.result.tags.retain(|t| !t.starts_with("X_HDR_"));
                 }
-                "mime" => {
+                "mime" | "archive" => {
                     server.spam_filter_analyze_mime(&mut spam_ctx).await;
                 }
                 "headers" => {
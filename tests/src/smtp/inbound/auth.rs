@@ -54,6 +54,7 @@ async fn auth() {
                     then: "2".into(),
                 }]),
                 else_: "3".into(),
+                ..Default::default()
             },
             must_match_sender: Expression {
                 else_: "true".into(),
@@ -65,6 +66,7 @@ async fn auth() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             sasl_mechanisms: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -72,11 +74,16 @@ async fn auth() {
                     then: "[plain, login]".into(),
                 }]),
                 else_: "0".into(),
+                ..Default::default()
             },
             wait_on_fail: Expression {
                 else_: "100ms".into(),
                 ..Default::default()
             },
+            client_cert_account: Expression {
+                else_: "false".into(),
+                ..Default::default()
+            },
         })
         .await;
     admin
@@ -87,6 +94,7 @@ async fn auth() {
                     then: "1d".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -0,0 +1,166 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::server::TestServerBuilder,
+};
+use registry::{
+    schema::structs::{Expression, ExpressionMatch, MtaExtensions, MtaStageConnect},
+    types::list::List,
+};
+
+#[tokio::test]
+async fn banner() {
+    let mut test = TestServerBuilder::new("smtp_banner_test")
+        .await
+        .with_http_listener(19063)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Verbose banner for internal hosts, generic banner for everyone else
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin.mta_allow_relaying().await;
+    admin
+        .registry_create_object(MtaStageConnect {
+            hostname: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "remote_ip = '10.0.0.1'".into(),
+                    then: "'mx.internal.example.org'".into(),
+                }]),
+                else_: "'mx.example.org'".into(),
+                ..Default::default()
+            },
+            smtp_greeting: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "remote_ip = '10.0.0.1'".into(),
+                    then: "'mx.internal.example.org Stalwart ESMTP at your service'".into(),
+                }]),
+                else_: "'mx.example.org ESMTP'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaExtensions {
+            dsn: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            hidden_extensions: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "remote_ip != '10.0.0.1'".into(),
+                    then: "['dsn', 'size', 'chunking']".into(),
+                }]),
+                else_: "false".into(),
+                ..Default::default()
+            },
+            reject_hidden_extensions: Expression {
+                else_: "remote_ip = '10.0.0.2'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+
+    // Internal host gets the verbose banner and all extensions
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session
+        .response()
+        .assert_code("220")
+        .assert_contains("220 mx.internal.example.org Stalwart ESMTP at your service");
+    session
+        .ehlo("mx.foobar.org")
+        .await
+        .assert_contains("250-mx.internal.example.org")
+        .assert_contains("SIZE")
+        .assert_contains("DSN")
+        .assert_contains("CHUNKING");
+    session
+        .mail_from("<john@example.org> SIZE=1024", "250")
+        .await;
+    session
+        .rcpt_to("<bill@foobar.org> NOTIFY=SUCCESS", "250")
+        .await;
+
+    // External host on the same listener gets the generic banner and
+    // does not see the hidden extensions
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session
+        .response()
+        .assert_code("220")
+        .assert_contains("220 mx.example.org ESMTP")
+        .assert_not_contains("Stalwart")
+        .assert_not_contains("internal");
+    session
+        .ehlo("mx.foobar.org")
+        .await
+        .assert_contains("250-mx.example.org")
+        .assert_not_contains("internal")
+        .assert_not_contains("SIZE")
+        .assert_not_contains("DSN")
+        .assert_not_contains("CHUNKING")
+        .assert_contains("PIPELINING");
+
+    // Parameters of hidden extensions are rejected
+    session
+        .mail_from("<john@example.org> SIZE=1024", "501 5.5.4")
+        .await;
+    session
+        .mail_from("<john@example.org> ENVID=abc", "501 5.5.4")
+        .await;
+    session.mail_from("<john@example.org>", "250").await;
+    session
+        .rcpt_to("<bill@foobar.org> NOTIFY=SUCCESS", "501 5.5.4")
+        .await;
+    session.rcpt_to("<bill@foobar.org>", "250").await;
+
+    // BDAT is refused and its chunk discarded when CHUNKING was not offered
+    session
+        .cmd("BDAT 11 LAST\r\nRSET\r\nXYZ", "502 5.5.1")
+        .await;
+    session.cmd("NOOP", "250").await;
+
+    // Hidden extensions are still accepted when rejection is disabled
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.3".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session
+        .ehlo("mx.foobar.org")
+        .await
+        .assert_not_contains("SIZE")
+        .assert_not_contains("DSN");
+    session
+        .mail_from("<john@example.org> SIZE=1024", "250")
+        .await;
+    session
+        .rcpt_to("<bill@foobar.org> NOTIFY=SUCCESS", "250")
+        .await;
+    session.cmd("BDAT 0 LAST", "502 5.5.1").await;
+
+    // BDAT requires CHUNKING to be offered in an EHLO response
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session.cmd("HELO mx.foobar.org", "250").await;
+    session.mail_from("<john@example.org>", "250").await;
+    session.rcpt_to("<bill@foobar.org>", "250").await;
+    session.cmd("BDAT 0 LAST", "502 5.5.1").await;
+}
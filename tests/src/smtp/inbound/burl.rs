@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, VerifyResponse},
+    },
+    utils::{
+        imap::{ImapConnection, Type},
+        server::TestServerBuilder,
+    },
+};
+use imap_proto::ResponseType;
+use registry::{
+    schema::structs::{Expression, ExpressionMatch, MtaStageAuth},
+    types::list::List,
+};
+
+#[tokio::test]
+async fn burl() {
+    let mut test = TestServerBuilder::new("smtp_burl_test")
+        .await
+        .with_http_listener(19077)
+        .await
+        .with_imap_listener(19078)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Create test users
+    let admin = test.account("admin");
+    admin
+        .create_user_account(
+            "john@example.org",
+            "12345 + extra safety",
+            "John Doe",
+            &[],
+            vec![],
+        )
+        .await;
+    admin
+        .create_user_account(
+            "jane@example.org",
+            "abcde + extra safety",
+            "Jane Smith",
+            &[],
+            vec![],
+        )
+        .await;
+    admin
+        .registry_create_object(MtaStageAuth {
+            sasl_mechanisms: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "is_tls".into(),
+                    then: "[plain, login]".into(),
+                }]),
+                else_: "0".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Append a message and generate an authorized URL for it
+    let message = concat!(
+        "From: John Doe <john@example.org>\r\n",
+        "To: Jane Smith <jane@example.org>\r\n",
+        "Subject: BURL test\r\n",
+        "Content-Type: multipart/mixed; boundary=\"boundary\"\r\n",
+        "\r\n",
+        "--boundary\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "First part\r\n",
+        "--boundary\r\n",
+        "Content-Type: text/plain\r\n",
+        "\r\n",
+        "Second part\r\n",
+        "--boundary--\r\n"
+    );
+    let mut imap = ImapConnection::connect_to(b"_x ", "127.0.0.1:19078").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate("john@example.org", "12345 + extra safety")
+        .await;
+    imap.send("CAPABILITY").await;
+    assert!(
+        imap.assert_read(Type::Tagged, ResponseType::Ok)
+            .await
+            .iter()
+            .any(|line| line.contains(" URLAUTH"))
+    );
+    imap.append("INBOX", message).await;
+
+    let rump =
+        "imap://john%40example.org@mail.example.org/INBOX/;UID=1;URLAUTH=submit+john@example.org";
+    let url = genurlauth(&mut imap, rump).await;
+    assert!(url.starts_with(&format!("{rump}:internal:")), "{url}");
+    let section_url = genurlauth(
+        &mut imap,
+        "imap://john%40example.org@mail.example.org/INBOX/;UID=1/;SECTION=2;URLAUTH=submit+john@example.org",
+    )
+    .await;
+
+    // URLs can only be generated for the authenticated user
+    imap.send(
+        "GENURLAUTH \"imap://jane%40example.org@mail.example.org/INBOX/;UID=1;URLAUTH=submit+jane@example.org\" INTERNAL",
+    )
+    .await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+    imap.send(&format!("GENURLAUTH \"{rump}\" MD5")).await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+
+    // BURL should be advertised to authenticated sessions only
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.stream.tls = true;
+    session.ehlo("mx.example.org").await.assert_contains("BURL");
+    session.cmd(&format!("BURL {url} LAST"), "530 5.7.0").await;
+    session
+        .auth_plain("john@example.org", "12345 + extra safety", "235 2.7.0")
+        .await;
+
+    // Submit the referenced message
+    session.mail_from("john@example.org", "250").await;
+    session.rcpt_to("jane@example.org", "250").await;
+    session.cmd(&format!("BURL {url} LAST"), "250").await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.ends_with(message), "{contents}");
+
+    // BDAT chunks and URL sections can be combined
+    session.mail_from("john@example.org", "250").await;
+    session.rcpt_to("jane@example.org", "250").await;
+    session
+        .cmd(
+            "BDAT 39\r\nFrom: john@example.org\r\nSubject: Hi\r\n",
+            "250",
+        )
+        .await;
+    session
+        .cmd(&format!("BURL {section_url}"), "250 2.5.0")
+        .await;
+    session.cmd(&format!("BURL {url} LAST"), "250").await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(
+        contents.contains("Subject: Hi\r\n\r\nSecond part"),
+        "{contents}"
+    );
+    assert!(contents.ends_with(message), "{contents}");
+
+    // Tampered tokens and unknown messages are rejected
+    session.mail_from("john@example.org", "250").await;
+    session.rcpt_to("jane@example.org", "250").await;
+    let tampered = format!(
+        "{}{}",
+        &url[..url.len() - 1],
+        if url.ends_with('0') { '1' } else { '0' }
+    );
+    session
+        .cmd(&format!("BURL {tampered} LAST"), "554 5.7.0")
+        .await;
+    session.mail_from("john@example.org", "250").await;
+    session.rcpt_to("jane@example.org", "250").await;
+    session
+        .cmd(
+            &format!("BURL {} LAST", url.replace(";UID=1;", ";UID=2;")),
+            "554 5.7.0",
+        )
+        .await;
+
+    // Other users cannot submit URLs authorized for John
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.eval_session_params().await;
+    session.stream.tls = true;
+    session.ehlo("mx.example.org").await;
+    session
+        .auth_plain("jane@example.org", "abcde + extra safety", "235 2.7.0")
+        .await;
+    session.mail_from("jane@example.org", "250").await;
+    session.rcpt_to("john@example.org", "250").await;
+    session.cmd(&format!("BURL {url} LAST"), "554 5.7.0").await;
+
+    // Resetting the mailbox key invalidates all previously issued URLs
+    imap.send("RESETKEY INBOX INTERNAL").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.stream.tls = true;
+    session.ehlo("mx.example.org").await;
+    session
+        .auth_plain("john@example.org", "12345 + extra safety", "235 2.7.0")
+        .await;
+    session.mail_from("john@example.org", "250").await;
+    session.rcpt_to("jane@example.org", "250").await;
+    session.cmd(&format!("BURL {url} LAST"), "554 5.7.0").await;
+    test.assert_no_events();
+}
+
+async fn genurlauth(imap: &mut ImapConnection, rump: &str) -> String {
+    imap.send(&format!("GENURLAUTH \"{rump}\" INTERNAL")).await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .into_iter()
+        .find_map(|line| {
+            line.strip_prefix("* GENURLAUTH \"")
+                .and_then(|url| url.strip_suffix('"'))
+                .map(|url| url.to_string())
+        })
+        .expect("GENURLAUTH response not found")
+}
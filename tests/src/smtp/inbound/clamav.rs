@@ -0,0 +1,257 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::{TestServer, TestServerBuilder};
+use common::config::mailstore::spamfilter::SpamFilterAction;
+use mail_parser::MessageParser;
+use registry::schema::{enums::SpamVirusAction, structs::SpamClamAv};
+use spam_filter::analysis::score::SpamFilterScore;
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+const EICAR: &str = r"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+const MOCK_STREAM_MAX_LENGTH: usize = 4096;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn clamav() {
+    let mut test = TestServerBuilder::new("smtp_clamav")
+        .await
+        .with_http_listener(19058)
+        .await
+        .disable_services()
+        .build()
+        .await;
+    spawn_mock_clamd();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let clean_message = build_message("Hello world");
+    let infected_message = build_message(EICAR);
+    let large_message = build_message(&"A".repeat(MOCK_STREAM_MAX_LENGTH * 2));
+
+    // Infected messages are tagged with the signature name
+    configure(&mut test, "127.0.0.1:9334", SpamVirusAction::Tag, true).await;
+    let score = classify(&test, &infected_message).await.unwrap();
+    assert!(score.headers.contains("CLAM_VIRUS"), "{}", score.headers);
+    assert!(
+        score
+            .headers
+            .contains("X-Spam-Virus: Eicar-Test-Signature\r\n"),
+        "{}",
+        score.headers
+    );
+
+    // Clean messages are not tagged, the pooled connection is reused
+    for _ in 0..2 {
+        let score = classify(&test, &clean_message).await.unwrap();
+        assert!(!score.headers.contains("CLAM_"), "{}", score.headers);
+        assert!(!score.headers.contains("X-Spam-Virus"), "{}", score.headers);
+    }
+
+    // Messages exceeding the clamd stream limit are accepted when failing open
+    let score = classify(&test, &large_message).await.unwrap();
+    assert!(!score.headers.contains("CLAM_"), "{}", score.headers);
+
+    // ...and have the configured action applied when failing closed
+    configure(&mut test, "127.0.0.1:9334", SpamVirusAction::Tag, false).await;
+    let score = classify(&test, &large_message).await.unwrap();
+    assert!(score.headers.contains("CLAM_FAIL"), "{}", score.headers);
+
+    // Messages over the size limit are not scanned, they are tagged when failing closed
+    for fail_open in [true, false] {
+        update_settings(
+            &mut test,
+            SpamClamAv {
+                enable: true,
+                address: "127.0.0.1:9334".into(),
+                timeout: Duration::from_secs(5).into(),
+                max_size: 1024,
+                action: SpamVirusAction::Tag,
+                fail_open,
+                ..Default::default()
+            },
+        )
+        .await;
+        let score = classify(&test, &large_message).await.unwrap();
+        assert_eq!(
+            score.headers.contains("CLAM_FAIL"),
+            !fail_open,
+            "{}",
+            score.headers
+        );
+    }
+
+    // Reject infected messages
+    configure(&mut test, "127.0.0.1:9334", SpamVirusAction::Reject, true).await;
+    assert!(matches!(
+        classify(&test, &infected_message).await,
+        Err(SpamFilterAction::Reject)
+    ));
+    assert!(classify(&test, &clean_message).await.is_ok());
+
+    // Quarantined messages are classified as spam regardless of their score
+    configure(
+        &mut test,
+        "127.0.0.1:9334",
+        SpamVirusAction::Quarantine,
+        true,
+    )
+    .await;
+    let score = classify(&test, &infected_message).await.unwrap();
+    assert!(score.is_spam, "{}", score.headers);
+    assert!(score.results.iter().all(|is_spam| *is_spam));
+    assert!(!classify(&test, &clean_message).await.unwrap().is_spam);
+
+    // Connection failures
+    configure(&mut test, "127.0.0.1:9335", SpamVirusAction::Reject, true).await;
+    let score = classify(&test, &infected_message).await.unwrap();
+    assert!(!score.headers.contains("CLAM_"), "{}", score.headers);
+    configure(&mut test, "127.0.0.1:9335", SpamVirusAction::Reject, false).await;
+    assert!(matches!(
+        classify(&test, &clean_message).await,
+        Err(SpamFilterAction::Reject)
+    ));
+}
+
+async fn configure(test: &mut TestServer, address: &str, action: SpamVirusAction, fail_open: bool) {
+    update_settings(
+        test,
+        SpamClamAv {
+            enable: true,
+            address: address.into(),
+            timeout: Duration::from_secs(5).into(),
+            action,
+            fail_open,
+            ..Default::default()
+        },
+    )
+    .await;
+}
+
+async fn update_settings(test: &mut TestServer, settings: SpamClamAv) {
+    let admin = test.account("admin");
+    admin.registry_update_setting(settings, &[]).await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+}
+
+async fn classify(
+    test: &TestServer,
+    message: &str,
+) -> Result<SpamFilterScore, SpamFilterAction<SpamFilterScore>> {
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.data.rcpt_to.push(Default::default());
+    let parsed_message = MessageParser::new().parse(message).unwrap();
+
+    match session
+        .spam_classify(&parsed_message, &[], None, None, None, None)
+        .await
+    {
+        SpamFilterAction::Allow(score) => Ok(score),
+        action => Err(action),
+    }
+}
+
+fn build_message(body: &str) -> String {
+    format!(
+        concat!(
+            "From: john@example.org\r\n",
+            "To: jane@example.com\r\n",
+            "Subject: Antivirus test\r\n",
+            "\r\n",
+            "{}\r\n"
+        ),
+        body
+    )
+}
+
+fn spawn_mock_clamd() {
+    tokio::spawn(async move {
+        let listener = TcpListener::bind("127.0.0.1:9334")
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock clamd server to 127.0.0.1:9334: {e}");
+            });
+
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(accept_clamd(stream));
+        }
+    });
+}
+
+async fn accept_clamd(stream: TcpStream) {
+    let mut stream = BufReader::new(stream);
+    let mut request_id = 0;
+
+    loop {
+        // Read NUL terminated command
+        let mut command = Vec::new();
+        loop {
+            match stream.read_u8().await {
+                Ok(0) => break,
+                Ok(ch) => command.push(ch),
+                Err(_) => return,
+            }
+        }
+
+        let response = match command.as_slice() {
+            b"zIDSESSION" => continue,
+            b"zEND" => return,
+            b"zPING" => "PONG".to_string(),
+            b"zINSTREAM" => {
+                let mut data = Vec::new();
+                loop {
+                    let len = match stream.read_u32().await {
+                        Ok(len) => len as usize,
+                        Err(_) => return,
+                    };
+                    if len == 0 {
+                        break;
+                    }
+                    let mut chunk = vec![0u8; len];
+                    if stream.read_exact(&mut chunk).await.is_err() {
+                        return;
+                    }
+                    data.extend_from_slice(&chunk);
+                }
+
+                if data.len() > MOCK_STREAM_MAX_LENGTH {
+                    request_id += 1;
+                    let _ = stream
+                        .write_all(
+                            format!("{request_id}: INSTREAM size limit exceeded. ERROR\0")
+                                .as_bytes(),
+                        )
+                        .await;
+                    return;
+                } else if data
+                    .windows(EICAR.len())
+                    .any(|window| window == EICAR.as_bytes())
+                {
+                    "stream: Eicar-Test-Signature FOUND".to_string()
+                } else {
+                    "stream: OK".to_string()
+                }
+            }
+            _ => panic!("Unexpected clamd command: {:?}", command),
+        };
+
+        request_id += 1;
+        if stream
+            .write_all(format!("{request_id}: {response}\0").as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
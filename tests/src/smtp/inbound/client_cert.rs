@@ -0,0 +1,305 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{smtp::session::DummyVerifier, utils::server::TestServerBuilder};
+use common::network::tls::ClientCertificate;
+use rcgen::{
+    BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose, SanType,
+};
+use registry::{
+    schema::{
+        enums::NetworkListenerProtocol,
+        prelude::SocketAddr,
+        structs::{Expression, ExpressionMatch, MemoryLookupKey, MtaStageAuth, NetworkListener},
+    },
+    types::{list::List, map::Map},
+};
+use rustls::{ClientConfig, crypto::aws_lc_rs::default_provider};
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{TlsConnector, client::TlsStream};
+
+struct TestCert {
+    der: Vec<u8>,
+    key: Vec<u8>,
+}
+
+#[tokio::test]
+async fn client_cert() {
+    // Issue client certificates from a trusted and an untrusted CA
+    let ca = test_ca("Test Client CA");
+    let untrusted_ca = test_ca("Untrusted Client CA");
+    let relay_cert = client_cert(
+        &ca,
+        "Relay",
+        SanType::Rfc822Name("relay@example.org".try_into().unwrap()),
+    );
+    let app_cert = client_cert(
+        &ca,
+        "Application server",
+        SanType::DnsName("app.example.org".try_into().unwrap()),
+    );
+    let unknown_cert = client_cert(
+        &ca,
+        "Unknown",
+        SanType::Rfc822Name("unknown@foobar.org".try_into().unwrap()),
+    );
+    let forged_cert = client_cert(
+        &untrusted_ca,
+        "Relay",
+        SanType::Rfc822Name("relay@example.org".try_into().unwrap()),
+    );
+
+    // Port 9932 validates client certificates against the test CA, port 9933
+    // requests certificates without a client CA
+    let mut test = TestServerBuilder::new("smtp_client_cert_test")
+        .await
+        .with_http_listener(19072)
+        .await
+        .with_object(NetworkListener {
+            bind: Map::new(vec![SocketAddr::from_str("0.0.0.0:9932").unwrap()]),
+            name: "smtp-client-ca".to_string(),
+            protocol: NetworkListenerProtocol::Smtp,
+            use_tls: true,
+            tls_implicit: true,
+            tls_request_client_cert: true,
+            tls_client_ca_certificates: Some(ca.1.clone()),
+            ..Default::default()
+        })
+        .await
+        .with_object(NetworkListener {
+            bind: Map::new(vec![SocketAddr::from_str("0.0.0.0:9933").unwrap()]),
+            name: "smtp-no-client-ca".to_string(),
+            protocol: NetworkListenerProtocol::Smtp,
+            use_tls: true,
+            tls_implicit: true,
+            tls_request_client_cert: true,
+            ..Default::default()
+        })
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Certificate attributes are available to the mapping rules
+    let relay = ClientCertificate::parse(&relay_cert.der).unwrap();
+    assert_eq!(relay.emails, ["relay@example.org"]);
+    assert!(relay.subject.contains("CN=Relay"), "{}", relay.subject);
+    let app = ClientCertificate::parse(&app_cert.der).unwrap();
+    assert_eq!(app.dns_names, ["app.example.org"]);
+    assert_eq!(app.fingerprint.len(), 64);
+
+    // Create the relay account
+    let admin = test.account("admin");
+    admin
+        .create_user_account(
+            "relay@example.org",
+            "relay secret + extra safety",
+            "Relay",
+            &[],
+            vec![],
+        )
+        .await;
+
+    // Certificates are not mapped while the mapping is disabled
+    let mut client = SmtpTestClient::connect(9932, &relay_cert).await.unwrap();
+    client.ehlo().await;
+    client.cmd("MAIL FROM:<relay@example.org>", "250").await;
+    client.cmd("RCPT TO:<bill@remote.net>", "550 5.1.2").await;
+
+    // Map certificates by email or fingerprint
+    admin
+        .registry_create_object(MtaStageAuth {
+            require: Expression {
+                else_: "false".into(),
+                ..Default::default()
+            },
+            client_cert_account: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: format!("cert_fingerprint = '{}'", app.fingerprint),
+                    then: "'relay@example.org'".into(),
+                }]),
+                else_: "cert_email".into(),
+                ..Default::default()
+            },
+            client_cert_revocation_list: Some("revoked-client-certs".into()),
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Certificates issued by the client CA that map to an account are allowed to relay
+    for cert in [&relay_cert, &app_cert] {
+        let mut client = SmtpTestClient::connect(9932, cert).await.unwrap();
+        client.ehlo().await;
+        client.cmd("MAIL FROM:<relay@example.org>", "250").await;
+        client.cmd("RCPT TO:<bill@remote.net>", "250").await;
+        client.cmd("DATA", "354").await;
+        client
+            .cmd(
+                "From: relay@example.org\r\nSubject: test\r\n\r\ntest\r\n.",
+                "250",
+            )
+            .await;
+        test.expect_message()
+            .await
+            .read_lines(&test)
+            .await
+            .assert_contains("relay@example.org");
+    }
+
+    // Certificates that do not map to an account are not allowed to relay
+    let mut client = SmtpTestClient::connect(9932, &unknown_cert).await.unwrap();
+    client.ehlo().await;
+    client.cmd("MAIL FROM:<unknown@foobar.org>", "250").await;
+    client.cmd("RCPT TO:<bill@remote.net>", "550 5.1.2").await;
+
+    // Certificates that do not chain up to the client CA are rejected
+    assert!(SmtpTestClient::connect(9932, &forged_cert).await.is_err());
+
+    // Listeners without a client CA do not map certificates to accounts
+    let mut client = SmtpTestClient::connect(9933, &relay_cert).await.unwrap();
+    client.ehlo().await;
+    client.cmd("MAIL FROM:<relay@example.org>", "250").await;
+    client.cmd("RCPT TO:<bill@remote.net>", "550 5.1.2").await;
+    let mut client = SmtpTestClient::connect(9933, &forged_cert).await.unwrap();
+    client.ehlo().await;
+    client.cmd("MAIL FROM:<relay@example.org>", "250").await;
+    client.cmd("RCPT TO:<bill@remote.net>", "550 5.1.2").await;
+
+    // Revoked certificates are rejected when connecting
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(MemoryLookupKey {
+            namespace: "revoked-client-certs".into(),
+            key: relay.fingerprint.clone(),
+            is_glob_pattern: false,
+        })
+        .await;
+    admin.reload_lookup_stores().await;
+    assert_eq!(
+        SmtpTestClient::connect(9932, &relay_cert).await.err(),
+        Some("421 4.7.1 Client certificate has been revoked.".to_string())
+    );
+
+    // Certificates that were not revoked are still accepted
+    let mut client = SmtpTestClient::connect(9932, &app_cert).await.unwrap();
+    client.ehlo().await;
+    client.cmd("MAIL FROM:<relay@example.org>", "250").await;
+    client.cmd("RCPT TO:<bill@remote.net>", "250").await;
+}
+
+fn test_ca(common_name: &str) -> (Issuer<'static, KeyPair>, String) {
+    let key = KeyPair::generate().unwrap();
+    let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    let pem = params.self_signed(&key).unwrap().pem();
+    (Issuer::new(params, key), pem)
+}
+
+fn client_cert(
+    ca: &(Issuer<'static, KeyPair>, String),
+    common_name: &str,
+    san: SanType,
+) -> TestCert {
+    let key = KeyPair::generate().unwrap();
+    let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.subject_alt_names = vec![san];
+    TestCert {
+        der: params.signed_by(&key, &ca.0).unwrap().der().to_vec(),
+        key: key.serialize_der(),
+    }
+}
+
+struct SmtpTestClient {
+    stream: BufReader<TlsStream<TcpStream>>,
+}
+
+impl SmtpTestClient {
+    // Connects presenting the certificate and returns the greeting on failure
+    async fn connect(port: u16, cert: &TestCert) -> Result<Self, String> {
+        let connector = TlsConnector::from(Arc::new(
+            ClientConfig::builder_with_provider(default_provider().into())
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(DummyVerifier))
+                .with_client_auth_cert(
+                    vec![CertificateDer::from(cert.der.clone())],
+                    PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(cert.key.clone())),
+                )
+                .unwrap(),
+        ));
+        let stream = TcpStream::connect(format!("127.0.0.1:{port}"))
+            .await
+            .map_err(|err| err.to_string())?;
+        let stream = connector
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .map_err(|err| err.to_string())?;
+        let mut client = SmtpTestClient {
+            stream: BufReader::new(stream),
+        };
+        let greeting = client.read().await?;
+        if greeting.starts_with("220") {
+            Ok(client)
+        } else {
+            Err(greeting)
+        }
+    }
+
+    async fn ehlo(&mut self) {
+        self.cmd("EHLO app.example.org", "250").await;
+    }
+
+    async fn cmd(&mut self, cmd: &str, expected_code: &str) {
+        self.stream
+            .get_mut()
+            .write_all(format!("{cmd}\r\n").as_bytes())
+            .await
+            .unwrap();
+        let response = self.read().await.unwrap();
+        assert!(
+            response.starts_with(expected_code),
+            "Expected {expected_code:?} for {cmd:?}, got {response:?}"
+        );
+    }
+
+    async fn read(&mut self) -> Result<String, String> {
+        let mut response = String::new();
+        loop {
+            let mut line = String::new();
+            let read =
+                tokio::time::timeout(Duration::from_secs(5), self.stream.read_line(&mut line))
+                    .await
+                    .map_err(|err| err.to_string())?
+                    .map_err(|err| err.to_string())?;
+            if read == 0 {
+                return Err("Connection closed".to_string());
+            }
+            let is_last = line.as_bytes().get(3) != Some(&b'-');
+            response.push_str(line.trim_end());
+            if is_last {
+                return Ok(response);
+            }
+            response.push('\n');
+        }
+    }
+}
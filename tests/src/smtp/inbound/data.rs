@@ -93,6 +93,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             add_date_header: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -100,6 +101,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             add_message_id_header: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -107,6 +109,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             add_received_header: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -114,6 +117,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             add_received_spf_header: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -121,6 +125,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             add_return_path_header: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -128,6 +133,7 @@ async fn data() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             max_messages: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -135,6 +141,7 @@ async fn data() {
                     then: "1".into(),
                 }]),
                 else_: "100".into(),
+                ..Default::default()
             },
             max_received_headers: Expression {
                 else_: "3".into(),
@@ -302,6 +309,129 @@ async fn data() {
         )
         .await;
 
+    // Oversized BDAT chunks abort the transaction
+    test.clear_queue().await;
+    session.params.max_message_size = 150;
+    let bdat = |chunk: &str, is_last: bool| {
+        format!(
+            "BDAT {}{}\r\n{chunk}",
+            chunk.len() + 2,
+            if is_last { " LAST" } else { "" }
+        )
+    };
+    session.mail_from("john@test.org", "250").await;
+    session.rcpt_to("mike@test.com", "250").await;
+    session
+        .cmd(
+            &bdat("From: john@test.org\r\nTo: mike@test.com", false),
+            "250 2.6.0",
+        )
+        .await;
+    session
+        .cmd(&bdat(&"x".repeat(120), false), "552 5.3.4")
+        .await;
+    session
+        .cmd(&bdat("Subject: chunked\r\n\r\nbody", true), "503 5.5.1")
+        .await;
+    session.ingest(b"DATA\r\n").await.unwrap();
+    session.response().assert_code("503 5.5.1");
+
+    // The sender can retry after an aborted BDAT transaction
+    session.mail_from("john@test.org", "250").await;
+    session.rcpt_to("mike@test.com", "250").await;
+    session
+        .cmd(
+            &bdat("From: john@test.org\r\nTo: mike@test.com", false),
+            "250 2.6.0",
+        )
+        .await;
+    session
+        .cmd(&bdat("Subject: chunked\r\n\r\nbody", false), "250 2.6.0")
+        .await;
+    session.cmd("BDAT 0 LAST", "250").await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("Subject: chunked");
+
+    // DATA cannot be mixed with BDAT
+    session.mail_from("john@test.org", "250").await;
+    session.rcpt_to("mike@test.com", "250").await;
+    session
+        .cmd(
+            &bdat("From: john@test.org\r\nTo: mike@test.com", false),
+            "250 2.6.0",
+        )
+        .await;
+    session.ingest(b"DATA\r\n").await.unwrap();
+    session.response().assert_code("503 5.5.1");
+    session.mail_from("john@test.org", "250").await;
+    session.rset().await;
+
+    // Large BDAT transactions are spooled to disk and delivered intact
+    session.params.max_message_size = 10 * 1024 * 1024;
+    let spool_prefix = format!(
+        "stalwart-bdat-{}-{:x}-",
+        std::process::id(),
+        session.data.session_id
+    );
+    let spooled_files = || {
+        std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(&spool_prefix)
+            })
+            .count()
+    };
+    let line = format!("{}\r\n", "y".repeat(98));
+    let body = line.repeat(256 * 1024 / line.len());
+    session.mail_from("john@test.org", "250").await;
+    session.rcpt_to("mike@test.com", "250").await;
+    session
+        .cmd(
+            &bdat("From: john@test.org\r\nSubject: spooled\r\n\r\n", false),
+            "250 2.6.0",
+        )
+        .await;
+    for _ in 0..12 {
+        session
+            .ingest(format!("BDAT {}\r\n{body}", body.len()).as_bytes())
+            .await
+            .unwrap();
+        session.response().assert_code("250 2.6.0");
+    }
+    assert!(session.data.message.len() < 2 * 1024 * 1024);
+    assert_eq!(spooled_files(), 1);
+    session.cmd("BDAT 0 LAST", "250").await;
+    assert_eq!(spooled_files(), 0);
+    let message = test.expect_message().await.read_message(&test).await;
+    assert!(message.contains("Subject: spooled"));
+    assert!(message.ends_with(&body));
+    assert_eq!(
+        message.matches(line.as_str()).count(),
+        12 * (body.len() / line.len())
+    );
+
+    // Aborted transactions remove the spooled chunks
+    session.mail_from("john@test.org", "250").await;
+    session.rcpt_to("mike@test.com", "250").await;
+    for _ in 0..8 {
+        session
+            .ingest(format!("BDAT {}\r\n{body}", body.len()).as_bytes())
+            .await
+            .unwrap();
+        session.response().assert_code("250 2.6.0");
+    }
+    assert_eq!(spooled_files(), 1);
+    session.rset().await;
+    assert_eq!(spooled_files(), 0);
+
     // Make sure store is empty
     test.clear_queue().await;
     let admin = test.account("admin");
@@ -0,0 +1,464 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, VerifyResponse},
+    },
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use common::expr::{Expression as Expr, tokenizer::TokenMap};
+use mail_auth::{
+    common::{parse::TxtRecordParser, verify::DomainKey},
+    dkim::DomainKeyReport,
+    dmarc::Dmarc,
+    spf::Spf,
+};
+use registry::{
+    schema::{
+        enums::ExpressionVariable,
+        prelude::{ObjectType, Property},
+        structs::{
+            CertificateManagement, DkimManagement, DkimReportSettings, DmarcReportSettings,
+            DnsManagement, Domain, Expression, ExpressionMatch, MtaStageData, SenderAuth,
+            SieveSystemScript, SpamSettings, Tenant,
+        },
+    },
+    types::{float::Float, list::List},
+};
+use smtp::queue::RecipientDomain;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn domain_settings() {
+    let mut test = TestServerBuilder::new("smtp_domain_settings_test")
+        .await
+        .with_http_listener(19071)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Create a tenant with default settings for its domains
+    let admin = test.account("admin");
+    let tenant_id = admin
+        .registry_create_object(Tenant {
+            name: "Hosting Tenant".into(),
+            dkim_sign_domain: Some("alpha.org".into()),
+            report_from_address: Some("reports@beta.org".into()),
+            max_message_size: Some(2048),
+            spam_threshold: Some(Float::new(2.0)),
+            ..Default::default()
+        })
+        .await;
+
+    // alpha.org signs with its own keys, beta.org overrides the tenant signer,
+    // gamma.org inherits all settings from the tenant and delta.org, which does
+    // not belong to the tenant, attempts to sign with the keys of alpha.org
+    for (name, member_tenant_id, dkim_sign_domain, report_from_address, spam_threshold, has_keys) in [
+        (
+            "alpha.org",
+            Some(tenant_id),
+            None,
+            Some("postmaster@alpha.org"),
+            Some(10.0),
+            true,
+        ),
+        (
+            "beta.org",
+            Some(tenant_id),
+            Some("beta.org"),
+            None,
+            None,
+            true,
+        ),
+        ("gamma.org", Some(tenant_id), None, None, None, false),
+        ("delta.org", None, Some("alpha.org"), None, None, false),
+    ] {
+        let domain_id = admin
+            .registry_create_object(Domain {
+                name: name.into(),
+                certificate_management: CertificateManagement::Manual,
+                dns_management: DnsManagement::Manual,
+                dkim_management: DkimManagement::Manual,
+                allow_relaying: true,
+                member_tenant_id,
+                dkim_sign_domain: dkim_sign_domain.map(Into::into),
+                report_from_address: report_from_address.map(Into::into),
+                spam_threshold: spam_threshold.map(Float::new),
+                ..Default::default()
+            })
+            .await;
+        if has_keys {
+            admin.create_dkim_signatures(domain_id).await;
+        }
+    }
+    admin.mta_no_auth().await;
+    admin.mta_add_all_headers().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dmarc_verify: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "sender_domain = 'test.net'".into(),
+                    then: "strict".into(),
+                }]),
+                else_: "relaxed".into(),
+            },
+            reverse_ip_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            spf_ehlo_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            spf_from_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            arc_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            dkim_sign_domain: Expression {
+                else_: "sender_domain".into(),
+                ..Default::default()
+            },
+            dkim_verify: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "sender_domain = 'example.com'".into(),
+                    then: "strict".into(),
+                }]),
+                else_: "relaxed".into(),
+            },
+            dkim_strict: false,
+        })
+        .await;
+    admin
+        .registry_create_object(DkimReportSettings {
+            dkim_sign_domain: Expression {
+                else_: "'alpha.org'".into(),
+                ..Default::default()
+            },
+            send_frequency: Expression {
+                else_: "[5, 1s]".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(DmarcReportSettings {
+            failure_dkim_sign_domain: Expression {
+                else_: "sender_domain".into(),
+                ..Default::default()
+            },
+            failure_send_frequency: Expression {
+                else_: "[5, 1s]".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SpamSettings {
+            enable: true,
+            spam_filter_rules_url: None,
+            score_spam: Float::new(5.0),
+            score_reject: Float::new(20.0),
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            script: Expression {
+                else_: "'spam_tags'".into(),
+                ..Default::default()
+            },
+            enable_spam_filter: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SieveSystemScript {
+            contents: SCRIPT.into(),
+            description: None,
+            is_active: true,
+            name: "spam_tags".into(),
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Domain settings fall back to the tenant settings
+    let settings = test
+        .server
+        .domain_settings("gamma.org")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.dkim_sign_domain.as_deref(), Some("alpha.org"));
+    assert_eq!(
+        settings.report_from_address.as_deref(),
+        Some("reports@beta.org")
+    );
+    assert_eq!(settings.max_message_size, Some(2048));
+    assert_eq!(settings.spam_threshold, Some(2.0));
+    let settings = test
+        .server
+        .domain_settings("beta.org")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.dkim_sign_domain.as_deref(), Some("beta.org"));
+    assert_eq!(
+        settings.report_from_address.as_deref(),
+        Some("reports@beta.org")
+    );
+    let settings = test
+        .server
+        .domain_settings("alpha.org")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.dkim_sign_domain.as_deref(), Some("alpha.org"));
+    assert_eq!(settings.spam_threshold, Some(10.0));
+    let settings = test
+        .server
+        .domain_settings("delta.org")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settings.max_message_size, None);
+    assert_eq!(settings.spam_threshold, None);
+
+    // Domain settings are available to expressions
+    let token_map = TokenMap::default().with_variables(&[ExpressionVariable::RcptDomain]);
+    for (expr, rcpt_domain, expected) in [
+        (
+            "domain_setting(rcpt_domain, 'report-address')",
+            "alpha.org",
+            "postmaster@alpha.org",
+        ),
+        (
+            "domain_setting(rcpt_domain, 'report-address')",
+            "gamma.org",
+            "reports@beta.org",
+        ),
+        (
+            "'size ' + domain_setting(rcpt_domain, 'max-message-size')",
+            "beta.org",
+            "size 2048",
+        ),
+        (
+            "domain_setting(rcpt_domain, 'dkim-sign-domain')",
+            "unknown.org",
+            "",
+        ),
+    ] {
+        assert_eq!(
+            test.server
+                .eval_expr::<String, _>(
+                    &Expr::parse(&token_map, expr),
+                    &RecipientDomain::new(rcpt_domain),
+                    ObjectType::Account.singleton(),
+                    Property::AccountName,
+                    0
+                )
+                .await
+                .unwrap(),
+            expected,
+            "failed for '{expr}' with {rcpt_domain}"
+        );
+    }
+
+    // Add SPF and DKIM records
+    test.server.txt_add(
+        "mx.example.com",
+        Spf::parse(b"v=spf1 ip4:10.0.0.1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "example.com",
+        Spf::parse(b"v=spf1 ip4:10.0.0.1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "default._domainkey.example.com",
+        DomainKey::parse(
+            concat!(
+                "v=DKIM1; t=s; p=MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQ",
+                "KBgQDwIRP/UC3SBsEmGqZ9ZJW3/DkMoGeLnQg1fWn7/zYt",
+                "IxN2SnFCjxOCKG9v3b4jYfcTNh5ijSsq631uBItLa7od+v",
+                "/RtdC2UzJ1lWT947qR+Rcac2gbto/NMqJ0fzfVjH4OuKhi",
+                "tdY9tf6mcwGjaNBcWToIMmPSPDdQPNUYckcQ2QIDAQAB",
+            )
+            .as_bytes(),
+        )
+        .unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "test.net",
+        Spf::parse(b"v=spf1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "_report._domainkey.example.com",
+        DomainKeyReport::parse(b"ra=dkim-failures; rp=100; rr=d:o:p:s:u:v:x;").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+
+    // Messages are signed with the signers configured for each domain
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.com").await;
+    for (sender, expected_domain) in [
+        ("bill@alpha.org", "alpha.org"),
+        ("bill@beta.org", "beta.org"),
+        ("bill@gamma.org", "alpha.org"),
+    ] {
+        session
+            .send_message(sender, &["jdoe@foobar.net"], "test:no_dkim", "250")
+            .await;
+        test.expect_message()
+            .await
+            .read_lines(&test)
+            .await
+            .assert_contains(&format!(
+                "DKIM-Signature: v=1; a=rsa-sha256; s=rsa; d={expected_domain};"
+            ));
+    }
+
+    // The keys of a domain owned by another tenant are never used
+    session
+        .send_message(
+            "bill@delta.org",
+            &["jdoe@foobar.net"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_not_contains("DKIM-Signature:");
+
+    // Failure reports are sent from the report address of the recipient domain
+    for (rcpt, expected_from) in [
+        ("jdoe@alpha.org", "postmaster@alpha.org"),
+        ("jdoe@gamma.org", "reports@beta.org"),
+    ] {
+        session
+            .send_message(
+                "bill@example.com",
+                &[rcpt],
+                "test:invalid_dkim",
+                "550 5.7.20",
+            )
+            .await;
+        let message = test.expect_message().await;
+        assert_eq!(&*message.message.return_path, expected_from);
+        message
+            .read_lines(&test)
+            .await
+            .assert_contains(&format!("<{expected_from}>"))
+            .assert_contains("To: dkim-failures@example.com")
+            .assert_contains("Auth-Failure: bodyhash");
+    }
+
+    // DMARC failure reports are sent from the report address of the recipient
+    // domain and signed with the keys of the domain of that address
+    test.server.txt_add(
+        "_dmarc.football.example.com",
+        Dmarc::parse(b"v=DMARC1; p=reject; fo=1; ruf=mailto:dmarc-failures@football.example.com")
+            .unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    for (rcpt, expected_from, expected_domain) in [
+        ("jdoe@alpha.org", "postmaster@alpha.org", "alpha.org"),
+        ("jdoe@gamma.org", "reports@beta.org", "beta.org"),
+    ] {
+        session
+            .send_message("joe@test.net", &[rcpt], "test:no_dkim", "550 5.7.1")
+            .await;
+        let message = test.expect_message().await;
+        assert_eq!(&*message.message.return_path, expected_from);
+        assert_eq!(
+            message.message.recipients.last().unwrap().address(),
+            "dmarc-failures@football.example.com"
+        );
+        message
+            .read_lines(&test)
+            .await
+            .assert_contains(&format!("<{expected_from}>"))
+            .assert_contains(&format!(
+                "DKIM-Signature: v=1; a=rsa-sha256; s=rsa; d={expected_domain};"
+            ))
+            .assert_contains("To: dmarc-failures@football.example.com")
+            .assert_contains("Feedback-Type: auth-failure")
+            .assert_contains("Auth-Failure: dmarc");
+    }
+
+    // Messages exceeding the size limit of the recipient domain are rejected
+    session
+        .send_message(
+            "bill@remote.org",
+            &["jdoe@gamma.org"],
+            &message("Large attachment", &"A".repeat(4096)),
+            "552 5.2.3",
+        )
+        .await;
+    test.assert_no_events();
+
+    // The spam threshold of the recipient domain classifies the message
+    for (rcpt, expected_class) in [("jdoe@alpha.org", "ham"), ("jdoe@gamma.org", "spam")] {
+        session
+            .send_message(
+                "bill@remote.org",
+                &[rcpt],
+                &message("Updated invoice", "Please see the attached document."),
+                "250",
+            )
+            .await;
+        let contents = test.expect_message().await.read_message(&test).await;
+        assert!(contents.contains("INVOICE_FRAUD (4.00)"), "{contents}");
+        assert!(
+            contents.contains(&format!("X-Spam-Score: {expected_class},")),
+            "{contents}"
+        );
+    }
+}
+
+const SCRIPT: &str = r#"require ["variables", "header", "vnd.stalwart.expressions"];
+
+if header :contains "subject" "invoice" {
+    eval "add_spam_tag('INVOICE_FRAUD', 4.0)";
+}
+"#;
+
+fn message(subject: &str, body: &str) -> String {
+    format!(
+        concat!(
+            "From: Bill <bill@remote.org>\r\n",
+            "To: John <jdoe@example.org>\r\n",
+            "Subject: {}\r\n",
+            "Message-ID: <domain-settings@remote.org>\r\n",
+            "\r\n",
+            "{}"
+        ),
+        subject, body
+    )
+}
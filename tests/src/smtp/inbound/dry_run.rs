@@ -0,0 +1,330 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::{TestMessage, TestQueueEvent, TestReportingEvent},
+        session::{TestSession, load_test_message},
+    },
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use mail_auth::{
+    common::{parse::TxtRecordParser, verify::DomainKey},
+    dmarc::Dmarc,
+    spf::Spf,
+};
+use registry::{
+    schema::structs::{
+        CertificateManagement, DkimManagement, DmarcReportSettings, DnsManagement, Domain,
+        Expression, MtaStageData, SenderAuth, SpamSettings,
+    },
+    types::float::Float,
+};
+use std::time::{Duration, Instant};
+use trc::{Collector, MetricType};
+
+#[tokio::test]
+async fn auth_dry_run() {
+    let mut test = TestServerBuilder::new("smtp_auth_dry_run_test")
+        .await
+        .with_http_listener(19076)
+        .await
+        .disable_services()
+        .capture_queue()
+        .capture_reporting()
+        .build()
+        .await;
+
+    // Evaluate SPF, DKIM, ARC and DMARC in dry-run mode
+    let admin = test.account("admin");
+    let domain_id = admin
+        .registry_create_object(Domain {
+            name: "localdomain.org".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            allow_relaying: true,
+            ..Default::default()
+        })
+        .await;
+    admin.create_dkim_signatures(domain_id).await;
+    admin.mta_no_auth().await;
+    admin.mta_add_all_headers().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dmarc_verify: Expression {
+                else_: "strict_dry_run".into(),
+                ..Default::default()
+            },
+            reverse_ip_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            spf_ehlo_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            spf_from_verify: Expression {
+                else_: "strict_dry_run".into(),
+                ..Default::default()
+            },
+            arc_verify: Expression {
+                else_: "strict_dry_run".into(),
+                ..Default::default()
+            },
+            dkim_sign_domain: Expression {
+                else_: "'localdomain.org'".into(),
+                ..Default::default()
+            },
+            dkim_verify: Expression {
+                else_: "strict_dry_run".into(),
+                ..Default::default()
+            },
+            dkim_strict: false,
+        })
+        .await;
+    admin
+        .registry_create_object(DmarcReportSettings {
+            failure_dkim_sign_domain: Expression {
+                else_: "'localdomain.org'".into(),
+                ..Default::default()
+            },
+            failure_send_frequency: Expression {
+                else_: "[1, 1s]".into(),
+                ..Default::default()
+            },
+            aggregate_send_frequency: Expression {
+                else_: "daily".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SpamSettings {
+            enable: true,
+            spam_filter_rules_url: None,
+            score_spam: Float::new(5.0),
+            score_reject: Float::new(20.0),
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            enable_spam_filter: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Add SPF, DKIM and DMARC records
+    test.server.txt_add(
+        "example.com",
+        Spf::parse(b"v=spf1 ip4:10.0.0.1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "test.net",
+        Spf::parse(b"v=spf1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "ed._domainkey.example.com",
+        DomainKey::parse(
+            concat!(
+                "v=DKIM1; k=ed25519; ",
+                "p=11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo="
+            )
+            .as_bytes(),
+        )
+        .unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "default._domainkey.example.com",
+        DomainKey::parse(
+            concat!(
+                "v=DKIM1; t=s; p=MIGfMA0GCSqGSIb3DQEBAQUAA4GNADCBiQ",
+                "KBgQDwIRP/UC3SBsEmGqZ9ZJW3/DkMoGeLnQg1fWn7/zYt",
+                "IxN2SnFCjxOCKG9v3b4jYfcTNh5ijSsq631uBItLa7od+v",
+                "/RtdC2UzJ1lWT947qR+Rcac2gbto/NMqJ0fzfVjH4OuKhi",
+                "tdY9tf6mcwGjaNBcWToIMmPSPDdQPNUYckcQ2QIDAQAB",
+            )
+            .as_bytes(),
+        )
+        .unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+    test.server.txt_add(
+        "_dmarc.example.com",
+        Dmarc::parse(
+            concat!(
+                "v=DMARC1; p=reject; sp=quarantine; np=None; aspf=s; adkim=s; fo=1;",
+                "rua=mailto:dmarc-feedback@example.com;",
+                "ruf=mailto:dmarc-failures@example.com"
+            )
+            .as_bytes(),
+        )
+        .unwrap(),
+        Instant::now() + Duration::from_secs(5),
+    );
+
+    let metrics = [
+        MetricType::SmtpSpfDryRunReject,
+        MetricType::SmtpDkimDryRunReject,
+        MetricType::SmtpArcDryRunReject,
+        MetricType::SmtpDmarcDryRunReject,
+    ];
+    let counters = metrics.map(Collector::read_metric);
+
+    // Messages that strict mode would reject are delivered and tagged
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.com").await;
+    session
+        .send_message(
+            "joe@test.net",
+            &["jdoe@localdomain.org"],
+            "test:invalid_dkim",
+            "250",
+        )
+        .await;
+
+    // DMARC failure reports are sent as if the message was rejected
+    test.read_event().await.assert_refresh();
+    test.read_event().await.assert_refresh();
+    let (report, message): (Vec<_>, Vec<_>) = test
+        .read_queued_messages()
+        .await
+        .into_iter()
+        .partition(|queued| {
+            queued.message.recipients.last().unwrap().address() == "dmarc-failures@example.com"
+        });
+    assert_eq!((report.len(), message.len()), (1, 1));
+    report[0]
+        .read_lines(&test)
+        .await
+        .assert_contains("To: dmarc-failures@example.com")
+        .assert_contains("Auth-Failure: dmarc")
+        .assert_contains("Delivery-Result: reject");
+    let contents = message[0].read_message(&test).await;
+    for tag in [
+        "DRYRUN_SPF_REJECT",
+        "DRYRUN_DKIM_REJECT",
+        "DRYRUN_DMARC_REJECT",
+    ] {
+        assert!(contents.contains(tag), "missing {tag}: {contents}");
+    }
+    assert!(!contents.contains("DRYRUN_ARC_REJECT"), "{contents}");
+    assert!(
+        contents.contains(
+            "X-Dry-Run-Result: DRYRUN_SPF_REJECT, DRYRUN_DKIM_REJECT, DRYRUN_DMARC_REJECT"
+        ),
+        "{contents}"
+    );
+    assert!(contents.contains("dmarc=fail"), "{contents}");
+    assert_eq!(
+        test.read_report().await.unwrap_dmarc().domain,
+        "example.com"
+    );
+
+    // Each policy counts the messages it would have rejected
+    assert_eq!(
+        metrics.map(Collector::read_metric),
+        [
+            counters[0] + 1.0,
+            counters[1] + 1.0,
+            counters[2],
+            counters[3] + 1.0
+        ]
+    );
+
+    // Messages passing verification are not tagged, and dry-run results
+    // added by other hosts are removed
+    session
+        .send_message(
+            "bill@example.com",
+            &["jdoe@localdomain.org"],
+            &format!(
+                "X-Dry-Run-Result: DRYRUN_DKIM_REJECT\r\n{}",
+                load_test_message("dkim", "messages")
+            ),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(!contents.contains("DRYRUN_"), "{contents}");
+    assert!(contents.contains("dmarc=pass"), "{contents}");
+    test.read_report().await.unwrap_dmarc();
+    assert_eq!(
+        metrics.map(Collector::read_metric),
+        [
+            counters[0] + 1.0,
+            counters[1] + 1.0,
+            counters[2],
+            counters[3] + 1.0
+        ]
+    );
+
+    // Messages are tagged when the spam filter is disabled
+    let admin = test.account("admin");
+    admin.mta_add_all_headers().await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    test.clear_queue().await;
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.com").await;
+    session
+        .send_message(
+            "joe@test.net",
+            &["jdoe@localdomain.org"],
+            "test:invalid_dkim",
+            "250",
+        )
+        .await;
+    let message = loop {
+        test.read_event().await.assert_refresh();
+        if let Some(message) = test
+            .read_queued_messages()
+            .await
+            .into_iter()
+            .find(|queued| {
+                queued.message.recipients.last().unwrap().address() == "jdoe@localdomain.org"
+            })
+        {
+            break message;
+        }
+    };
+    let contents = message.read_message(&test).await;
+    assert!(
+        contents.contains(
+            "X-Dry-Run-Result: DRYRUN_SPF_REJECT, DRYRUN_DKIM_REJECT, DRYRUN_DMARC_REJECT"
+        ),
+        "{contents}"
+    );
+    assert!(!contents.contains("X-Spam-Result"), "{contents}");
+    test.read_report().await.unwrap_dmarc();
+    assert_eq!(
+        metrics.map(Collector::read_metric),
+        [
+            counters[0] + 2.0,
+            counters[1] + 2.0,
+            counters[2],
+            counters[3] + 2.0
+        ]
+    );
+}
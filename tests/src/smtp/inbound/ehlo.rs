@@ -38,6 +38,7 @@ async fn ehlo() {
                     then: "1h".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             mt_priority: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -45,6 +46,7 @@ async fn ehlo() {
                     then: "nsep".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -66,6 +68,7 @@ async fn ehlo() {
                     then: "1024".into(),
                 }]),
                 else_: "2048".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -0,0 +1,155 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::server::TestServerBuilder,
+};
+use common::auth::{AccountCache, AccountInfo};
+use registry::{
+    schema::{
+        prelude::Property,
+        structs::{MailingList, MtaStageRcpt},
+    },
+    types::map::Map,
+};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn address_expansion() {
+    let mut test = TestServerBuilder::new("smtp_expansion_test")
+        .await
+        .with_http_listener(19070)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Create test users
+    let admin = test.account("admin");
+    for (name, secret, description) in [
+        ("john@foobar.org", "12345 + extra safety", "John Doe"),
+        ("jane@foobar.org", "abcde + extra safety", "Jane Smith"),
+        ("bill@foobar.org", "p4ssw0rd + extra safety", "Bill Foobar"),
+    ] {
+        admin
+            .create_user_account(name, secret, description, &[], vec![])
+            .await;
+    }
+
+    // Create lists that reference each other, a deeply nested list and a large list
+    let domain_id = admin.find_or_create_domain("foobar.org").await;
+    for (name, recipients) in [
+        (
+            "team",
+            vec!["john@foobar.org".into(), "ops@foobar.org".into()],
+        ),
+        (
+            "ops",
+            vec!["jane@foobar.org".into(), "team@foobar.org".into()],
+        ),
+        (
+            "nest1",
+            vec!["nest2@foobar.org".into(), "bill@foobar.org".into()],
+        ),
+        ("nest2", vec!["nest3@foobar.org".into()]),
+        ("nest3", vec!["nest4@foobar.org".into()]),
+        ("nest4", vec!["john@foobar.org".into()]),
+        (
+            "all",
+            (1..=10)
+                .map(|i| format!("member{i}@remote.org"))
+                .collect::<Vec<String>>(),
+        ),
+    ] {
+        admin
+            .registry_create_object(MailingList {
+                domain_id,
+                name: name.into(),
+                recipients: Map::new(recipients),
+                ..Default::default()
+            })
+            .await;
+    }
+
+    // Add test settings
+    admin.mta_no_auth().await;
+    admin
+        .registry_update_setting(
+            MtaStageRcpt {
+                max_expansion_depth: 3,
+                max_expansion_recipients: 5,
+                expansion_trace_header: true,
+                ..Default::default()
+            },
+            &[
+                Property::MaxExpansionDepth,
+                Property::MaxExpansionRecipients,
+                Property::ExpansionTraceHeader,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.org").await;
+
+    // Lists that include each other are expanded once
+    session.mail_from("sender@example.org", "250").await;
+    session.rcpt_to("team@foobar.org", "250").await;
+    assert_eq!(
+        rcpt_addresses(&session.data.rcpt_to),
+        ["john@foobar.org", "jane@foobar.org"]
+    );
+
+    // The expansion trace is added to the message
+    session.data("test:no_dkim", "250").await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("X-Expanded-From: team@foobar.org")
+        .assert_contains("X-Expanded-From: team@foobar.org, ops@foobar.org");
+
+    // Lists nested beyond the maximum depth are not expanded
+    session.mail_from("sender@example.org", "250").await;
+    session.rcpt_to("nest1@foobar.org", "250").await;
+    assert_eq!(rcpt_addresses(&session.data.rcpt_to), ["bill@foobar.org"]);
+    session.rset().await;
+
+    // Inbound messages to lists over the recipient limit are delivered to the first members
+    session.mail_from("sender@example.org", "250").await;
+    session.rcpt_to("all@foobar.org", "250").await;
+    assert_eq!(session.data.rcpt_to.len(), 5);
+    session.rset().await;
+
+    // Authenticated senders are rejected when the list is over the limit
+    session.data.authenticated_as = Some(AccountInfo {
+        account_id: u32::MAX,
+        addresses: vec!["sender@example.org".into()],
+        account: Arc::new(AccountCache {
+            name: "sender@example.org".into(),
+            ..Default::default()
+        }),
+    });
+    session.mail_from("sender@example.org", "250").await;
+    session.rcpt_to("all@foobar.org", "550 5.5.3").await;
+    assert!(session.data.rcpt_to.is_empty());
+    session.rcpt_to("team@foobar.org", "250").await;
+    assert_eq!(session.data.rcpt_to.len(), 2);
+}
+
+fn rcpt_addresses(rcpt_to: &[smtp::core::SessionAddress]) -> Vec<&str> {
+    rcpt_to
+        .iter()
+        .map(|rcpt| rcpt.address_lcase.as_str())
+        .collect()
+}
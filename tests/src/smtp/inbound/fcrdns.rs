@@ -0,0 +1,166 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use mail_auth::IprevResult;
+use registry::{
+    schema::structs::{Expression, ExpressionMatch, SenderAuth},
+    types::list::List,
+};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn fcrdns() {
+    let mut test = TestServerBuilder::new("smtp_fcrdns_test")
+        .await
+        .with_http_listener(19074)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Require FCrDNS except for a partner IP and a partner EHLO domain
+    let admin = test.account("admin");
+    let sender_auth = SenderAuth {
+        reverse_ip_verify: Expression {
+            else_: "relaxed".into(),
+            ..Default::default()
+        },
+        reverse_ip_require: Expression {
+            match_: List::from_iter([
+                ExpressionMatch {
+                    if_: "remote_ip = '10.0.0.9'".into(),
+                    then: "false".into(),
+                },
+                ExpressionMatch {
+                    if_: "helo_domain = 'mx.partner.org'".into(),
+                    then: "false".into(),
+                },
+            ]),
+            else_: "true".into(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    admin.registry_create_object(sender_auth.clone()).await;
+    admin.mta_no_auth().await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    let valid_until = Instant::now() + Duration::from_secs(60);
+    test.server.ptr_add(
+        "10.0.0.1".parse().unwrap(),
+        vec!["mx1.foobar.org.".to_string()],
+        valid_until,
+    );
+    test.server.ipv4_add(
+        "mx1.foobar.org.",
+        vec!["10.0.0.1".parse().unwrap()],
+        valid_until,
+    );
+    for ip in ["10.0.0.2", "10.0.0.9"] {
+        test.server.ptr_add(
+            ip.parse().unwrap(),
+            vec!["mx2.foobar.org.".to_string()],
+            valid_until,
+        );
+    }
+    test.server.ipv4_add(
+        "mx2.foobar.org.",
+        vec!["192.168.0.2".parse().unwrap()],
+        valid_until,
+    );
+
+    // PTR and forward lookups match
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    assert!(session.params.iprev_require);
+    session.ehlo("mx1.foobar.org").await;
+    session.mail_from("john@foobar.org", "250").await;
+    assert_eq!(
+        session.data.iprev.as_ref().unwrap().result(),
+        &IprevResult::Pass
+    );
+
+    // Forward lookup does not match the client IP
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx2.foobar.org").await;
+    session.mail_from("john@foobar.org", "550 5.7.25").await;
+    assert!(matches!(
+        session.data.iprev.as_ref().unwrap().result(),
+        IprevResult::Fail(_)
+    ));
+
+    // No PTR record for the client IP
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.3".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx3.foobar.org").await;
+    session.mail_from("john@foobar.org", "550 5.7.25").await;
+    assert!(matches!(
+        session.data.iprev.as_ref().unwrap().result(),
+        IprevResult::PermError(_) | IprevResult::Fail(_)
+    ));
+
+    // Exempted by IP address
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.9".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    assert!(!session.params.iprev_require);
+    session.ehlo("mx2.foobar.org").await;
+    session.mail_from("john@foobar.org", "250").await;
+    assert!(matches!(
+        session.data.iprev.as_ref().unwrap().result(),
+        IprevResult::Fail(_)
+    ));
+
+    // Exempted by EHLO domain
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    assert!(session.params.iprev_require);
+    session.ehlo("mx.partner.org").await;
+    assert!(!session.params.iprev_require);
+    session.mail_from("john@foobar.org", "250").await;
+
+    // Reply with a temporary failure when configured
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(SenderAuth {
+            reverse_ip_require_temp_fail: true,
+            ..sender_auth
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx2.foobar.org").await;
+    session.mail_from("john@foobar.org", "450 4.7.25").await;
+    session
+        .ingest(b"MAIL FROM:<john@foobar.org>\r\n")
+        .await
+        .unwrap();
+    session
+        .response()
+        .assert_contains("Forward-confirmed reverse DNS is required");
+}
@@ -0,0 +1,203 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::DummyIo,
+    utils::server::{TestServer, TestServerBuilder},
+};
+use common::config::mailstore::spamfilter::SpamFilterAction;
+use mail_parser::MessageParser;
+use registry::{
+    schema::structs::{Expression, ExpressionMatch, SpamRule, SpamRuleAny, SpamSettings},
+    types::{ipmask::IpAddrOrMask, list::List, map::Map},
+};
+use smtp::core::Session;
+use std::str::FromStr;
+
+const IP_TAGS: [(&str, &str); 5] = [
+    ("203.0.113.5", "ORIGINAL_CLIENT"),
+    ("192.0.2.1", "UNTRUSTED_HOP"),
+    ("198.51.100.7", "UNTRUSTED_RELAY"),
+    ("10.0.0.1", "TRUSTED_RELAY"),
+    ("10.0.0.3", "TRUSTED_HOP"),
+];
+
+#[tokio::test]
+#[serial_test::serial]
+async fn trusted_forwarders() {
+    let mut test = TestServerBuilder::new("smtp_trusted_forwarders")
+        .await
+        .with_http_listener(19057)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Trust the 10.0.0.0/24 relays and tag the IP evaluated by the spam filter
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(SpamSettings {
+            trusted_forwarders: Map::new(vec![IpAddrOrMask::from_str("10.0.0.0/24").unwrap()]),
+            trusted_forwarder_depth: 2,
+            spam_filter_rules_url: None,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SpamRule::Any(SpamRuleAny {
+            name: "EVALUATED_IP".into(),
+            condition: Expression {
+                match_: List::from_iter(IP_TAGS.iter().map(|(ip, tag)| ExpressionMatch {
+                    if_: format!("remote_ip == '{ip}'"),
+                    then: format!("'{tag}'"),
+                })),
+                else_: "false".into(),
+                ..Default::default()
+            },
+            enable: true,
+            ..Default::default()
+        }))
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    for (remote_ip, received, expected_ip, expected_helo) in [
+        // Single trusted hop
+        (
+            "10.0.0.1",
+            vec!["mail.example.org", "203.0.113.5"],
+            "203.0.113.5",
+            Some("mail.example.org"),
+        ),
+        // Stacked trusted hops
+        (
+            "10.0.0.1",
+            vec![
+                "relay2.internal",
+                "10.0.0.2",
+                "mail.example.org",
+                "203.0.113.5",
+            ],
+            "203.0.113.5",
+            Some("mail.example.org"),
+        ),
+        // Headers beyond the configured depth are ignored
+        (
+            "10.0.0.1",
+            vec![
+                "relay2.internal",
+                "10.0.0.2",
+                "relay3.internal",
+                "10.0.0.3",
+                "mail.example.org",
+                "203.0.113.5",
+            ],
+            "10.0.0.3",
+            Some("relay3.internal"),
+        ),
+        // Untrusted hosts cannot forge earlier hops
+        (
+            "10.0.0.1",
+            vec![
+                "spoofer.example.net",
+                "192.0.2.1",
+                "mail.example.org",
+                "203.0.113.5",
+            ],
+            "192.0.2.1",
+            Some("spoofer.example.net"),
+        ),
+        // Received headers are ignored for untrusted connections
+        (
+            "198.51.100.7",
+            vec!["mail.example.org", "203.0.113.5"],
+            "198.51.100.7",
+            None,
+        ),
+    ] {
+        let mut message = String::new();
+        for hop in received.chunks(2) {
+            message.push_str(&format!(
+                concat!(
+                    "Received: from {helo} ({helo} [{ip}])\r\n",
+                    "\tby relay.example.com (Stalwart) with ESMTP id 1234;\r\n",
+                    "\tSat, 20 Nov 2021 14:22:01 -0800\r\n"
+                ),
+                helo = hop[0],
+                ip = hop[1]
+            ));
+        }
+        message.push_str(concat!(
+            "From: john@example.org\r\n",
+            "To: jane@example.com\r\n",
+            "Subject: Forwarded message\r\n",
+            "\r\n",
+            "Test message\r\n"
+        ));
+
+        assert_evaluated_ip(
+            &new_session(&test, remote_ip),
+            &message,
+            expected_ip,
+            expected_helo,
+        )
+        .await;
+    }
+
+    // Malformed headers leave the connecting IP untouched
+    let message = concat!(
+        "Received: this is not a valid received header\r\n",
+        "Received: from mail.example.org (mail.example.org [203.0.113.5])\r\n",
+        "\tby relay.example.com; Sat, 20 Nov 2021 14:22:01 -0800\r\n",
+        "From: john@example.org\r\n",
+        "Subject: Malformed\r\n",
+        "\r\n",
+        "Test message\r\n"
+    );
+    assert_evaluated_ip(&new_session(&test, "10.0.0.1"), message, "10.0.0.1", None).await;
+}
+
+fn new_session(test: &TestServer, remote_ip: &str) -> Session<DummyIo> {
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = remote_ip.into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session
+}
+
+async fn assert_evaluated_ip(
+    session: &Session<DummyIo>,
+    message: &str,
+    expected_ip: &str,
+    expected_helo: Option<&str>,
+) {
+    let parsed_message = MessageParser::new().parse(message).unwrap();
+
+    // Verify the client extracted from the Received headers
+    let forwarded = session.forwarded_client(&parsed_message);
+    if expected_ip != session.data.remote_ip_str {
+        let forwarded = forwarded.as_ref().unwrap();
+        assert_eq!(forwarded.ip.to_string(), expected_ip);
+        assert_eq!(forwarded.helo, expected_helo);
+    } else {
+        assert!(forwarded.is_none());
+    }
+
+    let headers = match session
+        .spam_classify(&parsed_message, &[], None, None, None, None)
+        .await
+    {
+        SpamFilterAction::Allow(score) => score.headers,
+        _ => panic!("Unexpected spam filter action"),
+    };
+    for (ip, tag) in IP_TAGS {
+        assert_eq!(
+            headers.contains(tag),
+            ip == expected_ip,
+            "expected {expected_ip} to be evaluated: {headers}"
+        );
+    }
+}
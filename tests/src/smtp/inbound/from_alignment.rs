@@ -0,0 +1,269 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::server::TestServerBuilder,
+};
+use registry::{
+    schema::{
+        prelude::Property,
+        structs::{Expression, ExpressionMatch, MtaStageAuth, MtaStageData, SpamSettings},
+    },
+    types::list::List,
+};
+
+#[tokio::test]
+async fn from_alignment() {
+    let mut test = TestServerBuilder::new("smtp_from_alignment_test")
+        .await
+        .with_http_listener(19060)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Create test users
+    let admin = test.account("admin");
+    for (name, secret, description, aliases) in [
+        (
+            "john@example.org",
+            "12345 + extra safety",
+            "John Doe",
+            &["john.doe@example.org"][..],
+        ),
+        (
+            "jane@example.org",
+            "abcde + extra safety",
+            "Jane Smith",
+            &[],
+        ),
+    ] {
+        admin
+            .create_user_account(name, secret, description, aliases, vec![])
+            .await;
+    }
+
+    // Add test settings
+    admin
+        .registry_create_object(SpamSettings {
+            enable: false,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageAuth {
+            require: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            sasl_mechanisms: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "is_tls".into(),
+                    then: "[plain, login]".into(),
+                }]),
+                else_: "0".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            from_alignment: Expression {
+                else_: "reject".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.stream.tls = true;
+    session.ehlo("mx.example.org").await;
+    session
+        .auth_plain("john@example.org", "12345 + extra safety", "235 2.7.0")
+        .await;
+
+    // Messages from the primary address or an alias should be accepted
+    for from in ["john@example.org", "john.doe@example.org"] {
+        session
+            .send_message(
+                "john@example.org",
+                &["jane@example.org"],
+                &message(&format!("John Doe <{from}>")),
+                "250",
+            )
+            .await;
+        test.expect_message()
+            .await
+            .read_lines(&test)
+            .await
+            .assert_contains(&format!("From: John Doe <{from}>"))
+            .assert_not_contains("X-Original-From");
+    }
+
+    // Messages with a foreign From address should be rejected
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.org"],
+            &message("Jane Smith <jane@example.org>"),
+            "550 5.7.1",
+        )
+        .await;
+    test.assert_no_events();
+
+    // Mixed From headers should be rejected as well
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.org"],
+            &message("john@example.org, jane@example.org"),
+            "550 5.7.1",
+        )
+        .await;
+    test.assert_no_events();
+
+    // Switch to rewrite mode
+    let admin = test.account("admin");
+    admin
+        .registry_update_setting(
+            MtaStageData {
+                from_alignment: Expression {
+                    else_: "rewrite".into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            &[Property::FromAlignment],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Foreign From addresses should be replaced with the primary address
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.org"],
+            &message("\"Jane Smith\" <jane@example.org>"),
+            "250",
+        )
+        .await;
+    let lines = test
+        .expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("X-Original-From: \"Jane Smith\" <jane@example.org>");
+    let from = lines
+        .iter()
+        .find(|line| line.starts_with("From:"))
+        .expect("From header");
+    assert!(
+        from.contains("Jane Smith") && from.contains("<john@example.org>"),
+        "unexpected From header: {from}"
+    );
+
+    // Aligned messages should be left untouched
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.org"],
+            &message("John Doe <john.doe@example.org>"),
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("From: John Doe <john.doe@example.org>")
+        .assert_not_contains("X-Original-From");
+
+    // Only submission listeners are subject to alignment checks
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.local_port = 25;
+    session.eval_session_params().await;
+    session.stream.tls = true;
+    session.ehlo("mx.example.org").await;
+    session
+        .auth_plain("john@example.org", "12345 + extra safety", "235 2.7.0")
+        .await;
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.org"],
+            &message("Jane Smith <jane@example.org>"),
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("From: Jane Smith <jane@example.org>")
+        .assert_not_contains("X-Original-From");
+
+    // Unauthenticated sessions are not subject to alignment checks
+    let admin = test.account("admin");
+    admin
+        .registry_update_setting(
+            MtaStageAuth {
+                require: Expression {
+                    else_: "false".into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            &[Property::Require],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.foobar.org").await;
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jane@example.org"],
+            &message("Bill <bill@foobar.org>"),
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("From: Bill <bill@foobar.org>")
+        .assert_not_contains("X-Original-From");
+}
+
+fn message(from: &str) -> String {
+    format!(
+        concat!(
+            "From: {}\r\n",
+            "To: jane@example.org\r\n",
+            "Subject: TPS Report\r\n",
+            "\r\n",
+            "I'm going to need those TPS reports ASAP."
+        ),
+        from
+    )
+}
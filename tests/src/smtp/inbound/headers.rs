@@ -0,0 +1,188 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{smtp::session::TestSession, utils::server::TestServerBuilder};
+use common::config::smtp::queue::QueueName;
+use registry::{
+    schema::{
+        prelude::ObjectType,
+        structs::{
+            Expression, ExpressionMatch, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+            MtaDeliverySchedule, MtaOutboundStrategy, MtaVirtualQueue,
+        },
+    },
+    types::list::List,
+};
+use smtp::queue::QueueEnvelope;
+
+#[tokio::test]
+async fn header_expressions() {
+    let mut test = TestServerBuilder::new("smtp_header_expressions_test")
+        .await
+        .with_http_listener(19085)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Route bulk mail to its own queue at DATA time and mailing lists at queue time
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "header('precedence') == 'bulk'".into(),
+                    then: "'bulk'".into(),
+                }]),
+                else_: "'remote'".into(),
+                ..Default::default()
+            },
+            route: Expression {
+                match_: List::from_iter([
+                    ExpressionMatch {
+                        if_: "contains(header('List-Id'), 'announce.example.org')".into(),
+                        then: "'local'".into(),
+                    },
+                    ExpressionMatch {
+                        if_: "count(headers('Received')) > 1".into(),
+                        then: "'relay'".into(),
+                    },
+                    ExpressionMatch {
+                        if_: "header('X-Missing') != ''".into(),
+                        then: "'missing'".into(),
+                    },
+                ]),
+                else_: "'mx'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let queue_id = admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "bulkq".into(),
+            threads_per_node: 1,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaDeliverySchedule {
+            name: "bulk".into(),
+            expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                expire: 86_400_000u64.into(),
+                max_attempts: None,
+            }),
+            queue_id,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin.mta_allow_relaying().await;
+    admin.mta_disable_spam_filter().await;
+    admin.mta_allow_non_fqdn().await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_destroy_all(ObjectType::MtaInboundThrottle)
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let mut session = test.new_mta_session();
+    session.ehlo("mx.example.org").await;
+
+    // Header values parsed during the session are used at DATA time
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.net"],
+            concat!(
+                "From: john@example.org\r\n",
+                "To: jane@example.net\r\n",
+                "PRECEDENCE:  bulk\r\n",
+                "Subject: Bulk message\r\n",
+                "\r\n",
+                "Test message\r\n"
+            ),
+            "250",
+        )
+        .await;
+    let message = test.consume_message().await;
+    assert_eq!(
+        message.message.recipients[0].queue,
+        QueueName::new("bulkq").unwrap()
+    );
+
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.net"],
+            concat!(
+                "From: john@example.org\r\n",
+                "To: jane@example.net\r\n",
+                "Subject: Regular message\r\n",
+                "\r\n",
+                "Test message\r\n"
+            ),
+            "250",
+        )
+        .await;
+    let message = test.consume_message().await;
+    assert_eq!(
+        message.message.recipients[0].queue,
+        QueueName::new("remote").unwrap()
+    );
+
+    // Queued messages have their headers read from the blob store
+    for (headers, expected_route) in [
+        (
+            concat!(
+                "List-Id: Announcements\r\n",
+                " <announce.example.org>\r\n",
+                "Received: from mx.example.org\r\n"
+            ),
+            "local",
+        ),
+        (
+            concat!(
+                "Received: from relay.example.org\r\n",
+                "Received: from mx.example.org\r\n"
+            ),
+            "relay",
+        ),
+        ("Received: from mx.example.org\r\n", "mx"),
+    ] {
+        session
+            .send_message(
+                "john@example.org",
+                &["jane@example.net"],
+                &format!(
+                    concat!(
+                        "{}",
+                        "From: john@example.org\r\n",
+                        "To: jane@example.net\r\n",
+                        "Subject: Mailing list\r\n",
+                        "\r\n",
+                        "Test message\r\n"
+                    ),
+                    headers
+                ),
+                "250",
+            )
+            .await;
+        let message = test.consume_message().await;
+        let envelope = QueueEnvelope::new(&message.message, &message.message.recipients[0]);
+        assert_eq!(
+            test.server
+                .eval_if::<String, _>(&test.server.core.smtp.queue.route, &envelope, 0)
+                .await
+                .unwrap(),
+            expected_route
+        );
+    }
+}
@@ -34,6 +34,7 @@ async fn limits() {
                     then: "500ms".into(),
                 }]),
                 else_: "60m".into(),
+                ..Default::default()
             },
             timeout: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -41,6 +42,7 @@ async fn limits() {
                     then: "500ms".into(),
                 }]),
                 else_: "30m".into(),
+                ..Default::default()
             },
             transfer_limit: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -48,6 +50,7 @@ async fn limits() {
                     then: "10".into(),
                 }]),
                 else_: "1024".into(),
+                ..Default::default()
             },
         })
         .await;
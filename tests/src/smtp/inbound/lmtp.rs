@@ -0,0 +1,204 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{DummyIo, TestSession, VerifyResponse, test_server_instance},
+    utils::server::{TestServer, TestServerBuilder},
+};
+use common::{config::server::ServerProtocol, network::ServerInstance};
+use registry::schema::{
+    prelude::ObjectType,
+    structs::{Expression, MtaStageData, SenderAuth},
+};
+use serde_json::json;
+use smtp::core::Session;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn lmtp_local_delivery() {
+    let mut test = TestServerBuilder::new("smtp_lmtp_local_delivery_test")
+        .await
+        .with_http_listener(19100)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Jane's mailbox is too small for the test message
+    let admin = test.account("admin");
+    let john = admin
+        .create_user_account(
+            "john@foobar.org",
+            "12345 + extra safety",
+            "John",
+            &[],
+            vec![],
+        )
+        .await;
+    let jane = admin
+        .create_user_account(
+            "jane@foobar.org",
+            "abcde + extra safety",
+            "Jane",
+            &[],
+            vec![],
+        )
+        .await;
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                "quotas": {
+                    "maxDiskQuota": 1024
+                }
+            }),
+        )
+        .await;
+    admin.mta_no_auth().await;
+    admin.mta_disable_spam_filter().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dmarc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            reverse_ip_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            spf_ehlo_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            spf_from_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            arc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            dkim_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Messages are queued by default, with one reply per recipient
+    let mut session = lmtp_session(&test).await;
+    send_message(&mut session, &["john@foobar.org", "jane@foobar.org"])
+        .await
+        .assert_count("250 2.0.0 Message queued", 2);
+    assert_eq!(test.consume_message().await.message.recipients.len(), 2);
+
+    // Enable local delivery
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(MtaStageData {
+            lmtp_local_delivery: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Each recipient gets its own delivery status, in RCPT order
+    let mut session = lmtp_session(&test).await;
+    let replies = send_message(&mut session, &["john@foobar.org", "jane@foobar.org"]).await;
+    assert_eq!(replies.len(), 2, "{replies:?}");
+    assert!(replies[0].starts_with("250 2.1.5"), "{replies:?}");
+    assert!(replies[1].starts_with("452 4.2.2"), "{replies:?}");
+    test.assert_no_events();
+    assert!(
+        test.server
+            .get_used_quota_account(john.id().document_id())
+            .await
+            .unwrap()
+            > 0
+    );
+    assert_eq!(
+        test.server
+            .get_used_quota_account(jane.id().document_id())
+            .await
+            .unwrap(),
+        0
+    );
+
+    // Pipelined commands are answered in order
+    session
+        .ingest(
+            concat!(
+                "MAIL FROM:<sender@example.net>\r\n",
+                "RCPT TO:<jane@foobar.org>\r\n",
+                "RCPT TO:<john@foobar.org>\r\n",
+                "DATA\r\n"
+            )
+            .as_bytes(),
+        )
+        .await
+        .unwrap();
+    session.response().assert_code("354").assert_count("250", 3);
+    session.ingest(message().as_bytes()).await.unwrap();
+    let replies = session.response();
+    assert_eq!(replies.len(), 2, "{replies:?}");
+    assert!(replies[0].starts_with("452 4.2.2"), "{replies:?}");
+    assert!(replies[1].starts_with("250 2.1.5"), "{replies:?}");
+    test.assert_no_events();
+
+    // SMTP sessions still queue messages
+    let mut session = test.new_mta_session();
+    session.ehlo("mx.example.org").await;
+    send_message(&mut session, &["john@foobar.org", "jane@foobar.org"])
+        .await
+        .assert_count("250 2.0.0 Message queued", 1);
+    test.consume_message().await;
+}
+
+async fn lmtp_session(test: &TestServer) -> Session<DummyIo> {
+    let mut session = test.new_mta_session();
+    session.instance = Arc::new(ServerInstance {
+        protocol: ServerProtocol::Lmtp,
+        ..test_server_instance()
+    });
+    session.cmd("LHLO mx.example.org", "250").await;
+    session
+}
+
+async fn send_message(session: &mut Session<DummyIo>, rcpts: &[&str]) -> Vec<String> {
+    session.mail_from("sender@example.net", "250").await;
+    for rcpt in rcpts {
+        session.rcpt_to(rcpt, "250").await;
+    }
+    session.cmd("DATA", "354").await;
+    session.ingest(message().as_bytes()).await.unwrap();
+    session.response()
+}
+
+fn message() -> String {
+    let mut message = concat!(
+        "From: sender@example.net\r\n",
+        "To: john@foobar.org, jane@foobar.org\r\n",
+        "Subject: LMTP test\r\n",
+        "\r\n"
+    )
+    .to_string();
+    while message.len() < 2048 {
+        message.push_str("This message does not fit in Jane's mailbox.\r\n");
+    }
+    message.push_str("\r\n.\r\n");
+    message
+}
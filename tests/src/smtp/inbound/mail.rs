@@ -75,6 +75,7 @@ async fn mail() {
                     then: "1d".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             future_release: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -82,6 +83,7 @@ async fn mail() {
                     then: "1d".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             mt_priority: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -89,6 +91,7 @@ async fn mail() {
                     then: "nsep".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             require_tls: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -96,6 +99,7 @@ async fn mail() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -117,6 +121,7 @@ async fn mail() {
                     then: "2048".into(),
                 }]),
                 else_: "1024".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -13,7 +13,10 @@ use crate::{
 };
 use ahash::AHashSet;
 use common::{
-    config::smtp::session::{Milter, MilterVersion, Stage},
+    config::smtp::{
+        queue::QueueName,
+        session::{Milter, MilterVersion, Stage},
+    },
     expr::if_block::IfBlock,
     manager::application::Resource,
 };
@@ -26,9 +29,13 @@ use registry::{
     schema::{
         enums::{self, MtaStage},
         prelude::{ObjectType, Property},
-        structs::{Expression, MtaHook, MtaMilter, MtaStageRcpt},
+        structs::{
+            Expression, ExpressionMatch, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+            MtaDeliverySchedule, MtaHook, MtaMilter, MtaOutboundStrategy, MtaStageRcpt,
+            MtaVirtualQueue,
+        },
     },
-    types::map::Map,
+    types::{list::List, map::Map},
 };
 use serde::Deserialize;
 use smtp::{
@@ -236,6 +243,39 @@ async fn mta_hook_session() {
             ..Default::default()
         })
         .await;
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "header('X-Hello') == 'World'".into(),
+                    then: "'hooked'".into(),
+                }]),
+                else_: "'remote'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let queue_id = admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "hookq".into(),
+            threads_per_node: 1,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaDeliverySchedule {
+            name: "hooked".into(),
+            expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                expire: 86_400_000u64.into(),
+                max_attempts: None,
+            }),
+            queue_id,
+            description: None,
+            ..Default::default()
+        })
+        .await;
     admin.reload_settings().await;
     test.reload_core();
     test.expect_reload_settings().await;
@@ -304,7 +344,7 @@ async fn mta_hook_session() {
         .await;
     test.assert_no_events();
 
-    // Test accept with header addition
+    // Test accept with header addition, expressions see the added header
     session
         .send_message(
             "0@doe.org",
@@ -313,8 +353,12 @@ async fn mta_hook_session() {
             "250 2.0.0",
         )
         .await;
-    test.expect_message()
-        .await
+    let message = test.expect_message().await;
+    assert_eq!(
+        message.message.recipients[0].queue,
+        QueueName::new("hookq").unwrap()
+    );
+    message
         .read_lines(&test)
         .await
         .assert_contains("X-Hello: World")
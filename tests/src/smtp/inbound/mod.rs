@@ -13,7 +13,7 @@ use registry::{schema::prelude::ObjectType, types::ObjectImpl};
 use smtp::queue::{Message, MessageWrapper, QueueId, QueuedMessage};
 use std::time::Duration;
 use store::{
-    Deserialize, IterateParams, U64_LEN, ValueKey,
+    Deserialize, IterateParams, ValueKey,
     write::{AlignedBytes, Archive, QueueClass, ValueClass, key::DeserializeBigEndian},
 };
 use tokio::sync::mpsc::error::TryRecvError;
@@ -22,19 +22,40 @@ use types::id::Id;
 pub mod antispam;
 pub mod asn;
 pub mod auth;
+pub mod banner;
 pub mod basic;
+pub mod burl;
+pub mod clamav;
+pub mod client_cert;
 pub mod data;
 pub mod dkim2;
 pub mod dmarc;
+pub mod domain_settings;
+pub mod dry_run;
 pub mod ehlo;
+pub mod expansion;
+pub mod fcrdns;
+pub mod forwarded;
+pub mod from_alignment;
+pub mod headers;
 pub mod limits;
+pub mod lmtp;
 pub mod mail;
 pub mod milter;
+pub mod queue_meta;
 pub mod rcpt;
+pub mod reputation;
+pub mod response;
 pub mod rewrite;
+pub mod rspamd;
 pub mod scripts;
+pub mod sender_verify;
 pub mod sign;
+pub mod size_limits;
+pub mod spam_script;
+pub mod stream_filter;
 pub mod throttle;
+pub mod tls_policy;
 pub mod vrfy;
 
 impl TestServer {
@@ -128,39 +149,11 @@ impl TestServer {
 
     pub async fn read_queued_events(&self) -> Vec<store::write::QueueEvent> {
         let mut events = Vec::new();
+        let mut stream = self.server.store().queue_events(0, u64::MAX, true);
 
-        let from_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: 0,
-                queue_id: 0,
-                queue_name: [0; 8],
-            },
-        )));
-        let to_key = ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(
-            store::write::QueueEvent {
-                due: u64::MAX,
-                queue_id: u64::MAX,
-                queue_name: [u8::MAX; 8],
-            },
-        )));
-
-        self.server
-            .store()
-            .iterate(
-                IterateParams::new(from_key, to_key).ascending().no_values(),
-                |key, _| {
-                    events.push(store::write::QueueEvent {
-                        due: key.deserialize_be_u64(0)?,
-                        queue_id: key.deserialize_be_u64(U64_LEN)?,
-                        queue_name: key[U64_LEN + 1..U64_LEN + 9]
-                            .try_into()
-                            .expect("Queue name must be 8 bytes"),
-                    });
-                    Ok(true)
-                },
-            )
-            .await
-            .unwrap();
+        while let Some(event) = stream.next().await.unwrap() {
+            events.push(event);
+        }
 
         events
     }
@@ -0,0 +1,273 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::{TestMessage, TestQueueEvent},
+        session::TestSession,
+    },
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use common::config::smtp::queue::QueueName;
+use registry::{
+    schema::{
+        enums::MtaProtocol,
+        prelude::ObjectType,
+        structs::{
+            Expression, ExpressionMatch, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+            MtaDeliverySchedule, MtaOutboundStrategy, MtaRoute, MtaRouteRelay, MtaStageData,
+            MtaVirtualQueue, QueuedMessage, SieveSystemScript,
+        },
+    },
+    types::list::List,
+};
+use sieve::compiler::ErrorType;
+use std::time::{Duration, Instant};
+use types::id::Id;
+
+const SCRIPT: &str = r#"require ["variables", "header", "editheader", "vnd.stalwart.expressions"];
+
+if header :contains "subject" "campaign" {
+    eval "queue_meta_set('Campaign', 'spring')";
+    eval "queue_meta_set('tenant.id', 'acme')";
+}
+
+if not eval "queue_meta_set('stalwart.internal', 'value')" {
+    addheader "X-Meta-Reserved" "rejected";
+}
+
+if not eval "queue_meta_set('invalid key', 'value')" {
+    addheader "X-Meta-Invalid" "rejected";
+}
+"#;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn queue_metadata() {
+    let mut test = TestServerBuilder::new("smtp_queue_meta_test")
+        .await
+        .with_http_listener(19099)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_queue_meta_remote")
+        .await
+        .with_http_listener(19103)
+        .await
+        .with_smtp_listener(9929)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Route messages tagged by the DATA stage script using their metadata
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(MtaStageData {
+            script: Expression {
+                else_: "'queue_meta'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SieveSystemScript {
+            contents: SCRIPT.into(),
+            description: None,
+            is_active: true,
+            name: "queue_meta".into(),
+        })
+        .await;
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "meta('campaign') == 'spring'".into(),
+                    then: "'campaign'".into(),
+                }]),
+                else_: "'remote'".into(),
+                ..Default::default()
+            },
+            route: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "meta('tenant.id') == 'acme'".into(),
+                    then: "'relay'".into(),
+                }]),
+                else_: "'mx'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaRoute::Relay(MtaRouteRelay {
+            address: "relay.example.net".into(),
+            implicit_tls: false,
+            allow_invalid_certs: true,
+            name: "relay".into(),
+            port: 9929,
+            protocol: MtaProtocol::Smtp,
+            ..Default::default()
+        }))
+        .await;
+    let queue_id = admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "campaignq".into(),
+            threads_per_node: 1,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaDeliverySchedule {
+            name: "campaign".into(),
+            expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                expire: 86_400_000u64.into(),
+                max_attempts: None,
+            }),
+            queue_id,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin.mta_allow_relaying().await;
+    admin.mta_disable_spam_filter().await;
+    admin.mta_allow_non_fqdn().await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_destroy_all(ObjectType::MtaInboundThrottle)
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+    test.server.ipv4_add(
+        "relay.example.net",
+        vec!["127.0.0.1".parse().unwrap()],
+        Instant::now() + Duration::from_secs(10),
+    );
+
+    // Metadata cannot be set by untrusted scripts
+    let err = test
+        .server
+        .core
+        .sieve
+        .untrusted_compiler
+        .compile(SCRIPT.as_bytes())
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.error_type(),
+            ErrorType::InvalidExpression(expr) if expr.contains("queue_meta_set")
+        ),
+        "{err:?}"
+    );
+
+    let mut session = test.new_mta_session();
+    session.ehlo("mx.example.org").await;
+
+    // Messages without metadata use the default queue and route
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.net"],
+            &test_message("Hello"),
+            "250",
+        )
+        .await;
+    let message = test.expect_message().await;
+    assert_eq!(message.message.queue_meta().count(), 0);
+    assert_eq!(
+        message.message.recipients[0].queue,
+        QueueName::new("remote").unwrap()
+    );
+
+    // Reserved and malformed keys are refused
+    let contents = message.read_message(&test).await;
+    assert!(contents.contains("X-Meta-Reserved: rejected"), "{contents}");
+    assert!(contents.contains("X-Meta-Invalid: rejected"), "{contents}");
+    let due = test.last_queued_due().await;
+    message.remove(&test.server, due.into()).await;
+
+    // Metadata set by the script is used to select the queue and route
+    session
+        .send_message(
+            "john@example.org",
+            &["jane@example.net"],
+            &test_message("Spring campaign"),
+            "250",
+        )
+        .await;
+    let message = test.expect_message().await;
+    assert_eq!(
+        message.message.queue_meta().collect::<Vec<_>>(),
+        vec![("campaign", "spring"), ("tenant.id", "acme")]
+    );
+    assert_eq!(
+        message.message.recipients[0].queue,
+        QueueName::new("campaignq").unwrap()
+    );
+
+    // Metadata is persisted and exposed by the management API
+    let admin = test.account("admin");
+    let queued = admin
+        .registry_get::<QueuedMessage>(Id::from(message.queue_id))
+        .await;
+    assert_eq!(
+        queued
+            .metadata
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<_>>(),
+        vec![("campaign", "spring"), ("tenant.id", "acme")]
+    );
+
+    // The message is delivered through the route selected by its metadata
+    test.delivery_attempt_for_queue(message.queue_id, "campaignq")
+        .await
+        .try_deliver(test.server.clone());
+    test.read_event().await.assert_done();
+    let delivered = remote.expect_message().await;
+    assert_eq!(
+        delivered
+            .message
+            .recipients
+            .iter()
+            .map(|rcpt| rcpt.address())
+            .collect::<Vec<_>>(),
+        vec!["jane@example.net"]
+    );
+    assert!(
+        delivered
+            .read_message(&remote)
+            .await
+            .contains("Subject: Spring campaign")
+    );
+}
+
+fn test_message(subject: &str) -> String {
+    format!(
+        concat!(
+            "From: John <john@example.org>\r\n",
+            "To: Jane <jane@example.net>\r\n",
+            "Subject: {}\r\n",
+            "\r\n",
+            "Test message\r\n"
+        ),
+        subject
+    )
+}
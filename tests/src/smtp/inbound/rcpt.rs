@@ -70,6 +70,7 @@ async fn rcpt() {
                     then: "false".into(),
                 }]),
                 else_: "true".into(),
+                ..Default::default()
             },
             max_failures: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -77,6 +78,7 @@ async fn rcpt() {
                     then: "3".into(),
                 }]),
                 else_: "100".into(),
+                ..Default::default()
             },
             max_recipients: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -84,6 +86,7 @@ async fn rcpt() {
                     then: "3".into(),
                 }]),
                 else_: "5".into(),
+                ..Default::default()
             },
             wait_on_fail: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -91,6 +94,7 @@ async fn rcpt() {
                     then: "5ms".into(),
                 }]),
                 else_: "1s".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -103,6 +107,7 @@ async fn rcpt() {
                     then: "false".into(),
                 }]),
                 else_: "true".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
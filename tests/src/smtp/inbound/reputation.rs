@@ -0,0 +1,236 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{DummyIo, TestSession, build_test_message},
+    },
+    utils::{
+        dns::DnsCache,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use common::expr::{Variable, functions::ResolveVariable};
+use mail_auth::{common::parse::TxtRecordParser, spf::Spf};
+use registry::{
+    schema::{
+        enums::ExpressionVariable,
+        structs::{Expression, MtaStageData, SenderAuth, SieveSystemScript, SpamSettings},
+    },
+    types::float::Float,
+};
+use smtp::core::Session;
+use std::time::{Duration, Instant};
+
+const SCRIPT: &str = r#"require ["variables", "header", "vnd.stalwart.expressions"];
+
+if header :contains "subject" "pharmacy" {
+    eval "add_spam_tag('PHARMA_SPAM', 10.0)";
+}
+"#;
+
+#[tokio::test]
+async fn sender_reputation() {
+    let mut test = TestServerBuilder::new("smtp_sender_reputation_test")
+        .await
+        .with_http_listener(19087)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Enable sender reputation with greylisting for unknown senders
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin.mta_allow_relaying().await;
+    admin
+        .registry_create_object(SpamSettings {
+            enable: true,
+            spam_filter_rules_url: None,
+            score_spam: Float::new(5.0),
+            greylist_for: Some(3_600_000u64.into()),
+            reputation_enable: true,
+            reputation_min_messages: 3,
+            reputation_good_score: Float::new(0.9),
+            reputation_bad_score: Float::new(0.5),
+            reputation_skip_greylist: true,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SenderAuth {
+            spf_from_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            spf_ehlo_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            reverse_ip_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            dkim_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            arc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            dmarc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            script: Expression {
+                else_: "'spam_tags'".into(),
+                ..Default::default()
+            },
+            enable_spam_filter: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SieveSystemScript {
+            contents: SCRIPT.into(),
+            description: None,
+            is_active: true,
+            name: "spam_tags".into(),
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    test.server.txt_add(
+        "remote.org",
+        Spf::parse(b"v=spf1 ip4:10.0.0.1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(60),
+    );
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session.ehlo("mx.remote.org").await;
+
+    // New senders start with a neutral score, are greylisted and tagged
+    session.mail_from("bill@remote.org", "250").await;
+    assert_eq!(reputation_score(&session), 0.5);
+    session.rcpt_to("john@example.org", "452 4.2.2").await;
+    session.rset().await;
+    for _ in 0..3 {
+        session
+            .send_message(
+                "bill@remote.org",
+                &["john@example.org"],
+                &build_test_message("bill@remote.org", "Quarterly report"),
+                "250",
+            )
+            .await;
+        let contents = test.expect_message().await.read_message(&test).await;
+        assert!(contents.contains("SENDER_REP_NEW"), "{contents}");
+    }
+
+    // A clean history skips greylisting and is tagged as good
+    session.mail_from("bill@remote.org", "250").await;
+    assert_eq!(reputation_score(&session), 1.0);
+    session.rcpt_to("jane@example.org", "250").await;
+    session
+        .data(
+            &build_test_message("bill@remote.org", "Quarterly report"),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.contains("SENDER_REP_GOOD"), "{contents}");
+
+    // A spam burst lowers the score
+    for _ in 0..5 {
+        session
+            .send_message(
+                "bill@remote.org",
+                &["john@example.org"],
+                &build_test_message("bill@remote.org", "Cheap pharmacy"),
+                "250",
+            )
+            .await;
+        let contents = test.expect_message().await.read_message(&test).await;
+        assert!(contents.contains("X-Spam-Score: spam,"), "{contents}");
+    }
+    session.mail_from("bill@remote.org", "250").await;
+    let score = reputation_score(&session);
+    assert!(score < 0.5, "{score}");
+    session.rcpt_to("alice@example.org", "452 4.2.2").await;
+    session.rset().await;
+    session
+        .send_message(
+            "bill@remote.org",
+            &["john@example.org"],
+            &build_test_message("bill@remote.org", "Quarterly report"),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.contains("SENDER_REP_BAD"), "{contents}");
+    assert!(!contents.contains("SENDER_REP_GOOD"), "{contents}");
+
+    // Inspect the statistics of the sender domain and IP address
+    for subject in ["ip/10.0.0.1", "domain/remote.org"] {
+        let stats = reputation_request(&test, subject, false).await;
+        assert_eq!(stats["messages"], 10.0, "{stats}");
+        assert_eq!(stats["spam"], 5.0, "{stats}");
+        assert_eq!(stats["bounces"], 0.0, "{stats}");
+        assert_eq!(stats["score"], 0.5, "{stats}");
+    }
+
+    // Resetting the reputation clears the history of the sender
+    for subject in ["ip/10.0.0.1", "domain/remote.org"] {
+        let stats = reputation_request(&test, subject, true).await;
+        assert_eq!(stats["messages"], 0.0, "{stats}");
+        assert_eq!(stats["score"], 0.5, "{stats}");
+    }
+    session
+        .send_message(
+            "bill@remote.org",
+            &["john@example.org"],
+            &build_test_message("bill@remote.org", "Quarterly report"),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.contains("SENDER_REP_NEW"), "{contents}");
+    test.assert_no_events();
+}
+
+fn reputation_score(session: &Session<DummyIo>) -> f64 {
+    match session.resolve_variable(ExpressionVariable::SenderReputation) {
+        Variable::Float(score) => score,
+        other => panic!("Unexpected reputation {other:?}"),
+    }
+}
+
+async fn reputation_request(test: &TestServer, subject: &str, reset: bool) -> serde_json::Value {
+    let admin = test.account("admin");
+    let url = format!("{}/api/reputation/{subject}", admin.base_url());
+    let response = if reset {
+        admin.http_delete_raw(&url).await
+    } else {
+        admin.http_get_raw(&url, None).await
+    };
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
@@ -0,0 +1,148 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::server::TestServerBuilder,
+};
+use registry::{
+    schema::{
+        enums::MtaResponseId,
+        structs::{Expression, ExpressionMatch, MtaResponse, MtaStageData, MtaStageRcpt},
+    },
+    types::list::List,
+};
+
+#[tokio::test]
+async fn custom_responses() {
+    let mut test = TestServerBuilder::new("smtp_response_test")
+        .await
+        .with_http_listener(19065)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Override the relay and unknown mailbox responses
+    let admin = test.account("admin");
+    admin
+        .create_user_account(
+            "john@foobar.org",
+            "12345 + extra safety",
+            "John Doe",
+            &[],
+            vec![],
+        )
+        .await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(MtaStageRcpt {
+            allow_relaying: Expression {
+                else_: "false".into(),
+                ..Default::default()
+            },
+            max_failures: Expression {
+                else_: "10".into(),
+                ..Default::default()
+            },
+            wait_on_fail: Expression {
+                else_: "5ms".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            max_received_headers: Expression {
+                else_: "1".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaResponse {
+            response_id: MtaResponseId::RelayDenied,
+            message: Expression {
+                else_: concat!(
+                    "'Relaying to ' + rcpt_domain + ' is not permitted (ref ' + ",
+                    "session_id + '), see https://support.example.org'"
+                )
+                .into(),
+                ..Default::default()
+            },
+        })
+        .await;
+    admin
+        .registry_create_object(MtaResponse {
+            response_id: MtaResponseId::MailboxNotFound,
+            message: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "rcpt == 'jane@foobar.org'".into(),
+                    then: format!("'  Unknown   user {}  '", "x".repeat(300)),
+                }]),
+                else_: "''".into(),
+                ..Default::default()
+            },
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.data.session_id = 8_675_309;
+    session.eval_session_params().await;
+    session.ehlo("mx.remote.org").await;
+    session.mail_from("bill@remote.org", "250").await;
+
+    // Variables are substituted in the custom text
+    session
+        .ingest(b"RCPT TO:<external@domain.com>\r\n")
+        .await
+        .unwrap();
+    session
+        .response()
+        .assert_code("550 5.1.2 Relaying to domain.com is not permitted")
+        .assert_contains("(ref 8675309), see https://support.example.org");
+
+    // Whitespace is collapsed and long texts are truncated
+    session
+        .ingest(b"RCPT TO:<jane@foobar.org>\r\n")
+        .await
+        .unwrap();
+    let response = session.response();
+    assert_eq!(response.len(), 1, "{response:?}");
+    assert!(
+        response[0].starts_with("550 5.1.2 Unknown user xxx"),
+        "{response:?}"
+    );
+    assert_eq!(response[0].len(), "550 5.1.2 ".len() + 256, "{response:?}");
+
+    // Empty results and undefined responses fall back to the built-in text
+    session
+        .rcpt_to("tom@foobar.org", "550 5.1.2 Mailbox does not exist.")
+        .await;
+    session.rset().await;
+    session.mail_from("bill@remote.org", "250").await;
+    session.rcpt_to("john@foobar.org", "250").await;
+    session
+        .data(
+            concat!(
+                "Received: from mx1.remote.org\r\n",
+                "Received: from mx2.remote.org\r\n",
+                "From: bill@remote.org\r\n",
+                "To: john@foobar.org\r\n",
+                "Subject: Loop\r\n",
+                "\r\n",
+                "Test message.\r\n"
+            ),
+            "450 4.4.6 Too many Received headers. Possible loop detected.",
+        )
+        .await;
+}
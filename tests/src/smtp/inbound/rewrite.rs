@@ -52,6 +52,7 @@ pub async fn address_rewrite() {
                     then: "$1 + '+' + $2 + '@' + $3".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             script: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -59,6 +60,7 @@ pub async fn address_rewrite() {
                     then: "'mail'".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -72,6 +74,7 @@ pub async fn address_rewrite() {
                     then: "$1 + '+' + $2 + '@' + $3".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             script: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -79,6 +82,7 @@ pub async fn address_rewrite() {
                     then: "'rcpt'".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             allow_relaying: Expression {
                 else_: "true".into(),
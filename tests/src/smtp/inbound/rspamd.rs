@@ -0,0 +1,150 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{smtp::session::build_test_message, utils::server::TestServerBuilder};
+use registry::{
+    schema::{
+        prelude::Property,
+        structs::{SecretKeyOptional, SecretKeyValue, SpamSettings, SpamTag, SpamTagScore},
+    },
+    types::float::Float,
+};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+const SECRET: &str = "rspamd shared secret";
+
+#[tokio::test]
+async fn rspamd_checkv2() {
+    let test = TestServerBuilder::new("smtp_rspamd_test")
+        .await
+        .with_http_listener(19061)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Enable the rspamd endpoint
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(SpamSettings {
+            enable: true,
+            spam_filter_rules_url: None,
+            enable_rspamd_api: true,
+            rspamd_api_secret: SecretKeyOptional::Value(SecretKeyValue {
+                secret: SECRET.to_string(),
+            }),
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SpamTag::Score(SpamTagScore {
+            score: Float::new(100.0),
+            tag: "GTUBE_TEST".to_string(),
+        }))
+        .await;
+    admin.reload_settings().await;
+
+    // Requests without the shared secret should be rejected
+    let response = checkv2(&build_test_message("bill@example.org", "Hello"), None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = checkv2(
+        &build_test_message("bill@example.org", "Hello"),
+        Some("wrong secret"),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // GTUBE messages should be flagged as spam
+    let result = checkv2_json(&build_test_message(
+        "bill@example.org",
+        "XJS*C4JDBQADN1.NSBN3*2IDNEN*GTUBE-STANDARD-ANTI-UBE-TEST-EMAIL*C.34X",
+    ))
+    .await;
+    assert_eq!(result["action"], "add header", "{result}");
+    assert_eq!(result["is_skipped"], false);
+    assert_eq!(result["required_score"].as_f64(), Some(5.0));
+    assert!(result["score"].as_f64().unwrap() >= 100.0, "{result}");
+    let symbol = &result["symbols"]["GTUBE_TEST"];
+    assert_eq!(symbol["name"], "GTUBE_TEST");
+    assert_eq!(symbol["score"].as_f64(), Some(100.0));
+    let headers = &result["milter"]["add_headers"];
+    assert!(
+        headers["X-Spam-Result"]["value"]
+            .as_str()
+            .unwrap()
+            .contains("GTUBE_TEST (100.00)"),
+        "{result}"
+    );
+    assert!(
+        headers["X-Spam-Score"]["value"]
+            .as_str()
+            .unwrap()
+            .starts_with("spam,"),
+        "{result}"
+    );
+
+    // Clean messages should not require any action
+    let result = checkv2_json(&build_test_message("bill@example.org", "Quarterly report")).await;
+    assert_eq!(result["action"], "no action", "{result}");
+    assert!(result["score"].as_f64().unwrap() < 5.0, "{result}");
+    assert!(result["symbols"].get("GTUBE_TEST").is_none(), "{result}");
+    assert!(
+        result["milter"]["add_headers"]["X-Spam-Score"]["value"]
+            .as_str()
+            .unwrap()
+            .starts_with("ham,"),
+        "{result}"
+    );
+
+    // Messages above the reject threshold should be rejected
+    admin
+        .registry_update_setting(
+            SpamSettings {
+                score_reject: Float::new(50.0),
+                ..Default::default()
+            },
+            &[Property::ScoreReject],
+        )
+        .await;
+    admin.reload_settings().await;
+    let result = checkv2_json(&build_test_message(
+        "bill@example.org",
+        "XJS*C4JDBQADN1.NSBN3*2IDNEN*GTUBE-STANDARD-ANTI-UBE-TEST-EMAIL*C.34X",
+    ))
+    .await;
+    assert_eq!(result["action"], "reject", "{result}");
+    assert_eq!(result["thresholds"]["reject"].as_f64(), Some(50.0));
+    assert!(
+        result["milter"]["add_headers"]
+            .as_object()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+async fn checkv2_json(message: &str) -> Value {
+    let response = checkv2(message, Some(SECRET)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    serde_json::from_slice(&response.bytes().await.unwrap()).unwrap()
+}
+
+async fn checkv2(message: &str, secret: Option<&str>) -> reqwest::Response {
+    let mut request = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap()
+        .post("https://127.0.0.1:19061/checkv2")
+        .header("IP", "10.0.0.1")
+        .header("Helo", "mx.example.org")
+        .header("From", "<bill@example.org>")
+        .header("Rcpt", "<john@example.org>")
+        .body(message.to_string());
+    if let Some(secret) = secret {
+        request = request.header("Password", secret);
+    }
+    request.send().await.unwrap()
+}
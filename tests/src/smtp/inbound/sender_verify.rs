@@ -0,0 +1,172 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use common::config::smtp::auth::SenderVerifyResult;
+use mail_auth::{DnssecStatus, MX, spf::Spf};
+use registry::{
+    schema::structs::{Expression, ExpressionMatch, SenderAuth},
+    types::{list::List, map::Map},
+};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn sender_verify() {
+    let mut local = TestServerBuilder::new("smtp_sender_verify_local")
+        .await
+        .with_http_listener(19053)
+        .await
+        .disable_services()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_sender_verify_remote")
+        .await
+        .with_http_listener(19054)
+        .await
+        .with_smtp_listener(9925)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Sender verification is strict for 10.0.0.2 and relaxed for everyone else,
+    // only senders failing SPF are probed
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(SenderAuth {
+            spf_from_verify: Expression {
+                else_: "relaxed".into(),
+                ..Default::default()
+            },
+            sender_verify: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "remote_ip = '10.0.0.2'".into(),
+                    then: "strict".into(),
+                }]),
+                else_: "relaxed".into(),
+            },
+            sender_verify_skip_domains: Map::new(vec!["trusted.org".to_string()]),
+            ..Default::default()
+        })
+        .await;
+    local_admin.mta_no_auth().await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    // The remote only accepts mail for existing accounts
+    let remote_admin = remote.account("admin");
+    remote_admin
+        .create_user_account(
+            "jane@remote.org",
+            "abcde + extra safety",
+            "Jane",
+            &[],
+            vec![],
+        )
+        .await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_disable_spam_filter().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    local.server.mx_add(
+        "remote.org",
+        vec![MX {
+            exchanges: vec!["mx.remote.org".into()].into_boxed_slice(),
+            preference: 10,
+        }],
+        DnssecStatus::Secure,
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.ipv4_add(
+        "mx.remote.org",
+        vec!["127.0.0.1".parse().unwrap()],
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.ipv6_add(
+        "mx.remote.org",
+        vec![],
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.txt_add(
+        "remote.org",
+        Spf::parse(b"v=spf1 -all").unwrap(),
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.txt_add(
+        "neutral.org",
+        Spf::parse(b"v=spf1 ?all").unwrap(),
+        Instant::now() + Duration::from_secs(10),
+    );
+
+    // Existing senders pass, unknown senders fail, relaxed mode accepts both
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session.mail_from("jane@remote.org", "250").await;
+    assert_eq!(session.data.sender_verify, Some(SenderVerifyResult::Pass));
+    session.rset().await;
+    session.mail_from("unknown@remote.org", "250").await;
+    assert_eq!(session.data.sender_verify, Some(SenderVerifyResult::Fail));
+    session.rset().await;
+
+    // Null senders, skipped domains and senders not failing SPF are not verified
+    session.mail_from("", "250").await;
+    assert_eq!(session.data.sender_verify, None);
+    session.rset().await;
+    session.mail_from("john@trusted.org", "250").await;
+    assert_eq!(session.data.sender_verify, None);
+    session.rset().await;
+    session.mail_from("john@neutral.org", "250").await;
+    assert_eq!(session.data.sender_verify, None);
+    session.rset().await;
+
+    // Make the MX unreachable, cached results must not trigger new probes
+    local.server.ipv4_add(
+        "mx.remote.org",
+        vec![],
+        Instant::now() + Duration::from_secs(10),
+    );
+    session.mail_from("jane@remote.org", "250").await;
+    assert_eq!(session.data.sender_verify, Some(SenderVerifyResult::Pass));
+    session.rset().await;
+    session.mail_from("unknown@remote.org", "250").await;
+    assert_eq!(session.data.sender_verify, Some(SenderVerifyResult::Fail));
+    session.rset().await;
+    session.mail_from("other@remote.org", "250").await;
+    assert_eq!(
+        session.data.sender_verify,
+        Some(SenderVerifyResult::TempError)
+    );
+    session.rset().await;
+
+    // Strict mode rejects senders that fail verification
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.mail_from("jane@remote.org", "250").await;
+    session.rset().await;
+    session
+        .ingest(b"MAIL FROM:<unknown@remote.org>\r\n")
+        .await
+        .unwrap();
+    session.response().assert_code("550 5.1.7");
+    session
+        .ingest(b"MAIL FROM:<other@remote.org>\r\n")
+        .await
+        .unwrap();
+    session.response().assert_code("451 4.1.7");
+}
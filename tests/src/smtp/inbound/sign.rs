@@ -17,12 +17,22 @@ use mail_auth::{
 };
 use registry::schema::{
     enums::{DkimCanonicalization, DkimRotationStage},
+    prelude::ObjectType,
     structs::{
         CertificateManagement, Dkim1Signature, DkimManagement, DkimSignature, DnsManagement,
         Domain, Expression, SecretText, SecretTextValue, SenderAuth,
     },
 };
-use std::time::{Duration, Instant};
+use serde_json::{Value, json};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+use trc::{
+    Collector, EventType, Key, StoreEvent,
+    ipc::subscriber::{EventBatch, SubscriberBuilder},
+};
 use types::id::Id;
 
 #[tokio::test]
@@ -178,6 +188,402 @@ async fn sign_and_seal() {
         );*/
 }
 
+#[tokio::test]
+async fn dkim_key_generation() {
+    let mut test = TestServerBuilder::new("smtp_dkim_keygen_test")
+        .await
+        .with_http_listener(19084)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Add test settings
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(Domain {
+            name: "keygen.example".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            allow_relaying: true,
+            ..Default::default()
+        })
+        .await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dkim_sign_domain: Expression {
+                else_: "'keygen.example'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Generated keys are pending until their DNS record is verified
+    let admin = test.account("admin");
+    let ed = generate_dkim_key(&admin, "keygen.example", json!({"algorithm": "ed25519"})).await;
+    let ed_selector = ed["selector"].as_str().unwrap();
+    let ed_value = ed["dnsRecord"]["value"].as_str().unwrap();
+    assert!(ed_selector.starts_with("v1-ed25519-"), "{ed}");
+    assert_eq!(ed["stage"], "pending", "{ed}");
+    assert_eq!(
+        ed["dnsRecord"]["name"],
+        format!("{ed_selector}._domainkey.keygen.example."),
+        "{ed}"
+    );
+    assert!(ed_value.starts_with("v=DKIM1; k=ed25519; "), "{ed}");
+    assert_eq!(ed["dnsRecord"]["zoneValue"], format!("\"{ed_value}\""));
+    DomainKey::parse(ed_value.as_bytes()).unwrap();
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.foobar.org").await;
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jdoe@keygen.example"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_not_contains("DKIM-Signature:");
+
+    // Unpublished records are not activated
+    let admin = test.account("admin");
+    let response = admin
+        .http_post_raw(
+            &format!(
+                "{}/api/dkim/keygen.example/verify/{ed_selector}?activate=true",
+                admin.base_url()
+            ),
+            "application/json",
+            "",
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let verify: Value = response.json().unwrap();
+    assert_eq!(verify["matches"], false, "{verify}");
+    assert_eq!(verify["stage"], "pending", "{verify}");
+    assert_eq!(verify["dnsRecord"]["value"], ed_value, "{verify}");
+
+    // Long RSA records are split into multiple strings
+    let rsa = generate_dkim_key(
+        &admin,
+        "keygen.example",
+        json!({"algorithm": "rsa-2048", "selector": "rsa-keygen"}),
+    )
+    .await;
+    let rsa_value = rsa["dnsRecord"]["value"].as_str().unwrap();
+    let rsa_zone_value = rsa["dnsRecord"]["zoneValue"].as_str().unwrap();
+    assert_eq!(rsa["selector"], "rsa-keygen", "{rsa}");
+    assert_eq!(rsa["stage"], "pending", "{rsa}");
+    assert!(rsa_value.len() > 255, "{rsa}");
+    assert!(rsa_zone_value.contains("\" \""), "{rsa}");
+    assert_eq!(
+        rsa_zone_value.replace("\" \"", "").trim_matches('"'),
+        rsa_value
+    );
+    DomainKey::parse(rsa_value.as_bytes()).unwrap();
+
+    // Keys are only activated after their DNS record is verified
+    let response = admin
+        .http_post_raw(
+            &format!(
+                "{}/api/dkim/keygen.example/verify/rsa-keygen?activate=true",
+                admin.base_url()
+            ),
+            "application/json",
+            "",
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let verify: Value = response.json().unwrap();
+    assert_eq!(verify["matches"], false, "{verify}");
+    assert_eq!(verify["stage"], "pending", "{verify}");
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jdoe@keygen.example"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_not_contains("DKIM-Signature:");
+
+    // An empty body generates a key with the default parameters
+    let response = admin
+        .http_post_raw(
+            &format!("{}/api/dkim/keygen.example/generate", admin.base_url()),
+            "application/json",
+            "",
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let default: Value = response.json().unwrap();
+    assert!(
+        default["selector"].as_str().unwrap().starts_with("v1-rsa-"),
+        "{default}"
+    );
+
+    // Invalid requests are rejected
+    let admin = test.account("admin");
+    for (domain, body, status) in [
+        ("keygen.example", json!({"algorithm": "rsa-1024"}), 400),
+        ("keygen.example", json!({"version": 3}), 400),
+        ("keygen.example", json!({"selector": "rsa-keygen"}), 400),
+        ("keygen.example", json!({"activate": true}), 400),
+        ("unknown.example", json!({"algorithm": "ed25519"}), 404),
+    ] {
+        let response = admin
+            .http_post_raw(
+                &format!("{}/api/dkim/{domain}/generate", admin.base_url()),
+                "application/json",
+                body.to_string(),
+            )
+            .await;
+        assert_eq!(response.status, status, "{body}: {}", response.text());
+    }
+}
+
+#[tokio::test]
+async fn dkim_signer_cache() {
+    let mut test = TestServerBuilder::new("smtp_dkim_signer_cache_test")
+        .await
+        .with_http_listener(19101)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    let admin = test.account("admin");
+    let domain_id = admin
+        .registry_create_object(Domain {
+            name: "cache.example".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            allow_relaying: true,
+            ..Default::default()
+        })
+        .await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dkim_sign_domain: Expression {
+                else_: "'cache.example'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Domains without keys are cached and picked up once a key is added
+    assert!(
+        test.server
+            .dkim_signers("cache.example")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        test.server
+            .dkim_signers("cache.example")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let admin = test.account("admin");
+    let signature_ids = admin.create_dkim_signatures(domain_id).await;
+    let signers = test
+        .server
+        .dkim_signers("cache.example")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(signers.dkim1.len(), 2);
+
+    // Signers are built once and shared by all messages
+    let cached = test
+        .server
+        .dkim_signers("cache.example")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(Arc::ptr_eq(&signers, &cached));
+
+    // Signers are only built on a cache miss, cached entries are reused
+    // regardless of the time elapsed since they were built
+    const ITERATIONS: usize = 50;
+    let subscriber_id = "dkim-signer-cache-test".to_string();
+    let (_tx, mut rx) = SubscriberBuilder::new(subscriber_id.clone())
+        .set_interests([
+            EventType::Store(StoreEvent::CacheHit),
+            EventType::Store(StoreEvent::CacheMiss),
+        ])
+        .register();
+    while !Collector::get_subscribers().contains(&subscriber_id) {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    for _ in 0..ITERATIONS {
+        test.server.inner.cache.dkim_signers.clear();
+        test.server
+            .dkim_signers("cache.example")
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(signer_lookups(&mut rx).await, (0, ITERATIONS));
+    let signers = test
+        .server
+        .dkim_signers("cache.example")
+        .await
+        .unwrap()
+        .unwrap();
+    utils::clock::advance_test_clock(60);
+    for _ in 0..ITERATIONS {
+        let cached = test
+            .server
+            .dkim_signers("cache.example")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(Arc::ptr_eq(&signers, &cached));
+    }
+    assert_eq!(signer_lookups(&mut rx).await, (ITERATIONS + 1, 0));
+    Collector::remove_subscriber(subscriber_id);
+    Collector::reload();
+    let cached = signers;
+
+    // Messages are signed with the cached keys
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.2".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.foobar.org").await;
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jdoe@cache.example"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("DKIM-Signature: v=1; a=rsa-sha256; s=rsa; d=cache.example;")
+        .assert_contains("DKIM-Signature: v=1; a=ed25519-sha256; s=ed; d=cache.example;");
+
+    // Updating a key replaces the cached signers
+    let admin = test.account("admin");
+    admin
+        .registry_update_object(
+            ObjectType::DkimSignature,
+            signature_ids[0],
+            json!({
+                "selector": "rsa-rotated"
+            }),
+        )
+        .await;
+    let signers = test
+        .server
+        .dkim_signers("cache.example")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!Arc::ptr_eq(&signers, &cached));
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jdoe@cache.example"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("DKIM-Signature: v=1; a=rsa-sha256; s=rsa-rotated; d=cache.example;")
+        .assert_not_contains("s=rsa;")
+        .assert_contains("DKIM-Signature: v=1; a=ed25519-sha256; s=ed; d=cache.example;");
+
+    // Removing all keys stops signing
+    let admin = test.account("admin");
+    admin
+        .registry_destroy(ObjectType::DkimSignature, signature_ids)
+        .await;
+    assert!(
+        test.server
+            .dkim_signers("cache.example")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    session
+        .send_message(
+            "bill@foobar.org",
+            &["jdoe@cache.example"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_not_contains("DKIM-Signature:");
+}
+
+// Returns the number of signer cache hits and misses
+async fn signer_lookups(rx: &mut mpsc::Receiver<EventBatch>) -> (usize, usize) {
+    let mut hits = 0;
+    let mut misses = 0;
+    while let Ok(Some(batch)) = tokio::time::timeout(Duration::from_millis(500), rx.recv()).await {
+        for event in batch {
+            if event.value_as_str(Key::Collection) == Some("dkimSigners") {
+                match event.inner.typ {
+                    EventType::Store(StoreEvent::CacheHit) => hits += 1,
+                    EventType::Store(StoreEvent::CacheMiss) => misses += 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+    (hits, misses)
+}
+
+async fn generate_dkim_key(admin: &Account, domain: &str, request: Value) -> Value {
+    let response = admin
+        .http_post_raw(
+            &format!("{}/api/dkim/{domain}/generate", admin.base_url()),
+            "application/json",
+            request.to_string(),
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
+
 impl Account {
     pub async fn create_dkim_signatures(&self, domain_id: Id) -> Vec<Id> {
         let rsa_id = self
@@ -0,0 +1,201 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{smtp::session::TestSession, utils::server::TestServerBuilder};
+use registry::schema::{
+    prelude::ObjectType,
+    structs::{
+        CertificateManagement, DkimManagement, DnsManagement, Domain, Expression, MtaStageData,
+        SenderAuth,
+    },
+};
+use serde_json::json;
+use smtp::queue::Status;
+
+#[tokio::test]
+async fn recipient_size_limits() {
+    let mut test = TestServerBuilder::new("smtp_recipient_size_limits_test")
+        .await
+        .with_http_listener(19086)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Jane has a smaller limit than the default of her domain
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(Domain {
+            name: "foobar.org".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            max_message_size: Some(4096),
+            ..Default::default()
+        })
+        .await;
+    let jane = admin
+        .create_user_account(
+            "jane@foobar.org",
+            "abcde + extra safety",
+            "Jane",
+            &[],
+            vec![],
+        )
+        .await;
+    admin
+        .create_user_account(
+            "john@foobar.org",
+            "12345 + extra safety",
+            "John",
+            &[],
+            vec![],
+        )
+        .await;
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                "maxMessageSize": 2048,
+            }),
+        )
+        .await;
+    admin.mta_no_auth().await;
+    admin.mta_disable_spam_filter().await;
+    admin
+        .registry_create_object(SenderAuth {
+            dmarc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            reverse_ip_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            spf_ehlo_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            spf_from_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            arc_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            dkim_verify: Expression {
+                else_: "disable".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            add_received_header: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            add_return_path_header: Expression {
+                else_: "false".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let mut session = test.new_mta_session();
+    session.ehlo("mx.example.org").await;
+
+    // The declared size is checked against each recipient at RCPT time
+    session
+        .mail_from("<sender@example.net> SIZE=3000", "250")
+        .await;
+    session.rcpt_to("jane@foobar.org", "552 5.2.3").await;
+    session.rcpt_to("john@foobar.org", "250").await;
+    session.data(&message_of_size(3000), "250").await;
+    let message = test.consume_message().await;
+    assert_eq!(message.message.recipients.len(), 1);
+    assert_eq!(&*message.message.recipients[0].address, "john@foobar.org");
+
+    // Without a declared size, oversized recipients are bounced after DATA
+    session
+        .send_message(
+            "sender@example.net",
+            &["jane@foobar.org", "john@foobar.org"],
+            &message_of_size(3000),
+            "250",
+        )
+        .await;
+    assert_recipient_status(&test.consume_message().await.message, &["jane@foobar.org"]);
+
+    // Headers added by the server count towards the limit
+    session
+        .send_message(
+            "sender@example.net",
+            &["jane@foobar.org", "john@foobar.org"],
+            &message_of_size(1990),
+            "250",
+        )
+        .await;
+    let message = test.consume_message().await;
+    assert!(message.message.size > 2048);
+    assert_recipient_status(&message.message, &["jane@foobar.org"]);
+
+    // Smaller messages are delivered to both recipients
+    session
+        .send_message(
+            "sender@example.net",
+            &["jane@foobar.org", "john@foobar.org"],
+            &message_of_size(1024),
+            "250",
+        )
+        .await;
+    assert_recipient_status(&test.consume_message().await.message, &[]);
+
+    // Messages are rejected when no recipient can accept them
+    session.mail_from("sender@example.net", "250").await;
+    session.rcpt_to("jane@foobar.org", "250").await;
+    session.rcpt_to("john@foobar.org", "250").await;
+    session.data(&message_of_size(5000), "552 5.2.3").await;
+    test.assert_no_events();
+}
+
+fn assert_recipient_status(message: &smtp::queue::Message, failed: &[&str]) {
+    assert_eq!(message.recipients.len(), 2);
+    for rcpt in &message.recipients {
+        if failed.contains(&rcpt.address.as_ref()) {
+            assert!(
+                matches!(rcpt.status, Status::PermanentFailure(_)),
+                "{rcpt:?}"
+            );
+        } else {
+            assert!(matches!(rcpt.status, Status::Scheduled), "{rcpt:?}");
+        }
+    }
+}
+
+fn message_of_size(size: usize) -> String {
+    let mut message = concat!(
+        "From: sender@example.net\r\n",
+        "To: jane@foobar.org, john@foobar.org\r\n",
+        "Subject: Size limits\r\n",
+        "\r\n"
+    )
+    .to_string();
+    while message.len() + 80 <= size {
+        message.push_str(&"A".repeat(76));
+        message.push_str("\r\n");
+    }
+    message.push_str(&"A".repeat(size - message.len() - 2));
+    message
+}
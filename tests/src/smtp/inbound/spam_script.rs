@@ -0,0 +1,147 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, build_test_message},
+    },
+    utils::server::TestServerBuilder,
+};
+use registry::{
+    schema::structs::{Expression, MtaStageData, SieveSystemScript, SpamSettings},
+    types::float::Float,
+};
+use sieve::compiler::ErrorType;
+
+const SCRIPT: &str = r#"require ["variables", "header", "editheader", "vnd.stalwart.expressions"];
+
+if header :contains "subject" "invoice" {
+    eval "add_spam_tag('INVOICE_FRAUD', 6.0)";
+}
+
+if header :contains "subject" "wire transfer" {
+    eval "add_spam_tag('WIRE_FRAUD', 25.0)";
+}
+
+if eval "get_spam_score() >= 5.0" {
+    addheader "X-Script-Verdict" "suspicious";
+}
+"#;
+
+#[tokio::test]
+async fn spam_script_tags() {
+    let mut test = TestServerBuilder::new("smtp_spam_script_test")
+        .await
+        .with_http_listener(19064)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Enable the spam filter and run a trusted DATA stage script
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin.mta_allow_relaying().await;
+    admin
+        .registry_create_object(SpamSettings {
+            enable: true,
+            spam_filter_rules_url: None,
+            score_spam: Float::new(5.0),
+            score_reject: Float::new(20.0),
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaStageData {
+            script: Expression {
+                else_: "'spam_tags'".into(),
+                ..Default::default()
+            },
+            enable_spam_filter: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SieveSystemScript {
+            contents: SCRIPT.into(),
+            description: None,
+            is_active: true,
+            name: "spam_tags".into(),
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Untrusted scripts cannot adjust the spam score
+    let err = test
+        .server
+        .core
+        .sieve
+        .untrusted_compiler
+        .compile(SCRIPT.as_bytes())
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.error_type(),
+            ErrorType::InvalidExpression(expr) if expr.contains("add_spam_tag")
+        ),
+        "{err:?}"
+    );
+
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    assert!(session.init_conn().await);
+    session.ehlo("mx.remote.org").await;
+
+    // Messages not matching the script are scored as usual
+    session
+        .send_message(
+            "bill@remote.org",
+            &["john@example.org"],
+            &build_test_message("bill@remote.org", "Quarterly report"),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.contains("X-Spam-Score: ham,"), "{contents}");
+    assert!(!contents.contains("INVOICE_FRAUD"), "{contents}");
+    assert!(!contents.contains("X-Script-Verdict"), "{contents}");
+
+    // Script tags are included in the score and in the spam headers
+    session
+        .send_message(
+            "bill@remote.org",
+            &["john@example.org"],
+            &build_test_message("bill@remote.org", "Updated invoice"),
+            "250",
+        )
+        .await;
+    let contents = test.expect_message().await.read_message(&test).await;
+    assert!(contents.contains("INVOICE_FRAUD (6.00)"), "{contents}");
+    assert!(contents.contains("X-Spam-Score: spam,"), "{contents}");
+    assert!(
+        contents.contains("X-Script-Verdict: suspicious"),
+        "{contents}"
+    );
+
+    // Tags pushing the score over the reject threshold reject the message
+    session
+        .send_message(
+            "bill@remote.org",
+            &["john@example.org"],
+            &build_test_message("bill@remote.org", "Urgent wire transfer for invoice"),
+            "550 5.7.1",
+        )
+        .await;
+    test.assert_no_events();
+}
@@ -0,0 +1,496 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, VerifyResponse},
+    },
+    utils::server::TestServerBuilder,
+};
+use common::manager::application::Resource;
+use http_body_util::BodyExt;
+use http_proto::ToHttpResponse;
+use hyper::{
+    body,
+    server::conn::{http1, http2},
+    service::service_fn,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use registry::{
+    schema::{
+        enums::{MtaHookProtocol, MtaStage},
+        prelude::ObjectType,
+        structs::{Expression, MtaHook, MtaStageRcpt},
+    },
+    types::map::Map,
+};
+use serde_json::json;
+use smtp::inbound::hooks::{
+    self, SmtpResponse, StreamRequest,
+    client::HEADER_FILTER_TIMEOUT,
+    grpc::{FilterChunk, FilterVerdict},
+};
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+use tokio::{net::TcpListener, sync::watch};
+use tonic::server::ClientStreamingService;
+use tonic_prost::ProstCodec;
+
+const FILTER_PORT: u16 = 9336;
+const GRPC_FILTER_PORT: u16 = 9337;
+const FILTER_TIMEOUT: u64 = 1000;
+
+#[tokio::test]
+async fn mta_hook_stream() {
+    mta_hook_filter(
+        "smtp_mta_hook_stream_test",
+        19095,
+        MtaHookProtocol::Stream,
+        FILTER_PORT,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn mta_hook_grpc() {
+    mta_hook_filter(
+        "smtp_mta_hook_grpc_test",
+        19102,
+        MtaHookProtocol::Grpc,
+        GRPC_FILTER_PORT,
+    )
+    .await;
+}
+
+async fn mta_hook_filter(name: &str, http_port: u16, protocol: MtaHookProtocol, filter_port: u16) {
+    let mut test = TestServerBuilder::new(name)
+        .await
+        .with_http_listener(http_port)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Add test settings
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(MtaStageRcpt {
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let hook_id = admin
+        .registry_create_object(MtaHook {
+            enable: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            url: format!("http://127.0.0.1:{filter_port}"),
+            stages: Map::new(vec![MtaStage::Rcpt, MtaStage::Data]),
+            protocol,
+            stream_chunk_size: 1024,
+            timeout: FILTER_TIMEOUT.into(),
+            temp_fail_on_error: true,
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let _rx = if protocol == MtaHookProtocol::Grpc {
+        spawn_mock_grpc_filter_server()
+    } else {
+        spawn_mock_stream_filter_server()
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Build session
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.doe.org").await;
+
+    // Recipients are filtered at the RCPT stage
+    session.mail_from("john@doe.org", "250").await;
+    session.rcpt_to("blocked@foobar.org", "550 5.7.1").await;
+    session.rcpt_to("bill@foobar.org", "250").await;
+    session.data(&message(), "250 2.0.0").await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("Subject: Streamed report")
+        .assert_not_contains("X-Filter-Body");
+
+    // The message body is streamed in full and headers are modified
+    session
+        .send_message("tag@doe.org", &["bill@foobar.org"], &message(), "250 2.0.0")
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("X-Filter-Body: complete")
+        .assert_contains(&format!("X-Filter-Timeout: {FILTER_TIMEOUT}"))
+        .assert_contains("Subject: [filtered] Streamed report")
+        .assert_count("Subject: ", 1);
+
+    // Recipients are modified
+    session
+        .send_message(
+            "rcpt@doe.org",
+            &["bill@foobar.org"],
+            &message(),
+            "250 2.0.0",
+        )
+        .await;
+    let message_ = test.expect_message().await;
+    let recipients = message_
+        .message
+        .recipients
+        .iter()
+        .map(|rcpt| rcpt.address.as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(recipients, vec!["jane@foobar.org"]);
+
+    // Rejected messages
+    session
+        .send_message(
+            "reject@doe.org",
+            &["bill@foobar.org"],
+            &message(),
+            "550 5.7.1",
+        )
+        .await;
+    test.assert_no_events();
+
+    // Filters exceeding the deadline apply the failure policy
+    let started = Instant::now();
+    session
+        .send_message(
+            "slow@doe.org",
+            &["bill@foobar.org"],
+            &message(),
+            "451 4.3.5",
+        )
+        .await;
+    assert!(started.elapsed() < Duration::from_millis(FILTER_TIMEOUT * 2));
+    test.assert_no_events();
+
+    // Fail open
+    let admin = test.account("admin");
+    admin
+        .registry_update_object(
+            ObjectType::MtaHook,
+            hook_id,
+            json!({
+                "tempFailOnError": false,
+            }),
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.doe.org").await;
+    session
+        .send_message(
+            "slow@doe.org",
+            &["bill@foobar.org"],
+            &message(),
+            "250 2.0.0",
+        )
+        .await;
+    test.expect_message()
+        .await
+        .read_lines(&test)
+        .await
+        .assert_contains("Subject: Streamed report")
+        .assert_not_contains("X-Filter-Body");
+}
+
+fn message() -> String {
+    let mut message = concat!(
+        "From: john@doe.org\r\n",
+        "To: bill@foobar.org\r\n",
+        "Subject: Streamed report\r\n",
+        "\r\n",
+    )
+    .to_string();
+    // Large enough to be sent in several chunks
+    for line in 0..100 {
+        message.push_str(&format!("Line {line:03} of the streamed report body.\r\n"));
+    }
+    message
+}
+
+pub fn spawn_mock_stream_filter_server() -> watch::Sender<bool> {
+    let (tx, rx) = watch::channel(true);
+
+    tokio::spawn(async move {
+        let listener = TcpListener::bind(("127.0.0.1", FILTER_PORT))
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock stream filter to 127.0.0.1:{FILTER_PORT}: {e}");
+            });
+        let mut rx_ = rx.clone();
+        loop {
+            tokio::select! {
+                stream = listener.accept() => {
+                    match stream {
+                        Ok((stream, _)) => {
+                            tokio::spawn(async move {
+                                let _ = http1::Builder::new()
+                                    .keep_alive(false)
+                                    .serve_connection(
+                                        TokioIo::new(stream),
+                                        service_fn(|req: hyper::Request<body::Incoming>| async move {
+                                            let response = handle_stream_filter(req).await;
+                                            Ok::<_, hyper::Error>(
+                                                Resource::new(
+                                                    "application/json",
+                                                    serde_json::to_string(&response)
+                                                        .unwrap()
+                                                        .into_bytes(),
+                                                )
+                                                .into_http_response()
+                                                .build(),
+                                            )
+                                        }),
+                                    )
+                                    .await;
+                            });
+                        }
+                        Err(err) => {
+                            panic!("Something went wrong: {err}" );
+                        }
+                    }
+                },
+                _ = rx_.changed() => {
+                    break;
+                }
+            };
+        }
+    });
+
+    tx
+}
+
+pub fn spawn_mock_grpc_filter_server() -> watch::Sender<bool> {
+    let (tx, rx) = watch::channel(true);
+
+    tokio::spawn(async move {
+        let listener = TcpListener::bind(("127.0.0.1", GRPC_FILTER_PORT))
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock gRPC filter to 127.0.0.1:{GRPC_FILTER_PORT}: {e}");
+            });
+        let mut rx_ = rx.clone();
+        loop {
+            tokio::select! {
+                stream = listener.accept() => {
+                    match stream {
+                        Ok((stream, _)) => {
+                            tokio::spawn(async move {
+                                let _ = http2::Builder::new(TokioExecutor::new())
+                                    .serve_connection(
+                                        TokioIo::new(stream),
+                                        service_fn(|req: hyper::Request<body::Incoming>| async move {
+                                            let mut grpc = tonic::server::Grpc::new(
+                                                ProstCodec::<FilterVerdict, FilterChunk>::default(),
+                                            );
+                                            Ok::<_, Infallible>(
+                                                grpc.client_streaming(MockGrpcFilter, req).await,
+                                            )
+                                        }),
+                                    )
+                                    .await;
+                            });
+                        }
+                        Err(err) => {
+                            panic!("Something went wrong: {err}" );
+                        }
+                    }
+                },
+                _ = rx_.changed() => {
+                    break;
+                }
+            };
+        }
+    });
+
+    tx
+}
+
+struct MockGrpcFilter;
+
+impl ClientStreamingService<FilterChunk> for MockGrpcFilter {
+    type Response = FilterVerdict;
+    type Future =
+        Pin<Box<dyn Future<Output = Result<tonic::Response<FilterVerdict>, tonic::Status>> + Send>>;
+
+    fn call(&mut self, request: tonic::Request<tonic::Streaming<FilterChunk>>) -> Self::Future {
+        Box::pin(async move {
+            let timeout = request
+                .metadata()
+                .get("grpc-timeout")
+                .and_then(|value| value.to_str().ok())
+                .map(grpc_timeout_millis)
+                .unwrap_or_default();
+
+            // The first message holds the request, the following ones the body
+            let mut chunks = request.into_inner();
+            let request = chunks
+                .message()
+                .await?
+                .expect("Missing stream request message");
+            let request = serde_json::from_slice::<StreamRequest>(&request.request).unwrap();
+            let mut body = Vec::new();
+            while let Some(chunk) = chunks.message().await? {
+                body.extend_from_slice(&chunk.body);
+            }
+
+            let response = filter_response(request, body.len(), timeout).await;
+            Ok(tonic::Response::new(FilterVerdict {
+                response: serde_json::to_vec(&response).unwrap().into(),
+            }))
+        })
+    }
+}
+
+fn grpc_timeout_millis(value: &str) -> String {
+    let (value, unit) = value.split_at(value.len() - 1);
+    let value = value.parse::<u64>().unwrap();
+    match unit {
+        "H" => value * 3_600_000,
+        "M" => value * 60_000,
+        "S" => value * 1_000,
+        "m" => value,
+        "u" => value / 1_000,
+        "n" => value / 1_000_000,
+        _ => panic!("Invalid grpc-timeout unit {unit:?}"),
+    }
+    .to_string()
+}
+
+async fn handle_stream_filter(req: hyper::Request<body::Incoming>) -> hooks::Response {
+    let timeout = req
+        .headers()
+        .get(HEADER_FILTER_TIMEOUT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    // Read the request line, then the message body as it arrives
+    let mut body = req.into_body();
+    let mut buf = Vec::new();
+    let mut request = None;
+    while let Some(frame) = body.frame().await {
+        if let Ok(data) = frame.unwrap().into_data() {
+            buf.extend_from_slice(&data);
+        }
+        if request.is_none()
+            && let Some(pos) = buf.iter().position(|&ch| ch == b'\n')
+        {
+            request = Some(serde_json::from_slice::<StreamRequest>(&buf[..pos]).unwrap());
+            buf.drain(..=pos);
+        }
+    }
+    let request = request.expect("Missing stream request line");
+
+    filter_response(request, buf.len(), timeout).await
+}
+
+async fn filter_response(
+    request: StreamRequest,
+    body_size: usize,
+    timeout: String,
+) -> hooks::Response {
+    let envelope = request.envelope.unwrap();
+
+    if matches!(request.context.stage, hooks::Stage::Rcpt) {
+        return if envelope.to.last().unwrap().address == "blocked@foobar.org" {
+            reject()
+        } else {
+            accept(vec![])
+        };
+    }
+
+    match envelope.from.address.split_once('@').unwrap().0 {
+        "tag" => {
+            let message = request.message.unwrap();
+            accept(vec![
+                hooks::Modification::AddHeader {
+                    name: "X-Filter-Body".into(),
+                    value: if message.body_size == body_size {
+                        "complete".into()
+                    } else {
+                        format!("incomplete {} of {}", body_size, message.body_size)
+                    },
+                },
+                hooks::Modification::AddHeader {
+                    name: "X-Filter-Timeout".into(),
+                    value: timeout,
+                },
+                hooks::Modification::ChangeHeader {
+                    index: 1,
+                    name: "Subject".into(),
+                    value: "[filtered] Streamed report".into(),
+                },
+            ])
+        }
+        "rcpt" => accept(vec![
+            hooks::Modification::AddRecipient {
+                value: "jane@foobar.org".into(),
+                parameters: Default::default(),
+            },
+            hooks::Modification::DeleteRecipient {
+                value: "bill@foobar.org".into(),
+            },
+        ]),
+        "reject" => reject(),
+        "slow" => {
+            tokio::time::sleep(Duration::from_millis(FILTER_TIMEOUT * 3)).await;
+            accept(vec![])
+        }
+        _ => accept(vec![]),
+    }
+}
+
+fn accept(modifications: Vec<hooks::Modification>) -> hooks::Response {
+    hooks::Response {
+        action: hooks::Action::Accept,
+        response: None,
+        modifications,
+    }
+}
+
+fn reject() -> hooks::Response {
+    hooks::Response {
+        action: hooks::Action::Reject,
+        response: SmtpResponse {
+            status: 550.into(),
+            enhanced_status: Some("5.7.1".into()),
+            message: Some("Rejected by filter".into()),
+            disconnect: false,
+        }
+        .into(),
+        modifications: vec![],
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{smtp::session::DummyVerifier, utils::server::TestServerBuilder};
+use registry::{
+    schema::{
+        enums::{NetworkListenerProtocol, TlsCipherSuite, TlsVersion},
+        prelude::SocketAddr,
+        structs::NetworkListener,
+    },
+    types::map::Map,
+};
+use rustls::{
+    ClientConfig, SupportedProtocolVersion,
+    crypto::aws_lc_rs::{cipher_suite::*, default_provider},
+    version::{TLS12, TLS13},
+};
+use rustls_pki_types::ServerName;
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tokio::{io::AsyncReadExt, net::TcpStream};
+use tokio_rustls::TlsConnector;
+use trc::{Collector, MetricType};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn tls_policy() {
+    // Port 9926 accepts TLS 1.2 and requests client certificates, port 9927
+    // only accepts TLS 1.3 with AES-256
+    let _test = TestServerBuilder::new("smtp_tls_policy_test")
+        .await
+        .with_http_listener(19068)
+        .await
+        .with_object(NetworkListener {
+            bind: Map::new(vec![SocketAddr::from_str("0.0.0.0:9926").unwrap()]),
+            name: "smtp-legacy".to_string(),
+            protocol: NetworkListenerProtocol::Smtp,
+            use_tls: true,
+            tls_implicit: true,
+            tls_request_client_cert: true,
+            ..Default::default()
+        })
+        .await
+        .with_object(NetworkListener {
+            bind: Map::new(vec![SocketAddr::from_str("0.0.0.0:9927").unwrap()]),
+            name: "smtp-strict".to_string(),
+            protocol: NetworkListenerProtocol::Smtp,
+            use_tls: true,
+            tls_implicit: true,
+            tls_disable_protocols: Map::new(vec![TlsVersion::Tls12]),
+            tls_disable_cipher_suites: Map::new(vec![
+                TlsCipherSuite::Tls13Aes128GcmSha256,
+                TlsCipherSuite::Tls13Chacha20Poly1305Sha256,
+            ]),
+            ..Default::default()
+        })
+        .await
+        .disable_services()
+        .build()
+        .await;
+    let rejected = Collector::read_metric(MetricType::TlsPolicyRejected);
+
+    // TLS 1.2 clients are accepted by the legacy listener only
+    let tls12 = connector(&[&TLS12], None);
+    assert_eq!(handshake(&tls12, 9926).await, Ok("220".to_string()));
+    assert!(handshake(&tls12, 9927).await.is_err());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        Collector::read_metric(MetricType::TlsPolicyRejected),
+        rejected + 1.0
+    );
+
+    // TLS 1.3 clients are accepted by both listeners, unless they do not
+    // support any of the allowed cipher suites
+    let tls13 = connector(&[&TLS13], None);
+    assert_eq!(handshake(&tls13, 9926).await, Ok("220".to_string()));
+    assert_eq!(handshake(&tls13, 9927).await, Ok("220".to_string()));
+    let tls13_aes128 = connector(&[&TLS13], Some(TLS13_AES_128_GCM_SHA256));
+    assert_eq!(handshake(&tls13_aes128, 9926).await, Ok("220".to_string()));
+    assert!(handshake(&tls13_aes128, 9927).await.is_err());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        Collector::read_metric(MetricType::TlsPolicyRejected),
+        rejected + 2.0
+    );
+}
+
+fn connector(
+    versions: &[&'static SupportedProtocolVersion],
+    cipher_suite: Option<rustls::SupportedCipherSuite>,
+) -> TlsConnector {
+    let mut provider = default_provider();
+    if let Some(cipher_suite) = cipher_suite {
+        provider.cipher_suites = vec![cipher_suite];
+    }
+
+    TlsConnector::from(Arc::new(
+        ClientConfig::builder_with_provider(provider.into())
+            .with_protocol_versions(versions)
+            .unwrap()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(DummyVerifier))
+            .with_no_client_auth(),
+    ))
+}
+
+async fn handshake(connector: &TlsConnector, port: u16) -> Result<String, String> {
+    let stream = TcpStream::connect(format!("127.0.0.1:{port}"))
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut stream = connector
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut buf = [0u8; 3];
+    tokio::time::timeout(Duration::from_secs(5), stream.read_exact(&mut buf))
+        .await
+        .map_err(|err| err.to_string())?
+        .map_err(|err| err.to_string())?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
@@ -63,6 +63,7 @@ async fn vrfy_expn() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             expn: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -70,6 +71,7 @@ async fn vrfy_expn() {
                     then: "true".into(),
                 }]),
                 else_: "false".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -5,15 +5,24 @@
  */
 
 use crate::utils::{dns::DnsCache, server::TestServerBuilder};
-use common::expr::{tokenizer::TokenMap, *};
+use common::expr::{
+    if_block::BootstrapExprExt,
+    parser::ExpressionParser,
+    tokenizer::{TokenMap, Tokenizer},
+    *,
+};
 use mail_auth::{DnssecStatus, MX};
-use registry::schema::{
-    enums::ExpressionVariable,
-    prelude::{ObjectType, Property},
-    structs::{LookupStore, SqliteStore, StoreLookup},
+use registry::{
+    schema::{
+        enums::{ExpressionVariable, MTA_CONNECTION_VARIABLE},
+        prelude::{ObjectType, Property},
+        structs::{self, ExpressionMatch, LookupStore, SqliteStore, StoreLookup},
+    },
+    types::list::List,
 };
 use smtp::queue::RecipientDomain;
 use std::time::{Duration, Instant};
+use store::registry::bootstrap::Bootstrap;
 
 const TESTS: &[(&str, &str)] = &[
     ("dns_query(rcpt_domain, 'mx')[0]", "mx.foobar.org"),
@@ -33,6 +42,10 @@ const TESTS: &[(&str, &str)] = &[
         "is_local_domain('foobar.org') + '-' + is_local_domain('unknown.org')  + '-' + is_local_address('john@foobar.org') + '-' + is_local_address('unknown@foobar.org')",
         "1-0-1-0",
     ),
+    ("coalesce(sender, rcpt_domain)", "test.org"),
+    ("coalesce(sender, '') + '-' + coalesce(0, 1)", "-0"),
+    ("coalesce('', [], ['a', 'b'], 'c')[1]", "b"),
+    ("count(coalesce([], sender, ['a', 'b']))", "2"),
 ];
 
 #[tokio::test]
@@ -128,4 +141,66 @@ async fn expressions() {
             expr
         );
     }
+
+    // Variables outside the connect stage are rejected at compile time
+    let token_map = TokenMap::default()
+        .with_variables(MTA_CONNECTION_VARIABLE)
+        .with_context("MtaStageConnect.hostname");
+    assert_eq!(
+        ExpressionParser::new(Tokenizer::new("retry_num > 1", &token_map))
+            .parse()
+            .unwrap_err(),
+        "Variable \"retry_num\" is not available in context \"MtaStageConnect.hostname\""
+    );
+    assert!(
+        ExpressionParser::new(Tokenizer::new("remote_ip = '10.0.0.1'", &token_map))
+            .parse()
+            .is_ok()
+    );
+    let lenient_token_map = token_map.clone().with_strict(false);
+    assert!(
+        ExpressionParser::new(Tokenizer::new("retry_num > 1", &lenient_token_map))
+            .parse()
+            .is_ok()
+    );
+    assert_eq!(
+        ExpressionParser::new(Tokenizer::new("coalesce()", &token_map))
+            .parse()
+            .unwrap_err(),
+        "Expression function \"coalesce\" expected at least 1 argument"
+    );
+
+    // Strict mode is toggled per expression
+    for strict in [true, false] {
+        let stage = structs::MtaStageConnect {
+            hostname: structs::Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "retry_num > 1".into(),
+                    then: "'retry'".into(),
+                }]),
+                else_: "'first'".into(),
+                strict,
+            },
+            ..Default::default()
+        };
+        let mut bp = Bootstrap::new_uninitialized(test.server.registry().clone());
+        let if_block = bp.compile_expr(
+            ObjectType::MtaStageConnect.singleton(),
+            &stage.ctx_hostname(),
+        );
+        if strict {
+            assert!(if_block.is_empty(), "{if_block:?}");
+            assert_eq!(bp.errors.len(), 1, "{:?}", bp.errors);
+            assert!(
+                format!("{:?}", bp.errors[0]).contains(
+                    "Variable \\\"retry_num\\\" is not available in context \\\"MtaStageConnect.hostname\\\""
+                ),
+                "{:?}",
+                bp.errors
+            );
+        } else {
+            assert_eq!(if_block.if_then.len(), 1, "{if_block:?}");
+            assert!(bp.errors.is_empty(), "{:?}", bp.errors);
+        }
+    }
 }
@@ -0,0 +1,294 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::{TestServer, TestServerBuilder};
+use common::{
+    expr::{tokenizer::TokenMap, *},
+    scripts::plugins::lookup::VariableWrapper,
+};
+use http_body_util::Full;
+use hyper::{StatusCode, body, body::Bytes, header, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use registry::{
+    schema::{
+        prelude::{ObjectType, Property},
+        structs::{HttpLookup, HttpLookupCsv, HttpLookupFormat},
+    },
+    types::duration::Duration,
+};
+use sieve::runtime::Variable;
+use smtp::queue::RecipientDomain;
+use std::{sync::Arc, sync::atomic::Ordering, time::Instant};
+use store::{InMemoryStore, parking_lot::Mutex};
+use tokio::net::TcpListener;
+
+#[derive(Debug)]
+struct MockTable {
+    status: u16,
+    version: u32,
+    csv: &'static str,
+    json: &'static str,
+    not_modified: usize,
+}
+
+#[tokio::test]
+async fn http_lookup() {
+    let mut test = TestServerBuilder::new("smtp_http_lookup_test")
+        .await
+        .with_http_listener(19075)
+        .await
+        .disable_services()
+        .build()
+        .await;
+    let table = spawn_mock_table_server();
+
+    // Create the HTTP lookup stores
+    let admin = test.account("admin");
+    let mut ids = Vec::new();
+    for (namespace, path, format) in [
+        (
+            "routes-csv",
+            "routes.csv",
+            HttpLookupFormat::Csv(HttpLookupCsv {
+                index_key: 0,
+                index_value: None,
+                separator: ",".into(),
+                skip_first: true,
+                all_columns: true,
+            }),
+        ),
+        ("routes-json", "routes.json", HttpLookupFormat::Json),
+    ] {
+        ids.push(
+            admin
+                .registry_create_object(HttpLookup {
+                    namespace: namespace.into(),
+                    enable: true,
+                    format,
+                    url: format!("http://127.0.0.1:8823/{path}"),
+                    refresh: Duration::from_millis(1000),
+                    retry: Duration::from_millis(1000),
+                    ..Default::default()
+                })
+                .await,
+        );
+    }
+    admin.reload_lookup_stores().await;
+    test.reload_core();
+
+    // Multi-column CSV rows are returned as arrays
+    assert_eval(
+        &test,
+        "key_get('routes-csv', 'example.org')[0] + ':' + key_get('routes-csv', 'example.org')[1]",
+        "relay1.example.net:25",
+    )
+    .await;
+    assert_eval(
+        &test,
+        "key_get('routes-csv', 'example.com')[0] + ':' + key_exists('routes-csv', 'example.net')",
+        "relay2.example.net:0",
+    )
+    .await;
+
+    // JSON objects are mapped to keys, arrays are preserved
+    assert_eval(
+        &test,
+        "key_get('routes-json', 'example.org') + ':' + key_get('routes-json', 'example.com')[1] + ':' + key_get('routes-json', 'limit')",
+        "relay1.example.net:relay3.example.net:100",
+    )
+    .await;
+
+    // Values are also available to Sieve scripts
+    let store = test.server.get_lookup_store("routes-csv").unwrap();
+    match store
+        .key_get::<VariableWrapper>("example.org")
+        .await
+        .unwrap()
+        .map(|value| value.into_inner())
+    {
+        Some(Variable::Array(items)) => assert_eq!(
+            items
+                .iter()
+                .map(|item| item.to_string().into_owned())
+                .collect::<Vec<_>>(),
+            vec!["relay1.example.net".to_string(), "25".to_string()]
+        ),
+        other => panic!("Unexpected value: {other:?}"),
+    }
+
+    // Unchanged tables are not downloaded again
+    let time = Instant::now();
+    while table.lock().not_modified == 0 {
+        assert!(
+            time.elapsed() < std::time::Duration::from_secs(10),
+            "Table was downloaded again"
+        );
+        store.key_exists("example.org").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    assert_eval(
+        &test,
+        "key_get('routes-csv', 'example.org')[0]",
+        "relay1.example.net",
+    )
+    .await;
+
+    // Changes are picked up on refresh
+    {
+        let mut table = table.lock();
+        table.csv = concat!(
+            "domain,relay,port\n",
+            "example.org,relay4.example.net,2525\n",
+            "example.net,relay5.example.net,25\n"
+        );
+        table.version += 1;
+    }
+    assert_eval(
+        &test,
+        "key_get('routes-csv', 'example.org')[0] + ':' + key_get('routes-csv', 'example.org')[1] + ':' + key_exists('routes-csv', 'example.com')",
+        "relay4.example.net:2525:0",
+    )
+    .await;
+
+    // The last known good table is served when the server fails
+    table.lock().status = 500;
+    let failures = |store: &InMemoryStore| match store {
+        InMemoryStore::Http(store) => store.failures.load(Ordering::Relaxed),
+        _ => unreachable!(),
+    };
+    let time = Instant::now();
+    while failures(&store) < 3 {
+        assert!(
+            time.elapsed() < std::time::Duration::from_secs(10),
+            "Refresh did not fail"
+        );
+        store.key_exists("example.net").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    assert_eval(
+        &test,
+        "key_get('routes-csv', 'example.net')[0] + ':' + key_get('routes-json', 'example.org')",
+        "relay5.example.net:relay1.example.net",
+    )
+    .await;
+
+    // Recover once the server is back
+    table.lock().status = 200;
+    let time = Instant::now();
+    while failures(&store) != 0 {
+        assert!(
+            time.elapsed() < std::time::Duration::from_secs(10),
+            "Refresh did not recover"
+        );
+        store.key_exists("example.net").await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    // Remove test data
+    let admin = test.account("admin");
+    admin
+        .registry_destroy(ObjectType::HttpLookup, ids.iter())
+        .await
+        .assert_destroyed(&ids);
+    admin.reload_lookup_stores().await;
+}
+
+async fn assert_eval(test: &TestServer, expr: &str, expected: &str) {
+    let e = Expression::parse(&TokenMap::default(), expr);
+    let time = Instant::now();
+
+    // Tables are fetched in the background on first access
+    loop {
+        let result = test
+            .server
+            .eval_expr::<String, _>(
+                &e,
+                &RecipientDomain::new("test.org"),
+                ObjectType::Account.singleton(),
+                Property::AccountName,
+                0,
+            )
+            .await
+            .unwrap();
+        if result == expected {
+            break;
+        }
+        assert!(
+            time.elapsed() < std::time::Duration::from_secs(10),
+            "failed for '{expr}': expected {expected:?}, got {result:?}"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+}
+
+fn spawn_mock_table_server() -> Arc<Mutex<MockTable>> {
+    let table_ = Arc::new(Mutex::new(MockTable {
+        status: 200,
+        version: 1,
+        csv: concat!(
+            "domain,relay,port\n",
+            "example.org,relay1.example.net,25\n",
+            "example.com,\"relay2.example.net\",587\n"
+        ),
+        json: concat!(
+            "{\"example.org\": \"relay1.example.net\", ",
+            "\"example.com\": [\"relay2.example.net\", \"relay3.example.net\"], ",
+            "\"limit\": 100, \"disabled\": null}"
+        ),
+        not_modified: 0,
+    }));
+    let table = table_.clone();
+
+    tokio::spawn(async move {
+        let listener = TcpListener::bind("127.0.0.1:8823")
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock table server to 127.0.0.1:8823: {e}");
+            });
+
+        while let Ok((stream, _)) = listener.accept().await {
+            let table = table.clone();
+            let _ = http1::Builder::new()
+                .keep_alive(false)
+                .serve_connection(
+                    TokioIo::new(stream),
+                    service_fn(move |req: hyper::Request<body::Incoming>| {
+                        let table = table.clone();
+
+                        async move {
+                            let mut table = table.lock();
+                            let etag = format!("\"{}\"", table.version);
+                            let response = hyper::Response::builder().header(header::ETAG, &etag);
+                            let response = if table.status != 200 {
+                                response.status(table.status).body(Full::new(Bytes::new()))
+                            } else if req
+                                .headers()
+                                .get(header::IF_NONE_MATCH)
+                                .is_some_and(|value| value.as_bytes() == etag.as_bytes())
+                            {
+                                table.not_modified += 1;
+                                response
+                                    .status(StatusCode::NOT_MODIFIED)
+                                    .body(Full::new(Bytes::new()))
+                            } else {
+                                let body = if req.uri().path().ends_with(".json") {
+                                    table.json
+                                } else {
+                                    table.csv
+                                };
+                                response.body(Full::new(Bytes::from_static(body.as_bytes())))
+                            };
+
+                            Ok::<_, hyper::Error>(response.unwrap())
+                        }
+                    }),
+                )
+                .await;
+        }
+    });
+
+    table_
+}
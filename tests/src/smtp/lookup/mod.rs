@@ -5,4 +5,5 @@
  */
 
 pub mod expressions;
+pub mod http_lookup;
 pub mod utils;
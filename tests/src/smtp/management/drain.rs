@@ -0,0 +1,117 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{http::HttpRequest, server::TestServerBuilder, smtp::SmtpConnection};
+use hyper::Method;
+use registry::schema::{
+    enums::NetworkListenerProtocol,
+    prelude::Property,
+    structs::{Action, SystemSettings},
+};
+use reqwest::StatusCode;
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn drain_server() {
+    let mut test = TestServerBuilder::new("smtp_drain_test")
+        .await
+        .with_http_listener(19069)
+        .await
+        .with_listener(NetworkListenerProtocol::Smtp, "smtp-debug", 9928, false)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    let admin = test.account("admin");
+    admin.mta_allow_relaying().await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_update_setting(
+            SystemSettings {
+                shutdown_grace_period: 2_000u64.into(),
+                ..Default::default()
+            },
+            &[Property::ShutdownGracePeriod],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    assert_eq!(
+        test.server.core.network.shutdown_grace_period,
+        Duration::from_secs(2)
+    );
+
+    let http = HttpRequest {
+        port: 19069,
+        ..Default::default()
+    };
+    assert_eq!(health(&http, "ready").await, StatusCode::OK);
+
+    // Start a transaction on one session and leave another one idle
+    let mut active = SmtpConnection::connect_smtp_port(9928).await;
+    active.mail_from("john@example.org", 2).await;
+    active.rcpt_to("bill@foobar.org", 2).await;
+    let mut idle = SmtpConnection::connect_smtp_port(9928).await;
+
+    // Drain the server
+    test.account("admin")
+        .registry_create_object(Action::DrainServer)
+        .await;
+    assert!(test.server.is_draining());
+
+    // Idle sessions are closed and new connections are refused
+    let lines = idle.read(1, 4).await;
+    assert!(lines[0].starts_with("421 "), "{lines:?}");
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(TcpStream::connect("127.0.0.1:9928").await.is_err());
+
+    // The readiness probe fails while the server is still live
+    assert_eq!(
+        health(&http, "ready").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    assert_eq!(health(&http, "live").await, StatusCode::OK);
+    assert!(!test.server.inner.data.drain.is_drained());
+
+    // The transaction in progress is completed but new ones are rejected
+    active.data(3).await;
+    active
+        .send_raw(concat!(
+            "From: john@example.org\r\n",
+            "To: bill@foobar.org\r\n",
+            "Subject: Drain test\r\n",
+            "\r\n",
+            "Test message\r\n",
+            ".\r\n",
+            "MAIL FROM:<john@example.org>\r\n"
+        ))
+        .await;
+    let lines = active.read(2, u8::MAX).await;
+    assert!(lines[0].starts_with("250 "), "{lines:?}");
+    assert!(lines[1].starts_with("421 "), "{lines:?}");
+    test.expect_message().await;
+
+    // All sessions have been drained
+    assert!(
+        test.server
+            .inner
+            .data
+            .drain
+            .wait(Duration::from_secs(2))
+            .await
+    );
+}
+
+async fn health(http: &HttpRequest, probe: &str) -> StatusCode {
+    http.send_full(Method::GET, &format!("/healthz/{probe}"), None, None)
+        .await
+        .status
+}
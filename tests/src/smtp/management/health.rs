@@ -0,0 +1,165 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{http::HttpRequest, server::TestServerBuilder};
+use common::network::health::HEALTH_CHECK_DELAY;
+use futures::future::join_all;
+use hyper::Method;
+use registry::schema::{enums::HealthCheckDependency, prelude::Property, structs::Http};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn readiness_probe() {
+    let mut test = TestServerBuilder::new("smtp_readiness_test")
+        .await
+        .with_http_listener(19092)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(Http {
+            readiness_check_timeout: 2_000u64.into(),
+            readiness_timeout: 500u64.into(),
+            readiness_cache_ttl: 0u64.into(),
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    let http = HttpRequest {
+        port: 19092,
+        ..Default::default()
+    };
+
+    // All stores are reachable
+    let (status, readiness, _) = ready(&http).await;
+    assert_eq!(status, StatusCode::OK, "{readiness}");
+    assert_eq!(readiness["status"], "ready", "{readiness}");
+    for (dependency, critical) in [
+        ("dataStore", true),
+        ("blobStore", true),
+        ("inMemoryStore", true),
+        ("searchStore", false),
+    ] {
+        let check = find_check(&readiness, dependency);
+        assert_eq!(check["status"], "ok", "{readiness}");
+        assert_eq!(check["critical"], critical, "{readiness}");
+        assert!(check["latencyMs"].is_u64(), "{readiness}");
+    }
+
+    // A hanging critical store fails readiness within the budget
+    set_delay(HealthCheckDependency::BlobStore, 5_000);
+    let (status, readiness, elapsed) = ready(&http).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE, "{readiness}");
+    assert_eq!(readiness["status"], "unavailable", "{readiness}");
+    assert_eq!(
+        find_check(&readiness, "blobStore")["status"],
+        "timeout",
+        "{readiness}"
+    );
+    assert_eq!(
+        find_check(&readiness, "dataStore")["status"],
+        "ok",
+        "{readiness}"
+    );
+    assert!(elapsed < Duration::from_millis(1_500), "{elapsed:?}");
+
+    // Liveness never checks the stores
+    let started = Instant::now();
+    assert_eq!(live(&http).await, StatusCode::OK);
+    assert!(started.elapsed() < Duration::from_millis(500));
+    set_delay(HealthCheckDependency::BlobStore, 0);
+
+    // A hanging optional store is reported but does not fail readiness
+    set_delay(HealthCheckDependency::SearchStore, 5_000);
+    let (status, readiness, elapsed) = ready(&http).await;
+    assert_eq!(status, StatusCode::OK, "{readiness}");
+    assert_eq!(readiness["status"], "ready", "{readiness}");
+    assert_eq!(
+        find_check(&readiness, "searchStore")["status"],
+        "timeout",
+        "{readiness}"
+    );
+    assert!(elapsed < Duration::from_millis(1_500), "{elapsed:?}");
+    set_delay(HealthCheckDependency::SearchStore, 0);
+
+    // Cached results are reused until they expire
+    admin
+        .registry_update_setting(
+            Http {
+                readiness_check_timeout: 2_000u64.into(),
+                readiness_timeout: 500u64.into(),
+                readiness_cache_ttl: 60_000u64.into(),
+                ..Default::default()
+            },
+            &[Property::ReadinessCacheTtl],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Concurrent probes share a single check of the stores
+    set_delay(HealthCheckDependency::DataStore, 300);
+    let results = join_all((0..4).map(|_| ready(&http))).await;
+    for (status, readiness, elapsed) in &results {
+        assert_eq!(*status, StatusCode::OK, "{readiness}");
+        assert_eq!(readiness, &results[0].1);
+        assert!(*elapsed < Duration::from_millis(1_000), "{elapsed:?}");
+    }
+    set_delay(HealthCheckDependency::DataStore, 0);
+
+    // Stores are not checked again while the result is cached
+    set_delay(HealthCheckDependency::BlobStore, 5_000);
+    let (status, readiness, elapsed) = ready(&http).await;
+    assert_eq!(status, StatusCode::OK, "{readiness}");
+    assert_eq!(readiness, results[0].1);
+    assert!(elapsed < Duration::from_millis(200), "{elapsed:?}");
+    set_delay(HealthCheckDependency::BlobStore, 0);
+}
+
+fn set_delay(dependency: HealthCheckDependency, delay: u64) {
+    HEALTH_CHECK_DELAY[dependency as usize].store(delay, Ordering::Relaxed);
+}
+
+async fn ready(http: &HttpRequest) -> (StatusCode, Value, Duration) {
+    let started = Instant::now();
+    let response = http
+        .send_full(Method::GET, "/healthz/ready", None, None)
+        .await;
+    let elapsed = started.elapsed();
+    (
+        response.status,
+        serde_json::from_str(&response.body).unwrap(),
+        elapsed,
+    )
+}
+
+async fn live(http: &HttpRequest) -> StatusCode {
+    http.send_full(Method::GET, "/healthz/live", None, None)
+        .await
+        .status
+}
+
+fn find_check<'x>(readiness: &'x Value, dependency: &str) -> &'x Value {
+    readiness["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|check| check["dependency"] == dependency)
+        .unwrap_or_else(|| panic!("Missing check for {dependency}: {readiness}"))
+}
@@ -4,5 +4,8 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod drain;
+pub mod health;
+pub mod preview;
 pub mod queue;
 pub mod report;
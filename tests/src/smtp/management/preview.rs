@@ -0,0 +1,233 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::TestSession,
+    utils::{
+        account::Account,
+        jmap::RawResponse,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use registry::schema::{
+    enums::{CompressionAlgo, Permission},
+    structs::{Email, Http, QueuedMessage},
+};
+use trc::{Collector, MetricType};
+use types::id::Id;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn queue_message_preview() {
+    let mut test = TestServerBuilder::new("smtp_queue_preview_test")
+        .await
+        .with_http_listener(19073)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Limit the excerpt size
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin.mta_allow_relaying().await;
+    admin.mta_disable_spam_filter().await;
+    admin
+        .registry_create_object(Http {
+            queue_preview_size: 64,
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Staff members are granted the preview permission only
+    let admin = test.account("admin");
+    let staff = admin
+        .create_user_account(
+            "staff@example.org",
+            "staff secret + extra safety",
+            "Staff",
+            &[],
+            vec![Permission::QueuedMessagePreview],
+        )
+        .await;
+    let user = admin
+        .create_user_account(
+            "user@example.org",
+            "user secret + extra safety",
+            "User",
+            &[],
+            vec![],
+        )
+        .await;
+
+    // Queue a HTML message
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.com").await;
+    session
+        .send_message(
+            "john@example.com",
+            &["bill@remote.org"],
+            &[
+                "From: John Doe <john@example.com>",
+                "To: Bill <bill@remote.org>",
+                "Subject: Quarterly report",
+                "Date: Sat, 20 Nov 2021 14:22:01 -0800",
+                "Message-ID: <quarterly-report@example.com>",
+                "X-Spam-Score: ham, score=-1.20",
+                "MIME-Version: 1.0",
+                "Content-Type: text/html; charset=utf-8",
+                "",
+                "<html><body><p>Hello <b>Bill</b>,</p>",
+                "<p>The quarterly numbers are attached. Revenue grew in every region ",
+                "and the forecast for the next quarter has been revised upwards.</p>",
+                "</body></html>",
+            ]
+            .join("\r\n"),
+            "250",
+        )
+        .await;
+    let queue_id = Id::from(test.expect_message().await.queue_id);
+
+    // Users without the preview permission are rejected
+    let response = fetch_preview(&user, queue_id).await;
+    assert_eq!(response.status, 403, "{}", response.text());
+
+    // Headers and a plain text excerpt are returned
+    let response = fetch_preview(&staff, queue_id).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let preview = response.json().unwrap();
+    assert_eq!(preview["subject"], "Quarterly report", "{preview}");
+    assert_eq!(preview["from"][0]["email"], "john@example.com", "{preview}");
+    assert_eq!(preview["from"][0]["name"], "John Doe", "{preview}");
+    assert_eq!(preview["to"][0]["email"], "bill@remote.org", "{preview}");
+    assert_eq!(
+        preview["messageId"], "quarterly-report@example.com",
+        "{preview}"
+    );
+    assert_eq!(preview["date"], "2021-11-20T14:22:01-08:00", "{preview}");
+    assert_eq!(preview["spamScore"], -1.2, "{preview}");
+    assert_eq!(preview["isTruncated"], true, "{preview}");
+    let excerpt = preview["excerpt"].as_str().unwrap();
+    assert!(excerpt.len() <= 64, "{excerpt:?}");
+    assert!(excerpt.contains("Bill"), "{excerpt:?}");
+    assert!(!excerpt.contains('<'), "{excerpt:?}");
+
+    // The preview permission does not grant access to the full message
+    let admin = test.account("admin");
+    let blob_id = admin
+        .registry_get::<QueuedMessage>(queue_id)
+        .await
+        .blob_id
+        .to_string();
+    let response = staff
+        .http_get_raw(
+            &format!(
+                "{}/jmap/download/{}/{blob_id}/message.eml",
+                staff.base_url(),
+                staff.id_string()
+            ),
+            None,
+        )
+        .await;
+    assert!(response.is_client_error(), "{}", response.text());
+
+    // Unknown messages are not found
+    let response = fetch_preview(&staff, Id::from(u64::MAX - 1)).await;
+    assert_eq!(response.status, 404, "{}", response.text());
+
+    // Large compressed messages are decoded in full
+    let queue_id = queue_large_message(&mut test).await;
+    let response = fetch_preview(&staff, queue_id).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let preview = response.json().unwrap();
+    assert_eq!(preview["subject"], "Server logs", "{preview}");
+    assert_eq!(preview["isTruncated"], true, "{preview}");
+    assert!(
+        preview["excerpt"]
+            .as_str()
+            .unwrap()
+            .starts_with("Lorem ipsum dolor sit amet"),
+        "{preview}"
+    );
+
+    // Previewing a large uncompressed message only reads the first chunk
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(Email {
+            compression_algorithm: CompressionAlgo::None,
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    let queue_id = queue_large_message(&mut test).await;
+    let bytes_read = Collector::read_metric(MetricType::StoreBlobReadBytes);
+    let response = fetch_preview(&staff, queue_id).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let bytes_read = Collector::read_metric(MetricType::StoreBlobReadBytes) - bytes_read;
+    let preview = response.json().unwrap();
+    assert_eq!(preview["subject"], "Server logs", "{preview}");
+    assert_eq!(preview["isTruncated"], true, "{preview}");
+    assert!(preview["size"].as_u64().unwrap() > 25_000_000, "{preview}");
+    assert!(
+        preview["excerpt"]
+            .as_str()
+            .unwrap()
+            .starts_with("Lorem ipsum dolor sit amet"),
+        "{preview}"
+    );
+    assert!(
+        bytes_read > 0.0 && bytes_read < 64.0 * 1024.0,
+        "read {bytes_read} bytes from the blob store"
+    );
+}
+
+async fn queue_large_message(test: &mut TestServer) -> Id {
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.eval_session_params().await;
+    session.ehlo("mx.example.com").await;
+    let body = "Lorem ipsum dolor sit amet, consectetur adipiscing elit.\r\n"
+        .repeat(25 * 1024 * 1024 / 58);
+    session
+        .send_message(
+            "john@example.com",
+            &["bill@remote.org"],
+            &format!(
+                concat!(
+                    "From: john@example.com\r\n",
+                    "To: bill@remote.org\r\n",
+                    "Subject: Server logs\r\n",
+                    "\r\n{}"
+                ),
+                body
+            ),
+            "250",
+        )
+        .await;
+    Id::from(test.expect_message().await.queue_id)
+}
+
+async fn fetch_preview(account: &Account, queue_id: Id) -> RawResponse {
+    account
+        .http_get_raw(
+            &format!(
+                "{}/api/queue/messages/{queue_id}/preview",
+                account.base_url()
+            ),
+            None,
+        )
+        .await
+}
@@ -90,6 +90,7 @@ async fn manage_queue() {
             name: "myqueue".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -107,9 +108,11 @@ async fn manage_queue() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 3_000_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -0,0 +1,260 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{DummyIo, TestSession},
+    utils::{
+        dns::DnsCache,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use common::auth::{AccountCache, AccountInfo};
+use mail_auth::{DnssecStatus, MX};
+use registry::schema::{
+    prelude::ObjectType,
+    structs::{
+        CertificateManagement, DkimManagement, DnsManagement, Domain, SecretKeyOptional,
+        SecretKeyValue,
+    },
+};
+use serde_json::json;
+use smtp::core::Session;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use store::write::now;
+
+const BOUNCE: &str = concat!(
+    "From: MAILER-DAEMON@remote.org\r\n",
+    "To: john@example.org\r\n",
+    "Subject: Undelivered Mail Returned to Sender\r\n",
+    "\r\n",
+    "The message could not be delivered.\r\n"
+);
+
+#[tokio::test]
+#[serial_test::serial]
+async fn batv() {
+    let mut local = TestServerBuilder::new("smtp_batv_local")
+        .await
+        .with_http_listener(19088)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_batv_remote")
+        .await
+        .with_http_listener(19089)
+        .await
+        .with_smtp_listener(9925)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Sign the envelope sender of messages sent from example.org
+    let local_admin = local.account("admin");
+    let domain_id = local_admin
+        .registry_create_object(Domain {
+            name: "example.org".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            batv_key: secret("batv secret key"),
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .create_user_account(
+            "john@example.org",
+            "12345 + extra safety",
+            "John",
+            &[],
+            vec![],
+        )
+        .await;
+    local_admin.mta_no_auth().await;
+    local_admin.mta_allow_relaying().await;
+    local_admin.mta_disable_spam_filter().await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_disable_spam_filter().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    local.server.mx_add(
+        "remote.org",
+        vec![MX {
+            exchanges: vec!["mx.remote.org".into()].into_boxed_slice(),
+            preference: 10,
+        }],
+        DnssecStatus::Secure,
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.ipv4_add(
+        "mx.remote.org",
+        vec!["127.0.0.1".parse().unwrap()],
+        Instant::now() + Duration::from_secs(10),
+    );
+
+    // Messages submitted by local users are signed
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session.data.authenticated_as = Some(AccountInfo {
+        account_id: u32::MAX,
+        addresses: vec!["john@example.org".into()],
+        account: Arc::new(AccountCache {
+            name: "john@example.org".into(),
+            ..Default::default()
+        }),
+    });
+    let signed = deliver(&mut local, &mut remote, &mut session).await;
+    assert!(
+        signed.starts_with("prvs=0") && signed.ends_with("=john@example.org"),
+        "{signed}"
+    );
+    assert_eq!(signed.len(), "prvs=0123abcdef=john@example.org".len());
+
+    // Forwarded messages keep their original envelope sender
+    session.data.authenticated_as = None;
+    assert_eq!(
+        deliver(&mut local, &mut remote, &mut session).await,
+        "john@example.org"
+    );
+
+    // Bounces to signed addresses are delivered to the untagged address
+    assert_bounce(&mut local, &mut session, &signed, "250").await;
+
+    // Forged and expired tags are rejected
+    let day = signed[6..9].parse::<u64>().unwrap();
+    let forged = format!("prvs=0{:03}{}", (day + 1) % 1000, &signed[9..]);
+    assert_bounce(&mut local, &mut session, &forged, "550 5.7.1").await;
+    assert_bounce(
+        &mut local,
+        &mut session,
+        "prvs=0123abcdef=john@example.org",
+        "550 5.7.1",
+    )
+    .await;
+    let expired = local
+        .server
+        .domain("example.org")
+        .await
+        .unwrap()
+        .unwrap()
+        .batv
+        .as_ref()
+        .unwrap()
+        .sign("john", "example.org", now() - 8 * 86400);
+    assert_bounce(&mut local, &mut session, &expired, "550 5.7.1").await;
+
+    // Tags are removed from non-bounce messages without being validated
+    session.mail_from("bill@remote.org", "250").await;
+    session.rcpt_to(&forged, "250").await;
+    assert_eq!(session.data.rcpt_to[0].address_lcase, "john@example.org");
+    session.rset().await;
+
+    // Tags signed with the previous key remain valid after a key rotation
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_update_object(
+            ObjectType::Domain,
+            domain_id,
+            json!({
+                "batvKey": {"@type": "Value", "secret": "new batv secret key"},
+                "batvPreviousKey": {"@type": "Value", "secret": "batv secret key"},
+            }),
+        )
+        .await;
+    session.data.authenticated_as = Some(AccountInfo {
+        account_id: u32::MAX,
+        addresses: vec!["john@example.org".into()],
+        account: Arc::new(AccountCache {
+            name: "john@example.org".into(),
+            ..Default::default()
+        }),
+    });
+    let rotated = deliver(&mut local, &mut remote, &mut session).await;
+    assert_ne!(rotated, signed);
+    session.data.authenticated_as = None;
+    assert_bounce(&mut local, &mut session, &rotated, "250").await;
+    assert_bounce(&mut local, &mut session, &signed, "250").await;
+
+    // Once the previous key is removed its tags are no longer accepted
+    local_admin
+        .registry_update_object(
+            ObjectType::Domain,
+            domain_id,
+            json!({
+                "batvPreviousKey": {"@type": "None"},
+            }),
+        )
+        .await;
+    assert_bounce(&mut local, &mut session, &signed, "550 5.7.1").await;
+    assert_bounce(&mut local, &mut session, &rotated, "250").await;
+}
+
+async fn deliver(
+    local: &mut TestServer,
+    remote: &mut TestServer,
+    session: &mut Session<DummyIo>,
+) -> String {
+    session
+        .send_message(
+            "john@example.org",
+            &["bill@remote.org"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    remote
+        .consume_message()
+        .await
+        .message
+        .return_path
+        .to_string()
+}
+
+async fn assert_bounce(
+    local: &mut TestServer,
+    session: &mut Session<DummyIo>,
+    rcpt: &str,
+    expected_code: &str,
+) {
+    session.mail_from("<>", "250").await;
+    session.rcpt_to(rcpt, expected_code).await;
+    if expected_code == "250" {
+        session.data(BOUNCE, "250").await;
+        let message = local.consume_message().await;
+        assert_eq!(message.message.recipients.len(), 1);
+        assert_eq!(message.message.recipients[0].address(), "john@example.org");
+    } else {
+        session.rset().await;
+    }
+}
+
+fn secret(value: &str) -> SecretKeyOptional {
+    SecretKeyOptional::Value(SecretKeyValue {
+        secret: value.into(),
+    })
+}
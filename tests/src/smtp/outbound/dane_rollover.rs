@@ -0,0 +1,288 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    dns::DnsCache,
+    server::{TestServer, TestServerBuilder},
+};
+use common::{
+    config::smtp::resolver::{Tlsa, TlsaEntry, TlsaMatching},
+    ipc::RegistryChange,
+};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose};
+use registry::{
+    schema::{
+        prelude::{ObjectType, Property},
+        structs::{
+            Certificate, MailExchanger, PublicText, PublicTextValue, SecretText, SecretTextValue,
+            SystemSettings, Task, TaskDomainManagement, TaskStatus,
+        },
+    },
+    types::{datetime::UTCDateTime, list::List, map::Map},
+};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use store::{
+    registry::write::{RegistryWrite, RegistryWriteResult},
+    write::now,
+};
+use types::id::Id;
+use utils::HexEncode;
+
+const HTTP_PORT: u16 = 19097;
+const MX_HOST: &str = "mx.dane.org";
+const TLSA_NAME: &str = "_25._tcp.mx.dane.org.";
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dane_rollover() {
+    let mut test = TestServerBuilder::new("smtp_dane_rollover_test")
+        .await
+        .with_http_listener(HTTP_PORT)
+        .await
+        .build()
+        .await;
+
+    let admin = test.account("admin");
+    admin
+        .registry_update_setting(
+            SystemSettings {
+                mail_exchangers: List::from_iter([MailExchanger {
+                    priority: 10u64,
+                    hostname: MX_HOST.to_string().into(),
+                }]),
+                ..Default::default()
+            },
+            &[Property::MailExchangers],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    let admin = test.account("admin");
+    let domain_id = admin.find_or_create_domain("dane.org").await;
+
+    // Serve the current certificate and stage the next one
+    let ca = TestCa::new();
+    let current = ca.leaf(MX_HOST);
+    let next = ca.leaf(MX_HOST);
+    insert_certificate(&test, &current, 1, None).await;
+    let next_id = insert_certificate(&test, &next, 2, Some(now() as i64 + 3600)).await;
+    reload_certificates(&test).await;
+    assert_eq!(served_leaf(&test), current.der);
+
+    // Only the current certificate is published
+    test.server.tlsa_add(
+        TLSA_NAME,
+        Arc::new(tlsa([&current])),
+        Instant::now() + Duration::from_secs(3600),
+    );
+    let report = get_report(&test).await;
+    let hostname = &report["hostnames"][0];
+    assert_eq!(hostname["hostname"], MX_HOST, "{report}");
+    assert_eq!(hostname["name"], TLSA_NAME, "{report}");
+    assert_eq!(hostname["status"], "published", "{report}");
+    assert_eq!(hostname["published"], json!([current.record()]), "{report}");
+    let certificates = hostname["certificates"].as_array().unwrap();
+    assert_eq!(certificates.len(), 2, "{report}");
+    assert_eq!(certificates[0]["stage"], "active", "{report}");
+    assert_eq!(certificates[0]["matches"], true, "{report}");
+    assert_eq!(certificates[0]["records"][0], current.record(), "{report}");
+    assert_eq!(certificates[1]["stage"], "staged", "{report}");
+    assert_eq!(certificates[1]["id"], next_id.to_string(), "{report}");
+    assert_eq!(certificates[1]["matches"], false, "{report}");
+    assert_eq!(certificates[1]["records"][0], next.record(), "{report}");
+    let warnings = report["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1, "{report}");
+    assert!(
+        warnings[0].as_str().unwrap().contains(&next_id.to_string()),
+        "{report}"
+    );
+
+    // The staged certificate is not activated while its records are missing
+    admin
+        .registry_create_object(Task::DaneRollover(TaskDomainManagement {
+            domain_id,
+            status: TaskStatus::now(),
+        }))
+        .await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(staged_until(&test, next_id).await.is_some());
+    assert_eq!(served_leaf(&test), current.der);
+
+    // Publishing the records of the next certificate activates it
+    test.server.tlsa_add(
+        TLSA_NAME,
+        Arc::new(tlsa([&current, &next])),
+        Instant::now() + Duration::from_secs(3600),
+    );
+    wait_for_activation(&test, next_id).await;
+    assert_eq!(served_leaf(&test), next.der);
+    let report = get_report(&test).await;
+    let certificates = report["hostnames"][0]["certificates"].as_array().unwrap();
+    assert_eq!(certificates.len(), 1, "{report}");
+    assert_eq!(certificates[0]["matches"], true, "{report}");
+    assert_eq!(report["warnings"], json!([]), "{report}");
+
+    // Staged certificates are activated once the maximum delay expires
+    let last = ca.leaf(MX_HOST);
+    let last_id = insert_certificate(&test, &last, 3, Some(now() as i64 + 3)).await;
+    admin
+        .registry_create_object(Task::DaneRollover(TaskDomainManagement {
+            domain_id,
+            status: TaskStatus::now(),
+        }))
+        .await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(staged_until(&test, last_id).await.is_some());
+    wait_for_activation(&test, last_id).await;
+    assert_eq!(served_leaf(&test), last.der);
+}
+
+struct TestCa {
+    issuer: Issuer<'static, KeyPair>,
+    pem: String,
+}
+
+struct TestLeaf {
+    der: Vec<u8>,
+    pem: String,
+    key_pem: String,
+    spki: Vec<u8>,
+}
+
+impl TestCa {
+    fn new() -> Self {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "DANE Rollover CA");
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let cert = params.self_signed(&key).unwrap();
+        TestCa {
+            pem: cert.pem(),
+            issuer: Issuer::new(params, key),
+        }
+    }
+
+    fn leaf(&self, san: &str) -> TestLeaf {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec![san.to_string()]).unwrap();
+        params.distinguished_name.push(DnType::CommonName, san);
+        let cert = params.signed_by(&key, &self.issuer).unwrap();
+        TestLeaf {
+            der: cert.der().to_vec(),
+            pem: format!("{}{}", cert.pem(), self.pem),
+            key_pem: key.serialize_pem(),
+            spki: key.subject_public_key_info(),
+        }
+    }
+}
+
+impl TestLeaf {
+    fn record(&self) -> String {
+        format!("3 1 1 {}", Sha256::digest(&self.spki).hex_encode())
+    }
+}
+
+fn tlsa<'x>(leaves: impl IntoIterator<Item = &'x TestLeaf>) -> Tlsa {
+    Tlsa {
+        entries: leaves
+            .into_iter()
+            .map(|leaf| TlsaEntry {
+                is_end_entity: true,
+                is_spki: true,
+                matching: TlsaMatching::Sha256,
+                data: Sha256::digest(&leaf.spki).to_vec(),
+            })
+            .collect(),
+        has_end_entities: true,
+        has_intermediates: false,
+    }
+}
+
+// Newer certificates expire later, so they take precedence once served
+async fn insert_certificate(
+    test: &TestServer,
+    leaf: &TestLeaf,
+    valid_days: i64,
+    staged_until: Option<i64>,
+) -> Id {
+    let now = now() as i64;
+    match test
+        .server
+        .registry()
+        .write(RegistryWrite::insert(
+            &Certificate {
+                certificate: PublicText::Text(PublicTextValue {
+                    value: leaf.pem.clone(),
+                }),
+                private_key: SecretText::Text(SecretTextValue {
+                    secret: leaf.key_pem.clone(),
+                }),
+                issuer: "DANE Rollover CA".to_string(),
+                not_valid_after: UTCDateTime::from_timestamp(now + valid_days * 86400),
+                not_valid_before: UTCDateTime::from_timestamp(now - 10),
+                subject_alternative_names: Map::new(vec![MX_HOST.to_string()]),
+                staged_until: staged_until.map(UTCDateTime::from_timestamp),
+            }
+            .into(),
+        ))
+        .await
+        .unwrap()
+    {
+        RegistryWriteResult::Success(id) => id,
+        err => panic!("Failed to insert certificate: {err:?}"),
+    }
+}
+
+async fn reload_certificates(test: &TestServer) {
+    test.server
+        .reload_registry(RegistryChange::Reload(ObjectType::Certificate))
+        .await
+        .unwrap();
+}
+
+async fn staged_until(test: &TestServer, id: Id) -> Option<UTCDateTime> {
+    test.server
+        .registry()
+        .object::<Certificate>(id)
+        .await
+        .unwrap()
+        .unwrap()
+        .staged_until
+}
+
+async fn wait_for_activation(test: &TestServer, id: Id) {
+    for _ in 0..50 {
+        if staged_until(test, id).await.is_none() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    panic!("Staged certificate {id} was not activated");
+}
+
+fn served_leaf(test: &TestServer) -> Vec<u8> {
+    test.server
+        .resolve_certificate(MX_HOST)
+        .expect("No certificate served for MX host")
+        .cert[0]
+        .to_vec()
+}
+
+async fn get_report(test: &TestServer) -> Value {
+    let admin = test.account("admin");
+    let response = admin
+        .http_get_raw(&format!("{}/api/dane", admin.base_url()), None)
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
@@ -0,0 +1,278 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::{TestSession, VerifyResponse},
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use aws_lc_rs::hmac;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use common::manager::application::Resource;
+use http_proto::{ToHttpResponse, request::fetch_body};
+use hyper::{body, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use jmap_proto::error::set::SetErrorType;
+use mail_auth::{DnssecStatus, MX};
+use registry::schema::structs::{
+    DeliveryCallback, Expression, MtaStageAuth, MtaStageRcpt, SecretKeyOptional, SecretKeyValue,
+};
+use smtp::core::State;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use store::parking_lot::Mutex;
+use tokio::net::TcpListener;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn delivery_callback() {
+    let mut local = TestServerBuilder::new("smtp_delivery_callback_local")
+        .await
+        .with_http_listener(19055)
+        .await
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_delivery_callback_remote")
+        .await
+        .with_http_listener(19056)
+        .await
+        .with_smtp_listener(9925)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Spawn mock callback endpoint, the first request fails
+    let fail_next = Arc::new(AtomicBool::new(true));
+    let events = spawn_mock_callback_endpoint(fail_next.clone());
+
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(MtaStageRcpt {
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let john = local_admin
+        .create_user_account("john@test.org", "abcde + extra safety", "John", &[], vec![])
+        .await;
+    let callback_id = local_admin
+        .registry_create_object(DeliveryCallback {
+            account_id: john.id(),
+            url: "http://127.0.0.1:8822/callback".into(),
+            signature_key: SecretKeyOptional::Value(SecretKeyValue {
+                secret: "ovos-moles".into(),
+            }),
+            throttle: 100u64.into(),
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaStageAuth {
+            require: Expression {
+                else_: "false".into(),
+                ..Default::default()
+            },
+            sasl_mechanisms: Expression {
+                else_: "[plain]".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    for (domain, mx, ip) in [
+        ("foobar.org", "mx.foobar.org", "127.0.0.1"),
+        ("unreachable.org", "mx.unreachable.org", "127.0.0.9"),
+    ] {
+        local.server.mx_add(
+            domain,
+            vec![MX {
+                exchanges: vec![mx.into()].into_boxed_slice(),
+                preference: 10,
+            }],
+            DnssecStatus::Secure,
+            Instant::now() + Duration::from_secs(10),
+        );
+        local.server.ipv4_add(
+            mx,
+            vec![ip.parse().unwrap()],
+            Instant::now() + Duration::from_secs(10),
+        );
+        local
+            .server
+            .ipv6_add(mx, vec![], Instant::now() + Duration::from_secs(10));
+    }
+
+    // Callbacks cannot target internal addresses
+    for url in [
+        "http://169.254.169.254/latest/meta-data",
+        "http://10.0.0.1/callback",
+        "http://[fe80::1]/callback",
+    ] {
+        local_admin
+            .registry_create_object_expect_err(DeliveryCallback {
+                account_id: john.id(),
+                url: url.into(),
+                ..Default::default()
+            })
+            .await
+            .assert_type(SetErrorType::InvalidProperties)
+            .assert_description_contains("must not point to a private");
+    }
+
+    // X-CALLBACK is not offered on port 25
+    let mail_from = format!("MAIL FROM:<john@test.org> X-CALLBACK={callback_id}");
+    let mut session = local.new_mta_session();
+    session.data.local_port = 25;
+    session.eval_session_params().await;
+    session
+        .ehlo("mx.test.org")
+        .await
+        .assert_not_contains("X-CALLBACK");
+    session.cmd(&mail_from, "504 5.5.4").await;
+
+    // X-CALLBACK requires an authenticated session
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.data.remote_ip = session.data.remote_ip_str.parse().unwrap();
+    session.stream.tls = true;
+    session.eval_session_params().await;
+    session
+        .ehlo("mx.test.org")
+        .await
+        .assert_contains("X-CALLBACK");
+    session.cmd(&mail_from, "503 5.5.1").await;
+    assert!(session.data.mail_from.is_none());
+
+    // Unknown callbacks are rejected
+    session
+        .auth_plain("john@test.org", "abcde + extra safety", "235")
+        .await;
+    session
+        .cmd(
+            "MAIL FROM:<john@test.org> X-CALLBACK=invalid-id",
+            "501 5.5.4",
+        )
+        .await;
+    assert!(session.data.mail_from.is_none());
+
+    // Queue a message with a delivery callback attached
+    session.cmd(&mail_from, "250").await;
+    assert_eq!(session.data.delivery_callback, Some(callback_id.id()));
+    session.rcpt_to("bill@foobar.org", "250").await;
+    session.rcpt_to("jane@unreachable.org", "250").await;
+    session.data("test:no_dkim", "250").await;
+    assert!(matches!(session.state, State::Accepted(_)));
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+    remote.expect_message().await;
+
+    // Failed requests are retried by the task manager
+    local.wait_for_tasks_skip_failures().await;
+    assert!(!fail_next.load(Ordering::Relaxed));
+
+    // One event per recipient is expected
+    let events = events.lock().drain(..).collect::<Vec<_>>();
+    assert_eq!(events.len(), 2, "{events:?}");
+    for (rcpt, status) in [
+        ("bill@foobar.org", "delivered"),
+        ("jane@unreachable.org", "deferred"),
+    ] {
+        let event = events
+            .iter()
+            .find(|event| event["recipient"] == rcpt)
+            .unwrap_or_else(|| panic!("Missing event for {rcpt}: {events:?}"));
+        assert_eq!(event["status"], status, "{event:?}");
+        assert!(event["queueId"].as_str().is_some_and(|id| !id.is_empty()));
+    }
+}
+
+fn spawn_mock_callback_endpoint(fail_next: Arc<AtomicBool>) -> Arc<Mutex<Vec<serde_json::Value>>> {
+    let events_ = Arc::new(Mutex::new(vec![]));
+    let events = events_.clone();
+
+    tokio::spawn(async move {
+        let listener = TcpListener::bind("127.0.0.1:8822")
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock callback server to 127.0.0.1:8822: {e}");
+            });
+
+        while let Ok((stream, _)) = listener.accept().await {
+            let events = events.clone();
+            let fail_next = fail_next.clone();
+            let _ = http1::Builder::new()
+                .keep_alive(false)
+                .serve_connection(
+                    TokioIo::new(stream),
+                    service_fn(move |mut req: hyper::Request<body::Incoming>| {
+                        let events = events.clone();
+                        let fail_next = fail_next.clone();
+
+                        async move {
+                            if fail_next.swap(false, Ordering::Relaxed) {
+                                return Ok::<_, hyper::Error>(
+                                    Resource::new("text/plain", b"unavailable".to_vec())
+                                        .into_http_response()
+                                        .with_status_code(hyper::StatusCode::SERVICE_UNAVAILABLE)
+                                        .build(),
+                                );
+                            }
+
+                            // Verify HMAC signature
+                            let key = hmac::Key::new(hmac::HMAC_SHA256, "ovos-moles".as_bytes());
+                            let body = fetch_body(&mut req, usize::MAX, 0).await.unwrap();
+                            let tag = STANDARD
+                                .decode(req.headers().get("X-Signature").unwrap().to_str().unwrap())
+                                .unwrap();
+                            hmac::verify(&key, &body, &tag).expect("Invalid signature");
+
+                            #[derive(serde::Deserialize)]
+                            struct CallbackRequest {
+                                events: Vec<serde_json::Value>,
+                            }
+                            let request = serde_json::from_slice::<CallbackRequest>(&body)
+                                .expect("Failed to parse JSON");
+                            events.lock().extend(request.events);
+
+                            Ok::<_, hyper::Error>(
+                                Resource::new("application/json", "[]".to_string().into_bytes())
+                                    .into_http_response()
+                                    .build(),
+                            )
+                        }
+                    }),
+                )
+                .await;
+        }
+    });
+
+    events_
+}
@@ -65,6 +65,7 @@ async fn fallback_relay() {
                     then: "'fallback'".into(),
                 }]),
                 else_: "'mx'".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
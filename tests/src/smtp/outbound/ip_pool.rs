@@ -0,0 +1,202 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{inbound::TestQueueEvent, session::TestSession},
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use mail_auth::{DnssecStatus, MX};
+use registry::{
+    schema::{
+        enums::MtaIpSelection,
+        structs::{
+            Expression, ExpressionMatch, MtaConnectionIpHost, MtaConnectionStrategy,
+            MtaOutboundStrategy, MtaStageRcpt,
+        },
+    },
+    types::{datetime::UTCDateTime, ipaddr::IpAddr, list::List},
+};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use store::write::now;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn ip_pool() {
+    let mut local = TestServerBuilder::new("smtp_ip_pool_local")
+        .await
+        .with_http_listener(19051)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_ip_pool_remote")
+        .await
+        .with_http_listener(19052)
+        .await
+        .with_smtp_listener(9925)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Marketing mail is sent from a pool of two addresses under warmup,
+    // everything else from a dedicated transactional address
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(MtaStageRcpt {
+            max_recipients: Expression {
+                else_: "100".into(),
+                ..Default::default()
+            },
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaOutboundStrategy {
+            connection: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "sender_domain == 'marketing.org'".into(),
+                    then: "'marketing'".into(),
+                }]),
+                else_: "'transactional'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaConnectionStrategy {
+            name: "transactional".into(),
+            source_ips: List::from_iter([MtaConnectionIpHost {
+                source_ip: IpAddr::from_str("127.0.0.2").unwrap(),
+                ehlo_hostname: "mx-tx.example.org".to_string().into(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaConnectionStrategy {
+            name: "marketing".into(),
+            source_ip_selection: MtaIpSelection::RoundRobin,
+            source_ips: List::from_iter(["127.0.0.3", "127.0.0.4"].into_iter().map(|ip| {
+                MtaConnectionIpHost {
+                    source_ip: IpAddr::from_str(ip).unwrap(),
+                    ehlo_hostname: "mx-bulk.example.org".to_string().into(),
+                    warmup_start: UTCDateTime::from_timestamp(now() as i64).into(),
+                    warmup_days: 30,
+                    warmup_initial_limit: 1,
+                    warmup_target_limit: 1000,
+                    ..Default::default()
+                }
+            })),
+            ..Default::default()
+        })
+        .await;
+    local_admin.mta_no_auth().await;
+    local_admin.mta_disable_spam_filter().await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_disable_spam_filter().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    local.server.mx_add(
+        "foobar.org",
+        vec![MX {
+            exchanges: vec!["mx.foobar.org".into()].into_boxed_slice(),
+            preference: 10,
+        }],
+        DnssecStatus::Secure,
+        Instant::now() + Duration::from_secs(10),
+    );
+    local.server.ipv4_add(
+        "mx.foobar.org",
+        vec!["127.0.0.1".parse().unwrap()],
+        Instant::now() + Duration::from_secs(10),
+    );
+
+    // Transactional messages use the transactional pool
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session
+        .send_message("john@test.org", &["bill@foobar.org"], "test:no_dkim", "250")
+        .await;
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        remote.expect_message().await.message.received_from_ip,
+        "127.0.0.2".parse::<std::net::IpAddr>().unwrap()
+    );
+
+    // Marketing messages rotate through the warmup pool
+    let mut used_ips = Vec::new();
+    for _ in 0..2 {
+        session
+            .send_message(
+                "newsletter@marketing.org",
+                &["bill@foobar.org"],
+                "test:no_dkim",
+                "250",
+            )
+            .await;
+        local
+            .expect_message_then_deliver()
+            .await
+            .try_deliver(local.server.clone());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        used_ips.push(remote.expect_message().await.message.received_from_ip);
+    }
+    used_ips.sort();
+    assert_eq!(
+        used_ips,
+        vec![
+            "127.0.0.3".parse::<std::net::IpAddr>().unwrap(),
+            "127.0.0.4".parse::<std::net::IpAddr>().unwrap()
+        ]
+    );
+
+    // Both addresses reached their daily warmup limit, delivery is deferred
+    session
+        .send_message(
+            "newsletter@marketing.org",
+            &["bill@foobar.org"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    remote.assert_no_events();
+    let message = local.last_queued_message().await;
+    assert_eq!(&*message.message.return_path, "newsletter@marketing.org");
+    assert!(local.last_queued_due().await > now());
+}
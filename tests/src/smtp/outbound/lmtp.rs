@@ -74,6 +74,7 @@ async fn lmtp_delivery() {
                     then: "'lmtp'".into(),
                 }]),
                 else_: "'mx'".into(),
+                ..Default::default()
             },
             schedule: Expression {
                 match_: List::from_iter([ExpressionMatch {
@@ -81,6 +82,7 @@ async fn lmtp_delivery() {
                     then: "'foobar'".into(),
                 }]),
                 else_: "'default'".into(),
+                ..Default::default()
             },
             connection: Expression {
                 else_: "'impatient'".into(),
@@ -105,6 +107,7 @@ async fn lmtp_delivery() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -127,9 +130,11 @@ async fn lmtp_delivery() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 4_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -147,9 +152,11 @@ async fn lmtp_delivery() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 5_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -4,12 +4,19 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
+pub mod batv;
 pub mod dane;
+pub mod dane_rollover;
+pub mod delivery_callback;
 pub mod extensions;
 pub mod fallback_relay;
 pub mod ip_lookup;
+pub mod ip_pool;
 pub mod lmtp;
 pub mod mta_sts;
+pub mod relay_health;
 pub mod smtp;
+pub mod split;
 pub mod throttle;
 pub mod tls;
+pub mod unsubscribe;
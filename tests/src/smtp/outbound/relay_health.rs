@@ -0,0 +1,282 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{inbound::TestQueueEvent, session::TestSession},
+    utils::{
+        dns::DnsCache,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use registry::{
+    schema::{
+        enums::{MtaIpSelection, MtaProtocol},
+        structs::{
+            Expression, MtaOutboundStrategy, MtaRelayHost, MtaRoute, MtaRouteRelay, MtaStageRcpt,
+        },
+    },
+    types::{duration::Duration as RegistryDuration, list::List},
+};
+use smtp::outbound::health::RelayHealthCheck;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+};
+
+#[derive(Default)]
+struct MockRelay {
+    failing: AtomicBool,
+    delivered: AtomicUsize,
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn relay_health() {
+    let mut local = TestServerBuilder::new("smtp_relay_health")
+        .await
+        .with_http_listener(19083)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let relay_a = spawn_mock_relay(8824);
+    let relay_b = spawn_mock_relay(8825);
+
+    // Relay route with two hosts, a single failure marks a host as down
+    let admin = local.account("admin");
+    admin
+        .registry_create_object(MtaStageRcpt {
+            max_recipients: Expression {
+                else_: "100".into(),
+                ..Default::default()
+            },
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            route: Expression {
+                else_: "'balanced'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaRoute::Relay(MtaRouteRelay {
+            address: "relay-a.foobar.org".into(),
+            port: 8824,
+            additional_hosts: List::from_iter([MtaRelayHost {
+                address: "relay-b.foobar.org".into(),
+                port: 8825,
+                ..Default::default()
+            }]),
+            host_selection: MtaIpSelection::RoundRobin,
+            failure_threshold: 1,
+            failure_cool_off: RegistryDuration::from_millis(60_000),
+            health_check_interval: Some(RegistryDuration::from_millis(1000)),
+            implicit_tls: false,
+            allow_invalid_certs: true,
+            name: "balanced".into(),
+            protocol: MtaProtocol::Smtp,
+            ..Default::default()
+        }))
+        .await;
+    admin.mta_no_auth().await;
+    admin.mta_all_extensions().await;
+    admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    for host in ["relay-a.foobar.org", "relay-b.foobar.org"] {
+        local.server.ipv4_add(
+            host,
+            vec!["127.0.0.1".parse().unwrap()],
+            Instant::now() + Duration::from_secs(60),
+        );
+    }
+
+    // Hosts are used in turn while both are healthy
+    for _ in 0..2 {
+        send_and_deliver(&mut local, &[&relay_a, &relay_b]).await;
+    }
+    assert_eq!(relay_a.delivered.load(Ordering::Relaxed), 1);
+    assert_eq!(relay_b.delivered.load(Ordering::Relaxed), 1);
+
+    // Traffic moves to the remaining host once relay-a starts failing
+    relay_a.failing.store(true, Ordering::Relaxed);
+    for _ in 0..4 {
+        send_and_deliver(&mut local, &[&relay_a, &relay_b]).await;
+    }
+    assert_eq!(relay_a.delivered.load(Ordering::Relaxed), 1);
+    assert_eq!(relay_b.delivered.load(Ordering::Relaxed), 5);
+
+    let hosts = fetch_relay_hosts(&local).await;
+    assert_eq!(hosts[0]["address"], "relay-a.foobar.org", "{hosts:?}");
+    assert_eq!(hosts[0]["status"], "down", "{hosts:?}");
+    assert_eq!(hosts[0]["consecutiveFailures"], 1, "{hosts:?}");
+    assert!(hosts[0]["downUntil"].as_u64().is_some(), "{hosts:?}");
+    assert_eq!(hosts[1]["address"], "relay-b.foobar.org", "{hosts:?}");
+    assert_eq!(hosts[1]["status"], "up", "{hosts:?}");
+    assert_eq!(hosts[1]["activeConnections"], 0, "{hosts:?}");
+
+    // Reloading the configuration keeps the health of relay hosts
+    admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+    let hosts = fetch_relay_hosts(&local).await;
+    assert_eq!(hosts[0]["status"], "down", "{hosts:?}");
+    assert_eq!(hosts[0]["consecutiveFailures"], 1, "{hosts:?}");
+
+    // Failed probes keep the host down
+    probe_relay_hosts(&local).await;
+    assert_eq!(fetch_relay_hosts(&local).await[0]["status"], "down");
+
+    // A successful probe brings the host back before its cool-off expires
+    relay_a.failing.store(false, Ordering::Relaxed);
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    probe_relay_hosts(&local).await;
+    let hosts = fetch_relay_hosts(&local).await;
+    assert_eq!(hosts[0]["status"], "up", "{hosts:?}");
+    assert_eq!(hosts[0]["consecutiveFailures"], 0, "{hosts:?}");
+    assert!(hosts[0]["downUntil"].is_null(), "{hosts:?}");
+
+    for _ in 0..2 {
+        send_and_deliver(&mut local, &[&relay_a, &relay_b]).await;
+    }
+    assert_eq!(relay_a.delivered.load(Ordering::Relaxed), 2);
+    assert_eq!(relay_b.delivered.load(Ordering::Relaxed), 6);
+
+    local.assert_no_events();
+    local.assert_queue_is_empty().await;
+}
+
+async fn send_and_deliver(local: &mut TestServer, relays: &[&Arc<MockRelay>]) {
+    let delivered = || {
+        relays
+            .iter()
+            .map(|relay| relay.delivered.load(Ordering::Relaxed))
+            .sum::<usize>()
+    };
+    let expected = delivered() + 1;
+
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session
+        .send_message("john@test.org", &["bill@foobar.org"], "test:no_dkim", "250")
+        .await;
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+
+    for _ in 0..50 {
+        if delivered() == expected {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(delivered(), expected);
+    local.read_event().await.assert_done();
+}
+
+async fn probe_relay_hosts(local: &TestServer) {
+    let (hosts, next_probe) = local.server.schedule_relay_probes();
+    assert_eq!(
+        hosts,
+        [("balanced".to_string(), 0), ("balanced".to_string(), 1)]
+    );
+    assert!(next_probe.is_some_and(|next| next <= Duration::from_secs(1)));
+    local.server.probe_relay_hosts(hosts).await;
+}
+
+async fn fetch_relay_hosts(local: &TestServer) -> Vec<serde_json::Value> {
+    let admin = local.account("admin");
+    let response = admin
+        .http_get_raw(&format!("{}/api/queue/routes", admin.base_url()), None)
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let routes = response.json().unwrap();
+    assert_eq!(routes[0]["name"], "balanced", "{routes}");
+    assert_eq!(routes[0]["hostSelection"], "roundRobin", "{routes}");
+    routes[0]["hosts"].as_array().unwrap().clone()
+}
+
+fn spawn_mock_relay(port: u16) -> Arc<MockRelay> {
+    let relay = Arc::new(MockRelay::default());
+    let relay_ = relay.clone();
+
+    tokio::spawn(async move {
+        let listener = TcpListener::bind(format!("127.0.0.1:{port}"))
+            .await
+            .unwrap_or_else(|e| {
+                panic!("Failed to bind mock relay to 127.0.0.1:{port}: {e}");
+            });
+
+        while let Ok((stream, _)) = listener.accept().await {
+            let relay = relay_.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                if relay.failing.load(Ordering::Relaxed) {
+                    let _ = writer.write_all(b"554 5.3.2 Not accepting mail\r\n").await;
+                    return;
+                }
+
+                let _ = writer.write_all(b"220 mock ESMTP\r\n").await;
+                let mut lines = BufReader::new(reader).lines();
+                let mut in_data = false;
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let response: &[u8] = if in_data {
+                        if line != "." {
+                            continue;
+                        }
+                        in_data = false;
+                        relay.delivered.fetch_add(1, Ordering::Relaxed);
+                        b"250 2.0.0 Queued\r\n"
+                    } else {
+                        match line
+                            .get(..4)
+                            .unwrap_or_default()
+                            .to_ascii_uppercase()
+                            .as_str()
+                        {
+                            "EHLO" | "HELO" => b"250 mock\r\n",
+                            "DATA" => {
+                                in_data = true;
+                                b"354 Go ahead\r\n"
+                            }
+                            "QUIT" => {
+                                let _ = writer.write_all(b"221 Bye\r\n").await;
+                                break;
+                            }
+                            _ => b"250 OK\r\n",
+                        }
+                    };
+                    if writer.write_all(response).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    relay
+}
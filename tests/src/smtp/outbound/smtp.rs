@@ -102,6 +102,7 @@ async fn smtp_delivery() {
                     },
                 ]),
                 else_: "'default'".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -111,6 +112,7 @@ async fn smtp_delivery() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -128,9 +130,11 @@ async fn smtp_delivery() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 7_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -153,9 +157,11 @@ async fn smtp_delivery() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 6_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -178,9 +184,11 @@ async fn smtp_delivery() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 7_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin.mta_no_auth().await;
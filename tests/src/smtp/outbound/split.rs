@@ -0,0 +1,294 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{inbound::TestQueueEvent, session::TestSession},
+    utils::{dns::DnsCache, server::TestServerBuilder},
+};
+use mail_auth::{DnssecStatus, MX};
+use registry::{
+    schema::{
+        enums::{MtaProtocol, RecipientFlag},
+        structs::{
+            Expression, ExpressionMatch, MtaOutboundStrategy, MtaRoute, MtaRouteRelay,
+            MtaStageRcpt, QueuedMessage,
+        },
+    },
+    types::list::List,
+};
+use smtp::queue::{Metadata, RCPT_DSN_SENT, RCPT_SPLIT, Status};
+use std::time::{Duration, Instant};
+use store::write::now;
+use types::id::Id;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn route_split() {
+    let mut local = TestServerBuilder::new("smtp_route_split_local")
+        .await
+        .with_http_listener(19081)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_route_split_remote")
+        .await
+        .with_http_listener(19082)
+        .await
+        .with_smtp_listener(9925)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Recipients share a route on the first attempt and diverge on retries
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(MtaStageRcpt {
+            max_recipients: Expression {
+                else_: "100".into(),
+                ..Default::default()
+            },
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaOutboundStrategy {
+            route: Expression {
+                match_: List::from_iter([
+                    ExpressionMatch {
+                        if_: "retry_num > 0 && rcpt_domain == 'foobar.org'".into(),
+                        then: "'relay-a'".into(),
+                    },
+                    ExpressionMatch {
+                        if_: "retry_num > 0".into(),
+                        then: "'relay-b'".into(),
+                    },
+                ]),
+                else_: "'mx'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    for name in ["relay-a", "relay-b"] {
+        local_admin
+            .registry_create_object(MtaRoute::Relay(MtaRouteRelay {
+                address: format!("{name}.foobar.org"),
+                implicit_tls: false,
+                allow_invalid_certs: true,
+                name: name.into(),
+                port: 9925,
+                protocol: MtaProtocol::Smtp,
+                ..Default::default()
+            }))
+            .await;
+    }
+    local_admin.mta_no_auth().await;
+    local_admin.mta_all_extensions().await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_all_extensions().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    for domain in ["foobar.org", "foobar.net"] {
+        local.server.mx_add(
+            domain,
+            vec![MX {
+                exchanges: vec!["_dns_error.foobar.org".into()].into_boxed_slice(),
+                preference: 10,
+            }],
+            DnssecStatus::Secure,
+            Instant::now() + Duration::from_secs(10),
+        );
+    }
+    for host in ["relay-a.foobar.org", "relay-b.foobar.org"] {
+        local.server.ipv4_add(
+            host,
+            vec!["127.0.0.1".parse().unwrap()],
+            Instant::now() + Duration::from_secs(10),
+        );
+    }
+
+    // First attempt fails for all recipients
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session
+        .send_message(
+            "john@test.org",
+            &["bill@foobar.org", "mike@foobar.net", "jane@foobar.org"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    local
+        .expect_message_then_deliver()
+        .await
+        .try_deliver(local.server.clone());
+    let mut message = local.expect_message().await;
+    let parent_id = message.queue_id;
+    let prev_due = local.message_due(parent_id).await;
+    let next_due = now();
+    for rcpt in message.message.recipients.iter_mut() {
+        assert_eq!(rcpt.retry.inner, 1);
+        rcpt.retry.due = next_due;
+    }
+    message.save_changes(&local.server, prev_due.into()).await;
+
+    // Retrying splits the recipients routed to relay-b into a child message
+    local
+        .delivery_attempt(parent_id)
+        .await
+        .try_deliver(local.server.clone());
+    local.read_event().await.assert_refresh();
+    let messages = local.read_queued_messages().await;
+    assert_eq!(messages.len(), 2);
+    let parent = messages.iter().find(|m| m.queue_id == parent_id).unwrap();
+    let child = messages.iter().find(|m| m.queue_id != parent_id).unwrap();
+    let child_id = child.queue_id;
+    assert_eq!(parent.message.blob_hash, child.message.blob_hash);
+    assert_eq!(child.split_from(), Some(parent_id));
+    assert!(
+        child
+            .message
+            .metadata
+            .contains(&Metadata::SplitFrom { id: parent_id })
+    );
+    assert!(
+        parent
+            .message
+            .metadata
+            .contains(&Metadata::SplitInto { id: child_id })
+    );
+    assert_eq!(
+        child
+            .message
+            .recipients
+            .iter()
+            .map(|r| (r.address(), r.retry.inner))
+            .collect::<Vec<_>>(),
+        vec![("mike@foobar.net", 1)]
+    );
+    for rcpt in &parent.message.recipients {
+        let is_split = rcpt.address() == "mike@foobar.net";
+        assert_eq!(rcpt.flags & RCPT_SPLIT != 0, is_split, "{rcpt:?}");
+        assert_eq!(rcpt.flags & RCPT_DSN_SENT != 0, is_split, "{rcpt:?}");
+        assert_eq!(matches!(rcpt.status, Status::Completed(_)), is_split);
+    }
+
+    // The management API reports the lineage of the child message
+    let local_admin = local.account("admin");
+    let queued_child = local_admin
+        .registry_get::<QueuedMessage>(Id::from(child_id))
+        .await;
+    assert_eq!(queued_child.split_from, Some(Id::from(parent_id)));
+    let queued_parent = local_admin
+        .registry_get::<QueuedMessage>(Id::from(parent_id))
+        .await;
+    assert_eq!(queued_parent.split_from, None);
+    assert!(
+        queued_parent
+            .recipients
+            .get("mike@foobar.net")
+            .unwrap()
+            .flags
+            .contains(&RecipientFlag::Split)
+    );
+
+    // The parent is delivered without the split recipient
+    local
+        .delivery_attempt(parent_id)
+        .await
+        .try_deliver(local.server.clone());
+    local.read_event().await.assert_done();
+    let mut delivered = remote
+        .expect_message()
+        .await
+        .message
+        .recipients
+        .into_iter()
+        .map(|r| r.address().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(delivered, ["bill@foobar.org", "jane@foobar.org"]);
+
+    // The blob is kept while the child message is still queued
+    let blob_hash = child.message.blob_hash.clone();
+    local.blob_expire_all().await;
+    local
+        .server
+        .store()
+        .purge_blobs_all_shards(local.server.blob_store().clone())
+        .await
+        .unwrap();
+    assert!(
+        local
+            .server
+            .blob_store()
+            .get_blob(blob_hash.as_slice(), 0..usize::MAX)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // The child is delivered through its own route
+    local
+        .delivery_attempt(child_id)
+        .await
+        .try_deliver(local.server.clone());
+    local.read_event().await.assert_done();
+    delivered.extend(
+        remote
+            .expect_message()
+            .await
+            .message
+            .recipients
+            .into_iter()
+            .map(|r| r.address().to_string()),
+    );
+    delivered.sort_unstable();
+    assert_eq!(
+        delivered,
+        ["bill@foobar.org", "jane@foobar.org", "mike@foobar.net"]
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    remote.assert_no_events();
+    local.assert_no_events();
+
+    // The blob is removed once all messages are done
+    local.assert_queue_is_empty().await;
+    local
+        .server
+        .store()
+        .purge_blobs_all_shards(local.server.blob_store().clone())
+        .await
+        .unwrap();
+    assert!(
+        local
+            .server
+            .blob_store()
+            .get_blob(blob_hash.as_slice(), 0..usize::MAX)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
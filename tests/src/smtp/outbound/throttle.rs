@@ -50,6 +50,7 @@ async fn throttle_outbound() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -67,9 +68,11 @@ async fn throttle_outbound() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 3_600_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -322,6 +325,7 @@ async fn throttle_outbound_queue_name() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -331,9 +335,11 @@ async fn throttle_outbound_queue_name() {
             notify: MtaDeliveryScheduleIntervalsOrDefault::Default,
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 3_600_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     admin
@@ -408,6 +414,7 @@ impl<'x> TestQueueEnvelope<'x> for QueueEnvelope<'x> {
             local_ip: IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
             domain: rcpt.domain_part(),
             rcpt,
+            headers: Default::default(),
         }
     }
 }
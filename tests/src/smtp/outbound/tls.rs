@@ -58,6 +58,7 @@ async fn starttls_optional() {
                     then: "'no-tls'".into(),
                 }]),
                 else_: "'default'".into(),
+                ..Default::default()
             },
             connection: Expression {
                 else_: "'badtls'".into(),
@@ -75,6 +76,7 @@ async fn starttls_optional() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
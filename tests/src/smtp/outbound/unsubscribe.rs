@@ -0,0 +1,270 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, VerifyResponse},
+    },
+    utils::server::TestServerBuilder,
+};
+use registry::{
+    schema::{
+        prelude::ObjectType,
+        structs::{
+            CertificateManagement, DkimManagement, DnsManagement, Domain, Expression,
+            ExpressionMatch, MtaStageRcpt, MtaUnsubscribe, SecretKeyOptional, SecretKeyValue,
+            SenderAuth, UnsubscribeEvent,
+        },
+    },
+    types::list::List,
+};
+use reqwest::StatusCode;
+use smtp::queue::Metadata;
+
+const HTTP_PORT: u16 = 19096;
+
+#[tokio::test]
+async fn one_click_unsubscribe() {
+    let mut test = TestServerBuilder::new("smtp_unsubscribe_test")
+        .await
+        .with_http_listener(HTTP_PORT)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Add unsubscribe links to messages sent to foobar.org
+    let admin = test.account("admin");
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(MtaStageRcpt {
+            allow_relaying: Expression {
+                else_: "true".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaUnsubscribe {
+            enable: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "rcpt_domain == 'foobar.org'".into(),
+                    then: "true".into(),
+                }]),
+                else_: "false".into(),
+                ..Default::default()
+            },
+            signature_key: SecretKeyOptional::Value(SecretKeyValue {
+                secret: "unsubscribe secret key".into(),
+            }),
+            ..Default::default()
+        })
+        .await;
+
+    // Sign messages with two DKIM1 signers
+    let domain_id = admin
+        .registry_create_object(Domain {
+            name: "example.com".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            ..Default::default()
+        })
+        .await;
+    admin.create_dkim_signatures(domain_id).await;
+    admin
+        .registry_create_object(SenderAuth {
+            dkim_sign_domain: Expression {
+                else_: "'example.com'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Only matching recipients receive the headers
+    let mut session = test.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.doe.org").await;
+    session
+        .send_message(
+            "john@doe.org",
+            &["bill@foobar.org", "jane@example.net"],
+            &message(""),
+            "250 2.0.0",
+        )
+        .await;
+    let queued = test.expect_message().await;
+    let headers = queued
+        .message
+        .metadata
+        .iter()
+        .filter_map(|metadata| match metadata {
+            Metadata::Headers { value, id } => {
+                Some((*id, std::str::from_utf8(value).unwrap().to_string()))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(headers.len(), 2, "{headers:?}");
+
+    // Each copy carries a single signature per signer, the recipient with
+    // the unsubscribe headers gets signatures covering them instead of the
+    // ones of the message
+    queued
+        .read_lines(&test)
+        .await
+        .assert_count("DKIM-Signature:", 0);
+    let (_, default_headers) = headers
+        .iter()
+        .find(|(id, _)| *id == u64::MAX)
+        .expect("Missing default headers");
+    assert_eq!(
+        default_headers.matches("DKIM-Signature:").count(),
+        2,
+        "{default_headers}"
+    );
+    assert!(!default_headers.contains("List-Unsubscribe"));
+    let (rcpt_idx, headers) = headers
+        .iter()
+        .find(|(id, _)| *id != u64::MAX)
+        .expect("Missing recipient headers");
+    assert_eq!(
+        queued.message.recipients[*rcpt_idx as usize].address(),
+        "bill@foobar.org"
+    );
+    assert!(
+        headers.contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n"),
+        "{headers}"
+    );
+    assert_eq!(headers.matches("DKIM-Signature:").count(), 2, "{headers}");
+    let url = headers
+        .split_once("List-Unsubscribe: <")
+        .and_then(|(_, url)| url.split_once('>'))
+        .map(|(url, _)| url)
+        .unwrap_or_else(|| panic!("Missing List-Unsubscribe header: {headers}"));
+    assert!(url.starts_with("https://127.0.0.1/unsubscribe/"), "{url}");
+    let url = url.replace(
+        "https://127.0.0.1/",
+        &format!("https://127.0.0.1:{HTTP_PORT}/"),
+    );
+
+    // Links provided by the sender are kept
+    session
+        .send_message(
+            "john@doe.org",
+            &["bill@foobar.org"],
+            &message("List-Unsubscribe-Post: List-Unsubscribe=One-Click\r\n"),
+            "250 2.0.0",
+        )
+        .await;
+    let queued = test.expect_message().await;
+    assert!(
+        !queued
+            .message
+            .metadata
+            .iter()
+            .any(|metadata| matches!(metadata, Metadata::Headers { .. }))
+    );
+    queued
+        .read_lines(&test)
+        .await
+        .assert_count("DKIM-Signature:", 2);
+
+    // GET requests display a confirmation page without unsubscribing
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("<form method=\"post\">"), "{body}");
+    assert!(body.contains("bill@foobar.org"), "{body}");
+    let admin = test.account("admin");
+    assert!(
+        admin
+            .registry_get_all::<UnsubscribeEvent>()
+            .await
+            .is_empty()
+    );
+
+    // Tampered tokens and requests without the one-click body are rejected
+    let mut tampered = url.clone();
+    let last = tampered.pop().unwrap();
+    tampered.push(if last == 'A' { 'B' } else { 'A' });
+    assert_eq!(one_click(&client, &tampered).await, StatusCode::NOT_FOUND);
+    assert_eq!(
+        client.post(&url).send().await.unwrap().status(),
+        StatusCode::BAD_REQUEST
+    );
+    assert!(
+        admin
+            .registry_get_all::<UnsubscribeEvent>()
+            .await
+            .is_empty()
+    );
+
+    // One-click POST requests record the event once
+    assert_eq!(one_click(&client, &url).await, StatusCode::OK);
+    assert_eq!(one_click(&client, &url).await, StatusCode::OK);
+    let events = admin.registry_get_all::<UnsubscribeEvent>().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    let (event_id, event) = &events[0];
+    assert_eq!(event.sender, "john@doe.org");
+    assert_eq!(event.recipient, "bill@foobar.org");
+    assert_eq!(event.campaign_id.as_deref(), Some("spring-sale"));
+    assert_eq!(
+        admin
+            .registry_query_ids(
+                ObjectType::UnsubscribeEvent,
+                [("sender", "john@doe.org")],
+                Vec::<&str>::new()
+            )
+            .await,
+        vec![*event_id]
+    );
+
+    // Events are recorded by the server and can only be removed
+    admin.registry_create([event.clone()]).await.not_created(0);
+    admin
+        .registry_destroy(ObjectType::UnsubscribeEvent, [*event_id])
+        .await
+        .assert_destroyed(&[*event_id]);
+}
+
+async fn one_click(client: &reqwest::Client, url: &str) -> StatusCode {
+    client
+        .post(url)
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body("List-Unsubscribe=One-Click")
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+fn message(extra_headers: &str) -> String {
+    format!(
+        concat!(
+            "From: john@doe.org\r\n",
+            "To: bill@foobar.org\r\n",
+            "Subject: Spring sale\r\n",
+            "X-Campaign-Id: spring-sale\r\n",
+            "{}",
+            "\r\n",
+            "Everything must go.\r\n"
+        ),
+        extra_headers
+    )
+}
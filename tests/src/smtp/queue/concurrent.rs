@@ -74,6 +74,7 @@ async fn concurrent_queue() {
             name: "default".into(),
             threads_per_node: 4,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -91,9 +92,11 @@ async fn concurrent_queue() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 86_400_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin.mta_allow_relaying().await;
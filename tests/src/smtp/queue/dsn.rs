@@ -4,22 +4,29 @@
  * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
  */
 
-use crate::utils::server::{TestServer, TestServerBuilder};
+use crate::utils::{
+    dns::DnsCache,
+    server::{TestServer, TestServerBuilder},
+};
 use common::config::smtp::queue::{QueueExpiry, QueueName};
-use registry::schema::{
-    enums::CompressionAlgo,
-    structs::{DsnReportSettings, Expression, ReportSettings},
+use mail_auth::{DnssecStatus, MX};
+use registry::{
+    schema::{
+        enums::CompressionAlgo,
+        structs::{DsnReportSettings, Expression, MemoryLookupKey, Rate, ReportSettings},
+    },
+    types::float::Float,
 };
 use smtp::queue::{
-    Error, ErrorDetails, HostResponse, Message, MessageWrapper, Recipient, Schedule, Status,
-    UnexpectedResponse, dsn::SendDsn,
+    Error, ErrorDetails, HostResponse, Message, MessageWrapper, Metadata, RCPT_DSN_SENT, Recipient,
+    Schedule, Status, UnexpectedResponse, dsn::SendDsn,
 };
 use smtp_proto::{RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, RCPT_NOTIFY_SUCCESS, Response};
 use std::{
     fs,
     net::{IpAddr, Ipv4Addr},
     path::PathBuf,
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 use store::write::now;
 use types::blob_hash::BlobHash;
@@ -108,6 +115,7 @@ async fn generate_dsn() {
                 else_: "'Mail Delivery Subsystem'".into(),
                 ..Default::default()
             },
+            ..Default::default()
         })
         .await;
     let domain_id = local_admin.find_or_create_domain("example.org").await;
@@ -196,6 +204,182 @@ async fn generate_dsn() {
     assert_eq!(queue.len(), 4);
 }
 
+#[tokio::test]
+async fn dsn_suppression() {
+    let mut local = TestServerBuilder::new("smtp_queue_dsn_suppression")
+        .await
+        .with_http_listener(19098)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(DsnReportSettings {
+            spam_threshold: Some(Float::new(5.0)),
+            suppress_no_mx: true,
+            suppression_list: Some("dsn-suppress".into()),
+            rate_limit: Some(Rate {
+                count: 2,
+                period: Duration::from_secs(3600).into(),
+            }),
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MemoryLookupKey {
+            namespace: "dsn-suppress".into(),
+            key: "suppressed.org".into(),
+            is_glob_pattern: false,
+        })
+        .await;
+    local_admin
+        .registry_create_object(MemoryLookupKey {
+            namespace: "dsn-suppress".into(),
+            key: "victim@listed.org".into(),
+            is_glob_pattern: false,
+        })
+        .await;
+    local_admin.mta_allow_non_fqdn().await;
+    local_admin.mta_allow_relaying().await;
+    local_admin.reload_settings().await;
+    local_admin.reload_lookup_stores().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    // Add mock DNS entries
+    for (domain, exchange) in [
+        ("foobar.org", "mx.foobar.org"),
+        ("suppressed.org", "mx.suppressed.org"),
+        ("listed.org", "mx.listed.org"),
+        ("limited.org", "mx.limited.org"),
+        ("nullmx.org", "."),
+        ("delivered.org", "mx.delivered.org"),
+    ] {
+        local.server.mx_add(
+            domain,
+            vec![MX {
+                exchanges: vec![exchange.into()].into_boxed_slice(),
+                preference: 10,
+            }],
+            DnssecStatus::Secure,
+            Instant::now() + Duration::from_secs(100),
+        );
+    }
+
+    let blob = b"From: sender@foobar.org\r\nSubject: Test\r\n\r\nTest message\r\n";
+    local
+        .server
+        .blob_store()
+        .put_blob(
+            BlobHash::generate(blob).as_slice(),
+            blob,
+            CompressionAlgo::Lz4,
+        )
+        .await
+        .unwrap();
+
+    // DSNs are sent for clean messages and spam scores below the threshold
+    for score in [None, Some(250)] {
+        let mut message = bounced_message("sender@foobar.org", score, blob);
+        local.server.send_dsn(&mut message).await;
+        local.expect_message().await;
+    }
+
+    // Spam, suppressed domains and addresses and domains without MX are skipped
+    for (return_path, score) in [
+        ("sender@foobar.org", Some(750)),
+        ("sender@suppressed.org", None),
+        ("Victim@Listed.org", None),
+        ("sender@nullmx.org", None),
+    ] {
+        let mut message = bounced_message(return_path, score, blob);
+        local.server.send_dsn(&mut message).await;
+        local.assert_no_events();
+        assert!(
+            message.message.recipients[0].flags & RCPT_DSN_SENT != 0,
+            "DSN for {return_path} was not marked as sent"
+        );
+    }
+
+    // Spam only suppresses failure DSNs, requested success DSNs are still sent
+    let mut message = bounced_message("sender@delivered.org", Some(750), blob);
+    message.message.recipients[0].status = Status::Completed(HostResponse {
+        hostname: "mx.example.org".into(),
+        response: Response {
+            code: 250,
+            esc: [2, 1, 5],
+            message: "Message accepted for delivery".into(),
+        },
+    });
+    message.message.recipients[0].flags = RCPT_NOTIFY_SUCCESS | RCPT_NOTIFY_FAILURE;
+    local.server.send_dsn(&mut message).await;
+    local.expect_message().await;
+
+    // Other addresses of a listed domain still receive DSNs
+    let mut message = bounced_message("other@listed.org", None, blob);
+    local.server.send_dsn(&mut message).await;
+    local.expect_message().await;
+
+    // DSNs are rate limited per destination domain
+    for _ in 0..2 {
+        let mut message = bounced_message("sender@limited.org", None, blob);
+        local.server.send_dsn(&mut message).await;
+        local.expect_message().await;
+    }
+    let mut message = bounced_message("sender@limited.org", None, blob);
+    local.server.send_dsn(&mut message).await;
+    local.assert_no_events();
+    let mut message = bounced_message("sender@foobar.org", None, blob);
+    local.server.send_dsn(&mut message).await;
+    local.assert_no_events();
+}
+
+fn bounced_message(return_path: &str, spam_score: Option<i64>, blob: &[u8]) -> MessageWrapper {
+    MessageWrapper {
+        queue_id: 0,
+        span_id: 0,
+        is_multi_queue: false,
+        queue_name: QueueName::default(),
+        message: Message {
+            size: blob.len() as u64,
+            created: now(),
+            return_path: return_path.into(),
+            recipients: vec![Recipient {
+                address: "foobar@example.org".into(),
+                status: Status::PermanentFailure(ErrorDetails {
+                    entity: "mx.example.org".into(),
+                    details: Error::UnexpectedResponse(UnexpectedResponse {
+                        command: "RCPT TO:<foobar@example.org>".into(),
+                        response: Response {
+                            code: 550,
+                            esc: [5, 1, 2],
+                            message: "User does not exist".into(),
+                        },
+                    }),
+                }),
+                flags: RCPT_NOTIFY_FAILURE,
+                orcpt: None,
+                retry: Schedule::now(),
+                notify: Schedule::now(),
+                expires: QueueExpiry::Ttl(10),
+                queue: QueueName::default(),
+            }],
+            flags: 0,
+            env_id: None,
+            priority: 0,
+            blob_hash: BlobHash::generate(blob),
+            metadata: spam_score
+                .map(|score| Metadata::SpamScore { score })
+                .into_iter()
+                .collect(),
+            received_from_ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            received_via_port: 0,
+        },
+    }
+}
+
 impl TestServer {
     async fn compare_dsn(&self, message: Message, test: &str) {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
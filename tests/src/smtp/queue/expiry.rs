@@ -0,0 +1,228 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{
+        inbound::TestMessage,
+        session::{TestSession, VerifyResponse},
+    },
+    utils::server::{TestServer, TestServerBuilder},
+};
+use ahash::AHashSet;
+use common::{
+    config::smtp::queue::{QueueExpiry, QueueName},
+    ipc::{QueueEvent, QueueEventStatus},
+};
+use registry::{
+    schema::structs::{
+        Expression, ExpressionMatch, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+        MtaDeliverySchedule, MtaDeliveryScheduleInterval, MtaDeliveryScheduleIntervals,
+        MtaDeliveryScheduleIntervalsOrDefault, MtaOutboundStrategy, MtaVirtualQueue,
+    },
+    types::list::List,
+};
+use smtp::queue::{
+    MessageWrapper,
+    spool::{QUEUE_REFRESH, SmtpSpool},
+};
+use std::time::{Duration, Instant};
+use store::write::now;
+
+#[tokio::test]
+async fn queue_expiry() {
+    let mut local = TestServerBuilder::new("smtp_queue_expiry")
+        .await
+        .with_http_listener(19062)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    let local_admin = local.account("admin");
+    local_admin.mta_allow_relaying().await;
+    local_admin.mta_allow_non_fqdn().await;
+    local_admin.mta_no_auth().await;
+    local_admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "sender_domain == 'test.org'".into(),
+                    then: "'expire-time'".into(),
+                }]),
+                else_: "'expire-attempts'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let queue_id = local_admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "default".into(),
+            threads_per_node: 25,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    for (name, expire, max_attempts) in [
+        // Attempt limit is reached well before the message expires
+        ("expire-attempts", 86_400_000u64, 3),
+        // Message expires before the attempt limit is reached
+        ("expire-time", 3_000u64, 100),
+    ] {
+        local_admin
+            .registry_create_object(MtaDeliverySchedule {
+                name: name.into(),
+                retry: MtaDeliveryScheduleIntervalsOrDefault::Custom(
+                    MtaDeliveryScheduleIntervals {
+                        intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                            duration: 1_000u64.into(),
+                        }]),
+                    },
+                ),
+                notify: MtaDeliveryScheduleIntervalsOrDefault::Custom(
+                    MtaDeliveryScheduleIntervals {
+                        intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                            duration: (15 * 60 * 60 * 1000u64).into(),
+                        }]),
+                    },
+                ),
+                expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                    expire: expire.into(),
+                    max_attempts: Some(max_attempts),
+                }),
+                queue_id,
+                description: None,
+                ..Default::default()
+            })
+            .await;
+    }
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+
+    // Attempt limit triggers before the time limit
+    session
+        .send_message(
+            "john@foobar.net",
+            &["jane@_dns_error.org"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    let message = local.expect_message().await;
+    assert_eq!(
+        message.message.recipients.first().unwrap().expires,
+        QueueExpiry::TtlOrAttempts {
+            ttl: 86_400,
+            attempts: 3
+        }
+    );
+    let (attempts, dsn) = deliver_until_expired(&mut local, message).await;
+    assert_eq!(attempts, 3);
+    dsn.read_lines(&local)
+        .await
+        .assert_contains("<jane@_dns_error.org> (failed to lookup '_dns_error.org'")
+        .assert_contains("(giving up after 3 delivery attempts)")
+        .assert_not_contains("message expired")
+        .assert_contains("Action: failed");
+
+    // Time limit triggers before the attempt limit
+    let started = Instant::now();
+    session
+        .send_message(
+            "john@test.org",
+            &["jane@_dns_error.org"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    let message = local.expect_message().await;
+    let (attempts, dsn) = deliver_until_expired(&mut local, message).await;
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    assert!((2..100).contains(&attempts), "attempts: {attempts}");
+    dsn.read_lines(&local)
+        .await
+        .assert_contains("<jane@_dns_error.org> (failed to lookup '_dns_error.org'")
+        .assert_contains("(giving up, message expired before it could be delivered)")
+        .assert_not_contains("delivery attempts")
+        .assert_contains("Action: failed");
+}
+
+// Retries delivery until the queue is empty, returns the number of delivery
+// attempts made and the failure DSN.
+async fn deliver_until_expired(
+    local: &mut TestServer,
+    message: MessageWrapper,
+) -> (u32, MessageWrapper) {
+    let mut in_flight = AHashSet::from_iter([message.queue_id]);
+    let mut attempts = 0;
+    let mut dsn = Vec::new();
+    local
+        .delivery_attempt_for_queue(message.queue_id, "default")
+        .await
+        .try_deliver(local.server.clone());
+
+    loop {
+        match local.try_read_event().await {
+            Some(QueueEvent::WorkerDone {
+                queue_id, status, ..
+            }) => {
+                in_flight.remove(&queue_id);
+                match &status {
+                    QueueEventStatus::Completed => (),
+                    QueueEventStatus::Deferred => attempts += 1,
+                    _ => panic!("unexpected status {queue_id}: {status:?}"),
+                }
+            }
+            Some(QueueEvent::Refresh) | Some(QueueEvent::ReloadSettings) => (),
+            None | Some(QueueEvent::Stop) | Some(QueueEvent::Paused(_)) => break,
+        }
+
+        let now = now();
+        let mut events = local.all_queued_messages().await;
+        if events.messages.is_empty() {
+            if events.next_refresh < now + QUEUE_REFRESH {
+                tokio::time::sleep(Duration::from_secs(events.next_refresh - now)).await;
+                events = local.all_queued_messages().await;
+            } else if in_flight.is_empty() {
+                break;
+            }
+        }
+
+        for event in events.messages {
+            if in_flight.contains(&event.queue_id) {
+                continue;
+            }
+
+            let message = local
+                .server
+                .read_message(event.queue_id, QueueName::default())
+                .await
+                .unwrap();
+            if message.message.return_path.is_empty() {
+                message
+                    .clone()
+                    .remove(&local.server, event.due.into())
+                    .await;
+                dsn.push(message);
+            } else {
+                in_flight.insert(event.queue_id);
+                event.try_deliver(local.server.clone());
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+    local.assert_queue_is_empty().await;
+    assert_eq!(dsn.len(), 1);
+
+    (attempts, dsn.pop().unwrap())
+}
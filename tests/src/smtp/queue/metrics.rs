@@ -0,0 +1,266 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::TestSession,
+    utils::{
+        dns::DnsCache,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use common::{
+    ipc::QueueEvent,
+    telemetry::metrics::queue::{DomainCounters, QueueGauges},
+};
+use mail_auth::{DnssecStatus, MX};
+use registry::{
+    schema::{
+        enums::NetworkListenerProtocol,
+        prelude::Property,
+        structs::{
+            Metrics, MtaDeliveryExpiration, MtaDeliveryExpirationTtl, MtaDeliverySchedule,
+            MtaDeliveryScheduleInterval, MtaDeliveryScheduleIntervals,
+            MtaDeliveryScheduleIntervalsOrDefault, MtaVirtualQueue,
+        },
+    },
+    types::list::List,
+};
+use smtp::queue::manager::ReconcileQueueMetrics;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn queue_metrics() {
+    let mut local = TestServerBuilder::new("smtp_queue_metrics_local")
+        .await
+        .with_http_listener(19066)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_queue_metrics_remote")
+        .await
+        .with_http_listener(19067)
+        .await
+        .with_listener(NetworkListenerProtocol::Smtp, "smtp-debug", 9925, false)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Track a single domain, aggregate the rest under "other"
+    let local_admin = local.account("admin");
+    local_admin.mta_allow_relaying().await;
+    local_admin.mta_no_auth().await;
+    local_admin.mta_all_extensions().await;
+    local_admin.mta_disable_spam_filter().await;
+    local_admin
+        .registry_update_setting(
+            Metrics {
+                queue_top_domains: 1,
+                ..Default::default()
+            },
+            &[Property::QueueTopDomains],
+        )
+        .await;
+    let queue_id = local_admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "default".into(),
+            threads_per_node: 25,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    local_admin
+        .registry_create_object(MtaDeliverySchedule {
+            name: "default".into(),
+            retry: MtaDeliveryScheduleIntervalsOrDefault::Custom(MtaDeliveryScheduleIntervals {
+                intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                    duration: 1_000u64.into(),
+                }]),
+            }),
+            notify: MtaDeliveryScheduleIntervalsOrDefault::Custom(MtaDeliveryScheduleIntervals {
+                intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                    duration: 86_400_000u64.into(),
+                }]),
+            }),
+            expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                expire: 3_000u64.into(),
+                max_attempts: None,
+            }),
+            queue_id,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin.mta_disable_spam_filter().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    for domain in ["foobar.org", "foobar.net"] {
+        local.server.mx_add(
+            domain,
+            vec![MX {
+                exchanges: vec![format!("mx.{domain}").into()].into_boxed_slice(),
+                preference: 10,
+            }],
+            DnssecStatus::Secure,
+            Instant::now() + Duration::from_secs(30),
+        );
+        local.server.ipv4_add(
+            format!("mx.{domain}"),
+            vec!["127.0.0.1".parse().unwrap()],
+            Instant::now() + Duration::from_secs(30),
+        );
+    }
+
+    // Enqueue two messages
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session
+        .send_message(
+            "john@test.org",
+            &["<ok@foobar.org> NOTIFY=NEVER"],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    let delivered = local.expect_message().await;
+    session
+        .send_message(
+            "john@test.org",
+            &[
+                "<delay@foobar.org> NOTIFY=NEVER",
+                "<fail@foobar.net> NOTIFY=NEVER",
+            ],
+            "test:no_dkim",
+            "250",
+        )
+        .await;
+    let deferred = local.expect_message().await;
+    let expected = QueueGauges {
+        messages: 2,
+        bytes: delivered.message.size + deferred.message.size,
+        oldest: Some(delivered.message.created.min(deferred.message.created)),
+    };
+    assert_eq!(queue_gauges(&local), expected);
+
+    // Reconciliation agrees with the incremental values and picks the
+    // domain with the most queued recipients
+    local.server.reconcile_queue_metrics().await;
+    assert_eq!(queue_gauges(&local), expected);
+    assert_eq!(
+        local.server.inner.data.queue_metrics.domain_counters(),
+        vec![("foobar.org".into(), DomainCounters::default())]
+    );
+
+    // Delivered messages leave the queue
+    local
+        .delivery_attempt_for_queue(delivered.queue_id, "default")
+        .await
+        .try_deliver(local.server.clone());
+    wait_for_worker(&mut local).await;
+    assert_eq!(
+        queue_gauges(&local),
+        QueueGauges {
+            messages: 1,
+            bytes: deferred.message.size,
+            oldest: Some(delivered.message.created.min(deferred.message.created)),
+        }
+    );
+
+    // Deferred messages stay in the queue, bounces to untracked domains
+    // are aggregated as "other"
+    local
+        .delivery_attempt_for_queue(deferred.queue_id, "default")
+        .await
+        .try_deliver(local.server.clone());
+    wait_for_worker(&mut local).await;
+    assert_eq!(queue_gauges(&local).messages, 1);
+    assert_eq!(
+        local.server.inner.data.queue_metrics.domain_counters(),
+        vec![
+            (
+                "foobar.org".into(),
+                DomainCounters {
+                    attempts: 2,
+                    deferrals: 1,
+                    bounces: 0,
+                }
+            ),
+            (
+                "other".into(),
+                DomainCounters {
+                    attempts: 1,
+                    deferrals: 0,
+                    bounces: 1,
+                }
+            )
+        ]
+    );
+    assert_eq!(
+        local.server.inner.data.queue_metrics.message_age().count(),
+        2
+    );
+
+    // Metrics are exported with their labels
+    let metrics = local.server.export_prometheus_metrics().await.unwrap();
+    for expected in [
+        "queue_messages{queue=\"default\"} 1",
+        "queue_domain_attempts{domain=\"foobar.org\"} 2",
+        "queue_domain_deferrals{domain=\"foobar.org\"} 1",
+        "queue_domain_bounces{domain=\"other\"} 1",
+        "queue_message_age_count 2",
+    ] {
+        assert!(
+            metrics.contains(expected),
+            "{expected} not found in {metrics}"
+        );
+    }
+
+    // Expired messages leave the queue
+    tokio::time::sleep(Duration::from_millis(3100)).await;
+    local
+        .delivery_attempt_for_queue(deferred.queue_id, "default")
+        .await
+        .try_deliver(local.server.clone());
+    wait_for_worker(&mut local).await;
+    local.assert_queue_is_empty().await;
+    assert_eq!(queue_gauges(&local), QueueGauges::default());
+}
+
+fn queue_gauges(test: &TestServer) -> QueueGauges {
+    test.server
+        .inner
+        .data
+        .queue_metrics
+        .queue_gauges()
+        .into_iter()
+        .find_map(|(name, gauges)| (name.as_str() == "default").then_some(gauges))
+        .unwrap_or_default()
+}
+
+async fn wait_for_worker(test: &mut TestServer) {
+    for _ in 0..100 {
+        if let Some(QueueEvent::WorkerDone { .. }) = test.try_read_event().await {
+            return;
+        }
+    }
+    panic!("Timed out waiting for the delivery attempt to finish.");
+}
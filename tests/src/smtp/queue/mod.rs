@@ -11,9 +11,16 @@ use store::write::now;
 
 pub mod concurrent;
 pub mod dsn;
+pub mod expiry;
 pub mod manager;
+pub mod metrics;
+pub mod ratelimit;
+pub mod references;
 pub mod retry;
+pub mod scan;
+pub mod templates;
 pub mod virtualq;
+pub mod window;
 
 pub fn build_rcpt(address: &str, retry: u64, notify: u64, expires: u64) -> Recipient {
     Recipient {
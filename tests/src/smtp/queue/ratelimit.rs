@@ -0,0 +1,247 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::session::TestSession,
+    utils::{
+        dns::DnsCache,
+        server::{TestServer, TestServerBuilder},
+    },
+};
+use common::{BuildServer, config::smtp::queue::QueueName, ipc::QueueEvent};
+use mail_auth::{DnssecStatus, MX};
+use registry::{
+    schema::{
+        enums::NetworkListenerProtocol,
+        prelude::ObjectType,
+        structs::{
+            Expression, ExpressionMatch, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+            MtaDeliverySchedule, MtaDeliveryScheduleInterval, MtaDeliveryScheduleIntervals,
+            MtaDeliveryScheduleIntervalsOrDefault, MtaOutboundStrategy, MtaVirtualQueue,
+        },
+    },
+    types::list::List,
+};
+use smtp::queue::manager::Queue;
+use std::time::{Duration, Instant};
+
+const NUM_MESSAGES: usize = 6;
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial_test::serial]
+async fn virtual_queue_rate_limit() {
+    let mut local = TestServerBuilder::new("smtp_queue_rate_limit_local")
+        .await
+        .with_http_listener(19093)
+        .await
+        .disable_services()
+        .build()
+        .await;
+    let mut remote = TestServerBuilder::new("smtp_queue_rate_limit_remote")
+        .await
+        .with_http_listener(19094)
+        .await
+        .with_listener(NetworkListenerProtocol::Smtp, "smtp-debug", 9925, false)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Messages to the throttled recipient go through a rate limited queue
+    let local_admin = local.account("admin");
+    local_admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "rcpt == 'throttled@foobar.org'".into(),
+                    then: "'limited'".into(),
+                }]),
+                else_: "'unlimited'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    for (name, rate_limit) in [("limited", "[2, 1s]"), ("unlimited", "")] {
+        let queue_id = local_admin
+            .registry_create_object(MtaVirtualQueue {
+                name: name.into(),
+                threads_per_node: 4,
+                rate_limit: Expression {
+                    else_: rate_limit.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .await;
+        local_admin
+            .registry_create_object(MtaDeliverySchedule {
+                name: name.into(),
+                retry: MtaDeliveryScheduleIntervalsOrDefault::Custom(
+                    MtaDeliveryScheduleIntervals {
+                        intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                            duration: 3_600_000u64.into(),
+                        }]),
+                    },
+                ),
+                notify: MtaDeliveryScheduleIntervalsOrDefault::Custom(
+                    MtaDeliveryScheduleIntervals {
+                        intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                            duration: 86_400_000u64.into(),
+                        }]),
+                    },
+                ),
+                expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                    expire: 86_400_000u64.into(),
+                    max_attempts: None,
+                }),
+                queue_id,
+                ..Default::default()
+            })
+            .await;
+    }
+    local_admin.mta_allow_relaying().await;
+    local_admin.mta_disable_spam_filter().await;
+    local_admin.mta_allow_non_fqdn().await;
+    local_admin.mta_no_auth().await;
+    local_admin
+        .registry_destroy_all(ObjectType::MtaInboundThrottle)
+        .await;
+    local_admin.reload_settings().await;
+    local.reload_core();
+
+    let remote_admin = remote.account("admin");
+    remote_admin.mta_allow_relaying().await;
+    remote_admin.mta_disable_spam_filter().await;
+    remote_admin.mta_allow_non_fqdn().await;
+    remote_admin.mta_no_auth().await;
+    remote_admin
+        .registry_destroy_all(ObjectType::MtaInboundThrottle)
+        .await;
+    remote_admin.reload_settings().await;
+    remote.reload_core();
+    remote.expect_reload_settings().await;
+
+    // Validate parsing
+    let virtual_queues = &local.server.core.smtp.queue.virtual_queues;
+    assert!(
+        virtual_queues
+            .get(&QueueName::new("limited").unwrap())
+            .unwrap()
+            .rate_limit
+            .is_some()
+    );
+    assert!(
+        virtual_queues
+            .get(&QueueName::new("unlimited").unwrap())
+            .unwrap()
+            .rate_limit
+            .is_none()
+    );
+
+    // Spawn the queue manager
+    let (inner, rxs) = local.inner_with_rxs().await;
+    let server = inner.build_server();
+    for server in [&local.server, &server] {
+        server.mx_add(
+            "foobar.org",
+            vec![MX {
+                exchanges: vec!["mx.foobar.org".into()].into_boxed_slice(),
+                preference: 10,
+            }],
+            DnssecStatus::Secure,
+            Instant::now() + Duration::from_secs(100),
+        );
+        server.ipv4_add(
+            "mx.foobar.org",
+            vec!["127.0.0.1".parse().unwrap()],
+            Instant::now() + Duration::from_secs(100),
+        );
+    }
+    tokio::spawn({
+        let inner = inner.clone();
+        async move {
+            Queue::new(inner, rxs.queue_rx.unwrap()).start().await;
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // Queue messages for both the limited and unlimited queues
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    let started = Instant::now();
+    for _ in 0..NUM_MESSAGES {
+        for rcpt in ["throttled@foobar.org", "bill@foobar.org"] {
+            session
+                .send_message("john@test.org", &[rcpt], "test:no_dkim", "250")
+                .await;
+        }
+    }
+    inner.ipc.queue_tx.send(QueueEvent::Refresh).await.unwrap();
+
+    // The unlimited queue is drained right away while the limited queue
+    // holds back messages without counting them as delivery attempts
+    loop {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if delivered_to(&remote, "bill@foobar.org").await == NUM_MESSAGES {
+            break;
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "Unlimited queue was not drained"
+        );
+    }
+    let pending = local.read_queued_messages().await;
+    assert!(!pending.is_empty());
+    for message in &pending {
+        for rcpt in &message.message.recipients {
+            assert_eq!(rcpt.address.as_ref(), "throttled@foobar.org");
+            assert_eq!(rcpt.retry.inner, 0, "{message:?}");
+        }
+    }
+    assert!(delivered_to(&remote, "throttled@foobar.org").await < NUM_MESSAGES);
+
+    // The limited queue is drained at the configured rate
+    loop {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if local.read_queued_messages().await.is_empty() {
+            break;
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "Limited queue was not drained"
+        );
+    }
+    // Six messages at two per second span at least three rate windows, so at
+    // least one full window has to pass before the queue is drained
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_secs(1), "{elapsed:?}");
+    assert_eq!(
+        delivered_to(&remote, "throttled@foobar.org").await,
+        NUM_MESSAGES
+    );
+    assert!(inner.data.queue_metrics.throttle_delay().count() > 0);
+
+    local.assert_queue_is_empty().await;
+}
+
+async fn delivered_to(remote: &TestServer, rcpt: &str) -> usize {
+    remote
+        .read_queued_messages()
+        .await
+        .iter()
+        .filter(|message| {
+            message
+                .message
+                .recipients
+                .iter()
+                .any(|r| r.address.as_ref() == rcpt)
+        })
+        .count()
+}
@@ -0,0 +1,159 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::TestServerBuilder;
+use common::ipc::RegistryChange;
+use registry::{
+    schema::{
+        prelude::{ObjectType, Property},
+        structs::{
+            CertificateManagement, DkimManagement, DnsManagement, Domain, Expression,
+            ExpressionMatch, MtaOutboundStrategy, SenderAuth, SystemSettings,
+        },
+    },
+    types::list::List,
+};
+
+#[tokio::test]
+async fn config_references() {
+    let test = TestServerBuilder::new("smtp_config_references")
+        .await
+        .with_http_listener(19079)
+        .await
+        .disable_services()
+        .build()
+        .await;
+
+    // Reference an unknown queue schedule and an unknown DKIM signer
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                match_: List::from_iter([ExpressionMatch {
+                    if_: "rcpt_domain == 'foobar.org'".into(),
+                    then: "'q3'".into(),
+                }]),
+                else_: "'default'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SenderAuth {
+            dkim_sign_domain: Expression {
+                else_: "'unknown.org'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+
+    // Strict mode rejects the reload
+    let result = test
+        .server
+        .reload_registry(RegistryChange::Reload(ObjectType::SystemSettings))
+        .await
+        .unwrap();
+    let errors = format!("{:?}", result.errors);
+    assert!(!result.replaced_core);
+    assert_eq!(result.errors.len(), 2, "{errors}");
+    for message in [
+        "Unknown delivery schedule 'q3'",
+        "Unknown DKIM signing domain 'unknown.org'",
+    ] {
+        assert!(errors.contains(message), "{errors}");
+    }
+
+    // Unknown references are reported as warnings when strict mode is disabled
+    admin
+        .registry_update_setting(
+            SystemSettings {
+                strict_references: false,
+                ..Default::default()
+            },
+            &[Property::StrictReferences],
+        )
+        .await;
+    let result = test
+        .server
+        .reload_registry(RegistryChange::Reload(ObjectType::SystemSettings))
+        .await
+        .unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(result.replaced_core);
+    for (property, message) in [
+        (Property::Schedule, "Unknown delivery schedule 'q3'"),
+        (
+            Property::DkimSignDomain,
+            "Unknown DKIM signing domain 'unknown.org'",
+        ),
+    ] {
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|warning| warning.property == Some(property) && warning.message == message),
+            "{:?}",
+            result.warnings
+        );
+    }
+
+    // Known references are accepted
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: Expression {
+                else_: "'default'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+
+    // Signing domains only need to be configured, their keys can be added later
+    admin
+        .registry_create_object(Domain {
+            name: "nokeys.org".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(SenderAuth {
+            dkim_sign_domain: Expression {
+                else_: "'nokeys.org'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_update_setting(
+            SystemSettings {
+                strict_references: true,
+                ..Default::default()
+            },
+            &[Property::StrictReferences],
+        )
+        .await;
+    let result = test
+        .server
+        .reload_registry(RegistryChange::Reload(ObjectType::SystemSettings))
+        .await
+        .unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(
+        !result
+            .warnings
+            .iter()
+            .any(|warning| warning.message.starts_with("Unknown ")),
+        "{:?}",
+        result.warnings
+    );
+    assert!(result.replaced_core);
+}
@@ -51,6 +51,7 @@ async fn queue_retry() {
                     then: "'sender-test'".into(),
                 }]),
                 else_: "'sender-default'".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -73,6 +74,7 @@ async fn queue_retry() {
             name: "default".into(),
             threads_per_node: 25,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -98,9 +100,11 @@ async fn queue_retry() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 86_400_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -131,9 +135,11 @@ async fn queue_retry() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 6_000u64.into(),
+                max_attempts: None,
             }),
             queue_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin.reload_settings().await;
@@ -0,0 +1,235 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::queue::{build_rcpt, new_message},
+    thread_allocations,
+    utils::server::TestServerBuilder,
+};
+use smtp::queue::Message;
+use store::{
+    Deserialize, IterateParams, Serialize, U64_LEN, ValueKey,
+    write::{
+        AlignedBytes, Archive, Archiver, BatchBuilder, QueueClass, QueueEvent, ValueClass,
+        key::DeserializeBigEndian,
+    },
+};
+
+const MESSAGES: u64 = 10_000;
+
+#[tokio::test]
+async fn queue_scan() {
+    let test = TestServerBuilder::new("smtp_queue_scan")
+        .await
+        .with_http_listener(19090)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let store = test.server.store();
+
+    // Queue 10k messages spread over a hundred due times
+    let mut expected_events = Vec::with_capacity(MESSAGES as usize);
+    for chunk in (0..MESSAGES).collect::<Vec<_>>().chunks(500) {
+        let mut batch = BatchBuilder::new();
+        for &queue_id in chunk {
+            let mut message = new_message(queue_id);
+            message.message.recipients.push(build_rcpt(
+                &format!("rcpt{queue_id}@domain{}.org", queue_id % 10),
+                1000 + queue_id % 100,
+                2000,
+                3000,
+            ));
+            for (queue_name, due) in message.message.next_events() {
+                let event = QueueEvent {
+                    due,
+                    queue_id,
+                    queue_name: queue_name.into_inner(),
+                };
+                batch.set(
+                    ValueClass::Queue(QueueClass::MessageEvent(event.clone())),
+                    Vec::new(),
+                );
+                expected_events.push(event);
+            }
+            batch.set(
+                ValueClass::Queue(QueueClass::Message(queue_id)),
+                Archiver::new(message.message).serialize().unwrap(),
+            );
+        }
+        store.write(batch.build_all()).await.unwrap();
+    }
+    expected_events.sort_by_key(|event| (event.due, event.queue_id));
+
+    // Typed event streams return the same events as a raw key scan
+    for (due_to, ascending) in [
+        (u64::MAX, true),
+        (u64::MAX, false),
+        (expected_events[MESSAGES as usize / 2].due, true),
+    ] {
+        let mut stream = store.queue_events(0, due_to, ascending);
+        let mut events = Vec::with_capacity(MESSAGES as usize);
+        while let Some(event) = stream.next().await.unwrap() {
+            events.push(event);
+        }
+        if !ascending {
+            events.reverse();
+        }
+        assert_eq!(
+            events,
+            expected_events
+                .iter()
+                .filter(|event| event.due <= due_to)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // Queue id streams return every queued message
+    let mut stream = store.queue_ids();
+    let mut ids = Vec::with_capacity(MESSAGES as usize);
+    while let Some(queue_id) = stream.next().await.unwrap() {
+        ids.push(queue_id);
+    }
+    assert_eq!(ids, (0..MESSAGES).collect::<Vec<_>>());
+
+    // Reading ids from a streamed batch does not allocate, deserializing the
+    // message archives allocates for every message. Only the allocations of
+    // this thread are counted while no other task can run on it.
+    let mut id_allocations = 0;
+    let mut archive_allocations = 0;
+    let mut total = 0;
+    let mut recipients = 0;
+    let mut stream = store.stream(
+        IterateParams::new(
+            ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+            ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+        ),
+        1000,
+    );
+    while stream.next_batch().await.unwrap() {
+        let allocations = thread_allocations();
+        for (key, _) in stream.rows() {
+            total += key.deserialize_be_u64(0).unwrap();
+        }
+        id_allocations += thread_allocations() - allocations;
+
+        let allocations = thread_allocations();
+        for (_, value) in stream.rows() {
+            recipients += <Archive<AlignedBytes> as Deserialize>::deserialize(value)
+                .unwrap()
+                .deserialize::<Message>()
+                .unwrap()
+                .recipients
+                .len();
+        }
+        archive_allocations += thread_allocations() - allocations;
+    }
+    assert_eq!(total, ids.iter().sum::<u64>());
+    assert_eq!(recipients, MESSAGES as usize);
+    assert_eq!(id_allocations, 0);
+    assert!(
+        archive_allocations >= MESSAGES,
+        "{archive_allocations} < {MESSAGES}"
+    );
+
+    // Streams can be dropped before the end of the range
+    let mut stream = store.queue_ids();
+    assert_eq!(stream.next().await.unwrap(), Some(0));
+    drop(stream);
+    for (ascending, expected_id) in [(true, 0), (false, MESSAGES - 1)] {
+        let mut stream = store.stream(
+            IterateParams::new(
+                ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+                ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+            )
+            .set_ascending(ascending)
+            .only_first()
+            .no_values(),
+            1000,
+        );
+        let mut ids = Vec::new();
+        while let Some((key, _)) = stream.next().await.unwrap() {
+            ids.push(key.deserialize_be_u64(0).unwrap());
+        }
+        assert_eq!(ids, [expected_id]);
+    }
+
+    // Streams return the same rows as a regular scan in both directions
+    for ascending in [true, false] {
+        for batch_size in [1, 7, 1000, 20_000] {
+            let mut stream = store.stream(
+                IterateParams::new(
+                    ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(QueueEvent {
+                        due: 0,
+                        queue_id: 0,
+                        queue_name: [0; 8],
+                    }))),
+                    ValueKey::from(ValueClass::Queue(QueueClass::MessageEvent(QueueEvent {
+                        due: u64::MAX,
+                        queue_id: u64::MAX,
+                        queue_name: [u8::MAX; 8],
+                    }))),
+                )
+                .set_ascending(ascending)
+                .no_values(),
+                batch_size,
+            );
+            let mut events = Vec::with_capacity(MESSAGES as usize);
+            while stream.next_batch().await.unwrap() {
+                for (key, _) in stream.rows() {
+                    events.push(QueueEvent {
+                        due: key.deserialize_be_u64(0).unwrap(),
+                        queue_id: key.deserialize_be_u64(U64_LEN).unwrap(),
+                        queue_name: key[U64_LEN * 2..].try_into().unwrap(),
+                    });
+                }
+            }
+            if !ascending {
+                events.reverse();
+            }
+            assert_eq!(events, expected_events, "batch size {batch_size}");
+        }
+    }
+
+    // Streamed message archives match the ones read in a single scan
+    let mut stream = store.stream(
+        IterateParams::new(
+            ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+            ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+        )
+        .descending(),
+        333,
+    );
+    let mut archives = Vec::with_capacity(MESSAGES as usize);
+    while stream.next_batch().await.unwrap() {
+        archives.extend(
+            stream
+                .rows()
+                .map(|(key, value)| (key.to_vec(), value.to_vec())),
+        );
+    }
+    let mut expected_archives = Vec::with_capacity(MESSAGES as usize);
+    store
+        .iterate(
+            IterateParams::new(
+                ValueKey::from(ValueClass::Queue(QueueClass::Message(0))),
+                ValueKey::from(ValueClass::Queue(QueueClass::Message(u64::MAX))),
+            )
+            .descending(),
+            |key, value| {
+                expected_archives.push((key.to_vec(), value.to_vec()));
+                Ok(true)
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(archives.len(), MESSAGES as usize);
+    assert_eq!(archives, expected_archives);
+
+    test.clear_queue().await;
+}
@@ -0,0 +1,355 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{inbound::TestMessage, queue::new_message},
+    utils::server::TestServerBuilder,
+};
+use common::{
+    config::smtp::queue::{QueueExpiry, QueueName},
+    ipc::RegistryChange,
+};
+use mail_parser::MessageParser;
+use registry::{
+    schema::{
+        enums::{
+            CertificateManagement, CompressionAlgo, DkimManagement, DnsManagement, Locale,
+            MessageTemplateId,
+        },
+        prelude::ObjectType,
+        structs::{
+            Account, Credential, Domain, DsnReportSettings, Expression, MessageTemplate,
+            PasswordCredential, ReportSettings, Tenant, UserAccount,
+        },
+    },
+    types::list::List,
+};
+use smtp::queue::{
+    Error, ErrorDetails, Recipient, Schedule, Status, UnexpectedResponse, dsn::SendDsn,
+};
+use smtp_proto::{RCPT_NOTIFY_DELAY, RCPT_NOTIFY_FAILURE, Response};
+use types::blob_hash::BlobHash;
+
+const ORIGINAL: &str = "From: sender@foobar.org\r\nSubject: Hello\r\n\r\nHi there!\r\n";
+
+#[tokio::test]
+async fn message_templates() {
+    let mut test = TestServerBuilder::new("smtp_message_templates")
+        .await
+        .with_http_listener(19091)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+    let admin = test.account("admin");
+    admin
+        .registry_create_object(ReportSettings {
+            outbound_report_submitter: Expression {
+                else_: "'mx.example.org'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(DsnReportSettings {
+            dkim_sign_domain: Expression {
+                else_: "'example.org'".into(),
+                ..Default::default()
+            },
+            from_address: Expression {
+                else_: "'MAILER-DAEMON@example.org'".into(),
+                ..Default::default()
+            },
+            from_name: Expression {
+                else_: "'Mail Delivery Subsystem'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let domain_id = admin.find_or_create_domain("example.org").await;
+    admin.create_dkim_signatures(domain_id).await;
+    admin.mta_allow_relaying().await;
+
+    // Create two tenants, each with a domain and accounts in different locales
+    let mut tenant_ids = Vec::new();
+    for (tenant, domain, accounts) in [
+        ("Alpha", "alpha.org", &[("jane", Locale::DeDE)][..]),
+        (
+            "Beta",
+            "beta.org",
+            &[("pierre", Locale::FrFR), ("carol", Locale::EnUS)][..],
+        ),
+    ] {
+        let tenant_id = admin
+            .registry_create_object(Tenant {
+                name: tenant.into(),
+                ..Default::default()
+            })
+            .await;
+        let domain_id = admin
+            .registry_create_object(Domain {
+                name: domain.into(),
+                certificate_management: CertificateManagement::Manual,
+                dns_management: DnsManagement::Manual,
+                dkim_management: DkimManagement::Manual,
+                member_tenant_id: Some(tenant_id),
+                ..Default::default()
+            })
+            .await;
+        for (name, locale) in accounts {
+            admin
+                .registry_create_object(Account::User(UserAccount {
+                    name: name.to_string(),
+                    domain_id,
+                    member_tenant_id: Some(tenant_id),
+                    locale: *locale,
+                    credentials: List::from_iter([Credential::Password(PasswordCredential {
+                        secret: "secret".to_string(),
+                        ..Default::default()
+                    })]),
+                    ..Default::default()
+                }))
+                .await;
+        }
+        tenant_ids.push(tenant_id);
+    }
+
+    // A second domain of the Beta tenant has its own templates
+    let beta_net_id = admin
+        .registry_create_object(Domain {
+            name: "beta.net".into(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            member_tenant_id: Some(tenant_ids[1]),
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(Account::User(UserAccount {
+            name: "marc".to_string(),
+            domain_id: beta_net_id,
+            member_tenant_id: Some(tenant_ids[1]),
+            locale: Locale::FrFR,
+            credentials: List::from_iter([Credential::Password(PasswordCredential {
+                secret: "secret".to_string(),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        }))
+        .await;
+
+    // Templates are selected by domain, tenant and locale, falling back to
+    // the tenant's default and then to the global template
+    for (domain_id, tenant_id, locale, subject, text_body, html_body) in [
+        (
+            None,
+            None,
+            None,
+            "Global failure {{queue_id}}",
+            "Global: {{reason}}",
+            None,
+        ),
+        (
+            None,
+            Some(tenant_ids[0]),
+            Some(Locale::DeDE),
+            "Unzustellbar: {{recipient}}",
+            "Hallo {{recipient}},\r\n\r\n{{reason}}Warteschlange {{queue_id}}\r\n",
+            None,
+        ),
+        (
+            None,
+            Some(tenant_ids[1]),
+            Some(Locale::FrCA),
+            "Non remis {{queue_id}}",
+            "Bonjour {{recipient}},\r\n\r\n{{reason}}",
+            Some("<p>{{reason}}</p>"),
+        ),
+        (
+            None,
+            Some(tenant_ids[1]),
+            None,
+            "Beta {{recipient}} {{hostname}}",
+            "{{reason}}",
+            None,
+        ),
+        (
+            Some(beta_net_id),
+            Some(tenant_ids[1]),
+            None,
+            "Beta.net {{queue_id}}",
+            "Beta.net: {{reason}}",
+            Some("<p>{{reason}}</p>"),
+        ),
+    ] {
+        admin
+            .registry_create_object(MessageTemplate {
+                template_id: MessageTemplateId::DsnFailure,
+                locale,
+                subject: subject.into(),
+                text_body: text_body.into(),
+                html_body: html_body.map(Into::into),
+                member_tenant_id: tenant_id,
+                domain_id,
+            })
+            .await;
+    }
+
+    // Invalid templates are reported and fall back to the built-in message
+    admin
+        .registry_create_object(MessageTemplate {
+            template_id: MessageTemplateId::DsnDelay,
+            locale: None,
+            subject: "Delayed {{unknown}}".into(),
+            text_body: "{{reason}}".into(),
+            html_body: None,
+            member_tenant_id: None,
+            domain_id: None,
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+    let result = test
+        .server
+        .reload_registry(RegistryChange::Reload(ObjectType::MessageTemplate))
+        .await
+        .unwrap();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(
+        result.warnings.iter().any(|warning| warning
+            .message
+            .starts_with("Invalid template in property subject")),
+        "{:?}",
+        result.warnings
+    );
+
+    let mut message = new_message(1234);
+    message.message.size = ORIGINAL.len() as u64;
+    message.message.blob_hash = BlobHash::generate(ORIGINAL.as_bytes());
+    message.message.recipients.push(Recipient {
+        address: "foobar@example.org".into(),
+        status: Status::PermanentFailure(ErrorDetails {
+            entity: "mx.example.org".into(),
+            details: Error::UnexpectedResponse(UnexpectedResponse {
+                command: "RCPT TO:<foobar@example.org>".into(),
+                response: Response {
+                    code: 550,
+                    esc: [5, 1, 2],
+                    message: "User does not exist".into(),
+                },
+            }),
+        }),
+        flags: RCPT_NOTIFY_FAILURE | RCPT_NOTIFY_DELAY,
+        orcpt: None,
+        retry: Schedule::now(),
+        notify: Schedule::now(),
+        expires: QueueExpiry::Ttl(10),
+        queue: QueueName::default(),
+    });
+    test.server
+        .blob_store()
+        .put_blob(
+            message.message.blob_hash.as_slice(),
+            ORIGINAL.as_bytes(),
+            CompressionAlgo::None,
+        )
+        .await
+        .unwrap();
+
+    let reason = "<foobar@example.org> (host 'mx.example.org' rejected command \
+                  'RCPT TO:<foobar@example.org>' with code 550 (5.1.2) 'User does not exist')";
+    for (return_path, expected_subject, expected_text, expected_html) in [
+        (
+            "jane@alpha.org",
+            "Unzustellbar: jane@alpha.org".to_string(),
+            format!("Hallo jane@alpha.org,\r\n\r\n{reason}\r\n\r\nWarteschlange 1234\r\n"),
+            None,
+        ),
+        (
+            "pierre@beta.org",
+            "Non remis 1234".to_string(),
+            format!("Bonjour pierre@beta.org,\r\n\r\n{reason}\r\n\r\n"),
+            Some(format!(
+                "<p>{}\r\n\r\n</p>",
+                reason
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+                    .replace('\'', "&#39;")
+            )),
+        ),
+        (
+            "carol@beta.org",
+            "Beta carol@beta.org mx.example.org".to_string(),
+            format!("{reason}\r\n\r\n"),
+            None,
+        ),
+        (
+            "marc@beta.net",
+            "Beta.net 1234".to_string(),
+            format!("Beta.net: {reason}\r\n\r\n"),
+            Some(format!(
+                "<p>{}\r\n\r\n</p>",
+                reason
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;")
+                    .replace('\'', "&#39;")
+            )),
+        ),
+        (
+            "nobody@unknown.org",
+            "Global failure 1234".to_string(),
+            format!("Global: {reason}\r\n\r\n"),
+            None,
+        ),
+    ] {
+        message.message.return_path = return_path.into();
+        message.message.recipients[0].flags = RCPT_NOTIFY_FAILURE | RCPT_NOTIFY_DELAY;
+        test.server.send_dsn(&mut message).await;
+        let raw = test.expect_message().await.read_message(&test).await;
+        let dsn = MessageParser::new().parse(raw.as_bytes()).unwrap();
+        assert_eq!(
+            dsn.subject(),
+            Some(expected_subject.as_str()),
+            "{return_path}"
+        );
+        assert_eq!(
+            dsn.body_text(0).as_deref(),
+            Some(expected_text.as_str()),
+            "{return_path}"
+        );
+        assert_eq!(
+            raw.contains("Content-Type: text/html"),
+            expected_html.is_some(),
+            "{return_path}"
+        );
+        if let Some(expected_html) = expected_html {
+            assert_eq!(
+                dsn.body_html(0).as_deref(),
+                Some(expected_html.as_str()),
+                "{return_path}"
+            );
+        }
+    }
+
+    // Delay notifications use the built-in message as their template is invalid
+    message.message.return_path = "jane@alpha.org".into();
+    message.message.recipients[0].status = Status::TemporaryFailure(ErrorDetails {
+        entity: "mx.example.org".into(),
+        details: Error::ConnectionError("Connection timeout".into()),
+    });
+    message.message.recipients[0].flags = RCPT_NOTIFY_FAILURE | RCPT_NOTIFY_DELAY;
+    test.server.send_dsn(&mut message).await;
+    let dsn = test.expect_message().await.read_message(&test).await;
+    let dsn = MessageParser::new().parse(dsn.as_bytes()).unwrap();
+    assert_eq!(dsn.subject(), Some("Warning: Delay in message delivery"));
+
+    test.clear_queue().await;
+}
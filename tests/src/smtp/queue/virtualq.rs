@@ -59,6 +59,7 @@ async fn virtual_queue() {
                     then: "'q2'".into(),
                 }]),
                 else_: "'q1'".into(),
+                ..Default::default()
             },
             ..Default::default()
         })
@@ -77,6 +78,7 @@ async fn virtual_queue() {
             name: "q1".into(),
             threads_per_node: 5,
             description: None,
+            ..Default::default()
         })
         .await;
     let queue2_id = local_admin
@@ -84,6 +86,7 @@ async fn virtual_queue() {
             name: "q2".into(),
             threads_per_node: 4,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -101,9 +104,11 @@ async fn virtual_queue() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 86_400_000u64.into(),
+                max_attempts: None,
             }),
             queue_id: queue1_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin
@@ -121,9 +126,11 @@ async fn virtual_queue() {
             }),
             expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
                 expire: 86_400_000u64.into(),
+                max_attempts: None,
             }),
             queue_id: queue2_id,
             description: None,
+            ..Default::default()
         })
         .await;
     local_admin.mta_allow_relaying().await;
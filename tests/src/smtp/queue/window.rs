@@ -0,0 +1,221 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    smtp::{inbound::TestQueueEvent, session::TestSession},
+    utils::server::TestServerBuilder,
+};
+use calcard::common::timezone::Tz;
+use chrono::{TimeZone, Timelike, Utc};
+use common::{
+    config::smtp::queue::DeliveryWindow,
+    expr::{Expression, tokenizer::TokenMap},
+};
+use registry::{
+    schema::{
+        enums::{TimeZone as RegistryTimeZone, Weekday},
+        prelude::{ObjectType, Property},
+        structs::{
+            Expression as RegistryExpression, MtaDeliveryExpiration, MtaDeliveryExpirationTtl,
+            MtaDeliverySchedule, MtaDeliveryScheduleInterval, MtaDeliveryScheduleIntervals,
+            MtaDeliveryScheduleIntervalsOrDefault, MtaOutboundStrategy, MtaVirtualQueue,
+        },
+    },
+    types::{list::List, map::Map},
+};
+use smtp::queue::{RecipientDomain, Status, spool::SmtpSpool};
+use std::str::FromStr;
+use store::write::now;
+
+#[tokio::test]
+async fn queue_delivery_window() {
+    // Business hours, Monday to Friday from 09:00 to 17:00
+    let window = DeliveryWindow {
+        days: 0b0001_1111,
+        hour_start: 9,
+        hour_end: 17,
+        tz: Tz::UTC,
+    };
+    let ts = |day: u32, hour: u32, minute: u32| {
+        // January 5th, 2026 is a Monday
+        Utc.with_ymd_and_hms(2026, 1, day, hour, minute, 0)
+            .unwrap()
+            .timestamp() as u64
+    };
+    for (now, expected) in [
+        // Inside the window
+        (ts(5, 9, 0), None),
+        (ts(7, 12, 30), None),
+        (ts(9, 16, 59), None),
+        // Before the window opens on a weekday
+        (ts(6, 8, 59), Some(ts(6, 9, 0))),
+        (ts(6, 0, 0), Some(ts(6, 9, 0))),
+        // After the window closes on a weekday
+        (ts(6, 17, 0), Some(ts(7, 9, 0))),
+        (ts(6, 23, 59), Some(ts(7, 9, 0))),
+        // Friday evening and weekends move to Monday
+        (ts(9, 17, 0), Some(ts(12, 9, 0))),
+        (ts(10, 12, 0), Some(ts(12, 9, 0))),
+        (ts(11, 23, 0), Some(ts(12, 9, 0))),
+    ] {
+        assert_eq!(
+            window.next_start(now),
+            expected,
+            "failed for {}",
+            Utc.timestamp_opt(now as i64, 0).unwrap()
+        );
+    }
+
+    // Windows are evaluated in their own time zone
+    let window = DeliveryWindow {
+        days: 0,
+        hour_start: 9,
+        hour_end: 17,
+        tz: Tz::from_str("America/New_York").unwrap(),
+    };
+    assert_eq!(window.next_start(ts(6, 14, 0)), None);
+    assert_eq!(window.next_start(ts(6, 13, 59)), Some(ts(6, 14, 0)));
+    assert_eq!(window.next_start(ts(6, 22, 0)), Some(ts(7, 14, 0)));
+
+    // Create test server
+    let mut local = TestServerBuilder::new("smtp_queue_window")
+        .await
+        .with_http_listener(19059)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    // Open a one hour window that starts two hours from now
+    let hour_start = (Utc::now().hour() + 2) % 24;
+    let admin = local.account("admin");
+    admin.mta_allow_relaying().await;
+    admin.mta_allow_non_fqdn().await;
+    admin.mta_no_auth().await;
+    admin
+        .registry_create_object(MtaOutboundStrategy {
+            schedule: RegistryExpression {
+                else_: "'business-hours'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    let queue_id = admin
+        .registry_create_object(MtaVirtualQueue {
+            name: "default".into(),
+            threads_per_node: 25,
+            description: None,
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(MtaDeliverySchedule {
+            name: "business-hours".into(),
+            retry: MtaDeliveryScheduleIntervalsOrDefault::Custom(MtaDeliveryScheduleIntervals {
+                intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                    duration: 1_000u64.into(),
+                }]),
+            }),
+            notify: MtaDeliveryScheduleIntervalsOrDefault::Custom(MtaDeliveryScheduleIntervals {
+                intervals: List::from_iter([MtaDeliveryScheduleInterval {
+                    duration: 86_400_000u64.into(),
+                }]),
+            }),
+            expiry: MtaDeliveryExpiration::Ttl(MtaDeliveryExpirationTtl {
+                expire: (3 * 86_400_000u64).into(),
+                max_attempts: None,
+            }),
+            queue_id,
+            delivery_days: Map::new(vec![
+                Weekday::Monday,
+                Weekday::Tuesday,
+                Weekday::Wednesday,
+                Weekday::Thursday,
+                Weekday::Friday,
+                Weekday::Saturday,
+                Weekday::Sunday,
+            ]),
+            delivery_hour_start: Some(hour_start as u64),
+            delivery_hour_end: Some(hour_start as u64 + 1),
+            delivery_time_zone: Some(RegistryTimeZone::UTC),
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    local.reload_core();
+    local.expect_reload_settings().await;
+
+    // Expression function
+    let expr = Expression::parse(
+        &TokenMap::default(),
+        "in_delivery_window('business-hours') + '-' + in_delivery_window('unknown')",
+    );
+    assert_eq!(
+        local
+            .server
+            .eval_expr::<String, _>(
+                &expr,
+                &RecipientDomain::new("foobar.org"),
+                ObjectType::Account.singleton(),
+                Property::AccountName,
+                0
+            )
+            .await
+            .unwrap(),
+        "0-1"
+    );
+
+    // The first attempt is held until the window opens
+    let mut session = local.new_mta_session();
+    session.data.remote_ip_str = "10.0.0.1".into();
+    session.eval_session_params().await;
+    session.ehlo("mx.test.org").await;
+    session
+        .send_message("john@test.org", &["bill@foobar.org"], "test:no_dkim", "250")
+        .await;
+    let now_ = now();
+    let mut window_start = Utc::now()
+        .with_hour(hour_start)
+        .and_then(|dt| dt.with_minute(0))
+        .and_then(|dt| dt.with_second(0))
+        .unwrap()
+        .timestamp() as u64;
+    if window_start <= now_ {
+        window_start += 86400;
+    }
+    let mut message = local.expect_message().await;
+    let rcpt = message.message.recipients.first().unwrap();
+    assert_eq!(rcpt.retry.due, window_start);
+    assert_eq!(rcpt.retry.inner, 0);
+    assert_eq!(local.message_due(message.queue_id).await, window_start);
+
+    // Recipients that become due outside the window are deferred again
+    // without counting as a delivery attempt
+    message.message.recipients[0].retry.due = now_;
+    let queue_id = message.queue_id;
+    assert!(
+        message
+            .save_changes(&local.server, window_start.into())
+            .await
+    );
+    let attempt = local.delivery_attempt_for_queue(queue_id, "default").await;
+    assert_eq!(attempt.due, now_);
+    attempt.try_deliver(local.server.clone());
+    local.read_event().await.assert_refresh();
+
+    let message = local.last_queued_message().await;
+    let rcpt = message.message.recipients.first().unwrap();
+    assert_eq!(rcpt.retry.due, window_start);
+    assert_eq!(rcpt.retry.inner, 0);
+    assert!(matches!(rcpt.status, Status::Scheduled));
+    assert_eq!(local.message_due(message.queue_id).await, window_start);
+
+    // No delay DSNs are generated while waiting for the window
+    assert_eq!(local.read_queued_messages().await.len(), 1);
+    local.assert_no_events();
+}
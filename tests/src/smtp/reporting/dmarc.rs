@@ -8,11 +8,14 @@ use crate::{
     smtp::{inbound::TestMessage, session::VerifyResponse},
     utils::{dns::DnsCache, server::TestServerBuilder},
 };
+use ahash::{AHashMap, AHashSet};
 use common::{config::smtp::report::AggregateFrequency, ipc::DmarcEvent};
 use mail_auth::{
     common::parse::TxtRecordParser,
     dmarc::Dmarc,
-    report::{ActionDisposition, Disposition, DmarcResult, Record, Report},
+    report::{
+        ActionDisposition, DKIMAuthResult, Disposition, DkimResult, DmarcResult, Record, Report,
+    },
 };
 use registry::schema::structs::{
     DmarcInternalReport, DmarcReportSettings, Expression, ReportSettings,
@@ -186,3 +189,148 @@ async fn report_dmarc() {
     }
     test.assert_report_is_empty::<DmarcInternalReport>().await;
 }
+
+#[tokio::test]
+async fn report_dmarc_split() {
+    let mut test = TestServerBuilder::new("smtp_report_dmarc_split_test")
+        .await
+        .with_http_listener(19080)
+        .await
+        .disable_services()
+        .capture_queue()
+        .build()
+        .await;
+
+    let admin = test.account("admin");
+    let domain_id = admin.find_or_create_domain("example.org").await;
+    admin.create_dkim_signatures(domain_id).await;
+    admin
+        .registry_create_object(ReportSettings {
+            outbound_report_submitter: Expression {
+                else_: "'mx.example.org'".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin
+        .registry_create_object(DmarcReportSettings {
+            aggregate_dkim_sign_domain: Expression {
+                else_: "'example.org'".into(),
+                ..Default::default()
+            },
+            aggregate_from_address: Expression {
+                else_: "'reports@example.org'".into(),
+                ..Default::default()
+            },
+            aggregate_send_frequency: Expression {
+                else_: "daily".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .await;
+    admin.reload_settings().await;
+    test.reload_core();
+    test.expect_reload_settings().await;
+
+    // Seed two identical events for each source IP, the second one with a
+    // different human readable DKIM result
+    let max_size = 2048;
+    let dmarc_record =
+        Arc::new(Dmarc::parse(b"v=DMARC1; p=reject; rua=mailto:reports@foobar.org!2k").unwrap());
+    assert_eq!(dmarc_record.rua()[0].max_size, max_size);
+    let num_ips = 150;
+    for ip in 0..num_ips {
+        for human_result in ["first", "second"] {
+            test.server
+                .schedule_dmarc(Box::new(DmarcEvent {
+                    domain: "foobar.org".to_string(),
+                    report_record: Record::new()
+                        .with_source_ip(IpAddr::from([10, 0, (ip / 256) as u8, (ip % 256) as u8]))
+                        .with_action_disposition(ActionDisposition::Reject)
+                        .with_dmarc_dkim_result(DmarcResult::Fail)
+                        .with_dmarc_spf_result(DmarcResult::Fail)
+                        .with_dkim_auth_result(
+                            DKIMAuthResult::new()
+                                .with_domain("foobar.org")
+                                .with_selector("default")
+                                .with_result(DkimResult::Fail)
+                                .with_human_result(human_result),
+                        )
+                        .with_envelope_from("foobar.org")
+                        .with_header_from("foobar.org"),
+                    dmarc_record: dmarc_record.clone(),
+                    interval: AggregateFrequency::Weekly,
+                    span_id: 0,
+                }))
+                .await;
+        }
+    }
+    let reports = test.read_report_events::<DmarcInternalReport>().await;
+    assert_eq!(reports.len(), 1);
+    let (report_id, report) = reports.into_iter().next().unwrap();
+    assert_eq!(report.rua_max_size, max_size as u64);
+    assert_eq!(report.report.records.len(), num_ips);
+    assert!(report.report.records.iter().all(|record| record.count == 2));
+    test.server
+        .send_dmarc_aggregate_report(report_id.id())
+        .await
+        .unwrap();
+
+    // Expect the report to be split into multiple parts under the size limit,
+    // each one covering the period in which its own records were seen
+    let seen = report
+        .report
+        .records
+        .iter()
+        .map(|record| {
+            (
+                record.source_ip.unwrap().0,
+                (
+                    record.first_seen.unwrap().timestamp() as u64,
+                    record.last_seen.unwrap().timestamp() as u64,
+                ),
+            )
+        })
+        .collect::<AHashMap<_, _>>();
+    let mut report_ids = AHashSet::new();
+    let mut ips = AHashSet::new();
+    while ips.len() < num_ips {
+        let message = test.expect_message().await.read_message(&test).await;
+        let signature_len = message
+            .strip_prefix("DKIM-Signature:")
+            .and_then(|signature| {
+                signature
+                    .match_indices("\r\n")
+                    .find(|(pos, _)| !signature[pos + 2..].starts_with([' ', '\t']))
+                    .map(|(pos, _)| "DKIM-Signature:".len() + pos + 2)
+            })
+            .unwrap_or_default();
+        assert!(message.len() - signature_len <= max_size);
+        let part = Report::parse_rfc5322(message.as_bytes()).unwrap();
+        assert_eq!(part.domain(), "foobar.org");
+        assert!(
+            part.report_id()
+                .starts_with(&format!("{}.", report.report.report_id)),
+            "{}",
+            part.report_id()
+        );
+        assert!(report_ids.insert(part.report_id().to_string()));
+        let mut range = (u64::MAX, 0);
+        for record in part.records() {
+            assert_eq!(record.count(), 2);
+            assert_eq!(record.dkim_auth_result().len(), 1);
+            let ip = record.source_ip().unwrap();
+            assert!(ips.insert(ip));
+            let (first_seen, last_seen) = seen[&ip];
+            range = (range.0.min(first_seen), range.1.max(last_seen));
+        }
+        assert_eq!((part.date_range_begin(), part.date_range_end()), range);
+        assert!(part.date_range_begin() >= report.report.date_range_begin.timestamp() as u64);
+        assert!(part.date_range_end() < report.report.date_range_end.timestamp() as u64);
+    }
+    assert!(report_ids.len() >= 2, "{report_ids:?}");
+    test.assert_no_events();
+    test.assert_report_is_empty::<DmarcInternalReport>().await;
+}
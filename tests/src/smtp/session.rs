@@ -10,7 +10,13 @@ use common::{
     config::server::ServerProtocol,
     network::{ServerInstance, SessionStream, TcpAcceptor, limiter::ConcurrencyLimiter},
 };
-use rustls::{ServerConfig, server::ResolvesServerCert};
+use rustls::{
+    DigitallySignedStruct, ServerConfig, SignatureScheme,
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::aws_lc_rs::default_provider,
+    server::ResolvesServerCert,
+};
+use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
 use smtp::core::{Session, SessionAddress, SessionData, SessionParameters, State};
 use std::{borrow::Cow, path::PathBuf, sync::Arc};
 use tokio::{
@@ -75,6 +81,10 @@ impl SessionStream for DummyIo {
     fn tls_version_and_cipher(&self) -> (Cow<'static, str>, Cow<'static, str>) {
         ("".into(), "".into())
     }
+
+    fn peer_certificates(&self) -> Option<&[CertificateDer<'static>]> {
+        None
+    }
 }
 
 impl Unpin for DummyIo {}
@@ -298,6 +308,23 @@ pub fn load_test_message(file: &str, test: &str) -> String {
     std::fs::read_to_string(test_file).unwrap()
 }
 
+pub fn build_test_message(from: &str, subject: &str) -> String {
+    let domain = from.rsplit_once('@').map_or(from, |(_, domain)| domain);
+    format!(
+        concat!(
+            "From: Bill <{from}>\r\n",
+            "To: John <john@example.org>\r\n",
+            "Subject: {subject}\r\n",
+            "Message-ID: <test-message@{domain}>\r\n",
+            "\r\n",
+            "Please see the attached document.\r\n"
+        ),
+        from = from,
+        subject = subject,
+        domain = domain
+    )
+}
+
 pub trait VerifyResponse {
     fn assert_code(self, expected_code: &str) -> Self;
     fn assert_contains(self, expected_text: &str) -> Self;
@@ -366,6 +393,7 @@ impl TestServerInstance for ServerInstance {
                 config: tls_config.clone(),
                 acceptor: TlsAcceptor::from(tls_config),
                 implicit: false,
+                client_cert_verifier: None,
             },
             limiter: ConcurrencyLimiter::new(100),
             shutdown_rx,
@@ -384,6 +412,47 @@ impl ResolvesServerCert for DummyCertResolver {
     }
 }
 
+// Accepts any server certificate, the test listeners use self-signed ones
+#[derive(Debug)]
+pub struct DummyVerifier;
+
+impl ServerCertVerifier for DummyVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
 pub fn test_server_instance() -> ServerInstance {
     ServerInstance::test_with_shutdown(watch::channel(false).1)
 }
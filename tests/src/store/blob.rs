@@ -487,6 +487,30 @@ async fn test_store(store: BlobStore) {
             .is_none()
     );
 
+    // Prefixes are decoded according to the blob header, including blobs that
+    // start with what looks like an LZ4 length prefix
+    let mut data = (DATA.len() as u32).to_le_bytes().to_vec();
+    data.extend_from_slice(DATA);
+    let hash = BlobHash::generate(&data);
+    for compression in [CompressionAlgo::None, CompressionAlgo::Lz4] {
+        store
+            .put_blob(hash.as_slice(), &data, compression)
+            .await
+            .unwrap();
+        for len in [4, 16, data.len(), data.len() + 1] {
+            assert_eq!(
+                store
+                    .get_blob_prefix(hash.as_slice(), len)
+                    .await
+                    .unwrap()
+                    .unwrap(),
+                &data[..len.min(data.len())],
+                "{compression:?} {len}"
+            );
+        }
+        assert!(store.delete_blob(hash.as_slice()).await.unwrap());
+    }
+
     // Test large blob
     let mut data = Vec::with_capacity(50 * 1024 * 1024);
     while data.len() < 50 * 1024 * 1024 {
@@ -11,7 +11,15 @@ use crate::utils::{
 };
 use ::registry::schema::enums::CompressionAlgo;
 use ahash::AHashSet;
-use common::{DATABASE_SCHEMA_VERSION, manager::backup::BackupParams};
+use common::{
+    DATABASE_SCHEMA_VERSION,
+    manager::{
+        backup::BackupParams,
+        incremental::{
+            BackupKind, IncrementalBackupParams, restore_backup_chain, verify_backup_chain,
+        },
+    },
+};
 use store::{
     rand,
     write::{
@@ -208,6 +216,128 @@ pub async fn test(test: &TestServer) {
     snapshot.assert_is_eq(&Snapshot::new(&db).await);
     println!(" GREAT SUCCESS!");
 
+    // Take an incremental backup baseline
+    println!("Running incremental backup...");
+    let backup_dir = TempDir::new("incremental_backup_tests", true);
+    let params = IncrementalBackupParams {
+        path: backup_dir.path.clone(),
+        ..Default::default()
+    };
+    let baseline = test
+        .server
+        .core
+        .incremental_backup(params.clone())
+        .await
+        .unwrap();
+    assert_eq!(baseline.kind, BackupKind::Full);
+    assert_eq!(baseline.sequence, 1);
+    assert!(baseline.parent.is_none());
+
+    // Update, delete and add data
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(0)
+        .with_collection(Collection::Email)
+        .with_document(0)
+        .set(ValueClass::Property(0), random_bytes(32))
+        .clear(ValueClass::Property(1))
+        .any_op(Operation::Index {
+            field: 9,
+            key: random_bytes(4),
+            set: true,
+        })
+        .log_item_update(SyncCollection::Email, None)
+        .with_account_id(u32::MAX)
+        .with_collection(Collection::Principal)
+        .with_document(1)
+        .add(ValueClass::Quota, 500);
+    let data = random_bytes(4096);
+    let new_hash = BlobHash::generate(data.as_slice());
+    test.server
+        .blob_store()
+        .put_blob(new_hash.as_ref(), &data, CompressionAlgo::Lz4)
+        .await
+        .unwrap();
+    batch.set(ValueClass::Blob(BlobOp::Commit { hash: new_hash }), vec![]);
+    db.write(batch.build_all()).await.unwrap();
+    let snapshot = Snapshot::new(&db).await;
+
+    // The increment only contains the accounts with new changes
+    let increment = test
+        .server
+        .core
+        .incremental_backup(params.clone())
+        .await
+        .unwrap();
+    assert_eq!(increment.kind, BackupKind::Incremental);
+    assert_eq!(increment.sequence, 2);
+    assert_eq!(increment.baseline, 1);
+    assert_eq!(increment.parent.as_ref().unwrap().sequence, 1);
+    assert!(increment.change_ids[&0] > baseline.change_ids[&0]);
+    for account_id in 1u32..10u32 {
+        assert_eq!(
+            increment.change_ids[&account_id],
+            baseline.change_ids[&account_id]
+        );
+    }
+    // Only the modified keys are exported
+    let property_file = format!("subspace_{}", char::from(SUBSPACE_PROPERTY));
+    for (file, records) in [
+        (property_file.clone(), 1),
+        (format!("deleted_{}", char::from(SUBSPACE_PROPERTY)), 1),
+        (format!("subspace_{}", char::from(SUBSPACE_INDEXES)), 1),
+        (format!("deleted_{}", char::from(SUBSPACE_INDEXES)), 0),
+        (format!("subspace_{}", char::from(SUBSPACE_QUOTA)), 1),
+    ] {
+        assert_eq!(
+            increment.files[&file].records, records,
+            "{file}: {} records in the increment, {} in the baseline",
+            increment.files[&file].records, baseline.files[&file].records
+        );
+    }
+    assert_eq!(
+        increment.files[&format!("subspace_{}", char::from(SUBSPACE_BLOBS))].records,
+        1
+    );
+
+    // Restore the chain into an empty store
+    println!("Restoring incremental backup chain...");
+    store_destroy(&db).await;
+    store_assert_is_empty(&db, db.clone().into(), true).await;
+    let restored = restore_backup_chain(
+        db.clone(),
+        test.server.blob_store().clone(),
+        &backup_dir.path,
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored.sequence, 2);
+    snapshot.assert_is_eq(&Snapshot::new(&db).await);
+
+    // A new baseline is taken once the chain reaches the maximum length
+    let rebased = test
+        .server
+        .core
+        .incremental_backup(IncrementalBackupParams {
+            max_increments: 1,
+            ..params
+        })
+        .await
+        .unwrap();
+    assert_eq!(rebased.kind, BackupKind::Full);
+    assert_eq!(rebased.sequence, 3);
+    assert_eq!(rebased.baseline, 3);
+
+    // Tampered sets are rejected
+    let set_path = backup_dir.path.join("backup-00000002");
+    assert_eq!(verify_backup_chain(&set_path).unwrap().len(), 2);
+    let file_path = set_path.join(&property_file);
+    let mut contents = std::fs::read(&file_path).unwrap();
+    contents.push(0);
+    std::fs::write(&file_path, contents).unwrap();
+    assert!(verify_backup_chain(&set_path).is_err());
+    backup_dir.delete();
+
     // Destroy store
     store_destroy(&db).await;
     store_assert_is_empty(&db, db.clone().into(), true).await;
@@ -10,6 +10,8 @@ pub mod lookup;
 pub mod ops;
 pub mod query;
 pub mod registry;
+#[cfg(feature = "postgres")]
+pub mod replica;
 
 use crate::utils::server::TestServerBuilder;
 use std::io::Read;
@@ -50,6 +52,12 @@ pub async fn search_tests() {
     }
 }
 
+#[cfg(feature = "postgres")]
+#[tokio::test(flavor = "multi_thread")]
+pub async fn replica_tests() {
+    replica::test().await;
+}
+
 pub fn deflate_test_resource(name: &str) -> Vec<u8> {
     let mut csv_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     csv_path.push("resources");
@@ -10,7 +10,10 @@ use registry::{
     jmap::{IntoValue, JmapValue, JsonPointerPatch, MaybeUnpatched, RegistryJsonPatch},
     pickle::{Pickle, PickledStream},
     schema::{
-        enums::{AccountType, Locale, Permission, StorageQuota},
+        enums::{
+            AccountType, CalendarInvitationPolicy, Locale, Permission, ServiceProtocol,
+            StorageQuota,
+        },
         prelude::{Object, ObjectType, Property},
         structs::{
             Account, CertificateManagement, Credential, CredentialPermissions,
@@ -59,7 +62,10 @@ pub async fn test(test: &TestServer) {
                 name: "alias2".into(),
             },
         ]),
+        calendar_invitations: CalendarInvitationPolicy::AutoAdd,
+        categorize_mail: true,
         created_at: UTCDateTime::now(),
+        credential_generation: 3,
         credentials: List::from_iter([
             Credential::Password(PasswordCredential {
                 allowed_ips: Map::new(vec![IpAddrOrMask::from_str("192.168.1.1").unwrap()]),
@@ -85,12 +91,16 @@ pub async fn test(test: &TestServer) {
         ]),
         description: "This is a test Account".to_string().into(),
         domain_id: 1004u64.into(),
+        enabled_protocols: Map::new(vec![ServiceProtocol::Jmap, ServiceProtocol::Imap]),
         encryption_at_rest: EncryptionAtRest::Aes128(EncryptionSettings {
             allow_spam_training: true,
             encrypt_on_append: false,
             public_key: 0u64.into(),
         }),
         locale: Locale::EnUS,
+        max_message_size: Some(10 * 1024 * 1024),
+        max_sessions: Some(20),
+        max_sessions_per_protocol: VecMap::from_iter([(ServiceProtocol::Imap, 10u64)]),
         member_group_ids: Map::new(vec![2000u64.into(), 2001u64.into()]),
         member_tenant_id: None,
         name: "user".into(),
@@ -105,6 +115,7 @@ pub async fn test(test: &TestServer) {
         roles: UserRoles::Custom(CustomRoles {
             role_ids: Map::new(vec![5000u64.into()]),
         }),
+        sieve_logging: true,
         time_zone: None,
     });
     let account_pickle = account.to_pickled_vec();
@@ -0,0 +1,176 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::storage::build_data_store;
+use registry::{
+    schema::{
+        enums::DataStoreType,
+        structs::{DataStore, PostgreSqlSettings, PostgreSqlStore},
+    },
+    types::duration,
+};
+use std::time::Duration;
+use store::{
+    IterateParams, Store, ValueKey,
+    write::{BatchBuilder, ValueClass},
+};
+use types::{collection::Collection, field::EmailField};
+
+const REPLICA_DATABASE: &str = "stalwart_replica";
+// Window during which reads for a written account are served by the primary
+const WRITE_STICKINESS: Duration = Duration::from_secs(2);
+
+pub async fn test() {
+    println!("Running read replica tests...");
+
+    let DataStore::PostgreSql(config) = build_data_store(DataStoreType::PostgreSql, "").await
+    else {
+        unreachable!()
+    };
+
+    // The replica is a second database on the same server, it is not kept in
+    // sync with the primary so the value read tells which one served it
+    let primary = Store::build(DataStore::PostgreSql(config.clone()))
+        .await
+        .unwrap();
+    let _ = primary
+        .sql_query::<usize>(&format!("CREATE DATABASE {REPLICA_DATABASE}"), vec![])
+        .await;
+    let replica = Store::build(DataStore::PostgreSql(PostgreSqlStore {
+        database: REPLICA_DATABASE.into(),
+        ..config.clone()
+    }))
+    .await
+    .unwrap();
+    primary.create_tables().await.unwrap();
+    replica.create_tables().await.unwrap();
+    for account_id in [1, 2] {
+        write_value(&primary, account_id, "primary").await;
+        write_value(&replica, account_id, "replica").await;
+    }
+
+    // The second replica does not accept connections
+    let replica_settings = PostgreSqlSettings {
+        host: config.host.clone(),
+        port: config.port,
+        database: REPLICA_DATABASE.into(),
+        auth_username: config.auth_username.clone(),
+        auth_secret: config.auth_secret.clone(),
+        options: None,
+    };
+    let store = Store::build(DataStore::PostgreSql(PostgreSqlStore {
+        read_replicas: vec![
+            replica_settings.clone(),
+            PostgreSqlSettings {
+                port: 1,
+                ..replica_settings
+            },
+        ]
+        .into(),
+        read_replica_stickiness: duration::Duration(WRITE_STICKINESS),
+        ..config
+    }))
+    .await
+    .unwrap();
+
+    // Read-only scans are served by the healthy replica, the failing one falls
+    // back to the primary and is taken out of rotation
+    let mut values = Vec::new();
+    for _ in 0..4 {
+        values.extend(scan_values(&store, 1, true).await);
+    }
+    assert!(
+        values
+            .iter()
+            .all(|value| value == "replica" || value == "primary"),
+        "{values:?}"
+    );
+    assert!(values.iter().any(|value| value == "replica"), "{values:?}");
+    for _ in 0..4 {
+        assert_eq!(scan_values(&store, 1, true).await, ["replica"]);
+    }
+
+    // Other scans and point reads are always served by the primary
+    assert_eq!(scan_values(&store, 1, false).await, ["primary"]);
+    assert_eq!(read_value(&store, 1).await, "primary");
+
+    // Scans for an account are served by the primary right after it was written
+    write_value(&store, 2, "updated").await;
+    assert_eq!(scan_values(&store, 2, true).await, ["updated"]);
+    assert_eq!(scan_values(&store, 1, true).await, ["replica"]);
+
+    // Replicas serve the account again once the window has passed
+    tokio::time::sleep(WRITE_STICKINESS + Duration::from_secs(1)).await;
+    assert_eq!(scan_values(&store, 2, true).await, ["replica"]);
+    assert_eq!(read_value(&store, 2).await, "updated");
+
+    for account_id in [1, 2] {
+        delete_value(&primary, account_id).await;
+        delete_value(&replica, account_id).await;
+    }
+}
+
+async fn write_value(store: &Store, account_id: u32, value: &str) {
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(account_id)
+        .with_collection(Collection::Email)
+        .with_document(0)
+        .set(
+            ValueClass::Property(EmailField::Metadata.into()),
+            value.as_bytes().to_vec(),
+        );
+    store.write(batch.build_all()).await.unwrap();
+}
+
+async fn delete_value(store: &Store, account_id: u32) {
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(account_id)
+        .with_collection(Collection::Email)
+        .with_document(0)
+        .clear(ValueClass::Property(EmailField::Metadata.into()));
+    store.write(batch.build_all()).await.unwrap();
+}
+
+async fn read_value(store: &Store, account_id: u32) -> String {
+    store
+        .get_value::<String>(ValueKey::property(
+            account_id,
+            Collection::Email,
+            0,
+            EmailField::Metadata,
+        ))
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+async fn scan_values(store: &Store, account_id: u32, read_only: bool) -> Vec<String> {
+    let mut params = IterateParams::new(
+        ValueKey::property(account_id, Collection::Email, 0, EmailField::Metadata),
+        ValueKey::property(
+            account_id,
+            Collection::Email,
+            u32::MAX,
+            EmailField::Metadata,
+        ),
+    )
+    .ascending();
+    if read_only {
+        params = params.read_only();
+    }
+
+    let mut values = Vec::new();
+    store
+        .iterate(params, |_, value| {
+            values.push(String::from_utf8_lossy(value).into_owned());
+            Ok(true)
+        })
+        .await
+        .unwrap();
+    values
+}
@@ -0,0 +1,296 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    system::quota::create_message_with_size,
+    utils::{account::Account, server::TestServer},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use jmap_client::{client::Client, mailbox::Role};
+use mail_parser::MessageParser;
+use registry::schema::{
+    enums::{SharedBlobQuota, TaskStoreMaintenanceType},
+    prelude::Property,
+    structs::{Email, Task, TaskStatus, TaskStoreMaintenance},
+};
+use std::str::FromStr;
+use store::write::{BatchBuilder, BlobLink, BlobOp};
+use types::{blob_hash::BlobHash, collection::Collection, id::Id};
+
+const MESSAGE_SIZE: usize = 5000;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running shared blob tests...");
+    let admin = test.account("admin@example.org");
+    let john = test
+        .create_user_account(
+            "admin@example.org",
+            "john.blob@example.org",
+            "this is a very strong password",
+            &[],
+            "John Blob",
+        )
+        .await;
+    let jane = test
+        .create_user_account(
+            "admin@example.org",
+            "jane.blob@example.org",
+            "this is a very strong password",
+            &[],
+            "Jane Blob",
+        )
+        .await;
+    let john_id = john.id().document_id();
+    let jane_id = jane.id().document_id();
+    let message = create_message_with_size(
+        "bill@example.org",
+        "john.blob@example.org",
+        "Shared report",
+        MESSAGE_SIZE,
+    );
+    let hash = BlobHash::generate(&message);
+
+    // Import the same message into two mailboxes and into another account
+    let john_client = john.jmap_client().await;
+    let jane_client = jane.jmap_client().await;
+    let mut john_mailboxes = Vec::new();
+    for name in ["Blob Inbox", "Blob Archive"] {
+        john_mailboxes.push(
+            john_client
+                .mailbox_create(name, None::<String>, Role::None)
+                .await
+                .unwrap()
+                .take_id(),
+        );
+    }
+    let jane_mailbox = jane_client
+        .mailbox_create("Blob Inbox", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    let mut john_ids = Vec::new();
+    for mailbox_id in &john_mailboxes {
+        john_ids.push(import(&john_client, mailbox_id, &message).await);
+    }
+    let jane_ids = vec![import(&jane_client, &jane_mailbox, &message).await];
+    test.wait_for_tasks().await;
+
+    // A single blob is stored and each message references it
+    assert!(test.server.store().blob_exists(&hash).await.unwrap());
+    assert_references(test, john_id, &hash, &john_ids).await;
+    assert_references(test, jane_id, &hash, &jane_ids).await;
+
+    // Each reference is charged by default
+    assert_eq!(used_quota(test, john_id).await, 2 * MESSAGE_SIZE as i64);
+    assert_eq!(used_quota(test, jane_id).await, MESSAGE_SIZE as i64);
+
+    // Changing the policy recalculates the quotas, shared blobs are charged once
+    update_settings(admin, SharedBlobQuota::ChargeOnce).await;
+    test.wait_for_tasks().await;
+    assert_eq!(used_quota(test, john_id).await, MESSAGE_SIZE as i64);
+    assert_eq!(used_quota(test, jane_id).await, MESSAGE_SIZE as i64);
+
+    // New references are not charged
+    john_ids.push(import(&john_client, &john_mailboxes[1], &message).await);
+    test.wait_for_tasks().await;
+    assert_references(test, john_id, &hash, &john_ids).await;
+    assert_eq!(used_quota(test, john_id).await, MESSAGE_SIZE as i64);
+
+    // Deleting a reference keeps the blob and the quota charge
+    let message_id = john_ids.remove(0);
+    john_client.email_destroy(&message_id).await.unwrap();
+    test.wait_for_tasks().await;
+    assert!(test.server.store().blob_exists(&hash).await.unwrap());
+    assert_references(test, john_id, &hash, &john_ids).await;
+    assert_eq!(used_quota(test, john_id).await, MESSAGE_SIZE as i64);
+
+    // Deleting the last references releases the quota
+    for message_id in john_ids.drain(..) {
+        john_client.email_destroy(&message_id).await.unwrap();
+    }
+    test.wait_for_tasks().await;
+    assert_references(test, john_id, &hash, &[]).await;
+    assert_eq!(used_quota(test, john_id).await, 0);
+    assert!(test.server.store().blob_exists(&hash).await.unwrap());
+
+    // Attachments shared by different messages are charged once
+    let attachment = "0123456789abcdef".repeat(256);
+    let message_a = create_message_with_attachment("Report A", attachment.as_bytes());
+    let message_b = create_message_with_attachment("Report B", attachment.as_bytes());
+    let attachment_size = attachment_size(&message_a);
+    let message_a_id = import(&john_client, &john_mailboxes[0], &message_a).await;
+    let message_b_id = import(&john_client, &john_mailboxes[1], &message_b).await;
+    test.wait_for_tasks().await;
+    assert_eq!(
+        used_quota(test, john_id).await,
+        (message_a.len() + message_b.len()) as i64 - attachment_size
+    );
+    john_client.email_destroy(&message_a_id).await.unwrap();
+    test.wait_for_tasks().await;
+    assert_eq!(used_quota(test, john_id).await, message_b.len() as i64);
+    john_client.email_destroy(&message_b_id).await.unwrap();
+    test.wait_for_tasks().await;
+    assert_eq!(used_quota(test, john_id).await, 0);
+
+    // Missing links are restored by recounting the references
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(jane_id)
+        .with_collection(Collection::Email)
+        .with_document(document_id(&jane_ids[0]))
+        .clear(BlobOp::Link {
+            hash: hash.clone(),
+            to: BlobLink::Document,
+        });
+    test.server.store().write(batch.build_all()).await.unwrap();
+    assert_references(test, jane_id, &hash, &[]).await;
+
+    // Stale links left behind by deleted messages are removed as well
+    let mut batch = BatchBuilder::new();
+    batch
+        .with_account_id(jane_id)
+        .with_collection(Collection::Email)
+        .with_document(u32::MAX - 1)
+        .set(
+            BlobOp::Link {
+                hash: hash.clone(),
+                to: BlobLink::Document,
+            },
+            Vec::new(),
+        );
+    test.server.store().write(batch.build_all()).await.unwrap();
+    assert_eq!(
+        test.server
+            .store()
+            .blob_document_links(&hash, jane_id, Collection::Email.into())
+            .await
+            .unwrap(),
+        vec![u32::MAX - 1]
+    );
+    admin
+        .registry_create_object(Task::StoreMaintenance(TaskStoreMaintenance {
+            maintenance_type: TaskStoreMaintenanceType::RecountBlobReferences,
+            status: TaskStatus::now(),
+            shard_index: None,
+        }))
+        .await;
+    test.wait_for_tasks().await;
+    assert_references(test, jane_id, &hash, &jane_ids).await;
+    assert_eq!(used_quota(test, jane_id).await, MESSAGE_SIZE as i64);
+
+    // The blob is purged once it is no longer referenced
+    jane_client.email_destroy(&jane_ids[0]).await.unwrap();
+    test.wait_for_tasks().await;
+    test.blob_expire_all().await;
+    test.server
+        .store()
+        .purge_blobs_all_shards(test.server.blob_store().clone())
+        .await
+        .unwrap();
+    assert!(!test.server.store().blob_exists(&hash).await.unwrap());
+
+    // Remove test data
+    admin
+        .registry_update_setting(Email::default(), &[Property::SharedBlobQuota])
+        .await;
+    admin.reload_settings().await;
+    test.destroy_all_mailboxes(&john).await;
+    test.destroy_all_mailboxes(&jane).await;
+    admin.destroy_account(john).await;
+    admin.destroy_account(jane).await;
+    test.cleanup().await;
+}
+
+async fn update_settings(admin: &Account, shared_blob_quota: SharedBlobQuota) {
+    admin
+        .registry_update_setting(
+            Email {
+                shared_blob_quota,
+                ..Default::default()
+            },
+            &[Property::SharedBlobQuota],
+        )
+        .await;
+    admin.reload_settings().await;
+}
+
+fn create_message_with_attachment(subject: &str, attachment: &[u8]) -> Vec<u8> {
+    format!(
+        concat!(
+            "From: bill@example.org\r\n",
+            "To: john.blob@example.org\r\n",
+            "Subject: {}\r\n",
+            "MIME-Version: 1.0\r\n",
+            "Content-Type: multipart/mixed; boundary=\"report\"\r\n\r\n",
+            "--report\r\n",
+            "Content-Type: text/plain\r\n\r\n",
+            "{}\r\n",
+            "--report\r\n",
+            "Content-Type: application/octet-stream\r\n",
+            "Content-Disposition: attachment; filename=\"report.bin\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n\r\n",
+            "{}\r\n",
+            "--report--\r\n"
+        ),
+        subject,
+        subject,
+        STANDARD.encode(attachment)
+    )
+    .into_bytes()
+}
+
+fn attachment_size(message: &[u8]) -> i64 {
+    let message = MessageParser::new().parse(message).unwrap();
+    let part = message.attachment(0).unwrap();
+    (part.offset_end - part.offset_body) as i64
+}
+
+async fn import(client: &Client, mailbox_id: &str, message: &[u8]) -> String {
+    client
+        .email_import(message.to_vec(), [mailbox_id], None::<Vec<&str>>, None)
+        .await
+        .unwrap()
+        .take_id()
+}
+
+fn document_id(id: &str) -> u32 {
+    Id::from_str(id).unwrap().document_id()
+}
+
+async fn assert_references(
+    test: &TestServer,
+    account_id: u32,
+    hash: &BlobHash,
+    expected: &[String],
+) {
+    let mut expected = expected
+        .iter()
+        .map(|id| document_id(id))
+        .collect::<Vec<_>>();
+    expected.sort_unstable();
+    assert_eq!(
+        test.server
+            .store()
+            .blob_document_links(hash, account_id, Collection::Email.into())
+            .await
+            .unwrap(),
+        expected
+    );
+    assert_eq!(
+        test.server
+            .blob_references(account_id, Collection::Email, hash)
+            .await
+            .unwrap(),
+        expected
+    );
+}
+
+async fn used_quota(test: &TestServer, account_id: u32) -> i64 {
+    test.server
+        .get_used_quota_account(account_id)
+        .await
+        .unwrap()
+}
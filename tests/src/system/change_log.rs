@@ -0,0 +1,207 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer};
+use ::email::mailbox::INBOX_ID;
+use futures::StreamExt;
+use jmap_client::{
+    client::Client,
+    core::error::{MethodError, MethodErrorType},
+};
+use jmap_proto::types::state::State;
+use registry::schema::{
+    enums::TaskAccountMaintenanceType,
+    prelude::Property,
+    structs::{DataRetention, Task, TaskAccountMaintenance, TaskStatus},
+};
+use store::{IterateParams, LogKey};
+use types::{collection::SyncCollection, id::Id};
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running change log pruning tests...");
+    let admin = test.account("admin@example.org");
+
+    // Expire changes after one second
+    admin
+        .registry_update_setting(
+            DataRetention {
+                max_changes_history: None,
+                max_changes_age: Some(1000u64.into()),
+                ..Default::default()
+            },
+            &[Property::MaxChangesHistory, Property::MaxChangesAge],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    // Create test accounts
+    let idle = test
+        .create_user_account(
+            "admin@example.org",
+            "idle@example.org",
+            "this is a very strong password",
+            &[],
+            "idle@example.org",
+        )
+        .await;
+    let active = test
+        .create_user_account(
+            "admin@example.org",
+            "active@example.org",
+            "this is a very strong password",
+            &[],
+            "active@example.org",
+        )
+        .await;
+    let idle_client = idle.jmap_client().await;
+    let active_client = active.jmap_client().await;
+
+    // Both clients sync their mailboxes
+    import_message(&idle_client, 1).await;
+    import_message(&active_client, 1).await;
+    let idle_state = current_state(&idle_client).await;
+    let active_state = current_state(&active_client).await;
+
+    // The active client stays connected while new messages arrive
+    let mut changes = active_client
+        .event_source(None::<Vec<_>>, false, 1.into(), None)
+        .await
+        .unwrap();
+    let listener = tokio::spawn(async move { while changes.next().await.is_some() {} });
+    for num in 2..=3 {
+        import_message(&idle_client, num).await;
+        import_message(&active_client, num).await;
+    }
+    utils::clock::advance_test_clock(2);
+    purge_account(test, admin, &idle).await;
+    purge_account(test, admin, &active).await;
+
+    // The idle account is pruned up to its last change
+    assert_cannot_calculate(&idle_client, &idle_state).await;
+    assert_eq!(
+        changes_in_log(test, &idle).await,
+        vec![true],
+        "Expected only the truncation marker"
+    );
+
+    // The state referenced by the connected client is still available
+    let response = active_client
+        .email_changes(&active_state, None)
+        .await
+        .unwrap();
+    assert_eq!(response.created().len(), 2);
+    assert!(changes_in_log(test, &active).await.len() > 1);
+
+    // States recently requested by a client outlive its connection
+    listener.abort();
+    let _ = listener.await;
+    utils::clock::advance_test_clock(2);
+    purge_account(test, admin, &active).await;
+    let response = active_client
+        .email_changes(&active_state, None)
+        .await
+        .unwrap();
+    assert_eq!(response.created().len(), 2);
+
+    // Delete accounts
+    admin.destroy_account(idle).await;
+    admin.destroy_account(active).await;
+    test.wait_for_tasks().await;
+
+    // Reset settings
+    admin
+        .registry_update_setting(
+            DataRetention::default(),
+            &[Property::MaxChangesHistory, Property::MaxChangesAge],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.cleanup().await;
+}
+
+async fn import_message(client: &Client, num: usize) {
+    client
+        .email_import(
+            format!(
+                concat!(
+                    "From: bill@example.org\r\n",
+                    "To: jdoe@example.org\r\n",
+                    "Subject: TPS Report #{}\r\n",
+                    "\r\n",
+                    "I'm going to need those TPS reports ASAP."
+                ),
+                num
+            )
+            .into_bytes(),
+            [Id::from(INBOX_ID).to_string()],
+            None::<Vec<&str>>,
+            None,
+        )
+        .await
+        .unwrap();
+}
+
+async fn current_state(client: &Client) -> String {
+    client
+        .email_changes(State::Initial.to_string(), None)
+        .await
+        .unwrap()
+        .new_state()
+        .to_string()
+}
+
+async fn purge_account(test: &TestServer, admin: &Account, account: &Account) {
+    admin
+        .registry_create_object(Task::AccountMaintenance(TaskAccountMaintenance {
+            account_id: account.id(),
+            maintenance_type: TaskAccountMaintenanceType::Purge,
+            status: TaskStatus::now(),
+        }))
+        .await;
+    test.wait_for_tasks().await;
+}
+
+async fn assert_cannot_calculate(client: &Client, state: &str) {
+    match client.email_changes(state, None).await {
+        Err(jmap_client::Error::Method(MethodError {
+            p_type: MethodErrorType::CannotCalculateChanges,
+        })) => {}
+        other => panic!("Expected cannotCalculateChanges, got {other:?}"),
+    }
+}
+
+// Returns whether each email change log entry is a truncation marker
+async fn changes_in_log(test: &TestServer, account: &Account) -> Vec<bool> {
+    let account_id = account.id().document_id();
+    let collection = u8::from(SyncCollection::Email);
+    let mut entries = Vec::new();
+    test.server
+        .core
+        .storage
+        .data
+        .iterate(
+            IterateParams::new(
+                LogKey {
+                    account_id,
+                    collection,
+                    change_id: 0,
+                },
+                LogKey {
+                    account_id,
+                    collection,
+                    change_id: u64::MAX,
+                },
+            )
+            .ascending(),
+            |_, value| {
+                entries.push(value.is_empty());
+                Ok(true)
+            },
+        )
+        .await
+        .unwrap();
+    entries
+}
@@ -12,7 +12,8 @@ use common::{
     },
     storage::encryption::{EncryptionMethod, parse_public_key},
 };
-use email::message::crypto::EncryptMessage;
+use email::message::crypto::{DecryptMessage, EncryptMessage};
+use jmap_client::client::Client;
 use mail_parser::{MessageParser, MimeHeaders};
 use registry::schema::{
     prelude::{ObjectType, Property},
@@ -198,12 +199,145 @@ pub async fn test(test: &mut TestServer) {
         }
     }
 
+    // Rotate to an S/MIME key whose private key is held by the server
+    let server_key = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("resources")
+            .join("crypto")
+            .join("cert_smime_server.pem"),
+    )
+    .unwrap();
+    let server_key_id = account
+        .registry_create_many(
+            ObjectType::PublicKey,
+            [json!({
+                Property::Description: "This is a server-held key",
+                Property::Key: server_key
+            })],
+        )
+        .await
+        .created(0)
+        .object_id();
+    account
+        .registry_update_object(
+            ObjectType::AccountSettings,
+            Id::singleton(),
+            json!({
+                Property::EncryptionAtRest: EncryptionAtRest::Aes128(EncryptionSettings {
+                    allow_spam_training: false,
+                    encrypt_on_append: true,
+                    public_key: server_key_id,
+                })
+            }),
+        )
+        .await;
+    test.wait_for_tasks().await;
+
+    // Messages encrypted with a key the server does not hold are skipped
+    for message in download_all(&client).await {
+        if message.contains("should be encrypted") {
+            assert!(
+                message.contains("Content-Type: multipart/encrypted"),
+                "got message {message}, expected message encrypted with the old key"
+            );
+        } else if message.contains("already encrypted") {
+            assert!(
+                message.contains("xjMEZMYfNhYJKwYBBAHaRw8BAQdAYy"),
+                "got message {message}, expected message to be left intact"
+            );
+        } else if message.contains("plain text") {
+            assert!(
+                message.contains("Content-Type: application/pkcs7-mime")
+                    && !message.contains("I'm going to need those TPS reports ASAP."),
+                "got message {message}, expected message encrypted with the new key"
+            );
+            let decrypted = MessageParser::default()
+                .parse(message.as_bytes())
+                .unwrap()
+                .decrypt(std::slice::from_ref(&server_key))
+                .await
+                .unwrap();
+            assert!(
+                String::from_utf8(decrypted)
+                    .unwrap()
+                    .contains("I'm going to need those TPS reports ASAP."),
+                "expected message to decrypt with the new key"
+            );
+        } else {
+            panic!("Unexpected message: {:#?}", message)
+        }
+    }
+
+    // Rotate to the OpenPGP key, messages encrypted with the server-held key
+    // are decrypted and encrypted again with the new key
+    account
+        .registry_update_object(
+            ObjectType::AccountSettings,
+            Id::singleton(),
+            json!({
+                Property::EncryptionAtRest: EncryptionAtRest::Aes256(EncryptionSettings {
+                    allow_spam_training: false,
+                    encrypt_on_append: true,
+                    public_key: cert_ids[1],
+                })
+            }),
+        )
+        .await;
+    test.wait_for_tasks().await;
+
+    for message in download_all(&client).await {
+        if message.contains("should be encrypted") {
+            assert!(
+                message.contains("Content-Type: multipart/encrypted"),
+                "got message {message}, expected message to be left intact"
+            );
+        } else if message.contains("already encrypted") {
+            assert!(
+                message.contains("xjMEZMYfNhYJKwYBBAHaRw8BAQdAYy"),
+                "got message {message}, expected message to be left intact"
+            );
+        } else if message.contains("plain text") {
+            assert!(
+                message.contains("Content-Type: multipart/encrypted")
+                    && !message.contains("application/pkcs7-mime")
+                    && !message.contains("I'm going to need those TPS reports ASAP."),
+                "got message {message}, expected message encrypted with the new key only"
+            );
+            assert!(
+                MessageParser::default()
+                    .parse(message.as_bytes())
+                    .unwrap()
+                    .decrypt(std::slice::from_ref(&server_key))
+                    .await
+                    .is_err(),
+                "expected message to no longer decrypt with the old key"
+            );
+        } else {
+            panic!("Unexpected message: {:#?}", message)
+        }
+    }
+
     test.account("admin@example.org")
         .destroy_account(account)
         .await;
     test.cleanup().await;
 }
 
+async fn download_all(client: &Client) -> Vec<String> {
+    let mut request = client.build();
+    request.get_email();
+    let emails = request.send_get_email().await.unwrap().take_list();
+    assert_eq!(emails.len(), 3, "3 messages were expected: {:#?}.", emails);
+
+    let mut messages = Vec::with_capacity(emails.len());
+    for email in emails {
+        messages.push(
+            String::from_utf8(client.download(email.blob_id().unwrap()).await.unwrap()).unwrap(),
+        );
+    }
+    messages
+}
+
 pub async fn import_certs_and_encrypt() {
     for (name, method) in [
         ("cert_pgp.pem", EncryptionMethod::PGP),
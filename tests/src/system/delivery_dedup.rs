@@ -0,0 +1,154 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer, smtp::SmtpConnection};
+use registry::schema::{enums::DuplicateDelivery, prelude::Property, structs::Email};
+use serde_json::Value;
+use std::time::Duration;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running duplicate delivery tests...");
+    let admin = test.account("admin@example.org");
+    update_settings(admin, DuplicateDelivery::Discard).await;
+
+    let account = test
+        .create_user_account(
+            "admin@example.org",
+            "dedup@example.org",
+            "this is a very strong password",
+            &[],
+            "Dedup Test",
+        )
+        .await;
+    let mut lmtp = SmtpConnection::connect().await;
+
+    // First delivery is accepted
+    lmtp.ingest(
+        "bill@example.org",
+        &["dedup@example.org"],
+        &message("<dedup-1@example.org>", "Quarterly numbers"),
+    )
+    .await;
+    assert_eq!(destroy_emails(&account).await, 1);
+
+    // Repeated deliveries within the window are discarded, even if the
+    // original copy is no longer in the mailbox
+    lmtp.ingest(
+        "bill@example.org",
+        &["dedup@example.org"],
+        &message("<dedup-1@example.org>", "Quarterly numbers"),
+    )
+    .await;
+    assert_eq!(emails(&account).await.len(), 0);
+
+    // Same Message-ID with different contents is not a duplicate
+    lmtp.ingest(
+        "bill@example.org",
+        &["dedup@example.org"],
+        &message("<dedup-1@example.org>", "Corrected quarterly numbers"),
+    )
+    .await;
+    assert_eq!(destroy_emails(&account).await, 1);
+
+    // Deliveries outside the window are accepted
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    lmtp.ingest(
+        "bill@example.org",
+        &["dedup@example.org"],
+        &message("<dedup-1@example.org>", "Quarterly numbers"),
+    )
+    .await;
+    assert_eq!(destroy_emails(&account).await, 1);
+
+    // Duplicates are flagged rather than discarded
+    update_settings(admin, DuplicateDelivery::Flag).await;
+    for _ in 0..2 {
+        lmtp.ingest(
+            "bill@example.org",
+            &["dedup@example.org"],
+            &message("<dedup-2@example.org>", "Monthly numbers"),
+        )
+        .await;
+    }
+    let emails = emails(&account).await;
+    assert_eq!(emails.len(), 2, "{emails:?}");
+    assert_eq!(
+        emails
+            .iter()
+            .filter(|email| email["keywords"]["$duplicate"] == Value::Bool(true))
+            .count(),
+        1,
+        "{emails:?}"
+    );
+
+    // Remove test data
+    admin
+        .registry_update_setting(
+            Email::default(),
+            &[
+                Property::DuplicateDelivery,
+                Property::DuplicateDeliveryWindow,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+    test.destroy_all_mailboxes(&account).await;
+    admin.destroy_account(account).await;
+    test.cleanup().await;
+}
+
+async fn update_settings(admin: &Account, action: DuplicateDelivery) {
+    admin
+        .registry_update_setting(
+            Email {
+                duplicate_delivery: action,
+                duplicate_delivery_window: 2_000u64.into(),
+                ..Default::default()
+            },
+            &[
+                Property::DuplicateDelivery,
+                Property::DuplicateDeliveryWindow,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+}
+
+async fn emails(account: &Account) -> Vec<Value> {
+    account
+        .jmap_get("Email", ["id", "keywords"], Vec::<String>::new())
+        .await
+        .list()
+        .to_vec()
+}
+
+async fn destroy_emails(account: &Account) -> usize {
+    let ids = emails(account)
+        .await
+        .iter()
+        .filter_map(|email| email["id"].as_str().map(|id| id.to_string()))
+        .collect::<Vec<_>>();
+    if !ids.is_empty() {
+        account
+            .jmap_destroy("Email", &ids, Vec::<(&str, &str)>::new())
+            .await;
+    }
+    ids.len()
+}
+
+fn message(message_id: &str, body: &str) -> String {
+    format!(
+        concat!(
+            "From: bill@example.org\r\n",
+            "To: dedup@example.org\r\n",
+            "Message-ID: {}\r\n",
+            "Subject: Report\r\n",
+            "\r\n",
+            "{}\r\n"
+        ),
+        message_id, body
+    )
+}
@@ -388,6 +388,7 @@ pub async fn test(test: &TestServer) {
                         if_: "matches('^([^.]+)\\.([^.]+)', rcpt)".to_string(),
                         then: "$1".to_string(),
                     }]),
+                    ..Default::default()
                 },
             }),
             ..Default::default()
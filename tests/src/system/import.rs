@@ -0,0 +1,273 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    account::Account,
+    imap::{AssertResult, Type},
+    server::TestServer,
+};
+use imap_proto::ResponseType;
+use registry::schema::{
+    enums::MessageImportFormat,
+    prelude::Property,
+    structs::{Email, Task, TaskImportMessages, TaskStatus},
+};
+use std::path::Path;
+use store::write::now;
+use types::id::Id;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running message import tests...");
+    let admin = test.account("admin@example.org");
+
+    // Create a Maildir and an mbox file under the import root
+    let import_root = test.temp_dir.path.join("import");
+    let maildir = import_root.join("john").join("Maildir");
+    for (folder, name, contents) in [
+        ("cur", "1700000000.M1P1.mx:2,S", message(1, "Import seen")),
+        ("new", "1700000100.M2P1.mx", message(2, "Import unseen")),
+        ("cur", "1700000200.M3P1.mx:2,", String::new()),
+        ("tmp", "1700000250.M9P1.mx", message(9, "Import partial")),
+        (
+            ".Sent/cur",
+            "1700000300.M4P1.mx:2,RSa",
+            message(4, "Import sent"),
+        ),
+        (
+            ".Archive.2019/cur",
+            "1546300800.M5P1.mx:2,FS",
+            message(5, "Import archived"),
+        ),
+    ] {
+        write_file(&maildir.join(folder).join(name), &contents);
+    }
+    write_file(&maildir.join(".Sent").join("dovecot-keywords"), "0 Work\n");
+    write_file(
+        &import_root.join("john").join("messages.mbox"),
+        concat!(
+            "From alice@example.org Mon Jan  1 10:00:00 2024\n",
+            "From: alice@example.org\n",
+            "Subject: Mbox first\n",
+            "Message-ID: <mbox-1@example.org>\n",
+            "Status: RO\n",
+            "X-Status: F\n",
+            "\n",
+            "Hello\n",
+            ">From the mbox\n",
+            ">>From nested quotes\n",
+            "\n",
+            "From bob@example.org Tue Jan  2 10:00:00 2024\n",
+            "From: bob@example.org\n",
+            "Subject: Mbox second\n",
+            "Message-ID: <mbox-2@example.org>\n",
+            "\n",
+            "Bye\n",
+            "\n",
+            "From carol@example.org\n",
+            "Received: from mx.example.org by mail.example.org;\n",
+            "\tWed, 3 Jan 2024 10:00:00 +0000\n",
+            "From: carol@example.org\n",
+            "Subject: Mbox third\n",
+            "Message-ID: <mbox-3@example.org>\n",
+            "\n",
+            "Undated\n",
+        ),
+    );
+    admin
+        .registry_update_setting(
+            Email {
+                import_root: Some(import_root.to_string_lossy().into_owned()),
+                ..Default::default()
+            },
+            &[Property::ImportRoot],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    let john = test
+        .create_user_account(
+            "admin@example.org",
+            "john.import@example.org",
+            "this is a very strong password",
+            &[],
+            "John Import",
+        )
+        .await;
+
+    // Paths outside the import root should be rejected
+    let task_id = admin
+        .schedule_import(&john, MessageImportFormat::Maildir, "../john", None)
+        .await;
+    test.wait_for_tasks_skip_failures().await;
+    let task = admin.import_task(task_id).await;
+    assert!(
+        matches!(&task.status, TaskStatus::Failed(status) if status.failure_reason.contains("Invalid import path")),
+        "unexpected status {:?}",
+        task.status
+    );
+    assert_eq!(task.messages_imported, 0);
+
+    // Import the Maildir, messages that cannot be parsed are reported
+    let task_id = admin
+        .schedule_import(&john, MessageImportFormat::Maildir, "john/Maildir", None)
+        .await;
+    test.wait_for_tasks_skip_failures().await;
+    let task = admin.import_task(task_id).await;
+    assert_eq!(task.messages_total, 5);
+    assert_eq!(task.messages_imported, 4);
+    assert_eq!(task.messages_skipped, 0);
+    assert_eq!(task.messages_failed, 1);
+    assert_eq!(task.import_errors.len(), 1);
+    assert!(
+        task.import_errors
+            .iter()
+            .any(|error| error.starts_with("1700000200.M3P1.mx")),
+        "unexpected errors {:?}",
+        task.import_errors
+    );
+    assert!(matches!(task.status, TaskStatus::Failed(_)));
+
+    let mut imap = john.imap_client().await;
+    imap.send("LIST \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders(
+            [("INBOX", [""]), ("Sent", [""]), ("Archive/2019", [""])],
+            false,
+        );
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("2 EXISTS");
+    imap.send("SEARCH SEEN").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("SEARCH 1");
+    imap.send("SELECT Sent").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("1 EXISTS");
+    imap.send("FETCH 1 (FLAGS)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\\Answered")
+        .assert_contains("\\Seen")
+        .assert_contains("Work");
+    imap.send("SELECT Archive/2019").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("1 EXISTS");
+    imap.send("FETCH 1 (FLAGS INTERNALDATE)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\\Flagged")
+        .assert_contains("01-Jan-2019");
+
+    // Importing the same Maildir again should skip duplicates
+    let task_id = admin
+        .schedule_import(&john, MessageImportFormat::Maildir, "john/Maildir", None)
+        .await;
+    test.wait_for_tasks_skip_failures().await;
+    let task = admin.import_task(task_id).await;
+    assert_eq!(task.messages_imported, 0);
+    assert_eq!(task.messages_skipped, 4);
+    assert_eq!(task.messages_failed, 1);
+    imap.send("STATUS INBOX (MESSAGES)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("MESSAGES 2");
+
+    // Import an mbox file into a target mailbox
+    admin
+        .schedule_import(
+            &john,
+            MessageImportFormat::Mbox,
+            "john/messages.mbox",
+            Some("Imported"),
+        )
+        .await;
+    test.wait_for_tasks().await;
+    imap.send("SELECT Imported").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("3 EXISTS");
+    imap.send("FETCH 1 (FLAGS INTERNALDATE BODY[TEXT])").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\\Seen")
+        .assert_contains("\\Flagged")
+        .assert_contains("01-Jan-2024")
+        .assert_contains(">From nested quotes")
+        .assert_not_contains(">From the mbox")
+        .assert_contains("From the mbox");
+    imap.send("FETCH 2 (FLAGS BODY[TEXT])").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_not_contains("\\Seen")
+        .assert_contains("Bye");
+
+    // Messages without a delivery time use the date of their Received header
+    imap.send("FETCH 3 (INTERNALDATE)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("03-Jan-2024");
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+
+    admin
+        .registry_update_setting(Email::default(), &[Property::ImportRoot])
+        .await;
+    admin.reload_settings().await;
+    admin.destroy_account(john).await;
+    test.cleanup().await;
+}
+
+fn message(id: u32, subject: &str) -> String {
+    format!(
+        concat!(
+            "From: bill@example.org\r\n",
+            "To: john.import@example.org\r\n",
+            "Subject: {}\r\n",
+            "Message-ID: <import-{}@example.org>\r\n",
+            "\r\n",
+            "This message was imported."
+        ),
+        subject, id
+    )
+}
+
+fn write_file(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+impl Account {
+    async fn schedule_import(
+        &self,
+        account: &Account,
+        format: MessageImportFormat,
+        path: &str,
+        target_mailbox: Option<&str>,
+    ) -> Id {
+        self.registry_create_object(Task::ImportMessages(TaskImportMessages {
+            account_id: account.id(),
+            format,
+            path: Some(path.to_string()),
+            target_mailbox: target_mailbox.map(|mailbox| mailbox.to_string()),
+            deduplicate: true,
+            status: TaskStatus::at(now() as i64),
+            ..Default::default()
+        }))
+        .await
+    }
+
+    async fn import_task(&self, id: Id) -> TaskImportMessages {
+        match self.registry_get::<Task>(id).await {
+            Task::ImportMessages(task) => task,
+            task => panic!("unexpected task {task:?}"),
+        }
+    }
+}
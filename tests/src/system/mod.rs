@@ -8,15 +8,28 @@ pub mod antispam;
 pub mod archiving;
 pub mod authentication;
 pub mod authorization;
+pub mod blob_dedup;
+pub mod change_log;
 pub mod crypto;
 pub mod delivery;
+pub mod delivery_dedup;
 pub mod directory;
+pub mod import;
+pub mod mta_sts;
 pub mod oidc;
+pub mod protocol_access;
+pub mod provision;
 pub mod purge;
 pub mod quota;
+pub mod quota_warning;
+pub mod reindex;
+pub mod revocation;
 pub mod security;
+pub mod session_limits;
+pub mod step_up;
 pub mod task;
 pub mod tenant;
+pub mod tenant_quota;
 
 use crate::utils::server::TestServerBuilder;
 use registry::schema::structs::{Expression, Imap, MtaStageAuth};
@@ -63,13 +76,26 @@ pub async fn system_tests() {
     oidc::test(&mut test).await;
     authorization::test(&mut test).await;
     tenant::test(&mut test).await;
+    tenant_quota::test(&mut test).await;
     security::test(&mut test).await;
+    step_up::test(&mut test).await;
+    session_limits::test(&mut test).await;
+    protocol_access::test(&test).await;
+    revocation::test(&mut test).await;
+    mta_sts::test(&mut test).await;
     quota::test(&mut test).await;
+    quota_warning::test(&mut test).await;
     purge::test(&mut test).await;
+    change_log::test(&mut test).await;
     delivery::test(&mut test).await;
+    delivery_dedup::test(&mut test).await;
+    blob_dedup::test(&mut test).await;
     crypto::test(&mut test).await;
     antispam::test(&mut test).await;
     archiving::test(&mut test).await;
+    reindex::test(&mut test).await;
+    import::test(&mut test).await;
+    provision::test(&mut test).await;
     task::test(&mut test).await;
 
     if test.is_reset() {
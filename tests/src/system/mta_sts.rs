@@ -0,0 +1,176 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer};
+use registry::schema::prelude::ObjectType;
+use reqwest::header;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running MTA-STS publishing tests...");
+    let admin = test.account("admin@example.org");
+    let domain_id_org = admin.find_or_create_domain("mtasts.org").await;
+    let domain_id_net = admin.find_or_create_domain("mtasts.net").await;
+
+    // Without overrides both domains publish the server-wide policy
+    let global = get_policy(admin, "mtasts.org").await;
+    assert_eq!(global["mode"], "testing", "{global}");
+    assert_eq!(global["maxAge"], 604800, "{global}");
+    assert_eq!(global["warnings"], json!([]), "{global}");
+    assert_eq!(get_policy(admin, "mtasts.net").await["id"], global["id"]);
+    let hostname = global["mxHosts"][0].as_str().unwrap().to_string();
+
+    // Enforce the policy on one domain
+    let org = update_policy(
+        admin,
+        "mtasts.org",
+        json!({
+            "mode": "enforce",
+            "mxHosts": [&hostname],
+            "maxAge": 86400
+        }),
+    )
+    .await;
+    assert_ne!(org["id"], global["id"], "{org}");
+    assert_eq!(
+        org["txtRecord"],
+        format!("v=STSv1; id={}", org["id"].as_str().unwrap()),
+        "{org}"
+    );
+    assert_eq!(org["warnings"], json!([]), "{org}");
+    assert_eq!(get_policy(admin, "mtasts.org").await["id"], org["id"]);
+
+    // MX patterns that do not match the configured hostnames are reported
+    let net = update_policy(
+        admin,
+        "mtasts.net",
+        json!({
+            "mxHosts": ["mx.other-provider.net", "*.mtasts.net"]
+        }),
+    )
+    .await;
+    assert_ne!(net["id"], global["id"], "{net}");
+    assert_ne!(net["id"], org["id"], "{net}");
+    assert_eq!(net["mode"], "testing", "{net}");
+    assert_eq!(net["warnings"].as_array().unwrap().len(), 2, "{net}");
+    assert!(
+        net["warnings"][0]
+            .as_str()
+            .unwrap()
+            .contains("*.mtasts.net"),
+        "{net}"
+    );
+
+    // Each domain is served its own policy
+    let policy = fetch_policy(admin, "mtasts.org").await;
+    assert_eq!(
+        policy,
+        format!("version: STSv1\r\nmode: enforce\r\nmax_age: 86400\r\nmx: {hostname}\r\n")
+    );
+    let policy = fetch_policy(admin, "mtasts.net").await;
+    assert_eq!(
+        policy,
+        concat!(
+            "version: STSv1\r\nmode: testing\r\nmax_age: 604800\r\n",
+            "mx: *.mtasts.net\r\nmx: mx.other-provider.net\r\n"
+        )
+    );
+
+    // Updating the policy changes its id
+    let updated = update_policy(
+        admin,
+        "mtasts.org",
+        json!({
+            "mode": "enforce",
+            "mxHosts": [&hostname],
+            "maxAge": 172800
+        }),
+    )
+    .await;
+    assert_ne!(updated["id"], org["id"], "{updated}");
+    assert!(
+        fetch_policy(admin, "mtasts.org")
+            .await
+            .contains("max_age: 172800\r\n")
+    );
+    assert_eq!(get_policy(admin, "mtasts.net").await["id"], net["id"]);
+
+    // Invalid requests are rejected
+    let url = format!("{}/api/mta-sts/mtasts.org", admin.base_url());
+    for body in [
+        json!({"mxHosts": ["mx.*.mtasts.org"]}),
+        json!({"maxAge": 0}),
+        json!({"mode": "invalid"}),
+    ] {
+        let response = admin
+            .http_post_raw(&url, "application/json", body.to_string())
+            .await;
+        assert_eq!(response.status, 400, "{body}: {}", response.text());
+    }
+    let response = admin
+        .http_get_raw(
+            &format!("{}/api/mta-sts/unknown-domain.org", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 404, "{}", response.text());
+
+    // Removing the overrides restores the server-wide policy
+    for domain in ["mtasts.org", "mtasts.net"] {
+        let policy = update_policy(admin, domain, json!({})).await;
+        assert_eq!(policy["id"], global["id"], "{policy}");
+    }
+
+    // Remove test data
+    admin
+        .registry_destroy(ObjectType::Domain, [domain_id_org, domain_id_net])
+        .await
+        .assert_destroyed(&[domain_id_org, domain_id_net]);
+    test.cleanup().await;
+}
+
+async fn get_policy(admin: &Account, domain: &str) -> Value {
+    let response = admin
+        .http_get_raw(&format!("{}/api/mta-sts/{domain}", admin.base_url()), None)
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
+
+async fn update_policy(admin: &Account, domain: &str, policy: Value) -> Value {
+    let response = admin
+        .http_post_raw(
+            &format!("{}/api/mta-sts/{domain}", admin.base_url()),
+            "application/json",
+            policy.to_string(),
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
+
+async fn fetch_policy(admin: &Account, domain: &str) -> String {
+    let response = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_millis(5000))
+        .build()
+        .unwrap()
+        .get(format!("{}/.well-known/mta-sts.txt", admin.base_url()))
+        .header(header::HOST, format!("mta-sts.{domain}"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok()),
+        Some("text/plain")
+    );
+    response.text().await.unwrap()
+}
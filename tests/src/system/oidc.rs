@@ -855,6 +855,148 @@ pub async fn test(test: &mut TestServer) {
         }
     );
 
+    // ------------------------
+    // Device authorization through the management API
+    // ------------------------
+
+    // Pending authorizations are looked up by their user code, in any format
+    let device_code_params = AHashMap::from_iter([
+        ("client_id".to_string(), managed_id.to_string()),
+        ("scope".to_string(), PROFILE_SCOPE.to_string()),
+    ]);
+    let device_response: DeviceAuthResponse =
+        post(&metadata.device_authorization_endpoint, &device_code_params).await;
+    let device_url = format!(
+        "{}/api/oauth/device/{}",
+        admin.base_url(),
+        device_response.user_code.to_lowercase().replace('-', "")
+    );
+    let pending = admin.http_get_raw(&device_url, None).await.json().unwrap();
+    assert_eq!(pending[0]["userCode"], device_response.user_code.as_str());
+    assert_eq!(pending[0]["clientId"], managed_id);
+    assert_eq!(pending[0]["scope"], PROFILE_SCOPE);
+    assert_eq!(user.http_get_raw(&device_url, None).await.status, 403);
+
+    // Deny the authorization
+    assert_eq!(
+        admin
+            .http_post_raw(&device_url, "application/json", r#"{"action":"deny"}"#)
+            .await
+            .status,
+        200
+    );
+    let token_params = AHashMap::from_iter([
+        ("client_id".to_string(), managed_id.to_string()),
+        (
+            "grant_type".to_string(),
+            "urn:ietf:params:oauth:grant-type:device_code".to_string(),
+        ),
+        (
+            "device_code".to_string(),
+            device_response.device_code.to_string(),
+        ),
+    ]);
+    assert_eq!(
+        post::<TokenResponse>(&metadata.token_endpoint, &token_params).await,
+        TokenResponse::Error {
+            error: ErrorType::AccessDenied
+        }
+    );
+    assert_eq!(
+        admin.http_get_raw(&device_url, None).await.json().unwrap(),
+        serde_json::json!([])
+    );
+
+    // Approve the authorization on behalf of the user
+    let device_response: DeviceAuthResponse =
+        post(&metadata.device_authorization_endpoint, &device_code_params).await;
+    let device_url = format!(
+        "{}/api/oauth/device/{}",
+        admin.base_url(),
+        device_response.user_code
+    );
+    let approved = admin
+        .http_post_raw(
+            &device_url,
+            "application/json",
+            r#"{"action":"approve","accountName":"user@example.org"}"#,
+        )
+        .await
+        .json()
+        .unwrap();
+    assert_eq!(approved["accountId"], user_id.to_string());
+    assert_eq!(
+        admin
+            .http_post_raw(&device_url, "application/json", r#"{"action":"deny"}"#)
+            .await
+            .status,
+        404
+    );
+    let token_params = AHashMap::from_iter([
+        ("client_id".to_string(), managed_id.to_string()),
+        (
+            "grant_type".to_string(),
+            "urn:ietf:params:oauth:grant-type:device_code".to_string(),
+        ),
+        (
+            "device_code".to_string(),
+            device_response.device_code.to_string(),
+        ),
+    ]);
+    let (token, refresh_token, _) =
+        unwrap_token_response(post(&metadata.token_endpoint, &token_params).await);
+    let refresh_token = refresh_token.unwrap();
+
+    // Resource servers introspect tokens using their client credentials
+    for token in [&token, &refresh_token] {
+        let introspect: OAuthIntrospect = post_form_basic(
+            &metadata.introspection_endpoint,
+            managed_id,
+            managed_secret,
+            &AHashMap::from_iter([("token".to_string(), token.to_string())]),
+        )
+        .await;
+        assert!(introspect.active);
+        assert_eq!(introspect.username.unwrap(), "user@example.org");
+        assert_eq!(introspect.sub.unwrap(), user_id.to_string());
+        assert_eq!(introspect.scope.unwrap(), PROFILE_SCOPE);
+        assert!(introspect.exp.is_some());
+    }
+    assert_eq!(
+        post::<serde_json::Value>(
+            &metadata.introspection_endpoint,
+            &AHashMap::from_iter([
+                ("client_id".to_string(), managed_id.to_string()),
+                ("client_secret".to_string(), "wrong-secret".to_string()),
+                ("token".to_string(), refresh_token.to_string()),
+            ]),
+        )
+        .await["error"],
+        "invalid_client"
+    );
+
+    // Revoked tokens are no longer active
+    assert_eq!(
+        admin
+            .http_delete_raw(&format!(
+                "{}/api/sessions/{}?revoke=true",
+                admin.base_url(),
+                user.id_string()
+            ))
+            .await
+            .status,
+        200
+    );
+    let introspect: OAuthIntrospect = post_form_basic(
+        &metadata.introspection_endpoint,
+        managed_id,
+        managed_secret,
+        &AHashMap::from_iter([("token".to_string(), refresh_token)]),
+    )
+    .await;
+    assert!(!introspect.active);
+    assert!(introspect.username.is_none());
+
     // Clean up
     admin.registry_destroy_all(ObjectType::OAuthClient).await;
     admin.destroy_account(user).await;
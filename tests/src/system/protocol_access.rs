@@ -0,0 +1,200 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    imap::{AssertResult, ImapConnection, Type},
+    pop3::{self, Pop3Connection},
+    server::TestServer,
+    smtp::SmtpConnection,
+};
+use base64::{Engine, engine::general_purpose};
+use hyper::StatusCode;
+use imap_proto::ResponseType;
+use registry::schema::prelude::ObjectType;
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use trc::{
+    AuthEvent, Collector, EventType, Key,
+    ipc::subscriber::{EventBatch, SubscriberBuilder},
+};
+
+const USER: &str = "jmaponly@example.org";
+const PASS: &str = "this is a very strong password";
+
+pub async fn test(test: &TestServer) {
+    println!("Running protocol access tests...");
+    let account = test
+        .create_user_account("admin@example.org", USER, PASS, &[], "JMAP Only")
+        .await;
+    let admin = test.account("admin@example.org");
+    let subscriber_id = "protocol-access-test".to_string();
+    let (_tx, mut rx) = SubscriberBuilder::new(subscriber_id.clone())
+        .set_interests([EventType::Auth(AuthEvent::ProtocolDisabled)])
+        .register();
+
+    // All protocols are enabled by default
+    imap_login().await;
+    pop3_login().await;
+
+    // Restrict the account to JMAP
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            account.id(),
+            json!({
+                "enabledProtocols": {
+                    "jmap": true
+                }
+            }),
+        )
+        .await;
+    let account_id = account.id().document_id() as u64;
+    account.jmap_client().await;
+
+    // IMAP logins are rejected with an authorization failure
+    let mut imap = ImapConnection::connect(b"_z ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.send(&format!(
+        "AUTHENTICATE PLAIN {}",
+        general_purpose::STANDARD.encode(format!("\0{USER}\0{PASS}"))
+    ))
+    .await;
+    imap.assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("AUTHORIZATIONFAILED")
+        .assert_contains("IMAP access is disabled");
+    assert_eq!(received(&mut rx).await, vec![account_id]);
+
+    // POP3 logins are rejected with a permanent system error
+    let mut pop3 = Pop3Connection::connect().await;
+    pop3.send(&format!(
+        "AUTH PLAIN {}",
+        general_purpose::STANDARD.encode(format!("\0{USER}\0{PASS}"))
+    ))
+    .await;
+    let response = pop3.assert_read(pop3::ResponseType::Err).await;
+    assert!(
+        response
+            .last()
+            .unwrap()
+            .starts_with("-ERR [SYS/PERM] POP3 access is disabled"),
+        "{response:?}"
+    );
+    assert_eq!(received(&mut rx).await, vec![account_id]);
+
+    // SMTP submission is rejected as well
+    let mut smtp = SmtpConnection::connect().await;
+    smtp.send(&format!(
+        "AUTH PLAIN {}",
+        general_purpose::STANDARD.encode(format!("\0{USER}\0{PASS}"))
+    ))
+    .await;
+    let response = smtp.read(1, 5).await;
+    assert!(
+        response.last().unwrap().starts_with("535 5.7.8 SMTP"),
+        "{response:?}"
+    );
+    assert_eq!(received(&mut rx).await, vec![account_id]);
+
+    // DAV requests are rejected unless the protocol serving them is enabled
+    let dav = account.webdav_client();
+    for path in ["/dav/cal/", "/dav/card/", "/dav/file/", "/dav/pal/"] {
+        assert_eq!(
+            dav.request("PROPFIND", path, "").await.status,
+            StatusCode::UNAUTHORIZED,
+            "{path}"
+        );
+    }
+    assert_eq!(received(&mut rx).await.len(), 4);
+
+    // Restrict the account to IMAP and CardDAV
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            account.id(),
+            json!({
+                "enabledProtocols": {
+                    "imap": true,
+                    "carddav": true
+                }
+            }),
+        )
+        .await;
+    imap_login().await;
+    assert_eq!(jmap_session().await, StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        dav.request("PROPFIND", "/dav/cal/", "").await.status,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_ne!(
+        dav.request("PROPFIND", "/dav/card/", "").await.status,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_ne!(
+        dav.request("PROPFIND", "/dav/pal/", "").await.status,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(received(&mut rx).await, vec![account_id, account_id]);
+
+    // Clearing the list enables all protocols again
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            account.id(),
+            json!({
+                "enabledProtocols": {}
+            }),
+        )
+        .await;
+    imap_login().await;
+    pop3_login().await;
+    assert_eq!(jmap_session().await, StatusCode::OK);
+    assert_eq!(received(&mut rx).await, Vec::<u64>::new());
+
+    // Remove test data
+    Collector::remove_subscriber(subscriber_id);
+    Collector::reload();
+    admin.destroy_account(account).await;
+    test.cleanup().await;
+}
+
+async fn imap_login() {
+    let mut imap = ImapConnection::connect(b"_x ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(USER, PASS).await;
+}
+
+async fn pop3_login() {
+    let mut pop3 = Pop3Connection::connect().await;
+    pop3.authenticate(USER, PASS).await;
+}
+
+async fn jmap_session() -> StatusCode {
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+        .get("https://127.0.0.1:8899/jmap/session")
+        .basic_auth(USER, Some(PASS))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+async fn received(rx: &mut mpsc::Receiver<EventBatch>) -> Vec<u64> {
+    let mut account_ids = Vec::new();
+    while let Ok(Some(batch)) = tokio::time::timeout(Duration::from_millis(500), rx.recv()).await {
+        for event in batch {
+            if event.inner.typ == EventType::Auth(AuthEvent::ProtocolDisabled) {
+                account_ids.extend(event.value_as_uint(Key::AccountId));
+            }
+        }
+    }
+    account_ids
+}
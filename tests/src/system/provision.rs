@@ -0,0 +1,197 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    account::Account,
+    imap::{AssertResult, Type},
+    server::TestServer,
+};
+use imap_proto::ResponseType;
+use registry::{
+    schema::{
+        prelude::{ObjectType, Property},
+        structs::{CertificateManagement, DkimManagement, DnsManagement, Domain, Email},
+    },
+    types::map::Map,
+};
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running account provisioning tests...");
+    let admin = test.account("admin@example.org");
+
+    // Global template, applies to domains without their own template
+    admin
+        .registry_update_setting(
+            Email {
+                provision_folders: Map::new(vec![
+                    "Receipts".to_string(),
+                    "Archive/$domain".to_string(),
+                ]),
+                provision_subscribe: true,
+                provision_sieve_script: Some(
+                    concat!(
+                        "require \"fileinto\";\n",
+                        "if header :contains \"subject\" \"receipt\" {\n",
+                        "    fileinto \"Receipts\";\n",
+                        "}\n"
+                    )
+                    .to_string(),
+                ),
+                provision_signature: Some("-- \n$name at $domain".to_string()),
+                ..Default::default()
+            },
+            &[
+                Property::ProvisionFolders,
+                Property::ProvisionSubscribe,
+                Property::ProvisionSieveScript,
+                Property::ProvisionSignature,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    // Domain template, replaces the global template
+    let domain_id = admin
+        .registry_create_object(Domain {
+            is_enabled: true,
+            name: "provision.org".to_string(),
+            certificate_management: CertificateManagement::Manual,
+            dns_management: DnsManagement::Manual,
+            dkim_management: DkimManagement::Manual,
+            provision_folders: Map::new(vec!["Rechnungen".to_string()]),
+            provision_subscribe: false,
+            provision_signature: Some("Mit freundlichen Grüßen, $name".to_string()),
+            ..Default::default()
+        })
+        .await;
+
+    let john = test
+        .create_user_account(
+            "admin@example.org",
+            "john.provision@example.org",
+            "this is a very strong password",
+            &[],
+            "John Provision",
+        )
+        .await;
+    let jane = test
+        .create_user_account(
+            "admin@example.org",
+            "jane@provision.org",
+            "this is a very strong password",
+            &[],
+            "Jane Provision",
+        )
+        .await;
+
+    // First login applies the global template
+    let mut imap = john.imap_client().await;
+    imap.send("LIST \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders(
+            [
+                ("INBOX", [""]),
+                ("Receipts", [""]),
+                ("Archive", [""]),
+                ("Archive/example.org", [""]),
+            ],
+            false,
+        );
+    imap.send("LSUB \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_contains("\"Receipts\"")
+        .assert_contains("\"Archive/example.org\"");
+    assert_signature(&john, "-- \njohn.provision at example.org").await;
+    assert_active_script(&john, Some("default")).await;
+
+    // First login applies the domain template
+    let mut imap_jane = jane.imap_client().await;
+    imap_jane.send("LIST \"\" \"*\"").await;
+    imap_jane
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders([("INBOX", [""]), ("Rechnungen", [""])], false)
+        .assert_not_contains("\"Receipts\"");
+    imap_jane.send("LSUB \"\" \"*\"").await;
+    imap_jane
+        .assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_not_contains("\"Rechnungen\"");
+    assert_signature(&jane, "Mit freundlichen Grüßen, jane").await;
+    assert_active_script(&jane, None).await;
+    imap_jane.send("LOGOUT").await;
+    imap_jane
+        .assert_read(Type::Untagged, ResponseType::Bye)
+        .await;
+
+    // Provisioning is applied only once, deleted folders are not recreated
+    imap.send("DELETE \"Receipts\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+    test.server
+        .inner
+        .cache
+        .messages
+        .remove(&john.id().document_id());
+    let mut imap = john.imap_client().await;
+    imap.send("LIST \"\" \"*\"").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok)
+        .await
+        .assert_folders([("INBOX", [""]), ("Archive/example.org", [""])], false)
+        .assert_not_contains("\"Receipts\"");
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+
+    admin
+        .registry_update_setting(
+            Email::default(),
+            &[
+                Property::ProvisionFolders,
+                Property::ProvisionSieveScript,
+                Property::ProvisionSignature,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+    admin.destroy_account(john).await;
+    admin.destroy_account(jane).await;
+    admin
+        .registry_destroy(ObjectType::Domain, [domain_id])
+        .await
+        .assert_destroyed(&[domain_id]);
+    test.cleanup().await;
+}
+
+async fn assert_signature(account: &Account, expected: &str) {
+    let response = account
+        .jmap_get("Identity", ["textSignature"], Vec::<String>::new())
+        .await;
+    let identities = response.list();
+    assert!(!identities.is_empty());
+    for identity in identities {
+        assert_eq!(
+            identity["textSignature"].as_str(),
+            Some(expected),
+            "{identity}"
+        );
+    }
+}
+
+async fn assert_active_script(account: &Account, expected: Option<&str>) {
+    let response = account
+        .jmap_get("SieveScript", ["name", "isActive"], Vec::<String>::new())
+        .await;
+    let active = response
+        .list()
+        .iter()
+        .filter(|script| script["isActive"].as_bool() == Some(true))
+        .filter_map(|script| script["name"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(active, expected.into_iter().collect::<Vec<_>>());
+}
@@ -7,7 +7,7 @@
 use crate::utils::{
     account::Account, http::HttpRequest, jmap::JmapUtils, server::TestServer, smtp::SmtpConnection,
 };
-use email::{cache::MessageCacheFetch, mailbox::INBOX_ID};
+use email::{cache::MessageCacheFetch, mailbox::INBOX_ID, message::delete::EmailDeletion};
 use jmap::blob::upload::DISABLE_UPLOAD_QUOTA;
 use jmap_client::{
     core::set::{SetErrorType, SetObject},
@@ -15,16 +15,21 @@ use jmap_client::{
 };
 use registry::{
     schema::{
-        enums::{Permission, StorageQuota, TaskAccountMaintenanceType},
+        enums::{Permission, StorageQuota, TaskAccountMaintenanceType, TaskStoreMaintenanceType},
         prelude::{ObjectType, Property},
         structs::{
             self, Credential, Jmap, PasswordCredential, PermissionsList, Task,
-            TaskAccountMaintenance, TaskStatus, UserAccount,
+            TaskAccountMaintenance, TaskStatus, TaskStoreMaintenance, UserAccount,
         },
     },
     types::{EnumImpl, list::List, map::Map},
 };
 use serde_json::json;
+use std::str::FromStr;
+use store::{
+    roaring::RoaringBitmap,
+    write::{BatchBuilder, ValueClass},
+};
 use types::id::Id;
 use utils::map::vec_map::VecMap;
 
@@ -358,6 +363,123 @@ pub async fn test(test: &mut TestServer) {
         prev_quota
     );
 
+    // Simulate dropped counter updates and verify the counters converge
+    for (drift, maintenance_task) in [
+        (
+            1000,
+            Task::AccountMaintenance(TaskAccountMaintenance {
+                account_id,
+                maintenance_type: TaskAccountMaintenanceType::RecalculateQuota,
+                status: TaskStatus::now(),
+            }),
+        ),
+        (
+            -150,
+            Task::StoreMaintenance(TaskStoreMaintenance {
+                maintenance_type: TaskStoreMaintenanceType::ReconcileQuotas,
+                status: TaskStatus::now(),
+                shard_index: None,
+            }),
+        ),
+    ] {
+        let mut batch = BatchBuilder::new();
+        batch
+            .with_account_id(account.id().document_id())
+            .add(ValueClass::Quota, drift);
+        test.server.store().write(batch.build_all()).await.unwrap();
+        assert_eq!(
+            test.server
+                .get_used_quota_account(account.id().document_id())
+                .await
+                .unwrap(),
+            prev_quota + drift
+        );
+
+        admin.registry_create_object(maintenance_task).await;
+        test.wait_for_tasks().await;
+        assert_eq!(
+            test.server
+                .get_used_quota_account(account.id().document_id())
+                .await
+                .unwrap(),
+            prev_quota
+        );
+    }
+    assert_eq!(
+        test.server
+            .get_used_quota_mailbox(account.id().document_id(), INBOX_ID)
+            .await
+            .unwrap(),
+        prev_quota
+    );
+
+    // Simulate a crash that loses the counter updates of a deletion
+    let deleted_id = Id::from_str(&message_ids.pop().unwrap()).unwrap();
+    let mut batch = BatchBuilder::new();
+    test.server
+        .emails_delete(
+            account.id().document_id(),
+            None,
+            &mut batch,
+            RoaringBitmap::from_iter([deleted_id.document_id()]),
+        )
+        .await
+        .unwrap();
+    batch.discard_counters();
+    test.server.store().write(batch.build_all()).await.unwrap();
+    assert_eq!(
+        test.server
+            .get_used_quota_account(account.id().document_id())
+            .await
+            .unwrap(),
+        prev_quota
+    );
+    assert_eq!(
+        test.server
+            .get_used_quota_mailbox(account.id().document_id(), INBOX_ID)
+            .await
+            .unwrap(),
+        prev_quota
+    );
+
+    // Only administrators can force a reconciliation
+    let reconcile_url = format!(
+        "{}/api/store/quota/{}",
+        admin.base_url(),
+        account.id_string()
+    );
+    assert_eq!(
+        account
+            .http_post_raw(&reconcile_url, "application/json", "")
+            .await
+            .status,
+        403
+    );
+
+    // Forcing a reconciliation restores the account and mailbox counters
+    let response = admin
+        .http_post_raw(&reconcile_url, "application/json", "")
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let result = response.json().unwrap();
+    let drift = result["drift"].as_i64().unwrap();
+    assert!(drift < 0, "{result}");
+    assert_eq!(result["used"].as_i64().unwrap(), prev_quota + drift);
+    assert_eq!(
+        test.server
+            .get_used_quota_account(account.id().document_id())
+            .await
+            .unwrap(),
+        prev_quota + drift
+    );
+    assert_eq!(
+        test.server
+            .get_used_quota_mailbox(account.id().document_id(), INBOX_ID)
+            .await
+            .unwrap(),
+        prev_quota + drift
+    );
+
     // Delete messages and check available quota
     test.wait_for_tasks().await;
     for message_id in message_ids {
@@ -423,6 +545,31 @@ pub async fn test(test: &mut TestServer) {
             .await,
     );
 
+    // Originals are not destroyed when the copy fails
+    let mut request = client.build();
+    request
+        .copy_email(other_account.id_string())
+        .on_success_destroy_original(true)
+        .create(&other_message_ids[2])
+        .mailbox_id(&inbox_id, true);
+    assert_over_quota(
+        request
+            .send()
+            .await
+            .unwrap()
+            .method_response_by_pos(0)
+            .unwrap_copy_email()
+            .unwrap()
+            .created(&other_message_ids[2]),
+    );
+    assert!(
+        other_client
+            .email_get(&other_message_ids[2], None::<Vec<_>>)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
     // Delete messages and check available quota
     test.wait_for_tasks().await;
     for message_id in message_ids {
@@ -490,7 +637,7 @@ pub async fn test(test: &mut TestServer) {
     test.cleanup().await;
 }
 
-fn assert_over_quota<T: std::fmt::Debug>(result: Result<T, jmap_client::Error>) {
+pub fn assert_over_quota<T: std::fmt::Debug>(result: Result<T, jmap_client::Error>) {
     match result {
         Ok(result) => panic!("Expected error, got {:?}", result),
         Err(jmap_client::Error::Set(err)) if err.error() == &SetErrorType::OverQuota => (),
@@ -498,7 +645,7 @@ fn assert_over_quota<T: std::fmt::Debug>(result: Result<T, jmap_client::Error>)
     }
 }
 
-fn create_message_with_size(from: &str, to: &str, subject: &str, size: usize) -> Vec<u8> {
+pub fn create_message_with_size(from: &str, to: &str, subject: &str, size: usize) -> Vec<u8> {
     let mut message = format!(
         "From: {}\r\nTo: {}\r\nSubject: {}\r\n\r\n",
         from, to, subject
@@ -0,0 +1,212 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    system::quota::create_message_with_size,
+    utils::{account::Account, server::TestServer},
+};
+use common::{auth::BuildAccessToken, ipc::PushNotification};
+use email::mailbox::INBOX_ID;
+use jmap_client::client::Client;
+use registry::{
+    schema::{
+        enums::{Locale, MessageTemplateId, StorageQuota},
+        prelude::{ObjectType, Property},
+        structs::{self, Credential, Email, MessageTemplate, PasswordCredential, UserAccount},
+    },
+    types::{list::List, map::Map},
+};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use types::{id::Id, type_state::DataType};
+use utils::map::{bitmap::Bitmap, vec_map::VecMap};
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running quota warning tests...");
+    let admin = test.account("admin@example.org");
+    let domain_id = admin.find_or_create_domain("example.org").await;
+
+    admin
+        .registry_update_setting(
+            Email {
+                quota_warning_thresholds: Map::new(vec![80, 95]),
+                quota_warning_notify: true,
+                ..Default::default()
+            },
+            &[
+                Property::QuotaWarningThresholds,
+                Property::QuotaWarningNotify,
+                Property::QuotaWarningInterval,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    let account_id = admin
+        .registry_create_object(structs::Account::User(UserAccount {
+            name: "quota.warning".to_string(),
+            domain_id,
+            credentials: List::from_iter([Credential::Password(PasswordCredential {
+                secret: "this is a very strong password".to_string(),
+                ..Default::default()
+            })]),
+            quotas: VecMap::from_iter([(StorageQuota::MaxDiskQuota, 100_000)]),
+            locale: Locale::EnUS,
+            ..Default::default()
+        }))
+        .await;
+    let account = Account::new(
+        "quota.warning@example.org",
+        "this is a very strong password",
+        &[],
+        "Quota Warning",
+        account_id,
+    );
+    let client = account.jmap_client().await;
+    let inbox_id = Id::new(INBOX_ID as u64).to_string();
+
+    // Subscribe to quota state changes
+    let access_token = test
+        .server
+        .access_token(account.id().document_id())
+        .await
+        .unwrap()
+        .build();
+    let mut push_rx = test
+        .server
+        .subscribe_push_manager(&access_token, Bitmap::from_iter([DataType::Quota]))
+        .await
+        .unwrap();
+
+    // Usage below the lowest threshold does not trigger a warning
+    import_message(&client, &inbox_id, 70_000).await;
+    test.wait_for_tasks().await;
+    assert_no_quota_change(&mut push_rx).await;
+    assert_eq!(warnings(&account).await, Vec::<String>::new());
+
+    // Crossing 80% warns once
+    import_message(&client, &inbox_id, 15_000).await;
+    assert_quota_change(&mut push_rx).await;
+    test.wait_for_tasks().await;
+    assert_eq!(warnings(&account).await, vec!["Your mailbox is 80% full"]);
+
+    // Templates configured for the recipient's locale replace the built-in text
+    let template_id = admin
+        .registry_create_object(MessageTemplate {
+            template_id: MessageTemplateId::QuotaWarning,
+            locale: Some(Locale::EnUS),
+            subject: "Your mailbox is {{percent}}% full, please clean up".into(),
+            text_body: "You are using {{used}} of {{limit}}.".into(),
+            html_body: None,
+            member_tenant_id: None,
+            domain_id: Some(domain_id),
+        })
+        .await;
+    admin.reload_settings().await;
+
+    // Crossing 95% warns once more
+    let message_id = import_message(&client, &inbox_id, 10_000).await;
+    assert_quota_change(&mut push_rx).await;
+    test.wait_for_tasks().await;
+    assert_eq!(
+        warnings(&account).await,
+        vec![
+            "Your mailbox is 80% full",
+            "Your mailbox is 95% full, please clean up"
+        ]
+    );
+    assert_no_quota_change(&mut push_rx).await;
+
+    // Dropping below and crossing 95% again refreshes clients
+    // but does not deliver another warning within the interval
+    client.email_destroy(&message_id).await.unwrap();
+    test.wait_for_tasks().await;
+    import_message(&client, &inbox_id, 10_000).await;
+    assert_quota_change(&mut push_rx).await;
+    test.wait_for_tasks().await;
+    assert_eq!(
+        warnings(&account).await,
+        vec![
+            "Your mailbox is 80% full",
+            "Your mailbox is 95% full, please clean up"
+        ]
+    );
+
+    // Remove test data
+    admin
+        .registry_update_setting(
+            Email::default(),
+            &[
+                Property::QuotaWarningThresholds,
+                Property::QuotaWarningNotify,
+                Property::QuotaWarningInterval,
+            ],
+        )
+        .await;
+    admin
+        .registry_destroy(ObjectType::MessageTemplate, [template_id])
+        .await
+        .assert_destroyed(&[template_id]);
+    admin.reload_settings().await;
+    test.destroy_all_mailboxes(&account).await;
+    admin
+        .registry_destroy(ObjectType::Account, [account_id])
+        .await
+        .assert_destroyed(&[account_id]);
+    test.cleanup().await;
+}
+
+async fn import_message(client: &Client, inbox_id: &str, size: usize) -> String {
+    client
+        .email_import(
+            create_message_with_size(
+                "jane@example.org",
+                "quota.warning@example.org",
+                "Quota filler",
+                size,
+            ),
+            vec![inbox_id],
+            None::<Vec<String>>,
+            None,
+        )
+        .await
+        .unwrap()
+        .take_id()
+}
+
+async fn warnings(account: &Account) -> Vec<String> {
+    let response = account
+        .jmap_get("Email", ["subject"], Vec::<String>::new())
+        .await;
+    let mut subjects = response
+        .list()
+        .iter()
+        .filter_map(|email| email["subject"].as_str())
+        .filter(|subject| subject.starts_with("Your mailbox is"))
+        .map(|subject| subject.to_string())
+        .collect::<Vec<_>>();
+    subjects.sort();
+    subjects
+}
+
+async fn assert_quota_change(push_rx: &mut mpsc::Receiver<PushNotification>) {
+    match tokio::time::timeout(Duration::from_secs(5), push_rx.recv()).await {
+        Ok(Some(PushNotification::StateChange(state_change))) => {
+            assert!(
+                state_change.types.contains(DataType::Quota),
+                "{state_change:?}"
+            );
+        }
+        result => panic!("Expected quota state change, got {result:?}"),
+    }
+}
+
+async fn assert_no_quota_change(push_rx: &mut mpsc::Receiver<PushNotification>) {
+    if let Ok(notification) = tokio::time::timeout(Duration::from_millis(500), push_rx.recv()).await
+    {
+        panic!("Unexpected push notification: {notification:?}");
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer};
+use jmap_client::{client::Client, email, mailbox::Role};
+use registry::{
+    schema::structs::{Task, TaskReindexAccount, TaskStatus},
+    types::datetime::UTCDateTime,
+};
+use store::{
+    search::SearchQuery,
+    write::{SearchIndex, now},
+};
+use types::id::Id;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running scoped reindex tests...");
+    let admin = test.account("admin@example.org");
+
+    // Create test accounts
+    let john = test
+        .create_user_account(
+            "admin@example.org",
+            "john.reindex@example.org",
+            "this is a very strong password",
+            &[],
+            "John Reindex",
+        )
+        .await;
+    let jane = test
+        .create_user_account(
+            "admin@example.org",
+            "jane.reindex@example.org",
+            "this is a very strong password",
+            &[],
+            "Jane Reindex",
+        )
+        .await;
+    let john_client = john.jmap_client().await;
+    let jane_client = jane.jmap_client().await;
+
+    // Import test messages
+    let john_inbox = john_client
+        .mailbox_create("Reindex Inbox", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    let john_reports = john_client
+        .mailbox_create("Reindex Reports", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    let jane_inbox = jane_client
+        .mailbox_create("Reindex Inbox", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id();
+    for (client, mailbox_id, subject, received_at) in [
+        (&john_client, &john_inbox, "inbox", 1704067200),
+        (&john_client, &john_reports, "old report", 1577836800),
+        (&john_client, &john_reports, "new report", 1704067200),
+        (&jane_client, &jane_inbox, "inbox", 1704067200),
+    ] {
+        client
+            .email_import(
+                format!(
+                    concat!(
+                        "From: bill@example.org\r\n",
+                        "To: john.reindex@example.org\r\n",
+                        "Subject: Zymurgy {}\r\n",
+                        "\r\n",
+                        "The zymurgy report is attached."
+                    ),
+                    subject
+                )
+                .into_bytes(),
+                [mailbox_id],
+                None::<Vec<&str>>,
+                Some(received_at),
+            )
+            .await
+            .unwrap();
+    }
+    test.wait_for_tasks().await;
+    assert_search(
+        &john_client,
+        &["Zymurgy inbox", "Zymurgy new report", "Zymurgy old report"],
+    )
+    .await;
+    assert_search(&jane_client, &["Zymurgy inbox"]).await;
+
+    // Remove all index entries to simulate a stale index
+    for account in [&john, &jane] {
+        test.server
+            .search_store()
+            .unindex(
+                SearchQuery::new(SearchIndex::Email).with_account_id(account.id().document_id()),
+            )
+            .await
+            .unwrap();
+    }
+    assert_search(&john_client, &[]).await;
+    assert_search(&jane_client, &[]).await;
+
+    // Reindex the messages in one mailbox received after a date
+    admin
+        .schedule_reindex(&john, &john_reports, Some(1672531200))
+        .await;
+    test.wait_for_tasks().await;
+    assert_search(&john_client, &["Zymurgy new report"]).await;
+    assert_search(&jane_client, &[]).await;
+
+    // Reindex the full mailbox, other mailboxes and accounts should be untouched
+    admin.schedule_reindex(&john, &john_reports, None).await;
+    test.wait_for_tasks().await;
+    assert_search(&john_client, &["Zymurgy new report", "Zymurgy old report"]).await;
+    assert_search(&jane_client, &[]).await;
+
+    // Reindexing a mailbox twice should not duplicate entries
+    admin.schedule_reindex(&john, &john_reports, None).await;
+    test.wait_for_tasks().await;
+    assert_search(&john_client, &["Zymurgy new report", "Zymurgy old report"]).await;
+
+    for account in [john, jane] {
+        admin.destroy_account(account).await;
+    }
+    test.cleanup().await;
+}
+
+async fn assert_search(client: &Client, expected: &[&str]) {
+    let ids = client
+        .email_query(email::query::Filter::text("zymurgy").into(), None::<Vec<_>>)
+        .await
+        .unwrap()
+        .take_ids();
+    let mut subjects = Vec::with_capacity(ids.len());
+    for id in ids {
+        subjects.push(
+            client
+                .email_get(&id, [email::Property::Subject].into())
+                .await
+                .unwrap()
+                .unwrap()
+                .subject()
+                .unwrap()
+                .to_string(),
+        );
+    }
+    subjects.sort_unstable();
+    assert_eq!(subjects, expected);
+}
+
+impl Account {
+    async fn schedule_reindex(
+        &self,
+        account: &Account,
+        mailbox_id: &str,
+        received_after: Option<i64>,
+    ) {
+        self.registry_create_object(Task::ReindexAccount(TaskReindexAccount {
+            account_id: account.id(),
+            mailbox_id: Id::from_bytes(mailbox_id.as_bytes()),
+            received_after: received_after.map(UTCDateTime::from_timestamp),
+            status: TaskStatus::at(now() as i64),
+            ..Default::default()
+        }))
+        .await;
+    }
+}
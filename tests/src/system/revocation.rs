@@ -0,0 +1,178 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    imap::{ImapConnection, Type},
+    pop3::{self, Pop3Connection},
+    server::TestServer,
+};
+use common::auth::oauth::GrantType;
+use imap_proto::ResponseType;
+use jmap_proto::error::set::SetErrorType;
+use registry::schema::{prelude::ObjectType, structs::Action};
+use serde_json::{Value, json};
+use std::time::Duration;
+use types::id::Id;
+
+const USER: &str = "revoke@example.org";
+const PASS: &str = "this is a very strong password";
+const NEW_PASS: &str = "this is another strong password";
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running credential revocation tests...");
+    let admin = test.account("admin@example.org");
+    let mut account = test
+        .create_user_account("admin@example.org", USER, PASS, &[], "Revocation")
+        .await;
+    let account_id = account.id().document_id();
+
+    // Open long lived sessions and obtain OAuth tokens
+    let mut imap = imap_login(PASS).await;
+    let mut pop3 = Pop3Connection::connect().await;
+    pop3.authenticate(USER, PASS).await;
+    let (access_token, refresh_token) = issue_tokens(test, account_id).await;
+    assert_eq!(jmap_session(&access_token).await, 200);
+
+    // Changing the password drops existing sessions
+    account
+        .registry_update_object(
+            ObjectType::AccountPassword,
+            Id::singleton(),
+            json!({
+                "currentSecret": PASS,
+                "secret": NEW_PASS
+            }),
+        )
+        .await;
+    account.update_secret(NEW_PASS);
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+    imap.assert_disconnect().await;
+    pop3.assert_read(pop3::ResponseType::Err).await;
+
+    // Tokens issued before the password change are refused
+    assert_revoked(test, &access_token, &refresh_token).await;
+    let (access_token, refresh_token) = issue_tokens(test, account_id).await;
+    assert_eq!(jmap_session(&access_token).await, 200);
+    test.server
+        .validate_access_token(GrantType::RefreshToken.into(), &refresh_token)
+        .await
+        .unwrap();
+
+    // Sign out everywhere without changing the password
+    let mut imap = imap_login(NEW_PASS).await;
+    imap.send_ok("NOOP").await;
+    account
+        .registry_create_object(Action::SignOutEverywhere)
+        .await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+    imap.assert_disconnect().await;
+    assert_revoked(test, &access_token, &refresh_token).await;
+
+    // Regular users cannot perform other actions
+    account
+        .registry_create_object_expect_err(Action::InvalidateCaches)
+        .await
+        .assert_type(SetErrorType::Forbidden);
+
+    // Tokens without a credential version are refused
+    let unversioned_token = test
+        .server
+        .encode_access_token(GrantType::AccessToken, account_id, USER, 3600, None, None)
+        .await
+        .unwrap();
+    assert_eq!(jmap_session(&unversioned_token).await, 401);
+
+    // Regular users cannot revoke other accounts
+    let terminate_url = format!(
+        "{}/api/sessions/{}?revoke=true",
+        admin.base_url(),
+        account.id_string()
+    );
+    assert_eq!(account.http_delete_raw(&terminate_url).await.status, 403);
+
+    // Administrators can revoke all sessions and tokens of an account
+    let mut imap = imap_login(NEW_PASS).await;
+    let (access_token, refresh_token) = issue_tokens(test, account_id).await;
+    assert_eq!(jmap_session(&access_token).await, 200);
+    let response = admin.http_delete_raw(&terminate_url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    assert_eq!(response.json().unwrap()["terminated"], Value::from(1));
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+    imap.assert_disconnect().await;
+    assert_revoked(test, &access_token, &refresh_token).await;
+    let response = admin
+        .http_delete_raw(&format!("{terminate_url}&protocol=imap"))
+        .await;
+    assert_eq!(response.status, 400, "{}", response.text());
+
+    // New logins keep working after revocation
+    let mut imap = imap_login(NEW_PASS).await;
+    imap.send_ok("NOOP").await;
+    drop(imap);
+
+    // Remove test data
+    admin.destroy_account(account).await;
+    test.cleanup().await;
+}
+
+async fn imap_login(pass: &str) -> ImapConnection {
+    let mut imap = ImapConnection::connect(b"_x ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(USER, pass).await;
+    imap
+}
+
+async fn issue_tokens(test: &TestServer, account_id: u32) -> (String, String) {
+    let credential_version = test
+        .server
+        .access_token(account_id)
+        .await
+        .unwrap()
+        .credential_version();
+    let mut tokens = Vec::with_capacity(2);
+    for grant_type in [GrantType::AccessToken, GrantType::RefreshToken] {
+        tokens.push(
+            test.server
+                .encode_access_token(
+                    grant_type,
+                    account_id,
+                    USER,
+                    3600,
+                    None,
+                    credential_version.into(),
+                )
+                .await
+                .unwrap(),
+        );
+    }
+    let refresh_token = tokens.pop().unwrap();
+    (tokens.pop().unwrap(), refresh_token)
+}
+
+async fn assert_revoked(test: &TestServer, access_token: &str, refresh_token: &str) {
+    assert_eq!(jmap_session(access_token).await, 401);
+    assert!(
+        test.server
+            .validate_access_token(GrantType::RefreshToken.into(), refresh_token)
+            .await
+            .is_err()
+    );
+}
+
+async fn jmap_session(token: &str) -> u16 {
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+        .get("https://127.0.0.1:8899/jmap/session")
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .status()
+        .as_u16()
+}
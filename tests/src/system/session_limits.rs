@@ -0,0 +1,172 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    imap::{AssertResult, ImapConnection, Type},
+    pop3::{self, Pop3Connection},
+    server::TestServer,
+};
+use base64::{Engine, engine::general_purpose};
+use imap_proto::ResponseType;
+use registry::schema::{enums::ServiceProtocol, prelude::Property, structs::Authentication};
+use serde_json::Value;
+use std::time::Duration;
+use utils::map::vec_map::VecMap;
+
+const USER: &str = "sessions@example.org";
+const PASS: &str = "this is a very strong password";
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running session limit tests...");
+    let admin = test.account("admin@example.org");
+
+    // Allow three sessions per account, two of them over IMAP
+    admin
+        .registry_update_setting(
+            Authentication {
+                max_sessions: Some(3),
+                max_sessions_per_protocol: VecMap::from_iter([(ServiceProtocol::Imap, 2u64)]),
+                ..Default::default()
+            },
+            &[Property::MaxSessions, Property::MaxSessionsPerProtocol],
+        )
+        .await;
+    admin.reload_settings().await;
+    let account = test
+        .create_user_account("admin@example.org", USER, PASS, &[], "Session Limits")
+        .await;
+
+    // The third IMAP session is rejected
+    let mut imap1 = imap_login().await;
+    let mut imap2 = imap_login().await;
+    let mut imap3 = ImapConnection::connect(b"_z ").await;
+    imap3.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap3.send(&imap_plain()).await;
+    imap3
+        .assert_read(Type::Tagged, ResponseType::No)
+        .await
+        .assert_response_code("LIMIT")
+        .assert_contains("Too many connections");
+
+    // Existing sessions keep working
+    imap1.send_ok("SELECT INBOX").await;
+    imap2.send_ok("NOOP").await;
+
+    // The total limit applies across protocols
+    let mut pop1 = Pop3Connection::connect().await;
+    pop1.authenticate(USER, PASS).await;
+    let mut pop2 = Pop3Connection::connect().await;
+    pop2.send(&pop3_plain()).await;
+    let response = pop2.assert_read(pop3::ResponseType::Err).await;
+    assert!(
+        response.last().unwrap().contains("Too many connections"),
+        "{response:?}"
+    );
+    pop1.send("STAT").await;
+    pop1.assert_read(pop3::ResponseType::Ok).await;
+
+    // Closing a session frees a slot
+    drop(imap2);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut imap2 = imap_login().await;
+    imap2.send_ok("NOOP").await;
+
+    // List the open sessions
+    let sessions_url = format!("{}/api/sessions", admin.base_url());
+    let response = admin.http_get_raw(&sessions_url, None).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let sessions = response.json().unwrap();
+    let entry = sessions
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["accountId"] == account.id_string())
+        .unwrap_or_else(|| panic!("Account not listed: {sessions}"));
+    assert_eq!(entry["total"], Value::from(3), "{entry}");
+    assert_eq!(entry["protocols"]["imap"], Value::from(2), "{entry}");
+    assert_eq!(entry["protocols"]["pop3"], Value::from(1), "{entry}");
+    assert_eq!(entry["sessions"].as_array().unwrap().len(), 3, "{entry}");
+
+    // Regular users cannot list or terminate sessions
+    let terminate_url = format!("{sessions_url}/{}", account.id_string());
+    assert_eq!(account.http_get_raw(&sessions_url, None).await.status, 403);
+    assert_eq!(account.http_delete_raw(&terminate_url).await.status, 403);
+
+    // Terminate the IMAP sessions
+    let response = admin
+        .http_delete_raw(&format!("{terminate_url}?protocol=imap"))
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    assert_eq!(response.json().unwrap()["terminated"], Value::from(2));
+    for imap in [&mut imap1, &mut imap2] {
+        imap.assert_read(Type::Untagged, ResponseType::Bye)
+            .await
+            .assert_contains("Session terminated by administrator");
+        imap.assert_disconnect().await;
+    }
+
+    // The POP3 session is not affected and new IMAP logins are allowed
+    pop1.send("NOOP").await;
+    pop1.assert_read(pop3::ResponseType::Ok).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut imap1 = imap_login().await;
+    imap1.send_ok("NOOP").await;
+
+    // Terminate all remaining sessions
+    let response = admin.http_delete_raw(&terminate_url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    assert_eq!(response.json().unwrap()["terminated"], Value::from(2));
+    imap1
+        .assert_read(Type::Untagged, ResponseType::Bye)
+        .await
+        .assert_contains("Session terminated by administrator");
+    pop1.assert_read(pop3::ResponseType::Err).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let response = admin.http_get_raw(&sessions_url, None).await;
+    assert!(
+        !response
+            .json()
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|entry| entry["accountId"] == account.id_string()),
+        "{}",
+        response.text()
+    );
+
+    // Remove test data
+    admin
+        .registry_update_setting(
+            Authentication::default(),
+            &[Property::MaxSessions, Property::MaxSessionsPerProtocol],
+        )
+        .await;
+    admin.reload_settings().await;
+    admin.destroy_account(account).await;
+    test.cleanup().await;
+}
+
+async fn imap_login() -> ImapConnection {
+    let mut imap = ImapConnection::connect(b"_x ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.authenticate(USER, PASS).await;
+    imap
+}
+
+fn imap_plain() -> String {
+    format!(
+        "AUTHENTICATE PLAIN {}",
+        general_purpose::STANDARD.encode(format!("\0{USER}\0{PASS}"))
+    )
+}
+
+fn pop3_plain() -> String {
+    format!(
+        "AUTH PLAIN {}",
+        general_purpose::STANDARD.encode(format!("\0{USER}\0{PASS}"))
+    )
+}
@@ -0,0 +1,225 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::server::TestServer;
+use common::auth::oauth::GrantType;
+use http::auth::oauth::OAuthResponse;
+use registry::{
+    schema::{
+        enums::Permission,
+        prelude::{ObjectType, Property},
+        structs::{self, Authentication, Credential, PasswordCredential, UserAccount},
+    },
+    types::{list::List, map::Map},
+};
+use serde_json::{Value, json};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use totp_rs::TOTP;
+use types::id::Id;
+
+const TOTP_URI: &str = "otpauth://totp/Stalwart:step.up@example.org?secret=JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP&issuer=Stalwart";
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running step-up authentication tests...");
+    let admin = test.account("admin@example.org");
+    let domain_id = admin.find_or_create_domain("example.org").await;
+
+    // Require a recent second factor for deleting accounts
+    admin
+        .registry_update_setting(
+            Authentication {
+                step_up_permissions: Map::new(vec![Permission::SysAccountDestroy]),
+                step_up_max_age: 2_000u64.into(),
+                ..Default::default()
+            },
+            &[Property::StepUpPermissions, Property::StepUpMaxAge],
+        )
+        .await;
+    admin.reload_settings().await;
+
+    // Create an operator account with TOTP enrolled
+    let operator_id = admin
+        .registry_create_object(structs::Account::User(UserAccount {
+            name: "step.up".to_string(),
+            domain_id,
+            credentials: List::from_iter([Credential::Password(PasswordCredential {
+                secret: "this is a very strong password".to_string(),
+                otp_auth: TOTP_URI.to_string().into(),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        }))
+        .await;
+    admin
+        .assign_roles_to_account(operator_id, &["user", "system"])
+        .await;
+    let victim_ids = [
+        create_victim(test, domain_id, "step.up.victim1").await,
+        create_victim(test, domain_id, "step.up.victim2").await,
+    ];
+
+    // Obtain a regular access token for the operator
+    let account_id = operator_id.document_id();
+    let credential_version = test
+        .server
+        .access_token(account_id)
+        .await
+        .unwrap()
+        .credential_version();
+    let token = test
+        .server
+        .encode_access_token(
+            GrantType::AccessToken,
+            account_id,
+            "step.up@example.org",
+            3600,
+            None,
+            credential_version.into(),
+        )
+        .await
+        .unwrap();
+
+    // Unprotected operations do not require a step-up
+    let (status, response) = destroy_account(&token, None).await;
+    assert_eq!(status, 200, "{response}");
+
+    // Deleting an account without a recent step-up is rejected
+    let (status, response) = destroy_account(&token, Some(victim_ids[0])).await;
+    assert_eq!(status, 403, "{response}");
+    assert_eq!(response["type"], "stepUpRequired", "{response}");
+
+    // Accounts without an enrolled factor or invalid codes cannot step up
+    let response = admin
+        .http_post_raw(
+            &format!("{}/api/step-up", admin.base_url()),
+            "application/json",
+            json!({"mfaToken": "123456"}).to_string(),
+        )
+        .await;
+    assert_eq!(response.status, 403, "{}", response.text());
+    let (status, response) = step_up(&token, "000000").await;
+    assert_eq!(status, 403, "{response}");
+
+    // Step up with a valid TOTP code and retry
+    let code = totp_code();
+    let (status, response) = step_up(&token, &code).await;
+    assert_eq!(status, 200, "{response}");
+    let step_up_token = serde_json::from_value::<OAuthResponse>(response)
+        .unwrap()
+        .access_token;
+
+    // Codes cannot be replayed, nor can codes from earlier time steps be used
+    let (status, response) = step_up(&token, &code).await;
+    assert_eq!(status, 403, "{response}");
+    let totp = TOTP::from_url(TOTP_URI).unwrap();
+    let previous_code = totp.generate(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            - totp.step,
+    );
+    if previous_code != code {
+        let (status, response) = step_up(&token, &previous_code).await;
+        assert_eq!(status, 403, "{response}");
+    }
+    let (status, response) = destroy_account(&step_up_token, Some(victim_ids[0])).await;
+    assert_eq!(status, 200, "{response}");
+    assert_eq!(
+        response["methodResponses"][0][1]["destroyed"],
+        json!([victim_ids[0].to_string()]),
+        "{response}"
+    );
+
+    // The original token is still not allowed
+    let (status, response) = destroy_account(&token, Some(victim_ids[1])).await;
+    assert_eq!(status, 403, "{response}");
+
+    // The step-up expires after the configured window
+    tokio::time::sleep(Duration::from_millis(2_500)).await;
+    let (status, response) = destroy_account(&step_up_token, Some(victim_ids[1])).await;
+    assert_eq!(status, 403, "{response}");
+    assert_eq!(response["type"], "stepUpRequired", "{response}");
+
+    // Remove test data
+    admin
+        .registry_update_setting(
+            Authentication::default(),
+            &[Property::StepUpPermissions, Property::StepUpMaxAge],
+        )
+        .await;
+    admin.reload_settings().await;
+    admin
+        .registry_destroy(ObjectType::Account, [operator_id, victim_ids[1]])
+        .await
+        .assert_destroyed(&[operator_id, victim_ids[1]]);
+    test.cleanup().await;
+}
+
+async fn create_victim(test: &TestServer, domain_id: Id, name: &str) -> Id {
+    test.account("admin@example.org")
+        .registry_create_object(structs::Account::User(UserAccount {
+            name: name.to_string(),
+            domain_id,
+            credentials: List::from_iter([Credential::Password(PasswordCredential {
+                secret: "this is a very strong password".to_string(),
+                ..Default::default()
+            })]),
+            ..Default::default()
+        }))
+        .await
+}
+
+fn totp_code() -> String {
+    TOTP::from_url(TOTP_URI)
+        .unwrap()
+        .generate_current()
+        .unwrap()
+}
+
+async fn destroy_account(token: &str, account_id: Option<Id>) -> (u16, Value) {
+    bearer_post(
+        "/jmap",
+        token,
+        json!({
+            "using": ["urn:ietf:params:jmap:core", "urn:stalwart:jmap"],
+            "methodCalls": [[
+                "x:Account/set",
+                {
+                    "destroy": account_id.map(|id| vec![id.to_string()]).unwrap_or_default()
+                },
+                "0"
+            ]]
+        }),
+    )
+    .await
+}
+
+async fn step_up(token: &str, code: &str) -> (u16, Value) {
+    bearer_post("/api/step-up", token, json!({"mfaToken": code})).await
+}
+
+async fn bearer_post(path: &str, token: &str, body: Value) -> (u16, Value) {
+    let response = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+        .post(format!("https://127.0.0.1:8899{path}"))
+        .bearer_auth(token)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .unwrap();
+    let status = response.status().as_u16();
+    let body = response.bytes().await.unwrap();
+    (
+        status,
+        serde_json::from_slice(&body)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&body).into_owned())),
+    )
+}
@@ -527,6 +527,7 @@ pub async fn test(test: &mut TestServer) {
             .deliver_message(IngestMessage {
                 sender_address: "bill@foobar.org".to_string(),
                 sender_authenticated: true,
+                remote_ip: None,
                 recipients: vec![IngestRecipient {
                     address: "user@tenantx.org".to_string(),
                     orcpt: None,
@@ -534,6 +535,7 @@ pub async fn test(test: &mut TestServer) {
                 }],
                 message_blob: message_blob.clone(),
                 message_size: TEST_MESSAGE.len() as u64,
+                spam_tags: vec![],
                 session_id: 0,
             })
             .await
@@ -564,6 +566,7 @@ pub async fn test(test: &mut TestServer) {
             .deliver_message(IngestMessage {
                 sender_address: "bill@foobar.org".to_string(),
                 sender_authenticated: true,
+                remote_ip: None,
                 recipients: vec![IngestRecipient {
                     address: "user@tenantx.org".to_string(),
                     orcpt: None,
@@ -571,11 +574,13 @@ pub async fn test(test: &mut TestServer) {
                 }],
                 message_blob,
                 message_size: TEST_MESSAGE.len() as u64,
+                spam_tags: vec![],
                 session_id: 0,
             })
             .await
             .status,
         vec![LocalDeliveryStatus::TemporaryFailure {
+            code: [4, 2, 2],
             reason: "Organization over quota.".into()
         }]
     );
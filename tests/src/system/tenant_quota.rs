@@ -0,0 +1,336 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: LicenseRef-SEL
+ *
+ * This file is subject to the Stalwart Enterprise License Agreement (SEL) and
+ * is NOT open source software.
+ *
+ */
+
+use crate::{
+    system::quota::{assert_over_quota, create_message_with_size},
+    utils::{account::Account, jmap::JmapUtils, server::TestServer},
+};
+use email::message::delivery::{IngestMessage, IngestRecipient, LocalDeliveryStatus, MailDelivery};
+use jmap_client::{client::Client, mailbox::Role};
+use jmap_proto::error::set::SetErrorType;
+use registry::{
+    schema::{
+        enums::TenantStorageQuota,
+        prelude::{ObjectType, Property},
+        structs::{
+            CertificateManagement, Credential, DkimManagement, DnsManagement, Domain,
+            PasswordCredential, Tenant, UserAccount, UserRoles,
+        },
+    },
+    types::list::List,
+};
+use serde_json::json;
+use types::id::Id;
+use utils::map::vec_map::VecMap;
+
+const TENANT_DISK_QUOTA: u64 = 3000;
+const MESSAGE_SIZE: usize = 2000;
+
+pub async fn test(test: &mut TestServer) {
+    println!("Running tenant quota tests...");
+    let admin = test.account("admin@example.org");
+
+    // Create two tenants, only the first one has a disk quota
+    let mut tenant_ids = Vec::new();
+    let mut domain_ids = Vec::new();
+    for (name, disk_quota) in [("q", Some(TENANT_DISK_QUOTA)), ("r", None)] {
+        let tenant_id = admin
+            .registry_create_object(Tenant {
+                name: format!("Tenant {}", name.to_uppercase()),
+                quotas: VecMap::from_iter(
+                    [(TenantStorageQuota::MaxAccounts, 3)]
+                        .into_iter()
+                        .chain(disk_quota.map(|quota| (TenantStorageQuota::MaxDiskQuota, quota))),
+                ),
+                ..Default::default()
+            })
+            .await;
+        domain_ids.push(
+            admin
+                .registry_create_object(Domain {
+                    name: format!("tenant{name}.org"),
+                    member_tenant_id: tenant_id.into(),
+                    certificate_management: CertificateManagement::Manual,
+                    dns_management: DnsManagement::Manual,
+                    dkim_management: DkimManagement::Manual,
+                    ..Default::default()
+                })
+                .await,
+        );
+        tenant_ids.push(tenant_id);
+    }
+
+    // Create a tenant administrator and two users sharing the tenant quota
+    let mut accounts = Vec::new();
+    for (name, roles) in [
+        ("admin", UserRoles::Admin),
+        ("john", UserRoles::User),
+        ("jane", UserRoles::User),
+    ] {
+        let id = admin
+            .registry_create_object(registry::schema::structs::Account::User(UserAccount {
+                name: name.to_string(),
+                domain_id: domain_ids[0],
+                member_tenant_id: tenant_ids[0].into(),
+                roles,
+                credentials: List::from_iter([Credential::Password(PasswordCredential {
+                    secret: "this is a very strong password".to_string(),
+                    ..Default::default()
+                })]),
+                ..Default::default()
+            }))
+            .await;
+        accounts.push(Account::new(
+            match name {
+                "admin" => "admin@tenantq.org",
+                "john" => "john@tenantq.org",
+                _ => "jane@tenantq.org",
+            },
+            "this is a very strong password",
+            &[],
+            name,
+            id,
+        ));
+    }
+    let [tenant_admin, john, jane] = &accounts[..] else {
+        unreachable!()
+    };
+    let john_client = john.jmap_client().await;
+    let jane_client = jane.jmap_client().await;
+    let john_mailbox = create_mailbox(&john_client).await;
+    let jane_mailbox = create_mailbox(&jane_client).await;
+
+    // The first account fills most of the tenant quota
+    let john_message_id = import(&john_client, &john_mailbox, "john@tenantq.org").await;
+    let used_quota = test
+        .server
+        .get_used_quota_account(john.id().document_id())
+        .await
+        .unwrap();
+    assert!(used_quota >= MESSAGE_SIZE as i64);
+    assert_eq!(tenant_used_quota(test, tenant_ids[0]).await, used_quota);
+
+    // The second account is blocked even though it has no quota of its own
+    assert_over_quota(
+        jane_client
+            .email_import(
+                create_message_with_size(
+                    "bill@example.org",
+                    "jane@tenantq.org",
+                    "Tenant report",
+                    MESSAGE_SIZE,
+                ),
+                [&jane_mailbox],
+                None::<Vec<&str>>,
+                None,
+            )
+            .await,
+    );
+    let message = create_message_with_size(
+        "bill@example.org",
+        "jane@tenantq.org",
+        "Tenant report",
+        MESSAGE_SIZE,
+    );
+    let (message_blob, _) = test
+        .server
+        .put_temporary_blob(jane.id().document_id(), &message, 60)
+        .await
+        .unwrap();
+    assert_eq!(
+        test.server
+            .deliver_message(IngestMessage {
+                sender_address: "bill@example.org".to_string(),
+                sender_authenticated: true,
+                remote_ip: None,
+                recipients: vec![IngestRecipient {
+                    address: "jane@tenantq.org".to_string(),
+                    orcpt: None,
+                    is_spam: false
+                }],
+                message_blob,
+                message_size: message.len() as u64,
+                spam_tags: vec![],
+                session_id: 0,
+            })
+            .await
+            .status,
+        vec![LocalDeliveryStatus::TemporaryFailure {
+            code: [4, 2, 2],
+            reason: "Organization over quota.".into()
+        }]
+    );
+    assert_eq!(
+        test.server
+            .get_used_quota_account(jane.id().document_id())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(tenant_used_quota(test, tenant_ids[0]).await, used_quota);
+
+    // The tenant quota is only visible to tenant administrators
+    let response = tenant_admin
+        .jmap_method_call(
+            "Quota/get",
+            json!({
+                "accountId": tenant_admin.id_string(),
+                "ids": null
+            }),
+        )
+        .await
+        .to_string();
+    assert!(response.contains("\"scope\":\"domain\""), "{}", response);
+    assert!(
+        response.contains(&format!("\"hardLimit\":{TENANT_DISK_QUOTA}")),
+        "{}",
+        response
+    );
+    assert!(
+        response.contains(&format!("\"used\":{used_quota}")),
+        "{}",
+        response
+    );
+    assert!(response.contains("\"name\":\"Tenant Q\""), "{}", response);
+    let response = jane
+        .jmap_method_call(
+            "Quota/get",
+            json!({
+                "accountId": jane.id_string(),
+                "ids": null
+            }),
+        )
+        .await
+        .to_string();
+    assert!(!response.contains("\"scope\":\"domain\""), "{}", response);
+
+    // The tenant usage is exposed in the admin API
+    assert_eq!(
+        admin
+            .registry_get_many(ObjectType::Tenant, [tenant_ids[0]])
+            .await
+            .list()[0]
+            .integer_field(Property::UsedDiskQuota.as_str()),
+        used_quota
+    );
+
+    // Accounts can only be moved along with their domains
+    admin
+        .registry_update_object_expect_err(
+            ObjectType::Account,
+            john.id(),
+            json!({
+                "memberTenantId": tenant_ids[1],
+            }),
+        )
+        .await
+        .assert_type(SetErrorType::InvalidProperties)
+        .assert_description_contains("does not belong to the account's tenant");
+
+    // Moving an account to another tenant moves its usage
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            john.id(),
+            json!({
+                "memberTenantId": tenant_ids[1],
+                "domainId": domain_ids[1],
+            }),
+        )
+        .await;
+    test.wait_for_tasks().await;
+    assert_eq!(tenant_used_quota(test, tenant_ids[0]).await, 0);
+    assert_eq!(tenant_used_quota(test, tenant_ids[1]).await, used_quota);
+    let jane_message_id = import(&jane_client, &jane_mailbox, "jane@tenantq.org").await;
+
+    // Accounts whose usage does not fit in the tenant quota cannot be moved
+    admin
+        .registry_update_object_expect_err(
+            ObjectType::Account,
+            john.id(),
+            json!({
+                "memberTenantId": tenant_ids[0],
+                "domainId": domain_ids[0],
+            }),
+        )
+        .await
+        .assert_type(SetErrorType::OverQuota)
+        .assert_description_contains("exceed the tenant's disk quota");
+    assert_eq!(tenant_used_quota(test, tenant_ids[1]).await, used_quota);
+
+    // Once there is room the account can be moved back
+    jane_client.email_destroy(&jane_message_id).await.unwrap();
+    test.wait_for_tasks().await;
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            john.id(),
+            json!({
+                "memberTenantId": tenant_ids[0],
+                "domainId": domain_ids[0],
+            }),
+        )
+        .await;
+    test.wait_for_tasks().await;
+    assert_eq!(tenant_used_quota(test, tenant_ids[0]).await, used_quota);
+    assert_eq!(tenant_used_quota(test, tenant_ids[1]).await, 0);
+
+    // Remove test data
+    john_client.email_destroy(&john_message_id).await.unwrap();
+    test.wait_for_tasks().await;
+    assert_eq!(tenant_used_quota(test, tenant_ids[0]).await, 0);
+    assert_eq!(tenant_used_quota(test, tenant_ids[1]).await, 0);
+    test.destroy_all_mailboxes(john).await;
+    test.destroy_all_mailboxes(jane).await;
+    for account in accounts {
+        admin.destroy_account(account).await;
+    }
+    for domain_id in domain_ids {
+        admin
+            .registry_destroy(ObjectType::Domain, [domain_id])
+            .await
+            .assert_destroyed(&[domain_id]);
+    }
+    for tenant_id in tenant_ids {
+        admin
+            .registry_destroy(ObjectType::Tenant, [tenant_id])
+            .await
+            .assert_destroyed(&[tenant_id]);
+    }
+    test.cleanup().await;
+}
+
+async fn create_mailbox(client: &Client) -> String {
+    client
+        .mailbox_create("Tenant Inbox", None::<String>, Role::None)
+        .await
+        .unwrap()
+        .take_id()
+}
+
+async fn import(client: &Client, mailbox_id: &str, to: &str) -> String {
+    client
+        .email_import(
+            create_message_with_size("bill@example.org", to, "Tenant report", MESSAGE_SIZE),
+            [mailbox_id],
+            None::<Vec<&str>>,
+            None,
+        )
+        .await
+        .unwrap()
+        .take_id()
+}
+
+async fn tenant_used_quota(test: &TestServer, tenant_id: Id) -> i64 {
+    test.server
+        .get_used_quota_tenant(tenant_id.document_id())
+        .await
+        .unwrap()
+}
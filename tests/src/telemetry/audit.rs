@@ -0,0 +1,242 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{
+    imap::{ImapConnection, Type},
+    server::TestServer,
+};
+use imap_proto::ResponseType;
+use registry::{
+    schema::prelude::{ObjectType, Property},
+    types::datetime::UTCDateTime,
+};
+use serde_json::json;
+use std::time::Duration;
+use store::write::now;
+use types::id::Id;
+
+pub async fn test(test: &TestServer) {
+    println!("Running Audit log tests...");
+    let admin = test.account("admin@example.org");
+
+    // Enable audit logging for the domain
+    let domain_id = admin.find_or_create_domain("example.org").await;
+    admin
+        .registry_update_object(
+            ObjectType::Domain,
+            domain_id,
+            json!({
+                Property::AuditLog: true,
+                Property::AuditMessageReads: false,
+            }),
+        )
+        .await;
+
+    // Create test account
+    let account = test
+        .create_user_account(
+            "admin@example.org",
+            "jane@example.org",
+            "a strong password for jane",
+            &[],
+            "Jane Doe",
+        )
+        .await;
+    let account_id = account.id().to_string();
+
+    // Generate a failed login, a successful login and mailbox changes
+    let mut imap = ImapConnection::connect(b"_x ").await;
+    imap.assert_read(Type::Untagged, ResponseType::Ok).await;
+    imap.send("LOGIN jane@example.org wrong-password").await;
+    imap.assert_read(Type::Tagged, ResponseType::No).await;
+    imap.authenticate("jane@example.org", "a strong password for jane")
+        .await;
+    imap.send("CREATE Audited").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.append(
+        "INBOX",
+        "From: test@example.org\r\nSubject: audit\r\n\r\ntest",
+    )
+    .await;
+    imap.send("SELECT INBOX").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("FETCH 1 BODY[]").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("STORE 1 +FLAGS (\\Deleted)").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("EXPUNGE").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("DELETE Audited").await;
+    imap.assert_read(Type::Tagged, ResponseType::Ok).await;
+    imap.send("LOGOUT").await;
+    imap.assert_read(Type::Untagged, ResponseType::Bye).await;
+
+    // Events are written asynchronously
+    let mut event_ids = Vec::new();
+    for _ in 0..50 {
+        event_ids = admin
+            .registry_query(
+                ObjectType::AuditEvent,
+                [("accountId", account_id.as_str())],
+                Vec::<&str>::new(),
+            )
+            .await
+            .object_ids()
+            .collect::<Vec<_>>();
+        if event_ids.len() >= 4 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // Filter by event type
+    for (event, expected) in [
+        ("loginFailed", 1),
+        ("messageDelete", 1),
+        ("mailboxDelete", 1),
+        ("messageRead", 0),
+    ] {
+        assert_eq!(
+            admin
+                .registry_query(
+                    ObjectType::AuditEvent,
+                    [("accountId", account_id.as_str()), ("event", event)],
+                    Vec::<&str>::new(),
+                )
+                .await
+                .object_ids()
+                .count(),
+            expected,
+            "unexpected number of {event} events"
+        );
+    }
+    let login_ids = admin
+        .registry_query(
+            ObjectType::AuditEvent,
+            [("accountId", account_id.as_str()), ("event", "login")],
+            Vec::<&str>::new(),
+        )
+        .await
+        .object_ids()
+        .collect::<Vec<_>>();
+    assert!(!login_ids.is_empty(), "expected at least one login event");
+
+    // Fetch a login event
+    let response = admin
+        .registry_get_many(ObjectType::AuditEvent, [login_ids[0]])
+        .await;
+    let event = &response.list()[0];
+    assert_eq!(event["accountId"], json!(account_id));
+    assert_eq!(event["actorId"], json!(account_id));
+    assert_eq!(event["event"], json!("login"));
+    assert_eq!(event["protocol"], json!("imap"));
+
+    // Filter by timestamp
+    assert_eq!(
+        admin
+            .registry_query(
+                ObjectType::AuditEvent,
+                [
+                    ("accountId", account_id.clone()),
+                    (
+                        "timestampIsLessThan",
+                        UTCDateTime::from_timestamp((now() - 3600) as i64).to_string(),
+                    ),
+                ],
+                Vec::<&str>::new(),
+            )
+            .await
+            .object_ids()
+            .count(),
+        0
+    );
+    assert_eq!(
+        admin
+            .registry_query(
+                ObjectType::AuditEvent,
+                [
+                    ("accountId", account_id.clone()),
+                    (
+                        "timestampIsGreaterThan",
+                        UTCDateTime::from_timestamp((now() - 3600) as i64).to_string(),
+                    ),
+                ],
+                Vec::<&str>::new(),
+            )
+            .await
+            .object_ids()
+            .count(),
+        event_ids.len()
+    );
+
+    // Test pagination
+    let asc_order: Vec<Id> = admin
+        .registry_query_paginated(
+            ObjectType::AuditEvent,
+            "timestamp",
+            true,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .await
+        .object_ids()
+        .collect();
+    assert!(asc_order.len() >= event_ids.len());
+    let page = admin
+        .registry_query_paginated(
+            ObjectType::AuditEvent,
+            "timestamp",
+            true,
+            Some(1),
+            Some(2),
+            None,
+            None,
+            false,
+        )
+        .await
+        .object_ids()
+        .collect::<Vec<_>>();
+    assert_eq!(page, asc_order[1..3]);
+    let desc_order: Vec<Id> = admin
+        .registry_query_paginated(
+            ObjectType::AuditEvent,
+            "timestamp",
+            false,
+            None,
+            None,
+            None,
+            None,
+            false,
+        )
+        .await
+        .object_ids()
+        .collect();
+    assert_eq!(
+        desc_order,
+        asc_order.iter().rev().copied().collect::<Vec<_>>()
+    );
+
+    // Audit events are read-only
+    admin
+        .registry_destroy_object_expect_err(ObjectType::AuditEvent, login_ids[0])
+        .await;
+
+    // Disable audit logging
+    admin
+        .registry_update_object(
+            ObjectType::Domain,
+            domain_id,
+            json!({
+                Property::AuditLog: false,
+            }),
+        )
+        .await;
+    test.destroy_all_mailboxes(&account).await;
+    admin.destroy_account(account).await;
+}
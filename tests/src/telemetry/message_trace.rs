@@ -0,0 +1,135 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer, smtp::SmtpConnection};
+use common::telemetry::tracers::store::TracingStore;
+use registry::schema::prelude::{ObjectType, Property};
+use serde_json::Value;
+use std::time::Duration;
+
+pub async fn test(test: &TestServer) {
+    println!("Running message trace tests...");
+
+    let admin = test.account("admin@example.org");
+    let account = test
+        .create_user_account(
+            "admin@example.org",
+            "trace@example.org",
+            "this is a very strong password",
+            &[],
+            "Trace Test",
+        )
+        .await;
+    test.server
+        .tracing_store()
+        .purge_spans(Duration::from_secs(0), test.server.search_store().into())
+        .await
+        .unwrap();
+
+    // Send an email
+    let mut lmtp = SmtpConnection::connect().await;
+    lmtp.ingest(
+        "bill@example.org",
+        &["trace@example.org"],
+        concat!(
+            "From: bill@example.org\r\n",
+            "To: trace@example.org\r\n",
+            "Subject: TPS Report\r\n",
+            "X-Spam-Status: No\r\n",
+            "\r\n",
+            "Did you get the memo about the new cover sheets?"
+        ),
+    )
+    .await;
+    lmtp.quit().await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    test.server.notify_task_queue();
+    test.wait_for_tasks().await;
+
+    // Obtain the trace using the delivery span id
+    let span_id = admin
+        .registry_query(
+            ObjectType::Trace,
+            [(Property::Event, "delivery.attempt-start")],
+            Vec::<&str>::new(),
+        )
+        .await
+        .object_ids()
+        .next()
+        .expect("Missing delivery span");
+    let trace = get_trace(admin, &span_id.to_string(), "").await;
+    assert_eq!(trace["spanIds"].as_array().unwrap().len(), 2, "{trace}");
+    assert_eq!(trace["queueIds"].as_array().unwrap().len(), 1, "{trace}");
+    let total = trace["total"].as_u64().unwrap() as usize;
+    let events = trace["events"].as_array().unwrap();
+    assert_eq!(events.len(), total, "{trace}");
+
+    // Reception, delivery attempt and completion are returned in order
+    let names = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    let position = |name: &str| {
+        names
+            .iter()
+            .position(|n| *n == name)
+            .unwrap_or_else(|| panic!("Missing event {name}: {names:?}"))
+    };
+    assert!(position("smtp.connection-start") < position("queue.message-queued"));
+    assert!(position("queue.message-queued") < position("delivery.attempt-start"));
+    assert!(position("delivery.attempt-start") < position("delivery.completed"));
+    assert!(position("delivery.completed") < position("delivery.attempt-end"));
+    assert_eq!(
+        names
+            .iter()
+            .filter(|n| **n == "delivery.attempt-start")
+            .count(),
+        1
+    );
+
+    // Traces can also be retrieved by queue id
+    let queue_id = trace["queueIds"][0].as_str().unwrap();
+    assert_eq!(get_trace(admin, queue_id, "").await, trace);
+
+    // Pagination
+    let page = get_trace(admin, queue_id, "?position=2&limit=3").await;
+    assert_eq!(page["total"], trace["total"], "{page}");
+    assert_eq!(
+        page["events"].as_array().unwrap(),
+        &events[2..5].to_vec(),
+        "{page}"
+    );
+
+    // Unknown traces and unauthorized users are rejected
+    let response = admin
+        .http_get_raw(
+            &format!("{}/api/telemetry/trace/zzzzzz", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 404, "{}", response.text());
+    let response = account
+        .http_get_raw(
+            &format!("{}/api/telemetry/trace/{queue_id}", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 403, "{}", response.text());
+
+    admin.destroy_account(account).await;
+    test.cleanup().await;
+}
+
+async fn get_trace(admin: &Account, id: &str, query: &str) -> Value {
+    let response = admin
+        .http_get_raw(
+            &format!("{}/api/telemetry/trace/{id}{query}", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
@@ -5,7 +5,10 @@
  */
 
 pub mod alerts;
+pub mod audit;
+pub mod message_trace;
 pub mod metrics;
+pub mod overrides;
 pub mod tracing;
 pub mod webhooks;
 
@@ -56,8 +59,11 @@ pub async fn telemetry_tests() {
     test.insert_account(admin);
 
     alerts::test(&test).await;
+    audit::test(&test).await;
     metrics::test(&test).await;
     tracing::test(&test).await;
+    message_trace::test(&test).await;
+    overrides::test(&test).await;
     webhooks::test(&test).await;
 
     if test.is_reset() {
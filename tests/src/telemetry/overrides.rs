@@ -0,0 +1,235 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::utils::{account::Account, server::TestServer};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use trc::{
+    Collector, DeliveryEvent, EventType, Key,
+    ipc::subscriber::{EventBatch, SubscriberBuilder},
+};
+use types::id::Id;
+
+const ACCOUNT_ID: u32 = 987_654;
+const OTHER_ACCOUNT_ID: u32 = 987_655;
+
+pub async fn test(test: &TestServer) {
+    println!("Running tracing override tests...");
+
+    // Register a tracer that is not interested in any event
+    let admin = test.account("admin@example.org");
+    let subscriber_id = "override-test".to_string();
+    let (_tx, mut rx) = SubscriberBuilder::new(subscriber_id.clone())
+        .with_overrides(true)
+        .register();
+    emit_events();
+    assert_eq!(received(&mut rx).await, vec![]);
+
+    // Overly broad or invalid overrides are rejected
+    for (filter, level) in [
+        ("", "debug"),
+        ("s", "debug"),
+        ("delivery.unknown-event", "debug"),
+        ("delivery.", "verbose"),
+    ] {
+        let response = create_override(admin, filter, level, 60).await;
+        assert_eq!(response.0, 400, "{filter} {level}: {}", response.1);
+    }
+    let response = create_override(admin, "delivery.", "debug", 7 * 86400).await;
+    assert_eq!(response.0, 400, "{}", response.1);
+    assert_eq!(list_overrides(admin).await, json!([]));
+
+    // Enable debug delivery events for a single account
+    let (status, rule) = create_override(admin, "delivery.", "debug", 2).await;
+    assert_eq!(status, 200, "{rule}");
+    assert_eq!(rule["filter"], "delivery.", "{rule}");
+    assert_eq!(rule["level"], "DEBUG", "{rule}");
+    assert_eq!(
+        rule["accountId"],
+        Id::from(ACCOUNT_ID).to_string(),
+        "{rule}"
+    );
+    assert!(rule["events"].as_u64().unwrap() > 1, "{rule}");
+    assert_eq!(list_overrides(admin).await, json!([rule]));
+
+    // Only events of that account up to the override level pass the filter
+    emit_events();
+    assert_eq!(
+        received(&mut rx).await,
+        vec![(
+            EventType::Delivery(DeliveryEvent::DsnSuppressed),
+            ACCOUNT_ID as u64
+        )]
+    );
+
+    // Once the override expires the previous behavior is restored
+    utils::clock::advance_test_clock(3);
+    emit_events();
+    assert_eq!(received(&mut rx).await, vec![]);
+    assert_eq!(list_overrides(admin).await, json!([]));
+
+    // Events can be matched by the value of one of their keys
+    let (status, rule) = post_override(
+        admin,
+        json!({
+            "filter": "delivery.",
+            "level": "debug",
+            "key": "reason",
+            "value": "Override-Test",
+            "ttl": 60,
+        }),
+    )
+    .await;
+    assert_eq!(status, 200, "{rule}");
+    assert_eq!(rule["key"], "reason", "{rule}");
+    emit_events();
+    assert_eq!(
+        received(&mut rx).await,
+        vec![
+            (
+                EventType::Delivery(DeliveryEvent::DsnSuppressed),
+                ACCOUNT_ID as u64
+            ),
+            (
+                EventType::Delivery(DeliveryEvent::DsnSuppressed),
+                OTHER_ACCOUNT_ID as u64
+            )
+        ]
+    );
+    let url = format!(
+        "{}/api/telemetry/overrides/{}",
+        admin.base_url(),
+        rule["id"].as_u64().unwrap()
+    );
+    let response = admin.http_delete_raw(&url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    for body in [
+        json!({
+            "filter": "delivery.",
+            "level": "debug",
+            "key": "unknownKey",
+            "value": "override-test",
+            "ttl": 60,
+        }),
+        json!({
+            "filter": "delivery.",
+            "level": "debug",
+            "key": "reason",
+            "ttl": 60,
+        }),
+    ] {
+        let response = post_override(admin, body).await;
+        assert_eq!(response.0, 400, "{}", response.1);
+    }
+    let (status, rule) = post_override(
+        admin,
+        json!({
+            "filter": "delivery.",
+            "level": "debug",
+            "key": "reason",
+            "value": "other-reason",
+            "ttl": 60,
+        }),
+    )
+    .await;
+    assert_eq!(status, 200, "{rule}");
+    emit_events();
+    assert_eq!(received(&mut rx).await, vec![]);
+    let url = format!(
+        "{}/api/telemetry/overrides/{}",
+        admin.base_url(),
+        rule["id"].as_u64().unwrap()
+    );
+    let response = admin.http_delete_raw(&url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+
+    // Overrides can be removed before they expire
+    let (status, rule) = create_override(admin, "delivery.dsn-", "info", 3600).await;
+    assert_eq!(status, 200, "{rule}");
+    let id = rule["id"].as_u64().unwrap();
+    let url = format!("{}/api/telemetry/overrides/{id}", admin.base_url());
+    let response = admin.http_delete_raw(&url).await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    assert_eq!(list_overrides(admin).await, json!([]));
+    emit_events();
+    assert_eq!(received(&mut rx).await, vec![]);
+    let response = admin.http_delete_raw(&url).await;
+    assert_eq!(response.status, 404, "{}", response.text());
+
+    Collector::remove_subscriber(subscriber_id);
+    Collector::reload();
+}
+
+fn emit_events() {
+    trc::event!(
+        Delivery(DeliveryEvent::DsnSuppressed),
+        AccountId = ACCOUNT_ID,
+        Reason = "override-test",
+    );
+    trc::event!(
+        Delivery(DeliveryEvent::DsnSuppressed),
+        AccountId = OTHER_ACCOUNT_ID,
+        Reason = "override-test",
+    );
+    trc::event!(
+        Delivery(DeliveryEvent::RawInput),
+        AccountId = ACCOUNT_ID,
+        Contents = "override-test",
+    );
+}
+
+async fn received(rx: &mut mpsc::Receiver<EventBatch>) -> Vec<(EventType, u64)> {
+    let mut events = Vec::new();
+    while let Ok(Some(batch)) = tokio::time::timeout(Duration::from_millis(500), rx.recv()).await {
+        for event in batch {
+            if let Some(account_id) = event.value_as_uint(Key::AccountId)
+                && (account_id == ACCOUNT_ID as u64 || account_id == OTHER_ACCOUNT_ID as u64)
+            {
+                events.push((event.inner.typ, account_id));
+            }
+        }
+    }
+    events
+}
+
+async fn create_override(admin: &Account, filter: &str, level: &str, ttl: u64) -> (u16, Value) {
+    post_override(
+        admin,
+        json!({
+            "filter": filter,
+            "level": level,
+            "accountId": Id::from(ACCOUNT_ID).to_string(),
+            "ttl": ttl,
+        }),
+    )
+    .await
+}
+
+async fn post_override(admin: &Account, body: Value) -> (u16, Value) {
+    let response = admin
+        .http_post_raw(
+            &format!("{}/api/telemetry/overrides", admin.base_url()),
+            "application/json",
+            body.to_string(),
+        )
+        .await;
+    (
+        response.status,
+        response.json().unwrap_or_else(|| json!(response.text())),
+    )
+}
+
+async fn list_overrides(admin: &Account) -> Value {
+    let response = admin
+        .http_get_raw(
+            &format!("{}/api/telemetry/overrides", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    response.json().unwrap()
+}
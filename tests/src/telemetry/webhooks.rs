@@ -13,12 +13,13 @@ use http_proto::{ToHttpResponse, request::fetch_body};
 use hyper::{body, server::conn::http1, service::service_fn};
 use hyper_util::rt::TokioIo;
 use jmap::api::ToJmapHttpResponse;
+use jmap_client::{core::query::Filter, email};
 use jmap_proto::error::request::RequestError;
 use registry::{
     schema::{
         enums::EventPolicy,
-        prelude::ObjectType,
-        structs::{SecretKeyOptional, SecretKeyValue, WebHook},
+        prelude::{ObjectType, Property},
+        structs::{Jmap, SecretKeyOptional, SecretKeyValue, WebHook},
     },
     types::map::Map,
 };
@@ -29,9 +30,10 @@ use std::{
     },
     time::Duration,
 };
-use store::parking_lot::Mutex;
+use store::{dispatch::timing::STORE_READ_DELAY, parking_lot::Mutex};
 use tokio::{net::TcpListener, sync::watch};
 use trc::EventType;
+use utils::map::vec_map::VecMap;
 
 struct MockWebhookEndpoint {
     pub _tx: watch::Sender<bool>,
@@ -64,6 +66,7 @@ pub async fn test(test: &TestServer) {
                         ev.starts_with("smtp.connection-")
                             || ev.starts_with("delivery.dsn")
                             || ev.starts_with("message-ingest.")
+                            || ev == "jmap.slow-method-call"
                     })
                     .copied()
                     .collect(),
@@ -114,6 +117,123 @@ pub async fn test(test: &TestServer) {
         "\"jdoe@example.org\"",
     ]);
 
+    // Slow method calls are logged with a summary of their arguments and timings
+    admin
+        .registry_update_setting(
+            Jmap {
+                slow_method_threshold: None,
+                slow_method_threshold_per_method: VecMap::from_iter([(
+                    "Email/query".to_string(),
+                    registry::types::duration::Duration::from_millis(50),
+                )]),
+                ..Default::default()
+            },
+            &[
+                Property::SlowMethodThreshold,
+                Property::SlowMethodThresholdPerMethod,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+    STORE_READ_DELAY.store(25, Ordering::Relaxed);
+    let ids = john
+        .jmap_client()
+        .await
+        .email_query(
+            Filter::and(vec![
+                email::query::Filter::text("tps"),
+                email::query::Filter::from("bill"),
+            ])
+            .into(),
+            None::<Vec<_>>,
+        )
+        .await
+        .unwrap()
+        .take_ids();
+    STORE_READ_DELAY.store(0, Ordering::Relaxed);
+    assert_eq!(ids.len(), 1);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let events = webhook.events.lock().clone();
+    let slow_calls = events
+        .iter()
+        .filter(|event| event["type"] == "jmap.slow-method-call")
+        .collect::<Vec<_>>();
+    assert!(
+        !slow_calls.is_empty()
+            && slow_calls
+                .iter()
+                .all(|event| event["data"]["id"] == "Email/query"),
+        "Only Email/query has a slow method threshold: {events:?}"
+    );
+    let slow_call = slow_calls[0];
+    let data = &slow_call["data"];
+    assert_eq!(data["id"], "Email/query", "{data}");
+    assert_eq!(data["accountId"], john.id().document_id(), "{data}");
+    assert_eq!(data["total"], 1, "{data}");
+    assert!(
+        data["details"]
+            .as_str()
+            .is_some_and(|details| details.starts_with("filter=AND(*,*) sort=0")),
+        "{data}"
+    );
+    assert!(
+        data["storeReads"].as_u64().is_some_and(|reads| reads > 0),
+        "{data}"
+    );
+    for key in [
+        "parseElapsed",
+        "storeElapsed",
+        "serializeElapsed",
+        "elapsed",
+        "size",
+    ] {
+        assert!(!data[key].is_null(), "missing {key}: {data}");
+    }
+    webhook.events.lock().clear();
+
+    // Recent slow method calls can be retrieved from the management API
+    let response = admin
+        .http_get_raw(
+            &format!("{}/api/telemetry/slow-requests", admin.base_url()),
+            None,
+        )
+        .await;
+    assert_eq!(response.status, 200, "{}", response.text());
+    let requests = response.json().unwrap();
+    let request = requests
+        .as_array()
+        .and_then(|requests| requests.last())
+        .unwrap_or_else(|| panic!("No slow requests recorded: {requests}"));
+    assert_eq!(request["method"], "Email/query", "{request}");
+    assert_eq!(request["accountId"], john.id().document_id(), "{request}");
+    assert_eq!(request["results"], 1, "{request}");
+    assert!(
+        request["storeReads"]
+            .as_u64()
+            .is_some_and(|reads| reads > 0),
+        "{request}"
+    );
+    assert_eq!(
+        john.http_get_raw(
+            &format!("{}/api/telemetry/slow-requests", admin.base_url()),
+            None,
+        )
+        .await
+        .status,
+        403
+    );
+
+    admin
+        .registry_update_setting(
+            Jmap::default(),
+            &[
+                Property::SlowMethodThreshold,
+                Property::SlowMethodThresholdPerMethod,
+            ],
+        )
+        .await;
+    admin.reload_settings().await;
+
     // Cleanup
     admin.registry_destroy_all(ObjectType::WebHook).await;
     admin.reload_settings().await;
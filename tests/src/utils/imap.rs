@@ -60,6 +60,10 @@ impl ImapConnection {
         }
     }
 
+    pub fn into_inner(self) -> TcpStream {
+        self.reader.into_inner().unsplit(self.writer)
+    }
+
     pub fn assert_last_contains_bytes(&self, pattern: &[u8]) -> &Self {
         if !self.last_raw.windows(pattern.len()).any(|w| w == pattern) {
             panic!(
@@ -362,6 +362,18 @@ impl Account {
         .await
     }
 
+    pub async fn http_delete_raw(&self, url: &str) -> RawResponse {
+        RawResponse::from_response(
+            self.http_client(5000)
+                .delete(url)
+                .header(header::AUTHORIZATION, self.basic_auth())
+                .send()
+                .await
+                .unwrap(),
+        )
+        .await
+    }
+
     pub async fn jmap_raw_post(&self, body: impl Into<Vec<u8>>, content_type: &str) -> RawResponse {
         let url = self.api_url();
         self.http_post_raw(&url, content_type, body).await
@@ -408,7 +420,8 @@ impl Account {
             "urn:ietf:params:jmap:principals:availability",
             "urn:ietf:params:jmap:filenode",
             "urn:ietf:params:jmap:mail:share",
-            "urn:stalwart:jmap"
+            "urn:stalwart:jmap",
+            "urn:stalwart:jmap:followup"
           ],
           "methodCalls": calls
         });
@@ -75,6 +75,26 @@ impl SmtpConnection {
         conn
     }
 
+    pub async fn connect_smtp_port(port: u16) -> Self {
+        let (reader, writer) = tokio::io::split(
+            TcpStream::connect(&format!("127.0.0.1:{port}"))
+                .await
+                .unwrap(),
+        );
+        let mut conn = SmtpConnection {
+            reader: BufReader::new(reader).lines(),
+            writer,
+        };
+        conn.read(1, 2).await;
+        conn.ehlo().await;
+        conn
+    }
+
+    pub async fn ehlo(&mut self) -> Vec<String> {
+        self.send("EHLO mx.example.org").await;
+        self.read(1, 2).await
+    }
+
     pub async fn lhlo(&mut self) -> Vec<String> {
         self.send("LHLO localhost").await;
         self.read(1, 2).await
@@ -0,0 +1,257 @@
+/*
+ * SPDX-FileCopyrightText: 2020 Stalwart Labs LLC <hello@stalw.art>
+ *
+ * SPDX-License-Identifier: AGPL-3.0-only OR LicenseRef-SEL
+ */
+
+use crate::{
+    utils::{server::TestServer, webdav::DummyWebDavClient},
+    webdav::prop::ALL_DAV_PROPERTIES,
+};
+use dav_proto::Depth;
+use email::message::delivery::{IngestMessage, IngestRecipient, LocalDeliveryStatus, MailDelivery};
+use hyper::StatusCode;
+use registry::{
+    schema::{
+        enums::CalendarInvitationPolicy,
+        prelude::{ObjectType, Property},
+    },
+    types::EnumImpl,
+};
+use serde_json::json;
+
+const TEST_ORGANIZER_EVENT: &str = r#"BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//Stalwart Labs//Test//EN
+BEGIN:VEVENT
+UID:imip-review-91b4e0d3@example.com
+DTSTAMP:20260110T090000Z
+DTSTART:20300120T140000Z
+DTEND:20300120T150000Z
+SEQUENCE:0
+SUMMARY:Design review
+ORGANIZER;CN=John Doe:mailto:jdoe@example.com
+ATTENDEE;CN=John Doe;PARTSTAT=ACCEPTED:mailto:jdoe@example.com
+ATTENDEE;CN=Carol Remote;PARTSTAT=NEEDS-ACTION;SCHEDULE-AGENT=CLIENT:mailto:carol@remote.org
+ATTENDEE;CN=Dave Remote;PARTSTAT=NEEDS-ACTION;SCHEDULE-AGENT=CLIENT:mailto:dave@remote.org
+END:VEVENT
+END:VCALENDAR
+"#;
+
+pub async fn test(test: &TestServer) {
+    println!("Running iMIP ingestion tests...");
+    let jane = test.account("jane@example.com");
+    let john = test.account("john@example.com");
+    let jane_client = jane.webdav_client();
+    let john_client = john.webdav_client();
+    let jane_cal = "/dav/cal/jane%40example.com/default/";
+
+    // Invitations from external organizers are added with PARTSTAT=NEEDS-ACTION
+    deliver_imip(
+        test,
+        jane_client.account_id,
+        "jane.smith@example.com",
+        include_str!("../../resources/imip/request.eml"),
+    )
+    .await;
+    let events = jane_client.fetch_imip_events().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    let (href, ical) = &events[0];
+    assert!(ical.contains("SUMMARY:Project kickoff"), "{ical}");
+    assert!(
+        ical.contains("PARTSTAT=NEEDS-ACTION:mailto:jane.smith@example.com"),
+        "{ical}"
+    );
+    assert!(!ical.contains("METHOD:"), "{ical}");
+    let href = href.clone();
+    let sync_token = jane_client
+        .sync_collection(jane_cal, "", Depth::One, None, ["D:getetag"])
+        .await
+        .sync_token()
+        .to_string();
+
+    // Updates with a higher sequence are applied and bump the sync token
+    deliver_imip(
+        test,
+        jane_client.account_id,
+        "jane.smith@example.com",
+        include_str!("../../resources/imip/update.eml"),
+    )
+    .await;
+    let events = jane_client.fetch_imip_events().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    let ical = &events[0].1;
+    assert!(
+        ical.contains("SUMMARY:Project kickoff (rescheduled)"),
+        "{ical}"
+    );
+    assert!(ical.contains("DTSTART:20300116T100000Z"), "{ical}");
+    let response = jane_client
+        .sync_collection(jane_cal, &sync_token, Depth::One, None, ["D:getetag"])
+        .await;
+    assert_ne!(response.sync_token(), sync_token);
+    assert_eq!(response.hrefs(), vec![href.as_str()]);
+
+    // Stale requests are ignored
+    deliver_imip(
+        test,
+        jane_client.account_id,
+        "jane.smith@example.com",
+        include_str!("../../resources/imip/request.eml"),
+    )
+    .await;
+    let events = jane_client.fetch_imip_events().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert!(
+        events[0]
+            .1
+            .contains("SUMMARY:Project kickoff (rescheduled)"),
+        "{:?}",
+        events[0]
+    );
+
+    // Cancellations mark the event as cancelled
+    deliver_imip(
+        test,
+        jane_client.account_id,
+        "jane.smith@example.com",
+        include_str!("../../resources/imip/cancel.eml"),
+    )
+    .await;
+    let events = jane_client.fetch_imip_events().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert!(events[0].1.contains("STATUS:CANCELLED"), "{:?}", events[0]);
+
+    // Replies from external attendees are aggregated into the organizer's copy
+    john_client
+        .request_with_headers(
+            "PUT",
+            "/dav/cal/john%40example.com/default/imip-review.ics",
+            [("content-type", "text/calendar; charset=utf-8")],
+            TEST_ORGANIZER_EVENT,
+        )
+        .await
+        .with_status(StatusCode::CREATED);
+    for reply in [
+        include_str!("../../resources/imip/reply_carol.eml"),
+        include_str!("../../resources/imip/reply_dave.eml"),
+    ] {
+        deliver_imip(test, john_client.account_id, "jdoe@example.com", reply).await;
+    }
+    let ical = john_client
+        .request(
+            "GET",
+            "/dav/cal/john%40example.com/default/imip-review.ics",
+            "",
+        )
+        .await
+        .with_status(StatusCode::OK)
+        .body
+        .expect("Missing body");
+    assert!(
+        ical.contains("PARTSTAT=ACCEPTED;SCHEDULE-AGENT=CLIENT:mailto:carol@remote.org")
+            || ical.contains("PARTSTAT=ACCEPTED:mailto:carol@remote.org"),
+        "{ical}"
+    );
+    assert!(
+        ical.contains("PARTSTAT=DECLINED;SCHEDULE-AGENT=CLIENT:mailto:dave@remote.org")
+            || ical.contains("PARTSTAT=DECLINED:mailto:dave@remote.org"),
+        "{ical}"
+    );
+
+    // Accounts that opted out of invitation processing only receive the email
+    let admin = test.account("admin@example.com");
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                Property::CalendarInvitations: CalendarInvitationPolicy::Disabled.as_str(),
+            }),
+        )
+        .await;
+    deliver_imip(
+        test,
+        jane_client.account_id,
+        "jane.smith@example.com",
+        &include_str!("../../resources/imip/request.eml")
+            .replace("imip-kickoff-7f3a91c2", "imip-kickoff-c05d2e18"),
+    )
+    .await;
+    let events = jane_client.fetch_imip_events().await;
+    assert_eq!(events.len(), 1, "{events:?}");
+    assert!(
+        events
+            .iter()
+            .all(|(_, ical)| !ical.contains("imip-kickoff-c05d2e18")),
+        "{events:?}"
+    );
+    admin
+        .registry_update_object(
+            ObjectType::Account,
+            jane.id(),
+            json!({
+                Property::CalendarInvitations: CalendarInvitationPolicy::Default.as_str(),
+            }),
+        )
+        .await;
+
+    // Clean up
+    for client in [&jane_client, &john_client] {
+        client.delete_default_containers().await;
+    }
+    for account in [jane, john] {
+        test.destroy_all_mailboxes(account).await;
+    }
+    test.assert_is_empty().await;
+}
+
+async fn deliver_imip(test: &TestServer, account_id: u32, rcpt: &str, message: &str) {
+    let (message_blob, _) = test
+        .server
+        .put_temporary_blob(account_id, message.as_bytes(), 60)
+        .await
+        .unwrap();
+    assert_eq!(
+        test.server
+            .deliver_message(IngestMessage {
+                sender_address: "alice@remote.org".to_string(),
+                sender_authenticated: true,
+                remote_ip: None,
+                recipients: vec![IngestRecipient {
+                    address: rcpt.to_string(),
+                    orcpt: None,
+                    is_spam: false,
+                }],
+                message_blob,
+                message_size: message.len() as u64,
+                spam_tags: vec![],
+                session_id: 0,
+            })
+            .await
+            .status,
+        vec![LocalDeliveryStatus::Success]
+    );
+}
+
+impl DummyWebDavClient {
+    async fn fetch_imip_events(&self) -> Vec<(String, String)> {
+        let cal_path = format!("/dav/cal/{}/default/", self.name.replace('@', "%40"));
+        let response = self
+            .propfind_with_headers(&cal_path, ALL_DAV_PROPERTIES, [("depth", "1")])
+            .await;
+        let mut events = vec![];
+
+        for href in response.hrefs.keys().filter(|&href| href != &cal_path) {
+            let ical = self
+                .request("GET", href, "")
+                .await
+                .with_status(StatusCode::OK)
+                .body
+                .expect("Missing body");
+            events.push((href.to_string(), ical));
+        }
+
+        events
+    }
+}
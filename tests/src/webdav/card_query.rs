@@ -141,6 +141,63 @@ END:VCARD
         )
         .with_href_count(3);
 
+    // Test 5: Collations
+    for (collation, match_type, needle, negate, expected) in [
+        (
+            "i;unicode-casemap",
+            "contains",
+            "ACME-SOLUTIONS",
+            false,
+            vec![uri_acme],
+        ),
+        (
+            "i;ascii-casemap",
+            "starts-with",
+            "INFO@",
+            false,
+            vec![uri_acme],
+        ),
+        ("i;octet", "contains", "ACME", false, vec![]),
+        ("i;octet", "contains", "acme", false, vec![uri_acme]),
+        (
+            "i;octet",
+            "contains",
+            "acme",
+            true,
+            vec![uri_carlos, uri_sarah],
+        ),
+    ] {
+        let query = QUERY5
+            .replace("$COLLATION", collation)
+            .replace("$MATCH_TYPE", match_type)
+            .replace("$NEGATE", if negate { "yes" } else { "no" })
+            .replace("$NEEDLE", needle);
+        let response = client
+            .request("REPORT", &default_path, &query)
+            .await
+            .with_status(StatusCode::MULTI_STATUS);
+        if expected.is_empty() {
+            response.with_href_count(0);
+        } else {
+            response.with_hrefs(expected);
+        }
+    }
+
+    // Test 6: Unsupported collation
+    client
+        .request(
+            "REPORT",
+            &default_path,
+            &QUERY5
+                .replace("$COLLATION", "i;klingon")
+                .replace("$MATCH_TYPE", "contains")
+                .replace("$NEGATE", "no")
+                .replace("$NEEDLE", "acme"),
+        )
+        .await
+        .with_status(StatusCode::FORBIDDEN)
+        .with_failed_precondition("B:supported-collation", "i;klingon");
+
     client.delete_default_containers().await;
     test.assert_is_empty().await;
 }
@@ -231,6 +288,18 @@ const QUERY4: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
      </C:limit>
    </C:addressbook-query>"#;
 
+const QUERY5: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<C:addressbook-query xmlns:D="DAV:" xmlns:C="urn:ietf:params:xml:ns:carddav">
+  <D:prop>
+    <D:getetag/>
+  </D:prop>
+  <C:filter>
+    <C:prop-filter name="EMAIL">
+      <C:text-match collation="$COLLATION" match-type="$MATCH_TYPE" negate-condition="$NEGATE">$NEEDLE</C:text-match>
+    </C:prop-filter>
+  </C:filter>
+</C:addressbook-query>"#;
+
 const VCARD1: &str = r#"BEGIN:VCARD
 VERSION:4.0
 FN:Sarah Johnson
@@ -27,6 +27,7 @@ use std::time::Instant;
 pub mod acl;
 pub mod basic;
 pub mod cal_alarm;
+pub mod cal_imip;
 pub mod cal_itip;
 pub mod cal_query;
 pub mod cal_scheduling;
@@ -194,6 +195,7 @@ pub async fn webdav_tests() {
     cal_alarm::test(&test).await;
     cal_itip::test();
     cal_scheduling::test(&test).await;
+    cal_imip::test(&test).await;
 
     // Print elapsed time
     let elapsed = start_time.elapsed();